@@ -17,6 +17,24 @@ pub fn pretty_format_batches(results: &[RecordBatch]) -> Result<String> {
     Ok(create_table(results)?.to_string())
 }
 
+/// Convert a nanosecond timestamp to a String in RFC3339 format
+/// (e.g. `2021-07-20T23:28:50Z`), treating it as UTC
+pub fn timestamp_nanos_to_rfc3339(ts_value: i64) -> Result<String> {
+    const NANOS_IN_SEC: i64 = 1_000_000_000;
+    let secs = ts_value / NANOS_IN_SEC;
+    let nanos = (ts_value - (secs * NANOS_IN_SEC)) as u32;
+    let ts = NaiveDateTime::from_timestamp_opt(secs, nanos).ok_or_else(|| {
+        ArrowError::ExternalError(
+            format!("Cannot process timestamp (secs={secs}, nanos={nanos})").into(),
+        )
+    })?;
+    // treat as UTC
+    let ts = DateTime::<Utc>::from_utc(ts, Utc);
+    // convert to string in preferred influx format
+    let use_z = true;
+    Ok(ts.to_rfc3339_opts(SecondsFormat::AutoSi, use_z))
+}
+
 /// Convert the value at `column[row]` to a String
 ///
 /// Special cases printing Timestamps in RFC3339 for IOx, otherwise
@@ -30,20 +48,7 @@ fn array_value_to_string(column: &ArrayRef, row: usize) -> Result<String> {
                 .downcast_ref::<TimestampNanosecondArray>()
                 .unwrap();
 
-            let ts_value = ts_column.value(row);
-            const NANOS_IN_SEC: i64 = 1_000_000_000;
-            let secs = ts_value / NANOS_IN_SEC;
-            let nanos = (ts_value - (secs * NANOS_IN_SEC)) as u32;
-            let ts = NaiveDateTime::from_timestamp_opt(secs, nanos).ok_or_else(|| {
-                ArrowError::ExternalError(
-                    format!("Cannot process timestamp (secs={secs}, nanos={nanos})").into(),
-                )
-            })?;
-            // treat as UTC
-            let ts = DateTime::<Utc>::from_utc(ts, Utc);
-            // convert to string in preferred influx format
-            let use_z = true;
-            Ok(ts.to_rfc3339_opts(SecondsFormat::AutoSi, use_z))
+            timestamp_nanos_to_rfc3339(ts_column.value(row))
         }
         // TODO(edd): see https://github.com/apache/arrow-rs/issues/1168
         DataType::Duration(TimeUnit::Nanosecond) if column.is_valid(row) => {
@@ -0,0 +1,172 @@
+use std::sync::Arc;
+
+use iox_time::{SystemProvider, TimeProvider};
+use metric::{Registry, U64Counter};
+use observability_deps::tracing::warn;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+use super::{AuditRecord, AuditSink};
+
+const AUDIT_RECORDS_METRIC: &str = "audit_records";
+
+/// Number of records buffered for the sink, before new records are dropped.
+const BUFFER_SIZE: usize = 10_000;
+
+/// Maximum number of records written to the sink at once.
+const MAX_BATCH_SIZE: usize = 1_000;
+
+/// Records the writes and queries served by a server.
+///
+/// Records are buffered and written to the [`AuditSink`] in batches by a
+/// background task, which exits once all clones of the [`AuditLog`] are
+/// dropped. If the sink cannot keep up and the buffer is full, records are
+/// dropped rather than delaying requests. Dropped records and failures to
+/// write records are logged and counted by the `audit_records` metric.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    server: &'static str,
+    time_provider: Arc<dyn TimeProvider>,
+    tx: mpsc::Sender<AuditRecord>,
+    dropped: U64Counter,
+}
+
+impl AuditLog {
+    /// Write the records of the server of type `server` to `sink`.
+    ///
+    /// # Panics
+    ///
+    /// Must be called from within a tokio runtime, which the background task
+    /// is spawned onto.
+    pub fn new(server: &'static str, sink: Arc<dyn AuditSink>, metrics: &Registry) -> Self {
+        let metric = metrics.register_metric::<U64Counter>(
+            AUDIT_RECORDS_METRIC,
+            "number of audit records, by the result of writing them to the audit sink",
+        );
+        let written = metric.recorder(&[("result", "written")]);
+        let failed = metric.recorder(&[("result", "failed")]);
+        let dropped = metric.recorder(&[("result", "dropped")]);
+
+        let (tx, rx) = mpsc::channel(BUFFER_SIZE);
+        tokio::spawn(write_records(rx, sink, written, failed));
+
+        Self {
+            server,
+            time_provider: Arc::new(SystemProvider::new()),
+            tx,
+            dropped,
+        }
+    }
+
+    /// Record a request served by this server.
+    pub fn record(&self, mut record: AuditRecord) {
+        record.time = self.time_provider.now();
+        record.server = self.server;
+
+        match self.tx.try_send(record) {
+            Ok(()) => {}
+            Err(TrySendError::Full(record) | TrySendError::Closed(record)) => {
+                warn!(
+                    namespace = record.namespace.as_deref().unwrap_or_default(),
+                    operation = ?record.operation,
+                    "audit log cannot keep up, dropping record"
+                );
+                self.dropped.inc(1);
+            }
+        }
+    }
+}
+
+/// Write the records received from `rx` to `sink` in batches, until all
+/// senders are dropped.
+async fn write_records(
+    mut rx: mpsc::Receiver<AuditRecord>,
+    sink: Arc<dyn AuditSink>,
+    written: U64Counter,
+    failed: U64Counter,
+) {
+    let mut batch = Vec::with_capacity(MAX_BATCH_SIZE);
+    while let Some(record) = rx.recv().await {
+        batch.push(record);
+        while batch.len() < MAX_BATCH_SIZE {
+            match rx.try_recv() {
+                Ok(record) => batch.push(record),
+                Err(_) => break,
+            }
+        }
+
+        match sink.write(&batch).await {
+            Ok(()) => written.inc(batch.len() as _),
+            Err(e) => {
+                warn!(%e, n_records = batch.len(), "failed to write audit records");
+                failed.inc(batch.len() as _);
+            }
+        }
+        batch.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+    use crate::{mock::MockAuditSink, Error, Operation};
+
+    #[derive(Debug)]
+    struct FailingSink;
+
+    #[async_trait]
+    impl AuditSink for FailingSink {
+        async fn write(&self, _records: &[AuditRecord]) -> Result<(), Error> {
+            Err("sink unavailable".into())
+        }
+    }
+
+    fn metric_value(metrics: &Registry, result: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>(AUDIT_RECORDS_METRIC)
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("result", result)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let metrics = Registry::default();
+        let sink = Arc::new(MockAuditSink::default());
+        let log = AuditLog::new("router", Arc::clone(&sink) as _, &metrics);
+
+        log.record(AuditRecord::new(Operation::Write, "v2 write").with_namespace("bananas"));
+        log.clone()
+            .record(AuditRecord::new(Operation::Write, "v2 write").with_namespace("platanos"));
+
+        let records = sink.wait_for_records(2).await;
+        let namespaces = records
+            .iter()
+            .map(|r| r.namespace.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(namespaces, ["bananas", "platanos"]);
+        assert!(records.iter().all(|r| r.server == "router"));
+        assert!(records.iter().all(|r| r.time > iox_time::Time::MIN));
+    }
+
+    #[tokio::test]
+    async fn test_sink_failure() {
+        let metrics = Registry::default();
+        let log = AuditLog::new("querier", Arc::new(FailingSink), &metrics);
+
+        log.record(AuditRecord::new(Operation::Query, "SELECT 1"));
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while metric_value(&metrics, "failed") < 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("record not written");
+        assert_eq!(metric_value(&metrics, "written"), 0);
+    }
+}
@@ -0,0 +1,94 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::{fs::File, io::AsyncWriteExt, sync::Mutex};
+
+use super::{AuditRecord, AuditSink, Error};
+
+/// An [`AuditSink`] appending records to a file, one JSON object per line.
+#[derive(Debug)]
+pub struct FileAuditSink {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl FileAuditSink {
+    /// Open the file at `path` for appending, creating it if it does not
+    /// exist.
+    pub async fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let path = path.into();
+        let file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .await?;
+
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+        })
+    }
+
+    /// The path of the file records are appended to.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        for record in records {
+            serde_json::to_writer(&mut buf, record)?;
+            buf.push(b'\n');
+        }
+
+        // Write the whole batch at once, so that lines of concurrent writes
+        // are not interleaved.
+        let mut file = self.file.lock().await;
+        file.write_all(&buf).await?;
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    #[tokio::test]
+    async fn test_append() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+
+        let sink = FileAuditSink::open(&path).await.unwrap();
+        sink.write(&[
+            AuditRecord::new(Operation::Write, "v2 write").with_namespace("bananas"),
+            AuditRecord::new(Operation::Query, "SELECT 1").with_token(Some(b"GOOD".as_slice())),
+        ])
+        .await
+        .unwrap();
+        drop(sink);
+
+        // Reopening the file appends to it.
+        let sink = FileAuditSink::open(&path).await.unwrap();
+        assert_eq!(sink.path(), path);
+        sink.write(&[AuditRecord::new(Operation::Query, "SELECT 2")])
+            .await
+            .unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines = content
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0]["operation"], "write");
+        assert_eq!(lines[0]["namespace"], "bananas");
+        assert_eq!(lines[1]["summary"], "SELECT 1");
+        assert_eq!(lines[1]["identity"], crate::identity(b"GOOD"));
+        assert_eq!(lines[2]["summary"], "SELECT 2");
+    }
+}
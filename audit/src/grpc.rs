@@ -0,0 +1,83 @@
+use async_trait::async_trait;
+use generated_types::influxdata::iox::audit::v1::{self as proto, audit_record};
+
+use super::{AuditRecord, AuditSink, Error, Operation, Status};
+
+/// An [`AuditSink`] sending records to an audit service using the
+/// influxdata.iox.audit.v1 protocol.
+#[derive(Clone, Debug)]
+pub struct GrpcAuditSink {
+    client: proto::audit_service_client::AuditServiceClient<tonic::transport::Channel>,
+}
+
+impl GrpcAuditSink {
+    /// Attempt to create a new client by connecting to a given endpoint.
+    pub fn connect_lazy<D>(dst: D) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        D: TryInto<tonic::transport::Endpoint> + Send,
+        D::Error: Into<tonic::codegen::StdError>,
+    {
+        let ep = tonic::transport::Endpoint::new(dst)?;
+        let client = proto::audit_service_client::AuditServiceClient::new(ep.connect_lazy());
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl AuditSink for GrpcAuditSink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), Error> {
+        let req = proto::WriteRecordsRequest {
+            records: records.iter().map(Into::into).collect(),
+        };
+        let mut client = self.client.clone();
+        client.write_records(req).await?;
+        Ok(())
+    }
+}
+
+impl From<&AuditRecord> for proto::AuditRecord {
+    fn from(record: &AuditRecord) -> Self {
+        let operation = match record.operation {
+            Operation::Write => audit_record::Operation::Write,
+            Operation::Query => audit_record::Operation::Query,
+        };
+        let status = match record.status {
+            Status::Ok => audit_record::Status::Ok,
+            Status::Error => audit_record::Status::Error,
+        };
+
+        Self {
+            time_ns: record.time.timestamp_nanos(),
+            server: record.server.to_string(),
+            namespace: record.namespace.clone().unwrap_or_default(),
+            identity: record.identity.clone().unwrap_or_default(),
+            operation: operation.into(),
+            summary: record.summary.clone(),
+            bytes: record.bytes,
+            status: status.into(),
+            error: record.error.clone().unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_proto() {
+        let record = AuditRecord::new(Operation::Write, "v2 write")
+            .with_namespace("bananas")
+            .with_bytes(42)
+            .with_result(&Err::<(), _>("forbidden"));
+
+        let got = proto::AuditRecord::from(&record);
+        assert_eq!(got.namespace, "bananas");
+        assert_eq!(got.identity, "");
+        assert_eq!(got.operation(), audit_record::Operation::Write);
+        assert_eq!(got.summary, "v2 write");
+        assert_eq!(got.bytes, 42);
+        assert_eq!(got.status(), audit_record::Status::Error);
+        assert_eq!(got.error, "forbidden");
+    }
+}
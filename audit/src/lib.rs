@@ -0,0 +1,49 @@
+//! IOx audit log.
+//!
+//! Servers record an [`AuditRecord`] for every write and query they serve
+//! (successful or not) to an [`AuditLog`], which hands them to an
+//! [`AuditSink`] in the background, so that serving requests is never blocked
+//! by the audit log. Records are either appended to a file as JSON lines
+//! ([`FileAuditSink`]), or sent to an external service implementing the
+//! `influxdata.iox.audit.v1` protocol ([`GrpcAuditSink`]).
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+
+mod audit_log;
+pub use audit_log::AuditLog;
+mod file;
+pub use file::FileAuditSink;
+mod grpc;
+pub use grpc::GrpcAuditSink;
+pub mod mock;
+mod record;
+pub use record::{identity, AuditRecord, Operation, Status};
+
+/// Error returned by an [`AuditSink`].
+pub type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A destination for [`AuditRecord`]s.
+#[async_trait]
+pub trait AuditSink: Debug + Send + Sync + 'static {
+    /// Persist a batch of records, in the order given.
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), Error>;
+}
@@ -0,0 +1,48 @@
+//! A mock [`AuditSink`] for testing.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use parking_lot::Mutex;
+
+use super::{AuditRecord, AuditSink, Error};
+
+/// An [`AuditSink`] keeping the records written to it in memory.
+#[derive(Debug, Default)]
+pub struct MockAuditSink {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl MockAuditSink {
+    /// The records written so far.
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records.lock().clone()
+    }
+
+    /// Wait until at least `n` records were written, returning them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the records are not written within 5 seconds.
+    pub async fn wait_for_records(&self, n: usize) -> Vec<AuditRecord> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let records = self.records();
+                if records.len() >= n {
+                    return records;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("audit records not written")
+    }
+}
+
+#[async_trait]
+impl AuditSink for MockAuditSink {
+    async fn write(&self, records: &[AuditRecord]) -> Result<(), Error> {
+        self.records.lock().extend_from_slice(records);
+        Ok(())
+    }
+}
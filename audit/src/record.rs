@@ -0,0 +1,172 @@
+use std::fmt::Display;
+
+use iox_time::Time;
+use serde::{Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+/// The kind of an audited request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    /// Data was written to a namespace.
+    Write,
+    /// A namespace was queried.
+    Query,
+}
+
+/// The outcome of an audited request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// The request succeeded.
+    Ok,
+    /// The request failed, see [`AuditRecord::error`].
+    Error,
+}
+
+/// A write or query served by an IOx server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuditRecord {
+    /// Time the request was recorded, set by the [`AuditLog`].
+    ///
+    /// [`AuditLog`]: crate::AuditLog
+    #[serde(serialize_with = "serialize_time")]
+    pub time: Time,
+
+    /// Type of the server that served the request, set by the [`AuditLog`].
+    ///
+    /// [`AuditLog`]: crate::AuditLog
+    pub server: &'static str,
+
+    /// Namespace the request targeted, if it could be determined.
+    pub namespace: Option<String>,
+
+    /// Identity of the caller, see [`identity`].
+    pub identity: Option<String>,
+
+    /// The kind of the request.
+    pub operation: Operation,
+
+    /// Human readable summary of the request, e.g. the query text.
+    pub summary: String,
+
+    /// Size of the request payload in bytes.
+    pub bytes: u64,
+
+    /// Whether the request succeeded.
+    pub status: Status,
+
+    /// The error returned to the caller, if the request failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AuditRecord {
+    /// Create a record of a successful request of kind `operation`,
+    /// summarised by `summary`.
+    pub fn new(operation: Operation, summary: impl Into<String>) -> Self {
+        Self {
+            time: Time::MIN,
+            server: "",
+            namespace: None,
+            identity: None,
+            operation,
+            summary: summary.into(),
+            bytes: 0,
+            status: Status::Ok,
+            error: None,
+        }
+    }
+
+    /// Set the namespace the request targeted.
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Set the identity of the caller from the authorization token of the
+    /// request, if any.
+    pub fn with_token(mut self, token: Option<&[u8]>) -> Self {
+        self.identity = token.map(identity);
+        self
+    }
+
+    /// Set the size of the request payload.
+    pub fn with_bytes(mut self, bytes: u64) -> Self {
+        self.bytes = bytes;
+        self
+    }
+
+    /// Set the outcome of the request from its result.
+    pub fn with_result<T, E: Display>(mut self, result: &Result<T, E>) -> Self {
+        match result {
+            Ok(_) => {
+                self.status = Status::Ok;
+                self.error = None;
+            }
+            Err(e) => {
+                self.status = Status::Error;
+                self.error = Some(e.to_string());
+            }
+        }
+        self
+    }
+}
+
+/// Derive the identity recorded for the caller from the authorization token
+/// of a request.
+///
+/// The token itself must not end up in the audit log, so the identity is a
+/// truncated SHA-256 hash of it. It identifies all requests made with the
+/// same token, which can be mapped to a user by the service that issued it.
+pub fn identity(token: &[u8]) -> String {
+    format!("token:{}", hex::encode(&Sha256::digest(token)[..8]))
+}
+
+fn serialize_time<S: Serializer>(time: &Time, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&time.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity() {
+        let id = identity(b"GOOD");
+        assert!(id.starts_with("token:"), "{id}");
+        assert_eq!(id.len(), "token:".len() + 16);
+        assert_eq!(id, identity(b"GOOD"));
+        assert_ne!(id, identity(b"BAD"));
+    }
+
+    #[test]
+    fn test_serialize() {
+        let mut record = AuditRecord::new(Operation::Query, "SELECT 1")
+            .with_namespace("bananas")
+            .with_bytes(8)
+            .with_result(&Err::<(), _>("boom"));
+        record.time = Time::from_timestamp_nanos(0);
+        record.server = "querier";
+
+        assert_eq!(
+            serde_json::to_value(&record).unwrap(),
+            serde_json::json!({
+                "time": "1970-01-01T00:00:00+00:00",
+                "server": "querier",
+                "namespace": "bananas",
+                "identity": null,
+                "operation": "query",
+                "summary": "SELECT 1",
+                "bytes": 8,
+                "status": "error",
+                "error": "boom",
+            })
+        );
+
+        let record = record.with_result(&Ok::<_, String>(()));
+        let json = serde_json::to_value(&record).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert!(json.get("error").is_none());
+    }
+}
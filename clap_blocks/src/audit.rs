@@ -0,0 +1,133 @@
+//! CLI config for the audit log.
+use std::{path::PathBuf, sync::Arc};
+
+use audit::{AuditLog, AuditSink, FileAuditSink, GrpcAuditSink};
+use observability_deps::tracing::info;
+use snafu::{ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Cannot open audit log file {}: {source}", path.display()))]
+    OpenFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Invalid audit service address '{addr}': {source}"))]
+    ServiceAddress {
+        addr: String,
+        source: Box<dyn std::error::Error>,
+    },
+}
+
+/// CLI config for the audit log, recording every write and query served.
+#[derive(Debug, Clone, Default, PartialEq, Eq, clap::Parser)]
+pub struct AuditConfig {
+    /// Append a record of every write and query served to this file, one
+    /// JSON object per line.
+    ///
+    /// Records contain the namespace, the identity of the caller (derived
+    /// from the authorization token), a summary of the operation, the
+    /// request size and whether the request succeeded.
+    #[clap(
+        long = "audit-log-file",
+        env = "INFLUXDB_IOX_AUDIT_LOG_FILE",
+        conflicts_with = "audit_log_addr",
+        action
+    )]
+    pub audit_log_file: Option<PathBuf>,
+
+    /// Send a record of every write and query served to the audit service
+    /// at this gRPC address, see `--audit-log-file`.
+    #[clap(long = "audit-log-addr", env = "INFLUXDB_IOX_AUDIT_LOG_ADDR", action)]
+    pub audit_log_addr: Option<String>,
+}
+
+impl AuditConfig {
+    /// Create the [`AuditLog`] of a server of type `server`, if enabled.
+    pub async fn audit_log(
+        &self,
+        server: &'static str,
+        metrics: &metric::Registry,
+    ) -> Result<Option<AuditLog>, Error> {
+        let sink: Arc<dyn AuditSink> = match (&self.audit_log_file, &self.audit_log_addr) {
+            (Some(path), _) => {
+                info!(path=%path.display(), "writing audit log to file");
+                Arc::new(
+                    FileAuditSink::open(path)
+                        .await
+                        .context(OpenFileSnafu { path })?,
+                )
+            }
+            (None, Some(addr)) => {
+                info!(%addr, "sending audit log to audit service");
+                Arc::new(
+                    GrpcAuditSink::connect_lazy(addr.clone())
+                        .context(ServiceAddressSnafu { addr })?,
+                )
+            }
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(AuditLog::new(server, sink, metrics)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config = AuditConfig::try_parse_from(["server"]).unwrap();
+        assert!(config.audit_log_file.is_none());
+        assert!(config.audit_log_addr.is_none());
+
+        let config =
+            AuditConfig::try_parse_from(["server", "--audit-log-file", "/tmp/audit.log"]).unwrap();
+        assert_eq!(config.audit_log_file, Some(PathBuf::from("/tmp/audit.log")));
+
+        AuditConfig::try_parse_from([
+            "server",
+            "--audit-log-file",
+            "/tmp/audit.log",
+            "--audit-log-addr",
+            "http://audit:8080",
+        ])
+        .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let metrics = metric::Registry::default();
+
+        let config = AuditConfig::default();
+        assert!(config
+            .audit_log("router", &metrics)
+            .await
+            .unwrap()
+            .is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = AuditConfig {
+            audit_log_file: Some(dir.path().join("audit.log")),
+            audit_log_addr: None,
+        };
+        assert!(config
+            .audit_log("router", &metrics)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(dir.path().join("audit.log").exists());
+
+        let config = AuditConfig {
+            audit_log_file: Some(dir.path().join("missing").join("audit.log")),
+            audit_log_addr: None,
+        };
+        let err = config.audit_log("router", &metrics).await.unwrap_err();
+        assert!(matches!(err, Error::OpenFile { .. }), "{err}");
+    }
+}
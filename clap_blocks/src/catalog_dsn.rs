@@ -1,4 +1,5 @@
 //! Catalog-DSN-related configs.
+use backoff::BackoffConfig;
 use iox_catalog::sqlite::{SqliteCatalog, SqliteConnectionOptions};
 use iox_catalog::{
     interface::Catalog,
@@ -46,6 +47,12 @@ fn default_hotswap_poll_interval_timeout() -> &'static str {
     Box::leak(Box::new(s))
 }
 
+fn default_retry_deadline() -> &'static str {
+    let s =
+        humantime::format_duration(PostgresConnectionOptions::DEFAULT_RETRY_DEADLINE).to_string();
+    Box::leak(Box::new(s))
+}
+
 /// CLI config for catalog DSN.
 #[derive(Debug, Clone, Default, clap::Parser)]
 pub struct CatalogDsnConfig {
@@ -109,6 +116,36 @@ pub struct CatalogDsnConfig {
         value_parser = humantime::parse_duration,
     )]
     pub hotswap_poll_interval: Duration,
+
+    /// Comma-separated connection strings of read replicas of a
+    /// PostgreSQL-based catalog.
+    ///
+    /// Read-only schema lookups and parquet file listings are spread across
+    /// these replicas, falling back to the primary `--catalog-dsn` when a
+    /// replica cannot be reached. As replicas may lag behind the primary, only
+    /// configure them for services that tolerate slightly stale catalog
+    /// reads, such as the querier.
+    #[clap(
+        long = "catalog-postgres-replica-dsns",
+        env = "INFLUXDB_IOX_CATALOG_POSTGRES_REPLICA_DSNS",
+        required = false,
+        num_args=1..,
+        value_delimiter = ','
+    )]
+    pub postgres_replica_dsns: Vec<String>,
+
+    /// Maximum amount of time spent retrying a PostgreSQL catalog call that
+    /// failed with a transient error, such as a serialization failure or a
+    /// lost connection, before the error is returned.
+    ///
+    /// Set to `0s` to disable retrying.
+    #[clap(
+        long = "catalog-retry-deadline",
+        env = "INFLUXDB_IOX_CATALOG_RETRY_DEADLINE",
+        default_value = default_retry_deadline(),
+        value_parser = humantime::parse_duration,
+    )]
+    pub retry_deadline: Duration,
 }
 
 impl CatalogDsnConfig {
@@ -124,7 +161,11 @@ impl CatalogDsnConfig {
 
         if dsn.starts_with("postgres") || dsn.starts_with("dsn-file://") {
             // do not log entire postgres dsn as it may contain credentials
-            info!(postgres_schema_name=%self.postgres_schema_name, "Catalog: Postgres");
+            info!(
+                postgres_schema_name=%self.postgres_schema_name,
+                replicas=self.postgres_replica_dsns.len(),
+                "Catalog: Postgres"
+            );
             let options = PostgresConnectionOptions {
                 app_name: app_name.to_string(),
                 schema_name: self.postgres_schema_name.clone(),
@@ -133,6 +174,11 @@ impl CatalogDsnConfig {
                 connect_timeout: self.connect_timeout,
                 idle_timeout: self.idle_timeout,
                 hotswap_poll_interval: self.hotswap_poll_interval,
+                replica_dsns: self.postgres_replica_dsns.clone(),
+                retry_backoff: BackoffConfig {
+                    deadline: Some(self.retry_deadline),
+                    ..PostgresConnectionOptions::default_retry_backoff()
+                },
             };
             Ok(Arc::new(
                 PostgresCatalog::connect(options, metrics)
@@ -36,6 +36,25 @@ pub struct CompactorConfig {
     )]
     pub compaction_df_concurrency: NonZeroUsize,
 
+    /// Memory budget in bytes for concurrent compaction jobs scheduled
+    /// to DataFusion.
+    ///
+    /// The memory needed by each job is estimated from the sizes and
+    /// row counts of its input files. Jobs only start once their
+    /// estimate fits into the remaining budget, so several huge
+    /// partitions are not compacted at the same time. A job that is
+    /// estimated to exceed the whole budget runs on its own.
+    ///
+    /// The budget applies in addition to
+    /// `--compaction-df-concurrency`. If not set, concurrency is only
+    /// limited by the job count.
+    #[clap(
+        long = "compaction-memory-budget-bytes",
+        env = "INFLUXDB_IOX_COMPACTION_MEMORY_BUDGET_BYTES",
+        action
+    )]
+    pub memory_budget_bytes: Option<usize>,
+
     /// Number of jobs PER PARTITION that move files in and out of the
     /// scratchpad.
     #[clap(
@@ -82,6 +101,25 @@ pub struct CompactorConfig {
     )]
     pub max_desired_file_size_bytes: u64,
 
+    /// Desired min size of compacted parquet files.
+    ///
+    /// If set, the desired size of compacted files adapts to the write
+    /// throughput of each partition: files of partitions that receive
+    /// lots of writes target `--compaction-max-desired-size-bytes`,
+    /// files of partitions that only receive a trickle of writes
+    /// target this smaller size, so small writes do not rewrite large
+    /// files over and over again.
+    ///
+    /// Values below 5% of `--compaction-max-desired-size-bytes` are
+    /// raised to that. If not set, all partitions target
+    /// `--compaction-max-desired-size-bytes`.
+    #[clap(
+        long = "compaction-min-desired-size-bytes",
+        env = "INFLUXDB_IOX_COMPACTION_MIN_DESIRED_FILE_SIZE_BYTES",
+        action
+    )]
+    pub min_desired_file_size_bytes: Option<u64>,
+
     /// Percentage of desired max file size for "leading edge split"
     /// optimization.
     ///
@@ -161,6 +199,38 @@ pub struct CompactorConfig {
     )]
     pub shadow_mode: bool,
 
+    /// Dry run.
+    ///
+    /// Partitions are classified, split and planned as usual, but no
+    /// data is read or written and nothing is committed to the
+    /// catalog. The changes that would have been committed (deleted,
+    /// upgraded and created files) are logged instead.
+    ///
+    /// This is useful to validate new settings against a production
+    /// catalog.
+    #[clap(
+        long = "compaction-dry-run",
+        env = "INFLUXDB_IOX_COMPACTION_DRY_RUN",
+        action
+    )]
+    pub dry_run: bool,
+
+    /// Verify compacted files after committing them.
+    ///
+    /// Newly created parquet files are read back from the object store
+    /// and checked for plausible row counts and for being sorted by
+    /// their sort key. Mismatches are logged and counted in the
+    /// `iox_compactor_output_verification_failures` metric.
+    ///
+    /// This reads every compacted file once more and is meant for
+    /// validating compactor changes.
+    #[clap(
+        long = "compaction-verify-output",
+        env = "INFLUXDB_IOX_COMPACTION_VERIFY_OUTPUT",
+        action
+    )]
+    pub verify_output: bool,
+
     /// Enable scratchpad.
     ///
     /// This allows disabling the scratchpad in production.
@@ -252,4 +322,37 @@ pub struct CompactorConfig {
         action
     )]
     pub max_partition_fetch_queries_per_second: Option<usize>,
+
+    /// Number of L0 files above which a partition is compacted in
+    /// time slices.
+    ///
+    /// A partition with a very large backlog of L0 files is then
+    /// compacted one contiguous time range at a time, with each round
+    /// bounded by the maximum compaction size and number of files per
+    /// plan, rather than in a single compaction that may exceed
+    /// available memory.
+    ///
+    /// If not set, partitions are never compacted in time slices.
+    #[clap(
+        long = "compaction-time-slice-min-num-l0-files",
+        env = "INFLUXDB_IOX_COMPACTION_TIME_SLICE_MIN_NUM_L0_FILES",
+        action
+    )]
+    pub time_slice_min_num_l0_files: Option<usize>,
+
+    /// Number of consecutive compaction failures after which a
+    /// partition is marked as skipped.
+    ///
+    /// Failures before that are retried the next time the partition
+    /// is picked up. When the partition is skipped, the kind of the
+    /// last error, its message and the number and size of the
+    /// partition's files are recorded in the catalog, where they can
+    /// be listed with `influxdb_iox debug skipped-compactions list`.
+    #[clap(
+        long = "compaction-max-consecutive-failures",
+        env = "INFLUXDB_IOX_COMPACTION_MAX_CONSECUTIVE_FAILURES",
+        default_value = "1",
+        action
+    )]
+    pub max_consecutive_failures: NonZeroUsize,
 }
@@ -78,6 +78,29 @@ pub struct PartitionSourceConfigForLocalScheduler {
         action
     )]
     pub process_all_partitions: bool,
+
+    /// Additionally compact "cold" partitions that did not receive new Parquet files within this
+    /// many minutes but are not fully compacted yet.
+    ///
+    /// Cold partitions are scheduled after the partitions with recent writes, so that long-tail
+    /// partitions eventually reach the final compaction level. Disabled if not set.
+    #[clap(
+        long = "compaction-cold-partition-minute-threshold",
+        env = "INFLUXDB_IOX_COMPACTION_COLD_PARTITION_MINUTE_THRESHOLD",
+        action
+    )]
+    pub compaction_cold_partition_minute_threshold: Option<u64>,
+
+    /// Maximum number of cold partitions that are scheduled per round.
+    ///
+    /// Consecutive rounds page through all cold partitions.
+    #[clap(
+        long = "compaction-max-cold-partitions",
+        env = "INFLUXDB_IOX_COMPACTION_MAX_COLD_PARTITIONS",
+        default_value = "100",
+        action
+    )]
+    pub compaction_max_cold_partitions: usize,
 }
 
 /// CLI config for compactor scheduler.
@@ -100,6 +123,29 @@ pub struct CompactorSchedulerConfig {
     /// Shard config used by the local scheduler.
     #[clap(flatten)]
     pub shard_config: ShardConfigForLocalScheduler,
+
+    /// Compact partitions with a large backlog of L0 data first.
+    ///
+    /// This costs one additional catalog query per batch of partitions.
+    #[clap(
+        long = "compaction-prioritize-l0-backlog",
+        env = "INFLUXDB_IOX_COMPACTION_PRIORITIZE_L0_BACKLOG",
+        default_value = "false",
+        action
+    )]
+    pub compaction_prioritize_l0_backlog: bool,
+
+    /// Lease duration of compaction jobs in seconds.
+    ///
+    /// Compactors renew the lease of their in-progress jobs by sending heartbeats. Jobs whose
+    /// lease expires, e.g. because they stalled, are cancelled and handed out again once they
+    /// stopped. Jobs are not leased if not set.
+    #[clap(
+        long = "compaction-job-lease-secs",
+        env = "INFLUXDB_IOX_COMPACTION_JOB_LEASE_SECS",
+        action
+    )]
+    pub compaction_job_lease_secs: Option<u64>,
 }
 
 #[cfg(test)]
@@ -146,4 +192,72 @@ mod tests {
         );
         assert_contains!(&error, "[possible values: local, remote]");
     }
+
+    #[test]
+    fn l0_backlog_prioritization() {
+        let config = CompactorSchedulerConfig::try_parse_from(["my_binary"]).unwrap();
+        assert!(!config.compaction_prioritize_l0_backlog);
+
+        let config = CompactorSchedulerConfig::try_parse_from([
+            "my_binary",
+            "--compaction-prioritize-l0-backlog",
+        ])
+        .unwrap();
+        assert!(config.compaction_prioritize_l0_backlog);
+    }
+
+    #[test]
+    fn job_leases() {
+        let config = CompactorSchedulerConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(config.compaction_job_lease_secs, None);
+
+        let config = CompactorSchedulerConfig::try_parse_from([
+            "my_binary",
+            "--compaction-job-lease-secs",
+            "300",
+        ])
+        .unwrap();
+        assert_eq!(config.compaction_job_lease_secs, Some(300));
+    }
+
+    #[test]
+    fn cold_partitions_are_disabled_by_default() {
+        let config = CompactorSchedulerConfig::try_parse_from(["my_binary"]).unwrap();
+        assert_eq!(
+            config
+                .partition_source_config
+                .compaction_cold_partition_minute_threshold,
+            None
+        );
+        assert_eq!(
+            config
+                .partition_source_config
+                .compaction_max_cold_partitions,
+            100
+        );
+    }
+
+    #[test]
+    fn can_enable_cold_partitions() {
+        let config = CompactorSchedulerConfig::try_parse_from([
+            "my_binary",
+            "--compaction-cold-partition-minute-threshold",
+            "240",
+            "--compaction-max-cold-partitions",
+            "10",
+        ])
+        .unwrap();
+        assert_eq!(
+            config
+                .partition_source_config
+                .compaction_cold_partition_minute_threshold,
+            Some(240)
+        );
+        assert_eq!(
+            config
+                .partition_source_config
+                .compaction_max_cold_partitions,
+            10
+        );
+    }
 }
@@ -4,13 +4,23 @@ use humantime::parse_duration;
 use std::{fmt::Debug, time::Duration};
 
 /// Configuration specific to the object store garbage collector
-#[derive(Debug, Clone, Parser, Copy)]
+#[derive(Debug, Clone, Parser)]
 pub struct GarbageCollectorConfig {
     /// If this flag is specified, don't delete the files in object storage. Only print the files
     /// that would be deleted if this flag wasn't specified.
     #[clap(long, env = "INFLUXDB_IOX_GC_DRY_RUN")]
     pub dry_run: bool,
 
+    /// Location in object storage to write manifests of what a dry run would delete to.
+    ///
+    /// In dry-run mode, the object store files, the catalog rows of parquet files and the
+    /// namespaces that would be deleted (or flagged for deletion) are written, together with the
+    /// reason why, as JSON lines to new files under this prefix every
+    /// minute, for review before running without `--dry-run`. Files under this prefix are never
+    /// garbage collected.
+    #[clap(long, env = "INFLUXDB_IOX_GC_DRY_RUN_MANIFEST_PREFIX")]
+    pub dry_run_manifest_prefix: Option<String>,
+
     /// Items in the object store that are older than this duration that are not referenced in the
     /// catalog will be deleted.
     /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
@@ -82,4 +92,28 @@ pub struct GarbageCollectorConfig {
         env = "INFLUXDB_IOX_GC_RETENTION_SLEEP_INTERVAL_MINUTES"
     )]
     pub retention_sleep_interval_minutes: u64,
+
+    /// Namespaces soft-deleted before this duration will be purged: their tables, columns,
+    /// partitions and parquet file rows are removed from the catalog, after which their files in
+    /// object storage are deleted like any other untracked object. Until then, a soft-deleted
+    /// namespace can be restored with `influxdb_iox namespace undelete`.
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    ///
+    /// If not specified, defaults to 30 days ago.
+    #[clap(
+        long,
+        default_value = "30d",
+        value_parser = parse_duration,
+        env = "INFLUXDB_IOX_GC_NAMESPACE_PURGE_GRACE_PERIOD"
+    )]
+    pub namespace_purge_grace_period: Duration,
+
+    /// Number of minutes to sleep between iterations of the namespace purge loop.
+    /// Defaults to 60 minutes.
+    #[clap(
+        long,
+        default_value_t = 60,
+        env = "INFLUXDB_IOX_GC_NAMESPACE_PURGE_SLEEP_INTERVAL_MINUTES"
+    )]
+    pub namespace_purge_sleep_interval_minutes: u64,
 }
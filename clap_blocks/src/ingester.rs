@@ -29,6 +29,33 @@ pub struct IngesterConfig {
     )]
     pub wal_rotation_period_seconds: u64,
 
+    /// The compression applied to the entries of new WAL segment files.
+    ///
+    /// Existing segment files are always read back using the compression they
+    /// were written with, so this can be changed across restarts.
+    #[clap(
+        long = "wal-compression",
+        env = "INFLUXDB_IOX_WAL_COMPRESSION",
+        default_value = "snappy",
+        value_enum,
+        action
+    )]
+    pub wal_compression: WalCompression,
+
+    /// When writes are acknowledged to the caller, relative to their
+    /// write-ahead log entry being fsync'd.
+    ///
+    /// Individual writes can override this by setting the `iox-write-ack`
+    /// request header to either "strict" or "fast".
+    #[clap(
+        long = "write-ack-mode",
+        env = "INFLUXDB_IOX_WRITE_ACK_MODE",
+        default_value = "strict",
+        value_enum,
+        action
+    )]
+    pub write_ack_mode: WriteAckMode,
+
     /// Sets how many queries the ingester will handle simultaneously before
     /// rejecting further incoming requests.
     #[clap(
@@ -69,4 +96,79 @@ pub struct IngesterConfig {
         action
     )]
     pub persist_hot_partition_cost: usize,
+
+    /// The length of the partition time windows, used to determine when a
+    /// partition's window has closed. Windows are aligned to the UNIX epoch.
+    ///
+    /// The default matches the daily partitions of the default partition
+    /// template.
+    #[clap(
+        long = "persist-partition-window-seconds",
+        env = "INFLUXDB_IOX_PERSIST_PARTITION_WINDOW_SECONDS",
+        default_value = "86400", // 1 day
+        action
+    )]
+    pub persist_partition_window_seconds: u64,
+
+    /// When set, partitions are queued for persistence once their partition
+    /// time window has ended at least this many seconds ago, rather than
+    /// waiting for the next WAL rotation.
+    ///
+    /// Disabled if not specified.
+    #[clap(
+        long = "persist-closed-partition-grace-seconds",
+        env = "INFLUXDB_IOX_PERSIST_CLOSED_PARTITION_GRACE_SECONDS",
+        action
+    )]
+    pub persist_closed_partition_grace_seconds: Option<u64>,
+
+    /// The amount of memory the buffered data of a single namespace can use
+    /// before its largest partitions are queued for persistence.
+    ///
+    /// Must be specified together with the hard limit. Disabled if not
+    /// specified.
+    #[clap(
+        long = "namespace-memory-soft-limit-bytes",
+        env = "INFLUXDB_IOX_NAMESPACE_MEMORY_SOFT_LIMIT_BYTES",
+        requires = "namespace_memory_hard_limit_bytes",
+        action
+    )]
+    pub namespace_memory_soft_limit_bytes: Option<usize>,
+
+    /// The amount of memory the buffered data of a single namespace can use
+    /// before writes to it are rejected, until enough of its data has been
+    /// persisted.
+    ///
+    /// Must be specified together with the soft limit. Disabled if not
+    /// specified.
+    #[clap(
+        long = "namespace-memory-hard-limit-bytes",
+        env = "INFLUXDB_IOX_NAMESPACE_MEMORY_HARD_LIMIT_BYTES",
+        requires = "namespace_memory_soft_limit_bytes",
+        action
+    )]
+    pub namespace_memory_hard_limit_bytes: Option<usize>,
+}
+
+/// The compression applied to the entries of WAL segment files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WalCompression {
+    /// The snappy frame format, favouring speed over compression ratio.
+    #[default]
+    Snappy,
+
+    /// zstd, reducing disk usage and write volume at the cost of CPU time.
+    Zstd,
+}
+
+/// When a write is acknowledged to the caller.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WriteAckMode {
+    /// Once the write is buffered and its WAL entry has been fsync'd.
+    #[default]
+    Strict,
+
+    /// Once the write is buffered, without waiting for its WAL entry to be
+    /// fsync'd. Acknowledged writes may be lost if the ingester crashes.
+    Fast,
 }
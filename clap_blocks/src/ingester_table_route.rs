@@ -0,0 +1,132 @@
+//! Shared configuration for routing the writes of specific tables to dedicated ingesters.
+
+use crate::ingester_address::{self, IngesterAddress};
+use regex::Regex;
+use snafu::{ResultExt, Snafu};
+use std::str::FromStr;
+
+/// A rule routing the writes of all tables with a name matching `table_regex` to a dedicated set
+/// of ingesters. Create by using `IngesterTableRoute::from_str` on a string of the form
+/// `<table regex>=<ingester address>;<ingester address>...`.
+///
+/// The regex must match the full table name.
+#[derive(Debug, Clone)]
+pub struct IngesterTableRoute {
+    table_regex: Regex,
+    ingester_addresses: Vec<IngesterAddress>,
+}
+
+/// Why a specified ingester table route might be invalid
+#[allow(missing_docs)]
+#[derive(Snafu, Debug)]
+pub enum Error {
+    #[snafu(display("Expected `<table regex>=<ingester addresses>`, got `{value}`"))]
+    MissingSeparator { value: String },
+
+    #[snafu(display("Invalid table regex `{value}`: {source}"))]
+    InvalidRegex { value: String, source: regex::Error },
+
+    #[snafu(display("No ingester addresses specified in `{value}`"))]
+    NoIngesters { value: String },
+
+    #[snafu(display("Invalid ingester address in `{value}`: {source}"))]
+    InvalidAddress {
+        value: String,
+        source: ingester_address::Error,
+    },
+}
+
+impl IngesterTableRoute {
+    /// The regex a table name must match for its writes to be routed to the
+    /// [`Self::ingester_addresses`].
+    pub fn table_regex(&self) -> &Regex {
+        &self.table_regex
+    }
+
+    /// The ingesters the matching tables are routed to.
+    pub fn ingester_addresses(&self) -> &[IngesterAddress] {
+        &self.ingester_addresses
+    }
+}
+
+impl FromStr for IngesterTableRoute {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Ingester addresses never contain a `=`, so splitting at the last
+        // one allows it to be used in the regex.
+        let (table_regex, addresses) = s
+            .rsplit_once('=')
+            .ok_or_else(|| MissingSeparatorSnafu { value: s }.build())?;
+
+        let table_regex = Regex::new(&format!("^(?:{table_regex})$"))
+            .context(InvalidRegexSnafu { value: table_regex })?;
+
+        let ingester_addresses = addresses
+            .split(';')
+            .map(str::trim)
+            .filter(|addr| !addr.is_empty())
+            .map(IngesterAddress::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .context(InvalidAddressSnafu { value: s })?;
+        if ingester_addresses.is_empty() {
+            return NoIngestersSnafu { value: s }.fail();
+        }
+
+        Ok(Self {
+            table_regex,
+            ingester_addresses,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_helpers::assert_error;
+
+    #[test]
+    fn parse() {
+        let route: IngesterTableRoute = "cpu|mem.*=ingester-0:8083;http://ingester-1:8083"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            route.ingester_addresses(),
+            &[
+                "http://ingester-0:8083".parse().unwrap(),
+                "http://ingester-1:8083".parse().unwrap()
+            ]
+        );
+        assert!(route.table_regex().is_match("cpu"));
+        assert!(route.table_regex().is_match("memory"));
+        assert!(!route.table_regex().is_match("cpu2"));
+        assert!(!route.table_regex().is_match("disk"));
+    }
+
+    #[test]
+    fn parse_regex_with_separator() {
+        let route: IngesterTableRoute = "a=b=ingester-0:8083".parse().unwrap();
+        assert!(route.table_regex().is_match("a=b"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_error!(
+            "cpu".parse::<IngesterTableRoute>(),
+            Error::MissingSeparator { .. }
+        );
+        assert_error!(
+            "cpu(=ingester-0:8083".parse::<IngesterTableRoute>(),
+            Error::InvalidRegex { .. }
+        );
+        assert_error!(
+            "cpu=".parse::<IngesterTableRoute>(),
+            Error::NoIngesters { .. }
+        );
+        assert_error!(
+            "cpu=ingester-0".parse::<IngesterTableRoute>(),
+            Error::InvalidAddress { .. }
+        );
+    }
+}
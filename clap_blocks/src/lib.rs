@@ -18,12 +18,14 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+pub mod audit;
 pub mod catalog_dsn;
 pub mod compactor;
 pub mod compactor_scheduler;
 pub mod garbage_collector;
 pub mod ingester;
 pub mod ingester_address;
+pub mod ingester_table_route;
 pub mod object_store;
 pub mod querier;
 pub mod router;
@@ -1,10 +1,11 @@
 //! Querier-related configs.
 
 use crate::{
+    audit::AuditConfig,
     ingester_address::IngesterAddress,
     single_tenant::{CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG},
 };
-use std::{collections::HashMap, num::NonZeroUsize};
+use std::{collections::HashMap, num::NonZeroUsize, time::Duration};
 
 /// CLI config for querier configuration
 #[derive(Debug, Clone, PartialEq, Eq, clap::Parser)]
@@ -13,6 +14,10 @@ pub struct QuerierConfig {
     #[clap(long = CONFIG_AUTHZ_FLAG, env = CONFIG_AUTHZ_ENV_NAME)]
     pub authz_address: Option<String>,
 
+    /// Configuration of the audit log of queries
+    #[clap(flatten)]
+    pub audit_config: AuditConfig,
+
     /// The number of threads to use for queries.
     ///
     /// If not specified, defaults to the number of cores on the system
@@ -81,6 +86,56 @@ pub struct QuerierConfig {
     )]
     pub max_concurrent_queries: usize,
 
+    /// Limit the number of concurrent interactive queries.
+    ///
+    /// Clients set the priority class of a query with the `iox-query-priority` header (`interactive`, `batch` or
+    /// `background`); queries without that header are interactive. Queries that exceed the limit of their class are
+    /// queued and admitted in priority order once other queries finish. All classes share the
+    /// `--max-concurrent-queries` limit.
+    ///
+    /// Priority classes are only enforced if at least one of the `--max-concurrent-*-queries` limits is set. Unset
+    /// limits default to `--max-concurrent-queries`.
+    #[clap(
+        long = "max-concurrent-interactive-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_INTERACTIVE_QUERIES",
+        action
+    )]
+    pub max_concurrent_interactive_queries: Option<NonZeroUsize>,
+
+    /// Limit the number of concurrent batch queries.
+    ///
+    /// See `--max-concurrent-interactive-queries`.
+    #[clap(
+        long = "max-concurrent-batch-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_BATCH_QUERIES",
+        action
+    )]
+    pub max_concurrent_batch_queries: Option<NonZeroUsize>,
+
+    /// Limit the number of concurrent background queries.
+    ///
+    /// See `--max-concurrent-interactive-queries`.
+    #[clap(
+        long = "max-concurrent-background-queries",
+        env = "INFLUXDB_IOX_MAX_CONCURRENT_BACKGROUND_QUERIES",
+        action
+    )]
+    pub max_concurrent_background_queries: Option<NonZeroUsize>,
+
+    /// Size of the query result cache in bytes. Disabled if not set.
+    ///
+    /// Results of Flight queries are cached and served again as long as the query plan and the set of chunks it reads
+    /// are the same. A result is dropped as soon as the chunk set changes, e.g. because new data was persisted or
+    /// files were compacted. Data buffered by the ingesters is fetched again for every query, so only queries that
+    /// exclusively read persisted data are served from the cache. Results that are larger than the cache are never
+    /// cached.
+    #[clap(
+        long = "query-result-cache-bytes",
+        env = "INFLUXDB_IOX_QUERY_RESULT_CACHE_BYTES",
+        action
+    )]
+    pub query_result_cache_bytes: Option<usize>,
+
     /// After how many ingester query errors should the querier enter circuit breaker mode?
     ///
     /// The querier normally contacts the ingester for any unpersisted data during query planning.
@@ -104,6 +159,39 @@ pub struct QuerierConfig {
     )]
     pub ingester_circuit_breaker_threshold: u64,
 
+    /// Ingester responses that take longer than this count as errors for the circuit breaker.
+    ///
+    /// A single ingester that is overloaded but still answering can otherwise slow down every query. With this
+    /// option, enough consecutive slow responses open the circuit (see
+    /// `--ingester-circuit-breaker-threshold`) and the querier serves results without the unpersisted data of that
+    /// ingester until it recovers. Clients are warned about such incomplete results.
+    ///
+    /// Only the time until the ingester starts answering is measured. Disabled if not set.
+    #[clap(
+        long = "ingester-circuit-breaker-slow-response-threshold",
+        env = "INFLUXDB_IOX_INGESTER_CIRCUIT_BREAKER_SLOW_RESPONSE_THRESHOLD",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub ingester_circuit_breaker_slow_response_threshold: Option<Duration>,
+
+    /// How long the querier may reuse an ingester response for an identical query.
+    ///
+    /// Dashboards often re-issue the same query every few seconds. With a non-zero TTL, the querier caches the
+    /// unpersisted data returned by the ingesters and serves repeated queries from that cache instead of pulling the
+    /// same data over gRPC again. A cached response is discarded early if an ingester reports that one of its
+    /// partitions was persisted since the response was fetched.
+    ///
+    /// Queries may therefore miss data written within the last TTL. A TTL of zero disables the cache.
+    #[clap(
+        long = "ingester-response-cache-ttl",
+        env = "INFLUXDB_IOX_INGESTER_RESPONSE_CACHE_TTL",
+        default_value = "0s",
+        value_parser = humantime::parse_duration,
+        action
+    )]
+    pub ingester_response_cache_ttl: Duration,
+
     /// DataFusion config.
     #[clap(
         long = "datafusion-config",
@@ -113,6 +201,65 @@ pub struct QuerierConfig {
         action
     )]
     pub datafusion_config: HashMap<String, String>,
+
+    /// Maximum number of queries that may run concurrently for a single namespace.
+    ///
+    /// Queries beyond this limit are rejected with `RESOURCE_EXHAUSTED` instead of being queued, so that a single
+    /// tenant cannot take all slots of `--max-concurrent-queries`. Unlimited if not set.
+    #[clap(
+        long = "query-quota-max-concurrent-queries",
+        env = "INFLUXDB_IOX_QUERY_QUOTA_MAX_CONCURRENT_QUERIES",
+        action
+    )]
+    pub query_quota_max_concurrent_queries: Option<usize>,
+
+    /// Maximum number of queries that a single namespace may start within any 60 second window.
+    ///
+    /// Queries beyond this limit are rejected with `RESOURCE_EXHAUSTED`. Unlimited if not set.
+    #[clap(
+        long = "query-quota-queries-per-minute",
+        env = "INFLUXDB_IOX_QUERY_QUOTA_QUERIES_PER_MINUTE",
+        action
+    )]
+    pub query_quota_queries_per_minute: Option<usize>,
+
+    /// Maximum number of bytes that a single query may scan.
+    ///
+    /// The size is estimated during planning from the parquet files and ingester data that remain after pruning.
+    /// Queries above this limit fail with `RESOURCE_EXHAUSTED` before any data is read. Unlimited if not set.
+    #[clap(
+        long = "query-quota-max-scanned-bytes",
+        env = "INFLUXDB_IOX_QUERY_QUOTA_MAX_SCANNED_BYTES",
+        action
+    )]
+    pub query_quota_max_scanned_bytes: Option<u64>,
+
+    /// Per-namespace overrides of the `--query-quota-*` limits.
+    ///
+    /// Comma-separated list of `NAMESPACE.LIMIT:VALUE` entries, where `LIMIT` is one of `max_concurrent_queries`,
+    /// `queries_per_minute` or `max_scanned_bytes`. A value of `unlimited` lifts the respective limit. Limits that
+    /// are not overridden fall back to the defaults. For example:
+    ///
+    /// "big_tenant.max_concurrent_queries:20,big_tenant.max_scanned_bytes:unlimited"
+    #[clap(
+        long = "query-quota-overrides",
+        env = "INFLUXDB_IOX_QUERY_QUOTA_OVERRIDES",
+        default_value = "",
+        value_parser = parse_query_quota_overrides,
+        action
+    )]
+    pub query_quota_overrides: HashMap<String, QueryQuotaOverride>,
+}
+
+/// Per-namespace override of the query quota, see [`QuerierConfig::query_quota_overrides`].
+///
+/// `None` means "use the default", `Some(None)` means "unlimited".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct QueryQuotaOverride {
+    pub max_concurrent_queries: Option<Option<usize>>,
+    pub queries_per_minute: Option<Option<usize>>,
+    pub max_scanned_bytes: Option<Option<u64>>,
 }
 
 impl QuerierConfig {
@@ -138,6 +285,68 @@ impl QuerierConfig {
     }
 }
 
+fn parse_query_quota_overrides(
+    s: &str,
+) -> Result<HashMap<String, QueryQuotaOverride>, Box<dyn std::error::Error + Send + Sync + 'static>>
+{
+    fn parse_limit<T: std::str::FromStr>(
+        value: &str,
+    ) -> Result<Option<T>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+        if value == "unlimited" {
+            return Ok(None);
+        }
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| format!("invalid limit '{value}'").into())
+    }
+
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(HashMap::with_capacity(0));
+    }
+
+    let mut out: HashMap<String, QueryQuotaOverride> = HashMap::new();
+    for part in s.split(',') {
+        let part = part.trim();
+        let Some((key, value)) = part.split_once(':') else {
+            return Err(format!(
+                "Invalid quota override - expected 'NAMESPACE.LIMIT:VALUE' got '{part}'"
+            )
+            .into());
+        };
+        let Some((namespace, limit)) = key.trim().rsplit_once('.') else {
+            return Err(format!(
+                "Invalid quota override - expected 'NAMESPACE.LIMIT:VALUE' got '{part}'"
+            )
+            .into());
+        };
+        let value = value.trim();
+
+        let entry = out.entry(namespace.to_owned()).or_default();
+        let existed = match limit {
+            "max_concurrent_queries" => entry
+                .max_concurrent_queries
+                .replace(parse_limit(value)?)
+                .is_some(),
+            "queries_per_minute" => entry
+                .queries_per_minute
+                .replace(parse_limit(value)?)
+                .is_some(),
+            "max_scanned_bytes" => entry
+                .max_scanned_bytes
+                .replace(parse_limit(value)?)
+                .is_some(),
+            _ => return Err(format!("unknown quota limit '{limit}'").into()),
+        };
+        if existed {
+            return Err(format!("key '{key}' passed multiple times").into());
+        }
+    }
+
+    Ok(out)
+}
+
 fn parse_datafusion_config(
     s: &str,
 ) -> Result<HashMap<String, String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -182,6 +391,58 @@ mod tests {
         assert_eq!(actual.num_query_threads(), None);
         assert!(actual.ingester_addresses.is_empty());
         assert!(actual.datafusion_config.is_empty());
+        assert_eq!(actual.query_quota_max_concurrent_queries, None);
+        assert_eq!(actual.query_quota_queries_per_minute, None);
+        assert_eq!(actual.query_quota_max_scanned_bytes, None);
+        assert!(actual.query_quota_overrides.is_empty());
+        assert_eq!(actual.max_concurrent_interactive_queries, None);
+        assert_eq!(actual.max_concurrent_batch_queries, None);
+        assert_eq!(actual.max_concurrent_background_queries, None);
+        assert_eq!(actual.query_result_cache_bytes, None);
+        assert_eq!(
+            actual.ingester_circuit_breaker_slow_response_threshold,
+            None
+        );
+    }
+
+    #[test]
+    fn test_priority_class_limits() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--max-concurrent-batch-queries",
+            "3",
+            "--max-concurrent-background-queries",
+            "1",
+        ])
+        .unwrap();
+
+        assert_eq!(actual.max_concurrent_interactive_queries, None);
+        assert_eq!(actual.max_concurrent_batch_queries, NonZeroUsize::new(3));
+        assert_eq!(
+            actual.max_concurrent_background_queries,
+            NonZeroUsize::new(1)
+        );
+
+        let err =
+            QuerierConfig::try_parse_from(["my_binary", "--max-concurrent-batch-queries", "0"])
+                .unwrap_err()
+                .to_string();
+        assert_contains!(err, "invalid value '0' for '--max-concurrent-batch-queries");
+    }
+
+    #[test]
+    fn test_slow_response_threshold() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--ingester-circuit-breaker-slow-response-threshold",
+            "500ms",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            actual.ingester_circuit_breaker_slow_response_threshold,
+            Some(Duration::from_millis(500))
+        );
     }
 
     #[test]
@@ -269,4 +530,77 @@ mod tests {
             "error: invalid value 'foo:bar,baz:1,foo:2' for '--datafusion-config <DATAFUSION_CONFIG>': key 'foo' passed multiple times"
         );
     }
+
+    #[test]
+    fn test_query_quota() {
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--query-quota-max-concurrent-queries",
+            "2",
+            "--query-quota-queries-per-minute",
+            "60",
+            "--query-quota-max-scanned-bytes",
+            "1000",
+            "--query-quota-overrides= a.max_concurrent_queries : 5 , a.max_scanned_bytes:unlimited,my.ns.queries_per_minute:1",
+        ])
+        .unwrap();
+
+        assert_eq!(actual.query_quota_max_concurrent_queries, Some(2));
+        assert_eq!(actual.query_quota_queries_per_minute, Some(60));
+        assert_eq!(actual.query_quota_max_scanned_bytes, Some(1000));
+        assert_eq!(
+            actual.query_quota_overrides,
+            HashMap::from([
+                (
+                    String::from("a"),
+                    QueryQuotaOverride {
+                        max_concurrent_queries: Some(Some(5)),
+                        queries_per_minute: None,
+                        max_scanned_bytes: Some(None),
+                    }
+                ),
+                (
+                    String::from("my.ns"),
+                    QueryQuotaOverride {
+                        max_concurrent_queries: None,
+                        queries_per_minute: Some(Some(1)),
+                        max_scanned_bytes: None,
+                    }
+                ),
+            ]),
+        );
+    }
+
+    #[test]
+    fn bad_query_quota_overrides() {
+        let actual = QuerierConfig::try_parse_from(["my_binary", "--query-quota-overrides=a:1"])
+            .unwrap_err()
+            .to_string();
+        assert_contains!(
+            actual,
+            "Invalid quota override - expected 'NAMESPACE.LIMIT:VALUE' got 'a:1'"
+        );
+
+        let actual =
+            QuerierConfig::try_parse_from(["my_binary", "--query-quota-overrides=a.foo:1"])
+                .unwrap_err()
+                .to_string();
+        assert_contains!(actual, "unknown quota limit 'foo'");
+
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--query-quota-overrides=a.max_scanned_bytes:lots",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(actual, "invalid limit 'lots'");
+
+        let actual = QuerierConfig::try_parse_from([
+            "my_binary",
+            "--query-quota-overrides=a.queries_per_minute:1,a.queries_per_minute:2",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(actual, "key 'a.queries_per_minute' passed multiple times");
+    }
 }
@@ -1,7 +1,9 @@
 //! CLI config for the router using the RPC write path
 
 use crate::{
+    audit::AuditConfig,
     ingester_address::IngesterAddress,
+    ingester_table_route::IngesterTableRoute,
     single_tenant::{
         CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG, CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG,
     },
@@ -16,13 +18,19 @@ use std::{
 #[allow(missing_copy_implementations)]
 pub struct RouterConfig {
     /// Addr for connection to authz
+    ///
+    /// If set, writes and bucket requests must be authorised in both single
+    /// and multi-tenant deployments. Single-tenant deployments require it.
     #[clap(
         long = CONFIG_AUTHZ_FLAG,
         env = CONFIG_AUTHZ_ENV_NAME,
-        requires("single_tenant_deployment"),
     )]
     pub authz_address: Option<String>,
 
+    /// Configuration of the audit log of writes
+    #[clap(flatten)]
+    pub audit_config: AuditConfig,
+
     /// Differential handling based upon deployment to CST vs MT.
     ///
     /// At minimum, differs in supports of v1 endpoint. But also includes
@@ -52,6 +60,19 @@ pub struct RouterConfig {
     )]
     pub http_request_limit: usize,
 
+    /// Maximum size of a gzip or zstd compressed HTTP request body once
+    /// decompressed.
+    ///
+    /// Compressed request bodies are limited to --max-http-request-size
+    /// before being decompressed. If unset, the decompressed body is limited
+    /// to the same size.
+    #[clap(
+        long = "max-http-request-decompressed-size",
+        env = "INFLUXDB_IOX_MAX_HTTP_REQUEST_DECOMPRESSED_SIZE",
+        action
+    )]
+    pub max_http_request_decompressed_size: Option<usize>,
+
     /// gRPC address for the router to talk with the ingesters. For
     /// example:
     ///
@@ -71,6 +92,28 @@ pub struct RouterConfig {
     )]
     pub ingester_addresses: Vec<IngesterAddress>,
 
+    /// Route the writes of tables with a name matching a regex to a dedicated
+    /// set of ingesters instead of the ingesters given in
+    /// `--ingester-addresses`, to pin high-volume tables to their own
+    /// ingesters. For example:
+    ///
+    /// "cpu|mem_.*=http://10.10.10.3:8083;http://10.10.10.4:8083"
+    ///
+    /// The regex must match the full table name and cannot contain a comma, as
+    /// multiple routes are separated by a comma. If a table matches multiple routes, the first
+    /// one is used. Writes are replicated and balanced across the ingesters of
+    /// a route in the same way as across the ingesters in
+    /// `--ingester-addresses`, so each route must have at least
+    /// `--rpc-write-replicas` ingesters.
+    #[clap(
+        long = "ingester-table-routes",
+        env = "INFLUXDB_IOX_INGESTER_TABLE_ROUTES",
+        required = false,
+        num_args=1..,
+        value_delimiter = ','
+    )]
+    pub ingester_table_routes: Vec<IngesterTableRoute>,
+
     /// Retention period to use when auto-creating namespaces.
     /// For infinite retention, leave this unset and it will default to `None`.
     /// Setting it to zero will not make it infinite.
@@ -137,6 +180,125 @@ pub struct RouterConfig {
         value_parser = parse_duration
     )]
     pub rpc_write_health_error_window_seconds: Duration,
+
+    /// gRPC address of a secondary set of ingesters that every write is
+    /// mirrored to, for example to keep a warm-standby deployment up to date.
+    ///
+    /// The secondary ingesters are written to with the same replication,
+    /// timeout and health settings as the primary ingesters.
+    #[clap(
+        long = "secondary-ingester-addresses",
+        env = "INFLUXDB_IOX_SECONDARY_INGESTER_ADDRESSES",
+        required = false,
+        num_args=1..,
+        value_delimiter = ','
+    )]
+    pub secondary_ingester_addresses: Vec<IngesterAddress>,
+
+    /// Specify how writes are mirrored to the secondary ingesters.
+    ///
+    /// In "fire-and-forget" mode, failures to mirror a write are logged but
+    /// do not fail the write request. In "quorum" mode, a write request only
+    /// succeeds if it was written to both the primary and secondary
+    /// ingesters.
+    ///
+    /// Ignored if no secondary ingester addresses are configured.
+    #[clap(
+        value_enum,
+        long = "secondary-write-mode",
+        env = "INFLUXDB_IOX_SECONDARY_WRITE_MODE",
+        default_value = "fire-and-forget",
+        action
+    )]
+    pub secondary_write_mode: SecondaryWriteMode,
+
+    /// Specify how often the write rate limits of all namespaces are reloaded
+    /// from the catalog.
+    ///
+    /// Changes to the write rate limits of a namespace take up to this long to
+    /// take effect.
+    #[clap(
+        long = "namespace-rate-limit-refresh-seconds",
+        env = "INFLUXDB_IOX_NAMESPACE_RATE_LIMIT_REFRESH_SECONDS",
+        default_value = "10",
+        value_parser = parse_duration
+    )]
+    pub namespace_rate_limit_refresh_seconds: Duration,
+
+    /// Specify the maximum age of a cached namespace schema before it is
+    /// refreshed from the catalog.
+    ///
+    /// Changes made to a namespace by other nodes, such as a new retention
+    /// period or table limit, take up to this long to take effect. If unset,
+    /// cached schemas are never refreshed.
+    #[clap(
+        long = "namespace-cache-ttl-seconds",
+        env = "INFLUXDB_IOX_NAMESPACE_CACHE_TTL_SECONDS",
+        value_parser = parse_duration
+    )]
+    pub namespace_cache_ttl_seconds: Option<Duration>,
+
+    /// Specify how long a namespace the catalog reports as not existing is
+    /// remembered, rejecting writes to it without querying the catalog.
+    ///
+    /// A namespace created during this window cannot be written to until it
+    /// has passed. If unset, every write to a nonexistent namespace queries
+    /// the catalog.
+    ///
+    /// Ignored if namespace-autocreation-enabled is set to true.
+    #[clap(
+        long = "namespace-cache-negative-ttl-seconds",
+        env = "INFLUXDB_IOX_NAMESPACE_CACHE_NEGATIVE_TTL_SECONDS",
+        value_parser = parse_duration
+    )]
+    pub namespace_cache_negative_ttl_seconds: Option<Duration>,
+
+    /// Specify how often the schemas of all namespaces are reloaded from the
+    /// catalog and merged into the namespace cache.
+    ///
+    /// This allows the router to learn of tables and columns added by other
+    /// routers without waiting for a write to miss the cache. If unset, the
+    /// cache is not refreshed in the background.
+    #[clap(
+        long = "namespace-cache-refresh-interval-seconds",
+        env = "INFLUXDB_IOX_NAMESPACE_CACHE_REFRESH_INTERVAL_SECONDS",
+        value_parser = parse_duration
+    )]
+    pub namespace_cache_refresh_interval_seconds: Option<Duration>,
+
+    /// Specify the prefix of the table names the metrics received by the OTLP
+    /// metrics service are written to.
+    ///
+    /// Each metric is written to a table named after the metric, prefixed
+    /// with this string.
+    #[clap(
+        long = "otlp-metrics-table-prefix",
+        env = "INFLUXDB_IOX_OTLP_METRICS_TABLE_PREFIX",
+        default_value = "",
+        conflicts_with = "otlp_metrics_single_table"
+    )]
+    pub otlp_metrics_table_prefix: String,
+
+    /// Specify a single table all metrics received by the OTLP metrics service
+    /// are written to, with the metric name as the "metric_name" tag.
+    ///
+    /// If unset, each metric is written to its own table.
+    #[clap(
+        long = "otlp-metrics-single-table",
+        env = "INFLUXDB_IOX_OTLP_METRICS_SINGLE_TABLE"
+    )]
+    pub otlp_metrics_single_table: Option<String>,
+}
+
+/// How writes are mirrored to the secondary ingesters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SecondaryWriteMode {
+    /// Mirror writes in the background, ignoring failures.
+    #[default]
+    FireAndForget,
+
+    /// Fail write requests that cannot be mirrored.
+    Quorum,
 }
 
 /// Map a string containing an integer number of seconds into a [`Duration`].
@@ -1,6 +1,7 @@
 //! Main compactor entry point.
 use std::sync::Arc;
 
+use compactor_scheduler::CompactionBacklog;
 use futures::{
     future::{BoxFuture, Shared},
     FutureExt, TryFutureExt,
@@ -17,6 +18,7 @@ use crate::{
     },
     config::Config,
     driver::compact,
+    memory_budget::MemoryBudget,
 };
 
 /// A [`JoinHandle`] that can be cloned
@@ -32,6 +34,7 @@ fn shared_handle(handle: JoinHandle<()>) -> SharedJoinHandle {
 pub struct Compactor {
     shutdown: CancellationToken,
     worker: SharedJoinHandle,
+    backlog: CompactionBacklog,
 }
 
 impl Compactor {
@@ -45,12 +48,17 @@ impl Compactor {
 
         let components = hardcoded_components(&config);
         log_components(&components);
+        let backlog = components.backlog.clone();
 
         let semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
             &config.metric_registry,
             &[("semaphore", "job")],
         ));
         let df_semaphore = Arc::new(semaphore_metrics.new_semaphore(config.df_concurrency.get()));
+        let memory_budget = Arc::new(MemoryBudget::new(
+            config.memory_budget_bytes,
+            &config.metric_registry,
+        ));
 
         let worker = tokio::spawn(async move {
             tokio::select! {
@@ -61,6 +69,7 @@ impl Compactor {
                         config.partition_concurrency,
                         config.partition_timeout,
                         Arc::clone(&df_semaphore),
+                        Arc::clone(&memory_budget),
                         &components
                     ).await;
 
@@ -70,7 +79,16 @@ impl Compactor {
         });
         let worker = shared_handle(worker);
 
-        Self { shutdown, worker }
+        Self {
+            shutdown,
+            worker,
+            backlog,
+        }
+    }
+
+    /// Backlog of this compactor.
+    pub fn backlog(&self) -> CompactionBacklog {
+        self.backlog.clone()
     }
 
     /// Trigger shutdown. You should [join](Self::join) afterwards.
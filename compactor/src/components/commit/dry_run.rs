@@ -0,0 +1,135 @@
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicI64, Ordering},
+};
+
+use async_trait::async_trait;
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
+use observability_deps::tracing::info;
+
+use crate::DynError;
+
+use super::Commit;
+
+/// Logs the changes that would be committed instead of committing them.
+///
+/// Created files are handed out negative IDs so they can never be confused with files that exist
+/// in the catalog.
+#[derive(Debug)]
+pub struct DryRunCommit {
+    id_counter: AtomicI64,
+}
+
+impl DryRunCommit {
+    pub fn new() -> Self {
+        Self {
+            id_counter: AtomicI64::new(-1),
+        }
+    }
+}
+
+impl Default for DryRunCommit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display for DryRunCommit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dry_run")
+    }
+}
+
+#[async_trait]
+impl Commit for DryRunCommit {
+    async fn commit(
+        &self,
+        partition_id: PartitionId,
+        delete: &[ParquetFile],
+        upgrade: &[ParquetFile],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) -> Result<Vec<ParquetFileId>, DynError> {
+        let created = create
+            .iter()
+            .map(|_| ParquetFileId::new(self.id_counter.fetch_sub(1, Ordering::SeqCst)))
+            .collect::<Vec<_>>();
+
+        info!(
+            target_level=?target_level,
+            partition_id=partition_id.get(),
+            files_delete=delete.len(),
+            files_upgrade=upgrade.len(),
+            files_create=create.len(),
+            bytes_delete=delete.iter().map(|f| f.file_size_bytes).sum::<i64>(),
+            bytes_upgrade=upgrade.iter().map(|f| f.file_size_bytes).sum::<i64>(),
+            bytes_create=create.iter().map(|f| f.file_size_bytes).sum::<i64>(),
+            rows_delete=delete.iter().map(|f| f.row_count).sum::<i64>(),
+            rows_upgrade=upgrade.iter().map(|f| f.row_count).sum::<i64>(),
+            rows_create=create.iter().map(|f| f.row_count).sum::<i64>(),
+            delete=?delete.iter().map(|f| f.id.get()).collect::<Vec<_>>(),
+            upgrade=?upgrade.iter().map(|f| f.id.get()).collect::<Vec<_>>(),
+            create=?created.iter().map(|id| id.get()).collect::<Vec<_>>(),
+            "dry run: would commit parquet file change",
+        );
+
+        Ok(created)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+    use test_helpers::tracing::TracingCapture;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(DryRunCommit::new().to_string(), "dry_run");
+    }
+
+    #[tokio::test]
+    async fn test_commit() {
+        let commit = DryRunCommit::new();
+
+        let existing_1 = ParquetFileBuilder::new(1).with_row_count(10).build();
+        let existing_2 = ParquetFileBuilder::new(2).with_row_count(20).build();
+        let created_1 = ParquetFileBuilder::new(1000).with_row_count(30).build();
+        let created_2 = ParquetFileBuilder::new(1001).with_row_count(40).build();
+
+        let capture = TracingCapture::new();
+
+        let ids = commit
+            .commit(
+                PartitionId::new(1),
+                &[existing_1],
+                &[existing_2],
+                &[created_1.clone().into(), created_2.clone().into()],
+                CompactionLevel::FileNonOverlapped,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![ParquetFileId::new(-1), ParquetFileId::new(-2)]);
+
+        let ids = commit
+            .commit(
+                PartitionId::new(2),
+                &[],
+                &[],
+                &[created_1.into()],
+                CompactionLevel::Final,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![ParquetFileId::new(-3)]);
+
+        let logs = capture.to_string();
+        assert!(logs.contains("dry run: would commit parquet file change"));
+        assert!(logs.contains("partition_id = 1;"));
+        assert!(logs.contains("rows_create = 70;"));
+        assert!(logs.contains("create = [-1, -2];"));
+        assert!(logs.contains("partition_id = 2;"));
+        assert!(logs.contains("create = [-3];"));
+    }
+}
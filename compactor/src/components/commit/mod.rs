@@ -0,0 +1,25 @@
+use std::fmt::{Debug, Display};
+
+use async_trait::async_trait;
+use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
+
+use crate::DynError;
+
+pub mod dry_run;
+pub mod scheduler;
+
+/// Commits the outcome of a compaction round (i.e. deletion, upgrade and creation of files).
+#[async_trait]
+pub trait Commit: Debug + Display + Send + Sync {
+    /// Commit deletions, upgrades and creations in a single transaction.
+    ///
+    /// Returns the IDs of the created files.
+    async fn commit(
+        &self,
+        partition_id: PartitionId,
+        delete: &[ParquetFile],
+        upgrade: &[ParquetFile],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) -> Result<Vec<ParquetFileId>, DynError>;
+}
@@ -1,11 +1,17 @@
 use std::sync::Arc;
 
+use async_trait::async_trait;
 use compactor_scheduler::{
     CommitUpdate, CompactionJob, CompactionJobStatus, CompactionJobStatusResponse,
     CompactionJobStatusVariant, Scheduler,
 };
 use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
 
+use crate::DynError;
+
+use super::Commit;
+
+/// Commits changes via the [`Scheduler`].
 #[derive(Debug)]
 pub struct CommitToScheduler {
     scheduler: Arc<dyn Scheduler>,
@@ -15,15 +21,18 @@ impl CommitToScheduler {
     pub fn new(scheduler: Arc<dyn Scheduler>) -> Self {
         Self { scheduler }
     }
+}
 
-    pub async fn commit(
+#[async_trait]
+impl Commit for CommitToScheduler {
+    async fn commit(
         &self,
         partition_id: PartitionId,
         delete: &[ParquetFile],
         upgrade: &[ParquetFile],
         create: &[ParquetFileParams],
         target_level: CompactionLevel,
-    ) -> Result<Vec<ParquetFileId>, crate::DynError> {
+    ) -> Result<Vec<ParquetFileId>, DynError> {
         match self
             .scheduler
             .update_job_status(CompactionJobStatus {
@@ -40,6 +49,9 @@ impl CommitToScheduler {
         {
             CompactionJobStatusResponse::CreatedParquetFiles(ids) => Ok(ids),
             CompactionJobStatusResponse::Ack => unreachable!("scheduler should not ack"),
+            CompactionJobStatusResponse::Cancelled => {
+                unreachable!("scheduler should only cancel heartbeats")
+            }
         }
     }
 }
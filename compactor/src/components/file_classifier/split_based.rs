@@ -53,6 +53,15 @@ use super::FileClassifier;
 ///                      |  (files upgrade)        |            :
 ///                      |                         |            :
 ///                      |     +................................+
+///                      |     :                   |            :
+///                      V     V                   |            :
+///             [time slice split (FS)]            |            :
+///                      |     |                   |            :
+///                      |     |                   |            :
+///                      |     +-------------------+            :
+///                      |                         |            :
+///                      |                         |            :
+///                      |     +................................+
 ///                      |     |                   |
 ///                      V     V                   |
 ///            [split or compact (FSC)]            |
@@ -63,62 +72,72 @@ use super::FileClassifier;
 ///      (files compact or split)            (files keep)
 /// ```
 #[derive(Debug)]
-pub struct SplitBasedFileClassifier<FT, FO, FU, FSC>
+pub struct SplitBasedFileClassifier<FT, FO, FU, FS, FSC>
 where
     FT: FilesSplit,
     FO: FilesSplit,
     FU: FilesSplit,
+    FS: FilesSplit,
     FSC: SplitOrCompact,
 {
     target_level_split: FT,
     non_overlap_split: FO,
     upgrade_split: FU,
+    time_slice_split: FS,
     split_or_compact: FSC,
 }
 
-impl<FT, FO, FU, FSC> SplitBasedFileClassifier<FT, FO, FU, FSC>
+impl<FT, FO, FU, FS, FSC> SplitBasedFileClassifier<FT, FO, FU, FS, FSC>
 where
     FT: FilesSplit,
     FO: FilesSplit,
     FU: FilesSplit,
+    FS: FilesSplit,
     FSC: SplitOrCompact,
 {
     pub fn new(
         target_level_split: FT,
         non_overlap_split: FO,
         upgrade_split: FU,
+        time_slice_split: FS,
         split_or_compact: FSC,
     ) -> Self {
         Self {
             target_level_split,
             non_overlap_split,
             upgrade_split,
+            time_slice_split,
             split_or_compact,
         }
     }
 }
 
-impl<FT, FO, FU, FSC> Display for SplitBasedFileClassifier<FT, FO, FU, FSC>
+impl<FT, FO, FU, FS, FSC> Display for SplitBasedFileClassifier<FT, FO, FU, FS, FSC>
 where
     FT: FilesSplit,
     FO: FilesSplit,
     FU: FilesSplit,
+    FS: FilesSplit,
     FSC: SplitOrCompact,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "split_based(target_level_split={}, non_overlap_split={}, upgrade_split={})",
-            self.target_level_split, self.non_overlap_split, self.upgrade_split,
+            "split_based(target_level_split={}, non_overlap_split={}, upgrade_split={}, time_slice_split={})",
+            self.target_level_split,
+            self.non_overlap_split,
+            self.upgrade_split,
+            self.time_slice_split,
         )
     }
 }
 
-impl<FT, FO, FU, FSC> FileClassifier for SplitBasedFileClassifier<FT, FO, FU, FSC>
+impl<FT, FO, FU, FS, FSC> FileClassifier for SplitBasedFileClassifier<FT, FO, FU, FS, FSC>
 where
     FT: FilesSplit,
     FO: FilesSplit,
     FU: FilesSplit,
+    FS: FilesSplit,
     FSC: SplitOrCompact,
 {
     fn classify(
@@ -160,6 +179,12 @@ where
         let (files_to_compact, files_to_upgrade) =
             self.upgrade_split.apply(files_to_compact, target_level);
 
+        // Bound the input of this round to one time slice if there is a pathological backlog of
+        // start-level files. Files in later time slices are kept for the next rounds
+        let (files_to_compact, files_in_later_slices) =
+            self.time_slice_split.apply(files_to_compact, target_level);
+        files_to_keep.extend(files_in_later_slices);
+
         // See if we need to split start-level files due to over compaction size limit
         let (files_to_split_or_compact, other_files) =
             self.split_or_compact
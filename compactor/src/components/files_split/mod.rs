@@ -4,6 +4,7 @@ use data_types::{CompactionLevel, ParquetFile};
 
 pub mod non_overlap_split;
 pub mod target_level_split;
+pub mod time_slice_split;
 pub mod upgrade_split;
 
 pub trait FilesSplit: Debug + Display + Send + Sync {
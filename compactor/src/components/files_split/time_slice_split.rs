@@ -0,0 +1,306 @@
+use std::fmt::Display;
+
+use data_types::{CompactionLevel, ParquetFile};
+
+use crate::{
+    components::split_or_compact::start_level_files_to_split::split_into_chains,
+    file_group::{split_by_level, FilesTimeRange},
+};
+
+use super::FilesSplit;
+
+/// Split files of a partition with a pathological backlog of start-level files into
+/// `[files_in_time_slice]` and `[files_in_later_slices]`.
+///
+/// A partition that has accumulated hundreds of overlapping L0 files cannot be compacted in one
+/// go without risking running out of memory. This split bounds the input of a single round to
+/// one contiguous time slice of the partition and keeps the remaining files for later rounds.
+#[derive(Debug)]
+pub struct TimeSliceSplit {
+    /// Number of start-level files above which a partition is considered pathological.
+    /// Nothing is ever split if this is `None`.
+    min_num_files: Option<usize>,
+
+    /// Number of files a time slice should stay under.
+    max_num_files: usize,
+
+    /// Number of bytes a time slice should stay under.
+    max_compact_size: usize,
+}
+
+impl TimeSliceSplit {
+    pub fn new(
+        min_num_files: Option<usize>,
+        max_num_files: usize,
+        max_compact_size: usize,
+    ) -> Self {
+        Self {
+            min_num_files,
+            max_num_files,
+            max_compact_size,
+        }
+    }
+}
+
+impl Display for TimeSliceSplit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "time_slice_split({:?}, {}, {})",
+            self.min_num_files, self.max_num_files, self.max_compact_size
+        )
+    }
+}
+
+impl FilesSplit for TimeSliceSplit {
+    /// Return (`[files_in_time_slice]`, `[files_in_later_slices]`) of given files.
+    ///
+    /// Nothing is split unless there are more than `min_num_files` start-level files and
+    /// all files are larger than `max_compact_size` in total.
+    ///
+    /// Example:
+    ///  . Input:
+    ///        |--L0.1--|          |--L0.4--|      |--L0.6--|
+    ///          |--L0.2--|          |--L0.5--|      |--L0.7--|
+    ///        |--L0.3--|
+    ///      |--L1.1--|  |--L1.2--|    |--L1.3--|
+    ///
+    ///    There are three chains of overlapping start-level files: (L0.1, L0.2, L0.3),
+    ///    (L0.4, L0.5) and (L0.6, L0.7).
+    ///
+    ///  . Output, assuming only the first two chains fit into `max_compact_size`:
+    ///     . files_in_time_slice: [L0.1, L0.2, L0.3, L0.4, L0.5, L1.1, L1.2, L1.3]
+    ///     . files_in_later_slices: [L0.6, L0.7]
+    ///
+    /// Algorithm:
+    ///    Start-level files are divided into chains of overlapping files. Chains do not overlap
+    ///    each other, so each chain can be compacted independently of any newer or older chain.
+    ///    Chains are taken in time order, together with the target-level files they overlap, for
+    ///    as long as the slice stays under `max_compact_size` and `max_num_files`. The first chain
+    ///    is always taken.
+    ///
+    ///    If all start-level files form a single chain, i.e. they all overlap, there is no time at
+    ///    which the partition can be sliced and all files are returned as `files_in_time_slice`.
+    ///    Those files are then vertically split by
+    ///    [`SplitCompact`](crate::components::split_or_compact::split_compact::SplitCompact),
+    ///    which produces chains this split can slice in the following rounds.
+    fn apply(
+        &self,
+        files: Vec<ParquetFile>,
+        target_level: CompactionLevel,
+    ) -> (Vec<ParquetFile>, Vec<ParquetFile>) {
+        // Panic if given wrong target level, L0
+        assert_ne!(target_level, CompactionLevel::Initial);
+
+        let Some(min_num_files) = self.min_num_files else {
+            return (files, vec![]);
+        };
+
+        let prev_level = target_level.prev();
+        let num_start_level_files = files
+            .iter()
+            .filter(|f| f.compaction_level == prev_level)
+            .count();
+        let total_size: usize = files.iter().map(|f| f.file_size_bytes as usize).sum();
+        if num_start_level_files <= min_num_files || total_size <= self.max_compact_size {
+            return (files, vec![]);
+        }
+
+        let (prev_level_files, target_level_files) =
+            split_by_level(files, prev_level, target_level);
+
+        let chains = split_into_chains(prev_level_files);
+        if chains.len() == 1 {
+            // all start-level files overlap, nothing to slice
+            let mut files = chains.into_iter().flatten().collect::<Vec<_>>();
+            files.extend(target_level_files);
+            return (files, vec![]);
+        }
+
+        let mut target_level_files = target_level_files;
+        let mut files_in_slice = Vec::with_capacity(num_start_level_files);
+        let mut files_to_keep = Vec::with_capacity(num_start_level_files);
+        let mut slice_size = 0;
+        let mut slice_count = 0;
+
+        let mut chains = chains.into_iter();
+        for chain in chains.by_ref() {
+            let range = FilesTimeRange::try_new(&chain).expect("chains are never empty");
+            let (overlapping, remaining): (Vec<_>, Vec<_>) = target_level_files
+                .into_iter()
+                .partition(|f| range.contains(f));
+            target_level_files = remaining;
+
+            let chain_size: usize = chain
+                .iter()
+                .chain(overlapping.iter())
+                .map(|f| f.file_size_bytes as usize)
+                .sum();
+            let chain_count = chain.len() + overlapping.len();
+
+            if !files_in_slice.is_empty()
+                && (slice_size + chain_size > self.max_compact_size
+                    || slice_count + chain_count > self.max_num_files)
+            {
+                files_to_keep.extend(chain);
+                files_to_keep.extend(overlapping);
+                break;
+            }
+
+            slice_size += chain_size;
+            slice_count += chain_count;
+            files_in_slice.extend(chain);
+            files_in_slice.extend(overlapping);
+        }
+
+        files_to_keep.extend(chains.flatten());
+        files_to_keep.extend(target_level_files);
+
+        (files_in_slice, files_to_keep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+
+    use super::*;
+
+    fn l0(id: i64, min_time: i64, max_time: i64, size: i64) -> ParquetFile {
+        ParquetFileBuilder::new(id)
+            .with_compaction_level(CompactionLevel::Initial)
+            .with_time_range(min_time, max_time)
+            .with_file_size_bytes(size)
+            .build()
+    }
+
+    fn l1(id: i64, min_time: i64, max_time: i64, size: i64) -> ParquetFile {
+        ParquetFileBuilder::new(id)
+            .with_compaction_level(CompactionLevel::FileNonOverlapped)
+            .with_time_range(min_time, max_time)
+            .with_file_size_bytes(size)
+            .build()
+    }
+
+    fn ids(files: &[ParquetFile]) -> Vec<i64> {
+        let mut ids = files.iter().map(|f| f.id.get()).collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            TimeSliceSplit::new(Some(10), 20, 100).to_string(),
+            "time_slice_split(Some(10), 20, 100)"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_target_level() {
+        TimeSliceSplit::new(Some(1), 1, 1).apply(vec![], CompactionLevel::Initial);
+    }
+
+    #[test]
+    fn test_apply_empty_files() {
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(1), 1, 1).apply(vec![], CompactionLevel::FileNonOverlapped);
+        assert!(slice.is_empty());
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_apply_disabled() {
+        let files = vec![l0(1, 0, 10, 100), l0(2, 20, 30, 100), l0(3, 40, 50, 100)];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(None, 1, 10).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2, 3]);
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_apply_under_file_limit() {
+        let files = vec![l0(1, 0, 10, 100), l0(2, 20, 30, 100), l1(11, 0, 30, 100)];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(2), 2, 10).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2, 11]);
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_apply_under_size_limit() {
+        let files = vec![l0(1, 0, 10, 1), l0(2, 20, 30, 1), l0(3, 40, 50, 1)];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(1), 1, 10).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2, 3]);
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_apply_all_overlapping() {
+        let files = (1..=10).map(|id| l0(id, id, 100 + id, 10)).collect();
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(5), 5, 50).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), (1..=10).collect::<Vec<_>>());
+        assert!(keep.is_empty());
+    }
+
+    #[test]
+    fn test_apply_slices_by_size() {
+        let files = vec![
+            // chain 1
+            l0(1, 0, 10, 10),
+            l0(2, 5, 15, 10),
+            // chain 2
+            l0(3, 20, 30, 10),
+            l0(4, 25, 35, 10),
+            // chain 3
+            l0(5, 40, 50, 10),
+            l0(6, 45, 55, 10),
+            // overlapping chain 1 and 2
+            l1(11, 0, 22, 10),
+            // overlapping chain 3
+            l1(12, 40, 60, 10),
+        ];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(5), 5, 55).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2, 3, 4, 11]);
+        assert_eq!(ids(&keep), vec![5, 6, 12]);
+    }
+
+    #[test]
+    fn test_apply_slices_by_count() {
+        let files = vec![
+            l0(1, 0, 10, 10),
+            l0(2, 20, 30, 10),
+            l0(3, 40, 50, 10),
+            l0(4, 60, 70, 10),
+        ];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(2), 2, 35).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2]);
+        assert_eq!(ids(&keep), vec![3, 4]);
+    }
+
+    #[test]
+    fn test_apply_first_chain_over_limit() {
+        let files = vec![
+            l0(1, 0, 10, 100),
+            l0(2, 5, 15, 100),
+            l0(3, 20, 30, 1),
+            l1(11, 50, 60, 1),
+        ];
+
+        let (slice, keep) =
+            TimeSliceSplit::new(Some(2), 2, 50).apply(files, CompactionLevel::FileNonOverlapped);
+        assert_eq!(ids(&slice), vec![1, 2]);
+        assert_eq!(ids(&keep), vec![3, 11]);
+    }
+}
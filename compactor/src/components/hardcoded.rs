@@ -4,7 +4,7 @@
 
 use std::{sync::Arc, time::Duration};
 
-use compactor_scheduler::{create_scheduler, Scheduler};
+use compactor_scheduler::{create_scheduler, CompactionBacklog, Scheduler};
 use data_types::CompactionLevel;
 use object_store::memory::InMemory;
 
@@ -12,7 +12,7 @@ use crate::{config::Config, error::ErrorKind, object_store::ignore_writes::Ignor
 
 use super::{
     changed_files_filter::logging::LoggingChangedFiles,
-    commit::CommitToScheduler,
+    commit::{dry_run::DryRunCommit, scheduler::CommitToScheduler, Commit},
     df_plan_exec::{
         dedicated::DedicatedDataFusionPlanExec, noop::NoopDataFusionPlanExec, DataFusionPlanExec,
     },
@@ -25,19 +25,25 @@ use super::{
     file_filter::level_range::LevelRangeFileFilter,
     files_split::{
         non_overlap_split::NonOverlapSplit, target_level_split::TargetLevelSplit,
-        upgrade_split::UpgradeSplit,
+        time_slice_split::TimeSliceSplit, upgrade_split::UpgradeSplit,
     },
     ir_planner::{logging::LoggingIRPlannerWrapper, planner_v1::V1IRPlanner, IRPlanner},
+    job_heartbeat::{noop::NoopJobHeartbeat, scheduler::JobHeartbeatToScheduler, JobHeartbeat},
     namespaces_source::catalog::CatalogNamespacesSource,
+    output_verifier::{
+        noop::NoopOutputVerifier, object_store::ObjectStoreOutputVerifier, OutputVerifier,
+    },
     parquet_file_sink::{
         dedicated::DedicatedExecParquetFileSinkWrapper, logging::LoggingParquetFileSinkWrapper,
         object_store::ObjectStoreParquetFileSink,
     },
-    parquet_files_sink::{dispatch::DispatchParquetFilesSink, ParquetFilesSink},
+    parquet_files_sink::{
+        dispatch::DispatchParquetFilesSink, dry_run::DryRunParquetFilesSink, ParquetFilesSink,
+    },
     partition_done_sink::{
         error_kind::ErrorKindPartitionDoneSinkWrapper, logging::LoggingPartitionDoneSinkWrapper,
         metrics::MetricsPartitionDoneSinkWrapper, outcome::PartitionDoneSinkToScheduler,
-        PartitionDoneSink,
+        skip_details::SkipDetailsPartitionDoneSinkWrapper, PartitionDoneSink,
     },
     partition_files_source::{
         catalog::{CatalogPartitionFilesSource, QueryRateLimiter},
@@ -62,7 +68,8 @@ use super::{
     },
     partitions_source::{
         logging::LoggingPartitionsSourceWrapper, metrics::MetricsPartitionsSourceWrapper,
-        not_empty::NotEmptyPartitionsSourceWrapper,
+        not_empty::NotEmptyPartitionsSourceWrapper, not_paused::NotPausedPartitionsSourceWrapper,
+        priority_order::PriorityOrderPartitionsSourceWrapper,
         randomize_order::RandomizeOrderPartitionsSourcesWrapper,
         scheduled::ScheduledPartitionsSource, PartitionsSource,
     },
@@ -80,6 +87,9 @@ use super::{
         split_compact::SplitCompact,
     },
     tables_source::catalog::CatalogTablesSource,
+    target_file_size::{
+        fixed::FixedTargetFileSize, throughput::ThroughputTargetFileSize, TargetFileSize,
+    },
     Components,
 };
 
@@ -90,7 +100,8 @@ pub fn hardcoded_components(config: &Config) -> Arc<Components> {
         Arc::clone(&config.catalog),
         Arc::clone(&config.time_provider),
         Arc::clone(&config.metric_registry),
-        config.shadow_mode,
+        // the scheduler must not write to the catalog in dry-run mode either
+        config.shadow_mode || config.dry_run,
     );
     let (partitions_source, commit, partition_done_sink) =
         make_partitions_source_commit_partition_sink(config, Arc::clone(&scheduler));
@@ -113,6 +124,10 @@ pub fn hardcoded_components(config: &Config) -> Arc<Components> {
         file_classifier: make_file_classifier(config),
         post_classification_partition_filter: make_post_classification_partition_filter(config),
         changed_files_filter: Arc::new(LoggingChangedFiles::new()),
+        target_file_size: make_target_file_size(config),
+        job_heartbeat: make_job_heartbeat(config, scheduler),
+        output_verifier: make_output_verifier(config),
+        backlog: CompactionBacklog::new(&config.metric_registry, Arc::clone(&config.time_provider)),
     })
 }
 
@@ -121,14 +136,33 @@ fn make_partitions_source_commit_partition_sink(
     scheduler: Arc<dyn Scheduler>,
 ) -> (
     Arc<dyn PartitionsSource>,
-    Arc<CommitToScheduler>,
+    Arc<dyn Commit>,
     Arc<dyn PartitionDoneSink>,
 ) {
-    let partitions_source = ScheduledPartitionsSource::new(Arc::clone(&scheduler));
+    let partitions_source = NotPausedPartitionsSourceWrapper::new(
+        ScheduledPartitionsSource::new(Arc::clone(&scheduler)),
+        config.backoff_config.clone(),
+        Arc::clone(&config.catalog),
+    );
 
-    let commit = CommitToScheduler::new(Arc::clone(&scheduler));
+    let commit: Arc<dyn Commit> = if config.dry_run {
+        Arc::new(DryRunCommit::new())
+    } else {
+        Arc::new(CommitToScheduler::new(Arc::clone(&scheduler)))
+    };
 
-    let partition_done_sink = PartitionDoneSinkToScheduler::new(Arc::clone(&scheduler));
+    let partition_done_sink: Arc<dyn PartitionDoneSink> = if config.dry_run {
+        // error details would be written to the catalog
+        Arc::new(PartitionDoneSinkToScheduler::new(Arc::clone(&scheduler)))
+    } else {
+        // only skip partitions that failed repeatedly, recording details of the last error
+        Arc::new(SkipDetailsPartitionDoneSinkWrapper::new(
+            PartitionDoneSinkToScheduler::new(Arc::clone(&scheduler)),
+            config.max_consecutive_failures,
+            config.backoff_config.clone(),
+            Arc::clone(&config.catalog),
+        ))
+    };
 
     // compactors are responsible for error classification
     // and any future decisions regarding graceful shutdown
@@ -159,7 +193,11 @@ fn make_partitions_source_commit_partition_sink(
     // even when there is not data.
     let partitions_source =
         LoggingPartitionsSourceWrapper::new(MetricsPartitionsSourceWrapper::new(
-            RandomizeOrderPartitionsSourcesWrapper::new(partitions_source, 1234),
+            // jobs of the same priority are shuffled
+            PriorityOrderPartitionsSourceWrapper::new(RandomizeOrderPartitionsSourcesWrapper::new(
+                partitions_source,
+                1234,
+            )),
             &config.metric_registry,
         ));
     let partitions_source: Arc<dyn PartitionsSource> = if config.process_once {
@@ -174,7 +212,33 @@ fn make_partitions_source_commit_partition_sink(
         ))
     };
 
-    (partitions_source, Arc::new(commit), partition_done_sink)
+    (partitions_source, commit, partition_done_sink)
+}
+
+fn make_job_heartbeat(config: &Config, scheduler: Arc<dyn Scheduler>) -> Arc<dyn JobHeartbeat> {
+    match config.scheduler_config.job_heartbeat_interval() {
+        Some(interval) => Arc::new(JobHeartbeatToScheduler::new(
+            scheduler,
+            interval,
+            Arc::clone(&config.time_provider),
+        )),
+        // the scheduler does not lease jobs
+        None => Arc::new(NoopJobHeartbeat::new()),
+    }
+}
+
+fn make_output_verifier(config: &Config) -> Arc<dyn OutputVerifier> {
+    // created files only end up in the real object store if we actually write
+    if config.verify_output
+        && !(config.shadow_mode || config.dry_run || config.simulate_without_object_store)
+    {
+        Arc::new(ObjectStoreOutputVerifier::new(
+            config.parquet_store_real.clone(),
+            &config.metric_registry,
+        ))
+    } else {
+        Arc::new(NoopOutputVerifier::new())
+    }
 }
 
 fn make_partition_stream(
@@ -184,7 +248,11 @@ fn make_partition_stream(
     if config.process_once {
         Arc::new(OncePartititionStream::new(partitions_source))
     } else {
-        Arc::new(EndlessPartititionStream::new(partitions_source))
+        let stream = EndlessPartititionStream::new(partitions_source);
+        match config.scheduler_config.manual_partition_queue() {
+            Some(queue) => Arc::new(stream.with_manual_partition_queue(queue.clone())),
+            None => Arc::new(stream),
+        }
     }
 }
 
@@ -298,7 +366,7 @@ fn make_df_planner(config: &Config) -> Arc<dyn DataFusionPlanner> {
 }
 
 fn make_df_plan_exec(config: &Config) -> Arc<dyn DataFusionPlanExec> {
-    if config.simulate_without_object_store {
+    if config.simulate_without_object_store || config.dry_run {
         Arc::new(NoopDataFusionPlanExec::new())
     } else {
         Arc::new(DedicatedDataFusionPlanExec::new(Arc::clone(&config.exec)))
@@ -308,6 +376,10 @@ fn make_df_plan_exec(config: &Config) -> Arc<dyn DataFusionPlanExec> {
 fn make_parquet_files_sink(config: &Config) -> Arc<dyn ParquetFilesSink> {
     if let Some(sink) = config.parquet_files_sink_override.as_ref() {
         Arc::clone(sink)
+    } else if config.dry_run {
+        Arc::new(DryRunParquetFilesSink::new(Arc::clone(
+            &config.time_provider,
+        )))
     } else {
         let parquet_file_sink = Arc::new(LoggingParquetFileSinkWrapper::new(
             DedicatedExecParquetFileSinkWrapper::new(
@@ -324,7 +396,7 @@ fn make_parquet_files_sink(config: &Config) -> Arc<dyn ParquetFilesSink> {
 }
 
 fn make_scratchpad_gen(config: &Config) -> Arc<dyn ScratchpadGen> {
-    if config.simulate_without_object_store || !config.enable_scratchpad {
+    if config.simulate_without_object_store || config.dry_run || !config.enable_scratchpad {
         Arc::new(NoopScratchpadGen::new())
     } else {
         let scratchpad_store_output = if config.shadow_mode {
@@ -350,6 +422,11 @@ fn make_file_classifier(config: &Config) -> Arc<dyn FileClassifier> {
             TargetLevelSplit::new(),
             NonOverlapSplit::new(config.max_desired_file_size_bytes / 20), // rewrite non-overlapping files up to 5% of max
             UpgradeSplit::new(config.max_desired_file_size_bytes),
+            TimeSliceSplit::new(
+                config.time_slice_min_num_l0_files,
+                config.max_num_files_per_plan,
+                config.max_compact_size_bytes(),
+            ),
             LoggingSplitOrCompactWrapper::new(MetricsSplitOrCompactWrapper::new(
                 SplitCompact::new(
                     config.max_num_files_per_plan,
@@ -362,6 +439,20 @@ fn make_file_classifier(config: &Config) -> Arc<dyn FileClassifier> {
     )))
 }
 
+fn make_target_file_size(config: &Config) -> Arc<dyn TargetFileSize> {
+    let max_bytes = config.max_desired_file_size_bytes;
+    match config.min_desired_file_size_bytes {
+        Some(min_bytes) => Arc::new(ThroughputTargetFileSize::new(
+            // the file classifier rewrites non-overlapping files below 5% of max, see
+            // `make_file_classifier`, so smaller targets would rewrite files over and over again
+            min_bytes.clamp(max_bytes / 20, max_bytes),
+            max_bytes,
+            Arc::clone(&config.time_provider),
+        )),
+        None => Arc::new(FixedTargetFileSize::new(max_bytes)),
+    }
+}
+
 fn make_post_classification_partition_filter(
     config: &Config,
 ) -> Arc<dyn PostClassificationPartitionFilter> {
@@ -171,9 +171,12 @@ impl IRPlanner for V1IRPlanner {
         paths: Vec<ParquetFilePath>,
         object_store_ids: Vec<Uuid>,
         reason: CompactReason,
-        _partition: Arc<PartitionInfo>,
+        partition: Arc<PartitionInfo>,
         target_level: CompactionLevel,
     ) -> PlanIR {
+        let max_desired_file_size_bytes =
+            partition.max_desired_file_size_bytes(self.max_desired_file_size_bytes);
+
         // gather data
         // total file size is the sum of the file sizes of the files to compact
         let total_size = files.iter().map(|f| f.file_size_bytes).sum::<i64>() as u64;
@@ -192,10 +195,8 @@ impl IRPlanner for V1IRPlanner {
             .max()
             .expect("at least one file");
 
-        let (small_cutoff_bytes, large_cutoff_bytes) = Self::cutoff_bytes(
-            self.max_desired_file_size_bytes,
-            self.percentage_max_file_size,
-        );
+        let (small_cutoff_bytes, large_cutoff_bytes) =
+            Self::cutoff_bytes(max_desired_file_size_bytes, self.percentage_max_file_size);
 
         let files = files
             .into_iter()
@@ -234,7 +235,7 @@ impl IRPlanner for V1IRPlanner {
                     min_time,
                     max_time,
                     total_size,
-                    self.max_desired_file_size_bytes,
+                    max_desired_file_size_bytes,
                 )
             };
 
@@ -334,7 +335,11 @@ fn order(
 mod tests {
     use super::*;
 
+    use assert_matches::assert_matches;
     use data_types::TimestampMinMax;
+    use iox_tests::ParquetFileBuilder;
+
+    use crate::test_utils::PartitionInfoBuilder;
 
     #[test]
     fn test_cutoff_bytes() {
@@ -490,4 +495,40 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(result[0], 34);
     }
+
+    #[test]
+    fn test_compact_plan_partition_target() {
+        let planner = V1IRPlanner::new(1_000, 20, 80);
+        let files = vec![
+            ParquetFileBuilder::new(1)
+                .with_time_range(0, 50)
+                .with_file_size_bytes(75)
+                .build(),
+            ParquetFileBuilder::new(2)
+                .with_time_range(50, 100)
+                .with_file_size_bytes(75)
+                .build(),
+        ];
+        let plan = |partition: PartitionInfo| {
+            planner.compact_plan(
+                files.clone(),
+                files.iter().map(ParquetFilePath::from).collect(),
+                files.iter().map(|_| Uuid::new_v4()).collect(),
+                CompactReason::TotalSizeLessThanMaxCompactSize,
+                Arc::new(partition),
+                CompactionLevel::FileNonOverlapped,
+            )
+        };
+
+        // small compared to the configured max
+        let partition = PartitionInfoBuilder::new().build();
+        assert_matches!(plan(partition), PlanIR::Compact { .. });
+
+        // too large for the target of the partition
+        let partition = PartitionInfo {
+            target_file_size_bytes: Some(100),
+            ..PartitionInfoBuilder::new().build()
+        };
+        assert_matches!(plan(partition), PlanIR::Split { .. });
+    }
 }
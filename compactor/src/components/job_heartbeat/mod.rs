@@ -0,0 +1,30 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use compactor_scheduler::CompactionJob;
+
+pub mod noop;
+pub mod scheduler;
+
+/// Tells the scheduler that a job is still in progress, so it does not cancel the job.
+#[async_trait]
+pub trait JobHeartbeat: Debug + Display + Send + Sync {
+    /// Periodically send heartbeats for the given job.
+    ///
+    /// Returns once the scheduler cancelled the job, the caller must then stop working on the job
+    /// and end it. Otherwise this never returns, drop the future once the job ended.
+    async fn run(&self, job: &CompactionJob);
+}
+
+#[async_trait]
+impl<T> JobHeartbeat for Arc<T>
+where
+    T: JobHeartbeat + ?Sized,
+{
+    async fn run(&self, job: &CompactionJob) {
+        self.as_ref().run(job).await
+    }
+}
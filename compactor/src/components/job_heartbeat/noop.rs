@@ -0,0 +1,49 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use compactor_scheduler::CompactionJob;
+
+use super::JobHeartbeat;
+
+/// Does not send any heartbeats, for schedulers that do not lease jobs.
+#[derive(Debug, Default)]
+pub struct NoopJobHeartbeat;
+
+impl NoopJobHeartbeat {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Display for NoopJobHeartbeat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "noop")
+    }
+}
+
+#[async_trait]
+impl JobHeartbeat for NoopJobHeartbeat {
+    async fn run(&self, _job: &CompactionJob) {
+        futures::future::pending().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compactor_test_utils::AssertFutureExt;
+    use data_types::PartitionId;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(NoopJobHeartbeat::new().to_string(), "noop");
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let heartbeat = NoopJobHeartbeat::new();
+        let job = CompactionJob::new(PartitionId::new(1));
+        heartbeat.run(&job).assert_pending().await;
+    }
+}
@@ -0,0 +1,150 @@
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use compactor_scheduler::{
+    CompactionJob, CompactionJobStatus, CompactionJobStatusResponse, CompactionJobStatusVariant,
+    Scheduler,
+};
+use iox_time::TimeProvider;
+use observability_deps::tracing::warn;
+
+use super::JobHeartbeat;
+
+/// Sends heartbeats to the [`Scheduler`] every `interval`.
+#[derive(Debug)]
+pub struct JobHeartbeatToScheduler {
+    scheduler: Arc<dyn Scheduler>,
+    interval: Duration,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl JobHeartbeatToScheduler {
+    pub fn new(
+        scheduler: Arc<dyn Scheduler>,
+        interval: Duration,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            scheduler,
+            interval,
+            time_provider,
+        }
+    }
+}
+
+impl Display for JobHeartbeatToScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "scheduler({:?})", self.interval)
+    }
+}
+
+#[async_trait]
+impl JobHeartbeat for JobHeartbeatToScheduler {
+    async fn run(&self, job: &CompactionJob) {
+        loop {
+            self.time_provider.sleep(self.interval).await;
+
+            let res = self
+                .scheduler
+                .update_job_status(CompactionJobStatus {
+                    job: job.clone(),
+                    status: CompactionJobStatusVariant::Heartbeat,
+                })
+                .await;
+            match res {
+                Ok(CompactionJobStatusResponse::Cancelled) => {
+                    warn!(
+                        partition_id = job.partition_id.get(),
+                        "compaction job was cancelled by the scheduler",
+                    );
+                    return;
+                }
+                Ok(_) => {}
+                // keep going, the next heartbeat may still be in time
+                Err(e) => {
+                    warn!(
+                        partition_id = job.partition_id.get(),
+                        %e,
+                        "failed to send heartbeat for compaction job",
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compactor_scheduler::{
+        create_scheduler, LocalSchedulerConfig, PartitionsSourceConfig, SchedulerConfig,
+    };
+    use compactor_test_utils::AssertFutureExt;
+    use data_types::PartitionId;
+    use iox_tests::TestCatalog;
+
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_display() {
+        let catalog = TestCatalog::new();
+        let heartbeat = JobHeartbeatToScheduler::new(
+            compactor_scheduler::create_test_scheduler(
+                catalog.catalog(),
+                catalog.time_provider(),
+                None,
+            ),
+            Duration::from_secs(20),
+            catalog.time_provider(),
+        );
+        assert_eq!(heartbeat.to_string(), "scheduler(20s)");
+    }
+
+    #[tokio::test]
+    async fn test_run() {
+        let catalog = TestCatalog::new();
+        let scheduler = create_scheduler(
+            SchedulerConfig::Local(LocalSchedulerConfig {
+                partitions_source_config: PartitionsSourceConfig::Fixed(
+                    [PartitionId::new(1)].into(),
+                ),
+                job_lease_duration: Some(LEASE),
+                ..Default::default()
+            }),
+            catalog.catalog(),
+            catalog.time_provider(),
+            Arc::new(metric::Registry::new()),
+            true,
+        );
+        let jobs = scheduler.get_jobs().await;
+        assert_eq!(jobs.len(), 1);
+
+        let heartbeat = JobHeartbeatToScheduler::new(
+            Arc::clone(&scheduler),
+            LEASE / 3,
+            catalog.time_provider(),
+        );
+        let mut fut = heartbeat.run(&jobs[0]);
+        fut.assert_pending().await;
+
+        // heartbeats keep the job leased way past the lease duration
+        for _ in 0..10 {
+            catalog.mock_time_provider().inc(LEASE / 3);
+            fut.assert_pending().await;
+        }
+        assert!(scheduler.get_jobs().await.is_empty());
+
+        // without heartbeats, the lease expires and the job is cancelled, but not handed out
+        // again while it may still be running
+        drop(fut);
+        catalog.mock_time_provider().inc(LEASE);
+        assert!(scheduler.get_jobs().await.is_empty());
+
+        // the next heartbeat learns about the cancellation
+        let mut fut = heartbeat.run(&jobs[0]);
+        fut.assert_pending().await;
+        catalog.mock_time_provider().inc(LEASE / 3);
+        fut.poll_timeout().await;
+    }
+}
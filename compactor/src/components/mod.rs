@@ -1,14 +1,17 @@
 use std::sync::Arc;
 
+use compactor_scheduler::CompactionBacklog;
+
 use self::{
-    changed_files_filter::ChangedFilesFilter, commit::CommitToScheduler,
-    df_plan_exec::DataFusionPlanExec, df_planner::DataFusionPlanner, divide_initial::DivideInitial,
-    file_classifier::FileClassifier, ir_planner::IRPlanner, parquet_files_sink::ParquetFilesSink,
-    partition_done_sink::PartitionDoneSink, partition_files_source::PartitionFilesSource,
-    partition_filter::PartitionFilter, partition_info_source::PartitionInfoSource,
-    partition_stream::PartitionStream,
+    changed_files_filter::ChangedFilesFilter, commit::Commit, df_plan_exec::DataFusionPlanExec,
+    df_planner::DataFusionPlanner, divide_initial::DivideInitial, file_classifier::FileClassifier,
+    ir_planner::IRPlanner, job_heartbeat::JobHeartbeat, output_verifier::OutputVerifier,
+    parquet_files_sink::ParquetFilesSink, partition_done_sink::PartitionDoneSink,
+    partition_files_source::PartitionFilesSource, partition_filter::PartitionFilter,
+    partition_info_source::PartitionInfoSource, partition_stream::PartitionStream,
     post_classification_partition_filter::PostClassificationPartitionFilter,
     round_info_source::RoundInfoSource, round_split::RoundSplit, scratchpad::ScratchpadGen,
+    target_file_size::TargetFileSize,
 };
 
 pub mod changed_files_filter;
@@ -21,7 +24,9 @@ pub mod file_filter;
 pub mod files_split;
 pub mod hardcoded;
 pub mod ir_planner;
+pub mod job_heartbeat;
 pub mod namespaces_source;
+pub mod output_verifier;
 pub mod parquet_file_sink;
 pub mod parquet_files_sink;
 pub mod partition_done_sink;
@@ -39,6 +44,7 @@ pub mod scratchpad;
 pub mod skipped_compactions_source;
 pub mod split_or_compact;
 pub mod tables_source;
+pub mod target_file_size;
 pub mod timeout;
 
 /// Pluggable system to determine compactor behavior. Please see
@@ -61,7 +67,7 @@ pub struct Components {
     /// Records "partition is done" status for given partition.
     pub partition_done_sink: Arc<dyn PartitionDoneSink>,
     /// Commits changes (i.e. deletion and creation).
-    pub commit: Arc<CommitToScheduler>,
+    pub commit: Arc<dyn Commit>,
     /// Creates `PlanIR` that describes what files should be compacted and updated
     pub ir_planner: Arc<dyn IRPlanner>,
     /// Creates an Execution plan for a `PlanIR`
@@ -80,4 +86,12 @@ pub struct Components {
     pub file_classifier: Arc<dyn FileClassifier>,
     /// Check for other processes modifying files.
     pub changed_files_filter: Arc<dyn ChangedFilesFilter>,
+    /// Choose the desired max size of compacted files of a partition.
+    pub target_file_size: Arc<dyn TargetFileSize>,
+    /// Keeps the scheduler lease of running jobs alive.
+    pub job_heartbeat: Arc<dyn JobHeartbeat>,
+    /// Check the files created by a compaction step after committing them.
+    pub output_verifier: Arc<dyn OutputVerifier>,
+    /// Tracks files awaiting compaction and running jobs.
+    pub backlog: CompactionBacklog,
 }
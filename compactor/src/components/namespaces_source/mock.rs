@@ -131,6 +131,7 @@ mod tests {
                     TableSchema {
                         id: TableId::new(1),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
                         columns: ColumnsByName::new([
                             Column {
                                 name: "col1".to_string(),
@@ -152,6 +153,7 @@ mod tests {
                     TableSchema {
                         id: TableId::new(2),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
                         columns: ColumnsByName::new([
                             Column {
                                 name: "col1".to_string(),
@@ -187,6 +189,9 @@ mod tests {
                         retention_period_ns: None,
                         deleted_at: None,
                         partition_template: Default::default(),
+                        compaction_paused: false,
+                        max_write_lines_per_second: None,
+                        max_write_bytes_per_second: None,
                     },
                     schema: NamespaceSchema {
                         id,
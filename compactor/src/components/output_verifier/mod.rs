@@ -0,0 +1,44 @@
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use data_types::ParquetFile;
+
+use crate::partition_info::PartitionInfo;
+
+pub mod noop;
+pub mod object_store;
+
+/// Checks the files that a compaction step committed to the catalog.
+#[async_trait]
+pub trait OutputVerifier: Debug + Display + Send + Sync {
+    /// Verify the files that were created from the given input files.
+    ///
+    /// The files are already committed at this point, so mismatches are only reported (logged and
+    /// counted) and never fail the compaction.
+    async fn verify(
+        &self,
+        partition_info: &PartitionInfo,
+        input_files: &[ParquetFile],
+        created_files: &[ParquetFile],
+    );
+}
+
+#[async_trait]
+impl<T> OutputVerifier for Arc<T>
+where
+    T: OutputVerifier + ?Sized,
+{
+    async fn verify(
+        &self,
+        partition_info: &PartitionInfo,
+        input_files: &[ParquetFile],
+        created_files: &[ParquetFile],
+    ) {
+        self.as_ref()
+            .verify(partition_info, input_files, created_files)
+            .await
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt::Display;
+
+use async_trait::async_trait;
+use data_types::ParquetFile;
+
+use crate::partition_info::PartitionInfo;
+
+use super::OutputVerifier;
+
+/// Does not verify anything.
+#[derive(Debug, Default)]
+pub struct NoopOutputVerifier;
+
+impl NoopOutputVerifier {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Display for NoopOutputVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "noop")
+    }
+}
+
+#[async_trait]
+impl OutputVerifier for NoopOutputVerifier {
+    async fn verify(
+        &self,
+        _partition_info: &PartitionInfo,
+        _input_files: &[ParquetFile],
+        _created_files: &[ParquetFile],
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(NoopOutputVerifier::new().to_string(), "noop");
+    }
+}
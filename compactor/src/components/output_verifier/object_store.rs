@@ -0,0 +1,354 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::ParquetFile;
+use datafusion::{
+    arrow::{
+        record_batch::RecordBatch,
+        row::{OwnedRow, RowConverter, SortField},
+    },
+    parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+};
+use metric::U64Counter;
+use observability_deps::tracing::error;
+use parquet_file::{metadata::IoxParquetMetaData, storage::ParquetStorage, ParquetFilePath};
+use schema::sort::SortKey;
+
+use crate::{error::DynError, partition_info::PartitionInfo};
+
+use super::OutputVerifier;
+
+const METRIC_NAME_FAILURES: &str = "iox_compactor_output_verification_failures";
+
+/// Re-reads the created files from the object store and checks them against the catalog and
+/// the input files.
+///
+/// The following is checked:
+///
+/// - **row count:** the created files never contain more rows than the input files, because
+///   deduplication only removes rows. Non-empty input never results in empty output. Every file
+///   contains the number of rows that the catalog records for it.
+/// - **sort order:** the rows of every file are sorted by the sort key that is declared in the
+///   IOx metadata of the file.
+///
+/// Files that cannot be read back are reported as well.
+#[derive(Debug)]
+pub struct ObjectStoreOutputVerifier {
+    store: ParquetStorage,
+    row_count_failures: U64Counter,
+    sort_order_failures: U64Counter,
+    read_failures: U64Counter,
+}
+
+impl ObjectStoreOutputVerifier {
+    pub fn new(store: ParquetStorage, registry: &metric::Registry) -> Self {
+        let metric = registry.register_metric::<U64Counter>(
+            METRIC_NAME_FAILURES,
+            "Number of mismatches found when verifying the files created by the compactor",
+        );
+
+        Self {
+            store,
+            row_count_failures: metric.recorder(&[("check", "row_count")]),
+            sort_order_failures: metric.recorder(&[("check", "sort_order")]),
+            read_failures: metric.recorder(&[("check", "read")]),
+        }
+    }
+
+    /// Read the declared sort key and the data of the given file.
+    async fn read(
+        &self,
+        file: &ParquetFile,
+    ) -> Result<(Option<SortKey>, Vec<RecordBatch>), DynError> {
+        let path = ParquetFilePath::from(file).object_store_path();
+        let bytes = self.store.object_store().get(&path).await?.bytes().await?;
+
+        let sort_key = IoxParquetMetaData::from_file_bytes(bytes.clone())?
+            .ok_or("parquet file has no metadata")?
+            .decode()?
+            .read_iox_metadata_new()?
+            .sort_key;
+
+        let batches = ParquetRecordBatchReaderBuilder::try_new(bytes)?
+            .build()?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((sort_key, batches))
+    }
+
+    async fn verify_file(&self, partition_info: &PartitionInfo, file: &ParquetFile) {
+        let (sort_key, batches) = match self.read(file).await {
+            Ok(res) => res,
+            Err(e) => {
+                error!(
+                    partition_id = partition_info.partition_id.get(),
+                    uuid = file.object_store_id.to_string(),
+                    %e,
+                    "output verification: cannot read created file",
+                );
+                self.read_failures.inc(1);
+                return;
+            }
+        };
+
+        let actual_rows = batches.iter().map(|b| b.num_rows()).sum::<usize>();
+        if actual_rows as i64 != file.row_count {
+            error!(
+                partition_id = partition_info.partition_id.get(),
+                uuid = file.object_store_id.to_string(),
+                declared_rows = file.row_count,
+                actual_rows,
+                "output verification: row count of created file does not match catalog",
+            );
+            self.row_count_failures.inc(1);
+        }
+
+        let Some(sort_key) = sort_key else {
+            return;
+        };
+        match first_unsorted_row(&sort_key, &batches) {
+            Ok(None) => {}
+            Ok(Some(row)) => {
+                error!(
+                    partition_id = partition_info.partition_id.get(),
+                    uuid = file.object_store_id.to_string(),
+                    %sort_key,
+                    row,
+                    "output verification: created file is not sorted by its sort key",
+                );
+                self.sort_order_failures.inc(1);
+            }
+            Err(e) => {
+                error!(
+                    partition_id = partition_info.partition_id.get(),
+                    uuid = file.object_store_id.to_string(),
+                    %e,
+                    "output verification: cannot check sort order of created file",
+                );
+                self.read_failures.inc(1);
+            }
+        }
+    }
+}
+
+impl Display for ObjectStoreOutputVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "object_store")
+    }
+}
+
+#[async_trait]
+impl OutputVerifier for ObjectStoreOutputVerifier {
+    async fn verify(
+        &self,
+        partition_info: &PartitionInfo,
+        input_files: &[ParquetFile],
+        created_files: &[ParquetFile],
+    ) {
+        let input_rows = input_files.iter().map(|f| f.row_count).sum::<i64>();
+        let output_rows = created_files.iter().map(|f| f.row_count).sum::<i64>();
+        if output_rows > input_rows || (output_rows == 0 && input_rows > 0) {
+            error!(
+                partition_id = partition_info.partition_id.get(),
+                input_rows,
+                output_rows,
+                n_input_files = input_files.len(),
+                n_created_files = created_files.len(),
+                "output verification: row count of created files does not match input files",
+            );
+            self.row_count_failures.inc(1);
+        }
+
+        for file in created_files {
+            self.verify_file(partition_info, file).await;
+        }
+    }
+}
+
+/// Index of the first row that is smaller than its predecessor w.r.t. the given sort key, if any.
+///
+/// Sort key columns that are not part of the data are ignored.
+fn first_unsorted_row(
+    sort_key: &SortKey,
+    batches: &[RecordBatch],
+) -> Result<Option<usize>, DynError> {
+    let Some(schema) = batches.first().map(|b| b.schema()) else {
+        return Ok(None);
+    };
+
+    let (indices, fields): (Vec<_>, Vec<_>) = sort_key
+        .iter()
+        .filter_map(|(col, options)| {
+            let idx = schema.index_of(col).ok()?;
+            let field =
+                SortField::new_with_options(schema.field(idx).data_type().clone(), *options);
+            Some((idx, field))
+        })
+        .unzip();
+    if indices.is_empty() {
+        return Ok(None);
+    }
+
+    let mut converter = RowConverter::new(fields)?;
+    let mut last: Option<OwnedRow> = None;
+    let mut offset = 0;
+    for batch in batches {
+        let columns = indices
+            .iter()
+            .map(|idx| Arc::clone(batch.column(*idx)))
+            .collect::<Vec<_>>();
+        let rows = converter.convert_columns(&columns)?;
+
+        for i in 0..rows.num_rows() {
+            let previous = match i {
+                0 => last.as_ref().map(|row| row.row()),
+                _ => Some(rows.row(i - 1)),
+            };
+            if previous
+                .map(|previous| previous > rows.row(i))
+                .unwrap_or_default()
+            {
+                return Ok(Some(offset + i));
+            }
+        }
+
+        if rows.num_rows() > 0 {
+            last = Some(rows.row(rows.num_rows() - 1).owned());
+        }
+        offset += batch.num_rows();
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::ColumnType;
+    use datafusion::arrow::array::{ArrayRef, Int64Array, StringArray};
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use metric::{Attributes, Metric};
+    use uuid::Uuid;
+
+    use crate::test_utils::PartitionInfoBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let catalog = TestCatalog::new();
+        let verifier =
+            ObjectStoreOutputVerifier::new(catalog.parquet_store.clone(), &metric::Registry::new());
+        assert_eq!(verifier.to_string(), "object_store");
+    }
+
+    #[tokio::test]
+    async fn test_verify() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        table.create_column("tag", ColumnType::Tag).await;
+        table.create_column("field", ColumnType::I64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table.create_partition("k").await;
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table,tag=b field=1i 10\ntable,tag=a field=2i 20");
+        let file: ParquetFile = partition.create_parquet_file(builder).await.into();
+
+        let registry = metric::Registry::new();
+        let verifier = ObjectStoreOutputVerifier::new(catalog.parquet_store.clone(), &registry);
+        let partition_info = PartitionInfoBuilder::new().build();
+
+        // valid output
+        verifier
+            .verify(&partition_info, &[file.clone()], &[file.clone()])
+            .await;
+        assert_eq!(failures(&registry, "row_count"), 0);
+        assert_eq!(failures(&registry, "sort_order"), 0);
+        assert_eq!(failures(&registry, "read"), 0);
+
+        // more rows than the input
+        verifier.verify(&partition_info, &[], &[file.clone()]).await;
+        assert_eq!(failures(&registry, "row_count"), 1);
+
+        // non-empty input results in empty output
+        verifier.verify(&partition_info, &[file.clone()], &[]).await;
+        assert_eq!(failures(&registry, "row_count"), 2);
+
+        // catalog disagrees with the file
+        let mut wrong_row_count = file.clone();
+        wrong_row_count.row_count += 1;
+        verifier
+            .verify(
+                &partition_info,
+                &[wrong_row_count.clone()],
+                &[wrong_row_count],
+            )
+            .await;
+        assert_eq!(failures(&registry, "row_count"), 3);
+
+        // file does not exist
+        let mut missing = file.clone();
+        missing.object_store_id = Uuid::new_v4();
+        verifier
+            .verify(&partition_info, &[file.clone()], &[missing])
+            .await;
+        assert_eq!(failures(&registry, "read"), 1);
+
+        assert_eq!(failures(&registry, "sort_order"), 0);
+    }
+
+    #[test]
+    fn test_first_unsorted_row() {
+        let batch = |tags: &[&str], times: &[i64]| {
+            RecordBatch::try_from_iter([
+                (
+                    "tag",
+                    Arc::new(StringArray::from(tags.to_vec())) as ArrayRef,
+                ),
+                (
+                    "time",
+                    Arc::new(Int64Array::from(times.to_vec())) as ArrayRef,
+                ),
+            ])
+            .unwrap()
+        };
+        let sort_key = SortKey::from_columns(["tag", "time"]);
+
+        assert_eq!(first_unsorted_row(&sort_key, &[]).unwrap(), None);
+
+        let sorted = [
+            batch(&["a", "a", "b"], &[1, 2, 1]),
+            batch(&[], &[]),
+            batch(&["b", "c"], &[1, 0]),
+        ];
+        assert_eq!(first_unsorted_row(&sort_key, &sorted).unwrap(), None);
+
+        let unsorted_within_batch = [batch(&["a", "b", "a"], &[1, 1, 2])];
+        assert_eq!(
+            first_unsorted_row(&sort_key, &unsorted_within_batch).unwrap(),
+            Some(2)
+        );
+
+        let unsorted_across_batches = [batch(&["a", "b"], &[1, 2]), batch(&["b"], &[1])];
+        assert_eq!(
+            first_unsorted_row(&sort_key, &unsorted_across_batches).unwrap(),
+            Some(2)
+        );
+
+        // columns of the sort key that are not part of the data are ignored
+        let sort_key = SortKey::from_columns(["other", "time"]);
+        assert_eq!(
+            first_unsorted_row(&sort_key, &unsorted_within_batch).unwrap(),
+            None
+        );
+    }
+
+    fn failures(registry: &metric::Registry, check: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>(METRIC_NAME_FAILURES)
+            .unwrap()
+            .get_observer(&Attributes::from(&[("check", check)]))
+            .unwrap()
+            .fetch()
+    }
+}
@@ -81,6 +81,7 @@ impl ParquetFileSink for MockParquetFileSink {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new(vec![]),
             max_l0_created_at: max_l0_created_at.into(),
+            tag_ranges: None,
         });
         guard.push(StoredFile {
             batches,
@@ -178,6 +179,7 @@ mod tests {
                 created_at: Timestamp::new(1),
                 column_set: ColumnSet::new([]),
                 max_l0_created_at: max_l0_created_at.into(),
+                tag_ranges: None,
             }),
         );
 
@@ -242,6 +244,7 @@ mod tests {
                 created_at: Timestamp::new(1),
                 column_set: ColumnSet::new([]),
                 max_l0_created_at: max_l0_created_at.into(),
+                tag_ranges: None,
             }),
         );
 
@@ -0,0 +1,240 @@
+use std::{collections::BTreeSet, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::{ColumnSet, CompactionLevel, ParquetFileParams, Timestamp};
+use datafusion::physical_plan::SendableRecordBatchStream;
+use iox_time::TimeProvider;
+use uuid::Uuid;
+
+use crate::{error::DynError, partition_info::PartitionInfo, plan_ir::PlanIR};
+
+use super::ParquetFilesSink;
+
+/// Does not write anything but estimates the files a plan would produce.
+///
+/// The streams are dropped without being polled. The estimated output covers the time range of
+/// the input files, divided at the split times of the plan (if any), with the bytes and rows of
+/// the input distributed proportionally to the time range of each output file.
+#[derive(Debug)]
+pub struct DryRunParquetFilesSink {
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl DryRunParquetFilesSink {
+    pub fn new(time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self { time_provider }
+    }
+}
+
+impl Display for DryRunParquetFilesSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "dry_run")
+    }
+}
+
+#[async_trait]
+impl ParquetFilesSink for DryRunParquetFilesSink {
+    async fn stream_into_file_sink(
+        &self,
+        _streams: Vec<SendableRecordBatchStream>,
+        partition_info: Arc<PartitionInfo>,
+        target_level: CompactionLevel,
+        plan_ir: &PlanIR,
+    ) -> Result<Vec<ParquetFileParams>, DynError> {
+        let split_times: &[i64] = match plan_ir {
+            PlanIR::Compact { .. } => &[],
+            PlanIR::Split { split_times, .. } => split_times,
+            PlanIR::None { .. } => return Ok(vec![]),
+        };
+        let input_files = plan_ir.input_files();
+        if input_files.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let min_time = input_files.iter().map(|f| f.file.min_time).min().unwrap();
+        let max_time = input_files.iter().map(|f| f.file.max_time).max().unwrap();
+        let max_l0_created_at = input_files
+            .iter()
+            .map(|f| f.file.max_l0_created_at)
+            .max()
+            .unwrap();
+        let total_bytes = plan_ir.input_bytes();
+        let total_rows: i64 = input_files.iter().map(|f| f.file.row_count).sum();
+        let column_set =
+            ColumnSet::new(input_files.iter().fold(BTreeSet::new(), |mut columns, f| {
+                columns.extend(f.file.column_set.iter().copied());
+                columns
+            }));
+        let created_at = Timestamp::from(self.time_provider.now());
+
+        // time range of each output file
+        let mut last_time = min_time;
+        let mut time_ranges = split_times
+            .iter()
+            .map(|t| {
+                let t = Timestamp::new(*t);
+                let range = (last_time, t);
+                last_time = t + 1;
+                range
+            })
+            .collect::<Vec<_>>();
+        time_ranges.push((last_time, max_time));
+
+        // every output file covers at least one timestamp
+        let total_range = (max_time - min_time).get() + 1;
+        let mut output = time_ranges
+            .into_iter()
+            .map(|(min_time, max_time)| {
+                let range = (max_time - min_time).get() + 1;
+                let fraction = range as f64 / total_range as f64;
+                ParquetFileParams {
+                    namespace_id: partition_info.namespace_id,
+                    table_id: partition_info.table.id,
+                    partition_id: partition_info.partition_id,
+                    partition_hash_id: partition_info.partition_hash_id.clone(),
+                    object_store_id: Uuid::new_v4(),
+                    min_time,
+                    max_time,
+                    file_size_bytes: (total_bytes as f64 * fraction) as i64,
+                    row_count: (total_rows as f64 * fraction) as i64,
+                    compaction_level: target_level,
+                    created_at,
+                    column_set: column_set.clone(),
+                    max_l0_created_at,
+                    tag_ranges: None,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // assign rounding leftovers to the last file so nothing gets lost
+        let output_bytes: i64 = output.iter().map(|f| f.file_size_bytes).sum();
+        let output_rows: i64 = output.iter().map(|f| f.row_count).sum();
+        let last = output.last_mut().expect("at least one output file");
+        last.file_size_bytes += total_bytes - output_bytes;
+        last.row_count += total_rows - output_rows;
+
+        Ok(output)
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::ChunkOrder;
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::{MockProvider, Time};
+
+    use crate::{
+        file_classification::{CompactReason, NoneReason, SplitReason},
+        plan_ir::FileIR,
+        test_utils::PartitionInfoBuilder,
+    };
+
+    use super::*;
+
+    fn file(id: i64, min_time: i64, max_time: i64, size: i64, rows: i64) -> FileIR {
+        let file = ParquetFileBuilder::new(id)
+            .with_time_range(min_time, max_time)
+            .with_file_size_bytes(size)
+            .with_row_count(rows)
+            .with_max_l0_created_at(id)
+            .build();
+        FileIR {
+            path: (&file).into(),
+            file,
+            order: ChunkOrder::new(id),
+        }
+    }
+
+    fn sink() -> DryRunParquetFilesSink {
+        DryRunParquetFilesSink::new(Arc::new(MockProvider::new(Time::from_timestamp_nanos(42))))
+    }
+
+    fn summary(files: &[ParquetFileParams]) -> Vec<(i64, i64, i64, i64)> {
+        files
+            .iter()
+            .map(|f| {
+                (
+                    f.min_time.get(),
+                    f.max_time.get(),
+                    f.file_size_bytes,
+                    f.row_count,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(sink().to_string(), "dry_run");
+    }
+
+    #[tokio::test]
+    async fn test_none() {
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+        let plan = PlanIR::None {
+            reason: NoneReason::NoInputFiles,
+        };
+
+        let files = sink()
+            .stream_into_file_sink(vec![], partition, CompactionLevel::FileNonOverlapped, &plan)
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_compact() {
+        let partition = Arc::new(PartitionInfoBuilder::new().with_partition_id(3).build());
+        let plan = PlanIR::Compact {
+            files: vec![file(1, 10, 20, 100, 10), file(2, 0, 15, 50, 5)],
+            target_level: CompactionLevel::FileNonOverlapped,
+            reason: CompactReason::ManySmallFiles,
+        };
+
+        let files = sink()
+            .stream_into_file_sink(vec![], partition, CompactionLevel::FileNonOverlapped, &plan)
+            .await
+            .unwrap();
+        assert_eq!(summary(&files), vec![(0, 20, 150, 15)]);
+
+        let f = &files[0];
+        assert_eq!(f.partition_id.get(), 3);
+        assert_eq!(f.compaction_level, CompactionLevel::FileNonOverlapped);
+        assert_eq!(f.created_at.get(), 42);
+        assert_eq!(f.max_l0_created_at.get(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_split() {
+        let partition = Arc::new(PartitionInfoBuilder::new().build());
+        let plan = PlanIR::Split {
+            files: vec![file(1, 0, 99, 1000, 101)],
+            split_times: vec![24, 74],
+            target_level: CompactionLevel::Final,
+            reason: SplitReason::ReduceLargeFileSize,
+        };
+
+        let files = sink()
+            .stream_into_file_sink(vec![], partition, CompactionLevel::Final, &plan)
+            .await
+            .unwrap();
+        assert_eq!(
+            summary(&files),
+            vec![(0, 24, 250, 25), (25, 74, 500, 50), (75, 99, 250, 26)]
+        );
+        assert!(files
+            .iter()
+            .all(|f| f.compaction_level == CompactionLevel::Final));
+
+        // every output file gets its own object store ID
+        let ids = files
+            .iter()
+            .map(|f| f.object_store_id)
+            .collect::<BTreeSet<_>>();
+        assert_eq!(ids.len(), 3);
+    }
+}
@@ -77,6 +77,9 @@ where
                     CompactionJobStatusResponse::CreatedParquetFiles(_) => {
                         unreachable!("scheduler should not created parquet files")
                     }
+                    CompactionJobStatusResponse::Cancelled => {
+                        unreachable!("scheduler should only cancel heartbeats")
+                    }
                 }
 
                 self.inner.record(partition, Err(e)).await
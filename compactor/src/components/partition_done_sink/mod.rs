@@ -13,6 +13,7 @@ pub mod logging;
 pub mod metrics;
 pub mod mock;
 pub mod outcome;
+pub mod skip_details;
 
 /// Records "partition is done" status for given partition.
 #[async_trait]
@@ -0,0 +1,220 @@
+use std::{collections::HashMap, fmt::Display, num::NonZeroUsize, sync::Arc};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::{PartitionId, TransitionPartitionId};
+use iox_catalog::interface::Catalog;
+use parking_lot::Mutex;
+
+use crate::error::{DynError, ErrorKindExt};
+
+use super::PartitionDoneSink;
+
+/// Only passes an error for a partition to the inner sink once the partition failed
+/// `max_failures` times in a row, and records the details of the last error in the catalog
+/// before doing so.
+///
+/// The recorded details are the classified error kind, the number and total size of the files
+/// of the partition, the number of failures and the last error message. The inner sink is
+/// expected to write the actual skip marker.
+///
+/// Errors of earlier failures are NOT passed to the inner sink, so the partition is retried.
+#[derive(Debug)]
+pub struct SkipDetailsPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    inner: T,
+    max_failures: NonZeroUsize,
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+    failures: Mutex<HashMap<PartitionId, usize>>,
+}
+
+impl<T> SkipDetailsPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    pub fn new(
+        inner: T,
+        max_failures: NonZeroUsize,
+        backoff_config: BackoffConfig,
+        catalog: Arc<dyn Catalog>,
+    ) -> Self {
+        Self {
+            inner,
+            max_failures,
+            backoff_config,
+            catalog,
+            failures: Default::default(),
+        }
+    }
+}
+
+impl<T> Display for SkipDetailsPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "skip_details({}, {})", self.max_failures, self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionDoneSink for SkipDetailsPartitionDoneSinkWrapper<T>
+where
+    T: PartitionDoneSink,
+{
+    async fn record(
+        &self,
+        partition: PartitionId,
+        res: Result<(), DynError>,
+    ) -> Result<(), DynError> {
+        let e = match res {
+            Ok(()) => {
+                self.failures.lock().remove(&partition);
+                return self.inner.record(partition, Ok(())).await;
+            }
+            Err(e) => e,
+        };
+
+        let num_failures = {
+            let mut failures = self.failures.lock();
+            let num_failures = failures.entry(partition).or_default();
+            *num_failures += 1;
+            let n = *num_failures;
+            if n >= self.max_failures.get() {
+                failures.remove(&partition);
+            }
+            n
+        };
+        if num_failures < self.max_failures.get() {
+            // contract of this abstraction,
+            // where we do not pass to `self.inner` before the partition failed often enough
+            return Err(e);
+        }
+
+        let error_kind = e.classify().name();
+        let last_error = e.to_string();
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("record skipped compaction error", || async {
+                let mut repos = self.catalog.repositories().await;
+                let files = repos
+                    .parquet_files()
+                    .list_by_partition_not_to_delete(&TransitionPartitionId::Deprecated(partition))
+                    .await?;
+                let total_bytes = files.iter().map(|f| f.file_size_bytes as u64).sum();
+
+                repos
+                    .partitions()
+                    .record_skipped_compaction_error(
+                        partition,
+                        error_kind,
+                        files.len(),
+                        total_bytes,
+                        num_failures,
+                        &last_error,
+                    )
+                    .await
+            })
+            .await?;
+
+        self.inner.record(partition, Err(e)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::error::DataFusionError;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+
+    use super::{super::mock::MockPartitionDoneSink, *};
+
+    #[test]
+    fn test_display() {
+        let sink = SkipDetailsPartitionDoneSinkWrapper::new(
+            MockPartitionDoneSink::new(),
+            NonZeroUsize::new(3).unwrap(),
+            BackoffConfig::default(),
+            TestCatalog::new().catalog(),
+        );
+        assert_eq!(sink.to_string(), "skip_details(3, mock)");
+    }
+
+    #[tokio::test]
+    async fn test_record() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        let partition_1 = table.create_partition("k1").await;
+        let partition_2 = table.create_partition("k2").await;
+        partition_1
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default().with_file_size_bytes(10),
+            )
+            .await;
+        partition_1
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default().with_file_size_bytes(20),
+            )
+            .await;
+        let partition_1 = partition_1.partition.id;
+        let partition_2 = partition_2.partition.id;
+
+        let inner = Arc::new(MockPartitionDoneSink::new());
+        let sink = SkipDetailsPartitionDoneSinkWrapper::new(
+            Arc::clone(&inner),
+            NonZeroUsize::new(2).unwrap(),
+            BackoffConfig::default(),
+            catalog.catalog(),
+        );
+
+        // first failure is not passed on
+        sink.record(partition_1, Err("foo".into()))
+            .await
+            .unwrap_err();
+        // success resets the failure count
+        sink.record(partition_2, Err("foo".into()))
+            .await
+            .unwrap_err();
+        sink.record(partition_2, Ok(()))
+            .await
+            .expect("record failed");
+        sink.record(partition_2, Err("foo".into()))
+            .await
+            .unwrap_err();
+        // second failure in a row is passed on
+        sink.record(
+            partition_1,
+            Err(Box::new(DataFusionError::ResourcesExhausted(String::from(
+                "bar",
+            )))),
+        )
+        .await
+        .expect("record failed");
+
+        assert_eq!(
+            inner.results(),
+            HashMap::from([
+                (partition_1, Err(String::from("Resources exhausted: bar"))),
+                (partition_2, Ok(())),
+            ]),
+        );
+
+        let errors = catalog
+            .catalog()
+            .repositories()
+            .await
+            .partitions()
+            .list_skipped_compaction_errors()
+            .await
+            .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].partition_id, partition_1);
+        assert_eq!(errors[0].error_kind, "out_of_memory");
+        assert_eq!(errors[0].num_files, 2);
+        assert_eq!(errors[0].total_bytes, 30);
+        assert_eq!(errors[0].num_failures, 2);
+        assert_eq!(errors[0].last_error, "Resources exhausted: bar");
+    }
+}
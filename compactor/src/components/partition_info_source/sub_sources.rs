@@ -105,6 +105,7 @@ where
             table_schema: Arc::new(table_schema.clone()),
             sort_key: partition.sort_key(),
             partition_key: partition.partition_key,
+            target_file_size_bytes: None,
         }))
     }
 }
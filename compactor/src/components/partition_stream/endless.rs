@@ -1,6 +1,6 @@
 use std::{collections::VecDeque, fmt::Display, sync::Arc};
 
-use compactor_scheduler::CompactionJob;
+use compactor_scheduler::{CompactionJob, ManualPartitionQueue};
 use futures::{stream::BoxStream, StreamExt};
 
 use super::super::{
@@ -15,6 +15,7 @@ where
 {
     source: Arc<T>,
     limiter: RateLimit,
+    manual_partition_queue: Option<ManualPartitionQueue>,
 }
 
 impl<T> EndlessPartititionStream<T>
@@ -25,6 +26,19 @@ where
         Self {
             source: Arc::new(source),
             limiter: RateLimit::new(1, 1), // Initial rate is irrelevant, it will be updated before first use.
+            manual_partition_queue: None,
+        }
+    }
+
+    /// Fetch from the source as soon as the given queue is non-empty, instead of waiting for the current batch to be
+    /// drained.
+    ///
+    /// The source is expected to return the manually requested partitions in this case, they are processed before the
+    /// rest of the current batch.
+    pub fn with_manual_partition_queue(self, queue: ManualPartitionQueue) -> Self {
+        Self {
+            manual_partition_queue: Some(queue),
+            ..self
         }
     }
 }
@@ -57,6 +71,19 @@ where
                         tokio::time::sleep(d).await;
                     }
 
+                    if self
+                        .manual_partition_queue
+                        .as_ref()
+                        .map(|queue| !queue.is_empty())
+                        .unwrap_or_default()
+                    {
+                        // Manually requested partitions jump the queue. The rate limiter is left untouched since
+                        // this is not a new batch.
+                        for p_id in source.fetch().await.into_iter().rev() {
+                            buffer.push_front(p_id);
+                        }
+                    }
+
                     if let Some(p_id) = buffer.pop_front() {
                         return Some((p_id, buffer));
                     }
@@ -108,4 +135,33 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn test_stream_manual_partition_queue() {
+        let ids = vec![
+            CompactionJob::new(PartitionId::new(1)),
+            CompactionJob::new(PartitionId::new(2)),
+            CompactionJob::new(PartitionId::new(3)),
+        ];
+        let manual = CompactionJob::new(PartitionId::new(42));
+        let source = Arc::new(MockPartitionsSource::new(ids.clone()));
+        let queue = ManualPartitionQueue::default();
+        let stream = EndlessPartititionStream::new(Arc::clone(&source))
+            .with_manual_partition_queue(queue.clone());
+        let mut stream = stream.stream();
+
+        assert_eq!(stream.next().await.unwrap(), ids[0]);
+
+        // the scheduler returns the requested partition on the next fetch and drains the queue
+        queue.push(manual.partition_id);
+        source.set(vec![manual.clone()]);
+        assert_eq!(stream.next().await.unwrap(), manual);
+        queue.take();
+        source.set(ids.clone());
+
+        // the current batch continues
+        assert_eq!(stream.next().await.unwrap(), ids[1]);
+        assert_eq!(stream.next().await.unwrap(), ids[2]);
+        assert_eq!(stream.next().await.unwrap(), ids[0]);
+    }
 }
@@ -5,6 +5,8 @@ pub mod logging;
 pub mod metrics;
 pub mod mock;
 pub mod not_empty;
+pub mod not_paused;
+pub mod priority_order;
 pub mod randomize_order;
 pub mod scheduled;
 
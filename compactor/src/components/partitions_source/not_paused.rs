@@ -0,0 +1,178 @@
+use std::{collections::HashSet, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use compactor_scheduler::CompactionJob;
+use data_types::TableId;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::info;
+
+use super::PartitionsSource;
+
+/// Drops jobs for partitions of namespaces whose compaction is paused.
+///
+/// Operators pause compaction of a namespace, e.g. during an incident investigation or a bulk
+/// re-ingest, via the `compaction_paused` flag of the namespace in the catalog.
+#[derive(Debug)]
+pub struct NotPausedPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    inner: T,
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+}
+
+impl<T> NotPausedPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    pub fn new(inner: T, backoff_config: BackoffConfig, catalog: Arc<dyn Catalog>) -> Self {
+        Self {
+            inner,
+            backoff_config,
+            catalog,
+        }
+    }
+
+    /// Tables of all namespaces whose compaction is paused.
+    async fn paused_tables(&self) -> HashSet<TableId> {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("tables_of_paused_namespaces", || async {
+                let mut repos = self.catalog.repositories().await;
+                let namespaces = repos
+                    .namespaces()
+                    .list(SoftDeletedRows::ExcludeDeleted)
+                    .await?;
+
+                let mut tables = HashSet::new();
+                for ns in namespaces.into_iter().filter(|ns| ns.compaction_paused) {
+                    let ns_tables = repos.tables().list_by_namespace_id(ns.id).await?;
+                    tables.extend(ns_tables.into_iter().map(|t| t.id));
+                }
+                Ok::<_, iox_catalog::interface::Error>(tables)
+            })
+            .await
+            .expect("retry forever")
+    }
+}
+
+impl<T> Display for NotPausedPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not_paused({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for NotPausedPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<CompactionJob> {
+        let jobs = self.inner.fetch().await;
+        if jobs.is_empty() {
+            return jobs;
+        }
+
+        let paused_tables = self.paused_tables().await;
+        if paused_tables.is_empty() {
+            return jobs;
+        }
+
+        let partition_ids = jobs.iter().map(|job| job.partition_id).collect::<Vec<_>>();
+        let paused_partitions = Backoff::new(&self.backoff_config)
+            .retry_all_errors("partitions_of_jobs", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .get_by_id_batch(partition_ids.clone())
+                    .await
+            })
+            .await
+            .expect("retry forever")
+            .into_iter()
+            .filter(|p| paused_tables.contains(&p.table_id))
+            .map(|p| p.id)
+            .collect::<HashSet<_>>();
+
+        if !paused_partitions.is_empty() {
+            info!(
+                n_partitions = paused_partitions.len(),
+                "skipping partitions of namespaces with paused compaction",
+            );
+        }
+
+        jobs.into_iter()
+            .filter(|job| !paused_partitions.contains(&job.partition_id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::TestCatalog;
+
+    use super::{super::mock::MockPartitionsSource, *};
+
+    #[test]
+    fn test_display() {
+        let source = NotPausedPartitionsSourceWrapper::new(
+            MockPartitionsSource::new(vec![]),
+            BackoffConfig::default(),
+            TestCatalog::new().catalog(),
+        );
+        assert_eq!(source.to_string(), "not_paused(mock)",);
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let catalog = TestCatalog::new();
+        let ns_1 = catalog.create_namespace_1hr_retention("ns1").await;
+        let ns_2 = catalog.create_namespace_1hr_retention("ns2").await;
+        let table_1 = ns_1.create_table("table").await;
+        let table_2 = ns_2.create_table("table").await;
+        let p_1 = table_1.create_partition("k1").await.partition.id;
+        let p_2 = table_1.create_partition("k2").await.partition.id;
+        let p_3 = table_2.create_partition("k1").await.partition.id;
+
+        let jobs = vec![
+            CompactionJob::new(p_1),
+            CompactionJob::new(p_2),
+            CompactionJob::new(p_3),
+        ];
+        let source = NotPausedPartitionsSourceWrapper::new(
+            MockPartitionsSource::new(jobs.clone()),
+            BackoffConfig::default(),
+            catalog.catalog(),
+        );
+
+        // nothing paused
+        assert_eq!(source.fetch().await, jobs);
+
+        // pausing ns1 drops its partitions
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_compaction_paused("ns1", true)
+            .await
+            .unwrap();
+        assert_eq!(source.fetch().await, vec![jobs[2].clone()]);
+
+        // resuming ns1 brings them back
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_compaction_paused("ns1", false)
+            .await
+            .unwrap();
+        assert_eq!(source.fetch().await, jobs);
+    }
+}
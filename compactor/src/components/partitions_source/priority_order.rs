@@ -0,0 +1,89 @@
+use std::{cmp::Reverse, fmt::Display};
+
+use async_trait::async_trait;
+use compactor_scheduler::CompactionJob;
+
+use super::PartitionsSource;
+
+/// Orders jobs by descending [priority](CompactionJob::priority).
+///
+/// The sort is stable, so jobs with equal or without priority keep the order of the inner source.
+#[derive(Debug)]
+pub struct PriorityOrderPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    inner: T,
+}
+
+impl<T> PriorityOrderPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Display for PriorityOrderPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "priority_order({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for PriorityOrderPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<CompactionJob> {
+        let mut partitions = self.inner.fetch().await;
+        partitions.sort_by_key(|job| Reverse(job.priority));
+        partitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::PartitionId;
+
+    use super::{super::mock::MockPartitionsSource, *};
+
+    #[test]
+    fn test_display() {
+        let source = PriorityOrderPartitionsSourceWrapper::new(MockPartitionsSource::new(vec![]));
+        assert_eq!(source.to_string(), "priority_order(mock)",);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_without_priority() {
+        let p_1 = CompactionJob::new(PartitionId::new(5));
+        let p_2 = CompactionJob::new(PartitionId::new(1));
+        let p_3 = CompactionJob::new(PartitionId::new(12));
+        let partitions = vec![p_1, p_2, p_3];
+
+        let source = PriorityOrderPartitionsSourceWrapper::new(MockPartitionsSource::new(
+            partitions.clone(),
+        ));
+        assert_eq!(source.fetch().await, partitions);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_with_priority() {
+        let p_1 = CompactionJob::new(PartitionId::new(5)).with_priority(10);
+        let p_2 = CompactionJob::new(PartitionId::new(1)).with_priority(1_000);
+        let p_3 = CompactionJob::new(PartitionId::new(12)).with_priority(10);
+        let p_4 = CompactionJob::new(PartitionId::new(7));
+
+        let source = PriorityOrderPartitionsSourceWrapper::new(MockPartitionsSource::new(vec![
+            p_4.clone(),
+            p_1.clone(),
+            p_2.clone(),
+            p_3.clone(),
+        ]));
+        assert_eq!(source.fetch().await, vec![p_2, p_1, p_3, p_4]);
+    }
+}
@@ -23,12 +23,16 @@ pub fn log_config(config: &Config) {
         backoff_config,
         partition_concurrency,
         df_concurrency,
+        memory_budget_bytes,
         partition_scratchpad_concurrency,
         max_desired_file_size_bytes,
+        min_desired_file_size_bytes,
         percentage_max_file_size,
         split_percentage,
         partition_timeout,
         shadow_mode,
+        dry_run,
+        verify_output,
         enable_scratchpad,
         ignore_partition_skip_marker,
         min_num_l1_files_to_compact,
@@ -39,6 +43,8 @@ pub fn log_config(config: &Config) {
         max_num_columns_per_table,
         max_num_files_per_plan,
         max_partition_fetch_queries_per_second,
+        time_slice_min_num_l0_files,
+        max_consecutive_failures,
     } = &config;
 
     let parquet_files_sink_override = parquet_files_sink_override
@@ -56,12 +62,16 @@ pub fn log_config(config: &Config) {
         ?backoff_config,
         partition_concurrency=partition_concurrency.get(),
         df_concurrency=df_concurrency.get(),
+        memory_budget_bytes,
         partition_scratchpad_concurrency=partition_scratchpad_concurrency.get(),
         max_desired_file_size_bytes,
+        min_desired_file_size_bytes,
         percentage_max_file_size,
         split_percentage,
         partition_timeout_secs=partition_timeout.as_secs_f32(),
         shadow_mode,
+        dry_run,
+        verify_output,
         enable_scratchpad,
         ignore_partition_skip_marker,
         min_num_l1_files_to_compact,
@@ -72,6 +82,8 @@ pub fn log_config(config: &Config) {
         max_num_columns_per_table,
         max_num_files_per_plan,
         max_partition_fetch_queries_per_second,
+        time_slice_min_num_l0_files,
+        max_consecutive_failures=max_consecutive_failures.get(),
         "config",
     );
 }
@@ -97,6 +109,10 @@ pub fn log_components(components: &Components) {
         scratchpad_gen,
         file_classifier,
         changed_files_filter,
+        target_file_size,
+        job_heartbeat,
+        output_verifier,
+        backlog,
     } = components;
 
     info!(
@@ -117,6 +133,10 @@ pub fn log_components(components: &Components) {
         %scratchpad_gen,
         %file_classifier,
         %changed_files_filter,
+        %target_file_size,
+        %job_heartbeat,
+        %output_verifier,
+        %backlog,
         "component setup",
     );
 }
@@ -65,7 +65,7 @@ impl SplitOrCompact for SplitCompact {
     ///    files that will lead to more splits in next round, it won't be efficient
     fn apply(
         &self,
-        _partition_info: &PartitionInfo,
+        partition_info: &PartitionInfo,
         files: Vec<ParquetFile>,
         target_level: CompactionLevel,
     ) -> (FilesToSplitOrCompact, Vec<ParquetFile>) {
@@ -146,7 +146,7 @@ impl SplitOrCompact for SplitCompact {
         // (4) Not able to compact the smallest set, split the large files
         let (files_to_split, files_not_to_split) = compute_split_times_for_large_files(
             files_to_further_split,
-            self.max_desired_file_size,
+            partition_info.max_desired_file_size_bytes(self.max_desired_file_size),
             self.max_compact_size,
         );
 
@@ -0,0 +1,51 @@
+use std::fmt::Display;
+
+use data_types::ParquetFile;
+
+use super::TargetFileSize;
+
+/// Same target for every partition.
+#[derive(Debug)]
+pub struct FixedTargetFileSize {
+    bytes: u64,
+}
+
+impl FixedTargetFileSize {
+    pub fn new(bytes: u64) -> Self {
+        Self { bytes }
+    }
+}
+
+impl Display for FixedTargetFileSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fixed({})", self.bytes)
+    }
+}
+
+impl TargetFileSize for FixedTargetFileSize {
+    fn target_bytes(&self, _files: &[ParquetFile]) -> u64 {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(FixedTargetFileSize::new(100).to_string(), "fixed(100)");
+    }
+
+    #[test]
+    fn test_target_bytes() {
+        let target = FixedTargetFileSize::new(100);
+        assert_eq!(target.target_bytes(&[]), 100);
+        assert_eq!(
+            target.target_bytes(&[ParquetFileBuilder::new(1).with_file_size_bytes(1).build()]),
+            100
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use std::fmt::{Debug, Display};
+
+use data_types::ParquetFile;
+
+pub mod fixed;
+pub mod throughput;
+
+/// Chooses the desired max size of the compacted files of a partition.
+pub trait TargetFileSize: Debug + Display + Send + Sync {
+    /// Desired max size of compacted files, given all files of the partition.
+    fn target_bytes(&self, files: &[ParquetFile]) -> u64;
+}
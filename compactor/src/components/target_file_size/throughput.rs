@@ -0,0 +1,164 @@
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use data_types::{CompactionLevel, ParquetFile};
+use iox_time::{Time, TimeProvider};
+
+use super::TargetFileSize;
+
+/// A compacted file should hold roughly this much time worth of writes.
+const WRITE_DURATION_PER_FILE: Duration = Duration::from_secs(60 * 60);
+
+/// Chooses the target based on the write throughput of the partition.
+///
+/// The throughput is estimated from the L0 files of the partition, i.e. the data that was written
+/// since the partition was last compacted: their total size divided by the time since the oldest
+/// of them was created. The target is the amount of data written in [`WRITE_DURATION_PER_FILE`],
+/// clamped to `[min_bytes, max_bytes]`.
+///
+/// Partitions that only receive a trickle of writes get small targets, so every small write does
+/// not rewrite a large file. Busy partitions get large targets, so they do not end up with many
+/// small files. Partitions without L0 files do not receive writes anymore and are compacted into
+/// files of `max_bytes`.
+#[derive(Debug)]
+pub struct ThroughputTargetFileSize {
+    min_bytes: u64,
+    max_bytes: u64,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl ThroughputTargetFileSize {
+    pub fn new(min_bytes: u64, max_bytes: u64, time_provider: Arc<dyn TimeProvider>) -> Self {
+        assert!(
+            min_bytes <= max_bytes,
+            "min_bytes ({min_bytes}) must not exceed max_bytes ({max_bytes})"
+        );
+
+        Self {
+            min_bytes,
+            max_bytes,
+            time_provider,
+        }
+    }
+}
+
+impl Display for ThroughputTargetFileSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "throughput({}, {})", self.min_bytes, self.max_bytes)
+    }
+}
+
+impl TargetFileSize for ThroughputTargetFileSize {
+    fn target_bytes(&self, files: &[ParquetFile]) -> u64 {
+        let l0_files = files
+            .iter()
+            .filter(|f| f.compaction_level == CompactionLevel::Initial);
+
+        let Some(oldest) = l0_files.clone().map(|f| f.created_at).min() else {
+            return self.max_bytes;
+        };
+        let written_bytes = l0_files.map(|f| f.file_size_bytes as u128).sum::<u128>();
+
+        // files that were just created do not tell much about the throughput yet
+        let observed = self
+            .time_provider
+            .now()
+            .checked_duration_since(Time::from_timestamp_nanos(oldest.get()))
+            .unwrap_or_default()
+            .max(WRITE_DURATION_PER_FILE);
+
+        let bytes_per_file =
+            written_bytes * WRITE_DURATION_PER_FILE.as_nanos() / observed.as_nanos();
+        (bytes_per_file.min(u64::MAX as u128) as u64).clamp(self.min_bytes, self.max_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+    const HOUR: i64 = 60 * 60 * 1_000_000_000;
+
+    fn target() -> ThroughputTargetFileSize {
+        ThroughputTargetFileSize::new(
+            10 * MB,
+            100 * MB,
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(24 * HOUR))),
+        )
+    }
+
+    fn file(id: i64, level: CompactionLevel, size: u64, created_at: i64) -> ParquetFile {
+        ParquetFileBuilder::new(id)
+            .with_compaction_level(level)
+            .with_file_size_bytes(size as i64)
+            .with_created_at(created_at)
+            .build()
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(target().to_string(), "throughput(10485760, 104857600)");
+    }
+
+    #[test]
+    #[should_panic(expected = "min_bytes (2) must not exceed max_bytes (1)")]
+    fn test_invalid_range() {
+        ThroughputTargetFileSize::new(2, 1, Arc::new(MockProvider::new(Time::MIN)));
+    }
+
+    #[test]
+    fn test_no_writes() {
+        let target = target();
+        assert_eq!(target.target_bytes(&[]), 100 * MB);
+
+        let files = [
+            file(1, CompactionLevel::FileNonOverlapped, MB, 0),
+            file(2, CompactionLevel::Final, MB, 0),
+        ];
+        assert_eq!(target.target_bytes(&files), 100 * MB);
+    }
+
+    #[test]
+    fn test_trickle() {
+        // 3MB written over 12 hours
+        let files = [
+            file(1, CompactionLevel::Initial, MB, 12 * HOUR),
+            file(2, CompactionLevel::Initial, 2 * MB, 20 * HOUR),
+        ];
+        assert_eq!(target().target_bytes(&files), 10 * MB);
+    }
+
+    #[test]
+    fn test_moderate() {
+        // 60MB written over 2 hours, compacted files do not count
+        let files = [
+            file(1, CompactionLevel::Initial, 20 * MB, 22 * HOUR),
+            file(2, CompactionLevel::Initial, 40 * MB, 23 * HOUR),
+            file(3, CompactionLevel::FileNonOverlapped, 100 * MB, 0),
+        ];
+        assert_eq!(target().target_bytes(&files), 30 * MB);
+    }
+
+    #[test]
+    fn test_busy() {
+        // 500MB written over 2 hours
+        let files = [
+            file(1, CompactionLevel::Initial, 200 * MB, 22 * HOUR),
+            file(2, CompactionLevel::Initial, 300 * MB, 23 * HOUR),
+        ];
+        assert_eq!(target().target_bytes(&files), 100 * MB);
+    }
+
+    #[test]
+    fn test_fresh_files() {
+        // files written in the last minutes are spread over the whole write duration per file
+        let files = [
+            file(1, CompactionLevel::Initial, 20 * MB, 24 * HOUR - HOUR / 60),
+            file(2, CompactionLevel::Initial, 30 * MB, 24 * HOUR),
+        ];
+        assert_eq!(target().target_bytes(&files), 50 * MB);
+    }
+}
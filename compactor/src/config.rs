@@ -58,6 +58,12 @@ pub struct Config {
     /// jobs.
     pub df_concurrency: NonZeroUsize,
 
+    /// Memory budget in bytes for concurrent DataFusion jobs.
+    ///
+    /// Jobs only run once their estimated memory usage fits into the remaining budget. If this is
+    /// `None`, concurrency is only limited by [`df_concurrency`](Self::df_concurrency).
+    pub memory_budget_bytes: Option<usize>,
+
     /// Number of jobs PER PARTITION that move files in and out of the scratchpad.
     pub partition_scratchpad_concurrency: NonZeroUsize,
 
@@ -65,6 +71,12 @@ pub struct Config {
     /// It is a target desired value than a guarantee
     pub max_desired_file_size_bytes: u64,
 
+    /// Desired min size of compacted parquet files.
+    ///
+    /// If set, the desired size of compacted files of each partition adapts to its write
+    /// throughput, between this and [`max_desired_file_size_bytes`](Self::max_desired_file_size_bytes).
+    pub min_desired_file_size_bytes: Option<u64>,
+
     /// Percentage of desired max file size.
     /// If the estimated compacted result is too small, no need to split it.
     /// This percentage is to determine how small it is:
@@ -91,6 +103,20 @@ pub struct Config {
     /// This is mostly useful for debugging.
     pub shadow_mode: bool,
 
+    /// Dry run.
+    ///
+    /// Runs the full planning pipeline but does NOT read or write any parquet data and does NOT
+    /// commit anything to the catalog. Instead, the changes that would be committed are logged.
+    ///
+    /// This is useful to validate new settings against a production catalog.
+    pub dry_run: bool,
+
+    /// Verify compacted files after committing them.
+    ///
+    /// Newly created files are read back from the object store and checked for plausible row
+    /// counts and sort order. Mismatches are logged and counted, but do not fail the compaction.
+    pub verify_output: bool,
+
     /// Enable Scratchpad
     ///
     /// Enabled by default, if this is set to false, the compactor will not use the scratchpad
@@ -140,6 +166,17 @@ pub struct Config {
     ///
     /// Queries are smoothed over the full second.
     pub max_partition_fetch_queries_per_second: Option<usize>,
+
+    /// Number of L0 files above which the L0 backlog of a partition is compacted in time slices,
+    /// bounding the input of each compaction round.
+    ///
+    /// If this is `None`, partitions are never time-sliced.
+    pub time_slice_min_num_l0_files: Option<usize>,
+
+    /// Number of consecutive failures after which a partition is marked as skipped.
+    ///
+    /// Details of the last error are recorded in the catalog alongside the skip marker.
+    pub max_consecutive_failures: NonZeroUsize,
 }
 
 impl Config {
@@ -21,6 +21,7 @@ use crate::{
     },
     error::{DynError, ErrorKind, SimpleError},
     file_classification::{FileClassification, FilesForProgress},
+    memory_budget::MemoryBudget,
     partition_info::PartitionInfo,
     PlanIR, RoundInfo,
 };
@@ -32,6 +33,7 @@ pub async fn compact(
     partition_concurrency: NonZeroUsize,
     partition_timeout: Duration,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     components: &Arc<Components>,
 ) {
     components
@@ -52,6 +54,7 @@ pub async fn compact(
                 job,
                 partition_timeout,
                 Arc::clone(&df_semaphore),
+                Arc::clone(&memory_budget),
                 components,
             )
         })
@@ -65,14 +68,16 @@ async fn compact_partition(
     job: CompactionJob,
     partition_timeout: Duration,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     components: Arc<Components>,
 ) {
     let partition_id = job.partition_id;
     info!(partition_id = partition_id.get(), timeout = ?partition_timeout, "compact partition",);
     span.set_metadata("partition_id", partition_id.get().to_string());
+    components.backlog.job_started(partition_id);
     let scratchpad = components.scratchpad_gen.pad();
 
-    let res = timeout_with_progress_checking(partition_timeout, |transmit_progress_signal| {
+    let fut = timeout_with_progress_checking(partition_timeout, |transmit_progress_signal| {
         let components = Arc::clone(&components);
         let scratchpad = Arc::clone(&scratchpad);
         async {
@@ -80,14 +85,22 @@ async fn compact_partition(
                 span,
                 job.clone(),
                 df_semaphore,
+                memory_budget,
                 components,
                 scratchpad,
                 transmit_progress_signal,
             )
             .await // errors detected in the CompactionJob update_job_status(), will be handled in the timeout_with_progress_checking
         }
-    })
-    .await;
+    });
+
+    // keep the lease of the job alive while it is running, and stop once the scheduler cancelled
+    // the job (e.g. because its lease expired)
+    let res = tokio::select! {
+        res = fut => res,
+        // not a problem of the partition, it will be compacted again
+        _ = components.job_heartbeat.run(&job) => TimeoutWithProgress::SomeWorkTryAgain,
+    };
 
     let res = match res {
         // If `try_compact_partition` timed out and didn't make any progress, something is wrong
@@ -114,6 +127,7 @@ async fn compact_partition(
         .await;
 
     scratchpad.clean().await;
+    components.backlog.job_finished(partition_id);
     info!(partition_id = partition_id.get(), "compacted partition",);
 }
 
@@ -207,6 +221,7 @@ async fn try_compact_partition(
     span: SpanRecorder,
     job: CompactionJob,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     components: Arc<Components>,
     scratchpad_ctx: Arc<dyn Scratchpad>,
     transmit_progress_signal: Sender<bool>,
@@ -214,6 +229,17 @@ async fn try_compact_partition(
     let partition_id = job.partition_id;
     let mut files = components.partition_files_source.fetch(partition_id).await;
     let partition_info = components.partition_info_source.fetch(partition_id).await?;
+
+    // the target is chosen once per partition so all rounds produce files of the same size
+    let target_file_size_bytes = components.target_file_size.target_bytes(&files);
+    info!(
+        partition_id = partition_id.get(),
+        target_file_size_bytes, "chose target file size",
+    );
+    let partition_info = Arc::new(PartitionInfo {
+        target_file_size_bytes: Some(target_file_size_bytes),
+        ..partition_info.as_ref().clone()
+    });
     let transmit_progress_signal = Arc::new(transmit_progress_signal);
 
     // loop for each "Round", consider each file in the partition
@@ -229,9 +255,12 @@ async fn try_compact_partition(
                 partition_id = partition_info.partition_id.get(),
                 "that's odd - no files to compact in partition"
             );
+            components.backlog.partition_done(partition_id);
             return Ok(());
         }
 
+        components.backlog.record_files(partition_id, &files);
+
         let round_info = components
             .round_info_source
             .calculate(&partition_info, &files)
@@ -244,6 +273,7 @@ async fn try_compact_partition(
             .apply(&partition_info, &files)
             .await?
         {
+            components.backlog.partition_done(partition_id);
             return Ok(());
         }
 
@@ -270,6 +300,7 @@ async fn try_compact_partition(
                 let partition_info = Arc::clone(&partition_info);
                 let components = Arc::clone(&components);
                 let df_semaphore = Arc::clone(&df_semaphore);
+                let memory_budget = Arc::clone(&memory_budget);
                 let transmit_progress_signal = Arc::clone(&transmit_progress_signal);
                 let scratchpad = Arc::clone(&scratchpad_ctx);
                 let job = job.clone();
@@ -281,6 +312,7 @@ async fn try_compact_partition(
                         job,
                         branch,
                         df_semaphore,
+                        memory_budget,
                         components,
                         scratchpad,
                         partition_info,
@@ -305,6 +337,7 @@ async fn execute_branch(
     job: CompactionJob,
     branch: Vec<ParquetFile>,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     components: Arc<Components>,
     scratchpad_ctx: Arc<dyn Scratchpad>,
     partition_info: Arc<PartitionInfo>,
@@ -374,7 +407,7 @@ async fn execute_branch(
             .take(df_semaphore.total_permits() * 4)
             .collect();
 
-        let files_to_delete = chunk
+        let files_to_delete: Vec<ParquetFile> = chunk
             .iter()
             .flat_map(|plan| plan.input_parquet_files())
             .collect();
@@ -386,6 +419,7 @@ async fn execute_branch(
             &partition_info,
             &components,
             Arc::clone(&df_semaphore),
+            Arc::clone(&memory_budget),
             Arc::<dyn Scratchpad>::clone(&scratchpad_ctx),
         )
         .await?;
@@ -424,13 +458,18 @@ async fn execute_branch(
             Arc::clone(&components),
             job.clone(),
             &saved_parquet_file_state,
-            files_to_delete,
+            &files_to_delete,
             upgrade,
             created_file_params,
             target_level,
         )
         .await?;
 
+        components
+            .output_verifier
+            .verify(&partition_info, &files_to_delete, &created_files)
+            .await;
+
         // we only need to upgrade files on the first iteration, so empty the upgrade list for next loop.
         upgrade = Vec::new();
 
@@ -457,6 +496,7 @@ async fn run_plans(
     partition_info: &Arc<PartitionInfo>,
     components: &Arc<Components>,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     scratchpad_ctx: Arc<dyn Scratchpad>,
 ) -> Result<Vec<ParquetFileParams>, DynError> {
     let paths: Vec<ParquetFilePath> = plans.iter().flat_map(|plan| plan.input_paths()).collect();
@@ -486,6 +526,7 @@ async fn run_plans(
             partition_info,
             components,
             Arc::clone(&df_semaphore),
+            Arc::clone(&memory_budget),
             Arc::<dyn Scratchpad>::clone(&scratchpad_ctx),
         )
     })
@@ -502,6 +543,7 @@ async fn execute_plan(
     partition_info: &Arc<PartitionInfo>,
     components: &Arc<Components>,
     df_semaphore: Arc<InstrumentedAsyncSemaphore>,
+    memory_budget: Arc<MemoryBudget>,
     scratchpad_ctx: Arc<dyn Scratchpad>,
 ) -> Result<Vec<ParquetFileParams>, DynError> {
     span.set_metadata("input_files", plan_ir.input_files().len().to_string());
@@ -511,6 +553,7 @@ async fn execute_plan(
     let create = {
         // Adjust concurrency based on the column count in the partition.
         let permits = compute_permits(df_semaphore.total_permits(), partition_info.column_count());
+        let memory_estimate_bytes = MemoryBudget::estimate_bytes(&plan_ir);
 
         // use the address of the plan as a uniq identifier so logs can be matched despite the concurrency.
         let plan_id = format!("{:p}", &plan_ir);
@@ -522,6 +565,8 @@ async fn execute_plan(
             permits_needed = permits,
             permits_acquired = df_semaphore.permits_acquired(),
             permits_pending = df_semaphore.permits_pending(),
+            memory_estimate_bytes,
+            memory_budget_bytes = memory_budget.budget_bytes(),
             plan_id,
             "requesting job semaphore",
         );
@@ -532,7 +577,11 @@ async fn execute_plan(
         // We guard the DataFusion planning (that doesn't perform any IO) via the semaphore as well in case
         // DataFusion ever starts to pre-allocate buffers during the physical planning. To the best of our
         // knowledge, this is currently (2023-01-25) not the case but if this ever changes, then we are prepared.
+        //
+        // The memory budget is reserved first so that jobs waiting for memory do not hold on to job
+        // permits that smaller jobs could use in the meantime.
         let permit_span = span.child("acquire_permit");
+        let memory_reservation = memory_budget.reserve(memory_estimate_bytes).await;
         let permit = df_semaphore
             .acquire_many(permits, None)
             .await
@@ -571,6 +620,7 @@ async fn execute_plan(
         };
 
         drop(permit);
+        drop(memory_reservation);
         drop(df_span);
 
         // inputs can be removed from the scratchpad as soon as we're done with compaction.
@@ -633,7 +683,7 @@ async fn update_catalog(
     components: Arc<Components>,
     job: CompactionJob,
     saved_parquet_file_state: &SavedParquetFileState,
-    files_to_delete: Vec<ParquetFile>,
+    files_to_delete: &[ParquetFile],
     files_to_upgrade: Vec<ParquetFile>,
     file_params_to_create: Vec<ParquetFileParams>,
     target_level: CompactionLevel,
@@ -651,7 +701,7 @@ async fn update_catalog(
         .commit
         .commit(
             partition_id,
-            &files_to_delete,
+            files_to_delete,
             &files_to_upgrade,
             &file_params_to_create,
             target_level,
@@ -214,6 +214,7 @@ pub mod config;
 mod driver;
 mod error;
 mod file_classification;
+mod memory_budget;
 pub mod object_store;
 mod partition_info;
 mod plan_ir;
@@ -226,6 +227,7 @@ pub use components::{
 };
 pub use driver::compact;
 pub use error::DynError;
+pub use memory_budget::MemoryBudget;
 pub use partition_info::PartitionInfo;
 pub use plan_ir::PlanIR;
 pub use round_info::RoundInfo;
@@ -0,0 +1,185 @@
+//! Memory budget for concurrently running compaction jobs.
+use std::sync::Arc;
+
+use tracker::{
+    AsyncSemaphoreMetrics, InstrumentedAsyncSemaphore, InstrumentedAsyncSemaphorePermit,
+};
+
+use crate::plan_ir::PlanIR;
+
+/// Granularity of the budget. The budget and all estimates are rounded up to this.
+const BYTES_PER_PERMIT: usize = 1024 * 1024;
+
+/// Estimated in-memory size of a single value once decoded from parquet.
+const BYTES_PER_VALUE: usize = 8;
+
+/// Limits the number of concurrently running compaction jobs (i.e. DataFusion plans) such that
+/// their total estimated memory usage stays within a configured budget.
+///
+/// A fixed job count limit either wastes memory when all jobs are small or runs out of memory
+/// when several huge partitions are compacted at the same time. With a budget, many small jobs
+/// can run concurrently while a huge job waits until enough memory is available.
+///
+/// A single job that is estimated to need more than the whole budget is clamped to the budget,
+/// i.e. it still runs, but only on its own.
+#[derive(Debug)]
+pub struct MemoryBudget {
+    /// Semaphore with one permit per [`BYTES_PER_PERMIT`] of the budget.
+    ///
+    /// `None` if there is no budget, i.e. memory does not limit the concurrency.
+    semaphore: Option<Arc<InstrumentedAsyncSemaphore>>,
+}
+
+impl MemoryBudget {
+    /// Create a budget of `budget_bytes`, or no budget if `None`.
+    pub fn new(budget_bytes: Option<usize>, metric_registry: &metric::Registry) -> Self {
+        let semaphore = budget_bytes.map(|budget_bytes| {
+            let metrics = Arc::new(AsyncSemaphoreMetrics::new(
+                metric_registry,
+                &[("semaphore", "memory_budget")],
+            ));
+            Arc::new(metrics.new_semaphore(to_permits(budget_bytes).max(1)))
+        });
+
+        Self { semaphore }
+    }
+
+    /// Create without a budget.
+    pub fn unlimited() -> Self {
+        Self { semaphore: None }
+    }
+
+    /// Total budget in bytes, if any.
+    pub fn budget_bytes(&self) -> Option<usize> {
+        self.semaphore
+            .as_ref()
+            .map(|s| s.total_permits() * BYTES_PER_PERMIT)
+    }
+
+    /// Estimate the memory needed to execute the given plan.
+    ///
+    /// This is a rough upper bound: all input files are assumed to be held in memory both
+    /// compressed (as loaded from the scratchpad) and decoded, with every value of every column
+    /// taking [`BYTES_PER_VALUE`].
+    pub fn estimate_bytes(plan_ir: &PlanIR) -> usize {
+        plan_ir
+            .input_files()
+            .iter()
+            .map(|f| {
+                let file = &f.file;
+                let decoded = file.row_count as usize * file.column_set.len() * BYTES_PER_VALUE;
+                file.file_size_bytes as usize + decoded
+            })
+            .sum()
+    }
+
+    /// Reserve `bytes` of the budget, waiting until they are available.
+    ///
+    /// The reservation is released when the returned permit is dropped. Returns `None` if there is
+    /// no budget.
+    pub async fn reserve(&self, bytes: usize) -> Option<InstrumentedAsyncSemaphorePermit<'_>> {
+        let semaphore = self.semaphore.as_ref()?;
+        let permits = to_permits(bytes).clamp(1, semaphore.total_permits());
+        let permit = semaphore
+            .acquire_many(permits as u32, None)
+            .await
+            .expect("semaphore not closed");
+        Some(permit)
+    }
+}
+
+fn to_permits(bytes: usize) -> usize {
+    let permits = bytes / BYTES_PER_PERMIT + usize::from(bytes % BYTES_PER_PERMIT != 0);
+    permits.min(u32::MAX as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use data_types::{ChunkOrder, ColumnId, ColumnSet, CompactionLevel};
+    use futures::FutureExt;
+    use iox_tests::ParquetFileBuilder;
+
+    use crate::{file_classification::CompactReason, plan_ir::FileIR};
+
+    use super::*;
+
+    const MB: usize = BYTES_PER_PERMIT;
+
+    #[test]
+    fn test_budget_bytes() {
+        let registry = metric::Registry::new();
+        assert_eq!(MemoryBudget::new(None, &registry).budget_bytes(), None);
+        assert_eq!(MemoryBudget::unlimited().budget_bytes(), None);
+        assert_eq!(
+            MemoryBudget::new(Some(10 * MB), &registry).budget_bytes(),
+            Some(10 * MB)
+        );
+        // rounded up to the granularity of the budget
+        assert_eq!(
+            MemoryBudget::new(Some(1), &registry).budget_bytes(),
+            Some(MB)
+        );
+    }
+
+    #[test]
+    fn test_estimate_bytes() {
+        let file = |id: i64, size: i64, rows: i64, columns: i64| {
+            let mut file = ParquetFileBuilder::new(id)
+                .with_file_size_bytes(size)
+                .with_row_count(rows)
+                .build();
+            file.column_set = ColumnSet::new((0..columns).map(ColumnId::new));
+            FileIR {
+                path: (&file).into(),
+                file,
+                order: ChunkOrder::new(id),
+            }
+        };
+
+        let plan = PlanIR::Compact {
+            files: vec![file(1, 100, 10, 2), file(2, 1_000, 100, 3)],
+            target_level: CompactionLevel::FileNonOverlapped,
+            reason: CompactReason::ManySmallFiles,
+        };
+        assert_eq!(
+            MemoryBudget::estimate_bytes(&plan),
+            100 + 10 * 2 * 8 + 1_000 + 100 * 3 * 8
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unlimited() {
+        let budget = MemoryBudget::unlimited();
+        assert!(budget.reserve(usize::MAX).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reserve() {
+        let budget = MemoryBudget::new(Some(10 * MB), &metric::Registry::new());
+
+        let p1 = budget.reserve(4 * MB).await.unwrap();
+        // rounded up to 5MB
+        let p2 = budget.reserve(4 * MB + 1).await.unwrap();
+
+        // does not fit into the remaining budget
+        let mut fut = Box::pin(budget.reserve(2 * MB));
+        assert!((&mut fut).now_or_never().is_none());
+
+        drop(p1);
+        let p3 = tokio::time::timeout(Duration::from_secs(1), fut)
+            .await
+            .unwrap()
+            .unwrap();
+
+        drop(p2);
+        drop(p3);
+
+        // jobs larger than the budget are clamped to the budget
+        let p4 = budget.reserve(100 * MB).await.unwrap();
+        assert!(budget.reserve(0).now_or_never().is_none());
+        drop(p4);
+        budget.reserve(0).now_or_never().unwrap().unwrap();
+    }
+}
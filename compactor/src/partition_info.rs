@@ -9,7 +9,7 @@ use data_types::{
 use schema::sort::SortKey;
 
 /// Information about the Partition being compacted
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct PartitionInfo {
     /// the partition
     pub partition_id: PartitionId,
@@ -34,6 +34,11 @@ pub struct PartitionInfo {
 
     /// partition_key
     pub partition_key: PartitionKey,
+
+    /// Desired max size of compacted files of this partition.
+    ///
+    /// If `None`, the configured `max_desired_file_size_bytes` applies.
+    pub target_file_size_bytes: Option<u64>,
 }
 
 impl PartitionInfo {
@@ -47,4 +52,10 @@ impl PartitionInfo {
     pub fn transition_partition_id(&self) -> TransitionPartitionId {
         TransitionPartitionId::from((self.partition_id, self.partition_hash_id.as_ref()))
     }
+
+    /// Desired max size of compacted files of this partition, or `default` if the partition has
+    /// no target of its own.
+    pub fn max_desired_file_size_bytes(&self, default: u64) -> u64 {
+        self.target_file_size_bytes.unwrap_or(default)
+    }
 }
@@ -23,6 +23,7 @@ impl PartitionInfoBuilder {
             namespace_id,
             name: String::from("table"),
             partition_template: Default::default(),
+            retention_period_ns: None,
         });
         let table_schema = Arc::new(TableSchema::new_empty_from(&table));
 
@@ -36,6 +37,7 @@ impl PartitionInfoBuilder {
                 table_schema,
                 sort_key: None,
                 partition_key,
+                target_file_size_bytes: None,
             },
         }
     }
@@ -58,6 +60,7 @@ impl PartitionInfoBuilder {
         let table_schema = Arc::new(TableSchema {
             id: self.inner.table.id,
             partition_template: Default::default(),
+            retention_period_ns: None,
             columns: ColumnsByName::new(columns),
         });
         self.inner.table_schema = table_schema;
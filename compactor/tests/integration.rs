@@ -1,6 +1,7 @@
 use arrow_util::assert_batches_sorted_eq;
 use compactor_test_utils::{format_files, list_object_store, TestSetup};
 use data_types::{CompactionLevel, ParquetFile, PartitionId};
+use metric::{Attributes, Metric, U64Counter};
 
 mod layouts;
 
@@ -181,6 +182,70 @@ async fn test_compact_target_level() {
     );
 }
 
+#[tokio::test]
+async fn test_compact_with_memory_budget() {
+    test_helpers::maybe_start_logging();
+
+    // Create a test setup with 6 files and a budget that is smaller than any job,
+    // so jobs run one at a time
+    let setup = TestSetup::builder()
+        .await
+        .with_files()
+        .await
+        .with_max_num_files_per_plan(10)
+        .with_min_num_l1_files_to_compact(2)
+        .with_memory_budget_bytes(1)
+        .build()
+        .await;
+
+    // compact
+    setup.run_compact().await;
+
+    // same result as without a budget
+    let files = setup.list_by_table_not_to_delete().await;
+    assert_levels(
+        &files,
+        vec![(9, CompactionLevel::Final), (10, CompactionLevel::Final)],
+    );
+}
+
+#[tokio::test]
+async fn test_compact_with_output_verification() {
+    test_helpers::maybe_start_logging();
+
+    let setup = TestSetup::builder()
+        .await
+        .with_files()
+        .await
+        .with_max_num_files_per_plan(10)
+        .with_min_num_l1_files_to_compact(2)
+        .with_verify_output()
+        .build()
+        .await;
+
+    // compact
+    setup.run_compact().await;
+
+    let files = setup.list_by_table_not_to_delete().await;
+    assert_levels(
+        &files,
+        vec![(9, CompactionLevel::Final), (10, CompactionLevel::Final)],
+    );
+
+    // the compacted files passed all checks
+    let registry = setup.catalog.metric_registry();
+    let failures = registry
+        .get_instrument::<Metric<U64Counter>>("iox_compactor_output_verification_failures")
+        .unwrap();
+    for check in ["row_count", "sort_order", "read"] {
+        let count = failures
+            .get_observer(&Attributes::from(&[("check", check)]))
+            .unwrap()
+            .fetch();
+        assert_eq!(count, 0, "{check}");
+    }
+}
+
 #[tokio::test]
 async fn test_compact_large_overlapes() {
     test_helpers::maybe_start_logging();
@@ -524,6 +589,36 @@ async fn test_shadow_mode_partition_fail() {
     assert_skipped_compactions(&setup, []).await;
 }
 
+#[tokio::test]
+async fn test_dry_run() {
+    test_helpers::maybe_start_logging();
+
+    // Create a test setup with 6 files
+    let setup = TestSetup::builder()
+        .await
+        .with_files()
+        .await
+        .with_dry_run()
+        .build()
+        .await;
+
+    let catalog_files_pre = setup.list_by_table_not_to_delete().await;
+    assert!(!catalog_files_pre.is_empty());
+
+    let object_store_files_pre = list_object_store(&setup.catalog.object_store).await;
+    assert!(!object_store_files_pre.is_empty());
+
+    setup.run_compact().await;
+
+    let catalog_files_post = setup.list_by_table_not_to_delete().await;
+    assert_eq!(catalog_files_pre, catalog_files_post);
+
+    let object_store_files_post = list_object_store(&setup.catalog.object_store).await;
+    assert_eq!(object_store_files_pre, object_store_files_post);
+
+    assert_skipped_compactions(&setup, []).await;
+}
+
 #[track_caller]
 fn assert_levels<'a>(
     files: impl IntoIterator<Item = &'a ParquetFile>,
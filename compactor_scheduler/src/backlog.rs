@@ -0,0 +1,400 @@
+use std::{collections::HashMap, sync::Arc};
+
+use data_types::{CompactionLevel, ParquetFile, PartitionId};
+use iox_time::{Time, TimeProvider};
+use metric::{Registry, U64Gauge};
+use parking_lot::Mutex;
+
+/// Compaction backlog of a single partition, as last seen by the compactor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionBacklog {
+    /// The partition.
+    pub partition_id: PartitionId,
+
+    /// Number of L0 files.
+    pub num_l0_files: usize,
+
+    /// Number of L1 files.
+    pub num_l1_files: usize,
+
+    /// Total size of the L0 and L1 files.
+    pub bytes: u64,
+
+    /// Creation time of the oldest L0 or L1 file.
+    pub oldest_file_created_at: Time,
+}
+
+impl PartitionBacklog {
+    /// Compute the backlog from the files of a partition.
+    ///
+    /// Only L0 and L1 files are awaiting compaction. Returns `None` if there are none.
+    fn from_files(partition_id: PartitionId, files: &[ParquetFile]) -> Option<Self> {
+        let mut num_l0_files = 0;
+        let mut num_l1_files = 0;
+        let mut bytes = 0;
+        let mut oldest_file_created_at = None;
+
+        for file in files {
+            match file.compaction_level {
+                CompactionLevel::Initial => num_l0_files += 1,
+                CompactionLevel::FileNonOverlapped => num_l1_files += 1,
+                CompactionLevel::Final => continue,
+            }
+            bytes += file.file_size_bytes as u64;
+            let created_at = Time::from(file.created_at);
+            oldest_file_created_at = Some(match oldest_file_created_at {
+                Some(t) if t < created_at => t,
+                _ => created_at,
+            });
+        }
+
+        oldest_file_created_at.map(|oldest_file_created_at| Self {
+            partition_id,
+            num_l0_files,
+            num_l1_files,
+            bytes,
+            oldest_file_created_at,
+        })
+    }
+}
+
+/// A compaction job that is currently running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InFlightJob {
+    /// The partition being compacted.
+    pub partition_id: PartitionId,
+
+    /// When the job started.
+    pub started_at: Time,
+}
+
+/// Point-in-time view of a [`CompactionBacklog`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BacklogSnapshot {
+    /// Partitions with files awaiting compaction, ordered by partition ID.
+    pub partitions: Vec<PartitionBacklog>,
+
+    /// Jobs that are currently running, ordered by partition ID.
+    pub jobs: Vec<InFlightJob>,
+}
+
+/// Backlog of the partitions a compactor has seen and the jobs it is currently running.
+///
+/// The compactor updates the backlog of a partition at the start of every compaction round and
+/// removes it once there is nothing left to do. Partitions that the compactor has not picked up
+/// yet are NOT part of the backlog.
+///
+/// The totals are also exposed as metrics, so autoscaling can be driven by the backlog.
+///
+/// This is a cheaply cloneable handle, all clones share the same state.
+#[derive(Debug, Clone)]
+pub struct CompactionBacklog {
+    state: Arc<Mutex<State>>,
+    metrics: Arc<Metrics>,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    partitions: HashMap<PartitionId, PartitionBacklog>,
+    jobs: HashMap<PartitionId, Time>,
+}
+
+#[derive(Debug)]
+struct Metrics {
+    partitions: U64Gauge,
+    l0_files: U64Gauge,
+    l1_files: U64Gauge,
+    bytes: U64Gauge,
+    oldest_file_age_seconds: U64Gauge,
+    jobs: U64Gauge,
+}
+
+impl Metrics {
+    fn new(registry: &Registry) -> Self {
+        let partitions = registry
+            .register_metric::<U64Gauge>(
+                "iox_compactor_backlog_partitions",
+                "Number of partitions with files awaiting compaction",
+            )
+            .recorder(&[]);
+        let files = registry.register_metric::<U64Gauge>(
+            "iox_compactor_backlog_files",
+            "Number of files awaiting compaction",
+        );
+        let l0_files = files.recorder(&[("level", "0")]);
+        let l1_files = files.recorder(&[("level", "1")]);
+        let bytes = registry
+            .register_metric::<U64Gauge>(
+                "iox_compactor_backlog_bytes",
+                "Total size of the files awaiting compaction",
+            )
+            .recorder(&[]);
+        let oldest_file_age_seconds = registry
+            .register_metric::<U64Gauge>(
+                "iox_compactor_backlog_oldest_file_age_seconds",
+                "Age of the oldest file awaiting compaction, as of the last backlog update",
+            )
+            .recorder(&[]);
+        let jobs = registry
+            .register_metric::<U64Gauge>(
+                "iox_compactor_backlog_jobs_in_flight",
+                "Number of compaction jobs currently running",
+            )
+            .recorder(&[]);
+
+        Self {
+            partitions,
+            l0_files,
+            l1_files,
+            bytes,
+            oldest_file_age_seconds,
+            jobs,
+        }
+    }
+}
+
+impl CompactionBacklog {
+    /// Create a new, empty backlog.
+    pub fn new(registry: &Registry, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            state: Default::default(),
+            metrics: Arc::new(Metrics::new(registry)),
+            time_provider,
+        }
+    }
+
+    /// Update the backlog of a partition from its current files.
+    ///
+    /// The partition is removed from the backlog if none of the files awaits compaction.
+    pub fn record_files(&self, partition_id: PartitionId, files: &[ParquetFile]) {
+        let mut state = self.state.lock();
+        match PartitionBacklog::from_files(partition_id, files) {
+            Some(backlog) => {
+                state.partitions.insert(partition_id, backlog);
+            }
+            None => {
+                state.partitions.remove(&partition_id);
+            }
+        }
+        self.update_metrics(&state);
+    }
+
+    /// Remove a partition from the backlog because there is nothing left to compact.
+    pub fn partition_done(&self, partition_id: PartitionId) {
+        let mut state = self.state.lock();
+        state.partitions.remove(&partition_id);
+        self.update_metrics(&state);
+    }
+
+    /// Record that a job for the given partition started.
+    pub fn job_started(&self, partition_id: PartitionId) {
+        let mut state = self.state.lock();
+        state.jobs.insert(partition_id, self.time_provider.now());
+        self.update_metrics(&state);
+    }
+
+    /// Record that the job for the given partition finished.
+    pub fn job_finished(&self, partition_id: PartitionId) {
+        let mut state = self.state.lock();
+        state.jobs.remove(&partition_id);
+        self.update_metrics(&state);
+    }
+
+    /// Current state of the backlog.
+    pub fn snapshot(&self) -> BacklogSnapshot {
+        let state = self.state.lock();
+
+        let mut partitions = state.partitions.values().copied().collect::<Vec<_>>();
+        partitions.sort_by_key(|p| p.partition_id);
+
+        let mut jobs = state
+            .jobs
+            .iter()
+            .map(|(partition_id, started_at)| InFlightJob {
+                partition_id: *partition_id,
+                started_at: *started_at,
+            })
+            .collect::<Vec<_>>();
+        jobs.sort_by_key(|j| j.partition_id);
+
+        BacklogSnapshot { partitions, jobs }
+    }
+
+    fn update_metrics(&self, state: &State) {
+        let partitions = state.partitions.values();
+        self.metrics.partitions.set(state.partitions.len() as u64);
+        self.metrics
+            .l0_files
+            .set(partitions.clone().map(|p| p.num_l0_files as u64).sum());
+        self.metrics
+            .l1_files
+            .set(partitions.clone().map(|p| p.num_l1_files as u64).sum());
+        self.metrics
+            .bytes
+            .set(partitions.clone().map(|p| p.bytes).sum());
+
+        let now = self.time_provider.now();
+        let oldest_file_age_seconds = partitions
+            .map(|p| p.oldest_file_created_at)
+            .min()
+            .and_then(|oldest| now.checked_duration_since(oldest))
+            .map(|age| age.as_secs())
+            .unwrap_or_default();
+        self.metrics
+            .oldest_file_age_seconds
+            .set(oldest_file_age_seconds);
+
+        self.metrics.jobs.set(state.jobs.len() as u64);
+    }
+}
+
+impl std::fmt::Display for CompactionBacklog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "backlog")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use data_types::Timestamp;
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::MockProvider;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn file(id: i64, level: CompactionLevel, size: i64, created_at: i64) -> ParquetFile {
+        let mut file = ParquetFileBuilder::new(id)
+            .with_compaction_level(level)
+            .with_file_size_bytes(size)
+            .build();
+        file.created_at = Timestamp::new(created_at);
+        file
+    }
+
+    fn gauge(registry: &Registry, name: &'static str, attributes: impl Into<Attributes>) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Gauge>>(name)
+            .expect("metric registered")
+            .get_observer(&attributes.into())
+            .expect("observer registered")
+            .fetch()
+    }
+
+    #[test]
+    fn test_backlog() {
+        let registry = Registry::new();
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let backlog = CompactionBacklog::new(&registry, Arc::clone(&time_provider) as _);
+        assert_eq!(backlog.snapshot(), BacklogSnapshot::default());
+
+        let p1 = PartitionId::new(1);
+        let p2 = PartitionId::new(2);
+
+        backlog.job_started(p2);
+        time_provider.inc(Duration::from_secs(10));
+        backlog.job_started(p1);
+        backlog.record_files(
+            p2,
+            &[
+                file(1, CompactionLevel::Initial, 10, 3_000_000_000),
+                file(2, CompactionLevel::Initial, 20, 1_000_000_000),
+                file(3, CompactionLevel::FileNonOverlapped, 30, 2_000_000_000),
+                // not awaiting compaction
+                file(4, CompactionLevel::Final, 1_000, 0),
+            ],
+        );
+        backlog.record_files(
+            p1,
+            &[file(
+                5,
+                CompactionLevel::FileNonOverlapped,
+                5,
+                4_000_000_000,
+            )],
+        );
+
+        assert_eq!(
+            backlog.snapshot(),
+            BacklogSnapshot {
+                partitions: vec![
+                    PartitionBacklog {
+                        partition_id: p1,
+                        num_l0_files: 0,
+                        num_l1_files: 1,
+                        bytes: 5,
+                        oldest_file_created_at: Time::from_timestamp_nanos(4_000_000_000),
+                    },
+                    PartitionBacklog {
+                        partition_id: p2,
+                        num_l0_files: 2,
+                        num_l1_files: 1,
+                        bytes: 60,
+                        oldest_file_created_at: Time::from_timestamp_nanos(1_000_000_000),
+                    },
+                ],
+                jobs: vec![
+                    InFlightJob {
+                        partition_id: p1,
+                        started_at: Time::from_timestamp_nanos(10_000_000_000),
+                    },
+                    InFlightJob {
+                        partition_id: p2,
+                        started_at: Time::from_timestamp_nanos(0),
+                    },
+                ],
+            }
+        );
+        assert_eq!(gauge(&registry, "iox_compactor_backlog_partitions", &[]), 2);
+        assert_eq!(
+            gauge(&registry, "iox_compactor_backlog_files", &[("level", "0")]),
+            2
+        );
+        assert_eq!(
+            gauge(&registry, "iox_compactor_backlog_files", &[("level", "1")]),
+            2
+        );
+        assert_eq!(gauge(&registry, "iox_compactor_backlog_bytes", &[]), 65);
+        assert_eq!(
+            gauge(
+                &registry,
+                "iox_compactor_backlog_oldest_file_age_seconds",
+                &[]
+            ),
+            9
+        );
+        assert_eq!(
+            gauge(&registry, "iox_compactor_backlog_jobs_in_flight", &[]),
+            2
+        );
+
+        // only L2 files left
+        backlog.record_files(p2, &[file(6, CompactionLevel::Final, 100, 0)]);
+        backlog.job_finished(p2);
+        backlog.partition_done(p1);
+        backlog.job_finished(p1);
+
+        assert_eq!(backlog.snapshot(), BacklogSnapshot::default());
+        assert_eq!(gauge(&registry, "iox_compactor_backlog_partitions", &[]), 0);
+        assert_eq!(
+            gauge(&registry, "iox_compactor_backlog_files", &[("level", "0")]),
+            0
+        );
+        assert_eq!(gauge(&registry, "iox_compactor_backlog_bytes", &[]), 0);
+        assert_eq!(
+            gauge(
+                &registry,
+                "iox_compactor_backlog_oldest_file_age_seconds",
+                &[]
+            ),
+            0
+        );
+        assert_eq!(
+            gauge(&registry, "iox_compactor_backlog_jobs_in_flight", &[]),
+            0
+        );
+    }
+}
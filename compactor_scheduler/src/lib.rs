@@ -26,6 +26,9 @@ use iox_time::TimeProvider;
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+mod backlog;
+pub use backlog::{BacklogSnapshot, CompactionBacklog, InFlightJob, PartitionBacklog};
+
 pub(crate) mod commit;
 pub(crate) use commit::mock::MockCommit;
 pub use commit::{Commit, CommitWrapper, Error as CommitError};
@@ -38,7 +41,9 @@ mod local_scheduler;
 pub(crate) use local_scheduler::partition_done_sink::mock::MockPartitionDoneSink;
 pub use local_scheduler::{
     combos::throttle_partition::Error as ThrottleError,
-    partitions_source_config::PartitionsSourceConfig, shard_config::ShardConfig,
+    partitions_source::manual::ManualPartitionQueue,
+    partitions_source_config::{ColdPartitionsSourceConfig, PartitionsSourceConfig},
+    shard_config::ShardConfig,
     LocalSchedulerConfig,
 };
 pub(crate) use local_scheduler::{
@@ -100,6 +105,10 @@ pub fn create_test_scheduler(
                 partition_ids.into_iter().collect::<HashSet<PartitionId>>(),
             ),
             shard_config: None,
+            cold_partitions_source_config: None,
+            manual_partition_queue: ManualPartitionQueue::default(),
+            prioritize_l0_backlog: false,
+            job_lease_duration: None,
         }),
     };
     create_scheduler(
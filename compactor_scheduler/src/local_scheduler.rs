@@ -1,7 +1,10 @@
 //! Internals used by [`LocalScheduler`].
 pub(crate) mod catalog_commit;
 pub(crate) mod combos;
+pub(crate) mod compaction_history;
 pub(crate) mod id_only_partition_filter;
+pub(crate) mod job_leases;
+pub(crate) mod l0_backlog;
 pub(crate) mod partition_done_sink;
 pub(crate) mod partitions_source;
 pub(crate) mod partitions_source_config;
@@ -17,25 +20,31 @@ use observability_deps::tracing::{info, warn};
 
 use crate::{
     commit::{logging::LoggingCommitWrapper, metrics::MetricsCommitWrapper},
-    Commit, CommitUpdate, CommitWrapper, CompactionJob, CompactionJobEnd, CompactionJobEndVariant,
-    CompactionJobStatus, CompactionJobStatusResponse, CompactionJobStatusVariant, MockCommit,
-    MockPartitionsSource, PartitionsSource, PartitionsSourceConfig, Scheduler, ShardConfig,
-    SkipReason,
+    ColdPartitionsSourceConfig, Commit, CommitUpdate, CommitWrapper, CompactionJob,
+    CompactionJobEnd, CompactionJobEndVariant, CompactionJobStatus, CompactionJobStatusResponse,
+    CompactionJobStatusVariant, MockCommit, MockPartitionsSource, PartitionsSource,
+    PartitionsSourceConfig, Scheduler, ShardConfig, SkipReason,
 };
 
 use self::{
     catalog_commit::CatalogCommit,
     combos::{throttle_partition::throttle_partition, unique_partitions::unique_partitions},
+    compaction_history::CompactionHistoryRecorder,
     id_only_partition_filter::{
         and::AndIdOnlyPartitionFilter, shard::ShardPartitionFilter, IdOnlyPartitionFilter,
     },
+    job_leases::{Error as JobLeaseError, JobLeases},
+    l0_backlog::L0BacklogPrioritizer,
     partition_done_sink::{
         catalog::CatalogPartitionDoneSink, mock::MockPartitionDoneSink, PartitionDoneSink,
     },
     partitions_source::{
         catalog_all::CatalogAllPartitionsSource,
+        catalog_cold::CatalogColdPartitionsSource,
         catalog_to_compact::CatalogToCompactPartitionsSource,
+        chain::ChainPartitionsSource,
         filter::FilterPartitionsSourceWrapper,
+        manual::{ManualPartitionQueue, ManualPartitionsSourceWrapper},
     },
 };
 
@@ -50,6 +59,23 @@ pub struct LocalSchedulerConfig {
     pub partitions_source_config: PartitionsSourceConfig,
     /// The shard config used by the local sceduler.
     pub shard_config: Option<ShardConfig>,
+    /// Additionally schedule cold partitions, after the partitions of the partitions source.
+    ///
+    /// Only used with [`PartitionsSourceConfig::CatalogRecentWrites`].
+    pub cold_partitions_source_config: Option<ColdPartitionsSourceConfig>,
+    /// Partitions that operators requested to be compacted ahead of all others.
+    ///
+    /// These bypass the shard filter.
+    pub manual_partition_queue: ManualPartitionQueue,
+    /// Attach the size of the L0 backlog to each job as [priority](CompactionJob::priority).
+    pub prioritize_l0_backlog: bool,
+    /// Lease jobs for this long.
+    ///
+    /// Compactors must renew the lease of their in-progress jobs via
+    /// [`update_job_status`](Scheduler::update_job_status). Jobs whose lease expires are
+    /// cancelled, their partitions are handed out again once the cancelled jobs ended. Jobs are
+    /// not leased if `None`.
+    pub job_lease_duration: Option<Duration>,
 }
 
 /// Implementation of the scheduler for local (per compactor) scheduling.
@@ -65,6 +91,12 @@ pub(crate) struct LocalScheduler {
     partition_done_sink: Arc<dyn PartitionDoneSink>,
     /// The shard config used for generating the PartitionsSource.
     shard_config: Option<ShardConfig>,
+    /// Scores jobs, if enabled.
+    l0_backlog_prioritizer: Option<L0BacklogPrioritizer>,
+    /// Leases of in-progress jobs, if enabled.
+    job_leases: Option<JobLeases>,
+    /// Records commits in the catalog, unless in shadow mode.
+    compaction_history: Option<CompactionHistoryRecorder>,
 }
 
 impl LocalScheduler {
@@ -81,10 +113,22 @@ impl LocalScheduler {
             config.clone(),
             backoff_config.clone(),
             Arc::clone(&catalog),
-            metrics,
+            Arc::clone(&metrics),
             shadow_mode,
         );
 
+        let job_leases = config
+            .job_lease_duration
+            .map(|duration| JobLeases::new(duration, Arc::clone(&time_provider), &metrics));
+
+        let compaction_history = (!shadow_mode).then(|| {
+            CompactionHistoryRecorder::new(Arc::clone(&catalog), Arc::clone(&time_provider))
+        });
+
+        let l0_backlog_prioritizer = config
+            .prioritize_l0_backlog
+            .then(|| L0BacklogPrioritizer::new(backoff_config.clone(), Arc::clone(&catalog)));
+
         let partitions_source = Self::build_partitions_source(
             config.clone(),
             backoff_config.clone(),
@@ -106,6 +150,9 @@ impl LocalScheduler {
             partitions_source,
             partition_done_sink,
             shard_config: config.shard_config,
+            l0_backlog_prioritizer,
+            job_leases,
+            compaction_history,
         }
     }
 
@@ -118,13 +165,31 @@ impl LocalScheduler {
         let shard_config = config.shard_config;
         let partitions_source: Arc<dyn PartitionsSource> = match &config.partitions_source_config {
             PartitionsSourceConfig::CatalogRecentWrites { threshold } => {
-                Arc::new(CatalogToCompactPartitionsSource::new(
-                    backoff_config,
+                let recent_writes = CatalogToCompactPartitionsSource::new(
+                    backoff_config.clone(),
                     Arc::clone(&catalog),
                     *threshold,
                     None, // Recent writes is `threshold` ago to now
-                    time_provider,
-                ))
+                    Arc::clone(&time_provider),
+                );
+
+                match &config.cold_partitions_source_config {
+                    Some(ColdPartitionsSourceConfig {
+                        threshold,
+                        max_partitions,
+                    }) => Arc::new(ChainPartitionsSource::new(vec![
+                        Box::new(recent_writes),
+                        // cold partitions have lower priority
+                        Box::new(CatalogColdPartitionsSource::new(
+                            backoff_config,
+                            Arc::clone(&catalog),
+                            *threshold,
+                            *max_partitions,
+                            time_provider,
+                        )),
+                    ])),
+                    None => Arc::new(recent_writes),
+                }
             }
             PartitionsSourceConfig::CatalogAll => Arc::new(CatalogAllPartitionsSource::new(
                 backoff_config,
@@ -147,9 +212,12 @@ impl LocalScheduler {
                 shard_config.shard_id,
             )));
         }
-        Arc::new(FilterPartitionsSourceWrapper::new(
-            AndIdOnlyPartitionFilter::new(id_only_partition_filters),
-            partitions_source,
+        Arc::new(ManualPartitionsSourceWrapper::new(
+            config.manual_partition_queue,
+            FilterPartitionsSourceWrapper::new(
+                AndIdOnlyPartitionFilter::new(id_only_partition_filters),
+                partitions_source,
+            ),
         ))
     }
 
@@ -222,18 +290,50 @@ impl LocalScheduler {
 #[async_trait]
 impl Scheduler for LocalScheduler {
     async fn get_jobs(&self) -> Vec<CompactionJob> {
-        self.partitions_source
-            .fetch()
-            .await
-            .into_iter()
-            .map(CompactionJob::new)
-            .collect()
+        // Jobs with an expired lease may still be running, so they are cancelled rather than
+        // handed out again. Their partitions stay in progress until the jobs ended.
+        if let Some(job_leases) = &self.job_leases {
+            let cancelled = job_leases.cancel_expired();
+            if !cancelled.is_empty() {
+                warn!(
+                    n_partitions = cancelled.len(),
+                    "cancelling compaction jobs with expired lease",
+                );
+            }
+        }
+        let partition_ids = self.partitions_source.fetch().await;
+
+        if let Some(job_leases) = &self.job_leases {
+            job_leases.grant(partition_ids.iter().copied());
+        }
+        if let Some(compaction_history) = &self.compaction_history {
+            compaction_history.start(partition_ids.iter().copied());
+        }
+
+        match &self.l0_backlog_prioritizer {
+            Some(prioritizer) => prioritizer.jobs(partition_ids).await,
+            None => partition_ids.into_iter().map(CompactionJob::new).collect(),
+        }
     }
 
     async fn update_job_status(
         &self,
         job_status: CompactionJobStatus,
     ) -> Result<CompactionJobStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(job_leases) = &self.job_leases {
+            let res = job_leases.renew(job_status.job.partition_id);
+            // only reject heartbeats, commits of jobs without a lease are still valid
+            if matches!(job_status.status, CompactionJobStatusVariant::Heartbeat) {
+                match res {
+                    Ok(()) => {}
+                    Err(JobLeaseError::Cancelled(_)) => {
+                        return Ok(CompactionJobStatusResponse::Cancelled)
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+
         match job_status.status {
             CompactionJobStatusVariant::Update(commit_update) => {
                 let CommitUpdate {
@@ -249,12 +349,19 @@ impl Scheduler for LocalScheduler {
                     .commit(partition_id, &delete, &upgrade, &create, target_level)
                     .await?;
 
+                if let Some(compaction_history) = &self.compaction_history {
+                    compaction_history
+                        .record(&job_status.job, &delete, &upgrade, &create, target_level)
+                        .await;
+                }
+
                 Ok(CompactionJobStatusResponse::CreatedParquetFiles(result))
             }
             CompactionJobStatusVariant::Error(error_kind) => {
                 warn!("Error processing job: {:?}: {}", job_status.job, error_kind);
                 Ok(CompactionJobStatusResponse::Ack)
             }
+            CompactionJobStatusVariant::Heartbeat => Ok(CompactionJobStatusResponse::Ack),
         }
     }
 
@@ -262,6 +369,13 @@ impl Scheduler for LocalScheduler {
         &self,
         end: CompactionJobEnd,
     ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(job_leases) = &self.job_leases {
+            job_leases.release(end.job.partition_id);
+        }
+        if let Some(compaction_history) = &self.compaction_history {
+            compaction_history.end(end.job.partition_id);
+        }
+
         match end.end_action {
             CompactionJobEndVariant::RequestToSkip(SkipReason(msg)) => {
                 self.partition_done_sink
@@ -293,7 +407,8 @@ impl std::fmt::Display for LocalScheduler {
 
 #[cfg(test)]
 mod tests {
-    use iox_tests::TestCatalog;
+    use data_types::PartitionId;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
     use iox_time::{MockProvider, Time};
 
     use super::*;
@@ -323,6 +438,10 @@ mod tests {
             commit_wrapper: None,
             partitions_source_config: PartitionsSourceConfig::default(),
             shard_config,
+            cold_partitions_source_config: None,
+            manual_partition_queue: ManualPartitionQueue::default(),
+            prioritize_l0_backlog: false,
+            job_lease_duration: None,
         };
 
         let scheduler = LocalScheduler::new(
@@ -339,4 +458,114 @@ mod tests {
             "local_compaction_scheduler(shard_cfg(n_shards=2,shard_id=1))",
         );
     }
+
+    #[tokio::test]
+    async fn test_cold_partitions() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        let partition = table.create_partition("cold").await;
+        partition
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default()
+                    .with_creation_time(catalog.time_provider().hours_ago(2)),
+            )
+            .await;
+
+        let new_scheduler = |cold_partitions_source_config| {
+            LocalScheduler::new(
+                LocalSchedulerConfig {
+                    cold_partitions_source_config,
+                    ..Default::default()
+                },
+                BackoffConfig::default(),
+                catalog.catalog(),
+                catalog.time_provider(),
+                Arc::new(metric::Registry::default()),
+                false,
+            )
+        };
+
+        // recent writes only
+        let scheduler = new_scheduler(None);
+        assert!(scheduler.get_jobs().await.is_empty());
+
+        // with cold partitions
+        let scheduler = new_scheduler(Some(ColdPartitionsSourceConfig {
+            threshold: Duration::from_secs(60 * 60),
+            max_partitions: 10,
+        }));
+        let jobs = scheduler.get_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].partition_id, partition.partition.id);
+    }
+
+    #[tokio::test]
+    async fn test_manual_partitions() {
+        let catalog = TestCatalog::new();
+        let queue = ManualPartitionQueue::default();
+        let scheduler = LocalScheduler::new(
+            LocalSchedulerConfig {
+                partitions_source_config: PartitionsSourceConfig::Fixed(
+                    [PartitionId::new(1)].into(),
+                ),
+                // would filter out the requested partition
+                shard_config: Some(ShardConfig {
+                    n_shards: 1_000,
+                    shard_id: 999,
+                }),
+                manual_partition_queue: queue.clone(),
+                ..Default::default()
+            },
+            BackoffConfig::default(),
+            catalog.catalog(),
+            catalog.time_provider(),
+            Arc::new(metric::Registry::default()),
+            true,
+        );
+
+        let requested = PartitionId::new(42);
+        queue.push(requested);
+        let jobs = scheduler.get_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].partition_id, requested);
+
+        // the job is tracked like any other job
+        scheduler
+            .end_job(CompactionJobEnd {
+                job: jobs[0].clone(),
+                end_action: CompactionJobEndVariant::Complete,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prioritize_l0_backlog() {
+        let catalog = TestCatalog::new();
+        let new_scheduler = |prioritize_l0_backlog| {
+            LocalScheduler::new(
+                LocalSchedulerConfig {
+                    partitions_source_config: PartitionsSourceConfig::Fixed(
+                        [PartitionId::new(1)].into(),
+                    ),
+                    prioritize_l0_backlog,
+                    ..Default::default()
+                },
+                BackoffConfig::default(),
+                catalog.catalog(),
+                catalog.time_provider(),
+                Arc::new(metric::Registry::default()),
+                false,
+            )
+        };
+
+        let jobs = new_scheduler(false).get_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].priority, None);
+
+        let jobs = new_scheduler(true).get_jobs().await;
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].priority, Some(0));
+    }
 }
@@ -61,11 +61,33 @@ impl Commit for CatalogCommit {
             .retry_all_errors("commit parquet file changes", || async {
                 let mut repos = self.catalog.repositories().await;
                 let parquet_files = repos.parquet_files();
-                let ids = parquet_files
+                match parquet_files
                     .create_upgrade_delete(&delete, &upgrade, create, target_level)
-                    .await?;
-
-                Ok::<_, iox_catalog::interface::Error>(ids)
+                    .await
+                {
+                    Ok(ids) => Ok(ids),
+                    // A previous attempt may have been committed, despite
+                    // returning an error (such as when the connection is lost
+                    // while committing). The commit is applied atomically and
+                    // the object store IDs of the created files are unique to
+                    // it, so the created files identify the applied commit.
+                    Err(e @ iox_catalog::interface::Error::FileExists { object_store_id })
+                        if create.iter().any(|f| f.object_store_id == object_store_id) =>
+                    {
+                        let mut ids = Vec::with_capacity(create.len());
+                        for f in create {
+                            match parquet_files
+                                .get_by_object_store_id(f.object_store_id)
+                                .await?
+                            {
+                                Some(file) => ids.push(file.id),
+                                None => return Err(e),
+                            }
+                        }
+                        Ok(ids)
+                    }
+                    Err(e) => Err(e),
+                }
             })
             .await
             .expect("retry forever");
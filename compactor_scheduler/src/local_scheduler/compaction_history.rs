@@ -0,0 +1,172 @@
+//! History of the compaction commits of each partition.
+
+use std::{collections::HashMap, sync::Arc};
+
+use data_types::{
+    CompactionHistoryParams, CompactionLevel, ParquetFile, ParquetFileParams, PartitionId,
+    Timestamp,
+};
+use iox_catalog::interface::Catalog;
+use iox_time::{Time, TimeProvider};
+use observability_deps::tracing::warn;
+use parking_lot::Mutex;
+
+use crate::CompactionJob;
+
+/// Records every commit of a compaction job in the `compaction_history` catalog table.
+///
+/// The history is only used for post-hoc analysis (e.g. why a partition keeps getting
+/// recompacted), so failing to record a commit is logged but does not fail the commit.
+#[derive(Debug)]
+pub(crate) struct CompactionHistoryRecorder {
+    catalog: Arc<dyn Catalog>,
+    time_provider: Arc<dyn TimeProvider>,
+    /// Start of the current step of every in-progress job, i.e. when the job was handed out or
+    /// when it last committed.
+    step_started: Mutex<HashMap<PartitionId, Time>>,
+}
+
+impl CompactionHistoryRecorder {
+    pub(crate) fn new(catalog: Arc<dyn Catalog>, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            catalog,
+            time_provider,
+            step_started: Default::default(),
+        }
+    }
+
+    /// Start timing the jobs of the given partitions.
+    pub(crate) fn start(&self, partitions: impl IntoIterator<Item = PartitionId>) {
+        let now = self.time_provider.now();
+        let mut step_started = self.step_started.lock();
+        for partition_id in partitions {
+            step_started.insert(partition_id, now);
+        }
+    }
+
+    /// Stop timing the job of the given partition because it ended.
+    pub(crate) fn end(&self, partition_id: PartitionId) {
+        self.step_started.lock().remove(&partition_id);
+    }
+
+    /// Record a successful commit of the given job.
+    pub(crate) async fn record(
+        &self,
+        job: &CompactionJob,
+        delete: &[ParquetFile],
+        upgrade: &[ParquetFile],
+        create: &[ParquetFileParams],
+        target_level: CompactionLevel,
+    ) {
+        let partition_id = job.partition_id;
+        let now = self.time_provider.now();
+        let started = self.step_started.lock().insert(partition_id, now);
+        let duration = started
+            .and_then(|started| now.checked_duration_since(started))
+            .unwrap_or_default();
+
+        let params = CompactionHistoryParams {
+            partition_id,
+            job_id: job.uuid(),
+            num_input_files: delete.len() as i64,
+            input_bytes: delete.iter().map(|f| f.file_size_bytes).sum(),
+            num_upgraded_files: upgrade.len() as i64,
+            num_output_files: create.len() as i64,
+            output_bytes: create.iter().map(|f| f.file_size_bytes).sum(),
+            target_level,
+            duration_ms: duration.as_millis() as i64,
+            committed_at: Timestamp::from(now),
+        };
+
+        if let Err(e) = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .record_compaction_history(params)
+            .await
+        {
+            warn!(
+                partition_id = partition_id.get(),
+                %e,
+                "cannot record compaction history",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use iox_tests::{ParquetFileBuilder, TestCatalog};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+        let partition_id = table.create_partition("k").await.partition.id;
+
+        let recorder = CompactionHistoryRecorder::new(catalog.catalog(), catalog.time_provider());
+        let job = CompactionJob::new(partition_id);
+        let file = |id: i64, size: i64| {
+            ParquetFileBuilder::new(id)
+                .with_partition(partition_id.get())
+                .with_file_size_bytes(size)
+                .build()
+        };
+
+        recorder.start([partition_id]);
+        catalog.mock_time_provider().inc(Duration::from_secs(2));
+        recorder
+            .record(
+                &job,
+                &[file(1, 10), file(2, 20)],
+                &[file(3, 100)],
+                &[file(4, 25).into()],
+                CompactionLevel::FileNonOverlapped,
+            )
+            .await;
+
+        // the second step is timed from the first commit
+        catalog.mock_time_provider().inc(Duration::from_secs(1));
+        recorder
+            .record(
+                &job,
+                &[file(4, 25)],
+                &[],
+                &[file(5, 20).into()],
+                CompactionLevel::Final,
+            )
+            .await;
+        recorder.end(partition_id);
+
+        let history = catalog
+            .catalog()
+            .repositories()
+            .await
+            .partitions()
+            .list_compaction_history(partition_id)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 2);
+
+        assert_eq!(history[0].job_id, job.uuid());
+        assert_eq!(history[0].num_input_files, 2);
+        assert_eq!(history[0].input_bytes, 30);
+        assert_eq!(history[0].num_upgraded_files, 1);
+        assert_eq!(history[0].num_output_files, 1);
+        assert_eq!(history[0].output_bytes, 25);
+        assert_eq!(history[0].target_level, CompactionLevel::FileNonOverlapped);
+        assert_eq!(history[0].duration_ms, 2_000);
+
+        assert_eq!(history[1].job_id, job.uuid());
+        assert_eq!(history[1].num_input_files, 1);
+        assert_eq!(history[1].target_level, CompactionLevel::Final);
+        assert_eq!(history[1].duration_ms, 1_000);
+        assert!(history[1].committed_at > history[0].committed_at);
+    }
+}
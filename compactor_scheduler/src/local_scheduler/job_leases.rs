@@ -0,0 +1,181 @@
+//! Leases of in-progress compaction jobs.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use data_types::PartitionId;
+use iox_time::{Time, TimeProvider};
+use metric::U64Counter;
+use parking_lot::Mutex;
+
+/// Error returned by [`JobLeases`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum Error {
+    /// The partition is not leased, e.g. because the job was already ended.
+    #[error("No lease for partition: {0}")]
+    NotLeased(PartitionId),
+
+    /// The lease expired and the job was cancelled.
+    #[error("Lease for partition expired, job was cancelled: {0}")]
+    Cancelled(PartitionId),
+}
+
+/// Lease of a single job.
+#[derive(Debug)]
+enum Lease {
+    /// The job is in progress and must be renewed before the given time.
+    Active { expires_at: Time },
+    /// The lease expired, the job must stop and end.
+    Cancelled,
+}
+
+/// Tracks a lease for every partition that is handed out as a job.
+///
+/// Compactors renew the lease of a job by sending status updates (at least heartbeats) for it. A
+/// lease that is not renewed within the lease duration expires and its job is cancelled: further
+/// renewals fail with [`Error::Cancelled`], telling the compactor to stop working on the job.
+///
+/// Partitions of cancelled jobs are NOT handed out again right away, the job may still be running.
+/// They stay in progress until the job ended, which keeps the guarantee of the uniqueness wrapper
+/// of the scheduler that there is at most one job per partition. Hence leases are tracked per
+/// partition.
+#[derive(Debug)]
+pub(crate) struct JobLeases {
+    duration: Duration,
+    time_provider: Arc<dyn TimeProvider>,
+    /// Lease of every partition that is in progress.
+    leases: Mutex<HashMap<PartitionId, Lease>>,
+    expired: U64Counter,
+}
+
+impl JobLeases {
+    pub(crate) fn new(
+        duration: Duration,
+        time_provider: Arc<dyn TimeProvider>,
+        metric_registry: &metric::Registry,
+    ) -> Self {
+        let expired = metric_registry
+            .register_metric::<U64Counter>(
+                "iox_compaction_scheduler_expired_job_leases",
+                "Number of compaction jobs that were cancelled because their lease expired",
+            )
+            .recorder(&[]);
+
+        Self {
+            duration,
+            time_provider,
+            leases: Default::default(),
+            expired,
+        }
+    }
+
+    /// Lease the given partitions, i.e. start tracking them.
+    pub(crate) fn grant(&self, partitions: impl IntoIterator<Item = PartitionId>) {
+        let expires_at = self.time_provider.now() + self.duration;
+        let mut leases = self.leases.lock();
+        for partition_id in partitions {
+            leases.insert(partition_id, Lease::Active { expires_at });
+        }
+    }
+
+    /// Extend the lease of the given partition.
+    pub(crate) fn renew(&self, partition_id: PartitionId) -> Result<(), Error> {
+        let expires_at = self.time_provider.now() + self.duration;
+        match self.leases.lock().get_mut(&partition_id) {
+            Some(Lease::Active { expires_at: lease }) => {
+                *lease = expires_at;
+                Ok(())
+            }
+            Some(Lease::Cancelled) => Err(Error::Cancelled(partition_id)),
+            None => Err(Error::NotLeased(partition_id)),
+        }
+    }
+
+    /// Stop tracking the given partition because its job ended.
+    pub(crate) fn release(&self, partition_id: PartitionId) {
+        self.leases.lock().remove(&partition_id);
+    }
+
+    /// Cancel the jobs whose lease expired, returning their partitions.
+    ///
+    /// The partitions stay leased (and in progress) until their jobs ended and were
+    /// [released](Self::release).
+    pub(crate) fn cancel_expired(&self) -> Vec<PartitionId> {
+        let now = self.time_provider.now();
+
+        let mut expired = self
+            .leases
+            .lock()
+            .iter_mut()
+            .filter(
+                |(_, lease)| matches!(lease, Lease::Active { expires_at } if *expires_at <= now),
+            )
+            .map(|(partition_id, lease)| {
+                *lease = Lease::Cancelled;
+                *partition_id
+            })
+            .collect::<Vec<_>>();
+        expired.sort();
+
+        self.expired.inc(expired.len() as u64);
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use iox_time::MockProvider;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_leases() {
+        let time_provider = Arc::new(MockProvider::new(Time::MIN));
+        let registry = metric::Registry::new();
+        let leases = JobLeases::new(LEASE, Arc::clone(&time_provider) as _, &registry);
+
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+
+        assert_matches!(leases.renew(p_1), Err(Error::NotLeased(p)) if p == p_1);
+
+        leases.grant([p_1, p_2, p_3]);
+        assert_eq!(leases.cancel_expired(), vec![]);
+
+        time_provider.inc(LEASE / 2);
+        leases.renew(p_1).unwrap();
+        leases.release(p_3);
+
+        // p_2 was not renewed in time, p_3 is not tracked anymore
+        time_provider.inc(LEASE / 2);
+        assert_eq!(leases.cancel_expired(), vec![p_2]);
+        assert_eq!(leases.cancel_expired(), vec![]);
+
+        // the job of p_2 was cancelled and cannot be renewed anymore
+        assert_matches!(leases.renew(p_2), Err(Error::Cancelled(p)) if p == p_2);
+        time_provider.inc(LEASE);
+        assert_eq!(leases.cancel_expired(), vec![p_1]);
+        assert_matches!(leases.renew(p_2), Err(Error::Cancelled(p)) if p == p_2);
+
+        // ending the cancelled job releases the lease
+        leases.release(p_2);
+        assert_matches!(leases.renew(p_2), Err(Error::NotLeased(p)) if p == p_2);
+        assert_matches!(leases.renew(p_3), Err(Error::NotLeased(p)) if p == p_3);
+
+        // a new job for the partition is leased as usual
+        leases.grant([p_2]);
+        leases.renew(p_2).unwrap();
+
+        let expired = registry
+            .get_instrument::<Metric<U64Counter>>("iox_compaction_scheduler_expired_job_leases")
+            .unwrap()
+            .get_observer(&Attributes::from([]))
+            .unwrap()
+            .fetch();
+        assert_eq!(expired, 2);
+    }
+}
@@ -0,0 +1,130 @@
+//! Score [`CompactionJob`]s by the size of their L0 backlog.
+use std::{collections::HashMap, fmt::Display, sync::Arc};
+
+use backoff::{Backoff, BackoffConfig};
+use data_types::PartitionId;
+use iox_catalog::interface::Catalog;
+
+use crate::CompactionJob;
+
+/// Attaches the summed size of the not-deleted [L0](data_types::CompactionLevel::Initial) files of each partition as
+/// [priority](CompactionJob::priority), so that hot partitions with a big backlog are compacted before trivial ones.
+///
+/// This issues a single catalog query per batch of partitions.
+#[derive(Debug)]
+pub(crate) struct L0BacklogPrioritizer {
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+}
+
+impl L0BacklogPrioritizer {
+    /// Create a new [`L0BacklogPrioritizer`].
+    pub(crate) fn new(backoff_config: BackoffConfig, catalog: Arc<dyn Catalog>) -> Self {
+        Self {
+            backoff_config,
+            catalog,
+        }
+    }
+
+    /// Create jobs for the given partitions, in the given order.
+    ///
+    /// Partitions without L0 files get a priority of zero.
+    pub(crate) async fn jobs(&self, partition_ids: Vec<PartitionId>) -> Vec<CompactionJob> {
+        if partition_ids.is_empty() {
+            return vec![];
+        }
+
+        let sizes: HashMap<_, _> = Backoff::new(&self.backoff_config)
+            .retry_all_errors("sum_l0_file_size_by_partition", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .parquet_files()
+                    .sum_l0_file_size_by_partition(&partition_ids)
+                    .await
+            })
+            .await
+            .expect("retry forever")
+            .into_iter()
+            .collect();
+
+        partition_ids
+            .into_iter()
+            .map(|partition_id| {
+                let size = sizes.get(&partition_id).copied().unwrap_or_default();
+                CompactionJob::new(partition_id).with_priority(size.max(0) as u64)
+            })
+            .collect()
+    }
+}
+
+impl Display for L0BacklogPrioritizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "l0_backlog")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::CompactionLevel;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let prioritizer =
+            L0BacklogPrioritizer::new(BackoffConfig::default(), TestCatalog::new().catalog());
+        assert_eq!(prioritizer.to_string(), "l0_backlog");
+    }
+
+    #[tokio::test]
+    async fn test_jobs() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+
+        let small = table.create_partition("small").await;
+        small
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default().with_file_size_bytes(10),
+            )
+            .await;
+
+        let big = table.create_partition("big").await;
+        for _ in 0..2 {
+            big.create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default().with_file_size_bytes(100),
+            )
+            .await;
+        }
+        // not part of the backlog
+        big.create_parquet_file_catalog_record(
+            TestParquetFileBuilder::default()
+                .with_file_size_bytes(1_000)
+                .with_compaction_level(CompactionLevel::Final),
+        )
+        .await;
+
+        let no_files = PartitionId::new(1_000);
+
+        let prioritizer = L0BacklogPrioritizer::new(BackoffConfig::default(), catalog.catalog());
+        let jobs = prioritizer
+            .jobs(vec![small.partition.id, no_files, big.partition.id])
+            .await;
+        let jobs = jobs
+            .into_iter()
+            .map(|job| (job.partition_id, job.priority))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            jobs,
+            vec![
+                (small.partition.id, Some(10)),
+                (no_files, Some(0)),
+                (big.partition.id, Some(200)),
+            ],
+        );
+
+        assert!(prioritizer.jobs(vec![]).await.is_empty());
+    }
+}
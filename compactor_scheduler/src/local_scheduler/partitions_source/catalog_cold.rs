@@ -0,0 +1,162 @@
+use std::{fmt::Display, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::PartitionId;
+use iox_catalog::interface::Catalog;
+use iox_time::TimeProvider;
+use observability_deps::tracing::info;
+use parking_lot::Mutex;
+
+use crate::PartitionsSource;
+
+#[derive(Debug)]
+/// Returns "cold" [`PartitionId`](data_types::PartitionId)s, i.e. partitions that did not receive a new Parquet file
+/// within `threshold` but still have files that are not compacted to
+/// [`CompactionLevel::Final`](data_types::CompactionLevel::Final).
+///
+/// At most `max_partitions` are returned per fetch. Consecutive fetches page through all cold partitions (ordered by
+/// ID) and wrap around once the end is reached, so that partitions that cannot make progress do not starve the
+/// others.
+pub(crate) struct CatalogColdPartitionsSource {
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+    threshold: Duration,
+    max_partitions: usize,
+
+    /// Last partition returned by the previous fetch, `None` if the next fetch starts from the beginning.
+    cursor: Mutex<Option<PartitionId>>,
+
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl CatalogColdPartitionsSource {
+    /// Create a new [`CatalogColdPartitionsSource`].
+    pub(crate) fn new(
+        backoff_config: BackoffConfig,
+        catalog: Arc<dyn Catalog>,
+        threshold: Duration,
+        max_partitions: usize,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            backoff_config,
+            catalog,
+            threshold,
+            max_partitions,
+            cursor: Mutex::new(None),
+            time_provider,
+        }
+    }
+}
+
+impl Display for CatalogColdPartitionsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "catalog_cold({:?})", self.threshold)
+    }
+}
+
+#[async_trait]
+impl PartitionsSource for CatalogColdPartitionsSource {
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let maximum_time = self.time_provider.now() - self.threshold;
+        let after = *self.cursor.lock();
+
+        info!(
+            maximum_time = maximum_time.to_string().as_str(),
+            after = after.map(|id| id.get()),
+            "Fetching cold partitions to consider for compaction",
+        );
+
+        let partitions = Backoff::new(&self.backoff_config)
+            .retry_all_errors("partitions_needing_cold_compact", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .partitions_needing_cold_compact(
+                        maximum_time.into(),
+                        after,
+                        self.max_partitions,
+                    )
+                    .await
+            })
+            .await
+            .expect("retry forever");
+
+        // continue after the last partition if there may be more, otherwise start over
+        *self.cursor.lock() = if partitions.len() < self.max_partitions {
+            None
+        } else {
+            partitions.last().copied()
+        };
+
+        partitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::CompactionLevel;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let table = ns.create_table("table").await;
+
+        let time_provider = catalog.time_provider();
+        let now = time_provider.now();
+        let two_hours_ago = time_provider.hours_ago(2);
+
+        let mut cold = vec![];
+        for key in ["a", "b", "c"] {
+            let partition = table.create_partition(key).await;
+            partition
+                .create_parquet_file_catalog_record(
+                    TestParquetFileBuilder::default()
+                        .with_compaction_level(CompactionLevel::FileNonOverlapped)
+                        .with_creation_time(two_hours_ago),
+                )
+                .await;
+            cold.push(partition.partition.id);
+        }
+
+        // recent writes
+        table
+            .create_partition("hot")
+            .await
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default().with_creation_time(now),
+            )
+            .await;
+
+        // fully compacted
+        table
+            .create_partition("final")
+            .await
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default()
+                    .with_compaction_level(CompactionLevel::Final)
+                    .with_creation_time(two_hours_ago),
+            )
+            .await;
+
+        let source = CatalogColdPartitionsSource::new(
+            Default::default(),
+            catalog.catalog(),
+            Duration::from_secs(60 * 60),
+            2,
+            time_provider,
+        );
+        assert_eq!(source.to_string(), "catalog_cold(3600s)");
+
+        // pages through all cold partitions and wraps around
+        assert_eq!(source.fetch().await, vec![cold[0], cold[1]]);
+        assert_eq!(source.fetch().await, vec![cold[2]]);
+        assert_eq!(source.fetch().await, vec![cold[0], cold[1]]);
+    }
+}
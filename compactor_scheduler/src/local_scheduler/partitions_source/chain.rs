@@ -0,0 +1,86 @@
+use std::{collections::HashSet, fmt::Display};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+use crate::PartitionsSource;
+
+/// An implementation of [`PartitionsSource`] that concatenates the results of multiple other
+/// [`PartitionsSource`]s.
+///
+/// The sources are queried in order, so partitions of earlier sources are scheduled first. Partitions that are
+/// returned by multiple sources are only kept at their first occurrence.
+#[derive(Debug)]
+pub(crate) struct ChainPartitionsSource {
+    inner: Vec<Box<dyn PartitionsSource>>,
+}
+
+impl ChainPartitionsSource {
+    /// Create a new [`ChainPartitionsSource`].
+    pub(crate) fn new(inner: Vec<Box<dyn PartitionsSource>>) -> Self {
+        Self { inner }
+    }
+}
+
+impl Display for ChainPartitionsSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "chain(")?;
+        for (i, source) in self.inner.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{source}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[async_trait]
+impl PartitionsSource for ChainPartitionsSource {
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let mut seen = HashSet::new();
+        let mut out = vec![];
+        for source in &self.inner {
+            out.extend(
+                source
+                    .fetch()
+                    .await
+                    .into_iter()
+                    .filter(|id| seen.insert(*id)),
+            );
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MockPartitionsSource;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let source = ChainPartitionsSource::new(vec![
+            Box::new(MockPartitionsSource::new(vec![])),
+            Box::new(MockPartitionsSource::new(vec![])),
+        ]);
+        assert_eq!(source.to_string(), "chain(mock, mock)");
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+
+        let source = ChainPartitionsSource::new(vec![]);
+        assert!(source.fetch().await.is_empty());
+
+        let source = ChainPartitionsSource::new(vec![
+            Box::new(MockPartitionsSource::new(vec![p_2, p_1])),
+            Box::new(MockPartitionsSource::new(vec![p_3, p_1])),
+        ]);
+        assert_eq!(source.fetch().await, vec![p_2, p_1, p_3]);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::VecDeque, fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+use parking_lot::Mutex;
+
+use crate::PartitionsSource;
+
+/// Partitions that an operator explicitly requested to be compacted.
+///
+/// This is a cheaply cloneable handle, all clones share the same queue.
+#[derive(Debug, Default, Clone)]
+pub struct ManualPartitionQueue {
+    queue: Arc<Mutex<VecDeque<PartitionId>>>,
+}
+
+impl ManualPartitionQueue {
+    /// Request compaction of the given partition.
+    ///
+    /// Returns `false` if the partition is already queued.
+    pub fn push(&self, partition_id: PartitionId) -> bool {
+        let mut queue = self.queue.lock();
+        if queue.contains(&partition_id) {
+            return false;
+        }
+        queue.push_back(partition_id);
+        true
+    }
+
+    /// Returns `true` if no partition is queued.
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().is_empty()
+    }
+
+    /// Remove and return all queued partitions, in the order they were requested.
+    pub fn take(&self) -> Vec<PartitionId> {
+        self.queue.lock().drain(..).collect()
+    }
+}
+
+/// An implementation of [`PartitionsSource`] that returns the partitions of a [`ManualPartitionQueue`] and only falls
+/// back to the inner [`PartitionsSource`] if the queue is empty.
+#[derive(Debug)]
+pub(crate) struct ManualPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    queue: ManualPartitionQueue,
+    inner: T,
+}
+
+impl<T> ManualPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    /// Create a new [`ManualPartitionsSourceWrapper`].
+    pub(crate) fn new(queue: ManualPartitionQueue, inner: T) -> Self {
+        Self { queue, inner }
+    }
+}
+
+impl<T> Display for ManualPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "manual({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for ManualPartitionsSourceWrapper<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let requested = self.queue.take();
+        if !requested.is_empty() {
+            return requested;
+        }
+
+        self.inner.fetch().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::MockPartitionsSource;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let source = ManualPartitionsSourceWrapper::new(
+            ManualPartitionQueue::default(),
+            MockPartitionsSource::new(vec![]),
+        );
+        assert_eq!(source.to_string(), "manual(mock)");
+    }
+
+    #[test]
+    fn test_queue() {
+        let queue = ManualPartitionQueue::default();
+        assert!(queue.is_empty());
+
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        assert!(queue.push(p_2));
+        assert!(queue.clone().push(p_1));
+        assert!(!queue.push(p_2));
+        assert!(!queue.is_empty());
+
+        assert_eq!(queue.take(), vec![p_2, p_1]);
+        assert!(queue.is_empty());
+        assert_eq!(queue.take(), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+        let queue = ManualPartitionQueue::default();
+        let source = ManualPartitionsSourceWrapper::new(
+            queue.clone(),
+            MockPartitionsSource::new(vec![p_1, p_2]),
+        );
+
+        assert_eq!(source.fetch().await, vec![p_1, p_2]);
+
+        // requested partitions replace the regular batch
+        queue.push(p_3);
+        assert_eq!(source.fetch().await, vec![p_3]);
+
+        // ...but only once
+        assert_eq!(source.fetch().await, vec![p_1, p_2]);
+    }
+}
@@ -4,5 +4,8 @@
 //! The filtering and fetching operations in these [`PartitionsSource`](crate::PartitionsSource) implementations
 //! are limited to the PartitionId and metadata only, and not dependent upon any file IO.
 pub(crate) mod catalog_all;
+pub(crate) mod catalog_cold;
 pub(crate) mod catalog_to_compact;
+pub(crate) mod chain;
 pub(crate) mod filter;
+pub(crate) mod manual;
@@ -42,6 +42,30 @@ impl Display for PartitionsSourceConfig {
     }
 }
 
+/// Config for additionally scheduling "cold" partitions.
+///
+/// Cold partitions did not receive writes within `threshold` but still have files that are not compacted to
+/// [`CompactionLevel::Final`](data_types::CompactionLevel::Final). They are scheduled after the partitions of the
+/// main [`PartitionsSourceConfig`], so that they eventually get fully compacted without delaying "hot" compaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColdPartitionsSourceConfig {
+    /// The amount of time without new Parquet files after which a partition is considered cold.
+    pub threshold: Duration,
+
+    /// Maximum number of cold partitions that are scheduled per fetch.
+    pub max_partitions: usize,
+}
+
+impl Display for ColdPartitionsSourceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            threshold,
+            max_partitions,
+        } = self;
+        write!(f, "cold({threshold:?},max_partitions={max_partitions})")
+    }
+}
+
 impl Default for PartitionsSourceConfig {
     fn default() -> Self {
         Self::CatalogRecentWrites {
@@ -1,13 +1,16 @@
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
+    time::Duration,
 };
 
 use async_trait::async_trait;
 use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
 use uuid::Uuid;
 
-use crate::{CommitWrapper, ErrorKind, LocalSchedulerConfig, PartitionsSourceConfig};
+use crate::{
+    CommitWrapper, ErrorKind, LocalSchedulerConfig, ManualPartitionQueue, PartitionsSourceConfig,
+};
 
 /// Scheduler configuration.
 #[derive(Debug, Clone)]
@@ -25,8 +28,29 @@ impl SchedulerConfig {
             shard_config: None,
             partitions_source_config: PartitionsSourceConfig::default(),
             commit_wrapper: Some(commit_wrapper),
+            cold_partitions_source_config: None,
+            manual_partition_queue: ManualPartitionQueue::default(),
+            prioritize_l0_backlog: false,
+            job_lease_duration: None,
         })
     }
+
+    /// Queue for partitions that operators requested to be compacted, if supported by the scheduler.
+    pub fn manual_partition_queue(&self) -> Option<&ManualPartitionQueue> {
+        match self {
+            Self::Local(config) => Some(&config.manual_partition_queue),
+        }
+    }
+
+    /// Interval at which compactors must send [heartbeats](CompactionJobStatusVariant::Heartbeat)
+    /// for their in-progress jobs, or `None` if the scheduler does not lease jobs.
+    ///
+    /// This is a third of the lease duration, so a lease survives a missed heartbeat.
+    pub fn job_heartbeat_interval(&self) -> Option<Duration> {
+        match self {
+            Self::Local(config) => config.job_lease_duration.map(|d| d / 3),
+        }
+    }
 }
 
 impl Default for SchedulerConfig {
@@ -42,6 +66,10 @@ impl std::fmt::Display for SchedulerConfig {
                 commit_wrapper,
                 shard_config,
                 partitions_source_config: _,
+                cold_partitions_source_config: _,
+                manual_partition_queue: _,
+                prioritize_l0_backlog: _,
+                job_lease_duration: _,
             }) => match (&shard_config, commit_wrapper) {
                 (None, None) => write!(f, "local_compaction_scheduler_cfg"),
                 (Some(shard_config), None) => {
@@ -62,12 +90,15 @@ impl std::fmt::Display for SchedulerConfig {
 /// Job assignment for a given partition.
 #[derive(Debug, Clone, PartialEq)]
 pub struct CompactionJob {
-    #[allow(dead_code)]
     /// Unique identifier for this job.
     /// Should not be the same as the partition id.
     uuid: Uuid,
     /// Leased partition.
     pub partition_id: PartitionId,
+    /// Priority score, jobs with higher scores should be compacted first.
+    ///
+    /// This is the estimated number of bytes of L0 backlog. `None` if the scheduler does not score jobs.
+    pub priority: Option<u64>,
 }
 
 impl CompactionJob {
@@ -76,6 +107,20 @@ impl CompactionJob {
         Self {
             uuid: Uuid::new_v4(),
             partition_id,
+            priority: None,
+        }
+    }
+
+    /// Unique identifier for this job.
+    pub fn uuid(&self) -> Uuid {
+        self.uuid
+    }
+
+    /// Set priority score.
+    pub fn with_priority(self, priority: u64) -> Self {
+        Self {
+            priority: Some(priority),
+            ..self
         }
     }
 }
@@ -123,6 +168,10 @@ pub enum CompactionJobStatusVariant {
     ///
     /// These errors are not fatal, as some of the compaction job branches may succeed.
     Error(ErrorKind),
+    /// Compaction job is still in progress.
+    ///
+    /// Only renews the lease of the job, see [`SchedulerConfig::job_heartbeat_interval`].
+    Heartbeat,
 }
 
 /// Status ([`CompactionJobStatusVariant`]) associated with a [`CompactionJob`].
@@ -137,12 +186,18 @@ pub struct CompactionJobStatus {
 /// Response to a [`CompactionJobStatus`].
 #[derive(Debug)]
 pub enum CompactionJobStatusResponse {
-    /// Acknowledge receipt of a [`CompactionJobStatusVariant::Error`] request.
+    /// Acknowledge receipt of a [`CompactionJobStatusVariant::Error`] or
+    /// [`CompactionJobStatusVariant::Heartbeat`] request.
     Ack,
     /// IDs of the created files that were processed.
     ///
     /// This is the response to a [`CompactionJobStatusVariant::Update`] request.
     CreatedParquetFiles(Vec<ParquetFileId>),
+    /// The job was cancelled, e.g. because its lease expired.
+    ///
+    /// This is the response to a [`CompactionJobStatusVariant::Heartbeat`] request. The compactor
+    /// must stop working on the job and end it.
+    Cancelled,
 }
 
 /// Reason for skipping a partition.
@@ -180,6 +235,11 @@ pub trait Scheduler: Send + Sync + Debug + Display {
     /// Scheduler returns a [`CompactionJobStatusResponse`].
     ///
     /// Compactor can send multiple [`CompactionJobStatus`] requests for the same job.
+    ///
+    /// If the scheduler leases jobs, every request renews the lease of the job. A job whose lease
+    /// expires is cancelled, which is signalled by answering its next heartbeat with
+    /// [`CompactionJobStatusResponse::Cancelled`]. Its partition is handed out again once the job
+    /// ended.
     async fn update_job_status(
         &self,
         job_status: CompactionJobStatus,
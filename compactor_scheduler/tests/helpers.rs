@@ -84,6 +84,22 @@ pub async fn can_send_error(scheduler: Arc<dyn Scheduler>, job: CompactionJob) {
     );
 }
 
+pub async fn can_send_heartbeat(scheduler: Arc<dyn Scheduler>, job: CompactionJob) {
+    let res = scheduler
+        .update_job_status(CompactionJobStatus {
+            job,
+            status: CompactionJobStatusVariant::Heartbeat,
+        })
+        .await;
+
+    assert_matches!(
+        res,
+        Ok(CompactionJobStatusResponse::Ack),
+        "expected heartbeat to be accepted, got {:?}",
+        res
+    );
+}
+
 pub async fn can_do_complete(scheduler: Arc<dyn Scheduler>, job: CompactionJob) {
     let res = scheduler
         .end_job(CompactionJobEnd {
@@ -0,0 +1,137 @@
+use std::{sync::Arc, time::Duration};
+
+use assert_matches::assert_matches;
+use compactor_scheduler::{
+    CompactionJobStatus, CompactionJobStatusResponse, CompactionJobStatusVariant,
+    LocalSchedulerConfig, PartitionsSourceConfig,
+};
+
+use super::{super::helpers, TestLocalScheduler};
+
+const LEASE: Duration = Duration::from_secs(60);
+
+async fn test_scheduler_with_leases() -> TestLocalScheduler {
+    TestLocalScheduler::builder_with_config(LocalSchedulerConfig {
+        // unlike recent writes, this ignores the (mock) time that passes while jobs run
+        partitions_source_config: PartitionsSourceConfig::CatalogAll,
+        job_lease_duration: Some(LEASE),
+        ..Default::default()
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_expired_lease_cancels_slow_job() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = test_scheduler_with_leases().await;
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+
+    // lease is still valid
+    test_scheduler.catalog.mock_time_provider().inc(LEASE / 2);
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    // TEST: the job is slow and misses its heartbeats, the lease expires mid-run. The job may
+    // still be running, so its partition is NOT handed out again.
+    test_scheduler.catalog.mock_time_provider().inc(LEASE);
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    // the next heartbeat of the job tells it to stop
+    let res = scheduler
+        .update_job_status(CompactionJobStatus {
+            job: jobs[0].clone(),
+            status: CompactionJobStatusVariant::Heartbeat,
+        })
+        .await;
+    assert_matches!(
+        res,
+        Ok(CompactionJobStatusResponse::Cancelled),
+        "expected heartbeat of expired job to be cancelled, got {:?}",
+        res
+    );
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    // a commit that was already underway is still accepted, no other job works on the partition
+    let (existing_1, _) = test_scheduler.get_seeded_files();
+    helpers::can_do_upgrade_commit(Arc::clone(&scheduler), jobs[0].clone(), existing_1).await;
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    // once the cancelled job ended, the partition is handed out again
+    helpers::can_do_complete(Arc::clone(&scheduler), jobs[0].clone()).await;
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+
+    // the new job is leased
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+    helpers::can_send_heartbeat(Arc::clone(&scheduler), jobs[0].clone()).await;
+    helpers::can_do_complete(Arc::clone(&scheduler), jobs[0].clone()).await;
+}
+
+#[tokio::test]
+async fn test_heartbeat_renews_lease() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = test_scheduler_with_leases().await;
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+
+    for _ in 0..3 {
+        test_scheduler.catalog.mock_time_provider().inc(LEASE / 2);
+        helpers::can_send_heartbeat(Arc::clone(&scheduler), jobs[0].clone()).await;
+    }
+
+    // TEST: lease was renewed, partition is not handed out again
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    // commits also renew the lease
+    let (existing_1, _) = test_scheduler.get_seeded_files();
+    test_scheduler.catalog.mock_time_provider().inc(LEASE / 2);
+    helpers::can_do_upgrade_commit(Arc::clone(&scheduler), jobs[0].clone(), existing_1).await;
+    test_scheduler.catalog.mock_time_provider().inc(LEASE / 2);
+    helpers::assert_all_partitions_leased(Arc::clone(&scheduler)).await;
+
+    helpers::can_do_complete(Arc::clone(&scheduler), jobs[0].clone()).await;
+}
+
+#[tokio::test]
+async fn test_heartbeat_of_ended_job_is_rejected() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = test_scheduler_with_leases().await;
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+
+    helpers::can_do_complete(Arc::clone(&scheduler), jobs[0].clone()).await;
+
+    // TEST: job is not leased anymore
+    let res = scheduler
+        .update_job_status(CompactionJobStatus {
+            job: jobs[0].clone(),
+            status: CompactionJobStatusVariant::Heartbeat,
+        })
+        .await;
+    assert_matches!(
+        res,
+        Err(e) if e.to_string().contains("No lease for partition"),
+        "expected heartbeat of ended job to be rejected, got {:?}", res
+    );
+}
+
+#[tokio::test]
+async fn test_heartbeat_without_leases() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = TestLocalScheduler::builder().await;
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let jobs = scheduler.get_jobs().await;
+    test_scheduler.assert_matches_seeded_hot_partition(&jobs);
+
+    // TEST: heartbeats are accepted, even though jobs are not leased
+    helpers::can_send_heartbeat(Arc::clone(&scheduler), jobs[0].clone()).await;
+    helpers::can_do_complete(Arc::clone(&scheduler), jobs[0].clone()).await;
+    helpers::can_send_heartbeat(Arc::clone(&scheduler), jobs[0].clone()).await;
+}
@@ -9,6 +9,7 @@ use iox_tests::{ParquetFileBuilder, TestCatalog, TestParquetFileBuilder, TestPar
 
 mod end_job;
 mod get_jobs;
+mod job_leases;
 mod update_job_status;
 
 /// Test local_scheduler, with seeded files, used in these integration tests
@@ -22,6 +23,10 @@ pub struct TestLocalScheduler {
 
 impl TestLocalScheduler {
     pub async fn builder() -> Self {
+        Self::builder_with_config(LocalSchedulerConfig::default()).await
+    }
+
+    pub async fn builder_with_config(config: LocalSchedulerConfig) -> Self {
         // create a catalog with a table with one partition
         let catalog = TestCatalog::new();
         let ns = catalog.create_namespace_with_retention("ns", None).await;
@@ -41,10 +46,10 @@ impl TestLocalScheduler {
             partition.create_parquet_file(file_builder).await.into(),
         );
 
-        // local scheduler in default config
+        // local scheduler in given (usually default) config
         // created partitions are "hot" and should be returned with `get_jobs()`
         let scheduler = create_scheduler(
-            SchedulerConfig::Local(LocalSchedulerConfig::default()),
+            SchedulerConfig::Local(config),
             catalog.catalog(),
             Arc::clone(&catalog.time_provider()),
             Arc::new(metric::Registry::default()),
@@ -28,6 +28,46 @@ async fn test_has_two_commit_types() {
     .await;
 }
 
+#[tokio::test]
+async fn test_commits_are_recorded_in_history() {
+    test_helpers::maybe_start_logging();
+
+    let test_scheduler = TestLocalScheduler::builder().await;
+    let scheduler = Arc::clone(&test_scheduler.scheduler);
+    let jobs = scheduler.get_jobs().await;
+    let (existing_1, existing_2) = test_scheduler.get_seeded_files();
+
+    helpers::can_do_upgrade_commit(Arc::clone(&scheduler), jobs[0].clone(), existing_1).await;
+    helpers::can_do_replacement_commit(
+        scheduler,
+        jobs[0].clone(),
+        vec![existing_2.clone()],
+        vec![test_scheduler.create_params_for_new_parquet_file().await],
+    )
+    .await;
+
+    let history = test_scheduler
+        .catalog
+        .catalog()
+        .repositories()
+        .await
+        .partitions()
+        .list_compaction_history(test_scheduler.get_partition_id())
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 2);
+    assert!(history.iter().all(|h| h.job_id == jobs[0].uuid()));
+    assert_eq!(
+        (history[0].num_input_files, history[0].num_upgraded_files),
+        (0, 1)
+    );
+    assert_eq!(
+        (history[1].num_input_files, history[1].num_output_files),
+        (1, 1)
+    );
+    assert_eq!(history[1].input_bytes, existing_2.file_size_bytes);
+}
+
 #[tokio::test]
 async fn test_no_empty_commits_permitted() {
     test_helpers::maybe_start_logging();
@@ -38,8 +38,8 @@ use crate::{
 use async_trait::async_trait;
 use backoff::BackoffConfig;
 use compactor::{
-    compact, config::Config, hardcoded_components, Components, PanicDataFusionPlanner,
-    PartitionInfo,
+    compact, config::Config, hardcoded_components, Components, MemoryBudget,
+    PanicDataFusionPlanner, PartitionInfo,
 };
 use compactor_scheduler::SchedulerConfig;
 use data_types::{ColumnType, CompactionLevel, ParquetFile, TableId};
@@ -138,12 +138,16 @@ impl TestSetupBuilder<false> {
             backoff_config: BackoffConfig::default(),
             partition_concurrency: NonZeroUsize::new(1).unwrap(),
             df_concurrency: NonZeroUsize::new(1).unwrap(),
+            memory_budget_bytes: None,
             partition_scratchpad_concurrency: NonZeroUsize::new(1).unwrap(),
             max_desired_file_size_bytes: MAX_DESIRE_FILE_SIZE,
+            min_desired_file_size_bytes: None,
             percentage_max_file_size: PERCENTAGE_MAX_FILE_SIZE,
             split_percentage: SPLIT_PERCENTAGE,
             partition_timeout: Duration::from_secs(3_600),
             shadow_mode: false,
+            dry_run: false,
+            verify_output: false,
             enable_scratchpad: true,
             ignore_partition_skip_marker: false,
             min_num_l1_files_to_compact: MIN_NUM_L1_FILES_TO_COMPACT,
@@ -154,6 +158,8 @@ impl TestSetupBuilder<false> {
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
             max_partition_fetch_queries_per_second: None,
+            time_slice_min_num_l0_files: None,
+            max_consecutive_failures: NonZeroUsize::new(1).unwrap(),
         };
 
         let bytes_written = Arc::new(AtomicUsize::new(0));
@@ -513,6 +519,12 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
         self
     }
 
+    /// Use dry run mode
+    pub fn with_dry_run(mut self) -> Self {
+        self.config.dry_run = true;
+        self
+    }
+
     /// set min_num_l1_files_to_compact
     pub fn with_min_num_l1_files_to_compact(mut self, min_num_l1_files_to_compact: usize) -> Self {
         self.config.min_num_l1_files_to_compact = min_num_l1_files_to_compact;
@@ -525,6 +537,24 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
         self
     }
 
+    /// Set memory_budget_bytes
+    pub fn with_memory_budget_bytes(mut self, memory_budget_bytes: usize) -> Self {
+        self.config.memory_budget_bytes = Some(memory_budget_bytes);
+        self
+    }
+
+    /// Re-read and check the created files after every commit
+    pub fn with_verify_output(mut self) -> Self {
+        self.config.verify_output = true;
+        self
+    }
+
+    /// Set time_slice_min_num_l0_files
+    pub fn with_time_slice_min_num_l0_files(mut self, time_slice_min_num_l0_files: usize) -> Self {
+        self.config.time_slice_min_num_l0_files = Some(time_slice_min_num_l0_files);
+        self
+    }
+
     /// Set option to suppress output of compaction runs;
     pub fn with_suppress_run_output(mut self) -> Self {
         self.suppress_run_output = true;
@@ -577,6 +607,7 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
             table_schema: Arc::new(self.table.catalog_schema().await),
             sort_key: self.partition.partition.sort_key(),
             partition_key: self.partition.partition.partition_key.clone(),
+            target_file_size_bytes: None,
         });
 
         TestSetup {
@@ -682,6 +713,10 @@ impl TestSetup {
         let df_semaphore = Arc::new(
             Arc::new(AsyncSemaphoreMetrics::new(&config.metric_registry, [])).new_semaphore(10),
         );
+        let memory_budget = Arc::new(MemoryBudget::new(
+            config.memory_budget_bytes,
+            &config.metric_registry,
+        ));
         let trace_collector = config.trace_collector.clone();
 
         // register scratchpad store
@@ -702,6 +737,7 @@ impl TestSetup {
             NonZeroUsize::new(10).unwrap(),
             config.partition_timeout,
             df_semaphore,
+            memory_budget,
             &components,
         )
         .await;
@@ -213,6 +213,7 @@ impl SimulatedFile {
             created_at: Timestamp::new(1),
             column_set,
             max_l0_created_at: max_l0_created_at.into(),
+            tag_ranges: None,
         }
     }
 }
@@ -160,6 +160,22 @@ impl Column {
     }
 }
 
+/// Optional, descriptive metadata of a column.
+///
+/// This is not used by IOx itself but stored for downstream tools, e.g. to render the unit of a
+/// column without a separate registry.
+#[derive(Debug, Clone, sqlx::FromRow, Eq, PartialEq)]
+pub struct ColumnMetadata {
+    /// the column id
+    pub column_id: ColumnId,
+    /// human-readable description of the column
+    pub description: Option<String>,
+    /// unit of the column values, e.g. `bytes` or `ms`
+    pub unit: Option<String>,
+    /// semantic type of the column values, e.g. `counter` or `gauge`
+    pub semantic_type: Option<String>,
+}
+
 /// The column id and its type for a column
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct ColumnSchema {
@@ -368,6 +384,119 @@ impl Deref for ColumnSet {
     }
 }
 
+/// The smallest and largest (non-null) values of a tag column within a parquet file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRange {
+    /// The smallest value.
+    pub min: String,
+    /// The largest value.
+    pub max: String,
+}
+
+/// The [`TagRange`] of each tag column within a parquet file that has any non-null values.
+///
+/// These allow the querier to prune files based on predicates on tag columns without fetching the
+/// parquet file metadata.
+///
+/// Stored in the catalog as a JSON array of `[column ID, min, max]` triples.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagRanges(BTreeMap<ColumnId, TagRange>);
+
+impl TagRanges {
+    /// Create new tag ranges from the given columns and their ranges.
+    pub fn new<I>(ranges: I) -> Self
+    where
+        I: IntoIterator<Item = (ColumnId, TagRange)>,
+    {
+        Self(ranges.into_iter().collect())
+    }
+
+    /// Return the range of the given column, if known.
+    pub fn get(&self, column_id: ColumnId) -> Option<&TagRange> {
+        self.0.get(&column_id)
+    }
+
+    /// Iterate over the columns and their ranges, ordered by column ID.
+    pub fn iter(&self) -> impl Iterator<Item = (ColumnId, &TagRange)> {
+        self.0.iter().map(|(id, range)| (*id, range))
+    }
+
+    /// Return the number of columns with a known range.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if no column has a known range.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Estimate the memory consumption of this object and its contents
+    pub fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self
+                .0
+                .values()
+                .map(|range| {
+                    std::mem::size_of::<ColumnId>()
+                        + std::mem::size_of_val(range)
+                        + range.min.capacity()
+                        + range.max.capacity()
+                })
+                .sum::<usize>()
+    }
+}
+
+impl<DB> sqlx::Type<DB> for TagRanges
+where
+    sqlx::types::Json<Self>: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <sqlx::types::Json<Self> as sqlx::Type<DB>>::type_info()
+    }
+}
+
+impl<'q, DB> sqlx::Encode<'q, DB> for TagRanges
+where
+    DB: sqlx::Database,
+    for<'b> sqlx::types::Json<Vec<(i64, &'b str, &'b str)>>: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        let encoded = self
+            .0
+            .iter()
+            .map(|(id, range)| (id.get(), range.min.as_str(), range.max.as_str()))
+            .collect::<Vec<_>>();
+
+        <sqlx::types::Json<Vec<(i64, &str, &str)>> as sqlx::Encode<'_, DB>>::encode_by_ref(
+            &sqlx::types::Json(encoded),
+            buf,
+        )
+    }
+}
+
+impl<'q, DB> sqlx::Decode<'q, DB> for TagRanges
+where
+    DB: sqlx::Database,
+    sqlx::types::Json<Vec<(i64, String, String)>>: sqlx::Decode<'q, DB>,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'q>>::ValueRef,
+    ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+        let encoded =
+            <sqlx::types::Json<Vec<(i64, String, String)>> as sqlx::Decode<'_, DB>>::decode(value)?
+                .0;
+
+        Ok(Self::new(encoded.into_iter().map(|(id, min, max)| {
+            (ColumnId::new(id), TagRange { min, max })
+        })))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
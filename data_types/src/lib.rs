@@ -290,6 +290,14 @@ pub struct Namespace {
     /// The partition template to use for new tables in this namespace either created implicitly or
     /// created without specifying a partition template.
     pub partition_template: NamespacePartitionTemplateOverride,
+    /// When set, the compactor does not compact any partition of this namespace.
+    pub compaction_paused: bool,
+    /// The maximum number of lines per second the routers accept for this namespace. None
+    /// represents no limit.
+    pub max_write_lines_per_second: Option<i64>,
+    /// The maximum number of bytes per second the routers accept for this namespace. None
+    /// represents no limit.
+    pub max_write_bytes_per_second: Option<i64>,
 }
 
 use generated_types::influxdata::iox::namespace::v1 as namespace_proto;
@@ -383,6 +391,20 @@ pub struct Table {
     pub name: String,
     /// The partition template to use for writes in this table.
     pub partition_template: TablePartitionTemplateOverride,
+    /// The retention period of this table in nanoseconds, overriding the retention period of the
+    /// namespace. `None` means the retention period of the namespace applies.
+    pub retention_period_ns: Option<i64>,
+}
+
+impl Table {
+    /// The retention period (in nanoseconds) that applies to this table, given the retention
+    /// period of its namespace. `None` represents an infinite retention period.
+    pub fn effective_retention_period_ns(
+        &self,
+        namespace_retention_period_ns: Option<i64>,
+    ) -> Option<i64> {
+        self.retention_period_ns.or(namespace_retention_period_ns)
+    }
 }
 
 /// Column definitions for a table
@@ -394,6 +416,9 @@ pub struct TableSchema {
     /// The partition template to use for writes in this table.
     pub partition_template: TablePartitionTemplateOverride,
 
+    /// The retention period override of this table, see [`Table::retention_period_ns`].
+    pub retention_period_ns: Option<i64>,
+
     /// the table's columns by their name
     pub columns: ColumnsByName,
 }
@@ -404,6 +429,7 @@ impl TableSchema {
         Self {
             id: table.id,
             partition_template: table.partition_template.clone(),
+            retention_period_ns: table.retention_period_ns,
             columns: ColumnsByName::new([]),
         }
     }
@@ -514,6 +540,164 @@ impl From<SkippedCompaction> for compactor_proto::SkippedCompaction {
             num_files,
             limit_num_files,
             limit_num_files_first_in_partition: Some(limit_num_files_first_in_partition),
+            error: None,
+        }
+    }
+}
+
+/// Details of the error that caused compaction of a partition to be skipped.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::FromRow)]
+pub struct SkippedCompactionError {
+    /// the partition
+    pub partition_id: PartitionId,
+    /// the kind of error, as classified by the compactor
+    pub error_kind: String,
+    /// num files in the partition when compaction was skipped
+    pub num_files: i64,
+    /// total size in bytes of the files in the partition when compaction was skipped
+    pub total_bytes: i64,
+    /// num consecutive failures that led to compaction being skipped
+    pub num_failures: i64,
+    /// the message of the last error
+    pub last_error: String,
+    /// when compaction was skipped
+    pub skipped_at: Timestamp,
+}
+
+impl From<SkippedCompactionError> for compactor_proto::SkippedCompactionError {
+    fn from(skipped_compaction_error: SkippedCompactionError) -> Self {
+        let SkippedCompactionError {
+            partition_id: _,
+            error_kind,
+            num_files,
+            total_bytes,
+            num_failures,
+            last_error,
+            skipped_at: _,
+        } = skipped_compaction_error;
+
+        Self {
+            error_kind,
+            num_files,
+            total_bytes,
+            num_failures,
+            last_error,
+        }
+    }
+}
+
+/// Data recorded by the compaction scheduler for every commit of a compaction job.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactionHistoryParams {
+    /// the partition
+    pub partition_id: PartitionId,
+    /// the compaction job that made the commit
+    pub job_id: Uuid,
+    /// num files that were compacted, i.e. deleted by the commit
+    pub num_input_files: i64,
+    /// total size in bytes of the compacted files
+    pub input_bytes: i64,
+    /// num files that were upgraded to the target level without being rewritten
+    pub num_upgraded_files: i64,
+    /// num files created by the commit
+    pub num_output_files: i64,
+    /// total size in bytes of the created files
+    pub output_bytes: i64,
+    /// level of the created and upgraded files
+    pub target_level: CompactionLevel,
+    /// time in milliseconds the job took to produce the commit, i.e. since the job started or
+    /// since its previous commit
+    pub duration_ms: i64,
+    /// when the commit was made
+    pub committed_at: Timestamp,
+}
+
+/// A commit of a compaction job that has been recorded in the catalog.
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct CompactionHistory {
+    /// the id of the record, records of a partition are ordered by id
+    pub id: i64,
+    /// the partition
+    pub partition_id: PartitionId,
+    /// the compaction job that made the commit
+    pub job_id: Uuid,
+    /// num files that were compacted, i.e. deleted by the commit
+    pub num_input_files: i64,
+    /// total size in bytes of the compacted files
+    pub input_bytes: i64,
+    /// num files that were upgraded to the target level without being rewritten
+    pub num_upgraded_files: i64,
+    /// num files created by the commit
+    pub num_output_files: i64,
+    /// total size in bytes of the created files
+    pub output_bytes: i64,
+    /// level of the created and upgraded files
+    pub target_level: CompactionLevel,
+    /// time in milliseconds the job took to produce the commit
+    pub duration_ms: i64,
+    /// when the commit was made
+    pub committed_at: Timestamp,
+}
+
+impl CompactionHistory {
+    /// Create a record from the recorded params and the id assigned by the catalog.
+    pub fn from_params(params: CompactionHistoryParams, id: i64) -> Self {
+        let CompactionHistoryParams {
+            partition_id,
+            job_id,
+            num_input_files,
+            input_bytes,
+            num_upgraded_files,
+            num_output_files,
+            output_bytes,
+            target_level,
+            duration_ms,
+            committed_at,
+        } = params;
+
+        Self {
+            id,
+            partition_id,
+            job_id,
+            num_input_files,
+            input_bytes,
+            num_upgraded_files,
+            num_output_files,
+            output_bytes,
+            target_level,
+            duration_ms,
+            committed_at,
+        }
+    }
+}
+
+impl From<CompactionHistory> for compactor_proto::CompactionHistory {
+    fn from(history: CompactionHistory) -> Self {
+        let CompactionHistory {
+            id: _,
+            partition_id,
+            job_id,
+            num_input_files,
+            input_bytes,
+            num_upgraded_files,
+            num_output_files,
+            output_bytes,
+            target_level,
+            duration_ms,
+            committed_at,
+        } = history;
+
+        Self {
+            partition_id: partition_id.get(),
+            job_id: job_id.to_string(),
+            num_input_files,
+            input_bytes,
+            num_upgraded_files,
+            num_output_files,
+            output_bytes,
+            target_level: target_level as i32,
+            duration_ms,
+            committed_at: committed_at.get(),
         }
     }
 }
@@ -576,6 +760,10 @@ pub struct ParquetFile {
     pub column_set: ColumnSet,
     /// the max of created_at of all L0 files needed for file/chunk ordering for deduplication
     pub max_l0_created_at: Timestamp,
+    /// The min/max values of the tag columns within this file, if known.
+    ///
+    /// This is [`None`] for files created before the ranges were recorded.
+    pub tag_ranges: Option<TagRanges>,
 }
 
 impl ParquetFile {
@@ -599,6 +787,7 @@ impl ParquetFile {
             created_at: params.created_at,
             column_set: params.column_set,
             max_l0_created_at: params.max_l0_created_at,
+            tag_ranges: params.tag_ranges,
         }
     }
 
@@ -618,6 +807,11 @@ impl ParquetFile {
                 .unwrap_or_default()
             + self.column_set.size()
             - std::mem::size_of_val(&self.column_set)
+            + self
+                .tag_ranges
+                .as_ref()
+                .map(|r| r.size() - std::mem::size_of_val(r))
+                .unwrap_or_default()
     }
 
     /// Return true if the time range overlaps with the time range of the given file
@@ -631,6 +825,28 @@ impl ParquetFile {
     }
 }
 
+use generated_types::influxdata::iox::catalog::v1 as catalog_proto;
+impl From<ParquetFile> for catalog_proto::ParquetFile {
+    fn from(p: ParquetFile) -> Self {
+        Self {
+            id: p.id.get(),
+            namespace_id: p.namespace_id.get(),
+            table_id: p.table_id.get(),
+            partition_id: p.partition_id.get(),
+            object_store_id: p.object_store_id.to_string(),
+            min_time: p.min_time.get(),
+            max_time: p.max_time.get(),
+            to_delete: p.to_delete.map(|t| t.get()).unwrap_or(0),
+            file_size_bytes: p.file_size_bytes,
+            row_count: p.row_count,
+            compaction_level: p.compaction_level as i32,
+            created_at: p.created_at.get(),
+            column_set: p.column_set.iter().map(|id| id.get()).collect(),
+            max_l0_created_at: p.max_l0_created_at.get(),
+        }
+    }
+}
+
 /// Data for a parquet file to be inserted into the catalog.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ParquetFileParams {
@@ -660,6 +876,8 @@ pub struct ParquetFileParams {
     pub column_set: ColumnSet,
     /// the max of created_at of all L0 files
     pub max_l0_created_at: Timestamp,
+    /// the min/max values of the tag columns in this file, if known
+    pub tag_ranges: Option<TagRanges>,
 }
 
 impl ParquetFileParams {
@@ -686,6 +904,7 @@ impl From<ParquetFile> for ParquetFileParams {
             created_at: value.created_at,
             column_set: value.column_set,
             max_l0_created_at: value.max_l0_created_at,
+            tag_ranges: value.tag_ranges,
         }
     }
 }
@@ -2558,11 +2777,13 @@ mod tests {
         let schema1 = TableSchema {
             id: TableId::new(1),
             partition_template: Default::default(),
+            retention_period_ns: None,
             columns: ColumnsByName::new([]),
         };
         let schema2 = TableSchema {
             id: TableId::new(2),
             partition_template: Default::default(),
+            retention_period_ns: None,
             columns: ColumnsByName::new(
                 [Column {
                     id: ColumnId::new(1),
@@ -2594,6 +2815,7 @@ mod tests {
                     id: TableId::new(1),
                     columns: ColumnsByName::new([]),
                     partition_template: Default::default(),
+                    retention_period_ns: None,
                 },
             )]),
             max_columns_per_table: 4,
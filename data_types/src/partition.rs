@@ -2,6 +2,7 @@
 
 use super::{TableId, Timestamp};
 
+use generated_types::influxdata::iox::catalog::v1 as catalog_proto;
 use schema::sort::SortKey;
 use sha2::Digest;
 use std::{fmt::Display, sync::Arc};
@@ -421,6 +422,17 @@ impl Partition {
     }
 }
 
+impl From<Partition> for catalog_proto::Partition {
+    fn from(p: Partition) -> Self {
+        Self {
+            id: p.id.get(),
+            key: p.partition_key.to_string(),
+            table_id: p.table_id.get(),
+            array_sort_key: p.sort_key,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
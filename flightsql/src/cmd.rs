@@ -2,6 +2,10 @@
 
 use std::fmt::Display;
 
+use arrow::{
+    ipc::{reader::StreamReader, writer::StreamWriter},
+    record_batch::RecordBatch,
+};
 use arrow_flight::sql::{
     ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest, Any,
     CommandGetCatalogs, CommandGetCrossReference, CommandGetDbSchemas, CommandGetExportedKeys,
@@ -10,7 +14,7 @@ use arrow_flight::sql::{
 };
 use bytes::Bytes;
 use prost::Message;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 
 use crate::error::*;
 
@@ -21,11 +25,27 @@ use crate::error::*;
 pub struct PreparedStatementHandle {
     /// The raw SQL query text
     query: String,
+
+    /// Values for the query parameters, if they were bound via `DoPut`.
+    ///
+    /// This is a record batch with a single row and one column per parameter.
+    params: Option<RecordBatch>,
 }
 
 impl PreparedStatementHandle {
     pub fn new(query: String) -> Self {
-        Self { query }
+        Self {
+            query,
+            params: None,
+        }
+    }
+
+    /// Bind parameter values to this prepared statement, replacing any previously bound values.
+    pub fn with_params(self, params: RecordBatch) -> Self {
+        Self {
+            params: Some(params),
+            ..self
+        }
     }
 
     /// return the query
@@ -33,32 +53,98 @@ impl PreparedStatementHandle {
         self.query.as_ref()
     }
 
+    /// return the bound parameter values, if any
+    pub fn params(&self) -> Option<&RecordBatch> {
+        self.params.as_ref()
+    }
+
     fn try_decode(handle: Bytes) -> Result<Self> {
-        // Note: in IOx  handles are the entire decoded query
-        // It will likely need to get more sophisticated as part of
-        // https://github.com/influxdata/influxdb_iox/issues/6699
-        let query = String::from_utf8(handle.to_vec()).context(InvalidHandleSnafu)?;
-        Ok(Self { query })
+        let PreparedStatementHandleProto { query, params } =
+            Message::decode(handle).context(InvalidHandleSnafu)?;
+
+        let params = if params.is_empty() {
+            None
+        } else {
+            let mut reader =
+                StreamReader::try_new(params.as_ref(), None).context(InvalidParamsSnafu)?;
+            let batch = reader
+                .next()
+                .transpose()
+                .context(InvalidParamsSnafu)?
+                .context(MissingParamsSnafu)?;
+            Some(batch)
+        };
+
+        Ok(Self { query, params })
     }
 
-    fn encode(self) -> Bytes {
-        Bytes::from(self.query.into_bytes())
+    fn try_encode(self) -> Result<Bytes> {
+        let params = match self.params {
+            Some(batch) => {
+                let mut writer = StreamWriter::try_new(vec![], &batch.schema())?;
+                writer.write(&batch)?;
+                writer.into_inner()?.into()
+            }
+            None => Bytes::new(),
+        };
+
+        let proto = PreparedStatementHandleProto {
+            query: self.query,
+            params,
+        };
+        Ok(proto.encode_to_vec().into())
     }
 }
 
 impl Display for PreparedStatementHandle {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Pepared({})", self.query)
+        match &self.params {
+            Some(params) => write!(
+                f,
+                "Pepared({}, params={})",
+                self.query,
+                params.num_columns()
+            ),
+            None => write!(f, "Pepared({})", self.query),
+        }
     }
 }
 
 /// Encode a PreparedStatementHandle as Bytes
-impl From<PreparedStatementHandle> for Bytes {
-    fn from(value: PreparedStatementHandle) -> Self {
-        Self::from(value.query.into_bytes())
+impl TryFrom<PreparedStatementHandle> for Bytes {
+    type Error = Error;
+
+    fn try_from(value: PreparedStatementHandle) -> Result<Self> {
+        value.try_encode()
     }
 }
 
+/// Result of binding parameters to a prepared statement via `DoPut`, sent back as the
+/// `app_metadata` of the `PutResult`.
+///
+/// Since IOx does not keep any prepared statement state on the server, binding parameters
+/// results in a new handle that the client must use for subsequent requests.
+///
+/// This mirrors the `DoPutPreparedStatementResult` message of the FlightSQL protocol.
+#[derive(Clone, PartialEq, Message)]
+pub struct DoPutPreparedStatementResult {
+    /// The (potentially updated) opaque handle for the prepared statement on the server.
+    #[prost(bytes = "bytes", optional, tag = "1")]
+    pub prepared_statement_handle: Option<Bytes>,
+}
+
+/// Wire format of [`PreparedStatementHandle`].
+#[derive(Clone, PartialEq, Message)]
+struct PreparedStatementHandleProto {
+    /// The raw SQL query text
+    #[prost(string, tag = "1")]
+    query: String,
+
+    /// Bound parameter values as an Arrow IPC stream, empty if no values are bound.
+    #[prost(bytes = "bytes", tag = "2")]
+    params: Bytes,
+}
+
 /// Decoded / validated FlightSQL command messages
 ///
 /// Handles encoding/decoding prost::Any messages back
@@ -306,7 +392,7 @@ impl FlightSQLCommand {
         let msg = match self {
             Self::CommandStatementQuery(cmd) => Any::pack(&cmd),
             Self::CommandPreparedStatementQuery(handle) => {
-                let prepared_statement_handle = handle.encode();
+                let prepared_statement_handle = handle.try_encode()?;
                 let cmd = CommandPreparedStatementQuery {
                     prepared_statement_handle,
                 };
@@ -324,7 +410,7 @@ impl FlightSQLCommand {
             Self::CommandGetXdbcTypeInfo(cmd) => Any::pack(&cmd),
             Self::ActionCreatePreparedStatementRequest(cmd) => Any::pack(&cmd),
             Self::ActionClosePreparedStatementRequest(handle) => {
-                let prepared_statement_handle = handle.encode();
+                let prepared_statement_handle = handle.try_encode()?;
                 Any::pack(&ActionClosePreparedStatementRequest {
                     prepared_statement_handle,
                 })
@@ -333,3 +419,41 @@ impl FlightSQLCommand {
         Ok(msg.encode_to_vec().into())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int64Array};
+
+    use super::*;
+
+    #[test]
+    fn test_handle_roundtrip() {
+        let handle = PreparedStatementHandle::new("SELECT 1".to_string());
+        assert_eq!(roundtrip(handle.clone()), handle);
+
+        let params = RecordBatch::try_from_iter(vec![(
+            "1",
+            Arc::new(Int64Array::from(vec![1])) as ArrayRef,
+        )])
+        .unwrap();
+        let handle = PreparedStatementHandle::new("SELECT $1".to_string()).with_params(params);
+        assert_eq!(roundtrip(handle.clone()), handle);
+    }
+
+    #[test]
+    fn test_handle_invalid() {
+        let err = PreparedStatementHandle::try_decode(Bytes::from_static(&[0xff])).unwrap_err();
+        assert!(matches!(err, Error::InvalidHandle { .. }), "{err}");
+    }
+
+    fn roundtrip(handle: PreparedStatementHandle) -> PreparedStatementHandle {
+        let cmd = FlightSQLCommand::CommandPreparedStatementQuery(handle);
+        let cmd = FlightSQLCommand::try_decode(cmd.try_encode().unwrap()).unwrap();
+        let FlightSQLCommand::CommandPreparedStatementQuery(handle) = cmd else {
+            panic!("wrong command: {cmd:?}")
+        };
+        handle
+    }
+}
@@ -2,7 +2,8 @@
 use std::sync::Arc;
 
 use arrow::{
-    array::{ArrayRef, StringArray},
+    array::{ArrayRef, Int32Array, StringArray},
+    compute::concat_batches,
     datatypes::{DataType, Field, Schema, SchemaRef},
     error::ArrowError,
     ipc::writer::IpcWriteOptions,
@@ -25,13 +26,19 @@ use datafusion::{
     physical_plan::ExecutionPlan,
     sql::TableReference,
 };
-use iox_query::{exec::IOxSessionContext, QueryNamespace};
+use datafusion_util::config::{DEFAULT_CATALOG, DEFAULT_SCHEMA};
+use iox_query::{
+    exec::IOxSessionContext,
+    frontend::sql::{param_schema, QueryParams},
+    QueryNamespace,
+};
 use observability_deps::tracing::debug;
 use once_cell::sync::Lazy;
 use prost::Message;
+use schema::Schema as IoxSchema;
 
 use crate::{error::*, sql_info::iox_sql_info_list, xdbc_type_info::TYPE_INFO_RECORD_BATCH};
-use crate::{FlightSQLCommand, PreparedStatementHandle};
+use crate::{DoPutPreparedStatementResult, FlightSQLCommand, PreparedStatementHandle};
 
 /// Logic for creating plans for various Flight messages against a query database
 #[derive(Debug, Default)]
@@ -109,7 +116,8 @@ impl FlightSQLPlanner {
             FlightSQLCommand::CommandPreparedStatementQuery(handle) => {
                 let query = handle.query();
                 debug!(%query, "Planning FlightSQL prepared query");
-                Ok(ctx.sql_to_physical_plan(query).await?)
+                let params = params_for_handle(&handle)?;
+                Ok(ctx.sql_to_physical_plan_with_params(query, &params).await?)
             }
             FlightSQLCommand::CommandGetSqlInfo(CommandGetSqlInfo { info }) => {
                 debug!("Planning GetSqlInfo query");
@@ -250,18 +258,24 @@ impl FlightSQLPlanner {
             ) => {
                 debug!(%query, "Creating prepared statement");
 
-                // todo run the planner here and actually figure out parameter schemas
-                // see https://github.com/apache/arrow-datafusion/pull/4701
-                let parameter_schema = vec![];
+                let plan = ctx.sql_to_logical_plan(&query).await?;
+
+                // parameter schema is left empty if the query has no placeholders
+                let parameter_schema = param_schema(&plan)?;
+                let parameter_schema = if parameter_schema.fields().is_empty() {
+                    Bytes::new()
+                } else {
+                    encode_schema(&parameter_schema)?
+                };
 
-                let dataset_schema = get_schema_for_query(&query, ctx).await?;
+                let dataset_schema = get_schema_for_plan(plan);
                 let dataset_schema = encode_schema(dataset_schema.as_ref())?;
                 let handle = PreparedStatementHandle::new(query);
 
                 let result = ActionCreatePreparedStatementResult {
-                    prepared_statement_handle: Bytes::from(handle),
+                    prepared_statement_handle: Bytes::try_from(handle)?,
                     dataset_schema,
-                    parameter_schema: Bytes::from(parameter_schema),
+                    parameter_schema,
                 };
 
                 let msg = Any::pack(&result)?;
@@ -281,6 +295,57 @@ impl FlightSQLPlanner {
             .fail(),
         }
     }
+
+    /// Handles the data sent via `DoPut` for the command in `msg` and returns bytes for the
+    /// `app_metadata` of the [`arrow_flight::PutResult`].
+    ///
+    /// Only binding parameters to prepared statements is supported. The parameter values are
+    /// embedded into a new handle that is returned as [`DoPutPreparedStatementResult`].
+    pub fn do_put(
+        namespace_name: impl Into<String> + Send,
+        cmd: FlightSQLCommand,
+        batches: Vec<RecordBatch>,
+    ) -> Result<Bytes> {
+        let namespace_name = namespace_name.into();
+        debug!(%namespace_name, %cmd, "Handling flightsql do_put");
+
+        match cmd {
+            FlightSQLCommand::CommandPreparedStatementQuery(handle) => {
+                let Some(first) = batches.first() else {
+                    return MissingParamsSnafu.fail();
+                };
+                let params = concat_batches(&first.schema(), &batches)?;
+
+                // validate parameters early so the client gets the error on bind
+                QueryParams::try_from_batch(&params)?;
+
+                let query = handle.query();
+                debug!(
+                    %query,
+                    num_params = params.num_columns(),
+                    "Binding prepared statement parameters"
+                );
+
+                let result = DoPutPreparedStatementResult {
+                    prepared_statement_handle: Some(Bytes::try_from(handle.with_params(params))?),
+                };
+                Ok(result.encode_to_vec().into())
+            }
+            _ => ProtocolSnafu {
+                cmd: format!("{cmd:?}"),
+                method: "DoPut",
+            }
+            .fail(),
+        }
+    }
+}
+
+/// Return the parameter values bound to the prepared statement `handle`, if any
+fn params_for_handle(handle: &PreparedStatementHandle) -> Result<QueryParams> {
+    match handle.params() {
+        Some(batch) => Ok(QueryParams::try_from_batch(batch)?),
+        None => Ok(QueryParams::new()),
+    }
 }
 
 /// Return the schema for the specified query
@@ -386,13 +451,52 @@ async fn plan_get_imported_keys(
     Ok(ctx.batch_to_logical_plan(batch)?)
 }
 
+/// Return the primary key of a table, which for IOx tables is all tag columns, sorted by name,
+/// followed by the `time` column.
+///
+/// Tables that do not exist or that have no IOx schema (e.g. system tables) have no primary key.
 async fn plan_get_primary_keys(
     ctx: &IOxSessionContext,
-    _catalog: Option<String>,
-    _db_schema: Option<String>,
-    _table: String,
+    catalog: Option<String>,
+    db_schema: Option<String>,
+    table: String,
 ) -> Result<LogicalPlan> {
-    let batch = RecordBatch::new_empty(Arc::clone(&GET_PRIMARY_KEYS_SCHEMA));
+    let catalog = catalog.unwrap_or_else(|| DEFAULT_CATALOG.to_string());
+    let db_schema = db_schema.unwrap_or_else(|| DEFAULT_SCHEMA.to_string());
+    let table_ref = TableReference::full(&catalog, &db_schema, &table);
+
+    let primary_key = match ctx.inner().table_provider(table_ref).await {
+        Ok(provider) => match IoxSchema::try_from(provider.schema()) {
+            Ok(schema) => schema
+                .primary_key()
+                .into_iter()
+                .map(ToString::to_string)
+                .collect(),
+            Err(_) => vec![],
+        },
+        Err(_) => vec![],
+    };
+
+    let num_rows = primary_key.len();
+    let key_name = format!("{table}_pkey");
+    let repeat = |value: &str| {
+        Arc::new(StringArray::from_iter_values(
+            std::iter::repeat(value).take(num_rows),
+        )) as ArrayRef
+    };
+    let key_sequence = Int32Array::from_iter_values(1..=num_rows as i32);
+
+    let batch = RecordBatch::try_new(
+        Arc::clone(&GET_PRIMARY_KEYS_SCHEMA),
+        vec![
+            repeat(&catalog),
+            repeat(&db_schema),
+            repeat(&table),
+            Arc::new(StringArray::from_iter_values(primary_key)),
+            repeat(&key_name),
+            Arc::new(key_sequence),
+        ],
+    )?;
     Ok(ctx.batch_to_logical_plan(batch)?)
 }
 
@@ -24,6 +24,8 @@ use clap as _;
 use workspace_hack as _;
 
 use crate::{
+    manifest::Manifest,
+    namespace::purger as ns_purger,
     objectstore::{checker as os_checker, deleter as os_deleter, lister as os_lister},
     parquetfile::deleter as pf_deleter,
     retention::flagger as retention_flagger,
@@ -32,13 +34,17 @@ use crate::{
 use clap_blocks::garbage_collector::GarbageCollectorConfig;
 use humantime::format_duration;
 use iox_catalog::interface::Catalog;
-use object_store::DynObjectStore;
+use object_store::{path::Path, DynObjectStore};
 use observability_deps::tracing::*;
 use snafu::prelude::*;
 use std::{fmt::Debug, sync::Arc};
 use tokio::{select, sync::mpsc};
 use tokio_util::sync::CancellationToken;
 
+/// Logic for writing manifests of what a dry run would delete
+mod manifest;
+/// Logic for purging soft-deleted namespaces from the catalog
+mod namespace;
 /// Logic for listing, checking and deleting files in object storage
 mod objectstore;
 /// Logic for deleting parquet files from the catalog
@@ -46,6 +52,9 @@ mod parquetfile;
 /// Logic for flagging parquet files for deletion based on retention settings
 mod retention;
 
+#[cfg(test)]
+mod test_util;
+
 const BUFFER_SIZE: usize = 1000;
 
 /// Run the tasks that clean up old object store files that don't appear in the catalog.
@@ -61,6 +70,8 @@ pub struct GarbageCollector {
     os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
     pf_deleter: tokio::task::JoinHandle<Result<(), pf_deleter::Error>>,
     retention_flagger: tokio::task::JoinHandle<Result<(), retention_flagger::Error>>,
+    ns_purger: tokio::task::JoinHandle<Result<(), ns_purger::Error>>,
+    manifest_writer: Option<tokio::task::JoinHandle<Result<(), manifest::Error>>>,
 }
 
 impl Debug for GarbageCollector {
@@ -76,6 +87,7 @@ impl GarbageCollector {
             object_store,
             sub_config,
             catalog,
+            metric_registry,
         } = config;
 
         let dry_run = sub_config.dry_run;
@@ -85,12 +97,35 @@ impl GarbageCollector {
             objectstore_sleep_interval_minutes = %sub_config.objectstore_sleep_interval_minutes,
             parquetfile_sleep_interval_minutes = %sub_config.parquetfile_sleep_interval_minutes,
             retention_sleep_interval_minutes = %sub_config.retention_sleep_interval_minutes,
+            namespace_purge_grace_period = %format_duration(sub_config.namespace_purge_grace_period).to_string(),
+            namespace_purge_sleep_interval_minutes = %sub_config.namespace_purge_sleep_interval_minutes,
+            dry_run_manifest_prefix = ?sub_config.dry_run_manifest_prefix,
             "GarbageCollector starting"
         );
 
         // Shutdown handler channel to notify children
         let shutdown = CancellationToken::new();
 
+        // In dry-run mode, everything that would be deleted is recorded in a manifest in the
+        // object store, if configured.
+        let manifest_prefix = sub_config
+            .dry_run_manifest_prefix
+            .as_deref()
+            .map(Path::from);
+        let (manifest, manifest_writer) = match &manifest_prefix {
+            Some(prefix) if dry_run => {
+                let (tx, rx) = mpsc::channel(BUFFER_SIZE);
+                let writer = tokio::spawn(manifest::perform(
+                    shutdown.clone(),
+                    Arc::clone(&object_store),
+                    prefix.clone(),
+                    rx,
+                ));
+                (Manifest::new(tx), Some(writer))
+            }
+            _ => (Manifest::default(), None),
+        };
+
         // Initialise the object store garbage collector, which works as three communicating threads:
         // - lister lists objects in the object store and sends them on a channel. the lister will
         //   run until it has enumerated all matching files, then sleep for the configured
@@ -99,19 +134,28 @@ impl GarbageCollector {
         //   it sends them on another channel
         // - deleter receives object store entries that have been checked and therefore should be
         //   deleted.
+        //
+        // Each stage reports how many files it has seen, so that files leaked by failed persists
+        // or compactions and their cleanup can be tracked.
         let (tx1, rx1) = mpsc::channel(BUFFER_SIZE);
         let (tx2, rx2) = mpsc::channel(BUFFER_SIZE);
 
         let sdt = shutdown.clone();
         let osa = Arc::clone(&object_store);
+        let registry = Arc::clone(&metric_registry);
+        let sleep_interval_minutes = sub_config.objectstore_sleep_interval_minutes;
+        let sleep_interval_batch_milliseconds =
+            sub_config.objectstore_sleep_interval_batch_milliseconds;
 
         let os_lister = tokio::spawn(async move {
             select! {
                 ret = os_lister::perform(
                     osa,
                     tx1,
-                    sub_config.objectstore_sleep_interval_minutes,
-                    sub_config.objectstore_sleep_interval_batch_milliseconds,
+                    sleep_interval_minutes,
+                    sleep_interval_batch_milliseconds,
+                    &registry,
+                    manifest_prefix,
                 ) => {
                     ret
                 },
@@ -128,6 +172,8 @@ impl GarbageCollector {
                 message: e.to_string(),
             }
         })?;
+        let checker_metrics = os_checker::CheckerMetrics::new(&metric_registry);
+        let checker_manifest = manifest.clone();
 
         let os_checker = tokio::spawn(async move {
             select! {
@@ -136,6 +182,8 @@ impl GarbageCollector {
                     cutoff,
                     rx1,
                     tx2,
+                    checker_metrics,
+                    checker_manifest,
                 ) => {
                     ret
                 },
@@ -151,24 +199,42 @@ impl GarbageCollector {
             dry_run,
             sub_config.objectstore_concurrent_deletes,
             rx2,
+            os_deleter::DeleterMetrics::new(&metric_registry),
         ));
 
         // Initialise the parquet file deleter, which is just one thread that calls delete_old()
-        // on the catalog then sleeps.
+        // on the catalog then sleeps. In dry-run mode, it only lists the files it would delete.
         let pf_deleter = tokio::spawn(pf_deleter::perform(
             shutdown.clone(),
             Arc::clone(&catalog),
             sub_config.parquetfile_cutoff,
             sub_config.parquetfile_sleep_interval_minutes,
+            sub_config.dry_run,
+            manifest.clone(),
         ));
 
         // Initialise the retention code, which is just one thread that calls
-        // flag_for_delete_by_retention() on the catalog then sleeps.
+        // flag_for_delete_by_retention() on the catalog then sleeps. In dry-run mode, it only
+        // lists the files it would flag.
         let retention_flagger = tokio::spawn(retention_flagger::perform(
             shutdown.clone(),
-            catalog,
+            Arc::clone(&catalog),
             sub_config.retention_sleep_interval_minutes,
             sub_config.dry_run,
+            manifest.clone(),
+        ));
+
+        // Initialise the namespace purger, which is just one thread that removes namespaces
+        // soft-deleted longer than the grace period from the catalog, then sleeps. The object
+        // store files of a purged namespace are no longer referenced by the catalog and are
+        // removed by the object store garbage collector above.
+        let ns_purger = tokio::spawn(ns_purger::perform(
+            shutdown.clone(),
+            catalog,
+            sub_config.namespace_purge_grace_period,
+            sub_config.namespace_purge_sleep_interval_minutes,
+            sub_config.dry_run,
+            manifest,
         ));
 
         Ok(Self {
@@ -178,6 +244,8 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            ns_purger,
+            manifest_writer,
         })
     }
 
@@ -197,17 +265,26 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            ns_purger,
+            manifest_writer,
             shutdown: _,
         } = self;
 
-        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger) = futures::join!(
+        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger, ns_purger) = futures::join!(
             os_lister,
             os_checker,
             os_deleter,
             pf_deleter,
-            retention_flagger
+            retention_flagger,
+            ns_purger
         );
 
+        // the manifest writer only exits on shutdown, after the other tasks are done recording
+        if let Some(manifest_writer) = manifest_writer {
+            manifest_writer.await.context(ManifestWriterPanicSnafu)??;
+        }
+
+        ns_purger.context(NamespacePurgerPanicSnafu)??;
         retention_flagger.context(ParquetFileDeleterPanicSnafu)??;
         pf_deleter.context(ParquetFileDeleterPanicSnafu)??;
         os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
@@ -229,6 +306,9 @@ pub struct Config {
 
     /// The garbage collector specific configuration
     pub sub_config: GarbageCollectorConfig,
+
+    /// The registry the garbage collector reports its metrics to
+    pub metric_registry: Arc<metric::Registry>,
 }
 
 impl Debug for Config {
@@ -274,6 +354,18 @@ pub enum Error {
     ParquetFileRetentionFlagger { source: retention_flagger::Error },
     #[snafu(display("The parquet file retention flagger task panicked"))]
     ParquetFileRetentionFlaggerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The namespace purger task failed"))]
+    #[snafu(context(false))]
+    NamespacePurger { source: ns_purger::Error },
+    #[snafu(display("The namespace purger task panicked"))]
+    NamespacePurgerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The dry run manifest writer task failed"))]
+    #[snafu(context(false))]
+    ManifestWriter { source: manifest::Error },
+    #[snafu(display("The dry run manifest writer task panicked"))]
+    ManifestWriterPanic { source: tokio::task::JoinError },
 }
 
 #[allow(missing_docs)]
@@ -348,6 +440,7 @@ mod tests {
             object_store,
             catalog,
             sub_config,
+            metric_registry: Default::default(),
         }
     }
 
@@ -0,0 +1,215 @@
+use chrono::Utc;
+use object_store::{path::Path, DynObjectStore};
+use observability_deps::tracing::*;
+use serde::Serialize;
+use snafu::prelude::*;
+use std::{sync::Arc, time::Duration};
+use tokio::{select, sync::mpsc, time::interval};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// How often buffered entries are written to a new manifest file.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Maximum number of entries in a single manifest file.
+const MAX_ENTRIES_PER_FILE: usize = 10_000;
+
+/// Something a dry run of the garbage collector would have deleted, and why.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct ManifestEntry {
+    /// What would be deleted or flagged for deletion, `object` for a file in object storage,
+    /// `parquet_file` for a parquet file row in the catalog or `namespace` for a namespace in the
+    /// catalog.
+    kind: &'static str,
+    /// The object store location, the object store ID of the parquet file or the namespace name.
+    key: String,
+    /// Why it would be deleted.
+    reason: &'static str,
+}
+
+impl ManifestEntry {
+    pub(crate) fn object(location: &Path, reason: &'static str) -> Self {
+        Self {
+            kind: "object",
+            key: location.to_string(),
+            reason,
+        }
+    }
+
+    pub(crate) fn parquet_file(object_store_id: Uuid, reason: &'static str) -> Self {
+        Self {
+            kind: "parquet_file",
+            key: object_store_id.to_string(),
+            reason,
+        }
+    }
+
+    pub(crate) fn namespace(name: &str, reason: &'static str) -> Self {
+        Self {
+            kind: "namespace",
+            key: name.to_string(),
+            reason,
+        }
+    }
+}
+
+/// Handle to record [`ManifestEntry`]s, a no-op if no manifest is written.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Manifest {
+    entries: Option<mpsc::Sender<ManifestEntry>>,
+}
+
+impl Manifest {
+    pub(crate) fn new(entries: mpsc::Sender<ManifestEntry>) -> Self {
+        Self {
+            entries: Some(entries),
+        }
+    }
+
+    pub(crate) async fn record(&self, entry: ManifestEntry) {
+        let Some(entries) = &self.entries else {
+            return;
+        };
+        if entries.send(entry).await.is_err() {
+            warn!("manifest writer exited, dropping manifest entry");
+        }
+    }
+}
+
+/// Write the received entries as JSON lines to files under `prefix` in the object store, one new
+/// file per [`FLUSH_INTERVAL`] or [`MAX_ENTRIES_PER_FILE`] entries, named after the time it was
+/// written.
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    object_store: Arc<DynObjectStore>,
+    prefix: Path,
+    mut entries: mpsc::Receiver<ManifestEntry>,
+) -> Result<()> {
+    let mut buffer = Vec::new();
+    let mut flush_interval = interval(FLUSH_INTERVAL);
+
+    loop {
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            entry = entries.recv() => {
+                let Some(entry) = entry else {
+                    break
+                };
+                buffer.push(entry);
+                if buffer.len() >= MAX_ENTRIES_PER_FILE {
+                    flush(&object_store, &prefix, &mut buffer).await?;
+                }
+            },
+            _ = flush_interval.tick() => {
+                flush(&object_store, &prefix, &mut buffer).await?;
+            },
+        }
+    }
+
+    // drain what was already sent before exiting
+    while let Ok(entry) = entries.try_recv() {
+        buffer.push(entry);
+    }
+    flush(&object_store, &prefix, &mut buffer).await
+}
+
+async fn flush(
+    object_store: &Arc<DynObjectStore>,
+    prefix: &Path,
+    buffer: &mut Vec<ManifestEntry>,
+) -> Result<()> {
+    if buffer.is_empty() {
+        return Ok(());
+    }
+
+    let mut body = Vec::new();
+    for entry in buffer.iter() {
+        serde_json::to_writer(&mut body, entry).expect("manifest entries serialize");
+        body.push(b'\n');
+    }
+
+    let path = prefix.child(format!("{}.jsonl", Utc::now().format("%Y%m%dT%H%M%S%.6fZ")));
+    object_store
+        .put(&path, body.into())
+        .await
+        .context(WritingSnafu { path: path.clone() })?;
+    info!(%path, entries = buffer.len(), "wrote garbage collector dry run manifest");
+
+    buffer.clear();
+    Ok(())
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("The manifest {path} could not be written"))]
+    Writing {
+        source: object_store::Error,
+        path: Path,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    async fn writes_entries_on_shutdown() {
+        let object_store: Arc<DynObjectStore> = Arc::new(object_store::memory::InMemory::new());
+        let prefix = Path::from("gc_manifests");
+        let shutdown = CancellationToken::new();
+        let (tx, rx) = mpsc::channel(10);
+
+        let writer = tokio::spawn(perform(
+            shutdown.clone(),
+            Arc::clone(&object_store),
+            prefix.clone(),
+            rx,
+        ));
+
+        let manifest = Manifest::new(tx);
+        manifest
+            .record(ManifestEntry::object(
+                &Path::from("1/2/3/foo.parquet"),
+                "orphaned",
+            ))
+            .await;
+        manifest
+            .record(ManifestEntry::namespace("bananas", "soft_deleted"))
+            .await;
+        // entries recorded without a manifest go nowhere
+        Manifest::default()
+            .record(ManifestEntry::namespace("ignored", "soft_deleted"))
+            .await;
+
+        shutdown.cancel();
+        writer.await.unwrap().unwrap();
+
+        let files: Vec<_> = object_store
+            .list(Some(&prefix))
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 1);
+
+        let content = object_store
+            .get(&files[0].location)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(
+            std::str::from_utf8(&content).unwrap(),
+            "{\"kind\":\"object\",\"key\":\"1/2/3/foo.parquet\",\"reason\":\"orphaned\"}\n\
+             {\"kind\":\"namespace\",\"key\":\"bananas\",\"reason\":\"soft_deleted\"}\n"
+        );
+    }
+}
@@ -0,0 +1,2 @@
+/// Logic for purging soft-deleted namespaces once their grace period has passed
+pub(crate) mod purger;
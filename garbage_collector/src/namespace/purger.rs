@@ -0,0 +1,193 @@
+use crate::manifest::{Manifest, ManifestEntry};
+use data_types::Timestamp;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::*;
+use snafu::prelude::*;
+use std::{sync::Arc, time::Duration};
+use tokio::{select, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    catalog: Arc<dyn Catalog>,
+    grace_period: Duration,
+    sleep_interval_minutes: u64,
+    dry_run: bool,
+    manifest: Manifest,
+) -> Result<()> {
+    loop {
+        let purged = purge_expired(&catalog, grace_period, dry_run, &manifest).await?;
+        info!(purged_count = %purged, "purged soft-deleted namespaces");
+
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            _ = sleep(Duration::from_secs(60 * sleep_interval_minutes)) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Purge all namespaces that were soft-deleted at least `grace_period` ago, returning the number
+/// of (in dry-run mode: would-be) purged namespaces. In dry-run mode, the namespaces that would be
+/// purged are recorded in the `manifest`.
+///
+/// Purging removes the catalog rows of the namespace. The objects of its parquet files are then no
+/// longer referenced and get removed by the object store garbage collector.
+async fn purge_expired(
+    catalog: &Arc<dyn Catalog>,
+    grace_period: Duration,
+    dry_run: bool,
+    manifest: &Manifest,
+) -> Result<usize> {
+    let older_than = Timestamp::from(catalog.time_provider().now() - grace_period);
+
+    let mut repos = catalog.repositories().await;
+    let deleted = repos
+        .namespaces()
+        .list(SoftDeletedRows::OnlyDeleted) // read-only
+        .await
+        .context(ListingSnafu)?;
+
+    let mut purged = 0;
+    for namespace in deleted {
+        let Some(deleted_at) = namespace.deleted_at else {
+            continue;
+        };
+        if deleted_at > older_than {
+            continue;
+        }
+
+        if dry_run {
+            info!(
+                namespace_id = %namespace.id,
+                namespace_name = %namespace.name,
+                deleted_at = deleted_at.get(),
+                "dry run: would purge soft-deleted namespace",
+            );
+            manifest
+                .record(ManifestEntry::namespace(&namespace.name, "soft_deleted"))
+                .await;
+        } else {
+            repos
+                .namespaces()
+                .purge(namespace.id) // read/write
+                .await
+                .context(PurgingSnafu {
+                    namespace: namespace.name.clone(),
+                })?;
+            info!(
+                namespace_id = %namespace.id,
+                namespace_name = %namespace.name,
+                deleted_at = deleted_at.get(),
+                "purged soft-deleted namespace",
+            );
+        }
+        purged += 1;
+    }
+
+    Ok(purged)
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Failed to list soft-deleted namespaces"))]
+    Listing {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to purge soft-deleted namespace {namespace}"))]
+    Purging {
+        namespace: String,
+        source: iox_catalog::interface::Error,
+    },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[tokio::test]
+    async fn purges_namespaces_after_grace_period() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metric_registry));
+
+        let (deleted, deleted_table, active) = {
+            let mut repos = catalog.repositories().await;
+            let deleted = arbitrary_namespace(&mut *repos, "deleted").await;
+            let deleted_table = arbitrary_table(&mut *repos, "table", &deleted).await;
+            let active = arbitrary_namespace(&mut *repos, "active").await;
+            repos.namespaces().soft_delete(&deleted.name).await.unwrap();
+            (deleted, deleted_table, active)
+        };
+
+        // still within the grace period
+        assert_eq!(
+            purge_expired(&catalog, DAY, false, &Manifest::default())
+                .await
+                .unwrap(),
+            0
+        );
+        assert_namespace_exists(&catalog, &deleted.name, true).await;
+
+        // dry run does not touch the catalog, but reports the namespace in the manifest
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        assert_eq!(
+            purge_expired(&catalog, Duration::ZERO, true, &Manifest::new(tx))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_namespace_exists(&catalog, &deleted.name, true).await;
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ManifestEntry::namespace(&deleted.name, "soft_deleted")
+        );
+
+        assert_eq!(
+            purge_expired(&catalog, Duration::ZERO, false, &Manifest::default())
+                .await
+                .unwrap(),
+            1
+        );
+        assert_namespace_exists(&catalog, &deleted.name, false).await;
+        assert_namespace_exists(&catalog, &active.name, true).await;
+        assert!(catalog
+            .repositories()
+            .await
+            .tables()
+            .get_by_id(deleted_table.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // nothing left to purge, the active namespace is never purged
+        assert_eq!(
+            purge_expired(&catalog, Duration::ZERO, false, &Manifest::default())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    async fn assert_namespace_exists(catalog: &Arc<dyn Catalog>, name: &str, exists: bool) {
+        let got = catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_name(name, SoftDeletedRows::AllRows)
+            .await
+            .unwrap();
+        assert_eq!(got.is_some(), exists, "namespace {name}");
+    }
+}
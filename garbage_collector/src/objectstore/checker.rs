@@ -1,5 +1,7 @@
+use crate::manifest::{Manifest, ManifestEntry};
 use chrono::{DateTime, Duration, Utc};
 use iox_catalog::interface::{Catalog, ParquetFileRepo};
+use metric::U64Counter;
 use object_store::ObjectMeta;
 use observability_deps::tracing::*;
 use snafu::prelude::*;
@@ -45,16 +47,50 @@ const RECEIVE_TIMEOUT: core::time::Duration = core::time::Duration::from_millis(
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Counts of the object store files checked against the catalog, by outcome.
+#[derive(Debug, Clone)]
+pub(crate) struct CheckerMetrics {
+    /// Younger than the cutoff, so not checked any further.
+    too_new: U64Counter,
+    /// Referenced by the catalog.
+    referenced: U64Counter,
+    /// Not referenced by the catalog and older than the cutoff.
+    orphaned: U64Counter,
+    /// Not a parquet file named after a valid object store ID.
+    invalid: U64Counter,
+    /// Kept because the catalog could not be queried.
+    catalog_error: U64Counter,
+}
+
+impl CheckerMetrics {
+    pub(crate) fn new(registry: &metric::Registry) -> Self {
+        let metric = registry.register_metric::<U64Counter>(
+            "gc_objectstore_checked_files",
+            "number of object store files checked for a catalog reference, by outcome",
+        );
+
+        Self {
+            too_new: metric.recorder(&[("outcome", "too_new")]),
+            referenced: metric.recorder(&[("outcome", "referenced")]),
+            orphaned: metric.recorder(&[("outcome", "orphaned")]),
+            invalid: metric.recorder(&[("outcome", "invalid")]),
+            catalog_error: metric.recorder(&[("outcome", "catalog_error")]),
+        }
+    }
+}
+
 pub(crate) async fn perform(
     catalog: Arc<dyn Catalog>,
     cutoff: Duration,
     items: mpsc::Receiver<ObjectMeta>,
     deleter: mpsc::Sender<ObjectMeta>,
+    metrics: CheckerMetrics,
+    manifest: Manifest,
 ) -> Result<()> {
     let mut repositories = catalog.repositories().await;
     let parquet_files = repositories.parquet_files();
 
-    perform_inner(parquet_files, cutoff, items, deleter).await
+    perform_inner(parquet_files, cutoff, items, deleter, &metrics, &manifest).await
 }
 
 /// Allows easier mocking of just `ParquetFileRepo` in tests.
@@ -63,6 +99,8 @@ async fn perform_inner(
     cutoff: Duration,
     mut items: mpsc::Receiver<ObjectMeta>,
     deleter: mpsc::Sender<ObjectMeta>,
+    metrics: &CheckerMetrics,
+    manifest: &Manifest,
 ) -> Result<()> {
     let mut batch = Vec::with_capacity(CATALOG_BATCH_SIZE);
     loop {
@@ -84,7 +122,10 @@ async fn perform_inner(
 
         if batch.len() >= CATALOG_BATCH_SIZE || timedout {
             let older_than = chrono::offset::Utc::now() - cutoff;
-            for item in should_delete(batch, older_than, parquet_files).await {
+            for (item, reason) in should_delete(batch, older_than, parquet_files, metrics).await {
+                manifest
+                    .record(ManifestEntry::object(&item.location, reason))
+                    .await;
                 deleter.send(item).await.context(DeleterExitedSnafu)?;
             }
             batch = Vec::with_capacity(100);
@@ -96,7 +137,8 @@ async fn perform_inner(
 /// [ObjectMeta] can be deleted.
 /// It can be deleted if it is old enough AND there isn't a reference in the catalog for it anymore (or ever)
 /// It will also say the file can be deleted if it isn't a parquet file or the uuid isn't valid.
-/// [should_delete] returns a subset of the input, which are the items that "should" be deleted.
+/// [should_delete] returns a subset of the input, which are the items that "should" be deleted,
+/// together with the reason why.
 // It first processes the easy checks, age, uuid, file suffix, and other parse/data input errors. This
 // checking is cheap. For the files that need to be checked against the catalog, it batches them to
 // reduce the number of requests on the wire and amortize the catalog overhead. Setting the batch size
@@ -105,7 +147,8 @@ async fn should_delete(
     items: Vec<ObjectMeta>,
     cutoff: DateTime<Utc>,
     parquet_files: &mut dyn ParquetFileRepo,
-) -> Vec<ObjectMeta> {
+    metrics: &CheckerMetrics,
+) -> Vec<(ObjectMeta, &'static str)> {
     // to_delete is the vector we will return to the caller containing ObjectMeta we think should be deleted.
     // it is never longer than `items`
     let mut to_delete = Vec::with_capacity(items.len());
@@ -125,6 +168,7 @@ async fn should_delete(
                 "Ignoring object",
             );
             // Not old enough; do not delete
+            metrics.too_new.inc(1);
             continue;
         }
 
@@ -156,7 +200,8 @@ async fn should_delete(
                     reason = "not a valid UUID",
                     "Scheduling file for deletion",
                 );
-                to_delete.push(candidate)
+                metrics.invalid.inc(1);
+                to_delete.push((candidate, "invalid_uuid"))
             }
         } else {
             // expected to be a rare situation so warn.
@@ -166,7 +211,8 @@ async fn should_delete(
                 reason = "not a .parquet file",
                 "Scheduling file for deletion",
             );
-            to_delete.push(candidate)
+            metrics.invalid.inc(1);
+            to_delete.push((candidate, "not_parquet"))
         }
     }
 
@@ -176,10 +222,12 @@ async fn should_delete(
         let just_uuids: Vec<_> = batch.iter().map(|id| id.0).collect();
         match check_ids_exists_in_catalog(just_uuids.clone(), parquet_files).await {
             Ok(present_uuids) => {
+                metrics.referenced.inc(present_uuids.len() as u64);
                 do_not_delete.extend(present_uuids.iter());
             }
             Err(e) => {
                 // on error assume all the uuids in this batch are present in the catalog
+                metrics.catalog_error.inc(just_uuids.len() as u64);
                 do_not_delete.extend(just_uuids.iter());
                 warn!(
                     error = %e,
@@ -206,7 +254,10 @@ async fn should_delete(
     to_check_in_catalog
         .iter()
         .filter(|c| !do_not_delete.contains(&c.0))
-        .for_each(|c| to_delete.push(c.1.clone()));
+        .for_each(|c| {
+            metrics.orphaned.inc(1);
+            to_delete.push((c.1.clone(), "orphaned"))
+        });
 
     to_delete
 }
@@ -278,6 +329,7 @@ mod tests {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
             max_l0_created_at: Timestamp::new(1),
+            tag_ranges: None,
         };
 
         let parquet_file = repos
@@ -313,8 +365,10 @@ mod tests {
             e_tag: None,
         };
 
-        let results = should_delete(vec![item], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 0);
+        assert_eq!(metrics.too_new.fetch(), 1);
     }
 
     #[tokio::test]
@@ -342,7 +396,8 @@ mod tests {
             e_tag: None,
         };
 
-        let results = should_delete(vec![item], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 0);
     }
 
@@ -363,7 +418,8 @@ mod tests {
             e_tag: None,
         };
 
-        let results = should_delete(vec![item], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 0);
     }
 
@@ -391,8 +447,10 @@ mod tests {
             e_tag: None,
         };
 
-        let results = should_delete(vec![item], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 0);
+        assert_eq!(metrics.referenced.fetch(), 1);
     }
 
     #[tokio::test]
@@ -419,9 +477,11 @@ mod tests {
             size: 0,
             e_tag: None,
         };
-        let results = should_delete(vec![item.clone()], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item.clone()], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0], item);
+        assert_eq!(results[0], (item, "orphaned"));
+        assert_eq!(metrics.orphaned.fetch(), 1);
     }
 
     #[tokio::test]
@@ -441,9 +501,11 @@ mod tests {
             e_tag: None,
         };
 
-        let results = should_delete(vec![item.clone()], cutoff, parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(vec![item.clone()], cutoff, parquet_files, &metrics).await;
         assert_eq!(results.len(), 1);
-        assert_eq!(results[0], item);
+        assert_eq!(results[0], (item, "invalid_uuid"));
+        assert_eq!(metrics.invalid.fetch(), 1);
     }
 
     /// The garbage collector checks the catalog for files it _should not delete_. If we can't reach
@@ -490,8 +552,16 @@ mod tests {
         assert_eq!(pf, file_in_catalog);
 
         // because of the db error, there should be no results
-        let results = should_delete(vec![item.clone()], cutoff, &mut mocked_parquet_files).await;
+        let metrics = CheckerMetrics::new(&metric::Registry::new());
+        let results = should_delete(
+            vec![item.clone()],
+            cutoff,
+            &mut mocked_parquet_files,
+            &metrics,
+        )
+        .await;
         assert_eq!(results.len(), 0);
+        assert_eq!(metrics.catalog_error.fetch(), 1);
     }
 
     struct MockParquetFileRepo<'a> {
@@ -507,6 +577,13 @@ mod tests {
             self.inner.create(parquet_file_params).await
         }
 
+        async fn create_many(
+            &mut self,
+            parquet_file_params: Vec<ParquetFileParams>,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
+            self.inner.create_many(parquet_file_params).await
+        }
+
         async fn list_all(&mut self) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
             self.inner.list_all().await
         }
@@ -517,6 +594,12 @@ mod tests {
             self.inner.flag_for_delete_by_retention().await
         }
 
+        async fn list_to_flag_by_retention(
+            &mut self,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
+            self.inner.list_to_flag_by_retention().await
+        }
+
         async fn list_by_namespace_not_to_delete(
             &mut self,
             namespace_id: NamespaceId,
@@ -540,6 +623,13 @@ mod tests {
             self.inner.delete_old_ids_only(older_than).await
         }
 
+        async fn list_old(
+            &mut self,
+            older_than: Timestamp,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
+            self.inner.list_old(older_than).await
+        }
+
         async fn list_by_partition_not_to_delete(
             &mut self,
             partition_id: &TransitionPartitionId,
@@ -565,6 +655,15 @@ mod tests {
             })
         }
 
+        async fn sum_l0_file_size_by_partition(
+            &mut self,
+            partition_ids: &[PartitionId],
+        ) -> iox_catalog::interface::Result<Vec<(PartitionId, i64)>> {
+            self.inner
+                .sum_l0_file_size_by_partition(partition_ids)
+                .await
+        }
+
         async fn create_upgrade_delete(
             &mut self,
             delete: &[ParquetFileId],
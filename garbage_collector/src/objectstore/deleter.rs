@@ -1,4 +1,5 @@
 use futures::{StreamExt, TryStreamExt};
+use metric::U64Counter;
 use object_store::{DynObjectStore, ObjectMeta};
 use observability_deps::tracing::info;
 use snafu::prelude::*;
@@ -6,28 +7,59 @@ use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio_util::sync::CancellationToken;
 
+/// Counts of the object store files handed to the deleter, by result.
+#[derive(Debug, Clone)]
+pub(crate) struct DeleterMetrics {
+    deleted: U64Counter,
+    dry_run: U64Counter,
+    error: U64Counter,
+}
+
+impl DeleterMetrics {
+    pub(crate) fn new(registry: &metric::Registry) -> Self {
+        let metric = registry.register_metric::<U64Counter>(
+            "gc_objectstore_deleted_files",
+            "number of unreferenced object store files deleted, by result",
+        );
+
+        Self {
+            deleted: metric.recorder(&[("result", "success")]),
+            dry_run: metric.recorder(&[("result", "dry_run")]),
+            error: metric.recorder(&[("result", "error")]),
+        }
+    }
+}
+
 pub(crate) async fn perform(
     shutdown: CancellationToken,
     object_store: Arc<DynObjectStore>,
     dry_run: bool,
     concurrent_deletes: usize,
     items: mpsc::Receiver<ObjectMeta>,
+    metrics: DeleterMetrics,
 ) -> Result<()> {
     let stream_fu = tokio_stream::wrappers::ReceiverStream::new(items)
         .map(|item| {
             let object_store = Arc::clone(&object_store);
+            let metrics = metrics.clone();
 
             async move {
                 let path = item.location;
                 if dry_run {
                     info!(?path, "Not deleting due to dry run");
+                    metrics.dry_run.inc(1);
                     Ok(())
                 } else {
                     info!("Deleting {path}");
-                    object_store
+                    let res = object_store
                         .delete(&path)
                         .await
-                        .context(DeletingSnafu { path })
+                        .context(DeletingSnafu { path });
+                    match &res {
+                        Ok(_) => metrics.deleted.inc(1),
+                        Err(_) => metrics.error.inc(1),
+                    }
+                    res
                 }
             }
         })
@@ -112,6 +144,7 @@ mod tests {
             dry_run,
             concurrent_deletes,
             rx,
+            DeleterMetrics::new(&metric::Registry::default()),
         );
         // Unusual test because there is no assertion but the call below should
         // not panic which verifies that the deleter task shutdown gracefully.
@@ -1,6 +1,7 @@
 use backoff::*;
 use futures::prelude::*;
-use object_store::{DynObjectStore, ObjectMeta};
+use metric::U64Counter;
+use object_store::{path::Path, DynObjectStore, ObjectMeta};
 use observability_deps::tracing::*;
 use snafu::prelude::*;
 use std::{sync::Arc, time::Duration};
@@ -14,14 +15,25 @@ const MAX_ITEMS_PROCESSED_PER_LOOP: usize = 10_000;
 
 /// perform a object store list, limiting to ['MAX_ITEMS_PROCESSED_PER_LOOP'] files at a time,
 /// waiting sleep interval before listing afresh.
+///
+/// Files under `exclude_prefix` (the garbage collector's own dry run manifests) are not passed on.
 pub(crate) async fn perform(
     object_store: Arc<DynObjectStore>,
     checker: mpsc::Sender<ObjectMeta>,
     sleep_interval_iteration_minutes: u64,
     sleep_interval_list_page_milliseconds: u64,
+    metric_registry: &metric::Registry,
+    exclude_prefix: Option<Path>,
 ) -> Result<()> {
     info!("beginning object store listing");
 
+    let listed = metric_registry
+        .register_metric::<U64Counter>(
+            "gc_objectstore_listed_files",
+            "number of object store files listed by the garbage collector",
+        )
+        .recorder(&[]);
+
     loop {
         let mut backoff = Backoff::new(&BackoffConfig::default());
 
@@ -38,7 +50,7 @@ pub(crate) async fn perform(
         while let Some(v) = chunked_items.next().await {
             // relist and sleep on an error to allow time for transient errors to dissipate
             // todo(pjb): react differently to different errors
-            match process_item_list(v, &checker).await {
+            match process_item_list(v, &checker, exclude_prefix.as_ref()).await {
                 Err(e) => {
                     warn!("error processing items from object store, continuing: {e}");
                     // go back to start of loop to list again, hopefully to get past error.
@@ -46,6 +58,7 @@ pub(crate) async fn perform(
                 }
                 Ok(i) => {
                     count += i;
+                    listed.inc(i as u64);
                 }
             }
             sleep(Duration::from_millis(sleep_interval_list_page_milliseconds)).await;
@@ -59,10 +72,14 @@ pub(crate) async fn perform(
 async fn process_item_list(
     items: Vec<object_store::Result<ObjectMeta>>,
     checker: &mpsc::Sender<ObjectMeta>,
+    exclude_prefix: Option<&Path>,
 ) -> Result<i32> {
     let mut i = 0;
     for item in items {
         let item = item.context(MalformedSnafu)?;
+        if exclude_prefix.map_or(false, |p| item.location.prefix_matches(p)) {
+            continue;
+        }
         debug!(location = %item.location, "Object store item");
         checker.send(item).await?;
         i += 1;
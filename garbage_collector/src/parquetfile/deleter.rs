@@ -1,3 +1,4 @@
+use crate::manifest::{Manifest, ManifestEntry};
 use data_types::Timestamp;
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::*;
@@ -11,18 +12,13 @@ pub(crate) async fn perform(
     catalog: Arc<dyn Catalog>,
     cutoff: Duration,
     sleep_interval_minutes: u64,
+    dry_run: bool,
+    manifest: Manifest,
 ) -> Result<()> {
     loop {
         let older_than = Timestamp::from(catalog.time_provider().now() - cutoff);
-        // do the delete, returning the deleted files
-        let deleted = catalog
-            .repositories()
-            .await
-            .parquet_files()
-            .delete_old_ids_only(older_than) // read/write
-            .await
-            .context(DeletingSnafu)?;
-        info!(delete_count = %deleted.len(), "iox_catalog::delete_old()");
+        let deleted = delete_old(&catalog, older_than, dry_run, &manifest).await?;
+        info!(delete_count = %deleted, dry_run, "iox_catalog::delete_old()");
 
         select! {
             _ = shutdown.cancelled() => {
@@ -34,9 +30,55 @@ pub(crate) async fn perform(
     Ok(())
 }
 
+/// Delete the catalog rows of parquet files that were flagged for deletion before `older_than`,
+/// returning the number of (in dry-run mode: would-be) deleted rows. In dry-run mode, the files
+/// that would be deleted are recorded in the `manifest`.
+async fn delete_old(
+    catalog: &Arc<dyn Catalog>,
+    older_than: Timestamp,
+    dry_run: bool,
+    manifest: &Manifest,
+) -> Result<usize> {
+    let mut repos = catalog.repositories().await;
+
+    if dry_run {
+        let old = repos
+            .parquet_files()
+            .list_old(older_than) // read-only
+            .await
+            .context(ListingSnafu)?;
+        for file in &old {
+            debug!(
+                parquet_file_id = %file.id,
+                object_store_id = %file.object_store_id,
+                "dry run: would delete parquet file from catalog",
+            );
+            manifest
+                .record(ManifestEntry::parquet_file(
+                    file.object_store_id,
+                    "flagged_for_deletion",
+                ))
+                .await;
+        }
+        Ok(old.len())
+    } else {
+        let deleted = repos
+            .parquet_files()
+            .delete_old_ids_only(older_than) // read/write
+            .await
+            .context(DeletingSnafu)?;
+        Ok(deleted.len())
+    }
+}
+
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
 pub enum Error {
+    #[snafu(display("Failed to list old parquet files in catalog"))]
+    Listing {
+        source: iox_catalog::interface::Error,
+    },
+
     #[snafu(display("Failed to delete old parquet files in catalog"))]
     Deleting {
         source: iox_catalog::interface::Error,
@@ -44,3 +86,55 @@ pub enum Error {
 }
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::create_parquet_file;
+    use data_types::CompactionLevel;
+    use iox_catalog::mem::MemCatalog;
+
+    #[tokio::test]
+    async fn deletes_flagged_files() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metric_registry));
+
+        let file = create_parquet_file(&catalog).await;
+        catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create_upgrade_delete(&[file.id], &[], &[], CompactionLevel::Initial)
+            .await
+            .unwrap();
+        let older_than = Timestamp::new(i64::MAX);
+
+        // dry run does not touch the catalog, but reports the file in the manifest
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        assert_eq!(
+            delete_old(&catalog, older_than, true, &Manifest::new(tx))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ManifestEntry::parquet_file(file.object_store_id, "flagged_for_deletion")
+        );
+
+        assert_eq!(
+            delete_old(&catalog, older_than, false, &Manifest::default())
+                .await
+                .unwrap(),
+            1
+        );
+        assert!(catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .get_by_object_store_id(file.object_store_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
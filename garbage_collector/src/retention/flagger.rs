@@ -1,3 +1,4 @@
+use crate::manifest::{Manifest, ManifestEntry};
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::*;
 use snafu::prelude::*;
@@ -10,20 +11,11 @@ pub(crate) async fn perform(
     catalog: Arc<dyn Catalog>,
     sleep_interval_minutes: u64,
     dry_run: bool,
+    manifest: Manifest,
 ) -> Result<()> {
     loop {
-        if !dry_run {
-            let flagged = catalog
-                .repositories()
-                .await
-                .parquet_files()
-                .flag_for_delete_by_retention() //read/write
-                .await
-                .context(FlaggingSnafu)?;
-            info!(flagged_count = %flagged.len(), "iox_catalog::flag_for_delete_by_retention()");
-        } else {
-            debug!("dry run enabled for parquet retention flagger");
-        };
+        let flagged = flag_expired(&catalog, dry_run, &manifest).await?;
+        info!(flagged_count = %flagged, dry_run, "iox_catalog::flag_for_delete_by_retention()");
 
         select! {
             _ = shutdown.cancelled() => {
@@ -35,9 +27,54 @@ pub(crate) async fn perform(
     Ok(())
 }
 
+/// Flag the parquet files that are older than the retention period of their table or namespace
+/// for deletion, returning the number of (in dry-run mode: would-be) flagged files. In dry-run
+/// mode, the files that would be flagged are recorded in the `manifest`.
+async fn flag_expired(
+    catalog: &Arc<dyn Catalog>,
+    dry_run: bool,
+    manifest: &Manifest,
+) -> Result<usize> {
+    let mut repos = catalog.repositories().await;
+
+    if dry_run {
+        let expired = repos
+            .parquet_files()
+            .list_to_flag_by_retention() // read-only
+            .await
+            .context(ListingSnafu)?;
+        for file in &expired {
+            debug!(
+                parquet_file_id = %file.id,
+                object_store_id = %file.object_store_id,
+                "dry run: would flag parquet file for deletion",
+            );
+            manifest
+                .record(ManifestEntry::parquet_file(
+                    file.object_store_id,
+                    "retention_expired",
+                ))
+                .await;
+        }
+        Ok(expired.len())
+    } else {
+        let flagged = repos
+            .parquet_files()
+            .flag_for_delete_by_retention() //read/write
+            .await
+            .context(FlaggingSnafu)?;
+        Ok(flagged.len())
+    }
+}
+
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
 pub enum Error {
+    #[snafu(display("Failed to list parquet files expired by retention policy"))]
+    Listing {
+        source: iox_catalog::interface::Error,
+    },
+
     #[snafu(display("Failed to flag parquet files for deletion by retention policy"))]
     Flagging {
         source: iox_catalog::interface::Error,
@@ -45,3 +82,79 @@ pub enum Error {
 }
 
 pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::create_parquet_file;
+    use iox_catalog::{interface::SoftDeletedRows, mem::MemCatalog};
+
+    #[tokio::test]
+    async fn flags_expired_files() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metric_registry));
+
+        // the file ends at the beginning of time, hence expires with any retention period
+        let file = create_parquet_file(&catalog).await;
+        {
+            let mut repos = catalog.repositories().await;
+            let namespace = repos
+                .namespaces()
+                .get_by_id(file.namespace_id, SoftDeletedRows::AllRows)
+                .await
+                .unwrap()
+                .unwrap();
+            repos
+                .namespaces()
+                .update_retention_period(&namespace.name, Some(60 * 60 * 1_000_000_000)) // 1 hour
+                .await
+                .unwrap();
+        }
+
+        // dry run does not touch the catalog, but reports the file in the manifest
+        let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+        assert_eq!(
+            flag_expired(&catalog, true, &Manifest::new(tx))
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            ManifestEntry::parquet_file(file.object_store_id, "retention_expired")
+        );
+        assert_to_delete(&catalog, &file, false).await;
+
+        assert_eq!(
+            flag_expired(&catalog, false, &Manifest::default())
+                .await
+                .unwrap(),
+            1
+        );
+        assert_to_delete(&catalog, &file, true).await;
+
+        // nothing left to flag
+        assert_eq!(
+            flag_expired(&catalog, true, &Manifest::default())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    async fn assert_to_delete(
+        catalog: &Arc<dyn Catalog>,
+        file: &data_types::ParquetFile,
+        to_delete: bool,
+    ) {
+        let got = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .get_by_object_store_id(file.object_store_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.to_delete.is_some(), to_delete);
+    }
+}
@@ -0,0 +1,42 @@
+use data_types::{ColumnId, ColumnSet, CompactionLevel, ParquetFile, ParquetFileParams, Timestamp};
+use iox_catalog::{
+    interface::Catalog,
+    test_helpers::{arbitrary_namespace, arbitrary_table},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Create a namespace, table and partition with a single parquet file in the `catalog`.
+pub(crate) async fn create_parquet_file(catalog: &Arc<dyn Catalog>) -> ParquetFile {
+    let mut repos = catalog.repositories().await;
+    let namespace = arbitrary_namespace(&mut *repos, "namespace_parquet_file_test").await;
+    let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+    let partition = repos
+        .partitions()
+        .create_or_get("one".into(), table.id)
+        .await
+        .unwrap();
+
+    let parquet_file_params = ParquetFileParams {
+        namespace_id: namespace.id,
+        table_id: partition.table_id,
+        partition_id: partition.id,
+        partition_hash_id: partition.hash_id().cloned(),
+        object_store_id: Uuid::new_v4(),
+        min_time: Timestamp::new(1),
+        max_time: Timestamp::new(10),
+        file_size_bytes: 1337,
+        row_count: 0,
+        compaction_level: CompactionLevel::Initial,
+        created_at: Timestamp::new(1),
+        column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+        max_l0_created_at: Timestamp::new(1),
+        tag_ranges: None,
+    };
+
+    repos
+        .parquet_files()
+        .create(parquet_file_params)
+        .await
+        .unwrap()
+}
@@ -18,6 +18,7 @@ fn main() -> Result<()> {
 ///
 /// Creates:
 ///
+/// - `influxdata.iox.audit.v1.rs`
 /// - `influxdata.iox.authz.v1.rs`
 /// - `influxdata.iox.catalog.v1.rs`
 /// - `influxdata.iox.compactor.v1.rs`
@@ -27,12 +28,19 @@ fn main() -> Result<()> {
 /// - `influxdata.iox.object_store.v1.rs`
 /// - `influxdata.iox.predicate.v1.rs`
 /// - `influxdata.iox.querier.v1.rs`
+/// - `influxdata.iox.router.v1.rs`
 /// - `influxdata.iox.schema.v1.rs`
 /// - `influxdata.iox.table.v1.rs`
 /// - `influxdata.iox.wal.v1.rs`
 /// - `influxdata.iox.write.v1.rs`
 /// - `influxdata.platform.storage.rs`
+/// - `opentelemetry.proto.collector.metrics.v1.rs`
+/// - `opentelemetry.proto.common.v1.rs`
+/// - `opentelemetry.proto.metrics.v1.rs`
+/// - `opentelemetry.proto.resource.v1.rs`
+/// - `prometheus.rs`
 fn generate_grpc_types(root: &Path) -> Result<()> {
+    let audit_path = root.join("influxdata/iox/audit/v1");
     let authz_path = root.join("influxdata/iox/authz/v1");
     let catalog_path = root.join("influxdata/iox/catalog/v1");
     let compactor_path = root.join("influxdata/iox/compactor/v1");
@@ -43,6 +51,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
     let partition_template_path = root.join("influxdata/iox/partition_template/v1");
     let predicate_path = root.join("influxdata/iox/predicate/v1");
     let querier_path = root.join("influxdata/iox/querier/v1");
+    let router_path = root.join("influxdata/iox/router/v1");
     let schema_path = root.join("influxdata/iox/schema/v1");
     let storage_errors_path = root.join("influxdata/platform/errors");
     let storage_path = root.join("influxdata/platform/storage");
@@ -50,6 +59,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
     let wal_path = root.join("influxdata/iox/wal/v1");
 
     let proto_files = vec![
+        audit_path.join("service.proto"),
         authz_path.join("authz.proto"),
         catalog_path.join("parquet_file.proto"),
         catalog_path.join("service.proto"),
@@ -62,12 +72,19 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         object_store_path.join("service.proto"),
         partition_template_path.join("template.proto"),
         predicate_path.join("predicate.proto"),
+        querier_path.join("debug.proto"),
         querier_path.join("flight.proto"),
+        router_path.join("write.proto"),
         root.join("google/longrunning/operations.proto"),
         root.join("google/rpc/error_details.proto"),
         root.join("google/rpc/status.proto"),
         root.join("grpc/health/v1/service.proto"),
         root.join("influxdata/pbdata/v1/influxdb_pb_data_protocol.proto"),
+        root.join("opentelemetry/proto/collector/metrics/v1/metrics_service.proto"),
+        root.join("opentelemetry/proto/common/v1/common.proto"),
+        root.join("opentelemetry/proto/metrics/v1/metrics.proto"),
+        root.join("opentelemetry/proto/resource/v1/resource.proto"),
+        root.join("prometheus/remote.proto"),
         schema_path.join("service.proto"),
         storage_errors_path.join("errors.proto"),
         storage_path.join("predicate.proto"),
@@ -49,6 +49,16 @@ pub mod influxdata {
     }
 
     pub mod iox {
+        pub mod audit {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.audit.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.audit.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod authz {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.authz.v1.rs"));
@@ -155,6 +165,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod router {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.router.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.router.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod schema {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.schema.v1.rs"));
@@ -191,6 +211,54 @@ pub mod influxdata {
     }
 }
 
+/// The OpenTelemetry protocol (OTLP) metrics export service.
+pub mod opentelemetry {
+    pub mod proto {
+        pub mod collector {
+            pub mod metrics {
+                pub mod v1 {
+                    include!(concat!(
+                        env!("OUT_DIR"),
+                        "/opentelemetry.proto.collector.metrics.v1.rs"
+                    ));
+                }
+            }
+        }
+
+        pub mod common {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.common.v1.rs"
+                ));
+            }
+        }
+
+        pub mod metrics {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.metrics.v1.rs"
+                ));
+            }
+        }
+
+        pub mod resource {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/opentelemetry.proto.resource.v1.rs"
+                ));
+            }
+        }
+    }
+}
+
+/// The Prometheus remote-write protocol.
+pub mod prometheus {
+    include!(concat!(env!("OUT_DIR"), "/prometheus.rs"));
+}
+
 // Needed because of https://github.com/hyperium/tonic/issues/471
 pub mod grpc {
     pub mod health {
@@ -1,11 +1,22 @@
-use futures_util::TryStreamExt;
+use bytes::Bytes;
+use futures_util::{stream::BoxStream, StreamExt, TryStreamExt};
 use influxdb_iox_client::{
-    catalog::{self, generated_types::ParquetFile},
+    catalog::{
+        self,
+        generated_types::{GetTableExportResponse, ParquetFile, Partition},
+    },
     connection::Connection,
     store,
 };
-use observability_deps::tracing::{debug, info};
-use std::path::{Path, PathBuf};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use object_store::DynObjectStore;
+use observability_deps::tracing::info;
+use parquet_file::ParquetFilePath;
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use thiserror::Error;
 use tokio::{
     fs::{self, File, OpenOptions},
@@ -13,6 +24,8 @@ use tokio::{
 };
 use tokio_util::compat::FuturesAsyncReadCompatExt;
 
+use super::manifest::{ExportManifest, ManifestParquetFile, ManifestPartition, MANIFEST_FILE_NAME};
+
 #[derive(Debug, Error)]
 pub enum ExportError {
     #[error("JSON Serialization error: {0}")]
@@ -21,32 +34,184 @@ pub enum ExportError {
     #[error("IOx request failed: {0}")]
     Client(#[from] influxdb_iox_client::error::Error),
 
+    #[error("Catalog error: {0}")]
+    Catalog(#[from] iox_catalog::interface::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("{0} not found")]
+    NotFound(String),
+
     #[error("Writing file: {0}")]
     File(#[from] std::io::Error),
 }
 
 type Result<T, E = ExportError> = std::result::Result<T, E>;
 
-/// Exports data from a remote IOx instance to local files.
+/// Where a [`TableExporter`] reads catalog records and Parquet files from.
+#[derive(Debug)]
+enum Source {
+    /// A remote IOx instance, read using the clients in [`influxdb_iox_client`] so that this can
+    /// be used to debug remote systems.
+    Remote {
+        catalog_client: catalog::Client,
+        store_client: store::Client,
+    },
+    /// A catalog and object store accessed directly.
+    Local {
+        catalog: Arc<dyn Catalog>,
+        object_store: Arc<DynObjectStore>,
+        /// Object store paths of the files to export, by object store id.
+        paths: HashMap<String, ParquetFilePath>,
+    },
+}
+
+impl Source {
+    /// Returns the partitions in the partition key range of the table and their Parquet files.
+    async fn table_export(
+        &mut self,
+        namespace_name: String,
+        table_name: String,
+        partition_key_start: Option<String>,
+        partition_key_end: Option<String>,
+    ) -> Result<GetTableExportResponse> {
+        match self {
+            Self::Remote { catalog_client, .. } => Ok(catalog_client
+                .get_table_export(
+                    namespace_name,
+                    table_name,
+                    partition_key_start,
+                    partition_key_end,
+                )
+                .await?),
+            Self::Local { catalog, paths, .. } => {
+                let mut repos = catalog.repositories().await;
+                let namespace = repos
+                    .namespaces()
+                    .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+                    .await?
+                    .ok_or_else(|| ExportError::NotFound(format!("Namespace {namespace_name}")))?;
+                let table = repos
+                    .tables()
+                    .get_by_namespace_and_name(namespace.id, &table_name)
+                    .await?
+                    .ok_or_else(|| ExportError::NotFound(format!("Table {table_name}")))?;
+
+                let partitions: Vec<_> = repos
+                    .partitions()
+                    .list_by_table_id(table.id)
+                    .await?
+                    .into_iter()
+                    .filter(|p| {
+                        let key = p.partition_key.inner();
+                        partition_key_start
+                            .as_deref()
+                            .map_or(true, |start| key >= start)
+                            && partition_key_end.as_deref().map_or(true, |end| key < end)
+                    })
+                    .collect();
+                let partition_ids: HashSet<_> = partitions.iter().map(|p| p.id).collect();
+
+                let parquet_files: Vec<_> = repos
+                    .parquet_files()
+                    .list_by_table_not_to_delete(table.id)
+                    .await?
+                    .into_iter()
+                    .filter(|f| partition_ids.contains(&f.partition_id))
+                    .collect();
+                for parquet_file in &parquet_files {
+                    paths.insert(
+                        parquet_file.object_store_id.to_string(),
+                        ParquetFilePath::from(parquet_file),
+                    );
+                }
+
+                Ok(GetTableExportResponse {
+                    namespace_id: namespace.id.get(),
+                    table_id: table.id.get(),
+                    partitions: partitions.into_iter().map(Partition::from).collect(),
+                    parquet_files: parquet_files.into_iter().map(ParquetFile::from).collect(),
+                })
+            }
+        }
+    }
+
+    /// Returns the contents of a Parquet file returned by [`Self::table_export`].
+    async fn parquet_file_data(
+        &mut self,
+        parquet_file: &ParquetFile,
+    ) -> Result<BoxStream<'static, io::Result<Bytes>>> {
+        let uuid = &parquet_file.object_store_id;
+        match self {
+            Self::Remote { store_client, .. } => Ok(store_client
+                .get_parquet_file_by_object_store_id(uuid.clone())
+                .await?
+                .map_ok(|res| Bytes::from(res.data))
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+                .boxed()),
+            Self::Local {
+                object_store,
+                paths,
+                ..
+            } => {
+                let path = paths
+                    .get(uuid)
+                    .ok_or_else(|| ExportError::NotFound(format!("Parquet file {uuid}")))?
+                    .object_store_path();
+                Ok(object_store
+                    .get(&path)
+                    .await?
+                    .into_stream()
+                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+                    .boxed())
+            }
+        }
+    }
+}
+
+/// Exports the Parquet files of a table, and the catalog metadata needed to import them again,
+/// to local files.
 ///
-/// Data is read using the clients in [`influxdb_iox_client`] (rather
-/// than the catalog) so that this can be used to debug remote systems.
+/// See `influxdb_iox debug build-catalog` for importing the exported files.
 #[derive(Debug)]
-pub struct RemoteExporter {
-    catalog_client: catalog::Client,
-    store_client: store::Client,
+pub struct TableExporter {
+    source: Source,
 
     /// Optional partition filter. If `Some(partition_id)`, only these
     /// files with that `partition_id` are downloaded.
     partition_filter: Option<i64>,
+
+    /// Optional partition key range. Only partitions with a key greater than or equal to the
+    /// start and less than the end are exported.
+    partition_key_start: Option<String>,
+    partition_key_end: Option<String>,
 }
 
-impl RemoteExporter {
-    pub fn new(connection: Connection) -> Self {
-        Self {
+impl TableExporter {
+    /// Export data from a remote IOx instance, using its catalog and store gRPC APIs.
+    pub fn remote(connection: Connection) -> Self {
+        Self::new(Source::Remote {
             catalog_client: catalog::Client::new(connection.clone()),
             store_client: store::Client::new(connection),
+        })
+    }
+
+    /// Export data directly from a catalog and object store.
+    pub fn local(catalog: Arc<dyn Catalog>, object_store: Arc<DynObjectStore>) -> Self {
+        Self::new(Source::Local {
+            catalog,
+            object_store,
+            paths: HashMap::new(),
+        })
+    }
+
+    fn new(source: Source) -> Self {
+        Self {
+            source,
             partition_filter: None,
+            partition_key_start: None,
+            partition_key_end: None,
         }
     }
 
@@ -59,12 +224,26 @@ impl RemoteExporter {
         self
     }
 
+    /// Specify that only files and metadata for partitions with keys
+    /// in `start..end` should be exported. Either end of the range may be
+    /// left open.
+    pub fn with_partition_key_range(mut self, start: Option<String>, end: Option<String>) -> Self {
+        info!(?start, ?end, "Filtering by partition key range");
+
+        self.partition_key_start = start;
+        self.partition_key_end = end;
+        self
+    }
+
     /// Exports all data and metadata for `table_name` in
     /// `namespace` to local files.
     ///
     /// If `output_directory` is specified, all files are written
     /// there otherwise files are exported to a directory named
     /// `table_name`.
+    ///
+    /// Once everything is exported, a manifest describing the export
+    /// is written to `export_manifest.json`.
     pub async fn export_table(
         &mut self,
         output_directory: Option<PathBuf>,
@@ -74,43 +253,72 @@ impl RemoteExporter {
         let output_directory = output_directory.unwrap_or_else(|| PathBuf::from(&table_name));
         fs::create_dir_all(&output_directory).await?;
 
-        let parquet_files = self
-            .catalog_client
-            .get_parquet_files_by_namespace_table(namespace_name, table_name)
+        let GetTableExportResponse {
+            namespace_id,
+            table_id,
+            partitions,
+            parquet_files,
+        } = self
+            .source
+            .table_export(
+                namespace_name.clone(),
+                table_name.clone(),
+                self.partition_key_start.clone(),
+                self.partition_key_end.clone(),
+            )
             .await?;
 
-        // Export the metadata for the table. Since all
-        // parquet_files are part of the same table, use the table_id
-        // from the first parquet_file
-        let table_id = parquet_files
-            .get(0)
-            .map(|parquet_file| parquet_file.table_id);
-        if let Some(table_id) = table_id {
-            self.export_table_metadata(&output_directory, table_id)
-                .await?;
-        }
+        let partitions: Vec<_> = partitions
+            .into_iter()
+            .filter(|partition| self.should_export(partition.id))
+            .collect();
+        let parquet_files: Vec<_> = parquet_files
+            .into_iter()
+            .filter(|parquet_file| self.should_export(parquet_file.partition_id))
+            .collect();
+
+        self.export_table_metadata(&output_directory, table_id, &partitions)
+            .await?;
 
         let num_parquet_files = parquet_files.len();
         println!("found {num_parquet_files} Parquet files, exporting...");
-        let indexed_parquet_file_metadata = parquet_files.into_iter().enumerate();
+        let indexed_parquet_file_metadata = parquet_files.iter().enumerate();
 
         for (index, parquet_file) in indexed_parquet_file_metadata {
-            if self.should_export(parquet_file.partition_id) {
-                self.export_parquet_file(
-                    &output_directory,
-                    index,
-                    num_parquet_files,
-                    &parquet_file,
-                )
+            self.export_parquet_file(&output_directory, index, num_parquet_files, parquet_file)
                 .await?;
-            } else {
-                debug!(
-                    "skipping file {} of {num_parquet_files} ({} does not match request)",
-                    index + 1,
-                    parquet_file.partition_id
-                );
-            }
         }
+
+        let manifest = ExportManifest {
+            namespace_name,
+            table_name,
+            namespace_id,
+            table_id,
+            partition_id: self.partition_filter,
+            partition_key_start: self.partition_key_start.clone(),
+            partition_key_end: self.partition_key_end.clone(),
+            partitions: partitions
+                .iter()
+                .map(|partition| ManifestPartition {
+                    id: partition.id,
+                    key: partition.key.clone(),
+                })
+                .collect(),
+            parquet_files: parquet_files
+                .iter()
+                .map(|parquet_file| ManifestParquetFile {
+                    file_name: parquet_file_name(parquet_file),
+                    object_store_id: parquet_file.object_store_id.clone(),
+                    partition_id: parquet_file.partition_id,
+                    min_time: parquet_file.min_time,
+                    max_time: parquet_file.max_time,
+                    file_size_bytes: parquet_file.file_size_bytes,
+                    row_count: parquet_file.row_count,
+                })
+                .collect(),
+        };
+        let manifest_json = serde_json::to_string_pretty(&manifest)?;
+        write_string_to_file(&manifest_json, &output_directory.join(MANIFEST_FILE_NAME)).await?;
         println!("Done.");
 
         Ok(())
@@ -132,15 +340,16 @@ impl RemoteExporter {
     /// table. Overwrites existing files, if any, to ensure it has the
     /// latest catalog information.
     ///
-    /// 1. `<output_directory>/table.<partition_id>.json`: pbjson
+    /// 1. `<output_directory>/table.<table_id>.json`: pbjson
     /// encoded data about the table (minimal now)
     ///
     /// 2. `<output_directory>/partition.<partition_id>.json`: pbjson
     /// encoded data for each partition
     async fn export_table_metadata(
-        &mut self,
+        &self,
         output_directory: &Path,
         table_id: i64,
+        partitions: &[Partition],
     ) -> Result<()> {
         // write table metadata
         //
@@ -152,25 +361,18 @@ impl RemoteExporter {
         write_string_to_file(table_json, &file_path).await?;
 
         // write partition metadata for the table
-        let partitions = self
-            .catalog_client
-            .get_partitions_by_table_id(table_id)
-            .await?;
-
         for partition in partitions {
             let partition_id = partition.id;
-            if self.should_export(partition_id) {
-                let partition_json = serde_json::to_string_pretty(&partition)?;
-                let filename = format!("partition.{partition_id}.json");
-                let file_path = output_directory.join(&filename);
-                write_string_to_file(&partition_json, &file_path).await?;
-            }
+            let partition_json = serde_json::to_string_pretty(&partition)?;
+            let filename = format!("partition.{partition_id}.json");
+            let file_path = output_directory.join(&filename);
+            write_string_to_file(&partition_json, &file_path).await?;
         }
 
         Ok(())
     }
 
-    /// Exports a remote ParquetFile to:
+    /// Exports a ParquetFile to:
     ///
     /// 1. `<output_directory>/<uuid>.parquet`: The parquet bytes
     ///
@@ -182,20 +384,17 @@ impl RemoteExporter {
         num_parquet_files: usize,
         parquet_file: &ParquetFile,
     ) -> Result<()> {
-        let uuid = &parquet_file.object_store_id;
-        let partition_id = parquet_file.partition_id;
         let file_size_bytes = parquet_file.file_size_bytes as u64;
+        let filename = parquet_file_name(parquet_file);
 
         // copy out the metadata as pbjson encoded data always (to
         // ensure we have the most up to date version)
         {
-            let filename = format!("{uuid}.{partition_id}.parquet.json");
-            let file_path = output_directory.join(&filename);
+            let file_path = output_directory.join(format!("{filename}.json"));
             let json = serde_json::to_string_pretty(&parquet_file)?;
             write_string_to_file(&json, &file_path).await?;
         }
 
-        let filename = format!("{uuid}.{partition_id}.parquet");
         let file_path = output_directory.join(&filename);
 
         if fs::metadata(&file_path)
@@ -214,11 +413,9 @@ impl RemoteExporter {
                     index + 1
                 );
                 let mut response = self
-                    .store_client
-                    .get_parquet_file_by_object_store_id(uuid.clone())
+                    .source
+                    .parquet_file_data(parquet_file)
                     .await?
-                    .map_ok(|res| res.data)
-                    .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
                     .into_async_read()
                     .compat();
                 let mut file = File::create(&file_path).await?;
@@ -230,6 +427,14 @@ impl RemoteExporter {
     }
 }
 
+/// The name of the exported Parquet file, `<uuid>.<partition_id>.parquet`
+fn parquet_file_name(parquet_file: &ParquetFile) -> String {
+    format!(
+        "{}.{}.parquet",
+        parquet_file.object_store_id, parquet_file.partition_id
+    )
+}
+
 /// writes the contents of a string to a file, overwriting the previous contents, if any
 async fn write_string_to_file(contents: &str, path: &Path) -> Result<()> {
     let mut file = OpenOptions::new()
@@ -1,6 +1,7 @@
 //! Utilities for importing catalog and data from files
 //! MORE COMING SOON: <https://github.com/influxdata/influxdb_iox/issues/7744>
 
+use super::manifest::{ExportManifest, MANIFEST_FILE_NAME};
 use bytes::Bytes;
 use data_types::{
     partition_template::{
@@ -20,6 +21,7 @@ use parquet_file::{
 };
 use std::{
     borrow::Cow,
+    collections::HashSet,
     io::Read,
     path::{Path, PathBuf},
     sync::Arc,
@@ -77,6 +79,14 @@ pub enum Error {
 
     #[error("Object store error: {0}")]
     ObjectStore(#[from] object_store::Error),
+
+    #[error("No export manifest found in {0:?}, the export may be incomplete")]
+    NoManifest(PathBuf),
+
+    #[error(
+        "Exported file {path:?} is missing or is not {expected_size} bytes, the export may be incomplete"
+    )]
+    IncompleteExport { path: PathBuf, expected_size: i64 },
 }
 
 impl Error {
@@ -89,11 +99,17 @@ impl Error {
 type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Represents the contents of a directory exported using
-/// [`RemoteExporter`]. This is a partial catalog snapshot.
+/// [`TableExporter`]. This is a partial catalog snapshot.
 ///
-/// [`RemoteExporter`]: crate::file::RemoteExporter
+/// [`TableExporter`]: crate::file::TableExporter
 #[derive(Debug, Default)]
 pub struct ExportedContents {
+    /// The directory that was read
+    dir_path: PathBuf,
+
+    /// The manifest written at the end of the export, if any
+    manifest_file: Option<PathBuf>,
+
     /// .parquet files
     parquet_files: Vec<PathBuf>,
 
@@ -112,6 +128,9 @@ pub struct ExportedContents {
     /// Decoded parquet metata found in the export
     /// Key is object_store_id, value is decoded metadata
     parquet_metadata: Vec<proto::ParquetFile>,
+
+    /// Decoded export manifest, if any
+    manifest: Option<ExportManifest>,
 }
 
 impl ExportedContents {
@@ -132,7 +151,10 @@ impl ExportedContents {
 
         debug!(?entries, "Directory contents");
 
-        let mut new_self = Self::default();
+        let mut new_self = Self {
+            dir_path: dir_path.into(),
+            ..Default::default()
+        };
 
         for entry in entries {
             let path = entry.path();
@@ -148,7 +170,9 @@ impl ExportedContents {
                 new_self.parquet_files.push(path)
             } else if extension == "json" {
                 let name = file_name(&path);
-                if name.starts_with("table.") {
+                if name == MANIFEST_FILE_NAME {
+                    new_self.manifest_file = Some(path);
+                } else if name.starts_with("table.") {
                     new_self.table_json_files.push(path);
                 } else if name.starts_with("partition") {
                     // names like "partitition.<id>.json"
@@ -205,6 +229,65 @@ impl ExportedContents {
             self.parquet_metadata.push(parquet_file);
         }
 
+        if let Some(path) = &self.manifest_file {
+            debug!(?path, "Reading export manifest");
+            let json = std::fs::read_to_string(path).map_err(|e| Error::Reading {
+                path: path.clone(),
+                e,
+            })?;
+
+            let manifest: ExportManifest =
+                serde_json::from_str(&json).map_err(|e| Error::Json {
+                    path: path.clone(),
+                    e,
+                })?;
+
+            // Only import the files of the export the manifest describes,
+            // not ones left behind in the directory by earlier exports
+            let exported: HashSet<_> = manifest
+                .parquet_files
+                .iter()
+                .map(|f| f.file_name.as_str())
+                .collect();
+            self.parquet_files.retain(|path| {
+                let listed = exported.contains(file_name(path).as_ref());
+                if !listed {
+                    warn!(?path, "IGNORING parquet file not listed in export manifest");
+                }
+                listed
+            });
+
+            self.manifest = Some(manifest);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the export this directory contains completed, i.e.
+    /// that it has an export manifest and every parquet file listed
+    /// in the manifest is present with the expected size.
+    pub fn check_complete(&self) -> Result<()> {
+        let Some(manifest) = &self.manifest else {
+            return Err(Error::NoManifest(self.dir_path.clone()));
+        };
+
+        for parquet_file in &manifest.parquet_files {
+            let path = self.dir_path.join(&parquet_file.file_name);
+            let size = std::fs::metadata(&path).map(|m| m.len()).ok();
+            if size != u64::try_from(parquet_file.file_size_bytes).ok() {
+                return Err(Error::IncompleteExport {
+                    path,
+                    expected_size: parquet_file.file_size_bytes,
+                });
+            }
+        }
+
+        info!(
+            namespace_name=%manifest.namespace_name,
+            table_name=%manifest.table_name,
+            n_files=manifest.parquet_files.len(),
+            "Export is complete"
+        );
         Ok(())
     }
 
@@ -259,6 +342,10 @@ fn file_name(p: &Path) -> Cow<'_, str> {
         .unwrap_or_else(|| Cow::Borrowed(""))
 }
 
+/// The maximum number of imported parquet files whose catalog entries are
+/// created in a single transaction.
+const CATALOG_BATCH_SIZE: usize = 500;
+
 /// Imports the contents of a [`ExportedContents`] into a catalog and
 /// object_store instance
 #[derive(Debug)]
@@ -288,8 +375,13 @@ impl RemoteImporter {
 
         let total_files = parquet_files.len();
         info!(%total_files, "Begin importing files");
+        let mut pending = Vec::with_capacity(CATALOG_BATCH_SIZE);
         for (files_done, file) in parquet_files.iter().enumerate() {
-            self.import_parquet(file).await?;
+            pending.push(self.import_parquet(file).await?);
+            if pending.len() >= CATALOG_BATCH_SIZE {
+                self.create_parquet_files(std::mem::take(&mut pending))
+                    .await?;
+            }
 
             // print a log message every 50 files
             if files_done % 50 == 0 {
@@ -298,12 +390,19 @@ impl RemoteImporter {
             }
         }
 
+        self.create_parquet_files(pending).await?;
+
         info!(%total_files, "Completed importing files");
         Ok(())
     }
 
-    // tries to import the specified parquet file into the catalog
-    async fn import_parquet(&self, file_path: &Path) -> Result<()> {
+    // tries to import the specified parquet file into the object store,
+    // creating the namespace, table and partition it belongs to in the
+    // catalog if needed.
+    //
+    // Returns the parameters of the catalog entry for the file, which is
+    // created by [`Self::create_parquet_files`].
+    async fn import_parquet(&self, file_path: &Path) -> Result<ParquetFileParams> {
         info!(?file_path, "Beginning Import");
 
         // step 1: figure out the location to write the parquet file in object store and do so
@@ -402,19 +501,6 @@ impl RemoteImporter {
             .await?;
 
         let object_store_id = parquet_params.object_store_id;
-        let parquet_file = repos.parquet_files().create(parquet_params).await;
-
-        match parquet_file {
-            Ok(parquet_file) => {
-                debug!(parquet_file_id=?parquet_file.id, "  Created parquet file entry {}", parquet_file.id);
-            }
-            Err(iox_catalog::interface::Error::FileExists { .. }) => {
-                warn!(%object_store_id, "parquet file already exists, skipping");
-            }
-            Err(e) => {
-                return Err(Error::Catalog(e));
-            }
-        };
 
         // Now copy the parquet files into the object store
         //let partition_id = TransitionPartitionId::Deprecated(partition.id);
@@ -431,6 +517,41 @@ impl RemoteImporter {
         self.object_store.put(&object_store_path, bytes).await?;
 
         info!(?file_path, %namespace_name, %object_store_path, %transition_partition_id, %table_id, "Successfully imported file");
+        Ok(parquet_params)
+    }
+
+    /// Create the catalog entries for a batch of imported parquet files in a
+    /// single transaction, skipping any files that already exist.
+    async fn create_parquet_files(&self, parquet_params: Vec<ParquetFileParams>) -> Result<()> {
+        if parquet_params.is_empty() {
+            return Ok(());
+        }
+
+        let mut repos = self.catalog.repositories().await;
+
+        let existing: HashSet<_> = repos
+            .parquet_files()
+            .exists_by_object_store_id_batch(
+                parquet_params.iter().map(|p| p.object_store_id).collect(),
+            )
+            .await?
+            .into_iter()
+            .collect();
+
+        let parquet_params = parquet_params
+            .into_iter()
+            .filter(|p| {
+                let exists = existing.contains(&p.object_store_id);
+                if exists {
+                    warn!(object_store_id=%p.object_store_id, "parquet file already exists, skipping");
+                }
+                !exists
+            })
+            .collect::<Vec<_>>();
+
+        let created = repos.parquet_files().create_many(parquet_params).await?;
+        debug!(n_files = created.len(), "Created parquet file entries");
+
         Ok(())
     }
 
@@ -590,6 +711,7 @@ impl RemoteImporter {
                 created_at: Timestamp::new(proto_parquet_file.created_at),
                 column_set,
                 max_l0_created_at: Timestamp::new(proto_parquet_file.max_l0_created_at),
+                tag_ranges: None,
             }
         } else {
             warn!("Could not read parquet file metadata, reconstructing based on encoded metadata");
@@ -614,6 +736,7 @@ impl RemoteImporter {
                 created_at,
                 column_set,
                 max_l0_created_at: created_at,
+                tag_ranges: None,
             }
         };
         debug!(?params, "Created ParquetFileParams");
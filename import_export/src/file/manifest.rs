@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the file describing a completed export, written last.
+pub(crate) const MANIFEST_FILE_NAME: &str = "export_manifest.json";
+
+/// Description of a completed export, written to `export_manifest.json`
+/// in the output directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ExportManifest {
+    pub(crate) namespace_name: String,
+    pub(crate) table_name: String,
+    pub(crate) namespace_id: i64,
+    pub(crate) table_id: i64,
+    /// The partition id filter of the export, if any
+    pub(crate) partition_id: Option<i64>,
+    /// The partition key range of the export, if any
+    pub(crate) partition_key_start: Option<String>,
+    pub(crate) partition_key_end: Option<String>,
+    pub(crate) partitions: Vec<ManifestPartition>,
+    pub(crate) parquet_files: Vec<ManifestParquetFile>,
+}
+
+/// A partition in an [`ExportManifest`]
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ManifestPartition {
+    pub(crate) id: i64,
+    pub(crate) key: String,
+}
+
+/// A Parquet file in an [`ExportManifest`]
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ManifestParquetFile {
+    /// The name of the exported file in the output directory
+    pub(crate) file_name: String,
+    pub(crate) object_store_id: String,
+    pub(crate) partition_id: i64,
+    pub(crate) min_time: i64,
+    pub(crate) max_time: i64,
+    pub(crate) file_size_bytes: i64,
+    pub(crate) row_count: i64,
+}
@@ -1,6 +1,7 @@
 /// Code to import/export files
 mod export;
 mod import;
+mod manifest;
 
-pub use export::{ExportError, RemoteExporter};
+pub use export::{ExportError, TableExporter};
 pub use import::{Error, ExportedContents, RemoteImporter};
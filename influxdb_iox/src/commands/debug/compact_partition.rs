@@ -0,0 +1,35 @@
+//! This module implements the `compact-partition` CLI command
+
+use influxdb_iox_client::{compactor, connection::Connection};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Request compaction of a partition ahead of all other partitions.
+///
+/// This must be sent to a compactor.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The ID of the partition to compact
+    partition_id: i64,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = compactor::Client::new(connection);
+    let queued = client.compact_partition(config.partition_id).await?;
+
+    if queued {
+        println!("Partition {} queued for compaction", config.partition_id);
+    } else {
+        println!(
+            "Partition {} is already queued for compaction",
+            config.partition_id
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! This module implements the `compaction-history` CLI command
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{
+    compactor::{self, generated_types::CompactionHistory},
+    connection::Connection,
+};
+use iox_time::Time;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Show the recorded compaction commits of a partition, oldest first.
+///
+/// Each commit of a compaction job is recorded by the compaction scheduler. This helps to find
+/// out why a partition keeps getting recompacted.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The ID of the partition
+    partition_id: i64,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = compactor::Client::new(connection);
+    let history = client.compaction_history(config.partition_id).await?;
+
+    if history.is_empty() {
+        println!(
+            "No compaction history recorded for partition {}",
+            config.partition_id
+        );
+    } else {
+        println!("{}", create_table(&history));
+    }
+
+    Ok(())
+}
+
+/// Turn compaction history records into a table
+fn create_table(history: &[CompactionHistory]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "committed_at",
+        "job_id",
+        "input_files",
+        "input_bytes",
+        "upgraded_files",
+        "output_files",
+        "output_bytes",
+        "target_level",
+        "duration_ms",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    for entry in history {
+        table.add_row(vec![
+            Cell::new(Time::from_timestamp_nanos(entry.committed_at).to_rfc3339()),
+            Cell::new(&entry.job_id),
+            Cell::new(entry.num_input_files.to_string()),
+            Cell::new(entry.input_bytes.to_string()),
+            Cell::new(entry.num_upgraded_files.to_string()),
+            Cell::new(entry.num_output_files.to_string()),
+            Cell::new(entry.output_bytes.to_string()),
+            Cell::new(entry.target_level.to_string()),
+            Cell::new(entry.duration_ms.to_string()),
+        ]);
+    }
+
+    table
+}
@@ -3,6 +3,8 @@ use influxdb_iox_client::connection::Connection;
 use snafu::prelude::*;
 
 mod build_catalog;
+mod compact_partition;
+mod compaction_history;
 mod parquet_to_lp;
 mod print_cpu;
 mod schema;
@@ -19,6 +21,14 @@ pub enum Error {
     #[snafu(display("Error in build_catalog subcommand: {}", source))]
     BuildCatalog { source: build_catalog::Error },
 
+    #[snafu(context(false))]
+    #[snafu(display("Error in compact-partition subcommand: {}", source))]
+    CompactPartition { source: compact_partition::Error },
+
+    #[snafu(context(false))]
+    #[snafu(display("Error in compaction-history subcommand: {}", source))]
+    CompactionHistory { source: compaction_history::Error },
+
     #[snafu(context(false))]
     #[snafu(display("Error in parquet_to_lp subcommand: {}", source))]
     ParquetToLp { source: parquet_to_lp::Error },
@@ -66,6 +76,12 @@ enum Command {
     #[clap(verbatim_doc_comment)]
     BuildCatalog(build_catalog::Config),
 
+    /// Ask a compactor to compact a partition ahead of all other partitions
+    CompactPartition(compact_partition::Config),
+
+    /// Show the recorded compaction commits of a partition
+    CompactionHistory(compaction_history::Config),
+
     /// Convert IOx Parquet files back into line protocol format
     ParquetToLp(parquet_to_lp::Config),
 
@@ -88,6 +104,14 @@ where
             schema::command(connection, config).await?
         }
         Command::BuildCatalog(config) => build_catalog::command(config).await?,
+        Command::CompactPartition(config) => {
+            let connection = connection().await;
+            compact_partition::command(connection, config).await?
+        }
+        Command::CompactionHistory(config) => {
+            let connection = connection().await;
+            compaction_history::command(connection, config).await?
+        }
         Command::ParquetToLp(config) => parquet_to_lp::command(config).await?,
         Command::SkippedCompactions(config) => {
             let connection = connection().await;
@@ -1,6 +1,17 @@
 //! This module implements the `schema` CLI command
 
-use influxdb_iox_client::{connection::Connection, schema};
+use std::collections::{BTreeMap, BTreeSet};
+
+use bytes::BytesMut;
+use data_types::ColumnType;
+use futures::TryStreamExt;
+use influxdb_iox_client::{
+    catalog::{self, generated_types::ParquetFile},
+    connection::Connection,
+    schema::{self, generated_types::column_schema},
+    store,
+};
+use parquet_file::metadata::IoxParquetMetaData;
 use thiserror::Error;
 
 #[allow(clippy::enum_variant_names)]
@@ -11,6 +22,21 @@ pub enum Error {
 
     #[error("Client error: {0}")]
     ClientError(#[from] influxdb_iox_client::error::Error),
+
+    #[error("Table {0} not found")]
+    TableNotFound(String),
+
+    #[error("Column {column} has an unknown type in the catalog")]
+    UnknownColumnType { column: String },
+
+    #[error("Cannot read the schema of parquet file {object_store_id}: {source}")]
+    ParquetSchema {
+        object_store_id: String,
+        source: parquet_file::metadata::Error,
+    },
+
+    #[error("Parquet file {0} has no metadata")]
+    NoParquetMetadata(String),
 }
 
 /// Various commands for catalog schema inspection
@@ -28,11 +54,31 @@ struct Get {
     namespace: String,
 }
 
+/// Compare the catalog schema of a table with the schemas of its parquet files
+#[derive(Debug, clap::Parser)]
+struct Diff {
+    /// The name of the namespace the table is in
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table
+    #[clap(action)]
+    table: String,
+
+    /// Only check this many parquet files. Every file is downloaded in full to read its schema.
+    #[clap(long, action)]
+    max_files: Option<usize>,
+}
+
 /// All possible subcommands for catalog
 #[derive(Debug, clap::Parser)]
 enum Command {
     /// Fetch schema for a namespace
     Get(Get),
+
+    /// Print the discrepancies between the catalog schema of a table and the schemas embedded in
+    /// its parquet files, e.g. to find out why a query can't see a column
+    Diff(Diff),
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
@@ -41,9 +87,338 @@ pub async fn command(connection: Connection, config: Config) -> Result<(), Error
             let mut client = schema::Client::new(connection);
             let schema = client.get_schema(&command.namespace).await?;
             println!("{}", serde_json::to_string_pretty(&schema)?);
+        }
+        Command::Diff(command) => {
+            diff(connection, command).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
 
     Ok(())
 }
+
+/// The schema of a parquet file, as recorded in the catalog and as embedded in the file
+#[derive(Debug)]
+struct FileSchema {
+    object_store_id: String,
+    /// IDs of the columns the catalog records for the file
+    column_set: Vec<i64>,
+    /// Columns of the schema embedded in the file
+    columns: BTreeMap<String, ColumnType>,
+}
+
+/// A difference between the catalog schema of a table and its parquet files
+#[derive(Debug, PartialEq, Eq)]
+enum Discrepancy {
+    /// The file has a column that is not in the catalog
+    NotInCatalog {
+        object_store_id: String,
+        column: String,
+        file_type: ColumnType,
+    },
+    /// The file has a column with a different type than in the catalog
+    TypeConflict {
+        object_store_id: String,
+        column: String,
+        catalog_type: ColumnType,
+        file_type: ColumnType,
+    },
+    /// The catalog records a column for the file that the file does not have
+    NotInFile {
+        object_store_id: String,
+        column: String,
+    },
+    /// The catalog records a column ID for the file that is not a column of the table
+    UnknownColumnId { object_store_id: String, id: i64 },
+    /// A column of the catalog is in none of the files, e.g. because its data has not been
+    /// persisted yet
+    NotInAnyFile { column: String },
+}
+
+impl std::fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotInCatalog {
+                object_store_id,
+                column,
+                file_type,
+            } => write!(
+                f,
+                "file {object_store_id}: column {column:?} ({file_type}) is not in the catalog"
+            ),
+            Self::TypeConflict {
+                object_store_id,
+                column,
+                catalog_type,
+                file_type,
+            } => write!(
+                f,
+                "file {object_store_id}: column {column:?} is {file_type} in the file \
+                 but {catalog_type} in the catalog"
+            ),
+            Self::NotInFile {
+                object_store_id,
+                column,
+            } => write!(
+                f,
+                "file {object_store_id}: the catalog records column {column:?} for the file \
+                 but the file does not have it"
+            ),
+            Self::UnknownColumnId {
+                object_store_id,
+                id,
+            } => write!(
+                f,
+                "file {object_store_id}: the catalog records column ID {id} for the file \
+                 but the table has no such column"
+            ),
+            Self::NotInAnyFile { column } => write!(
+                f,
+                "column {column:?} is in the catalog but in none of the checked files"
+            ),
+        }
+    }
+}
+
+async fn diff(connection: Connection, command: Diff) -> Result<(), Error> {
+    let Diff {
+        namespace,
+        table,
+        max_files,
+    } = command;
+
+    let mut schema_client = schema::Client::new(connection.clone());
+    let mut catalog_client = catalog::Client::new(connection.clone());
+    let mut store_client = store::Client::new(connection);
+
+    let namespace_schema = schema_client.get_schema(&namespace).await?;
+    let table_schema = namespace_schema
+        .tables
+        .get(&table)
+        .ok_or_else(|| Error::TableNotFound(table.clone()))?;
+    let mut catalog_columns = BTreeMap::new();
+    for (name, column) in &table_schema.columns {
+        let column_type = column_schema::ColumnType::from_i32(column.column_type)
+            .and_then(|t| ColumnType::try_from(t).ok())
+            .ok_or_else(|| Error::UnknownColumnType {
+                column: name.clone(),
+            })?;
+        catalog_columns.insert(name.clone(), (column.id, column_type));
+    }
+
+    let parquet_files = catalog_client
+        .get_parquet_files_by_namespace_table(&namespace, &table)
+        .await?;
+    // files flagged for deletion are no longer queried, so their schemas don't matter
+    let parquet_files = parquet_files
+        .into_iter()
+        .filter(|f| f.to_delete == 0)
+        .take(max_files.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    let mut files = Vec::with_capacity(parquet_files.len());
+    for parquet_file in parquet_files {
+        files.push(read_file_schema(&mut store_client, parquet_file).await?);
+    }
+
+    let discrepancies = diff_schemas(&catalog_columns, &files);
+
+    println!(
+        "Compared {} catalog columns of table {table:?} with {} parquet files",
+        catalog_columns.len(),
+        files.len()
+    );
+    if discrepancies.is_empty() {
+        println!("No discrepancies found");
+    }
+    for discrepancy in discrepancies {
+        println!("{discrepancy}");
+    }
+
+    Ok(())
+}
+
+/// Download a parquet file and read the schema embedded in it
+async fn read_file_schema(
+    store_client: &mut store::Client,
+    parquet_file: ParquetFile,
+) -> Result<FileSchema, Error> {
+    let ParquetFile {
+        object_store_id,
+        column_set,
+        ..
+    } = parquet_file;
+
+    let mut data = BytesMut::new();
+    let mut stream = store_client
+        .get_parquet_file_by_object_store_id(object_store_id.clone())
+        .await?;
+    while let Some(response) = stream
+        .try_next()
+        .await
+        .map_err(influxdb_iox_client::error::Error::from)?
+    {
+        data.extend_from_slice(&response.data);
+    }
+
+    let schema = IoxParquetMetaData::from_file_bytes(data.freeze())
+        .and_then(|metadata| metadata.map(|m| m.decode()).transpose())
+        .and_then(|decoded| decoded.map(|d| d.read_schema()).transpose())
+        .map_err(|source| Error::ParquetSchema {
+            object_store_id: object_store_id.clone(),
+            source,
+        })?
+        .ok_or_else(|| Error::NoParquetMetadata(object_store_id.clone()))?;
+
+    let columns = schema
+        .iter()
+        .map(|(influx_type, field)| (field.name().clone(), ColumnType::from(influx_type)))
+        .collect();
+
+    Ok(FileSchema {
+        object_store_id,
+        column_set,
+        columns,
+    })
+}
+
+/// Compare the columns of a table in the catalog, by name, with the schemas of its files
+fn diff_schemas(
+    catalog_columns: &BTreeMap<String, (i64, ColumnType)>,
+    files: &[FileSchema],
+) -> Vec<Discrepancy> {
+    let names_by_id = catalog_columns
+        .iter()
+        .map(|(name, (id, _))| (*id, name))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut discrepancies = vec![];
+    let mut seen = BTreeSet::new();
+    for file in files {
+        for (column, file_type) in &file.columns {
+            seen.insert(column);
+            match catalog_columns.get(column) {
+                None => discrepancies.push(Discrepancy::NotInCatalog {
+                    object_store_id: file.object_store_id.clone(),
+                    column: column.clone(),
+                    file_type: *file_type,
+                }),
+                Some((_, catalog_type)) if catalog_type != file_type => {
+                    discrepancies.push(Discrepancy::TypeConflict {
+                        object_store_id: file.object_store_id.clone(),
+                        column: column.clone(),
+                        catalog_type: *catalog_type,
+                        file_type: *file_type,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for id in &file.column_set {
+            match names_by_id.get(id) {
+                None => discrepancies.push(Discrepancy::UnknownColumnId {
+                    object_store_id: file.object_store_id.clone(),
+                    id: *id,
+                }),
+                Some(column) if !file.columns.contains_key(*column) => {
+                    discrepancies.push(Discrepancy::NotInFile {
+                        object_store_id: file.object_store_id.clone(),
+                        column: (*column).clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    discrepancies.extend(
+        catalog_columns
+            .keys()
+            .filter(|column| !seen.contains(column))
+            .map(|column| Discrepancy::NotInAnyFile {
+                column: column.clone(),
+            }),
+    );
+
+    discrepancies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_schemas() {
+        let catalog_columns = BTreeMap::from([
+            ("host".to_string(), (1, ColumnType::Tag)),
+            ("usage".to_string(), (2, ColumnType::F64)),
+            ("time".to_string(), (3, ColumnType::Time)),
+            ("new_field".to_string(), (4, ColumnType::Bool)),
+        ]);
+        let file = |id: &str, column_set: Vec<i64>, columns: &[(&str, ColumnType)]| FileSchema {
+            object_store_id: id.to_string(),
+            column_set,
+            columns: columns
+                .iter()
+                .map(|(name, t)| (name.to_string(), *t))
+                .collect(),
+        };
+        let files = vec![
+            file(
+                "a",
+                vec![1, 2, 3],
+                &[
+                    ("host", ColumnType::Tag),
+                    ("usage", ColumnType::F64),
+                    ("time", ColumnType::Time),
+                ],
+            ),
+            file(
+                "b",
+                vec![1, 2, 3, 42],
+                &[
+                    ("host", ColumnType::Tag),
+                    ("usage", ColumnType::I64),
+                    ("region", ColumnType::Tag),
+                ],
+            ),
+        ];
+
+        assert_eq!(
+            diff_schemas(&catalog_columns, &files),
+            vec![
+                Discrepancy::NotInCatalog {
+                    object_store_id: "b".to_string(),
+                    column: "region".to_string(),
+                    file_type: ColumnType::Tag,
+                },
+                Discrepancy::TypeConflict {
+                    object_store_id: "b".to_string(),
+                    column: "usage".to_string(),
+                    catalog_type: ColumnType::F64,
+                    file_type: ColumnType::I64,
+                },
+                Discrepancy::NotInFile {
+                    object_store_id: "b".to_string(),
+                    column: "time".to_string(),
+                },
+                Discrepancy::UnknownColumnId {
+                    object_store_id: "b".to_string(),
+                    id: 42,
+                },
+                Discrepancy::NotInAnyFile {
+                    column: "new_field".to_string(),
+                },
+            ]
+        );
+
+        // the first file matches the catalog
+        assert_eq!(
+            diff_schemas(&catalog_columns, &files[..1]),
+            vec![Discrepancy::NotInAnyFile {
+                column: "new_field".to_string(),
+            }]
+        );
+    }
+}
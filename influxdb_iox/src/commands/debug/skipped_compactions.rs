@@ -76,6 +76,9 @@ fn create_table(skipped_compactions: &[SkippedCompaction]) -> Result<Table, Erro
         "limit_bytes",
         "num_files",
         "limit_num_files",
+        "error_kind",
+        "num_failures",
+        "total_bytes",
     ]
     .into_iter()
     .map(Cell::new)
@@ -85,6 +88,7 @@ fn create_table(skipped_compactions: &[SkippedCompaction]) -> Result<Table, Erro
     for skipped_compaction in skipped_compactions {
         let timestamp = Time::from_timestamp(skipped_compaction.skipped_at, 0)
             .ok_or(Error::InvalidTimestamp(skipped_compaction.skipped_at))?;
+        let error = skipped_compaction.error.as_ref();
 
         table.add_row(vec![
             Cell::new(skipped_compaction.partition_id.to_string()),
@@ -94,6 +98,13 @@ fn create_table(skipped_compactions: &[SkippedCompaction]) -> Result<Table, Erro
             Cell::new(skipped_compaction.limit_bytes.to_string()),
             Cell::new(skipped_compaction.num_files.to_string()),
             Cell::new(skipped_compaction.limit_num_files.to_string()),
+            Cell::new(error.map(|e| e.error_kind.as_str()).unwrap_or_default()),
+            Cell::new(
+                error
+                    .map(|e| e.num_failures.to_string())
+                    .unwrap_or_default(),
+            ),
+            Cell::new(error.map(|e| e.total_bytes.to_string()).unwrap_or_default()),
         ]);
     }
 
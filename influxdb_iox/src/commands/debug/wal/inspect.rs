@@ -1,7 +1,10 @@
 //! A module providing a CLI command to inspect the contents of a WAL file.
-use std::{io::Write, ops::RangeInclusive, path::PathBuf};
+use std::{fmt::Display, io::Write, ops::RangeInclusive, path::PathBuf};
 
+use data_types::TableId;
+use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op as WalOp;
 use itertools::Itertools;
+use prost::Message;
 use wal::SequencedWalOp;
 
 use super::Error;
@@ -17,6 +20,79 @@ pub struct Config {
     /// within the range (inclusive) will be displayed
     #[clap(long, short, value_parser = parse_sequence_number_range)]
     sequence_number_range: Option<RangeInclusive<u64>>,
+
+    /// The format to display the inspected entries in
+    #[clap(long, short, value_enum, default_value_t = OutputFormat::Debug)]
+    format: OutputFormat,
+}
+
+/// The formats WAL entries can be displayed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum OutputFormat {
+    /// The full contents of each entry, including the encoded data
+    Debug,
+    /// A single line of metadata (sequence number, table ID, row count and
+    /// encoded size) per table in each entry
+    Summary,
+}
+
+impl OutputFormat {
+    fn separator(&self) -> &'static str {
+        match self {
+            Self::Debug => ",\n",
+            Self::Summary => "\n",
+        }
+    }
+}
+
+/// A [`Display`] implementation printing the metadata of a [`SequencedWalOp`],
+/// one line per table it affects.
+struct Summary<'a>(&'a SequencedWalOp);
+
+impl<'a> Display for Summary<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sequence_number = |table_id: i64| {
+            self.0
+                .table_write_sequence_numbers
+                .get(&TableId::new(table_id))
+                .map_or_else(|| "unknown".to_string(), ToString::to_string)
+        };
+
+        match &self.0.op {
+            WalOp::Write(w) => {
+                let lines = w.table_batches.iter().format_with("\n", |batch, f| {
+                    f(&format_args!(
+                        "op=write sequence_number={} namespace_id={} table_id={} \
+                        partition_key={:?} rows={} size_bytes={}",
+                        sequence_number(batch.table_id),
+                        w.database_id,
+                        batch.table_id,
+                        w.partition_key,
+                        batch.row_count,
+                        batch.encoded_len(),
+                    ))
+                });
+                write!(f, "{lines}")
+            }
+            WalOp::Delete(d) => write!(
+                f,
+                "op=delete namespace_id={} table_name={:?} size_bytes={}",
+                d.database_id,
+                d.table_name,
+                d.encoded_len(),
+            ),
+            WalOp::Persist(p) => write!(
+                f,
+                "op=persist sequence_number={} namespace_id={} table_id={} partition_id={} \
+                parquet_file_uuid={}",
+                sequence_number(p.table_id),
+                p.namespace_id,
+                p.table_id,
+                p.partition_id,
+                p.parquet_file_uuid,
+            ),
+        }
+    }
 }
 
 fn parse_sequence_number_range(s: &str) -> Result<RangeInclusive<u64>, String> {
@@ -44,11 +120,17 @@ pub fn command(config: Config) -> Result<(), Error> {
     let reader = wal::ClosedSegmentFileReader::from_path(&config.input)
         .map_err(Error::UnableToReadWalFile)?;
 
-    inspect(config.sequence_number_range, &mut std::io::stdout(), reader)
+    inspect(
+        config.sequence_number_range,
+        config.format,
+        &mut std::io::stdout(),
+        reader,
+    )
 }
 
 fn inspect<W, R>(
     sequence_number_range: Option<RangeInclusive<u64>>,
+    format: OutputFormat,
     output: &mut W,
     reader: R,
 ) -> Result<(), Error>
@@ -67,8 +149,11 @@ where
                     .any(|seq| range.contains(seq))
             })
         })
-        .format_with(",\n", |op, f| match op {
-            Ok(op) => f(&format_args!("{:#?}", op)),
+        .format_with(format.separator(), |op, f| match op {
+            Ok(op) => match format {
+                OutputFormat::Debug => f(&format_args!("{:#?}", op)),
+                OutputFormat::Summary => f(&Summary(&op)),
+            },
             Err(e) => {
                 let err_string = e.to_string();
                 inspect_errors.push(e);
@@ -89,8 +174,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use data_types::TableId;
-    use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op as WalOp;
+    use generated_types::influxdata::pbdata::v1::{DatabaseBatch, TableBatch};
     use proptest::{prelude::*, prop_assert};
 
     use super::*;
@@ -108,6 +192,7 @@ mod tests {
 
         inspect(
             Some(RangeInclusive::new(2, 3)),
+            OutputFormat::Debug,
             &mut sink,
             [
                 Ok(vec![
@@ -145,6 +230,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_summary_format() {
+        let batch = |table_id, row_count| TableBatch {
+            table_id,
+            row_count,
+            ..Default::default()
+        };
+        let op = SequencedWalOp {
+            table_write_sequence_numbers: [(TableId::new(1), 7), (TableId::new(2), 8)].into(),
+            op: WalOp::Write(DatabaseBatch {
+                database_id: 42,
+                partition_key: "2023-07-01".to_string(),
+                table_batches: vec![batch(1, 3), batch(2, 5)],
+            }),
+        };
+
+        let mut sink = Vec::<u8>::new();
+        inspect(
+            None,
+            OutputFormat::Summary,
+            &mut sink,
+            [Ok(vec![op])].into_iter(),
+        )
+        .expect("should inspect entries given without error");
+
+        let results = String::from_utf8(sink).expect("failed to recover string from write sink");
+        let lines = results.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(
+            "op=write sequence_number=7 namespace_id=42 table_id=1 \
+            partition_key=\"2023-07-01\" rows=3 size_bytes="
+        ));
+        assert!(lines[1].starts_with(
+            "op=write sequence_number=8 namespace_id=42 table_id=2 \
+            partition_key=\"2023-07-01\" rows=5 size_bytes="
+        ));
+    }
+
     proptest! {
         #[test]
         fn test_sequence_number_range_parsing(a in any::<u64>(), b in any::<u64>()) {
@@ -0,0 +1,109 @@
+//! This module implements the `export` CLI command
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use import_export::file::TableExporter;
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::process_info::setup_metric_registry;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Exporting: {0}")]
+    Export(#[from] import_export::file::ExportError),
+}
+
+/// Export data directly from the catalog and object store.
+///
+/// To export from a running IOx instance through its API instead, use
+/// `influxdb_iox remote store get-table`.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Export the Parquet files and catalog metadata of a table into a local directory.
+///
+/// The catalog metadata of the exported files is written next to them, and an
+/// `export_manifest.json` listing everything that was exported is written once the
+/// export completed. See `influxdb_iox debug build-catalog` to create a local catalog
+/// from these files.
+#[derive(Debug, clap::Parser)]
+struct Table {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store_config: ObjectStoreConfig,
+
+    /// The namespace to export the Parquet files for
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to export the Parquet files for
+    #[clap(action)]
+    table: String,
+
+    /// If specified, only files from the specified partition are exported
+    #[clap(action, long)]
+    partition_id: Option<i64>,
+
+    /// If specified, only files from partitions with a key greater than or equal to this key are
+    /// exported
+    #[clap(action, long)]
+    partition_key_start: Option<String>,
+
+    /// If specified, only files from partitions with a key less than this key are exported
+    #[clap(action, long)]
+    partition_key_end: Option<String>,
+
+    /// The output directory to use. If not specified, files will be placed in a directory named
+    /// after the table in the current working directory.
+    #[clap(action, long)]
+    output_dir: Option<PathBuf>,
+}
+
+/// All possible subcommands for export
+#[derive(Debug, clap::Parser)]
+enum Command {
+    Table(Table),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Table(Table {
+            catalog_dsn,
+            object_store_config,
+            namespace,
+            table,
+            partition_id,
+            partition_key_start,
+            partition_key_end,
+            output_dir,
+        }) => {
+            let metrics = setup_metric_registry();
+            let catalog = catalog_dsn.get_catalog("cli", metrics).await?;
+            let object_store = make_object_store(&object_store_config)?;
+
+            let mut exporter = TableExporter::local(catalog, object_store)
+                .with_partition_key_range(partition_key_start, partition_key_end);
+            if let Some(partition_id) = partition_id {
+                exporter = exporter.with_partition_filter(partition_id);
+            }
+            exporter.export_table(output_dir, namespace, table).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! This module implements the `import` CLI command
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_object_store, ObjectStoreConfig},
+};
+use import_export::file::{ExportedContents, RemoteImporter};
+use std::path::PathBuf;
+use thiserror::Error;
+
+use crate::process_info::setup_metric_registry;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Object store error: {0}")]
+    ObjectStore(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Importing: {0}")]
+    Import(#[from] import_export::file::Error),
+}
+
+/// Import data directly into the catalog and object store.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Import a table exported with `influxdb_iox export table` or `influxdb_iox remote store
+/// get-table`.
+///
+/// The Parquet files are uploaded to the object store and registered in the catalog, creating
+/// the namespace, table, partitions and columns as needed and keeping the partition sort keys of
+/// the source. Only complete exports, with an `export_manifest.json` listing all exported files,
+/// are imported. Files that already exist in the catalog are skipped, so an interrupted import
+/// can be run again.
+#[derive(Debug, clap::Parser)]
+struct Table {
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store_config: ObjectStoreConfig,
+
+    /// Directory containing the exported table
+    #[clap(action)]
+    input_dir: PathBuf,
+}
+
+/// All possible subcommands for import
+#[derive(Debug, clap::Parser)]
+enum Command {
+    Table(Table),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Table(Table {
+            catalog_dsn,
+            object_store_config,
+            input_dir,
+        }) => {
+            let exported_contents = ExportedContents::try_new(&input_dir)?;
+            exported_contents.check_complete()?;
+
+            let metrics = setup_metric_registry();
+            let catalog = catalog_dsn.get_catalog("cli", metrics).await?;
+            let object_store = make_object_store(&object_store_config)?;
+
+            RemoteImporter::new(exported_contents, catalog, object_store)
+                .import()
+                .await?;
+            println!("Done.");
+        }
+    }
+
+    Ok(())
+}
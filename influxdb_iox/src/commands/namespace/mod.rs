@@ -6,7 +6,10 @@ use thiserror::Error;
 mod create;
 mod delete;
 mod retention;
+mod undelete;
+mod update;
 mod update_limit;
+mod update_rate_limit;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -37,13 +40,24 @@ enum Command {
     List,
 
     /// Update retention of an existing namespace
+    #[clap(alias = "update-retention")]
     Retention(retention::Config),
 
     /// Update one of the service protection limits for an existing namespace
     UpdateLimit(update_limit::Config),
 
+    /// Update settings of an existing namespace: its retention period, service protection limits
+    /// or whether its compaction is paused
+    Update(update::Config),
+
+    /// Set or remove the write rate limits of an existing namespace
+    UpdateRateLimit(update_rate_limit::Config),
+
     /// Delete a namespace
     Delete(delete::Config),
+
+    /// Restore a deleted namespace that has not been purged by the garbage collector yet
+    Undelete(undelete::Config),
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
@@ -62,8 +76,17 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         Command::UpdateLimit(config) => {
             update_limit::command(connection, config).await?;
         }
+        Command::Update(config) => {
+            update::command(connection, config).await?;
+        }
+        Command::UpdateRateLimit(config) => {
+            update_rate_limit::command(connection, config).await?;
+        }
         Command::Delete(config) => {
             delete::command(connection, config).await?;
+        }
+        Command::Undelete(config) => {
+            undelete::command(connection, config).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
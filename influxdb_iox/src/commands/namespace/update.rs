@@ -0,0 +1,96 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+/// Update settings of an existing namespace
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update
+    #[clap(action)]
+    namespace: String,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+#[derive(Debug, clap::Args)]
+#[clap(group(
+            // At least one setting must be given, settings that are not given are left unchanged.
+            clap::ArgGroup::new("settings")
+                .required(true)
+                .multiple(true)
+                .args(&[
+                    "retention_hours",
+                    "max_tables",
+                    "max_columns_per_table",
+                    "pause_compaction",
+                    "resume_compaction",
+                ])
+        ))]
+pub struct Args {
+    /// Num of hours of the retention period of this namespace. 0 represents infinite retention
+    #[clap(action, long = "retention-hours", short = 'r')]
+    retention_hours: Option<u32>,
+
+    /// The maximum number of tables to allow for this namespace
+    #[clap(action, long = "max-tables", short = 't')]
+    max_tables: Option<i32>,
+
+    /// The maximum number of columns to allow per table for this namespace
+    #[clap(action, long = "max-columns-per-table", short = 'c')]
+    max_columns_per_table: Option<i32>,
+
+    /// Stop compacting the partitions of this namespace, e.g. during an incident investigation
+    /// or a bulk re-ingest
+    #[clap(
+        action,
+        long = "pause-compaction",
+        conflicts_with = "resume_compaction"
+    )]
+    pause_compaction: bool,
+
+    /// Resume compacting the partitions of this namespace
+    #[clap(action, long = "resume-compaction")]
+    resume_compaction: bool,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config { namespace, args } = config;
+    let Args {
+        retention_hours,
+        max_tables,
+        max_columns_per_table,
+        pause_compaction,
+        resume_compaction,
+    } = args;
+
+    // we take retention from the user in hours, for ease of use, but it's stored as nanoseconds
+    // internally. 0 is passed through and means infinite retention.
+    let retention_period_ns = retention_hours.map(|h| h as i64 * 60 * 60 * 1_000_000_000);
+
+    let compaction_paused = match (pause_compaction, resume_compaction) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        (false, false) => None,
+    };
+
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+    let namespace = client
+        .update_namespace(
+            &namespace,
+            retention_period_ns,
+            max_tables,
+            max_columns_per_table,
+            compaction_paused,
+        )
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    if max_tables.is_some() || max_columns_per_table.is_some() {
+        println!(
+            r"
+NOTE: The new service protection limits will NOT take effect until all router instances have been restarted!"
+        );
+    }
+    Ok(())
+}
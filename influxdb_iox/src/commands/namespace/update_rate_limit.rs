@@ -0,0 +1,38 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+/// Set or remove the write rate limits of an existing namespace.
+///
+/// Writes exceeding a limit are rejected by the routers with "429 Too Many Requests". Limits that
+/// are not specified are removed.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the write rate limits for
+    #[clap(action)]
+    namespace: String,
+
+    /// The maximum number of lines per second to accept for this namespace
+    #[clap(action, long = "max-lines-per-second")]
+    max_lines_per_second: Option<i64>,
+
+    /// The maximum number of bytes per second to accept for this namespace
+    #[clap(action, long = "max-bytes-per-second")]
+    max_bytes_per_second: Option<i64>,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        namespace,
+        max_lines_per_second,
+        max_bytes_per_second,
+    } = config;
+
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+    let namespace = client
+        .update_namespace_write_rate_limit(&namespace, max_lines_per_second, max_bytes_per_second)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
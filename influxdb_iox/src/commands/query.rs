@@ -1,8 +1,22 @@
-use arrow::record_batch::RecordBatch;
+use arrow::{
+    csv, datatypes::SchemaRef, error::ArrowError, json::LineDelimitedWriter,
+    record_batch::RecordBatch,
+};
 use clap::ValueEnum;
 use futures::TryStreamExt;
 use influxdb_iox_client::format::influxql::{write_columnar, Options};
-use influxdb_iox_client::{connection::Connection, flight, format::QueryOutputFormat};
+use influxdb_iox_client::{
+    connection::Connection,
+    flight,
+    format::{prepare_for_text, QueryOutputFormat},
+};
+use parquet::{arrow::ArrowWriter, errors::ParquetError};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -15,6 +29,18 @@ pub enum Error {
 
     #[error("Error formatting InfluxQL: {0}")]
     InfluxQlFormatting(#[from] influxdb_iox_client::format::influxql::Error),
+
+    #[error("Error writing results: {0}")]
+    Writing(#[from] ArrowError),
+
+    #[error("Error writing Parquet: {0}")]
+    Parquet(#[from] ParquetError),
+
+    #[error("Error writing output: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Parquet output must be written to a file, use --output")]
+    ParquetToStdout,
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -47,6 +73,11 @@ pub struct Config {
     /// Query type used
     #[clap(short = 'l', long = "lang", default_value = "sql")]
     query_lang: QueryLanguage,
+
+    /// Write the query results to this file instead of stdout. The csv, jsonl and parquet
+    /// formats are written as the results arrive, without buffering all of them.
+    #[clap(short, long, action)]
+    output: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, ValueEnum)]
@@ -57,23 +88,77 @@ enum OutputFormat {
     /// Output the query results using the Arrow JSON formatter
     Json,
 
+    /// Output the query results as JSON objects, one row per line
+    Jsonl,
+
     /// Output the query results using the Arrow CSV formatter
     Csv,
 
     /// Output the query results using the Arrow pretty formatter
     Table,
+
+    /// Output the query results as a Parquet file, requires `--output`
+    Parquet,
 }
 
 impl From<OutputFormat> for QueryOutputFormat {
     fn from(value: OutputFormat) -> Self {
         match value {
-            OutputFormat::Pretty | OutputFormat::Table => Self::Pretty,
+            OutputFormat::Pretty | OutputFormat::Table | OutputFormat::Parquet => Self::Pretty,
             OutputFormat::Json => Self::Json,
+            OutputFormat::Jsonl => Self::JsonLines,
             OutputFormat::Csv => Self::Csv,
         }
     }
 }
 
+/// Writes query results in one of the formats that can be written as
+/// the record batches arrive.
+enum StreamingWriter<W: Write + Send> {
+    Csv(csv::Writer<W>),
+    Jsonl(LineDelimitedWriter<W>),
+    Parquet(ArrowWriter<W>),
+}
+
+impl<W: Write + Send> StreamingWriter<W> {
+    fn try_new(format: &OutputFormat, writer: W, schema: SchemaRef) -> Result<Self> {
+        Ok(match format {
+            OutputFormat::Csv => {
+                Self::Csv(csv::WriterBuilder::new().has_headers(true).build(writer))
+            }
+            OutputFormat::Jsonl => Self::Jsonl(LineDelimitedWriter::new(writer)),
+            OutputFormat::Parquet => Self::Parquet(ArrowWriter::try_new(writer, schema, None)?),
+            OutputFormat::Pretty | OutputFormat::Json | OutputFormat::Table => {
+                unreachable!("{format:?} is not a streaming format")
+            }
+        })
+    }
+
+    fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        match self {
+            Self::Csv(writer) => writer.write(&prepare_for_text(batch)?)?,
+            Self::Jsonl(writer) => writer.write(&prepare_for_text(batch)?)?,
+            // Parquet keeps the Arrow types as they are
+            Self::Parquet(writer) => writer.write(batch)?,
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Csv(writer) => writer.into_inner().flush()?,
+            Self::Jsonl(mut writer) => {
+                writer.finish()?;
+                writer.into_inner().flush()?;
+            }
+            Self::Parquet(writer) => {
+                writer.close()?;
+            }
+        }
+        Ok(())
+    }
+}
+
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
     let mut client = flight::Client::new(connection);
 
@@ -82,15 +167,53 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         format,
         query,
         query_lang,
+        output,
     } = config;
 
+    let mut out: Box<dyn Write + Send> = match &output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None if matches!(format, OutputFormat::Parquet) => return Err(Error::ParquetToStdout),
+        None => Box::new(std::io::stdout()),
+    };
+
     let mut query_results = match query_lang {
         QueryLanguage::Sql => client.sql(namespace, query).await,
         QueryLanguage::InfluxQL => client.influxql(namespace, query).await,
     }?;
 
-    // It might be nice to do some sort of streaming write
-    // rather than buffering the whole thing.
+    if matches!(
+        format,
+        OutputFormat::Csv | OutputFormat::Jsonl | OutputFormat::Parquet
+    ) {
+        let mut out = Some(out);
+        let mut writer = None;
+        while let Some(batch) = query_results.try_next().await? {
+            if writer.is_none() {
+                let out = out.take().expect("writer not created yet");
+                writer = Some(StreamingWriter::try_new(&format, out, batch.schema())?);
+            }
+            writer.as_mut().expect("created above").write(&batch)?;
+        }
+
+        let writer = match (writer, out) {
+            (Some(writer), _) => writer,
+            (None, Some(out)) => {
+                // no results, write an empty result to get the headers or schema
+                let schema = query_results
+                    .inner()
+                    .schema()
+                    .cloned()
+                    .ok_or(influxdb_iox_client::flight::Error::NoSchema)?;
+                let mut writer = StreamingWriter::try_new(&format, out, Arc::clone(&schema))?;
+                writer.write(&RecordBatch::new_empty(schema))?;
+                writer
+            }
+            (None, None) => unreachable!("output is only taken to create the writer"),
+        };
+        return writer.finish();
+    }
+
+    // The other formats need all results at once
     let mut batches: Vec<_> = (&mut query_results).try_collect().await?;
 
     // read schema AFTER collection, otherwise the stream does not have the schema data yet
@@ -105,14 +228,15 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
 
     match (query_lang, &format) {
         (QueryLanguage::InfluxQL, OutputFormat::Pretty) => {
-            write_columnar(std::io::stdout(), &batches, Options::default())?
+            write_columnar(&mut out, &batches, Options::default())?
         }
         _ => {
             let format: QueryOutputFormat = format.into();
             let formatted_result = format.format(&batches)?;
-            println!("{formatted_result}");
+            writeln!(out, "{formatted_result}")?;
         }
     }
+    out.flush()?;
 
     Ok(())
 }
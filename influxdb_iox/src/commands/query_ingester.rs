@@ -75,6 +75,7 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         columns,
         predicate,
         namespace_id,
+        announce_partitions: false,
     };
 
     // send the message directly encoded as bytes to the ingester.
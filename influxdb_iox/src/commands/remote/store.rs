@@ -1,7 +1,7 @@
 //! This module implements the `remote store` CLI subcommand
 
 use futures::StreamExt;
-use import_export::file::RemoteExporter;
+use import_export::file::TableExporter;
 use influxdb_iox_client::{connection::Connection, store};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -59,6 +59,15 @@ struct GetTable {
     #[clap(action, short, long)]
     partition_id: Option<i64>,
 
+    /// If specified, only files from partitions with a key greater than or equal to this key are
+    /// downloaded
+    #[clap(action, long)]
+    partition_key_start: Option<String>,
+
+    /// If specified, only files from partitions with a key less than this key are downloaded
+    #[clap(action, long)]
+    partition_key_end: Option<String>,
+
     /// The output directory to use. If not specified, files will be placed in a directory named
     /// after the table in the current working directory.
     #[clap(action, short)]
@@ -92,9 +101,12 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
             namespace,
             table,
             partition_id,
+            partition_key_start,
+            partition_key_end,
             output_directory,
         }) => {
-            let mut exporter = RemoteExporter::new(connection);
+            let mut exporter = TableExporter::remote(connection)
+                .with_partition_key_range(partition_key_start, partition_key_end);
             if let Some(partition_id) = partition_id {
                 exporter = exporter.with_partition_filter(partition_id);
             }
@@ -471,24 +471,41 @@ impl Config {
         let ingester_config = IngesterConfig {
             wal_directory,
             wal_rotation_period_seconds,
+            wal_compression: Default::default(),
+            write_ack_mode: Default::default(),
             concurrent_query_limit,
             persist_max_parallelism,
             persist_queue_depth,
             persist_hot_partition_cost,
+            persist_partition_window_seconds: 24 * 60 * 60, // 1 day
+            persist_closed_partition_grace_seconds: None,
+            namespace_memory_soft_limit_bytes: None,
+            namespace_memory_hard_limit_bytes: None,
             rpc_write_max_incoming_bytes: 1024 * 1024 * 1024, // 1GiB
         };
 
         let router_config = RouterConfig {
             authz_address: authz_address.clone(),
+            audit_config: Default::default(),
             single_tenant_deployment,
             http_request_limit: 1_000,
+            max_http_request_decompressed_size: None,
             ingester_addresses: ingester_addresses.clone(),
+            ingester_table_routes: vec![],
             new_namespace_retention_hours: None, // infinite retention
             namespace_autocreation_enabled: true,
             rpc_write_timeout_seconds: Duration::new(3, 0),
             rpc_write_replicas: 1.try_into().unwrap(),
             rpc_write_max_outgoing_bytes: ingester_config.rpc_write_max_incoming_bytes,
             rpc_write_health_error_window_seconds: Duration::from_secs(5),
+            secondary_ingester_addresses: vec![],
+            secondary_write_mode: Default::default(),
+            namespace_rate_limit_refresh_seconds: Duration::from_secs(10),
+            namespace_cache_ttl_seconds: None,
+            namespace_cache_negative_ttl_seconds: None,
+            namespace_cache_refresh_interval_seconds: None,
+            otlp_metrics_table_prefix: String::new(),
+            otlp_metrics_single_table: None,
         };
 
         // create a CompactorConfig for the all in one server based on
@@ -498,14 +515,18 @@ impl Config {
             compactor_scheduler_config,
             compaction_partition_concurrency: compactor_concurrency,
             compaction_df_concurrency: compactor_concurrency,
+            memory_budget_bytes: None,
             compaction_partition_scratchpad_concurrency: compactor_concurrency,
             query_exec_thread_count: Some(num_threads),
             exec_mem_pool_bytes,
             max_desired_file_size_bytes: 100 * 1024 * 1024, // 100 MB
+            min_desired_file_size_bytes: None,
             percentage_max_file_size: 30,
             split_percentage: 80,
             partition_timeout_secs: 30 * 60, // 30 minutes
             shadow_mode: false,
+            dry_run: false,
+            verify_output: false,
             enable_scratchpad: true,
             ignore_partition_skip_marker: false,
             min_num_l1_files_to_compact: 1,
@@ -513,18 +534,31 @@ impl Config {
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
             max_partition_fetch_queries_per_second: Some(500),
+            time_slice_min_num_l0_files: None,
+            max_consecutive_failures: NonZeroUsize::new(1).unwrap(),
         };
 
         let querier_config = QuerierConfig {
             authz_address,
+            audit_config: Default::default(),
             num_query_threads: None, // will be ignored
             ingester_addresses,
             ram_pool_metadata_bytes: querier_ram_pool_metadata_bytes,
             ram_pool_data_bytes: querier_ram_pool_data_bytes,
             max_concurrent_queries: querier_max_concurrent_queries,
+            max_concurrent_interactive_queries: None,
+            max_concurrent_batch_queries: None,
+            max_concurrent_background_queries: None,
+            query_result_cache_bytes: None,
             exec_mem_pool_bytes,
             ingester_circuit_breaker_threshold: u64::MAX, // never for all-in-one-mode
+            ingester_circuit_breaker_slow_response_threshold: None,
+            ingester_response_cache_ttl: Duration::ZERO,
             datafusion_config: Default::default(),
+            query_quota_max_concurrent_queries: None,
+            query_quota_queries_per_minute: None,
+            query_quota_max_scanned_bytes: None,
+            query_quota_overrides: Default::default(),
         };
 
         SpecializedConfig {
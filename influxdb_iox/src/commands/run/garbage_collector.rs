@@ -57,6 +57,7 @@ pub async fn command(config: Config) -> Result<()> {
             object_store,
             catalog,
             sub_config,
+            metric_registry: Arc::clone(&metric_registry),
         };
         let metric_registry = Arc::clone(&metric_registry);
 
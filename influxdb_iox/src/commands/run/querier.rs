@@ -13,7 +13,7 @@ use ioxd_common::{
     server_type::{CommonServerState, CommonServerStateError},
     Service,
 };
-use ioxd_querier::{create_querier_server_type, QuerierServerTypeArgs};
+use ioxd_querier::{configure_executor, create_querier_server_type, QuerierServerTypeArgs};
 use object_store::DynObjectStore;
 use object_store_metrics::ObjectStoreMetrics;
 use observability_deps::tracing::*;
@@ -101,10 +101,13 @@ pub async fn command(config: Config) -> Result<(), Error> {
     let ingester_addresses = &config.querier_config.ingester_addresses;
     info!(?ingester_addresses, "using ingester addresses");
 
-    let exec = Arc::new(Executor::new(
-        num_threads,
-        config.querier_config.exec_mem_pool_bytes,
-        Arc::clone(&metric_registry),
+    let exec = Arc::new(configure_executor(
+        Executor::new(
+            num_threads,
+            config.querier_config.exec_mem_pool_bytes,
+            Arc::clone(&metric_registry),
+        ),
+        &config.querier_config,
     ));
 
     let server_type = create_querier_server_type(QuerierServerTypeArgs {
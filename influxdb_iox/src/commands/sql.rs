@@ -10,8 +10,9 @@ mod repl_command;
 
 /// Start IOx interactive SQL REPL loop
 ///
-/// Supports command history and interactive editing. History is
-/// stored in $HOME/.iox_sql_history.
+/// Supports command history, multi-line editing and meta-commands
+/// such as `\d` (try `\?`). History is stored in
+/// $HOME/.iox_sql_history.
 #[derive(Debug, clap::Parser)]
 pub struct Config {
     // TODO add an option to avoid saving history
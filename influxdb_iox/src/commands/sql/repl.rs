@@ -48,6 +48,12 @@ pub enum Error {
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Schema of the tables of a namespace (`datafusion_util::config::DEFAULT_SCHEMA`)
+const DEFAULT_SCHEMA: &str = "iox";
+
+/// Schema of the system tables (`datafusion_util::config::SYSTEM_SCHEMA`)
+const SYSTEM_SCHEMA: &str = "system";
+
 enum QueryEngine {
     /// Run queries against the namespace on the remote server
     Remote(String),
@@ -76,7 +82,10 @@ impl rustyline::validate::Validator for RustylineHelper {
     ) -> rustyline::Result<rustyline::validate::ValidationResult> {
         let input = ctx.input();
 
-        if input.trim_end().ends_with(';') {
+        // SQL may span multiple lines until it is terminated with a semicolon, meta-commands and
+        // the exit commands are complete at the end of the line
+        let trimmed = input.trim();
+        if trimmed.ends_with(';') || trimmed.starts_with('\\') || is_exit_command(trimmed) {
             match ReplCommand::try_from(input) {
                 Ok(_) => Ok(rustyline::validate::ValidationResult::Valid(None)),
                 Err(err) => Ok(rustyline::validate::ValidationResult::Invalid(Some(err))),
@@ -172,6 +181,9 @@ pub struct Repl {
 
     /// Formatter to use to format query results
     output_format: QueryOutputFormat,
+
+    /// Whether to print the execution time of queries
+    timing: bool,
 }
 
 impl Repl {
@@ -203,6 +215,7 @@ impl Repl {
             flight_client,
             query_engine: None,
             output_format,
+            timing: true,
         })
     }
 
@@ -222,6 +235,19 @@ impl Repl {
                         .map_err(|e| println!("{e}"))
                         .ok();
                 }
+                ReplCommand::ListTables => {
+                    self.list_tables().await.map_err(|e| println!("{e}")).ok();
+                }
+                ReplCommand::DescribeTable { table } => {
+                    self.describe_table(&table)
+                        .await
+                        .map_err(|e| println!("{e}"))
+                        .ok();
+                }
+                ReplCommand::Timing { enabled } => {
+                    self.timing = enabled.unwrap_or(!self.timing);
+                    println!("Timing is {}.", if self.timing { "on" } else { "off" });
+                }
                 ReplCommand::UseNamespace { db_name } => {
                     self.use_namespace(db_name);
                 }
@@ -232,6 +258,9 @@ impl Repl {
                     info!("exiting at user request");
                     return Ok(());
                 }
+                ReplCommand::ShowFormat => {
+                    println!("Output format is {}", self.output_format);
+                }
                 ReplCommand::SetFormat { format } => {
                     self.set_output_format(format)?;
                 }
@@ -248,6 +277,10 @@ impl Repl {
                 self.rl
                     .add_history_entry(request.to_owned())
                     .context(ReadlineSnafu)?;
+                // save the history right away so that it is not lost if the process is killed
+                if let Err(e) = self.rl.save_history(&history_file()) {
+                    debug!(%e, "error saving history file");
+                }
 
                 request
                     .try_into()
@@ -287,38 +320,88 @@ impl Repl {
         self.print_results(&[record_batch])
     }
 
+    // print the user tables of the current namespace to the output
+    async fn list_tables(&mut self) -> Result<()> {
+        let sql = format!(
+            "SELECT table_schema, table_name FROM information_schema.tables \
+             WHERE table_schema NOT IN ('information_schema', '{SYSTEM_SCHEMA}') \
+             ORDER BY table_schema, table_name"
+        );
+        let Some(batches) = self.query(sql).await? else {
+            return Ok(());
+        };
+
+        self.print_results(&batches)
+    }
+
+    // print the columns of a table of the current namespace to the output. `table` may be
+    // qualified with its schema, e.g. `system.queries`.
+    async fn describe_table(&mut self, table: &str) -> Result<()> {
+        let (schema, table_name) = table.split_once('.').unwrap_or((DEFAULT_SCHEMA, table));
+        let sql = format!(
+            "SELECT column_name, data_type, is_nullable FROM information_schema.columns \
+             WHERE table_schema = {} AND table_name = {} \
+             ORDER BY ordinal_position",
+            quote_literal(schema),
+            quote_literal(table_name),
+        );
+        let Some(batches) = self.query(sql).await? else {
+            return Ok(());
+        };
+
+        if batches.iter().all(|b| b.num_rows() == 0) {
+            println!("Did not find any table named {table}");
+            return Ok(());
+        }
+        self.print_results(&batches)
+    }
+
     // Run a command against the currently selected remote namespace
     async fn run_sql(&mut self, sql: String) -> Result<()> {
         let start = Instant::now();
 
-        let batches: Vec<_> = match &self.query_engine {
+        let Some(batches) = self.query(sql).await? else {
+            return Ok(());
+        };
+
+        let end = Instant::now();
+        self.print_results(&batches)?;
+
+        if self.timing {
+            println!(
+                "Returned {} in {:?}",
+                Self::row_summary(&batches),
+                end - start
+            );
+        } else {
+            println!("Returned {}", Self::row_summary(&batches));
+        }
+        Ok(())
+    }
+
+    /// Run a query against the currently selected remote namespace. Returns `None` if no
+    /// namespace is selected.
+    async fn query(&mut self, sql: String) -> Result<Option<Vec<RecordBatch>>> {
+        match &self.query_engine {
             None => {
                 println!("Error: no namespace selected.");
                 println!("Hint: Run USE NAMESPACE <dbname> to select namespace");
-                return Ok(());
+                Ok(None)
             }
             Some(QueryEngine::Remote(db_name)) => {
                 info!(%db_name, %sql, "Running sql on remote namespace");
 
-                self.flight_client
+                let batches = self
+                    .flight_client
                     .sql(db_name.to_string(), sql)
                     .await
                     .context(RunningRemoteQuerySnafu)?
                     .try_collect()
                     .await
-                    .context(RunningRemoteQuerySnafu)?
+                    .context(RunningRemoteQuerySnafu)?;
+                Ok(Some(batches))
             }
-        };
-
-        let end = Instant::now();
-        self.print_results(&batches)?;
-
-        println!(
-            "Returned {} in {:?}",
-            Self::row_summary(&batches),
-            end - start
-        );
-        Ok(())
+        }
     }
 
     fn row_summary<'a>(batches: impl IntoIterator<Item = &'a RecordBatch>) -> String {
@@ -380,6 +463,11 @@ impl Drop for Repl {
     }
 }
 
+/// Quote `s` as a SQL string literal
+fn quote_literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
 fn is_exit_command(line: &str) -> bool {
     let line = line.trim_end().to_lowercase();
     line == "quit" || line == "exit"
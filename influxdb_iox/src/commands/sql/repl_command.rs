@@ -5,7 +5,11 @@ use observability_deps::tracing::{debug, warn};
 pub enum ReplCommand {
     Help,
     ShowNamespaces,
+    ListTables,
+    DescribeTable { table: String },
+    ShowFormat,
     SetFormat { format: String },
+    Timing { enabled: Option<bool> },
     UseNamespace { db_name: String },
     SqlCommand { sql: String },
     Exit,
@@ -30,6 +34,10 @@ impl TryFrom<&str> for ReplCommand {
             return Err("No command specified".to_string());
         }
 
+        if let Some(meta_command) = input.trim().strip_prefix('\\') {
+            return Self::parse_meta_command(meta_command);
+        }
+
         // tokenized commands, normalized whitespace but original case
         let raw_commands = input
             .trim()
@@ -90,6 +98,43 @@ impl TryFrom<&str> for ReplCommand {
 }
 
 impl ReplCommand {
+    /// Parse a backslash meta-command such as `\d my_table`, without the leading backslash.
+    ///
+    /// Unlike SQL, meta-command names and their arguments are case sensitive, except for the
+    /// `on` / `off` of `\timing`.
+    fn parse_meta_command(input: &str) -> Result<Self, String> {
+        let input = input.trim().trim_end_matches(';');
+        let tokens = input.split_whitespace().collect::<Vec<_>>();
+
+        debug!(?tokens, "processing meta-command tokens");
+
+        match tokens.as_slice() {
+            ["?"] => Ok(Self::Help),
+            ["q"] => Ok(Self::Exit),
+            ["d"] => Ok(Self::ListTables),
+            ["d", table] => Ok(Self::DescribeTable {
+                table: table.to_string(),
+            }),
+            ["format"] => Ok(Self::ShowFormat),
+            ["format", format] => Ok(Self::SetFormat {
+                format: format.to_string(),
+            }),
+            ["timing"] => Ok(Self::Timing { enabled: None }),
+            ["timing", enabled] => match enabled.to_ascii_lowercase().as_str() {
+                "on" => Ok(Self::Timing {
+                    enabled: Some(true),
+                }),
+                "off" => Ok(Self::Timing {
+                    enabled: Some(false),
+                }),
+                _ => Err(format!(
+                    "unrecognized value \"{enabled}\". Usage: \\timing [on|off]"
+                )),
+            },
+            _ => Err(format!("invalid command \\{input}. Try \\? for help")),
+        }
+    }
+
     /// Information for each command
     pub fn help() -> &'static str {
         r#"
@@ -100,10 +145,21 @@ SHOW NAMESPACES: List namespaces available on the server
 
 USE NAMESPACE <name>: Set the current remote namespace to name
 
-SET FORMAT <format>: Set the output format to Pretty, csv or json
+SET FORMAT <format>: Set the output format to Pretty, csv, json or jsonl
 
 [EXIT | QUIT]: Quit this session and exit the program
 
+Meta-commands (case sensitive, complete without a trailing semicolon):
+\d: List the tables of the current namespace
+\d <table>: Describe the columns of a table
+\format [<format>]: Show or set the output format
+\timing [on|off]: Toggle or set printing the execution time of queries
+\?: Show this help
+\q: Quit this session and exit the program
+
+SQL commands can span multiple lines and end with a semicolon. The command history
+is kept in $HOME/.iox_sql_history.
+
 # Examples: use remote namespace foo
 SHOW NAMESPACES;
 USE foo;
@@ -213,6 +269,67 @@ mod tests {
         assert_eq!("set format Hmm".try_into(), expected);
     }
 
+    #[test]
+    fn meta_commands() {
+        assert_eq!(r"\?".try_into(), Ok(ReplCommand::Help));
+        assert_eq!(r"\q".try_into(), Ok(ReplCommand::Exit));
+        assert_eq!(r" \q ; ".try_into(), Ok(ReplCommand::Exit));
+
+        assert_eq!(r"\d".try_into(), Ok(ReplCommand::ListTables));
+        assert_eq!(r"\d;".try_into(), Ok(ReplCommand::ListTables));
+        // table names are case sensitive
+        assert_eq!(
+            r"\d  MyTable".try_into(),
+            Ok(ReplCommand::DescribeTable {
+                table: "MyTable".to_string()
+            })
+        );
+        assert_eq!(
+            r"\d system.queries;".try_into(),
+            Ok(ReplCommand::DescribeTable {
+                table: "system.queries".to_string()
+            })
+        );
+
+        assert_eq!(r"\format".try_into(), Ok(ReplCommand::ShowFormat));
+        assert_eq!(
+            r"\format jsonl".try_into(),
+            Ok(ReplCommand::SetFormat {
+                format: "jsonl".to_string()
+            })
+        );
+
+        assert_eq!(
+            r"\timing".try_into(),
+            Ok(ReplCommand::Timing { enabled: None })
+        );
+        assert_eq!(
+            r"\timing ON".try_into(),
+            Ok(ReplCommand::Timing {
+                enabled: Some(true)
+            })
+        );
+        assert_eq!(
+            r"\timing off".try_into(),
+            Ok(ReplCommand::Timing {
+                enabled: Some(false)
+            })
+        );
+
+        let expected: Result<ReplCommand, String> =
+            Err(r#"unrecognized value "maybe". Usage: \timing [on|off]"#.to_string());
+        assert_eq!(r"\timing maybe".try_into(), expected);
+        let expected: Result<ReplCommand, String> =
+            Err(r"invalid command \x. Try \? for help".to_string());
+        assert_eq!(r"\x".try_into(), expected);
+        let expected: Result<ReplCommand, String> =
+            Err(r"invalid command \D. Try \? for help".to_string());
+        assert_eq!(r"\D".try_into(), expected);
+        let expected: Result<ReplCommand, String> =
+            Err(r"invalid command \d a b. Try \? for help".to_string());
+        assert_eq!(r"\d a b".try_into(), expected);
+    }
+
     #[test]
     fn sql_command() {
         let expected = sql_cmd("SELECT * from foo");
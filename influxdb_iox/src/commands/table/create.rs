@@ -0,0 +1,73 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::table::generated_types::PartitionTemplate;
+
+use crate::commands::table::Result;
+
+/// Create a new table
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to create the table in
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to be created
+    #[clap(action)]
+    table: String,
+
+    /// The partition template of the table as JSON, e.g.
+    /// '{"parts": [{"tagValue": "region"}, {"timeFormat": "%Y-%m"}]}'. If not specified, the
+    /// partition template of the namespace is used.
+    #[clap(long = "partition-template", value_parser = parse_partition_template)]
+    partition_template: Option<PartitionTemplate>,
+}
+
+fn parse_partition_template(s: &str) -> Result<PartitionTemplate, String> {
+    serde_json::from_str(s).map_err(|e| format!("invalid partition template: {e}"))
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        namespace,
+        table,
+        partition_template,
+    } = config;
+
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+    let table = client
+        .create_table(&namespace, &table, partition_template)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&table)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use influxdb_iox_client::table::generated_types::{Part, TemplatePart};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_partition_template() {
+        let template = parse_partition_template(
+            r#"{"parts": [{"tagValue": "region"}, {"timeFormat": "%Y"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            template,
+            PartitionTemplate {
+                parts: vec![
+                    TemplatePart {
+                        part: Some(Part::TagValue("region".to_string())),
+                    },
+                    TemplatePart {
+                        part: Some(Part::TimeFormat("%Y".to_string())),
+                    },
+                ],
+            }
+        );
+
+        let err = parse_partition_template("region,%Y").unwrap_err();
+        assert!(err.starts_with("invalid partition template"), "{err}");
+    }
+}
@@ -0,0 +1,27 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::table::Result;
+
+/// Delete a table that has not been written to yet. Routers cache tables, restart them before
+/// writing to a table with the same name again.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace the table is in
+    #[clap(action)]
+    namespace: String,
+
+    /// The table to be deleted
+    #[clap(action)]
+    table: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config { namespace, table } = config;
+
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+
+    client.delete_table(&namespace, &table).await?;
+    println!("Deleted table {table:?} of namespace {namespace:?}");
+
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::table::Result;
+
+/// List the tables of a namespace
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to list the tables of
+    #[clap(action)]
+    namespace: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config { namespace } = config;
+
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+    let tables = client.get_tables(&namespace).await?;
+    println!("{}", serde_json::to_string_pretty(&tables)?);
+
+    Ok(())
+}
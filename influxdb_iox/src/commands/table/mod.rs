@@ -0,0 +1,56 @@
+//! This module implements the `table` CLI command
+
+use influxdb_iox_client::connection::Connection;
+use thiserror::Error;
+
+mod create;
+mod delete;
+mod list;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Various commands for table management
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for table
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Create a new table, e.g. to set its partition template before it is first written to
+    Create(create::Config),
+
+    /// List the tables of a namespace
+    List(list::Config),
+
+    /// Delete a table that has not been written to yet
+    Delete(delete::Config),
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    match config.command {
+        Command::Create(config) => {
+            create::command(connection, config).await?;
+        }
+        Command::List(config) => {
+            list::command(connection, config).await?;
+        }
+        Command::Delete(config) => {
+            delete::command(connection, config).await?;
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+    Ok(())
+}
@@ -39,6 +39,8 @@ use trace_exporters::{
 mod commands {
     pub mod catalog;
     pub mod debug;
+    pub mod export;
+    pub mod import;
     pub mod namespace;
     pub mod query;
     pub mod query_ingester;
@@ -46,6 +48,7 @@ mod commands {
     pub mod run;
     pub mod sql;
     pub mod storage;
+    pub mod table;
     pub mod tracing;
     pub mod write;
 }
@@ -208,6 +211,12 @@ enum Command {
     /// Interrogate internal data
     Debug(commands::debug::Config),
 
+    /// Export data from the catalog and object store
+    Export(commands::export::Config),
+
+    /// Import data into the catalog and object store
+    Import(commands::import::Config),
+
     /// Initiate a read request to the gRPC storage service.
     Storage(commands::storage::Config),
 
@@ -222,6 +231,9 @@ enum Command {
 
     /// Various commands for namespace manipulation
     Namespace(commands::namespace::Config),
+
+    /// Various commands for table management
+    Table(commands::table::Config),
 }
 
 fn main() -> Result<(), std::io::Error> {
@@ -339,6 +351,20 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Export(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::export::command(config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::Import(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::import::command(config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Debug(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 if let Err(e) = commands::debug::command(|| connection(grpc_host), config).await {
@@ -378,6 +404,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Table(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::table::command(connection, config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
         }
     });
 
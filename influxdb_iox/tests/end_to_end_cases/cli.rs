@@ -236,6 +236,38 @@ async fn schema_cli() {
                                     .and(predicate::str::contains("val")),
                             );
                     }
+
+                    // the persisted file agrees with the catalog
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("debug")
+                        .arg("schema")
+                        .arg("diff")
+                        .arg(state.cluster().namespace())
+                        .arg("my_awesome_table2")
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains("with 1 parquet files")
+                                .and(predicate::str::contains("No discrepancies found")),
+                        );
+
+                    // unknown tables are an error
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("debug")
+                        .arg("schema")
+                        .arg("diff")
+                        .arg(state.cluster().namespace())
+                        .arg("no_such_table")
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains("Table no_such_table not found"));
                 }
                 .boxed()
             })),
@@ -320,6 +352,104 @@ async fn write_and_query() {
     .await
 }
 
+/// Test the output formats of the query CLI command
+#[tokio::test]
+async fn query_output_formats() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+    let table_name = "formats_table";
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(format!("{table_name},tag1=A val=42i 123456")),
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let querier_addr = state.cluster().querier().querier_grpc_base().to_string();
+                    let namespace = state.cluster().namespace();
+                    let sql = format!("select tag1, val, time from {table_name}");
+                    let dir = tempdir().unwrap();
+
+                    // dictionary encoded tags are written as their values and
+                    // timestamps in RFC3339 format
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("query")
+                        .arg("--format")
+                        .arg("jsonl")
+                        .arg(namespace)
+                        .arg(&sql)
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(r#""tag1":"A""#).and(
+                            predicate::str::contains(r#""time":"1970-01-01T00:00:00.000123456Z""#),
+                        ));
+
+                    let csv_file = dir.path().join("out.csv");
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("query")
+                        .arg("--format")
+                        .arg("csv")
+                        .arg("--output")
+                        .arg(&csv_file)
+                        .arg(namespace)
+                        .arg(&sql)
+                        .assert()
+                        .success();
+                    assert_eq!(
+                        std::fs::read_to_string(&csv_file).unwrap(),
+                        "tag1,val,time\nA,42,1970-01-01T00:00:00.000123456Z\n"
+                    );
+
+                    // Parquet is only written to files
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("query")
+                        .arg("--format")
+                        .arg("parquet")
+                        .arg(namespace)
+                        .arg(&sql)
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains(
+                            "Parquet output must be written to a file",
+                        ));
+
+                    let parquet_file = dir.path().join("out.parquet");
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&querier_addr)
+                        .arg("query")
+                        .arg("--format")
+                        .arg("parquet")
+                        .arg("--output")
+                        .arg(&parquet_file)
+                        .arg(namespace)
+                        .arg(&sql)
+                        .assert()
+                        .success();
+                    let parquet = std::fs::read(&parquet_file).unwrap();
+                    assert!(parquet.starts_with(b"PAR1"));
+                    assert!(parquet.ends_with(b"PAR1"));
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 /// Test error handling for the query CLI command
 #[tokio::test]
 async fn query_error_handling() {
@@ -778,6 +908,35 @@ async fn namespace_deletion() {
                 }
                 .boxed()
             })),
+            // restore the deleted namespace
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = "bananas_namespace";
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("undelete")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(namespace));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("list")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(namespace));
+                }
+                .boxed()
+            })),
         ],
     )
     .run()
@@ -1020,6 +1179,112 @@ async fn namespace_create_service_limits() {
     .await
 }
 
+/// Test the table create, list and delete commands
+#[tokio::test]
+async fn table_management() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(String::from("written,region=eu val=42i 123456")),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = state.cluster().namespace();
+
+                    // a table can be created with its own partition template before it is
+                    // written to
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("create")
+                        .arg("--partition-template")
+                        .arg(r#"{"parts": [{"tagValue": "region"}, {"timeFormat": "%Y"}]}"#)
+                        .arg(namespace)
+                        .arg("provisioned")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains(r#""name": "provisioned""#));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("create")
+                        .arg(namespace)
+                        .arg("provisioned")
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains("already exists"));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("list")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(r#""name": "provisioned""#)
+                                .and(predicate::str::contains(r#""name": "written""#)),
+                        );
+
+                    // only tables that have not been written to can be deleted
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("delete")
+                        .arg(namespace)
+                        .arg("written")
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains("cannot be deleted"));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("delete")
+                        .arg(namespace)
+                        .arg("provisioned")
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains("Deleted table"));
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("table")
+                        .arg("list")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains("provisioned")
+                                .not()
+                                .and(predicate::str::contains(r#""name": "written""#)),
+                        );
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 /// Test the namespace update service limit command
 #[tokio::test]
 async fn namespace_update_service_limit() {
@@ -1124,6 +1389,120 @@ async fn namespace_update_service_limit() {
                 }
                 .boxed()
             })),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let namespace = "service_limiter_namespace";
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg("--pause-compaction")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace)
+                                .and(predicate::str::contains(r#""compactionPaused": true"#)),
+                        );
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg("--resume-compaction")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace)
+                                .and(predicate::str::contains("compactionPaused").not()),
+                        );
+                }
+                .boxed()
+            })),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let namespace = "service_limiter_namespace";
+                    let addr = state.cluster().router().router_grpc_base().to_string();
+
+                    // Several settings can be changed at once
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg("--retention-hours")
+                        .arg("1")
+                        .arg("--max-tables")
+                        .arg("7")
+                        .arg("--max-columns-per-table")
+                        .arg("9")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace)
+                                .and(predicate::str::contains(
+                                    r#""retentionPeriodNs": "3600000000000""#,
+                                ))
+                                .and(predicate::str::contains(r#""maxTables": 7"#))
+                                .and(predicate::str::contains(r#""maxColumnsPerTable": 9"#))
+                                .and(predicate::str::contains("NOTE")),
+                        );
+
+                    // Settings that are not given are left unchanged
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg("--retention-hours")
+                        .arg("0")
+                        .arg(namespace)
+                        .assert()
+                        .success()
+                        .stdout(
+                            predicate::str::contains(namespace)
+                                .and(predicate::str::contains("retentionPeriodNs").not())
+                                .and(predicate::str::contains(r#""maxTables": 7"#))
+                                .and(predicate::str::contains(r#""maxColumnsPerTable": 9"#))
+                                .and(predicate::str::contains("NOTE").not()),
+                        );
+
+                    // At least one setting is required
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg(namespace)
+                        .assert()
+                        .failure();
+
+                    // Pausing and resuming compaction at once is rejected
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&addr)
+                        .arg("namespace")
+                        .arg("update")
+                        .arg("--pause-compaction")
+                        .arg("--resume-compaction")
+                        .arg(namespace)
+                        .assert()
+                        .failure();
+                }
+                .boxed()
+            })),
         ],
     )
     .run()
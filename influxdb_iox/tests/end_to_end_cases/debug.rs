@@ -117,6 +117,123 @@ async fn build_catalog() {
     .await
 }
 
+/// Tests that we can
+///
+/// 1. export a table from one IOx instance into a directory of files
+/// 2. import that directory into the catalog and object store of another instance
+/// 3. Start that instance and run a query successfully
+#[tokio::test]
+async fn import_table() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+    let table_name = "my_imported_table";
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    let sql = "select tag1, tag2, val from my_imported_table";
+    let expected = [
+        "+------+------+-----+",
+        "| tag1 | tag2 | val |",
+        "+------+------+-----+",
+        "| E    | F    | 44  |",
+        "+------+------+-----+",
+    ];
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol(format!("{table_name},tag1=E,tag2=F val=44i 123456")),
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let router_addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = state.cluster().namespace().to_string();
+
+                    let export_dir =
+                        tempfile::tempdir().expect("could not get temporary directory");
+                    let table_dir = export_dir.path().join(table_name);
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&router_addr)
+                        .arg("remote")
+                        .arg("store")
+                        .arg("get-table")
+                        .arg("-o")
+                        .arg(&table_dir)
+                        .arg(&namespace)
+                        .arg(table_name)
+                        .assert()
+                        .success();
+
+                    // the catalog and object store of the target instance
+                    let data_dir = tempfile::tempdir().expect("could not get temporary directory");
+                    let catalog_dsn = format!(
+                        "sqlite://{}",
+                        data_dir.path().join("catalog.sqlite").display()
+                    );
+                    let object_store_dir = data_dir.path().join("object_store");
+
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("catalog")
+                        .arg("setup")
+                        .arg("--catalog-dsn")
+                        .arg(&catalog_dsn)
+                        .assert()
+                        .success();
+
+                    // Exports without a manifest may be incomplete and are not imported
+                    let only_parquet_dir = copy_only_parquet_files(&table_dir);
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("import")
+                        .arg("table")
+                        .arg("--catalog-dsn")
+                        .arg(&catalog_dsn)
+                        .arg("--object-store")
+                        .arg("file")
+                        .arg("--data-dir")
+                        .arg(&object_store_dir)
+                        .arg(only_parquet_dir.path())
+                        .assert()
+                        .failure()
+                        .stderr(predicate::str::contains("No export manifest found"));
+
+                    // Importing twice doesn't duplicate any files
+                    for _ in 0..2 {
+                        Command::cargo_bin("influxdb_iox")
+                            .unwrap()
+                            .arg("import")
+                            .arg("table")
+                            .arg("--catalog-dsn")
+                            .arg(&catalog_dsn)
+                            .arg("--object-store")
+                            .arg("file")
+                            .arg("--data-dir")
+                            .arg(&object_store_dir)
+                            .arg(&table_dir)
+                            .assert()
+                            .success()
+                            .stdout(predicate::str::contains("Done."));
+                    }
+
+                    let restarted = RestartedServer::start(data_dir).await;
+                    let batches = restarted.run_sql(sql, &namespace).await;
+                    assert_batches_sorted_eq!(&expected, &batches);
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 /// Rebuilds a catalog from an export directory, starts up a server
 /// and verifies the running `sql` in `namespace` produces `expected`
 async fn rebuild_and_query(table_dir: &Path, namespace: &str, sql: &str, expected: &[&str]) {
@@ -200,8 +317,11 @@ impl RestartedServer {
 
         println!("Completed rebuild in {data_dir:?}");
 
-        // now, start up a new server in all-in-one mode
-        // using the  newly built data directory
+        Self::start(data_dir).await
+    }
+
+    /// starts up a new server in all-in-one mode using `data_dir`
+    async fn start(data_dir: TempDir) -> Self {
         let test_config = TestConfig::new_all_in_one_with_data_dir(data_dir.path());
         let all_in_one = ServerFixture::create(test_config).await;
 
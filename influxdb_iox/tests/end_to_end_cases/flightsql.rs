@@ -1,7 +1,7 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, sync::Arc};
 
 use arrow::{
-    array::as_generic_binary_array,
+    array::{as_generic_binary_array, ArrayRef, Int64Array, StringArray},
     datatypes::{DataType, Schema, TimeUnit},
     record_batch::RecordBatch,
 };
@@ -158,6 +158,80 @@ async fn flightsql_prepared_query() {
     .await
 }
 
+#[tokio::test]
+async fn flightsql_prepared_query_with_params() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let table_name = "the_table";
+
+    // Set up the cluster  ====================================
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(format!(
+                "{table_name},tag1=A,tag2=B val=42i 123456\n\
+                 {table_name},tag1=A,tag2=C val=43i 123457\n\
+                 {table_name},tag1=A,tag2=C val=44i 123458"
+            )),
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let sql = format!("select * from {table_name} where tag2 = $1 and val > $min");
+                    let mut client = flightsql_client(state.cluster());
+
+                    let handle = client.prepare(sql).await.unwrap();
+
+                    let parameter_schema = handle.get_parameter_schema();
+                    let names: Vec<_> = parameter_schema
+                        .fields()
+                        .iter()
+                        .map(|f| f.name().as_str())
+                        .collect();
+                    assert_eq!(names, vec!["1", "min"]);
+
+                    let params = RecordBatch::try_from_iter(vec![
+                        ("1", Arc::new(StringArray::from(vec!["C"])) as ArrayRef),
+                        ("min", Arc::new(Int64Array::from(vec![43])) as ArrayRef),
+                    ])
+                    .unwrap();
+                    let handle = client.bind(handle, params).await.unwrap();
+                    let stream = client.execute(handle).await.unwrap();
+
+                    let batches = collect_stream(stream).await;
+                    insta::assert_yaml_snapshot!(
+                        batches_to_sorted_lines(&batches),
+                        @r###"
+                    ---
+                    - +------+------+--------------------------------+-----+
+                    - "| tag1 | tag2 | time                           | val |"
+                    - +------+------+--------------------------------+-----+
+                    - "| A    | C    | 1970-01-01T00:00:00.000123458Z | 44  |"
+                    - +------+------+--------------------------------+-----+
+                    "###
+                    );
+                }
+                .boxed()
+            })),
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let sql = format!("select * from {table_name} where tag2 = $1");
+                    let mut client = flightsql_client(state.cluster());
+
+                    // executing without binding the parameters fails
+                    let handle = client.prepare(sql).await.unwrap();
+                    let err = client.execute(handle).await.unwrap_err();
+                    assert_contains!(err.to_string(), "No value found for placeholder '$1'");
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 #[tokio::test]
 async fn flightsql_get_sql_infos() {
     test_helpers::maybe_start_logging();
@@ -515,6 +589,9 @@ async fn flightsql_get_tables() {
                     - "| public       | information_schema | tables      | VIEW       |"
                     - "| public       | information_schema | views       | VIEW       |"
                     - "| public       | iox                | the_table   | BASE TABLE |"
+                    - "| public       | system             | chunks      | BASE TABLE |"
+                    - "| public       | system             | columns     | BASE TABLE |"
+                    - "| public       | system             | partitions  | BASE TABLE |"
                     - "| public       | system             | queries     | BASE TABLE |"
                     - +--------------+--------------------+-------------+------------+
                     - "catalog:None"
@@ -527,6 +604,9 @@ async fn flightsql_get_tables() {
                     - "| catalog_name | db_schema_name | table_name | table_type |"
                     - +--------------+----------------+------------+------------+
                     - "| public       | iox            | the_table  | BASE TABLE |"
+                    - "| public       | system         | chunks     | BASE TABLE |"
+                    - "| public       | system         | columns    | BASE TABLE |"
+                    - "| public       | system         | partitions | BASE TABLE |"
                     - "| public       | system         | queries    | BASE TABLE |"
                     - +--------------+----------------+------------+------------+
                     - "catalog:None"
@@ -693,6 +773,9 @@ async fn flightsql_get_tables_matches_information_schema() {
                     - "| public       | information_schema | tables      | VIEW       |"
                     - "| public       | information_schema | views       | VIEW       |"
                     - "| public       | iox                | the_table   | BASE TABLE |"
+                    - "| public       | system             | chunks      | BASE TABLE |"
+                    - "| public       | system             | columns     | BASE TABLE |"
+                    - "| public       | system             | partitions  | BASE TABLE |"
                     - "| public       | system             | queries     | BASE TABLE |"
                     - +--------------+--------------------+-------------+------------+
                     "###
@@ -1112,6 +1195,31 @@ async fn flightsql_get_primary_keys() {
                         .unwrap();
                     let batches = collect_stream(stream).await;
 
+                    insta::assert_yaml_snapshot!(
+                        batches_to_sorted_lines(&batches),
+                        @r###"
+                    ---
+                    - +--------------+----------------+------------+-------------+----------------+--------------+
+                    - "| catalog_name | db_schema_name | table_name | column_name | key_name       | key_sequence |"
+                    - +--------------+----------------+------------+-------------+----------------+--------------+
+                    - "| public       | iox            | the_table  | tag1        | the_table_pkey | 1            |"
+                    - "| public       | iox            | the_table  | tag2        | the_table_pkey | 2            |"
+                    - "| public       | iox            | the_table  | time        | the_table_pkey | 3            |"
+                    - +--------------+----------------+------------+-------------+----------------+--------------+
+                    "###
+                    );
+
+                    // unknown tables have no primary key
+                    let stream = client
+                        .get_primary_keys(
+                            Some("public".to_string()),
+                            Some("iox".to_string()),
+                            "no_such_table".to_string(),
+                        )
+                        .await
+                        .unwrap();
+                    let batches = collect_stream(stream).await;
+
                     insta::assert_yaml_snapshot!(
                         batches_to_sorted_lines(&batches),
                         @r###"
@@ -1448,6 +1556,8 @@ async fn jdbc_tests(jdbc_url: &str, table_name: &str) {
                                      public,  information_schema,  tables,  VIEW,  null,  null,  null,  null,  null,  null\n\
                                      public,  information_schema,  views,  VIEW,  null,  null,  null,  null,  null,  null\n\
                                      public,  iox,  the_table,  BASE TABLE,  null,  null,  null,  null,  null,  null\n\
+                                     public,  system,  chunks,  BASE TABLE,  null,  null,  null,  null,  null,  null\n\
+                                     public,  system,  partitions,  BASE TABLE,  null,  null,  null,  null,  null,  null\n\
                                      public,  system,  queries,  BASE TABLE,  null,  null,  null,  null,  null,  null";
 
     // CommandGetTables output
@@ -1456,6 +1566,8 @@ async fn jdbc_tests(jdbc_url: &str, table_name: &str) {
                                         **************\n\
                                         TABLE_CAT,  TABLE_SCHEM,  TABLE_NAME,  TABLE_TYPE,  REMARKS,  TYPE_CAT,  TYPE_SCHEM,  TYPE_NAME,  SELF_REFERENCING_COL_NAME,  REF_GENERATION\n\
                                         ------------\n\
+                                        public,  system,  chunks,  BASE TABLE,  null,  null,  null,  null,  null,  null\n\
+                                        public,  system,  partitions,  BASE TABLE,  null,  null,  null,  null,  null,  null\n\
                                         public,  system,  queries,  BASE TABLE,  null,  null,  null,  null,  null,  null";
 
     // CommandGetTableTypes output
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use influxdb_iox_client::{connection::Connection, health};
+use test_helpers_end_to_end::{maybe_skip_integration, MiniCluster};
+
+/// The subsystem health checks of every server report their status via the gRPC health service.
+#[tokio::test]
+async fn subsystem_health() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+    let cluster = MiniCluster::create_shared(database_url).await;
+
+    let servers = [
+        (
+            "router",
+            cluster.router().router_grpc_connection(),
+            &["iox.catalog", "iox.object_store"][..],
+        ),
+        (
+            "ingester",
+            cluster.ingester().ingester_grpc_connection(),
+            &["iox.catalog", "iox.object_store", "iox.wal"][..],
+        ),
+        (
+            "querier",
+            cluster.querier().querier_grpc_connection(),
+            &["iox.catalog", "iox.object_store"][..],
+        ),
+    ];
+
+    for (server, connection, subsystems) in servers {
+        for subsystem in subsystems {
+            wait_for_serving(server, connection.clone(), subsystem).await;
+        }
+    }
+}
+
+/// The checks run in the background, so the first result may not be reported yet.
+async fn wait_for_serving(server: &str, connection: Connection, subsystem: &str) {
+    let mut client = health::Client::new(connection);
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            match client.check(subsystem).await {
+                Ok(true) => return,
+                Ok(false) => panic!("{subsystem} of {server} is not serving"),
+                // unknown until the first check completed
+                Err(_) => tokio::time::sleep(Duration::from_millis(100)).await,
+            }
+        }
+    })
+    .await
+    .unwrap_or_else(|_| panic!("no health status for {subsystem} of {server}"));
+}
@@ -1,7 +1,8 @@
 use futures::FutureExt;
+use serde_json::json;
 use test_helpers_end_to_end::{
-    check_flight_error, maybe_skip_integration, try_run_influxql, Authorizer, MiniCluster, Step,
-    StepTest, StepTestState,
+    check_flight_error, maybe_skip_integration, query_influxql_via_http, try_run_influxql,
+    Authorizer, MiniCluster, Step, StepTest, StepTestState,
 };
 
 #[tokio::test]
@@ -145,3 +146,85 @@ async fn authz() {
 
     authz.close().await;
 }
+
+#[tokio::test]
+async fn influxql_via_http() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let table_name = "the_table";
+
+    // Set up the cluster  ====================================
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(format!(
+                "{table_name},tag1=A,tag2=B val=42i 123456\n\
+                 {table_name},tag1=A,tag2=C val=43i 123457"
+            )),
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let querier_base = state.cluster().querier().querier_http_base();
+                    let namespace = state.cluster().namespace();
+
+                    let query = format!(
+                        "SELECT tag1, val FROM {table_name}; \
+                         SELECT val FROM {table_name} GROUP BY tag2; \
+                         SELECT val FROM does_not_exist"
+                    );
+                    let response = query_influxql_via_http(query, namespace, &querier_base).await;
+                    assert_eq!(response.status(), http::StatusCode::OK);
+                    let body: serde_json::Value =
+                        serde_json::from_str(&response.text().await.unwrap()).unwrap();
+                    assert_eq!(
+                        body,
+                        json!({
+                            "results": [
+                                {
+                                    "statement_id": 0,
+                                    "series": [{
+                                        "name": "the_table",
+                                        "columns": ["time", "tag1", "val"],
+                                        "values": [
+                                            ["1970-01-01T00:00:00.000123456Z", "A", 42],
+                                            ["1970-01-01T00:00:00.000123457Z", "A", 43],
+                                        ],
+                                    }],
+                                },
+                                {
+                                    "statement_id": 1,
+                                    "series": [
+                                        {
+                                            "name": "the_table",
+                                            "tags": {"tag2": "B"},
+                                            "columns": ["time", "val"],
+                                            "values": [["1970-01-01T00:00:00.000123456Z", 42]],
+                                        },
+                                        {
+                                            "name": "the_table",
+                                            "tags": {"tag2": "C"},
+                                            "columns": ["time", "val"],
+                                            "values": [["1970-01-01T00:00:00.000123457Z", 43]],
+                                        },
+                                    ],
+                                },
+                                {"statement_id": 2},
+                            ],
+                        })
+                    );
+
+                    // the database is required
+                    let response =
+                        query_influxql_via_http("SELECT val FROM the_table", "", &querier_base)
+                            .await;
+                    assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
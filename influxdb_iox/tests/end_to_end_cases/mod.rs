@@ -9,6 +9,7 @@ mod compactor;
 mod debug;
 mod error;
 mod flightsql;
+mod health;
 mod influxql;
 mod ingester;
 mod logging;
@@ -6,11 +6,12 @@ use arrow_flight::{
     decode::{DecodedFlightData, DecodedPayload},
     error::FlightError,
 };
+use arrow_util::assert_batches_sorted_eq;
 use futures::{FutureExt, StreamExt, TryStreamExt};
 use generated_types::{
     aggregate::AggregateType, read_group_request::Group, read_response::frame::Data,
 };
-use influxdb_iox_client::flight::IOxRecordBatchStream;
+use influxdb_iox_client::flight::{IOxRecordBatchStream, QueryMessage};
 use test_helpers_end_to_end::{
     check_flight_error, check_tonic_status, maybe_skip_integration, run_sql, try_run_sql,
     Authorizer, GrpcRequestBuilder, MiniCluster, Step, StepTest, StepTestState, TestConfig,
@@ -188,6 +189,78 @@ async fn basic_empty() {
     .await
 }
 
+#[tokio::test]
+async fn query_with_progress() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let table_name = "the_table";
+
+    // Set up the cluster  ====================================
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol(format!(
+                "{table_name},tag1=A,tag2=B val=42i 123456\n\
+                 {table_name},tag1=A,tag2=C val=43i 123457"
+            )),
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            Step::Custom(Box::new(move |state: &mut StepTestState| {
+                async move {
+                    let querier_connection = state.cluster().querier().querier_grpc_connection();
+                    let namespace = state.cluster().namespace();
+
+                    let mut client = influxdb_iox_client::flight::Client::new(querier_connection);
+
+                    let sql = format!("select * from {table_name}");
+                    let mut stream = client.sql_with_progress(namespace, sql).await.unwrap();
+
+                    let mut batches = vec![];
+                    let mut progress = vec![];
+                    while let Some(msg) = stream.try_next().await.unwrap() {
+                        match msg {
+                            QueryMessage::RecordBatch(batch) => batches.push(batch),
+                            QueryMessage::Progress(p) => progress.push(p),
+                        }
+                    }
+
+                    let expected = [
+                        "+------+------+--------------------------------+-----+",
+                        "| tag1 | tag2 | time                           | val |",
+                        "+------+------+--------------------------------+-----+",
+                        "| A    | B    | 1970-01-01T00:00:00.000123456Z | 42  |",
+                        "| A    | C    | 1970-01-01T00:00:00.000123457Z | 43  |",
+                        "+------+------+--------------------------------+-----+",
+                    ];
+                    assert_batches_sorted_eq!(&expected, &batches);
+                    assert!(stream.schema().is_some());
+
+                    // the query is fast, so there is at least the final progress message
+                    let last = progress.last().expect("final progress message");
+                    assert!(last.done);
+                    assert_eq!(last.rows_returned, 2);
+                    assert!(last.partitions_total > 0);
+
+                    // errors are reported like for `DoGet`
+                    let err = client
+                        .sql_with_progress(namespace, "select * from no_such_table")
+                        .await
+                        .unwrap_err();
+                    check_flight_error(err, tonic::Code::InvalidArgument, None);
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
 #[tokio::test]
 async fn basic_no_ingester_connection() {
     test_helpers::maybe_start_logging();
@@ -299,6 +372,100 @@ async fn query_after_persist_sees_new_files() {
     StepTest::new(&mut cluster, steps).run().await
 }
 
+#[tokio::test]
+async fn query_result_cache() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let table_name = "the_table";
+
+    // Set up the cluster  ====================================
+    let ingester_config = TestConfig::new_ingester(&database_url);
+    let router_config = TestConfig::new_router(&ingester_config);
+    let querier_config =
+        TestConfig::new_querier(&ingester_config).with_querier_result_cache_bytes(1024 * 1024);
+    let mut cluster = MiniCluster::new()
+        .with_router(router_config)
+        .await
+        .with_ingester(ingester_config)
+        .await
+        .with_querier(querier_config)
+        .await;
+
+    let query = || Step::Query {
+        sql: format!("select * from {table_name}"),
+        expected: vec![
+            "+------+------+--------------------------------+-----+",
+            "| tag1 | tag2 | time                           | val |",
+            "+------+------+--------------------------------+-----+",
+            "| A    | B    | 1970-01-01T00:00:00.000123456Z | 42  |",
+            "+------+------+--------------------------------+-----+",
+        ],
+    };
+    let assert_requests = |hit: u64, miss: u64| {
+        Step::Custom(Box::new(move |state: &mut StepTestState| {
+            async move {
+                let metrics = state.cluster().querier().metrics().await;
+                assert_eq!(
+                    (
+                        result_cache_requests(&metrics, "hit"),
+                        result_cache_requests(&metrics, "miss"),
+                    ),
+                    (hit, miss),
+                    "{metrics}",
+                );
+            }
+            .boxed()
+        }))
+    };
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol(format!("{table_name},tag1=A,tag2=B val=42i 123456")),
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            query(),
+            assert_requests(0, 1),
+            // second, identical query is served from the cache
+            query(),
+            assert_requests(1, 1),
+            // new file changes the chunk set, so the cached result is dropped
+            Step::RecordNumParquetFiles,
+            Step::WriteLineProtocol(format!("{table_name},tag1=B,tag2=A val=43i 789101112")),
+            Step::WaitForPersisted {
+                expected_increase: 1,
+            },
+            Step::Query {
+                sql: format!("select * from {table_name}"),
+                expected: vec![
+                    "+------+------+--------------------------------+-----+",
+                    "| tag1 | tag2 | time                           | val |",
+                    "+------+------+--------------------------------+-----+",
+                    "| A    | B    | 1970-01-01T00:00:00.000123456Z | 42  |",
+                    "| B    | A    | 1970-01-01T00:00:00.789101112Z | 43  |",
+                    "+------+------+--------------------------------+-----+",
+                ],
+            },
+            assert_requests(1, 2),
+        ],
+    )
+    .run()
+    .await
+}
+
+/// Parse the number of query result cache requests with the given result from Prometheus metrics.
+fn result_cache_requests(metrics: &str, result: &str) -> u64 {
+    let prefix = format!("query_result_cache_requests_total{{result=\"{result}\"}} ");
+    metrics
+        .lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().parse().unwrap())
+        .unwrap_or_default()
+}
+
 #[tokio::test]
 async fn query_after_shutdown_sees_new_files() {
     test_helpers::maybe_start_logging();
@@ -846,6 +1013,7 @@ async fn iox_debug_header() {
                     "+---------------+--------------+------------+------------+",
                     "| table_catalog | table_schema | table_name | table_type |",
                     "+---------------+--------------+------------+------------+",
+                    "| public        | system       | columns    | BASE TABLE |",
                     "+---------------+--------------+------------+------------+",
                 ],
             },
@@ -857,6 +1025,9 @@ async fn iox_debug_header() {
                     "+---------------+--------------+------------+------------+",
                     "| table_catalog | table_schema | table_name | table_type |",
                     "+---------------+--------------+------------+------------+",
+                    "| public        | system       | chunks     | BASE TABLE |",
+                    "| public        | system       | columns    | BASE TABLE |",
+                    "| public        | system       | partitions | BASE TABLE |",
                     "| public        | system       | queries    | BASE TABLE |",
                     "+---------------+--------------+------------+------------+",
                 ],
@@ -872,6 +1043,7 @@ async fn iox_debug_header() {
                     "| public        | information_schema | tables      | VIEW       |",
                     "| public        | information_schema | views       | VIEW       |",
                     "| public        | iox                | the_table   | BASE TABLE |",
+                    "| public        | system             | columns     | BASE TABLE |",
                     "+---------------+--------------------+-------------+------------+",
                 ],
             },
@@ -886,6 +1058,9 @@ async fn iox_debug_header() {
                     "| public        | information_schema | tables      | VIEW       |",
                     "| public        | information_schema | views       | VIEW       |",
                     "| public        | iox                | the_table   | BASE TABLE |",
+                    "| public        | system             | chunks      | BASE TABLE |",
+                    "| public        | system             | columns     | BASE TABLE |",
+                    "| public        | system             | partitions  | BASE TABLE |",
                     "| public        | system             | queries     | BASE TABLE |",
                     "+---------------+--------------------+-------------+------------+",
                 ],
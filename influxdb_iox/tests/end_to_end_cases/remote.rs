@@ -67,6 +67,35 @@ async fn remote_store_get_table() {
                     let table_dir = dir.as_ref().join(table_name);
                     assert_two_parquet_files_and_meta(&table_dir);
 
+                    // The export manifest lists the exported files
+                    let manifest: serde_json::Value = serde_json::from_str(
+                        &fs::read_to_string(table_dir.join("export_manifest.json"))
+                            .await
+                            .unwrap(),
+                    )
+                    .unwrap();
+                    assert_eq!(manifest["table_name"], table_name);
+                    assert_eq!(manifest["parquet_files"].as_array().unwrap().len(), 2);
+
+                    // Partitions outside of the partition key range are not exported
+                    let out_of_range_dir = dir.as_ref().join("out_of_range");
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&router_addr)
+                        .arg("remote")
+                        .arg("store")
+                        .arg("get-table")
+                        .arg("--partition-key-start")
+                        .arg("1970-01-02")
+                        .arg("-o")
+                        .arg(&out_of_range_dir)
+                        .arg(&namespace)
+                        .arg(table_name)
+                        .assert()
+                        .success()
+                        .stdout(predicate::str::contains("found 0 Parquet files"));
+
                     // The `-o` argument should specify where the files go instead of a directory
                     // named after the table. Note that this `Command` doesn't set `current dir`;
                     // the `-o` argument shouldn't have anything to do with the current working
@@ -8,3 +8,7 @@ SELECT * from information_schema.tables where table_schema = 'system';
 -- Note the issue_time changes, so we can't display it directly
 -- Instead check that it is reasonable and non null
 SELECT issue_time <= now(), query_type, query_text, success FROM system.queries;
+
+-- IOX_COMPARE: sorted
+-- The query that reads the table is still running
+SELECT query_type, query_text, status FROM system.queries;
@@ -82,4 +82,28 @@ impl Client {
 
         Ok(response.into_inner().parquet_files)
     }
+
+    /// Get the partition and Parquet file records needed to export a table, optionally limited to
+    /// the partitions with keys in `partition_key_start..partition_key_end`
+    pub async fn get_table_export(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        table_name: impl Into<String> + Send,
+        partition_key_start: Option<String>,
+        partition_key_end: Option<String>,
+    ) -> Result<GetTableExportResponse, Error> {
+        let namespace_name = namespace_name.into();
+        let table_name = table_name.into();
+        let response = self
+            .inner
+            .get_table_export(GetTableExportRequest {
+                namespace_name,
+                table_name,
+                partition_key_start,
+                partition_key_end,
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
 }
@@ -43,4 +43,36 @@ impl Client {
 
         Ok(response.into_inner().skipped_compaction)
     }
+
+    /// Request compaction of the given partition ahead of all other partitions.
+    ///
+    /// Returns `false` if the partition was already waiting for compaction.
+    pub async fn compact_partition(&mut self, partition_id: i64) -> Result<bool, Error> {
+        let response = self
+            .inner
+            .compact_partition(CompactPartitionRequest { partition_id })
+            .await?;
+
+        Ok(response.into_inner().queued)
+    }
+
+    /// Get the compaction backlog of the compactor.
+    pub async fn backlog(&mut self) -> Result<GetBacklogResponse, Error> {
+        let response = self.inner.get_backlog(GetBacklogRequest {}).await?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Get the recorded compaction commits of the given partition, oldest first.
+    pub async fn compaction_history(
+        &mut self,
+        partition_id: i64,
+    ) -> Result<Vec<CompactionHistory>, Error> {
+        let response = self
+            .inner
+            .get_compaction_history(GetCompactionHistoryRequest { partition_id })
+            .await?;
+
+        Ok(response.into_inner().history)
+    }
 }
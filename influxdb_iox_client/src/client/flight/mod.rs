@@ -2,20 +2,27 @@
 
 use std::{pin::Pin, task::Poll};
 
-use ::generated_types::influxdata::iox::querier::v1::{read_info::QueryType, ReadInfo};
-use futures_util::{Stream, StreamExt};
+use ::generated_types::influxdata::iox::querier::v1::{
+    read_info::QueryType, QueryProgress, ReadInfo,
+};
+use futures_util::{ready, Stream, StreamExt, TryStreamExt};
 use prost::Message;
 use thiserror::Error;
 use tonic::metadata::{MetadataKey, MetadataMap, MetadataValue};
 
 use arrow::{
+    datatypes::SchemaRef,
     ipc::{self},
     record_batch::RecordBatch,
 };
 
 use rand::Rng;
 
-use arrow_flight::{decode::FlightRecordBatchStream, error::FlightError, FlightClient, Ticket};
+use arrow_flight::{
+    decode::{DecodedFlightData, DecodedPayload, FlightDataDecoder, FlightRecordBatchStream},
+    error::FlightError,
+    FlightClient, FlightData, FlightDescriptor, Ticket,
+};
 
 use crate::connection::Connection;
 
@@ -203,6 +210,25 @@ impl Client {
         self.do_get_with_read_info(request).await
     }
 
+    /// Query the given database with the given SQL query, returning
+    /// a struct that can stream Arrow [`RecordBatch`] results,
+    /// interleaved with the [progress](QueryProgress) of the query.
+    pub async fn sql_with_progress(
+        &mut self,
+        database: impl Into<String> + Send,
+        sql_query: impl Into<String> + Send,
+    ) -> Result<IOxQueryProgressStream, Error> {
+        let request = ReadInfo {
+            database: database.into(),
+            sql_query: sql_query.into(),
+            query_type: QueryType::Sql.into(),
+            flightsql_command: vec![],
+            is_debug: false,
+        };
+
+        self.do_exchange_with_read_info(request).await
+    }
+
     /// Query the given database with the given InfluxQL query, returning
     /// a struct that can stream Arrow [`RecordBatch`] results.
     pub async fn influxql(
@@ -221,6 +247,25 @@ impl Client {
         self.do_get_with_read_info(request).await
     }
 
+    /// Query the given database with the given InfluxQL query, returning
+    /// a struct that can stream Arrow [`RecordBatch`] results,
+    /// interleaved with the [progress](QueryProgress) of the query.
+    pub async fn influxql_with_progress(
+        &mut self,
+        database: impl Into<String> + Send,
+        influxql_query: impl Into<String> + Send,
+    ) -> Result<IOxQueryProgressStream, Error> {
+        let request = ReadInfo {
+            database: database.into(),
+            sql_query: influxql_query.into(),
+            query_type: QueryType::InfluxQl.into(),
+            flightsql_command: vec![],
+            is_debug: false,
+        };
+
+        self.do_exchange_with_read_info(request).await
+    }
+
     /// Perform a lower level client read with the `ReadInfo`
     async fn do_get_with_read_info(
         &mut self,
@@ -237,6 +282,25 @@ impl Client {
             .map_err(Error::ArrowFlightError)
     }
 
+    /// Perform a lower level client read with the `ReadInfo` via
+    /// `DoExchange`, which reports the progress of the query
+    async fn do_exchange_with_read_info(
+        &mut self,
+        read_info: ReadInfo,
+    ) -> Result<IOxQueryProgressStream, Error> {
+        // the encoded readinfo is sent as the command of the first and only message
+        let data = FlightData {
+            flight_descriptor: Some(FlightDescriptor::new_cmd(read_info.encode_to_vec())),
+            ..Default::default()
+        };
+        let mut request = tonic::Request::new(futures_util::stream::iter([data]));
+        *request.metadata_mut() = self.inner.metadata().clone();
+
+        let response = self.inner.inner_mut().do_exchange(request).await?;
+        let stream = response.into_inner().map_err(FlightError::Tonic);
+        Ok(IOxQueryProgressStream::new(FlightDataDecoder::new(stream)))
+    }
+
     /// Perform a handshake with the server, returning Ok on success
     /// and Err if the server fails the handshake.
     ///
@@ -305,3 +369,75 @@ impl Stream for IOxRecordBatchStream {
             .map_err(Error::ArrowFlightError)
     }
 }
+
+/// A message of a query that reports its progress, see
+/// [`Client::sql_with_progress`].
+#[derive(Debug)]
+pub enum QueryMessage {
+    /// Results of the query.
+    RecordBatch(RecordBatch),
+
+    /// Progress of the query, sent periodically and once after the last
+    /// results.
+    Progress(QueryProgress),
+}
+
+/// Decodes the response of a query that reports its progress into
+/// [`QueryMessage`]s.
+#[derive(Debug)]
+pub struct IOxQueryProgressStream {
+    inner: FlightDataDecoder,
+    schema: Option<SchemaRef>,
+}
+
+impl IOxQueryProgressStream {
+    /// create a new IOxQueryProgressStream
+    pub fn new(inner: FlightDataDecoder) -> Self {
+        Self {
+            inner,
+            schema: None,
+        }
+    }
+
+    /// Return the schema of the results, if it was already received
+    pub fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+}
+
+impl Stream for IOxQueryProgressStream {
+    type Item = Result<QueryMessage, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Result<QueryMessage, Error>>> {
+        loop {
+            let data = match ready!(self.inner.poll_next_unpin(cx)) {
+                None => return Poll::Ready(None),
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                Some(Ok(data)) => data,
+            };
+
+            let DecodedFlightData { inner, payload } = data;
+            match payload {
+                DecodedPayload::None => {}
+                DecodedPayload::Schema(schema) => {
+                    self.schema = Some(schema);
+                }
+                // progress is sent as the metadata of empty batches
+                DecodedPayload::RecordBatch(batch)
+                    if batch.num_rows() == 0 && !inner.app_metadata.is_empty() =>
+                {
+                    let progress = QueryProgress::decode(&inner.app_metadata[..]);
+                    return Poll::Ready(Some(
+                        progress.map(QueryMessage::Progress).map_err(Error::from),
+                    ));
+                }
+                DecodedPayload::RecordBatch(batch) => {
+                    return Poll::Ready(Some(Ok(QueryMessage::RecordBatch(batch))));
+                }
+            }
+        }
+    }
+}
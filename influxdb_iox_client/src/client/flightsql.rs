@@ -23,9 +23,13 @@
 
 use std::sync::Arc;
 
-use arrow::datatypes::{Schema, SchemaRef};
+use arrow::{
+    datatypes::{Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
 use arrow_flight::{
     decode::FlightRecordBatchStream,
+    encode::FlightDataEncoderBuilder,
     error::{FlightError, Result},
     sql::{
         ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, Any,
@@ -34,7 +38,7 @@ use arrow_flight::{
         CommandGetTables, CommandGetXdbcTypeInfo, CommandPreparedStatementQuery,
         CommandStatementQuery, ProstMessageExt,
     },
-    Action, FlightClient, FlightDescriptor, FlightInfo, IpcMessage, Ticket,
+    Action, FlightClient, FlightDescriptor, FlightInfo, IpcMessage, PutResult, Ticket,
 };
 use bytes::Bytes;
 use futures_util::TryStreamExt;
@@ -446,7 +450,8 @@ impl FlightSqlClient {
     /// the `DoAction` endpoint of the FlightSQL server, and returns
     /// the handle from the server.
     ///
-    /// See [`Self::execute`] to run a previously prepared statement
+    /// See [`Self::execute`] to run a previously prepared statement and
+    /// [`Self::bind`] to set values for its parameters
     pub async fn prepare(&mut self, query: String) -> Result<PreparedStatement> {
         let cmd = ActionCreatePreparedStatementRequest {
             query,
@@ -510,7 +515,6 @@ impl FlightSqlClient {
             dataset_schema: _,
             parameter_schema: _,
         } = statement;
-        // TODO handle parameters (via DoPut)
 
         let cmd = CommandPreparedStatementQuery {
             prepared_statement_handle,
@@ -518,6 +522,70 @@ impl FlightSqlClient {
 
         self.do_get_with_cmd(cmd.as_any()).await
     }
+
+    /// Bind values to the parameters (`$1`, `$name`, ...) of a prepared statement.
+    ///
+    /// `params` must contain a single row with one column per parameter, see
+    /// [`PreparedStatement::get_parameter_schema`].
+    ///
+    /// Sends the parameters to the `DoPut` endpoint of the FlightSQL
+    /// server and returns the prepared statement with the updated
+    /// handle, which can be passed to [`Self::execute`].
+    pub async fn bind(
+        &mut self,
+        statement: PreparedStatement,
+        params: RecordBatch,
+    ) -> Result<PreparedStatement> {
+        let PreparedStatement {
+            prepared_statement_handle,
+            dataset_schema,
+            parameter_schema,
+        } = statement;
+
+        let cmd = CommandPreparedStatementQuery {
+            prepared_statement_handle,
+        };
+        let descriptor = FlightDescriptor::new_cmd(cmd.as_any().encode_to_vec());
+
+        let flight_data = FlightDataEncoderBuilder::new()
+            .with_flight_descriptor(Some(descriptor))
+            .build(futures_util::stream::iter([Ok(params)]));
+
+        let mut results: Vec<PutResult> =
+            self.inner.do_put(flight_data).await?.try_collect().await?;
+
+        if results.len() != 1 {
+            return Err(FlightError::ProtocolError(format!(
+                "Expected 1 response for binding parameters, got {}",
+                results.len()
+            )));
+        }
+        let PutResult { app_metadata } = results.pop().unwrap();
+
+        let DoPutPreparedStatementResult {
+            prepared_statement_handle,
+        } = Message::decode(app_metadata.as_ref())
+            .map_err(|e| FlightError::ExternalError(Box::new(e)))?;
+        let prepared_statement_handle = prepared_statement_handle.ok_or_else(|| {
+            FlightError::protocol("No prepared statement handle in DoPut response")
+        })?;
+
+        Ok(PreparedStatement::new(
+            prepared_statement_handle,
+            dataset_schema,
+            parameter_schema,
+        ))
+    }
+}
+
+/// Response of the FlightSQL server to binding parameters via `DoPut`.
+///
+/// Not yet part of the arrow-flight version in use.
+#[derive(Clone, PartialEq, Message)]
+struct DoPutPreparedStatementResult {
+    /// The updated handle for the prepared statement
+    #[prost(bytes = "bytes", optional, tag = "1")]
+    prepared_statement_handle: Option<Bytes>,
 }
 
 fn schema_bytes_to_schema(schema: Bytes) -> Result<SchemaRef> {
@@ -1,6 +1,8 @@
 use self::generated_types::{persist_service_client::PersistServiceClient, *};
 use crate::{connection::Connection, error::Error};
 use client_util::connection::GrpcConnection;
+use futures_util::stream::BoxStream;
+use tonic::Status;
 
 /// Re-export generated_types
 pub mod generated_types {
@@ -29,4 +31,38 @@ impl Client {
 
         Ok(())
     }
+
+    /// Instruct the ingester to stop accepting writes, persist all of its buffered data and
+    /// truncate its write-ahead log in preparation for it to be stopped.
+    ///
+    /// Returns a stream of the drain progress, ending once the drain is complete.
+    pub async fn drain(
+        &mut self,
+    ) -> Result<BoxStream<'static, Result<DrainResponse, Status>>, Error> {
+        let response = self.inner.drain(DrainRequest {}).await?;
+
+        Ok(Box::pin(response.into_inner()))
+    }
+
+    /// Instruct the ingester to write a copy of the unpersisted data of the specified partition
+    /// to a parquet file in the debug prefix of its object store, without persisting it.
+    ///
+    /// Returns the object store path of the parquet file.
+    pub async fn snapshot_partition(
+        &mut self,
+        namespace: String,
+        table: String,
+        partition_key: String,
+    ) -> Result<String, Error> {
+        let response = self
+            .inner
+            .snapshot_partition(SnapshotPartitionRequest {
+                namespace,
+                table,
+                partition_key,
+            })
+            .await?;
+
+        Ok(response.into_inner().object_store_path)
+    }
 }
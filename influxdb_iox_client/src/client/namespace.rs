@@ -108,6 +108,69 @@ impl Client {
         Ok(response.into_inner().namespace.unwrap_field("namespace")?)
     }
 
+    /// Pause (`paused` is true) or resume compaction of all partitions of a namespace.
+    pub async fn update_namespace_compaction(
+        &mut self,
+        namespace: &str,
+        paused: bool,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_compaction(UpdateNamespaceCompactionRequest {
+                name: namespace.to_string(),
+                compaction_paused: paused,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Set the write rate limits of a namespace. `None` removes the respective limit.
+    pub async fn update_namespace_write_rate_limit(
+        &mut self,
+        namespace: &str,
+        max_write_lines_per_second: Option<i64>,
+        max_write_bytes_per_second: Option<i64>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_write_rate_limit(UpdateNamespaceWriteRateLimitRequest {
+                name: namespace.to_string(),
+                max_write_lines_per_second,
+                max_write_bytes_per_second,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update several settings of a namespace at once. Settings that are `None` are left
+    /// unchanged.
+    ///
+    /// A `retention_period_ns` of 0 sets an infinite retention period. Negative retention
+    /// periods and zero-valued limits are rejected, returning an error.
+    pub async fn update_namespace(
+        &mut self,
+        namespace: &str,
+        retention_period_ns: Option<i64>,
+        max_tables: Option<i32>,
+        max_columns_per_table: Option<i32>,
+        compaction_paused: Option<bool>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace(UpdateNamespaceRequest {
+                name: namespace.to_string(),
+                retention_period_ns,
+                max_tables,
+                max_columns_per_table,
+                compaction_paused,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
     /// Delete a namespace
     pub async fn delete_namespace(&mut self, namespace: &str) -> Result<(), Error> {
         self.inner
@@ -118,4 +181,16 @@ impl Client {
 
         Ok(())
     }
+
+    /// Restore a soft-deleted namespace
+    pub async fn undelete_namespace(&mut self, namespace: &str) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .undelete_namespace(UndeleteNamespaceRequest {
+                name: namespace.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
 }
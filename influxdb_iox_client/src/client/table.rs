@@ -45,4 +45,90 @@ impl Client {
 
         Ok(response.into_inner().table.unwrap_field("table")?)
     }
+
+    /// List the tables of a namespace, ordered by name
+    pub async fn get_tables(&mut self, namespace: &str) -> Result<Vec<Table>, Error> {
+        let response = self
+            .inner
+            .get_tables(GetTablesRequest {
+                namespace: namespace.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().tables)
+    }
+
+    /// Delete a table that has not been written to yet
+    pub async fn delete_table(&mut self, namespace: &str, table: &str) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .delete_table(DeleteTableRequest {
+                namespace: namespace.to_string(),
+                name: table.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Set or remove (`None`) the retention period override of a table
+    pub async fn update_table_retention(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .update_table_retention(UpdateTableRetentionRequest {
+                namespace: namespace.to_string(),
+                name: table.to_string(),
+                retention_period_ns,
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Set the metadata of a column, clearing the fields that are `None`
+    pub async fn update_column_metadata(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        column: &str,
+        description: Option<String>,
+        unit: Option<String>,
+        semantic_type: Option<String>,
+    ) -> Result<ColumnMetadata, Error> {
+        let response = self
+            .inner
+            .update_column_metadata(UpdateColumnMetadataRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                column: column.to_string(),
+                description,
+                unit,
+                semantic_type,
+            })
+            .await?;
+
+        Ok(response.into_inner().metadata.unwrap_field("metadata")?)
+    }
+
+    /// Get the metadata of the columns of a namespace, optionally restricted to a single table
+    pub async fn get_column_metadata(
+        &mut self,
+        namespace: &str,
+        table: Option<&str>,
+    ) -> Result<Vec<ColumnMetadata>, Error> {
+        let response = self
+            .inner
+            .get_column_metadata(GetColumnMetadataRequest {
+                namespace: namespace.to_string(),
+                table: table.map(ToString::to_string),
+            })
+            .await?;
+
+        Ok(response.into_inner().metadata)
+    }
 }
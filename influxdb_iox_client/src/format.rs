@@ -1,12 +1,20 @@
 //! Output formatting utilities for Arrow record batches
 
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, str::FromStr, sync::Arc};
 
 use thiserror::Error;
 
 use arrow::{
-    self, csv::WriterBuilder, error::ArrowError, json::ArrayWriter, record_batch::RecordBatch,
+    self,
+    array::{ArrayRef, StringArray, TimestampNanosecondArray},
+    compute::cast,
+    csv::WriterBuilder,
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    error::ArrowError,
+    json::{ArrayWriter, LineDelimitedWriter},
+    record_batch::RecordBatch,
 };
+use arrow_util::display::timestamp_nanos_to_rfc3339;
 
 /// Output formatting for InfluxQL.
 pub mod influxql;
@@ -15,7 +23,10 @@ pub mod influxql;
 #[derive(Debug, Error)]
 pub enum Error {
     /// Unknown formatting type
-    #[error("Unknown format type: {}. Expected one of 'pretty', 'csv' or 'json'", .0)]
+    #[error(
+        "Unknown format type: {}. Expected one of 'pretty', 'csv', 'json' or 'jsonl'",
+        .0
+    )]
     Invalid(String),
 
     /// Error pretty printing
@@ -37,6 +48,10 @@ pub enum Error {
     /// Error converting JSON output to utf-8
     #[error("Error converting JSON output to UTF-8: {}", .0)]
     JsonUtf8(std::string::FromUtf8Error),
+
+    /// Error converting column types for text output
+    #[error("Error converting columns for output: {}", .0)]
+    TextArrow(ArrowError),
 }
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -49,6 +64,8 @@ pub enum QueryOutputFormat {
     Csv,
     /// Arrow JSON format
     Json,
+    /// Arrow JSON format, one object per line
+    JsonLines,
 }
 
 impl Display for QueryOutputFormat {
@@ -57,6 +74,7 @@ impl Display for QueryOutputFormat {
             QueryOutputFormat::Pretty => write!(f, "pretty"),
             QueryOutputFormat::Csv => write!(f, "csv"),
             QueryOutputFormat::Json => write!(f, "json"),
+            QueryOutputFormat::JsonLines => write!(f, "jsonl"),
         }
     }
 }
@@ -75,6 +93,7 @@ impl FromStr for QueryOutputFormat {
             "pretty" => Ok(Self::Pretty),
             "csv" => Ok(Self::Csv),
             "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
             _ => Err(Error::Invalid(s.to_string())),
         }
     }
@@ -87,6 +106,7 @@ impl QueryOutputFormat {
             Self::Pretty => "text/plain",
             Self::Csv => "text/csv",
             Self::Json => "application/json",
+            Self::JsonLines => "application/x-ndjson",
         }
     }
 }
@@ -119,15 +139,75 @@ impl QueryOutputFormat {
     ///  {"location":"Boston","state":"MA","surface_degrees":50.2,"time":1568756160}
     /// ]
     /// ```
+    ///
+    /// JSON Lines:
+    /// ```text
+    /// {"bottom_degrees":50.4,"location":"santa_monica","state":"CA","surface_degrees":65.2,"time":1568756160}
+    /// {"location":"Boston","state":"MA","surface_degrees":50.2,"time":1568756160}
+    /// ```
+    ///
+    /// In all formats, timestamps are rendered in RFC3339 format and
+    /// dictionary encoded columns as their values, see
+    /// [`prepare_for_text`].
     pub fn format(&self, batches: &[RecordBatch]) -> Result<String> {
         match self {
             Self::Pretty => batches_to_pretty(batches),
             Self::Csv => batches_to_csv(batches),
             Self::Json => batches_to_json(batches),
+            Self::JsonLines => batches_to_json_lines(batches),
         }
     }
 }
 
+/// Prepares a [`RecordBatch`] for the text formats that don't render
+/// all Arrow types the way IOx displays them: dictionary encoded
+/// columns are unpacked to their values, and nanosecond timestamps
+/// are converted to strings in RFC3339 format in UTC
+/// (e.g. `2021-07-20T23:28:50Z`), the same as the pretty format.
+pub fn prepare_for_text(batch: &RecordBatch) -> Result<RecordBatch> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let column = match column.data_type() {
+            DataType::Dictionary(_, value_type) => {
+                cast(column, value_type).map_err(Error::TextArrow)?
+            }
+            _ => Arc::clone(column),
+        };
+
+        let column: ArrayRef = match column.data_type() {
+            DataType::Timestamp(TimeUnit::Nanosecond, None) => {
+                let timestamps = column
+                    .as_any()
+                    .downcast_ref::<TimestampNanosecondArray>()
+                    .expect("timestamp nanosecond array");
+                let strings = timestamps
+                    .iter()
+                    .map(|ts| ts.map(timestamp_nanos_to_rfc3339).transpose())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(Error::TextArrow)?;
+                Arc::new(StringArray::from(strings))
+            }
+            _ => column,
+        };
+
+        fields.push(
+            Field::new(
+                field.name(),
+                column.data_type().clone(),
+                field.is_nullable(),
+            )
+            .with_metadata(field.metadata().clone()),
+        );
+        columns.push(column);
+    }
+
+    let schema = Schema::new_with_metadata(fields, schema.metadata().clone());
+    RecordBatch::try_new(Arc::new(schema), columns).map_err(Error::TextArrow)
+}
+
 fn batches_to_pretty(batches: &[RecordBatch]) -> Result<String> {
     arrow_util::display::pretty_format_batches(batches).map_err(Error::PrettyArrow)
 }
@@ -139,7 +219,9 @@ fn batches_to_csv(batches: &[RecordBatch]) -> Result<String> {
         let mut writer = WriterBuilder::new().has_headers(true).build(&mut bytes);
 
         for batch in batches {
-            writer.write(batch).map_err(Error::CsvArrow)?;
+            writer
+                .write(&prepare_for_text(batch)?)
+                .map_err(Error::CsvArrow)?;
         }
     }
     let csv = String::from_utf8(bytes).map_err(Error::CsvUtf8)?;
@@ -149,13 +231,36 @@ fn batches_to_csv(batches: &[RecordBatch]) -> Result<String> {
 fn batches_to_json(batches: &[RecordBatch]) -> Result<String> {
     let mut bytes = vec![];
 
+    let batches = batches
+        .iter()
+        .map(prepare_for_text)
+        .collect::<Result<Vec<_>>>()?;
     // json writer wants &[&RecordBatch]
     let batches: Vec<_> = batches.iter().collect();
     {
         let mut writer = ArrayWriter::new(&mut bytes);
-        writer.write_batches(&batches).map_err(Error::CsvArrow)?;
+        writer.write_batches(&batches).map_err(Error::JsonArrow)?;
+
+        writer.finish().map_err(Error::JsonArrow)?;
+    }
+
+    let json = String::from_utf8(bytes).map_err(Error::JsonUtf8)?;
+
+    Ok(json)
+}
+
+fn batches_to_json_lines(batches: &[RecordBatch]) -> Result<String> {
+    let mut bytes = vec![];
+
+    {
+        let mut writer = LineDelimitedWriter::new(&mut bytes);
+        for batch in batches {
+            writer
+                .write(&prepare_for_text(batch)?)
+                .map_err(Error::JsonArrow)?;
+        }
 
-        writer.finish().map_err(Error::CsvArrow)?;
+        writer.finish().map_err(Error::JsonArrow)?;
     }
 
     let json = String::from_utf8(bytes).map_err(Error::JsonUtf8)?;
@@ -166,6 +271,10 @@ fn batches_to_json(batches: &[RecordBatch]) -> Result<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use arrow::{
+        array::{DictionaryArray, Float64Array},
+        datatypes::Int32Type,
+    };
 
     #[test]
     fn test_from_str() {
@@ -196,9 +305,14 @@ mod tests {
             QueryOutputFormat::Json
         );
 
+        assert_eq!(
+            QueryOutputFormat::from_str("jsonl").unwrap(),
+            QueryOutputFormat::JsonLines
+        );
+
         assert_eq!(
             QueryOutputFormat::from_str("un").unwrap_err().to_string(),
-            "Unknown format type: un. Expected one of 'pretty', 'csv' or 'json'"
+            "Unknown format type: un. Expected one of 'pretty', 'csv', 'json' or 'jsonl'"
         );
     }
 
@@ -218,5 +332,66 @@ mod tests {
             QueryOutputFormat::from_str(&QueryOutputFormat::Json.to_string()).unwrap(),
             QueryOutputFormat::Json
         );
+
+        assert_eq!(
+            QueryOutputFormat::from_str(&QueryOutputFormat::JsonLines.to_string()).unwrap(),
+            QueryOutputFormat::JsonLines
+        );
+    }
+
+    fn test_batch() -> RecordBatch {
+        let tags: DictionaryArray<Int32Type> =
+            vec![Some("santa_monica"), None, Some("santa_monica")]
+                .into_iter()
+                .collect();
+        let degrees = Float64Array::from(vec![Some(65.2), Some(50.2), None]);
+        let time = TimestampNanosecondArray::from(vec![1_568_756_160_000_000_000, 100, 0]);
+
+        RecordBatch::try_from_iter(vec![
+            ("location", Arc::new(tags) as ArrayRef),
+            ("surface_degrees", Arc::new(degrees) as ArrayRef),
+            ("time", Arc::new(time) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let csv = QueryOutputFormat::Csv.format(&[test_batch()]).unwrap();
+        assert_eq!(
+            csv,
+            "location,surface_degrees,time\n\
+             santa_monica,65.2,2019-09-17T21:36:00Z\n\
+             ,50.2,1970-01-01T00:00:00.000000100Z\n\
+             santa_monica,,1970-01-01T00:00:00Z\n"
+        );
+    }
+
+    #[test]
+    fn test_format_json_lines() {
+        let json = QueryOutputFormat::JsonLines
+            .format(&[test_batch()])
+            .unwrap();
+        assert_eq!(
+            json,
+            "{\"location\":\"santa_monica\",\"surface_degrees\":65.2,\"time\":\"2019-09-17T21:36:00Z\"}\n\
+             {\"surface_degrees\":50.2,\"time\":\"1970-01-01T00:00:00.000000100Z\"}\n\
+             {\"location\":\"santa_monica\",\"time\":\"1970-01-01T00:00:00Z\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_prepare_for_text() {
+        let batch = prepare_for_text(&test_batch()).unwrap();
+        let types: Vec<_> = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.data_type().clone())
+            .collect();
+        assert_eq!(
+            types,
+            vec![DataType::Utf8, DataType::Float64, DataType::Utf8]
+        );
     }
 }
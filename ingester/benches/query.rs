@@ -106,6 +106,7 @@ fn run_projection_bench(
                     table_id: table_id.get(),
                     columns: projection.clone(),
                     predicate: None,
+                    announce_partitions: false,
                 })
                 .await
                 .expect("query request failed");
@@ -162,6 +163,7 @@ fn run_concurrent_bench(
         table_id: table_id.get(),
         columns: vec![],
         predicate: None,
+        announce_partitions: false,
     });
 
     let ctx = Arc::new(ctx);
@@ -261,6 +263,7 @@ fn run_partition_prune_bench(
                     table_id: table_id.get(),
                     columns: vec![],
                     predicate: predicate.clone(),
+                    announce_partitions: false,
                 })
                 .await
                 .expect("query request failed");
@@ -1,5 +1,6 @@
 //! Namespace level data buffer structures.
 
+pub(crate) mod memory;
 pub(crate) mod name_resolver;
 
 use std::sync::Arc;
@@ -0,0 +1,458 @@
+//! Per-namespace accounting of the memory used by buffered data, and
+//! enforcement of per-namespace memory limits.
+//!
+//! A [`NamespaceMemoryEnforcer`] periodically sums the memory used by the
+//! partitions of each namespace, and:
+//!
+//!   * Enqueues the largest buffered partitions of namespaces exceeding the
+//!     soft limit for persistence.
+//!   * Rejects writes to namespaces exceeding the hard limit (through the
+//!     [`NamespaceMemoryLimiter`] on the write path) until enough of their
+//!     data has been persisted.
+//!
+//! This prevents a single namespace from consuming the ingester's memory,
+//! forcing persistence (or write rejection) for all other namespaces.
+
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use data_types::NamespaceId;
+use hashbrown::HashMap;
+use observability_deps::tracing::*;
+use parking_lot::{Mutex, RwLock};
+use thiserror::Error;
+
+use crate::{
+    buffer_tree::partition::PartitionData,
+    dml_payload::IngestOp,
+    dml_sink::{DmlError, DmlSink},
+    partition_iter::PartitionIter,
+    persist::queue::PersistQueue,
+};
+
+/// The interval between evaluations of the per-namespace memory usage.
+const MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An error returned by [`NamespaceMemoryLimits::new`] when the soft limit
+/// exceeds the hard limit.
+#[derive(Debug, Error)]
+#[error(
+    "namespace memory soft limit ({soft_limit_bytes} bytes) must not exceed \
+    the hard limit ({hard_limit_bytes} bytes)"
+)]
+pub struct InvalidNamespaceMemoryLimits {
+    soft_limit_bytes: usize,
+    hard_limit_bytes: usize,
+}
+
+/// Limits on the memory used by the data of a single namespace buffered in
+/// the ingester, including data that is in the process of being persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NamespaceMemoryLimits {
+    soft_limit_bytes: usize,
+    hard_limit_bytes: usize,
+}
+
+impl NamespaceMemoryLimits {
+    /// Persist the buffered data of namespaces using at least
+    /// `soft_limit_bytes`, and reject writes to namespaces using at least
+    /// `hard_limit_bytes`.
+    ///
+    /// Returns an error if `soft_limit_bytes` is greater than
+    /// `hard_limit_bytes`.
+    pub fn new(
+        soft_limit_bytes: usize,
+        hard_limit_bytes: usize,
+    ) -> Result<Self, InvalidNamespaceMemoryLimits> {
+        if soft_limit_bytes > hard_limit_bytes {
+            return Err(InvalidNamespaceMemoryLimits {
+                soft_limit_bytes,
+                hard_limit_bytes,
+            });
+        }
+
+        Ok(Self {
+            soft_limit_bytes,
+            hard_limit_bytes,
+        })
+    }
+}
+
+/// The set of namespaces that have exceeded their hard memory limit, and are
+/// rejecting writes.
+#[derive(Debug, Default)]
+pub(crate) struct RejectedNamespaces(RwLock<HashSet<NamespaceId>>);
+
+impl RejectedNamespaces {
+    /// Returns true if writes to `namespace_id` are currently rejected.
+    pub(crate) fn contains(&self, namespace_id: NamespaceId) -> bool {
+        self.0.read().contains(&namespace_id)
+    }
+
+    /// Replace the set of rejected namespaces with `set`, returning the
+    /// previous set.
+    fn replace(&self, set: HashSet<NamespaceId>) -> HashSet<NamespaceId> {
+        std::mem::replace(&mut *self.0.write(), set)
+    }
+}
+
+/// A [`DmlSink`] decorator that rejects [`IngestOp`] targeting a namespace in
+/// the [`RejectedNamespaces`] set, before passing them to the inner
+/// [`DmlSink`].
+///
+/// This MUST be placed before the write-ahead log in the write path, so that
+/// rejected writes are never committed.
+#[derive(Debug)]
+pub(crate) struct NamespaceMemoryLimiter<T> {
+    inner: T,
+    rejected: Arc<RejectedNamespaces>,
+}
+
+impl<T> NamespaceMemoryLimiter<T> {
+    pub(crate) fn new(inner: T, rejected: Arc<RejectedNamespaces>) -> Self {
+        Self { inner, rejected }
+    }
+}
+
+#[async_trait]
+impl<T> DmlSink for NamespaceMemoryLimiter<T>
+where
+    T: DmlSink,
+{
+    type Error = DmlError;
+
+    async fn apply(&self, op: IngestOp) -> Result<(), Self::Error> {
+        let namespace_id = op.namespace();
+        if self.rejected.contains(namespace_id) {
+            return Err(DmlError::NamespaceMemoryLimit(namespace_id));
+        }
+
+        self.inner.apply(op).await.map_err(Into::into)
+    }
+}
+
+/// The memory usage of a single namespace.
+#[derive(Debug, Default)]
+struct NamespaceUsage {
+    /// The memory used by all data of the namespace, in bytes.
+    total: usize,
+
+    /// The partitions with buffered (not persisting) data, and the estimated
+    /// size of their buffers.
+    buffered: Vec<(usize, Arc<Mutex<PartitionData>>)>,
+}
+
+/// A background task that periodically evaluates the memory used by each
+/// namespace in `buffer`, enforcing the configured [`NamespaceMemoryLimits`].
+#[derive(Debug)]
+pub(crate) struct NamespaceMemoryEnforcer<T, P> {
+    buffer: T,
+    persist_handle: P,
+    limits: NamespaceMemoryLimits,
+    rejected: Arc<RejectedNamespaces>,
+
+    /// A metric tracking the number of partitions persisted because their
+    /// namespace exceeded the soft limit.
+    persist_count: metric::U64Counter,
+    /// A metric tracking the number of namespaces currently rejecting writes.
+    rejected_count: metric::U64Gauge,
+}
+
+impl<T, P> NamespaceMemoryEnforcer<T, P>
+where
+    T: PartitionIter + Sync + 'static,
+    P: PersistQueue + Clone + 'static,
+{
+    pub(crate) fn new(
+        buffer: T,
+        persist_handle: P,
+        limits: NamespaceMemoryLimits,
+        rejected: Arc<RejectedNamespaces>,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let persist_count = metrics
+            .register_metric::<metric::U64Counter>(
+                "ingester_persist_namespace_memory_enqueue_count",
+                "number of times persistence of a partition has been triggered \
+                because its namespace exceeded the memory soft limit",
+            )
+            .recorder(&[]);
+        let rejected_count = metrics
+            .register_metric::<metric::U64Gauge>(
+                "ingester_namespace_memory_rejected_namespaces",
+                "number of namespaces rejecting writes because they exceeded \
+                the memory hard limit",
+            )
+            .recorder(&[]);
+
+        Self {
+            buffer,
+            persist_handle,
+            limits,
+            rejected,
+            persist_count,
+            rejected_count,
+        }
+    }
+
+    /// Evaluate the namespace memory usage every [`MEMORY_CHECK_INTERVAL`].
+    pub(crate) async fn run(self) {
+        let mut interval = tokio::time::interval(MEMORY_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            self.enforce();
+        }
+    }
+
+    /// Enqueue partitions of namespaces over the soft limit for persistence,
+    /// and update the set of namespaces over the hard limit, returning the
+    /// number of partitions enqueued.
+    fn enforce(&self) -> usize {
+        let mut usage: HashMap<NamespaceId, NamespaceUsage> = HashMap::new();
+        for partition in self.buffer.partition_iter() {
+            let (namespace_id, total, buffered) = {
+                let guard = partition.lock();
+                (
+                    guard.namespace_id(),
+                    guard.memory_usage(),
+                    guard.persist_cost_estimate(),
+                )
+            };
+
+            let ns = usage.entry(namespace_id).or_default();
+            ns.total += total;
+            if buffered > 0 {
+                ns.buffered.push((buffered, partition));
+            }
+        }
+
+        let mut rejected = HashSet::new();
+        let mut n = 0;
+        for (namespace_id, mut ns) in usage {
+            if ns.total >= self.limits.hard_limit_bytes {
+                rejected.insert(namespace_id);
+            }
+            if ns.total < self.limits.soft_limit_bytes {
+                continue;
+            }
+
+            // Persist the largest buffers first, until the namespace is
+            // expected to fall below the soft limit once the persist
+            // operations complete.
+            ns.buffered.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+            let mut remaining = ns.total;
+            for (cost, partition) in ns.buffered {
+                if remaining < self.limits.soft_limit_bytes {
+                    break;
+                }
+                if self.persist(namespace_id, partition) {
+                    n += 1;
+                }
+                remaining = remaining.saturating_sub(cost);
+            }
+        }
+
+        self.rejected_count.set(rejected.len() as _);
+        let previous = self.rejected.replace(rejected.clone());
+        for namespace_id in rejected.difference(&previous) {
+            warn!(
+                %namespace_id,
+                "namespace exceeded memory hard limit - rejecting writes"
+            );
+        }
+        for namespace_id in previous.difference(&rejected) {
+            info!(
+                %namespace_id,
+                "namespace below memory hard limit - accepting writes"
+            );
+        }
+
+        self.persist_count.inc(n as _);
+        n
+    }
+
+    /// Mark `partition` as persisting and enqueue it for persistence,
+    /// returning false if it has no data to persist.
+    fn persist(&self, namespace_id: NamespaceId, partition: Arc<Mutex<PartitionData>>) -> bool {
+        // The partition may have been persisted since it was evaluated.
+        let data = match partition.lock().mark_persisting() {
+            Some(v) => v,
+            None => return false,
+        };
+
+        debug!(
+            %namespace_id,
+            partition_id = data.partition_id().get(),
+            "marking partition of namespace over memory soft limit for persistence"
+        );
+
+        // Perform the enqueue in a separate task, to avoid blocking the
+        // evaluation of the remaining namespaces if the persist system is
+        // saturated.
+        let persist_handle = self.persist_handle.clone();
+        tokio::spawn(async move {
+            // There is no need to await on the completion handle.
+            persist_handle.enqueue(partition, data).await;
+        });
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+    use crate::{
+        dml_sink::mock_sink::MockDmlSink,
+        persist::queue::mock::MockPersistQueue,
+        test_util::{
+            make_write_op, PartitionDataBuilder, ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY,
+            ARBITRARY_TABLE_ID, ARBITRARY_TABLE_NAME,
+        },
+    };
+
+    const OTHER_NAMESPACE_ID: NamespaceId = NamespaceId::new(42);
+
+    fn new_partition(namespace_id: NamespaceId, rows: i64) -> Arc<Mutex<PartitionData>> {
+        let mut p = PartitionDataBuilder::new()
+            .with_namespace_id(namespace_id)
+            .build();
+        for ts in 0..rows {
+            let mb = lp_to_mutable_batch(&format!(
+                r#"{},city=Hereford people=1 {ts}"#,
+                &*ARBITRARY_TABLE_NAME
+            ))
+            .1;
+            p.buffer_write(mb, SequenceNumber::new(ts as _))
+                .expect("write should succeed");
+        }
+        Arc::new(Mutex::new(p))
+    }
+
+    #[tokio::test]
+    async fn test_limiter() {
+        let rejected = Arc::new(RejectedNamespaces::default());
+        rejected.replace([OTHER_NAMESPACE_ID].into_iter().collect());
+
+        let mock = Arc::new(MockDmlSink::default().with_apply_return([Ok(())]));
+        let limiter = NamespaceMemoryLimiter::new(Arc::clone(&mock), rejected);
+
+        let op = |namespace_id| {
+            IngestOp::Write(make_write_op(
+                &ARBITRARY_PARTITION_KEY,
+                namespace_id,
+                &ARBITRARY_TABLE_NAME,
+                ARBITRARY_TABLE_ID,
+                0,
+                &format!("{},city=Madrid day=\"sun\" 42", &*ARBITRARY_TABLE_NAME),
+                None,
+            ))
+        };
+
+        limiter
+            .apply(op(ARBITRARY_NAMESPACE_ID))
+            .await
+            .expect("write should succeed");
+
+        let err = limiter
+            .apply(op(OTHER_NAMESPACE_ID))
+            .await
+            .expect_err("write to rejected namespace should fail");
+        assert_matches!(err, DmlError::NamespaceMemoryLimit(id) => {
+            assert_eq!(id, OTHER_NAMESPACE_ID);
+        });
+
+        // The rejected write must not reach the inner sink.
+        assert_eq!(mock.get_calls().len(), 1);
+    }
+
+    #[test]
+    fn test_limits_validation() {
+        assert_matches!(NamespaceMemoryLimits::new(0, 0), Ok(_));
+        assert_matches!(NamespaceMemoryLimits::new(1, 2), Ok(_));
+
+        let err = NamespaceMemoryLimits::new(3, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "namespace memory soft limit (3 bytes) must not exceed the hard limit (2 bytes)"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_enforce_limits() {
+        // Two partitions in one namespace, one of which is larger than the
+        // other, and a small partition in another namespace.
+        let big = new_partition(ARBITRARY_NAMESPACE_ID, 10);
+        let small = new_partition(ARBITRARY_NAMESPACE_ID, 1);
+        let other = new_partition(OTHER_NAMESPACE_ID, 1);
+
+        let big_size = big.lock().memory_usage();
+        let small_size = small.lock().memory_usage();
+        assert!(big_size > small_size);
+        assert_eq!(other.lock().memory_usage(), small_size);
+
+        // Persisting the big partition brings the namespace below the soft
+        // limit, which the other namespace never exceeds.
+        let limits = NamespaceMemoryLimits::new(small_size + 1, big_size + small_size).unwrap();
+
+        let metrics = metric::Registry::default();
+        let rejected = Arc::new(RejectedNamespaces::default());
+        let persist_handle = Arc::new(MockPersistQueue::default());
+        let enforcer = NamespaceMemoryEnforcer::new(
+            vec![Arc::clone(&small), Arc::clone(&other), Arc::clone(&big)],
+            Arc::clone(&persist_handle),
+            limits,
+            Arc::clone(&rejected),
+            &metrics,
+        );
+
+        assert_eq!(enforcer.enforce(), 1);
+        tokio::task::yield_now().await;
+        assert_matches!(persist_handle.calls().as_slice(), [got] => {
+            assert!(Arc::ptr_eq(got, &big));
+        });
+
+        // The namespace hit the hard limit.
+        assert!(rejected.contains(ARBITRARY_NAMESPACE_ID));
+        assert!(!rejected.contains(OTHER_NAMESPACE_ID));
+
+        metric::assert_counter!(
+            metrics,
+            metric::U64Counter,
+            "ingester_persist_namespace_memory_enqueue_count",
+            value = 1,
+        );
+        metric::assert_counter!(
+            metrics,
+            metric::U64Gauge,
+            "ingester_namespace_memory_rejected_namespaces",
+            value = 1,
+        );
+
+        // Complete the persist operation, releasing the memory.
+        drop(enforcer);
+        Arc::try_unwrap(persist_handle)
+            .expect("should be no more refs")
+            .join()
+            .await;
+        assert_eq!(big.lock().memory_usage(), 0);
+
+        // Once the persisted data is released, writes are accepted again.
+        let persist_handle = Arc::new(MockPersistQueue::default());
+        let enforcer = NamespaceMemoryEnforcer::new(
+            vec![small, other, big],
+            Arc::clone(&persist_handle),
+            limits,
+            Arc::clone(&rejected),
+            &metrics,
+        );
+        assert_eq!(enforcer.enforce(), 0);
+        assert!(!rejected.contains(ARBITRARY_NAMESPACE_ID));
+        tokio::task::yield_now().await;
+        assert!(persist_handle.calls().is_empty());
+    }
+}
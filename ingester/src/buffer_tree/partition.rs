@@ -2,13 +2,18 @@
 
 use std::{collections::VecDeque, sync::Arc};
 
+use arrow::{
+    array::{Array, BooleanArray, TimestampNanosecondArray},
+    compute::filter_record_batch,
+    record_batch::RecordBatch,
+};
 use data_types::{
     sequence_number_set::SequenceNumberSet, NamespaceId, PartitionHashId, PartitionId,
     PartitionKey, SequenceNumber, TableId, TimestampMinMax, TransitionPartitionId,
 };
 use mutable_batch::MutableBatch;
 use observability_deps::tracing::*;
-use schema::sort::SortKey;
+use schema::{sort::SortKey, TIME_COLUMN_NAME};
 
 use self::{
     buffer::{traits::Queryable, BufferState, DataBuffer, Persisting},
@@ -88,8 +93,10 @@ pub struct PartitionData {
     /// forward iteration order matches write order.
     ///
     /// The [`BatchIdent`] is a generational counter that is used to tag each
-    /// persisting with a unique, opaque identifier.
-    persisting: VecDeque<(BatchIdent, BufferState<Persisting>)>,
+    /// persisting with a unique, opaque identifier, followed by the number of
+    /// [`PersistingData`] parts of the buffer that have not yet been persisted
+    /// (see [`Self::mark_persisting_split()`]).
+    persisting: VecDeque<(BatchIdent, usize, BufferState<Persisting>)>,
 
     /// The number of persist operations started over the lifetime of this
     /// [`PartitionData`].
@@ -98,6 +105,10 @@ pub struct PartitionData {
     /// The number of persist operations completed over the lifetime of this
     /// [`PartitionData`].
     completed_persistence_count: u64,
+
+    /// The highest [`SequenceNumber`] of the writes persisted over the
+    /// lifetime of this [`PartitionData`], if any.
+    max_persisted_sequence_number: Option<SequenceNumber>,
 }
 
 impl PartitionData {
@@ -126,6 +137,7 @@ impl PartitionData {
             persisting: VecDeque::with_capacity(1),
             started_persistence_count: BatchIdent::default(),
             completed_persistence_count: 0,
+            max_persisted_sequence_number: None,
         }
     }
 
@@ -156,6 +168,19 @@ impl PartitionData {
         self.buffer.persist_cost_estimate()
     }
 
+    /// Return an estimate of the memory used by the data in this
+    /// [`PartitionData`], in bytes.
+    ///
+    /// This value is inclusive of "hot" buffered data, and all currently
+    /// persisting data.
+    pub(crate) fn memory_usage(&self) -> usize {
+        self.persisting
+            .iter()
+            .map(|(_, _, v)| v.size())
+            .sum::<usize>()
+            + self.buffer.persist_cost_estimate()
+    }
+
     /// Returns the number of rows currently buffered in this [`PartitionData`].
     ///
     /// The returned value will always match the row count of the data returned
@@ -169,7 +194,11 @@ impl PartitionData {
     /// persisting batches, plus 1 for the "hot" buffer. Reading the row count
     /// of each batch is `O(1)`. This method is expected to be fast.
     pub(crate) fn rows(&self) -> usize {
-        self.persisting.iter().map(|(_, v)| v.rows()).sum::<usize>() + self.buffer.rows()
+        self.persisting
+            .iter()
+            .map(|(_, _, v)| v.rows())
+            .sum::<usize>()
+            + self.buffer.rows()
     }
 
     /// Return the timestamp min/max values for the data contained within this
@@ -189,7 +218,7 @@ impl PartitionData {
     pub(crate) fn timestamp_stats(&self) -> Option<TimestampMinMax> {
         self.persisting
             .iter()
-            .map(|(_, v)| {
+            .map(|(_, _, v)| {
                 v.timestamp_stats()
                     .expect("persisting batches must be non-empty")
             })
@@ -214,7 +243,7 @@ impl PartitionData {
         let data = self
             .persisting
             .iter()
-            .flat_map(|(_, b)| b.get_query_data(projection))
+            .flat_map(|(_, _, b)| b.get_query_data(projection))
             .chain(buffered_data)
             .collect::<Vec<_>>();
 
@@ -279,7 +308,29 @@ impl PartitionData {
     /// serialised (unless it can be known in advance no sort key update is
     /// necessary for a given persistence).
     pub(crate) fn mark_persisting(&mut self) -> Option<PersistingData> {
-        let fsm = std::mem::take(&mut self.buffer).into_persisting()?;
+        self.mark_persisting_split(1).pop()
+    }
+
+    /// Snapshot and mark all buffered data as persisting, splitting the data
+    /// into at most `max_parts` [`PersistingData`] covering disjoint time
+    /// ranges that can be persisted independently, and concurrently.
+    ///
+    /// This method returns an empty [`Vec`] if no data is buffered in
+    /// [`Self`].
+    ///
+    /// The buffered data is retained (and remains queryable) until
+    /// [`Self::mark_persisted()`] has been called for every returned part.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_parts` is 0.
+    pub(crate) fn mark_persisting_split(&mut self, max_parts: usize) -> Vec<PersistingData> {
+        assert_ne!(max_parts, 0, "must split into at least one part");
+
+        let fsm = match std::mem::take(&mut self.buffer).into_persisting() {
+            Some(v) => v,
+            None => return vec![],
+        };
 
         // From this point on, all code MUST be infallible or the buffered data
         // contained within persisting may be dropped.
@@ -290,6 +341,13 @@ impl PartitionData {
         // mark_persisted() call.
         let batch_ident = self.started_persistence_count.next();
 
+        let parts = split_by_time(
+            fsm.get_query_data(&OwnedProjection::default()),
+            fsm.timestamp_stats()
+                .expect("persisting batches must be non-empty"),
+            max_parts,
+        );
+
         debug!(
             namespace_id = %self.namespace_id,
             table_id = %self.table_id,
@@ -297,25 +355,36 @@ impl PartitionData {
             partition_id = %self.partition_id,
             partition_key = %self.partition_key,
             %batch_ident,
+            n_parts = parts.len(),
             "marking partition as persisting"
         );
 
-        // Wrap the persisting data in the type wrapper
-        let data = PersistingData::new(
-            QueryAdaptor::new(
-                self.partition_id,
-                self.transition_partition_id(),
-                fsm.get_query_data(&OwnedProjection::default()),
-            ),
-            batch_ident,
-        );
+        // Wrap each part of the persisting data in the type wrapper
+        let data = parts
+            .into_iter()
+            .map(|batches| {
+                PersistingData::new(
+                    QueryAdaptor::new(self.partition_id, self.transition_partition_id(), batches),
+                    batch_ident,
+                )
+            })
+            .collect::<Vec<_>>();
 
         // Push the new buffer to the back of the persisting queue, so that
         // iterating from back to front during queries iterates over writes from
         // oldest to newest.
-        self.persisting.push_back((batch_ident, fsm));
+        self.persisting.push_back((batch_ident, data.len(), fsm));
 
-        Some(data)
+        data
+    }
+
+    /// Returns the number of snapshots of this partition that are currently
+    /// persisting.
+    ///
+    /// A partition that accumulates persisting snapshots is being written to
+    /// faster than it can be persisted.
+    pub(crate) fn persisting_batches(&self) -> usize {
+        self.persisting.len()
     }
 
     /// Mark this partition as having completed persistence of the specified
@@ -332,15 +401,38 @@ impl PartitionData {
         let idx = self
             .persisting
             .iter()
-            .position(|(old, _)| *old == batch.batch_ident())
+            .position(|(old, _, _)| *old == batch.batch_ident())
             .expect("no currently persisting batch");
 
+        self.completed_persistence_count += 1;
+
+        // If the buffer was split into several parts, retain it until the last
+        // part is persisted, so that the buffered data remains queryable and
+        // its writes are not released from the WAL prematurely.
+        let outstanding_parts = &mut self.persisting[idx].1;
+        *outstanding_parts -= 1;
+        if *outstanding_parts > 0 {
+            debug!(
+                batch_ident = %batch.batch_ident(),
+                outstanding_parts = *outstanding_parts,
+                persistence_count = %self.completed_persistence_count,
+                partition_id = %self.partition_id,
+                partition_key = %self.partition_key,
+                "marking partition persistence of part complete"
+            );
+            return SequenceNumberSet::default();
+        }
+
         // Remove the batch from the queue, preserving the order of the queue
         // for batch iteration during queries.
-        let (old_ident, fsm) = self.persisting.remove(idx).unwrap();
+        let (old_ident, _, fsm) = self.persisting.remove(idx).unwrap();
         assert_eq!(old_ident, batch.batch_ident());
 
-        self.completed_persistence_count += 1;
+        let sequence_numbers = fsm.into_sequence_number_set();
+        self.max_persisted_sequence_number = sequence_numbers
+            .iter()
+            .max()
+            .max(self.max_persisted_sequence_number);
 
         debug!(
             batch_ident = %old_ident,
@@ -355,7 +447,7 @@ impl PartitionData {
         );
 
         // Return the set of IDs this buffer contained.
-        fsm.into_sequence_number_set()
+        sequence_numbers
     }
 
     pub(crate) fn partition_id(&self) -> PartitionId {
@@ -375,6 +467,12 @@ impl PartitionData {
         self.completed_persistence_count
     }
 
+    /// Return the highest [`SequenceNumber`] persisted for this
+    /// [`PartitionData`] instance, if any.
+    pub(crate) fn max_persisted_sequence_number(&self) -> Option<SequenceNumber> {
+        self.max_persisted_sequence_number
+    }
+
     /// Return the metadata of the table this [`PartitionData`] is buffering writes
     /// for.
     pub(crate) fn table(&self) -> &Arc<DeferredLoad<TableMetadata>> {
@@ -420,6 +518,54 @@ impl PartitionData {
     }
 }
 
+/// Split the rows in `batches` into at most `n` sets of [`RecordBatch`], each
+/// covering a disjoint, contiguous range of the `ts` time range.
+///
+/// All rows with the same timestamp are placed in the same set, and the rows
+/// within each set retain their relative order, preserving the semantics of
+/// deduplicating each set independently. Sets containing no rows are omitted.
+fn split_by_time(
+    batches: Vec<RecordBatch>,
+    ts: TimestampMinMax,
+    n: usize,
+) -> Vec<Vec<RecordBatch>> {
+    if n == 1 {
+        return vec![batches];
+    }
+
+    // The width of the time range covered by each part, rounded up such that
+    // the maximum timestamp falls within the last part.
+    let width = (ts.max as i128 - ts.min as i128) / n as i128 + 1;
+
+    let mut parts = vec![vec![]; n];
+    for batch in batches {
+        let part_idx = batch
+            .column_by_name(TIME_COLUMN_NAME)
+            .expect("persisting batch must contain time column")
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .expect("time column must be a nanosecond timestamp")
+            .values()
+            .iter()
+            .map(|t| ((*t as i128 - ts.min as i128) / width) as usize)
+            .collect::<Vec<_>>();
+
+        for (i, part) in parts.iter_mut().enumerate() {
+            let mask = part_idx
+                .iter()
+                .map(|v| Some(*v == i))
+                .collect::<BooleanArray>();
+            let b = filter_record_batch(&batch, &mask).expect("time filter must succeed");
+            if b.num_rows() > 0 {
+                part.push(b);
+            }
+        }
+    }
+
+    parts.retain(|p| !p.is_empty());
+    parts
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
@@ -913,9 +1059,14 @@ mod tests {
 
         // Finish persisting the second batch out-of-order! The middle entry,
         // ensuring the first and last entries remain ordered.
+        assert_eq!(p.max_persisted_sequence_number(), None);
         let set = p.mark_persisted(persisting_data2);
         assert_eq!(set.len(), 1);
         assert!(set.contains(SequenceNumber::new(3)));
+        assert_eq!(
+            p.max_persisted_sequence_number(),
+            Some(SequenceNumber::new(3))
+        );
 
         let data = p.get_query_data(&OwnedProjection::default()).unwrap();
         assert_batches_eq!(
@@ -935,6 +1086,10 @@ mod tests {
         let set = p.mark_persisted(persisting_data3);
         assert_eq!(set.len(), 1);
         assert!(set.contains(SequenceNumber::new(4)));
+        assert_eq!(
+            p.max_persisted_sequence_number(),
+            Some(SequenceNumber::new(4))
+        );
 
         let data = p.get_query_data(&OwnedProjection::default()).unwrap();
         assert_batches_eq!(
@@ -954,6 +1109,12 @@ mod tests {
         assert_eq!(set.len(), 1);
         assert!(set.contains(SequenceNumber::new(1)));
 
+        // The persisted watermark never regresses.
+        assert_eq!(
+            p.max_persisted_sequence_number(),
+            Some(SequenceNumber::new(4))
+        );
+
         // Assert only the buffered data remains
         let data = p.get_query_data(&OwnedProjection::default()).unwrap();
         assert_batches_eq!(
@@ -1083,6 +1244,71 @@ mod tests {
         assert!(p.mark_persisting().is_none());
     }
 
+    // Ensure a buffer split into several parts by time is retained until all
+    // parts are persisted, and only then releases its sequence numbers.
+    #[tokio::test]
+    async fn test_mark_persisting_split() {
+        let mut p = PartitionDataBuilder::new().build();
+
+        // No data, no parts.
+        assert!(p.mark_persisting_split(2).is_empty());
+
+        for (ts, n) in [(10, 1), (30, 2), (20, 3), (10, 4)] {
+            let mb = lp_to_mutable_batch(&format!(r#"bananas,city=London people={n} {ts}"#)).1;
+            p.buffer_write(mb, SequenceNumber::new(n))
+                .expect("write should succeed");
+        }
+
+        let parts = p.mark_persisting_split(2);
+        assert_eq!(p.persisting_batches(), 1);
+        let (part1, part2) = assert_matches!(parts.as_slice(), [a, b] => (a.clone(), b.clone()));
+
+        // Each part covers a disjoint time range, with rows of the same
+        // timestamp in the same part, in write order.
+        assert_batches_eq!(
+            [
+                "+--------+--------+--------------------------------+",
+                "| city   | people | time                           |",
+                "+--------+--------+--------------------------------+",
+                "| London | 1.0    | 1970-01-01T00:00:00.000000010Z |",
+                "| London | 3.0    | 1970-01-01T00:00:00.000000020Z |",
+                "| London | 4.0    | 1970-01-01T00:00:00.000000010Z |",
+                "+--------+--------+--------------------------------+",
+            ],
+            part1.record_batches()
+        );
+        assert_batches_eq!(
+            [
+                "+--------+--------+--------------------------------+",
+                "| city   | people | time                           |",
+                "+--------+--------+--------------------------------+",
+                "| London | 2.0    | 1970-01-01T00:00:00.000000030Z |",
+                "+--------+--------+--------------------------------+",
+            ],
+            part2.record_batches()
+        );
+
+        // Persisting one part does not release the buffer, or its sequence
+        // numbers.
+        let set = p.mark_persisted(part2);
+        assert!(set.is_empty());
+        assert_eq!(p.rows(), 4);
+        assert_eq!(p.persisting_batches(), 1);
+        assert_eq!(p.completed_persistence_count(), 1);
+        assert_eq!(p.max_persisted_sequence_number(), None);
+
+        // Until the last part is persisted.
+        let set = p.mark_persisted(part1);
+        assert_eq!(set.len(), 4);
+        assert_eq!(p.rows(), 0);
+        assert_eq!(p.persisting_batches(), 0);
+        assert_eq!(p.completed_persistence_count(), 2);
+        assert_eq!(
+            p.max_persisted_sequence_number(),
+            Some(SequenceNumber::new(4))
+        );
+    }
+
     // Ensure an empty PartitionData does not panic due to constructing an empty
     // QueryAdaptor.
     #[tokio::test]
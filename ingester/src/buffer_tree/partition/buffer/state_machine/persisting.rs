@@ -20,6 +20,9 @@ pub(crate) struct Persisting {
     /// Statistics describing the data in snapshots.
     row_count: usize,
     timestamp_stats: TimestampMinMax,
+
+    /// The memory footprint of the snapshots, in bytes.
+    size: usize,
 }
 
 impl Persisting {
@@ -39,10 +42,13 @@ impl Persisting {
             compute_timenanosecond_min_max(snapshots.iter()).unwrap()
         );
 
+        let size = snapshots.iter().map(|v| v.get_array_memory_size()).sum();
+
         Self {
             snapshots,
             row_count,
             timestamp_stats,
+            size,
         }
     }
 }
@@ -62,6 +68,11 @@ impl Queryable for Persisting {
 }
 
 impl BufferState<Persisting> {
+    /// Return the memory footprint of the data being persisted, in bytes.
+    pub(crate) fn size(&self) -> usize {
+        self.state.size
+    }
+
     /// Consume `self` and all references to the buffered data, returning the owned
     /// [`SequenceNumberSet`] within it.
     pub(crate) fn into_sequence_number_set(self) -> SequenceNumberSet {
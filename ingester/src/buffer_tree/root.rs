@@ -812,6 +812,49 @@ mod tests {
         ]
     );
 
+    // This test asserts that time and tag predicates are used to filter the
+    // rows of buffered data before it is returned, while predicates on field
+    // values are left to the querier (they must be evaluated after
+    // deduplication).
+    test_write_query!(
+        filter_rows_by_time_and_tag_predicate,
+        partitions = [PartitionDataBuilder::new()
+            .with_partition_id(ARBITRARY_PARTITION_ID)
+            .with_partition_key(ARBITRARY_PARTITION_KEY.clone())
+            .build()],
+        writes = [make_write_op(
+            &ARBITRARY_PARTITION_KEY,
+            ARBITRARY_NAMESPACE_ID,
+            &ARBITRARY_TABLE_NAME,
+            ARBITRARY_TABLE_ID,
+            0,
+            &format!(
+                "{t},region=Asturias temp=35 1000\n\
+                {t},region=Asturias temp=12 2000\n\
+                {t},region=Madrid temp=17 2000\n\
+                {t},region=Madrid temp=13 3000",
+                t = &*ARBITRARY_TABLE_NAME
+            ),
+            None,
+        )],
+        predicate = Some(
+            Predicate::new()
+                .with_range(1500, 3000)
+                .with_expr(col("region").eq(lit(ScalarValue::Dictionary(
+                    Box::new(DataType::Int32),
+                    Box::new(ScalarValue::from("Madrid"))
+                ))))
+                .with_expr(col("temp").gt(lit(100.0)))
+        ),
+        want = [
+            "+--------+------+----------------------------+",
+            "| region | temp | time                       |",
+            "+--------+------+----------------------------+",
+            "| Madrid | 17.0 | 1970-01-01T00:00:00.000002 |",
+            "+--------+------+----------------------------+",
+        ]
+    );
+
     /// Ensure partition pruning during query execution also prunes metadata
     /// frames.
     ///
@@ -2,22 +2,30 @@
 
 pub(crate) mod metadata_resolver;
 
-use std::{collections::HashMap, fmt::Debug, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    sync::Arc,
+};
 
+use arrow::{compute::filter_record_batch, record_batch::RecordBatch};
 use async_trait::async_trait;
 use data_types::{
     partition_template::{build_column_values, ColumnValue, TablePartitionTemplateOverride},
     NamespaceId, PartitionKey, SequenceNumber, Table, TableId,
 };
-use datafusion::scalar::ScalarValue;
+use datafusion::{common::cast::as_boolean_array, error::DataFusionError, scalar::ScalarValue};
 use iox_query::{
     chunk_statistics::{create_chunk_statistics, ColumnRange},
     pruning::prune_summaries,
+    util::df_physical_expr_from_schema,
     QueryChunk,
 };
 use mutable_batch::MutableBatch;
+use observability_deps::tracing::warn;
 use parking_lot::Mutex;
 use predicate::Predicate;
+use schema::{Schema, TIME_COLUMN_NAME};
 use trace::span::{Span, SpanRecorder};
 
 use super::{
@@ -273,12 +281,22 @@ where
         let partitions = self.partitions().into_iter().filter_map(move |p| {
             let mut span = span.child("partition read");
 
-            let (id, hash_id, completed_persistence_count, data, partition_key) = {
+            let (
+                id,
+                hash_id,
+                completed_persistence_count,
+                max_persisted_sequence_number,
+                buffered_row_count,
+                data,
+                partition_key,
+            ) = {
                 let mut p = p.lock();
                 (
                     p.partition_id(),
                     p.partition_hash_id().cloned(),
                     p.completed_persistence_count(),
+                    p.max_persisted_sequence_number(),
+                    p.rows() as u64,
                     p.get_query_data(&projection),
                     p.partition_key().clone(),
                 )
@@ -321,14 +339,31 @@ where
                         return None;
                     }
 
+                    // Only send the rows that match the predicate. The
+                    // partition is still returned if no rows remain, because
+                    // its persisted data may match.
+                    let batches = match &predicate {
+                        Some(p) => filter_rows(data.into_record_batches(), p),
+                        None => data.into_record_batches(),
+                    };
+
                     PartitionResponse::new(
-                        data.into_record_batches(),
+                        batches,
                         id,
                         hash_id,
                         completed_persistence_count,
+                        max_persisted_sequence_number,
+                        buffered_row_count,
                     )
                 }
-                None => PartitionResponse::new(vec![], id, hash_id, completed_persistence_count),
+                None => PartitionResponse::new(
+                    vec![],
+                    id,
+                    hash_id,
+                    completed_persistence_count,
+                    max_persisted_sequence_number,
+                    buffered_row_count,
+                ),
             };
 
             span.ok("read partition data");
@@ -415,7 +450,7 @@ fn keep_after_pruning_partition_key(
     );
 
     let chunk_statistics = Arc::new(create_chunk_statistics(
-        data.num_rows(),
+        Some(data.num_rows()),
         data.schema(),
         data.ts_min_max(),
         &column_ranges,
@@ -439,6 +474,68 @@ fn keep_after_pruning_partition_key(
     .unwrap_or(true)
 }
 
+/// Remove the rows from `batches` that do not match `predicate`, so that they
+/// are not transferred to the querier.
+///
+/// Only the time range and expressions that exclusively reference tag and time
+/// columns are evaluated. These columns form the primary key, so rows that the
+/// querier deduplicates against each other are either all kept or all removed.
+/// Expressions on field values must be evaluated after deduplication and are
+/// left to the querier.
+///
+/// Like partition pruning, this is a mere optimization: batches that the
+/// predicate cannot be evaluated against are returned unfiltered.
+fn filter_rows(batches: Vec<RecordBatch>, predicate: &Predicate) -> Vec<RecordBatch> {
+    batches
+        .into_iter()
+        .filter_map(|batch| match filter_batch(&batch, predicate) {
+            Ok(filtered) => (filtered.num_rows() > 0).then_some(filtered),
+            Err(e) => {
+                warn!(%e, %predicate, "failed to filter buffered data, returning it unfiltered");
+                Some(batch)
+            }
+        })
+        .collect()
+}
+
+fn filter_batch(
+    batch: &RecordBatch,
+    predicate: &Predicate,
+) -> Result<RecordBatch, DataFusionError> {
+    let schema =
+        Schema::try_from(batch.schema()).map_err(|e| DataFusionError::External(Box::new(e)))?;
+    let primary_key = schema.primary_key().into_iter().collect::<HashSet<_>>();
+
+    let pushdown = Predicate {
+        range: predicate
+            .range
+            .filter(|_| primary_key.contains(TIME_COLUMN_NAME)),
+        exprs: predicate
+            .exprs
+            .iter()
+            .filter(|expr| {
+                expr.to_columns()
+                    .map(|columns| {
+                        columns
+                            .iter()
+                            .all(|col| primary_key.contains(col.name.as_str()))
+                    })
+                    .unwrap_or_default()
+            })
+            .cloned()
+            .collect(),
+        ..Default::default()
+    };
+    let Some(expr) = pushdown.filter_expr() else {
+        return Ok(batch.clone());
+    };
+
+    let expr = df_physical_expr_from_schema(batch.schema(), expr)?;
+    let mask = expr.evaluate(batch)?.into_array(batch.num_rows());
+    let mask = as_boolean_array(&mask)?;
+    Ok(filter_record_batch(batch, mask)?)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
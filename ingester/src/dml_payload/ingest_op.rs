@@ -2,6 +2,7 @@ use data_types::{sequence_number_set::SequenceNumberSet, NamespaceId};
 use trace::ctx::SpanContext;
 
 use super::write::WriteOperation;
+use crate::wal::wal_sink::WriteAckMode;
 
 /// The set of operations which the ingester can derive and process from wire
 /// requests
@@ -26,6 +27,13 @@ impl IngestOp {
         }
     }
 
+    /// The [`WriteAckMode`] requested for the [`IngestOp`], if any.
+    pub fn ack_mode(&self) -> Option<WriteAckMode> {
+        match self {
+            Self::Write(w) => w.ack_mode(),
+        }
+    }
+
     /// Construct a new [`SequenceNumberSet`] containing all the sequence
     /// numbers in this op.
     pub fn sequence_number_set(&self) -> SequenceNumberSet {
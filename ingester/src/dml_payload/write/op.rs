@@ -3,6 +3,7 @@ use hashbrown::HashMap;
 use trace::ctx::SpanContext;
 
 use super::table_data::TableData;
+use crate::wal::wal_sink::WriteAckMode;
 
 /// A decoded representation of the data contained by an RPC write
 /// represented by an [`crate::dml_payload::IngestOp::Write`]
@@ -14,6 +15,10 @@ pub struct WriteOperation {
     partition_key: PartitionKey,
 
     span_context: Option<SpanContext>,
+
+    /// An optional per-request override of the deployment's
+    /// [`WriteAckMode`].
+    ack_mode: Option<WriteAckMode>,
 }
 
 impl WriteOperation {
@@ -37,6 +42,7 @@ impl WriteOperation {
             tables,
             partition_key,
             span_context,
+            ack_mode: None,
         }
     }
 
@@ -49,6 +55,7 @@ impl WriteOperation {
             tables: Default::default(),
             partition_key,
             span_context: None,
+            ack_mode: None,
         }
     }
 
@@ -78,4 +85,16 @@ impl WriteOperation {
     pub fn span_context(&self) -> Option<&SpanContext> {
         self.span_context.as_ref()
     }
+
+    /// Acknowledge this write according to `mode`, regardless of the
+    /// [`WriteAckMode`] configured for the ingester.
+    pub fn with_ack_mode(mut self, mode: WriteAckMode) -> Self {
+        self.ack_mode = Some(mode);
+        self
+    }
+
+    /// The [`WriteAckMode`] requested for this write, if any.
+    pub fn ack_mode(&self) -> Option<WriteAckMode> {
+        self.ack_mode
+    }
 }
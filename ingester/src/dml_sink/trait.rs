@@ -2,6 +2,7 @@ use std::{error::Error, fmt::Debug, ops::Deref, sync::Arc};
 
 use crate::dml_payload::IngestOp;
 use async_trait::async_trait;
+use data_types::NamespaceId;
 use thiserror::Error;
 
 /// Errors returned due from calls to [`DmlSink::apply()`].
@@ -21,6 +22,12 @@ pub enum DmlError {
     /// retrying indefinitely.
     #[error("buffer apply request timeout")]
     ApplyTimeout,
+
+    /// The namespace the [`IngestOp`] targets has exceeded its buffer memory
+    /// limit, and writes to it are rejected until the buffered data is
+    /// persisted.
+    #[error("namespace {0} exceeds the ingester buffer memory limit")]
+    NamespaceMemoryLimit(NamespaceId),
 }
 
 /// A [`DmlSink`] handles [`IngestOp`] instances in some abstract way.
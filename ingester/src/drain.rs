@@ -0,0 +1,308 @@
+//! Drain all buffered data from an ingester, in preparation for it to be
+//! stopped.
+
+use std::{sync::Arc, time::Duration};
+
+use futures::{future::BoxFuture, FutureExt};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use tokio::sync::watch;
+
+use crate::{
+    ingest_state::{IngestState, IngestStateError},
+    partition_iter::PartitionIter,
+    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+    wal::reference_tracker::WalReferenceHandle,
+};
+
+/// Defines how often the drain task polls the partition buffers for
+/// emptiness.
+///
+/// Polls faster in tests to avoid unnecessary delay.
+#[cfg(test)]
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+#[cfg(not(test))]
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The progress of a drain operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DrainProgress {
+    /// The drain has been requested, but has not yet started.
+    Pending,
+
+    /// Ingest is blocked, and the buffered data is being persisted.
+    Persisting {
+        /// The number of partitions with data that has not yet been persisted.
+        unpersisted_partitions: usize,
+    },
+
+    /// All data has been persisted, and the WAL is being truncated.
+    TruncatingWal,
+
+    /// All data has been persisted, and no WAL segments remain to be replayed.
+    Complete,
+}
+
+/// Block ingest, persist all buffered data and truncate the WAL, reporting
+/// the progress of each step to `progress`.
+///
+/// Returns once all outstanding persist jobs have completed (regardless of what
+/// started them), all buffered data has been flushed to object store, and all
+/// WAL segments have been deleted.
+///
+/// Correctly accounts for persist jobs that have been started (by a call to
+/// [`PartitionData::mark_persisting()`] but not yet enqueued).
+///
+/// Ingest is blocked by setting [`IngestStateError::GracefulStop`] in the
+/// [`IngestState`].
+///
+/// [`PartitionData::mark_persisting()`]:
+///     crate::buffer_tree::partition::PartitionData::mark_persisting()
+pub(crate) async fn drain<T, P>(
+    ingest_state: &IngestState,
+    buffer: &T,
+    persist: &P,
+    wal: &wal::Wal,
+    wal_reference_handle: &WalReferenceHandle,
+    progress: &watch::Sender<DrainProgress>,
+) where
+    T: PartitionIter + Sync,
+    P: PersistQueue + Clone,
+{
+    // Reject RPC writes.
+    //
+    // There MAY be writes ongoing that started before this state was set.
+    ingest_state.set(IngestStateError::GracefulStop);
+
+    info!("persisting all buffered data");
+    progress.send_replace(DrainProgress::Persisting {
+        unpersisted_partitions: unpersisted_partitions(buffer),
+    });
+
+    // Drain the buffer tree, persisting all data.
+    //
+    // Returns once the persist jobs it starts have complete.
+    persist_partitions(buffer.partition_iter(), persist).await;
+
+    // There may have been concurrent persist jobs started previously by hot
+    // partition persistence or WAL rotation (or some other, arbitrary persist
+    // source) that have not yet completed (this is unlikely). There may also be
+    // late arriving writes that started before ingest was blocked, but did not
+    // buffer until after the persist was completed above (also unlikely).
+    //
+    // Wait until there is no data in the buffer at all before proceeding,
+    // therefore ensuring those concurrent persist operations have completed and
+    // no late arriving data remains buffered.
+    //
+    // NOTE: There is a small race in which a late arriving write starts before
+    // ingest is blocked, is then stalled the entire time partitions are
+    // persisted, remains stalled while this "empty" check occurs, and then
+    // springs to life and buffers in the buffer tree after this check has
+    // completed - I think this is extreme enough to accept as a theoretical
+    // possibility that doesn't need covering off in practice.
+    loop {
+        let n = unpersisted_partitions(buffer);
+        progress.send_replace(DrainProgress::Persisting {
+            unpersisted_partitions: n,
+        });
+        if n == 0 {
+            break;
+        }
+
+        if persist_partitions(buffer.partition_iter(), persist).await != 0 {
+            // Late arriving writes needed persisting.
+            debug!("re-persisting late arriving data");
+        } else {
+            // At least one partition is returning data, and there is no data to
+            // start persisting, therefore there is an outstanding persist
+            // operation that hasn't yet been marked as complete.
+            debug!("waiting for concurrent persist to complete");
+        }
+
+        tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+    }
+
+    progress.send_replace(DrainProgress::TruncatingWal);
+
+    // Register interest with the WAL reference handle to notify this thread
+    // when there are no tracked inactive WAL segments, ensuring they are
+    // deleted before shutdown (so as not to be replayed).
+    //
+    // This future MUST be created before the active WAL segment is rotated
+    // out and enqueued, but `await`ed on afterwards. Failure to do so may
+    // cause the notifier to deadlock if the inactive segment tracking set
+    // empties before the notifier is created.
+    //
+    // TL;DR: Please read the docs for [`WalReferenceHandle::empty_inactive_notifier()`]
+    // before moving this about.
+    let empty_waker = wal_reference_handle.empty_inactive_notifier();
+
+    // There is now no data buffered in the ingester - all data has been
+    // persisted to object storage.
+    //
+    // We can rotate the open WAL segment and notify the reference handle
+    // that the segment's file can be deleted because everything has been
+    // persisted.
+    let (closed_segment, sequence_number_set) = wal.rotate().expect("failed to rotate wal");
+    let rx = wal_reference_handle
+        .enqueue_rotated_file(closed_segment.id(), sequence_number_set)
+        .await;
+    if let Err(e) = rx.await {
+        error!(%e, "encountered failure waiting on file rotation receiver during drain");
+    };
+
+    // Wait for the file rotation to be processed and the tracked set
+    // to drop to empty.
+    empty_waker.await;
+
+    info!("persisted all data and truncated wal");
+    progress.send_replace(DrainProgress::Complete);
+}
+
+/// Return the number of partitions in `buffer` containing data that has not
+/// yet been persisted.
+fn unpersisted_partitions<T>(buffer: &T) -> usize
+where
+    T: PartitionIter,
+{
+    buffer
+        .partition_iter()
+        .filter(|p| p.lock().rows() != 0)
+        .count()
+}
+
+/// A handle to start a single, shared [`drain()`] operation on demand, and
+/// observe its progress.
+pub(crate) struct DrainHandle {
+    progress: watch::Receiver<DrainProgress>,
+
+    /// The drain task, taken and spawned by the first call to
+    /// [`DrainHandle::drain()`].
+    task: Mutex<Option<BoxFuture<'static, ()>>>,
+}
+
+impl std::fmt::Debug for DrainHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DrainHandle")
+            .field("progress", &*self.progress.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DrainHandle {
+    /// Initialise a [`DrainHandle`] that drains `buffer` into `persist` when
+    /// first requested.
+    pub(crate) fn new<T, P>(
+        ingest_state: Arc<IngestState>,
+        buffer: T,
+        persist: P,
+        wal: Arc<wal::Wal>,
+        wal_reference_handle: WalReferenceHandle,
+    ) -> Self
+    where
+        T: PartitionIter + Sync + 'static,
+        P: PersistQueue + Clone + Sync + 'static,
+    {
+        let (tx, progress) = watch::channel(DrainProgress::Pending);
+        let task = async move {
+            drain(
+                &ingest_state,
+                &buffer,
+                &persist,
+                &wal,
+                &wal_reference_handle,
+                &tx,
+            )
+            .await
+        }
+        .boxed();
+
+        Self {
+            progress,
+            task: Mutex::new(Some(task)),
+        }
+    }
+
+    /// Start draining the ingester if it is not already draining, returning a
+    /// receiver of the drain progress.
+    pub(crate) fn drain(&self) -> watch::Receiver<DrainProgress> {
+        if let Some(task) = self.task.lock().take() {
+            info!("draining ingester");
+            tokio::spawn(task);
+        }
+        self.progress.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use test_helpers::timeout::FutureTimeout;
+
+    use super::*;
+    use crate::{
+        persist::queue::mock::MockPersistQueue,
+        test_util::{PartitionDataBuilder, ARBITRARY_TABLE_NAME},
+    };
+
+    #[tokio::test]
+    async fn test_drain_handle() {
+        let mut partition = PartitionDataBuilder::new().build();
+        let mb = lp_to_mutable_batch(&format!(
+            r#"{},city=London people=2,pigeons="millions" 10"#,
+            &*ARBITRARY_TABLE_NAME
+        ))
+        .1;
+        partition
+            .buffer_write(mb, SequenceNumber::new(1))
+            .expect("failed to write dummy data");
+        let partition = Arc::new(Mutex::new(partition));
+
+        let dir = tempfile::tempdir().expect("failed to get temporary WAL directory");
+        let wal = wal::Wal::new(dir.path())
+            .await
+            .expect("failed to initialise WAL to write");
+        let (wal_reference_handle, wal_reference_actor) =
+            WalReferenceHandle::new(Arc::clone(&wal), &metric::Registry::default());
+        tokio::spawn(wal_reference_actor.run());
+
+        let ingest_state = Arc::new(IngestState::default());
+        let persist = Arc::new(MockPersistQueue::new_with_observer(
+            wal_reference_handle.clone(),
+        ));
+
+        let handle = DrainHandle::new(
+            Arc::clone(&ingest_state),
+            vec![Arc::clone(&partition)],
+            Arc::clone(&persist),
+            Arc::clone(&wal),
+            wal_reference_handle,
+        );
+
+        // The drain does not start until requested.
+        assert!(ingest_state.read().is_ok());
+
+        let mut rx = handle.drain();
+
+        // Wait for the drain to complete.
+        async {
+            while *rx.borrow_and_update() != DrainProgress::Complete {
+                rx.changed().await.expect("drain task stopped");
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        // Writes are rejected, the data was persisted and the WAL truncated.
+        assert_matches!(ingest_state.read(), Err(IngestStateError::GracefulStop));
+        assert_matches!(persist.calls().as_slice(), [p] => {
+            assert!(Arc::ptr_eq(p, &partition));
+        });
+        assert!(wal.closed_segments().is_empty());
+
+        // Subsequent calls observe the completed drain.
+        assert_eq!(*handle.drain().borrow(), DrainProgress::Complete);
+    }
+}
@@ -16,6 +16,7 @@ use generated_types::influxdata::iox::{
 };
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
+use iox_time::SystemProvider;
 use observability_deps::tracing::*;
 use parquet_file::storage::ParquetStorage;
 use thiserror::Error;
@@ -24,9 +25,19 @@ use tokio_util::sync::CancellationToken;
 use tracker::DiskSpaceMetrics;
 use wal::Wal;
 
+pub use crate::{
+    buffer_tree::namespace::memory::{InvalidNamespaceMemoryLimits, NamespaceMemoryLimits},
+    persist::closed_partitions::ClosedPartitionPolicy,
+    wal::wal_sink::WriteAckMode,
+};
+pub use wal::SegmentCompression as WalCompression;
+
 use crate::{
     buffer_tree::{
-        namespace::name_resolver::{NamespaceNameProvider, NamespaceNameResolver},
+        namespace::{
+            memory::{NamespaceMemoryEnforcer, NamespaceMemoryLimiter, RejectedNamespaces},
+            name_resolver::{NamespaceNameProvider, NamespaceNameResolver},
+        },
         partition::resolver::{
             CatalogPartitionResolver, CoalescePartitionResolver, PartitionCache, PartitionProvider,
         },
@@ -34,11 +45,12 @@ use crate::{
         BufferTree,
     },
     dml_sink::{instrumentation::DmlSinkInstrumentation, tracing::DmlSinkTracing},
+    drain::DrainHandle,
     ingest_state::IngestState,
     ingester_id::IngesterId,
     persist::{
-        file_metrics::ParquetFileInstrumentation, handle::PersistHandle,
-        hot_partitions::HotPartitionPersister,
+        closed_partitions::ClosedPartitionPersister, file_metrics::ParquetFileInstrumentation,
+        handle::PersistHandle, hot_partitions::HotPartitionPersister,
     },
     query::{
         exec_instrumentation::QueryExecInstrumentation,
@@ -106,6 +118,16 @@ pub struct IngesterGuard<T> {
     /// Aborted on drop.
     disk_metric_task: tokio::task::JoinHandle<()>,
 
+    /// The handle of the closed partition persistence task, if enabled.
+    ///
+    /// Aborted on drop.
+    closed_partition_task: Option<tokio::task::JoinHandle<()>>,
+
+    /// The handle of the namespace memory limit enforcement task, if enabled.
+    ///
+    /// Aborted on drop.
+    namespace_memory_task: Option<tokio::task::JoinHandle<()>>,
+
     /// The task handle executing the graceful shutdown once triggered.
     graceful_shutdown_handler: tokio::task::JoinHandle<()>,
     shutdown_complete: Shared<oneshot::Receiver<()>>,
@@ -133,6 +155,12 @@ impl<T> Drop for IngesterGuard<T> {
     fn drop(&mut self) {
         self.rotation_task.abort();
         self.disk_metric_task.abort();
+        if let Some(task) = &self.closed_partition_task {
+            task.abort();
+        }
+        if let Some(task) = &self.namespace_memory_task {
+            task.abort();
+        }
         self.graceful_shutdown_handler.abort();
     }
 }
@@ -169,6 +197,21 @@ pub enum InitError {
 ///
 /// Any error during replay is fatal.
 ///
+/// The entries of new WAL segment files are compressed with
+/// `wal_compression`. Existing files are replayed using the compression
+/// recorded in their headers, regardless of this setting.
+///
+/// ## Write Acknowledgement
+///
+/// By default writes are acknowledged to the caller only once their WAL entry
+/// has been fsync'd ([`WriteAckMode::Strict`]). If `write_ack_mode` is
+/// [`WriteAckMode::Fast`], writes are acknowledged as soon as they are
+/// buffered, trading durability of acknowledged writes for lower write
+/// latency.
+///
+/// Individual write requests can override this setting by specifying the
+/// `iox-write-ack` request header with a value of either `strict` or `fast`.
+///
 /// ## Graceful Shutdown
 ///
 /// When `shutdown` completes, the ingester blocks ingest (returning an error to
@@ -182,6 +225,16 @@ pub enum InitError {
 /// The ingester will continue answering queries until the gRPC server is
 /// stopped by the caller (managed outside of this crate).
 ///
+/// ## Draining
+///
+/// The same block-persist-truncate sequence can be started ahead of shutdown
+/// through the `Drain` RPC of the persist service, which streams the progress
+/// of the drain to the caller. This allows orchestrators to wait for the
+/// ingester to be drained before stopping it, rather than relying on the
+/// termination grace period being long enough to persist all buffered data.
+///
+/// Once drained, writes are rejected while queries continue to be served.
+///
 /// ## Deferred Loading for Persist Operations
 ///
 /// Several items within the ingester's internal state are loaded only when
@@ -225,6 +278,28 @@ pub enum InitError {
 /// Decreasing this value increases the frequency of persist operations, and
 /// usually decreases the size of the resulting parquet files.
 ///
+/// ## Closed Partition Persistence
+///
+/// If a `persist_closed_partition_policy` is provided, the buffered partitions
+/// are periodically evaluated, and those whose partition time window has
+/// closed (plus a grace period for late-arriving writes) are enqueued for
+/// persistence without waiting for the next WAL rotation.
+///
+/// This reduces the time data from closed windows spends in the ingester
+/// buffer, and produces files for these windows that are not overlapped by
+/// later persist operations.
+///
+/// ## Namespace Memory Limits
+///
+/// If `namespace_memory_limits` are provided, the memory used by the buffered
+/// and persisting data of each namespace is periodically evaluated.
+///
+/// Namespaces exceeding the soft limit have their largest partitions enqueued
+/// for persistence, and writes to namespaces exceeding the hard limit are
+/// rejected with a retryable error until enough of their data has been
+/// persisted. This prevents a single namespace from exhausting the memory
+/// available to the buffers of all namespaces.
+///
 /// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
 #[allow(clippy::too_many_arguments)]
 pub async fn new<F>(
@@ -233,10 +308,14 @@ pub async fn new<F>(
     persist_background_fetch_time: Duration,
     wal_directory: PathBuf,
     wal_rotation_period: Duration,
+    wal_compression: WalCompression,
+    write_ack_mode: WriteAckMode,
     persist_executor: Arc<Executor>,
     persist_workers: usize,
     persist_queue_depth: usize,
     persist_hot_partition_cost: usize,
+    persist_closed_partition_policy: Option<ClosedPartitionPolicy>,
+    namespace_memory_limits: Option<NamespaceMemoryLimits>,
     object_store: ParquetStorage,
     shutdown: F,
 ) -> Result<IngesterGuard<impl IngesterRpcInterface>, InitError>
@@ -296,7 +375,7 @@ where
     let ingest_state = Arc::new(IngestState::default());
 
     // Initialise the WAL
-    let wal = Wal::new(wal_directory.clone())
+    let wal = Wal::new_with_compression(wal_directory.clone(), wal_compression)
         .await
         .map_err(InitError::WalInit)?;
 
@@ -310,8 +389,8 @@ where
         persist_workers,
         persist_queue_depth,
         Arc::clone(&ingest_state),
-        persist_executor,
-        object_store,
+        Arc::clone(&persist_executor),
+        object_store.clone(),
         Arc::clone(&catalog),
         // Register a post-persistence observer that emits Parquet file
         // attributes as metrics, and notifies the WAL segment reference tracker of
@@ -363,21 +442,32 @@ where
             .await
             .map_err(|e| InitError::WalReplay(e.into()))?;
 
+    // The set of namespaces rejecting writes because they exceed their memory
+    // limit, which remains empty if namespace memory limits are not enabled.
+    let rejected_namespaces = Arc::new(RejectedNamespaces::default());
+
     // Build the chain of DmlSink that forms the write path.
+    //
+    // Writes to namespaces over their memory limit are rejected before they
+    // are committed to the WAL.
     let write_path = DmlSinkInstrumentation::new(
         "write_apply",
         DmlSinkTracing::new(
-            DmlSinkTracing::new(
-                WalSink::new(
-                    DmlSinkInstrumentation::new(
-                        "buffer",
-                        DmlSinkTracing::new(Arc::clone(&buffer), "buffer"),
-                        &metrics,
+            NamespaceMemoryLimiter::new(
+                DmlSinkTracing::new(
+                    WalSink::new(
+                        DmlSinkInstrumentation::new(
+                            "buffer",
+                            DmlSinkTracing::new(Arc::clone(&buffer), "buffer"),
+                            &metrics,
+                        ),
+                        Arc::clone(&wal),
+                        wal_reference_handle.clone(),
+                        write_ack_mode,
                     ),
-                    Arc::clone(&wal),
-                    wal_reference_handle.clone(),
+                    "wal",
                 ),
-                "wal",
+                Arc::clone(&rejected_namespaces),
             ),
             "write_apply",
         ),
@@ -401,6 +491,36 @@ where
         Arc::clone(&persist_handle),
     ));
 
+    // Spawn a background task to persist partitions with closed time windows,
+    // if configured.
+    let closed_partition_task = persist_closed_partition_policy.map(|policy| {
+        tokio::spawn(
+            ClosedPartitionPersister::new(
+                Arc::clone(&buffer),
+                Arc::clone(&persist_handle),
+                policy,
+                SystemProvider::new(),
+                &metrics,
+            )
+            .run(),
+        )
+    });
+
+    // Spawn a background task to enforce the per-namespace memory limits, if
+    // configured.
+    let namespace_memory_task = namespace_memory_limits.map(|limits| {
+        tokio::spawn(
+            NamespaceMemoryEnforcer::new(
+                Arc::clone(&buffer),
+                Arc::clone(&persist_handle),
+                limits,
+                rejected_namespaces,
+                &metrics,
+            )
+            .run(),
+        )
+    });
+
     // Restore the highest sequence number from the WAL files, and default to 0
     // if there were no files to replay.
     //
@@ -411,6 +531,15 @@ where
         max_sequence_number.map(|v| v.get()).unwrap_or(0),
     ));
 
+    // Prepare the drain operation, started on demand by the drain RPC.
+    let drain = Arc::new(DrainHandle::new(
+        Arc::clone(&ingest_state),
+        Arc::clone(&buffer),
+        Arc::clone(&persist_handle),
+        Arc::clone(&wal),
+        wal_reference_handle.clone(),
+    ));
+
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
     let shutdown_task = tokio::spawn(graceful_shutdown_handler(
         shutdown,
@@ -433,9 +562,14 @@ where
             metrics,
             buffer,
             persist_handle,
+            drain,
+            object_store,
+            persist_executor,
         ),
         rotation_task,
         disk_metric_task,
+        closed_partition_task,
+        namespace_memory_task,
         graceful_shutdown_handler: shutdown_task,
         shutdown_complete: shutdown_rx.shared(),
     })
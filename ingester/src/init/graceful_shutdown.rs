@@ -1,40 +1,23 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use futures::Future;
 use observability_deps::tracing::*;
-use tokio::sync::oneshot;
+use tokio::sync::{oneshot, watch};
 use tokio_util::sync::CancellationToken;
 
 use crate::{
-    ingest_state::{IngestState, IngestStateError},
+    drain::{drain, DrainProgress},
+    ingest_state::IngestState,
     partition_iter::PartitionIter,
-    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
-    query::projection::OwnedProjection,
+    persist::queue::PersistQueue,
     wal::reference_tracker::WalReferenceHandle,
 };
 
-/// Defines how often the shutdown task polls the partition buffers for
-/// emptiness.
-///
-/// Polls faster in tests to avoid unnecessary delay.
-#[cfg(test)]
-const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(50);
-#[cfg(not(test))]
-const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_secs(1);
-
 /// Awaits `fut`, before blocking ingest and persisting all data.
 ///
 /// Returns once all outstanding persist jobs have completed (regardless of what
-/// started them) and all buffered data has been flushed to object store.
-///
-/// Correctly accounts for persist jobs that have been started (by a call to
-/// [`PartitionData::mark_persisting()`] but not yet enqueued).
-///
-/// Ingest is blocked by setting [`IngestStateError::GracefulStop`] in the
-/// [`IngestState`].
-///
-/// [`PartitionData::mark_persisting()`]:
-///     crate::buffer_tree::partition::PartitionData::mark_persisting()
+/// started them) and all buffered data has been flushed to object store. See
+/// [`drain()`] for details.
 pub(super) async fn graceful_shutdown_handler<F, T, P>(
     fut: F,
     complete: oneshot::Sender<()>,
@@ -52,82 +35,19 @@ pub(super) async fn graceful_shutdown_handler<F, T, P>(
     let rpc_server_stop = fut.await;
     info!("gracefully stopping ingester");
 
-    // Reject RPC writes.
-    //
-    // There MAY be writes ongoing that started before this state was set.
-    ingest_state.set(IngestStateError::GracefulStop);
-
-    info!("persisting all data before shutdown");
-
-    // Drain the buffer tree, persisting all data.
-    //
-    // Returns once the persist jobs it starts have complete.
-    persist_partitions(buffer.partition_iter(), &persist).await;
-
-    // There may have been concurrent persist jobs started previously by hot
-    // partition persistence or WAL rotation (or some other, arbitrary persist
-    // source) that have not yet completed (this is unlikely). There may also be
-    // late arriving writes that started before ingest was blocked, but did not
-    // buffer until after the persist was completed above (also unlikely).
-    //
-    // Wait until there is no data in the buffer at all before proceeding,
-    // therefore ensuring those concurrent persist operations have completed and
-    // no late arriving data remains buffered.
-    //
-    // NOTE: There is a small race in which a late arriving write starts before
-    // ingest is blocked, is then stalled the entire time partitions are
-    // persisted, remains stalled while this "empty" check occurs, and then
-    // springs to life and buffers in the buffer tree after this check has
-    // completed - I think this is extreme enough to accept as a theoretical
-    // possibility that doesn't need covering off in practice.
-    while buffer.partition_iter().any(|p| {
-        p.lock()
-            .get_query_data(&OwnedProjection::default())
-            .is_some()
-    }) {
-        if persist_partitions(buffer.partition_iter(), &persist).await != 0 {
-            // Late arriving writes needed persisting.
-            debug!("re-persisting late arriving data");
-        } else {
-            // At least one partition is returning data, and there is no data to
-            // start persisting, therefore there is an outstanding persist
-            // operation that hasn't yet been marked as complete.
-            debug!("waiting for concurrent persist to complete");
-        }
-
-        tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
-    }
-
-    // Register interest with the WAL reference handle to notify this thread
-    // when there are no tracked inactive WAL segments, ensuring they are
-    // deleted before shutdown (so as not to be replayed).
-    //
-    // This future MUST be created before the active WAL segment is rotated
-    // out and enqueued, but `await`ed on afterwards. Failure to do so may
-    // cause the notifier to deadlock if the inactive segment tracking set
-    // empties before the notifier is created.
+    // Block ingest, persist all buffered data and truncate the WAL.
     //
-    // TL;DR: Please read the docs for [`WalReferenceHandle::empty_inactive_notifier()`]
-    // before moving this about.
-    let empty_waker = wal_reference_handle.empty_inactive_notifier();
-
-    // There is now no data buffered in the ingester - all data has been
-    // persisted to object storage.
-    //
-    // We can rotate the open WAL segment and notify the reference handle
-    // that the segment's file can be deleted because everything has been
-    // persisted.
-    let (closed_segment, sequence_number_set) = wal.rotate().expect("failed to rotate wal");
-    let rx = wal_reference_handle
-        .enqueue_rotated_file(closed_segment.id(), sequence_number_set)
-        .await;
-    if let Err(e) = rx.await {
-        error!(%e, "encountered failure waiting on file rotation receiver during shutdown");
-    };
-
-    // Wait for the file rotation to be processed and the tracked set
-    // to drop to empty.
-    empty_waker.await;
+    // The progress is only reported to callers of the drain RPC.
+    let (progress, _) = watch::channel(DrainProgress::Pending);
+    drain(
+        &ingest_state,
+        &buffer,
+        &persist,
+        &wal,
+        &wal_reference_handle,
+        &progress,
+    )
+    .await;
 
     info!("persisted all data - stopping ingester");
 
@@ -139,7 +59,7 @@ pub(super) async fn graceful_shutdown_handler<F, T, P>(
 
 #[cfg(test)]
 mod tests {
-    use std::{future::ready, sync::Arc, task::Poll};
+    use std::{future::ready, sync::Arc, task::Poll, time::Duration};
 
     use assert_matches::assert_matches;
     use data_types::SequenceNumber;
@@ -292,7 +292,7 @@ mod tests {
             ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_ID, ARBITRARY_PARTITION_KEY,
             ARBITRARY_TABLE_ID, ARBITRARY_TABLE_NAME,
         },
-        wal::wal_sink::{mock::MockUnbufferedWriteNotifier, WalSink},
+        wal::wal_sink::{mock::MockUnbufferedWriteNotifier, WalSink, WriteAckMode},
     };
 
     use super::*;
@@ -400,6 +400,7 @@ mod tests {
                 Arc::clone(&inner),
                 Arc::clone(&wal),
                 Arc::clone(&notifier_handle),
+                WriteAckMode::Strict,
             );
 
             // Apply the first op through the decorator
@@ -0,0 +1,292 @@
+use std::time::Duration;
+
+use iox_time::{Time, TimeProvider};
+use observability_deps::tracing::*;
+
+use crate::partition_iter::PartitionIter;
+
+use super::queue::PersistQueue;
+
+/// The interval between evaluations of the buffered partitions for closed
+/// partition time windows.
+const CLOSURE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Configuration of when the time window of a partition is considered closed,
+/// and the partition persisted.
+///
+/// Partition time windows are assumed to be `window` long and aligned to the
+/// UNIX epoch, as is the case for the hourly and daily partitions produced by
+/// the `%Y-%m-%d %H` and `%Y-%m-%d` partition templates.
+///
+/// The window of a partition is derived from the newest timestamp buffered in
+/// it, and considered closed once `grace` has elapsed since the end of the
+/// window, allowing late-arriving writes to be buffered before persisting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClosedPartitionPolicy {
+    window_nanos: i64,
+    grace: Duration,
+}
+
+impl ClosedPartitionPolicy {
+    /// Consider partitions with time windows of length `window` closed once
+    /// `grace` has elapsed since the end of their window.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is zero, or too large to be represented as
+    /// nanoseconds in an `i64`.
+    pub fn new(window: Duration, grace: Duration) -> Self {
+        let window_nanos = i64::try_from(window.as_nanos()).expect("partition window too large");
+        assert!(window_nanos > 0, "partition window must be non-zero");
+
+        Self {
+            window_nanos,
+            grace,
+        }
+    }
+
+    /// Returns true if the partition time window containing the timestamp
+    /// `max_timestamp` closed at least the grace period before `now`.
+    fn is_closed(&self, max_timestamp: i64, now: Time) -> bool {
+        let window_end = match max_timestamp
+            .div_euclid(self.window_nanos)
+            .checked_add(1)
+            .and_then(|v| v.checked_mul(self.window_nanos))
+        {
+            Some(v) => Time::from_timestamp_nanos(v),
+            // The window ends beyond the range of representable timestamps.
+            None => return false,
+        };
+
+        window_end
+            .checked_add(self.grace)
+            .map(|closed_at| now >= closed_at)
+            .unwrap_or_default()
+    }
+}
+
+/// A background task that periodically enqueues the partitions in `buffer`
+/// whose partition time window has closed according to `policy` for
+/// persistence.
+///
+/// Persisting partitions once no more writes are expected for them produces
+/// files that do not overlap with those of subsequent persist operations,
+/// reducing compaction work.
+#[derive(Debug)]
+pub(crate) struct ClosedPartitionPersister<T, P, C> {
+    buffer: T,
+    persist_handle: P,
+    policy: ClosedPartitionPolicy,
+    time_provider: C,
+
+    /// A metric tracking the number of partitions persisted as "closed
+    /// partitions".
+    persist_count: metric::U64Counter,
+}
+
+impl<T, P, C> ClosedPartitionPersister<T, P, C>
+where
+    T: PartitionIter + Sync + 'static,
+    P: PersistQueue + Clone + 'static,
+    C: TimeProvider,
+{
+    pub(crate) fn new(
+        buffer: T,
+        persist_handle: P,
+        policy: ClosedPartitionPolicy,
+        time_provider: C,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let persist_count = metrics
+            .register_metric::<metric::U64Counter>(
+                "ingester_persist_closed_partition_enqueue_count",
+                "number of times persistence of a partition has been triggered \
+                because its partition time window closed",
+            )
+            .recorder(&[]);
+
+        Self {
+            buffer,
+            persist_handle,
+            policy,
+            time_provider,
+            persist_count,
+        }
+    }
+
+    /// Evaluate the buffered partitions every [`CLOSURE_CHECK_INTERVAL`],
+    /// enqueuing closed partitions for persistence.
+    pub(crate) async fn run(self) {
+        let mut interval = tokio::time::interval(CLOSURE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let n = self.persist_closed();
+            if n > 0 {
+                info!(
+                    n_partitions = n,
+                    "enqueued closed partitions for persistence"
+                );
+            }
+        }
+    }
+
+    /// Mark all closed partitions with buffered data as persisting, and
+    /// enqueue them for persistence, returning the number of partitions
+    /// enqueued.
+    fn persist_closed(&self) -> usize {
+        let now = self.time_provider.now();
+
+        let mut n = 0;
+        for partition in self.buffer.partition_iter() {
+            let data = {
+                let mut guard = partition.lock();
+
+                // Evaluate and mark the partition atomically, so that a write
+                // landing concurrently cannot re-open the window unobserved.
+                let closed = guard
+                    .timestamp_stats()
+                    .map(|ts| self.policy.is_closed(ts.max, now))
+                    .unwrap_or_default();
+                if !closed {
+                    continue;
+                }
+
+                // Skip partitions with no data that isn't already persisting.
+                match guard.mark_persisting() {
+                    Some(v) => v,
+                    None => continue,
+                }
+            };
+
+            debug!(
+                partition_id = data.partition_id().get(),
+                "marking closed partition for persistence"
+            );
+
+            // Perform the enqueue in a separate task, to avoid blocking the
+            // evaluation of the remaining partitions if the persist system is
+            // saturated.
+            let persist_handle = self.persist_handle.clone();
+            tokio::spawn(async move {
+                // There is no need to await on the completion handle.
+                persist_handle.enqueue(partition, data).await;
+            });
+
+            n += 1;
+        }
+
+        self.persist_count.inc(n as _);
+        n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use iox_time::MockProvider;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::{
+        buffer_tree::partition::PartitionData,
+        persist::queue::mock::MockPersistQueue,
+        test_util::{PartitionDataBuilder, ARBITRARY_TABLE_NAME},
+    };
+
+    const HOUR: Duration = Duration::from_secs(60 * 60);
+    const HOUR_NANOS: i64 = 60 * 60 * 1_000_000_000;
+
+    #[test]
+    fn test_is_closed() {
+        let policy = ClosedPartitionPolicy::new(HOUR, Duration::from_secs(60));
+        let at = Time::from_timestamp_nanos;
+        let minute = 60 * 1_000_000_000;
+
+        // The window of a timestamp ends at the next window boundary.
+        assert!(!policy.is_closed(0, at(HOUR_NANOS + minute - 1)));
+        assert!(policy.is_closed(0, at(HOUR_NANOS + minute)));
+        assert!(!policy.is_closed(HOUR_NANOS - 1, at(HOUR_NANOS + minute - 1)));
+        assert!(policy.is_closed(HOUR_NANOS - 1, at(HOUR_NANOS + minute)));
+        assert!(!policy.is_closed(HOUR_NANOS, at(HOUR_NANOS + minute)));
+
+        // Pre-epoch windows are aligned to the epoch too.
+        assert!(policy.is_closed(-1, at(minute)));
+        assert!(!policy.is_closed(-1, at(minute - 1)));
+
+        // Windows ending beyond the representable range never close.
+        assert!(!policy.is_closed(i64::MAX, at(i64::MAX)));
+    }
+
+    #[tokio::test]
+    async fn test_persist_closed_partitions() {
+        let write = |p: &Arc<Mutex<PartitionData>>, ts: i64, seq| {
+            let mb = lp_to_mutable_batch(&format!(
+                r#"{},city=Hereford people=1 {ts}"#,
+                &*ARBITRARY_TABLE_NAME
+            ))
+            .1;
+            p.lock()
+                .buffer_write(mb, SequenceNumber::new(seq))
+                .expect("write should succeed");
+        };
+
+        let p = Arc::new(Mutex::new(PartitionDataBuilder::new().build()));
+        write(&p, 10, 1);
+
+        let metrics = metric::Registry::default();
+        let persist_handle = Arc::new(MockPersistQueue::default());
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+
+        let persister = ClosedPartitionPersister::new(
+            vec![Arc::clone(&p)],
+            Arc::clone(&persist_handle),
+            ClosedPartitionPolicy::new(HOUR, HOUR),
+            Arc::clone(&time_provider),
+            &metrics,
+        );
+
+        // The window is open until the end of the grace period.
+        time_provider.set(Time::from_timestamp_nanos(2 * HOUR_NANOS - 1));
+        assert_eq!(persister.persist_closed(), 0);
+        tokio::task::yield_now().await;
+        assert!(persist_handle.calls().is_empty());
+
+        time_provider.set(Time::from_timestamp_nanos(2 * HOUR_NANOS));
+        assert_eq!(persister.persist_closed(), 1);
+        tokio::task::yield_now().await;
+        assert_matches!(persist_handle.calls().as_slice(), [got] => {
+            assert!(Arc::ptr_eq(got, &p));
+        });
+
+        metric::assert_counter!(
+            metrics,
+            metric::U64Counter,
+            "ingester_persist_closed_partition_enqueue_count",
+            value = 1,
+        );
+
+        // Nothing is left to persist until another write arrives.
+        assert_eq!(persister.persist_closed(), 0);
+
+        // A late write to the closed window is persisted on the next check,
+        // while a write to the current window is not.
+        write(&p, 20, 2);
+        assert_eq!(persister.persist_closed(), 1);
+        write(&p, 2 * HOUR_NANOS, 3);
+        assert_eq!(persister.persist_closed(), 0);
+        tokio::task::yield_now().await;
+        assert_eq!(persist_handle.calls().len(), 2);
+
+        // Check persist completion.
+        drop(persister);
+        Arc::try_unwrap(persist_handle)
+            .expect("should be no more refs")
+            .join()
+            .await;
+        assert_eq!(p.lock().completed_persistence_count(), 2);
+    }
+}
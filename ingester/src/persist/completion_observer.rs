@@ -184,6 +184,7 @@ mod tests {
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            tag_ranges: None,
         }
     }
 
@@ -1,12 +1,30 @@
 use std::{fmt::Debug, sync::Arc};
 
-use observability_deps::tracing::info;
+use observability_deps::tracing::{info, warn};
 use parking_lot::{Mutex, MutexGuard};
 
 use crate::buffer_tree::{partition::PartitionData, post_write::PostWriteObserver};
 
 use super::queue::PersistQueue;
 
+/// The number of snapshots of a partition that may be persisting when the
+/// partition becomes hot again before it is considered to be outpacing
+/// persistence.
+///
+/// A partition that refills its buffer before the previous snapshot has been
+/// persisted is being written to faster than a single persist job can persist
+/// it.
+const OUTPACING_PERSISTING_SNAPSHOTS: usize = 1;
+
+/// The maximum number of parts the snapshot of a partition that is outpacing
+/// persistence is split into.
+///
+/// Each part contains the buffered rows for a disjoint time range, and is
+/// persisted as an independent (smaller) persist job that can be executed in
+/// parallel with the other parts, rather than as a single large job that
+/// occupies a worker for the duration of the persist.
+const SPLIT_PERSIST_PARTS: usize = 4;
+
 /// A [`PostWriteObserver`] that triggers persistence of a partition when the
 /// estimated persistence cost exceeds a pre-configured limit.
 #[derive(Debug)]
@@ -16,6 +34,10 @@ pub(crate) struct HotPartitionPersister<P> {
 
     /// A metric tracking the number of partitions persisted as "hot partitions".
     persist_count: metric::U64Counter,
+
+    /// A metric tracking the number of hot partition persists that were split
+    /// because the partition was outpacing persistence.
+    split_count: metric::U64Counter,
 }
 
 impl<P> HotPartitionPersister<P>
@@ -34,10 +56,19 @@ where
                 because the persist cost exceeded the pre-configured limit",
             )
             .recorder(&[]);
+        let split_count = metrics
+            .register_metric::<metric::U64Counter>(
+                "ingester_persist_hot_partition_split_count",
+                "number of times the persistence of a hot partition was split \
+                by time because the partition was being written to faster \
+                than it was persisted",
+            )
+            .recorder(&[]);
         Self {
             persist_handle,
             max_estimated_persist_cost,
             persist_count,
+            split_count,
         }
     }
 
@@ -53,16 +84,38 @@ where
             cost_estimate, "marking hot partition for persistence"
         );
 
-        let data = guard
-            .mark_persisting()
-            .expect("failed to transition buffer fsm to persisting state");
+        // If the previous snapshots of this partition are still persisting,
+        // the partition is being written to faster than it is persisted -
+        // split this snapshot by time so the parts can be persisted in
+        // parallel, instead of queuing one more large persist job behind the
+        // others.
+        let persisting = guard.persisting_batches();
+        let max_parts = if persisting >= OUTPACING_PERSISTING_SNAPSHOTS {
+            warn!(
+                partition_id = guard.partition_id().get(),
+                persisting_snapshots = persisting,
+                "hot partition is outpacing persistence, splitting persist job"
+            );
+            self.split_count.inc(1);
+            SPLIT_PERSIST_PARTS
+        } else {
+            1
+        };
+
+        let parts = guard.mark_persisting_split(max_parts);
+        assert!(
+            !parts.is_empty(),
+            "failed to transition buffer fsm to persisting state"
+        );
 
         // Perform the enqueue in a separate task, to avoid blocking this
         // writer if the persist system is saturated.
         let persist_handle = self.persist_handle.clone();
         tokio::spawn(async move {
-            // There is no need to await on the completion handle.
-            persist_handle.enqueue(partition, data).await;
+            for data in parts {
+                // There is no need to await on the completion handle.
+                persist_handle.enqueue(Arc::clone(&partition), data).await;
+            }
         });
         // Update any exported metrics.
         self.persist_count.inc(1);
@@ -192,4 +245,65 @@ mod tests {
             .await;
         assert_eq!(p.lock().completed_persistence_count(), 1);
     }
+
+    #[tokio::test]
+    async fn test_hot_partition_outpacing_persist_split() {
+        let write = |p: &Arc<Mutex<PartitionData>>, ts: i64, seq| {
+            let mb = lp_to_mutable_batch(&format!(
+                r#"{},city=Hereford people=1 {ts}"#,
+                &*ARBITRARY_TABLE_NAME
+            ))
+            .1;
+            p.lock()
+                .buffer_write(mb, SequenceNumber::new(seq))
+                .expect("write should succeed");
+        };
+
+        let p = Arc::new(Mutex::new(PartitionDataBuilder::new().build()));
+
+        // Start persisting a snapshot of the partition that does not
+        // complete.
+        write(&p, 1, 1);
+        let _persisting = p.lock().mark_persisting().expect("must contain data");
+
+        // Refill the buffer with data spanning a large time range.
+        write(&p, 5, 2);
+        write(&p, 100, 3);
+
+        let metrics = metric::Registry::default();
+        let persist_handle = Arc::new(MockPersistQueue::default());
+        let hot_partition_persister =
+            HotPartitionPersister::new(Arc::clone(&persist_handle), 1, &metrics);
+
+        hot_partition_persister.observe(Arc::clone(&p), p.lock());
+        tokio::task::yield_now().await;
+
+        // The snapshot was split into a persist job per populated time range.
+        assert_eq!(persist_handle.calls().len(), 2);
+
+        metric::assert_counter!(
+            metrics,
+            metric::U64Counter,
+            "ingester_persist_hot_partition_split_count",
+            value = 1,
+        );
+        metric::assert_counter!(
+            metrics,
+            metric::U64Counter,
+            "ingester_persist_hot_partition_enqueue_count",
+            value = 1,
+        );
+
+        // Once both parts complete, only the first (unsplit) snapshot remains
+        // persisting.
+        drop(hot_partition_persister);
+        Arc::try_unwrap(persist_handle)
+            .expect("should be no more refs")
+            .join()
+            .await;
+        let guard = p.lock();
+        assert_eq!(guard.completed_persistence_count(), 2);
+        assert_eq!(guard.persisting_batches(), 1);
+        assert_eq!(guard.rows(), 1);
+    }
 }
@@ -1,6 +1,7 @@
 //! The persistence subsystem; abstractions, types, and implementation.
 
 pub(crate) mod backpressure;
+pub(crate) mod closed_partitions;
 pub(super) mod compact;
 pub(crate) mod completion_observer;
 mod context;
@@ -9,6 +10,7 @@ pub(crate) mod file_metrics;
 pub(crate) mod handle;
 pub(crate) mod hot_partitions;
 pub mod queue;
+pub(crate) mod snapshot;
 mod worker;
 
 #[cfg(test)]
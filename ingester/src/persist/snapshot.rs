@@ -0,0 +1,221 @@
+//! Serialisation of a copy of the data buffered in a partition to object
+//! storage, for offline debugging.
+
+use std::sync::Arc;
+
+use arrow_util::util::ensure_schema;
+use data_types::CompactionLevel;
+use datafusion::{error::DataFusionError, physical_plan::stream::RecordBatchStreamAdapter};
+use futures::stream;
+use iox_query::{exec::Executor, QueryChunk};
+use iox_time::{SystemProvider, TimeProvider};
+use object_store::path::Path;
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use parquet_file::{
+    metadata::IoxMetadata,
+    serialize::{to_parquet_bytes, CodecError},
+    storage::ParquetStorage,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{buffer_tree::partition::PartitionData, query::projection::OwnedProjection};
+
+/// The object store prefix partition snapshots are written under.
+const SNAPSHOT_PREFIX: &str = "debug/ingester_snapshots";
+
+/// Errors snapshotting a partition.
+#[derive(Debug, Error)]
+pub(crate) enum SnapshotError {
+    /// The partition contains no buffered or persisting data.
+    #[error("partition contains no unpersisted data")]
+    NoData,
+
+    /// The partition data could not be encoded as parquet.
+    #[error("failed to serialise partition data: {0}")]
+    Serialise(#[from] CodecError),
+
+    /// The parquet file could not be written to object storage.
+    #[error("failed to upload partition snapshot: {0}")]
+    Upload(#[from] object_store::Error),
+}
+
+/// The result of a successful [`snapshot_partition()`] call.
+#[derive(Debug)]
+pub(crate) struct Snapshot {
+    /// The object store path of the parquet file written.
+    pub(crate) path: Path,
+
+    /// The number of rows in the parquet file.
+    pub(crate) row_count: u64,
+}
+
+/// Write a copy of all unpersisted data in `partition` (both buffered, and
+/// currently persisting) to a parquet file under the debug prefix of `store`.
+///
+/// The partition is not modified - the data remains buffered, no persistence
+/// watermarks advance and no parquet file is added to the catalog. The written
+/// file is never garbage collected, and is expected to be removed once
+/// inspected.
+pub(crate) async fn snapshot_partition(
+    partition: &Mutex<PartitionData>,
+    store: &ParquetStorage,
+    exec: &Executor,
+) -> Result<Snapshot, SnapshotError> {
+    // Copy the data out of the partition, and release the lock before
+    // performing any (slow) encoding or IO.
+    let (data, namespace_id, namespace_name, table_id, table, partition_key) = {
+        let mut p = partition.lock();
+        (
+            p.get_query_data(&OwnedProjection::default()),
+            p.namespace_id(),
+            Arc::clone(p.namespace_name()),
+            p.table_id(),
+            Arc::clone(p.table()),
+            p.partition_key().clone(),
+        )
+    };
+    let data = data.ok_or(SnapshotError::NoData)?;
+    let row_count = data.num_rows();
+
+    // The buffered data may span several schemas as columns were added over
+    // time - project all batches onto the merged schema of the partition.
+    let schema = data.schema().as_arrow();
+    let batches = data
+        .record_batches()
+        .iter()
+        .map(|b| ensure_schema(&schema, b).map_err(DataFusionError::from))
+        .collect::<Vec<_>>();
+    let batches = Box::pin(RecordBatchStreamAdapter::new(
+        Arc::clone(&schema),
+        stream::iter(batches),
+    ));
+
+    let object_store_id = Uuid::new_v4();
+    let time_now = SystemProvider::new().now();
+    let iox_metadata = IoxMetadata {
+        object_store_id,
+        creation_timestamp: time_now,
+        namespace_id,
+        namespace_name: Arc::clone(&*namespace_name.get().await),
+        table_id,
+        table_name: Arc::clone(table.get().await.name()),
+        partition_key: partition_key.clone(),
+        compaction_level: CompactionLevel::Initial,
+        // The snapshot is not compacted, and therefore not sorted.
+        sort_key: None,
+        max_l0_created_at: time_now,
+    };
+
+    let (bytes, _) = to_parquet_bytes(batches, &iox_metadata, exec.pool()).await?;
+
+    let path = Path::from(SNAPSHOT_PREFIX)
+        .child(namespace_id.to_string())
+        .child(table_id.to_string())
+        .child(partition_key.inner())
+        .child(format!("{object_store_id}.parquet"));
+
+    store.object_store().put(&path, bytes.into()).await?;
+
+    info!(
+        %namespace_id,
+        %table_id,
+        %partition_key,
+        %path,
+        row_count,
+        "wrote partition snapshot"
+    );
+
+    Ok(Snapshot { path, row_count })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use object_store::{memory::InMemory, ObjectStore};
+    use parquet_file::{metadata::IoxParquetMetaData, storage::StorageId};
+
+    use super::*;
+    use crate::test_util::{
+        PartitionDataBuilder, ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID,
+        ARBITRARY_TABLE_NAME,
+    };
+
+    #[tokio::test]
+    async fn test_snapshot_partition() {
+        let object_store: Arc<dyn ObjectStore> = Arc::new(InMemory::default());
+        let store = ParquetStorage::new(Arc::clone(&object_store), StorageId::from("iox"));
+        let exec = Executor::new_testing();
+
+        let partition = Mutex::new(PartitionDataBuilder::new().build());
+
+        // An empty partition cannot be snapshot.
+        assert_matches!(
+            snapshot_partition(&partition, &store, &exec).await,
+            Err(SnapshotError::NoData)
+        );
+
+        // Buffer two writes with differing schemas, one of which is
+        // persisting.
+        for (fields, seq) in [
+            (r#"city=London people=2 10"#, 1),
+            (r#"city=Madrid pigeons="none" 20"#, 2),
+        ] {
+            let mb = lp_to_mutable_batch(&format!("{},{fields}", &*ARBITRARY_TABLE_NAME)).1;
+            let mut p = partition.lock();
+            p.buffer_write(mb, SequenceNumber::new(seq))
+                .expect("write should succeed");
+            if seq == 1 {
+                p.mark_persisting().expect("must contain data");
+            }
+        }
+
+        let snapshot = snapshot_partition(&partition, &store, &exec)
+            .await
+            .expect("snapshot should succeed");
+        assert_eq!(snapshot.row_count, 2);
+        assert_eq!(
+            snapshot.path.as_ref(),
+            format!(
+                "debug/ingester_snapshots/{}/{}/{}/{}",
+                ARBITRARY_NAMESPACE_ID,
+                ARBITRARY_TABLE_ID,
+                ARBITRARY_PARTITION_KEY.inner(),
+                snapshot.path.filename().unwrap(),
+            )
+        );
+
+        // The file is a valid IOx parquet file containing all the rows.
+        let bytes = object_store
+            .get(&snapshot.path)
+            .await
+            .expect("snapshot must exist")
+            .bytes()
+            .await
+            .expect("failed to read snapshot");
+        let meta = IoxParquetMetaData::from_file_bytes(bytes)
+            .expect("failed to decode parquet metadata")
+            .expect("parquet file must contain metadata");
+        let decoded = meta.decode().expect("failed to decode metadata");
+        assert_eq!(decoded.row_count(), 2);
+        let iox_meta = decoded
+            .read_iox_metadata_new()
+            .expect("failed to read iox metadata");
+        assert_eq!(iox_meta.table_id, ARBITRARY_TABLE_ID);
+        assert_eq!(iox_meta.partition_key, *ARBITRARY_PARTITION_KEY);
+
+        // The partition remains untouched.
+        let mut p = partition.lock();
+        assert_eq!(p.rows(), 2);
+        assert_eq!(p.completed_persistence_count(), 0);
+        assert_matches!(
+            p.get_query_data(&OwnedProjection::default()),
+            Some(data) => {
+                assert_eq!(data.num_rows(), 2);
+            }
+        );
+    }
+}
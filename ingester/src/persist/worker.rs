@@ -507,10 +507,25 @@ where
     Backoff::new(&Default::default())
         .retry_all_errors("add parquet file to catalog", || async {
             let mut repos = worker_state.catalog.repositories().await;
-            let parquet_file = repos
+            let parquet_file = match repos
                 .parquet_files()
                 .create(parquet_table_data.clone())
-                .await?;
+                .await
+            {
+                Ok(v) => v,
+                // A previous attempt may have been committed, despite
+                // returning an error (such as when the connection is lost
+                // while committing). The object store ID is unique to this
+                // persist, so the existing file is the one being added.
+                Err(iox_catalog::interface::Error::FileExists {
+                    object_store_id: existing,
+                }) if existing == object_store_id => repos
+                    .parquet_files()
+                    .get_by_object_store_id(object_store_id)
+                    .await?
+                    .ok_or(iox_catalog::interface::Error::FileExists { object_store_id })?,
+                Err(e) => return Err(e),
+            };
 
             debug!(
                 namespace_id = %ctx.namespace_id(),
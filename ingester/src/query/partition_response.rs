@@ -3,7 +3,7 @@
 //! [`QueryResponse`]: super::response::QueryResponse
 
 use arrow::record_batch::RecordBatch;
-use data_types::{PartitionHashId, PartitionId};
+use data_types::{PartitionHashId, PartitionId, SequenceNumber};
 
 /// Response data for a single partition.
 #[derive(Debug)]
@@ -19,6 +19,14 @@ pub(crate) struct PartitionResponse {
 
     /// Count of persisted Parquet files for this partition by this ingester instance.
     completed_persistence_count: u64,
+
+    /// The highest sequence number persisted for this partition by this
+    /// ingester instance, if any.
+    max_persisted_sequence_number: Option<SequenceNumber>,
+
+    /// The number of unpersisted rows buffered for this partition, regardless
+    /// of the query projection and predicate.
+    buffered_row_count: u64,
 }
 
 impl PartitionResponse {
@@ -27,12 +35,16 @@ impl PartitionResponse {
         id: PartitionId,
         partition_hash_id: Option<PartitionHashId>,
         completed_persistence_count: u64,
+        max_persisted_sequence_number: Option<SequenceNumber>,
+        buffered_row_count: u64,
     ) -> Self {
         Self {
             batches: data,
             id,
             partition_hash_id,
             completed_persistence_count,
+            max_persisted_sequence_number,
+            buffered_row_count,
         }
     }
 
@@ -48,6 +60,14 @@ impl PartitionResponse {
         self.completed_persistence_count
     }
 
+    pub(crate) fn max_persisted_sequence_number(&self) -> Option<SequenceNumber> {
+        self.max_persisted_sequence_number
+    }
+
+    pub(crate) fn buffered_row_count(&self) -> u64 {
+        self.buffered_row_count
+    }
+
     pub(crate) fn into_record_batches(self) -> Vec<RecordBatch> {
         self.batches
     }
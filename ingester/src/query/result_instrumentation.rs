@@ -335,6 +335,8 @@ where
                 let id = p.id();
                 let hash_id = p.partition_hash_id().cloned();
                 let persist_count = p.completed_persistence_count();
+                let max_persisted = p.max_persisted_sequence_number();
+                let buffered_row_count = p.buffered_row_count();
 
                 // And wrap the underlying stream of RecordBatch for this
                 // partition with a metric observer.
@@ -351,6 +353,8 @@ where
                     id,
                     hash_id,
                     persist_count,
+                    max_persisted,
+                    buffered_row_count,
                 )))
             }
             Poll::Ready(None) => {
@@ -460,6 +464,8 @@ mod tests {
             ARBITRARY_PARTITION_ID,
             Some(ARBITRARY_PARTITION_HASH_ID.clone()),
             42,
+            None,
+            0,
         )]));
 
         let mock_time = Arc::new(MockProvider::new(Time::MIN));
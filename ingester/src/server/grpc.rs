@@ -7,10 +7,13 @@ mod rpc_write;
 use std::{fmt::Debug, sync::Arc};
 
 use iox_catalog::interface::Catalog;
+use iox_query::exec::Executor;
+use parquet_file::storage::ParquetStorage;
 use service_grpc_catalog::CatalogService;
 
 use crate::{
     dml_sink::DmlSink,
+    drain::DrainHandle,
     ingest_state::IngestState,
     ingester_id::IngesterId,
     init::IngesterRpcInterface,
@@ -38,6 +41,9 @@ pub(crate) struct GrpcDelegate<D, Q, T, P> {
     metrics: Arc<metric::Registry>,
     buffer: Arc<T>,
     persist_handle: Arc<P>,
+    drain: Arc<DrainHandle>,
+    store: ParquetStorage,
+    persist_executor: Arc<Executor>,
 }
 
 impl<D, Q, T, P> GrpcDelegate<D, Q, T, P>
@@ -59,6 +65,9 @@ where
         metrics: Arc<metric::Registry>,
         buffer: Arc<T>,
         persist_handle: Arc<P>,
+        drain: Arc<DrainHandle>,
+        store: ParquetStorage,
+        persist_executor: Arc<Executor>,
     ) -> Self {
         Self {
             dml_sink,
@@ -70,6 +79,9 @@ where
             metrics,
             buffer,
             persist_handle,
+            drain,
+            store,
+            persist_executor,
         }
     }
 }
@@ -114,6 +126,9 @@ where
             Arc::clone(&self.buffer),
             Arc::clone(&self.persist_handle),
             Arc::clone(&self.catalog),
+            Arc::clone(&self.drain),
+            self.store.clone(),
+            Arc::clone(&self.persist_executor),
         )
     }
 
@@ -1,19 +1,33 @@
 use crate::{
+    drain::{DrainHandle, DrainProgress},
     partition_iter::PartitionIter,
-    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+    persist::{
+        drain_buffer::persist_partitions,
+        queue::PersistQueue,
+        snapshot::{snapshot_partition, SnapshotError},
+    },
 };
+use data_types::PartitionKey;
+use futures::{stream, Stream};
 use generated_types::influxdata::iox::ingester::v1::{
-    self as proto, persist_service_server::PersistService,
+    self as proto, drain_response::Phase, persist_service_server::PersistService,
 };
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
-use std::sync::Arc;
+use iox_query::exec::Executor;
+use parquet_file::storage::ParquetStorage;
+use std::{pin::Pin, sync::Arc};
 use tonic::{Request, Response};
 
+type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send + 'static>>;
+
 #[derive(Debug)]
 pub(crate) struct PersistHandler<T, P> {
     buffer: T,
     persist_handle: P,
     catalog: Arc<dyn Catalog>,
+    drain: Arc<DrainHandle>,
+    store: ParquetStorage,
+    exec: Arc<Executor>,
 }
 
 impl<T, P> PersistHandler<T, P>
@@ -21,11 +35,39 @@ where
     T: PartitionIter + Sync + 'static,
     P: PersistQueue + Clone + Sync + 'static,
 {
-    pub(crate) fn new(buffer: T, persist_handle: P, catalog: Arc<dyn Catalog>) -> Self {
+    pub(crate) fn new(
+        buffer: T,
+        persist_handle: P,
+        catalog: Arc<dyn Catalog>,
+        drain: Arc<DrainHandle>,
+        store: ParquetStorage,
+        exec: Arc<Executor>,
+    ) -> Self {
         Self {
             buffer,
             persist_handle,
             catalog,
+            drain,
+            store,
+            exec,
+        }
+    }
+}
+
+impl From<DrainProgress> for proto::DrainResponse {
+    fn from(v: DrainProgress) -> Self {
+        let (phase, unpersisted_partitions) = match v {
+            DrainProgress::Pending => (Phase::Pending, 0),
+            DrainProgress::Persisting {
+                unpersisted_partitions,
+            } => (Phase::Persisting, unpersisted_partitions as u64),
+            DrainProgress::TruncatingWal => (Phase::TruncatingWal, 0),
+            DrainProgress::Complete => (Phase::Complete, 0),
+        };
+
+        Self {
+            phase: phase.into(),
+            unpersisted_partitions,
         }
     }
 }
@@ -65,4 +107,83 @@ where
 
         Ok(Response::new(proto::PersistResponse {}))
     }
+
+    type DrainStream = TonicStream<proto::DrainResponse>;
+
+    /// Handle the RPC request to drain the ingester, streaming the progress of
+    /// the drain operation until it completes.
+    async fn drain(
+        &self,
+        _request: Request<proto::DrainRequest>,
+    ) -> Result<Response<Self::DrainStream>, tonic::Status> {
+        let rx = self.drain.drain();
+
+        // Yield the current progress, and then each subsequent change until
+        // the drain completes.
+        let stream = stream::unfold(Some((rx, true)), |state| async move {
+            let (mut rx, first) = state?;
+            if !first && rx.changed().await.is_err() {
+                // The drain task has stopped without completing.
+                return Some((Err(tonic::Status::internal("drain task stopped")), None));
+            }
+
+            let progress = *rx.borrow_and_update();
+            let next = (progress != DrainProgress::Complete).then_some((rx, false));
+            Some((Ok(proto::DrainResponse::from(progress)), next))
+        });
+
+        Ok(Response::new(Box::pin(stream)))
+    }
+    /// Handle the RPC request to write a copy of the unpersisted data of a
+    /// partition to the debug prefix of the object store, leaving the buffered
+    /// data untouched.
+    async fn snapshot_partition(
+        &self,
+        request: Request<proto::SnapshotPartitionRequest>,
+    ) -> Result<Response<proto::SnapshotPartitionResponse>, tonic::Status> {
+        let request = request.into_inner();
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&request.namespace, SoftDeletedRows::AllRows)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found(&request.namespace))?;
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &request.table)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found(&request.table))?;
+        drop(repos);
+
+        let partition_key = PartitionKey::from(request.partition_key);
+        let partition = self
+            .buffer
+            .partition_iter()
+            .find(|p| {
+                let p = p.lock();
+                p.namespace_id() == namespace.id
+                    && p.table_id() == table.id
+                    && *p.partition_key() == partition_key
+            })
+            .ok_or_else(|| {
+                tonic::Status::not_found(format!("no buffered partition with key {partition_key}"))
+            })?;
+
+        let snapshot = snapshot_partition(&partition, &self.store, &self.exec)
+            .await
+            .map_err(|e| match e {
+                SnapshotError::NoData => tonic::Status::not_found(e.to_string()),
+                SnapshotError::Serialise(_) | SnapshotError::Upload(_) => {
+                    tonic::Status::internal(e.to_string())
+                }
+            })?;
+
+        Ok(Response::new(proto::SnapshotPartitionResponse {
+            object_store_path: snapshot.path.to_string(),
+            row_count: snapshot.row_count,
+        }))
+    }
 }
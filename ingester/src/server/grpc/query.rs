@@ -6,7 +6,7 @@ use arrow_flight::{
     FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, HandshakeResponse, PutResult,
     SchemaResult, Ticket,
 };
-use data_types::{NamespaceId, PartitionHashId, PartitionId, TableId};
+use data_types::{NamespaceId, TableId};
 use flatbuffers::FlatBufferBuilder;
 use futures::{Stream, StreamExt, TryStreamExt};
 use ingester_query_grpc::influxdata::iox::ingester::v1 as proto;
@@ -27,7 +27,10 @@ use instrumentation::FlightFrameEncodeInstrumentation;
 
 use crate::{
     ingester_id::IngesterId,
-    query::{projection::OwnedProjection, response::QueryResponse, QueryError, QueryExec},
+    query::{
+        partition_response::PartitionResponse, projection::OwnedProjection,
+        response::QueryResponse, QueryError, QueryExec,
+    },
 };
 
 /// Error states for the query RPC handler.
@@ -208,6 +211,7 @@ where
         };
 
         let projection = OwnedProjection::from(request.columns);
+        let announce_partitions = request.announce_partitions;
 
         let response = match self
             .query_handler
@@ -236,6 +240,7 @@ where
         let output = encode_response(
             response,
             self.ingester_id,
+            announce_partitions,
             query_recorder.child_span("serialise response"),
             Arc::clone(&self.query_request_frame_encoding_duration),
         )
@@ -303,24 +308,22 @@ where
 
 /// Encode the partition information as a None flight data with meatadata
 fn encode_partition(
-    // Partition ID.
-    partition_id: PartitionId,
-    // Partition hash ID.
-    partition_hash_id: Option<PartitionHashId>,
-    // Count of persisted Parquet files for the [`PartitionData`] instance this
-    // [`PartitionResponse`] was generated from.
-    //
-    // [`PartitionData`]: crate::buffer_tree::partition::PartitionData
-    // [`PartitionResponse`]: crate::query::partition_response::PartitionResponse
-    completed_persistence_count: u64,
+    partition: &PartitionResponse,
     ingester_id: IngesterId,
+    // Only announce the partition, its data follows later.
+    announcement: bool,
 ) -> Result<FlightData, FlightError> {
     let mut bytes = bytes::BytesMut::new();
     let app_metadata = proto::IngesterQueryResponseMetadata {
-        partition_id: partition_id.get(),
-        partition_hash_id: partition_hash_id.map(|hash_id| hash_id.as_bytes().to_owned()),
+        partition_id: partition.id().get(),
+        partition_hash_id: partition
+            .partition_hash_id()
+            .map(|hash_id| hash_id.as_bytes().to_owned()),
         ingester_uuid: ingester_id.to_string(),
-        completed_persistence_count,
+        completed_persistence_count: partition.completed_persistence_count(),
+        max_persisted_sequence_number: partition.max_persisted_sequence_number().map(|v| v.get()),
+        buffered_row_count: partition.buffered_row_count(),
+        announcement,
     };
     prost::Message::encode(&app_metadata, &mut bytes)
         .map_err(|e| FlightError::from_external_error(Box::new(e)))?;
@@ -345,52 +348,92 @@ fn build_none_flight_msg() -> Vec<u8> {
 }
 
 /// Converts a QueryResponse into a stream of Arrow Flight [`FlightData`] response frames.
+///
+/// If `announce_partitions` is set, all partitions are announced before their data is sent, see
+/// [`proto::IngesterQueryRequest::announce_partitions`].
 fn encode_response(
     response: QueryResponse,
     ingester_id: IngesterId,
+    announce_partitions: bool,
     span: Option<Span>,
     frame_encoding_duration_metric: Arc<DurationHistogram>,
 ) -> impl Stream<Item = Result<FlightData, FlightError>> {
-    response.into_partition_stream().flat_map(move |partition| {
-        let partition_id = partition.id();
-        let partition_hash_id = partition.partition_hash_id().cloned();
-        let completed_persistence_count = partition.completed_persistence_count();
-
-        // prefix payload data w/ metadata for that particular partition
-        let head = futures::stream::once(async move {
-            encode_partition(
-                partition_id,
-                partition_hash_id,
-                completed_persistence_count,
-                ingester_id,
-            )
-        });
+    let partitions = response.into_partition_stream();
+
+    if !announce_partitions {
+        return partitions
+            .flat_map(move |partition| {
+                encode_partition_data(
+                    partition,
+                    ingester_id,
+                    span.clone(),
+                    Arc::clone(&frame_encoding_duration_metric),
+                )
+            })
+            .left_stream();
+    }
 
-        // An output vector of FlightDataEncoder streams, each entry stream with
-        // a differing schema.
-        //
-        // Optimized for the common case of there being a single consistent
-        // schema across all batches (1 stream).
-        let mut output = Vec::with_capacity(1);
-
-        let mut batch_iter = partition.into_record_batches().into_iter().peekable();
-
-        // While there are more batches to process.
-        while let Some(schema) = batch_iter.peek().map(|v| v.schema()) {
-            output.push(FlightFrameEncodeInstrumentation::new(
-                FlightDataEncoderBuilder::new().build(futures::stream::iter(
-                    // Take all the RecordBatch with a matching schema
-                    std::iter::from_fn(|| batch_iter.next_if(|v| v.schema() == schema))
-                        .map(Ok)
-                        .collect::<Vec<Result<_, FlightError>>>(),
-                )),
-                span.clone(),
-                Arc::clone(&frame_encoding_duration_metric),
-            ))
-        }
+    futures::stream::once(partitions.collect::<Vec<_>>())
+        .flat_map(move |partitions| {
+            let announcements = partitions
+                .iter()
+                .map(|partition| encode_partition(partition, ingester_id, true))
+                .collect::<Vec<_>>();
+
+            let span = span.clone();
+            let frame_encoding_duration_metric = Arc::clone(&frame_encoding_duration_metric);
+            let data = futures::stream::iter(partitions).flat_map(move |partition| {
+                encode_partition_data(
+                    partition,
+                    ingester_id,
+                    span.clone(),
+                    Arc::clone(&frame_encoding_duration_metric),
+                )
+            });
 
-        head.chain(futures::stream::iter(output).flatten())
-    })
+            futures::stream::iter(announcements).chain(data)
+        })
+        .right_stream()
+}
+
+/// Encode a single partition: its metadata followed by its data.
+fn encode_partition_data(
+    partition: PartitionResponse,
+    ingester_id: IngesterId,
+    span: Option<Span>,
+    frame_encoding_duration_metric: Arc<DurationHistogram>,
+) -> impl Stream<Item = Result<FlightData, FlightError>> {
+    // prefix payload data w/ metadata for that particular partition
+    let head = futures::stream::once(futures::future::ready(encode_partition(
+        &partition,
+        ingester_id,
+        false,
+    )));
+
+    // An output vector of FlightDataEncoder streams, each entry stream with
+    // a differing schema.
+    //
+    // Optimized for the common case of there being a single consistent
+    // schema across all batches (1 stream).
+    let mut output = Vec::with_capacity(1);
+
+    let mut batch_iter = partition.into_record_batches().into_iter().peekable();
+
+    // While there are more batches to process.
+    while let Some(schema) = batch_iter.peek().map(|v| v.schema()) {
+        output.push(FlightFrameEncodeInstrumentation::new(
+            FlightDataEncoderBuilder::new().build(futures::stream::iter(
+                // Take all the RecordBatch with a matching schema
+                std::iter::from_fn(|| batch_iter.next_if(|v| v.schema() == schema))
+                    .map(Ok)
+                    .collect::<Vec<Result<_, FlightError>>>(),
+            )),
+            span.clone(),
+            Arc::clone(&frame_encoding_duration_metric),
+        ))
+    }
+
+    head.chain(futures::stream::iter(output).flatten())
 }
 
 #[cfg(test)]
@@ -408,6 +451,7 @@ mod tests {
     use arrow_flight::decode::{DecodedPayload, FlightRecordBatchStream};
     use assert_matches::assert_matches;
     use bytes::Bytes;
+    use data_types::{PartitionId, SequenceNumber};
     use tonic::Code;
 
     #[tokio::test]
@@ -422,6 +466,8 @@ mod tests {
                     PartitionId::new(2),
                     Some(ARBITRARY_PARTITION_HASH_ID.clone()),
                     42,
+                    None,
+                    0,
                 )]),
             )))),
             ingester_id,
@@ -451,6 +497,9 @@ mod tests {
             partition_hash_id: Some(ARBITRARY_PARTITION_HASH_ID.as_bytes().to_vec()),
             ingester_uuid: ingester_id.to_string(),
             completed_persistence_count: 42,
+            max_persisted_sequence_number: None,
+            buffered_row_count: 0,
+            announcement: false,
         };
         assert_eq!(md_actual, md_expected);
     }
@@ -465,6 +514,8 @@ mod tests {
                     PartitionId::new(2),
                     None,
                     42,
+                    None,
+                    0,
                 )]),
             )))),
             ingester_id,
@@ -494,10 +545,94 @@ mod tests {
             partition_hash_id: None,
             ingester_uuid: ingester_id.to_string(),
             completed_persistence_count: 42,
+            max_persisted_sequence_number: None,
+            buffered_row_count: 0,
+            announcement: false,
         };
         assert_eq!(md_actual, md_expected);
     }
 
+    #[tokio::test]
+    async fn announces_partitions() {
+        let ingester_id = IngesterId::new();
+        let (batch, schema) = make_batch!(
+            Int32Array("int" => vec![1, 2, 3]),
+        );
+
+        let flight = FlightService::new(
+            MockQueryExec::default().with_result(Ok(QueryResponse::new(PartitionStream::new(
+                futures::stream::iter([
+                    PartitionResponse::new(
+                        vec![batch.clone()],
+                        PartitionId::new(1),
+                        None,
+                        13,
+                        Some(SequenceNumber::new(7)),
+                        1,
+                    ),
+                    PartitionResponse::new(vec![], PartitionId::new(2), None, 42, None, 0),
+                ]),
+            )))),
+            ingester_id,
+            100,
+            &metric::Registry::default(),
+        );
+
+        let req = tonic::Request::new(Ticket {
+            ticket: proto::IngesterQueryRequest {
+                announce_partitions: true,
+                ..Default::default()
+            }
+            .encode_to_vec()
+            .into(),
+        });
+        let response_stream = flight
+            .do_get(req)
+            .await
+            .unwrap()
+            .into_inner()
+            .map_err(FlightError::Tonic);
+        let flight_decoder =
+            FlightRecordBatchStream::new_from_flight_data(response_stream).into_inner();
+        let flight_data = flight_decoder.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(flight_data.len(), 6);
+
+        let md = |idx: usize| {
+            proto::IngesterQueryResponseMetadata::decode(flight_data[idx].app_metadata()).unwrap()
+        };
+        let md_expected = |partition_id: i64,
+                           completed_persistence_count: u64,
+                           max_persisted_sequence_number: Option<u64>,
+                           buffered_row_count: u64,
+                           announcement| {
+            proto::IngesterQueryResponseMetadata {
+                partition_id,
+                partition_hash_id: None,
+                ingester_uuid: ingester_id.to_string(),
+                completed_persistence_count,
+                max_persisted_sequence_number,
+                buffered_row_count,
+                announcement,
+            }
+        };
+
+        // announcements
+        assert_matches!(flight_data[0].payload, DecodedPayload::None);
+        assert_eq!(md(0), md_expected(1, 13, Some(7), 1, true));
+        assert_matches!(flight_data[1].payload, DecodedPayload::None);
+        assert_eq!(md(1), md_expected(2, 42, None, 0, true));
+
+        // data of first partition
+        assert_matches!(flight_data[2].payload, DecodedPayload::None);
+        assert_eq!(md(2), md_expected(1, 13, Some(7), 1, false));
+        assert_matches!(&flight_data[3].payload, DecodedPayload::Schema(actual) if actual == &schema);
+        assert_matches!(&flight_data[4].payload, DecodedPayload::RecordBatch(actual) if actual == &batch);
+
+        // second partition has no data
+        assert_matches!(flight_data[5].payload, DecodedPayload::None);
+        assert_eq!(md(5), md_expected(2, 42, None, 0, false));
+    }
+
     #[tokio::test]
     async fn limits_concurrent_queries() {
         let mut flight = FlightService::new(
@@ -565,6 +700,8 @@ mod tests {
                     PartitionId::new(2),
                     partition_hash_id.clone(),
                     42,
+                    None,
+                    0,
                 )]),
             )))),
             ingester_id,
@@ -595,6 +732,9 @@ mod tests {
             partition_hash_id: partition_hash_id.map(|hash_id| hash_id.as_bytes().to_vec()),
             ingester_uuid: ingester_id.to_string(),
             completed_persistence_count: 42,
+            max_persisted_sequence_number: None,
+            buffered_row_count: 0,
+            announcement: false,
         };
         assert_eq!(md_actual, md_expected);
 
@@ -20,8 +20,15 @@ use crate::{
     dml_sink::{DmlError, DmlSink},
     ingest_state::{IngestState, IngestStateError},
     timestamp_oracle::TimestampOracle,
+    wal::wal_sink::WriteAckMode,
 };
 
+/// The request header used to select the [`WriteAckMode`] of a single write,
+/// overriding the mode configured for the ingester.
+///
+/// Accepts a value of either `strict` or `fast`.
+pub(crate) const WRITE_ACK_HEADER: &str = "iox-write-ack";
+
 /// A list of error states when handling an RPC write request.
 ///
 /// Note that this isn't strictly necessary as the [`WriteService`] trait
@@ -46,12 +53,19 @@ enum RpcError {
     /// set by a subsystem. See [`IngestState`] for documentation.
     #[error(transparent)]
     SystemState(IngestStateError),
+
+    /// The request specified an unknown [`WriteAckMode`].
+    #[error("invalid {WRITE_ACK_HEADER} header value: {0}")]
+    InvalidAckMode(String),
 }
 
 impl From<RpcError> for tonic::Status {
     fn from(e: RpcError) -> Self {
         let code = match e {
-            RpcError::Decode(_) | RpcError::NoPayload | RpcError::NoTables => Code::InvalidArgument,
+            RpcError::Decode(_)
+            | RpcError::NoPayload
+            | RpcError::NoTables
+            | RpcError::InvalidAckMode(_) => Code::InvalidArgument,
             RpcError::SystemState(IngestStateError::PersistSaturated) => Code::ResourceExhausted,
             RpcError::SystemState(IngestStateError::GracefulStop) => Code::FailedPrecondition,
         };
@@ -68,6 +82,7 @@ impl From<DmlError> for tonic::Status {
             DmlError::Buffer(e) => map_write_error(e),
             DmlError::Wal(_) => Self::internal(e.to_string()),
             DmlError::ApplyTimeout => Self::internal(e.to_string()),
+            DmlError::NamespaceMemoryLimit(_) => Self::resource_exhausted(e.to_string()),
         }
     }
 }
@@ -99,6 +114,23 @@ fn map_write_error(e: mutable_batch::Error) -> tonic::Status {
     }
 }
 
+/// Parse the optional [`WriteAckMode`] requested by the caller in the
+/// [`WRITE_ACK_HEADER`] of `request`.
+fn requested_ack_mode<T>(request: &Request<T>) -> Result<Option<WriteAckMode>, RpcError> {
+    let v = match request.metadata().get(WRITE_ACK_HEADER) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+
+    match v.to_str() {
+        Ok("strict") => Ok(Some(WriteAckMode::Strict)),
+        Ok("fast") => Ok(Some(WriteAckMode::Fast)),
+        _ => Err(RpcError::InvalidAckMode(
+            String::from_utf8_lossy(v.as_bytes()).into_owned(),
+        )),
+    }
+}
+
 /// A gRPC [`WriteService`] handler.
 ///
 /// This handler accepts writes from an upstream, and applies them to the
@@ -161,6 +193,9 @@ where
         //
         self.ingest_state.read().map_err(RpcError::SystemState)?;
 
+        // Extract the acknowledgement mode requested by the caller, if any.
+        let ack_mode = requested_ack_mode(&request)?;
+
         // Extract the remote address for debugging.
         let remote_addr = request
             .remote_addr()
@@ -189,7 +224,7 @@ where
 
         // Construct the corresponding ingester write operation for the RPC payload,
         // independently sequencing the data contained by the write per-partition
-        let op = WriteOperation::new(
+        let mut op = WriteOperation::new(
             namespace_id,
             batches
                 .into_iter()
@@ -208,6 +243,9 @@ where
             partition_key,
             span_recorder.span().map(|span| span.ctx.clone()),
         );
+        if let Some(mode) = ack_mode {
+            op = op.with_ack_mode(mode);
+        }
 
         // Apply the IngestOp to the DML sink.
         match self.sink.apply(IngestOp::Write(op)).await {
@@ -616,6 +654,80 @@ mod tests {
         assert_matches!(*mock.get_calls(), [IngestOp::Write(_)]);
     }
 
+    /// Validate that the acknowledgement mode requested in the request headers
+    /// is applied to the write, and that invalid values are rejected.
+    #[tokio::test]
+    async fn test_rpc_write_ack_mode_header() {
+        let mock = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(())]));
+        let timestamp = Arc::new(TimestampOracle::new(0));
+
+        let ingest_state = Arc::new(IngestState::default());
+
+        let handler = RpcWrite::new(Arc::clone(&mock), timestamp, ingest_state);
+
+        let req = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                }],
+            }),
+        };
+
+        let with_header = |v: &'static str| {
+            let mut r = Request::new(req.clone());
+            r.metadata_mut()
+                .insert(WRITE_ACK_HEADER, v.parse().expect("valid header value"));
+            r
+        };
+
+        handler
+            .write(Request::new(req.clone()))
+            .await
+            .expect("write should succeed");
+        handler
+            .write(with_header("fast"))
+            .await
+            .expect("write should succeed");
+        handler
+            .write(with_header("strict"))
+            .await
+            .expect("write should succeed");
+
+        let err = handler
+            .write(with_header("bananas"))
+            .await
+            .expect_err("write should fail");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // The invalid request is never applied to the DML sink.
+        assert_matches!(
+            mock.get_calls().as_slice(),
+            [IngestOp::Write(w1), IngestOp::Write(w2), IngestOp::Write(w3)] => {
+                assert_eq!(w1.ack_mode(), None);
+                assert_eq!(w2.ack_mode(), Some(WriteAckMode::Fast));
+                assert_eq!(w3.ack_mode(), Some(WriteAckMode::Strict));
+            }
+        );
+    }
+
     /// Assert that the ingester propagates the SpanContext from the client
     /// request.
     #[tokio::test]
@@ -288,6 +288,8 @@ macro_rules! make_partition_stream {
                             )
                         ),
                         42,
+                        None,
+                        0,
                     )
                 },)+
             ]))
@@ -405,6 +407,7 @@ where
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            tag_ranges: None,
         },
         sequence_numbers
             .into_iter()
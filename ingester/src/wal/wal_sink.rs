@@ -27,6 +27,26 @@ use super::{
 /// problem (catalog unavailable, etc) preventing a write from making progress.
 const DELEGATE_APPLY_TIMEOUT: Duration = Duration::from_secs(15);
 
+/// When a write is acknowledged to the caller, relative to it being made
+/// durable in the write-ahead log.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WriteAckMode {
+    /// Acknowledge writes only once they are buffered and their WAL entry has
+    /// been fsync'd.
+    ///
+    /// An acknowledged write survives a crash of the ingester.
+    #[default]
+    Strict,
+
+    /// Acknowledge writes once they are buffered and their WAL entry has been
+    /// enqueued, without waiting for it to be fsync'd.
+    ///
+    /// This removes the WAL flush from the write latency, but an acknowledged
+    /// write MAY be lost if the ingester crashes before the entry is flushed,
+    /// or if the flush fails.
+    Fast,
+}
+
 /// A [`DmlSink`] decorator that ensures any [`IngestOp`] is committed to
 /// the write-ahead log before passing the operation to the inner [`DmlSink`].
 #[derive(Debug)]
@@ -41,17 +61,29 @@ pub(crate) struct WalSink<T, W = wal::Wal, N = WalReferenceHandle> {
     /// A notifier handle to report the sequence numbers of writes that enter
     /// the write-ahead log but fail to buffer.
     notifier_handle: N,
+
+    /// The [`WriteAckMode`] applied to writes that do not request one.
+    default_ack_mode: WriteAckMode,
 }
 
 impl<T, W, N> WalSink<T, W, N> {
     /// Initialise a new [`WalSink`] that appends [`IngestOp`] to `W` and
     /// on success, passes the op through to `T`, using `N` to keep `W` informed
     /// of writes that fail to buffer.
-    pub(crate) fn new(inner: T, wal: W, notifier_handle: N) -> Self {
+    ///
+    /// Writes are acknowledged according to `default_ack_mode`, unless the
+    /// [`IngestOp`] specifies a [`WriteAckMode`] of its own.
+    pub(crate) fn new(
+        inner: T,
+        wal: W,
+        notifier_handle: N,
+        default_ack_mode: WriteAckMode,
+    ) -> Self {
         Self {
             inner,
             wal,
             notifier_handle,
+            default_ack_mode,
         }
     }
 }
@@ -70,6 +102,7 @@ where
         let mut write_result = self.wal.append(&op);
 
         let set = op.sequence_number_set();
+        let ack_mode = op.ack_mode().unwrap_or(self.default_ack_mode);
 
         // Pass it to the inner handler while we wait for the write to be made
         // durable.
@@ -101,6 +134,14 @@ where
         }
         inner_result?;
 
+        // The write is buffered and readable - if the caller does not require
+        // it to be durable, return without waiting for the WAL flush.
+        //
+        // A failure to flush the entry is still logged by the WAL writer.
+        if ack_mode == WriteAckMode::Fast {
+            return Ok(());
+        }
+
         // Wait for the write to be durable before returning to the user
         write_result
             .changed()
@@ -181,6 +222,7 @@ mod tests {
     use assert_matches::assert_matches;
     use data_types::{SequenceNumber, TableId};
     use lazy_static::lazy_static;
+    use test_helpers::timeout::FutureTimeout;
     use wal::Wal;
 
     use super::*;
@@ -234,7 +276,12 @@ mod tests {
                 .expect("failed to initialise WAL");
             let notifier_handle = Arc::new(mock::MockUnbufferedWriteNotifier::default());
 
-            let wal_sink = WalSink::new(Arc::clone(&inner), wal, Arc::clone(&notifier_handle));
+            let wal_sink = WalSink::new(
+                Arc::clone(&inner),
+                wal,
+                Arc::clone(&notifier_handle),
+                WriteAckMode::Strict,
+            );
 
             // Apply the op through the decorator
             wal_sink
@@ -334,7 +381,12 @@ mod tests {
             .expect("failed to initialise WAL");
         let notifier_handle = Arc::new(mock::MockUnbufferedWriteNotifier::default());
 
-        let wal_sink = WalSink::new(BlockingDmlSink, wal, Arc::clone(&notifier_handle));
+        let wal_sink = WalSink::new(
+            BlockingDmlSink,
+            wal,
+            Arc::clone(&notifier_handle),
+            WriteAckMode::Strict,
+        );
 
         // Allow tokio to automatically advance time past the timeout duration,
         // when all threads are blocked on await points.
@@ -370,4 +422,77 @@ mod tests {
             .collect::<Vec<_>>()
         );
     }
+
+    /// A [`WalAppender`] that never completes (or fails) the flush of
+    /// appended ops.
+    #[derive(Debug, Default)]
+    struct NeverFlushedWal {
+        pending: parking_lot::Mutex<Vec<tokio::sync::watch::Sender<Option<WriteResult>>>>,
+    }
+
+    impl WalAppender for NeverFlushedWal {
+        fn append(&self, _op: &IngestOp) -> Receiver<Option<WriteResult>> {
+            let (tx, rx) = tokio::sync::watch::channel(None);
+            self.pending.lock().push(tx);
+            rx
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ack_mode() {
+        let op = make_multi_table_write_op(
+            &ARBITRARY_PARTITION_KEY,
+            ARBITRARY_NAMESPACE_ID,
+            [(
+                ARBITRARY_TABLE_NAME.to_string().as_str(),
+                ARBITRARY_TABLE_ID,
+                SequenceNumber::new(42),
+            )]
+            .into_iter(),
+            &format!(
+                r#"{},region=Madrid temp=35,climate="dry" 4242424242"#,
+                &*ARBITRARY_TABLE_NAME
+            ),
+        );
+
+        let inner =
+            Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(())]));
+        let notifier_handle = Arc::new(mock::MockUnbufferedWriteNotifier::default());
+        let wal_sink = WalSink::new(
+            Arc::clone(&inner),
+            NeverFlushedWal::default(),
+            Arc::clone(&notifier_handle),
+            WriteAckMode::Fast,
+        );
+
+        // In fast mode the write is acknowledged once buffered, without
+        // waiting for the WAL flush.
+        wal_sink
+            .apply(IngestOp::Write(op.clone()))
+            .with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("wal should not error");
+
+        // As is a write explicitly requesting the fast mode.
+        wal_sink
+            .apply(IngestOp::Write(
+                op.clone().with_ack_mode(WriteAckMode::Fast),
+            ))
+            .with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("wal should not error");
+
+        // A write requesting the strict mode overrides the configured default,
+        // waiting for the flush that never completes.
+        wal_sink
+            .apply(IngestOp::Write(op.with_ack_mode(WriteAckMode::Strict)))
+            .with_timeout(Duration::from_millis(50))
+            .await
+            .expect_err("strict write must wait for the wal flush");
+
+        // All writes were buffered, and all entered the WAL.
+        assert_eq!(inner.get_calls().len(), 3);
+        assert_eq!(wal_sink.wal.pending.lock().len(), 3);
+        assert!(notifier_handle.calls().is_empty());
+    }
 }
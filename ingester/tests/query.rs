@@ -50,6 +50,7 @@ async fn write_query() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            announce_partitions: false,
         })
         .await
         .expect("query request failed");
@@ -124,6 +125,7 @@ async fn write_query_projection() {
             table_id: ctx.table_id(namespace_name, "bananas").await.get(),
             columns: vec![],
             predicate: None,
+            announce_partitions: false,
         })
         .await
         .expect("query request failed");
@@ -151,6 +153,7 @@ async fn write_query_projection() {
                 "platanos".to_string(),
             ],
             predicate: None,
+            announce_partitions: false,
         })
         .await
         .expect("query request failed");
@@ -117,6 +117,9 @@ pub struct IngesterQueryRequest {
 
     /// Predicate for filtering
     pub predicate: Option<Predicate>,
+
+    /// Announce all partitions before sending any data, so that the response can be streamed.
+    pub announce_partitions: bool,
 }
 
 impl IngesterQueryRequest {
@@ -132,6 +135,15 @@ impl IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            announce_partitions: false,
+        }
+    }
+
+    /// Ask the ingester to announce all partitions before sending any data.
+    pub fn with_announce_partitions(self, announce_partitions: bool) -> Self {
+        Self {
+            announce_partitions,
+            ..self
         }
     }
 }
@@ -145,13 +157,15 @@ impl TryFrom<proto::IngesterQueryRequest> for IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            announce_partitions,
         } = proto;
 
         let namespace_id = NamespaceId::new(namespace_id);
         let table_id = TableId::new(table_id);
         let predicate = predicate.map(TryInto::try_into).transpose()?;
 
-        Ok(Self::new(namespace_id, table_id, columns, predicate))
+        Ok(Self::new(namespace_id, table_id, columns, predicate)
+            .with_announce_partitions(announce_partitions))
     }
 }
 
@@ -164,6 +178,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id,
             columns,
             predicate,
+            announce_partitions,
         } = query;
 
         Ok(Self {
@@ -171,6 +186,7 @@ impl TryFrom<IngesterQueryRequest> for proto::IngesterQueryRequest {
             table_id: table_id.get(),
             columns,
             predicate: predicate.map(TryInto::try_into).transpose()?,
+            announce_partitions,
         })
     }
 }
@@ -329,7 +345,8 @@ mod tests {
             TableId::new(1337),
             vec!["usage".into(), "time".into()],
             Some(rust_predicate),
-        );
+        )
+        .with_announce_partitions(true);
 
         let proto_query: proto::IngesterQueryRequest = rust_query.clone().try_into().unwrap();
 
@@ -163,10 +163,14 @@ impl TestContextBuilder {
             persist_background_fetch_time,
             dir.path().to_owned(),
             wal_rotation_period,
+            ingester::WalCompression::default(),
+            ingester::WriteAckMode::default(),
             persist_executor,
             persist_workers,
             max_persist_queue_depth,
             persist_hot_partition_cost,
+            None,
+            None,
             storage.clone(),
             shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
         )
@@ -0,0 +1,11 @@
+-- Structured details of the error that caused compaction of a partition to be skipped.
+CREATE TABLE IF NOT EXISTS skipped_compaction_errors (
+    partition_id BIGINT REFERENCES PARTITION (id) ON DELETE CASCADE,
+    error_kind TEXT NOT NULL,
+    num_files BIGINT NOT NULL,
+    total_bytes BIGINT NOT NULL,
+    num_failures BIGINT NOT NULL,
+    last_error TEXT NOT NULL,
+    skipped_at BIGINT NOT NULL,
+    PRIMARY KEY (partition_id)
+);
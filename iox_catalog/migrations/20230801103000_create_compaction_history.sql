@@ -0,0 +1,16 @@
+-- History of the compaction commits of each partition, recorded by the compaction scheduler.
+CREATE TABLE IF NOT EXISTS compaction_history (
+    id BIGSERIAL PRIMARY KEY,
+    partition_id BIGINT NOT NULL REFERENCES PARTITION (id) ON DELETE CASCADE,
+    job_id UUID NOT NULL,
+    num_input_files BIGINT NOT NULL,
+    input_bytes BIGINT NOT NULL,
+    num_upgraded_files BIGINT NOT NULL,
+    num_output_files BIGINT NOT NULL,
+    output_bytes BIGINT NOT NULL,
+    target_level SMALLINT NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    committed_at BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS compaction_history_partition_idx ON compaction_history (partition_id);
@@ -0,0 +1,6 @@
+-- Add the optional min/max values of the tag columns of each parquet file, used by the querier to
+-- prune files without fetching their metadata. NULL means the ranges are not known.
+ALTER TABLE
+    parquet_file
+ADD
+    COLUMN tag_ranges JSONB DEFAULT NULL;
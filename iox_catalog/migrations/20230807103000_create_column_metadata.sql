@@ -0,0 +1,8 @@
+-- Optional descriptive metadata of a column, e.g. so that downstream tools can render units.
+CREATE TABLE IF NOT EXISTS column_metadata (
+    column_id BIGINT REFERENCES column_name (id) ON DELETE CASCADE,
+    description TEXT,
+    unit TEXT,
+    semantic_type TEXT,
+    PRIMARY KEY (column_id)
+);
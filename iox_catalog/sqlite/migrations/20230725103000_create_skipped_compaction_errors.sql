@@ -0,0 +1,14 @@
+create table if not exists skipped_compaction_errors
+(
+    partition_id INTEGER not null
+        constraint skipped_compaction_errors_pkey
+            primary key
+        references partition
+            on delete cascade,
+    error_kind   text    not null,
+    num_files    numeric not null,
+    total_bytes  numeric not null,
+    num_failures numeric not null,
+    last_error   text    not null,
+    skipped_at   numeric not null
+);
@@ -0,0 +1,5 @@
+-- Add a flag to the "namespace" table that pauses compaction of all partitions of the namespace.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN compaction_paused boolean NOT NULL DEFAULT FALSE;
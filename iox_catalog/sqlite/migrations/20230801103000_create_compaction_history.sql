@@ -0,0 +1,20 @@
+create table if not exists compaction_history
+(
+    id                 INTEGER
+        constraint compaction_history_pkey
+            primary key autoincrement,
+    partition_id       INTEGER  not null
+        references partition
+            on delete cascade,
+    job_id             uuid     not null,
+    num_input_files    numeric  not null,
+    input_bytes        numeric  not null,
+    num_upgraded_files numeric  not null,
+    num_output_files   numeric  not null,
+    output_bytes       numeric  not null,
+    target_level       smallint not null,
+    duration_ms        numeric  not null,
+    committed_at       numeric  not null
+);
+
+create index if not exists compaction_history_partition_idx on compaction_history (partition_id);
@@ -0,0 +1,11 @@
+-- Add optional per-namespace write rate limits to the "namespace" table, enforced by the routers.
+-- NULL means the writes to the namespace are not rate limited.
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_write_lines_per_second BIGINT DEFAULT NULL;
+
+ALTER TABLE
+    namespace
+ADD
+    COLUMN max_write_bytes_per_second BIGINT DEFAULT NULL;
@@ -0,0 +1,6 @@
+-- Add an optional per-table retention period. NULL means the table uses the retention period of
+-- its namespace.
+ALTER TABLE
+    table_name
+ADD
+    COLUMN retention_period_ns numeric DEFAULT NULL;
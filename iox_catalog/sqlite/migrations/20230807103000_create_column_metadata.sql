@@ -0,0 +1,11 @@
+create table if not exists column_metadata
+(
+    column_id     INTEGER not null
+        constraint column_metadata_pkey
+            primary key
+        references column_name
+            on delete cascade,
+    description   text,
+    unit          text,
+    semantic_type text
+);
@@ -3,10 +3,11 @@
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, ColumnsByName, CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    Column, ColumnId, ColumnMetadata, ColumnType, ColumnsByName, CompactionHistory,
+    CompactionHistoryParams, CompactionLevel, Namespace, NamespaceId, NamespaceName,
     NamespaceSchema, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
     ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
+    SkippedCompactionError, Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -79,6 +80,9 @@ pub enum Error {
     #[snafu(display("table {} not found", id))]
     TableNotFound { id: TableId },
 
+    #[snafu(display("column {} not found", id))]
+    ColumnNotFound { id: ColumnId },
+
     #[snafu(display("partition {} not found", id))]
     PartitionNotFound { id: TransitionPartitionId },
 
@@ -146,8 +150,28 @@ pub enum Error {
     #[snafu(display("could not delete skipped compactions: {source}"))]
     CouldNotDeleteSkippedCompactions { source: sqlx::Error },
 
+    #[snafu(display(
+        "could not record compaction history for partition {partition_id}: {source}"
+    ))]
+    CouldNotRecordCompactionHistory {
+        source: sqlx::Error,
+        partition_id: PartitionId,
+    },
+
+    #[snafu(display("could not list compaction history: {source}"))]
+    CouldNotListCompactionHistory { source: sqlx::Error },
+
     #[snafu(display("could not delete namespace: {source}"))]
     CouldNotDeleteNamespace { source: sqlx::Error },
+
+    #[snafu(display("namespace {} is not soft-deleted", id))]
+    NamespaceNotSoftDeleted { id: NamespaceId },
+
+    #[snafu(display("table {} has partitions", id))]
+    TableNotEmpty { id: TableId },
+
+    #[snafu(display("could not delete table: {source}"))]
+    CouldNotDeleteTable { source: sqlx::Error },
 }
 
 /// A specialized `Error` for Catalog errors
@@ -288,11 +312,38 @@ pub trait NamespaceRepo: Send + Sync {
     /// Soft-delete a namespace by name
     async fn soft_delete(&mut self, name: &str) -> Result<()>;
 
+    /// Restore a soft-deleted namespace by name.
+    ///
+    /// Returns [`Error::NamespaceNotFoundByName`] if there is no soft-deleted namespace with this
+    /// name.
+    async fn undelete(&mut self, name: &str) -> Result<Namespace>;
+
+    /// Permanently remove a soft-deleted namespace and everything attached to it (tables,
+    /// columns, partitions and parquet file records) from the catalog.
+    ///
+    /// The objects of the removed parquet files are no longer referenced by the catalog and are
+    /// cleaned up by the object store garbage collector.
+    ///
+    /// Returns [`Error::NamespaceNotSoftDeleted`] if the namespace exists but is not
+    /// soft-deleted and [`Error::NamespaceNotFoundById`] if it does not exist at all.
+    async fn purge(&mut self, id: NamespaceId) -> Result<()>;
+
     /// Update the limit on the number of tables that can exist per namespace.
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
 
     /// Update the limit on the number of columns that can exist per table in a given namespace.
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+
+    /// Pause or resume compaction of all partitions in a given namespace.
+    async fn update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace>;
+
+    /// Update the write rate limits of a given namespace. `None` removes the respective limit.
+    async fn update_write_rate_limits(
+        &mut self,
+        name: &str,
+        max_write_lines_per_second: Option<i64>,
+        max_write_bytes_per_second: Option<i64>,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
@@ -322,6 +373,21 @@ pub trait TableRepo: Send + Sync {
 
     /// List all tables.
     async fn list(&mut self) -> Result<Vec<Table>>;
+
+    /// Update the retention period override of a table. `None` removes the override, i.e. the
+    /// retention period of the namespace applies again.
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table>;
+
+    /// Delete a table that has no partitions, i.e. that has never been written to, together with
+    /// its columns, and return it.
+    ///
+    /// Returns [`Error::TableNotEmpty`] if the table has partitions and [`Error::TableNotFound`]
+    /// if it does not exist.
+    async fn delete(&mut self, table_id: TableId) -> Result<Table>;
 }
 
 /// Functions for working with columns in the catalog
@@ -360,6 +426,15 @@ pub trait ColumnRepo: Send + Sync {
 
     /// List all columns.
     async fn list(&mut self) -> Result<Vec<Column>>;
+
+    /// Set the descriptive metadata of a column, replacing any metadata stored before.
+    async fn update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata>;
+
+    /// List the metadata of all columns in the given namespace that have metadata attached.
+    async fn list_metadata_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnMetadata>>;
 }
 
 /// Functions for working with IOx partitions in the catalog. These are how IOx splits up
@@ -444,6 +519,40 @@ pub trait PartitionRepo: Send + Sync {
         partition_id: PartitionId,
     ) -> Result<Option<SkippedCompaction>>;
 
+    /// Record details of the error that caused compaction of a partition to be skipped, replacing
+    /// any details recorded earlier for the same partition.
+    async fn record_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+        error_kind: &str,
+        num_files: usize,
+        total_bytes: u64,
+        num_failures: usize,
+        last_error: &str,
+    ) -> Result<()>;
+
+    /// List the recorded details of errors that caused compaction to be skipped.
+    async fn list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>>;
+
+    /// Delete the recorded details of the error that caused compaction of a partition to be
+    /// skipped.
+    async fn delete_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompactionError>>;
+
+    /// Record a commit of a compaction job.
+    async fn record_compaction_history(
+        &mut self,
+        params: CompactionHistoryParams,
+    ) -> Result<CompactionHistory>;
+
+    /// List the recorded compaction commits of a partition, oldest first.
+    async fn list_compaction_history(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Vec<CompactionHistory>>;
+
     /// Return the N most recently created partitions.
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
 
@@ -455,6 +564,18 @@ pub trait PartitionRepo: Send + Sync {
         minimum_time: Timestamp,
         maximum_time: Option<Timestamp>,
     ) -> Result<Vec<PartitionId>>;
+
+    /// Select up to `n` "cold" partitions, i.e. partitions with a `new_file_at` value less than the maximum time
+    /// (exclusive) that still have non-deleted files below [`CompactionLevel::Final`].
+    ///
+    /// The result is ordered by partition ID. If `after` is specified, only partitions with a greater ID are
+    /// returned, so that callers can page through all cold partitions.
+    async fn partitions_needing_cold_compact(
+        &mut self,
+        maximum_time: Timestamp,
+        after: Option<PartitionId>,
+        n: usize,
+    ) -> Result<Vec<PartitionId>>;
 }
 
 /// Functions for working with parquet file pointers in the catalog
@@ -463,6 +584,19 @@ pub trait ParquetFileRepo: Send + Sync {
     /// create the parquet file
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
 
+    /// Create all the given parquet files in a single transaction.
+    ///
+    /// Either all files are created, or none are: if a file with the same
+    /// [`object_store_id`](ParquetFileParams::object_store_id) as any of the
+    /// given files already exists (or the same ID is given more than once),
+    /// [`Error::FileExists`] is returned and no file is created.
+    ///
+    /// Returns the created files in the order of `parquet_file_params`.
+    async fn create_many(
+        &mut self,
+        parquet_file_params: Vec<ParquetFileParams>,
+    ) -> Result<Vec<ParquetFile>>;
+
     /// List all parquet files in implementation-defined, non-deterministic order.
     ///
     /// This includes files that were marked for deletion.
@@ -473,6 +607,12 @@ pub trait ParquetFileRepo: Send + Sync {
     /// Flag all parquet files for deletion that are older than their namespace's retention period.
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
 
+    /// List the parquet files that [`flag_for_delete_by_retention`] would flag, without flagging
+    /// them.
+    ///
+    /// [`flag_for_delete_by_retention`]: Self::flag_for_delete_by_retention
+    async fn list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>>;
+
     /// List all parquet files within a given namespace that are NOT marked as
     /// [`to_delete`](ParquetFile::to_delete).
     async fn list_by_namespace_not_to_delete(
@@ -492,6 +632,10 @@ pub trait ParquetFileRepo: Send + Sync {
     /// changes. The caller MAY call this method again if the result was NOT empty.
     async fn delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>>;
 
+    /// List the parquet files that [`delete_old_ids_only`](Self::delete_old_ids_only) would
+    /// delete, without deleting them.
+    async fn list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
+
     /// List parquet files for a given partition that are NOT marked as
     /// [`to_delete`](ParquetFile::to_delete).
     async fn list_by_partition_not_to_delete(
@@ -512,6 +656,15 @@ pub trait ParquetFileRepo: Send + Sync {
         object_store_ids: Vec<Uuid>,
     ) -> Result<Vec<Uuid>>;
 
+    /// Sum up the sizes of the [L0](CompactionLevel::Initial) files that are NOT marked as
+    /// [`to_delete`](ParquetFile::to_delete), for each of the given partitions.
+    ///
+    /// Partitions without such files are not part of the output. The output order is undefined.
+    async fn sum_l0_file_size_by_partition(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<(PartitionId, i64)>>;
+
     /// Commit deletions, upgrades and creations in a single transaction.
     ///
     /// Returns IDs of created files.
@@ -717,7 +870,7 @@ pub(crate) mod test_helpers {
     use super::*;
     use ::test_helpers::assert_error;
     use assert_matches::assert_matches;
-    use data_types::{ColumnId, CompactionLevel};
+    use data_types::{ColumnId, CompactionLevel, TagRange, TagRanges};
     use futures::Future;
     use generated_types::influxdata::iox::partition_template::v1 as proto;
     use metric::{Attributes, DurationHistogram, Metric};
@@ -731,15 +884,24 @@ pub(crate) mod test_helpers {
         test_setup(clean_state().await).await;
         test_namespace_soft_deletion(clean_state().await).await;
         test_partitions_new_file_between(clean_state().await).await;
+        test_partitions_needing_cold_compact(clean_state().await).await;
+        test_sum_l0_file_size_by_partition(clean_state().await).await;
         test_column(clean_state().await).await;
         test_partition(clean_state().await).await;
         test_parquet_file(clean_state().await).await;
+        test_parquet_file_create_many(clean_state().await).await;
+        test_parquet_file_tag_ranges(clean_state().await).await;
         test_parquet_file_delete_broken(clean_state().await).await;
         test_update_to_compaction_level_1(clean_state().await).await;
         test_list_by_partiton_not_to_delete(clean_state().await).await;
         test_list_schemas(clean_state().await).await;
         test_list_schemas_soft_deleted_rows(clean_state().await).await;
         test_delete_namespace(clean_state().await).await;
+        test_namespace_undelete_and_purge(clean_state().await).await;
+        test_namespace_purge_newest_ids_not_reused(clean_state().await).await;
+        test_table_retention_period(clean_state().await).await;
+        test_column_metadata(clean_state().await).await;
+        test_table_delete(clean_state().await).await;
 
         let catalog = clean_state().await;
         test_namespace(Arc::clone(&catalog)).await;
@@ -760,6 +922,10 @@ pub(crate) mod test_helpers {
         let catalog = clean_state().await;
         test_parquet_file(Arc::clone(&catalog)).await;
         assert_metric_hit(&catalog.metrics(), "parquet_create");
+
+        let catalog = clean_state().await;
+        test_parquet_file_create_many(Arc::clone(&catalog)).await;
+        assert_metric_hit(&catalog.metrics(), "parquet_create_many");
     }
 
     async fn test_setup(catalog: Arc<dyn Catalog>) {
@@ -795,6 +961,9 @@ pub(crate) mod test_helpers {
             namespace.max_columns_per_table,
             DEFAULT_MAX_COLUMNS_PER_TABLE
         );
+        assert!(!namespace.compaction_paused);
+        assert_eq!(namespace.max_write_lines_per_second, None);
+        assert_eq!(namespace.max_write_bytes_per_second, None);
 
         let conflict = repos
             .namespaces()
@@ -860,6 +1029,61 @@ pub(crate) mod test_helpers {
             .expect("namespace should be updateable");
         assert_eq!(NEW_COLUMN_LIMIT, modified.max_columns_per_table);
 
+        let modified = repos
+            .namespaces()
+            .update_compaction_paused(namespace_name.as_str(), true)
+            .await
+            .expect("namespace should be updateable");
+        assert!(modified.compaction_paused);
+        let got = repos
+            .namespaces()
+            .get_by_id(modified.id, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(got.compaction_paused);
+        let modified = repos
+            .namespaces()
+            .update_compaction_paused(namespace_name.as_str(), false)
+            .await
+            .expect("namespace should be updateable");
+        assert!(!modified.compaction_paused);
+        let err = repos
+            .namespaces()
+            .update_compaction_paused("does_not_exist", true)
+            .await
+            .expect_err("should fail to update unknown namespace");
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
+        let modified = repos
+            .namespaces()
+            .update_write_rate_limits(namespace_name.as_str(), Some(1_000), Some(42_000))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_write_lines_per_second, Some(1_000));
+        assert_eq!(modified.max_write_bytes_per_second, Some(42_000));
+        let got = repos
+            .namespaces()
+            .get_by_id(modified.id, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.max_write_lines_per_second, Some(1_000));
+        assert_eq!(got.max_write_bytes_per_second, Some(42_000));
+        let modified = repos
+            .namespaces()
+            .update_write_rate_limits(namespace_name.as_str(), None, Some(42_000))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.max_write_lines_per_second, None);
+        assert_eq!(modified.max_write_bytes_per_second, Some(42_000));
+        let err = repos
+            .namespaces()
+            .update_write_rate_limits("does_not_exist", Some(1), None)
+            .await
+            .expect_err("should fail to update unknown namespace");
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
         const NEW_RETENTION_PERIOD_NS: i64 = 5 * 60 * 60 * 1000 * 1000 * 1000;
         let modified = repos
             .namespaces()
@@ -1794,6 +2018,111 @@ pub(crate) mod test_helpers {
             "Expected no skipped compactions, got: {skipped_compactions:?}"
         );
 
+        // The compactor can record details of the error that caused compaction to be skipped
+        let errors = repos
+            .partitions()
+            .list_skipped_compaction_errors()
+            .await
+            .unwrap();
+        assert!(errors.is_empty(), "Expected no errors, got: {errors:?}");
+        repos
+            .partitions()
+            .record_skipped_compaction_error(other_partition.id, "timeout", 1, 10, 3, "too slow")
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .record_skipped_compaction_error(
+                other_partition.id,
+                "out_of_memory",
+                2,
+                20,
+                4,
+                "too big",
+            )
+            .await
+            .unwrap();
+        let errors = repos
+            .partitions()
+            .list_skipped_compaction_errors()
+            .await
+            .unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].partition_id, other_partition.id);
+        assert_eq!(errors[0].error_kind, "out_of_memory");
+        assert_eq!(errors[0].num_files, 2);
+        assert_eq!(errors[0].total_bytes, 20);
+        assert_eq!(errors[0].num_failures, 4);
+        assert_eq!(errors[0].last_error, "too big");
+
+        let deleted_error = repos
+            .partitions()
+            .delete_skipped_compaction_error(other_partition.id)
+            .await
+            .unwrap()
+            .expect("The error should have been returned");
+        assert_eq!(deleted_error, errors[0]);
+        let deleted_error = repos
+            .partitions()
+            .delete_skipped_compaction_error(other_partition.id)
+            .await
+            .unwrap();
+        assert!(deleted_error.is_none());
+
+        // The compaction scheduler records the commits of compaction jobs
+        let history = repos
+            .partitions()
+            .list_compaction_history(other_partition.id)
+            .await
+            .unwrap();
+        assert!(history.is_empty(), "Expected no history, got: {history:?}");
+        let params = CompactionHistoryParams {
+            partition_id: other_partition.id,
+            job_id: Uuid::new_v4(),
+            num_input_files: 3,
+            input_bytes: 300,
+            num_upgraded_files: 1,
+            num_output_files: 2,
+            output_bytes: 250,
+            target_level: CompactionLevel::FileNonOverlapped,
+            duration_ms: 1_000,
+            committed_at: Timestamp::new(10),
+        };
+        let first = repos
+            .partitions()
+            .record_compaction_history(params.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            first,
+            CompactionHistory::from_params(params.clone(), first.id)
+        );
+        let second = repos
+            .partitions()
+            .record_compaction_history(CompactionHistoryParams {
+                num_input_files: 2,
+                target_level: CompactionLevel::Final,
+                committed_at: Timestamp::new(20),
+                ..params.clone()
+            })
+            .await
+            .unwrap();
+        assert!(second.id > first.id);
+        repos
+            .partitions()
+            .record_compaction_history(CompactionHistoryParams {
+                partition_id: created_sorted[0].id,
+                ..params
+            })
+            .await
+            .unwrap();
+        let history = repos
+            .partitions()
+            .list_compaction_history(other_partition.id)
+            .await
+            .unwrap();
+        assert_eq!(history, vec![first, second]);
+
         let recent = repos
             .partitions()
             .most_recent_n(10)
@@ -2045,16 +2374,20 @@ pub(crate) mod test_helpers {
             .unwrap();
         assert!(files.is_empty());
 
-        // test delete_old_ids_only
+        // test delete_old_ids_only, listing the same files before without deleting them
         let older_than = Timestamp::new(
             (catalog.time_provider().now() + Duration::from_secs(100)).timestamp_nanos(),
         );
+        let old = repos.parquet_files().list_old(older_than).await.unwrap();
         let ids = repos
             .parquet_files()
             .delete_old_ids_only(older_than)
             .await
             .unwrap();
         assert_eq!(ids.len(), 1);
+        assert_eq!(old.into_iter().map(|f| f.id).collect::<Vec<_>>(), ids);
+        let old = repos.parquet_files().list_old(older_than).await.unwrap();
+        assert!(old.is_empty());
 
         // test retention-based flagging for deletion
         // Since mem catalog has default retention 1 hour, let us first set it to 0 means infinite
@@ -2111,11 +2444,22 @@ pub(crate) mod test_helpers {
             .create(f5_params.clone())
             .await
             .unwrap();
-        let ids = repos
+        let mut to_flag: Vec<_> = repos
+            .parquet_files()
+            .list_to_flag_by_retention()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+        let mut ids = repos
             .parquet_files()
             .flag_for_delete_by_retention()
             .await
             .unwrap();
+        to_flag.sort();
+        ids.sort();
+        assert_eq!(to_flag, ids);
         assert!(ids.len() > 1); // it's also going to flag f1, f2 & f3 because they have low max
                                 // timestamps but i don't want this test to be brittle if those
                                 // values change so i'm not asserting len == 4
@@ -2262,65 +2606,416 @@ pub(crate) mod test_helpers {
         assert_eq!(present, expected);
     }
 
-    async fn test_parquet_file_delete_broken(catalog: Arc<dyn Catalog>) {
+    async fn test_partitions_needing_cold_compact(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
-        let namespace_1 = arbitrary_namespace(&mut *repos, "retention_broken_1").await;
-        let namespace_2 = repos
-            .namespaces()
-            .create(
-                &NamespaceName::new("retention_broken_2").unwrap(),
-                None,
-                Some(1),
-                None,
-            )
-            .await
-            .unwrap();
-        let table_1 = arbitrary_table(&mut *repos, "test_table", &namespace_1).await;
-        let table_2 = arbitrary_table(&mut *repos, "test_table", &namespace_2).await;
-        let partition_1 = repos
-            .partitions()
-            .create_or_get("one".into(), table_1.id)
+        let namespace =
+            arbitrary_namespace(&mut *repos, "test_partitions_needing_cold_compact").await;
+        let table = arbitrary_table(&mut *repos, "test_table_cold_compact", &namespace).await;
+
+        let time_now = Timestamp::from(catalog.time_provider().now());
+        let time_two_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(2));
+        let time_three_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(3));
+
+        // cold L0 file
+        let p1 = create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "cold_l0",
+            vec![(time_three_hour_ago, CompactionLevel::Initial, false)],
+        )
+        .await;
+        // cold L1 and L2 files
+        let p2 = create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "cold_l1",
+            vec![
+                (
+                    time_three_hour_ago,
+                    CompactionLevel::FileNonOverlapped,
+                    false,
+                ),
+                (time_three_hour_ago, CompactionLevel::Final, false),
+            ],
+        )
+        .await;
+        // fully compacted
+        create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "cold_l2",
+            vec![(time_three_hour_ago, CompactionLevel::Final, false)],
+        )
+        .await;
+        // only deleted files
+        create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "cold_deleted",
+            vec![(time_three_hour_ago, CompactionLevel::Initial, true)],
+        )
+        .await;
+        // recent writes
+        create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "hot",
+            vec![
+                (time_three_hour_ago, CompactionLevel::Initial, false),
+                (time_now, CompactionLevel::Initial, false),
+            ],
+        )
+        .await;
+        // no files
+        create_partition_with_files(&mut *repos, &namespace, &table, "empty", vec![]).await;
+
+        let mut partitions = repos.partitions();
+        assert_eq!(
+            partitions
+                .partitions_needing_cold_compact(time_two_hour_ago, None, 10)
+                .await
+                .unwrap(),
+            vec![p1, p2],
+        );
+        assert!(partitions
+            .partitions_needing_cold_compact(time_three_hour_ago, None, 10)
             .await
-            .unwrap();
-        let partition_2 = repos
-            .partitions()
-            .create_or_get("one".into(), table_2.id)
+            .unwrap()
+            .is_empty());
+
+        // paging
+        assert_eq!(
+            partitions
+                .partitions_needing_cold_compact(time_two_hour_ago, None, 1)
+                .await
+                .unwrap(),
+            vec![p1],
+        );
+        assert_eq!(
+            partitions
+                .partitions_needing_cold_compact(time_two_hour_ago, Some(p1), 1)
+                .await
+                .unwrap(),
+            vec![p2],
+        );
+        assert!(partitions
+            .partitions_needing_cold_compact(time_two_hour_ago, Some(p2), 1)
             .await
-            .unwrap();
+            .unwrap()
+            .is_empty());
+    }
 
-        let parquet_file_params_1 =
-            arbitrary_parquet_file_params(&namespace_1, &table_1, &partition_1);
-        let parquet_file_params_2 =
-            arbitrary_parquet_file_params(&namespace_2, &table_2, &partition_2);
-        let _parquet_file_1 = repos
+    async fn test_sum_l0_file_size_by_partition(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace =
+            arbitrary_namespace(&mut *repos, "test_sum_l0_file_size_by_partition").await;
+        let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+        let time = Timestamp::from(catalog.time_provider().now());
+
+        let p1 = create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "two_l0",
+            vec![
+                (time, CompactionLevel::Initial, false),
+                (time, CompactionLevel::Initial, false),
+                (time, CompactionLevel::FileNonOverlapped, false),
+                (time, CompactionLevel::Initial, true),
+            ],
+        )
+        .await;
+        let p2 = create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "one_l0",
+            vec![(time, CompactionLevel::Initial, false)],
+        )
+        .await;
+        let p3 = create_partition_with_files(
+            &mut *repos,
+            &namespace,
+            &table,
+            "no_l0",
+            vec![(time, CompactionLevel::Final, false)],
+        )
+        .await;
+
+        let mut sizes = repos
             .parquet_files()
-            .create(parquet_file_params_1)
+            .sum_l0_file_size_by_partition(&[p1, p3])
             .await
             .unwrap();
-        let parquet_file_2 = repos
+        sizes.sort();
+        // `arbitrary_parquet_file_params` creates files of 1337 bytes
+        assert_eq!(sizes, vec![(p1, 2 * 1337)]);
+
+        let mut sizes = repos
             .parquet_files()
-            .create(parquet_file_params_2)
+            .sum_l0_file_size_by_partition(&[p1, p2, p3])
             .await
             .unwrap();
+        sizes.sort();
+        assert_eq!(sizes, vec![(p1, 2 * 1337), (p2, 1337)]);
 
-        let ids = repos
+        assert!(repos
             .parquet_files()
-            .flag_for_delete_by_retention()
+            .sum_l0_file_size_by_partition(&[])
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    /// Create partition with files of the given creation time and compaction level. Files flagged with `true` are
+    /// deleted right away.
+    async fn create_partition_with_files(
+        repos: &mut dyn RepoCollection,
+        namespace: &Namespace,
+        table: &Table,
+        key: &str,
+        files: Vec<(Timestamp, CompactionLevel, bool)>,
+    ) -> PartitionId {
+        let partition = repos
+            .partitions()
+            .create_or_get(key.into(), table.id)
             .await
             .unwrap();
-        assert_eq!(ids, vec![parquet_file_2.id]);
+        for (created_at, compaction_level, deleted) in files {
+            let params = ParquetFileParams {
+                created_at,
+                compaction_level,
+                ..arbitrary_parquet_file_params(namespace, table, &partition)
+            };
+            let file = repos.parquet_files().create(params).await.unwrap();
+            if deleted {
+                repos
+                    .parquet_files()
+                    .create_upgrade_delete(&[file.id], &[], &[], CompactionLevel::Initial)
+                    .await
+                    .unwrap();
+            }
+        }
+        partition.id
     }
 
-    async fn test_partitions_new_file_between(catalog: Arc<dyn Catalog>) {
+    async fn test_parquet_file_create_many(catalog: Arc<dyn Catalog>) {
         let mut repos = catalog.repositories().await;
-        let namespace = arbitrary_namespace(&mut *repos, "test_partitions_new_file_between").await;
-        let table =
-            arbitrary_table(&mut *repos, "test_table_for_new_file_between", &namespace).await;
-
-        // param for the tests
-        let time_now = Timestamp::from(catalog.time_provider().now());
-        let time_one_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(1));
-        let time_two_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(2));
+        let namespace = arbitrary_namespace(&mut *repos, "test_parquet_file_create_many").await;
+        let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+
+        // Creating no files is a no-op.
+        let created = repos.parquet_files().create_many(vec![]).await.unwrap();
+        assert!(created.is_empty());
+
+        let params = (0..3)
+            .map(|i| ParquetFileParams {
+                file_size_bytes: i,
+                ..arbitrary_parquet_file_params(&namespace, &table, &partition)
+            })
+            .collect::<Vec<_>>();
+        let created = repos
+            .parquet_files()
+            .create_many(params.clone())
+            .await
+            .unwrap();
+
+        // The files are returned in the order they were given.
+        assert_eq!(
+            created
+                .iter()
+                .map(|f| (f.object_store_id, f.file_size_bytes))
+                .collect::<Vec<_>>(),
+            params
+                .iter()
+                .map(|p| (p.object_store_id, p.file_size_bytes))
+                .collect::<Vec<_>>(),
+        );
+        let mut listed = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition.transition_partition_id())
+            .await
+            .unwrap();
+        listed.sort_by_key(|f| f.file_size_bytes);
+        assert_eq!(listed, created);
+
+        // A batch containing an existing file is rejected as a whole.
+        let new_file = arbitrary_parquet_file_params(&namespace, &table, &partition);
+        let err = repos
+            .parquet_files()
+            .create_many(vec![new_file.clone(), params[1].clone()])
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::FileExists { object_store_id } if object_store_id == params[1].object_store_id
+        );
+        assert!(repos
+            .parquet_files()
+            .get_by_object_store_id(new_file.object_store_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // As is a batch containing the same file twice.
+        let err = repos
+            .parquet_files()
+            .create_many(vec![new_file.clone(), new_file.clone()])
+            .await
+            .unwrap_err();
+        assert_matches!(
+            err,
+            Error::FileExists { object_store_id } if object_store_id == new_file.object_store_id
+        );
+        assert!(repos
+            .parquet_files()
+            .get_by_object_store_id(new_file.object_store_id)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    async fn test_parquet_file_tag_ranges(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "test_parquet_file_tag_ranges").await;
+        let table = arbitrary_table(&mut *repos, "test_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+
+        let tag_ranges = TagRanges::new([
+            (
+                ColumnId::new(1),
+                TagRange {
+                    min: "a".to_string(),
+                    max: "z".to_string(),
+                },
+            ),
+            (
+                ColumnId::new(2),
+                TagRange {
+                    min: "host-\"1\"".to_string(),
+                    max: "host-\"1\"".to_string(),
+                },
+            ),
+        ]);
+        let with_ranges = ParquetFileParams {
+            tag_ranges: Some(tag_ranges.clone()),
+            ..arbitrary_parquet_file_params(&namespace, &table, &partition)
+        };
+        let without_ranges = arbitrary_parquet_file_params(&namespace, &table, &partition);
+
+        let created = repos
+            .parquet_files()
+            .create(with_ranges.clone())
+            .await
+            .unwrap();
+        assert_eq!(created.tag_ranges.as_ref(), Some(&tag_ranges));
+        repos
+            .parquet_files()
+            .create_many(vec![without_ranges.clone()])
+            .await
+            .unwrap();
+
+        // The ranges are returned when reading the files back.
+        let got = repos
+            .parquet_files()
+            .get_by_object_store_id(with_ranges.object_store_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.tag_ranges, Some(tag_ranges.clone()));
+        let got = repos
+            .parquet_files()
+            .get_by_object_store_id(without_ranges.object_store_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.tag_ranges, None);
+
+        let listed = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .unwrap();
+        let got = listed
+            .iter()
+            .find(|f| f.object_store_id == with_ranges.object_store_id)
+            .unwrap();
+        assert_eq!(got.tag_ranges, Some(tag_ranges));
+    }
+
+    async fn test_parquet_file_delete_broken(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace_1 = arbitrary_namespace(&mut *repos, "retention_broken_1").await;
+        let namespace_2 = repos
+            .namespaces()
+            .create(
+                &NamespaceName::new("retention_broken_2").unwrap(),
+                None,
+                Some(1),
+                None,
+            )
+            .await
+            .unwrap();
+        let table_1 = arbitrary_table(&mut *repos, "test_table", &namespace_1).await;
+        let table_2 = arbitrary_table(&mut *repos, "test_table", &namespace_2).await;
+        let partition_1 = repos
+            .partitions()
+            .create_or_get("one".into(), table_1.id)
+            .await
+            .unwrap();
+        let partition_2 = repos
+            .partitions()
+            .create_or_get("one".into(), table_2.id)
+            .await
+            .unwrap();
+
+        let parquet_file_params_1 =
+            arbitrary_parquet_file_params(&namespace_1, &table_1, &partition_1);
+        let parquet_file_params_2 =
+            arbitrary_parquet_file_params(&namespace_2, &table_2, &partition_2);
+        let _parquet_file_1 = repos
+            .parquet_files()
+            .create(parquet_file_params_1)
+            .await
+            .unwrap();
+        let parquet_file_2 = repos
+            .parquet_files()
+            .create(parquet_file_params_2)
+            .await
+            .unwrap();
+
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![parquet_file_2.id]);
+    }
+
+    async fn test_partitions_new_file_between(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "test_partitions_new_file_between").await;
+        let table =
+            arbitrary_table(&mut *repos, "test_table_for_new_file_between", &namespace).await;
+
+        // param for the tests
+        let time_now = Timestamp::from(catalog.time_provider().now());
+        let time_one_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(1));
+        let time_two_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(2));
         let time_three_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(3));
         let time_five_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(5));
         let time_six_hour_ago = Timestamp::from(catalog.time_provider().hours_ago(6));
@@ -3103,6 +3798,541 @@ pub(crate) mod test_helpers {
         assert!(!got.contains(&ns2), "{:#?}\n\n do not want{:#?}", got, &ns2);
     }
 
+    async fn test_namespace_undelete_and_purge(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+
+        let namespace_1 = arbitrary_namespace(&mut *repos, "namespace_test_purge_1").await;
+        let table_1 = arbitrary_table(&mut *repos, "test_table_1", &namespace_1).await;
+        let column_1 = repos
+            .columns()
+            .create_or_get("column_test_1", table_1.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let partition_1 = repos
+            .partitions()
+            .create_or_get("test_purge_one".into(), table_1.id)
+            .await
+            .unwrap();
+        let parquet_file_1 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace_1,
+                &table_1,
+                &partition_1,
+            ))
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .record_skipped_compaction(partition_1.id, "purge me", 1, 2, 4, 10, 20)
+            .await
+            .unwrap();
+
+        // a second namespace that must survive purging the first one
+        let namespace_2 = arbitrary_namespace(&mut *repos, "namespace_test_purge_2").await;
+        let table_2 = arbitrary_table(&mut *repos, "test_table_2", &namespace_2).await;
+        let column_2 = repos
+            .columns()
+            .create_or_get("column_test_2", table_2.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let partition_2 = repos
+            .partitions()
+            .create_or_get("test_purge_two".into(), table_2.id)
+            .await
+            .unwrap();
+        let parquet_file_2 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace_2,
+                &table_2,
+                &partition_2,
+            ))
+            .await
+            .unwrap();
+        repos
+            .partitions()
+            .record_skipped_compaction(partition_2.id, "keep me", 1, 2, 4, 10, 20)
+            .await
+            .unwrap();
+
+        // namespaces that are not soft-deleted can neither be undeleted nor purged
+        let err = repos
+            .namespaces()
+            .undelete(&namespace_1.name)
+            .await
+            .expect_err("undeleting an active namespace should fail");
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+        let err = repos
+            .namespaces()
+            .purge(namespace_1.id)
+            .await
+            .expect_err("purging an active namespace should fail");
+        assert_matches!(err, Error::NamespaceNotSoftDeleted { id } if id == namespace_1.id);
+
+        // soft-deleting and undeleting restores the namespace as it was
+        repos
+            .namespaces()
+            .soft_delete(&namespace_1.name)
+            .await
+            .unwrap();
+        let undeleted = repos
+            .namespaces()
+            .undelete(&namespace_1.name)
+            .await
+            .expect("undelete should succeed");
+        assert_eq!(undeleted, namespace_1);
+        assert_eq!(
+            repos
+                .namespaces()
+                .get_by_name(&namespace_1.name, SoftDeletedRows::ExcludeDeleted)
+                .await
+                .unwrap(),
+            Some(namespace_1.clone())
+        );
+
+        // purging a soft-deleted namespace removes it and everything attached to it
+        repos
+            .namespaces()
+            .soft_delete(&namespace_1.name)
+            .await
+            .unwrap();
+        repos
+            .namespaces()
+            .purge(namespace_1.id)
+            .await
+            .expect("purge should succeed");
+        assert!(repos
+            .namespaces()
+            .get_by_id(namespace_1.id, SoftDeletedRows::AllRows)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repos
+            .tables()
+            .get_by_id(table_1.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repos
+            .columns()
+            .list_by_table_id(table_1.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repos
+            .partitions()
+            .get_by_id(partition_1.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repos
+            .partitions()
+            .get_in_skipped_compaction(partition_1.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(repos
+            .parquet_files()
+            .get_by_object_store_id(parquet_file_1.object_store_id)
+            .await
+            .unwrap()
+            .is_none());
+
+        // the other namespace is untouched
+        assert_eq!(
+            repos
+                .namespaces()
+                .get_by_id(namespace_2.id, SoftDeletedRows::AllRows)
+                .await
+                .unwrap(),
+            Some(namespace_2.clone())
+        );
+        assert_eq!(
+            repos.tables().get_by_id(table_2.id).await.unwrap(),
+            Some(table_2.clone())
+        );
+        assert_eq!(
+            repos.columns().list_by_table_id(table_2.id).await.unwrap(),
+            vec![column_2]
+        );
+        assert!(repos
+            .partitions()
+            .get_by_id(partition_2.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(repos
+            .partitions()
+            .get_in_skipped_compaction(partition_2.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            repos
+                .parquet_files()
+                .get_by_object_store_id(parquet_file_2.object_store_id)
+                .await
+                .unwrap(),
+            Some(parquet_file_2)
+        );
+
+        // IDs of purged rows are not handed out again
+        let namespace_3 = arbitrary_namespace(&mut *repos, "namespace_test_purge_3").await;
+        assert_ne!(namespace_3.id, namespace_1.id);
+        assert_ne!(namespace_3.id, namespace_2.id);
+        let table_3 = arbitrary_table(&mut *repos, "test_table_3", &namespace_3).await;
+        assert_ne!(table_3.id, table_1.id);
+        let column_3 = repos
+            .columns()
+            .create_or_get("column_test_3", table_3.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        assert_ne!(column_3.id, column_1.id);
+
+        // a purged namespace is gone for good
+        let err = repos
+            .namespaces()
+            .purge(namespace_1.id)
+            .await
+            .expect_err("purging a purged namespace should fail");
+        assert_matches!(err, Error::NamespaceNotFoundById { .. });
+        let err = repos
+            .namespaces()
+            .undelete(&namespace_1.name)
+            .await
+            .expect_err("undeleting a purged namespace should fail");
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+    }
+
+    /// Purging the most recently created namespace must not hand out the IDs of its rows again,
+    /// they are the highest ones handed out so far.
+    async fn test_namespace_purge_newest_ids_not_reused(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+
+        let namespace_1 = arbitrary_namespace(&mut *repos, "namespace_test_purge_newest").await;
+        let table_1 = arbitrary_table(&mut *repos, "test_table_1", &namespace_1).await;
+        let column_1 = repos
+            .columns()
+            .create_or_get("column_test_1", table_1.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let partition_1 = repos
+            .partitions()
+            .create_or_get("test_purge_newest".into(), table_1.id)
+            .await
+            .unwrap();
+        let parquet_file_1 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace_1,
+                &table_1,
+                &partition_1,
+            ))
+            .await
+            .unwrap();
+
+        repos
+            .namespaces()
+            .soft_delete(&namespace_1.name)
+            .await
+            .unwrap();
+        repos
+            .namespaces()
+            .purge(namespace_1.id)
+            .await
+            .expect("purge should succeed");
+
+        // recreating everything under the same names yields new, higher IDs
+        let namespace_2 = arbitrary_namespace(&mut *repos, "namespace_test_purge_newest").await;
+        assert!(namespace_2.id > namespace_1.id);
+        let table_2 = arbitrary_table(&mut *repos, "test_table_1", &namespace_2).await;
+        assert!(table_2.id > table_1.id);
+        let column_2 = repos
+            .columns()
+            .create_or_get("column_test_1", table_2.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        assert!(column_2.id > column_1.id);
+        let partition_2 = repos
+            .partitions()
+            .create_or_get("test_purge_newest".into(), table_2.id)
+            .await
+            .unwrap();
+        assert!(partition_2.id > partition_1.id);
+        let parquet_file_2 = repos
+            .parquet_files()
+            .create(arbitrary_parquet_file_params(
+                &namespace_2,
+                &table_2,
+                &partition_2,
+            ))
+            .await
+            .unwrap();
+        assert!(parquet_file_2.id > parquet_file_1.id);
+    }
+
+    async fn test_table_retention_period(catalog: Arc<dyn Catalog>) {
+        const HOUR_NS: i64 = 60 * 60 * 1_000_000_000;
+
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_table_retention").await;
+        let namespace = repos
+            .namespaces()
+            .update_retention_period(&namespace.name, Some(24 * HOUR_NS))
+            .await
+            .unwrap();
+        let events = arbitrary_table(&mut *repos, "events", &namespace).await;
+        let metrics = arbitrary_table(&mut *repos, "metrics", &namespace).await;
+        assert_eq!(events.retention_period_ns, None);
+        assert_eq!(metrics.retention_period_ns, None);
+
+        // keep `events` for a year but `metrics` only for an hour
+        let events = repos
+            .tables()
+            .update_retention_period(events.id, Some(365 * 24 * HOUR_NS))
+            .await
+            .unwrap();
+        assert_eq!(events.retention_period_ns, Some(365 * 24 * HOUR_NS));
+        let metrics = repos
+            .tables()
+            .update_retention_period(metrics.id, Some(HOUR_NS))
+            .await
+            .unwrap();
+        assert_eq!(metrics.retention_period_ns, Some(HOUR_NS));
+        let got = repos.tables().get_by_id(metrics.id).await.unwrap().unwrap();
+        assert_eq!(got, metrics);
+
+        let err = repos
+            .tables()
+            .update_retention_period(TableId::new(i64::MAX), Some(HOUR_NS))
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableNotFound { .. });
+
+        // files of both tables that are two hours old: only the `metrics` file has expired
+        let two_hours_ago =
+            Timestamp::from(catalog.time_provider().now() - Duration::from_secs(2 * 60 * 60));
+        let mut files = vec![];
+        for table in [&events, &metrics] {
+            let partition = repos
+                .partitions()
+                .create_or_get("retention".into(), table.id)
+                .await
+                .unwrap();
+            let file = repos
+                .parquet_files()
+                .create(ParquetFileParams {
+                    max_time: two_hours_ago,
+                    ..arbitrary_parquet_file_params(&namespace, table, &partition)
+                })
+                .await
+                .unwrap();
+            files.push(file);
+        }
+        let (events_file, metrics_file) = (&files[0], &files[1]);
+
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![metrics_file.id]);
+
+        // removing the override makes the table inherit the namespace retention period again
+        let events = repos
+            .tables()
+            .update_retention_period(events.id, None)
+            .await
+            .unwrap();
+        assert_eq!(events.retention_period_ns, None);
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert!(ids.is_empty());
+
+        repos
+            .namespaces()
+            .update_retention_period(&namespace.name, Some(HOUR_NS))
+            .await
+            .unwrap();
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![events_file.id]);
+    }
+
+    async fn test_column_metadata(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_column_metadata").await;
+        let table = arbitrary_table(&mut *repos, "table", &namespace).await;
+        let column = repos
+            .columns()
+            .create_or_get("duration", table.id, ColumnType::F64)
+            .await
+            .unwrap();
+        let other_column = repos
+            .columns()
+            .create_or_get("host", table.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        let other_namespace = arbitrary_namespace(&mut *repos, "namespace_column_metadata_2").await;
+        let other_table = arbitrary_table(&mut *repos, "table", &other_namespace).await;
+        let other_namespace_column = repos
+            .columns()
+            .create_or_get("duration", other_table.id, ColumnType::F64)
+            .await
+            .unwrap();
+
+        // columns without metadata are not listed
+        let listed = repos
+            .columns()
+            .list_metadata_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert!(listed.is_empty());
+
+        let metadata = ColumnMetadata {
+            column_id: column.id,
+            description: Some("request duration".to_string()),
+            unit: Some("ms".to_string()),
+            semantic_type: None,
+        };
+        let got = repos
+            .columns()
+            .update_metadata(metadata.clone())
+            .await
+            .unwrap();
+        assert_eq!(got, metadata);
+        repos
+            .columns()
+            .update_metadata(ColumnMetadata {
+                column_id: other_namespace_column.id,
+                description: None,
+                unit: Some("s".to_string()),
+                semantic_type: None,
+            })
+            .await
+            .unwrap();
+
+        let listed = repos
+            .columns()
+            .list_metadata_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![metadata]);
+
+        // updating replaces all metadata of the column
+        let metadata = ColumnMetadata {
+            column_id: column.id,
+            description: None,
+            unit: Some("s".to_string()),
+            semantic_type: Some("gauge".to_string()),
+        };
+        let got = repos
+            .columns()
+            .update_metadata(metadata.clone())
+            .await
+            .unwrap();
+        assert_eq!(got, metadata);
+        let other_metadata = ColumnMetadata {
+            column_id: other_column.id,
+            description: Some("host name".to_string()),
+            unit: None,
+            semantic_type: None,
+        };
+        repos
+            .columns()
+            .update_metadata(other_metadata.clone())
+            .await
+            .unwrap();
+
+        let mut listed = repos
+            .columns()
+            .list_metadata_by_namespace_id(namespace.id)
+            .await
+            .unwrap();
+        listed.sort_by_key(|m| m.column_id);
+        assert_eq!(listed, vec![metadata, other_metadata]);
+
+        let err = repos
+            .columns()
+            .update_metadata(ColumnMetadata {
+                column_id: ColumnId::new(i64::MAX),
+                description: None,
+                unit: None,
+                semantic_type: None,
+            })
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::ColumnNotFound { .. });
+    }
+
+    async fn test_table_delete(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_table_delete").await;
+        let provisioned = arbitrary_table(&mut *repos, "provisioned", &namespace).await;
+        let column = repos
+            .columns()
+            .create_or_get("host", provisioned.id, ColumnType::Tag)
+            .await
+            .unwrap();
+        repos
+            .columns()
+            .update_metadata(ColumnMetadata {
+                column_id: column.id,
+                description: Some("host name".to_string()),
+                unit: None,
+                semantic_type: None,
+            })
+            .await
+            .unwrap();
+        let written = arbitrary_table(&mut *repos, "written", &namespace).await;
+        repos
+            .partitions()
+            .create_or_get("one".into(), written.id)
+            .await
+            .unwrap();
+
+        // a table without partitions is deleted together with its columns
+        let deleted = repos.tables().delete(provisioned.id).await.unwrap();
+        assert_eq!(deleted, provisioned);
+        assert_eq!(
+            repos.tables().get_by_id(provisioned.id).await.unwrap(),
+            None
+        );
+        assert!(repos
+            .columns()
+            .list_by_table_id(provisioned.id)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(repos
+            .columns()
+            .list_metadata_by_namespace_id(namespace.id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // the name can be used again
+        arbitrary_table(&mut *repos, "provisioned", &namespace).await;
+
+        // a table that has been written to is kept
+        let err = repos.tables().delete(written.id).await.unwrap_err();
+        assert_matches!(err, Error::TableNotEmpty { id } if id == written.id);
+        assert_eq!(
+            repos.tables().get_by_id(written.id).await.unwrap(),
+            Some(written)
+        );
+
+        let err = repos.tables().delete(provisioned.id).await.unwrap_err();
+        assert_matches!(err, Error::TableNotFound { id } if id == provisioned.id);
+    }
+
     fn assert_metric_hit(metrics: &metric::Registry, name: &'static str) {
         let histogram = metrics
             .get_instrument::<Metric<DurationHistogram>>("catalog_op_duration")
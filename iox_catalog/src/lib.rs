@@ -43,6 +43,7 @@ pub mod mem;
 pub mod metrics;
 pub mod migrate;
 pub mod postgres;
+pub mod retry;
 pub mod sqlite;
 
 /// An [`crate::interface::Error`] scoped to a single table for schema validation errors.
@@ -407,6 +408,7 @@ pub mod test_helpers {
             created_at: Timestamp::new(1),
             column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
             max_l0_created_at: Timestamp::new(1),
+            tag_ranges: None,
         }
     }
 }
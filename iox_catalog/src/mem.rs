@@ -16,10 +16,11 @@ use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnId, ColumnType, CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    Column, ColumnId, ColumnMetadata, ColumnType, CompactionHistory, CompactionHistoryParams,
+    CompactionLevel, Namespace, NamespaceId, NamespaceName,
     NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
-    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction, Table, TableId,
-    Timestamp, TransitionPartitionId,
+    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
+    SkippedCompactionError, Table, TableId, Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use snafu::ensure;
@@ -53,6 +54,7 @@ impl MemCatalog {
     /// uniqueness checks
     pub async fn add_partition(&self, partition: Partition) {
         let mut collections = Arc::clone(&self.collections).lock_owned().await;
+        collections.partition_ids.observe(partition.id.get());
         collections.partitions.push(partition);
     }
 }
@@ -68,9 +70,40 @@ struct MemCollections {
     namespaces: Vec<Namespace>,
     tables: Vec<Table>,
     columns: Vec<Column>,
+    column_metadata: Vec<ColumnMetadata>,
     partitions: Vec<Partition>,
     skipped_compactions: Vec<SkippedCompaction>,
+    skipped_compaction_errors: Vec<SkippedCompactionError>,
+    compaction_history: Vec<CompactionHistory>,
     parquet_files: Vec<ParquetFile>,
+    namespace_ids: IdSequence,
+    table_ids: IdSequence,
+    column_ids: IdSequence,
+    partition_ids: IdSequence,
+    compaction_history_ids: IdSequence,
+    parquet_file_ids: IdSequence,
+}
+
+/// Monotonically increasing IDs of one catalog table, mirroring a database sequence.
+///
+/// IDs are not derived from the stored rows, so the IDs of purged or deleted rows are never handed
+/// out again.
+#[derive(Default, Debug, Clone)]
+struct IdSequence {
+    last: i64,
+}
+
+impl IdSequence {
+    /// Returns the next ID.
+    fn next(&mut self) -> i64 {
+        self.last += 1;
+        self.last
+    }
+
+    /// Records an ID that was assigned outside of this sequence, so it is not handed out again.
+    fn observe(&mut self, id: i64) {
+        self.last = self.last.max(id);
+    }
 }
 
 /// transaction bound to an in-memory catalog.
@@ -163,13 +196,16 @@ impl NamespaceRepo for MemTxn {
         let max_columns_per_table = service_protection_limits.and_then(|l| l.max_columns_per_table);
 
         let namespace = Namespace {
-            id: NamespaceId::new(stage.namespaces.len() as i64 + 1),
+            id: NamespaceId::new(stage.namespace_ids.next()),
             name: name.to_string(),
             max_tables: max_tables.unwrap_or(DEFAULT_MAX_TABLES),
             max_columns_per_table: max_columns_per_table.unwrap_or(DEFAULT_MAX_COLUMNS_PER_TABLE),
             retention_period_ns,
             deleted_at: None,
             partition_template: partition_template.unwrap_or_default(),
+            compaction_paused: false,
+            max_write_lines_per_second: None,
+            max_write_bytes_per_second: None,
         };
         stage.namespaces.push(namespace);
         Ok(stage.namespaces.last().unwrap().clone())
@@ -224,6 +260,73 @@ impl NamespaceRepo for MemTxn {
         }
     }
 
+    async fn undelete(&mut self, name: &str) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_some())
+        {
+            Some(n) => {
+                n.deleted_at = None;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn purge(&mut self, id: NamespaceId) -> Result<()> {
+        let stage = self.stage();
+        match stage.namespaces.iter().find(|n| n.id == id) {
+            Some(n) if n.deleted_at.is_none() => {
+                return Err(Error::NamespaceNotSoftDeleted { id });
+            }
+            Some(_) => {}
+            None => return Err(Error::NamespaceNotFoundById { id }),
+        }
+
+        let table_ids: HashSet<_> = stage
+            .tables
+            .iter()
+            .filter(|t| t.namespace_id == id)
+            .map(|t| t.id)
+            .collect();
+        let partition_ids: HashSet<_> = stage
+            .partitions
+            .iter()
+            .filter(|p| table_ids.contains(&p.table_id))
+            .map(|p| p.id)
+            .collect();
+
+        stage.parquet_files.retain(|f| f.namespace_id != id);
+        stage
+            .skipped_compactions
+            .retain(|s| !partition_ids.contains(&s.partition_id));
+        stage
+            .skipped_compaction_errors
+            .retain(|s| !partition_ids.contains(&s.partition_id));
+        stage
+            .compaction_history
+            .retain(|h| !partition_ids.contains(&h.partition_id));
+        stage.partitions.retain(|p| !partition_ids.contains(&p.id));
+        let column_ids: HashSet<_> = stage
+            .columns
+            .iter()
+            .filter(|c| table_ids.contains(&c.table_id))
+            .map(|c| c.id)
+            .collect();
+        stage
+            .column_metadata
+            .retain(|m| !column_ids.contains(&m.column_id));
+        stage.columns.retain(|c| !table_ids.contains(&c.table_id));
+        stage.tables.retain(|t| t.namespace_id != id);
+        stage.namespaces.retain(|n| n.id != id);
+
+        Ok(())
+    }
+
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let stage = self.stage();
         match stage.namespaces.iter_mut().find(|n| n.name == name) {
@@ -250,6 +353,38 @@ impl NamespaceRepo for MemTxn {
         }
     }
 
+    async fn update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.compaction_paused = paused;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_write_rate_limits(
+        &mut self,
+        name: &str,
+        max_write_lines_per_second: Option<i64>,
+        max_write_bytes_per_second: Option<i64>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.max_write_lines_per_second = max_write_lines_per_second;
+                n.max_write_bytes_per_second = max_write_bytes_per_second;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
     async fn update_retention_period(
         &mut self,
         name: &str,
@@ -320,10 +455,11 @@ impl TableRepo for MemTxn {
                 }
                 None => {
                     let table = Table {
-                        id: TableId::new(stage.tables.len() as i64 + 1),
+                        id: TableId::new(stage.table_ids.next()),
                         namespace_id,
                         name: name.to_string(),
                         partition_template,
+                        retention_period_ns: None,
                     };
                     stage.tables.push(table);
                     stage.tables.last().unwrap()
@@ -384,6 +520,49 @@ impl TableRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.tables.clone())
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let stage = self.stage();
+        match stage.tables.iter_mut().find(|t| t.id == table_id) {
+            Some(t) => {
+                t.retention_period_ns = retention_period_ns;
+                Ok(t.clone())
+            }
+            None => Err(Error::TableNotFound { id: table_id }),
+        }
+    }
+
+    async fn delete(&mut self, table_id: TableId) -> Result<Table> {
+        let stage = self.stage();
+
+        let table = stage
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .cloned()
+            .ok_or(Error::TableNotFound { id: table_id })?;
+        if stage.partitions.iter().any(|p| p.table_id == table_id) {
+            return Err(Error::TableNotEmpty { id: table_id });
+        }
+
+        let column_ids: HashSet<_> = stage
+            .columns
+            .iter()
+            .filter(|c| c.table_id == table_id)
+            .map(|c| c.id)
+            .collect();
+        stage
+            .column_metadata
+            .retain(|m| !column_ids.contains(&m.column_id));
+        stage.columns.retain(|c| c.table_id != table_id);
+        stage.tables.retain(|t| t.id != table_id);
+
+        Ok(table)
+    }
 }
 
 #[async_trait]
@@ -451,7 +630,7 @@ impl ColumnRepo for MemTxn {
             }
             None => {
                 let column = Column {
-                    id: ColumnId::new(stage.columns.len() as i64 + 1),
+                    id: ColumnId::new(stage.column_ids.next()),
                     table_id,
                     name: name.to_string(),
                     column_type,
@@ -496,7 +675,7 @@ impl ColumnRepo for MemTxn {
                     }
                     None => {
                         let new_column = Column {
-                            id: ColumnId::new(stage.columns.len() as i64 + 1),
+                            id: ColumnId::new(stage.column_ids.next()),
                             table_id,
                             name: column_name.to_string(),
                             column_type,
@@ -547,6 +726,54 @@ impl ColumnRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.columns.clone())
     }
+
+    async fn update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata> {
+        let stage = self.stage();
+
+        if !stage.columns.iter().any(|c| c.id == metadata.column_id) {
+            return Err(Error::ColumnNotFound {
+                id: metadata.column_id,
+            });
+        }
+
+        match stage
+            .column_metadata
+            .iter_mut()
+            .find(|m| m.column_id == metadata.column_id)
+        {
+            Some(m) => *m = metadata.clone(),
+            None => stage.column_metadata.push(metadata.clone()),
+        }
+
+        Ok(metadata)
+    }
+
+    async fn list_metadata_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnMetadata>> {
+        let stage = self.stage();
+
+        let table_ids: HashSet<_> = stage
+            .tables
+            .iter()
+            .filter(|t| t.namespace_id == namespace_id)
+            .map(|t| t.id)
+            .collect();
+        let column_ids: HashSet<_> = stage
+            .columns
+            .iter()
+            .filter(|c| table_ids.contains(&c.table_id))
+            .map(|c| c.id)
+            .collect();
+
+        Ok(stage
+            .column_metadata
+            .iter()
+            .filter(|m| column_ids.contains(&m.column_id))
+            .cloned()
+            .collect())
+    }
 }
 
 #[async_trait]
@@ -562,7 +789,7 @@ impl PartitionRepo for MemTxn {
             Some(p) => p,
             None => {
                 let p = Partition::new_in_memory_only(
-                    PartitionId::new(stage.partitions.len() as i64 + 1),
+                    PartitionId::new(stage.partition_ids.next()),
                     table_id,
                     key,
                     vec![],
@@ -761,6 +988,77 @@ impl PartitionRepo for MemTxn {
         }
     }
 
+    async fn record_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+        error_kind: &str,
+        num_files: usize,
+        total_bytes: u64,
+        num_failures: usize,
+        last_error: &str,
+    ) -> Result<()> {
+        let record = SkippedCompactionError {
+            partition_id,
+            error_kind: error_kind.to_string(),
+            num_files: num_files as i64,
+            total_bytes: total_bytes as i64,
+            num_failures: num_failures as i64,
+            last_error: last_error.to_string(),
+            skipped_at: Timestamp::from(self.time_provider.now()),
+        };
+
+        let stage = self.stage();
+        match stage
+            .skipped_compaction_errors
+            .iter_mut()
+            .find(|s| s.partition_id == partition_id)
+        {
+            Some(s) => *s = record,
+            None => stage.skipped_compaction_errors.push(record),
+        }
+        Ok(())
+    }
+
+    async fn list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>> {
+        let stage = self.stage();
+        Ok(stage.skipped_compaction_errors.clone())
+    }
+
+    async fn delete_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompactionError>> {
+        let stage = self.stage();
+        let pos = stage
+            .skipped_compaction_errors
+            .iter()
+            .position(|s| s.partition_id == partition_id);
+        Ok(pos.map(|pos| stage.skipped_compaction_errors.remove(pos)))
+    }
+
+    async fn record_compaction_history(
+        &mut self,
+        params: CompactionHistoryParams,
+    ) -> Result<CompactionHistory> {
+        let stage = self.stage();
+        let record = CompactionHistory::from_params(params, stage.compaction_history_ids.next());
+        stage.compaction_history.push(record.clone());
+        Ok(record)
+    }
+
+    async fn list_compaction_history(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Vec<CompactionHistory>> {
+        let stage = self.stage();
+        Ok(stage
+            .compaction_history
+            .iter()
+            .filter(|h| h.partition_id == partition_id)
+            .cloned()
+            .collect())
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         let stage = self.stage();
         Ok(stage.partitions.iter().rev().take(n).cloned().collect())
@@ -787,6 +1085,36 @@ impl PartitionRepo for MemTxn {
 
         Ok(partitions)
     }
+
+    async fn partitions_needing_cold_compact(
+        &mut self,
+        maximum_time: Timestamp,
+        after: Option<PartitionId>,
+        n: usize,
+    ) -> Result<Vec<PartitionId>> {
+        let stage = self.stage();
+
+        let mut partitions: Vec<_> = stage
+            .partitions
+            .iter()
+            .filter(|p| {
+                p.new_file_at
+                    .map(|new_file_at| new_file_at < maximum_time)
+                    .unwrap_or_default()
+                    && after.map(|after| p.id > after).unwrap_or(true)
+                    && stage.parquet_files.iter().any(|f| {
+                        f.partition_id == p.id
+                            && f.to_delete.is_none()
+                            && f.compaction_level < CompactionLevel::Final
+                    })
+            })
+            .map(|p| p.id)
+            .collect();
+        partitions.sort();
+        partitions.truncate(n);
+
+        Ok(partitions)
+    }
 }
 
 #[async_trait]
@@ -795,6 +1123,22 @@ impl ParquetFileRepo for MemTxn {
         create_parquet_file(self.stage(), parquet_file_params).await
     }
 
+    async fn create_many(
+        &mut self,
+        parquet_file_params: Vec<ParquetFileParams>,
+    ) -> Result<Vec<ParquetFile>> {
+        let mut stage = self.inner.clone();
+
+        let mut files = Vec::with_capacity(parquet_file_params.len());
+        for file in parquet_file_params {
+            files.push(create_parquet_file(&mut stage, file).await?);
+        }
+
+        *self.inner = stage;
+
+        Ok(files)
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         let stage = self.stage();
 
@@ -810,25 +1154,26 @@ impl ParquetFileRepo for MemTxn {
             .iter_mut()
             // don't flag if already flagged for deletion
             .filter(|f| f.to_delete.is_none())
-            .filter_map(|f| {
-                // table retention, if it exists, overrides namespace retention
-                // TODO - include check of table retention period once implemented
-                stage
-                    .namespaces
-                    .iter()
-                    .find(|n| n.id == f.namespace_id)
-                    .and_then(|ns| {
-                        ns.retention_period_ns.and_then(|rp| {
-                            if f.max_time < now - rp {
-                                f.to_delete = Some(now);
-                                Some(f.id)
-                            } else {
-                                None
-                            }
-                        })
-                    })
+            .filter(|f| is_retention_expired(&stage.namespaces, &stage.tables, f, now))
+            .take(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION as usize)
+            .map(|f| {
+                f.to_delete = Some(now);
+                f.id
             })
+            .collect())
+    }
+
+    async fn list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>> {
+        let now = Timestamp::from(self.time_provider.now());
+        let stage = self.stage();
+
+        Ok(stage
+            .parquet_files
+            .iter()
+            .filter(|f| f.to_delete.is_none())
+            .filter(|f| is_retention_expired(&stage.namespaces, &stage.tables, f, now))
             .take(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION as usize)
+            .cloned()
             .collect())
     }
 
@@ -881,6 +1226,18 @@ impl ParquetFileRepo for MemTxn {
         Ok(delete)
     }
 
+    async fn list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>> {
+        let stage = self.stage();
+
+        Ok(stage
+            .parquet_files
+            .iter()
+            .filter(|f| matches!(f.to_delete, Some(marked_deleted) if marked_deleted < older_than))
+            .take(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_DELETE as usize)
+            .cloned()
+            .collect())
+    }
+
     async fn list_by_partition_not_to_delete(
         &mut self,
         partition_id: &TransitionPartitionId,
@@ -928,6 +1285,25 @@ impl ParquetFileRepo for MemTxn {
             .collect())
     }
 
+    async fn sum_l0_file_size_by_partition(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<(PartitionId, i64)>> {
+        let stage = self.stage();
+
+        let mut sizes: HashMap<PartitionId, i64> = HashMap::new();
+        for f in &stage.parquet_files {
+            if partition_ids.contains(&f.partition_id)
+                && f.compaction_level == CompactionLevel::Initial
+                && f.to_delete.is_none()
+            {
+                *sizes.entry(f.partition_id).or_default() += f.file_size_bytes;
+            }
+        }
+
+        Ok(sizes.into_iter().collect())
+    }
+
     async fn create_upgrade_delete(
         &mut self,
         delete: &[ParquetFileId],
@@ -975,6 +1351,29 @@ fn filter_namespace_soft_delete<'a>(
     })
 }
 
+/// Returns true if the data of `file` is older than the retention period of its table or, if the
+/// table has none, of its namespace.
+fn is_retention_expired(
+    namespaces: &[Namespace],
+    tables: &[Table],
+    file: &ParquetFile,
+    now: Timestamp,
+) -> bool {
+    let Some(namespace) = namespaces.iter().find(|n| n.id == file.namespace_id) else {
+        return false;
+    };
+    let namespace_retention_period_ns = namespace.retention_period_ns;
+
+    // table retention, if it exists, overrides namespace retention
+    let rp = tables
+        .iter()
+        .find(|t| t.id == file.table_id)
+        .map(|t| t.effective_retention_period_ns(namespace_retention_period_ns))
+        .unwrap_or(namespace_retention_period_ns);
+
+    matches!(rp, Some(rp) if file.max_time < now - rp)
+}
+
 // The following three functions are helpers to the create_upgrade_delete method.
 // They are also used by the respective create/flag_for_delete/update_compaction_level methods.
 async fn create_parquet_file(
@@ -993,7 +1392,7 @@ async fn create_parquet_file(
 
     let parquet_file = ParquetFile::from_params(
         parquet_file_params,
-        ParquetFileId::new(stage.parquet_files.len() as i64 + 1),
+        ParquetFileId::new(stage.parquet_file_ids.next()),
     );
     let created_at = parquet_file.created_at;
     let partition_id = parquet_file.partition_id;
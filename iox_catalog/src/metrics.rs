@@ -7,10 +7,11 @@ use crate::interface::{
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    Column, ColumnMetadata, ColumnType, CompactionHistory, CompactionHistoryParams,
+    CompactionLevel, Namespace, NamespaceId, NamespaceName,
     NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
-    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction, Table, TableId,
-    Timestamp, TransitionPartitionId,
+    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
+    SkippedCompactionError, Table, TableId, Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, Metric};
@@ -139,8 +140,12 @@ decorate!(
         "namespace_get_by_id" = get_by_id(&mut self, id: NamespaceId, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
         "namespace_get_by_name" = get_by_name(&mut self, name: &str, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
         "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
+        "namespace_undelete" = undelete(&mut self, name: &str) -> Result<Namespace>;
+        "namespace_purge" = purge(&mut self, id: NamespaceId) -> Result<()>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+        "namespace_update_compaction_paused" = update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace>;
+        "namespace_update_write_rate_limits" = update_write_rate_limits(&mut self, name: &str, max_write_lines_per_second: Option<i64>, max_write_bytes_per_second: Option<i64>) -> Result<Namespace>;
     ]
 );
 
@@ -152,6 +157,8 @@ decorate!(
         "table_get_by_namespace_and_name" = get_by_namespace_and_name(&mut self, namespace_id: NamespaceId, name: &str) -> Result<Option<Table>>;
         "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
         "table_list" = list(&mut self) -> Result<Vec<Table>>;
+        "table_update_retention_period" = update_retention_period(&mut self, table_id: TableId, retention_period_ns: Option<i64>) -> Result<Table>;
+        "table_delete" = delete(&mut self, table_id: TableId) -> Result<Table>;
     ]
 );
 
@@ -163,6 +170,8 @@ decorate!(
         "column_list_by_table_id" = list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>>;
         "column_create_or_get_many_unchecked" = create_or_get_many_unchecked(&mut self, table_id: TableId, columns: HashMap<&str, ColumnType>) -> Result<Vec<Column>>;
         "column_list" = list(&mut self) -> Result<Vec<Column>>;
+        "column_update_metadata" = update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata>;
+        "column_list_metadata_by_namespace_id" = list_metadata_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<ColumnMetadata>>;
     ]
 );
 
@@ -180,8 +189,14 @@ decorate!(
         "partition_record_skipped_compaction" = record_skipped_compaction(&mut self, partition_id: PartitionId, reason: &str, num_files: usize, limit_num_files: usize, limit_num_files_first_in_partition: usize, estimated_bytes: u64, limit_bytes: u64) -> Result<()>;
         "partition_list_skipped_compactions" = list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
         "partition_delete_skipped_compactions" = delete_skipped_compactions(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+        "partition_record_skipped_compaction_error" = record_skipped_compaction_error(&mut self, partition_id: PartitionId, error_kind: &str, num_files: usize, total_bytes: u64, num_failures: usize, last_error: &str) -> Result<()>;
+        "partition_list_skipped_compaction_errors" = list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>>;
+        "partition_delete_skipped_compaction_error" = delete_skipped_compaction_error(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompactionError>>;
+        "partition_record_compaction_history" = record_compaction_history(&mut self, params: CompactionHistoryParams) -> Result<CompactionHistory>;
+        "partition_list_compaction_history" = list_compaction_history(&mut self, partition_id: PartitionId) -> Result<Vec<CompactionHistory>>;
         "partition_most_recent_n" = most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
         "partition_partitions_new_file_between" = partitions_new_file_between(&mut self, minimum_time: Timestamp, maximum_time: Option<Timestamp>) -> Result<Vec<PartitionId>>;
+        "partition_partitions_needing_cold_compact" = partitions_needing_cold_compact(&mut self, maximum_time: Timestamp, after: Option<PartitionId>, n: usize) -> Result<Vec<PartitionId>>;
         "partition_get_in_skipped_compaction" = get_in_skipped_compaction(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
     ]
 );
@@ -190,14 +205,18 @@ decorate!(
     impl_trait = ParquetFileRepo,
     methods = [
         "parquet_create" = create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
+        "parquet_create_many" = create_many(&mut self, parquet_file_params: Vec<ParquetFileParams>) -> Result<Vec<ParquetFile>>;
         "parquet_list_all" = list_all(&mut self) -> Result<Vec<ParquetFile>>;
         "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
+        "parquet_list_to_flag_by_retention" = list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_table_not_to_delete" = list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
         "parquet_delete_old_ids_only" = delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>>;
+        "parquet_list_old" = list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
         "parquet_list_by_partition_not_to_delete" = list_by_partition_not_to_delete(&mut self, partition_id: &TransitionPartitionId) -> Result<Vec<ParquetFile>>;
         "parquet_get_by_object_store_id" = get_by_object_store_id(&mut self, object_store_id: Uuid) -> Result<Option<ParquetFile>>;
         "parquet_exists_by_object_store_id_batch" = exists_by_object_store_id_batch(&mut self, object_store_ids: Vec<Uuid>) -> Result<Vec<Uuid>>;
+        "parquet_sum_l0_file_size_by_partition" = sum_l0_file_size_by_partition(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<(PartitionId, i64)>>;
         "parquet_create_upgrade_delete" = create_upgrade_delete(&mut self, delete: &[ParquetFileId], upgrade: &[ParquetFileId], create: &[ParquetFileParams], target_level: CompactionLevel) -> Result<Vec<ParquetFileId>>;
     ]
 );
@@ -13,37 +13,83 @@ use crate::{
     },
     metrics::MetricDecorator,
     migrate::IOxMigrator,
+    retry::RetryDecorator,
     DEFAULT_MAX_COLUMNS_PER_TABLE, DEFAULT_MAX_TABLES,
 };
 use async_trait::async_trait;
+use backoff::BackoffConfig;
 use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnType, CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    Column, ColumnMetadata, ColumnType, CompactionHistory, CompactionHistoryParams,
+    CompactionLevel, Namespace, NamespaceId, NamespaceName,
     NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
-    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction, Table, TableId,
-    Timestamp, TransitionPartitionId,
+    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
+    SkippedCompactionError, Table, TableId, Timestamp, TransitionPartitionId,
 };
 use iox_time::{SystemProvider, TimeProvider};
-use metric::{Attributes, Instrument, MetricKind};
+use metric::{Attributes, Instrument, MetricKind, U64Counter};
 use observability_deps::tracing::{debug, info, warn};
-use parking_lot::{RwLock, RwLockWriteGuard};
+use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
 use snafu::prelude::*;
 use sqlx::{
     migrate::Migrator,
     postgres::{PgConnectOptions, PgPoolOptions},
     types::Uuid,
-    Acquire, ConnectOptions, Executor, Postgres, Row,
+    Acquire, ConnectOptions, Executor, PgConnection, Postgres, QueryBuilder, Row,
 };
 use sqlx_hotswap_pool::HotSwapPool;
 use std::borrow::Cow;
 use std::collections::{BTreeMap, HashSet};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc, time::Duration};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 static MIGRATOR: Migrator = sqlx::migrate!();
 
+/// The duration of time a read replica that failed to serve a query because
+/// it could not be reached is not used, before reads are attempted against it
+/// again.
+const REPLICA_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Evaluate the read-only query `$query` against the next available read
+/// replica (bound to `$executor`), if any, falling back to the primary if no
+/// replica is available or the replica cannot be reached.
+///
+/// `$query` is evaluated at most twice, and MUST NOT modify the catalog.
+macro_rules! read_replica {
+    ($self:ident, |$executor:ident| $query:expr) => {{
+        let replicas = Arc::clone(&$self.inner.replicas);
+        match replicas.next() {
+            Some((idx, pool)) => {
+                let $executor = pool;
+                match $query {
+                    Err(e) if is_connection_error(&e) => {
+                        warn!(
+                            error=%e,
+                            "catalog read replica unavailable, reading from primary"
+                        );
+                        replicas.mark_unavailable(idx);
+                        let $executor = &mut $self.inner;
+                        $query
+                    }
+                    res => res,
+                }
+            }
+            None => {
+                let $executor = &mut $self.inner;
+                $query
+            }
+        }
+    }};
+}
+
 /// Postgres connection options.
 #[derive(Debug, Clone)]
 pub struct PostgresConnectionOptions {
@@ -72,6 +118,23 @@ pub struct PostgresConnectionOptions {
     ///
     /// If an update is encountered, the underlying connection pool will be hot-swapped.
     pub hotswap_poll_interval: Duration,
+
+    /// DSNs of read replicas of the catalog database.
+    ///
+    /// Read-only schema lookups and parquet file listings are spread across
+    /// these replicas, falling back to the primary [`dsn`](Self::dsn) when no
+    /// replica can be reached. Reads from a replica may observe stale data
+    /// due to replication lag.
+    ///
+    /// A replica that cannot be connected to at startup is not used.
+    pub replica_dsns: Vec<String>,
+
+    /// Backoff used to retry catalog calls that fail with a transient error,
+    /// such as a serialization failure or a lost connection.
+    ///
+    /// The [`deadline`](BackoffConfig::deadline) bounds the total time spent
+    /// backing off, after which the last error is returned to the caller.
+    pub retry_backoff: BackoffConfig,
 }
 
 impl PostgresConnectionOptions {
@@ -89,6 +152,20 @@ impl PostgresConnectionOptions {
 
     /// Default value for [`hotswap_poll_interval`](Self::hotswap_poll_interval).
     pub const DEFAULT_HOTSWAP_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Default value for the [`deadline`](BackoffConfig::deadline) of
+    /// [`retry_backoff`](Self::retry_backoff).
+    pub const DEFAULT_RETRY_DEADLINE: Duration = Duration::from_secs(10);
+
+    /// Default value for [`retry_backoff`](Self::retry_backoff).
+    pub fn default_retry_backoff() -> BackoffConfig {
+        BackoffConfig {
+            init_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(2),
+            base: 2.,
+            deadline: Some(Self::DEFAULT_RETRY_DEADLINE),
+        }
+    }
 }
 
 impl Default for PostgresConnectionOptions {
@@ -101,6 +178,8 @@ impl Default for PostgresConnectionOptions {
             connect_timeout: Self::DEFAULT_CONNECT_TIMEOUT,
             idle_timeout: Self::DEFAULT_IDLE_TIMEOUT,
             hotswap_poll_interval: Self::DEFAULT_HOTSWAP_POLL_INTERVAL,
+            replica_dsns: vec![],
+            retry_backoff: Self::default_retry_backoff(),
         }
     }
 }
@@ -110,6 +189,7 @@ impl Default for PostgresConnectionOptions {
 pub struct PostgresCatalog {
     metrics: Arc<metric::Registry>,
     pool: HotSwapPool<Postgres>,
+    replicas: Arc<ReadReplicas>,
     time_provider: Arc<dyn TimeProvider>,
     // Connection options for display
     options: PostgresConnectionOptions,
@@ -125,8 +205,28 @@ impl PostgresCatalog {
             .await
             .map_err(|e| Error::SqlxError { source: e })?;
 
+        let mut replica_pools = Vec::with_capacity(options.replica_dsns.len());
+        for (idx, dsn) in options.replica_dsns.iter().enumerate() {
+            let replica_options = PostgresConnectionOptions {
+                dsn: dsn.clone(),
+                replica_dsns: vec![],
+                ..options.clone()
+            };
+            // Do not log the dsn as it may contain credentials.
+            match new_pool(&replica_options, Arc::clone(&metrics)).await {
+                Ok(p) => replica_pools.push(p),
+                Err(e) => warn!(
+                    error=%e,
+                    replica=idx,
+                    "failed to connect to catalog read replica, not using it"
+                ),
+            }
+        }
+        let replicas = Arc::new(ReadReplicas::new(replica_pools, &metrics));
+
         Ok(Self {
             pool,
+            replicas,
             metrics,
             time_provider: Arc::new(SystemProvider::new()),
             options,
@@ -165,6 +265,99 @@ pub struct PostgresTxn {
 #[derive(Debug)]
 struct PostgresTxnInner {
     pool: HotSwapPool<Postgres>,
+    replicas: Arc<ReadReplicas>,
+}
+
+/// A set of read replicas that read-only catalog queries are spread across in
+/// round-robin order.
+///
+/// A replica that fails to serve a query because it cannot be reached is not
+/// used for [`REPLICA_RETRY_INTERVAL`], during which reads are served by the
+/// other replicas, or by the primary if no replica is available.
+#[derive(Debug)]
+struct ReadReplicas<P = HotSwapPool<Postgres>> {
+    /// The replica pools, and the time until which each is unavailable, if
+    /// any.
+    replicas: Vec<(P, Mutex<Option<Instant>>)>,
+
+    /// The round-robin cursor.
+    next: AtomicUsize,
+
+    /// The number of reads that failed against a replica and were retried
+    /// against the primary.
+    fallback_count: U64Counter,
+}
+
+impl<P> ReadReplicas<P> {
+    fn new(pools: Vec<P>, metrics: &metric::Registry) -> Self {
+        let fallback_count = metrics
+            .register_metric::<U64Counter>(
+                "iox_catalog_postgres_replica_fallback",
+                "number of catalog reads that could not be served by a read replica \
+                and were retried against the primary",
+            )
+            .recorder(&[]);
+
+        Self {
+            replicas: pools.into_iter().map(|p| (p, Mutex::new(None))).collect(),
+            next: AtomicUsize::new(0),
+            fallback_count,
+        }
+    }
+
+    /// Return the index and pool of the next available replica, or [`None`]
+    /// if no replica is available.
+    fn next(&self) -> Option<(usize, &P)> {
+        if self.replicas.is_empty() {
+            return None;
+        }
+
+        let now = Instant::now();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        (0..self.replicas.len())
+            .map(|offset| start.wrapping_add(offset) % self.replicas.len())
+            .find(|idx| {
+                let mut unavailable_until = self.replicas[*idx].1.lock();
+                match *unavailable_until {
+                    Some(t) if t > now => false,
+                    Some(_) => {
+                        // The retry interval has elapsed - fail back to this
+                        // replica.
+                        info!(replica = *idx, "retrying catalog read replica");
+                        *unavailable_until = None;
+                        true
+                    }
+                    None => true,
+                }
+            })
+            .map(|idx| (idx, &self.replicas[idx].0))
+    }
+
+    /// Mark the replica at `idx` as unavailable for
+    /// [`REPLICA_RETRY_INTERVAL`].
+    fn mark_unavailable(&self, idx: usize) {
+        *self.replicas[idx].1.lock() = Some(Instant::now() + REPLICA_RETRY_INTERVAL);
+        self.fallback_count.inc(1);
+    }
+}
+
+/// Returns true if `e` indicates the database could not be reached, or the
+/// connection to it was lost, rather than a failure of the query itself.
+pub(crate) fn is_connection_error(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        // Class 08 (connection exception) and 57P (operator intervention,
+        // such as the server shutting down).
+        sqlx::Error::Database(e) => e
+            .code()
+            .map(|c| c.starts_with("08") || c.starts_with("57P"))
+            .unwrap_or_default(),
+        _ => false,
+    }
 }
 
 impl<'c> Executor<'c> for &'c mut PostgresTxnInner {
@@ -304,14 +497,18 @@ DO NOTHING;
     }
 
     async fn repositories(&self) -> Box<dyn RepoCollection> {
-        Box::new(MetricDecorator::new(
-            PostgresTxn {
-                inner: PostgresTxnInner {
-                    pool: self.pool.clone(),
+        Box::new(RetryDecorator::new(
+            MetricDecorator::new(
+                PostgresTxn {
+                    inner: PostgresTxnInner {
+                        pool: self.pool.clone(),
+                        replicas: Arc::clone(&self.replicas),
+                    },
+                    time_provider: Arc::clone(&self.time_provider),
                 },
-                time_provider: Arc::clone(&self.time_provider),
-            },
-            Arc::clone(&self.metrics),
+                Arc::clone(&self.metrics),
+            ),
+            self.options.retry_backoff.clone(),
         ))
     }
 
@@ -382,21 +579,21 @@ impl PoolMetrics {
     /// Acquire connection.
     fn acquire(&self, id: &str) {
         let pools = self.state.pools.read();
-        let Some(p) = pools.get(id) else {return};
+        let Some(p) = pools.get(id) else { return };
         p.used.fetch_add(1, Ordering::SeqCst);
     }
 
     /// Release connection.
     fn release(&self, id: &str) {
         let pools = self.state.pools.read();
-        let Some(p) = pools.get(id) else {return};
+        let Some(p) = pools.get(id) else { return };
         p.used.fetch_sub(1, Ordering::SeqCst);
     }
 
     /// Register a new pool.
     fn register_pool(&self, id: &str, pool: sqlx::Pool<Postgres>) {
         let pools = self.state.pools.read();
-        let Some(p) = pools.get(id) else {return};
+        let Some(p) = pools.get(id) else { return };
         let mut p = p.pool.write();
         assert!(p.is_none(), "Pool with same ID already known");
         *p = Some(pool);
@@ -435,7 +632,9 @@ impl Instrument for PoolMetrics {
         );
         for (id, pool) in pools.iter() {
             let p = pool.pool.read();
-            let Some(p) = p.as_ref() else {continue;};
+            let Some(p) = p.as_ref() else {
+                continue;
+            };
 
             reporter.report_observation(
                 &Attributes::from([
@@ -747,6 +946,57 @@ RETURNING *;
     Ok(rec)
 }
 
+/// Statements removing everything attached to the namespace `$1` from the catalog, in dependency
+/// order, followed by the namespace itself.
+const PURGE_NAMESPACE_QUERIES: &[&str] = &[
+    r#"DELETE FROM parquet_file WHERE namespace_id = $1;"#,
+    r#"
+DELETE FROM skipped_compactions
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"
+DELETE FROM skipped_compaction_errors
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"
+DELETE FROM compaction_history
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"DELETE FROM partition WHERE table_id IN (SELECT id FROM table_name WHERE namespace_id = $1);"#,
+    r#"
+DELETE FROM column_metadata
+WHERE column_id IN (
+    SELECT column_name.id FROM column_name
+    JOIN table_name ON table_name.id = column_name.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"DELETE FROM column_name WHERE table_id IN (SELECT id FROM table_name WHERE namespace_id = $1);"#,
+    r#"DELETE FROM table_name WHERE namespace_id = $1;"#,
+    r#"DELETE FROM namespace WHERE id = $1;"#,
+];
+
+const DELETE_TABLE_QUERIES: &[&str] = &[
+    r#"
+DELETE FROM column_metadata
+WHERE column_id IN (SELECT id FROM column_name WHERE table_id = $1);
+    "#,
+    r#"DELETE FROM column_name WHERE table_id = $1;"#,
+    r#"DELETE FROM table_name WHERE id = $1;"#,
+];
+
 #[async_trait]
 impl NamespaceRepo for PostgresTxn {
     async fn create(
@@ -766,7 +1016,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -793,20 +1044,23 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
     }
 
     async fn list(&mut self, deleted: SoftDeletedRows) -> Result<Vec<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
-            format!(
-                r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Namespace>(
+                format!(
+                    r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE {v};
                 "#,
-                v = deleted.as_sql_predicate()
+                    v = deleted.as_sql_predicate()
+                )
+                .as_str(),
             )
-            .as_str(),
-        )
-        .fetch_all(&mut self.inner)
-        .await
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
@@ -817,21 +1071,24 @@ WHERE {v};
         id: NamespaceId,
         deleted: SoftDeletedRows,
     ) -> Result<Option<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
-            format!(
-                r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Namespace>(
+                format!(
+                    r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
-                v = deleted.as_sql_predicate()
+                    v = deleted.as_sql_predicate()
+                )
+                .as_str(),
             )
-            .as_str(),
-        )
-        .bind(id) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+            .bind(id) // $1
+            .fetch_one(executor)
+            .await
+        });
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -847,21 +1104,24 @@ WHERE id=$1 AND {v};
         name: &str,
         deleted: SoftDeletedRows,
     ) -> Result<Option<Namespace>> {
-        let rec = sqlx::query_as::<_, Namespace>(
-            format!(
-                r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Namespace>(
+                format!(
+                    r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
-                v = deleted.as_sql_predicate()
+                    v = deleted.as_sql_predicate()
+                )
+                .as_str(),
             )
-            .as_str(),
-        )
-        .bind(name) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+            .bind(name) // $1
+            .fetch_one(executor)
+            .await
+        });
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -885,6 +1145,68 @@ WHERE name=$1 AND {v};
             .map(|_| ())
     }
 
+    async fn undelete(&mut self, name: &str) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = NULL
+WHERE name = $1 AND deleted_at IS NOT NULL
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(name) // $1
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn purge(&mut self, id: NamespaceId) -> Result<()> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let deleted_at = sqlx::query_scalar::<_, Option<Timestamp>>(
+            r#"SELECT deleted_at FROM namespace WHERE id = $1 FOR UPDATE;"#,
+        )
+        .bind(id) // $1
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        match deleted_at {
+            None => return Err(Error::NamespaceNotFoundById { id }),
+            Some(None) => return Err(Error::NamespaceNotSoftDeleted { id }),
+            Some(Some(_)) => {}
+        }
+
+        for query in PURGE_NAMESPACE_QUERIES {
+            sqlx::query(query)
+                .bind(id) // $1
+                .execute(&mut *tx)
+                .await
+                .context(interface::CouldNotDeleteNamespaceSnafu)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(())
+    }
+
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let rec = sqlx::query_as::<_, Namespace>(
             r#"
@@ -892,7 +1214,8 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
         "#,
         )
         .bind(new_max)
@@ -917,7 +1240,8 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
         "#,
         )
         .bind(new_max)
@@ -935,6 +1259,64 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
         Ok(namespace)
     }
 
+    async fn update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET compaction_paused = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(paused)
+        .bind(name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_write_rate_limits(
+        &mut self,
+        name: &str,
+        max_write_lines_per_second: Option<i64>,
+        max_write_bytes_per_second: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_write_lines_per_second = $1, max_write_bytes_per_second = $2
+WHERE name = $3
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(max_write_lines_per_second) // $1
+        .bind(max_write_bytes_per_second) // $2
+        .bind(name) // $3
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
     async fn update_retention_period(
         &mut self,
         name: &str,
@@ -946,7 +1328,8 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
         "#,
         )
         .bind(retention_period_ns) // $1
@@ -1044,16 +1427,18 @@ RETURNING *;
     }
 
     async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
-            r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Table>(
+                r#"
 SELECT *
 FROM table_name
 WHERE id = $1;
             "#,
-        )
-        .bind(table_id) // $1
-        .fetch_one(&mut self.inner)
-        .await;
+            )
+            .bind(table_id) // $1
+            .fetch_one(executor)
+            .await
+        });
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -1069,17 +1454,19 @@ WHERE id = $1;
         namespace_id: NamespaceId,
         name: &str,
     ) -> Result<Option<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
-            r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Table>(
+                r#"
 SELECT *
 FROM table_name
 WHERE namespace_id = $1 AND name = $2;
             "#,
-        )
-        .bind(namespace_id) // $1
-        .bind(name) // $2
-        .fetch_one(&mut self.inner)
-        .await;
+            )
+            .bind(namespace_id) // $1
+            .bind(name) // $2
+            .fetch_one(executor)
+            .await
+        });
 
         if let Err(sqlx::Error::RowNotFound) = rec {
             return Ok(None);
@@ -1091,29 +1478,103 @@ WHERE namespace_id = $1 AND name = $2;
     }
 
     async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>(
-            r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Table>(
+                r#"
 SELECT *
 FROM table_name
 WHERE namespace_id = $1;
             "#,
-        )
-        .bind(namespace_id)
-        .fetch_all(&mut self.inner)
-        .await
+            )
+            .bind(namespace_id)
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list(&mut self) -> Result<Vec<Table>> {
-        let rec = sqlx::query_as::<_, Table>("SELECT * FROM table_name;")
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })?;
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Table>("SELECT * FROM table_name;")
+                .fetch_all(executor)
+                .await
+        })
+        .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(table)
+    }
+
+    async fn delete(&mut self, table_id: TableId) -> Result<Table> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        // Lock the table row, creating a partition needs a key share lock on it, so that no
+        // partition can be created concurrently.
+        let table =
+            sqlx::query_as::<_, Table>(r#"SELECT * FROM table_name WHERE id = $1 FOR UPDATE;"#)
+                .bind(table_id) // $1
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(|e| Error::SqlxError { source: e })?
+                .ok_or(Error::TableNotFound { id: table_id })?;
+
+        let has_partitions = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS (SELECT 1 FROM partition WHERE table_id = $1);"#,
+        )
+        .bind(table_id) // $1
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+        if has_partitions {
+            return Err(Error::TableNotEmpty { id: table_id });
+        }
+
+        for query in DELETE_TABLE_QUERIES {
+            sqlx::query(query)
+                .bind(table_id) // $1
+                .execute(&mut *tx)
+                .await
+                .context(interface::CouldNotDeleteTableSnafu)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(table)
+    }
 }
 
 #[async_trait]
@@ -1128,41 +1589,47 @@ impl ColumnRepo for PostgresTxn {
     }
 
     async fn list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>(
-            r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Column>(
+                r#"
 SELECT column_name.* FROM table_name
 INNER JOIN column_name on column_name.table_id = table_name.id
 WHERE table_name.namespace_id = $1;
             "#,
-        )
-        .bind(namespace_id)
-        .fetch_all(&mut self.inner)
-        .await
+            )
+            .bind(namespace_id)
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>(
-            r#"
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Column>(
+                r#"
 SELECT * FROM column_name
-WHERE table_id = $1;
-            "#,
-        )
-        .bind(table_id)
-        .fetch_all(&mut self.inner)
-        .await
+WHERE table_id = $1;
+            "#,
+            )
+            .bind(table_id)
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
 
     async fn list(&mut self) -> Result<Vec<Column>> {
-        let rec = sqlx::query_as::<_, Column>("SELECT * FROM column_name;")
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })?;
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, Column>("SELECT * FROM column_name;")
+                .fetch_all(executor)
+                .await
+        })
+        .map_err(|e| Error::SqlxError { source: e })?;
 
         Ok(rec)
     }
@@ -1224,6 +1691,59 @@ RETURNING *;
 
         Ok(out)
     }
+
+    async fn update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata> {
+        let column_id = metadata.column_id;
+        let rec = sqlx::query_as::<_, ColumnMetadata>(
+            r#"
+INSERT INTO column_metadata ( column_id, description, unit, semantic_type )
+SELECT $1, $2, $3, $4
+WHERE EXISTS (SELECT 1 FROM column_name WHERE id = $1)
+ON CONFLICT (column_id)
+DO UPDATE SET
+    description = excluded.description,
+    unit = excluded.unit,
+    semantic_type = excluded.semantic_type
+RETURNING *;
+        "#,
+        )
+        .bind(column_id) // $1
+        .bind(metadata.description) // $2
+        .bind(metadata.unit) // $3
+        .bind(metadata.semantic_type) // $4
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let metadata = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::ColumnNotFound { id: column_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(metadata)
+    }
+
+    async fn list_metadata_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnMetadata>> {
+        let rec = read_replica!(self, |executor| {
+            sqlx::query_as::<_, ColumnMetadata>(
+                r#"
+SELECT column_metadata.*
+FROM column_metadata
+JOIN column_name ON column_name.id = column_metadata.column_id
+JOIN table_name ON table_name.id = column_name.table_id
+WHERE table_name.namespace_id = $1;
+            "#,
+            )
+            .bind(namespace_id) // $1
+            .fetch_all(executor)
+            .await
+        })
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
 }
 
 #[async_trait]
@@ -1536,6 +2056,119 @@ RETURNING *
         .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
     }
 
+    async fn record_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+        error_kind: &str,
+        num_files: usize,
+        total_bytes: u64,
+        num_failures: usize,
+        last_error: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO skipped_compaction_errors
+    ( partition_id, error_kind, num_files, total_bytes, num_failures, last_error, skipped_at )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, extract(epoch from NOW()) )
+ON CONFLICT ( partition_id )
+DO UPDATE
+SET
+error_kind = EXCLUDED.error_kind,
+num_files = EXCLUDED.num_files,
+total_bytes = EXCLUDED.total_bytes,
+num_failures = EXCLUDED.num_failures,
+last_error = EXCLUDED.last_error,
+skipped_at = EXCLUDED.skipped_at;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(error_kind)
+        .bind(num_files as i64)
+        .bind(total_bytes as i64)
+        .bind(num_failures as i64)
+        .bind(last_error)
+        .execute(&mut self.inner)
+        .await
+        .context(interface::CouldNotRecordSkippedCompactionSnafu { partition_id })?;
+        Ok(())
+    }
+
+    async fn list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>> {
+        sqlx::query_as::<_, SkippedCompactionError>(
+            r#"
+SELECT * FROM skipped_compaction_errors
+        "#,
+        )
+        .fetch_all(&mut self.inner)
+        .await
+        .context(interface::CouldNotListSkippedCompactionsSnafu)
+    }
+
+    async fn delete_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompactionError>> {
+        sqlx::query_as::<_, SkippedCompactionError>(
+            r#"
+DELETE FROM skipped_compaction_errors
+WHERE partition_id = $1
+RETURNING *
+        "#,
+        )
+        .bind(partition_id)
+        .fetch_optional(&mut self.inner)
+        .await
+        .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
+    }
+
+    async fn record_compaction_history(
+        &mut self,
+        params: CompactionHistoryParams,
+    ) -> Result<CompactionHistory> {
+        let partition_id = params.partition_id;
+        sqlx::query_as::<_, CompactionHistory>(
+            r#"
+INSERT INTO compaction_history
+    ( partition_id, job_id, num_input_files, input_bytes, num_upgraded_files, num_output_files,
+      output_bytes, target_level, duration_ms, committed_at )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10 )
+RETURNING *;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(params.job_id) // $2
+        .bind(params.num_input_files) // $3
+        .bind(params.input_bytes) // $4
+        .bind(params.num_upgraded_files) // $5
+        .bind(params.num_output_files) // $6
+        .bind(params.output_bytes) // $7
+        .bind(params.target_level) // $8
+        .bind(params.duration_ms) // $9
+        .bind(params.committed_at) // $10
+        .fetch_one(&mut self.inner)
+        .await
+        .context(interface::CouldNotRecordCompactionHistorySnafu { partition_id })
+    }
+
+    async fn list_compaction_history(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Vec<CompactionHistory>> {
+        sqlx::query_as::<_, CompactionHistory>(
+            r#"
+SELECT * FROM compaction_history
+WHERE partition_id = $1
+ORDER BY id;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .fetch_all(&mut self.inner)
+        .await
+        .context(interface::CouldNotListCompactionHistorySnafu)
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         sqlx::query_as(
             r#"
@@ -1574,6 +2207,38 @@ LIMIT $1;"#,
             .await
             .map_err(|e| Error::SqlxError { source: e })
     }
+
+    async fn partitions_needing_cold_compact(
+        &mut self,
+        maximum_time: Timestamp,
+        after: Option<PartitionId>,
+        n: usize,
+    ) -> Result<Vec<PartitionId>> {
+        sqlx::query_as(
+            r#"
+SELECT p.id as partition_id
+FROM partition p
+WHERE p.new_file_at < $1
+  AND p.id > $2
+  AND EXISTS (
+    SELECT 1
+    FROM parquet_file f
+    WHERE f.partition_id = p.id
+      AND f.to_delete IS NULL
+      AND f.compaction_level < $3
+  )
+ORDER BY p.id
+LIMIT $4;
+            "#,
+        )
+        .bind(maximum_time) // $1
+        .bind(after.map(|id| id.get()).unwrap_or(i64::MIN)) // $2
+        .bind(CompactionLevel::Final) // $3
+        .bind(n as i64) // $4
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
 }
 
 #[async_trait]
@@ -1584,6 +2249,30 @@ impl ParquetFileRepo for PostgresTxn {
         Ok(ParquetFile::from_params(parquet_file_params, id))
     }
 
+    async fn create_many(
+        &mut self,
+        parquet_file_params: Vec<ParquetFileParams>,
+    ) -> Result<Vec<ParquetFile>> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let ids = create_parquet_files(&mut tx, &parquet_file_params).await?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(parquet_file_params
+            .into_iter()
+            .zip(ids)
+            .map(|(params, id)| ParquetFile::from_params(params, id))
+            .collect())
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         sqlx::query_as::<_, ParquetFile>(
             r#"
@@ -1591,7 +2280,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
 FROM parquet_file;
              "#,
         )
@@ -1602,16 +2292,18 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // table retention, if it exists, overrides namespace retention
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time
+        < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1630,42 +2322,77 @@ RETURNING id;
         Ok(flagged)
     }
 
+    async fn list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>> {
+        let now = Timestamp::from(self.time_provider.now());
+        // same selection as `flag_for_delete_by_retention`, without the update
+        read_replica!(self, |executor| {
+            sqlx::query_as::<_, ParquetFile>(
+                r#"
+SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
+       parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
+       parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
+       parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
+FROM namespace, table_name, parquet_file
+WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
+AND parquet_file.to_delete IS NULL
+AND parquet_file.max_time
+    < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
+AND namespace.id = parquet_file.namespace_id
+AND table_name.id = parquet_file.table_id
+LIMIT $2;
+             "#,
+            )
+            .bind(now) // $1
+            .bind(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION) // $2
+            .fetch_all(executor)
+            .await
+        })
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn list_by_namespace_not_to_delete(
         &mut self,
         namespace_id: NamespaceId,
     ) -> Result<Vec<ParquetFile>> {
-        sqlx::query_as::<_, ParquetFile>(
-            r#"
+        read_replica!(self, |executor| {
+            sqlx::query_as::<_, ParquetFile>(
+                r#"
 SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
   AND parquet_file.to_delete IS NULL;
              "#,
-        )
-        .bind(namespace_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+            )
+            .bind(namespace_id) // $1
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>> {
-        sqlx::query_as::<_, ParquetFile>(
-            r#"
+        read_replica!(self, |executor| {
+            sqlx::query_as::<_, ParquetFile>(
+                r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
-       column_set, max_l0_created_at
+       column_set, max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
-        )
-        .bind(table_id) // $1
-        .fetch_all(&mut self.inner)
-        .await
+            )
+            .bind(table_id) // $1
+            .fetch_all(executor)
+            .await
+        })
         .map_err(|e| Error::SqlxError { source: e })
     }
 
@@ -1694,40 +2421,61 @@ RETURNING id;
         Ok(deleted)
     }
 
+    async fn list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>> {
+        // same selection as `delete_old_ids_only`, without the delete
+        read_replica!(self, |executor| {
+            sqlx::query_as::<_, ParquetFile>(
+                r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
+       min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
+       column_set, max_l0_created_at, tag_ranges
+FROM parquet_file
+WHERE to_delete < $1
+LIMIT $2;
+             "#,
+            )
+            .bind(older_than) // $1
+            .bind(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_DELETE) // $2
+            .fetch_all(executor)
+            .await
+        })
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn list_by_partition_not_to_delete(
         &mut self,
         partition_id: &TransitionPartitionId,
     ) -> Result<Vec<ParquetFile>> {
-        // This `match` will go away when all partitions have hash IDs in the database.
-        let query = match partition_id {
-            TransitionPartitionId::Deterministic(hash_id) => sqlx::query_as::<_, ParquetFile>(
-                r#"
+        read_replica!(self, |executor| {
+            // This `match` will go away when all partitions have hash IDs in the database.
+            let query = match partition_id {
+                TransitionPartitionId::Deterministic(hash_id) => sqlx::query_as::<_, ParquetFile>(
+                    r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE parquet_file.partition_hash_id = $1
   AND parquet_file.to_delete IS NULL;
         "#,
-            )
-            .bind(hash_id), // $1
-            TransitionPartitionId::Deprecated(id) => sqlx::query_as::<_, ParquetFile>(
-                r#"
+                )
+                .bind(hash_id), // $1
+                TransitionPartitionId::Deprecated(id) => sqlx::query_as::<_, ParquetFile>(
+                    r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE parquet_file.partition_id = $1
   AND parquet_file.to_delete IS NULL;
         "#,
-            )
-            .bind(id), // $1
-        };
+                )
+                .bind(id), // $1
+            };
 
-        query
-            .fetch_all(&mut self.inner)
-            .await
-            .map_err(|e| Error::SqlxError { source: e })
+            query.fetch_all(executor).await
+        })
+        .map_err(|e| Error::SqlxError { source: e })
     }
 
     async fn get_by_object_store_id(
@@ -1738,7 +2486,7 @@ WHERE parquet_file.partition_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1776,6 +2524,29 @@ WHERE object_store_id = ANY($1);
         .map_err(|e| Error::SqlxError { source: e })
     }
 
+    async fn sum_l0_file_size_by_partition(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<(PartitionId, i64)>> {
+        let ids: Vec<_> = partition_ids.iter().map(|p| p.get()).collect();
+
+        sqlx::query_as(
+            r#"
+SELECT partition_id, SUM(file_size_bytes)::BIGINT AS file_size_bytes
+FROM parquet_file
+WHERE partition_id = ANY($1)
+  AND compaction_level = $2
+  AND to_delete IS NULL
+GROUP BY partition_id;
+            "#,
+        )
+        .bind(&ids[..]) // $1
+        .bind(CompactionLevel::Initial) // $2
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn create_upgrade_delete(
         &mut self,
         delete: &[ParquetFileId],
@@ -1803,11 +2574,7 @@ WHERE object_store_id = ANY($1);
 
         update_compaction_level(&mut *tx, upgrade, target_level).await?;
 
-        let mut ids = Vec::with_capacity(create.len());
-        for file in create {
-            let id = create_parquet_file(&mut *tx, file).await?;
-            ids.push(id);
-        }
+        let ids = create_parquet_files(&mut tx, create).await?;
 
         tx.commit()
             .await
@@ -1816,8 +2583,9 @@ WHERE object_store_id = ANY($1);
     }
 }
 
-// The following three functions are helpers to the create_upgrade_delete method.
-// They are also used by the respective create/flag_for_delete/update_compaction_level methods.
+// The following functions are helpers to the create_upgrade_delete method.
+// They are also used by the respective create/create_many/flag_for_delete/update_compaction_level
+// methods.
 async fn create_parquet_file<'q, E>(
     executor: E,
     parquet_file_params: &ParquetFileParams,
@@ -1839,6 +2607,7 @@ where
         created_at,
         column_set,
         max_l0_created_at,
+        tag_ranges,
     } = parquet_file_params;
 
     let partition_hash_id_ref = &partition_hash_id.as_ref();
@@ -1847,8 +2616,9 @@ where
 INSERT INTO parquet_file (
     shard_id, table_id, partition_id, partition_hash_id, object_store_id,
     min_time, max_time, file_size_bytes,
-    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at )
-VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 )
+    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at,
+    tag_ranges )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15 )
 RETURNING id;
         "#,
     )
@@ -1865,7 +2635,8 @@ RETURNING id;
     .bind(created_at) // $11
     .bind(namespace_id) // $12
     .bind(column_set) // $13
-    .bind(max_l0_created_at); // $14
+    .bind(max_l0_created_at) // $14
+    .bind(tag_ranges); // $15
 
     let parquet_file_id = query.fetch_one(executor).await.map_err(|e| {
         if is_unique_violation(&e) {
@@ -1882,6 +2653,84 @@ RETURNING id;
     Ok(parquet_file_id)
 }
 
+/// The maximum number of parquet files inserted by a single statement in
+/// [`create_parquet_files()`], keeping the number of bind parameters (15 per
+/// file) well below the Postgres limit of 65535.
+const MAX_PARQUET_FILES_PER_INSERT: usize = 1_000;
+
+/// Insert all of `parquet_file_params` using multi-row `INSERT` statements,
+/// returning the IDs of the created files in the order of
+/// `parquet_file_params`.
+///
+/// If a file with the same object store ID already exists, or is given more
+/// than once, [`Error::FileExists`] is returned - callers MUST execute this
+/// within a transaction that is rolled back on error.
+async fn create_parquet_files(
+    conn: &mut PgConnection,
+    parquet_file_params: &[ParquetFileParams],
+) -> Result<Vec<ParquetFileId>> {
+    let mut ids = Vec::with_capacity(parquet_file_params.len());
+
+    for chunk in parquet_file_params.chunks(MAX_PARQUET_FILES_PER_INSERT) {
+        let mut query = QueryBuilder::<Postgres>::new(
+            r#"
+INSERT INTO parquet_file (
+    shard_id, table_id, partition_id, partition_hash_id, object_store_id,
+    min_time, max_time, file_size_bytes,
+    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at,
+    tag_ranges ) "#,
+        );
+        query.push_values(chunk, |mut row, f| {
+            row.push_bind(TRANSITION_SHARD_ID)
+                .push_bind(f.table_id)
+                .push_bind(f.partition_id)
+                .push_bind(f.partition_hash_id.as_ref())
+                .push_bind(f.object_store_id)
+                .push_bind(f.min_time)
+                .push_bind(f.max_time)
+                .push_bind(f.file_size_bytes)
+                .push_bind(f.row_count)
+                .push_bind(f.compaction_level)
+                .push_bind(f.created_at)
+                .push_bind(f.namespace_id)
+                .push_bind(&f.column_set)
+                .push_bind(f.max_l0_created_at)
+                .push_bind(f.tag_ranges.as_ref());
+        });
+        // Files that already exist are skipped rather than failing the
+        // statement, so that the conflicting object store ID can be reported.
+        query.push(" ON CONFLICT (object_store_id) DO NOTHING RETURNING id, object_store_id;");
+
+        let mut created = query
+            .build_query_as::<(ParquetFileId, Uuid)>()
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(|e| {
+                if is_fk_violation(&e) {
+                    Error::ForeignKeyViolation { source: e }
+                } else {
+                    Error::SqlxError { source: e }
+                }
+            })?
+            .into_iter()
+            .map(|(id, object_store_id)| (object_store_id, id))
+            .collect::<HashMap<_, _>>();
+
+        for f in chunk {
+            // A file is not returned if it already existed, and is returned
+            // once if it was given more than once.
+            let id = created
+                .remove(&f.object_store_id)
+                .ok_or(Error::FileExists {
+                    object_store_id: f.object_store_id,
+                })?;
+            ids.push(id);
+        }
+    }
+
+    Ok(ids)
+}
+
 async fn flag_for_delete<'q, E>(
     executor: E,
     ids: &[ParquetFileId],
@@ -2096,7 +2945,7 @@ mod tests {
     use data_types::partition_template::TemplatePart;
     use generated_types::influxdata::iox::partition_template::v1 as proto;
     use metric::{Attributes, DurationHistogram, Metric, Observation, RawReporter};
-    use std::{io::Write, sync::Arc, time::Instant};
+    use std::{io::Write, sync::Arc};
     use tempfile::NamedTempFile;
     use test_helpers::maybe_start_logging;
 
@@ -2613,7 +3462,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
             "#,
         )
         .bind(namespace_name) // $1
@@ -3025,6 +3875,114 @@ RETURNING *;
         assert!(partition_template.is_none());
     }
 
+    #[test]
+    fn test_read_replicas_round_robin() {
+        let metrics = metric::Registry::default();
+
+        let replicas = ReadReplicas::new(vec![(), (), ()], &metrics);
+        let got = (0..6)
+            .map(|_| replicas.next().map(|(idx, _)| idx))
+            .collect::<Vec<_>>();
+        assert_eq!(got, [0, 1, 2, 0, 1, 2].map(Some));
+
+        // An unavailable replica is skipped.
+        replicas.mark_unavailable(1);
+        for _ in 0..6 {
+            assert_matches!(replicas.next(), Some((0 | 2, _)));
+        }
+
+        // No replica is returned when all are unavailable.
+        replicas.mark_unavailable(0);
+        replicas.mark_unavailable(2);
+        assert!(replicas.next().is_none());
+
+        let fallback_count = metrics
+            .get_instrument::<Metric<U64Counter>>("iox_catalog_postgres_replica_fallback")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[]))
+            .expect("failed to get observer")
+            .fetch();
+        assert_eq!(fallback_count, 3);
+    }
+
+    #[test]
+    fn test_read_replicas_failback() {
+        let metrics = metric::Registry::default();
+
+        let replicas = ReadReplicas::new(vec![()], &metrics);
+        replicas.mark_unavailable(0);
+        assert!(replicas.next().is_none());
+
+        // Once the retry interval has elapsed, the replica is used again.
+        *replicas.replicas[0].1.lock() = Some(Instant::now() - Duration::from_secs(1));
+        assert_matches!(replicas.next(), Some((0, _)));
+        assert_matches!(*replicas.replicas[0].1.lock(), None);
+    }
+
+    #[test]
+    fn test_read_replicas_none() {
+        let metrics = metric::Registry::default();
+
+        let replicas = ReadReplicas::<()>::new(vec![], &metrics);
+        assert!(replicas.next().is_none());
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        assert!(is_connection_error(&sqlx::Error::PoolTimedOut));
+        assert!(is_connection_error(&sqlx::Error::PoolClosed));
+        assert!(is_connection_error(&sqlx::Error::Io(std::io::Error::from(
+            std::io::ErrorKind::ConnectionRefused
+        ))));
+        assert!(!is_connection_error(&sqlx::Error::RowNotFound));
+    }
+
+    #[tokio::test]
+    async fn test_read_replica_fallback() {
+        maybe_skip_integration!();
+
+        let mut postgres = setup_db().await;
+        let metrics = Arc::clone(&postgres.metrics);
+
+        // Configure a read replica that cannot serve any queries.
+        let closed = PgPoolOptions::new()
+            .connect_lazy(&postgres.options.dsn)
+            .expect("failed to build pool");
+        closed.close().await;
+        postgres.replicas = Arc::new(ReadReplicas::new(vec![HotSwapPool::new(closed)], &metrics));
+
+        let fallback_count = || {
+            metrics
+                .get_instrument::<Metric<U64Counter>>("iox_catalog_postgres_replica_fallback")
+                .expect("failed to read metric")
+                .get_observer(&Attributes::from(&[]))
+                .expect("failed to get observer")
+                .fetch()
+        };
+
+        let mut repos = postgres.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "ns4").await;
+
+        // The read is retried against the primary.
+        let got = repos
+            .namespaces()
+            .get_by_name(&namespace.name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .expect("read should fall back to the primary");
+        assert_eq!(got, Some(namespace.clone()));
+        assert_eq!(fallback_count(), 1);
+
+        // The replica is now marked unavailable, and reads are served by the
+        // primary without first trying the replica.
+        let got = repos
+            .namespaces()
+            .get_by_id(namespace.id, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .expect("read from primary");
+        assert_eq!(got, Some(namespace));
+        assert_eq!(fallback_count(), 1);
+    }
+
     #[tokio::test]
     async fn test_metrics() {
         maybe_skip_integration!();
@@ -0,0 +1,390 @@
+//! Retrying of transient catalog errors.
+
+use crate::{
+    interface::{
+        CasFailure, ColumnRepo, Error, NamespaceRepo, ParquetFileRepo, PartitionRepo,
+        RepoCollection, Result, SoftDeletedRows, TableRepo,
+    },
+    postgres::is_connection_error,
+};
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::{
+    partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
+    Column, ColumnMetadata, ColumnType, CompactionHistory, CompactionHistoryParams,
+    CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
+    SkippedCompactionError, Table, TableId, Timestamp, TransitionPartitionId,
+};
+use observability_deps::tracing::warn;
+use std::{collections::HashMap, fmt::Debug};
+use uuid::Uuid;
+
+/// Decorates an implementation of the catalog's [`RepoCollection`] with
+/// retries of transient errors.
+///
+/// A call that fails with a retryable error is retried according to the
+/// [`BackoffConfig`] until it either succeeds, fails with an error that is not
+/// retryable or the backoff deadline is exceeded, in which case the last error
+/// is returned. Which errors are retryable depends on the call:
+///
+/// * Reads, and writes that have the same effect and result when applied
+///   twice (such as setting a limit or a create-or-get), are retried after
+///   any transient error (a serialization failure, a deadlock or a connection
+///   problem, see [`Error::is_transient`]).
+/// * All other writes (such as creating a namespace or parquet files, or
+///   committing a compaction) are only retried after errors guaranteeing
+///   that no change was made (see [`Error::is_rolled_back`]). A connection
+///   lost while committing may have lost the acknowledgement of a successful
+///   commit, and retrying it would report that success as a conflict (such
+///   as [`Error::NameExists`] or [`Error::FileExists`]) or apply the change
+///   twice.
+///
+/// # Retries by callers
+///
+/// Components that must make progress regardless of how long the catalog is
+/// unavailable (the ingester persisting data and resolving its buffer tree,
+/// the compactor and its scheduler, the querier caches, and the garbage
+/// collector) keep retrying the calls they depend on forever, on all errors.
+/// These loops are not redundant: the decorator only wraps the Postgres
+/// catalog, gives up after its deadline and does not retry writes after
+/// ambiguous errors. Callers retrying a write that is not idempotent must
+/// themselves handle it having been applied by a previous attempt.
+#[derive(Debug)]
+pub struct RetryDecorator<T> {
+    inner: T,
+    backoff_config: BackoffConfig,
+}
+
+impl<T> RetryDecorator<T> {
+    /// Wrap `T`, retrying transient errors with `backoff_config`.
+    pub fn new(inner: T, backoff_config: BackoffConfig) -> Self {
+        Self {
+            inner,
+            backoff_config,
+        }
+    }
+}
+
+impl<T> RepoCollection for RetryDecorator<T>
+where
+    T: NamespaceRepo + TableRepo + ColumnRepo + PartitionRepo + ParquetFileRepo + Debug,
+{
+    fn namespaces(&mut self) -> &mut dyn NamespaceRepo {
+        self
+    }
+
+    fn tables(&mut self) -> &mut dyn TableRepo {
+        self
+    }
+
+    fn columns(&mut self) -> &mut dyn ColumnRepo {
+        self
+    }
+
+    fn partitions(&mut self) -> &mut dyn PartitionRepo {
+        self
+    }
+
+    fn parquet_files(&mut self) -> &mut dyn ParquetFileRepo {
+        self
+    }
+}
+
+impl Error {
+    /// Returns true if this error is likely to go away when the failed call
+    /// is retried, i.e. it is caused by a serialization failure, a deadlock or
+    /// a connection problem rather than by the request itself.
+    pub fn is_transient(&self) -> bool {
+        self.sqlx_source().map_or(false, |e| {
+            is_rolled_back_sqlx_error(e) || is_connection_error(e)
+        })
+    }
+
+    /// Returns true if this error is transient, and guarantees that the
+    /// failed call made no change to the catalog: its transaction was aborted
+    /// by a serialization failure or a deadlock, or no connection could be
+    /// acquired to run it.
+    ///
+    /// Other connection problems are ambiguous, as the connection may have
+    /// been lost after the transaction was committed.
+    pub fn is_rolled_back(&self) -> bool {
+        self.sqlx_source().map_or(false, is_rolled_back_sqlx_error)
+    }
+
+    /// The underlying [`sqlx::Error`] of errors that may be transient.
+    fn sqlx_source(&self) -> Option<&sqlx::Error> {
+        match self {
+            Self::SqlxError { source }
+            | Self::StartTransaction { source }
+            | Self::FailedToCommit { source }
+            | Self::CouldNotRecordSkippedCompaction { source, .. }
+            | Self::CouldNotListSkippedCompactions { source }
+            | Self::CouldNotDeleteSkippedCompactions { source }
+            | Self::CouldNotRecordCompactionHistory { source, .. }
+            | Self::CouldNotListCompactionHistory { source }
+            | Self::CouldNotDeleteNamespace { source } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if `e` is caused by a serialization failure or a deadlock,
+/// which abort the transaction, or by failing to acquire a connection.
+fn is_rolled_back_sqlx_error(e: &sqlx::Error) -> bool {
+    match e {
+        // serialization_failure and deadlock_detected
+        sqlx::Error::Database(db) => matches!(db.code().as_deref(), Some("40001" | "40P01")),
+        sqlx::Error::PoolTimedOut => true,
+        _ => false,
+    }
+}
+
+/// The errors a catalog call is retried after.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Retry {
+    /// Any transient error, as applying the call more than once has the same
+    /// effect and result as applying it once.
+    Idempotent,
+    /// Only transient errors guaranteeing that the call made no change.
+    RolledBack,
+}
+
+impl Retry {
+    /// Returns true if a call failing with `e` may be retried.
+    fn allows(self, e: &Error) -> bool {
+        match self {
+            Self::Idempotent => e.is_transient(),
+            Self::RolledBack => e.is_rolled_back(),
+        }
+    }
+}
+
+/// An error that may be caused by an [`Error`] of the catalog.
+trait CatalogError {
+    /// Returns the underlying catalog error, if any.
+    fn catalog_error(&self) -> Option<&Error>;
+}
+
+impl CatalogError for Error {
+    fn catalog_error(&self) -> Option<&Error> {
+        Some(self)
+    }
+}
+
+impl<T> CatalogError for CasFailure<T> {
+    fn catalog_error(&self) -> Option<&Error> {
+        match self {
+            Self::ValueMismatch(_) => None,
+            Self::QueryError(e) => Some(e),
+        }
+    }
+}
+
+/// Emit a trait impl for `impl_trait` that delegates calls to the inner
+/// implementation, retrying calls that fail with an error allowed by their
+/// [`Retry`] policy.
+///
+/// Arguments are cloned for every attempt. The format is the same as for the
+/// `decorate!()` macro of the metric decorator, with every method prefixed by
+/// its [`Retry`] policy, and as there all methods of a given trait MUST be
+/// listed.
+macro_rules! decorate {
+    (
+        impl_trait = $trait:ident,
+        methods = [$(
+            $retry:ident $op:literal = $method:ident(
+                &mut self $(,)?
+                $($arg:ident : $t:ty),*
+            ) -> Result<$out:ty$(, $err:ty)?>;
+        )+]
+    ) => {
+        #[async_trait]
+        impl<T: $trait> $trait for RetryDecorator<T> {
+            $(
+                #[allow(clippy::clone_on_copy)]
+                async fn $method(&mut self, $($arg : $t),*) -> Result<$out$(, $err)?> {
+                    let mut backoff = Backoff::new(&self.backoff_config);
+                    loop {
+                        let res = self.inner.$method($($arg.clone()),*).await;
+                        let Err(e) = &res else {
+                            return res;
+                        };
+                        let Some(transient) = e
+                            .catalog_error()
+                            .filter(|e| Retry::$retry.allows(e))
+                        else {
+                            return res;
+                        };
+                        let Some(delay) = backoff.next() else {
+                            return res;
+                        };
+
+                        warn!(
+                            error=%transient,
+                            op=$op,
+                            backoff_secs=delay.as_secs_f64(),
+                            "catalog call failed with transient error - backing off",
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            )+
+        }
+    };
+}
+
+decorate!(
+    impl_trait = NamespaceRepo,
+    methods = [
+        RolledBack "namespace_create" = create(&mut self, name: &NamespaceName<'_>, partition_template: Option<NamespacePartitionTemplateOverride>, retention_period_ns: Option<i64>, service_protection_limits: Option<NamespaceServiceProtectionLimitsOverride>) -> Result<Namespace>;
+        Idempotent "namespace_update_retention_period" = update_retention_period(&mut self, name: &str, retention_period_ns: Option<i64>) -> Result<Namespace>;
+        Idempotent "namespace_list" = list(&mut self, deleted: SoftDeletedRows) -> Result<Vec<Namespace>>;
+        Idempotent "namespace_get_by_id" = get_by_id(&mut self, id: NamespaceId, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
+        Idempotent "namespace_get_by_name" = get_by_name(&mut self, name: &str, deleted: SoftDeletedRows) -> Result<Option<Namespace>>;
+        Idempotent "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
+        RolledBack "namespace_undelete" = undelete(&mut self, name: &str) -> Result<Namespace>;
+        RolledBack "namespace_purge" = purge(&mut self, id: NamespaceId) -> Result<()>;
+        Idempotent "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+        Idempotent "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+        Idempotent "namespace_update_compaction_paused" = update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace>;
+        Idempotent "namespace_update_write_rate_limits" = update_write_rate_limits(&mut self, name: &str, max_write_lines_per_second: Option<i64>, max_write_bytes_per_second: Option<i64>) -> Result<Namespace>;
+    ]
+);
+
+decorate!(
+    impl_trait = TableRepo,
+    methods = [
+        RolledBack "table_create" = create(&mut self, name: &str, partition_template: TablePartitionTemplateOverride, namespace_id: NamespaceId) -> Result<Table>;
+        Idempotent "table_get_by_id" = get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>>;
+        Idempotent "table_get_by_namespace_and_name" = get_by_namespace_and_name(&mut self, namespace_id: NamespaceId, name: &str) -> Result<Option<Table>>;
+        Idempotent "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
+        Idempotent "table_list" = list(&mut self) -> Result<Vec<Table>>;
+        Idempotent "table_update_retention_period" = update_retention_period(&mut self, table_id: TableId, retention_period_ns: Option<i64>) -> Result<Table>;
+        RolledBack "table_delete" = delete(&mut self, table_id: TableId) -> Result<Table>;
+    ]
+);
+
+decorate!(
+    impl_trait = ColumnRepo,
+    methods = [
+        Idempotent "column_create_or_get" = create_or_get(&mut self, name: &str, table_id: TableId, column_type: ColumnType) -> Result<Column>;
+        Idempotent "column_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Column>>;
+        Idempotent "column_list_by_table_id" = list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Column>>;
+        Idempotent "column_create_or_get_many_unchecked" = create_or_get_many_unchecked(&mut self, table_id: TableId, columns: HashMap<&str, ColumnType>) -> Result<Vec<Column>>;
+        Idempotent "column_list" = list(&mut self) -> Result<Vec<Column>>;
+        Idempotent "column_update_metadata" = update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata>;
+        Idempotent "column_list_metadata_by_namespace_id" = list_metadata_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<ColumnMetadata>>;
+    ]
+);
+
+decorate!(
+    impl_trait = PartitionRepo,
+    methods = [
+        Idempotent "partition_create_or_get" = create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition>;
+        Idempotent "partition_get_by_id" = get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>>;
+        Idempotent "partition_get_by_id_batch" = get_by_id_batch(&mut self, partition_ids: Vec<PartitionId>) -> Result<Vec<Partition>>;
+        Idempotent "partition_get_by_hash_id" = get_by_hash_id(&mut self, partition_hash_id: &PartitionHashId) -> Result<Option<Partition>>;
+        Idempotent "partition_get_by_hash_id_batch" = get_by_hash_id_batch(&mut self, partition_hash_ids: &[&PartitionHashId]) -> Result<Vec<Partition>>;
+        Idempotent "partition_list_by_table_id" = list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Partition>>;
+        Idempotent "partition_list_ids" = list_ids(&mut self) -> Result<Vec<PartitionId>>;
+        RolledBack "partition_update_sort_key" = cas_sort_key(&mut self, partition_id: &TransitionPartitionId, old_sort_key: Option<Vec<String>>, new_sort_key: &[&str]) -> Result<Partition, CasFailure<Vec<String>>>;
+        Idempotent "partition_record_skipped_compaction" = record_skipped_compaction(&mut self, partition_id: PartitionId, reason: &str, num_files: usize, limit_num_files: usize, limit_num_files_first_in_partition: usize, estimated_bytes: u64, limit_bytes: u64) -> Result<()>;
+        Idempotent "partition_list_skipped_compactions" = list_skipped_compactions(&mut self) -> Result<Vec<SkippedCompaction>>;
+        RolledBack "partition_delete_skipped_compactions" = delete_skipped_compactions(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+        Idempotent "partition_record_skipped_compaction_error" = record_skipped_compaction_error(&mut self, partition_id: PartitionId, error_kind: &str, num_files: usize, total_bytes: u64, num_failures: usize, last_error: &str) -> Result<()>;
+        Idempotent "partition_list_skipped_compaction_errors" = list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>>;
+        RolledBack "partition_delete_skipped_compaction_error" = delete_skipped_compaction_error(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompactionError>>;
+        RolledBack "partition_record_compaction_history" = record_compaction_history(&mut self, params: CompactionHistoryParams) -> Result<CompactionHistory>;
+        Idempotent "partition_list_compaction_history" = list_compaction_history(&mut self, partition_id: PartitionId) -> Result<Vec<CompactionHistory>>;
+        Idempotent "partition_most_recent_n" = most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>>;
+        Idempotent "partition_partitions_new_file_between" = partitions_new_file_between(&mut self, minimum_time: Timestamp, maximum_time: Option<Timestamp>) -> Result<Vec<PartitionId>>;
+        Idempotent "partition_partitions_needing_cold_compact" = partitions_needing_cold_compact(&mut self, maximum_time: Timestamp, after: Option<PartitionId>, n: usize) -> Result<Vec<PartitionId>>;
+        Idempotent "partition_get_in_skipped_compaction" = get_in_skipped_compaction(&mut self, partition_id: PartitionId) -> Result<Option<SkippedCompaction>>;
+    ]
+);
+
+decorate!(
+    impl_trait = ParquetFileRepo,
+    methods = [
+        RolledBack "parquet_create" = create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
+        RolledBack "parquet_create_many" = create_many(&mut self, parquet_file_params: Vec<ParquetFileParams>) -> Result<Vec<ParquetFile>>;
+        Idempotent "parquet_list_all" = list_all(&mut self) -> Result<Vec<ParquetFile>>;
+        RolledBack "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
+        Idempotent "parquet_list_to_flag_by_retention" = list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>>;
+        Idempotent "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
+        Idempotent "parquet_list_by_table_not_to_delete" = list_by_table_not_to_delete(&mut self, table_id: TableId) -> Result<Vec<ParquetFile>>;
+        RolledBack "parquet_delete_old_ids_only" = delete_old_ids_only(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFileId>>;
+        Idempotent "parquet_list_old" = list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
+        Idempotent "parquet_list_by_partition_not_to_delete" = list_by_partition_not_to_delete(&mut self, partition_id: &TransitionPartitionId) -> Result<Vec<ParquetFile>>;
+        Idempotent "parquet_get_by_object_store_id" = get_by_object_store_id(&mut self, object_store_id: Uuid) -> Result<Option<ParquetFile>>;
+        Idempotent "parquet_exists_by_object_store_id_batch" = exists_by_object_store_id_batch(&mut self, object_store_ids: Vec<Uuid>) -> Result<Vec<Uuid>>;
+        Idempotent "parquet_sum_l0_file_size_by_partition" = sum_l0_file_size_by_partition(&mut self, partition_ids: &[PartitionId]) -> Result<Vec<(PartitionId, i64)>>;
+        RolledBack "parquet_create_upgrade_delete" = create_upgrade_delete(&mut self, delete: &[ParquetFileId], upgrade: &[ParquetFileId], create: &[ParquetFileParams], target_level: CompactionLevel) -> Result<Vec<ParquetFileId>>;
+    ]
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient() {
+        assert!(Error::SqlxError {
+            source: sqlx::Error::PoolTimedOut
+        }
+        .is_transient());
+        assert!(Error::StartTransaction {
+            source: sqlx::Error::Io(std::io::ErrorKind::ConnectionReset.into())
+        }
+        .is_transient());
+
+        assert!(!Error::SqlxError {
+            source: sqlx::Error::RowNotFound
+        }
+        .is_transient());
+        assert!(!Error::ForeignKeyViolation {
+            source: sqlx::Error::PoolTimedOut
+        }
+        .is_transient());
+        assert!(!Error::NameExists {
+            name: String::from("foo")
+        }
+        .is_transient());
+
+        assert!(CasFailure::<()>::QueryError(Error::SqlxError {
+            source: sqlx::Error::PoolTimedOut
+        })
+        .catalog_error()
+        .is_some());
+        assert!(CasFailure::ValueMismatch(()).catalog_error().is_none());
+    }
+
+    #[test]
+    fn test_retry_policy() {
+        // No connection could be acquired, so nothing was applied.
+        let pool_timeout = Error::StartTransaction {
+            source: sqlx::Error::PoolTimedOut,
+        };
+        // The connection was lost, possibly after the commit was applied.
+        let connection_lost = Error::FailedToCommit {
+            source: sqlx::Error::Io(std::io::ErrorKind::ConnectionReset.into()),
+        };
+        let not_transient = Error::NameExists {
+            name: String::from("foo"),
+        };
+
+        assert!(pool_timeout.is_rolled_back());
+        assert!(!connection_lost.is_rolled_back());
+        assert!(!not_transient.is_rolled_back());
+
+        assert!(Retry::Idempotent.allows(&pool_timeout));
+        assert!(Retry::Idempotent.allows(&connection_lost));
+        assert!(!Retry::Idempotent.allows(&not_transient));
+
+        assert!(Retry::RolledBack.allows(&pool_timeout));
+        assert!(!Retry::RolledBack.allows(&connection_lost));
+        assert!(!Retry::RolledBack.allows(&not_transient));
+    }
+}
@@ -18,10 +18,11 @@ use data_types::{
     partition_template::{
         NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
     },
-    Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, Namespace, NamespaceId,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    Table, TableId, Timestamp, TransitionPartitionId,
+    Column, ColumnId, ColumnMetadata, ColumnSet, ColumnType, CompactionHistory,
+    CompactionHistoryParams, CompactionLevel, Namespace, NamespaceId, NamespaceName,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
+    SkippedCompactionError, Table, TableId, TagRanges, Timestamp, TransitionPartitionId,
 };
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, fmt::Display};
@@ -262,6 +263,57 @@ impl RepoCollection for SqliteTxn {
     }
 }
 
+/// Statements removing everything attached to the namespace `$1` from the catalog, in dependency
+/// order, followed by the namespace itself.
+const PURGE_NAMESPACE_QUERIES: &[&str] = &[
+    r#"DELETE FROM parquet_file WHERE namespace_id = $1;"#,
+    r#"
+DELETE FROM skipped_compactions
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"
+DELETE FROM skipped_compaction_errors
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"
+DELETE FROM compaction_history
+WHERE partition_id IN (
+    SELECT partition.id FROM partition
+    JOIN table_name ON table_name.id = partition.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"DELETE FROM partition WHERE table_id IN (SELECT id FROM table_name WHERE namespace_id = $1);"#,
+    r#"
+DELETE FROM column_metadata
+WHERE column_id IN (
+    SELECT column_name.id FROM column_name
+    JOIN table_name ON table_name.id = column_name.table_id
+    WHERE table_name.namespace_id = $1
+);
+    "#,
+    r#"DELETE FROM column_name WHERE table_id IN (SELECT id FROM table_name WHERE namespace_id = $1);"#,
+    r#"DELETE FROM table_name WHERE namespace_id = $1;"#,
+    r#"DELETE FROM namespace WHERE id = $1;"#,
+];
+
+const DELETE_TABLE_QUERIES: &[&str] = &[
+    r#"
+DELETE FROM column_metadata
+WHERE column_id IN (SELECT id FROM column_name WHERE table_id = $1);
+    "#,
+    r#"DELETE FROM column_name WHERE table_id = $1;"#,
+    r#"DELETE FROM table_name WHERE id = $1;"#,
+];
+
 #[async_trait]
 impl NamespaceRepo for SqliteTxn {
     async fn create(
@@ -279,7 +331,8 @@ impl NamespaceRepo for SqliteTxn {
 INSERT INTO namespace ( name, topic_id, query_pool_id, retention_period_ns, max_tables, max_columns_per_table, partition_template )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -310,7 +363,8 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE {v};
                 "#,
@@ -334,7 +388,8 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -364,7 +419,8 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, compaction_paused, max_write_lines_per_second,
+       max_write_bytes_per_second
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -398,6 +454,69 @@ WHERE name=$1 AND {v};
             .map(|_| ())
     }
 
+    async fn undelete(&mut self, name: &str) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = NULL
+WHERE name = $1 AND deleted_at IS NOT NULL
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(name) // $1
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn purge(&mut self, id: NamespaceId) -> Result<()> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let deleted_at = sqlx::query_scalar::<_, Option<Timestamp>>(
+            r#"SELECT deleted_at FROM namespace WHERE id = $1;"#,
+        )
+        .bind(id) // $1
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        match deleted_at {
+            None => return Err(Error::NamespaceNotFoundById { id }),
+            Some(None) => return Err(Error::NamespaceNotSoftDeleted { id }),
+            Some(Some(_)) => {}
+        }
+
+        for query in PURGE_NAMESPACE_QUERIES {
+            sqlx::query(query)
+                .bind(id) // $1
+                .execute(&mut *tx)
+                .await
+                .context(interface::CouldNotDeleteNamespaceSnafu)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(())
+    }
+
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let rec = sqlx::query_as::<_, Namespace>(
             r#"
@@ -405,7 +524,8 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
         "#,
         )
         .bind(new_max)
@@ -430,7 +550,8 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
         "#,
         )
         .bind(new_max)
@@ -448,6 +569,64 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
         Ok(namespace)
     }
 
+    async fn update_compaction_paused(&mut self, name: &str, paused: bool) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET compaction_paused = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(paused)
+        .bind(name)
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_write_rate_limits(
+        &mut self,
+        name: &str,
+        max_write_lines_per_second: Option<i64>,
+        max_write_bytes_per_second: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET max_write_lines_per_second = $1, max_write_bytes_per_second = $2
+WHERE name = $3
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
+        "#,
+        )
+        .bind(max_write_lines_per_second) // $1
+        .bind(max_write_bytes_per_second) // $2
+        .bind(name) // $3
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
     async fn update_retention_period(
         &mut self,
         name: &str,
@@ -459,7 +638,8 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
             "#,
         )
         .bind(retention_period_ns) // $1
@@ -687,6 +867,74 @@ WHERE namespace_id = $1;
 
         Ok(rec)
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+            "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(table)
+    }
+
+    async fn delete(&mut self, table_id: TableId) -> Result<Table> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let table = sqlx::query_as::<_, Table>(r#"SELECT * FROM table_name WHERE id = $1;"#)
+            .bind(table_id) // $1
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or(Error::TableNotFound { id: table_id })?;
+
+        let has_partitions = sqlx::query_scalar::<_, bool>(
+            r#"SELECT EXISTS (SELECT 1 FROM partition WHERE table_id = $1);"#,
+        )
+        .bind(table_id) // $1
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+        if has_partitions {
+            return Err(Error::TableNotEmpty { id: table_id });
+        }
+
+        for query in DELETE_TABLE_QUERIES {
+            sqlx::query(query)
+                .bind(table_id) // $1
+                .execute(&mut *tx)
+                .await
+                .context(interface::CouldNotDeleteTableSnafu)?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(table)
+    }
 }
 
 #[async_trait]
@@ -806,6 +1054,57 @@ RETURNING *;
 
         Ok(out)
     }
+
+    async fn update_metadata(&mut self, metadata: ColumnMetadata) -> Result<ColumnMetadata> {
+        let column_id = metadata.column_id;
+        let rec = sqlx::query_as::<_, ColumnMetadata>(
+            r#"
+INSERT INTO column_metadata ( column_id, description, unit, semantic_type )
+SELECT $1, $2, $3, $4
+WHERE EXISTS (SELECT 1 FROM column_name WHERE id = $1)
+ON CONFLICT (column_id)
+DO UPDATE SET
+    description = excluded.description,
+    unit = excluded.unit,
+    semantic_type = excluded.semantic_type
+RETURNING *;
+            "#,
+        )
+        .bind(column_id) // $1
+        .bind(metadata.description) // $2
+        .bind(metadata.unit) // $3
+        .bind(metadata.semantic_type) // $4
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let metadata = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::ColumnNotFound { id: column_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(metadata)
+    }
+
+    async fn list_metadata_by_namespace_id(
+        &mut self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnMetadata>> {
+        let rec = sqlx::query_as::<_, ColumnMetadata>(
+            r#"
+SELECT column_metadata.*
+FROM column_metadata
+JOIN column_name ON column_name.id = column_metadata.column_id
+JOIN table_name ON table_name.id = column_name.table_id
+WHERE table_name.namespace_id = $1;
+            "#,
+        )
+        .bind(namespace_id) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        Ok(rec)
+    }
 }
 
 // We can't use [`Partition`], as uses Vec<String> which the Sqlite
@@ -1164,6 +1463,125 @@ RETURNING *
         .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
     }
 
+    async fn record_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+        error_kind: &str,
+        num_files: usize,
+        total_bytes: u64,
+        num_failures: usize,
+        last_error: &str,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO skipped_compaction_errors
+    ( partition_id, error_kind, num_files, total_bytes, num_failures, last_error, skipped_at )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, $7 )
+ON CONFLICT ( partition_id )
+DO UPDATE
+SET
+error_kind = EXCLUDED.error_kind,
+num_files = EXCLUDED.num_files,
+total_bytes = EXCLUDED.total_bytes,
+num_failures = EXCLUDED.num_failures,
+last_error = EXCLUDED.last_error,
+skipped_at = EXCLUDED.skipped_at;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(error_kind)
+        .bind(num_files as i64)
+        .bind(total_bytes as i64)
+        .bind(num_failures as i64)
+        .bind(last_error)
+        .bind(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs() as i64,
+        )
+        .execute(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotRecordSkippedCompactionSnafu { partition_id })?;
+        Ok(())
+    }
+
+    async fn list_skipped_compaction_errors(&mut self) -> Result<Vec<SkippedCompactionError>> {
+        sqlx::query_as::<_, SkippedCompactionError>(
+            r#"
+SELECT * FROM skipped_compaction_errors
+        "#,
+        )
+        .fetch_all(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotListSkippedCompactionsSnafu)
+    }
+
+    async fn delete_skipped_compaction_error(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Option<SkippedCompactionError>> {
+        sqlx::query_as::<_, SkippedCompactionError>(
+            r#"
+DELETE FROM skipped_compaction_errors
+WHERE partition_id = $1
+RETURNING *
+        "#,
+        )
+        .bind(partition_id)
+        .fetch_optional(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotDeleteSkippedCompactionsSnafu)
+    }
+
+    async fn record_compaction_history(
+        &mut self,
+        params: CompactionHistoryParams,
+    ) -> Result<CompactionHistory> {
+        let partition_id = params.partition_id;
+        sqlx::query_as::<_, CompactionHistory>(
+            r#"
+INSERT INTO compaction_history
+    ( partition_id, job_id, num_input_files, input_bytes, num_upgraded_files, num_output_files,
+      output_bytes, target_level, duration_ms, committed_at )
+VALUES
+    ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10 )
+RETURNING *;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .bind(params.job_id) // $2
+        .bind(params.num_input_files) // $3
+        .bind(params.input_bytes) // $4
+        .bind(params.num_upgraded_files) // $5
+        .bind(params.num_output_files) // $6
+        .bind(params.output_bytes) // $7
+        .bind(params.target_level) // $8
+        .bind(params.duration_ms) // $9
+        .bind(params.committed_at) // $10
+        .fetch_one(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotRecordCompactionHistorySnafu { partition_id })
+    }
+
+    async fn list_compaction_history(
+        &mut self,
+        partition_id: PartitionId,
+    ) -> Result<Vec<CompactionHistory>> {
+        sqlx::query_as::<_, CompactionHistory>(
+            r#"
+SELECT * FROM compaction_history
+WHERE partition_id = $1
+ORDER BY id;
+        "#,
+        )
+        .bind(partition_id) // $1
+        .fetch_all(self.inner.get_mut())
+        .await
+        .context(interface::CouldNotListCompactionHistorySnafu)
+    }
+
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"
@@ -1206,6 +1624,38 @@ LIMIT $1;
             .await
             .map_err(|e| Error::SqlxError { source: e })
     }
+
+    async fn partitions_needing_cold_compact(
+        &mut self,
+        maximum_time: Timestamp,
+        after: Option<PartitionId>,
+        n: usize,
+    ) -> Result<Vec<PartitionId>> {
+        sqlx::query_as(
+            r#"
+SELECT p.id as partition_id
+FROM partition p
+WHERE p.new_file_at < $1
+  AND p.id > $2
+  AND EXISTS (
+    SELECT 1
+    FROM parquet_file f
+    WHERE f.partition_id = p.id
+      AND f.to_delete IS NULL
+      AND f.compaction_level < $3
+  )
+ORDER BY p.id
+LIMIT $4;
+            "#,
+        )
+        .bind(maximum_time) // $1
+        .bind(after.map(|id| id.get()).unwrap_or(i64::MIN)) // $2
+        .bind(CompactionLevel::Final) // $3
+        .bind(n as i64) // $4
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
 }
 
 fn from_column_set(v: &ColumnSet) -> Json<Vec<i64>> {
@@ -1233,6 +1683,7 @@ struct ParquetFilePod {
     created_at: Timestamp,
     column_set: Json<Vec<i64>>,
     max_l0_created_at: Timestamp,
+    tag_ranges: Option<TagRanges>,
 }
 
 impl From<ParquetFilePod> for ParquetFile {
@@ -1253,6 +1704,7 @@ impl From<ParquetFilePod> for ParquetFile {
             created_at: value.created_at,
             column_set: to_column_set(&value.column_set),
             max_l0_created_at: value.max_l0_created_at,
+            tag_ranges: value.tag_ranges,
         }
     }
 }
@@ -1264,6 +1716,30 @@ impl ParquetFileRepo for SqliteTxn {
         create_parquet_file(executor, parquet_file_params).await
     }
 
+    async fn create_many(
+        &mut self,
+        parquet_file_params: Vec<ParquetFileParams>,
+    ) -> Result<Vec<ParquetFile>> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let mut files = Vec::with_capacity(parquet_file_params.len());
+        for file in parquet_file_params {
+            files.push(create_parquet_file(&mut *tx, file).await?);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::FailedToCommit { source: e })?;
+
+        Ok(files)
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
         // `parquet_metadata` column!!
@@ -1273,7 +1749,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
 FROM parquet_file;
              "#,
         )
@@ -1287,16 +1764,18 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // table retention, if it exists, overrides namespace retention
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time
+        < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1315,6 +1794,37 @@ RETURNING id;
         Ok(flagged)
     }
 
+    async fn list_to_flag_by_retention(&mut self) -> Result<Vec<ParquetFile>> {
+        let now = Timestamp::from(self.time_provider.now());
+        // same selection as `flag_for_delete_by_retention`, without the update
+        Ok(sqlx::query_as::<_, ParquetFilePod>(
+            r#"
+SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
+       parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
+       parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
+       parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
+FROM namespace, table_name, parquet_file
+WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
+AND parquet_file.to_delete IS NULL
+AND parquet_file.max_time
+    < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
+AND namespace.id = parquet_file.namespace_id
+AND table_name.id = parquet_file.table_id
+LIMIT $2;
+             "#,
+        )
+        .bind(now) // $1
+        .bind(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_RETENTION) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
     async fn list_by_namespace_not_to_delete(
         &mut self,
         namespace_id: NamespaceId,
@@ -1327,7 +1837,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.tag_ranges
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
@@ -1348,7 +1859,7 @@ WHERE table_name.namespace_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes,
-       row_count, compaction_level, created_at, column_set, max_l0_created_at
+       row_count, compaction_level, created_at, column_set, max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
@@ -1387,6 +1898,28 @@ RETURNING id;
         Ok(deleted)
     }
 
+    async fn list_old(&mut self, older_than: Timestamp) -> Result<Vec<ParquetFile>> {
+        // same selection as `delete_old_ids_only`, without the delete
+        Ok(sqlx::query_as::<_, ParquetFilePod>(
+            r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
+       min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
+       column_set, max_l0_created_at, tag_ranges
+FROM parquet_file
+WHERE to_delete < $1
+LIMIT $2;
+             "#,
+        )
+        .bind(older_than) // $1
+        .bind(MAX_PARQUET_FILES_SELECTED_ONCE_FOR_DELETE) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
     async fn list_by_partition_not_to_delete(
         &mut self,
         partition_id: &TransitionPartitionId,
@@ -1397,7 +1930,7 @@ RETURNING id;
                 r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE parquet_file.partition_hash_id = $1
   AND parquet_file.to_delete IS NULL;
@@ -1408,7 +1941,7 @@ WHERE parquet_file.partition_hash_id = $1
                 r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE parquet_file.partition_id = $1
   AND parquet_file.to_delete IS NULL;
@@ -1434,7 +1967,7 @@ WHERE parquet_file.partition_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, tag_ranges
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1478,6 +2011,30 @@ WHERE object_store_id IN ({v});",
         .map_err(|e| Error::SqlxError { source: e })
     }
 
+    async fn sum_l0_file_size_by_partition(
+        &mut self,
+        partition_ids: &[PartitionId],
+    ) -> Result<Vec<(PartitionId, i64)>> {
+        // We use a JSON-based "IS IN" check.
+        let ids: Vec<_> = partition_ids.iter().map(|p| p.get()).collect();
+
+        sqlx::query_as(
+            r#"
+SELECT partition_id, SUM(file_size_bytes) AS file_size_bytes
+FROM parquet_file
+WHERE partition_id IN (SELECT value FROM json_each($1))
+  AND compaction_level = $2
+  AND to_delete IS NULL
+GROUP BY partition_id;
+            "#,
+        )
+        .bind(Json(&ids[..])) // $1
+        .bind(CompactionLevel::Initial) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
     async fn create_upgrade_delete(
         &mut self,
         delete: &[ParquetFileId],
@@ -1543,6 +2100,7 @@ where
         created_at,
         column_set,
         max_l0_created_at,
+        tag_ranges,
     } = parquet_file_params;
 
     let partition_hash_id_ref = &partition_hash_id.as_ref();
@@ -1551,12 +2109,13 @@ where
 INSERT INTO parquet_file (
     shard_id, table_id, partition_id, partition_hash_id, object_store_id,
     min_time, max_time, file_size_bytes,
-    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at )
-VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 )
+    row_count, compaction_level, created_at, namespace_id, column_set, max_l0_created_at,
+    tag_ranges )
+VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15 )
 RETURNING
     id, table_id, partition_id, partition_hash_id, object_store_id, min_time, max_time, to_delete,
     file_size_bytes, row_count, compaction_level, created_at, namespace_id, column_set,
-    max_l0_created_at;
+    max_l0_created_at, tag_ranges;
         "#,
     )
     .bind(TRANSITION_SHARD_ID) // $1
@@ -1573,6 +2132,7 @@ RETURNING
     .bind(namespace_id) // $12
     .bind(from_column_set(&column_set)) // $13
     .bind(max_l0_created_at) // $14
+    .bind(tag_ranges) // $15
     .fetch_one(executor)
     .await;
 
@@ -2058,7 +2618,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, compaction_paused, max_write_lines_per_second,
+          max_write_bytes_per_second;
             "#,
         )
         .bind(namespace_name) // $1
@@ -24,8 +24,10 @@ pub struct ColumnRange {
 pub type ColumnRanges = Arc<HashMap<Arc<str>, ColumnRange>>;
 
 /// Create chunk [statistics](Statistics).
+///
+/// If the row count is unknown (e.g. because the data is streamed), the statistics are marked as inexact.
 pub fn create_chunk_statistics(
-    row_count: u64,
+    row_count: Option<u64>,
     schema: &Schema,
     ts_min_max: Option<TimestampMinMax>,
     ranges: &ColumnRanges,
@@ -60,10 +62,10 @@ pub fn create_chunk_statistics(
     }
 
     Statistics {
-        num_rows: Some(row_count as usize),
+        num_rows: row_count.map(|row_count| row_count as usize),
         total_byte_size: None,
         column_statistics: Some(columns),
-        is_exact: true,
+        is_exact: row_count.is_some(),
     }
 }
 
@@ -78,7 +80,7 @@ mod tests {
         let schema = SchemaBuilder::new().build().unwrap();
         let row_count = 0;
 
-        let actual = create_chunk_statistics(row_count, &schema, None, &Default::default());
+        let actual = create_chunk_statistics(Some(row_count), &schema, None, &Default::default());
         let expected = Statistics {
             num_rows: Some(row_count as usize),
             total_byte_size: None,
@@ -117,7 +119,8 @@ mod tests {
         ]));
 
         for row_count in [0u64, 1337u64] {
-            let actual = create_chunk_statistics(row_count, &schema, Some(ts_min_max), &ranges);
+            let actual =
+                create_chunk_statistics(Some(row_count), &schema, Some(ts_min_max), &ranges);
             let expected = Statistics {
                 num_rows: Some(row_count as usize),
                 total_byte_size: None,
@@ -165,7 +168,7 @@ mod tests {
             },
         )]));
 
-        let actual = create_chunk_statistics(row_count, &schema, Some(ts_min_max), &ranges);
+        let actual = create_chunk_statistics(Some(row_count), &schema, Some(ts_min_max), &ranges);
         let expected = Statistics {
             num_rows: Some(row_count as usize),
             total_byte_size: None,
@@ -189,6 +192,25 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_create_chunk_statistics_unknown_row_count() {
+        let schema = SchemaBuilder::new().timestamp().build().unwrap();
+
+        let actual = create_chunk_statistics(None, &schema, None, &Default::default());
+        let expected = Statistics {
+            num_rows: None,
+            total_byte_size: None,
+            column_statistics: Some(vec![ColumnStatistics {
+                null_count: Some(0),
+                min_value: Some(ScalarValue::TimestampNanosecond(None, None)),
+                max_value: Some(ScalarValue::TimestampNanosecond(None, None)),
+                distinct_count: None,
+            }]),
+            is_exact: false,
+        };
+        assert_eq!(actual, expected);
+    }
+
     fn full_schema() -> Schema {
         SchemaBuilder::new()
             .tag("tag1")
@@ -1,7 +1,11 @@
-use std::{str::FromStr, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
 
 use datafusion::{common::extensions_options, config::ConfigExtension};
 
+use crate::physical_optimizer::cost::{
+    ByteSizeCostModel, ChunkCountCostModel, CostModel, RowCountCostModel,
+};
+
 /// IOx-specific config extension prefix.
 pub const IOX_CONFIG_PREFIX: &str = "iox";
 
@@ -25,6 +29,13 @@ extensions_options! {
         /// This protects against certain highly degenerative plans.
         pub max_dedup_time_split: usize, default = 100
 
+        /// Number of disjoint time buckets that a de-duplication operation is split into so that the buckets can be
+        /// processed in parallel. Values below 2 disable this split.
+        ///
+        /// This helps to utilize more cores for large partitions with many overlapping chunks but may read chunks
+        /// that span multiple buckets more than once.
+        pub dedup_time_buckets: usize, default = 1
+
         /// When multiple parquet files are required in a sorted way (e.g. for de-duplication), we have two options:
         ///
         /// 1. **In-mem sorting:** Put them into [`target_partitions`] DataFusion partitions. This limits the fan-out,
@@ -43,6 +54,37 @@ extensions_options! {
 
         /// Cuttoff date for InfluxQL metadata queries.
         pub influxql_metadata_cutoff: MetadataCutoff, default = MetadataCutoff::Relative(Duration::from_secs(3600 * 24))
+
+        /// Estimate the InfluxQL `PERCENTILE` and `MEDIAN` functions from a t-digest instead of buffering all values.
+        ///
+        /// The approximate results need a bounded amount of memory per group but differ from InfluxDB 1.x, hence exact
+        /// results are the default.
+        pub influxql_approximate_percentile: bool, default = false
+
+        /// [Cost model](CostModel) that is consulted by the IOx-specific optimizer passes.
+        pub cost_model: CostModelKind, default = CostModelKind::ChunkCount
+
+        /// Late materialization for parquet scans: predicates on tag and time columns are evaluated first and the
+        /// remaining (field) columns are only decoded for rows that pass these predicates.
+        ///
+        /// This is especially useful for wide tables and selective predicates.
+        pub parquet_late_materialization: bool, default = true
+
+        /// Level of detail of the [pruning reports](crate::pruning::PruningReport) that are recorded for a query.
+        ///
+        /// Reports are shown by `EXPLAIN VERBOSE`. Recording them costs additional pruning passes, hence they are
+        /// disabled by default.
+        pub pruning_report: PruningReportVerbosity, default = PruningReportVerbosity::Off
+
+        /// Fraction of the table data that is scanned, in the range `(0, 1]`.
+        ///
+        /// Values below `1` make every chunk scan skip record batches at random (see [`SampleExec`]), so that
+        /// exploratory queries on huge tables return quickly. Results are approximate: aggregates are computed over the
+        /// sampled rows only.
+        ///
+        ///
+        /// [`SampleExec`]: crate::provider::SampleExec
+        pub sample_fraction: SampleFraction, default = SampleFraction::ALL
     }
 }
 
@@ -92,3 +134,191 @@ impl std::fmt::Display for MetadataCutoff {
         }
     }
 }
+
+/// Selects the [`CostModel`] that is used by the optimizer passes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CostModelKind {
+    /// [`ChunkCountCostModel`]
+    #[default]
+    ChunkCount,
+
+    /// [`RowCountCostModel`]
+    RowCount,
+
+    /// [`ByteSizeCostModel`]
+    ByteSize,
+}
+
+impl CostModelKind {
+    /// Create model.
+    pub fn model(&self) -> Arc<dyn CostModel> {
+        match self {
+            Self::ChunkCount => Arc::new(ChunkCountCostModel),
+            Self::RowCount => Arc::new(RowCountCostModel),
+            Self::ByteSize => Arc::new(ByteSizeCostModel),
+        }
+    }
+}
+
+impl FromStr for CostModelKind {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chunk_count" => Ok(Self::ChunkCount),
+            "row_count" => Ok(Self::RowCount),
+            "byte_size" => Ok(Self::ByteSize),
+            _ => Err(ParseError(format!("unknown cost model: {s}"))),
+        }
+    }
+}
+
+impl std::fmt::Display for CostModelKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ChunkCount => write!(f, "chunk_count"),
+            Self::RowCount => write!(f, "row_count"),
+            Self::ByteSize => write!(f, "byte_size"),
+        }
+    }
+}
+
+/// Level of detail of [pruning reports](crate::pruning::PruningReport).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PruningReportVerbosity {
+    /// Do not record pruning reports.
+    #[default]
+    Off,
+
+    /// Number of chunks per pruning outcome.
+    Summary,
+
+    /// Pruning outcome of every single chunk.
+    Full,
+}
+
+impl PruningReportVerbosity {
+    /// Returns `true` if reports should be recorded at all.
+    pub fn enabled(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+}
+
+impl FromStr for PruningReportVerbosity {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Self::Off),
+            "summary" => Ok(Self::Summary),
+            "full" => Ok(Self::Full),
+            _ => Err(ParseError(format!("unknown pruning report verbosity: {s}"))),
+        }
+    }
+}
+
+impl std::fmt::Display for PruningReportVerbosity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Summary => write!(f, "summary"),
+            Self::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// Fraction of the data that is scanned, see [`IoxConfigExt::sample_fraction`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleFraction(f64);
+
+impl SampleFraction {
+    /// Scan all data.
+    pub const ALL: Self = Self(1.0);
+
+    /// Create new fraction.
+    ///
+    /// Returns `None` if `fraction` is not within `(0, 1]`.
+    pub fn new(fraction: f64) -> Option<Self> {
+        (fraction > 0.0 && fraction <= 1.0).then_some(Self(fraction))
+    }
+
+    /// Get the fraction as a float.
+    pub fn get(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns `true` if all data is scanned, i.e. sampling is disabled.
+    pub fn is_all(&self) -> bool {
+        self.0 >= 1.0
+    }
+}
+
+impl Default for SampleFraction {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl FromStr for SampleFraction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fraction = f64::from_str(s).map_err(|e| ParseError(e.to_string()))?;
+        Self::new(fraction)
+            .ok_or_else(|| ParseError(format!("sample fraction must be within (0, 1]: {s}")))
+    }
+}
+
+impl std::fmt::Display for SampleFraction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cost_model_kind_roundtrip() {
+        for kind in [
+            CostModelKind::ChunkCount,
+            CostModelKind::RowCount,
+            CostModelKind::ByteSize,
+        ] {
+            assert_eq!(CostModelKind::from_str(&kind.to_string()).unwrap(), kind);
+        }
+        assert!(CostModelKind::from_str("foo").is_err());
+    }
+
+    #[test]
+    fn test_pruning_report_verbosity_roundtrip() {
+        for verbosity in [
+            PruningReportVerbosity::Off,
+            PruningReportVerbosity::Summary,
+            PruningReportVerbosity::Full,
+        ] {
+            assert_eq!(
+                PruningReportVerbosity::from_str(&verbosity.to_string()).unwrap(),
+                verbosity
+            );
+        }
+        assert!(PruningReportVerbosity::from_str("foo").is_err());
+    }
+
+    #[test]
+    fn test_sample_fraction_roundtrip() {
+        for fraction in [SampleFraction::ALL, SampleFraction::new(0.25).unwrap()] {
+            assert_eq!(
+                SampleFraction::from_str(&fraction.to_string()).unwrap(),
+                fraction
+            );
+        }
+        assert!(SampleFraction::ALL.is_all());
+        assert!(!SampleFraction::new(0.25).unwrap().is_all());
+
+        for s in ["0", "-0.5", "1.5", "NaN", "foo"] {
+            assert!(SampleFraction::from_str(s).is_err(), "{s}");
+        }
+    }
+}
@@ -1,16 +1,21 @@
 //! This module handles the manipulation / execution of storage
 //! plans. This is currently implemented using DataFusion, and this
 //! interface abstracts away many of the details
+pub mod admission;
 pub(crate) mod context;
 pub mod field;
 pub mod fieldlist;
 pub mod gapfill;
+pub mod holt_winters;
 mod non_null_checker;
 pub mod query_tracing;
+pub mod result_cache;
+pub mod scan_budget;
 mod schema_pivot;
 pub mod seriesset;
 pub(crate) mod split;
 pub mod stringset;
+pub mod warnings;
 use datafusion_util::config::register_iox_object_store;
 use executor::DedicatedExecutor;
 use metric::Registry;
@@ -34,7 +39,12 @@ use datafusion::{
 pub use context::{IOxSessionConfig, IOxSessionContext, SessionContextIOxExt};
 use schema_pivot::SchemaPivotNode;
 
-use self::{non_null_checker::NonNullCheckerNode, split::StreamSplitNode};
+use self::{
+    admission::{AdmissionController, AdmissionLimits},
+    non_null_checker::NonNullCheckerNode,
+    result_cache::QueryResultCache,
+    split::StreamSplitNode,
+};
 
 /// Configuration for an Executor
 #[derive(Debug, Clone)]
@@ -121,6 +131,12 @@ pub struct Executor {
     /// The DataFusion [RuntimeEnv] (including memory manager and disk
     /// manager) used for all executions
     runtime: Arc<RuntimeEnv>,
+
+    /// Optional cache for query results, see [`with_result_cache`](Self::with_result_cache).
+    result_cache: Option<Arc<QueryResultCache>>,
+
+    /// Optional admission control, see [`with_admission_control`](Self::with_admission_control).
+    admission_controller: Option<Arc<AdmissionController>>,
 }
 
 impl Display for Executor {
@@ -203,9 +219,34 @@ impl Executor {
             executors,
             config,
             runtime,
+            result_cache: None,
+            admission_controller: None,
         }
     }
 
+    /// Enable caching of query results of up to `max_bytes`.
+    ///
+    /// The cache is only consulted by [`IOxSessionContext::execute_stream_cached`].
+    pub fn with_result_cache(mut self, max_bytes: usize) -> Self {
+        self.result_cache = Some(Arc::new(QueryResultCache::new(
+            max_bytes,
+            &self.config.metric_registry,
+        )));
+        self
+    }
+
+    /// Limit the number of concurrently running queries per [priority class](admission::QueryPriority).
+    ///
+    /// Queries that exceed the limits are queued and admitted in priority order. Queries are only subject to admission
+    /// control if they call [`IOxSessionContext::admit`].
+    ///
+    /// # Panic
+    /// Panics if any of the limits is zero.
+    pub fn with_admission_control(mut self, limits: AdmissionLimits) -> Self {
+        self.admission_controller = Some(Arc::new(AdmissionController::new(limits)));
+        self
+    }
+
     /// Return a new execution config, suitable for executing a new query or system task.
     ///
     /// Note that this context (and all its clones) will be shut down once `Executor` is dropped.
@@ -213,6 +254,8 @@ impl Executor {
         let exec = self.executor(executor_type).clone();
         IOxSessionConfig::new(exec, Arc::clone(&self.runtime))
             .with_target_partitions(self.config.target_query_partitions)
+            .with_result_cache(self.result_cache.clone())
+            .with_admission_controller(self.admission_controller.clone())
     }
 
     /// Create a new execution context, suitable for executing a new query or system task
@@ -374,12 +417,17 @@ mod tests {
     use datafusion::{
         datasource::{provider_as_source, MemTable},
         logical_expr::LogicalPlanBuilder,
+        physical_plan::{empty::EmptyExec, ExecutionPlan},
     };
+    use futures::{StreamExt, TryStreamExt};
     use stringset::StringSet;
 
     use super::*;
+    use crate::exec::admission::QueryPriority;
     use crate::exec::stringset::StringSetRef;
     use crate::plan::stringset::StringSetPlan;
+    use crate::provider::chunks_to_physical_nodes;
+    use crate::test::TestChunk;
     use arrow::record_batch::RecordBatch;
 
     #[tokio::test]
@@ -540,6 +588,114 @@ mod tests {
         assert_eq!(results, to_set(&["f1", "f2"]));
     }
 
+    #[tokio::test]
+    async fn executor_execute_stream_cached() {
+        let chunk = Arc::new(
+            TestChunk::new("t")
+                .with_tag_column("tag")
+                .with_one_row_of_data(),
+        ) as _;
+        let schema = TestChunk::new("t")
+            .with_tag_column("tag")
+            .schema()
+            .as_arrow();
+        let plan = chunks_to_physical_nodes(&schema, None, vec![chunk], 1);
+
+        let exec = Executor::new_testing().with_result_cache(1024 * 1024);
+        let result_cache = exec.result_cache.clone().unwrap();
+        let ctx = exec.new_context(ExecutorType::Query);
+
+        // results of streams that are dropped early are not cached
+        let mut stream = ctx.execute_stream_cached(Arc::clone(&plan)).await.unwrap();
+        stream.next().await.unwrap().unwrap();
+        drop(stream);
+        assert!(result_cache.is_empty());
+
+        let stream = ctx.execute_stream_cached(Arc::clone(&plan)).await.unwrap();
+        let batches1 = stream.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(result_cache.len(), 1);
+
+        let stream = ctx.execute_stream_cached(plan).await.unwrap();
+        let batches2 = stream.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(result_cache.len(), 1);
+        assert_eq!(batches1, batches2);
+
+        // caching is opt-in
+        let exec = Executor::new_testing();
+        assert!(exec.result_cache.is_none());
+    }
+
+    #[tokio::test]
+    async fn executor_cancel_run() {
+        let exec = Executor::new_testing();
+        let ctx = exec.new_context(ExecutorType::Query);
+        let child_ctx = ctx.child_ctx("child");
+
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let barrier_captured = Arc::clone(&barrier);
+        let fut = child_ctx.run(async move {
+            barrier_captured.wait().await;
+            futures::future::pending::<datafusion::error::Result<()>>().await
+        });
+        let handle = tokio::spawn(fut);
+
+        // wait until task is running
+        barrier.wait().await;
+        assert_eq!(exec.executor(ExecutorType::Query).tasks(), 1);
+
+        ctx.cancellation_token().cancel();
+        let err = handle.await.unwrap().unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: query cancelled");
+    }
+
+    #[tokio::test]
+    async fn executor_admission_control() {
+        let exec = Executor::new_testing().with_admission_control(AdmissionLimits {
+            total: 1,
+            interactive: 1,
+            batch: 1,
+            background: 1,
+        });
+        let controller = exec.admission_controller.clone().unwrap();
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Utf8, true)]));
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(EmptyExec::new(false, schema));
+
+        let ctx = exec
+            .new_context(ExecutorType::Query)
+            .with_priority(QueryPriority::Batch);
+        let permit = ctx.admit().await.unwrap().unwrap();
+        assert_eq!(controller.running(), [0, 1, 0]);
+
+        // an admitted query may execute any number of plans
+        ctx.collect(Arc::clone(&plan)).await.unwrap();
+        ctx.collect(Arc::clone(&plan)).await.unwrap();
+        assert_eq!(controller.running(), [0, 1, 0]);
+
+        // second query has to wait until the first permit is dropped
+        let ctx2 = exec.new_context(ExecutorType::Query);
+        let mut fut = Box::pin(ctx2.admit());
+        assert!(futures::poll!(fut.as_mut()).is_pending());
+        assert_eq!(controller.queued(), [1, 0, 0]);
+
+        drop(permit);
+        let permit = fut.await.unwrap().unwrap();
+        assert_eq!(controller.running(), [1, 0, 0]);
+        drop(permit);
+        assert_eq!(controller.running(), [0, 0, 0]);
+
+        // cancel while waiting
+        let _permit = ctx.admit().await.unwrap().unwrap();
+        let ctx3 = exec.new_context(ExecutorType::Query);
+        ctx3.cancellation_token().cancel();
+        let err = ctx3.admit().await.unwrap_err();
+        assert_eq!(err.to_string(), "Execution error: query cancelled");
+        assert_eq!(controller.queued(), [0, 0, 0]);
+
+        // without admission control, queries are admitted right away
+        let ctx = Executor::new_testing().new_context(ExecutorType::Query);
+        assert!(ctx.admit().await.unwrap().is_none());
+    }
+
     /// return a set for testing
     fn to_set(strs: &[&str]) -> StringSetRef {
         StringSetRef::new(strs.iter().map(|s| s.to_string()).collect::<StringSet>())
@@ -0,0 +1,395 @@
+//! Priority classes and admission control for queries.
+//!
+//! Queries are tagged with a [`QueryPriority`]. Every priority class has its own concurrency limit and all classes
+//! share a total limit. If a query cannot be admitted right away it is queued. Whenever a query finishes, queued
+//! queries are admitted in priority order (FIFO within the same class), so long-running analytical scans cannot starve
+//! interactive (e.g. dashboard) queries.
+use std::{collections::VecDeque, fmt::Display, str::FromStr, sync::Arc};
+
+use parking_lot::Mutex;
+use tokio::sync::oneshot;
+
+/// Priority class of a query.
+///
+/// The order of the variants defines the priority: earlier variants are admitted first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum QueryPriority {
+    /// Latency-sensitive queries, e.g. issued by dashboards.
+    #[default]
+    Interactive,
+
+    /// Analytical queries that may take a while.
+    Batch,
+
+    /// Background tasks that should only use spare capacity.
+    Background,
+}
+
+impl QueryPriority {
+    /// All priority classes, ordered from highest to lowest priority.
+    pub const ALL: [Self; 3] = [Self::Interactive, Self::Batch, Self::Background];
+
+    /// Name of the priority class.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Batch => "batch",
+            Self::Background => "background",
+        }
+    }
+
+    fn idx(&self) -> usize {
+        *self as usize
+    }
+}
+
+impl Display for QueryPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for QueryPriority {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!(
+                    "unknown query priority '{s}', expected one of: {}",
+                    Self::ALL.map(|p| p.name()).join(", ")
+                )
+            })
+    }
+}
+
+/// Concurrency limits for [`AdmissionController`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdmissionLimits {
+    /// Maximum number of concurrently running queries, across all priority classes.
+    pub total: usize,
+
+    /// Maximum number of concurrently running [interactive](QueryPriority::Interactive) queries.
+    pub interactive: usize,
+
+    /// Maximum number of concurrently running [batch](QueryPriority::Batch) queries.
+    pub batch: usize,
+
+    /// Maximum number of concurrently running [background](QueryPriority::Background) queries.
+    pub background: usize,
+}
+
+impl AdmissionLimits {
+    fn get(&self, priority: QueryPriority) -> usize {
+        match priority {
+            QueryPriority::Interactive => self.interactive,
+            QueryPriority::Batch => self.batch,
+            QueryPriority::Background => self.background,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    running_total: usize,
+    running: [usize; 3],
+    queued: [VecDeque<oneshot::Sender<()>>; 3],
+}
+
+impl State {
+    fn can_admit(&self, priority: QueryPriority, limits: &AdmissionLimits) -> bool {
+        (self.running_total < limits.total) && (self.running[priority.idx()] < limits.get(priority))
+    }
+
+    fn admit(&mut self, priority: QueryPriority) {
+        self.running_total += 1;
+        self.running[priority.idx()] += 1;
+    }
+
+    fn release(&mut self, priority: QueryPriority) {
+        self.running_total -= 1;
+        self.running[priority.idx()] -= 1;
+    }
+
+    /// Remove waiters that were cancelled.
+    fn prune(&mut self) {
+        for queue in &mut self.queued {
+            queue.retain(|tx| !tx.is_closed());
+        }
+    }
+}
+
+/// Admission control for queries, see [module docs](self).
+#[derive(Debug)]
+pub struct AdmissionController {
+    limits: AdmissionLimits,
+    state: Mutex<State>,
+}
+
+impl AdmissionController {
+    /// Create new controller with the given limits.
+    ///
+    /// # Panic
+    /// Panics if any of the limits is zero.
+    pub fn new(limits: AdmissionLimits) -> Self {
+        assert!(limits.total > 0, "total limit must be positive");
+        for priority in QueryPriority::ALL {
+            assert!(
+                limits.get(priority) > 0,
+                "limit for {priority} must be positive"
+            );
+        }
+
+        Self {
+            limits,
+            state: Default::default(),
+        }
+    }
+
+    /// Wait until a query of the given priority can be admitted.
+    ///
+    /// The query counts as running until the returned [`AdmissionPermit`] is dropped.
+    pub async fn admit(self: &Arc<Self>, priority: QueryPriority) -> AdmissionPermit {
+        let rx = {
+            let mut state = self.state.lock();
+            state.prune();
+
+            // do not overtake queued queries of the same or a higher priority
+            let queued_ahead = QueryPriority::ALL
+                .into_iter()
+                .filter(|p| *p <= priority)
+                .any(|p| !state.queued[p.idx()].is_empty());
+
+            if !queued_ahead && state.can_admit(priority, &self.limits) {
+                state.admit(priority);
+                None
+            } else {
+                let (tx, rx) = oneshot::channel();
+                state.queued[priority.idx()].push_back(tx);
+                Some(rx)
+            }
+        };
+
+        // if we get cancelled while waiting, the permit is dropped w/o being admitted
+        let mut permit = AdmissionPermit {
+            controller: Arc::clone(self),
+            priority,
+            waiting: rx,
+        };
+
+        if let Some(rx) = permit.waiting.as_mut() {
+            rx.await.expect("controller alive");
+            permit.waiting = None;
+        }
+
+        permit
+    }
+
+    /// Number of running queries per priority class, in the order of [`QueryPriority::ALL`].
+    pub fn running(&self) -> [usize; 3] {
+        self.state.lock().running
+    }
+
+    /// Number of queued queries per priority class, in the order of [`QueryPriority::ALL`].
+    pub fn queued(&self) -> [usize; 3] {
+        let mut state = self.state.lock();
+        state.prune();
+        std::array::from_fn(|idx| state.queued[idx].len())
+    }
+
+    fn release(&self, priority: QueryPriority) {
+        let mut state = self.state.lock();
+        state.release(priority);
+        self.wake(&mut state);
+    }
+
+    /// Admit queued queries in priority order.
+    fn wake(&self, state: &mut State) {
+        for priority in QueryPriority::ALL {
+            while state.can_admit(priority, &self.limits) {
+                let Some(tx) = state.queued[priority.idx()].pop_front() else {
+                    break;
+                };
+
+                // count as running BEFORE we send the signal, the permit releases it again if it is dropped before it
+                // observed the signal
+                state.admit(priority);
+                if tx.send(()).is_err() {
+                    // receiver gone (i.e. the waiting query was cancelled)
+                    state.release(priority);
+                }
+            }
+        }
+    }
+}
+
+/// Permit for a running query, see [`AdmissionController::admit`].
+///
+/// Dropping the permit releases the slot.
+#[derive(Debug)]
+pub struct AdmissionPermit {
+    controller: Arc<AdmissionController>,
+    priority: QueryPriority,
+
+    /// Set while the query is still queued.
+    waiting: Option<oneshot::Receiver<()>>,
+}
+
+impl AdmissionPermit {
+    /// Priority class of the admitted query.
+    pub fn priority(&self) -> QueryPriority {
+        self.priority
+    }
+}
+
+impl Drop for AdmissionPermit {
+    fn drop(&mut self) {
+        let admitted = match self.waiting.take() {
+            None => true,
+            Some(mut rx) => {
+                // might have been admitted concurrently
+                rx.close();
+                rx.try_recv().is_ok()
+            }
+        };
+
+        if admitted {
+            self.controller.release(self.priority);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::FutureExt;
+
+    use super::*;
+
+    #[test]
+    fn test_priority_parse_display() {
+        for priority in QueryPriority::ALL {
+            assert_eq!(
+                QueryPriority::from_str(&priority.to_string()).unwrap(),
+                priority
+            );
+        }
+        assert_eq!(
+            QueryPriority::from_str(" Batch ").unwrap(),
+            QueryPriority::Batch
+        );
+        assert_eq!(
+            QueryPriority::from_str("foo").unwrap_err(),
+            "unknown query priority 'foo', expected one of: interactive, batch, background"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_class_limit() {
+        let controller = controller(10, 1);
+
+        let p1 = controller.admit(QueryPriority::Batch).await;
+        let mut f2 = Box::pin(controller.admit(QueryPriority::Batch));
+        assert!(f2.as_mut().now_or_never().is_none());
+
+        // other classes are not affected
+        let _p3 = controller
+            .admit(QueryPriority::Interactive)
+            .now_or_never()
+            .unwrap();
+        assert_eq!(controller.running(), [1, 1, 0]);
+        assert_eq!(controller.queued(), [0, 1, 0]);
+
+        drop(p1);
+        let _p2 = f2.await;
+        assert_eq!(controller.running(), [1, 1, 0]);
+        assert_eq!(controller.queued(), [0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_queue_order() {
+        let controller = controller(1, 1);
+
+        let p1 = controller.admit(QueryPriority::Background).await;
+        let mut f_background = Box::pin(controller.admit(QueryPriority::Background));
+        let mut f_batch = Box::pin(controller.admit(QueryPriority::Batch));
+        let mut f_interactive = Box::pin(controller.admit(QueryPriority::Interactive));
+        assert!(f_background.as_mut().now_or_never().is_none());
+        assert!(f_batch.as_mut().now_or_never().is_none());
+        assert!(f_interactive.as_mut().now_or_never().is_none());
+        assert_eq!(controller.queued(), [1, 1, 1]);
+
+        // interactive goes first
+        drop(p1);
+        assert!(f_background.as_mut().now_or_never().is_none());
+        assert!(f_batch.as_mut().now_or_never().is_none());
+        let p = f_interactive.await;
+
+        // then batch
+        drop(p);
+        assert!(f_background.as_mut().now_or_never().is_none());
+        let p = f_batch.await;
+
+        // then background
+        drop(p);
+        let _p = f_background.await;
+        assert_eq!(controller.running(), [0, 0, 1]);
+        assert_eq!(controller.queued(), [0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_queued() {
+        let controller = controller(1, 1);
+
+        let p1 = controller.admit(QueryPriority::Batch).await;
+        let mut f2 = Box::pin(controller.admit(QueryPriority::Batch));
+        assert!(f2.as_mut().now_or_never().is_none());
+        drop(f2);
+        assert_eq!(controller.queued(), [0, 0, 0]);
+
+        drop(p1);
+        assert_eq!(controller.running(), [0, 0, 0]);
+
+        let _p3 = controller
+            .admit(QueryPriority::Batch)
+            .now_or_never()
+            .unwrap();
+        assert_eq!(controller.running(), [0, 1, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_after_wakeup() {
+        let controller = controller(1, 1);
+
+        let p1 = controller.admit(QueryPriority::Batch).await;
+        let mut f2 = Box::pin(controller.admit(QueryPriority::Batch));
+        assert!(f2.as_mut().now_or_never().is_none());
+
+        // admits f2, but f2 never observes it
+        drop(p1);
+        assert_eq!(controller.running(), [0, 1, 0]);
+        drop(f2);
+        assert_eq!(controller.running(), [0, 0, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "limit for batch must be positive")]
+    fn test_zero_limit() {
+        AdmissionController::new(AdmissionLimits {
+            total: 1,
+            interactive: 1,
+            batch: 0,
+            background: 1,
+        });
+    }
+
+    fn controller(total: usize, per_class: usize) -> Arc<AdmissionController> {
+        Arc::new(AdmissionController::new(AdmissionLimits {
+            total,
+            interactive: per_class,
+            batch: per_class,
+            background: per_class,
+        }))
+    }
+}
@@ -2,11 +2,16 @@
 //! DataFusion
 
 use super::{
+    admission::{AdmissionController, AdmissionPermit, QueryPriority},
     cross_rt_stream::CrossRtStream,
     gapfill::{plan_gap_fill, GapFill},
+    holt_winters::{plan_holt_winters, HoltWinters},
     non_null_checker::NonNullCheckerNode,
+    result_cache::{CachingStream, QueryResultCache, QueryResultCacheKey},
+    scan_budget::ScanBudget,
     seriesset::{series::Either, SeriesSet},
     split::StreamSplitNode,
+    warnings::QueryWarnings,
 };
 use crate::{
     config::IoxConfigExt,
@@ -22,6 +27,7 @@ use crate::{
         split::StreamSplitExec,
         stringset::{IntoStringSet, StringSetRef},
     },
+    frontend::sql::{bind_params, QueryParams},
     logical_optimizer::register_iox_logical_optimizers,
     physical_optimizer::register_iox_physical_optimizers,
     plan::{
@@ -29,6 +35,7 @@ use crate::{
         seriesset::{SeriesSetPlan, SeriesSetPlans},
         stringset::StringSetPlan,
     },
+    pruning::PruningReports,
 };
 use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
@@ -39,10 +46,11 @@ use datafusion::{
         memory_pool::MemoryPool,
         runtime_env::RuntimeEnv,
     },
-    logical_expr::{LogicalPlan, UserDefinedLogicalNode},
+    logical_expr::{LogicalPlan, PlanType, StringifiedPlan, UserDefinedLogicalNode},
     physical_plan::{
-        coalesce_partitions::CoalescePartitionsExec, displayable, stream::RecordBatchStreamAdapter,
-        EmptyRecordBatchStream, ExecutionPlan, RecordBatchStream, SendableRecordBatchStream,
+        coalesce_partitions::CoalescePartitionsExec, displayable, explain::ExplainExec,
+        memory::MemoryStream, stream::RecordBatchStreamAdapter, EmptyRecordBatchStream,
+        ExecutionPlan, RecordBatchStream, SendableRecordBatchStream,
     },
     physical_planner::{DefaultPhysicalPlanner, ExtensionPlanner, PhysicalPlanner},
     prelude::*,
@@ -51,8 +59,12 @@ use datafusion_util::config::{iox_session_config, DEFAULT_CATALOG};
 use executor::DedicatedExecutor;
 use futures::{Stream, StreamExt, TryStreamExt};
 use observability_deps::tracing::{debug, warn};
-use query_functions::{register_scalar_functions, selectors::register_selector_aggregates};
+use query_functions::{
+    register_aggregate_functions, register_scalar_functions, register_window_functions,
+    selectors::register_selector_aggregates,
+};
 use std::{fmt, num::NonZeroUsize, sync::Arc};
+use tokio_util::sync::CancellationToken;
 use trace::{
     ctx::SpanContext,
     span::{MetaValue, Span, SpanExt, SpanRecorder},
@@ -150,6 +162,14 @@ impl ExtensionPlanner for IOxExtensionPlanner {
                 physical_inputs,
             )?;
             Some(Arc::new(gap_fill_exec) as Arc<dyn ExecutionPlan>)
+        } else if let Some(holt_winters) = any.downcast_ref::<HoltWinters>() {
+            let holt_winters_exec = plan_holt_winters(
+                session_state.execution_props(),
+                holt_winters,
+                logical_inputs,
+                physical_inputs,
+            )?;
+            Some(Arc::new(holt_winters_exec) as Arc<dyn ExecutionPlan>)
         } else {
             None
         };
@@ -176,6 +196,15 @@ pub struct IOxSessionConfig {
 
     /// Span context from which to create spans for this query
     span_ctx: Option<SpanContext>,
+
+    /// Optional query result cache
+    result_cache: Option<Arc<QueryResultCache>>,
+
+    /// Optional admission control
+    admission_controller: Option<Arc<AdmissionController>>,
+
+    /// Optional limit for the number of bytes scanned by this query
+    max_scanned_bytes: Option<u64>,
 }
 
 impl fmt::Debug for IOxSessionConfig {
@@ -198,6 +227,9 @@ impl IOxSessionConfig {
             runtime,
             default_catalog: None,
             span_ctx: None,
+            result_cache: None,
+            admission_controller: None,
+            max_scanned_bytes: None,
         }
     }
 
@@ -222,6 +254,35 @@ impl IOxSessionConfig {
         Self { span_ctx, ..self }
     }
 
+    /// Set the query result cache that is used by [`IOxSessionContext::execute_stream_cached`].
+    pub fn with_result_cache(self, result_cache: Option<Arc<QueryResultCache>>) -> Self {
+        Self {
+            result_cache,
+            ..self
+        }
+    }
+
+    /// Set the admission controller that limits the number of concurrently running queries.
+    pub fn with_admission_controller(
+        self,
+        admission_controller: Option<Arc<AdmissionController>>,
+    ) -> Self {
+        Self {
+            admission_controller,
+            ..self
+        }
+    }
+
+    /// Limit the (estimated) number of bytes that this query may scan.
+    ///
+    /// See [`ScanBudget`].
+    pub fn with_max_scanned_bytes(self, max_scanned_bytes: Option<u64>) -> Self {
+        Self {
+            max_scanned_bytes,
+            ..self
+        }
+    }
+
     /// Set DataFusion [config option].
     ///
     /// May be used to set [IOx-specific] option as well.
@@ -248,10 +309,32 @@ impl IOxSessionConfig {
         let recorder = SpanRecorder::new(maybe_span);
 
         // attach span to DataFusion session
-        let session_config = self
+        let mut session_config = self
             .session_config
             .with_extension(Arc::new(recorder.span().cloned()));
 
+        // attach result cache to DataFusion session
+        if let Some(result_cache) = self.result_cache {
+            session_config = session_config.with_extension(result_cache);
+        }
+
+        // attach admission control to DataFusion session
+        if let Some(admission_controller) = self.admission_controller {
+            session_config = session_config.with_extension(admission_controller);
+        }
+
+        // attach scan budget to DataFusion session
+        if let Some(max_scanned_bytes) = self.max_scanned_bytes {
+            session_config =
+                session_config.with_extension(Arc::new(ScanBudget::new(max_scanned_bytes)));
+        }
+
+        // collect pruning reports of this query
+        session_config = session_config.with_extension(Arc::new(PruningReports::default()));
+
+        // collect warnings of this query
+        session_config = session_config.with_extension(Arc::new(QueryWarnings::default()));
+
         let state = SessionState::with_config_rt(session_config, self.runtime)
             .with_query_planner(Arc::new(IOxQueryPlanner {}));
         let state = register_iox_physical_optimizers(state);
@@ -260,11 +343,20 @@ impl IOxSessionConfig {
         let inner = SessionContext::with_state(state);
         register_selector_aggregates(&inner);
         register_scalar_functions(&inner);
+        register_aggregate_functions(&inner);
+        register_window_functions(&inner);
         if let Some(default_catalog) = self.default_catalog {
             inner.register_catalog(DEFAULT_CATALOG, default_catalog);
         }
 
-        IOxSessionContext::new(inner, self.exec, recorder)
+        IOxSessionContext::new(
+            inner,
+            self.exec,
+            recorder,
+            CancellationToken::new(),
+            QueryPriority::default(),
+            None,
+        )
     }
 }
 
@@ -293,6 +385,15 @@ pub struct IOxSessionContext {
 
     /// Span context from which to create spans for this query
     recorder: SpanRecorder,
+
+    /// Cancels all work that was spawned by this context (and its children).
+    cancel: CancellationToken,
+
+    /// Priority class used for admission control.
+    priority: QueryPriority,
+
+    /// Who issued this query, e.g. the client address.
+    issuer: Option<Arc<str>>,
 }
 
 impl fmt::Debug for IOxSessionContext {
@@ -301,6 +402,9 @@ impl fmt::Debug for IOxSessionContext {
             .field("inner", &"<DataFusion ExecutionContext>")
             .field("exec", &self.exec)
             .field("recorder", &self.recorder)
+            .field("cancelled", &self.cancel.is_cancelled())
+            .field("priority", &self.priority)
+            .field("issuer", &self.issuer)
             .finish()
     }
 }
@@ -315,6 +419,9 @@ impl IOxSessionContext {
             inner: SessionContext::default(),
             exec: DedicatedExecutor::new_testing(),
             recorder: SpanRecorder::default(),
+            cancel: CancellationToken::new(),
+            priority: QueryPriority::default(),
+            issuer: None,
         }
     }
 
@@ -323,11 +430,17 @@ impl IOxSessionContext {
         inner: SessionContext,
         exec: DedicatedExecutor,
         recorder: SpanRecorder,
+        cancel: CancellationToken,
+        priority: QueryPriority,
+        issuer: Option<Arc<str>>,
     ) -> Self {
         Self {
             inner,
             exec,
             recorder,
+            cancel,
+            priority,
+            issuer,
         }
     }
 
@@ -336,6 +449,39 @@ impl IOxSessionContext {
         &self.inner
     }
 
+    /// Token that cancels all work spawned by this context, e.g. when the client that issued the query disconnected.
+    ///
+    /// The token is shared with all [child contexts](Self::child_ctx). Cancelled futures return an error and
+    /// cancelled streams end with an error.
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Set the priority class of this query.
+    ///
+    /// The priority is inherited by all [child contexts](Self::child_ctx) and is used for admission control, see
+    /// [`Executor::with_admission_control`](crate::exec::Executor::with_admission_control).
+    pub fn with_priority(self, priority: QueryPriority) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Priority class of this query.
+    pub fn priority(&self) -> QueryPriority {
+        self.priority
+    }
+
+    /// Set who issued this query, e.g. the client address.
+    ///
+    /// The issuer is inherited by all [child contexts](Self::child_ctx) and is recorded in the query log.
+    pub fn with_issuer(self, issuer: Option<Arc<str>>) -> Self {
+        Self { issuer, ..self }
+    }
+
+    /// Who issued this query, if known.
+    pub fn issuer(&self) -> Option<&Arc<str>> {
+        self.issuer.as_ref()
+    }
+
     /// Plan a SQL statement. This assumes that any tables referenced
     /// in the SQL have been registered with this context. Use
     /// `create_physical_plan` to actually execute the query.
@@ -346,6 +492,19 @@ impl IOxSessionContext {
         ctx.inner.state().create_logical_plan(sql).await
     }
 
+    /// Plan a SQL query that may contain placeholders (`$1`, `$name`) and substitute them with
+    /// the given `params`.
+    ///
+    /// Fails if any placeholder has no value.
+    pub async fn sql_to_logical_plan_with_params(
+        &self,
+        sql: &str,
+        params: &QueryParams,
+    ) -> Result<LogicalPlan> {
+        let logical_plan = self.sql_to_logical_plan(sql).await?;
+        bind_params(&logical_plan, params)
+    }
+
     /// Create a logical plan that reads a single [`RecordBatch`]. Use
     /// `create_physical_plan` to actually execute the query.
     pub fn batch_to_logical_plan(&self, batch: RecordBatch) -> Result<LogicalPlan> {
@@ -363,6 +522,19 @@ impl IOxSessionContext {
         ctx.create_physical_plan(&logical_plan).await
     }
 
+    /// Same as [`sql_to_physical_plan`](Self::sql_to_physical_plan) but substitutes the
+    /// placeholders within `sql` with `params`.
+    pub async fn sql_to_physical_plan_with_params(
+        &self,
+        sql: &str,
+        params: &QueryParams,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let logical_plan = self.sql_to_logical_plan_with_params(sql, params).await?;
+
+        let ctx = self.child_ctx("sql_to_physical_plan_with_params");
+        ctx.create_physical_plan(&logical_plan).await
+    }
+
     /// Prepare (optimize + plan) a pre-created [`LogicalPlan`] for execution
     pub async fn create_physical_plan(
         &self,
@@ -371,12 +543,63 @@ impl IOxSessionContext {
         let mut ctx = self.child_ctx("create_physical_plan");
         debug!(text=%logical_plan.display_indent_schema(), "create_physical_plan: initial plan");
         let physical_plan = ctx.inner.state().create_physical_plan(logical_plan).await?;
+        let physical_plan = self.explain_pruning_reports(physical_plan);
 
         ctx.recorder.event("physical plan");
         debug!(text=%displayable(physical_plan.as_ref()).indent(false), "create_physical_plan: plan to run");
         Ok(physical_plan)
     }
 
+    /// Pruning reports of the tables that were scanned by this query so far.
+    ///
+    /// Returns `None` if [`pruning_report`](IoxConfigExt::pruning_report) is disabled.
+    pub fn pruning_reports(&self) -> Option<Arc<PruningReports>> {
+        self.inner.state().pruning_reports()
+    }
+
+    /// Collector for the warnings of this query.
+    ///
+    /// Warnings are added during planning, e.g. if a data source could not be contacted and the result is
+    /// incomplete, and should be returned to the client alongside the result.
+    pub fn query_warnings(&self) -> Option<Arc<QueryWarnings>> {
+        self.inner.state().query_warnings()
+    }
+
+    /// Add the [pruning reports](Self::pruning_reports) to the output of `EXPLAIN VERBOSE`.
+    fn explain_pruning_reports(&self, plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let Some(explain) = plan.as_any().downcast_ref::<ExplainExec>() else {
+            return plan;
+        };
+        let state = self.inner.state();
+        let verbosity = state
+            .config()
+            .options()
+            .extensions
+            .get::<IoxConfigExt>()
+            .cloned()
+            .unwrap_or_default()
+            .pruning_report;
+        let Some(report) = state
+            .pruning_reports()
+            .and_then(|reports| reports.display(verbosity))
+        else {
+            return plan;
+        };
+
+        let mut stringified_plans = explain.stringified_plans().to_vec();
+        stringified_plans.push(StringifiedPlan::new(
+            PlanType::OptimizedPhysicalPlan {
+                optimizer_name: "pruning_report".to_owned(),
+            },
+            report,
+        ));
+        Arc::new(ExplainExec::new(
+            explain.schema(),
+            stringified_plans,
+            explain.verbose(),
+        ))
+    }
+
     /// Executes the logical plan using DataFusion on a separate
     /// thread pool and produces RecordBatches
     pub async fn collect(&self, physical_plan: Arc<dyn ExecutionPlan>) -> Result<Vec<RecordBatch>> {
@@ -395,6 +618,28 @@ impl IOxSessionContext {
         .await
     }
 
+    /// Wait until this query is admitted, if admission control is configured (see
+    /// [`Executor::with_admission_control`](crate::exec::Executor::with_admission_control)).
+    ///
+    /// The query counts as running until the returned permit is dropped. This must be called once per query and not
+    /// for every plan that the query executes, otherwise a query may wait for itself.
+    pub async fn admit(&self) -> Result<Option<AdmissionPermit>> {
+        let Some(admission_controller) = self
+            .inner
+            .state()
+            .config()
+            .get_extension::<AdmissionController>()
+        else {
+            return Ok(None);
+        };
+
+        tokio::select! {
+            biased;
+            _ = self.cancel.cancelled() => Err(cancelled_error()),
+            permit = admission_controller.admit(self.priority) => Ok(Some(permit)),
+        }
+    }
+
     /// Executes the physical plan and produces a
     /// `SendableRecordBatchStream` to stream over the result that
     /// iterates over the results. The creation of the stream is
@@ -419,6 +664,39 @@ impl IOxSessionContext {
         }
     }
 
+    /// Same as [`execute_stream`](Self::execute_stream) but consults the query result cache (if configured).
+    ///
+    /// On a cache miss, the results are streamed as they are produced and cached once the stream completed successfully.
+    /// Plans that read data from sources other than IOx chunks are never cached.
+    pub async fn execute_stream_cached(
+        &self,
+        physical_plan: Arc<dyn ExecutionPlan>,
+    ) -> Result<SendableRecordBatchStream> {
+        let Some(result_cache) = self
+            .inner
+            .state()
+            .config()
+            .get_extension::<QueryResultCache>()
+        else {
+            return self.execute_stream(physical_plan).await;
+        };
+        let Some(key) = QueryResultCacheKey::try_new(physical_plan.as_ref()) else {
+            return self.execute_stream(physical_plan).await;
+        };
+
+        if let Some(batches) = result_cache.get(&key) {
+            debug!("Query result cache hit");
+            return Ok(Box::pin(MemoryStream::try_new(
+                batches,
+                physical_plan.schema(),
+                None,
+            )?));
+        }
+
+        let stream = self.execute_stream(physical_plan).await?;
+        Ok(Box::pin(CachingStream::new(stream, result_cache, key)))
+    }
+
     /// Executes a single partition of a physical plan and produces a
     /// `SendableRecordBatchStream` to stream over the result that
     /// iterates over the results. The creation of the stream is
@@ -446,6 +724,7 @@ impl IOxSessionContext {
         // dedicated executor because otherwise this may block the top-level tokio/tonic runtime which may lead to
         // requests timetouts (either for new requests, metrics or even for HTTP2 pings on the active connection).
         let schema = stream.schema();
+        let stream = cancellable_stream(stream, self.cancel.clone());
         let stream = CrossRtStream::new_with_df_error_stream(stream, self.exec.clone());
         let stream = RecordBatchStreamAdapter::new(schema, stream);
         Ok(Box::pin(stream))
@@ -474,13 +753,15 @@ impl IOxSessionContext {
         // Run the plans in parallel
         let ctx = self.child_ctx("to_series_set");
         let exec = self.exec.clone();
+        let cancel = self.cancel.clone();
         let data = futures::stream::iter(plans)
             .then(move |plan| {
                 let ctx = ctx.child_ctx("for plan");
                 let exec = exec.clone();
+                let cancel = cancel.clone();
 
                 async move {
-                    let stream = Self::run_inner(exec.clone(), async move {
+                    let stream = Self::run_inner(exec.clone(), cancel, async move {
                         let SeriesSetPlan {
                             table_name,
                             plan,
@@ -637,20 +918,30 @@ impl IOxSessionContext {
         Fut: std::future::Future<Output = Result<T>> + Send + 'static,
         T: Send + 'static,
     {
-        Self::run_inner(self.exec.clone(), fut).await
+        Self::run_inner(self.exec.clone(), self.cancel.clone(), fut).await
     }
 
-    async fn run_inner<Fut, T>(exec: DedicatedExecutor, fut: Fut) -> Result<T>
+    async fn run_inner<Fut, T>(
+        exec: DedicatedExecutor,
+        cancel: CancellationToken,
+        fut: Fut,
+    ) -> Result<T>
     where
         Fut: std::future::Future<Output = Result<T>> + Send + 'static,
         T: Send + 'static,
     {
-        exec.spawn(fut).await.unwrap_or_else(|e| {
-            Err(Error::Context(
-                "Join Error".to_string(),
-                Box::new(Error::External(Box::new(e))),
-            ))
-        })
+        // dropping the job cancels the spawned task
+        let job = exec.spawn(fut);
+
+        tokio::select! {
+            _ = cancel.cancelled() => Err(cancelled_error()),
+            res = job => res.unwrap_or_else(|e| {
+                Err(Error::Context(
+                    "Join Error".to_string(),
+                    Box::new(Error::External(Box::new(e))),
+                ))
+            }),
+        }
     }
 
     /// Returns a IOxSessionContext with a SpanRecorder that is a child of the current
@@ -659,6 +950,9 @@ impl IOxSessionContext {
             self.inner.clone(),
             self.exec.clone(),
             self.recorder.child(name),
+            self.cancel.clone(),
+            self.priority,
+            self.issuer.clone(),
         )
     }
 
@@ -695,6 +989,19 @@ pub trait SessionContextIOxExt {
 
     /// Get span context
     fn span_ctx(&self) -> Option<SpanContext>;
+
+    /// Get the collector for the [pruning reports](crate::pruning::PruningReport) of the current query.
+    ///
+    /// Returns `None` if [`pruning_report`](IoxConfigExt::pruning_report) is disabled.
+    fn pruning_reports(&self) -> Option<Arc<PruningReports>>;
+
+    /// Get the collector for the [warnings](QueryWarnings) of the current query.
+    fn query_warnings(&self) -> Option<Arc<QueryWarnings>>;
+
+    /// Get the [scan budget](ScanBudget) of the current query.
+    ///
+    /// Returns `None` if the query is not limited.
+    fn scan_budget(&self) -> Option<Arc<ScanBudget>>;
 }
 
 impl SessionContextIOxExt for SessionState {
@@ -709,4 +1016,48 @@ impl SessionContextIOxExt for SessionState {
             .get_extension::<Option<Span>>()
             .and_then(|span| span.as_ref().as_ref().map(|span| span.ctx.clone()))
     }
+
+    fn pruning_reports(&self) -> Option<Arc<PruningReports>> {
+        let enabled = self
+            .config()
+            .options()
+            .extensions
+            .get::<IoxConfigExt>()
+            .map(|ext| ext.pruning_report.enabled())
+            .unwrap_or_default();
+        if !enabled {
+            return None;
+        }
+
+        self.config().get_extension::<PruningReports>()
+    }
+
+    fn query_warnings(&self) -> Option<Arc<QueryWarnings>> {
+        self.config().get_extension::<QueryWarnings>()
+    }
+
+    fn scan_budget(&self) -> Option<Arc<ScanBudget>> {
+        self.config().get_extension::<ScanBudget>()
+    }
+}
+
+/// Error that is returned when work is cancelled via [`IOxSessionContext::cancellation_token`].
+fn cancelled_error() -> Error {
+    Error::Execution("query cancelled".to_owned())
+}
+
+/// Stops polling `stream` (and thus drops it) once `cancel` is triggered and emits a final error in this case.
+fn cancellable_stream<S, T>(stream: S, cancel: CancellationToken) -> impl Stream<Item = Result<T>>
+where
+    S: Stream<Item = Result<T>>,
+{
+    let cancel_captured = cancel.clone();
+    stream
+        .take_until(cancel.cancelled_owned())
+        .chain(futures::stream::once(async move {
+            cancel_captured
+                .is_cancelled()
+                .then(|| Err(cancelled_error()))
+        }))
+        .filter_map(futures::future::ready)
 }
@@ -0,0 +1,334 @@
+//! The Holt-Winters forecasting algorithm.
+//!
+//! This follows the model used by InfluxDB 1.x: a triple exponential smoothing
+//! with a damped trend and multiplicative seasonality, whose smoothing parameters
+//! are fitted to the input by minimizing the sum of squared errors of the
+//! one-step-ahead predictions with the Nelder-Mead method.
+
+/// Parameters of a forecast of a single series.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub(super) struct ForecastParams {
+    /// The interval between two points of the series, in nanoseconds.
+    pub(super) interval: i64,
+    /// The number of points to forecast.
+    pub(super) h: usize,
+    /// The length of a season, in points. Values below 2 disable seasonality.
+    pub(super) m: usize,
+    /// Also return the fitted values for the input points.
+    pub(super) include_fit: bool,
+}
+
+/// Forecast the series of `(timestamp, value)` points, which must be sorted by time.
+///
+/// Returns no points if the series is too short to fit the model, that is shorter
+/// than two points or one season.
+pub(super) fn forecast(points: &[(i64, f64)], params: ForecastParams) -> Vec<(i64, f64)> {
+    let ForecastParams {
+        interval,
+        h,
+        m,
+        include_fit,
+    } = params;
+    let seasonal = m >= 2;
+
+    if points.len() < 2 || (seasonal && points.len() < m) || h == 0 || interval <= 0 {
+        return vec![];
+    }
+
+    // Place the values on a regular grid, using NaN for missing intervals.
+    let start = points[0].0;
+    let mut y = vec![points[0].1];
+    let mut t = start;
+    for &(time, value) in &points[1..] {
+        if time <= t {
+            // drop values that fall into an interval that already has a value
+            continue;
+        }
+        t += interval;
+        while t < time {
+            y.push(f64::NAN);
+            t += interval;
+        }
+        y.push(value);
+    }
+    if y.len() < 2 || (seasonal && y.len() < m) {
+        return vec![];
+    }
+
+    let model = Model::new(&y, if seasonal { m } else { 0 });
+    let smoothing = nelder_mead(|p| model.sse(p), [0.5, 0.1, 0.1, 0.9]);
+    let Some(predicted) = model.predict(smoothing, h) else {
+        return vec![];
+    };
+
+    let n = y.len();
+    let skip = if include_fit { 0 } else { n };
+    predicted
+        .into_iter()
+        .enumerate()
+        .skip(skip)
+        .filter(|(_, v)| !v.is_nan())
+        .map(|(i, v)| (start + interval * i as i64, v))
+        .collect()
+}
+
+/// The Holt-Winters model of a series with the initial level, trend and seasonal
+/// components estimated from its first values.
+#[derive(Debug)]
+struct Model<'a> {
+    y: &'a [f64],
+    level: f64,
+    trend: f64,
+    season: Vec<f64>,
+}
+
+impl<'a> Model<'a> {
+    fn new(y: &'a [f64], m: usize) -> Self {
+        let (level, trend, season) = if m == 0 {
+            let trend = if y[1].is_nan() { 0.0 } else { y[1] - y[0] };
+            (y[0], trend, vec![])
+        } else {
+            let level = y[..m].iter().filter(|v| !v.is_nan()).sum::<f64>() / m as f64;
+            let trend = (0..m)
+                .filter(|i| m + i < y.len() && !y[*i].is_nan() && !y[m + i].is_nan())
+                .map(|i| (y[m + i] - y[i]) / (m * m) as f64)
+                .sum();
+            let season = y[..m]
+                .iter()
+                .map(|v| if v.is_nan() { 0.0 } else { v / level })
+                .collect();
+            (level, trend, season)
+        };
+        Self {
+            y,
+            level,
+            trend,
+            season,
+        }
+    }
+
+    /// Returns the one-step-ahead predictions of all values of the series followed
+    /// by `h` forecasted values, or `None` if the smoothing parameters
+    /// `[alpha, beta, gamma, phi]` are outside of `[0, 1]`.
+    fn predict(&self, smoothing: [f64; 4], h: usize) -> Option<Vec<f64>> {
+        if smoothing.iter().any(|p| !(0.0..=1.0).contains(p)) {
+            return None;
+        }
+        let [alpha, beta, gamma, phi] = smoothing;
+        let m = self.season.len();
+        let season_at = |season: &[f64], t: usize| if m == 0 { 1.0 } else { season[t % m] };
+
+        let mut level = self.level;
+        let mut trend = self.trend;
+        let mut season = self.season.clone();
+        let mut predicted = Vec::with_capacity(self.y.len() + h);
+
+        // the initial components describe the first value, so its "prediction" is exact
+        predicted.push(self.y[0]);
+        for (t, &y) in self.y.iter().enumerate().skip(1) {
+            let s = season_at(&season, t);
+            let base = level + phi * trend;
+            let prediction = base * s;
+            predicted.push(prediction);
+
+            // a missing value is replaced by its prediction
+            let y = if y.is_nan() { prediction } else { y };
+            let next_level = alpha * y / s + (1.0 - alpha) * base;
+            trend = beta * (next_level - level) + (1.0 - beta) * phi * trend;
+            if m > 0 {
+                season[t % m] = gamma * y / base + (1.0 - gamma) * s;
+            }
+            level = next_level;
+        }
+
+        let mut damping = 0.0;
+        for k in 1..=h {
+            damping += phi.powi(k as i32);
+            let s = season_at(&season, self.y.len() + k - 1);
+            predicted.push((level + damping * trend) * s);
+        }
+
+        Some(predicted)
+    }
+
+    /// The sum of squared errors of the one-step-ahead predictions of the present values.
+    fn sse(&self, smoothing: [f64; 4]) -> f64 {
+        let Some(predicted) = self.predict(smoothing, 0) else {
+            return f64::INFINITY;
+        };
+        let mut sse = 0.0;
+        for (y, p) in self.y.iter().zip(predicted) {
+            if y.is_nan() {
+                continue;
+            }
+            if p.is_nan() {
+                return f64::INFINITY;
+            }
+            sse += (p - y) * (p - y);
+        }
+        sse
+    }
+}
+
+/// Minimize `f` with the Nelder-Mead method, starting from `start`.
+fn nelder_mead<const N: usize>(f: impl Fn([f64; N]) -> f64, start: [f64; N]) -> [f64; N] {
+    const MAX_ITERATIONS: usize = 1000;
+    const EPSILON: f64 = 1e-6;
+    const STEP: f64 = 0.1;
+
+    fn lerp<const N: usize>(from: &[f64; N], to: &[f64; N], by: f64) -> [f64; N] {
+        let mut p = *from;
+        for (p, to) in p.iter_mut().zip(to) {
+            *p += by * (to - *p);
+        }
+        p
+    }
+
+    let mut simplex: Vec<([f64; N], f64)> = (0..=N)
+        .map(|i| {
+            let mut p = start;
+            if i < N {
+                // step towards the middle of the unit interval to stay in bounds
+                p[i] += if p[i] > 0.5 { -STEP } else { STEP };
+            }
+            (p, f(p))
+        })
+        .collect();
+
+    for _ in 0..MAX_ITERATIONS {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (best, worst) = (simplex[0].1, simplex[N].1);
+        if (worst - best).abs() <= EPSILON * (best.abs() + EPSILON) {
+            break;
+        }
+
+        let mut centroid = [0.0; N];
+        for (p, _) in &simplex[..N] {
+            for (c, v) in centroid.iter_mut().zip(p) {
+                *c += v / N as f64;
+            }
+        }
+
+        let worst_point = simplex[N].0;
+        let reflected = lerp(&centroid, &worst_point, -1.0);
+        let reflected_value = f(reflected);
+
+        if reflected_value < best {
+            let expanded = lerp(&centroid, &worst_point, -2.0);
+            let expanded_value = f(expanded);
+            simplex[N] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[N - 1].1 {
+            simplex[N] = (reflected, reflected_value);
+        } else {
+            let contracted = lerp(&centroid, &worst_point, 0.5);
+            let contracted_value = f(contracted);
+            if contracted_value < simplex[N].1 {
+                simplex[N] = (contracted, contracted_value);
+            } else {
+                // shrink towards the best point
+                let best_point = simplex[0].0;
+                for (p, v) in &mut simplex[1..] {
+                    *p = lerp(&best_point, p, 0.5);
+                    *v = f(*p);
+                }
+            }
+        }
+    }
+
+    simplex
+        .into_iter()
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(p, _)| p)
+        .expect("simplex is not empty")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(h: usize, m: usize, include_fit: bool) -> ForecastParams {
+        ForecastParams {
+            interval: 10,
+            h,
+            m,
+            include_fit,
+        }
+    }
+
+    fn series(values: &[f64]) -> Vec<(i64, f64)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i as i64 * 10, *v))
+            .collect()
+    }
+
+    fn assert_close(got: &[(i64, f64)], want: &[(i64, f64)]) {
+        assert_eq!(got.len(), want.len(), "got {got:?}, want {want:?}");
+        for ((got_t, got_v), (want_t, want_v)) in got.iter().zip(want) {
+            assert_eq!(got_t, want_t, "got {got:?}, want {want:?}");
+            assert!(
+                (got_v - want_v).abs() < 1e-3 * want_v.abs().max(1.0),
+                "got {got:?}, want {want:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_too_few_points() {
+        assert!(forecast(&series(&[1.0]), params(3, 0, false)).is_empty());
+        assert!(forecast(&series(&[1.0, 2.0, 3.0]), params(3, 4, false)).is_empty());
+        assert!(forecast(&series(&[1.0, 2.0, 3.0]), params(0, 0, false)).is_empty());
+    }
+
+    #[test]
+    fn test_constant() {
+        let got = forecast(&series(&[5.0; 6]), params(3, 0, false));
+        assert_close(&got, &[(60, 5.0), (70, 5.0), (80, 5.0)]);
+    }
+
+    #[test]
+    fn test_linear_trend() {
+        let got = forecast(
+            &series(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]),
+            params(2, 0, false),
+        );
+        assert_close(&got, &[(80, 9.0), (90, 10.0)]);
+    }
+
+    #[test]
+    fn test_seasonal() {
+        let season = [1.0, 3.0, 2.0, 4.0];
+        let values = season.iter().cycle().take(16).copied().collect::<Vec<_>>();
+        let got = forecast(&series(&values), params(4, 4, false));
+        assert_close(&got, &[(160, 1.0), (170, 3.0), (180, 2.0), (190, 4.0)]);
+    }
+
+    #[test]
+    fn test_include_fit() {
+        let got = forecast(&series(&[5.0; 4]), params(2, 0, true));
+        assert_close(
+            &got,
+            &[
+                (0, 5.0),
+                (10, 5.0),
+                (20, 5.0),
+                (30, 5.0),
+                (40, 5.0),
+                (50, 5.0),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_missing_intervals() {
+        // the value at 20 is missing, so the first forecast is at 50
+        let points = vec![(0, 5.0), (10, 5.0), (30, 5.0), (40, 5.0)];
+        let got = forecast(&points, params(1, 0, false));
+        assert_close(&got, &[(50, 5.0)]);
+    }
+}
@@ -0,0 +1,711 @@
+//! This module contains code that implements the InfluxQL `HOLT_WINTERS`
+//! forecasting function as an extension to DataFusion.
+//!
+//! Unlike a window function, a forecast produces points that are not part of
+//! its input, therefore it is implemented as a dedicated plan node that
+//! replaces each series of its input with the forecasted points.
+
+mod algo;
+
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{Array, ArrayRef, Float64Array, TimestampNanosecondArray, UInt32Array},
+    compute::{cast, concat_batches, take, SortOptions},
+    datatypes::{DataType, SchemaRef},
+    record_batch::RecordBatch,
+    row::{RowConverter, SortField},
+};
+use datafusion::{
+    common::{DFField, DFSchema, DFSchemaRef},
+    error::{DataFusionError, Result},
+    execution::{
+        context::TaskContext,
+        memory_pool::{MemoryConsumer, MemoryReservation},
+    },
+    logical_expr::{ExprSchemable, LogicalPlan, UserDefinedLogicalNodeCore},
+    physical_expr::{
+        create_physical_expr, execution_props::ExecutionProps, PhysicalSortExpr,
+        PhysicalSortRequirement,
+    },
+    physical_plan::{
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        stream::RecordBatchStreamAdapter,
+        DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning, PhysicalExpr,
+        SendableRecordBatchStream, Statistics,
+    },
+    prelude::Expr,
+};
+use futures::{StreamExt, TryStreamExt};
+
+use self::algo::{forecast, ForecastParams};
+
+/// A logical node that replaces each series of its input with a forecast
+/// computed with the Holt-Winters method.
+///
+/// The output has the series columns, the time column and a single `Float64`
+/// column with the forecasted values.
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HoltWinters {
+    /// The incoming logical plan
+    pub input: Arc<LogicalPlan>,
+    /// Expressions that identify a series
+    pub series_expr: Vec<Expr>,
+    /// The time column
+    pub time_column: Expr,
+    /// The values to forecast
+    pub value_expr: Expr,
+    /// Parameters to configure the forecast
+    pub params: HoltWintersParams,
+    /// The output schema
+    schema: DFSchemaRef,
+}
+
+/// Parameters to the HoltWinters operation
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct HoltWintersParams {
+    /// The interval between the points of a series, in nanoseconds,
+    /// that is the interval of the `GROUP BY TIME` clause.
+    pub interval: i64,
+    /// The number of points to forecast, the `N` argument of `HOLT_WINTERS`.
+    pub h: usize,
+    /// The number of points in a season, the `S` argument of `HOLT_WINTERS`.
+    /// Values below 2 disable seasonality.
+    pub m: usize,
+    /// Also output the fitted values for the input points, as
+    /// `HOLT_WINTERS_WITH_FIT` does.
+    pub include_fit: bool,
+    /// The name of the output column with the forecasted values.
+    pub alias: String,
+}
+
+impl HoltWinters {
+    /// Create a new forecasting operator.
+    pub fn try_new(
+        input: Arc<LogicalPlan>,
+        series_expr: Vec<Expr>,
+        time_column: Expr,
+        value_expr: Expr,
+        params: HoltWintersParams,
+    ) -> Result<Self> {
+        if params.interval <= 0 {
+            return Err(DataFusionError::Internal(format!(
+                "HoltWinters requires a positive interval, got {}",
+                params.interval
+            )));
+        }
+
+        let input_schema = input.schema();
+        let mut fields = series_expr
+            .iter()
+            .chain(std::iter::once(&time_column))
+            .map(|e| match e {
+                // keep the qualifier, so that references to the column remain valid
+                Expr::Column(c) => input_schema.field_from_column(c).cloned(),
+                e => e.to_field(input_schema),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        fields.push(DFField::new_unqualified(
+            &params.alias,
+            DataType::Float64,
+            true,
+        ));
+        let schema = Arc::new(DFSchema::new_with_metadata(
+            fields,
+            input_schema.metadata().clone(),
+        )?);
+
+        Ok(Self {
+            input,
+            series_expr,
+            time_column,
+            value_expr,
+            params,
+            schema,
+        })
+    }
+}
+
+impl UserDefinedLogicalNodeCore for HoltWinters {
+    fn name(&self) -> &str {
+        "HoltWinters"
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![self.input.as_ref()]
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.schema
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.series_expr
+            .iter()
+            .chain([&self.time_column, &self.value_expr])
+            .cloned()
+            .collect()
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let series_expr = self
+            .series_expr
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        write!(
+            f,
+            "{}: series=[{series_expr}], time_column={}, value={} AS {}, interval={}, h={}, m={}, include_fit={}",
+            self.name(),
+            self.time_column,
+            self.value_expr,
+            self.params.alias,
+            self.params.interval,
+            self.params.h,
+            self.params.m,
+            self.params.include_fit,
+        )
+    }
+
+    fn from_template(&self, exprs: &[Expr], inputs: &[LogicalPlan]) -> Self {
+        assert_eq!(inputs.len(), 1, "HoltWinters: input sizes inconsistent");
+        assert_eq!(
+            exprs.len(),
+            self.series_expr.len() + 2,
+            "HoltWinters: expression sizes inconsistent"
+        );
+        let mut series_expr = exprs.to_vec();
+        let value_expr = series_expr.pop().unwrap();
+        let time_column = series_expr.pop().unwrap();
+        Self::try_new(
+            Arc::new(inputs[0].clone()),
+            series_expr,
+            time_column,
+            value_expr,
+            self.params.clone(),
+        )
+        .expect("should not fail")
+    }
+}
+
+/// Called by the extension planner to plan a [HoltWinters] node.
+pub(crate) fn plan_holt_winters(
+    execution_props: &ExecutionProps,
+    holt_winters: &HoltWinters,
+    logical_inputs: &[&LogicalPlan],
+    physical_inputs: &[Arc<dyn ExecutionPlan>],
+) -> Result<HoltWintersExec> {
+    if logical_inputs.len() != 1 {
+        return Err(DataFusionError::Internal(
+            "HoltWintersExec: wrong number of logical inputs".to_string(),
+        ));
+    }
+    if physical_inputs.len() != 1 {
+        return Err(DataFusionError::Internal(
+            "HoltWintersExec: wrong number of physical inputs".to_string(),
+        ));
+    }
+
+    let input_dfschema = logical_inputs[0].schema().as_ref();
+    let input_schema = physical_inputs[0].schema();
+    let input_schema = input_schema.as_ref();
+    let physical_expr =
+        |e: &Expr| create_physical_expr(e, input_dfschema, input_schema, execution_props);
+
+    let series_expr = holt_winters
+        .series_expr
+        .iter()
+        .map(physical_expr)
+        .collect::<Result<Vec<_>>>()?;
+    let time_column = physical_expr(&holt_winters.time_column)?;
+    let value_expr = physical_expr(&holt_winters.value_expr)?;
+
+    let params = &holt_winters.params;
+    Ok(HoltWintersExec::new(
+        Arc::clone(&physical_inputs[0]),
+        Arc::new(holt_winters.schema.as_ref().into()),
+        series_expr,
+        time_column,
+        value_expr,
+        ForecastParams {
+            interval: params.interval,
+            h: params.h,
+            m: params.m,
+            include_fit: params.include_fit,
+        },
+    ))
+}
+
+/// A physical node for the HoltWinters operation.
+pub struct HoltWintersExec {
+    input: Arc<dyn ExecutionPlan>,
+    /// Output schema
+    schema: SchemaRef,
+    /// Expressions that identify a series
+    series_expr: Vec<Arc<dyn PhysicalExpr>>,
+    /// The time column
+    time_column: Arc<dyn PhysicalExpr>,
+    /// The values to forecast
+    value_expr: Arc<dyn PhysicalExpr>,
+    // The sort expressions for the required sort order of the input:
+    // all of the series expressions, followed by the time column.
+    sort_expr: Vec<PhysicalSortExpr>,
+    /// Parameters to configure the forecast
+    params: ForecastParams,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl HoltWintersExec {
+    fn new(
+        input: Arc<dyn ExecutionPlan>,
+        schema: SchemaRef,
+        series_expr: Vec<Arc<dyn PhysicalExpr>>,
+        time_column: Arc<dyn PhysicalExpr>,
+        value_expr: Arc<dyn PhysicalExpr>,
+        params: ForecastParams,
+    ) -> Self {
+        let sort_expr = series_expr
+            .iter()
+            .chain(std::iter::once(&time_column))
+            .map(|expr| PhysicalSortExpr {
+                expr: Arc::clone(expr),
+                options: SortOptions::default(),
+            })
+            .collect();
+
+        Self {
+            input,
+            schema,
+            series_expr,
+            time_column,
+            value_expr,
+            sort_expr,
+            params,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl Debug for HoltWintersExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HoltWintersExec")
+    }
+}
+
+impl ExecutionPlan for HoltWintersExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::SinglePartition]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(
+            &self.sort_expr,
+        ))]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::new(
+                Arc::clone(&children[0]),
+                Arc::clone(&self.schema),
+                self.series_expr.clone(),
+                Arc::clone(&self.time_column),
+                Arc::clone(&self.value_expr),
+                self.params,
+            ))),
+            _ => Err(DataFusionError::Internal(
+                "HoltWintersExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if partition != 0 {
+            return Err(DataFusionError::Internal(format!(
+                "HoltWintersExec invalid partition {partition}, there can be only one partition"
+            )));
+        }
+
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let reservation = MemoryConsumer::new(format!("HoltWintersExec[{partition}]"))
+            .register(context.memory_pool());
+        let input_stream = self.input.execute(partition, context)?;
+
+        let forecaster = Forecaster {
+            schema: Arc::clone(&self.schema),
+            series_expr: self.series_expr.clone(),
+            time_column: Arc::clone(&self.time_column),
+            value_expr: Arc::clone(&self.value_expr),
+            params: self.params,
+        };
+        let stream =
+            futures::stream::once(forecaster.run(input_stream, reservation, baseline_metrics))
+                .boxed();
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for HoltWintersExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let series_expr: Vec<_> = self.series_expr.iter().map(|e| e.to_string()).collect();
+                write!(
+                    f,
+                    "HoltWintersExec: series_expr=[{}], time_column={}, value_expr={}, interval={}, h={}, m={}, include_fit={}",
+                    series_expr.join(", "),
+                    self.time_column,
+                    self.value_expr,
+                    self.params.interval,
+                    self.params.h,
+                    self.params.m,
+                    self.params.include_fit,
+                )
+            }
+        }
+    }
+}
+
+/// Computes the forecasts of all series of the input.
+struct Forecaster {
+    schema: SchemaRef,
+    series_expr: Vec<Arc<dyn PhysicalExpr>>,
+    time_column: Arc<dyn PhysicalExpr>,
+    value_expr: Arc<dyn PhysicalExpr>,
+    params: ForecastParams,
+}
+
+impl Forecaster {
+    /// Buffer the whole input, which is sorted by series and time, and
+    /// produce a single output batch.
+    async fn run(
+        self,
+        input_stream: SendableRecordBatchStream,
+        mut reservation: MemoryReservation,
+        baseline_metrics: BaselineMetrics,
+    ) -> Result<RecordBatch> {
+        let input_schema = input_stream.schema();
+        let batches = input_stream
+            .and_then(|batch| {
+                let res = reservation
+                    .try_grow(batch.get_array_memory_size())
+                    .map(|_| batch);
+                futures::future::ready(res)
+            })
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let timer = baseline_metrics.elapsed_compute().timer();
+        let batch = concat_batches(&input_schema, &batches)?;
+        drop(batches);
+        let output = self.forecast(&batch)?;
+        timer.done();
+
+        baseline_metrics.record_output(output.num_rows());
+        Ok(output)
+    }
+
+    fn forecast(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let num_rows = batch.num_rows();
+        let series = self
+            .series_expr
+            .iter()
+            .map(|e| Ok(e.evaluate(batch)?.into_array(num_rows)))
+            .collect::<Result<Vec<_>>>()?;
+        let time = self.time_column.evaluate(batch)?.into_array(num_rows);
+        let time = time
+            .as_any()
+            .downcast_ref::<TimestampNanosecondArray>()
+            .ok_or_else(|| {
+                DataFusionError::Internal(format!(
+                    "HoltWintersExec: unexpected time column type {}",
+                    time.data_type()
+                ))
+            })?;
+        let value = cast(
+            &self.value_expr.evaluate(batch)?.into_array(num_rows),
+            &DataType::Float64,
+        )?;
+        let value = value
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .expect("cast to Float64");
+
+        // Find the boundaries of the series, the input is sorted by the series
+        // expressions.
+        let mut boundaries = vec![0];
+        if !series.is_empty() && num_rows > 0 {
+            let mut converter = RowConverter::new(
+                series
+                    .iter()
+                    .map(|a| SortField::new(a.data_type().clone()))
+                    .collect(),
+            )?;
+            let rows = converter.convert_columns(&series)?;
+            boundaries.extend((1..num_rows).filter(|&i| rows.row(i) != rows.row(i - 1)));
+        }
+        boundaries.push(num_rows);
+
+        let mut series_indices = vec![];
+        let mut times = vec![];
+        let mut values = vec![];
+        for range in boundaries.windows(2) {
+            let (start, end) = (range[0], range[1]);
+            if start == end {
+                continue;
+            }
+            let points = (start..end)
+                .filter(|&i| time.is_valid(i) && value.is_valid(i))
+                .map(|i| (time.value(i), value.value(i)))
+                .collect::<Vec<_>>();
+            for (t, v) in forecast(&points, self.params) {
+                series_indices.push(start as u32);
+                times.push(t);
+                values.push(v);
+            }
+        }
+
+        let series_indices = UInt32Array::from(series_indices);
+        let mut columns = series
+            .iter()
+            .map(|a| Ok(take(a.as_ref(), &series_indices, None)?))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        columns.push(Arc::new(
+            TimestampNanosecondArray::from(times).with_timezone_opt(time.timezone()),
+        ));
+        columns.push(Arc::new(Float64Array::from(values)));
+
+        Ok(RecordBatch::try_new(Arc::clone(&self.schema), columns)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        exec::{Executor, ExecutorType},
+        test::{format_execution_plan, format_logical_plan},
+    };
+    use arrow::{
+        array::{DictionaryArray, Int64Array, StringArray},
+        datatypes::{Field, Int32Type, Schema, TimeUnit},
+    };
+    use arrow_util::assert_batches_eq;
+    use datafusion::{
+        logical_expr::{logical_plan, Extension, UserDefinedLogicalNode},
+        prelude::col,
+    };
+
+    fn schema() -> Schema {
+        Schema::new(vec![
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+            Field::new(
+                "loc",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                false,
+            ),
+            Field::new("temp", DataType::Int64, true),
+        ])
+    }
+
+    fn table_scan() -> LogicalPlan {
+        logical_plan::table_scan(Some("temps"), &schema(), None)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn holt_winters(scan: LogicalPlan, include_fit: bool) -> HoltWinters {
+        HoltWinters::try_new(
+            Arc::new(scan),
+            vec![col("loc")],
+            col("time"),
+            col("temp"),
+            HoltWintersParams {
+                interval: 10,
+                h: 2,
+                m: 0,
+                include_fit,
+                alias: "holt_winters".to_string(),
+            },
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_from_template() {
+        let node = holt_winters(table_scan(), false);
+        let node_dyn: &dyn UserDefinedLogicalNode = &node;
+        let exprs = node_dyn.expressions();
+        assert_eq!(exprs.len(), 3);
+        let from_template = node_dyn.from_template(&exprs, &[node.input.as_ref().clone()]);
+        let from_template = from_template
+            .as_any()
+            .downcast_ref::<HoltWinters>()
+            .expect("should be a HoltWinters");
+        assert_eq!(&node, from_template);
+    }
+
+    #[test]
+    fn fmt_logical_plan() {
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(holt_winters(table_scan(), false)),
+        });
+
+        insta::assert_yaml_snapshot!(
+            format_logical_plan(&plan),
+            @r###"
+        ---
+        - " HoltWinters: series=[loc], time_column=time, value=temp AS holt_winters, interval=10, h=2, m=0, include_fit=false"
+        - "   TableScan: temps"
+        "###
+        );
+    }
+
+    async fn forecast(include_fit: bool) -> (Vec<String>, Vec<RecordBatch>) {
+        let loc = DictionaryArray::<Int32Type>::new(
+            vec![0, 0, 0, 0, 1, 1, 1].into(),
+            Arc::new(StringArray::from(vec!["a", "b"])),
+        );
+        let batch = RecordBatch::try_new(
+            Arc::new(schema()),
+            vec![
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    0, 10, 20, 30, 0, 10, 30,
+                ])),
+                Arc::new(loc),
+                Arc::new(Int64Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    Some(4),
+                    Some(5),
+                    Some(5),
+                    None,
+                ])),
+            ],
+        )
+        .unwrap();
+
+        let executor = Executor::new_testing();
+        let context = executor.new_context(ExecutorType::Query);
+        context.inner().register_batch("temps", batch).unwrap();
+        let scan = context
+            .inner()
+            .table("temps")
+            .await
+            .unwrap()
+            .into_unoptimized_plan();
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(holt_winters(scan, include_fit)),
+        });
+
+        let physical_plan = context.create_physical_plan(&plan).await.unwrap();
+        let batches = context.collect(Arc::clone(&physical_plan)).await.unwrap();
+        (format_execution_plan(&physical_plan), batches)
+    }
+
+    #[tokio::test]
+    async fn test_forecast() {
+        let (plan, batches) = forecast(false).await;
+
+        insta::assert_yaml_snapshot!(
+            plan,
+            @r###"
+        ---
+        - " HoltWintersExec: series_expr=[loc@1], time_column=time@0, value_expr=temp@2, interval=10, h=2, m=0, include_fit=false"
+        - "   SortExec: expr=[loc@1 ASC,time@0 ASC]"
+        - "     MemoryExec: partitions=1, partition_sizes=[1]"
+        "###
+        );
+
+        // the last value of series `b` is NULL, so it is forecast from two points only
+        assert_batches_eq!(
+            &[
+                "+-----+--------------------------------+--------------+",
+                "| loc | time                           | holt_winters |",
+                "+-----+--------------------------------+--------------+",
+                "| a   | 1970-01-01T00:00:00.000000040Z | 5.0          |",
+                "| a   | 1970-01-01T00:00:00.000000050Z | 6.0          |",
+                "| b   | 1970-01-01T00:00:00.000000020Z | 5.0          |",
+                "| b   | 1970-01-01T00:00:00.000000030Z | 5.0          |",
+                "+-----+--------------------------------+--------------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_forecast_with_fit() {
+        let (_, batches) = forecast(true).await;
+
+        assert_batches_eq!(
+            &[
+                "+-----+--------------------------------+--------------+",
+                "| loc | time                           | holt_winters |",
+                "+-----+--------------------------------+--------------+",
+                "| a   | 1970-01-01T00:00:00Z           | 1.0          |",
+                "| a   | 1970-01-01T00:00:00.000000010Z | 2.0          |",
+                "| a   | 1970-01-01T00:00:00.000000020Z | 3.0          |",
+                "| a   | 1970-01-01T00:00:00.000000030Z | 4.0          |",
+                "| a   | 1970-01-01T00:00:00.000000040Z | 5.0          |",
+                "| a   | 1970-01-01T00:00:00.000000050Z | 6.0          |",
+                "| b   | 1970-01-01T00:00:00Z           | 5.0          |",
+                "| b   | 1970-01-01T00:00:00.000000010Z | 5.0          |",
+                "| b   | 1970-01-01T00:00:00.000000020Z | 5.0          |",
+                "| b   | 1970-01-01T00:00:00.000000030Z | 5.0          |",
+                "+-----+--------------------------------+--------------+",
+            ],
+            &batches
+        );
+    }
+}
@@ -0,0 +1,425 @@
+//! Opt-in cache for query results.
+//!
+//! Dashboards tend to re-issue identical queries every few seconds. If neither the plan nor the set of participating
+//! [`QueryChunk`]s changed, the result cannot have changed either, so we can serve the previously computed
+//! [`RecordBatch`]es instead of re-scanning the data.
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use data_types::{ChunkId, ChunkOrder};
+use datafusion::{
+    datasource::physical_plan::ParquetExec,
+    error::DataFusionError,
+    physical_plan::{
+        displayable, empty::EmptyExec, visit_execution_plan, ExecutionPlan, ExecutionPlanVisitor,
+        RecordBatchStream, SendableRecordBatchStream,
+    },
+};
+use futures::{ready, Stream, StreamExt};
+use metric::{Registry, U64Counter};
+use parking_lot::Mutex;
+
+use crate::{
+    provider::{PartitionedFileExt, RecordBatchesExec},
+    QueryChunk,
+};
+
+/// Key of the [`QueryResultCache`].
+///
+/// Consists of the normalized (i.e. optimized and rendered) plan and the IDs of all [`QueryChunk`]s that are scanned
+/// by it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QueryResultCacheKey {
+    plan: String,
+    chunks: Vec<(ChunkId, ChunkOrder)>,
+}
+
+impl QueryResultCacheKey {
+    /// Create key for the given physical plan.
+    ///
+    /// Returns `None` if the plan reads data from sources that are not backed by [`QueryChunk`]s since we cannot
+    /// reason about their freshness.
+    pub fn try_new(plan: &dyn ExecutionPlan) -> Option<Self> {
+        let mut visitor = ChunkIdVisitor::default();
+        visit_execution_plan(plan, &mut visitor).ok()?;
+
+        let mut chunks = visitor.chunks;
+        chunks.sort();
+
+        Some(Self {
+            plan: displayable(plan).indent(false).to_string(),
+            chunks,
+        })
+    }
+}
+
+/// Collect IDs of all chunks within a physical plan.
+#[derive(Debug, Default)]
+struct ChunkIdVisitor {
+    chunks: Vec<(ChunkId, ChunkOrder)>,
+}
+
+impl ChunkIdVisitor {
+    fn add_chunk(&mut self, chunk: &dyn QueryChunk) {
+        self.chunks.push((chunk.id(), chunk.order()));
+    }
+}
+
+impl ExecutionPlanVisitor for ChunkIdVisitor {
+    type Error = DataFusionError;
+
+    fn pre_visit(&mut self, plan: &dyn ExecutionPlan) -> Result<bool, Self::Error> {
+        let plan_any = plan.as_any();
+
+        if let Some(record_batches_exec) = plan_any.downcast_ref::<RecordBatchesExec>() {
+            for chunk in record_batches_exec.chunks() {
+                self.add_chunk(chunk.as_ref());
+            }
+        } else if let Some(parquet_exec) = plan_any.downcast_ref::<ParquetExec>() {
+            for file in parquet_exec.base_config().file_groups.iter().flatten() {
+                let ext = file
+                    .extensions
+                    .as_ref()
+                    .and_then(|any| any.downcast_ref::<PartitionedFileExt>())
+                    .ok_or_else(|| {
+                        DataFusionError::External(
+                            String::from("PartitionedFileExt not found").into(),
+                        )
+                    })?;
+                self.add_chunk(ext.chunk.as_ref());
+            }
+        } else if plan.children().is_empty() && plan_any.downcast_ref::<EmptyExec>().is_none() {
+            return Err(DataFusionError::External(
+                format!("unknown data source: {}", displayable(plan).one_line()).into(),
+            ));
+        }
+
+        Ok(true)
+    }
+}
+
+/// Cached query result.
+#[derive(Debug)]
+struct CacheEntry {
+    chunks: Vec<(ChunkId, ChunkOrder)>,
+    batches: Vec<RecordBatch>,
+    size: usize,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    /// Entries by plan.
+    ///
+    /// There is at most one entry per plan: a result for a different set of chunks is outdated.
+    entries: HashMap<String, CacheEntry>,
+    used_bytes: usize,
+    tick: u64,
+}
+
+impl CacheState {
+    fn remove(&mut self, plan: &str) {
+        if let Some(entry) = self.entries.remove(plan) {
+            self.used_bytes -= entry.size;
+        }
+    }
+}
+
+/// Size-bounded LRU cache for query results.
+///
+/// Results that are larger than the configured limit are never cached. Results are dropped as soon as they are
+/// requested for a different set of chunks, e.g. because new data was persisted or files were compacted.
+#[derive(Debug)]
+pub struct QueryResultCache {
+    max_bytes: usize,
+    state: Mutex<CacheState>,
+    hit: U64Counter,
+    miss: U64Counter,
+}
+
+impl QueryResultCache {
+    /// Create new, empty cache that holds at most `max_bytes` of record batches.
+    pub fn new(max_bytes: usize, metric_registry: &Registry) -> Self {
+        let requests = metric_registry.register_metric::<U64Counter>(
+            "query_result_cache_requests",
+            "Number of requests to the query result cache",
+        );
+
+        Self {
+            max_bytes,
+            state: Default::default(),
+            hit: requests.recorder(&[("result", "hit")]),
+            miss: requests.recorder(&[("result", "miss")]),
+        }
+    }
+
+    /// Maximum number of bytes that this cache holds.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Get cached result.
+    ///
+    /// A cached result of the same plan for a different set of chunks is outdated and dropped.
+    pub fn get(&self, key: &QueryResultCacheKey) -> Option<Vec<RecordBatch>> {
+        let mut state = self.state.lock();
+        state.tick += 1;
+        let tick = state.tick;
+
+        match state.entries.get_mut(&key.plan) {
+            Some(entry) if entry.chunks == key.chunks => {
+                entry.last_used = tick;
+                self.hit.inc(1);
+                Some(entry.batches.clone())
+            }
+            Some(_) => {
+                state.remove(&key.plan);
+                self.miss.inc(1);
+                None
+            }
+            None => {
+                self.miss.inc(1);
+                None
+            }
+        }
+    }
+
+    /// Store result, evicting least recently used entries if required.
+    pub fn insert(&self, key: QueryResultCacheKey, batches: Vec<RecordBatch>) {
+        let size = batches
+            .iter()
+            .map(|batch| batch.get_array_memory_size())
+            .sum::<usize>()
+            + key.plan.len();
+        if size > self.max_bytes {
+            return;
+        }
+
+        let mut state = self.state.lock();
+        state.remove(&key.plan);
+
+        while state.used_bytes + size > self.max_bytes {
+            let Some(lru) = state
+                .entries
+                .iter()
+                .min_by_key(|(_plan, entry)| entry.last_used)
+                .map(|(plan, _entry)| plan.clone())
+            else {
+                break;
+            };
+            state.remove(&lru);
+        }
+
+        state.tick += 1;
+        let last_used = state.tick;
+        state.used_bytes += size;
+        let QueryResultCacheKey { plan, chunks } = key;
+        state.entries.insert(
+            plan,
+            CacheEntry {
+                chunks,
+                batches,
+                size,
+                last_used,
+            },
+        );
+    }
+
+    /// Number of cached results.
+    pub fn len(&self) -> usize {
+        self.state.lock().entries.len()
+    }
+
+    /// Returns `true` if no results are cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Stream that passes through the results of a query and caches them once the query completed successfully.
+///
+/// Results that are larger than the cache are not buffered any further.
+pub(crate) struct CachingStream {
+    inner: SendableRecordBatchStream,
+    cache: Arc<QueryResultCache>,
+
+    /// Key and results seen so far, `None` if the results are not cached (because of an error or their size) or were
+    /// already cached.
+    pending: Option<(QueryResultCacheKey, Vec<RecordBatch>, usize)>,
+}
+
+impl CachingStream {
+    pub(crate) fn new(
+        inner: SendableRecordBatchStream,
+        cache: Arc<QueryResultCache>,
+        key: QueryResultCacheKey,
+    ) -> Self {
+        Self {
+            inner,
+            cache,
+            pending: Some((key, vec![], 0)),
+        }
+    }
+}
+
+impl Stream for CachingStream {
+    type Item = Result<RecordBatch, DataFusionError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        match ready!(this.inner.poll_next_unpin(cx)) {
+            Some(Ok(batch)) => {
+                if let Some((_key, batches, size)) = &mut this.pending {
+                    *size += batch.get_array_memory_size();
+                    if *size > this.cache.max_bytes() {
+                        this.pending = None;
+                    } else {
+                        batches.push(batch.clone());
+                    }
+                }
+                Poll::Ready(Some(Ok(batch)))
+            }
+            Some(Err(e)) => {
+                this.pending = None;
+                Poll::Ready(Some(Err(e)))
+            }
+            None => {
+                if let Some((key, batches, _size)) = this.pending.take() {
+                    this.cache.insert(key, batches);
+                }
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+impl RecordBatchStream for CachingStream {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{ArrayRef, Int64Array};
+    use datafusion::physical_plan::memory::MemoryExec;
+    use metric::{Attributes, Metric};
+
+    use crate::{provider::chunks_to_physical_nodes, test::TestChunk};
+
+    use super::*;
+
+    #[test]
+    fn test_key() {
+        let chunk1 = Arc::new(TestChunk::new("t").with_id(1).with_time_column()) as _;
+        let chunk2 = Arc::new(
+            TestChunk::new("t")
+                .with_id(2)
+                .with_time_column()
+                .with_dummy_parquet_file(),
+        ) as _;
+        let schema = TestChunk::new("t").with_time_column().schema().as_arrow();
+
+        let plan = chunks_to_physical_nodes(&schema, None, vec![Arc::clone(&chunk1), chunk2], 1);
+        let key1 = QueryResultCacheKey::try_new(plan.as_ref()).unwrap();
+        assert_eq!(key1.chunks.len(), 2);
+        let key2 = QueryResultCacheKey::try_new(plan.as_ref()).unwrap();
+        assert_eq!(key1, key2);
+
+        // different chunk set
+        let plan = chunks_to_physical_nodes(&schema, None, vec![chunk1], 1);
+        let key3 = QueryResultCacheKey::try_new(plan.as_ref()).unwrap();
+        assert_ne!(key1, key3);
+
+        // unknown data source
+        let plan = MemoryExec::try_new(&[], schema, None).unwrap();
+        assert!(QueryResultCacheKey::try_new(&plan).is_none());
+    }
+
+    #[test]
+    fn test_cache() {
+        let registry = Registry::default();
+        let batch = batch();
+        let batch_size = batch.get_array_memory_size();
+        let cache = QueryResultCache::new(2 * batch_size + 10, &registry);
+
+        let k1 = key("a");
+        let k2 = key("b");
+        let k3 = key("c");
+
+        assert!(cache.get(&k1).is_none());
+        cache.insert(k1.clone(), vec![batch.clone()]);
+        cache.insert(k2.clone(), vec![batch.clone()]);
+        assert_eq!(cache.get(&k1).unwrap(), vec![batch.clone()]);
+
+        // evicts k2 because k1 was used more recently
+        cache.insert(k3.clone(), vec![batch.clone()]);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&k2).is_none());
+        assert!(cache.get(&k1).is_some());
+        assert!(cache.get(&k3).is_some());
+
+        // too large
+        cache.insert(key("d"), vec![batch.clone(), batch.clone(), batch]);
+        assert!(cache.get(&key("d")).is_none());
+
+        assert_eq!(requests(&registry, "hit"), 3);
+        assert_eq!(requests(&registry, "miss"), 3);
+    }
+
+    #[test]
+    fn test_cache_drops_outdated_results() {
+        let registry = Registry::default();
+        let batch = batch();
+        let cache = QueryResultCache::new(1024 * 1024, &registry);
+
+        let k1 = key("a");
+        let k2 = QueryResultCacheKey {
+            chunks: vec![(ChunkId::new_test(1), ChunkOrder::new(1))],
+            ..key("a")
+        };
+
+        cache.insert(k1.clone(), vec![batch.clone()]);
+        assert_eq!(cache.len(), 1);
+
+        // same plan, different chunks
+        assert!(cache.get(&k2).is_none());
+        assert!(cache.is_empty());
+        assert!(cache.get(&k1).is_none());
+
+        // replaces the result for the previous chunks
+        cache.insert(k1.clone(), vec![batch.clone()]);
+        cache.insert(k2.clone(), vec![batch.clone()]);
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get(&k2).is_some());
+
+        assert_eq!(requests(&registry, "hit"), 1);
+        assert_eq!(requests(&registry, "miss"), 2);
+    }
+
+    fn key(plan: &str) -> QueryResultCacheKey {
+        QueryResultCacheKey {
+            plan: plan.to_owned(),
+            chunks: vec![],
+        }
+    }
+
+    fn batch() -> RecordBatch {
+        RecordBatch::try_from_iter([("a", Arc::new(Int64Array::from(vec![1, 2, 3])) as ArrayRef)])
+            .unwrap()
+    }
+
+    fn requests(registry: &Registry, result: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Counter>>("query_result_cache_requests")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("result", result)]))
+            .unwrap()
+            .fetch()
+    }
+}
@@ -0,0 +1,77 @@
+//! Limit for the amount of data that a single query may scan.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use datafusion::error::DataFusionError;
+
+/// Upper bound for the (estimated) number of bytes that a single query may scan.
+///
+/// This is attached to the DataFusion session, see
+/// [`IOxSessionConfig::with_max_scanned_bytes`](crate::exec::IOxSessionConfig::with_max_scanned_bytes). Table
+/// providers [consume](Self::consume) the budget during planning, so that oversized queries are rejected before any
+/// data is read.
+#[derive(Debug)]
+pub struct ScanBudget {
+    limit: u64,
+    used: AtomicU64,
+}
+
+impl ScanBudget {
+    /// Create new budget with the given limit in bytes.
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    /// Limit in bytes.
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Bytes consumed so far.
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::SeqCst)
+    }
+
+    /// Consume given number of bytes.
+    ///
+    /// Returns [`DataFusionError::ResourcesExhausted`] if the budget is exceeded. The bytes are still accounted for in
+    /// this case.
+    pub fn consume(&self, bytes: u64) -> Result<(), DataFusionError> {
+        let used = self
+            .used
+            .fetch_add(bytes, Ordering::SeqCst)
+            .saturating_add(bytes);
+        if used > self.limit {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "query would scan approximately {used} bytes, which exceeds the limit of {} bytes",
+                self.limit
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consume() {
+        let budget = ScanBudget::new(10);
+        assert_eq!(budget.limit(), 10);
+
+        budget.consume(4).unwrap();
+        budget.consume(6).unwrap();
+        assert_eq!(budget.used(), 10);
+
+        let err = budget.consume(1).unwrap_err();
+        assert!(matches!(err, DataFusionError::ResourcesExhausted(_)));
+        assert_eq!(
+            err.to_string(),
+            "Resources exhausted: query would scan approximately 11 bytes, which exceeds the limit of 10 bytes",
+        );
+        assert_eq!(budget.used(), 11);
+    }
+}
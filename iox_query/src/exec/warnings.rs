@@ -0,0 +1,48 @@
+//! Warnings that are returned to the client alongside the query result.
+//!
+//! A warning signals that a query succeeded but that its result may be incomplete, e.g. because an ingester could not
+//! be contacted and only persisted data was queried.
+use parking_lot::Mutex;
+
+/// Collects the warnings of a single query.
+///
+/// This is attached to the DataFusion session, see
+/// [`IOxSessionContext::query_warnings`](crate::exec::IOxSessionContext::query_warnings).
+#[derive(Debug, Default)]
+pub struct QueryWarnings {
+    warnings: Mutex<Vec<String>>,
+}
+
+impl QueryWarnings {
+    /// Add warning.
+    ///
+    /// Duplicates are ignored, so that the same problem is only reported once even if it affects multiple tables.
+    pub fn push(&self, warning: impl Into<String>) {
+        let warning = warning.into();
+        let mut warnings = self.warnings.lock();
+        if !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+
+    /// Get all warnings that were added so far, in insertion order.
+    pub fn warnings(&self) -> Vec<String> {
+        self.warnings.lock().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedup() {
+        let warnings = QueryWarnings::default();
+        assert!(warnings.warnings().is_empty());
+
+        warnings.push("b");
+        warnings.push("a");
+        warnings.push("b");
+        assert_eq!(warnings.warnings(), vec!["b", "a"]);
+    }
+}
@@ -1,7 +1,29 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::ControlFlow,
+    sync::Arc,
+};
 
 use crate::exec::context::IOxSessionContext;
-use datafusion::{error::Result, physical_plan::ExecutionPlan};
+use arrow::{
+    datatypes::{DataType, Field, Schema},
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    error::{DataFusionError, Result},
+    logical_expr::{utils::from_plan, Cast, Expr, LogicalPlan},
+    physical_plan::ExecutionPlan,
+    scalar::ScalarValue,
+    sql::{
+        parser::{DFParser, Statement as DFStatement},
+        sqlparser::ast::{visit_relations, Ident},
+    },
+};
+use datafusion_util::config::{DEFAULT_CATALOG, DEFAULT_SCHEMA, SYSTEM_SCHEMA};
+
+/// Schema that DataFusion uses for the `information_schema` views.
+const INFORMATION_SCHEMA: &str = "information_schema";
 
 /// This struct can create plans for running SQL queries against databases
 #[derive(Debug, Default)]
@@ -22,3 +44,310 @@ impl SqlQueryPlanner {
         ctx.sql_to_physical_plan(query).await
     }
 }
+
+/// Names of the schemas that tables within `sql` are qualified with, e.g. `other` for `SELECT * FROM other.cpu`.
+///
+/// Identifiers are normalized the same way DataFusion does it, i.e. unquoted identifiers are lowercased. The schemas that
+/// IOx provides for every namespace (default, system and information schema) are NOT included.
+///
+/// Returns an empty list if `sql` cannot be parsed. Planning the query will report the error.
+pub fn referenced_schemas(sql: &str) -> Vec<String> {
+    let Ok(statements) = DFParser::parse_sql(sql) else {
+        return vec![];
+    };
+
+    let mut schemas = BTreeSet::new();
+    for statement in &statements {
+        let DFStatement::Statement(statement) = statement else {
+            continue;
+        };
+
+        let _ = visit_relations(statement.as_ref(), |relation| {
+            let schema = match relation.0.as_slice() {
+                [schema, _table] => Some(normalize_ident(schema)),
+                [catalog, schema, _table] if normalize_ident(catalog) == DEFAULT_CATALOG => {
+                    Some(normalize_ident(schema))
+                }
+                _ => None,
+            };
+            if let Some(schema) = schema {
+                if ![DEFAULT_SCHEMA, SYSTEM_SCHEMA, INFORMATION_SCHEMA].contains(&schema.as_str()) {
+                    schemas.insert(schema);
+                }
+            }
+            ControlFlow::<()>::Continue(())
+        });
+    }
+
+    schemas.into_iter().collect()
+}
+
+fn normalize_ident(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Values for the placeholders of a parameterized SQL query.
+///
+/// Placeholders can either be positional (`$1`, `$2`, ...) or named (`$name`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryParams {
+    /// Values keyed by placeholder ID, including the leading `$`.
+    values: BTreeMap<String, ScalarValue>,
+}
+
+impl QueryParams {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set value for placeholder `$<name>`, e.g. `"1"` for `$1` or `"host"` for `$host`.
+    pub fn with_value(mut self, name: impl AsRef<str>, value: ScalarValue) -> Self {
+        self.values.insert(format!("${}", name.as_ref()), value);
+        self
+    }
+
+    /// Create parameters from a record batch with a single row and one column per parameter, as it is used by Flight
+    /// SQL.
+    ///
+    /// Every column can be referenced by its position (`$1` is the first column) as well as by its name.
+    pub fn try_from_batch(batch: &RecordBatch) -> Result<Self> {
+        if batch.num_rows() != 1 {
+            return Err(DataFusionError::Plan(format!(
+                "Query parameters must consist of exactly one row but got {}",
+                batch.num_rows()
+            )));
+        }
+
+        let mut params = Self::new();
+        for (idx, (field, array)) in batch
+            .schema()
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .enumerate()
+        {
+            let value = ScalarValue::try_from_array(array, 0)?;
+            params = params
+                .with_value(field.name(), value.clone())
+                .with_value((idx + 1).to_string(), value);
+        }
+        Ok(params)
+    }
+
+    /// Returns `true` if no values are set.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get value for given placeholder ID (including the leading `$`).
+    pub fn get(&self, id: &str) -> Option<&ScalarValue> {
+        self.values.get(id)
+    }
+}
+
+/// Replace all placeholders within `plan` with the values in `params`.
+///
+/// Values are cast to the placeholder type if the planner was able to infer one. Fails if a placeholder has no value.
+pub fn bind_params(plan: &LogicalPlan, params: &QueryParams) -> Result<LogicalPlan> {
+    let exprs = plan
+        .expressions()
+        .into_iter()
+        .map(|expr| {
+            expr.transform_up(&|expr| {
+                let Expr::Placeholder(placeholder) = expr else {
+                    return Ok(Transformed::No(expr));
+                };
+
+                let value = params.get(&placeholder.id).cloned().ok_or_else(|| {
+                    DataFusionError::Plan(format!(
+                        "No value found for placeholder '{}'",
+                        placeholder.id
+                    ))
+                })?;
+                let expr = match placeholder.data_type {
+                    Some(data_type) if data_type != value.get_datatype() => {
+                        Expr::Cast(Cast::new(Box::new(Expr::Literal(value)), data_type))
+                    }
+                    _ => Expr::Literal(value),
+                };
+                Ok(Transformed::Yes(expr))
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let inputs = plan
+        .inputs()
+        .into_iter()
+        .map(|input| bind_params(input, params))
+        .collect::<Result<Vec<_>>>()?;
+
+    from_plan(plan, &exprs, &inputs)
+}
+
+/// Schema of the placeholders within `plan`, with one nullable field per placeholder (without the leading `$`).
+///
+/// Positional placeholders come first, ordered by position, followed by named placeholders ordered by name. Fields
+/// for placeholders that have no inferred type use [`DataType::Null`].
+pub fn param_schema(plan: &LogicalPlan) -> Result<Schema> {
+    let mut params = plan
+        .get_parameter_types()?
+        .into_iter()
+        .map(|(id, data_type)| {
+            let name = id.strip_prefix('$').unwrap_or(&id).to_owned();
+            let position = name.parse::<usize>().ok();
+            (position.is_none(), position, name, data_type)
+        })
+        .collect::<Vec<_>>();
+    params.sort_unstable_by(|a, b| (a.0, a.1, &a.2).cmp(&(b.0, b.1, &b.2)));
+
+    Ok(Schema::new(
+        params
+            .into_iter()
+            .map(|(_named, _position, name, data_type)| {
+                Field::new(name, data_type.unwrap_or(DataType::Null), true)
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{ArrayRef, Int64Array, StringArray};
+    use datafusion::logical_expr::{col, expr::Placeholder, lit, LogicalPlanBuilder};
+
+    use super::*;
+
+    #[test]
+    fn test_params_from_batch() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "host",
+                Arc::new(StringArray::from(vec![Some("a")])) as ArrayRef,
+            ),
+            (
+                "limit",
+                Arc::new(Int64Array::from(vec![Some(10)])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let params = QueryParams::try_from_batch(&batch).unwrap();
+        assert_eq!(params.get("$1"), Some(&ScalarValue::from("a")));
+        assert_eq!(params.get("$host"), Some(&ScalarValue::from("a")));
+        assert_eq!(params.get("$2"), Some(&ScalarValue::Int64(Some(10))));
+        assert_eq!(params.get("$limit"), Some(&ScalarValue::Int64(Some(10))));
+        assert_eq!(params.get("$3"), None);
+    }
+
+    #[test]
+    fn test_params_from_batch_wrong_row_count() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "host",
+            Arc::new(StringArray::from(vec![Some("a"), Some("b")])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let err = QueryParams::try_from_batch(&batch).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: Query parameters must consist of exactly one row but got 2"
+        );
+    }
+
+    #[test]
+    fn test_bind_params() {
+        let plan = LogicalPlanBuilder::empty(true)
+            .project(vec![
+                placeholder("$1", DataType::Int64).alias("a"),
+                placeholder("$name", DataType::Utf8).alias("b"),
+            ])
+            .unwrap()
+            .filter(col("b").eq(lit("foo")))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let params = QueryParams::new()
+            .with_value("1", ScalarValue::Int64(Some(1)))
+            .with_value("name", ScalarValue::from("foo"));
+        let bound = bind_params(&plan, &params).unwrap();
+        assert_eq!(
+            bound.display_indent().to_string(),
+            "Filter: b = Utf8(\"foo\")\
+            \n  Projection: Int64(1) AS a, Utf8(\"foo\") AS b\
+            \n    EmptyRelation",
+        );
+
+        let err = bind_params(
+            &plan,
+            &QueryParams::new().with_value("1", ScalarValue::Null),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: No value found for placeholder '$name'"
+        );
+    }
+
+    #[test]
+    fn test_param_schema() {
+        let plan = LogicalPlanBuilder::empty(true)
+            .project(vec![
+                placeholder("$name", DataType::Utf8).alias("a"),
+                placeholder("$2", DataType::Float64).alias("b"),
+                placeholder("$1", DataType::Int64).alias("c"),
+            ])
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            param_schema(&plan).unwrap(),
+            Schema::new(vec![
+                Field::new("1", DataType::Int64, true),
+                Field::new("2", DataType::Float64, true),
+                Field::new("name", DataType::Utf8, true),
+            ]),
+        );
+    }
+
+    #[test]
+    fn test_referenced_schemas() {
+        assert_eq!(
+            referenced_schemas("SELECT * FROM cpu"),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            referenced_schemas(
+                "SELECT * FROM cpu JOIN other_ns.mem USING (time) JOIN public.\"Third\".disk USING (time)"
+            ),
+            vec!["Third".to_owned(), "other_ns".to_owned()],
+        );
+        assert_eq!(
+            referenced_schemas(
+                "SELECT * FROM (SELECT * FROM OTHER_NS.cpu) WHERE host IN (SELECT host FROM other_ns.mem)"
+            ),
+            vec!["other_ns".to_owned()],
+        );
+
+        // built-in schemas and other catalogs are ignored
+        assert_eq!(
+            referenced_schemas(
+                "SELECT * FROM iox.cpu, system.queries, information_schema.tables, other_catalog.ns.cpu"
+            ),
+            Vec::<String>::new(),
+        );
+
+        // invalid SQL
+        assert_eq!(referenced_schemas("SELECT * FROM"), Vec::<String>::new());
+    }
+
+    fn placeholder(id: &str, data_type: DataType) -> Expr {
+        Expr::Placeholder(Placeholder {
+            id: id.to_owned(),
+            data_type: Some(data_type),
+        })
+    }
+}
@@ -21,18 +21,29 @@ use arrow::{
 };
 use async_trait::async_trait;
 use data_types::{ChunkId, ChunkOrder, PartitionId, TransitionPartitionId};
-use datafusion::{error::DataFusionError, physical_plan::Statistics, prelude::SessionContext};
+use datafusion::{
+    error::DataFusionError,
+    physical_plan::{SendableRecordBatchStream, Statistics},
+    prelude::SessionContext,
+    scalar::ScalarValue,
+};
 use exec::IOxSessionContext;
 use hashbrown::HashMap;
 use observability_deps::tracing::trace;
 use once_cell::sync::Lazy;
 use parquet_file::storage::ParquetExecInput;
 use predicate::{rpc_predicate::QueryNamespaceMeta, Predicate};
+use pruning::RowGroupFilter;
 use schema::{
     sort::{SortKey, SortKeyBuilder},
     InfluxColumnType, Projection, Schema, TIME_COLUMN_NAME,
 };
-use std::{any::Any, fmt::Debug, sync::Arc};
+use std::{
+    any::Any,
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub mod chunk_statistics;
 pub mod config;
@@ -96,35 +107,170 @@ pub trait QueryChunk: Debug + Send + Sync + 'static {
     /// Order of this chunk relative to other overlapping chunks.
     fn order(&self) -> ChunkOrder;
 
+    /// Returns a filter (e.g. a bloom filter persisted alongside the parquet file) that can prove that certain values
+    /// are NOT present in the given column.
+    ///
+    /// This is used for pruning in addition to the [statistics](Self::stats) and is especially useful for
+    /// high-cardinality tag columns where min/max statistics are not selective.
+    fn column_filter(&self, _column: &str) -> Option<Arc<dyn RowGroupFilter>> {
+        None
+    }
+
+    /// Answers the given aggregate over all rows of this chunk without scanning the data, e.g. from pre-aggregated
+    /// data or metadata of a remote service.
+    ///
+    /// Returns `None` if this is not possible. The result MUST be exact, i.e. it must NOT be derived from statistics
+    /// that are only estimates. The value will be cast to the output type of the aggregate.
+    fn pushdown_aggregate(&self, _aggregate: &ChunkAggregate) -> Option<ScalarValue> {
+        None
+    }
+
     /// Return backend as [`Any`] which can be used to downcast to a specific implementation.
     fn as_any(&self) -> &dyn Any;
 }
 
+/// Aggregate over all rows of a chunk, see [`QueryChunk::pushdown_aggregate`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ChunkAggregate {
+    /// Number of rows (`COUNT(*)`).
+    CountRows,
+
+    /// Number of non-NULL values of the given column.
+    Count(String),
+
+    /// Minimum non-NULL value of the given column, NULL if there is none.
+    Min(String),
+
+    /// Maximum non-NULL value of the given column, NULL if there is none.
+    Max(String),
+
+    /// Sum of all non-NULL values of the given column, NULL if there is none.
+    Sum(String),
+}
+
+/// Phase of a query, see [`QueryCompletedToken::enter_phase`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueryPhase {
+    /// Waiting for a slot to run the query (e.g. query concurrency semaphore).
+    QueueWait,
+
+    /// Logical and physical planning.
+    Planning,
+
+    /// Execution until the first result is produced.
+    Execution,
+
+    /// Streaming of results to the client.
+    Streaming,
+}
+
+impl QueryPhase {
+    /// All phases, in the order in which they usually occur.
+    pub const ALL: [Self; 4] = [
+        Self::QueueWait,
+        Self::Planning,
+        Self::Execution,
+        Self::Streaming,
+    ];
+
+    /// Name of the phase.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::QueueWait => "queue_wait",
+            Self::Planning => "planning",
+            Self::Execution => "execution",
+            Self::Streaming => "streaming",
+        }
+    }
+}
+
+/// Time spent in the different [phases](QueryPhase) of a query.
+///
+/// Phases that were never entered are `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryPhaseTimings {
+    /// See [`QueryPhase::QueueWait`].
+    pub queue_wait: Option<Duration>,
+
+    /// See [`QueryPhase::Planning`].
+    pub planning: Option<Duration>,
+
+    /// See [`QueryPhase::Execution`].
+    pub execution: Option<Duration>,
+
+    /// See [`QueryPhase::Streaming`].
+    pub streaming: Option<Duration>,
+}
+
+impl QueryPhaseTimings {
+    /// Get duration of the given phase.
+    pub fn get(&self, phase: QueryPhase) -> Option<Duration> {
+        match phase {
+            QueryPhase::QueueWait => self.queue_wait,
+            QueryPhase::Planning => self.planning,
+            QueryPhase::Execution => self.execution,
+            QueryPhase::Streaming => self.streaming,
+        }
+    }
+
+    /// Add `d` to the given phase.
+    pub fn add(&mut self, phase: QueryPhase, d: Duration) {
+        let slot = match phase {
+            QueryPhase::QueueWait => &mut self.queue_wait,
+            QueryPhase::Planning => &mut self.planning,
+            QueryPhase::Execution => &mut self.execution,
+            QueryPhase::Streaming => &mut self.streaming,
+        };
+        *slot = Some(slot.unwrap_or_default() + d);
+    }
+}
+
+/// Outcome of a query, passed to the callback of [`QueryCompletedToken`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryCompletion {
+    /// If this query completed successfully.
+    pub success: bool,
+
+    /// Time spent in the different phases.
+    pub timings: QueryPhaseTimings,
+}
+
 /// A `QueryCompletedToken` is returned by `record_query` implementations of
 /// a `QueryNamespace`. It is used to trigger side-effects (such as query timing)
 /// on query completion.
 ///
+/// The holder of the token should mark the [phases](QueryPhase) of the query via
+/// [`enter_phase`](Self::enter_phase) so that slow planning can be distinguished from slow execution.
 pub struct QueryCompletedToken {
     /// If this query completed successfully
     success: bool,
 
-    /// Function invoked when the token is dropped. It is passed the
-    /// vaue of `self.success`
-    f: Option<Box<dyn FnOnce(bool) + Send>>,
+    /// Timings of all completed phases.
+    timings: QueryPhaseTimings,
+
+    /// Currently running phase and when it started.
+    current_phase: Option<(QueryPhase, Instant)>,
+
+    /// Function invoked when the token is dropped.
+    f: Option<Box<dyn FnOnce(QueryCompletion) + Send>>,
 }
 
 impl Debug for QueryCompletedToken {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("QueryCompletedToken")
             .field("success", &self.success)
+            .field("timings", &self.timings)
+            .field("current_phase", &self.current_phase.map(|(phase, _)| phase))
             .finish()
     }
 }
 
 impl QueryCompletedToken {
-    pub fn new(f: impl FnOnce(bool) + Send + 'static) -> Self {
+    pub fn new(f: impl FnOnce(QueryCompletion) + Send + 'static) -> Self {
         Self {
             success: false,
+            timings: QueryPhaseTimings::default(),
+            current_phase: None,
             f: Some(Box::new(f)),
         }
     }
@@ -133,12 +279,44 @@ impl QueryCompletedToken {
     pub fn set_success(&mut self) {
         self.success = true;
     }
+
+    /// Record time spent in a phase that was measured externally, e.g. queue wait time that happened before this
+    /// token was created.
+    pub fn record_phase(&mut self, phase: QueryPhase, d: Duration) {
+        self.timings.add(phase, d);
+    }
+
+    /// Finish the current phase (if any) and start the given one.
+    ///
+    /// The last phase is finished when the token is dropped.
+    pub fn enter_phase(&mut self, phase: QueryPhase) {
+        let now = Instant::now();
+        self.finish_phase(now);
+        self.current_phase = Some((phase, now));
+    }
+
+    /// Currently running phase.
+    pub fn current_phase(&self) -> Option<QueryPhase> {
+        self.current_phase.map(|(phase, _)| phase)
+    }
+
+    fn finish_phase(&mut self, now: Instant) {
+        if let Some((phase, start)) = self.current_phase.take() {
+            self.timings
+                .add(phase, now.saturating_duration_since(start));
+        }
+    }
 }
 
 impl Drop for QueryCompletedToken {
     fn drop(&mut self) {
+        self.finish_phase(Instant::now());
+
         if let Some(f) = self.f.take() {
-            (f)(self.success)
+            (f)(QueryCompletion {
+                success: self.success,
+                timings: self.timings,
+            })
         }
     }
 }
@@ -170,7 +348,7 @@ pub trait QueryNamespace: QueryNamespaceMeta + Debug + Send + Sync {
         ctx: IOxSessionContext,
     ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError>;
 
-    /// Retention cutoff time.
+    /// Retention cutoff time of the given table.
     ///
     /// This gives the timestamp (NOT the duration) at which data should be cut off. This should result in an additional
     /// filter of the following form:
@@ -179,8 +357,10 @@ pub trait QueryNamespace: QueryNamespaceMeta + Debug + Send + Sync {
     /// time >= retention_time_ns
     /// ```
     ///
-    /// Returns `None` if now retention policy was defined.
-    fn retention_time_ns(&self) -> Option<i64>;
+    /// A retention period set on the table overrides the retention period of the namespace.
+    ///
+    /// Returns `None` if no retention policy was defined.
+    fn retention_time_ns(&self, table_name: &str) -> Option<i64>;
 
     /// Record that particular type of query was run / planned
     fn record_query(
@@ -197,19 +377,36 @@ pub trait QueryNamespace: QueryNamespaceMeta + Debug + Send + Sync {
 }
 
 /// Raw data of a [`QueryChunk`].
-#[derive(Debug, Clone)]
 pub enum QueryChunkData {
     /// In-memory record batches.
     ///
     /// **IMPORTANT: All batches MUST have the schema that the [chunk reports](QueryChunk::schema).**
     RecordBatches(Vec<RecordBatch>),
 
+    /// Lazily produced record batches, e.g. from a remote service.
+    ///
+    /// The engine may call [`QueryChunk::data`] multiple times during planning and only polls the stream that is
+    /// requested at execution time, so implementations should NOT perform any work before the stream is polled.
+    ///
+    /// **IMPORTANT: All batches MUST have the schema that the [chunk reports](QueryChunk::schema).**
+    Stream(SendableRecordBatchStream),
+
     /// Parquet file.
     ///
     /// See [`ParquetExecInput`] for details.
     Parquet(ParquetExecInput),
 }
 
+impl Debug for QueryChunkData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RecordBatches(batches) => f.debug_tuple("RecordBatches").field(batches).finish(),
+            Self::Stream(_) => f.debug_tuple("Stream").field(&"(OPAQUE STREAM)").finish(),
+            Self::Parquet(input) => f.debug_tuple("Parquet").field(input).finish(),
+        }
+    }
+}
+
 impl QueryChunkData {
     /// Read data into [`RecordBatch`]es. This is mostly meant for testing!
     pub async fn read_to_batches(
@@ -219,6 +416,9 @@ impl QueryChunkData {
     ) -> Vec<RecordBatch> {
         match self {
             Self::RecordBatches(batches) => batches,
+            Self::Stream(stream) => datafusion::physical_plan::common::collect(stream)
+                .await
+                .unwrap(),
             Self::Parquet(exec_input) => exec_input
                 .read_to_batches(schema.as_arrow(), Projection::All, session_ctx)
                 .await
@@ -230,7 +430,7 @@ impl QueryChunkData {
     pub fn into_record_batches(self) -> Option<Vec<RecordBatch>> {
         match self {
             Self::RecordBatches(batches) => Some(batches),
-            Self::Parquet(_) => None,
+            Self::Stream(_) | Self::Parquet(_) => None,
         }
     }
 }
@@ -279,6 +479,14 @@ where
         self.as_ref().order()
     }
 
+    fn column_filter(&self, column: &str) -> Option<Arc<dyn RowGroupFilter>> {
+        self.as_ref().column_filter(column)
+    }
+
+    fn pushdown_aggregate(&self, aggregate: &ChunkAggregate) -> Option<ScalarValue> {
+        self.as_ref().pushdown_aggregate(aggregate)
+    }
+
     fn as_any(&self) -> &dyn Any {
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
@@ -326,6 +534,14 @@ impl QueryChunk for Arc<dyn QueryChunk> {
         self.as_ref().order()
     }
 
+    fn column_filter(&self, column: &str) -> Option<Arc<dyn RowGroupFilter>> {
+        self.as_ref().column_filter(column)
+    }
+
+    fn pushdown_aggregate(&self, aggregate: &ChunkAggregate) -> Option<ScalarValue> {
+        self.as_ref().pushdown_aggregate(aggregate)
+    }
+
     fn as_any(&self) -> &dyn Any {
         // present the underlying implementation, not the wrapper
         self.as_ref().as_any()
@@ -494,6 +494,8 @@ impl TreeNodeRewriter for FillFnRewriter {
 impl FillFnRewriter {
     fn add_fill_strategy(&mut self, e: Expr, fs: FillStrategy) -> Result<()> {
         match self.aggr_col_fill_map.entry(e) {
+            // the same column may be filled more than once, e.g. under different aliases
+            hash_map::Entry::Occupied(oe) if oe.get() == &fs => Ok(()),
             hash_map::Entry::Occupied(_) => Err(DataFusionError::NotImplemented(
                 "multiple fill strategies for the same column".to_string(),
             )),
@@ -1073,6 +1075,41 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn with_locf_twice() -> Result<()> {
+        let plan = LogicalPlanBuilder::from(table_scan()?)
+            .filter(
+                col("time")
+                    .gt_eq(lit_timestamp_nano(1000))
+                    .and(col("time").lt(lit_timestamp_nano(2000))),
+            )?
+            .aggregate(
+                vec![date_bin_gapfill(
+                    lit(ScalarValue::IntervalDayTime(Some(60_000))),
+                    col("time"),
+                )?],
+                vec![avg(col("temp")), min(col("temp"))],
+            )?
+            .project(vec![
+                col("date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)"),
+                locf(col("MIN(temps.temp)"))?.alias("a"),
+                locf(col("MIN(temps.temp)"))?.alias("b"),
+            ])?
+            .build()?;
+
+        insta::assert_yaml_snapshot!(
+            format_optimized_plan(&plan)?,
+            @r###"
+        ---
+        - "Projection: date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time), MIN(temps.temp) AS locf(MIN(temps.temp)) AS a, MIN(temps.temp) AS locf(MIN(temps.temp)) AS b"
+        - "  GapFill: groupBy=[date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)], aggr=[[AVG(temps.temp), LOCF(MIN(temps.temp))]], time_column=date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time), stride=IntervalDayTime(\"60000\"), range=Included(Literal(TimestampNanosecond(1000, None)))..Excluded(Literal(TimestampNanosecond(2000, None)))"
+        - "    Aggregate: groupBy=[[date_bin(IntervalDayTime(\"60000\"), temps.time) AS date_bin_gapfill(IntervalDayTime(\"60000\"),temps.time)]], aggr=[[AVG(temps.temp), MIN(temps.temp)]]"
+        - "      Filter: temps.time >= TimestampNanosecond(1000, None) AND temps.time < TimestampNanosecond(2000, None)"
+        - "        TableScan: temps"
+        "###);
+        Ok(())
+    }
+
     #[test]
     fn with_interpolate() -> Result<()> {
         let plan = LogicalPlanBuilder::from(table_scan()?)
@@ -4,10 +4,12 @@ use datafusion::execution::context::SessionState;
 
 use self::{
     handle_gapfill::HandleGapFill, influx_regex_to_datafusion_regex::InfluxRegexToDataFusionRegex,
+    validate_percentile::ValidatePercentile,
 };
 
 mod handle_gapfill;
 mod influx_regex_to_datafusion_regex;
+mod validate_percentile;
 pub use handle_gapfill::range_predicate;
 
 /// Register IOx-specific logical [`OptimizerRule`]s with the SessionContext
@@ -17,4 +19,5 @@ pub fn register_iox_logical_optimizers(state: SessionState) -> SessionState {
     state
         .add_optimizer_rule(Arc::new(InfluxRegexToDataFusionRegex::new()))
         .add_optimizer_rule(Arc::new(HandleGapFill::new()))
+        .add_optimizer_rule(Arc::new(ValidatePercentile))
 }
@@ -0,0 +1,135 @@
+use datafusion::{
+    common::tree_node::{TreeNode, VisitRecursion},
+    error::{DataFusionError, Result},
+    logical_expr::{expr::AggregateUDF, LogicalPlan},
+    optimizer::{optimizer::ApplyOrder, OptimizerConfig, OptimizerRule},
+    prelude::Expr,
+};
+use query_functions::PERCENTILE_UDAF_NAME;
+
+/// Rejects calls of the `percentile` aggregate whose percentile or `exact` argument is not a
+/// constant.
+///
+/// The accumulator only reads these arguments once, so a value that differs between rows cannot
+/// be honored. Constant expressions like `100 - 5` are folded by DataFusion before this rule runs.
+#[derive(Debug, Default)]
+pub struct ValidatePercentile;
+
+impl OptimizerRule for ValidatePercentile {
+    fn try_optimize(
+        &self,
+        plan: &LogicalPlan,
+        _config: &dyn OptimizerConfig,
+    ) -> Result<Option<LogicalPlan>> {
+        if let LogicalPlan::Aggregate(aggregate) = plan {
+            for expr in &aggregate.aggr_expr {
+                expr.apply(&mut |expr| {
+                    if let Expr::AggregateUDF(AggregateUDF { fun, args, .. }) = expr {
+                        if fun.name == PERCENTILE_UDAF_NAME {
+                            check_args(args)?;
+                        }
+                    }
+                    Ok(VisitRecursion::Continue)
+                })?;
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn name(&self) -> &str {
+        "validate_percentile"
+    }
+
+    fn apply_order(&self) -> Option<ApplyOrder> {
+        Some(ApplyOrder::TopDown)
+    }
+}
+
+fn check_args(args: &[Expr]) -> Result<()> {
+    for (arg, name) in args.iter().skip(1).zip(["percentile", "exact"]) {
+        if !matches!(arg, Expr::Literal(_)) {
+            return Err(DataFusionError::Plan(format!(
+                "{PERCENTILE_UDAF_NAME}: {name} must be a constant, got {arg}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion::{
+        logical_expr::{logical_plan, LogicalPlanBuilder},
+        optimizer::{optimizer::Optimizer, OptimizerContext},
+        prelude::{col, lit},
+    };
+
+    use super::*;
+
+    fn optimize(plan: &LogicalPlan) -> Result<Option<LogicalPlan>> {
+        let optimizer = Optimizer::with_rules(vec![Arc::new(ValidatePercentile)]);
+        optimizer.optimize_recursively(
+            optimizer.rules.get(0).unwrap(),
+            plan,
+            &OptimizerContext::new(),
+        )
+    }
+
+    fn aggregate_plan(aggr_expr: Expr) -> LogicalPlan {
+        let schema = Schema::new(vec![
+            Field::new("v", DataType::Float64, true),
+            Field::new("n", DataType::Int64, true),
+            Field::new("exact", DataType::Boolean, true),
+        ]);
+        LogicalPlanBuilder::from(
+            logical_plan::table_scan(Some("t"), &schema, None)
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+        .aggregate(Vec::<Expr>::new(), vec![aggr_expr])
+        .unwrap()
+        .build()
+        .unwrap()
+    }
+
+    fn percentile(args: Vec<Expr>) -> Expr {
+        query_functions::registry()
+            .udaf(PERCENTILE_UDAF_NAME)
+            .unwrap()
+            .call(args)
+    }
+
+    #[test]
+    fn test_constant_args() {
+        let plan = aggregate_plan(percentile(vec![col("v"), lit(90_i64), lit(true)]));
+        assert!(optimize(&plan).unwrap().is_none());
+
+        let plan = aggregate_plan(query_functions::median_expr(col("v"), false));
+        assert!(optimize(&plan).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_non_constant_percentile() {
+        let plan = aggregate_plan(percentile(vec![col("v"), col("n")]));
+        let err = optimize(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: percentile: percentile must be a constant, got t.n"
+        );
+    }
+
+    #[test]
+    fn test_non_constant_exact() {
+        let plan = aggregate_plan(percentile(vec![col("v"), lit(90_i64), col("exact")]));
+        let err = optimize(&plan).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: percentile: exact must be a constant, got t.exact"
+        );
+    }
+}
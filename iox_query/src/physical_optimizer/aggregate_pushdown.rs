@@ -0,0 +1,473 @@
+use std::sync::Arc;
+
+use arrow::{
+    array::Array,
+    compute::{cast, concat},
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        aggregates::{AggregateExec, AggregateMode},
+        expressions::{Column, Count, Literal, Max, Min, Sum},
+        memory::MemoryExec,
+        AggregateExpr, ExecutionPlan, PhysicalExpr,
+    },
+    scalar::ScalarValue,
+};
+use observability_deps::tracing::debug;
+
+use crate::{
+    physical_optimizer::chunk_extraction::extract_chunks,
+    provider::{overlap::timestamp_min_max, RetentionFilterExec},
+    ChunkAggregate,
+};
+
+/// Answers aggregates without grouping directly from the chunks if all chunks support it, see
+/// [`QueryChunk::pushdown_aggregate`].
+///
+/// This replaces the partial [`AggregateExec`] and the scan below it with a [`MemoryExec`] that contains one row of
+/// partial aggregation state per chunk. The final aggregation is left untouched.
+///
+/// This only works if there are no filters, de-duplication, or other operations between the aggregation and the
+/// chunks. A [`RetentionFilterExec`] is skipped if all chunks are entirely within the retention period, because it
+/// would not remove any rows.
+///
+///
+/// [`QueryChunk::pushdown_aggregate`]: crate::QueryChunk::pushdown_aggregate
+#[derive(Debug, Default)]
+pub struct AggregatePushdown;
+
+impl PhysicalOptimizerRule for AggregatePushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(aggregate_exec) = plan.as_any().downcast_ref::<AggregateExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            match pushdown(aggregate_exec)? {
+                Some(new_plan) => Ok(Transformed::Yes(new_plan)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "aggregate_pushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn pushdown(aggregate_exec: &AggregateExec) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if !matches!(aggregate_exec.mode(), AggregateMode::Partial)
+        || !aggregate_exec.group_expr().expr().is_empty()
+        || aggregate_exec.filter_expr().iter().any(Option::is_some)
+    {
+        return Ok(None);
+    }
+
+    let (input, retention_cutoff) = match aggregate_exec
+        .input()
+        .as_any()
+        .downcast_ref::<RetentionFilterExec>()
+    {
+        Some(retention_exec) => (retention_exec.input(), Some(retention_exec.cutoff())),
+        None => (aggregate_exec.input(), None),
+    };
+    let Some((_schema, chunks, _output_sort_key)) = extract_chunks(input.as_ref()) else {
+        return Ok(None);
+    };
+    if chunks.is_empty() {
+        return Ok(None);
+    }
+    if let Some(cutoff) = retention_cutoff {
+        let all_retained = chunks.iter().all(|chunk| {
+            timestamp_min_max(chunk.as_ref()).map_or(false, |time_range| time_range.min > cutoff)
+        });
+        if !all_retained {
+            debug!(
+                cutoff,
+                "cannot push down aggregate, chunks may contain expired rows"
+            );
+            return Ok(None);
+        }
+    }
+
+    // every aggregate must have a single state field so we can fill it with one value per chunk
+    let schema = aggregate_exec.schema();
+    if aggregate_exec
+        .aggr_expr()
+        .iter()
+        .any(|expr| !matches!(expr.state_fields().as_deref(), Ok([_])))
+    {
+        return Ok(None);
+    }
+    let Some(aggregates) = aggregate_exec
+        .aggr_expr()
+        .iter()
+        .map(|expr| chunk_aggregate(expr.as_ref()))
+        .collect::<Option<Vec<_>>>()
+    else {
+        return Ok(None);
+    };
+
+    let mut columns = Vec::with_capacity(aggregates.len());
+    for (aggregate, field) in aggregates.iter().zip(schema.fields()) {
+        let Some(values) = chunks
+            .iter()
+            .map(|chunk| chunk.pushdown_aggregate(aggregate))
+            .collect::<Option<Vec<_>>>()
+        else {
+            debug!(?aggregate, "cannot push down aggregate");
+            return Ok(None);
+        };
+
+        // chunks may return different types, so cast them individually
+        let arrays = values
+            .iter()
+            .map(|value| cast(&value.to_array_of_size(1), field.data_type()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let arrays = arrays
+            .iter()
+            .map(|a| a.as_ref())
+            .collect::<Vec<&dyn Array>>();
+        columns.push(concat(&arrays)?);
+    }
+
+    let batch = RecordBatch::try_new(Arc::clone(&schema), columns)?;
+    Ok(Some(Arc::new(MemoryExec::try_new(
+        &[vec![batch]],
+        schema,
+        None,
+    )?)))
+}
+
+/// Convert supported aggregate expression to [`ChunkAggregate`].
+fn chunk_aggregate(expr: &dyn AggregateExpr) -> Option<ChunkAggregate> {
+    let expr_any = expr.as_any();
+    let args = expr.expressions();
+    let [arg] = args.as_slice() else {
+        return None;
+    };
+
+    if expr_any.is::<Count>() {
+        if let Some(lit) = arg.as_any().downcast_ref::<Literal>() {
+            // `COUNT(*)` is planned as `COUNT(1)`
+            return (!lit.value().is_null()).then_some(ChunkAggregate::CountRows);
+        }
+        return column_name(arg).map(ChunkAggregate::Count);
+    }
+
+    let column = column_name(arg)?;
+    if expr_any.is::<Min>() {
+        Some(ChunkAggregate::Min(column))
+    } else if expr_any.is::<Max>() {
+        Some(ChunkAggregate::Max(column))
+    } else if expr_any.is::<Sum>() {
+        Some(ChunkAggregate::Sum(column))
+    } else {
+        None
+    }
+}
+
+fn column_name(expr: &Arc<dyn PhysicalExpr>) -> Option<String> {
+    expr.as_any()
+        .downcast_ref::<Column>()
+        .map(|col| col.name().to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType;
+    use datafusion::{
+        physical_plan::{aggregates::PhysicalGroupBy, filter::FilterExec},
+        prelude::SessionContext,
+    };
+
+    use crate::{
+        physical_optimizer::test_util::OptimizationTest, provider::chunks_to_physical_nodes,
+        test::TestChunk, QueryChunk,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pushdown() {
+        let chunk1 = chunk(1)
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::Int64(Some(3)))
+            .with_pushdown_aggregate(
+                ChunkAggregate::Min(String::from("field")),
+                ScalarValue::Int64(Some(5)),
+            )
+            .with_pushdown_aggregate(
+                ChunkAggregate::Sum(String::from("field")),
+                ScalarValue::Int64(Some(10)),
+            );
+        let chunk2 = chunk(2)
+            .with_dummy_parquet_file()
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::UInt64(Some(2)))
+            .with_pushdown_aggregate(
+                ChunkAggregate::Min(String::from("field")),
+                ScalarValue::Int64(Some(1)),
+            )
+            .with_pushdown_aggregate(
+                ChunkAggregate::Sum(String::from("field")),
+                ScalarValue::Int64(None),
+            );
+        let schema = chunk1.schema().as_arrow();
+        let plan = aggregate_plan(chunks_to_physical_nodes(
+            &schema,
+            None,
+            vec![Arc::new(chunk1), Arc::new(chunk2)],
+            2,
+        ));
+        let opt = AggregatePushdown;
+        let test = OptimizationTest::new(plan, opt);
+        insta::assert_yaml_snapshot!(
+            test,
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "     UnionExec"
+          - "       RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+          - "       ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, time]"
+        output:
+          Ok:
+            - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "   MemoryExec: partitions=1, partition_sizes=[1]"
+        "###
+        );
+
+        let plan = Arc::clone(test.output_plan().unwrap());
+        let batches = datafusion::physical_plan::collect(plan, SessionContext::new().task_ctx())
+            .await
+            .unwrap();
+        datafusion::assert_batches_eq!(
+            [
+                "+----------+------------+------------+",
+                "| COUNT(*) | MIN(field) | SUM(field) |",
+                "+----------+------------+------------+",
+                "| 5        | 1          | 10         |",
+                "+----------+------------+------------+",
+            ],
+            &batches
+        );
+    }
+
+    #[test]
+    fn test_chunk_does_not_support_aggregate() {
+        let chunk1 = chunk(1)
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::Int64(Some(3)));
+        let chunk2 = chunk(2);
+        let schema = chunk1.schema().as_arrow();
+        let plan = aggregate_plan(chunks_to_physical_nodes(
+            &schema,
+            None,
+            vec![Arc::new(chunk1), Arc::new(chunk2)],
+            2,
+        ));
+        let opt = AggregatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "     UnionExec"
+          - "       RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let chunk1 = chunk(1)
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::Int64(Some(3)))
+            .with_pushdown_aggregate(
+                ChunkAggregate::Min(String::from("field")),
+                ScalarValue::Int64(Some(5)),
+            )
+            .with_pushdown_aggregate(
+                ChunkAggregate::Sum(String::from("field")),
+                ScalarValue::Int64(Some(10)),
+            );
+        let schema = chunk1.schema().as_arrow();
+        let plan = aggregate_plan(Arc::new(
+            FilterExec::try_new(
+                Arc::new(Literal::new(ScalarValue::from(false))),
+                chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1)], 2),
+            )
+            .unwrap(),
+        ));
+        let opt = AggregatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "     FilterExec: false"
+          - "       UnionExec"
+          - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "     FilterExec: false"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_retention() {
+        let chunk1 = chunk(1)
+            .with_timestamp_min_max(101, 200)
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::Int64(Some(3)))
+            .with_pushdown_aggregate(
+                ChunkAggregate::Min(String::from("field")),
+                ScalarValue::Int64(Some(5)),
+            )
+            .with_pushdown_aggregate(
+                ChunkAggregate::Sum(String::from("field")),
+                ScalarValue::Int64(Some(10)),
+            );
+        let schema = chunk1.schema().as_arrow();
+        let plan = aggregate_plan(Arc::new(
+            RetentionFilterExec::try_new(
+                chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1)], 2),
+                100,
+            )
+            .unwrap(),
+        ));
+        let opt = AggregatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "     RetentionFilterExec: time@2 > 100"
+          - "       UnionExec"
+          - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "   MemoryExec: partitions=1, partition_sizes=[1]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_retention_expired_rows() {
+        let chunk1 = chunk(1)
+            .with_timestamp_min_max(100, 200)
+            .with_pushdown_aggregate(ChunkAggregate::CountRows, ScalarValue::Int64(Some(3)))
+            .with_pushdown_aggregate(
+                ChunkAggregate::Min(String::from("field")),
+                ScalarValue::Int64(Some(5)),
+            )
+            .with_pushdown_aggregate(
+                ChunkAggregate::Sum(String::from("field")),
+                ScalarValue::Int64(Some(10)),
+            );
+        let schema = chunk1.schema().as_arrow();
+        let plan = aggregate_plan(Arc::new(
+            RetentionFilterExec::try_new(
+                chunks_to_physical_nodes(&schema, None, vec![Arc::new(chunk1)], 2),
+                100,
+            )
+            .unwrap(),
+        ));
+        let opt = AggregatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+          - "     RetentionFilterExec: time@2 > 100"
+          - "       UnionExec"
+          - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " AggregateExec: mode=Final, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "   AggregateExec: mode=Partial, gby=[], aggr=[COUNT(*), MIN(field), SUM(field)]"
+            - "     RetentionFilterExec: time@2 > 100"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    fn chunk(id: u128) -> TestChunk {
+        TestChunk::new("table")
+            .with_id(id)
+            .with_tag_column("tag1")
+            .with_i64_field_column("field")
+            .with_time_column()
+    }
+
+    /// Create `SELECT COUNT(*), MIN(field), SUM(field)` as partial+final aggregation.
+    fn aggregate_plan(input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let schema = input.schema();
+        let field = Arc::new(Column::new_with_schema("field", &schema).unwrap()) as _;
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Count::new(
+                Arc::new(Literal::new(ScalarValue::UInt8(Some(1)))),
+                "COUNT(*)",
+                DataType::Int64,
+            )),
+            Arc::new(Min::new(Arc::clone(&field), "MIN(field)", DataType::Int64)),
+            Arc::new(Sum::new(field, "SUM(field)", DataType::Int64)),
+        ];
+
+        let partial = Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Partial,
+                PhysicalGroupBy::new_single(vec![]),
+                aggr_expr.clone(),
+                vec![None; aggr_expr.len()],
+                vec![None; aggr_expr.len()],
+                input,
+                Arc::clone(&schema),
+            )
+            .unwrap(),
+        );
+        Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Final,
+                PhysicalGroupBy::new_single(vec![]),
+                aggr_expr.clone(),
+                vec![None; aggr_expr.len()],
+                vec![None; aggr_expr.len()],
+                partial,
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+}
@@ -0,0 +1,132 @@
+//! Cost models that are consulted by the IOx-specific optimizer passes.
+//!
+//! Some optimizer passes have to pick between multiple equally correct plans, e.g. the sort order of a
+//! [`DeduplicateExec`]. The [`CostModel`] tells them how "expensive" the individual [`QueryChunk`]s involved in that
+//! decision are.
+//!
+//!
+//! [`DeduplicateExec`]: crate::provider::DeduplicateExec
+use std::{fmt::Debug, sync::Arc};
+
+use datafusion::config::ConfigOptions;
+
+use crate::{config::IoxConfigExt, QueryChunk, QueryChunkData};
+
+/// Estimates the cost of processing [`QueryChunk`]s.
+///
+/// Costs are unit-less and are only comparable within the same model.
+pub trait CostModel: Debug + Send + Sync {
+    /// Estimate the cost of processing the given chunk.
+    ///
+    /// Returns `None` if the model cannot estimate the cost for this chunk.
+    fn chunk_cost(&self, chunk: &dyn QueryChunk) -> Option<u64>;
+
+    /// Estimate the cost of processing each of the given chunks.
+    ///
+    /// If the cost for any of the chunks is unknown, this falls back to [`ChunkCountCostModel`] for ALL chunks so that
+    /// the returned costs are comparable.
+    fn chunk_costs(&self, chunks: &[Arc<dyn QueryChunk>]) -> Vec<u64> {
+        chunks
+            .iter()
+            .map(|chunk| self.chunk_cost(chunk.as_ref()))
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_else(|| vec![1; chunks.len()])
+    }
+}
+
+/// Every chunk has the same cost.
+///
+/// This is the default and results in the same behavior as if no cost model would be used.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ChunkCountCostModel;
+
+impl CostModel for ChunkCountCostModel {
+    fn chunk_cost(&self, _chunk: &dyn QueryChunk) -> Option<u64> {
+        Some(1)
+    }
+}
+
+/// Chunk cost is the number of rows in that chunk.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RowCountCostModel;
+
+impl CostModel for RowCountCostModel {
+    fn chunk_cost(&self, chunk: &dyn QueryChunk) -> Option<u64> {
+        chunk.stats().num_rows.map(|n| n as u64)
+    }
+}
+
+/// Chunk cost is the number of bytes in that chunk.
+///
+/// For parquet-based chunks this is the file size, for record batches this is the in-memory size.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ByteSizeCostModel;
+
+impl CostModel for ByteSizeCostModel {
+    fn chunk_cost(&self, chunk: &dyn QueryChunk) -> Option<u64> {
+        if let Some(size) = chunk.stats().total_byte_size {
+            return Some(size as u64);
+        }
+
+        match chunk.data() {
+            QueryChunkData::RecordBatches(batches) => Some(
+                batches
+                    .iter()
+                    .map(|batch| batch.get_array_memory_size() as u64)
+                    .sum(),
+            ),
+            QueryChunkData::Parquet(input) => Some(input.object_meta.size as u64),
+            QueryChunkData::Stream(_) => None,
+        }
+    }
+}
+
+/// Get [`CostModel`] that was configured for the given session.
+pub fn cost_model(config: &ConfigOptions) -> Arc<dyn CostModel> {
+    config
+        .extensions
+        .get::<IoxConfigExt>()
+        .cloned()
+        .unwrap_or_default()
+        .cost_model
+        .model()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test::TestChunk;
+
+    use super::*;
+
+    #[test]
+    fn test_chunk_count() {
+        let chunks = chunks();
+        assert_eq!(ChunkCountCostModel.chunk_costs(&chunks), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_row_count() {
+        let chunks = chunks();
+        assert_eq!(RowCountCostModel.chunk_costs(&chunks), vec![1, 3]);
+
+        // unknown row count falls back to chunk count
+        let chunks = vec![Arc::clone(&chunks[0]), Arc::new(TestChunk::new("t")) as _];
+        assert_eq!(RowCountCostModel.chunk_costs(&chunks), vec![1, 1]);
+    }
+
+    #[test]
+    fn test_byte_size() {
+        let chunk = TestChunk::new("t").with_dummy_parquet_file();
+        assert_eq!(ByteSizeCostModel.chunk_cost(&chunk), Some(1));
+
+        let chunk = TestChunk::new("t").with_time_column().with_stream();
+        assert_eq!(ByteSizeCostModel.chunk_cost(&chunk), None);
+    }
+
+    fn chunks() -> Vec<Arc<dyn QueryChunk>> {
+        vec![
+            Arc::new(TestChunk::new("t").with_row_count(1)) as _,
+            Arc::new(TestChunk::new("t").with_row_count(3)) as _,
+        ]
+    }
+}
@@ -0,0 +1,344 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    physical_expr::utils::expr_list_eq_any_order,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        aggregates::{AggregateExec, AggregateMode},
+        ExecutionPlan,
+    },
+};
+
+use crate::provider::DateBinOrderExec;
+
+/// Stream `GROUP BY date_bin(...)` aggregations over time-sorted input instead of hash-aggregating them.
+///
+/// DataFusion only evaluates an [`AggregateExec`] in streaming mode -- emitting each group as soon as the next one
+/// starts -- if the input is ordered by all group expressions. Chunk scans are usually sorted by time, but DataFusion
+/// does not know that this implies an ordering by `date_bin(stride, time, origin)`, so long-range downsampling queries
+/// keep all groups in memory until the input is exhausted.
+///
+/// This rule inserts a [`DateBinOrderExec`] that declares the derived ordering:
+///
+/// ```text
+/// AggregateExec: gby=[date_bin(...)]            AggregateExec: gby=[date_bin(...)], ordering_mode=FullyOrdered
+///   ParquetExec: output_ordering=[time]   --->    DateBinOrderExec: output_ordering=[date_bin(...), time]
+///                                                   ParquetExec: output_ordering=[time]
+/// ```
+///
+/// The rewrite only happens if the extended ordering covers all group expressions.
+#[derive(Debug, Default)]
+pub struct DateBinAggregate;
+
+impl PhysicalOptimizerRule for DateBinAggregate {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let Some(aggregate_exec) = plan.as_any().downcast_ref::<AggregateExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            match ordered_aggregate(aggregate_exec)? {
+                Some(new_plan) => Ok(Transformed::Yes(new_plan)),
+                None => Ok(Transformed::No(plan)),
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "date_bin_aggregate"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn ordered_aggregate(aggregate_exec: &AggregateExec) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    // final aggregations read partial states that are hash-partitioned, so they are never ordered
+    if !matches!(
+        aggregate_exec.mode(),
+        AggregateMode::Partial | AggregateMode::Single
+    ) {
+        return Ok(None);
+    }
+
+    let group_by = aggregate_exec.group_expr();
+    if group_by.groups().len() != 1 || !group_by.null_expr().is_empty() {
+        // grouping sets
+        return Ok(None);
+    }
+    let group_exprs = group_by
+        .expr()
+        .iter()
+        .map(|(expr, _name)| Arc::clone(expr))
+        .collect::<Vec<_>>();
+
+    let input = aggregate_exec.input();
+    if input.as_any().is::<DateBinOrderExec>() {
+        return Ok(None);
+    }
+
+    for date_bin in &group_exprs {
+        let Some(exec) = DateBinOrderExec::try_new(Arc::clone(input), Arc::clone(date_bin)) else {
+            continue;
+        };
+
+        // only worth it if the aggregation can be fully streamed
+        let Some(ordering) = exec.output_ordering() else {
+            continue;
+        };
+        if ordering.len() < group_exprs.len() {
+            continue;
+        }
+        let ordering_prefix = ordering[..group_exprs.len()]
+            .iter()
+            .map(|sort_expr| Arc::clone(&sort_expr.expr))
+            .collect::<Vec<_>>();
+        if !expr_list_eq_any_order(&ordering_prefix, &group_exprs) {
+            continue;
+        }
+
+        return Ok(Some(Arc::new(AggregateExec::try_new(
+            *aggregate_exec.mode(),
+            group_by.clone(),
+            aggregate_exec.aggr_expr().to_vec(),
+            aggregate_exec.filter_expr().to_vec(),
+            aggregate_exec.order_by_expr().to_vec(),
+            Arc::new(exec),
+            aggregate_exec.input_schema(),
+        )?)));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+    use datafusion::{
+        datasource::{
+            listing::PartitionedFile,
+            object_store::ObjectStoreUrl,
+            physical_plan::{FileScanConfig, ParquetExec},
+        },
+        execution::context::ExecutionProps,
+        logical_expr::BuiltinScalarFunction,
+        physical_expr::{functions::create_physical_expr, PhysicalSortExpr},
+        physical_plan::{
+            aggregates::PhysicalGroupBy,
+            expressions::{Column, Count, Literal},
+            AggregateExpr, PhysicalExpr, Statistics,
+        },
+        scalar::ScalarValue,
+    };
+    use object_store::{path::Path, ObjectMeta};
+
+    use crate::physical_optimizer::test_util::OptimizationTest;
+
+    use super::*;
+
+    #[test]
+    fn test_time_ordered() {
+        let schema = schema();
+        let plan = aggregate_plan(
+            vec![date_bin(&schema)],
+            parquet_exec(ordering(["time"], &schema)),
+        );
+        let opt = DateBinAggregate;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Partial, gby=[date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+          - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[time@2 ASC]"
+        output:
+          Ok:
+            - " AggregateExec: mode=Partial, gby=[date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)], ordering_mode=FullyOrdered"
+            - "   DateBinOrderExec: output_ordering=[date_bin(10, time@2, 0) ASC, time@2 ASC]"
+            - "     ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[time@2 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_tag_and_time_ordered() {
+        let schema = schema();
+        let plan = aggregate_plan(
+            vec![col("tag", &schema), date_bin(&schema)],
+            parquet_exec(ordering(["tag", "time"], &schema)),
+        );
+        let opt = DateBinAggregate;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Partial, gby=[tag@0 as tag, date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+          - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[tag@0 ASC, time@2 ASC]"
+        output:
+          Ok:
+            - " AggregateExec: mode=Partial, gby=[tag@0 as tag, date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)], ordering_mode=FullyOrdered"
+            - "   DateBinOrderExec: output_ordering=[tag@0 ASC, date_bin(10, time@2, 0) ASC, time@2 ASC]"
+            - "     ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[tag@0 ASC, time@2 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_group_not_covered_by_ordering() {
+        // ordered by time only, but also grouped by tag
+        let schema = schema();
+        let plan = aggregate_plan(
+            vec![col("tag", &schema), date_bin(&schema)],
+            parquet_exec(ordering(["time"], &schema)),
+        );
+        let opt = DateBinAggregate;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Partial, gby=[tag@0 as tag, date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+          - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[time@2 ASC]"
+        output:
+          Ok:
+            - " AggregateExec: mode=Partial, gby=[tag@0 as tag, date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+            - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[time@2 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_not_time_ordered() {
+        let schema = schema();
+        let plan = aggregate_plan(
+            vec![date_bin(&schema)],
+            parquet_exec(ordering(["tag"], &schema)),
+        );
+        let opt = DateBinAggregate;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " AggregateExec: mode=Partial, gby=[date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+          - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[tag@0 ASC]"
+        output:
+          Ok:
+            - " AggregateExec: mode=Partial, gby=[date_bin(10, time@2, 0) as bin], aggr=[COUNT(field)]"
+            - "   ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[tag, field, time], output_ordering=[tag@0 ASC]"
+        "###
+        );
+    }
+
+    /// `SELECT COUNT(field) ... GROUP BY <group_exprs>` as partial aggregation.
+    fn aggregate_plan(
+        group_exprs: Vec<(Arc<dyn PhysicalExpr>, String)>,
+        input: Arc<dyn ExecutionPlan>,
+    ) -> Arc<dyn ExecutionPlan> {
+        let schema = input.schema();
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Count::new(
+            col("field", &schema).0,
+            "COUNT(field)",
+            DataType::Int64,
+        ))];
+
+        Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Partial,
+                PhysicalGroupBy::new_single(group_exprs),
+                aggr_expr.clone(),
+                vec![None; aggr_expr.len()],
+                vec![None; aggr_expr.len()],
+                input,
+                schema,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn col(name: &str, schema: &SchemaRef) -> (Arc<dyn PhysicalExpr>, String) {
+        (
+            Arc::new(Column::new_with_schema(name, schema).unwrap()),
+            name.to_owned(),
+        )
+    }
+
+    /// `date_bin(INTERVAL '10 nanoseconds', time, 0) AS bin`
+    fn date_bin(schema: &SchemaRef) -> (Arc<dyn PhysicalExpr>, String) {
+        let expr = create_physical_expr(
+            &BuiltinScalarFunction::DateBin,
+            &[
+                Arc::new(Literal::new(ScalarValue::new_interval_mdn(0, 0, 10))),
+                col("time", schema).0,
+                Arc::new(Literal::new(ScalarValue::TimestampNanosecond(
+                    Some(0),
+                    None,
+                ))),
+            ],
+            schema,
+            &ExecutionProps::new(),
+        )
+        .unwrap();
+        (expr, "bin".to_owned())
+    }
+
+    fn parquet_exec(output_ordering: Vec<PhysicalSortExpr>) -> Arc<dyn ExecutionPlan> {
+        let schema = schema();
+        let base_config = FileScanConfig {
+            object_store_url: ObjectStoreUrl::parse("test://").unwrap(),
+            file_schema: Arc::clone(&schema),
+            file_groups: vec![vec![file(1)]],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![output_ordering],
+            infinite_source: false,
+        };
+        Arc::new(ParquetExec::new(base_config, None, None))
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("tag", DataType::Utf8, true),
+            Field::new("field", DataType::Int64, true),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+        ]))
+    }
+
+    fn file(n: u128) -> PartitionedFile {
+        PartitionedFile {
+            object_meta: ObjectMeta {
+                location: Path::parse(format!("{n}.parquet")).unwrap(),
+                last_modified: Default::default(),
+                size: 0,
+                e_tag: None,
+            },
+            partition_values: vec![],
+            range: None,
+            extensions: None,
+        }
+    }
+
+    fn ordering<const N: usize>(cols: [&str; N], schema: &SchemaRef) -> Vec<PhysicalSortExpr> {
+        cols.into_iter()
+            .map(|name| PhysicalSortExpr {
+                expr: col(name, schema).0,
+                options: Default::default(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,441 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    physical_expr::{
+        utils::{collect_columns, reassign_predicate_columns},
+        PhysicalSortExpr,
+    },
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        expressions::Column,
+        filter::FilterExec,
+        projection::ProjectionExec,
+        sorts::{sort::SortExec, sort_preserving_merge::SortPreservingMergeExec},
+        union::UnionExec,
+        ExecutionPlan, PhysicalExpr,
+    },
+};
+
+use crate::{
+    provider::{DeduplicateExec, RetentionFilterExec},
+    CHUNK_ORDER_COLUMN_NAME,
+};
+
+/// Drops the [chunk order column](CHUNK_ORDER_COLUMN_NAME) as soon as de-duplication is complete.
+///
+/// [`DeduplicateExec`] needs the chunk order column to resolve duplicates, but nothing downstream of it does. The
+/// column is only removed by the final [`ProjectionExec`] of the scan though, so it -- and sort expressions on it --
+/// would otherwise be carried through filters, sorts and unions.
+///
+/// This looks for [`ProjectionExec`]s that do NOT use the column and rewrites the chain of operators between the
+/// projection and the de-duplication so that the column is projected away directly after [`DeduplicateExec`]. The
+/// chain is only rewritten if it solely consists of [`FilterExec`], [`RetentionFilterExec`], [`SortExec`],
+/// [`SortPreservingMergeExec`] and [`UnionExec`] nodes.
+#[derive(Debug, Default)]
+pub struct ChunkOrderElimination;
+
+impl PhysicalOptimizerRule for ChunkOrderElimination {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        _config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(proj_exec) = plan.as_any().downcast_ref::<ProjectionExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+
+            if proj_exec
+                .expr()
+                .iter()
+                .any(|(expr, _name)| uses_chunk_order(expr))
+            {
+                return Ok(Transformed::No(plan));
+            }
+
+            // projection already drops the column directly after de-dup
+            if proj_exec.input().as_any().is::<DeduplicateExec>() {
+                return Ok(Transformed::No(plan));
+            }
+
+            let Some(input) = strip_chunk_order(Arc::clone(proj_exec.input()))? else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let expr = proj_exec
+                .expr()
+                .iter()
+                .map(|(expr, name)| {
+                    Ok((
+                        reassign_predicate_columns(Arc::clone(expr), &input.schema(), false)?,
+                        name.clone(),
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Transformed::Yes(Arc::new(ProjectionExec::try_new(
+                expr, input,
+            )?)))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "chunk_order_elimination"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Rebuilds `plan` so that it no longer outputs the chunk order column.
+///
+/// Returns `None` if `plan` is not a chain of supported nodes that ends in [`DeduplicateExec`] or if a node within
+/// that chain uses the chunk order column for something else than sorting.
+fn strip_chunk_order(plan: Arc<dyn ExecutionPlan>) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let schema = plan.schema();
+    let Ok(chunk_order_idx) = schema.index_of(CHUNK_ORDER_COLUMN_NAME) else {
+        return Ok(None);
+    };
+
+    let plan_any = plan.as_any();
+    if plan_any.is::<DeduplicateExec>() {
+        let expr = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .filter(|(idx, _field)| *idx != chunk_order_idx)
+            .map(|(idx, field)| {
+                (
+                    Arc::new(Column::new(field.name(), idx)) as Arc<dyn PhysicalExpr>,
+                    field.name().clone(),
+                )
+            })
+            .collect();
+        Ok(Some(Arc::new(ProjectionExec::try_new(expr, plan)?)))
+    } else if let Some(filter_exec) = plan_any.downcast_ref::<FilterExec>() {
+        if uses_chunk_order(filter_exec.predicate()) {
+            return Ok(None);
+        }
+        let Some(input) = strip_chunk_order(Arc::clone(filter_exec.input()))? else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(FilterExec::try_new(
+            reassign_predicate_columns(
+                Arc::clone(filter_exec.predicate()),
+                &input.schema(),
+                false,
+            )?,
+            input,
+        )?)))
+    } else if let Some(retention_exec) = plan_any.downcast_ref::<RetentionFilterExec>() {
+        let Some(input) = strip_chunk_order(Arc::clone(retention_exec.input()))? else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(RetentionFilterExec::try_new(
+            input,
+            retention_exec.cutoff(),
+        )?)))
+    } else if let Some(sort_exec) = plan_any.downcast_ref::<SortExec>() {
+        let Some(input) = strip_chunk_order(Arc::clone(sort_exec.input()))? else {
+            return Ok(None);
+        };
+        let Some(expr) = sort_exprs_without_chunk_order(sort_exec.expr(), &input)? else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(
+            SortExec::new(expr, input)
+                .with_preserve_partitioning(sort_exec.preserve_partitioning())
+                .with_fetch(sort_exec.fetch()),
+        )))
+    } else if let Some(merge_exec) = plan_any.downcast_ref::<SortPreservingMergeExec>() {
+        let Some(input) = strip_chunk_order(Arc::clone(merge_exec.input()))? else {
+            return Ok(None);
+        };
+        let Some(expr) = sort_exprs_without_chunk_order(merge_exec.expr(), &input)? else {
+            return Ok(None);
+        };
+        Ok(Some(Arc::new(SortPreservingMergeExec::new(expr, input))))
+    } else if let Some(union_exec) = plan_any.downcast_ref::<UnionExec>() {
+        let mut inputs = Vec::with_capacity(union_exec.inputs().len());
+        for input in union_exec.inputs() {
+            let Some(input) = strip_chunk_order(Arc::clone(input))? else {
+                return Ok(None);
+            };
+            inputs.push(input);
+        }
+        Ok(Some(Arc::new(UnionExec::new(inputs))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Removes sort expressions on the chunk order column and rebinds the remaining ones to the schema of `input`.
+///
+/// Returns `None` if no sort expressions are left.
+fn sort_exprs_without_chunk_order(
+    sort_exprs: &[PhysicalSortExpr],
+    input: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Vec<PhysicalSortExpr>>> {
+    let schema = input.schema();
+    let sort_exprs = sort_exprs
+        .iter()
+        .filter(|sort_expr| !uses_chunk_order(&sort_expr.expr))
+        .map(|sort_expr| {
+            Ok(PhysicalSortExpr {
+                expr: reassign_predicate_columns(Arc::clone(&sort_expr.expr), &schema, false)?,
+                options: sort_expr.options,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if sort_exprs.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(sort_exprs))
+    }
+}
+
+fn uses_chunk_order(expr: &Arc<dyn PhysicalExpr>) -> bool {
+    collect_columns(expr)
+        .iter()
+        .any(|col| col.name() == CHUNK_ORDER_COLUMN_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::compute::SortOptions;
+    use datafusion::{
+        logical_expr::Operator,
+        physical_plan::expressions::{col, lit, BinaryExpr},
+        scalar::ScalarValue,
+    };
+
+    use crate::{
+        physical_optimizer::{
+            dedup::test_util::{chunk, dedup_plan, dedup_plan_with_chunk_order_col},
+            test_util::OptimizationTest,
+        },
+        QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan_with_chunk_order_col(schema, vec![chunk1]);
+        let plan = Arc::new(
+            FilterExec::try_new(
+                Arc::new(BinaryExpr::new(
+                    col("field", &plan.schema()).unwrap(),
+                    Operator::Gt,
+                    lit(ScalarValue::Int64(Some(1))),
+                )),
+                plan,
+            )
+            .unwrap(),
+        );
+        let plan = projection(&["tag1", "time"], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+          - "   FilterExec: field@0 > 1"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+            - "   FilterExec: field@0 > 1"
+            - "     ProjectionExec: expr=[field@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+            - "       DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "         UnionExec"
+            - "           ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_sort_on_chunk_order() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan_with_chunk_order_col(schema, vec![chunk1]);
+        let plan = Arc::new(SortExec::new(
+            vec![
+                sort_expr("time", &plan),
+                sort_expr(CHUNK_ORDER_COLUMN_NAME, &plan),
+            ],
+            plan,
+        ));
+        let plan = projection(&["tag1", "time"], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+          - "   SortExec: expr=[time@3 ASC,__chunk_order@4 ASC]"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+            - "   SortExec: expr=[time@3 ASC]"
+            - "     ProjectionExec: expr=[field@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+            - "       DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "         UnionExec"
+            - "           ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_union() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let chunk2 = chunk(2).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = Arc::new(UnionExec::new(vec![
+            dedup_plan_with_chunk_order_col(schema.clone(), vec![chunk1]),
+            dedup_plan_with_chunk_order_col(schema, vec![chunk2]),
+        ]));
+        let plan = projection(&["tag1", "time"], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+          - "   UnionExec"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+            - "   UnionExec"
+            - "     ProjectionExec: expr=[field@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+            - "       DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "         UnionExec"
+            - "           ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+            - "     ProjectionExec: expr=[field@0 as field, tag1@1 as tag1, tag2@2 as tag2, time@3 as time]"
+            - "       DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "         UnionExec"
+            - "           ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_projection_directly_above_dedup() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan_with_chunk_order_col(schema, vec![chunk1]);
+        let plan = projection(&["tag1", "time"], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+          - "   DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "     UnionExec"
+          - "       ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+            - "   DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "     UnionExec"
+            - "       ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_projection_uses_chunk_order() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan_with_chunk_order_col(schema, vec![chunk1]);
+        let plan = Arc::new(SortExec::new(vec![sort_expr("time", &plan)], plan));
+        let plan = projection(&["time", CHUNK_ORDER_COLUMN_NAME], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[time@3 as time, __chunk_order@4 as __chunk_order]"
+          - "   SortExec: expr=[time@3 ASC]"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[time@3 as time, __chunk_order@4 as __chunk_order]"
+            - "   SortExec: expr=[time@3 ASC]"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_no_chunk_order_col() {
+        let chunk1 = chunk(1).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1]);
+        let plan = Arc::new(SortExec::new(vec![sort_expr("time", &plan)], plan));
+        let plan = projection(&["tag1", "time"], plan);
+        let opt = ChunkOrderElimination;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+          - "   SortExec: expr=[time@3 ASC]"
+          - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "       UnionExec"
+          - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+            - "   SortExec: expr=[time@3 ASC]"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         ParquetExec: file_groups={1 group: [[1.parquet]]}, projection=[field, tag1, tag2, time]"
+        "###
+        );
+    }
+
+    fn projection(cols: &[&str], input: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        let schema = input.schema();
+        let expr = cols
+            .iter()
+            .map(|name| (col(name, &schema).unwrap(), name.to_string()))
+            .collect();
+        Arc::new(ProjectionExec::try_new(expr, input).unwrap())
+    }
+
+    fn sort_expr(name: &str, input: &Arc<dyn ExecutionPlan>) -> PhysicalSortExpr {
+        PhysicalSortExpr {
+            expr: col(name, &input.schema()).unwrap(),
+            options: SortOptions::default(),
+        }
+    }
+}
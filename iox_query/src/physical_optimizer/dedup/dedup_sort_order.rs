@@ -12,7 +12,7 @@ use indexmap::IndexSet;
 use schema::{sort::SortKeyBuilder, TIME_COLUMN_NAME};
 
 use crate::{
-    physical_optimizer::chunk_extraction::extract_chunks,
+    physical_optimizer::{chunk_extraction::extract_chunks, cost::cost_model},
     provider::{chunks_to_physical_nodes, DeduplicateExec},
     util::arrow_sort_key_exprs,
     CHUNK_ORDER_COLUMN_NAME,
@@ -24,7 +24,9 @@ use crate::{
 /// This means that the sort key of the [`DeduplicateExec`] should be as close as possible to the pre-sorted chunks to
 /// avoid resorting. If all chunks are pre-sorted (or not sorted at all), this is basically the joined merged sort key
 /// of all of them. If the chunks do not agree on a single sort order[^different_orders], then we use a vote-based
-/// system where we column-by-column pick the sort key order in the hope that this does the least harm.
+/// system where we column-by-column pick the sort key order in the hope that this does the least harm. Votes are
+/// weighted by the configured [cost model](crate::physical_optimizer::cost::CostModel), so that expensive chunks avoid
+/// resorting rather than cheap ones.
 ///
 /// The produces sort key MUST be the same set of columns as before, i.e. this rule does NOT change the column set, it
 /// only changes the order.
@@ -61,6 +63,8 @@ impl PhysicalOptimizerRule for DedupSortOrder {
                     return Ok(Transformed::No(plan))
                 };
 
+                let chunk_costs = cost_model(config).chunk_costs(&chunks);
+
                 let mut chunk_sort_keys: Vec<IndexSet<_>> = chunks
                     .iter()
                     .map(|chunk| {
@@ -86,7 +90,8 @@ impl PhysicalOptimizerRule for DedupSortOrder {
                     let candidate_counts = todo_pk_columns.iter().copied().map(|col| {
                         let count = chunk_sort_keys
                             .iter()
-                            .filter(|sort_key| {
+                            .zip(&chunk_costs)
+                            .filter(|(sort_key, _cost)| {
                                 match sort_key.get_index_of(col) {
                                     Some(idx) if idx == 0 => {
                                         // Column next in sort order from this chunks PoV. This is good.
@@ -105,7 +110,8 @@ impl PhysicalOptimizerRule for DedupSortOrder {
                                     }
                                 }
                             })
-                            .count();
+                            .map(|(_sort_key, cost)| *cost)
+                            .sum::<u64>();
                         (col, count)
                     });
                     let candidate_counts = sorted(
@@ -169,6 +175,7 @@ mod tests {
     use schema::{sort::SortKey, SchemaBuilder, TIME_COLUMN_NAME};
 
     use crate::{
+        config::{CostModelKind, IoxConfigExt},
         physical_optimizer::{
             dedup::test_util::{chunk, dedup_plan, dedup_plan_with_chunk_order_col},
             test_util::OptimizationTest,
@@ -407,6 +414,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_two_chunks_weighted_by_cost_model() {
+        let chunk1 = chunk(1)
+            .with_dummy_parquet_file()
+            .with_row_count(1)
+            .with_sort_key(SortKey::from_columns([
+                Arc::from("tag1"),
+                Arc::from("tag2"),
+                Arc::from(TIME_COLUMN_NAME),
+            ]));
+        let chunk2 = chunk(2)
+            .with_dummy_parquet_file()
+            .with_row_count(10)
+            .with_sort_key(SortKey::from_columns([
+                Arc::from("tag2"),
+                Arc::from("tag1"),
+                Arc::from(TIME_COLUMN_NAME),
+            ]));
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = DedupSortOrder;
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            cost_model: CostModelKind::RowCount,
+            ..Default::default()
+        });
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new_with_config(plan, opt, &config),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     ParquetExec: file_groups={2 groups: [[1.parquet], [2.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag2@2 ASC,tag1@1 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     ParquetExec: file_groups={2 groups: [[1.parquet], [2.parquet]]}, projection=[field, tag1, tag2, time]"
+        "###
+        );
+    }
+
     #[test]
     fn test_two_chunks_sorted_ranks_higher_than_not_sorted() {
         let chunk1 = chunk(1)
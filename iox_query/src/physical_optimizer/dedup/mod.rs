@@ -1,9 +1,12 @@
 //! Optimizer passes concering de-duplication.
 
+pub mod chunk_order_elimination;
 pub mod dedup_null_columns;
 pub mod dedup_sort_order;
 pub mod partition_split;
 pub mod remove_dedup;
+pub mod remove_disjoint_dedup;
+pub mod time_bucket_split;
 pub mod time_split;
 
 #[cfg(test)]
@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::ExecutionPlan,
+};
+
+use crate::{
+    physical_optimizer::chunk_extraction::extract_chunks,
+    provider::{chunks_to_physical_nodes, group_potential_duplicates, DeduplicateExec},
+};
+
+/// Removes de-duplication operation if the primary-key ranges of all chunks are disjoint and none of the chunks
+/// contains primary-key duplicates.
+///
+/// Since the time column is part of the primary key, non-overlapping time ranges prove that the chunks cannot share
+/// any primary key. This is usually the case for cleanly compacted partitions.
+///
+/// In contrast to [`TimeSplit`] followed by [`RemoveDedup`], this keeps all chunks within a single scan and is not
+/// limited by [`max_dedup_time_split`].
+///
+///
+/// [`max_dedup_time_split`]: crate::config::IoxConfigExt::max_dedup_time_split
+/// [`RemoveDedup`]: super::remove_dedup::RemoveDedup
+/// [`TimeSplit`]: super::time_split::TimeSplit
+#[derive(Debug, Default)]
+pub struct RemoveDisjointDedup;
+
+impl PhysicalOptimizerRule for RemoveDisjointDedup {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_up(&|plan| {
+            let plan_any = plan.as_any();
+
+            if let Some(dedup_exec) = plan_any.downcast_ref::<DeduplicateExec>() {
+                let mut children = dedup_exec.children();
+                assert_eq!(children.len(), 1);
+                let child = children.remove(0);
+                let Some((schema, chunks, output_sort_key)) = extract_chunks(child.as_ref()) else {
+                    return Ok(Transformed::No(plan));
+                };
+
+                if chunks.len() < 2 || chunks.iter().any(|c| c.may_contain_pk_duplicates()) {
+                    // single chunks are handled by `RemoveDedup`
+                    return Ok(Transformed::No(plan));
+                }
+
+                let groups = group_potential_duplicates(chunks);
+                if groups.iter().any(|group| group.len() > 1) {
+                    return Ok(Transformed::No(plan));
+                }
+
+                return Ok(Transformed::Yes(chunks_to_physical_nodes(
+                    &schema,
+                    output_sort_key.as_ref(),
+                    groups.into_iter().flatten().collect(),
+                    config.execution.target_partitions,
+                )));
+            }
+
+            Ok(Transformed::No(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "remove_disjoint_dedup"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        physical_optimizer::{
+            dedup::test_util::{chunk, dedup_plan},
+            test_util::OptimizationTest,
+        },
+        QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_no_chunks() {
+        let schema = chunk(1).schema().clone();
+        let plan = dedup_plan(schema, vec![]);
+        let opt = RemoveDisjointDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   EmptyExec: produce_one_row=false"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   EmptyExec: produce_one_row=false"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_disjoint() {
+        let chunk1 = chunk(1)
+            .with_may_contain_pk_duplicates(false)
+            .with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2)
+            .with_may_contain_pk_duplicates(false)
+            .with_timestamp_min_max(11, 12);
+        let chunk3 = chunk(3)
+            .with_may_contain_pk_duplicates(false)
+            .with_dummy_parquet_file()
+            .with_timestamp_min_max(20, 30);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2, chunk3]);
+        let opt = RemoveDisjointDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+          - "     ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " UnionExec"
+            - "   RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+            - "   ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_overlap() {
+        let chunk1 = chunk(1)
+            .with_may_contain_pk_duplicates(false)
+            .with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2)
+            .with_may_contain_pk_duplicates(false)
+            .with_timestamp_min_max(10, 12);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = RemoveDisjointDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_disjoint_with_pk_dups() {
+        let chunk1 = chunk(1)
+            .with_may_contain_pk_duplicates(false)
+            .with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2)
+            .with_may_contain_pk_duplicates(true)
+            .with_timestamp_min_max(11, 12);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = RemoveDisjointDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_unknown_time_range() {
+        let chunk1 = chunk(1).with_may_contain_pk_duplicates(false);
+        let chunk2 = chunk(2).with_may_contain_pk_duplicates(false);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = RemoveDisjointDedup;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+}
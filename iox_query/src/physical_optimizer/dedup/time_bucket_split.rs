@@ -0,0 +1,322 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    error::Result,
+    logical_expr::Operator,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        expressions::{BinaryExpr, Column, Literal},
+        filter::FilterExec,
+        union::UnionExec,
+        ExecutionPlan, PhysicalExpr,
+    },
+    scalar::ScalarValue,
+};
+use schema::TIME_COLUMN_NAME;
+
+use crate::{
+    config::IoxConfigExt,
+    physical_optimizer::chunk_extraction::extract_chunks,
+    provider::{chunks_to_physical_nodes, overlap::timestamp_min_max, DeduplicateExec},
+};
+
+/// Split de-duplication operations into disjoint time buckets that are processed in parallel.
+///
+/// Since the time column is part of the primary key, rows that are in different time buckets can never be
+/// duplicates. Hence every bucket can be de-duplicated on its own, using only the chunks that overlap with the
+/// bucket. This is mostly useful for large partitions with many overlapping chunks that would otherwise be
+/// de-duplicated by a single stream.
+///
+/// The number of buckets is controlled by [`dedup_time_buckets`]. The time filter for every bucket is placed above the
+/// de-duplication so that other passes still see plain chunks below [`DeduplicateExec`]. It is later pushed down into
+/// the scans by [`PredicatePushdown`].
+///
+/// [`dedup_time_buckets`]: crate::config::IoxConfigExt::dedup_time_buckets
+/// [`PredicatePushdown`]: crate::physical_optimizer::predicate_pushdown::PredicatePushdown
+#[derive(Debug, Default)]
+pub struct TimeBucketSplit;
+
+impl PhysicalOptimizerRule for TimeBucketSplit {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let n_buckets = config
+            .extensions
+            .get::<IoxConfigExt>()
+            .cloned()
+            .unwrap_or_default()
+            .dedup_time_buckets;
+        if n_buckets < 2 {
+            return Ok(plan);
+        }
+
+        plan.transform_up(&|plan| {
+            let plan_any = plan.as_any();
+
+            if let Some(dedup_exec) = plan_any.downcast_ref::<DeduplicateExec>() {
+                let mut children = dedup_exec.children();
+                assert_eq!(children.len(), 1);
+                let child = children.remove(0);
+                let Some((schema, chunks, output_sort_key)) = extract_chunks(child.as_ref()) else {
+                    return Ok(Transformed::No(plan));
+                };
+                let Ok(time_col) = Column::new_with_schema(TIME_COLUMN_NAME, &schema) else {
+                    return Ok(Transformed::No(plan));
+                };
+
+                // all chunks must have a known time range
+                let Some(ranges) = chunks
+                    .iter()
+                    .map(|chunk| timestamp_min_max(chunk.as_ref()))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return Ok(Transformed::No(plan));
+                };
+                let (Some(min), Some(max)) = (
+                    ranges.iter().map(|r| r.min).min(),
+                    ranges.iter().map(|r| r.max).max(),
+                ) else {
+                    return Ok(Transformed::No(plan));
+                };
+
+                let buckets = bucket_bounds(min, max, n_buckets);
+                let n = buckets.len();
+                if n < 2 {
+                    return Ok(Transformed::No(plan));
+                }
+
+                let inputs = buckets
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(idx, (start, end))| {
+                        // first and last bucket are open so we don't rely on exact statistics
+                        let start = (idx > 0).then_some(start);
+                        let end = (idx < n - 1).then_some(end);
+
+                        let chunks = chunks
+                            .iter()
+                            .zip(&ranges)
+                            .filter(|(_chunk, range)| {
+                                start.map(|start| range.max >= start).unwrap_or(true)
+                                    && end.map(|end| range.min < end).unwrap_or(true)
+                            })
+                            .map(|(chunk, _range)| Arc::clone(chunk))
+                            .collect::<Vec<_>>();
+                        if chunks.is_empty() {
+                            return None;
+                        }
+
+                        let dedup = Arc::new(DeduplicateExec::new(
+                            chunks_to_physical_nodes(
+                                &schema,
+                                output_sort_key.as_ref(),
+                                chunks,
+                                config.execution.target_partitions,
+                            ),
+                            dedup_exec.sort_keys().to_vec(),
+                            dedup_exec.use_chunk_order_col(),
+                        ));
+                        Some((start, end, dedup))
+                    })
+                    .map(|(start, end, dedup)| {
+                        let predicate = time_predicate(&time_col, start, end)
+                            .expect("at least two buckets, so every bucket has at least one bound");
+                        Ok(Arc::new(FilterExec::try_new(predicate, dedup)?) as _)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                if inputs.len() < 2 {
+                    return Ok(Transformed::No(plan));
+                }
+
+                return Ok(Transformed::Yes(Arc::new(UnionExec::new(inputs))));
+            }
+
+            Ok(Transformed::No(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "time_bucket_split"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Split `[min, max]` into at most `n` buckets of equal width.
+///
+/// Every bucket is represented as `[start, end)`.
+fn bucket_bounds(min: i64, max: i64, n: usize) -> Vec<(i64, i64)> {
+    let span = (max as i128) - (min as i128) + 1;
+    let n = (n as i128).min(span).max(1);
+    let width = (span + n - 1) / n;
+
+    (0..n)
+        .map(|idx| {
+            let start = min as i128 + idx * width;
+            let end = (start + width).min(max as i128 + 1);
+            (start as i64, end.min(i64::MAX as i128) as i64)
+        })
+        .filter(|(start, end)| start < end)
+        .collect()
+}
+
+/// Create `start <= time AND time < end`.
+///
+/// Returns `None` if neither bound is set.
+fn time_predicate(
+    time_col: &Column,
+    start: Option<i64>,
+    end: Option<i64>,
+) -> Option<Arc<dyn PhysicalExpr>> {
+    let bound = |op: Operator, ts: i64| -> Arc<dyn PhysicalExpr> {
+        Arc::new(BinaryExpr::new(
+            Arc::new(time_col.clone()),
+            op,
+            Arc::new(Literal::new(ScalarValue::TimestampNanosecond(
+                Some(ts),
+                None,
+            ))),
+        ))
+    };
+
+    let start = start.map(|ts| bound(Operator::GtEq, ts));
+    let end = end.map(|ts| bound(Operator::Lt, ts));
+    match (start, end) {
+        (Some(start), Some(end)) => Some(Arc::new(BinaryExpr::new(start, Operator::And, end))),
+        (Some(expr), None) | (None, Some(expr)) => Some(expr),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        physical_optimizer::{
+            dedup::test_util::{chunk, dedup_plan},
+            test_util::OptimizationTest,
+        },
+        QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let chunk1 = chunk(1).with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2).with_timestamp_min_max(5, 40);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = TimeBucketSplit;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let chunk1 = chunk(1).with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2)
+            .with_dummy_parquet_file()
+            .with_timestamp_min_max(5, 30);
+        let chunk3 = chunk(3).with_timestamp_min_max(25, 59);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2, chunk3]);
+        let opt = TimeBucketSplit;
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            dedup_time_buckets: 3,
+            ..Default::default()
+        });
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new_with_config(plan, opt, &config),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+          - "     ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " UnionExec"
+            - "   FilterExec: time@3 < 20"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+            - "         ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+            - "   FilterExec: time@3 >= 20 AND time@3 < 40"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+            - "         ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+            - "   FilterExec: time@3 >= 40"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_unknown_time_range() {
+        let chunk1 = chunk(1).with_timestamp_min_max(0, 10);
+        let chunk2 = chunk(2);
+        let schema = chunk1.schema().clone();
+        let plan = dedup_plan(schema, vec![chunk1, chunk2]);
+        let opt = TimeBucketSplit;
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            dedup_time_buckets: 3,
+            ..Default::default()
+        });
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new_with_config(plan, opt, &config),
+            @r###"
+        ---
+        input:
+          - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "   UnionExec"
+          - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        output:
+          Ok:
+            - " DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "   UnionExec"
+            - "     RecordBatchesExec: batches_groups=2 batches=0 total_rows=0"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_bucket_bounds() {
+        assert_eq!(bucket_bounds(0, 59, 3), vec![(0, 20), (20, 40), (40, 60)]);
+        assert_eq!(
+            bucket_bounds(0, 9, 4),
+            vec![(0, 3), (3, 6), (6, 9), (9, 10)]
+        );
+        assert_eq!(bucket_bounds(5, 6, 10), vec![(5, 6), (6, 7)]);
+        assert_eq!(bucket_bounds(5, 5, 10), vec![(5, 6)]);
+        assert_eq!(
+            bucket_bounds(i64::MIN, i64::MAX, 2),
+            vec![(i64::MIN, 0), (0, i64::MAX)]
+        );
+    }
+}
@@ -3,22 +3,31 @@ use std::sync::Arc;
 use datafusion::{execution::context::SessionState, physical_optimizer::PhysicalOptimizerRule};
 
 use self::{
+    aggregate_pushdown::AggregatePushdown,
     combine_chunks::CombineChunks,
+    date_bin_aggregate::DateBinAggregate,
     dedup::{
-        dedup_null_columns::DedupNullColumns, dedup_sort_order::DedupSortOrder,
-        partition_split::PartitionSplit, remove_dedup::RemoveDedup, time_split::TimeSplit,
+        chunk_order_elimination::ChunkOrderElimination, dedup_null_columns::DedupNullColumns,
+        dedup_sort_order::DedupSortOrder, partition_split::PartitionSplit,
+        remove_dedup::RemoveDedup, remove_disjoint_dedup::RemoveDisjointDedup,
+        time_bucket_split::TimeBucketSplit, time_split::TimeSplit,
     },
     predicate_pushdown::PredicatePushdown,
     projection_pushdown::ProjectionPushdown,
-    sort::parquet_sortness::ParquetSortness,
+    sample::Sample,
+    sort::{merge_sorted_chunks::MergeSortedChunks, parquet_sortness::ParquetSortness},
     union::{nested_union::NestedUnion, one_union::OneUnion},
 };
 
+mod aggregate_pushdown;
 mod chunk_extraction;
 mod combine_chunks;
+pub mod cost;
+mod date_bin_aggregate;
 mod dedup;
 mod predicate_pushdown;
 mod projection_pushdown;
+mod sample;
 mod sort;
 mod union;
 
@@ -31,14 +40,21 @@ pub fn register_iox_physical_optimizers(state: SessionState) -> SessionState {
     // The optimizer rules have to be done in this order
     let mut optimizers: Vec<Arc<dyn PhysicalOptimizerRule + Sync + Send>> = vec![
         Arc::new(PartitionSplit),
+        Arc::new(RemoveDisjointDedup),
         Arc::new(TimeSplit),
+        Arc::new(TimeBucketSplit),
         Arc::new(RemoveDedup),
         Arc::new(CombineChunks),
         Arc::new(DedupNullColumns),
         Arc::new(DedupSortOrder),
         Arc::new(PredicatePushdown),
+        Arc::new(ChunkOrderElimination),
         Arc::new(ProjectionPushdown),
+        Arc::new(Sample),
+        Arc::new(AggregatePushdown),
+        Arc::new(MergeSortedChunks),
         Arc::new(ParquetSortness) as _,
+        Arc::new(DateBinAggregate),
         Arc::new(NestedUnion),
         Arc::new(OneUnion),
     ];
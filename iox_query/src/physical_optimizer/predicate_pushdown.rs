@@ -1,5 +1,6 @@
 use std::{collections::HashSet, sync::Arc};
 
+use arrow::datatypes::SchemaRef;
 use datafusion::{
     common::tree_node::{RewriteRecursion, Transformed, TreeNode, TreeNodeRewriter},
     config::ConfigOptions,
@@ -17,9 +18,14 @@ use datafusion::{
     },
 };
 
-use crate::provider::DeduplicateExec;
+use schema::{InfluxColumnType, Schema};
+
+use crate::{config::IoxConfigExt, provider::DeduplicateExec};
 
 /// Push down predicates.
+///
+/// For [`ParquetExec`] nodes this also orders the predicates so that the ones on tag and time columns are evaluated
+/// first, see [`parquet_late_materialization`](IoxConfigExt::parquet_late_materialization).
 #[derive(Debug, Default)]
 pub struct PredicatePushdown;
 
@@ -27,8 +33,15 @@ impl PhysicalOptimizerRule for PredicatePushdown {
     fn optimize(
         &self,
         plan: Arc<dyn ExecutionPlan>,
-        _config: &ConfigOptions,
+        config: &ConfigOptions,
     ) -> Result<Arc<dyn ExecutionPlan>> {
+        let late_materialization = config
+            .extensions
+            .get::<IoxConfigExt>()
+            .cloned()
+            .unwrap_or_default()
+            .parquet_late_materialization;
+
         plan.transform_down(&|plan| {
             let plan_any = plan.as_any();
 
@@ -61,20 +74,38 @@ impl PhysicalOptimizerRule for PredicatePushdown {
                         .predicate()
                         .map(split_conjunction)
                         .unwrap_or_default();
-                    let both = conjunction(
-                        existing
-                            .into_iter()
-                            .chain(split_conjunction(filter_exec.predicate()))
-                            .cloned(),
+                    let mut both = existing
+                        .into_iter()
+                        .chain(split_conjunction(filter_exec.predicate()))
+                        .cloned()
+                        .collect::<Vec<_>>();
+
+                    let mut row_filters = false;
+                    if late_materialization {
+                        if let Some(ordered) = late_materialization_order(
+                            &both,
+                            &child_parquet.base_config().file_schema,
+                        ) {
+                            both = ordered;
+                            row_filters = true;
+                        }
+                    }
+
+                    let mut new_parquet = ParquetExec::new(
+                        child_parquet.base_config().clone(),
+                        conjunction(both),
+                        None,
                     );
+                    if row_filters {
+                        // evaluate the predicates as row filters in the given order
+                        new_parquet = new_parquet
+                            .with_pushdown_filters(true)
+                            .with_reorder_filters(false);
+                    }
 
                     let new_node = Arc::new(FilterExec::try_new(
                         Arc::clone(filter_exec.predicate()),
-                        Arc::new(ParquetExec::new(
-                            child_parquet.base_config().clone(),
-                            both,
-                            None,
-                        )),
+                        Arc::new(new_parquet),
                     )?);
                     return Ok(Transformed::Yes(new_node));
                 } else if let Some(child_dedup) = child_any.downcast_ref::<DeduplicateExec>() {
@@ -152,6 +183,35 @@ impl TreeNodeRewriter for ColumnCollector {
     }
 }
 
+/// Order conjuncts so that predicates that only reference tag and time columns come first.
+///
+/// The parquet reader evaluates the row filters in order and only decodes the columns of later filters (and the
+/// final projection) for rows that passed the earlier ones. So even if all predicates only reference tag and time
+/// columns (e.g. below a [`DeduplicateExec`]), the field columns are only decoded for the rows that pass them.
+///
+/// Returns `None` if the column types are unknown or if there are no predicates on tag and time columns, i.e. if row
+/// filters would not help.
+fn late_materialization_order(
+    exprs: &[Arc<dyn PhysicalExpr>],
+    file_schema: &SchemaRef,
+) -> Option<Vec<Arc<dyn PhysicalExpr>>> {
+    let schema = Schema::try_from(Arc::clone(file_schema)).ok()?;
+
+    let (pk, other): (Vec<_>, Vec<_>) = exprs.iter().cloned().partition(|expr| {
+        collect_columns(expr).iter().all(|col| {
+            matches!(
+                schema.field_type_by_name(col.name()),
+                Some(InfluxColumnType::Tag | InfluxColumnType::Timestamp)
+            )
+        })
+    });
+    if pk.is_empty() {
+        return None;
+    }
+
+    Some(pk.into_iter().chain(other).collect())
+}
+
 fn conjunction(
     parts: impl IntoIterator<Item = Arc<dyn PhysicalExpr>>,
 ) -> Option<Arc<dyn PhysicalExpr>> {
@@ -162,19 +222,30 @@ fn conjunction(
 
 #[cfg(test)]
 mod tests {
-    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use arrow::{
+        array::{Int64Array, TimestampNanosecondArray},
+        datatypes::{DataType, Field, Schema, SchemaRef},
+        record_batch::RecordBatch,
+    };
+    use arrow_util::assert_batches_eq;
     use datafusion::{
+        datasource::listing::PartitionedFile,
         datasource::object_store::ObjectStoreUrl,
         datasource::physical_plan::FileScanConfig,
         logical_expr::Operator,
+        parquet::arrow::ArrowWriter,
         physical_expr::PhysicalSortExpr,
         physical_plan::{
+            collect,
             expressions::{BinaryExpr, Column, Literal},
             PhysicalExpr, Statistics,
         },
+        prelude::SessionContext,
         scalar::ScalarValue,
     };
-    use schema::sort::SortKeyBuilder;
+    use object_store::{memory::InMemory, path::Path, ObjectStore};
+    use schema::{builder::SchemaBuilder, sort::SortKeyBuilder, InfluxFieldType};
+    use test_helpers::assert_contains;
 
     use crate::{physical_optimizer::test_util::OptimizationTest, util::arrow_sort_key_exprs};
 
@@ -344,6 +415,95 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parquet_late_materialization() {
+        let schema = SchemaBuilder::new()
+            .tag("tag1")
+            .tag("tag2")
+            .influx_field("field", InfluxFieldType::String)
+            .timestamp()
+            .build()
+            .unwrap()
+            .as_arrow();
+        let base_config = FileScanConfig {
+            object_store_url: ObjectStoreUrl::parse("test://").unwrap(),
+            file_schema: Arc::clone(&schema),
+            file_groups: vec![],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![vec![]],
+            infinite_source: false,
+        };
+        let plan = Arc::new(
+            FilterExec::try_new(
+                predicate_tag(&schema),
+                Arc::new(ParquetExec::new(
+                    base_config,
+                    Some(predicate_field(&schema)),
+                    None,
+                )),
+            )
+            .unwrap(),
+        );
+        let opt = PredicatePushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " FilterExec: tag1@0 = foo"
+          - "   ParquetExec: file_groups={0 groups: []}, projection=[tag1, tag2, field, time], predicate=field@2 = val, pruning_predicate=field_min@0 <= val AND val <= field_max@1"
+        output:
+          Ok:
+            - " FilterExec: tag1@0 = foo"
+            - "   ParquetExec: file_groups={0 groups: []}, projection=[tag1, tag2, field, time], predicate=tag1@0 = foo AND field@2 = val, pruning_predicate=tag1_min@0 <= foo AND foo <= tag1_max@1 AND field_min@2 <= val AND val <= field_max@3"
+        "###
+        );
+
+        // can be disabled
+        let plan = Arc::new(
+            FilterExec::try_new(
+                predicate_tag(&schema),
+                Arc::new(ParquetExec::new(
+                    FileScanConfig {
+                        object_store_url: ObjectStoreUrl::parse("test://").unwrap(),
+                        file_schema: Arc::clone(&schema),
+                        file_groups: vec![],
+                        statistics: Statistics::default(),
+                        projection: None,
+                        limit: None,
+                        table_partition_cols: vec![],
+                        output_ordering: vec![vec![]],
+                        infinite_source: false,
+                    },
+                    Some(predicate_field(&schema)),
+                    None,
+                )),
+            )
+            .unwrap(),
+        );
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            parquet_late_materialization: false,
+            ..Default::default()
+        });
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new_with_config(plan, PredicatePushdown, &config),
+            @r###"
+        ---
+        input:
+          - " FilterExec: tag1@0 = foo"
+          - "   ParquetExec: file_groups={0 groups: []}, projection=[tag1, tag2, field, time], predicate=field@2 = val, pruning_predicate=field_min@0 <= val AND val <= field_max@1"
+        output:
+          Ok:
+            - " FilterExec: tag1@0 = foo"
+            - "   ParquetExec: file_groups={0 groups: []}, projection=[tag1, tag2, field, time], predicate=field@2 = val AND tag1@0 = foo, pruning_predicate=field_min@0 <= val AND val <= field_max@1 AND tag1_min@2 <= foo AND foo <= tag1_max@3"
+        "###
+        );
+    }
+
     #[test]
     fn test_dedup_no_pushdown() {
         let schema = schema();
@@ -448,6 +608,192 @@ mod tests {
         );
     }
 
+    /// The field predicate fails for rows that do not pass the time predicate. Late materialization must only
+    /// evaluate it (and decode the field column) for rows that pass the time predicate.
+    #[tokio::test]
+    async fn test_parquet_late_materialization_execution() {
+        let ctx = SessionContext::new();
+        let (schema, base_config) = parquet_file(&ctx).await;
+        let plan = Arc::new(
+            FilterExec::try_new(
+                conjunction([predicate_divide_by_field(&schema), predicate_time(&schema)])
+                    .expect("not empty"),
+                Arc::new(ParquetExec::new(base_config.clone(), None, None)),
+            )
+            .unwrap(),
+        );
+
+        let optimized = PredicatePushdown
+            .optimize(Arc::clone(&plan) as _, &ConfigOptions::default())
+            .unwrap();
+        let batches = collect(Arc::clone(&optimized), ctx.task_ctx())
+            .await
+            .unwrap();
+        assert_batches_eq!(
+            [
+                "+-------+--------------------------------+",
+                "| field | time                           |",
+                "+-------+--------------------------------+",
+                "| 1     | 1970-01-01T00:00:00.000000006Z |",
+                "| 1     | 1970-01-01T00:00:00.000000007Z |",
+                "| 1     | 1970-01-01T00:00:00.000000008Z |",
+                "+-------+--------------------------------+",
+            ],
+            &batches
+        );
+        assert_eq!(pushdown_rows_filtered(&optimized), 5);
+
+        // without late materialization, the field predicate is evaluated for all rows
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            parquet_late_materialization: false,
+            ..Default::default()
+        });
+        let optimized = PredicatePushdown.optimize(plan, &config).unwrap();
+        let err = collect(optimized, ctx.task_ctx()).await.unwrap_err();
+        assert_contains!(err.to_string(), "Divide by zero");
+    }
+
+    /// Predicates that are pushed below a [`DeduplicateExec`] only reference primary key columns, but they are still
+    /// evaluated while decoding the parquet file.
+    #[tokio::test]
+    async fn test_parquet_late_materialization_execution_dedup() {
+        let ctx = SessionContext::new();
+        let (schema, base_config) = parquet_file(&ctx).await;
+        let sort_key = SortKeyBuilder::new().with_col("time").build();
+        let plan = Arc::new(
+            FilterExec::try_new(
+                conjunction([predicate_divide_by_field(&schema), predicate_time(&schema)])
+                    .expect("not empty"),
+                Arc::new(DeduplicateExec::new(
+                    Arc::new(ParquetExec::new(base_config, None, None)),
+                    arrow_sort_key_exprs(&sort_key, &schema),
+                    false,
+                )),
+            )
+            .unwrap(),
+        );
+
+        let optimized = PredicatePushdown
+            .optimize(Arc::clone(&plan) as _, &ConfigOptions::default())
+            .unwrap();
+        let batches = collect(Arc::clone(&optimized), ctx.task_ctx())
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+        let parquet_exec = find_parquet_exec(&optimized);
+        assert_eq!(pushdown_rows_filtered(&parquet_exec), 5);
+
+        // without late materialization, the filter below the deduplication does all the work
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            parquet_late_materialization: false,
+            ..Default::default()
+        });
+        let optimized = PredicatePushdown.optimize(plan, &config).unwrap();
+        let batches = collect(Arc::clone(&optimized), ctx.task_ctx())
+            .await
+            .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+        let parquet_exec = find_parquet_exec(&optimized);
+        assert_eq!(pushdown_rows_filtered(&parquet_exec), 0);
+    }
+
+    /// Write a parquet file with the times 1..=8 and a field that is zero for the times 1..=5 and register it with the
+    /// session.
+    async fn parquet_file(ctx: &SessionContext) -> (SchemaRef, FileScanConfig) {
+        let schema = SchemaBuilder::new()
+            .influx_field("field", InfluxFieldType::Integer)
+            .timestamp()
+            .build()
+            .unwrap()
+            .as_arrow();
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![0, 0, 0, 0, 0, 1, 1, 1])),
+                Arc::new(TimestampNanosecondArray::from(vec![1, 2, 3, 4, 5, 6, 7, 8])),
+            ],
+        )
+        .unwrap();
+
+        let mut buf = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buf, Arc::clone(&schema), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let store = Arc::new(InMemory::new());
+        let location = Path::from("file.parquet");
+        store.put(&location, buf.into()).await.unwrap();
+        let object_meta = store.head(&location).await.unwrap();
+
+        let object_store_url = ObjectStoreUrl::parse("memory://").unwrap();
+        ctx.runtime_env()
+            .register_object_store(object_store_url.as_ref(), store);
+
+        let base_config = FileScanConfig {
+            object_store_url,
+            file_schema: Arc::clone(&schema),
+            file_groups: vec![vec![PartitionedFile {
+                object_meta,
+                partition_values: vec![],
+                range: None,
+                extensions: None,
+            }]],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![vec![]],
+            infinite_source: false,
+        };
+        (schema, base_config)
+    }
+
+    fn find_parquet_exec(plan: &Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        if plan.as_any().downcast_ref::<ParquetExec>().is_some() {
+            return Arc::clone(plan);
+        }
+        let children = plan.children();
+        assert_eq!(children.len(), 1);
+        find_parquet_exec(&children[0])
+    }
+
+    /// Number of rows that the parquet row filters removed.
+    fn pushdown_rows_filtered(plan: &Arc<dyn ExecutionPlan>) -> usize {
+        let parquet_exec = find_parquet_exec(plan);
+        parquet_exec
+            .metrics()
+            .expect("metrics")
+            .sum_by_name("pushdown_rows_filtered")
+            .map(|v| v.as_usize())
+            .unwrap_or_default()
+    }
+
+    fn predicate_time(schema: &SchemaRef) -> Arc<dyn PhysicalExpr> {
+        Arc::new(BinaryExpr::new(
+            Arc::new(Column::new_with_schema("time", schema).unwrap()),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::TimestampNanosecond(
+                Some(5),
+                None,
+            ))),
+        ))
+    }
+
+    /// `10 / field > 1`, fails for rows where the field is zero.
+    fn predicate_divide_by_field(schema: &SchemaRef) -> Arc<dyn PhysicalExpr> {
+        Arc::new(BinaryExpr::new(
+            Arc::new(BinaryExpr::new(
+                Arc::new(Literal::new(ScalarValue::Int64(Some(10)))),
+                Operator::Divide,
+                Arc::new(Column::new_with_schema("field", schema).unwrap()),
+            )),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::Int64(Some(1)))),
+        ))
+    }
+
     fn schema() -> SchemaRef {
         Arc::new(Schema::new(vec![
             Field::new("tag1", DataType::Utf8, true),
@@ -24,8 +24,9 @@ use datafusion::{
         ExecutionPlan, PhysicalExpr,
     },
 };
+use schema::TIME_COLUMN_NAME;
 
-use crate::provider::{DeduplicateExec, RecordBatchesExec};
+use crate::provider::{DeduplicateExec, RecordBatchesExec, RetentionFilterExec};
 
 /// Push down projections.
 #[derive(Debug, Default)]
@@ -228,6 +229,24 @@ impl PhysicalOptimizerRule for ProjectionPushdown {
                         },
                     )?;
 
+                    return Ok(Transformed::Yes(plan));
+                } else if let Some(child_retention) =
+                    child_any.downcast_ref::<RetentionFilterExec>()
+                {
+                    let retention_required_cols = HashSet::from([TIME_COLUMN_NAME]);
+
+                    let plan = wrap_user_into_projections(
+                        &retention_required_cols,
+                        &column_names,
+                        Arc::clone(child_retention.input()),
+                        |plan| {
+                            Ok(Arc::new(RetentionFilterExec::try_new(
+                                plan,
+                                child_retention.cutoff(),
+                            )?))
+                        },
+                    )?;
+
                     return Ok(Transformed::Yes(plan));
                 } else if let Some(child_recordbatches) =
                     child_any.downcast_ref::<RecordBatchesExec>()
@@ -427,7 +446,7 @@ fn reassign_sort_exprs_columns(
 mod tests {
     use arrow::{
         compute::SortOptions,
-        datatypes::{DataType, Field, Fields, Schema, SchemaRef},
+        datatypes::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit},
     };
     use datafusion::{
         datasource::object_store::ObjectStoreUrl,
@@ -1272,6 +1291,46 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_retention_projection_split() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("tag1", DataType::Utf8, true),
+            Field::new("field", DataType::UInt64, true),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+        ]));
+        let plan = Arc::new(
+            ProjectionExec::try_new(
+                vec![(expr_col("tag1", &schema), String::from("tag1"))],
+                Arc::new(
+                    RetentionFilterExec::try_new(Arc::new(TestExec::new(Arc::clone(&schema))), 100)
+                        .unwrap(),
+                ),
+            )
+            .unwrap(),
+        );
+        let opt = ProjectionPushdown;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " ProjectionExec: expr=[tag1@0 as tag1]"
+          - "   RetentionFilterExec: time@2 > 100"
+          - "     Test"
+        output:
+          Ok:
+            - " ProjectionExec: expr=[tag1@0 as tag1]"
+            - "   RetentionFilterExec: time@1 > 100"
+            - "     ProjectionExec: expr=[tag1@0 as tag1, time@2 as time]"
+            - "       Test"
+        "###
+        );
+    }
+
     #[test]
     fn test_recordbatches() {
         let schema = schema();
@@ -0,0 +1,201 @@
+use std::sync::Arc;
+
+use datafusion::{
+    config::ConfigOptions, datasource::physical_plan::ParquetExec, error::Result,
+    physical_optimizer::PhysicalOptimizerRule, physical_plan::ExecutionPlan,
+};
+
+use crate::{
+    config::{IoxConfigExt, SampleFraction},
+    provider::{DeduplicateExec, RecordBatchesExec, SampleExec},
+};
+
+/// Sample chunk scans if [`sample_fraction`] is below `1`.
+///
+/// A [`SampleExec`] is placed on top of every chunk scan. Scans that are de-duplicated are sampled after the
+/// [`DeduplicateExec`], because skipping a batch below the de-duplication could resurrect rows that were overwritten
+/// by the skipped batch:
+///
+/// ```text
+/// UnionExec                                   UnionExec
+///   DeduplicateExec                             SampleExec
+///     UnionExec                                   DeduplicateExec
+///       RecordBatchesExec              --->         UnionExec
+///       ParquetExec                                   RecordBatchesExec
+///   UnionExec                                         ParquetExec
+///     ParquetExec                               UnionExec
+///                                                 SampleExec
+///                                                   ParquetExec
+/// ```
+///
+/// [`sample_fraction`]: crate::config::IoxConfigExt::sample_fraction
+#[derive(Debug, Default)]
+pub struct Sample;
+
+impl PhysicalOptimizerRule for Sample {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let fraction = config
+            .extensions
+            .get::<IoxConfigExt>()
+            .cloned()
+            .unwrap_or_default()
+            .sample_fraction;
+        if fraction.is_all() {
+            return Ok(plan);
+        }
+
+        sample(plan, fraction)
+    }
+
+    fn name(&self) -> &str {
+        "sample"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn sample(
+    plan: Arc<dyn ExecutionPlan>,
+    fraction: SampleFraction,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let plan_any = plan.as_any();
+
+    if plan_any.is::<SampleExec>() {
+        return Ok(plan);
+    }
+
+    // do NOT descend into de-duplication, see struct docs
+    if plan_any.is::<DeduplicateExec>()
+        || plan_any.is::<ParquetExec>()
+        || plan_any.is::<RecordBatchesExec>()
+    {
+        return Ok(Arc::new(SampleExec::new(plan, fraction)));
+    }
+
+    let children = plan.children();
+    if children.is_empty() {
+        return Ok(plan);
+    }
+    let children = children
+        .into_iter()
+        .map(|child| sample(child, fraction))
+        .collect::<Result<Vec<_>>>()?;
+    plan.with_new_children(children)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::physical_plan::union::UnionExec;
+    use schema::sort::SortKey;
+
+    use crate::{
+        physical_optimizer::test_util::OptimizationTest, provider::chunks_to_physical_nodes,
+        test::TestChunk, util::arrow_sort_key_exprs, QueryChunk,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_disabled_by_default() {
+        let plan = plan();
+        let opt = Sample;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " UnionExec"
+          - "   DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "     UnionExec"
+          - "       RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+          - "       ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+          - "   UnionExec"
+          - "     ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " UnionExec"
+            - "   DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "     UnionExec"
+            - "       RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+            - "       ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+            - "   UnionExec"
+            - "     ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_sample() {
+        let plan = plan();
+        let opt = Sample;
+        let mut config = ConfigOptions::default();
+        config.extensions.insert(IoxConfigExt {
+            sample_fraction: SampleFraction::new(0.1).unwrap(),
+            ..Default::default()
+        });
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new_with_config(plan, opt, &config),
+            @r###"
+        ---
+        input:
+          - " UnionExec"
+          - "   DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+          - "     UnionExec"
+          - "       RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+          - "       ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+          - "   UnionExec"
+          - "     ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        output:
+          Ok:
+            - " UnionExec"
+            - "   SampleExec: fraction=0.1"
+            - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+            - "       UnionExec"
+            - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+            - "         ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time]"
+            - "   UnionExec"
+            - "     SampleExec: fraction=0.1"
+            - "       ParquetExec: file_groups={1 group: [[3.parquet]]}, projection=[field, tag1, tag2, time]"
+        "###
+        );
+    }
+
+    /// Union of a de-duplicated and a plain chunk scan.
+    fn plan() -> Arc<dyn ExecutionPlan> {
+        let chunk1 = chunk(1);
+        let chunk2 = chunk(2).with_dummy_parquet_file();
+        let chunk3 = chunk(3).with_dummy_parquet_file();
+        let schema = chunk1.schema().clone();
+        let arrow_schema = schema.as_arrow();
+
+        let dedup_input = chunks_to_physical_nodes(
+            &arrow_schema,
+            None,
+            vec![Arc::new(chunk1) as _, Arc::new(chunk2) as _],
+            2,
+        );
+        let sort_key = SortKey::from_columns(schema.primary_key());
+        let sort_exprs = arrow_sort_key_exprs(&sort_key, &dedup_input.schema());
+        let dedup: Arc<dyn ExecutionPlan> =
+            Arc::new(DeduplicateExec::new(dedup_input, sort_exprs, false));
+
+        let scan = chunks_to_physical_nodes(&arrow_schema, None, vec![Arc::new(chunk3) as _], 2);
+
+        Arc::new(UnionExec::new(vec![dedup, scan]))
+    }
+
+    fn chunk(id: u128) -> TestChunk {
+        TestChunk::new("table")
+            .with_id(id)
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field")
+            .with_time_column()
+    }
+}
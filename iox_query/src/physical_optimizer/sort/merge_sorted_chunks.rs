@@ -0,0 +1,383 @@
+use std::sync::Arc;
+
+use datafusion::{
+    common::tree_node::{Transformed, TreeNode},
+    config::ConfigOptions,
+    datasource::physical_plan::ParquetExec,
+    error::Result,
+    physical_expr::PhysicalSortExpr,
+    physical_optimizer::PhysicalOptimizerRule,
+    physical_plan::{
+        coalesce_batches::CoalesceBatchesExec,
+        filter::FilterExec,
+        limit::GlobalLimitExec,
+        sorts::{sort::SortExec, sort_preserving_merge::SortPreservingMergeExec},
+        union::UnionExec,
+        ExecutionPlan,
+    },
+};
+
+use crate::provider::RetentionFilterExec;
+
+use super::parquet_sortness::split_parquet_files;
+
+/// Replace a full [`SortExec`] over a union of chunks by a k-way [`SortPreservingMergeExec`] if the chunks are already
+/// sorted.
+///
+/// This is the case for queries that only project and filter data and order by a prefix of the chunk sort key, e.g.
+/// `SELECT tag, time FROM t ORDER BY tag, time`:
+///
+/// ```text
+/// SortExec                            SortPreservingMergeExec
+///   UnionExec                           UnionExec
+///     ParquetExec            --->         ParquetExec (one file per partition)
+///     RecordBatchesExec                   SortExec (per partition)
+///                                           RecordBatchesExec
+/// ```
+///
+/// Inputs that are not sorted yet (e.g. ingester data) are sorted per partition, which is still cheaper than a full
+/// re-sort. The rewrite only happens if at least one union input is already sorted.
+#[derive(Debug, Default)]
+pub struct MergeSortedChunks;
+
+impl PhysicalOptimizerRule for MergeSortedChunks {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        plan.transform_down(&|plan| {
+            let Some(sort_exec) = plan.as_any().downcast_ref::<SortExec>() else {
+                return Ok(Transformed::No(plan));
+            };
+            if sort_exec.preserve_partitioning() {
+                // output must stay partitioned
+                return Ok(Transformed::No(plan));
+            }
+
+            let sort_expr = sort_exec.expr();
+            let Some(input) = sort_partitions(sort_exec.input(), sort_expr, config)? else {
+                return Ok(Transformed::No(plan));
+            };
+
+            let mut plan: Arc<dyn ExecutionPlan> =
+                Arc::new(SortPreservingMergeExec::new(sort_expr.to_vec(), input));
+            if let Some(fetch) = sort_exec.fetch() {
+                plan = Arc::new(GlobalLimitExec::new(plan, 0, Some(fetch)));
+            }
+            Ok(Transformed::Yes(plan))
+        })
+    }
+
+    fn name(&self) -> &str {
+        "merge_sorted_chunks"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Rewrite `plan` so that every output partition is sorted by `sort_expr`.
+///
+/// Returns `None` if the plan is not eligible or if none of the union inputs is pre-sorted.
+fn sort_partitions(
+    plan: &Arc<dyn ExecutionPlan>,
+    sort_expr: &[PhysicalSortExpr],
+    config: &ConfigOptions,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let plan_any = plan.as_any();
+
+    if plan_any.is::<FilterExec>()
+        || plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<RetentionFilterExec>()
+    {
+        // nodes that preserve both schema and order
+        let mut children = plan.children();
+        assert_eq!(children.len(), 1);
+        let child = children.remove(0);
+        let Some(child) = sort_partitions(&child, sort_expr, config)? else {
+            return Ok(None);
+        };
+        return Ok(Some(Arc::clone(plan).with_new_children(vec![child])?));
+    }
+
+    let Some(union_exec) = plan_any.downcast_ref::<UnionExec>() else {
+        return Ok(None);
+    };
+
+    let mut any_sorted = false;
+    let children = union_exec
+        .inputs()
+        .iter()
+        .map(|child| {
+            let child = child
+                .as_any()
+                .downcast_ref::<ParquetExec>()
+                .and_then(|parquet_exec| split_parquet_files(parquet_exec, config))
+                .map(|parquet_exec| Arc::new(parquet_exec) as _)
+                .unwrap_or_else(|| Arc::clone(child));
+
+            if is_sorted(child.as_ref(), sort_expr) {
+                any_sorted = true;
+                child
+            } else {
+                Arc::new(SortExec::new(sort_expr.to_vec(), child).with_preserve_partitioning(true))
+                    as _
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !any_sorted {
+        return Ok(None);
+    }
+
+    Ok(Some(Arc::new(UnionExec::new(children))))
+}
+
+/// Checks if the output of `plan` is sorted by `sort_expr`.
+fn is_sorted(plan: &dyn ExecutionPlan, sort_expr: &[PhysicalSortExpr]) -> bool {
+    let Some(ordering) = plan.output_ordering() else {
+        return false;
+    };
+    if ordering.len() < sort_expr.len() {
+        return false;
+    }
+
+    let schema = plan.schema();
+    ordering.iter().zip(sort_expr).all(|(actual, desired)| {
+        actual.expr.eq(&desired.expr)
+            && (actual.options.descending == desired.options.descending)
+            && ((actual.options.nulls_first == desired.options.nulls_first)
+                // NULL ordering is irrelevant if there are no NULLs, e.g. for the time column
+                || matches!(desired.expr.nullable(&schema), Ok(false)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+    use datafusion::{
+        datasource::{
+            listing::PartitionedFile, object_store::ObjectStoreUrl, physical_plan::FileScanConfig,
+        },
+        physical_plan::{empty::EmptyExec, expressions::Column, Statistics},
+    };
+    use object_store::{path::Path, ObjectMeta};
+
+    use crate::physical_optimizer::test_util::OptimizationTest;
+
+    use super::*;
+
+    #[test]
+    fn test_happy_path() {
+        let schema = schema();
+        let plan = Arc::new(
+            SortExec::new(
+                ordering(["col2", "col1"], &schema),
+                Arc::new(UnionExec::new(vec![
+                    parquet_exec(ordering(["col2", "col1", "col3"], &schema)),
+                    Arc::new(EmptyExec::new(false, Arc::clone(&schema))),
+                ])),
+            )
+            .with_fetch(Some(42)),
+        );
+        let opt = MergeSortedChunks;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " SortExec: fetch=42, expr=[col2@1 ASC,col1@0 ASC]"
+          - "   UnionExec"
+          - "     ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC, col3@2 ASC]"
+          - "     EmptyExec: produce_one_row=false"
+        output:
+          Ok:
+            - " GlobalLimitExec: skip=0, fetch=42"
+            - "   SortPreservingMergeExec: [col2@1 ASC,col1@0 ASC]"
+            - "     UnionExec"
+            - "       ParquetExec: file_groups={2 groups: [[1.parquet], [2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC, col3@2 ASC]"
+            - "       SortExec: expr=[col2@1 ASC,col1@0 ASC]"
+            - "         EmptyExec: produce_one_row=false"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_coalesce_batches() {
+        let schema = schema();
+        let plan = Arc::new(SortExec::new(
+            ordering(["col2"], &schema),
+            Arc::new(CoalesceBatchesExec::new(
+                Arc::new(UnionExec::new(vec![parquet_exec(ordering(
+                    ["col2", "col1"],
+                    &schema,
+                ))])),
+                8192,
+            )),
+        ));
+        let opt = MergeSortedChunks;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " SortExec: expr=[col2@1 ASC]"
+          - "   CoalesceBatchesExec: target_batch_size=8192"
+          - "     UnionExec"
+          - "       ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+        output:
+          Ok:
+            - " SortPreservingMergeExec: [col2@1 ASC]"
+            - "   CoalesceBatchesExec: target_batch_size=8192"
+            - "     UnionExec"
+            - "       ParquetExec: file_groups={2 groups: [[1.parquet], [2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_retention_filter() {
+        let schema = schema();
+        let plan = Arc::new(SortExec::new(
+            ordering(["col2", "time"], &schema),
+            Arc::new(
+                RetentionFilterExec::try_new(
+                    Arc::new(UnionExec::new(vec![
+                        parquet_exec(ordering(["col2", "time"], &schema)),
+                        Arc::new(EmptyExec::new(false, Arc::clone(&schema))),
+                    ])),
+                    100,
+                )
+                .unwrap(),
+            ),
+        ));
+        let opt = MergeSortedChunks;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " SortExec: expr=[col2@1 ASC,time@3 ASC]"
+          - "   RetentionFilterExec: time@3 > 100"
+          - "     UnionExec"
+          - "       ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, time@3 ASC]"
+          - "       EmptyExec: produce_one_row=false"
+        output:
+          Ok:
+            - " SortPreservingMergeExec: [col2@1 ASC,time@3 ASC]"
+            - "   RetentionFilterExec: time@3 > 100"
+            - "     UnionExec"
+            - "       ParquetExec: file_groups={2 groups: [[1.parquet], [2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, time@3 ASC]"
+            - "       SortExec: expr=[col2@1 ASC,time@3 ASC]"
+            - "         EmptyExec: produce_one_row=false"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_different_ordering() {
+        let schema = schema();
+        let plan = Arc::new(SortExec::new(
+            ordering(["col1", "col2"], &schema),
+            Arc::new(UnionExec::new(vec![
+                parquet_exec(ordering(["col2", "col1"], &schema)),
+                Arc::new(EmptyExec::new(false, Arc::clone(&schema))),
+            ])),
+        ));
+        let opt = MergeSortedChunks;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " SortExec: expr=[col1@0 ASC,col2@1 ASC]"
+          - "   UnionExec"
+          - "     ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+          - "     EmptyExec: produce_one_row=false"
+        output:
+          Ok:
+            - " SortExec: expr=[col1@0 ASC,col2@1 ASC]"
+            - "   UnionExec"
+            - "     ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+            - "     EmptyExec: produce_one_row=false"
+        "###
+        );
+    }
+
+    #[test]
+    fn test_no_union() {
+        let schema = schema();
+        let plan = Arc::new(SortExec::new(
+            ordering(["col2", "col1"], &schema),
+            parquet_exec(ordering(["col2", "col1"], &schema)),
+        ));
+        let opt = MergeSortedChunks;
+        insta::assert_yaml_snapshot!(
+            OptimizationTest::new(plan, opt),
+            @r###"
+        ---
+        input:
+          - " SortExec: expr=[col2@1 ASC,col1@0 ASC]"
+          - "   ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+        output:
+          Ok:
+            - " SortExec: expr=[col2@1 ASC,col1@0 ASC]"
+            - "   ParquetExec: file_groups={1 group: [[1.parquet, 2.parquet]]}, projection=[col1, col2, col3, time], output_ordering=[col2@1 ASC, col1@0 ASC]"
+        "###
+        );
+    }
+
+    fn parquet_exec(output_ordering: Vec<PhysicalSortExpr>) -> Arc<dyn ExecutionPlan> {
+        let schema = schema();
+        let base_config = FileScanConfig {
+            object_store_url: ObjectStoreUrl::parse("test://").unwrap(),
+            file_schema: Arc::clone(&schema),
+            file_groups: vec![vec![file(1), file(2)]],
+            statistics: Statistics::default(),
+            projection: None,
+            limit: None,
+            table_partition_cols: vec![],
+            output_ordering: vec![output_ordering],
+            infinite_source: false,
+        };
+        Arc::new(ParquetExec::new(base_config, None, None))
+    }
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("col1", DataType::Utf8, true),
+            Field::new("col2", DataType::Utf8, true),
+            Field::new("col3", DataType::Utf8, true),
+            Field::new(
+                "time",
+                DataType::Timestamp(TimeUnit::Nanosecond, None),
+                false,
+            ),
+        ]))
+    }
+
+    fn file(n: u128) -> PartitionedFile {
+        PartitionedFile {
+            object_meta: ObjectMeta {
+                location: Path::parse(format!("{n}.parquet")).unwrap(),
+                last_modified: Default::default(),
+                size: 0,
+                e_tag: None,
+            },
+            partition_values: vec![],
+            range: None,
+            extensions: None,
+        }
+    }
+
+    fn ordering<const N: usize>(cols: [&str; N], schema: &SchemaRef) -> Vec<PhysicalSortExpr> {
+        cols.into_iter()
+            .map(|col| PhysicalSortExpr {
+                expr: Arc::new(Column::new_with_schema(col, schema.as_ref()).unwrap()),
+                options: Default::default(),
+            })
+            .collect()
+    }
+}
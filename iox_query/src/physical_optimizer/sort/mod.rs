@@ -2,4 +2,5 @@
 //!
 //! [`SortExec`]: datafusion::physical_plan::sorts::sort::SortExec
 
+pub mod merge_sorted_chunks;
 pub mod parquet_sortness;
@@ -122,45 +122,9 @@ impl<'a> TreeNodeRewriter for ParquetSortnessRewriter<'a> {
             return Ok(node);
         };
 
-        let base_config = parquet_exec.base_config();
-        if base_config.output_ordering.is_empty() {
-            // no output ordering requested
+        let Some(new_parquet_exec) = split_parquet_files(parquet_exec, self.config) else {
             return Ok(node);
-        }
-
-        if base_config.file_groups.iter().all(|g| g.len() < 2) {
-            // already flat
-            return Ok(node);
-        }
-
-        // Protect against degenerative plans
-        let n_files = base_config.file_groups.iter().map(Vec::len).sum::<usize>();
-        let max_parquet_fanout = self
-            .config
-            .extensions
-            .get::<IoxConfigExt>()
-            .cloned()
-            .unwrap_or_default()
-            .max_parquet_fanout;
-        if n_files > max_parquet_fanout {
-            warn!(
-                n_files,
-                max_parquet_fanout, "cannot use pre-sorted parquet files, fan-out too wide"
-            );
-            return Ok(node);
-        }
-
-        let base_config = FileScanConfig {
-            file_groups: base_config
-                .file_groups
-                .iter()
-                .flat_map(|g| g.iter())
-                .map(|f| vec![f.clone()])
-                .collect(),
-            ..base_config.clone()
         };
-        let new_parquet_exec =
-            ParquetExec::new(base_config, parquet_exec.predicate().cloned(), None);
 
         // did this help?
         if new_parquet_exec.output_ordering() == Some(self.desired_ordering) {
@@ -171,6 +135,61 @@ impl<'a> TreeNodeRewriter for ParquetSortnessRewriter<'a> {
     }
 }
 
+/// Place every file of the given [`ParquetExec`] into its own file group so that the sort order of the individual
+/// files is preserved.
+///
+/// Returns `None` if the files are not sorted, if every group already contains at most one file, or if the fan-out
+/// would exceed [`max_parquet_fanout`].
+///
+///
+/// [`max_parquet_fanout`]: crate::config::IoxConfigExt::max_parquet_fanout
+pub(super) fn split_parquet_files(
+    parquet_exec: &ParquetExec,
+    config: &ConfigOptions,
+) -> Option<ParquetExec> {
+    let base_config = parquet_exec.base_config();
+    if base_config.output_ordering.is_empty() {
+        // no output ordering requested
+        return None;
+    }
+
+    if base_config.file_groups.iter().all(|g| g.len() < 2) {
+        // already flat
+        return None;
+    }
+
+    // Protect against degenerative plans
+    let n_files = base_config.file_groups.iter().map(Vec::len).sum::<usize>();
+    let max_parquet_fanout = config
+        .extensions
+        .get::<IoxConfigExt>()
+        .cloned()
+        .unwrap_or_default()
+        .max_parquet_fanout;
+    if n_files > max_parquet_fanout {
+        warn!(
+            n_files,
+            max_parquet_fanout, "cannot use pre-sorted parquet files, fan-out too wide"
+        );
+        return None;
+    }
+
+    let base_config = FileScanConfig {
+        file_groups: base_config
+            .file_groups
+            .iter()
+            .flat_map(|g| g.iter())
+            .map(|f| vec![f.clone()])
+            .collect(),
+        ..base_config.clone()
+    };
+    Some(ParquetExec::new(
+        base_config,
+        parquet_exec.predicate().cloned(),
+        None,
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use arrow::datatypes::{DataType, Field, Fields, Schema, SchemaRef};
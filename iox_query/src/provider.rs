@@ -32,13 +32,19 @@ use crate::{
 use snafu::{ResultExt, Snafu};
 
 mod adapter;
+mod date_bin_order;
 mod deduplicate;
 pub mod overlap;
 mod physical;
 mod record_batch_exec;
+mod retention;
+mod sample;
 pub use self::overlap::group_potential_duplicates;
+pub use date_bin_order::DateBinOrderExec;
 pub use deduplicate::{DeduplicateExec, RecordBatchDeduplicator};
 pub(crate) use physical::{chunks_to_physical_nodes, PartitionedFileExt};
+pub use retention::RetentionFilterExec;
+pub use sample::SampleExec;
 
 pub(crate) use record_batch_exec::RecordBatchesExec;
 
@@ -107,6 +113,7 @@ pub struct ProviderBuilder {
     schema: Schema,
     chunks: Vec<Arc<dyn QueryChunk>>,
     deduplication: bool,
+    retention_time: Option<i64>,
 }
 
 impl ProviderBuilder {
@@ -118,6 +125,7 @@ impl ProviderBuilder {
             schema,
             chunks: Vec::new(),
             deduplication: true,
+            retention_time: None,
         }
     }
 
@@ -126,6 +134,13 @@ impl ProviderBuilder {
         self
     }
 
+    /// Enforce retention: rows with `time <= retention_time` (in nanoseconds) are removed by a
+    /// [`RetentionFilterExec`] on top of every scan.
+    pub fn with_retention_time(mut self, retention_time: Option<i64>) -> Self {
+        self.retention_time = retention_time;
+        self
+    }
+
     /// Add a new chunk to this provider
     pub fn add_chunk(mut self, chunk: Arc<dyn QueryChunk>) -> Self {
         self.chunks.push(chunk);
@@ -139,6 +154,7 @@ impl ProviderBuilder {
             table_name: self.table_name,
             chunks: self.chunks,
             deduplication: self.deduplication,
+            retention_time: self.retention_time,
         })
     }
 }
@@ -156,6 +172,8 @@ pub struct ChunkTableProvider {
     chunks: Vec<Arc<dyn QueryChunk>>,
     /// do deduplication
     deduplication: bool,
+    /// retention cutoff in nanoseconds, if any
+    retention_time: Option<i64>,
 }
 
 impl ChunkTableProvider {
@@ -178,6 +196,11 @@ impl ChunkTableProvider {
     pub fn deduplication(&self) -> bool {
         self.deduplication
     }
+
+    /// Retention cutoff (in nanoseconds) that is enforced by this provider, if any
+    pub fn retention_time(&self) -> Option<i64> {
+        self.retention_time
+    }
 }
 
 #[async_trait]
@@ -260,6 +283,13 @@ impl TableProvider for ChunkTableProvider {
             plan
         };
 
+        // Enforce retention regardless of the filters that the caller passed in, so that ad-hoc plans that did not
+        // add a retention predicate never see expired rows.
+        let plan = match self.retention_time {
+            Some(cutoff) => Arc::new(RetentionFilterExec::try_new(plan, cutoff)?),
+            None => plan,
+        };
+
         // Project at last because it removes columns and hence other operations may fail. Projection pushdown will
         // optimize that later.
         // Always project because we MUST make sure that chunk order col doesn't leak to the user or to our parquet
@@ -309,6 +339,7 @@ mod test {
         exec::IOxSessionContext,
         test::{format_execution_plan, TestChunk},
     };
+    use arrow_util::assert_batches_sorted_eq;
     use datafusion::prelude::{col, lit};
 
     #[tokio::test]
@@ -623,4 +654,108 @@ mod test {
         "###
         );
     }
+
+    #[tokio::test]
+    async fn provider_scan_retention_time() {
+        let table_name = "t";
+        let chunk1 = Arc::new(
+            TestChunk::new(table_name)
+                .with_id(1)
+                .with_tag_column("tag1")
+                .with_tag_column("tag2")
+                .with_f64_field_column("field")
+                .with_time_column(),
+        ) as Arc<dyn QueryChunk>;
+        let chunk2 = Arc::new(
+            TestChunk::new(table_name)
+                .with_id(2)
+                .with_dummy_parquet_file()
+                .with_tag_column("tag1")
+                .with_tag_column("tag2")
+                .with_f64_field_column("field")
+                .with_time_column(),
+        ) as Arc<dyn QueryChunk>;
+        let schema = chunk1.schema().clone();
+
+        let ctx = IOxSessionContext::with_testing();
+        let state = ctx.inner().state();
+
+        let provider = ProviderBuilder::new(Arc::from(table_name), schema)
+            .add_chunk(Arc::clone(&chunk1))
+            .add_chunk(Arc::clone(&chunk2))
+            .with_retention_time(Some(100))
+            .build()
+            .unwrap();
+        assert_eq!(provider.retention_time(), Some(100));
+
+        // retention is enforced even if the caller did not pass a retention filter
+        let plan = provider
+            .scan(&state, Some(&vec![1, 3]), &[], None)
+            .await
+            .unwrap();
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " ProjectionExec: expr=[tag1@1 as tag1, time@3 as time]"
+        - "   RetentionFilterExec: time@3 > 100"
+        - "     DeduplicateExec: [tag1@1 ASC,tag2@2 ASC,time@3 ASC]"
+        - "       UnionExec"
+        - "         RecordBatchesExec: batches_groups=1 batches=0 total_rows=0"
+        - "         ParquetExec: file_groups={1 group: [[2.parquet]]}, projection=[field, tag1, tag2, time, __chunk_order], output_ordering=[__chunk_order@4 ASC]"
+        "###
+        );
+    }
+
+    #[tokio::test]
+    async fn provider_scan_stream() {
+        let table_name = "t";
+        let chunk = Arc::new(
+            TestChunk::new(table_name)
+                .with_id(1)
+                .with_tag_column("tag1")
+                .with_i64_field_column("field_int")
+                .with_time_column()
+                .with_three_rows_of_data()
+                .with_stream(),
+        ) as Arc<dyn QueryChunk>;
+        let schema = chunk.schema().clone();
+
+        let ctx = IOxSessionContext::with_testing();
+        let state = ctx.inner().state();
+
+        let provider = ProviderBuilder::new(Arc::from(table_name), schema)
+            .add_chunk(Arc::clone(&chunk))
+            .build()
+            .unwrap();
+
+        let plan = provider
+            .scan(&state, Some(&vec![1, 2]), &[], None)
+            .await
+            .unwrap();
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " ProjectionExec: expr=[tag1@1 as tag1, time@2 as time]"
+        - "   DeduplicateExec: [tag1@1 ASC,time@2 ASC]"
+        - "     UnionExec"
+        - "       RecordBatchesExec: batches_groups=1 batches=0 total_rows=0 streams=1"
+        "###
+        );
+
+        let batches = ctx.collect(plan).await.unwrap();
+        assert_batches_sorted_eq!(
+            [
+                "+------+-----------------------------+",
+                "| tag1 | time                        |",
+                "+------+-----------------------------+",
+                "| UT   | 1970-01-01T00:00:00.000020Z |",
+                "| VT   | 1970-01-01T00:00:00.000010Z |",
+                "| WA   | 1970-01-01T00:00:00.000008Z |",
+                "+------+-----------------------------+",
+            ],
+            &batches
+        );
+    }
 }
@@ -0,0 +1,177 @@
+//! Implementation of [`DateBinOrderExec`].
+use std::{fmt, sync::Arc};
+
+use arrow::datatypes::SchemaRef;
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    physical_expr::ScalarFunctionExpr,
+    physical_plan::{
+        expressions::{Column, Literal, PhysicalSortExpr},
+        DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+        SendableRecordBatchStream, Statistics,
+    },
+};
+
+/// Passes its input through unchanged, but declares that the output is also ordered by a `date_bin` expression over
+/// the time column.
+///
+/// `date_bin(stride, time, origin)` is monotonically non-decreasing in `time`, so an input ordered by
+/// `[a, time, b]` is also ordered by `[a, date_bin(stride, time, origin), time, b]`. DataFusion cannot derive this
+/// itself, so without this node an [`AggregateExec`] that groups by `date_bin` always buffers every group in a hash
+/// table, even if the input is sorted by time. With the extended ordering, the aggregate emits each group as soon as
+/// it is complete.
+///
+/// [`AggregateExec`]: datafusion::physical_plan::aggregates::AggregateExec
+#[derive(Debug)]
+pub struct DateBinOrderExec {
+    input: Arc<dyn ExecutionPlan>,
+
+    /// The `date_bin` expression.
+    date_bin: Arc<dyn PhysicalExpr>,
+
+    /// Input ordering extended by `date_bin`.
+    ordering: Vec<PhysicalSortExpr>,
+}
+
+impl DateBinOrderExec {
+    /// Create a new node.
+    ///
+    /// Returns `None` if `date_bin` is not a `date_bin` call with a constant stride and origin, or if the input is
+    /// not ordered by the time column that `date_bin` is applied to.
+    pub fn try_new(input: Arc<dyn ExecutionPlan>, date_bin: Arc<dyn PhysicalExpr>) -> Option<Self> {
+        let ordering = date_bin_ordering(input.output_ordering()?, &date_bin)?;
+
+        Some(Self {
+            input,
+            date_bin,
+            ordering,
+        })
+    }
+
+    /// Input plan.
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// The `date_bin` expression.
+    pub fn date_bin(&self) -> &Arc<dyn PhysicalExpr> {
+        &self.date_bin
+    }
+}
+
+/// Insert `date_bin` into `ordering` before the time column it is applied to.
+fn date_bin_ordering(
+    ordering: &[PhysicalSortExpr],
+    date_bin: &Arc<dyn PhysicalExpr>,
+) -> Option<Vec<PhysicalSortExpr>> {
+    let func = date_bin.as_any().downcast_ref::<ScalarFunctionExpr>()?;
+    if func.name() != "date_bin" {
+        return None;
+    }
+    let (time, constants) = match func.args() {
+        [stride, time] => (time, vec![stride]),
+        [stride, time, origin] => (time, vec![stride, origin]),
+        _ => return None,
+    };
+    let time = time.as_any().downcast_ref::<Column>()?;
+    if !constants.iter().all(|e| e.as_any().is::<Literal>()) {
+        return None;
+    }
+
+    let pos = ordering.iter().position(|sort_expr| {
+        sort_expr
+            .expr
+            .as_any()
+            .downcast_ref::<Column>()
+            .map(|col| col == time)
+            .unwrap_or_default()
+    })?;
+
+    let mut ordering = ordering.to_vec();
+    let options = ordering[pos].options;
+    ordering.insert(
+        pos,
+        PhysicalSortExpr {
+            expr: Arc::clone(date_bin),
+            options,
+        },
+    );
+    Some(ordering)
+}
+
+impl ExecutionPlan for DateBinOrderExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.ordering)
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => {
+                // if the new input is no longer ordered by time, this node must be removed
+                match Self::try_new(Arc::clone(&children[0]), Arc::clone(&self.date_bin)) {
+                    Some(exec) => Ok(Arc::new(exec)),
+                    None => Ok(Arc::clone(&children[0])),
+                }
+            }
+            _ => Err(DataFusionError::Internal(
+                "DateBinOrderExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        self.input.execute(partition, context)
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+impl DisplayAs for DateBinOrderExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let ordering = self
+                    .ordering
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "DateBinOrderExec: output_ordering=[{ordering}]")
+            }
+        }
+    }
+}
@@ -91,7 +91,8 @@ pub fn group_potential_duplicates(
     groups
 }
 
-fn timestamp_min_max(chunk: &dyn QueryChunk) -> Option<TimestampMinMax> {
+/// Time range of the given chunk, based on its statistics.
+pub(crate) fn timestamp_min_max(chunk: &dyn QueryChunk) -> Option<TimestampMinMax> {
     chunk
         .stats()
         .column_statistics
@@ -117,10 +117,11 @@ fn combine_sort_key(
 
 /// Place [chunk](QueryChunk)s into physical nodes.
 ///
-/// This will group chunks into [record batch](QueryChunkData::RecordBatches) and [parquet
-/// file](QueryChunkData::Parquet) chunks. The latter will also be grouped by store.
+/// This will group chunks into [record batch](QueryChunkData::RecordBatches) / [stream](QueryChunkData::Stream) and
+/// [parquet file](QueryChunkData::Parquet) chunks. The latter will also be grouped by store.
 ///
-/// Record batch chunks will be turned into a single [`RecordBatchesExec`].
+/// Record batch and stream chunks will be turned into a single [`RecordBatchesExec`]. Streams are NOT materialized
+/// during planning.
 ///
 /// Parquet chunks will be turned into a [`ParquetExec`] per store, each of them with
 /// [`target_partitions`](datafusion::execution::context::SessionConfig::target_partitions) file groups.
@@ -153,7 +154,7 @@ pub fn chunks_to_physical_nodes(
 
     for chunk in &chunks {
         match chunk.data() {
-            QueryChunkData::RecordBatches(_) => {
+            QueryChunkData::RecordBatches(_) | QueryChunkData::Stream(_) => {
                 record_batch_chunks.push(Arc::clone(chunk));
             }
             QueryChunkData::Parquet(parquet_input) => {
@@ -579,4 +580,34 @@ mod tests {
         "###
         );
     }
+
+    #[test]
+    fn test_chunks_to_physical_nodes_stream() {
+        let chunk1 = TestChunk::new("table")
+            .with_tag_column("tag")
+            .with_dummy_parquet_file();
+        let chunk2 = TestChunk::new("table")
+            .with_tag_column("tag")
+            .with_one_row_of_data()
+            .with_stream();
+        let chunk3 = TestChunk::new("table")
+            .with_tag_column("tag")
+            .with_one_row_of_data();
+        let schema = chunk1.schema().as_arrow();
+        let plan = chunks_to_physical_nodes(
+            &schema,
+            None,
+            vec![Arc::new(chunk1), Arc::new(chunk2), Arc::new(chunk3)],
+            2,
+        );
+        insta::assert_yaml_snapshot!(
+            format_execution_plan(&plan),
+            @r###"
+        ---
+        - " UnionExec"
+        - "   RecordBatchesExec: batches_groups=2 batches=1 total_rows=1 streams=1"
+        - "   ParquetExec: file_groups={1 group: [[0.parquet]]}, projection=[tag]"
+        "###
+        );
+    }
 }
@@ -1,6 +1,6 @@
 //! Implementation of a DataFusion PhysicalPlan node across partition chunks
 
-use crate::{statistics::DFStatsAggregator, QueryChunk, CHUNK_ORDER_COLUMN_NAME};
+use crate::{statistics::DFStatsAggregator, QueryChunk, QueryChunkData, CHUNK_ORDER_COLUMN_NAME};
 
 use super::adapter::SchemaAdapterStream;
 use arrow::{
@@ -14,11 +14,13 @@ use datafusion::{
         expressions::{Column, PhysicalSortExpr},
         memory::MemoryStream,
         metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        stream::RecordBatchStreamAdapter,
         ColumnStatistics, DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
         SendableRecordBatchStream, Statistics,
     },
     scalar::ScalarValue,
 };
+use futures::StreamExt;
 use observability_deps::tracing::trace;
 use schema::sort::SortKey;
 use std::{
@@ -27,11 +29,24 @@ use std::{
     sync::Arc,
 };
 
+/// Data of a single chunk within [`RecordBatchesExec`].
+#[derive(Debug)]
+enum ChunkBatches {
+    /// Batches that are fully buffered in memory.
+    Buffered(Vec<RecordBatch>),
+
+    /// Batches that are streamed from [`QueryChunk::data`] at execution time.
+    Streamed,
+}
+
 /// Implements the DataFusion physical plan interface for [`RecordBatch`]es with automatic projection and NULL-column creation.
+///
+/// Chunks may either provide [buffered](QueryChunkData::RecordBatches) or [streamed](QueryChunkData::Stream) data. The
+/// latter is only requested when the respective partition is executed.
 #[derive(Debug)]
 pub(crate) struct RecordBatchesExec {
     /// Chunks contained in this exec node.
-    chunks: Vec<(Arc<dyn QueryChunk>, Vec<RecordBatch>)>,
+    chunks: Vec<(Arc<dyn QueryChunk>, ChunkBatches)>,
 
     /// Overall schema.
     schema: SchemaRef,
@@ -67,10 +82,13 @@ impl RecordBatchesExec {
         let chunks: Vec<_> = chunks
             .into_iter()
             .map(|chunk| {
-                let batches = chunk
-                    .data()
-                    .into_record_batches()
-                    .expect("chunk must have record batches");
+                let batches = match chunk.data() {
+                    QueryChunkData::RecordBatches(batches) => ChunkBatches::Buffered(batches),
+                    QueryChunkData::Stream(_) => ChunkBatches::Streamed,
+                    QueryChunkData::Parquet(_) => {
+                        panic!("chunk must have record batches or a stream")
+                    }
+                };
 
                 (chunk, batches)
             })
@@ -216,11 +234,35 @@ impl ExecutionPlan for RecordBatchesExec {
             .map(|projection| Arc::new(part_schema.project(projection).expect("projection broken")))
             .unwrap_or(part_schema);
 
-        let stream = Box::pin(MemoryStream::try_new(
-            batches.clone(),
-            incomplete_output_schema,
-            projection,
-        )?);
+        let stream: SendableRecordBatchStream = match batches {
+            ChunkBatches::Buffered(batches) => Box::pin(MemoryStream::try_new(
+                batches.clone(),
+                incomplete_output_schema,
+                projection,
+            )?),
+            ChunkBatches::Streamed => {
+                let QueryChunkData::Stream(stream) = chunk.data() else {
+                    return Err(DataFusionError::Internal(format!(
+                        "chunk {} no longer provides a stream",
+                        chunk.id()
+                    )));
+                };
+
+                match projection {
+                    Some(projection) => Box::pin(RecordBatchStreamAdapter::new(
+                        incomplete_output_schema,
+                        stream.map(move |batch| {
+                            batch.and_then(|batch| {
+                                batch
+                                    .project(&projection)
+                                    .map_err(DataFusionError::ArrowError)
+                            })
+                        }),
+                    )),
+                    None => stream,
+                }
+            }
+        };
         let virtual_columns = HashMap::from([(
             CHUNK_ORDER_COLUMN_NAME,
             ScalarValue::from(chunk.order().get()),
@@ -247,24 +289,37 @@ impl DisplayAs for RecordBatchesExec {
     fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let total_groups = self.chunks.len();
 
-        let total_batches = self
-            .chunks
-            .iter()
-            .map(|(_chunk, batches)| batches.len())
+        let buffered = || {
+            self.chunks
+                .iter()
+                .filter_map(|(_chunk, batches)| match batches {
+                    ChunkBatches::Buffered(batches) => Some(batches),
+                    ChunkBatches::Streamed => None,
+                })
+        };
+
+        let total_batches = buffered().map(|batches| batches.len()).sum::<usize>();
+
+        let total_rows = buffered()
+            .flat_map(|batches| batches.iter().map(|batch| batch.num_rows()))
             .sum::<usize>();
 
-        let total_rows = self
+        let total_streams = self
             .chunks
             .iter()
-            .flat_map(|(_chunk, batches)| batches.iter().map(|batch| batch.num_rows()))
-            .sum::<usize>();
+            .filter(|(_chunk, batches)| matches!(batches, ChunkBatches::Streamed))
+            .count();
 
         match t {
             DisplayFormatType::Default | DisplayFormatType::Verbose => {
                 write!(
                     f,
                     "RecordBatchesExec: batches_groups={total_groups} batches={total_batches} total_rows={total_rows}",
-                )
+                )?;
+                if total_streams > 0 {
+                    write!(f, " streams={total_streams}")?;
+                }
+                Ok(())
             }
         }
     }
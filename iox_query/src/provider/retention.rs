@@ -0,0 +1,230 @@
+//! Implementation of [`RetentionFilterExec`].
+use std::{fmt, sync::Arc};
+
+use arrow::{compute::filter_record_batch, datatypes::SchemaRef, record_batch::RecordBatch};
+use datafusion::{
+    common::cast::as_boolean_array,
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    logical_expr::Operator,
+    physical_plan::{
+        expressions::{BinaryExpr, Column, Literal, PhysicalSortExpr},
+        metrics::{BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
+        stream::RecordBatchStreamAdapter,
+        DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, PhysicalExpr,
+        SendableRecordBatchStream, Statistics,
+    },
+    scalar::ScalarValue,
+};
+use futures::StreamExt;
+use schema::{TIME_COLUMN_NAME, TIME_DATA_TIMEZONE};
+
+/// Removes rows that are outside of the retention period of the namespace, i.e. all rows with `time <= cutoff`.
+///
+/// Retention is usually already enforced by a time predicate that is pushed down into the chunk scans. This operator
+/// is the last line of defense that is injected by [`ChunkTableProvider`](super::ChunkTableProvider) into every scan,
+/// so that no plan -- including ad-hoc plans created by Flight SQL or InfluxQL -- serves expired rows, even if the
+/// predicate got lost along the way.
+#[derive(Debug)]
+pub struct RetentionFilterExec {
+    input: Arc<dyn ExecutionPlan>,
+
+    /// Rows at or before this timestamp (in nanoseconds) are expired.
+    cutoff: i64,
+
+    /// `time > cutoff`
+    predicate: Arc<dyn PhysicalExpr>,
+
+    /// Execution metrics.
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl RetentionFilterExec {
+    /// Create new retention filter.
+    ///
+    /// Fails if the input does not have a time column.
+    pub fn try_new(input: Arc<dyn ExecutionPlan>, cutoff: i64) -> Result<Self> {
+        let time_col = Column::new_with_schema(TIME_COLUMN_NAME, &input.schema())?;
+        let predicate = Arc::new(BinaryExpr::new(
+            Arc::new(time_col),
+            Operator::Gt,
+            Arc::new(Literal::new(ScalarValue::TimestampNanosecond(
+                Some(cutoff),
+                TIME_DATA_TIMEZONE(),
+            ))),
+        ));
+
+        Ok(Self {
+            input,
+            cutoff,
+            predicate,
+            metrics: ExecutionPlanMetricsSet::new(),
+        })
+    }
+
+    /// Input plan.
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Retention cutoff in nanoseconds. Rows at or before this timestamp are removed.
+    pub fn cutoff(&self) -> i64 {
+        self.cutoff
+    }
+}
+
+impl ExecutionPlan for RetentionFilterExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::try_new(
+                Arc::clone(&children[0]),
+                self.cutoff,
+            )?)),
+            _ => Err(DataFusionError::Internal(
+                "RetentionFilterExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let expired_rows = MetricBuilder::new(&self.metrics).counter("expired_rows", partition);
+        let predicate = Arc::clone(&self.predicate);
+
+        let stream = self.input.execute(partition, context)?.map(move |batch| {
+            let _timer = baseline_metrics.elapsed_compute().timer();
+            let batch = batch.and_then(|batch| filter_batch(&batch, &predicate, &expired_rows));
+            if let Ok(batch) = &batch {
+                baseline_metrics.record_output(batch.num_rows());
+            }
+            batch
+        });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        // we don't know how many rows are expired
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for RetentionFilterExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "RetentionFilterExec: {}", self.predicate)
+            }
+        }
+    }
+}
+
+/// Remove rows from `batch` that do NOT satisfy the retention `predicate`.
+fn filter_batch(
+    batch: &RecordBatch,
+    predicate: &Arc<dyn PhysicalExpr>,
+    expired_rows: &Count,
+) -> Result<RecordBatch> {
+    let mask = predicate.evaluate(batch)?.into_array(batch.num_rows());
+    let mask = as_boolean_array(&mask)?;
+    let filtered = filter_record_batch(batch, mask)?;
+    expired_rows.add(batch.num_rows() - filtered.num_rows());
+    Ok(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{ArrayRef, Float64Array, TimestampNanosecondArray};
+    use arrow_util::assert_batches_eq;
+    use datafusion::physical_plan::{displayable, memory::MemoryExec};
+    use datafusion_util::test_collect;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_filter() {
+        let f = Float64Array::from(vec![Some(1.0), Some(2.0), Some(3.0), None]);
+        let time = TimestampNanosecondArray::from(vec![Some(50), Some(100), Some(150), Some(200)]);
+        let batch = RecordBatch::try_from_iter(vec![
+            ("f", Arc::new(f) as ArrayRef),
+            ("time", Arc::new(time) as ArrayRef),
+        ])
+        .unwrap();
+
+        let input =
+            Arc::new(MemoryExec::try_new(&[vec![batch.clone()]], batch.schema(), None).unwrap());
+        let exec = Arc::new(RetentionFilterExec::try_new(input, 100).unwrap());
+
+        assert_eq!(
+            displayable(exec.as_ref()).one_line().to_string().trim_end(),
+            "RetentionFilterExec: time@1 > 100",
+        );
+
+        let output = test_collect(Arc::clone(&exec) as Arc<dyn ExecutionPlan>).await;
+        let expected = vec![
+            "+-----+--------------------------------+",
+            "| f   | time                           |",
+            "+-----+--------------------------------+",
+            "| 3.0 | 1970-01-01T00:00:00.000000150Z |",
+            "|     | 1970-01-01T00:00:00.000000200Z |",
+            "+-----+--------------------------------+",
+        ];
+        assert_batches_eq!(&expected, &output);
+
+        let metrics = exec.metrics().unwrap();
+        assert_eq!(metrics.sum_by_name("expired_rows").unwrap().as_usize(), 2);
+        assert_eq!(metrics.output_rows().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_no_time_column() {
+        let f = Float64Array::from(vec![Some(1.0)]);
+        let batch = RecordBatch::try_from_iter(vec![("f", Arc::new(f) as ArrayRef)]).unwrap();
+        let input =
+            Arc::new(MemoryExec::try_new(&[vec![batch.clone()]], batch.schema(), None).unwrap());
+
+        RetentionFilterExec::try_new(input, 100).unwrap_err();
+    }
+}
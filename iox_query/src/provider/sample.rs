@@ -0,0 +1,256 @@
+//! Implementation of [`SampleExec`].
+use std::{fmt, sync::Arc};
+
+use arrow::datatypes::SchemaRef;
+use datafusion::{
+    error::{DataFusionError, Result},
+    execution::context::TaskContext,
+    physical_plan::{
+        expressions::PhysicalSortExpr,
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
+        stream::RecordBatchStreamAdapter,
+        DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+        Statistics,
+    },
+};
+use futures::StreamExt;
+
+use crate::config::SampleFraction;
+
+/// Passes through a random subset of the record batches of its input.
+///
+/// Every batch is kept with a probability of `fraction`. Skipped batches are dropped as a whole, so sampling is cheap
+/// but coarse: it follows the batch boundaries of the input (e.g. parquet row groups and ingester buffers).
+///
+/// The selection is deterministic for a given input partition and batch position, so repeating a query over the same
+/// data returns the same sample.
+#[derive(Debug)]
+pub struct SampleExec {
+    input: Arc<dyn ExecutionPlan>,
+
+    /// Fraction of the batches that is kept.
+    fraction: SampleFraction,
+
+    /// Execution metrics.
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl SampleExec {
+    /// Create new sampling node.
+    pub fn new(input: Arc<dyn ExecutionPlan>, fraction: SampleFraction) -> Self {
+        Self {
+            input,
+            fraction,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Input plan.
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Fraction of the batches that is kept.
+    pub fn fraction(&self) -> SampleFraction {
+        self.fraction
+    }
+}
+
+impl ExecutionPlan for SampleExec {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        self.input.output_partitioning()
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> bool {
+        false
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::new(Arc::clone(&children[0]), self.fraction))),
+            _ => Err(DataFusionError::Internal(
+                "SampleExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let skipped_batches =
+            MetricBuilder::new(&self.metrics).counter("skipped_batches", partition);
+        let fraction = self.fraction.get();
+
+        let stream = self
+            .input
+            .execute(partition, context)?
+            .enumerate()
+            .filter_map(move |(idx, batch)| {
+                let keep = match &batch {
+                    // never swallow errors
+                    Err(_) => true,
+                    Ok(_) => sample_value(partition, idx) < fraction,
+                };
+
+                if keep {
+                    if let Ok(batch) = &batch {
+                        baseline_metrics.record_output(batch.num_rows());
+                    }
+                } else {
+                    skipped_batches.add(1);
+                }
+
+                futures::future::ready(keep.then_some(batch))
+            });
+
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            self.schema(),
+            stream,
+        )))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        // we don't know which batches are skipped
+        Statistics::default()
+    }
+}
+
+impl DisplayAs for SampleExec {
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                write!(f, "SampleExec: fraction={}", self.fraction)
+            }
+        }
+    }
+}
+
+/// Pseudo-random value in `[0, 1)` for the given batch.
+///
+/// Uses the [SplitMix64] finalizer, which is good enough to decorrelate neighbouring batches.
+///
+///
+/// [SplitMix64]: https://prng.di.unimi.it/splitmix64.c
+fn sample_value(partition: usize, idx: usize) -> f64 {
+    let mut z = (((partition as u64) << 32) ^ idx as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+
+    // use the upper 53 bits, which is the precision of a f64
+    (z >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{ArrayRef, Int64Array},
+        record_batch::RecordBatch,
+    };
+    use datafusion::physical_plan::{displayable, memory::MemoryExec};
+    use datafusion_util::test_collect;
+
+    use super::*;
+
+    fn input(n_batches: usize) -> Arc<dyn ExecutionPlan> {
+        let batches = (0..n_batches)
+            .map(|i| {
+                RecordBatch::try_from_iter(vec![(
+                    "i",
+                    Arc::new(Int64Array::from(vec![i as i64])) as ArrayRef,
+                )])
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let schema = batches[0].schema();
+        Arc::new(MemoryExec::try_new(&[batches], schema, None).unwrap())
+    }
+
+    fn kept_values(batches: &[RecordBatch]) -> Vec<i64> {
+        batches
+            .iter()
+            .flat_map(|batch| {
+                batch
+                    .column(0)
+                    .as_any()
+                    .downcast_ref::<Int64Array>()
+                    .unwrap()
+                    .values()
+                    .to_vec()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sample() {
+        let exec = Arc::new(SampleExec::new(
+            input(1_000),
+            SampleFraction::new(0.25).unwrap(),
+        ));
+
+        assert_eq!(
+            displayable(exec.as_ref()).one_line().to_string().trim_end(),
+            "SampleExec: fraction=0.25",
+        );
+
+        let output = test_collect(Arc::clone(&exec) as Arc<dyn ExecutionPlan>).await;
+        let kept = kept_values(&output);
+        assert!(
+            (200..300).contains(&kept.len()),
+            "unexpected sample size: {}",
+            kept.len()
+        );
+
+        // order is preserved
+        assert!(kept.windows(2).all(|w| w[0] < w[1]));
+
+        let metrics = exec.metrics().unwrap();
+        assert_eq!(metrics.output_rows().unwrap(), kept.len());
+        assert_eq!(
+            metrics.sum_by_name("skipped_batches").unwrap().as_usize(),
+            1_000 - kept.len()
+        );
+
+        // sample is stable
+        let output2 = test_collect(Arc::clone(&exec) as Arc<dyn ExecutionPlan>).await;
+        assert_eq!(kept_values(&output2), kept);
+    }
+
+    #[tokio::test]
+    async fn test_sample_all() {
+        let exec = Arc::new(SampleExec::new(input(10), SampleFraction::ALL));
+
+        let output = test_collect(exec as Arc<dyn ExecutionPlan>).await;
+        assert_eq!(kept_values(&output), (0..10).collect::<Vec<_>>());
+    }
+}
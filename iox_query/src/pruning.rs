@@ -1,28 +1,32 @@
 //! Implementation of statistics based pruning
 
-use crate::QueryChunk;
+use crate::{config::PruningReportVerbosity, provider::overlap::timestamp_min_max, QueryChunk};
 use arrow::{
     array::{ArrayRef, UInt64Array},
     datatypes::{DataType, SchemaRef},
 };
+use data_types::ChunkId;
 use datafusion::{
+    logical_expr::{expr::InList, BinaryExpr, Operator},
+    optimizer::utils::split_conjunction,
     physical_expr::execution_props::ExecutionProps,
     physical_optimizer::pruning::PruningStatistics,
     physical_plan::{ColumnStatistics, Statistics},
-    prelude::Column,
+    prelude::{Column, Expr},
     scalar::ScalarValue,
 };
 use datafusion_util::create_pruning_predicate;
 use observability_deps::tracing::{debug, trace, warn};
+use parking_lot::Mutex;
 use predicate::Predicate;
 use query_functions::group_by::Aggregate;
-use schema::Schema;
-use std::sync::Arc;
+use schema::{InfluxColumnType, Schema};
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
 
 /// Reason why a chunk could not be pruned.
 ///
 /// Also see [`PruningObserver::could_not_prune`].
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum NotPrunedReason {
     /// No expression on predicate
     NoExpressionOnPredicate,
@@ -51,6 +55,190 @@ impl std::fmt::Display for NotPrunedReason {
     }
 }
 
+/// Reason why a chunk was pruned.
+///
+/// Also see [`PruningReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PruneReason {
+    /// Chunk only contains data that is older than the retention period of the namespace.
+    Retention,
+
+    /// Time range of the chunk does not overlap with the time range of the query.
+    TimeRange,
+
+    /// Tag min/max statistics of the chunk prove that the tag predicates of the query cannot match.
+    TagRange,
+
+    /// A [column filter](QueryChunk::column_filter) proves that the chunk does not contain the requested values.
+    ColumnFilter,
+
+    /// Statistics of other columns or a combination of multiple predicates.
+    Statistics,
+}
+
+impl PruneReason {
+    /// Human-readable string representation.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Retention => "retention",
+            Self::TimeRange => "time range",
+            Self::TagRange => "tag range",
+            Self::ColumnFilter => "column filter",
+            Self::Statistics => "statistics",
+        }
+    }
+}
+
+impl std::fmt::Display for PruneReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Pruning outcome of a single chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PruningOutcome {
+    /// Chunk was not pruned and needs to be scanned.
+    NotPruned,
+
+    /// Chunk was pruned.
+    Pruned(PruneReason),
+
+    /// Pruning was not possible, so the chunk needs to be scanned.
+    CouldNotPrune(NotPrunedReason),
+}
+
+impl std::fmt::Display for PruningOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotPruned => write!(f, "not pruned"),
+            Self::Pruned(reason) => write!(f, "pruned ({reason})"),
+            Self::CouldNotPrune(reason) => write!(f, "could not prune ({reason})"),
+        }
+    }
+}
+
+/// [Outcome](PruningOutcome) for a single chunk within a [`PruningReport`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkPruningOutcome {
+    /// Chunk ID.
+    pub chunk_id: ChunkId,
+
+    /// Chunk type, see [`QueryChunk::chunk_type`].
+    pub chunk_type: String,
+
+    /// Outcome.
+    pub outcome: PruningOutcome,
+}
+
+/// Records why the chunks of a single table were pruned or not.
+///
+/// Created by [`prune_chunks_with_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PruningReport {
+    /// Table name.
+    pub table_name: String,
+
+    /// Outcome for every chunk that was considered, in the order in which they were passed to the pruning.
+    pub chunks: Vec<ChunkPruningOutcome>,
+}
+
+impl PruningReport {
+    /// Create empty report for the given table.
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            chunks: vec![],
+        }
+    }
+
+    fn push(&mut self, chunk: &dyn QueryChunk, outcome: PruningOutcome) {
+        self.chunks.push(ChunkPruningOutcome {
+            chunk_id: chunk.id(),
+            chunk_type: chunk.chunk_type().to_owned(),
+            outcome,
+        });
+    }
+
+    /// Number of chunks per outcome.
+    pub fn counts(&self) -> BTreeMap<PruningOutcome, usize> {
+        let mut counts = BTreeMap::new();
+        for chunk in &self.chunks {
+            *counts.entry(chunk.outcome).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Render report.
+    ///
+    /// Returns `None` if `verbosity` is [`Off`](PruningReportVerbosity::Off).
+    pub fn display(&self, verbosity: PruningReportVerbosity) -> Option<String> {
+        if !verbosity.enabled() {
+            return None;
+        }
+
+        let mut out = format!("{}: {} chunks", self.table_name, self.chunks.len());
+        for (outcome, count) in self.counts() {
+            out.push_str(&format!(", {outcome}: {count}"));
+        }
+
+        if verbosity == PruningReportVerbosity::Full {
+            for chunk in &self.chunks {
+                out.push_str(&format!(
+                    "\n  {} {}: {}",
+                    chunk.chunk_type, chunk.chunk_id, chunk.outcome
+                ));
+            }
+        }
+
+        Some(out)
+    }
+}
+
+/// Collects the [`PruningReport`]s of all tables that are scanned by a query.
+///
+/// This is attached to the DataFusion session, see
+/// [`IOxSessionContext::pruning_reports`](crate::exec::IOxSessionContext::pruning_reports).
+#[derive(Debug, Default)]
+pub struct PruningReports {
+    reports: Mutex<Vec<PruningReport>>,
+}
+
+impl PruningReports {
+    /// Add report.
+    pub fn push(&self, report: PruningReport) {
+        self.reports.lock().push(report);
+    }
+
+    /// Get all reports that were added so far.
+    pub fn reports(&self) -> Vec<PruningReport> {
+        self.reports.lock().clone()
+    }
+
+    /// Render all reports, one table per line.
+    ///
+    /// Returns `None` if there are no reports or `verbosity` is [`Off`](PruningReportVerbosity::Off).
+    pub fn display(&self, verbosity: PruningReportVerbosity) -> Option<String> {
+        let reports = self.reports.lock();
+        let lines = reports
+            .iter()
+            .filter_map(|report| report.display(verbosity))
+            .collect::<Vec<_>>();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+/// Filter for a single column of a [`QueryChunk`] that can prove that a value is NOT present, e.g. a bloom or ngram
+/// filter.
+///
+/// Also see [`QueryChunk::column_filter`].
+pub trait RowGroupFilter: Debug + Send + Sync {
+    /// Returns `false` if the column definitely does NOT contain `value`.
+    ///
+    /// `true` means that the value may or may not be present.
+    fn may_contain(&self, value: &ScalarValue) -> bool;
+}
+
 /// Something that cares to be notified when pruning of chunks occurs
 pub trait PruningObserver {
     /// Called when the specified chunk was pruned
@@ -83,7 +271,249 @@ pub fn prune_chunks(
         .iter()
         .map(|c| (c.stats(), c.schema().as_arrow()))
         .collect();
-    prune_summaries(table_schema, &summaries, predicate)
+    let mut results = prune_summaries(table_schema, &summaries, predicate)?;
+    prune_by_column_filters(chunks, predicate, &mut results);
+    Ok(results)
+}
+
+/// Same as [`prune_chunks`] but also returns a [`PruningReport`] that records why chunks were (not) pruned.
+///
+/// Chunks that only contain data up to `retention_time` are reported as [`PruneReason::Retention`]. Attributing the
+/// other reasons requires additional pruning passes over the pruned chunks, so this should only be used if the report
+/// is actually requested.
+pub fn prune_chunks_with_report(
+    table_name: &str,
+    table_schema: &Schema,
+    chunks: &[Arc<dyn QueryChunk>],
+    predicate: &Predicate,
+    retention_time: Option<i64>,
+) -> (Vec<bool>, PruningReport) {
+    let mut report = PruningReport::new(table_name);
+
+    let summaries: Vec<_> = chunks
+        .iter()
+        .map(|c| (c.stats(), c.schema().as_arrow()))
+        .collect();
+    let mut results = match prune_summaries(table_schema, &summaries, predicate) {
+        Ok(results) => results,
+        Err(reason) => {
+            for chunk in chunks {
+                report.push(chunk.as_ref(), PruningOutcome::CouldNotPrune(reason));
+            }
+            return (vec![true; chunks.len()], report);
+        }
+    };
+    let pruned_by_stats = results
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, keep)| (!keep).then_some(idx))
+        .collect::<Vec<_>>();
+    prune_by_column_filters(chunks, predicate, &mut results);
+
+    let mut stats_reasons = stats_prune_reasons(
+        table_schema,
+        chunks,
+        &summaries,
+        &pruned_by_stats,
+        predicate,
+        retention_time,
+    )
+    .into_iter()
+    .peekable();
+
+    for (idx, (chunk, keep)) in chunks.iter().zip(&results).enumerate() {
+        let outcome = match stats_reasons.next_if(|(stats_idx, _)| *stats_idx == idx) {
+            Some((_, reason)) => PruningOutcome::Pruned(reason),
+            None if *keep => PruningOutcome::NotPruned,
+            None => PruningOutcome::Pruned(PruneReason::ColumnFilter),
+        };
+        report.push(chunk.as_ref(), outcome);
+    }
+
+    (results, report)
+}
+
+/// Determines why the chunks at the given (sorted) indices were pruned based on their statistics.
+///
+/// Returns `(index, reason)` pairs in the order of `pruned`.
+fn stats_prune_reasons(
+    table_schema: &Schema,
+    chunks: &[Arc<dyn QueryChunk>],
+    summaries: &[(Arc<Statistics>, SchemaRef)],
+    pruned: &[usize],
+    predicate: &Predicate,
+    retention_time: Option<i64>,
+) -> Vec<(usize, PruneReason)> {
+    let mut reasons = pruned
+        .iter()
+        .map(|idx| (*idx, PruneReason::Statistics))
+        .collect::<Vec<_>>();
+
+    // positions within `reasons` that are not attributed yet
+    let mut unknown = (0..reasons.len()).collect::<Vec<_>>();
+
+    // the retention predicate is `time > retention_time`
+    if let Some(retention_time) = retention_time {
+        unknown.retain(|pos| {
+            let expired = timestamp_min_max(chunks[reasons[*pos].0].as_ref())
+                .map(|range| range.max <= retention_time)
+                .unwrap_or_default();
+            if expired {
+                reasons[*pos].1 = PruneReason::Retention;
+            }
+            !expired
+        });
+    }
+
+    let conjuncts = predicate
+        .exprs
+        .iter()
+        .flat_map(split_conjunction)
+        .collect::<Vec<_>>();
+    let sub_predicates =
+        [
+            (
+                PruneReason::TimeRange,
+                Predicate {
+                    range: predicate.range,
+                    ..Default::default()
+                }
+                .with_exprs(
+                    conjuncts.iter().copied().cloned().filter(|expr| {
+                        refers_only_to(expr, table_schema, InfluxColumnType::Timestamp)
+                    }),
+                ),
+            ),
+            (
+                PruneReason::TagRange,
+                Predicate::new().with_exprs(
+                    conjuncts
+                        .iter()
+                        .copied()
+                        .cloned()
+                        .filter(|expr| refers_only_to(expr, table_schema, InfluxColumnType::Tag)),
+                ),
+            ),
+        ];
+
+    for (reason, sub_predicate) in sub_predicates {
+        if unknown.is_empty() {
+            break;
+        }
+
+        let sub_summaries = unknown
+            .iter()
+            .map(|pos| summaries[reasons[*pos].0].clone())
+            .collect::<Vec<_>>();
+        let Ok(keeps) = prune_summaries(table_schema, &sub_summaries, &sub_predicate) else {
+            continue;
+        };
+
+        let mut keeps = keeps.into_iter();
+        unknown.retain(|pos| {
+            let keep = keeps.next().unwrap_or(true);
+            if !keep {
+                reasons[*pos].1 = reason;
+            }
+            keep
+        });
+    }
+
+    reasons
+}
+
+/// Returns `true` if `expr` only refers to columns of the given type.
+fn refers_only_to(expr: &Expr, table_schema: &Schema, column_type: InfluxColumnType) -> bool {
+    let Ok(columns) = expr.to_columns() else {
+        return false;
+    };
+
+    !columns.is_empty()
+        && columns.iter().all(|column| {
+            table_schema
+                .find_index_of(&column.name)
+                .map(|idx| table_schema.field(idx).0 == column_type)
+                .unwrap_or_default()
+        })
+}
+
+/// Prunes chunks that are NOT pruned yet using their [column filters](QueryChunk::column_filter).
+///
+/// Only `col = literal` and `col IN (literal, ...)` expressions are considered.
+fn prune_by_column_filters(
+    chunks: &[Arc<dyn QueryChunk>],
+    predicate: &Predicate,
+    results: &mut [bool],
+) {
+    let equalities: Vec<_> = predicate
+        .exprs
+        .iter()
+        .flat_map(split_conjunction)
+        .filter_map(equality_values)
+        .collect();
+    if equalities.is_empty() {
+        return;
+    }
+
+    for (chunk, keep) in chunks.iter().zip(results.iter_mut()) {
+        if !*keep {
+            continue;
+        }
+
+        *keep = equalities
+            .iter()
+            .all(|(column, values)| match chunk.column_filter(column) {
+                Some(filter) => values.iter().any(|v| filter.may_contain(v)),
+                None => true,
+            });
+        if !*keep {
+            trace!(chunk_id=%chunk.id(), "Pruned chunk using column filter");
+        }
+    }
+}
+
+/// Extracts column name and candidate values from `col = literal`, `literal = col` and `col IN (literal, ...)`
+/// expressions.
+fn equality_values(expr: &Expr) -> Option<(&str, Vec<ScalarValue>)> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(col), Expr::Literal(value))
+            | (Expr::Literal(value), Expr::Column(col)) => {
+                Some((col.name.as_str(), vec![unpack_dictionary(value)]))
+            }
+            _ => None,
+        },
+        Expr::InList(InList {
+            expr,
+            list,
+            negated: false,
+        }) => {
+            let Expr::Column(col) = expr.as_ref() else {
+                return None;
+            };
+            let values = list
+                .iter()
+                .map(|expr| match expr {
+                    Expr::Literal(value) => Some(unpack_dictionary(value)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some((col.name.as_str(), values))
+        }
+        _ => None,
+    }
+}
+
+/// Tag predicates use dictionary-encoded literals, but filters operate on the plain values.
+fn unpack_dictionary(value: &ScalarValue) -> ScalarValue {
+    match value {
+        ScalarValue::Dictionary(_, value) => value.as_ref().clone(),
+        value => value.clone(),
+    }
 }
 
 /// Given a `Vec` of pruning summaries, return a `Vec<bool>` where `false` indicates that the
@@ -212,7 +642,7 @@ fn get_aggregate(stats: &ColumnStatistics, aggregate: Aggregate) -> Option<&Scal
 mod test {
     use std::{ops::Not, sync::Arc};
 
-    use datafusion::prelude::{col, lit};
+    use datafusion::prelude::{col, lit, lit_timestamp_nano};
     use datafusion_util::lit_dict;
     use predicate::Predicate;
     use schema::merge::SchemaMerger;
@@ -221,6 +651,71 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn test_pruned_column_filter() {
+        test_helpers::maybe_start_logging();
+        // tag1 = 'foo' where
+        //   c1: filter contains 'foo' --> not pruned
+        //   c2: filter does NOT contain 'foo' --> pruned
+        //   c3: no filter --> not pruned
+        let c1 = Arc::new(
+            TestChunk::new("chunk1")
+                .with_tag_column("tag1")
+                .with_column_filter("tag1", ValueSetFilter::new(&["foo", "bar"])),
+        ) as Arc<dyn QueryChunk>;
+        let c2 = Arc::new(
+            TestChunk::new("chunk2")
+                .with_tag_column("tag1")
+                .with_column_filter("tag1", ValueSetFilter::new(&["bar"])),
+        ) as Arc<dyn QueryChunk>;
+        let c3 = Arc::new(TestChunk::new("chunk3").with_tag_column("tag1")) as Arc<dyn QueryChunk>;
+
+        let predicate = Predicate::new().with_expr(col("tag1").eq(lit_dict("foo")));
+
+        let schema = c1.schema().clone();
+        let result = prune_chunks(&schema, &[c1, c2, c3], &predicate);
+        assert_eq!(result.expect("pruning succeeds"), vec![true, false, true]);
+    }
+
+    #[test]
+    fn test_pruned_column_filter_in_list() {
+        test_helpers::maybe_start_logging();
+        // tag1 IN ('foo', 'baz') AND tag2 = 'x' where
+        //   c1: tag1 filter contains 'baz', tag2 filter contains 'x' --> not pruned
+        //   c2: tag1 filter contains neither --> pruned
+        //   c3: tag1 filter contains 'foo', tag2 filter does NOT contain 'x' --> pruned
+        let c1 = Arc::new(
+            TestChunk::new("chunk1")
+                .with_tag_column("tag1")
+                .with_tag_column("tag2")
+                .with_column_filter("tag1", ValueSetFilter::new(&["baz"]))
+                .with_column_filter("tag2", ValueSetFilter::new(&["x"])),
+        ) as Arc<dyn QueryChunk>;
+        let c2 = Arc::new(
+            TestChunk::new("chunk2")
+                .with_tag_column("tag1")
+                .with_tag_column("tag2")
+                .with_column_filter("tag1", ValueSetFilter::new(&["bar"])),
+        ) as Arc<dyn QueryChunk>;
+        let c3 = Arc::new(
+            TestChunk::new("chunk3")
+                .with_tag_column("tag1")
+                .with_tag_column("tag2")
+                .with_column_filter("tag1", ValueSetFilter::new(&["foo"]))
+                .with_column_filter("tag2", ValueSetFilter::new(&["y"])),
+        ) as Arc<dyn QueryChunk>;
+
+        let predicate = Predicate::new().with_expr(
+            col("tag1")
+                .in_list(vec![lit_dict("foo"), lit_dict("baz")], false)
+                .and(col("tag2").eq(lit_dict("x"))),
+        );
+
+        let schema = c1.schema().clone();
+        let result = prune_chunks(&schema, &[c1, c2, c3], &predicate);
+        assert_eq!(result.expect("pruning succeeds"), vec![true, false, false]);
+    }
+
     #[test]
     fn test_empty() {
         test_helpers::maybe_start_logging();
@@ -677,4 +1172,124 @@ mod test {
             vec![true, false, false, true, false, true]
         );
     }
+
+    #[test]
+    fn test_report() {
+        test_helpers::maybe_start_logging();
+        // time > 20 (retention) AND time >= 300 AND column1 > 100 AND tag1 = 'foo' where
+        //   c1: time [0, 10] --> pruned (retention)
+        //   c2: time [100, 200] --> pruned (time range)
+        //   c3: time [300, 400], column1 [0, 10] --> pruned (statistics)
+        //   c4: time [300, 400], column1 [0, 1000], filter does NOT contain 'foo' --> pruned (column filter)
+        //   c5: time [300, 400], column1 [0, 1000], filter contains 'foo' --> not pruned
+        let c1 = Arc::new(
+            TestChunk::new("chunk1")
+                .with_time_column_with_stats(Some(0), Some(10))
+                .with_i64_field_column_with_stats("column1", Some(0), Some(1000)),
+        ) as Arc<dyn QueryChunk>;
+        let c2 = Arc::new(
+            TestChunk::new("chunk2")
+                .with_time_column_with_stats(Some(100), Some(200))
+                .with_i64_field_column_with_stats("column1", Some(0), Some(1000)),
+        ) as Arc<dyn QueryChunk>;
+        let c3 = Arc::new(
+            TestChunk::new("chunk3")
+                .with_time_column_with_stats(Some(300), Some(400))
+                .with_i64_field_column_with_stats("column1", Some(0), Some(10)),
+        ) as Arc<dyn QueryChunk>;
+        let c4 = Arc::new(
+            TestChunk::new("chunk4")
+                .with_time_column_with_stats(Some(300), Some(400))
+                .with_i64_field_column_with_stats("column1", Some(0), Some(1000))
+                .with_tag_column("tag1")
+                .with_column_filter("tag1", ValueSetFilter::new(&["bar"])),
+        ) as Arc<dyn QueryChunk>;
+        let c5 = Arc::new(
+            TestChunk::new("chunk5")
+                .with_time_column_with_stats(Some(300), Some(400))
+                .with_i64_field_column_with_stats("column1", Some(0), Some(1000))
+                .with_tag_column("tag1")
+                .with_column_filter("tag1", ValueSetFilter::new(&["foo"])),
+        ) as Arc<dyn QueryChunk>;
+
+        let predicate = Predicate::new()
+            .with_retention(20)
+            .with_expr(col("time").gt_eq(lit_timestamp_nano(300)))
+            .with_expr(col("column1").gt(lit(100i64)))
+            .with_expr(col("tag1").eq(lit_dict("foo")));
+
+        let chunks = vec![c1, c2, c3, c4, c5];
+        let schema = merge_schema(&chunks);
+
+        let (keeps, report) =
+            prune_chunks_with_report("table", &schema, &chunks, &predicate, Some(20));
+        assert_eq!(keeps, vec![false, false, false, false, true]);
+        assert_eq!(
+            report
+                .chunks
+                .iter()
+                .map(|chunk| chunk.outcome)
+                .collect::<Vec<_>>(),
+            vec![
+                PruningOutcome::Pruned(PruneReason::Retention),
+                PruningOutcome::Pruned(PruneReason::TimeRange),
+                PruningOutcome::Pruned(PruneReason::Statistics),
+                PruningOutcome::Pruned(PruneReason::ColumnFilter),
+                PruningOutcome::NotPruned,
+            ]
+        );
+
+        // same result as the plain pruning
+        assert_eq!(prune_chunks(&schema, &chunks, &predicate).unwrap(), keeps);
+
+        assert_eq!(report.display(PruningReportVerbosity::Off), None);
+        assert_eq!(
+            report.display(PruningReportVerbosity::Summary).unwrap(),
+            "table: 5 chunks, not pruned: 1, pruned (retention): 1, pruned (time range): 1, \
+             pruned (column filter): 1, pruned (statistics): 1",
+        );
+        assert_eq!(
+            report
+                .display(PruningReportVerbosity::Full)
+                .unwrap()
+                .lines()
+                .count(),
+            6,
+        );
+    }
+
+    #[test]
+    fn test_report_could_not_prune() {
+        test_helpers::maybe_start_logging();
+        let c1 = Arc::new(TestChunk::new("chunk1")) as Arc<dyn QueryChunk>;
+
+        let (keeps, report) = prune_chunks_with_report(
+            "table",
+            &c1.schema().clone(),
+            &[c1],
+            &Predicate::new(),
+            None,
+        );
+        assert_eq!(keeps, vec![true]);
+        assert_eq!(
+            report.display(PruningReportVerbosity::Summary).unwrap(),
+            "table: 1 chunks, could not prune (No expression on predicate): 1",
+        );
+    }
+
+    /// Exact [`RowGroupFilter`] for testing.
+    #[derive(Debug)]
+    struct ValueSetFilter(Vec<ScalarValue>);
+
+    impl ValueSetFilter {
+        fn new(values: &[&str]) -> Arc<dyn RowGroupFilter> {
+            Arc::new(Self(values.iter().map(|v| ScalarValue::from(*v)).collect()))
+        }
+    }
+
+    impl RowGroupFilter for ValueSetFilter {
+        fn may_contain(&self, value: &ScalarValue) -> bool {
+            self.0.contains(value)
+        }
+    }
 }
@@ -7,8 +7,9 @@ use crate::{
         stringset::{StringSet, StringSetRef},
         ExecutionContextProvider, Executor, ExecutorType, IOxSessionContext,
     },
-    pruning::prune_chunks,
-    Predicate, QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace, QueryText,
+    pruning::{prune_chunks, RowGroupFilter},
+    ChunkAggregate, Predicate, QueryChunk, QueryChunkData, QueryCompletedToken, QueryNamespace,
+    QueryText,
 };
 use arrow::array::{BooleanArray, Float64Array};
 use arrow::datatypes::SchemaRef;
@@ -26,7 +27,7 @@ use data_types::{
 use datafusion::error::DataFusionError;
 use datafusion::execution::context::SessionState;
 use datafusion::logical_expr::Expr;
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::physical_plan::{memory::MemoryStream, ExecutionPlan};
 use datafusion::{catalog::schema::SchemaProvider, logical_expr::LogicalPlan};
 use datafusion::{catalog::CatalogProvider, physical_plan::displayable};
 use datafusion::{
@@ -159,7 +160,7 @@ impl QueryNamespace for TestDatabase {
             .collect::<Vec<_>>())
     }
 
-    fn retention_time_ns(&self) -> Option<i64> {
+    fn retention_time_ns(&self, _table_name: &str) -> Option<i64> {
         self.retention_time_ns
     }
 
@@ -359,6 +360,15 @@ pub struct TestChunk {
 
     /// Suppress output
     quiet: bool,
+
+    /// Expose record batches as [stream](QueryChunkData::Stream) instead of buffered data.
+    stream: bool,
+
+    /// Filters returned by [`QueryChunk::column_filter`].
+    column_filters: HashMap<String, Arc<dyn RowGroupFilter>>,
+
+    /// Results returned by [`QueryChunk::pushdown_aggregate`].
+    pushdown_aggregates: HashMap<ChunkAggregate, ScalarValue>,
 }
 
 /// Implements a method for adding a column with default stats
@@ -426,6 +436,9 @@ impl TestChunk {
                 &PartitionKey::from("arbitrary"),
             )),
             quiet: false,
+            stream: false,
+            column_filters: Default::default(),
+            pushdown_aggregates: Default::default(),
         }
     }
 
@@ -434,6 +447,7 @@ impl TestChunk {
             QueryChunkData::RecordBatches(batches) => {
                 batches.push(batch);
             }
+            QueryChunkData::Stream(_) => unreachable!("never stored"),
             QueryChunkData::Parquet(_) => panic!("chunk is parquet-based"),
         }
     }
@@ -454,8 +468,10 @@ impl TestChunk {
             QueryChunkData::RecordBatches(batches) => {
                 assert!(batches.is_empty(), "chunk already has record batches");
             }
+            QueryChunkData::Stream(_) => unreachable!("never stored"),
             QueryChunkData::Parquet(_) => panic!("chunk already has a file"),
         }
+        assert!(!self.stream, "chunk is stream-based");
 
         Self {
             table_data: QueryChunkData::Parquet(ParquetExecInput {
@@ -481,6 +497,16 @@ impl TestChunk {
         self
     }
 
+    /// Expose record batches as a [stream](QueryChunkData::Stream) instead of buffered data.
+    pub fn with_stream(mut self) -> Self {
+        assert!(
+            matches!(self.table_data, QueryChunkData::RecordBatches(_)),
+            "chunk is parquet-based"
+        );
+        self.stream = true;
+        self
+    }
+
     pub fn with_id(mut self, id: u128) -> Self {
         self.id = ChunkId::new_test(id);
 
@@ -521,6 +547,26 @@ impl TestChunk {
         }
     }
 
+    /// Set the filter that is returned by [`QueryChunk::column_filter`] for the given column.
+    pub fn with_column_filter(
+        mut self,
+        column: impl Into<String>,
+        filter: Arc<dyn RowGroupFilter>,
+    ) -> Self {
+        self.column_filters.insert(column.into(), filter);
+        self
+    }
+
+    /// Set the result that is returned by [`QueryChunk::pushdown_aggregate`] for the given aggregate.
+    pub fn with_pushdown_aggregate(
+        mut self,
+        aggregate: ChunkAggregate,
+        value: ScalarValue,
+    ) -> Self {
+        self.pushdown_aggregates.insert(aggregate, value);
+        self
+    }
+
     /// Set the `may_contain_pk_duplicates` flag
     pub fn with_may_contain_pk_duplicates(mut self, v: bool) -> Self {
         self.may_contain_pk_duplicates = v;
@@ -568,6 +614,12 @@ impl TestChunk {
         )
     }
 
+    /// Set the number of rows that the chunk statistics report.
+    pub fn with_row_count(mut self, count: usize) -> Self {
+        self.update_count(count);
+        self
+    }
+
     fn update_count(&mut self, count: usize) {
         match self.num_rows {
             Some(existing) => assert_eq!(existing, count),
@@ -1133,7 +1185,18 @@ impl QueryChunk for TestChunk {
     fn data(&self) -> QueryChunkData {
         self.check_error().unwrap();
 
-        self.table_data.clone()
+        match &self.table_data {
+            QueryChunkData::RecordBatches(batches) if self.stream => {
+                QueryChunkData::Stream(Box::pin(
+                    MemoryStream::try_new(batches.clone(), self.schema.as_arrow(), None).unwrap(),
+                ))
+            }
+            QueryChunkData::RecordBatches(batches) => {
+                QueryChunkData::RecordBatches(batches.clone())
+            }
+            QueryChunkData::Stream(_) => unreachable!("never stored"),
+            QueryChunkData::Parquet(input) => QueryChunkData::Parquet(input.clone()),
+        }
     }
 
     fn chunk_type(&self) -> &str {
@@ -1144,6 +1207,14 @@ impl QueryChunk for TestChunk {
         self.order
     }
 
+    fn column_filter(&self, column: &str) -> Option<Arc<dyn RowGroupFilter>> {
+        self.column_filters.get(column).cloned()
+    }
+
+    fn pushdown_aggregate(&self, aggregate: &ChunkAggregate) -> Option<ScalarValue> {
+        self.pushdown_aggregates.get(aggregate).cloned()
+    }
+
     fn as_any(&self) -> &dyn Any {
         self
     }
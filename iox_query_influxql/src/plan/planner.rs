@@ -11,15 +11,16 @@ use crate::plan::planner_time_range_expression::time_range_to_df_expr;
 use crate::plan::rewriter::{find_table_names, rewrite_statement, ProjectionType};
 use crate::plan::udaf::MOVING_AVERAGE;
 use crate::plan::udf::{
-    cumulative_sum, derivative, difference, find_window_udfs, moving_average,
-    non_negative_derivative, non_negative_difference,
+    cumulative_sum, derivative, difference, elapsed, exponential_moving_average,
+    find_holt_winters_udfs, find_window_udfs, holt_winters, holt_winters_with_fit,
+    is_holt_winters_with_fit, moving_average, non_negative_derivative, non_negative_difference,
 };
 use crate::plan::util::{binary_operator_to_df_operator, rebase_expr, IQLSchema};
 use crate::plan::var_ref::var_ref_data_type_to_data_type;
 use crate::plan::{planner_rewrite_expression, udf, util_copy};
 use crate::window::{
-    CUMULATIVE_SUM, DERIVATIVE, DIFFERENCE, NON_NEGATIVE_DERIVATIVE, NON_NEGATIVE_DIFFERENCE,
-    PERCENT_ROW_NUMBER,
+    CUMULATIVE_SUM, DERIVATIVE, DIFFERENCE, ELAPSED, EXPONENTIAL_MOVING_AVERAGE,
+    NON_NEGATIVE_DERIVATIVE, NON_NEGATIVE_DIFFERENCE, PERCENT_ROW_NUMBER,
 };
 use arrow::array::{StringBuilder, StringDictionaryBuilder};
 use arrow::datatypes::{DataType, Field as ArrowField, Int32Type, Schema as ArrowSchema};
@@ -75,12 +76,13 @@ use influxdb_influxql_parser::{
 };
 use iox_query::config::{IoxConfigExt, MetadataCutoff};
 use iox_query::exec::gapfill::{FillStrategy, GapFill, GapFillParams};
+use iox_query::exec::holt_winters::{HoltWinters, HoltWintersParams};
 use iox_query::exec::IOxSessionContext;
 use iox_query::logical_optimizer::range_predicate::find_time_range;
 use itertools::Itertools;
 use observability_deps::tracing::debug;
 use query_functions::{
-    clean_non_meta_escapes,
+    clean_non_meta_escapes, median_expr, percentile_expr,
     selectors::{selector_first, selector_last, selector_max, selector_min},
 };
 use schema::{
@@ -855,6 +857,9 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
         let (plan, select_exprs) =
             self.select_aggregate(ctx, input, fields, select_exprs, group_by_tag_set)?;
 
+        let (plan, select_exprs) =
+            self.select_holt_winters(ctx, plan, fields, select_exprs, group_by_tag_set)?;
+
         // Wrap the plan in a `LogicalPlan::Projection` from the select expressions
         project(plan, select_exprs)
     }
@@ -1397,6 +1402,94 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
         Ok((plan, select_exprs))
     }
 
+    /// Generate a plan for the `HOLT_WINTERS` or `HOLT_WINTERS_WITH_FIT` function, which
+    /// replaces each series of the aggregated input with its forecast.
+    fn select_holt_winters(
+        &self,
+        ctx: &Context<'_>,
+        input: LogicalPlan,
+        fields: &[Field],
+        select_exprs: Vec<Expr>,
+        group_by_tag_set: &[&str],
+    ) -> Result<(LogicalPlan, Vec<Expr>)> {
+        let udf = match find_holt_winters_udfs(&select_exprs).as_slice() {
+            [] => return Ok((input, select_exprs)),
+            [udf] => udf.clone(),
+            _ => return error::not_implemented("multiple HOLT_WINTERS functions in a query"),
+        };
+
+        // The forecasted points have no values for any other field.
+        if fields
+            .iter()
+            .filter(|f| matches!(f.data_type, Some(InfluxColumnType::Field(_))))
+            .count()
+            > 1
+        {
+            return error::not_implemented("HOLT_WINTERS combined with other fields");
+        }
+
+        let Expr::ScalarUDF(expr::ScalarUDF { fun, args }) = &udf else {
+            return error::internal(format!("select_holt_winters: unexpected expression: {udf}"));
+        };
+        let include_fit = is_holt_winters_with_fit(fun)
+            .ok_or_else(|| error::map::internal("expected a HOLT_WINTERS function"))?;
+        // N and S have been validated by `select_statement_info`
+        let int_arg = |idx: usize| match args.get(idx) {
+            Some(Expr::Literal(ScalarValue::Int64(Some(v)))) if *v >= 0 => Ok(*v as usize),
+            _ => error::internal(format!("{} expects integer for argument {idx}", fun.name)),
+        };
+        let (h, m) = (int_arg(1)?, int_arg(2)?);
+        let Some(interval) = ctx.interval else {
+            // Should have been validated by `select_statement_info`
+            return error::internal(format!("{} requires a GROUP BY interval", fun.name));
+        };
+
+        // The aggregated time column is named after its `DATE_BIN` expression, so
+        // use the time expression of the projection rather than the `time` column.
+        let time_column = match find_time_column_index(fields).map(|i| &select_exprs[i]) {
+            Some(Expr::Alias(Alias { expr, .. })) => expr.as_ref().clone(),
+            _ => return error::internal("unable to find time column"),
+        };
+
+        let alias = udf
+            .display_name()
+            .map_err(|err| error::map::internal(format!("display_name: {err}")))?;
+        let series_expr =
+            fields_to_exprs_no_nulls(input.schema(), group_by_tag_set).collect::<Vec<_>>();
+
+        let plan = LogicalPlan::Extension(Extension {
+            node: Arc::new(HoltWinters::try_new(
+                Arc::new(input),
+                series_expr,
+                time_column,
+                args[0].clone(),
+                HoltWintersParams {
+                    interval: interval.duration,
+                    h,
+                    m,
+                    include_fit,
+                    alias,
+                },
+            )?),
+        });
+
+        // Rewrite the projection, so that it refers to the forecasted values.
+        let select_exprs = select_exprs
+            .iter()
+            .map(|expr| {
+                util_copy::clone_with_replacement(expr, &|e| {
+                    Ok(if e == &udf {
+                        Some(expr_as_column_expr(e, &plan)?)
+                    } else {
+                        None
+                    })
+                })
+            })
+            .collect::<Result<Vec<Expr>>>()?;
+
+        Ok((plan, select_exprs))
+    }
+
     /// Generate a plan to select the first n rows from each partition in
     /// the input data, optionally sorted by the requested field.
     fn select_first(
@@ -1553,6 +1646,42 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
                 },
             })
             .alias(alias)),
+            Some(udf::WindowFunction::Elapsed) => {
+                // The unit defaults to 1ns, irrespective of any GROUP BY TIME interval.
+                let unit = match args.get(1) {
+                    Some(Expr::Literal(v)) => v.clone(),
+                    Some(e) => {
+                        return error::internal(format!("udf_to_expr: unexpected expression: {e}"))
+                    }
+                    None => ScalarValue::new_interval_mdn(0, 0, 1),
+                };
+                Ok(Expr::WindowFunction(WindowFunction {
+                    fun: ELAPSED.clone(),
+                    args: vec![args[0].clone(), lit(unit), "time".as_expr()],
+                    partition_by,
+                    order_by,
+                    window_frame: WindowFrame {
+                        units: WindowFrameUnits::Rows,
+                        start_bound: WindowFrameBound::Preceding(ScalarValue::Null),
+                        end_bound: WindowFrameBound::Following(ScalarValue::Null),
+                    },
+                })
+                .alias(alias))
+            }
+            Some(udf::WindowFunction::ExponentialMovingAverage) => {
+                Ok(Expr::WindowFunction(WindowFunction {
+                    fun: EXPONENTIAL_MOVING_AVERAGE.clone(),
+                    args,
+                    partition_by,
+                    order_by,
+                    window_frame: WindowFrame {
+                        units: WindowFrameUnits::Rows,
+                        start_bound: WindowFrameBound::Preceding(ScalarValue::Null),
+                        end_bound: WindowFrameBound::Following(ScalarValue::Null),
+                    },
+                })
+                .alias(alias))
+            }
             None => error::internal(format!(
                 "unexpected user-defined window function: {}",
                 fun.name
@@ -1965,6 +2094,15 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
                     None,
                 )))
             }
+            "median" if self.approximate_percentile() => {
+                let expr = self.expr_to_df_expr(scope, &args[0], schema)?;
+                if let Expr::Literal(ScalarValue::Null) = expr {
+                    return Ok(expr);
+                }
+
+                check_arg_count(name, args, 1)?;
+                Ok(median_expr(expr, false))
+            }
             "sum" | "stddev" | "mean" | "median" => {
                 let expr = self.expr_to_df_expr(scope, &args[0], schema)?;
                 if let Expr::Literal(ScalarValue::Null) = expr {
@@ -1988,6 +2126,9 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
 
                 check_arg_count(name, args, 2)?;
                 let nexpr = self.expr_to_df_expr(scope, &args[1], schema)?;
+                if self.approximate_percentile() {
+                    return Ok(percentile_expr(expr, nexpr, false));
+                }
                 Ok(Expr::AggregateUDF(expr::AggregateUDF::new(
                     PERCENTILE.clone(),
                     vec![expr, nexpr],
@@ -2104,6 +2245,88 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
 
                 Ok(cumulative_sum(vec![arg0]))
             }
+            "elapsed" => {
+                check_arg_count_range(name, args, 1, 2)?;
+
+                // arg0 should be a column or function
+                let arg0 = self.expr_to_df_expr(scope, &args[0], schema)?;
+                if let Expr::Literal(ScalarValue::Null) = arg0 {
+                    return Ok(arg0);
+                }
+                let mut eargs = vec![arg0];
+                if args.len() > 1 {
+                    let arg1 = self.expr_to_df_expr(scope, &args[1], schema)?;
+                    eargs.push(arg1);
+                }
+
+                Ok(elapsed(eargs))
+            }
+            "exponential_moving_average" => {
+                check_arg_count_range(name, args, 2, 4)?;
+
+                // arg0 should be a column or function
+                let arg0 = self.expr_to_df_expr(scope, &args[0], schema)?;
+                if let Expr::Literal(ScalarValue::Null) = arg0 {
+                    return Ok(arg0);
+                }
+
+                // arg1 and the optional arg2 should be integers.
+                let int_arg = |idx: usize, default: i64| -> Result<i64> {
+                    let Some(arg) = args.get(idx) else {
+                        return Ok(default);
+                    };
+                    match self.expr_to_df_expr(scope, arg, schema)? {
+                        Expr::Literal(ScalarValue::Int64(Some(v))) => Ok(v),
+                        Expr::Literal(ScalarValue::UInt64(Some(v))) => Ok(v as i64),
+                        _ => error::query(format!("{name} expects number for argument {idx}")),
+                    }
+                };
+                let period = int_arg(1, 0)?;
+                // The default hold period of -1 is replaced with the number
+                // of values required to warm up the average when evaluated.
+                let hold_period = int_arg(2, -1)?;
+
+                // The optional arg3 should be a string.
+                let warmup = match args.get(3) {
+                    Some(arg) => match self.expr_to_df_expr(scope, arg, schema)? {
+                        Expr::Literal(ScalarValue::Utf8(Some(v))) => v,
+                        _ => return error::query(format!("{name} expects string for argument 3")),
+                    },
+                    None => "exponential".to_owned(),
+                };
+
+                Ok(exponential_moving_average(vec![
+                    arg0,
+                    lit(period),
+                    lit(hold_period),
+                    lit(warmup),
+                ]))
+            }
+            name @ ("holt_winters" | "holt_winters_with_fit") => {
+                check_arg_count(name, args, 3)?;
+
+                // arg0 should be an aggregate function
+                let arg0 = self.expr_to_df_expr(scope, &args[0], schema)?;
+                if let Expr::Literal(ScalarValue::Null) = arg0 {
+                    return Ok(arg0);
+                }
+
+                // arg1 and arg2 should be integers
+                let int_arg = |idx: usize| -> Result<i64> {
+                    match self.expr_to_df_expr(scope, &args[idx], schema)? {
+                        Expr::Literal(ScalarValue::Int64(Some(v))) => Ok(v),
+                        Expr::Literal(ScalarValue::UInt64(Some(v))) => Ok(v as i64),
+                        _ => error::query(format!("{name} expects number for argument {idx}")),
+                    }
+                };
+                let args = vec![arg0, lit(int_arg(1)?), lit(int_arg(2)?)];
+
+                Ok(if name == "holt_winters" {
+                    holt_winters(args)
+                } else {
+                    holt_winters_with_fit(args)
+                })
+            }
             // The TOP/BOTTOM function is handled as a `ProjectionType::TopBottomSelector`
             // query, so the planner only needs to project the single column
             // argument.
@@ -2655,7 +2878,10 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
                     .index_of_column_by_name(None, key)?
                     .expect("where is the key?");
 
+                // Skip series without this tag. Like the time range, this
+                // predicate is pushed down into the scan.
                 let plan = LogicalPlanBuilder::from(plan.clone())
+                    .filter(key.as_expr().is_not_null())?
                     .select([idx])?
                     .distinct()?
                     .project(measurement_expr.iter().cloned().chain([
@@ -2830,6 +3056,16 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
     }
 
     fn metadata_cutoff(&self) -> MetadataCutoff {
+        self.iox_config().influxql_metadata_cutoff
+    }
+
+    /// Returns `true` if `PERCENTILE` and `MEDIAN` should be estimated instead of computed exactly,
+    /// see [`IoxConfigExt::influxql_approximate_percentile`].
+    fn approximate_percentile(&self) -> bool {
+        self.iox_config().influxql_approximate_percentile
+    }
+
+    fn iox_config(&self) -> IoxConfigExt {
         self.iox_ctx
             .inner()
             .state()
@@ -2839,7 +3075,6 @@ impl<'a> InfluxQLToLogicalPlan<'a> {
             .get::<IoxConfigExt>()
             .cloned()
             .unwrap_or_default()
-            .influxql_metadata_cutoff
     }
 }
 
@@ -3232,6 +3467,10 @@ mod test {
     use schema::SchemaBuilder;
 
     fn logical_plan(sql: &str) -> Result<LogicalPlan> {
+        logical_plan_with_context(sql, &IOxSessionContext::with_testing())
+    }
+
+    fn logical_plan_with_context(sql: &str, iox_ctx: &IOxSessionContext) -> Result<LogicalPlan> {
         let mut statements = parse_statements(sql).unwrap();
         let mut sp = MockSchemaProvider::default();
         sp.add_schemas(vec![
@@ -3273,8 +3512,7 @@ mod test {
                 .unwrap(),
         ]);
 
-        let iox_ctx = IOxSessionContext::with_testing();
-        let planner = InfluxQLToLogicalPlan::new(&sp, &iox_ctx);
+        let planner = InfluxQLToLogicalPlan::new(&sp, iox_ctx);
 
         planner.statement_to_plan(statements.pop().unwrap())
     }
@@ -3687,8 +3925,9 @@ mod test {
               Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
                 Distinct: [bar:Dictionary(Int32, Utf8);N]
                   Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
-                    Filter: data.time >= TimestampNanosecond(1672444800000000000, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
-                      TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                    Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                      Filter: data.time >= TimestampNanosecond(1672444800000000000, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
             "###);
         }
 
@@ -3703,8 +3942,9 @@ mod test {
                       Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
                         Distinct: [bar:Dictionary(Int32, Utf8);N]
                           Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
-                            Filter: data.time >= TimestampNanosecond(1672444800000000000, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
-                              TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                            Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                              Filter: data.time >= TimestampNanosecond(1672444800000000000, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                                TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
             "###);
         }
 
@@ -3715,8 +3955,9 @@ mod test {
               Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
                 Distinct: [bar:Dictionary(Int32, Utf8);N]
                   Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
-                    Filter: data.time >= TimestampNanosecond(1672444800000000000, None) AND data.foo = Dictionary(Int32, Utf8("some_foo")) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
-                      TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                    Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                      Filter: data.time >= TimestampNanosecond(1672444800000000000, None) AND data.foo = Dictionary(Int32, Utf8("some_foo")) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
             "###);
         }
 
@@ -3727,8 +3968,48 @@ mod test {
               Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
                 Distinct: [bar:Dictionary(Int32, Utf8);N]
                   Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
-                    Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
-                      TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                    Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                      Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+            "###);
+        }
+
+        #[test]
+        fn test_show_tag_values_5() {
+            // multiple keys are planned as a union, each with the same time range
+            assert_snapshot!(plan("SHOW TAG VALUES WITH KEY IN (foo, bar, does_not_exist) WHERE time > 1337"), @r###"
+            Sort: iox::measurement ASC NULLS LAST, key ASC NULLS LAST, value ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+              Union [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                  Distinct: [bar:Dictionary(Int32, Utf8);N]
+                    Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
+                      Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                          TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("foo")) AS key, data.foo AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                  Distinct: [foo:Dictionary(Int32, Utf8);N]
+                    Projection: data.foo [foo:Dictionary(Int32, Utf8);N]
+                      Filter: data.foo IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                          TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+            "###);
+
+            // regex matching the keys
+            assert_snapshot!(plan("SHOW TAG VALUES WITH KEY =~ /^(foo|bar)$/ WHERE time > 1337"), @r###"
+            Sort: iox::measurement ASC NULLS LAST, key ASC NULLS LAST, value ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+              Union [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("bar")) AS key, data.bar AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                  Distinct: [bar:Dictionary(Int32, Utf8);N]
+                    Projection: data.bar [bar:Dictionary(Int32, Utf8);N]
+                      Filter: data.bar IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                          TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                Projection: Dictionary(Int32, Utf8("data")) AS iox::measurement, Dictionary(Int32, Utf8("foo")) AS key, data.foo AS value [iox::measurement:Dictionary(Int32, Utf8), key:Dictionary(Int32, Utf8), value:Dictionary(Int32, Utf8);N]
+                  Distinct: [foo:Dictionary(Int32, Utf8);N]
+                    Projection: data.foo [foo:Dictionary(Int32, Utf8);N]
+                      Filter: data.foo IS NOT NULL [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                        Filter: data.time >= TimestampNanosecond(1338, None) [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
+                          TableScan: data [TIME:Boolean;N, bar:Dictionary(Int32, Utf8);N, bool_field:Boolean;N, f64_field:Float64;N, foo:Dictionary(Int32, Utf8);N, i64_field:Int64;N, mixedCase:Float64;N, str_field:Utf8;N, time:Timestamp(Nanosecond, None), with space:Float64;N]
             "###);
         }
     }
@@ -3787,6 +4068,35 @@ mod test {
                 "###);
             }
 
+            /// Re-aggregating the windows of a subquery using a coarser `GROUP BY TIME` interval.
+            #[test]
+            fn group_by_time_reaggregate() {
+                assert_snapshot!(plan("SELECT mean(value) FROM (SELECT mean(usage_idle) AS value FROM cpu GROUP BY TIME(10s)) GROUP BY TIME(30s) FILL(none)"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, mean:Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, AVG(value) AS mean [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, mean:Float64;N]
+                    Aggregate: groupBy=[[date_bin(IntervalMonthDayNano("30000000000"), time, TimestampNanosecond(0, None)) AS time]], aggr=[[AVG(value)]] [time:Timestamp(Nanosecond, None);N, AVG(value):Float64;N]
+                      Filter: time <= TimestampNanosecond(1672531200000000000, None) [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                        Sort: time ASC NULLS LAST [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                          Projection: time, AVG(cpu.usage_idle) AS value [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                            Aggregate: groupBy=[[date_bin(IntervalMonthDayNano("10000000000"), cpu.time, TimestampNanosecond(0, None)) AS time]], aggr=[[AVG(cpu.usage_idle)]] [time:Timestamp(Nanosecond, None);N, AVG(cpu.usage_idle):Float64;N]
+                              Filter: cpu.time <= TimestampNanosecond(1672531200000000000, None) [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                                TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+
+                // the time range of the outer query restricts the subquery
+                assert_snapshot!(plan("SELECT mean(value) FROM (SELECT mean(usage_idle) AS value FROM cpu GROUP BY TIME(10s)) WHERE time >= '2022-10-31T02:00:00Z' AND time < '2022-10-31T02:01:00Z' GROUP BY TIME(30s) FILL(none)"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, mean:Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, AVG(value) AS mean [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, mean:Float64;N]
+                    Aggregate: groupBy=[[date_bin(IntervalMonthDayNano("30000000000"), time, TimestampNanosecond(0, None)) AS time]], aggr=[[AVG(value)]] [time:Timestamp(Nanosecond, None);N, AVG(value):Float64;N]
+                      Filter: time >= TimestampNanosecond(1667181600000000000, None) AND time <= TimestampNanosecond(1667181659999999999, None) [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                        Sort: time ASC NULLS LAST [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                          Projection: time, AVG(cpu.usage_idle) AS value [time:Timestamp(Nanosecond, None);N, value:Float64;N]
+                            Aggregate: groupBy=[[date_bin(IntervalMonthDayNano("10000000000"), cpu.time, TimestampNanosecond(0, None)) AS time]], aggr=[[AVG(cpu.usage_idle)]] [time:Timestamp(Nanosecond, None);N, AVG(cpu.usage_idle):Float64;N]
+                              Filter: cpu.time >= TimestampNanosecond(1667181600000000000, None) AND cpu.time <= TimestampNanosecond(1667181659999999999, None) [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                                TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+            }
+
             /// Projecting subqueries that use the `DISTINCT` function / operator.
             #[test]
             fn distinct() {
@@ -3916,6 +4226,16 @@ mod test {
                           TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
                 "###);
 
+                // no aggregates, partitioned by series
+                assert_snapshot!(plan("SELECT DIFFERENCE(usage_idle) FROM cpu GROUP BY cpu"), @r###"
+                Sort: cpu ASC NULLS LAST, time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), cpu:Dictionary(Int32, Utf8);N, difference:Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, cpu, difference [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), cpu:Dictionary(Int32, Utf8);N, difference:Float64;N]
+                    Filter: NOT difference IS NULL [time:Timestamp(Nanosecond, None), cpu:Dictionary(Int32, Utf8);N, difference:Float64;N]
+                      Projection: cpu.time AS time, cpu.cpu AS cpu, difference(cpu.usage_idle) AS difference [time:Timestamp(Nanosecond, None), cpu:Dictionary(Int32, Utf8);N, difference:Float64;N]
+                        WindowAggr: windowExpr=[[difference(cpu.usage_idle) PARTITION BY [cpu.cpu] ORDER BY [cpu.time ASC NULLS LAST] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS difference(cpu.usage_idle)]] [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N, difference(cpu.usage_idle):Float64;N]
+                          TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+
                 // aggregate
                 assert_snapshot!(plan("SELECT DIFFERENCE(MEAN(usage_idle)) FROM cpu GROUP BY TIME(10s)"), @r###"
                 Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None);N, difference:Float64;N]
@@ -4076,6 +4396,69 @@ mod test {
                 "###);
             }
 
+            #[test]
+            fn test_elapsed() {
+                // no aggregates
+                assert_snapshot!(plan("SELECT ELAPSED(usage_idle) FROM cpu"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, elapsed [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                    Filter: NOT elapsed IS NULL [time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                      Projection: cpu.time AS time, elapsed(cpu.usage_idle) AS elapsed [time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                        WindowAggr: windowExpr=[[elapsed(cpu.usage_idle, IntervalMonthDayNano("1"), cpu.time) ORDER BY [cpu.time ASC NULLS LAST] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS elapsed(cpu.usage_idle)]] [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N, elapsed(cpu.usage_idle):Int64;N]
+                          TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+
+                // explicit unit
+                assert_snapshot!(plan("SELECT ELAPSED(usage_idle, 1s) FROM cpu"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, elapsed [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                    Filter: NOT elapsed IS NULL [time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                      Projection: cpu.time AS time, elapsed(cpu.usage_idle,IntervalMonthDayNano("1000000000")) AS elapsed [time:Timestamp(Nanosecond, None), elapsed:Int64;N]
+                        WindowAggr: windowExpr=[[elapsed(cpu.usage_idle, IntervalMonthDayNano("1000000000"), cpu.time) ORDER BY [cpu.time ASC NULLS LAST] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS elapsed(cpu.usage_idle,IntervalMonthDayNano("1000000000"))]] [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N, elapsed(cpu.usage_idle,IntervalMonthDayNano("1000000000")):Int64;N]
+                          TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+            }
+
+            #[test]
+            fn test_exponential_moving_average() {
+                // default hold period and warmup type
+                assert_snapshot!(plan("SELECT EXPONENTIAL_MOVING_AVERAGE(usage_idle, 3) FROM cpu"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, exponential_moving_average [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                    Filter: NOT exponential_moving_average IS NULL [time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                      Projection: cpu.time AS time, exponential_moving_average(cpu.usage_idle,Int64(3),Int64(-1),Utf8("exponential")) AS exponential_moving_average [time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                        WindowAggr: windowExpr=[[exponential_moving_average(cpu.usage_idle, Int64(3), Int64(-1), Utf8("exponential")) ORDER BY [cpu.time ASC NULLS LAST] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS exponential_moving_average(cpu.usage_idle,Int64(3),Int64(-1),Utf8("exponential"))]] [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N, exponential_moving_average(cpu.usage_idle,Int64(3),Int64(-1),Utf8("exponential")):Float64;N]
+                          TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+
+                // explicit hold period and warmup type
+                assert_snapshot!(plan("SELECT EXPONENTIAL_MOVING_AVERAGE(usage_idle, 3, 0, 'simple') FROM cpu"), @r###"
+                Sort: time ASC NULLS LAST [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                  Projection: Dictionary(Int32, Utf8("cpu")) AS iox::measurement, time, exponential_moving_average [iox::measurement:Dictionary(Int32, Utf8), time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                    Filter: NOT exponential_moving_average IS NULL [time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                      Projection: cpu.time AS time, exponential_moving_average(cpu.usage_idle,Int64(3),Int64(0),Utf8("simple")) AS exponential_moving_average [time:Timestamp(Nanosecond, None), exponential_moving_average:Float64;N]
+                        WindowAggr: windowExpr=[[exponential_moving_average(cpu.usage_idle, Int64(3), Int64(0), Utf8("simple")) ORDER BY [cpu.time ASC NULLS LAST] ROWS BETWEEN UNBOUNDED PRECEDING AND UNBOUNDED FOLLOWING AS exponential_moving_average(cpu.usage_idle,Int64(3),Int64(0),Utf8("simple"))]] [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N, exponential_moving_average(cpu.usage_idle,Int64(3),Int64(0),Utf8("simple")):Float64;N]
+                          TableScan: cpu [cpu:Dictionary(Int32, Utf8);N, host:Dictionary(Int32, Utf8);N, region:Dictionary(Int32, Utf8);N, time:Timestamp(Nanosecond, None), usage_idle:Float64;N, usage_system:Float64;N, usage_user:Float64;N]
+                "###);
+            }
+
+            #[test]
+            fn test_holt_winters() {
+                let got =
+                    plan("SELECT HOLT_WINTERS(MEAN(usage_idle), 3, 0) FROM cpu GROUP BY TIME(10s)");
+                assert!(got.contains("HoltWinters: series=[], "), "{got}");
+                assert!(
+                    got.contains("interval=10000000000, h=3, m=0, include_fit=false"),
+                    "{got}"
+                );
+
+                let got = plan("SELECT HOLT_WINTERS_WITH_FIT(MEAN(usage_idle), 3, 2) FROM cpu GROUP BY TIME(10s), cpu");
+                assert!(got.contains("HoltWinters: series=[cpu], "), "{got}");
+                assert!(got.contains("h=3, m=2, include_fit=true"), "{got}");
+
+                assert_snapshot!(plan("SELECT HOLT_WINTERS(MEAN(usage_idle), 3, 0), MEAN(usage_system) FROM cpu GROUP BY TIME(10s)"), @"This feature is not implemented: HOLT_WINTERS combined with other fields");
+            }
+
             #[test]
             fn test_not_implemented() {
                 assert_snapshot!(plan("SELECT DIFFERENCE(MEAN(usage_idle)), MEAN(usage_idle) FROM cpu GROUP BY TIME(10s)"), @"This feature is not implemented: mixed window-aggregate and aggregate columns, such as DIFFERENCE(MEAN(col)), MEAN(col)");
@@ -4313,6 +4696,39 @@ mod test {
             "###);
         }
 
+        #[test]
+        fn test_approximate_percentile() {
+            use iox_query::exec::{Executor, ExecutorType};
+
+            let exec = Executor::new_testing();
+            let iox_ctx = exec
+                .new_execution_config(ExecutorType::Query)
+                .with_config_option("iox.influxql_approximate_percentile", "true")
+                .build();
+            let plan = |sql| {
+                logical_plan_with_context(sql, &iox_ctx)
+                    .unwrap()
+                    .display_indent_schema()
+                    .to_string()
+            };
+
+            // PERCENTILE and MEDIAN are estimated by the t-digest based aggregate
+            let got = plan("SELECT percentile(usage_idle, 90) FROM cpu");
+            assert!(
+                got.contains("percentile(cpu.usage_idle, Int64(90), Boolean(false))"),
+                "{got}"
+            );
+            let got = plan("SELECT median(usage_idle) FROM cpu");
+            assert!(
+                got.contains("percentile(cpu.usage_idle, Int64(50), Boolean(false))"),
+                "{got}"
+            );
+
+            // the selector form of PERCENTILE is unaffected
+            let got = plan("SELECT percentile(usage_idle, 90), usage_system FROM cpu");
+            assert!(got.contains("percent_row_number(Float64(90))"), "{got}");
+        }
+
         #[test]
         fn test_top() {
             assert_snapshot!(plan("SELECT top(usage_idle,10) FROM cpu"), @r###"
@@ -1272,7 +1272,7 @@ impl FieldChecker {
         for expr in rest {
             if !matches!(expr, Expr::VarRef(_)) {
                 return error::query(format!(
-                    "only fields or tags are allow for {name}(), got {expr:?}"
+                    "only fields or tags are allowed for {name}(), got {expr:?}"
                 ));
             }
         }
@@ -1814,18 +1814,24 @@ mod test {
         select_statement_info(&sel).unwrap();
         let sel = parse_select("SELECT top(foo, bar, 3) FROM cpu");
         select_statement_info(&sel).unwrap();
+        let sel = parse_select("SELECT bottom(foo, bar, baz, 3) FROM cpu");
+        select_statement_info(&sel).unwrap();
         let sel = parse_select("SELECT top(foo) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "invalid number of arguments for top, expected at least 2, got 1");
         let sel = parse_select("SELECT bottom(foo) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "invalid number of arguments for bottom, expected at least 2, got 1");
         let sel = parse_select("SELECT top(foo, -2) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "limit (-2) for top must be greater than 0");
+        let sel = parse_select("SELECT bottom(foo, 0) FROM cpu");
+        assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "limit (0) for bottom must be greater than 0");
         let sel = parse_select("SELECT top(foo, bar) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "expected integer as last argument for top, got VarRef(VarRef { name: Identifier(\"bar\"), data_type: None })");
         let sel = parse_select("SELECT top('foo', 3) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "expected first argument to be a field for top");
         let sel = parse_select("SELECT top(foo, 2, 3) FROM cpu");
-        assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "only fields or tags are allow for top(), got Literal(Integer(2))");
+        assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "only fields or tags are allowed for top(), got Literal(Integer(2))");
+        let sel = parse_select("SELECT bottom(foo, bar, 'baz', 3) FROM cpu");
+        assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "only fields or tags are allowed for bottom(), got Literal(String(\"baz\"))");
         let sel = parse_select("SELECT top(foo, 2), mean(bar) FROM cpu");
         assert_error!(select_statement_info(&sel), DataFusionError::Plan(ref s) if s == "selector functions top and bottom cannot be combined with other functions");
 
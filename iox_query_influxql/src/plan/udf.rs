@@ -22,6 +22,8 @@ pub(super) enum WindowFunction {
     Derivative,
     NonNegativeDerivative,
     CumulativeSum,
+    Elapsed,
+    ExponentialMovingAverage,
 }
 
 impl WindowFunction {
@@ -34,6 +36,8 @@ impl WindowFunction {
             DERIVATIVE_UDF_NAME => Some(Self::Derivative),
             NON_NEGATIVE_DERIVATIVE_UDF_NAME => Some(Self::NonNegativeDerivative),
             CUMULATIVE_SUM_UDF_NAME => Some(Self::CumulativeSum),
+            ELAPSED_UDF_NAME => Some(Self::Elapsed),
+            EXPONENTIAL_MOVING_AVERAGE_UDF_NAME => Some(Self::ExponentialMovingAverage),
             _ => None,
         }
     }
@@ -48,6 +52,25 @@ pub(super) fn find_window_udfs(exprs: &[Expr]) -> Vec<Expr> {
     )
 }
 
+/// Find all [`Expr::ScalarUDF`] expressions that represent the `HOLT_WINTERS`
+/// or `HOLT_WINTERS_WITH_FIT` function.
+pub(super) fn find_holt_winters_udfs(exprs: &[Expr]) -> Vec<Expr> {
+    find_exprs_in_exprs(
+        exprs,
+        &|nested_expr| matches!(nested_expr, Expr::ScalarUDF(s) if is_holt_winters_with_fit(&s.fun).is_some()),
+    )
+}
+
+/// Returns `Some(true)` if `fun` is the `HOLT_WINTERS_WITH_FIT` function,
+/// `Some(false)` if it is the `HOLT_WINTERS` function and `None` otherwise.
+pub(super) fn is_holt_winters_with_fit(fun: &ScalarUDF) -> Option<bool> {
+    match fun.name.as_str() {
+        HOLT_WINTERS_UDF_NAME => Some(false),
+        HOLT_WINTERS_WITH_FIT_UDF_NAME => Some(true),
+        _ => None,
+    }
+}
+
 const MOVING_AVERAGE_UDF_NAME: &str = "moving_average";
 
 /// Create an expression to represent the `MOVING_AVERAGE` function.
@@ -209,6 +232,110 @@ static CUMULATIVE_SUM: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
     ))
 });
 
+const ELAPSED_UDF_NAME: &str = "elapsed";
+
+/// Create an expression to represent the `ELAPSED` function.
+pub(crate) fn elapsed(args: Vec<Expr>) -> Expr {
+    ELAPSED.call(args)
+}
+
+/// Definition of the `ELAPSED` function.
+static ELAPSED: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+    Arc::new(ScalarUDF::new(
+        ELAPSED_UDF_NAME,
+        &Signature::one_of(
+            NUMERICS
+                .iter()
+                .chain(&[DataType::Utf8, DataType::Boolean])
+                .flat_map(|dt| {
+                    vec![
+                        TypeSignature::Exact(vec![dt.clone()]),
+                        TypeSignature::Exact(vec![
+                            dt.clone(),
+                            DataType::Duration(TimeUnit::Nanosecond),
+                        ]),
+                    ]
+                })
+                .collect(),
+            Volatility::Immutable,
+        ),
+        &return_type_fn,
+        &stand_in_impl(ELAPSED_UDF_NAME),
+    ))
+});
+
+const EXPONENTIAL_MOVING_AVERAGE_UDF_NAME: &str = "exponential_moving_average";
+
+/// Create an expression to represent the `EXPONENTIAL_MOVING_AVERAGE` function.
+pub(crate) fn exponential_moving_average(args: Vec<Expr>) -> Expr {
+    EXPONENTIAL_MOVING_AVERAGE.call(args)
+}
+
+/// Definition of the `EXPONENTIAL_MOVING_AVERAGE` function.
+static EXPONENTIAL_MOVING_AVERAGE: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    Arc::new(ScalarUDF::new(
+        EXPONENTIAL_MOVING_AVERAGE_UDF_NAME,
+        &Signature::one_of(
+            NUMERICS
+                .iter()
+                .map(|dt| {
+                    TypeSignature::Exact(vec![
+                        dt.clone(),
+                        DataType::Int64,
+                        DataType::Int64,
+                        DataType::Utf8,
+                    ])
+                })
+                .collect(),
+            Volatility::Immutable,
+        ),
+        &return_type_fn,
+        &stand_in_impl(EXPONENTIAL_MOVING_AVERAGE_UDF_NAME),
+    ))
+});
+
+const HOLT_WINTERS_UDF_NAME: &str = "holt_winters";
+
+/// Create an expression to represent the `HOLT_WINTERS` function.
+pub(crate) fn holt_winters(args: Vec<Expr>) -> Expr {
+    HOLT_WINTERS.call(args)
+}
+
+/// Definition of the `HOLT_WINTERS` function.
+static HOLT_WINTERS: Lazy<Arc<ScalarUDF>> =
+    Lazy::new(|| holt_winters_stand_in(HOLT_WINTERS_UDF_NAME));
+
+const HOLT_WINTERS_WITH_FIT_UDF_NAME: &str = "holt_winters_with_fit";
+
+/// Create an expression to represent the `HOLT_WINTERS_WITH_FIT` function.
+pub(crate) fn holt_winters_with_fit(args: Vec<Expr>) -> Expr {
+    HOLT_WINTERS_WITH_FIT.call(args)
+}
+
+/// Definition of the `HOLT_WINTERS_WITH_FIT` function.
+static HOLT_WINTERS_WITH_FIT: Lazy<Arc<ScalarUDF>> =
+    Lazy::new(|| holt_winters_stand_in(HOLT_WINTERS_WITH_FIT_UDF_NAME));
+
+/// Returns the definition of a `HOLT_WINTERS` function, with the arguments
+/// `(value, N, S)`.
+fn holt_winters_stand_in(name: &'static str) -> Arc<ScalarUDF> {
+    let return_type_fn: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(DataType::Float64)));
+    Arc::new(ScalarUDF::new(
+        name,
+        &Signature::one_of(
+            NUMERICS
+                .iter()
+                .map(|dt| TypeSignature::Exact(vec![dt.clone(), DataType::Int64, DataType::Int64]))
+                .collect(),
+            Volatility::Immutable,
+        ),
+        &return_type_fn,
+        &stand_in_impl(name),
+    ))
+}
+
 /// Returns an implementation that always returns an error.
 fn stand_in_impl(name: &'static str) -> ScalarFunctionImplementation {
     Arc::new(move |_| error::internal(format!("{name} should not exist in the final logical plan")))
@@ -9,6 +9,8 @@ use std::sync::Arc;
 mod cumulative_sum;
 mod derivative;
 mod difference;
+mod elapsed;
+mod exponential_moving_average;
 mod non_negative;
 mod percent_row_number;
 
@@ -54,6 +56,34 @@ pub(crate) static DIFFERENCE: Lazy<WindowFunction> = Lazy::new(|| {
     )))
 });
 
+/// Definition of the `ELAPSED` user-defined window function.
+pub(crate) static ELAPSED: Lazy<WindowFunction> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(elapsed::return_type);
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(elapsed::partition_evaluator_factory);
+
+    WindowFunction::WindowUDF(Arc::new(WindowUDF::new(
+        elapsed::NAME,
+        &elapsed::SIGNATURE,
+        &return_type,
+        &partition_evaluator_factory,
+    )))
+});
+
+/// Definition of the `EXPONENTIAL_MOVING_AVERAGE` user-defined window function.
+pub(crate) static EXPONENTIAL_MOVING_AVERAGE: Lazy<WindowFunction> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(exponential_moving_average::return_type);
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(exponential_moving_average::partition_evaluator_factory);
+
+    WindowFunction::WindowUDF(Arc::new(WindowUDF::new(
+        exponential_moving_average::NAME,
+        &exponential_moving_average::SIGNATURE,
+        &return_type,
+        &partition_evaluator_factory,
+    )))
+});
+
 const NON_NEGATIVE_DERIVATIVE_NAME: &str = "non_negative_derivative";
 
 /// Definition of the `NON_NEGATIVE_DERIVATIVE` user-defined window function.
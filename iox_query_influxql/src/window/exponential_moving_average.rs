@@ -0,0 +1,159 @@
+use crate::{error, NUMERICS};
+use arrow::array::{Array, ArrayRef, AsArray, Float64Builder};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type};
+use datafusion::common::{Result, ScalarValue};
+use datafusion::logical_expr::{PartitionEvaluator, Signature, TypeSignature, Volatility};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// The name of the exponential_moving_average window function.
+pub(super) const NAME: &str = "exponential_moving_average";
+
+/// Valid signatures for the exponential_moving_average window function.
+pub(super) static SIGNATURE: Lazy<Signature> = Lazy::new(|| {
+    Signature::one_of(
+        NUMERICS
+            .iter()
+            .map(|dt| {
+                TypeSignature::Exact(vec![
+                    dt.clone(),
+                    DataType::Int64,
+                    DataType::Int64,
+                    DataType::Utf8,
+                ])
+            })
+            .collect(),
+        Volatility::Immutable,
+    )
+});
+
+/// Calculate the return type given the function signature.
+pub(super) fn return_type(_: &[DataType]) -> Result<Arc<DataType>> {
+    Ok(Arc::new(DataType::Float64))
+}
+
+/// Create a new partition_evaluator_factory.
+pub(super) fn partition_evaluator_factory() -> Result<Box<dyn PartitionEvaluator>> {
+    Ok(Box::new(ExponentialMovingAveragePartitionEvaluator {}))
+}
+
+/// The method used to calculate the average of the first `period`
+/// values, before there are enough values to calculate a full
+/// exponential moving average.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Warmup {
+    /// An exponential moving average with a period equal to the
+    /// number of values seen so far.
+    Exponential,
+    /// The simple mean of the values seen so far.
+    Simple,
+}
+
+/// The state of an exponential moving average, which matches the
+/// implementation used by InfluxDB 1.x ([source][1]).
+///
+/// [1]: https://github.com/influxdata/influxdb/blob/1.8/query/internal/gota/ema.go
+#[derive(Debug)]
+struct Ema {
+    period: usize,
+    alpha: f64,
+    warmup: Warmup,
+    count: usize,
+    last: f64,
+}
+
+impl Ema {
+    fn new(period: usize, warmup: Warmup) -> Self {
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            warmup,
+            count: 0,
+            last: 0.0,
+        }
+    }
+
+    fn add(&mut self, v: f64) -> f64 {
+        let avg = if self.count == 0 {
+            v
+        } else if self.count < self.period {
+            match self.warmup {
+                Warmup::Simple => (self.last * self.count as f64 + v) / (self.count as f64 + 1.0),
+                Warmup::Exponential => {
+                    let alpha = 2.0 / (self.count as f64 + 2.0);
+                    (v - self.last) * alpha + self.last
+                }
+            }
+        } else {
+            (v - self.last) * self.alpha + self.last
+        };
+
+        self.last = avg;
+        if self.count < self.period {
+            self.count += 1;
+        }
+        avg
+    }
+}
+
+/// PartitionEvaluator which returns the exponential moving average of
+/// the input values.
+#[derive(Debug)]
+struct ExponentialMovingAveragePartitionEvaluator {}
+
+impl PartitionEvaluator for ExponentialMovingAveragePartitionEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], _num_rows: usize) -> Result<Arc<dyn Array>> {
+        assert_eq!(values.len(), 4);
+
+        // INVARIANT:
+        // The planner guarantees that the period, hold period and warmup
+        // type arguments are always literals, which have been validated.
+        let period = match ScalarValue::try_from_array(&values[1], 0)? {
+            ScalarValue::Int64(Some(v)) if v >= 1 => v as usize,
+            v => return error::internal(format!("{NAME} attempted with invalid period: {v}")),
+        };
+        // A hold period of -1 selects the default, which is the number
+        // of values required to warm up the average.
+        let hold_period = match ScalarValue::try_from_array(&values[2], 0)? {
+            ScalarValue::Int64(Some(-1)) => period - 1,
+            ScalarValue::Int64(Some(v)) if v >= 0 => v as usize,
+            v => return error::internal(format!("{NAME} attempted with invalid hold period: {v}")),
+        };
+        let warmup = match ScalarValue::try_from_array(&values[3], 0)? {
+            ScalarValue::Utf8(Some(v)) if v == "exponential" => Warmup::Exponential,
+            ScalarValue::Utf8(Some(v)) if v == "simple" => Warmup::Simple,
+            v => return error::internal(format!("{NAME} attempted with invalid warmup type: {v}")),
+        };
+
+        let array = cast(&values[0], &DataType::Float64)?;
+        let array = array.as_primitive::<Float64Type>();
+
+        let mut ema = Ema::new(period, warmup);
+        let mut count = 0;
+        let mut builder = Float64Builder::with_capacity(array.len());
+        for v in array.iter() {
+            match v {
+                Some(v) => {
+                    let avg = ema.add(v);
+                    count += 1;
+                    if count > hold_period {
+                        builder.append_value(avg);
+                    } else {
+                        builder.append_null();
+                    }
+                }
+                None => builder.append_null(),
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+
+    fn uses_window_frame(&self) -> bool {
+        false
+    }
+
+    fn include_rank(&self) -> bool {
+        false
+    }
+}
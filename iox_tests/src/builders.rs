@@ -35,6 +35,7 @@ impl ParquetFileBuilder {
                 created_at: Timestamp::new(0),
                 column_set: ColumnSet::new(vec![]),
                 max_l0_created_at: Timestamp::new(0),
+                tag_ranges: None,
             },
         }
     }
@@ -100,6 +101,16 @@ impl ParquetFileBuilder {
         }
     }
 
+    /// Set created_at
+    pub fn with_created_at(self, created_at: i64) -> Self {
+        Self {
+            file: ParquetFile {
+                created_at: Timestamp::new(created_at),
+                ..self.file
+            },
+        }
+    }
+
     /// Create the [`ParquetFile`]
     pub fn build(self) -> ParquetFile {
         self.file
@@ -127,6 +138,7 @@ impl TableBuilder {
                 namespace_id: NamespaceId::new(0),
                 name: "table".to_string(),
                 partition_template: Default::default(),
+                retention_period_ns: None,
             },
         }
     }
@@ -371,6 +371,7 @@ impl TestTable {
         TableSchema {
             id: self.table.id,
             partition_template: Default::default(),
+            retention_period_ns: self.table.retention_period_ns,
             columns: self.catalog_columns().await,
         }
     }
@@ -613,6 +614,7 @@ impl TestPartition {
             compaction_level,
             column_set,
             max_l0_created_at: Timestamp::new(max_l0_created_at),
+            tag_ranges: None,
         };
 
         let mut repos = self.catalog.catalog.repositories().await;
@@ -0,0 +1,211 @@
+//! Health of the subsystems a server depends on, reported via the standard gRPC health service.
+//!
+//! Every server registers the gRPC health service (see [`setup_builder!`](crate::setup_builder)), which reports the
+//! status of each gRPC service it serves. On top of that, a server can provide [`HealthCheck`]s for the subsystems it
+//! depends on (see [`ServerType::health_checks`](crate::server_type::ServerType::health_checks)). These are run
+//! periodically and their result is reported under the "service" name [`HealthCheck::name`], so orchestration probes
+//! can check e.g. whether the catalog is reachable:
+//!
+//! ```text
+//! grpc_health_probe -addr=localhost:8082 -service=iox.catalog
+//! ```
+use std::{fmt::Debug, path::PathBuf, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use data_types::NamespaceId;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use object_store::{path::Path, DynObjectStore};
+use observability_deps::tracing::{info, warn};
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
+use tonic_health::{server::HealthReporter, ServingStatus};
+
+/// How often the health checks are run.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A single health check is deemed failed if it takes longer than this.
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Checks the health of a subsystem.
+#[async_trait]
+pub trait HealthCheck: Debug + Send + Sync + 'static {
+    /// Name the status of the subsystem is reported under.
+    fn name(&self) -> &'static str;
+
+    /// Check if the subsystem is healthy, returning a description of the problem if not.
+    async fn check(&self) -> Result<(), String>;
+}
+
+/// Checks that the catalog is reachable.
+#[derive(Debug)]
+pub struct CatalogHealthCheck {
+    catalog: Arc<dyn Catalog>,
+}
+
+impl CatalogHealthCheck {
+    pub fn new(catalog: Arc<dyn Catalog>) -> Self {
+        Self { catalog }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for CatalogHealthCheck {
+    fn name(&self) -> &'static str {
+        "iox.catalog"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        // any cheap query will do, it doesn't matter if the namespace exists
+        self.catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_id(NamespaceId::new(0), SoftDeletedRows::AllRows)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Checks that the object store is reachable.
+#[derive(Debug)]
+pub struct ObjectStoreHealthCheck {
+    object_store: Arc<DynObjectStore>,
+}
+
+impl ObjectStoreHealthCheck {
+    pub fn new(object_store: Arc<DynObjectStore>) -> Self {
+        Self { object_store }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for ObjectStoreHealthCheck {
+    fn name(&self) -> &'static str {
+        "iox.object_store"
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        // the probe object doesn't exist, but getting an answer means the store is reachable
+        match self
+            .object_store
+            .head(&Path::from("iox_health_check"))
+            .await
+        {
+            Ok(_) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+}
+
+/// Checks that a directory, e.g. the one of the write-ahead log, is writable.
+#[derive(Debug)]
+pub struct DirectoryWritableHealthCheck {
+    name: &'static str,
+    directory: PathBuf,
+}
+
+impl DirectoryWritableHealthCheck {
+    pub fn new(name: &'static str, directory: impl Into<PathBuf>) -> Self {
+        Self {
+            name,
+            directory: directory.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheck for DirectoryWritableHealthCheck {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn check(&self) -> Result<(), String> {
+        let path = self.directory.join(".iox_health_check");
+        tokio::fs::write(&path, b"")
+            .await
+            .map_err(|e| format!("cannot write to {}: {e}", self.directory.display()))?;
+        tokio::fs::remove_file(&path)
+            .await
+            .map_err(|e| format!("cannot remove {}: {e}", path.display()))
+    }
+}
+
+/// Periodically run the given checks and report their status via `health_reporter`, until `shutdown` is cancelled.
+async fn run_health_checks(
+    checks: Vec<Arc<dyn HealthCheck>>,
+    mut health_reporter: HealthReporter,
+    shutdown: CancellationToken,
+) {
+    let mut ticker = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => return,
+            _ = ticker.tick() => {}
+        }
+
+        let results = futures::future::join_all(checks.iter().map(|check| async move {
+            let res = tokio::time::timeout(HEALTH_CHECK_TIMEOUT, check.check())
+                .await
+                .unwrap_or_else(|_| Err(format!("timed out after {HEALTH_CHECK_TIMEOUT:?}")));
+            (check.name(), res)
+        }))
+        .await;
+
+        for (name, res) in results {
+            let status = match res {
+                Ok(()) => ServingStatus::Serving,
+                Err(e) => {
+                    warn!(subsystem = name, %e, "health check failed");
+                    ServingStatus::NotServing
+                }
+            };
+            health_reporter.set_service_status(name, status).await;
+        }
+    }
+}
+
+/// Spawn a task running [`run_health_checks`].
+pub fn spawn_health_checks(
+    checks: Vec<Arc<dyn HealthCheck>>,
+    health_reporter: HealthReporter,
+    shutdown: CancellationToken,
+) {
+    if checks.is_empty() {
+        return;
+    }
+
+    info!(
+        subsystems = ?checks.iter().map(|c| c.name()).collect::<Vec<_>>(),
+        "starting health checks"
+    );
+    tokio::spawn(run_health_checks(checks, health_reporter, shutdown));
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_catalog::mem::MemCatalog;
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_checks() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog = CatalogHealthCheck::new(Arc::new(MemCatalog::new(metrics)));
+        catalog.check().await.unwrap();
+
+        let store = ObjectStoreHealthCheck::new(Arc::new(InMemory::new()));
+        store.check().await.unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let wal = DirectoryWritableHealthCheck::new("iox.wal", dir.path());
+        wal.check().await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+
+        let wal = DirectoryWritableHealthCheck::new("iox.wal", dir.path().join("missing"));
+        wal.check().await.unwrap_err();
+    }
+}
@@ -1,4 +1,6 @@
-use hyper::{Body, Response, StatusCode};
+use std::time::Duration;
+
+use hyper::{header::RETRY_AFTER, Body, Response, StatusCode};
 use observability_deps::tracing::warn;
 
 /// Constants used in API error codes.
@@ -101,6 +103,13 @@ pub struct HttpApiError {
 
     /// Human-readable message.
     msg: String,
+
+    /// The time after which the client should retry the request, if any.
+    retry_after: Option<Duration>,
+
+    /// The individual lines of the request that were rejected, as pairs of
+    /// line number and reason.
+    line_errors: Vec<(usize, String)>,
 }
 
 impl HttpApiError {
@@ -109,27 +118,58 @@ impl HttpApiError {
         Self {
             code: code.into(),
             msg: msg.into(),
+            retry_after: None,
+            line_errors: vec![],
         }
     }
 
+    /// Advise the client to retry the request after the given time, using the
+    /// `Retry-After` header (rounded up to whole seconds).
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = Some(retry_after);
+        self
+    }
+
+    /// Report the individual lines of the request that were rejected, as pairs
+    /// of line number and reason, in the `line_errors` array of the response
+    /// body.
+    pub fn with_line_errors(
+        mut self,
+        line_errors: impl IntoIterator<Item = (usize, String)>,
+    ) -> Self {
+        self.line_errors = line_errors.into_iter().collect();
+        self
+    }
+
     /// Generate response body for this error.
     fn body(&self) -> Body {
-        let json = serde_json::json!({
+        let mut json = serde_json::json!({
             "code": self.code.as_text().to_string(),
             "message": self.msg.clone(),
-        })
-        .to_string();
+        });
+        if !self.line_errors.is_empty() {
+            json["line_errors"] = self
+                .line_errors
+                .iter()
+                .map(|(line, error)| serde_json::json!({ "line": line, "error": error }))
+                .collect();
+        }
 
-        Body::from(json)
+        Body::from(json.to_string())
     }
 
     /// Generate response for this error.
     pub fn response(&self) -> Response<Body> {
-        Response::builder()
+        let mut builder = Response::builder()
             .status(self.code.status_code())
-            .header("content-type", "application/json")
-            .body(self.body())
-            .unwrap()
+            .header("content-type", "application/json");
+        if let Some(retry_after) = self.retry_after {
+            builder = builder.header(
+                RETRY_AFTER,
+                retry_after.as_secs_f64().ceil().max(1.0) as u64,
+            );
+        }
+        builder.body(self.body()).unwrap()
     }
 
     /// Check if the error is an internal server error.
@@ -14,6 +14,7 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
+pub mod health;
 pub mod http;
 pub mod rpc;
 pub mod server_type;
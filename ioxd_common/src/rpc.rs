@@ -108,6 +108,12 @@ macro_rules! setup_builder {
                 ),
             );
 
+        $crate::health::spawn_health_checks(
+            $server_type.health_checks(),
+            health_reporter.clone(),
+            shutdown.clone(),
+        );
+
         let builder = RpcBuilder {
             inner: builder,
             health_reporter,
@@ -11,7 +11,7 @@ use trace::TraceCollector;
 
 pub use common_state::{CommonServerState, CommonServerStateError};
 
-use crate::{http::error::HttpApiErrorSource, rpc::RpcBuilderInput};
+use crate::{health::HealthCheck, http::error::HttpApiErrorSource, rpc::RpcBuilderInput};
 
 #[derive(Debug, Snafu)]
 pub enum RpcError {
@@ -55,6 +55,11 @@ pub trait ServerType: std::fmt::Debug + Send + Sync + 'static {
         req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>>;
 
+    /// Checks of the subsystems the server depends on, reported via the gRPC health service.
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        vec![]
+    }
+
     /// Construct and serve gRPC subsystem.
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError>;
 
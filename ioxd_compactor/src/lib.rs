@@ -19,12 +19,15 @@ use async_trait::async_trait;
 use backoff::BackoffConfig;
 use clap_blocks::compactor::CompactorConfig;
 use compactor::{compactor::Compactor, config::Config};
+use compactor_scheduler::ManualPartitionQueue;
+use generated_types::influxdata::iox::compactor::v1::compaction_service_server::CompactionServiceServer;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use iox_time::TimeProvider;
 use ioxd_common::{
     add_service,
+    health::{CatalogHealthCheck, HealthCheck, ObjectStoreHealthCheck},
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
@@ -33,6 +36,7 @@ use ioxd_common::{
 };
 use metric::Registry;
 use parquet_file::storage::ParquetStorage;
+use service_grpc_compactor::CompactionService;
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -45,6 +49,9 @@ use crate::scheduler_config::convert_scheduler_config;
 
 pub struct CompactorServerType {
     compactor: Compactor,
+    catalog: Arc<dyn Catalog>,
+    manual_partition_queue: Option<ManualPartitionQueue>,
+    health_checks: Vec<Arc<dyn HealthCheck>>,
     metric_registry: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
 }
@@ -58,11 +65,17 @@ impl std::fmt::Debug for CompactorServerType {
 impl CompactorServerType {
     pub fn new(
         compactor: Compactor,
+        catalog: Arc<dyn Catalog>,
+        manual_partition_queue: Option<ManualPartitionQueue>,
+        health_checks: Vec<Arc<dyn HealthCheck>>,
         metric_registry: Arc<metric::Registry>,
         common_state: &CommonServerState,
     ) -> Self {
         Self {
             compactor,
+            catalog,
+            manual_partition_queue,
+            health_checks,
             metric_registry,
             trace_collector: common_state.trace_collector(),
         }
@@ -86,6 +99,11 @@ impl ServerType for CompactorServerType {
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
+    /// The compactor depends on the catalog and the object store the compacted files live in.
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        self.health_checks.clone()
+    }
+
     /// Just return "not found".
     async fn route_http_request(
         &self,
@@ -98,6 +116,15 @@ impl ServerType for CompactorServerType {
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
 
+        add_service!(
+            builder,
+            CompactionServiceServer::new(CompactionService::new(
+                Arc::clone(&self.catalog),
+                self.manual_partition_queue.clone(),
+                Some(self.compactor.backlog()),
+            ))
+        );
+
         serve_builder!(builder);
 
         Ok(())
@@ -158,13 +185,22 @@ pub async fn create_compactor_server_type(
 ) -> Arc<dyn ServerType> {
     let backoff_config = BackoffConfig::default();
 
+    let scheduler_config =
+        convert_scheduler_config(compactor_config.compactor_scheduler_config.clone());
+    let manual_partition_queue = scheduler_config.manual_partition_queue().cloned();
+
+    let health_checks: Vec<Arc<dyn HealthCheck>> = vec![
+        Arc::new(CatalogHealthCheck::new(Arc::clone(&catalog))),
+        Arc::new(ObjectStoreHealthCheck::new(Arc::clone(
+            parquet_store_real.object_store(),
+        ))),
+    ];
+
     let compactor = Compactor::start(Config {
         metric_registry: Arc::clone(&metric_registry),
         trace_collector: common_state.trace_collector(),
-        catalog,
-        scheduler_config: convert_scheduler_config(
-            compactor_config.compactor_scheduler_config.clone(),
-        ),
+        catalog: Arc::clone(&catalog),
+        scheduler_config,
         parquet_store_real,
         parquet_store_scratchpad,
         exec,
@@ -172,13 +208,17 @@ pub async fn create_compactor_server_type(
         backoff_config,
         partition_concurrency: compactor_config.compaction_partition_concurrency,
         df_concurrency: compactor_config.compaction_df_concurrency,
+        memory_budget_bytes: compactor_config.memory_budget_bytes,
         partition_scratchpad_concurrency: compactor_config
             .compaction_partition_scratchpad_concurrency,
         max_desired_file_size_bytes: compactor_config.max_desired_file_size_bytes,
+        min_desired_file_size_bytes: compactor_config.min_desired_file_size_bytes,
         percentage_max_file_size: compactor_config.percentage_max_file_size,
         split_percentage: compactor_config.split_percentage,
         partition_timeout: Duration::from_secs(compactor_config.partition_timeout_secs),
         shadow_mode: compactor_config.shadow_mode,
+        dry_run: compactor_config.dry_run,
+        verify_output: compactor_config.verify_output,
         enable_scratchpad: compactor_config.enable_scratchpad,
         ignore_partition_skip_marker: compactor_config.ignore_partition_skip_marker,
         min_num_l1_files_to_compact: compactor_config.min_num_l1_files_to_compact,
@@ -190,10 +230,15 @@ pub async fn create_compactor_server_type(
         max_num_files_per_plan: compactor_config.max_num_files_per_plan,
         max_partition_fetch_queries_per_second: compactor_config
             .max_partition_fetch_queries_per_second,
+        time_slice_min_num_l0_files: compactor_config.time_slice_min_num_l0_files,
+        max_consecutive_failures: compactor_config.max_consecutive_failures,
     });
 
     Arc::new(CompactorServerType::new(
         compactor,
+        catalog,
+        manual_partition_queue,
+        health_checks,
         metric_registry,
         common_state,
     ))
@@ -5,7 +5,8 @@ use clap_blocks::compactor_scheduler::{
     ShardConfigForLocalScheduler,
 };
 use compactor_scheduler::{
-    LocalSchedulerConfig, PartitionsSourceConfig, SchedulerConfig, ShardConfig,
+    ColdPartitionsSourceConfig, LocalSchedulerConfig, ManualPartitionQueue, PartitionsSourceConfig,
+    SchedulerConfig, ShardConfig,
 };
 use data_types::PartitionId;
 
@@ -16,6 +17,8 @@ fn convert_partitions_source_config(
         partition_filter,
         process_all_partitions,
         compaction_partition_minute_threshold,
+        compaction_cold_partition_minute_threshold: _,
+        compaction_max_cold_partitions: _,
     } = config;
 
     match (partition_filter, process_all_partitions) {
@@ -32,9 +35,20 @@ fn convert_partitions_source_config(
     }
 }
 
+fn convert_cold_partitions_source_config(
+    config: &PartitionSourceConfigForLocalScheduler,
+) -> Option<ColdPartitionsSourceConfig> {
+    config
+        .compaction_cold_partition_minute_threshold
+        .map(|minutes| ColdPartitionsSourceConfig {
+            threshold: Duration::from_secs(minutes * 60),
+            max_partitions: config.compaction_max_cold_partitions,
+        })
+}
+
 /// Create a new [`ShardConfig`] from a [`ShardConfigForLocalScheduler`].
 fn convert_shard_config(config: ShardConfigForLocalScheduler) -> Option<ShardConfig> {
-    match (config.shard_count, config.shard_id, config.hostname) {
+    let shard_config = match (config.shard_count, config.shard_id, config.hostname) {
         // if no shard_count is provided, then we are not sharding
         (None, _, _) => None,
         // always use the shard_id if provided
@@ -44,33 +58,44 @@ fn convert_shard_config(config: ShardConfigForLocalScheduler) -> Option<ShardCon
         }),
         // if no shard_id is provided, then we are sharding by hostname
         (Some(shard_count), None, Some(hostname)) => {
-            let parsed_id = hostname
-                .chars()
-                .skip_while(|ch| !ch.is_ascii_digit())
-                .take_while(|ch| ch.is_ascii_digit())
-                .fold(None, |acc, ch| {
-                    ch.to_digit(10).map(|b| acc.unwrap_or(0) * 10 + b)
-                });
+            // use the trailing digits, other digits may be part of the name (e.g. `compactor-v2-3`)
+            let prefix = hostname.trim_end_matches(|ch: char| ch.is_ascii_digit());
+            let parsed_id = hostname[prefix.len()..].parse::<usize>().ok();
             assert!(parsed_id.is_some(), "hostname must end in a shard ID");
             Some(ShardConfig {
-                shard_id: parsed_id.unwrap() as usize,
+                shard_id: parsed_id.unwrap(),
                 n_shards: shard_count,
             })
         }
         (Some(_), None, None) => {
             panic!("shard_count must be paired with either shard_id or hostname")
         }
+    };
+
+    if let Some(ShardConfig { n_shards, shard_id }) = &shard_config {
+        assert!(
+            shard_id < n_shards,
+            "shard_id ({shard_id}) must be smaller than shard_count ({n_shards})"
+        );
     }
+
+    shard_config
 }
 
 pub(crate) fn convert_scheduler_config(config: CompactorSchedulerConfig) -> SchedulerConfig {
     match config.compactor_scheduler_type {
         CompactorSchedulerType::Local => SchedulerConfig::Local(LocalSchedulerConfig {
             commit_wrapper: None,
+            cold_partitions_source_config: convert_cold_partitions_source_config(
+                &config.partition_source_config,
+            ),
             partitions_source_config: convert_partitions_source_config(
                 config.partition_source_config,
             ),
             shard_config: convert_shard_config(config.shard_config),
+            manual_partition_queue: ManualPartitionQueue::default(),
+            prioritize_l0_backlog: config.compaction_prioritize_l0_backlog,
+            job_lease_duration: config.compaction_job_lease_secs.map(Duration::from_secs),
         }),
         CompactorSchedulerType::Remote => unimplemented!("Remote scheduler not implemented"),
     }
@@ -89,6 +114,8 @@ mod tests {
             compaction_partition_minute_threshold: 10,
             partition_filter: Some(vec![1, 7]),
             process_all_partitions: true,
+            compaction_cold_partition_minute_threshold: None,
+            compaction_max_cold_partitions: 100,
         };
         convert_partitions_source_config(config);
     }
@@ -99,6 +126,8 @@ mod tests {
             compaction_partition_minute_threshold: 10,
             partition_filter: Some(vec![1, 7]),
             process_all_partitions: false,
+            compaction_cold_partition_minute_threshold: None,
+            compaction_max_cold_partitions: 100,
         };
         let partitions_source_config = convert_partitions_source_config(config);
 
@@ -114,6 +143,8 @@ mod tests {
             compaction_partition_minute_threshold: 10,
             partition_filter: None,
             process_all_partitions: true,
+            compaction_cold_partition_minute_threshold: None,
+            compaction_max_cold_partitions: 100,
         };
         let partitions_source_config = convert_partitions_source_config(config);
 
@@ -126,6 +157,8 @@ mod tests {
             compaction_partition_minute_threshold: 10,
             partition_filter: None,
             process_all_partitions: false,
+            compaction_cold_partition_minute_threshold: None,
+            compaction_max_cold_partitions: 100,
         };
         let partitions_source_config = convert_partitions_source_config(config);
 
@@ -136,4 +169,71 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn cold_partitions() {
+        let mut config = PartitionSourceConfigForLocalScheduler {
+            compaction_partition_minute_threshold: 10,
+            partition_filter: None,
+            process_all_partitions: false,
+            compaction_cold_partition_minute_threshold: None,
+            compaction_max_cold_partitions: 100,
+        };
+        assert_eq!(convert_cold_partitions_source_config(&config), None);
+
+        config.compaction_cold_partition_minute_threshold = Some(240);
+        assert_eq!(
+            convert_cold_partitions_source_config(&config),
+            Some(ColdPartitionsSourceConfig {
+                threshold: Duration::from_secs(240 * 60),
+                max_partitions: 100,
+            }),
+        );
+    }
+
+    #[test]
+    fn shard_id() {
+        let shard_config = convert_shard_config(ShardConfigForLocalScheduler {
+            shard_count: Some(3),
+            shard_id: Some(1),
+            hostname: Some("iox-compactor-2".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(shard_config.n_shards, 3);
+        assert_eq!(shard_config.shard_id, 1);
+
+        assert!(convert_shard_config(ShardConfigForLocalScheduler::default()).is_none());
+    }
+
+    #[test]
+    fn shard_id_from_hostname() {
+        let shard_config = convert_shard_config(ShardConfigForLocalScheduler {
+            shard_count: Some(20),
+            shard_id: None,
+            hostname: Some("iox-compactor-v2-12".to_owned()),
+        })
+        .unwrap();
+        assert_eq!(shard_config.n_shards, 20);
+        assert_eq!(shard_config.shard_id, 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "hostname must end in a shard ID")]
+    fn shard_id_from_hostname_without_id() {
+        convert_shard_config(ShardConfigForLocalScheduler {
+            shard_count: Some(3),
+            shard_id: None,
+            hostname: Some("iox-compactor-2a".to_owned()),
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "shard_id (3) must be smaller than shard_count (3)")]
+    fn shard_id_out_of_range() {
+        convert_shard_config(ShardConfigForLocalScheduler {
+            shard_count: Some(3),
+            shard_id: Some(3),
+            hostname: None,
+        });
+    }
 }
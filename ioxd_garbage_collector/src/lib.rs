@@ -30,6 +30,7 @@ use futures::{
 use garbage_collector::GarbageCollector;
 use hyper::{Body, Request, Response};
 use ioxd_common::{
+    health::{CatalogHealthCheck, HealthCheck, ObjectStoreHealthCheck},
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
@@ -48,6 +49,7 @@ pub use garbage_collector::Config;
 /// The object store garbage collection server
 pub struct Server {
     metric_registry: Arc<metric::Registry>,
+    health_checks: Vec<Arc<dyn HealthCheck>>,
     worker: SharedCloneError<(), JoinError>,
     shutdown_tx: broadcast::Sender<()>,
 }
@@ -64,11 +66,19 @@ impl Server {
     pub fn start(metric_registry: Arc<metric::Registry>, config: Config) -> Self {
         let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
 
+        let health_checks: Vec<Arc<dyn HealthCheck>> = vec![
+            Arc::new(CatalogHealthCheck::new(Arc::clone(&config.catalog))),
+            Arc::new(ObjectStoreHealthCheck::new(Arc::clone(
+                &config.object_store,
+            ))),
+        ];
+
         let worker = tokio::spawn(Self::worker_task(config, shutdown_rx));
         let worker = shared_clone_error(worker);
 
         Self {
             metric_registry,
+            health_checks,
             worker,
             shutdown_tx,
         }
@@ -122,6 +132,10 @@ impl ServerType for Server {
         None
     }
 
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        self.health_checks.clone()
+    }
+
     async fn route_http_request(
         &self,
         _req: Request<Body>,
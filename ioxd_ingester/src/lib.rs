@@ -16,7 +16,7 @@ use workspace_hack as _;
 
 use arrow_flight::flight_service_server::FlightServiceServer;
 use async_trait::async_trait;
-use clap_blocks::ingester::IngesterConfig;
+use clap_blocks::ingester::{IngesterConfig, WalCompression, WriteAckMode};
 use futures::FutureExt;
 use generated_types::influxdata::iox::{
     catalog::v1::catalog_service_server::CatalogServiceServer,
@@ -30,6 +30,9 @@ use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use ioxd_common::{
     add_service,
+    health::{
+        CatalogHealthCheck, DirectoryWritableHealthCheck, HealthCheck, ObjectStoreHealthCheck,
+    },
     http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
     rpc::RpcBuilderInput,
     serve_builder,
@@ -57,12 +60,16 @@ const MAX_OUTGOING_MSG_BYTES: usize = 1024 * 1024; // 1 MiB
 pub enum Error {
     #[error("error initializing ingester: {0}")]
     Ingester(#[from] ingester::InitError),
+
+    #[error("invalid ingester config: {0}")]
+    NamespaceMemoryLimits(#[from] ingester::InvalidNamespaceMemoryLimits),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 struct IngesterServerType<I: IngesterRpcInterface> {
     server: IngesterGuard<I>,
+    health_checks: Vec<Arc<dyn HealthCheck>>,
     shutdown: Mutex<Option<oneshot::Sender<CancellationToken>>>,
     metrics: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
@@ -73,6 +80,7 @@ struct IngesterServerType<I: IngesterRpcInterface> {
 impl<I: IngesterRpcInterface> IngesterServerType<I> {
     pub fn new(
         server: IngesterGuard<I>,
+        health_checks: Vec<Arc<dyn HealthCheck>>,
         metrics: Arc<Registry>,
         common_state: &CommonServerState,
         max_simultaneous_queries: usize,
@@ -81,6 +89,7 @@ impl<I: IngesterRpcInterface> IngesterServerType<I> {
     ) -> Self {
         Self {
             server,
+            health_checks,
             shutdown: Mutex::new(Some(shutdown)),
             metrics,
             trace_collector: common_state.trace_collector(),
@@ -113,6 +122,11 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
+    /// The ingester depends on the catalog, the object store to persist to and a writable WAL.
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        self.health_checks.clone()
+    }
+
     /// Just return "not found".
     async fn route_http_request(
         &self,
@@ -210,16 +224,50 @@ pub async fn create_ingester_server_type(
 ) -> Result<Arc<dyn ServerType>> {
     let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
+    let health_checks: Vec<Arc<dyn HealthCheck>> = vec![
+        Arc::new(CatalogHealthCheck::new(Arc::clone(&catalog))),
+        Arc::new(ObjectStoreHealthCheck::new(Arc::clone(
+            object_store.object_store(),
+        ))),
+        Arc::new(DirectoryWritableHealthCheck::new(
+            "iox.wal",
+            ingester_config.wal_directory.clone(),
+        )),
+    ];
+
+    let namespace_memory_limits = ingester_config
+        .namespace_memory_soft_limit_bytes
+        .zip(ingester_config.namespace_memory_hard_limit_bytes)
+        .map(|(soft, hard)| ingester::NamespaceMemoryLimits::new(soft, hard))
+        .transpose()?;
+
     let grpc = ingester::new(
         catalog,
         Arc::clone(&metrics),
         PERSIST_BACKGROUND_FETCH_TIME,
         ingester_config.wal_directory.clone(),
         Duration::from_secs(ingester_config.wal_rotation_period_seconds),
+        match ingester_config.wal_compression {
+            WalCompression::Snappy => ingester::WalCompression::Snappy,
+            WalCompression::Zstd => ingester::WalCompression::Zstd,
+        },
+        match ingester_config.write_ack_mode {
+            WriteAckMode::Strict => ingester::WriteAckMode::Strict,
+            WriteAckMode::Fast => ingester::WriteAckMode::Fast,
+        },
         exec,
         ingester_config.persist_max_parallelism,
         ingester_config.persist_queue_depth,
         ingester_config.persist_hot_partition_cost,
+        ingester_config
+            .persist_closed_partition_grace_seconds
+            .map(|grace| {
+                ingester::ClosedPartitionPolicy::new(
+                    Duration::from_secs(ingester_config.persist_partition_window_seconds),
+                    Duration::from_secs(grace),
+                )
+            }),
+        namespace_memory_limits,
         object_store,
         shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
     )
@@ -227,6 +275,7 @@ pub async fn create_ingester_server_type(
 
     Ok(Arc::new(IngesterServerType::new(
         grpc,
+        health_checks,
         metrics,
         common_state,
         ingester_config.concurrent_query_limit,
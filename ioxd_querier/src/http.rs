@@ -0,0 +1,733 @@
+//! The HTTP API of the querier.
+//!
+//! The querier serves the InfluxDB 1.x compatible [`/query` endpoint], so that clients that cannot speak Flight can
+//! run InfluxQL queries and receive the results in the classic JSON format.
+//!
+//! [`/query` endpoint]: https://docs.influxdata.com/influxdb/v1.8/tools/api/#query-http-endpoint
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray, StringArray},
+    compute::cast,
+    datatypes::{
+        DataType, Float64Type, Int64Type, SchemaRef, TimeUnit, TimestampNanosecondType, UInt64Type,
+    },
+    error::ArrowError,
+    record_batch::RecordBatch,
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use audit::{AuditLog, AuditRecord, Operation};
+use authz::{
+    extract_token, http::AuthorizationHeaderExtension, Action, Authorizer, Permission, Resource,
+};
+use datafusion::error::DataFusionError;
+use generated_types::influxdata::iox::querier::v1::InfluxQlMetadata;
+use hyper::{
+    body::HttpBody,
+    header::{HeaderValue, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
+use influxdb_influxql_parser::parse_statements;
+use iox_query::{exec::ExecutionContextProvider, QueryNamespace};
+use ioxd_common::http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource};
+use observability_deps::tracing::{debug, info};
+use querier::{FederatedNamespace, QuerierDatabase};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use service_common::{planner::Planner, quota::QuotaError, QueryNamespaceProvider};
+use thiserror::Error;
+
+/// When a retention policy is given, the namespace name is the database name and the retention policy separated by
+/// `/` -- the same mapping the router applies to v1 writes.
+const V1_NAMESPACE_RP_SEPARATOR: char = '/';
+
+/// Errors of the querier HTTP API.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// No handler exists for the requested path.
+    #[error("not found")]
+    NotFound,
+
+    /// The request parameters could not be decoded.
+    #[error("invalid query parameters: {0}")]
+    InvalidParams(#[from] serde_urlencoded::de::Error),
+
+    /// The client disconnected while sending the request body.
+    #[error("client disconnected")]
+    ClientHangup(hyper::Error),
+
+    /// The request body exceeds the configured maximum.
+    #[error("max request size ({0} bytes) exceeded")]
+    RequestSizeExceeded(usize),
+
+    /// The request contains no query.
+    #[error("missing required parameter \"q\"")]
+    MissingQuery,
+
+    /// The request names no database.
+    #[error("database name required")]
+    MissingDatabase,
+
+    /// The `epoch` parameter is not a known precision.
+    #[error("invalid epoch {0:?}, expected one of h, m, s, ms, u, µ or ns")]
+    InvalidEpoch(String),
+
+    /// The query is not valid InfluxQL.
+    #[error("error parsing query: {0}")]
+    ParseQuery(String),
+
+    /// The client is not allowed to query the namespace.
+    #[error(transparent)]
+    Authz(#[from] authz::Error),
+
+    /// The namespace exhausted its query quota.
+    #[error(transparent)]
+    Quota(#[from] QuotaError),
+
+    /// The namespace does not exist.
+    #[error("database not found: {0}")]
+    NamespaceNotFound(String),
+
+    /// The response could not be serialized.
+    #[error("cannot serialize response: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+impl HttpApiErrorSource for Error {
+    fn to_http_api_error(&self) -> HttpApiError {
+        let code = match self {
+            Self::NotFound | Self::NamespaceNotFound(_) => HttpApiErrorCode::NotFound,
+            Self::InvalidParams(_)
+            | Self::ClientHangup(_)
+            | Self::MissingQuery
+            | Self::MissingDatabase
+            | Self::InvalidEpoch(_)
+            | Self::ParseQuery(_) => HttpApiErrorCode::Invalid,
+            Self::RequestSizeExceeded(_) => HttpApiErrorCode::RequestTooLarge,
+            Self::Authz(authz::Error::NoToken) => HttpApiErrorCode::Unauthorized,
+            Self::Authz(authz::Error::Forbidden | authz::Error::InvalidToken) => {
+                HttpApiErrorCode::Forbidden
+            }
+            Self::Authz(_) | Self::Serialize(_) => HttpApiErrorCode::InternalError,
+            Self::Quota(_) => HttpApiErrorCode::TooManyRequests,
+        };
+        HttpApiError::new(code, self.to_string())
+    }
+}
+
+/// Parameters of a `/query` request, given in the URL or -- for `POST` requests -- as a form in the body.
+#[derive(Debug, Default, Deserialize)]
+struct QueryParams {
+    /// The InfluxQL query, possibly several statements separated by `;`.
+    q: Option<String>,
+
+    /// The database to query.
+    db: Option<String>,
+
+    /// The retention policy to query.
+    rp: Option<String>,
+
+    /// Return timestamps as integers of this precision instead of RFC3339 strings.
+    epoch: Option<String>,
+
+    /// Pretty-print the JSON response.
+    #[serde(default)]
+    pretty: bool,
+
+    /// The v1 password, which is treated as a token. The v1 username is ignored.
+    p: Option<String>,
+}
+
+impl QueryParams {
+    /// Use the parameters of `other` that are not set in `self`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            q: self.q.or(other.q),
+            db: self.db.or(other.db),
+            rp: self.rp.or(other.rp),
+            epoch: self.epoch.or(other.epoch),
+            pretty: self.pretty || other.pretty,
+            p: self.p.or(other.p),
+        }
+    }
+
+    /// The namespace the query runs against.
+    fn namespace(&self) -> Result<String, Error> {
+        let db = self
+            .db
+            .as_deref()
+            .filter(|db| !db.is_empty())
+            .ok_or(Error::MissingDatabase)?;
+        Ok(match self.rp.as_deref().map(str::to_lowercase).as_deref() {
+            None | Some("") | Some("''") | Some("autogen") | Some("default") => db.to_string(),
+            Some(rp) => format!("{db}{V1_NAMESPACE_RP_SEPARATOR}{rp}"),
+        })
+    }
+}
+
+/// The format of timestamps in the response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Epoch {
+    /// RFC3339 strings, the default.
+    Rfc3339,
+    /// Integer timestamps, which are the nanosecond timestamps divided by this.
+    Integer(i64),
+}
+
+impl Epoch {
+    fn try_new(epoch: Option<&str>) -> Result<Self, Error> {
+        let divisor = match epoch {
+            None | Some("") => return Ok(Self::Rfc3339),
+            Some("h") => 60 * 60 * 1_000_000_000,
+            Some("m") => 60 * 1_000_000_000,
+            Some("s") => 1_000_000_000,
+            Some("ms") => 1_000_000,
+            Some("u") | Some("µ") => 1_000,
+            Some("ns") => 1,
+            Some(v) => return Err(Error::InvalidEpoch(v.to_string())),
+        };
+        Ok(Self::Integer(divisor))
+    }
+
+    fn format(&self, nanos: i64) -> Value {
+        match self {
+            Self::Rfc3339 => Value::from(
+                iox_time::Time::from_timestamp_nanos(nanos)
+                    .date_time()
+                    .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            ),
+            Self::Integer(divisor) => Value::from(nanos / divisor),
+        }
+    }
+}
+
+/// The JSON response of a `/query` request.
+#[derive(Debug, Serialize)]
+struct QueryResponse {
+    results: Vec<StatementResult>,
+}
+
+/// The result of a single statement of a query.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct StatementResult {
+    statement_id: usize,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    series: Vec<Series>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The rows of a measurement and, for `GROUP BY` queries, a combination of tag values.
+#[derive(Debug, PartialEq, Serialize)]
+struct Series {
+    name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<BTreeMap<String, String>>,
+
+    columns: Vec<String>,
+
+    values: Vec<Vec<Value>>,
+}
+
+/// Serves the HTTP API of the querier.
+#[derive(Debug)]
+pub(crate) struct HttpApi {
+    database: Arc<QuerierDatabase>,
+    authz: Option<Arc<dyn Authorizer>>,
+    max_request_bytes: usize,
+    audit_log: Option<AuditLog>,
+}
+
+impl HttpApi {
+    pub(crate) fn new(
+        database: Arc<QuerierDatabase>,
+        authz: Option<Arc<dyn Authorizer>>,
+        max_request_bytes: usize,
+        audit_log: Option<AuditLog>,
+    ) -> Self {
+        Self {
+            database,
+            authz,
+            max_request_bytes,
+            audit_log,
+        }
+    }
+
+    /// Routes `req` to the appropriate handler, if any.
+    pub(crate) async fn route(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        match (req.method(), req.uri().path()) {
+            (&Method::GET | &Method::POST, "/query") => self.query(req).await,
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    /// Serve a `/query` request, recording it in the audit log, if enabled.
+    ///
+    /// Errors running individual statements are returned in the response, so the request is recorded as successful.
+    async fn query(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let token = extract_token(
+            req.extensions()
+                .get::<AuthorizationHeaderExtension>()
+                .and_then(|v| v.as_ref()),
+        );
+        let params = self.read_params(req).await;
+        let token = match &params {
+            Ok(params) => token.or_else(|| params.p.clone().map(String::into_bytes)),
+            Err(_) => token,
+        };
+
+        let Some(audit_log) = &self.audit_log else {
+            return self.run_query(token, params?).await;
+        };
+
+        let record = match &params {
+            Ok(params) => {
+                let query = params.q.as_deref().unwrap_or_default();
+                let record = AuditRecord::new(Operation::Query, format!("InfluxQL: {query}"))
+                    .with_bytes(query.len() as u64);
+                match params.namespace() {
+                    Ok(namespace_name) => record.with_namespace(namespace_name),
+                    Err(_) => record,
+                }
+            }
+            Err(_) => AuditRecord::new(Operation::Query, "InfluxQL"),
+        }
+        .with_token(token.as_deref());
+
+        let response = match params {
+            Ok(params) => self.run_query(token, params).await,
+            Err(e) => Err(e),
+        };
+        audit_log.record(record.with_result(&response));
+        response
+    }
+
+    async fn run_query(
+        &self,
+        token: Option<Vec<u8>>,
+        params: QueryParams,
+    ) -> Result<Response<Body>, Error> {
+        let query = params
+            .q
+            .as_deref()
+            .filter(|q| !q.trim().is_empty())
+            .ok_or(Error::MissingQuery)?;
+        let namespace_name = params.namespace()?;
+        let epoch = Epoch::try_new(params.epoch.as_deref())?;
+        let statements = parse_statements(query).map_err(|e| Error::ParseQuery(e.to_string()))?;
+
+        let perms = [Permission::ResourceAction(
+            Resource::Database(namespace_name.clone()),
+            Action::Read,
+        )];
+        self.authz.permissions(token, &perms).await?;
+
+        let _quota_permit = self.database.acquire_quota(&namespace_name).await?;
+        let _permit = self.database.acquire_semaphore(None).await;
+
+        let db = self
+            .database
+            .db(&namespace_name, None, false)
+            .await
+            .ok_or_else(|| Error::NamespaceNotFound(namespace_name.clone()))?;
+
+        let mut results = Vec::with_capacity(statements.len());
+        for (statement_id, statement) in statements.into_iter().enumerate() {
+            let statement = statement.to_string();
+            let series = run_statement(&db, &statement)
+                .await
+                .and_then(|(schema, batches)| {
+                    to_series(&schema, &batches, epoch).map_err(DataFusionError::ArrowError)
+                });
+            let result = match series {
+                Ok(series) => StatementResult {
+                    statement_id,
+                    series,
+                    error: None,
+                },
+                Err(e) => {
+                    info!(%namespace_name, %statement, %e, "Error running InfluxQL query via HTTP");
+                    StatementResult {
+                        statement_id,
+                        series: vec![],
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            results.push(result);
+        }
+
+        let response = QueryResponse { results };
+        let body = if params.pretty {
+            serde_json::to_vec_pretty(&response)?
+        } else {
+            serde_json::to_vec(&response)?
+        };
+
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(Body::from(body))
+            .unwrap())
+    }
+
+    /// Read the parameters of the request from the URL and -- for `POST` requests -- the form in the body.
+    async fn read_params(&self, req: Request<Body>) -> Result<QueryParams, Error> {
+        let params: QueryParams =
+            serde_urlencoded::from_str(req.uri().query().unwrap_or_default())?;
+        if req.method() != Method::POST {
+            return Ok(params);
+        }
+
+        let is_form = req
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(';').next())
+            .map(|v| {
+                v.trim()
+                    .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+            })
+            .unwrap_or_default();
+        if !is_form {
+            return Ok(params);
+        }
+
+        let mut payload = req.into_body();
+        let mut body = Vec::new();
+        while let Some(chunk) = payload.data().await {
+            let chunk = chunk.map_err(Error::ClientHangup)?;
+            if (body.len() + chunk.len()) > self.max_request_bytes {
+                return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let form: QueryParams = serde_urlencoded::from_bytes(&body)?;
+
+        Ok(params.or(form))
+    }
+}
+
+/// Plan and execute a single InfluxQL statement.
+async fn run_statement(
+    db: &Arc<FederatedNamespace>,
+    statement: &str,
+) -> Result<(SchemaRef, Vec<RecordBatch>), DataFusionError> {
+    let ctx = db.new_query_context(None);
+    let mut token = db.record_query(&ctx, "influxql", Box::new(statement.to_string()));
+
+    debug!(%statement, "Running InfluxQL query via HTTP");
+    let plan = Planner::new(&ctx).influxql(statement).await?;
+    // the schema of the plan carries the InfluxQL metadata, the schema of the batches may not
+    let schema = plan.schema();
+
+    token.enter_phase(iox_query::QueryPhase::Execution);
+    let batches = ctx.collect(plan).await?;
+    token.set_success();
+
+    Ok((schema, batches))
+}
+
+/// Convert the results of an InfluxQL query to series, i.e. one series per measurement and -- for `GROUP BY` queries
+/// -- combination of tag values.
+fn to_series(
+    schema: &SchemaRef,
+    batches: &[RecordBatch],
+    epoch: Epoch,
+) -> Result<Vec<Series>, ArrowError> {
+    let md = match schema.metadata().get(schema::INFLUXQL_METADATA_KEY) {
+        Some(md) => serde_json::from_str::<InfluxQlMetadata>(md)
+            .map_err(|e| ArrowError::ExternalError(Box::new(e)))?,
+        None => {
+            return Err(ArrowError::SchemaError(
+                "missing InfluxQL metadata".to_string(),
+            ))
+        }
+    };
+
+    let measurement_idx = md.measurement_column_index as usize;
+    let tag_keys = md
+        .tag_key_columns
+        .iter()
+        .map(|tk| (tk.tag_key.clone(), tk.column_index as usize))
+        .collect::<Vec<_>>();
+
+    // The measurement name column and any tag key columns that only appear in the `GROUP BY` clause are not
+    // returned as columns.
+    let col_indexes = (0..schema.fields().len())
+        .filter(|i| {
+            *i != measurement_idx
+                && !md
+                    .tag_key_columns
+                    .iter()
+                    .any(|tk| tk.column_index as usize == *i && !tk.is_projected)
+        })
+        .collect::<Vec<_>>();
+    let columns = col_indexes
+        .iter()
+        .map(|i| schema.field(*i).name().clone())
+        .collect::<Vec<_>>();
+
+    let mut series: Vec<Series> = vec![];
+    for batch in batches {
+        let measurement = string_values(batch.column(measurement_idx))?;
+        let tag_values = tag_keys
+            .iter()
+            .map(|(_, i)| string_values(batch.column(*i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let values = col_indexes
+            .iter()
+            .map(|i| json_values(batch.column(*i), epoch))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for row in 0..batch.num_rows() {
+            let name = measurement.value(row);
+            let tags = (!tag_keys.is_empty()).then(|| {
+                tag_keys
+                    .iter()
+                    .zip(&tag_values)
+                    .map(|((tag_key, _), values)| {
+                        let value = if values.is_null(row) {
+                            ""
+                        } else {
+                            values.value(row)
+                        };
+                        (tag_key.clone(), value.to_string())
+                    })
+                    .collect::<BTreeMap<_, _>>()
+            });
+
+            // rows of a series are adjacent, as the results are sorted by measurement and group key
+            let current = match series.last_mut() {
+                Some(s) if s.name == name && s.tags == tags => s,
+                _ => {
+                    series.push(Series {
+                        name: name.to_string(),
+                        tags,
+                        columns: columns.clone(),
+                        values: vec![],
+                    });
+                    series.last_mut().expect("just pushed")
+                }
+            };
+            current
+                .values
+                .push(values.iter().map(|v| v[row].clone()).collect());
+        }
+    }
+
+    Ok(series)
+}
+
+/// The values of a string or dictionary-encoded string column.
+fn string_values(array: &ArrayRef) -> Result<StringArray, ArrowError> {
+    let array = cast(array, &DataType::Utf8)?;
+    Ok(array.as_string::<i32>().clone())
+}
+
+/// Convert the values of a column to JSON, formatting timestamps according to `epoch`.
+fn json_values(array: &ArrayRef, epoch: Epoch) -> Result<Vec<Value>, ArrowError> {
+    let array = match array.data_type() {
+        DataType::Dictionary(_, _) => cast(array, &DataType::Utf8)?,
+        _ => Arc::clone(array),
+    };
+
+    fn collect<T>(values: impl Iterator<Item = Option<T>>, f: impl Fn(T) -> Value) -> Vec<Value> {
+        values.map(|v| v.map(&f).unwrap_or(Value::Null)).collect()
+    }
+
+    Ok(match array.data_type() {
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => collect(
+            array.as_primitive::<TimestampNanosecondType>().iter(),
+            |v| epoch.format(v),
+        ),
+        DataType::Int64 => collect(array.as_primitive::<Int64Type>().iter(), Value::from),
+        DataType::UInt64 => collect(array.as_primitive::<UInt64Type>().iter(), Value::from),
+        // non-finite floats are not valid JSON and are returned as null
+        DataType::Float64 => collect(array.as_primitive::<Float64Type>().iter(), Value::from),
+        DataType::Boolean => collect(array.as_boolean().iter(), Value::from),
+        DataType::Utf8 => collect(array.as_string::<i32>().iter(), Value::from),
+        _ => {
+            let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+            (0..array.len())
+                .map(|i| {
+                    if array.is_null(i) {
+                        Value::Null
+                    } else {
+                        Value::from(formatter.value(i).to_string())
+                    }
+                })
+                .collect()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{DictionaryArray, Float64Array, TimestampNanosecondArray},
+        datatypes::{Field, Int32Type, Schema},
+    };
+    use generated_types::influxdata::iox::querier::v1::influx_ql_metadata::TagKeyColumn;
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema(tag_key_columns: Vec<TagKeyColumn>) -> SchemaRef {
+        let md = InfluxQlMetadata {
+            measurement_column_index: 0,
+            tag_key_columns,
+        };
+        let dict = DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+        Arc::new(Schema::new_with_metadata(
+            vec![
+                Field::new(
+                    schema::INFLUXQL_MEASUREMENT_COLUMN_NAME,
+                    dict.clone(),
+                    false,
+                ),
+                Field::new(
+                    "time",
+                    DataType::Timestamp(TimeUnit::Nanosecond, None),
+                    false,
+                ),
+                Field::new("host", dict, true),
+                Field::new("usage", DataType::Float64, true),
+            ],
+            [(
+                schema::INFLUXQL_METADATA_KEY.to_string(),
+                serde_json::to_string(&md).unwrap(),
+            )]
+            .into(),
+        ))
+    }
+
+    fn batch(schema: &SchemaRef) -> RecordBatch {
+        RecordBatch::try_new(
+            Arc::clone(schema),
+            vec![
+                Arc::new(
+                    ["cpu", "cpu", "cpu", "mem"]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+                Arc::new(TimestampNanosecondArray::from(vec![
+                    1_000_000_000,
+                    2_000_000_000,
+                    1_500_000_000,
+                    0,
+                ])),
+                Arc::new(
+                    [Some("a"), Some("a"), Some("b"), None]
+                        .into_iter()
+                        .collect::<DictionaryArray<Int32Type>>(),
+                ),
+                Arc::new(Float64Array::from(vec![
+                    Some(1.0),
+                    Some(f64::NAN),
+                    None,
+                    Some(4.5),
+                ])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_series() {
+        let schema = schema(vec![]);
+        let series = to_series(&schema, &[batch(&schema)], Epoch::Rfc3339).unwrap();
+        assert_eq!(
+            serde_json::to_value(series).unwrap(),
+            json!([
+                {
+                    "name": "cpu",
+                    "columns": ["time", "host", "usage"],
+                    "values": [
+                        ["1970-01-01T00:00:01Z", "a", 1.0],
+                        ["1970-01-01T00:00:02Z", "a", null],
+                        ["1970-01-01T00:00:01.500Z", "b", null],
+                    ],
+                },
+                {
+                    "name": "mem",
+                    "columns": ["time", "host", "usage"],
+                    "values": [["1970-01-01T00:00:00Z", null, 4.5]],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_series_group_by() {
+        let schema = schema(vec![TagKeyColumn {
+            tag_key: "host".to_string(),
+            column_index: 2,
+            is_projected: false,
+        }]);
+        let series = to_series(
+            &schema,
+            &[batch(&schema)],
+            Epoch::try_new(Some("ms")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(series).unwrap(),
+            json!([
+                {
+                    "name": "cpu",
+                    "tags": {"host": "a"},
+                    "columns": ["time", "usage"],
+                    "values": [[1000, 1.0], [2000, null]],
+                },
+                {
+                    "name": "cpu",
+                    "tags": {"host": "b"},
+                    "columns": ["time", "usage"],
+                    "values": [[1500, null]],
+                },
+                {
+                    "name": "mem",
+                    "tags": {"host": ""},
+                    "columns": ["time", "usage"],
+                    "values": [[0, 4.5]],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let params: QueryParams =
+            serde_urlencoded::from_str("db=foo&q=SELECT+*+FROM+cpu&rp=autogen").unwrap();
+        assert_eq!(params.namespace().unwrap(), "foo");
+        assert_eq!(params.q.as_deref(), Some("SELECT * FROM cpu"));
+
+        let params: QueryParams = serde_urlencoded::from_str("db=foo&rp=Weekly").unwrap();
+        assert_eq!(params.namespace().unwrap(), "foo/weekly");
+
+        let params: QueryParams = serde_urlencoded::from_str("q=SHOW+MEASUREMENTS").unwrap();
+        assert!(matches!(params.namespace(), Err(Error::MissingDatabase)));
+
+        // parameters of the URL take precedence over those of the form
+        let url: QueryParams = serde_urlencoded::from_str("db=foo&pretty=true").unwrap();
+        let form: QueryParams = serde_urlencoded::from_str("db=bar&q=SHOW+MEASUREMENTS").unwrap();
+        let params = url.or(form);
+        assert_eq!(params.namespace().unwrap(), "foo");
+        assert_eq!(params.q.as_deref(), Some("SHOW MEASUREMENTS"));
+        assert!(params.pretty);
+
+        assert_eq!(Epoch::try_new(None).unwrap(), Epoch::Rfc3339);
+        assert_eq!(
+            Epoch::try_new(Some("s")).unwrap(),
+            Epoch::Integer(1_000_000_000)
+        );
+        assert!(matches!(
+            Epoch::try_new(Some("d")),
+            Err(Error::InvalidEpoch(_))
+        ));
+    }
+}
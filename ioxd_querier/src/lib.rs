@@ -23,16 +23,18 @@ use service_grpc_schema::SchemaService;
 use workspace_hack as _;
 
 use async_trait::async_trait;
+use audit::AuditLog;
 use authz::{Authorizer, IoxAuthorizer};
 use clap_blocks::querier::QuerierConfig;
 use datafusion_util::config::register_iox_object_store;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
-use iox_query::exec::{Executor, ExecutorType};
+use iox_query::exec::{admission::AdmissionLimits, Executor, ExecutorType};
 use iox_time::TimeProvider;
 use ioxd_common::{
     add_service,
-    http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    health::{CatalogHealthCheck, HealthCheck, ObjectStoreHealthCheck},
+    http::error::HttpApiErrorSource,
     rpc::RpcBuilderInput,
     serve_builder,
     server_type::{CommonServerState, RpcError, ServerType},
@@ -40,26 +42,29 @@ use ioxd_common::{
 };
 use metric::Registry;
 use object_store::{DynObjectStore, ObjectStore};
-use querier::{create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierServer};
-use std::{
-    fmt::{Debug, Display},
-    sync::Arc,
+use observability_deps::tracing::info;
+use querier::{
+    create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierServer, QueryQuota,
 };
+use std::{num::NonZeroUsize, sync::Arc};
 use thiserror::Error;
 use tokio::runtime::Handle;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
+mod http;
 mod rpc;
 
 pub struct QuerierServerType {
     catalog: Arc<dyn Catalog>,
     database: Arc<QuerierDatabase>,
     server: QuerierServer,
+    http: http::HttpApi,
     metric_registry: Arc<Registry>,
     object_store: Arc<dyn ObjectStore>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
 }
 
 impl std::fmt::Debug for QuerierServerType {
@@ -85,12 +90,23 @@ impl ServerType for QuerierServerType {
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
-    /// Just return "not found".
+    /// The querier depends on the catalog and the object store to read parquet files from.
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        vec![
+            Arc::new(CatalogHealthCheck::new(Arc::clone(&self.catalog))),
+            Arc::new(ObjectStoreHealthCheck::new(Arc::clone(&self.object_store))),
+        ]
+    }
+
+    /// Serve the InfluxDB 1.x compatible `/query` endpoint, see [`http`].
     async fn route_http_request(
         &self,
-        _req: Request<Body>,
+        req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
-        Err(Box::new(IoxHttpError::NotFound))
+        self.http
+            .route(req)
+            .await
+            .map_err(|e| Box::new(e) as Box<dyn HttpApiErrorSource>)
     }
 
     /// Configure the gRPC services.
@@ -100,7 +116,8 @@ impl ServerType for QuerierServerType {
             builder,
             rpc::query::make_flight_server(
                 Arc::clone(&self.database),
-                self.authz.as_ref().map(Arc::clone)
+                self.authz.as_ref().map(Arc::clone),
+                self.audit_log.clone()
             )
         );
         add_service!(
@@ -111,6 +128,10 @@ impl ServerType for QuerierServerType {
             builder,
             rpc::namespace::namespace_service(Arc::clone(&self.database))
         );
+        add_service!(
+            builder,
+            rpc::debug::querier_debug_service(Arc::clone(&self.database))
+        );
         add_service!(
             builder,
             SchemaServiceServer::new(SchemaService::new(Arc::clone(&self.catalog)))
@@ -142,34 +163,6 @@ impl ServerType for QuerierServerType {
     }
 }
 
-/// Simple error struct, we're not really providing an HTTP interface for the compactor.
-#[derive(Debug)]
-pub enum IoxHttpError {
-    NotFound,
-}
-
-impl IoxHttpError {
-    fn status_code(&self) -> HttpApiErrorCode {
-        match self {
-            Self::NotFound => HttpApiErrorCode::NotFound,
-        }
-    }
-}
-
-impl Display for IoxHttpError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
-    }
-}
-
-impl std::error::Error for IoxHttpError {}
-
-impl HttpApiErrorSource for IoxHttpError {
-    fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.status_code(), self.to_string())
-    }
-}
-
 /// Arguments required to create a [`ServerType`] for the querier.
 #[derive(Debug)]
 pub struct QuerierServerTypeArgs<'a> {
@@ -193,6 +186,43 @@ pub enum Error {
         source: Box<dyn std::error::Error>,
         addr: String,
     },
+
+    #[error("audit log configuration error: {0}")]
+    AuditConfig(#[from] clap_blocks::audit::Error),
+}
+
+/// Configure the querier-specific features of the executor used by the querier:
+///
+/// - admission control of the [priority classes](iox_query::exec::admission::QueryPriority), if any of the per-class
+///   limits of `config` are set. Unset per-class limits default to [`QuerierConfig::max_concurrent_queries`], which is
+///   also the total limit.
+/// - the query result cache, if [`QuerierConfig::query_result_cache_bytes`] is set.
+pub fn configure_executor(mut exec: Executor, config: &QuerierConfig) -> Executor {
+    let class_limits = [
+        config.max_concurrent_interactive_queries,
+        config.max_concurrent_batch_queries,
+        config.max_concurrent_background_queries,
+    ];
+    if class_limits.iter().any(Option::is_some) {
+        let total = config.max_concurrent_queries();
+        let [interactive, batch, background] =
+            class_limits.map(|limit| limit.map_or(total, NonZeroUsize::get));
+        let limits = AdmissionLimits {
+            total,
+            interactive,
+            batch,
+            background,
+        };
+        info!(?limits, "enabling query admission control");
+        exec = exec.with_admission_control(limits);
+    }
+
+    if let Some(max_bytes) = config.query_result_cache_bytes {
+        info!(max_bytes, "enabling query result cache");
+        exec = exec.with_result_cache(max_bytes);
+    }
+
+    exec
 }
 
 /// Instantiate a querier server
@@ -238,6 +268,12 @@ pub async fn create_querier_server_type(
         None => None,
     };
 
+    let audit_log = args
+        .querier_config
+        .audit_config
+        .audit_log("querier", &args.metric_registry)
+        .await?;
+
     let ingester_connections = if args.querier_config.ingester_addresses.is_empty() {
         None
     } else {
@@ -251,10 +287,38 @@ pub async fn create_querier_server_type(
             ingester_addresses,
             Arc::clone(&catalog_cache),
             args.querier_config.ingester_circuit_breaker_threshold,
+            args.querier_config
+                .ingester_circuit_breaker_slow_response_threshold,
+            args.querier_config.ingester_response_cache_ttl,
             &args.trace_context_header_name,
         ))
     };
 
+    let default_quota = QueryQuota {
+        max_concurrent_queries: args.querier_config.query_quota_max_concurrent_queries,
+        queries_per_minute: args.querier_config.query_quota_queries_per_minute,
+        max_scanned_bytes: args.querier_config.query_quota_max_scanned_bytes,
+    };
+    let quota_overrides = args
+        .querier_config
+        .query_quota_overrides
+        .iter()
+        .map(|(namespace, o)| {
+            let quota = QueryQuota {
+                max_concurrent_queries: o
+                    .max_concurrent_queries
+                    .unwrap_or(default_quota.max_concurrent_queries),
+                queries_per_minute: o
+                    .queries_per_minute
+                    .unwrap_or(default_quota.queries_per_minute),
+                max_scanned_bytes: o
+                    .max_scanned_bytes
+                    .unwrap_or(default_quota.max_scanned_bytes),
+            };
+            (namespace.clone(), quota)
+        })
+        .collect();
+
     let database = Arc::new(
         QuerierDatabase::new(
             catalog_cache,
@@ -264,17 +328,26 @@ pub async fn create_querier_server_type(
             args.querier_config.max_concurrent_queries(),
             Arc::new(args.querier_config.datafusion_config),
         )
-        .await?,
+        .await?
+        .with_quotas(default_quota, quota_overrides),
     );
 
     let server = QuerierServer::new(Arc::clone(&database));
+    let http = http::HttpApi::new(
+        Arc::clone(&database),
+        authz.as_ref().map(Arc::clone),
+        args.common_state.run_config().max_http_request_size,
+        audit_log.clone(),
+    );
     Ok(Arc::new(QuerierServerType {
         catalog: args.catalog,
         database,
         server,
+        http,
         metric_registry: args.metric_registry,
         object_store: args.object_store,
         trace_collector: args.common_state.trace_collector(),
         authz,
+        audit_log,
     }))
 }
@@ -0,0 +1,196 @@
+//! QuerierDebugService gRPC implementation
+
+use generated_types::influxdata::iox::querier::v1 as proto;
+use iox_query::pruning::{PruningOutcome, PruningReport};
+use querier::{QuerierDatabase, QueryLogEntry};
+use std::sync::Arc;
+
+/// Acquire a [`QuerierDebugService`](proto::querier_debug_service_server::QuerierDebugService) gRPC service
+/// implementation.
+pub fn querier_debug_service(
+    server: Arc<QuerierDatabase>,
+) -> proto::querier_debug_service_server::QuerierDebugServiceServer<
+    impl proto::querier_debug_service_server::QuerierDebugService,
+> {
+    proto::querier_debug_service_server::QuerierDebugServiceServer::new(
+        QuerierDebugServiceImpl::new(server),
+    )
+}
+
+#[derive(Debug)]
+struct QuerierDebugServiceImpl {
+    server: Arc<QuerierDatabase>,
+}
+
+impl QuerierDebugServiceImpl {
+    pub fn new(server: Arc<QuerierDatabase>) -> Self {
+        Self { server }
+    }
+}
+
+/// Translate a query log entry to a protobuf form, if the query recorded any pruning reports
+fn entry_to_proto(entry: &QueryLogEntry) -> Option<proto::QueryPruningReports> {
+    let reports = entry.pruning_reports();
+    if reports.is_empty() {
+        return None;
+    }
+
+    Some(proto::QueryPruningReports {
+        namespace_id: entry.namespace_id.get(),
+        query_type: entry.query_type.clone(),
+        query_text: entry.query_text.to_string(),
+        trace_id: entry.trace_id.map(|id| format!("{:x}", id.0)),
+        tables: reports.iter().map(report_to_proto).collect(),
+    })
+}
+
+/// Translate a pruning report to a protobuf form
+fn report_to_proto(report: &PruningReport) -> proto::PruningReport {
+    use proto::chunk_pruning_outcome::Outcome;
+
+    proto::PruningReport {
+        table_name: report.table_name.clone(),
+        chunks: report
+            .chunks
+            .iter()
+            .map(|chunk| {
+                let (outcome, reason) = match chunk.outcome {
+                    PruningOutcome::NotPruned => (Outcome::NotPruned, String::new()),
+                    PruningOutcome::Pruned(reason) => (Outcome::Pruned, reason.to_string()),
+                    PruningOutcome::CouldNotPrune(reason) => {
+                        (Outcome::CouldNotPrune, reason.to_string())
+                    }
+                };
+
+                proto::ChunkPruningOutcome {
+                    chunk_id: chunk.chunk_id.to_string(),
+                    chunk_type: chunk.chunk_type.clone(),
+                    outcome: outcome.into(),
+                    reason,
+                }
+            })
+            .collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl proto::querier_debug_service_server::QuerierDebugService for QuerierDebugServiceImpl {
+    async fn get_pruning_reports(
+        &self,
+        request: tonic::Request<proto::GetPruningReportsRequest>,
+    ) -> Result<tonic::Response<proto::GetPruningReportsResponse>, tonic::Status> {
+        let trace_id = request.into_inner().trace_id.map(|id| id.to_lowercase());
+
+        let queries = self
+            .server
+            .query_log_entries()
+            .iter()
+            .filter(|entry| match &trace_id {
+                Some(trace_id) => entry
+                    .trace_id
+                    .map(|id| &format!("{:x}", id.0) == trace_id)
+                    .unwrap_or_default(),
+                None => true,
+            })
+            .filter_map(|entry| entry_to_proto(entry))
+            .collect();
+
+        Ok(tonic::Response::new(proto::GetPruningReportsResponse {
+            queries,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use data_types::ChunkId;
+    use generated_types::influxdata::iox::querier::v1::querier_debug_service_server::QuerierDebugService;
+    use iox_query::pruning::{ChunkPruningOutcome, NotPrunedReason, PruneReason};
+    use iox_tests::TestCatalog;
+    use querier::{create_ingester_connection_for_testing, QuerierCatalogCache};
+    use tokio::runtime::Handle;
+
+    #[tokio::test]
+    async fn test_get_pruning_reports_empty() {
+        let catalog = TestCatalog::new();
+
+        let catalog_cache = Arc::new(QuerierCatalogCache::new_testing(
+            catalog.catalog(),
+            catalog.time_provider(),
+            catalog.metric_registry(),
+            catalog.object_store(),
+            &Handle::current(),
+        ));
+        let db = Arc::new(
+            QuerierDatabase::new(
+                catalog_cache,
+                catalog.metric_registry(),
+                catalog.exec(),
+                Some(create_ingester_connection_for_testing()),
+                QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
+                Arc::new(HashMap::default()),
+            )
+            .await
+            .unwrap(),
+        );
+
+        let service = QuerierDebugServiceImpl::new(db);
+
+        let response = service
+            .get_pruning_reports(tonic::Request::new(proto::GetPruningReportsRequest {
+                trace_id: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            response,
+            proto::GetPruningReportsResponse { queries: vec![] }
+        );
+    }
+
+    #[test]
+    fn test_report_to_proto() {
+        let chunk = |id: u128, outcome: PruningOutcome| ChunkPruningOutcome {
+            chunk_id: ChunkId::new_test(id),
+            chunk_type: "parquet".to_owned(),
+            outcome,
+        };
+        let report = PruningReport {
+            table_name: "cpu".to_owned(),
+            chunks: vec![
+                chunk(1, PruningOutcome::NotPruned),
+                chunk(2, PruningOutcome::Pruned(PruneReason::TimeRange)),
+                chunk(
+                    3,
+                    PruningOutcome::CouldNotPrune(NotPrunedReason::NoExpressionOnPredicate),
+                ),
+            ],
+        };
+
+        let converted = report_to_proto(&report);
+        assert_eq!(converted.table_name, "cpu");
+        assert_eq!(
+            converted
+                .chunks
+                .iter()
+                .map(|chunk| (chunk.outcome(), chunk.reason.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (proto::chunk_pruning_outcome::Outcome::NotPruned, ""),
+                (proto::chunk_pruning_outcome::Outcome::Pruned, "time range"),
+                (
+                    proto::chunk_pruning_outcome::Outcome::CouldNotPrune,
+                    "No expression on predicate"
+                ),
+            ]
+        );
+        assert_eq!(
+            converted.chunks[0].chunk_id,
+            ChunkId::new_test(1).to_string()
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub(crate) mod debug;
 pub(crate) mod namespace;
 pub(crate) mod query;
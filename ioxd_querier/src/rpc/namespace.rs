@@ -38,6 +38,9 @@ fn namespace_to_proto(namespace: Namespace) -> proto::Namespace {
         retention_period_ns: namespace.retention_period_ns,
         max_tables: namespace.max_tables,
         max_columns_per_table: namespace.max_columns_per_table,
+        compaction_paused: namespace.compaction_paused,
+        max_write_lines_per_second: namespace.max_write_lines_per_second,
+        max_write_bytes_per_second: namespace.max_write_bytes_per_second,
     }
 }
 
@@ -94,6 +97,42 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             "use router instances to manage namespaces",
         ))
     }
+
+    async fn undelete_namespace(
+        &self,
+        _request: tonic::Request<proto::UndeleteNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::UndeleteNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_compaction(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceCompactionRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceCompactionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_write_rate_limit(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceWriteRateLimitRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceWriteRateLimitResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -186,6 +225,9 @@ mod tests {
                         retention_period_ns: TEST_RETENTION_PERIOD_NS,
                         max_tables: TEST_MAX_TABLES,
                         max_columns_per_table: TEST_MAX_COLUMNS_PER_TABLE,
+                        compaction_paused: false,
+                        max_write_lines_per_second: None,
+                        max_write_bytes_per_second: None,
                     },
                     proto::Namespace {
                         id: 2,
@@ -193,6 +235,9 @@ mod tests {
                         retention_period_ns: TEST_RETENTION_PERIOD_NS,
                         max_tables: TEST_MAX_TABLES,
                         max_columns_per_table: TEST_MAX_COLUMNS_PER_TABLE,
+                        compaction_paused: false,
+                        max_write_lines_per_second: None,
+                        max_write_bytes_per_second: None,
                     },
                 ]
             }
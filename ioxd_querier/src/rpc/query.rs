@@ -1,3 +1,4 @@
+use audit::AuditLog;
 use authz::Authorizer;
 use std::sync::Arc;
 
@@ -10,8 +11,9 @@ use querier::QuerierDatabase;
 pub fn make_flight_server(
     server: Arc<QuerierDatabase>,
     authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
 ) -> FlightServer<impl Flight> {
-    service_grpc_flight::make_server(server, authz)
+    service_grpc_flight::make_server(server, authz, audit_log)
 }
 
 pub fn make_storage_server(server: Arc<QuerierDatabase>) -> StorageServer<impl Storage> {
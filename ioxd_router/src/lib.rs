@@ -21,19 +21,26 @@ use std::{
 
 use async_trait::async_trait;
 use authz::{Authorizer, AuthorizerInstrumentation, IoxAuthorizer};
-use clap_blocks::router::RouterConfig;
+use clap_blocks::{
+    ingester_address::IngesterAddress,
+    router::{RouterConfig, SecondaryWriteMode},
+};
 use data_types::NamespaceName;
 use hashbrown::HashMap;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use ioxd_common::{
     add_service,
+    health::{CatalogHealthCheck, HealthCheck, ObjectStoreHealthCheck},
     http::error::{HttpApiError, HttpApiErrorSource},
     reexport::{
-        generated_types::influxdata::iox::{
-            catalog::v1::catalog_service_server, namespace::v1::namespace_service_server,
-            object_store::v1::object_store_service_server, schema::v1::schema_service_server,
-            table::v1::table_service_server,
+        generated_types::{
+            influxdata::iox::{
+                catalog::v1::catalog_service_server, namespace::v1::namespace_service_server,
+                object_store::v1::object_store_service_server, router::v1::write_service_server,
+                schema::v1::schema_service_server, table::v1::table_service_server,
+            },
+            opentelemetry::proto::collector::metrics::v1::metrics_service_server,
         },
         tonic::transport::Endpoint,
     },
@@ -48,18 +55,21 @@ use object_store::DynObjectStore;
 use router::{
     dml_handlers::{
         lazy_connector::LazyConnector, DmlHandler, DmlHandlerChainExt, FanOutAdaptor,
-        InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite, SchemaValidator,
+        InstrumentationDecorator, MirrorMode, Partitioner, RateLimiter, RetentionValidator,
+        RpcWrite, SchemaValidator, ShardedWrite, WriteMirror,
     },
     namespace_cache::{
-        metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache, ReadThroughCache,
-        ShardedCache,
+        metrics::InstrumentedCache, spawn_refresh_task, MemoryNamespaceCache, NamespaceCache,
+        ReadThroughCache, ShardedCache,
     },
     namespace_resolver::{
         MissingNamespaceAction, NamespaceAutocreation, NamespaceResolver, NamespaceSchemaResolver,
+        SoftDeleteCheck,
     },
     server::{
-        grpc::RpcWriteGrpcDelegate,
+        grpc::{otlp::TableNaming, RpcWriteGrpcDelegate},
         http::{
+            buckets::{BucketTenancy, BucketsApi},
             write::{
                 multi_tenant::MultiTenantRequestUnifier, single_tenant::SingleTenantRequestUnifier,
                 WriteRequestUnifier,
@@ -69,6 +79,7 @@ use router::{
         RpcWriteRouterServer,
     },
 };
+use sharder::{JumpHash, TableRegexSharder};
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
@@ -86,20 +97,29 @@ pub enum Error {
         source: Box<dyn std::error::Error>,
         addr: String,
     },
+
+    #[error("audit log configuration error: {0}")]
+    AuditConfig(#[from] clap_blocks::audit::Error),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 pub struct RpcWriteRouterServerType<D, N> {
     server: RpcWriteRouterServer<D, N>,
+    health_checks: Vec<Arc<dyn HealthCheck>>,
     shutdown: CancellationToken,
     trace_collector: Option<Arc<dyn TraceCollector>>,
 }
 
 impl<D, N> RpcWriteRouterServerType<D, N> {
-    pub fn new(server: RpcWriteRouterServer<D, N>, common_state: &CommonServerState) -> Self {
+    pub fn new(
+        server: RpcWriteRouterServer<D, N>,
+        health_checks: Vec<Arc<dyn HealthCheck>>,
+        common_state: &CommonServerState,
+    ) -> Self {
         Self {
             server,
+            health_checks,
             shutdown: CancellationToken::new(),
             trace_collector: common_state.trace_collector(),
         }
@@ -115,8 +135,8 @@ impl<D, N> std::fmt::Debug for RpcWriteRouterServerType<D, N> {
 #[async_trait]
 impl<D, N> ServerType for RpcWriteRouterServerType<D, N>
 where
-    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()> + 'static,
-    N: NamespaceResolver + 'static,
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()> + Clone + 'static,
+    N: NamespaceResolver + Clone + 'static,
 {
     fn name(&self) -> &str {
         "rpc_write_router"
@@ -132,6 +152,11 @@ where
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
+    /// The router depends on the catalog and, for the object store service, on the object store.
+    fn health_checks(&self) -> Vec<Arc<dyn HealthCheck>> {
+        self.health_checks.clone()
+    }
+
     /// Dispatches `req` to the router [`HttpDelegate`] delegate.
     ///
     /// [`HttpDelegate`]: router::server::http::HttpDelegate
@@ -176,6 +201,16 @@ where
             builder,
             table_service_server::TableServiceServer::new(self.server.grpc().table_service())
         );
+        add_service!(
+            builder,
+            write_service_server::WriteServiceServer::new(self.server.grpc().write_service())
+        );
+        add_service!(
+            builder,
+            metrics_service_server::MetricsServiceServer::new(
+                self.server.grpc().otlp_metrics_service()
+            )
+        );
         serve_builder!(builder);
 
         Ok(())
@@ -207,7 +242,16 @@ impl std::error::Error for IoxHttpErrorAdaptor {}
 
 impl HttpApiErrorSource for IoxHttpErrorAdaptor {
     fn to_http_api_error(&self) -> HttpApiError {
-        HttpApiError::new(self.0.as_status_code(), self.to_string())
+        let err = HttpApiError::new(self.0.as_status_code(), self.to_string()).with_line_errors(
+            self.0
+                .line_errors()
+                .iter()
+                .map(|v| (v.line, v.error.clone())),
+        );
+        match self.0.retry_after() {
+            Some(retry_after) => err.with_retry_after(retry_after),
+            None => err,
+        }
     }
 }
 
@@ -220,36 +264,86 @@ pub async fn create_router_server_type(
     router_config: &RouterConfig,
     trace_context_header_name: String,
 ) -> Result<Arc<dyn ServerType>> {
-    let ingester_connections = router_config.ingester_addresses.iter().map(|addr| {
-        let addr = addr.to_string();
-        let endpoint = Endpoint::from_shared(hyper::body::Bytes::from(addr.clone()))
-            .expect("invalid ingester connection address");
-        (
-            LazyConnector::new(
-                endpoint,
-                router_config.rpc_write_timeout_seconds,
-                router_config.rpc_write_max_outgoing_bytes,
-                trace_context_header_name.clone(),
-            ),
-            addr,
-        )
-    });
+    let ingester_connections = |addresses: &[IngesterAddress]| {
+        addresses
+            .iter()
+            .map(|addr| {
+                let addr = addr.to_string();
+                let endpoint = Endpoint::from_shared(hyper::body::Bytes::from(addr.clone()))
+                    .expect("invalid ingester connection address");
+                (
+                    LazyConnector::new(
+                        endpoint,
+                        router_config.rpc_write_timeout_seconds,
+                        router_config.rpc_write_max_outgoing_bytes,
+                        trace_context_header_name.clone(),
+                    ),
+                    addr,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
 
     // Initialise the DML handler that sends writes to the ingester using the RPC write path.
-    let rpc_writer = RpcWrite::new(
-        ingester_connections,
+    let rpc_writer = Arc::new(RpcWrite::new(
+        ingester_connections(&router_config.ingester_addresses),
         router_config.rpc_write_replicas,
         &metrics,
         router_config.rpc_write_health_error_window_seconds,
-    );
-    let rpc_writer = InstrumentationDecorator::new("rpc_writer", &metrics, rpc_writer);
+    ));
+
+    // # Table routing
+    //
+    // Tables matching an ingester table route are written to the dedicated
+    // ingesters of that route, and all other tables to the ingesters above.
+    let table_routes = router_config
+        .ingester_table_routes
+        .iter()
+        .map(|route| {
+            let rpc_writer = RpcWrite::new(
+                ingester_connections(route.ingester_addresses()),
+                router_config.rpc_write_replicas,
+                &metrics,
+                router_config.rpc_write_health_error_window_seconds,
+            );
+            (route.table_regex().clone(), [Arc::new(rpc_writer)])
+        })
+        .collect::<Vec<_>>();
+    let sharder = TableRegexSharder::new(table_routes, JumpHash::new([rpc_writer]));
+    let rpc_writer =
+        InstrumentationDecorator::new("rpc_writer", &metrics, ShardedWrite::new(sharder));
+
+    // # Write mirror
+    //
+    // Optionally mirror all writes to a secondary set of ingesters, using the
+    // same RPC write configuration as the primary ingesters.
+    let secondary_rpc_writer =
+        (!router_config.secondary_ingester_addresses.is_empty()).then(|| {
+            let rpc_writer = RpcWrite::new(
+                ingester_connections(&router_config.secondary_ingester_addresses),
+                router_config.rpc_write_replicas,
+                &metrics,
+                router_config.rpc_write_health_error_window_seconds,
+            );
+            InstrumentationDecorator::new("secondary_rpc_writer", &metrics, rpc_writer)
+        });
+    let mirror_mode = match router_config.secondary_write_mode {
+        SecondaryWriteMode::FireAndForget => MirrorMode::FireAndForget,
+        SecondaryWriteMode::Quorum => MirrorMode::Quorum,
+    };
+    let rpc_writer = WriteMirror::new(rpc_writer, secondary_rpc_writer, mirror_mode, &metrics);
 
     // # Namespace cache
     //
     // Initialise an instrumented namespace cache to be shared with the schema
     // validator, and namespace auto-creator that reports cache hit/miss/update
     // metrics.
-    let ns_cache = Arc::new(ReadThroughCache::new(
+    //
+    // Cached schemas are optionally refreshed once they exceed the configured
+    // TTL. Negative caching of nonexistent namespaces is only enabled when
+    // they are rejected, as an auto-created namespace must be visible
+    // immediately.
+    let mut ns_cache = ReadThroughCache::new(
         Arc::new(InstrumentedCache::new(
             Arc::new(ShardedCache::new(
                 std::iter::repeat_with(|| Arc::new(MemoryNamespaceCache::default())).take(10),
@@ -257,12 +351,28 @@ pub async fn create_router_server_type(
             &metrics,
         )),
         Arc::clone(&catalog),
-    ));
+    );
+    if let Some(ttl) = router_config.namespace_cache_ttl_seconds {
+        ns_cache = ns_cache.with_ttl(ttl);
+    }
+    if let Some(ttl) = router_config
+        .namespace_cache_negative_ttl_seconds
+        .filter(|_| !router_config.namespace_autocreation_enabled)
+    {
+        ns_cache = ns_cache.with_negative_ttl(ttl);
+    }
+    let ns_cache = Arc::new(ns_cache);
 
     pre_warm_schema_cache(&ns_cache, &*catalog)
         .await
         .expect("namespace cache pre-warming failed");
 
+    // Optionally keep the cache converged with the schema changes made by
+    // other routers. The refresh task stops once the cache is dropped.
+    if let Some(interval) = router_config.namespace_cache_refresh_interval_seconds {
+        spawn_refresh_task(&ns_cache, Arc::clone(&catalog), interval);
+    }
+
     // # Schema validator
     //
     // Initialise and instrument the schema validator
@@ -271,6 +381,18 @@ pub async fn create_router_server_type(
     let schema_validator =
         InstrumentationDecorator::new("schema_validator", &metrics, schema_validator);
 
+    // # Rate limiter
+    //
+    // Reject writes exceeding the write rate limits of their namespace, protecting the ingesters
+    // from a single noisy namespace
+    let rate_limiter = RateLimiter::new(
+        Arc::clone(&catalog),
+        router_config.namespace_rate_limit_refresh_seconds,
+        &metrics,
+    )
+    .await;
+    let rate_limiter = InstrumentationDecorator::new("rate_limiter", &metrics, rate_limiter);
+
     // # Retention validator
     //
     // Add a retention validator into handler stack to reject data outside the retention period
@@ -306,6 +428,13 @@ pub async fn create_router_server_type(
             }
         },
     );
+
+    // Reject writes to soft-deleted namespaces with a dedicated error, rather
+    // than reporting them as not existing.
+    let namespace_resolver = Arc::new(SoftDeleteCheck::new(
+        namespace_resolver,
+        Arc::clone(&catalog),
+    ));
     //
     ////////////////////////////////////////////////////////////////////////////
 
@@ -315,7 +444,8 @@ pub async fn create_router_server_type(
     // # Handler stack
     //
     // Build the chain of DML handlers that forms the request processing pipeline
-    let handler_stack = retention_validator
+    let handler_stack = rate_limiter
+        .and_then(retention_validator)
         .and_then(schema_validator)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
@@ -331,14 +461,18 @@ pub async fn create_router_server_type(
         ));
 
     // Record the overall request handling latency
-    let handler_stack = InstrumentationDecorator::new("request", &metrics, handler_stack);
+    //
+    // The handler stack is shared by the HTTP and gRPC write APIs.
+    let handler_stack = Arc::new(InstrumentationDecorator::new(
+        "request",
+        &metrics,
+        handler_stack,
+    ));
 
-    // Initialize the HTTP API delegate
-    let write_request_unifier: Result<Box<dyn WriteRequestUnifier>> = match (
-        router_config.single_tenant_deployment,
-        &router_config.authz_address,
-    ) {
-        (true, Some(addr)) => {
+    // Connect to the authz service, if configured, which authorises requests
+    // to the HTTP and gRPC write APIs and the buckets API.
+    let authz = match &router_config.authz_address {
+        Some(addr) => {
             let authz = IoxAuthorizer::connect_lazy(addr.clone())
                 .map(|c| {
                     Arc::new(AuthorizerInstrumentation::new(&metrics, c)) as Arc<dyn Authorizer>
@@ -349,8 +483,30 @@ pub async fn create_router_server_type(
                 })?;
             authz.probe().await.expect("Authz connection test failed.");
 
-            Ok(Box::new(SingleTenantRequestUnifier::new(authz)))
+            Some(authz)
         }
+        None => None,
+    };
+
+    // Record every write served by the HTTP and gRPC write APIs, if
+    // configured.
+    let audit_log = router_config
+        .audit_config
+        .audit_log("router", &metrics)
+        .await?;
+
+    // Initialize the HTTP API delegate
+    //
+    // Buckets of the V2 buckets API map onto namespaces in the same way as
+    // the V2 write API does.
+    let (write_request_unifier, bucket_tenancy): (Box<dyn WriteRequestUnifier>, _) = match (
+        router_config.single_tenant_deployment,
+        &authz,
+    ) {
+        (true, Some(authz)) => (
+            Box::new(SingleTenantRequestUnifier::new(Arc::clone(authz))),
+            BucketTenancy::SingleTenant,
+        ),
         (true, None) => {
             // Single tenancy was requested, but no auth was provided - the
             // router's clap flag parse configuration should not allow this
@@ -358,30 +514,64 @@ pub async fn create_router_server_type(
             // never reach here.
             unreachable!("INFLUXDB_IOX_SINGLE_TENANCY is set, but could not create an authz service. Check the INFLUXDB_IOX_AUTHZ_ADDR")
         }
-        (false, None) => Ok(Box::<MultiTenantRequestUnifier>::default()),
-        (false, Some(_)) => {
-            // As above, this combination should be prevented by the
-            // router's clap flag parse configuration.
-            unreachable!("INFLUXDB_IOX_AUTHZ_ADDR is set, but authz only exists for single_tenancy. Check the INFLUXDB_IOX_SINGLE_TENANCY")
-        }
+        (false, authz) => (
+            Box::new(MultiTenantRequestUnifier::new(authz.clone())),
+            BucketTenancy::MultiTenant,
+        ),
     };
-    let http = HttpDelegate::new(
+    let mut http = HttpDelegate::new(
         common_state.run_config().max_http_request_size,
         router_config.http_request_limit,
-        namespace_resolver,
-        handler_stack,
+        Arc::clone(&namespace_resolver),
+        Arc::clone(&handler_stack),
         &metrics,
-        write_request_unifier?,
-    );
+        write_request_unifier,
+    )
+    .with_max_decompressed_request_bytes(
+        router_config
+            .max_http_request_decompressed_size
+            .unwrap_or(common_state.run_config().max_http_request_size),
+    )
+    .with_buckets(BucketsApi::new(
+        Arc::clone(&catalog),
+        bucket_tenancy,
+        authz.clone(),
+    ));
+    if let Some(audit_log) = audit_log.clone() {
+        http = http.with_audit_log(audit_log);
+    }
 
     // Initialize the gRPC API delegate that creates the services relevant to the RPC
     // write router path and use it to create the relevant `RpcWriteRouterServer` and
     // `RpcWriteRouterServerType`.
-    let grpc = RpcWriteGrpcDelegate::new(catalog, object_store);
+    let otlp_table_naming = match router_config.otlp_metrics_single_table.clone() {
+        Some(table) => TableNaming::Single(table),
+        None => TableNaming::PerMetric {
+            prefix: router_config.otlp_metrics_table_prefix.clone(),
+        },
+    };
+    let health_checks: Vec<Arc<dyn HealthCheck>> = vec![
+        Arc::new(CatalogHealthCheck::new(Arc::clone(&catalog))),
+        Arc::new(ObjectStoreHealthCheck::new(Arc::clone(&object_store))),
+    ];
+    let mut grpc =
+        RpcWriteGrpcDelegate::new(catalog, object_store, handler_stack, namespace_resolver)
+            .with_otlp_table_naming(otlp_table_naming)
+            .with_undelete_observer(Arc::clone(&ns_cache) as _);
+    if let Some(authz) = authz {
+        grpc = grpc.with_authz(authz);
+    }
+    if let Some(audit_log) = audit_log {
+        grpc = grpc.with_audit_log(audit_log);
+    }
 
     let router_server =
         RpcWriteRouterServer::new(http, grpc, metrics, common_state.trace_collector());
-    let server_type = Arc::new(RpcWriteRouterServerType::new(router_server, common_state));
+    let server_type = Arc::new(RpcWriteRouterServerType::new(
+        router_server,
+        health_checks,
+        common_state,
+    ));
     Ok(server_type)
 }
 
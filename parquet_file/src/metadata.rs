@@ -91,7 +91,7 @@ use bytes::Bytes;
 use data_types::{
     ColumnId, ColumnSet, ColumnSummary, CompactionLevel, InfluxDbType, NamespaceId,
     ParquetFileParams, PartitionHashId, PartitionId, PartitionKey, StatValues, Statistics, TableId,
-    Timestamp,
+    TagRange, TagRanges, Timestamp,
 };
 use generated_types::influxdata::iox::ingester::v1 as proto;
 use iox_time::Time;
@@ -465,6 +465,29 @@ impl IoxMetadata {
             .read_statistics(&schema)
             .expect("invalid statistics");
         let columns: Vec<_> = stats.iter().map(|v| column_id_map(&v.name)).collect();
+
+        // Record the min/max values of the tag columns, allowing files to be
+        // pruned by tag predicates without reading this metadata.
+        let tag_ranges = TagRanges::new(
+            stats
+                .iter()
+                .filter(|v| v.influxdb_type == InfluxDbType::Tag)
+                .filter_map(|v| match &v.stats {
+                    Statistics::String(StatValues {
+                        min: Some(min),
+                        max: Some(max),
+                        ..
+                    }) => Some((
+                        column_id_map(&v.name),
+                        TagRange {
+                            min: min.clone(),
+                            max: max.clone(),
+                        },
+                    )),
+                    _ => None,
+                }),
+        );
+
         let time_summary = stats
             .into_iter()
             .find(|v| v.name == TIME_COLUMN_NAME)
@@ -497,6 +520,7 @@ impl IoxMetadata {
             created_at: Timestamp::from(self.creation_timestamp),
             column_set: ColumnSet::new(columns),
             max_l0_created_at: Timestamp::from(self.max_l0_created_at),
+            tag_ranges: Some(tag_ranges),
         }
     }
 
@@ -1,11 +1,13 @@
 use std::{collections::HashMap, sync::Arc};
 
 use arrow::{
-    array::{ArrayRef, StringArray, TimestampNanosecondArray},
+    array::{ArrayRef, DictionaryArray, StringArray, TimestampNanosecondArray},
+    datatypes::Int32Type,
     record_batch::RecordBatch,
 };
 use data_types::{
-    ColumnId, CompactionLevel, NamespaceId, PartitionId, TableId, Timestamp, TransitionPartitionId,
+    ColumnId, CompactionLevel, NamespaceId, PartitionId, TableId, TagRange, TagRanges, Timestamp,
+    TransitionPartitionId,
 };
 use datafusion_util::{unbounded_memory_pool, MemoryStream};
 use iox_time::Time;
@@ -357,6 +359,7 @@ async fn test_derive_parquet_file_params() {
     // A representative IOx data sample (with a time column, an invariant upheld
     // in the IOx write path)
     let data = vec![
+        to_tag_array(&["uk", "us", "de"]),
         to_string_array(&["bananas", "platanos", "manzana"]),
         to_timestamp_array(&[
             // NOTE: not ordered to ensure min/max extracted, not head/tail
@@ -387,6 +390,7 @@ async fn test_derive_parquet_file_params() {
     // Build a schema that contains the IOx metadata, ensuring it is correctly
     // populated in the final parquet file's metadata.
     let schema = SchemaBuilder::new()
+        .tag("region")
         .influx_field("some_field", InfluxFieldType::String)
         .timestamp()
         .build()
@@ -414,6 +418,7 @@ async fn test_derive_parquet_file_params() {
     let column_id_map: HashMap<String, ColumnId> = HashMap::from([
         ("some_field".into(), ColumnId::new(1)),
         ("time".into(), ColumnId::new(2)),
+        ("region".into(), ColumnId::new(3)),
     ]);
     let catalog_data = meta.to_parquet_file(
         partition_id,
@@ -437,6 +442,18 @@ async fn test_derive_parquet_file_params() {
     assert_eq!(catalog_data.min_time, Timestamp::new(1646917692000000000));
     assert_eq!(catalog_data.max_time, Timestamp::new(1653311292000000000));
     assert_eq!(catalog_data.max_l0_created_at, Timestamp::new(1234));
+
+    // Only tag columns have their ranges recorded.
+    assert_eq!(
+        catalog_data.tag_ranges,
+        Some(TagRanges::new([(
+            ColumnId::new(3),
+            TagRange {
+                min: "de".to_string(),
+                max: "us".to_string(),
+            }
+        )]))
+    );
 }
 
 fn to_string_array(strs: &[&str]) -> ArrayRef {
@@ -444,6 +461,11 @@ fn to_string_array(strs: &[&str]) -> ArrayRef {
     Arc::new(array)
 }
 
+fn to_tag_array(strs: &[&str]) -> ArrayRef {
+    let array: DictionaryArray<Int32Type> = strs.iter().copied().collect();
+    Arc::new(array)
+}
+
 fn to_timestamp_array(timestamps: &[i64]) -> ArrayRef {
     let array: TimestampNanosecondArray = timestamps.iter().map(|v| Some(*v)).collect();
     Arc::new(array)
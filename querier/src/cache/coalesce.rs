@@ -0,0 +1,387 @@
+//! Coalescing of concurrent, identical catalog requests.
+use data_types::{Column, ColumnMetadata, Namespace, NamespaceId, ParquetFile, Table, TableId};
+use futures::{
+    future::{BoxFuture, Shared},
+    FutureExt, TryFutureExt,
+};
+use iox_catalog::interface::{Catalog, Error, SoftDeletedRows};
+use metric::{Metric, U64Counter};
+use parking_lot::Mutex;
+use std::{collections::HashMap, fmt::Debug, future::Future, hash::Hash, sync::Arc};
+
+/// Result of a coalesced catalog request.
+///
+/// The error is wrapped into an [`Arc`] because it is shared between all callers that joined the request.
+pub type Result<T, E = Arc<Error>> = std::result::Result<T, E>;
+
+/// Catalog access layer that coalesces concurrent, identical read requests.
+///
+/// Dashboards tend to fire many queries for the same namespace and table at the same time. Every one of them that
+/// misses (or invalidates) a querier cache would otherwise issue its own catalog request. This layer keeps track of
+/// all requests that are currently in-flight. A caller that asks for the same data as an in-flight request does NOT
+/// issue a new catalog request but waits for the running one and gets a copy of its result (including errors).
+///
+/// Results are NOT kept around once the request finished, i.e. this is not a cache. Requests that start after an
+/// identical request finished always hit the catalog, so callers never observe data that is older than what they
+/// would get from the catalog directly.
+#[derive(Debug)]
+pub struct CoalescingCatalog {
+    catalog: Arc<dyn Catalog>,
+    namespace_by_name: Coalescer<Arc<str>, Option<Namespace>>,
+    namespaces: Coalescer<(), Vec<Namespace>>,
+    tables_by_namespace: Coalescer<NamespaceId, Vec<Table>>,
+    columns_by_namespace: Coalescer<NamespaceId, Vec<Column>>,
+    column_metadata_by_namespace: Coalescer<NamespaceId, Vec<ColumnMetadata>>,
+    parquet_files_by_table: Coalescer<TableId, Vec<ParquetFile>>,
+}
+
+impl CoalescingCatalog {
+    /// Create new coalescing layer on top of the given catalog.
+    pub fn new(catalog: Arc<dyn Catalog>, metric_registry: &metric::Registry) -> Self {
+        let metric: Metric<U64Counter> = metric_registry.register_metric(
+            "querier_catalog_requests",
+            "number of catalog read requests issued by the querier or coalesced into an in-flight request",
+        );
+
+        Self {
+            catalog,
+            namespace_by_name: Coalescer::new("namespace_get_by_name", &metric),
+            namespaces: Coalescer::new("namespace_list", &metric),
+            tables_by_namespace: Coalescer::new("table_list_by_namespace_id", &metric),
+            columns_by_namespace: Coalescer::new("column_list_by_namespace_id", &metric),
+            column_metadata_by_namespace: Coalescer::new(
+                "column_list_metadata_by_namespace_id",
+                &metric,
+            ),
+            parquet_files_by_table: Coalescer::new("parquet_list_by_table_not_to_delete", &metric),
+        }
+    }
+
+    /// Underlying catalog, for requests that are not coalesced.
+    pub fn catalog(&self) -> Arc<dyn Catalog> {
+        Arc::clone(&self.catalog)
+    }
+
+    /// Get non-deleted namespace by name.
+    pub async fn namespace_by_name(&self, name: Arc<str>) -> Result<Option<Namespace>> {
+        let catalog = self.catalog();
+        let name_captured = Arc::clone(&name);
+        self.namespace_by_name
+            .get(name, move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .get_by_name(&name_captured, SoftDeletedRows::ExcludeDeleted)
+                    .await
+            })
+            .await
+    }
+
+    /// List all non-deleted namespaces.
+    pub async fn namespaces(&self) -> Result<Vec<Namespace>> {
+        let catalog = self.catalog();
+        self.namespaces
+            .get((), move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .list(SoftDeletedRows::ExcludeDeleted)
+                    .await
+            })
+            .await
+    }
+
+    /// List all tables of the given namespace.
+    pub async fn tables_by_namespace(&self, namespace_id: NamespaceId) -> Result<Vec<Table>> {
+        let catalog = self.catalog();
+        self.tables_by_namespace
+            .get(namespace_id, move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .tables()
+                    .list_by_namespace_id(namespace_id)
+                    .await
+            })
+            .await
+    }
+
+    /// List all columns of the given namespace.
+    pub async fn columns_by_namespace(&self, namespace_id: NamespaceId) -> Result<Vec<Column>> {
+        let catalog = self.catalog();
+        self.columns_by_namespace
+            .get(namespace_id, move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .columns()
+                    .list_by_namespace_id(namespace_id)
+                    .await
+            })
+            .await
+    }
+
+    /// List the metadata of all columns of the given namespace that have metadata attached.
+    pub async fn column_metadata_by_namespace(
+        &self,
+        namespace_id: NamespaceId,
+    ) -> Result<Vec<ColumnMetadata>> {
+        let catalog = self.catalog();
+        self.column_metadata_by_namespace
+            .get(namespace_id, move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .columns()
+                    .list_metadata_by_namespace_id(namespace_id)
+                    .await
+            })
+            .await
+    }
+
+    /// List all parquet files of the given table that are not marked for deletion.
+    pub async fn parquet_files_by_table(&self, table_id: TableId) -> Result<Vec<ParquetFile>> {
+        let catalog = self.catalog();
+        self.parquet_files_by_table
+            .get(table_id, move || async move {
+                catalog
+                    .repositories()
+                    .await
+                    .parquet_files()
+                    .list_by_table_not_to_delete(table_id)
+                    .await
+            })
+            .await
+    }
+}
+
+type SharedRequest<V> = Shared<BoxFuture<'static, Result<V>>>;
+
+/// Request that is currently running.
+struct InFlight<V> {
+    /// Unique tag, used to make sure that a finished request does not remove a newer request for the same key.
+    tag: u64,
+
+    /// Shared future of the request.
+    fut: SharedRequest<V>,
+}
+
+/// State of a [`Coalescer`].
+struct CoalescerState<K, V> {
+    in_flight: HashMap<K, InFlight<V>>,
+    tag_counter: u64,
+}
+
+/// Coalesces concurrent requests for the same key into a single request.
+struct Coalescer<K, V> {
+    op: &'static str,
+    state: Mutex<CoalescerState<K, V>>,
+    issued: U64Counter,
+    coalesced: U64Counter,
+}
+
+impl<K, V> Debug for Coalescer<K, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Coalescer")
+            .field("op", &self.op)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K, V> Coalescer<K, V>
+where
+    K: Clone + Eq + Hash + Send,
+    V: Clone + Send + Sync + 'static,
+{
+    fn new(op: &'static str, metric: &Metric<U64Counter>) -> Self {
+        Self {
+            op,
+            state: Mutex::new(CoalescerState {
+                in_flight: HashMap::new(),
+                tag_counter: 0,
+            }),
+            issued: metric.recorder(&[("op", op), ("status", "issued")]),
+            coalesced: metric.recorder(&[("op", op), ("status", "coalesced")]),
+        }
+    }
+
+    /// Get result for `k`.
+    ///
+    /// Joins the in-flight request for `k` if there is one, otherwise issues a new request created by `f`.
+    async fn get<F, Fut>(&self, k: K, f: F) -> Result<V>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<V, Error>> + Send + 'static,
+    {
+        let (tag, fut) = {
+            let mut state = self.state.lock();
+
+            match state.in_flight.get(&k) {
+                Some(in_flight) => {
+                    self.coalesced.inc(1);
+                    (in_flight.tag, in_flight.fut.clone())
+                }
+                None => {
+                    self.issued.inc(1);
+
+                    let tag = state.tag_counter;
+                    state.tag_counter += 1;
+
+                    let fut = f().map_err(Arc::new).boxed().shared();
+                    state.in_flight.insert(
+                        k.clone(),
+                        InFlight {
+                            tag,
+                            fut: fut.clone(),
+                        },
+                    );
+                    (tag, fut)
+                }
+            }
+        };
+
+        // If all callers are cancelled, the request stays registered and the next caller for the same key picks it
+        // up. Whoever observes the result first removes the request so that later calls hit the catalog again.
+        let res = fut.await;
+
+        let mut state = self.state.lock();
+        if state
+            .in_flight
+            .get(&k)
+            .map(|in_flight| in_flight.tag == tag)
+            .unwrap_or_default()
+        {
+            state.in_flight.remove(&k);
+        }
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::poll;
+    use metric::{Attributes, Observation};
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        task::Poll,
+    };
+
+    #[tokio::test]
+    async fn test_coalesce_concurrent_requests() {
+        let (coalescer, metric_registry) = coalescer();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (res_1, res_2) = futures::join!(
+            coalescer.get(1, request(&calls, Ok(1))),
+            coalescer.get(1, request(&calls, Ok(2))),
+        );
+        assert_eq!(res_1.unwrap(), 1);
+        assert_eq!(res_2.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_requests(&metric_registry, 1, 1);
+
+        // finished requests are not cached
+        let res = coalescer.get(1, request(&calls, Ok(3))).await;
+        assert_eq!(res.unwrap(), 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_requests(&metric_registry, 2, 1);
+    }
+
+    #[tokio::test]
+    async fn test_different_keys() {
+        let (coalescer, metric_registry) = coalescer();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (res_1, res_2) = futures::join!(
+            coalescer.get(1, request(&calls, Ok(1))),
+            coalescer.get(2, request(&calls, Ok(2))),
+        );
+        assert_eq!(res_1.unwrap(), 1);
+        assert_eq!(res_2.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_requests(&metric_registry, 2, 0);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared() {
+        let (coalescer, _metric_registry) = coalescer();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let (res_1, res_2) = futures::join!(
+            coalescer.get(
+                1,
+                request(
+                    &calls,
+                    Err(Error::NamespaceNotFoundByName {
+                        name: String::from("ns")
+                    })
+                )
+            ),
+            coalescer.get(1, request(&calls, Ok(2))),
+        );
+        let err_1 = res_1.unwrap_err();
+        let err_2 = res_2.unwrap_err();
+        assert!(Arc::ptr_eq(&err_1, &err_2));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // errors are not cached either
+        let res = coalescer.get(1, request(&calls, Ok(3))).await;
+        assert_eq!(res.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_request_is_picked_up() {
+        let (coalescer, metric_registry) = coalescer();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let mut fut = Box::pin(coalescer.get(1, request(&calls, Ok(1))));
+        assert!(matches!(poll!(&mut fut), Poll::Pending));
+        drop(fut);
+
+        let res = coalescer.get(1, request(&calls, Ok(2))).await;
+        assert_eq!(res.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_requests(&metric_registry, 1, 1);
+
+        let res = coalescer.get(1, request(&calls, Ok(3))).await;
+        assert_eq!(res.unwrap(), 3);
+    }
+
+    fn coalescer() -> (Coalescer<u8, u8>, metric::Registry) {
+        let metric_registry = metric::Registry::new();
+        let metric = metric_registry.register_metric("querier_catalog_requests", "test");
+        (Coalescer::new("test", &metric), metric_registry)
+    }
+
+    /// Request that yields once (so concurrent callers can join it) and counts how often it was issued.
+    fn request(
+        calls: &Arc<AtomicUsize>,
+        res: Result<u8, Error>,
+    ) -> impl FnOnce() -> BoxFuture<'static, Result<u8, Error>> {
+        let calls = Arc::clone(calls);
+        move || {
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                tokio::task::yield_now().await;
+                res
+            }
+            .boxed()
+        }
+    }
+
+    fn assert_requests(metric_registry: &metric::Registry, issued: u64, coalesced: u64) {
+        for (status, expected) in [("issued", issued), ("coalesced", coalesced)] {
+            let observation = metric_registry
+                .get_instrument::<Metric<U64Counter>>("querier_catalog_requests")
+                .unwrap()
+                .get_observer(&Attributes::from(&[("op", "test"), ("status", status)]))
+                .unwrap()
+                .observe();
+            assert_eq!(observation, Observation::U64Counter(expected), "{status}");
+        }
+    }
+}
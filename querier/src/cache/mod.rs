@@ -9,10 +9,12 @@ use std::sync::Arc;
 use tokio::runtime::Handle;
 
 use self::{
-    namespace::NamespaceCache, object_store::ObjectStoreCache, parquet_file::ParquetFileCache,
-    partition::PartitionCache, projected_schema::ProjectedSchemaCache, ram::RamSize,
+    coalesce::CoalescingCatalog, namespace::NamespaceCache, object_store::ObjectStoreCache,
+    parquet_file::ParquetFileCache, partition::PartitionCache,
+    projected_schema::ProjectedSchemaCache, ram::RamSize,
 };
 
+pub mod coalesce;
 pub mod namespace;
 pub mod object_store;
 pub mod parquet_file;
@@ -29,6 +31,9 @@ pub struct CatalogCache {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
 
+    /// Catalog access that coalesces concurrent, identical requests.
+    coalescing_catalog: Arc<CoalescingCatalog>,
+
     /// Partition cache.
     partition_cache: PartitionCache,
 
@@ -120,6 +125,11 @@ impl CatalogCache {
             Arc::clone(&metric_registry),
         ));
 
+        let coalescing_catalog = Arc::new(CoalescingCatalog::new(
+            Arc::clone(&catalog),
+            &metric_registry,
+        ));
+
         let partition_cache = PartitionCache::new(
             Arc::clone(&catalog),
             backoff_config.clone(),
@@ -129,7 +139,7 @@ impl CatalogCache {
             testing,
         );
         let namespace_cache = NamespaceCache::new(
-            Arc::clone(&catalog),
+            Arc::clone(&coalescing_catalog),
             backoff_config.clone(),
             Arc::clone(&time_provider),
             &metric_registry,
@@ -138,7 +148,7 @@ impl CatalogCache {
             testing,
         );
         let parquet_file_cache = ParquetFileCache::new(
-            Arc::clone(&catalog),
+            Arc::clone(&coalescing_catalog),
             backoff_config.clone(),
             Arc::clone(&time_provider),
             &metric_registry,
@@ -162,6 +172,7 @@ impl CatalogCache {
 
         Self {
             catalog,
+            coalescing_catalog,
             partition_cache,
             namespace_cache,
             parquet_file_cache,
@@ -177,6 +188,11 @@ impl CatalogCache {
         Arc::clone(&self.catalog)
     }
 
+    /// Get catalog access that coalesces concurrent, identical requests.
+    pub(crate) fn coalescing_catalog(&self) -> &CoalescingCatalog {
+        &self.coalescing_catalog
+    }
+
     /// Get underlying metric registry.
     pub(crate) fn metric_registry(&self) -> Arc<metric::Registry> {
         Arc::clone(&self.metric_registry)
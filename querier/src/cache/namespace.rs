@@ -17,7 +17,6 @@ use data_types::{
     partition_template::TablePartitionTemplateOverride, Column, ColumnId, Namespace, NamespaceId,
     Table, TableId,
 };
-use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use iox_time::TimeProvider;
 use schema::{InfluxColumnType, Schema, SchemaBuilder};
 use std::{
@@ -29,7 +28,7 @@ use std::{
 use tokio::runtime::Handle;
 use trace::span::Span;
 
-use super::ram::RamSize;
+use super::{coalesce::CoalescingCatalog, ram::RamSize};
 
 /// Duration to keep existing namespaces.
 pub const TTL_EXISTING: Duration = Duration::from_secs(300);
@@ -78,7 +77,7 @@ pub struct NamespaceCache {
 impl NamespaceCache {
     /// Create new empty cache.
     pub fn new(
-        catalog: Arc<dyn Catalog>,
+        catalog: Arc<CoalescingCatalog>,
         backoff_config: BackoffConfig,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: &metric::Registry,
@@ -92,37 +91,22 @@ impl NamespaceCache {
 
             async move {
                 let namespace = Backoff::new(&backoff_config)
-                    .retry_all_errors("get namespace", || async {
-                        catalog
-                            .repositories()
-                            .await
-                            .namespaces()
-                            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
-                            .await
+                    .retry_all_errors("get namespace", || {
+                        catalog.namespace_by_name(Arc::clone(&namespace_name))
                     })
                     .await
                     .expect("retry forever")?;
 
                 let tables = Backoff::new(&backoff_config)
-                    .retry_all_errors("get namespace tables", || async {
-                        catalog
-                            .repositories()
-                            .await
-                            .tables()
-                            .list_by_namespace_id(namespace.id)
-                            .await
+                    .retry_all_errors("get namespace tables", || {
+                        catalog.tables_by_namespace(namespace.id)
                     })
                     .await
                     .expect("retry forever");
 
                 let columns = Backoff::new(&backoff_config)
-                    .retry_all_errors("get namespace columns", || async {
-                        catalog
-                            .repositories()
-                            .await
-                            .columns()
-                            .list_by_namespace_id(namespace.id)
-                            .await
+                    .retry_all_errors("get namespace columns", || {
+                        catalog.columns_by_namespace(namespace.id)
                     })
                     .await
                     .expect("retry forever");
@@ -236,6 +220,8 @@ pub struct CachedTable {
     pub column_id_map_rev: HashMap<Arc<str>, ColumnId>,
     pub primary_key_column_ids: Box<[ColumnId]>,
     pub partition_template: TablePartitionTemplateOverride,
+    /// Retention period override of the table, `None` if the namespace retention period applies.
+    pub retention_period: Option<Duration>,
 }
 
 impl CachedTable {
@@ -280,6 +266,9 @@ impl CachedTable {
             column_id_map_rev,
             primary_key_column_ids,
             partition_template: table.partition_template,
+            retention_period: table
+                .retention_period_ns
+                .map(|retention| Duration::from_nanos(retention as u64)),
         }
     }
 
@@ -357,7 +346,8 @@ impl CachedNamespace {
 #[cfg(test)]
 mod tests {
     use crate::cache::{
-        ram::test_util::test_ram_pool, test_util::assert_catalog_access_metric_count,
+        ram::test_util::test_ram_pool,
+        test_util::{assert_catalog_access_metric_count, coalescing_catalog},
     };
     use arrow::datatypes::DataType;
     use data_types::ColumnType;
@@ -389,6 +379,14 @@ mod tests {
             .await;
         let table12 = ns1.create_table("table2").await;
         let table21 = ns2.create_table("table1").await;
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .update_retention_period(table12.table.id, Some(24 * 60 * 60 * 1_000_000_000))
+            .await
+            .unwrap();
 
         let col111 = table11.create_column("col1", ColumnType::I64).await;
         let col112 = table11.create_column("col2", ColumnType::Tag).await;
@@ -398,7 +396,7 @@ mod tests {
         let col211 = table21.create_column("time", ColumnType::Time).await;
 
         let cache = NamespaceCache::new(
-            catalog.catalog(),
+            coalescing_catalog(&catalog),
             BackoffConfig::default(),
             catalog.time_provider(),
             &catalog.metric_registry(),
@@ -442,6 +440,7 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col112.column.id, col113.column.id].into(),
                         partition_template: table11.table.partition_template.clone(),
+                        retention_period: None,
                     }),
                 ),
                 (
@@ -464,6 +463,7 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col122.column.id].into(),
                         partition_template: TablePartitionTemplateOverride::default(),
+                        retention_period: Some(Duration::from_secs(24 * 60 * 60)),
                     }),
                 ),
             ]),
@@ -497,6 +497,7 @@ mod tests {
                     )]),
                     primary_key_column_ids: [col211.column.id].into(),
                     partition_template: TablePartitionTemplateOverride::default(),
+                    retention_period: None,
                 }),
             )]),
         };
@@ -516,7 +517,7 @@ mod tests {
         let catalog = TestCatalog::new();
 
         let cache = NamespaceCache::new(
-            catalog.catalog(),
+            coalescing_catalog(&catalog),
             BackoffConfig::default(),
             catalog.time_provider(),
             &catalog.metric_registry(),
@@ -539,7 +540,7 @@ mod tests {
         let catalog = TestCatalog::new();
 
         let cache = NamespaceCache::new(
-            catalog.catalog(),
+            coalescing_catalog(&catalog),
             BackoffConfig::default(),
             catalog.time_provider(),
             &catalog.metric_registry(),
@@ -13,14 +13,13 @@ use cache_system::{
     resource_consumption::FunctionEstimator,
 };
 use data_types::{ParquetFile, TableId};
-use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use snafu::{ResultExt, Snafu};
 use std::{collections::HashMap, mem, sync::Arc, time::Duration};
 use trace::span::Span;
 use uuid::Uuid;
 
-use super::ram::RamSize;
+use super::{coalesce::CoalescingCatalog, ram::RamSize};
 
 /// Duration to keep cached view.
 ///
@@ -34,7 +33,7 @@ const CACHE_ID: &str = "parquet_file";
 pub enum Error {
     #[snafu(display("CatalogError refreshing parquet file cache: {}", source))]
     Catalog {
-        source: iox_catalog::interface::Error,
+        source: Arc<iox_catalog::interface::Error>,
     },
 }
 
@@ -121,7 +120,7 @@ pub struct ParquetFileCache {
 impl ParquetFileCache {
     /// Create new empty cache.
     pub fn new(
-        catalog: Arc<dyn Catalog>,
+        catalog: Arc<CoalescingCatalog>,
         backoff_config: BackoffConfig,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: &metric::Registry,
@@ -149,11 +148,8 @@ impl ParquetFileCache {
                             // 2. track time ranges needed for queries and
                             // limit files fetched to what is actually
                             // needed
-                            let parquet_files: Vec<_> = catalog
-                                .repositories()
-                                .await
-                                .parquet_files()
-                                .list_by_table_not_to_delete(table_id)
+                            let parquet_files = catalog
+                                .parquet_files_by_table(table_id)
                                 .await
                                 .context(CatalogSnafu)?;
 
@@ -286,7 +282,8 @@ mod tests {
     use iox_tests::{TestCatalog, TestNamespace, TestParquetFileBuilder, TestPartition, TestTable};
 
     use crate::cache::{
-        ram::test_util::test_ram_pool, test_util::assert_catalog_access_metric_count,
+        ram::test_util::test_ram_pool,
+        test_util::{assert_catalog_access_metric_count, coalescing_catalog},
     };
 
     const METRIC_NAME: &str = "parquet_list_by_table_not_to_delete";
@@ -361,8 +358,8 @@ mod tests {
         partition.create_parquet_file(builder).await;
         let table_id = table.table.id;
 
-        let single_file_size = 240;
-        let two_file_size = 448;
+        let single_file_size = 264;
+        let two_file_size = 496;
         assert!(single_file_size < two_file_size);
 
         let cache = make_cache(&catalog);
@@ -519,7 +516,7 @@ mod tests {
 
     fn make_cache(catalog: &TestCatalog) -> ParquetFileCache {
         ParquetFileCache::new(
-            catalog.catalog(),
+            coalescing_catalog(catalog),
             BackoffConfig::default(),
             catalog.time_provider(),
             &catalog.metric_registry(),
@@ -408,6 +408,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -537,6 +538,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id, c3.column.id, c4.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -739,6 +741,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -881,6 +884,7 @@ mod tests {
             column_id_map_rev: HashMap::default(),
             primary_key_column_ids: [].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -1023,6 +1027,7 @@ mod tests {
                 column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
                 primary_key_column_ids: [c.column.id].into(),
                 partition_template: TablePartitionTemplateOverride::default(),
+                retention_period: None,
             });
             const N_PARTITIONS: usize = 20;
             let mut partitions = futures::stream::iter(0..N_PARTITIONS)
@@ -213,6 +213,7 @@ mod tests {
             ]
             .into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
         let table_1b = Arc::new(CachedTable {
             id: table_id_1,
@@ -227,6 +228,7 @@ mod tests {
             ]
             .into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
         let table_2a = Arc::new(CachedTable {
             id: table_id_2,
@@ -242,6 +244,7 @@ mod tests {
             ]
             .into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         // initial request
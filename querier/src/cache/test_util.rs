@@ -1,5 +1,18 @@
+use std::sync::Arc;
+
+use iox_tests::TestCatalog;
 use metric::{Attributes, DurationHistogram, Metric};
 
+use super::coalesce::CoalescingCatalog;
+
+/// Coalescing catalog access for the given test catalog.
+pub fn coalescing_catalog(catalog: &TestCatalog) -> Arc<CoalescingCatalog> {
+    Arc::new(CoalescingCatalog::new(
+        catalog.catalog(),
+        &catalog.metric_registry(),
+    ))
+}
+
 #[track_caller]
 pub fn assert_catalog_access_metric_count(metrics: &metric::Registry, name: &'static str, n: u64) {
     let histogram = metrics
@@ -3,17 +3,21 @@
 use crate::{
     cache::CatalogCache,
     ingester::IngesterConnection,
-    namespace::{QuerierNamespace, QuerierNamespaceArgs},
+    namespace::{FederatedNamespace, QuerierNamespace, QuerierNamespaceArgs},
     parquet::ChunkAdapter,
-    query_log::QueryLog,
+    query_log::{QueryLog, QueryLogEntry, QueryPhaseMetrics},
+    quota::{QueryQuota, QueryQuotas},
     table::PruneMetrics,
 };
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
 use data_types::Namespace;
-use iox_catalog::interface::SoftDeletedRows;
+use futures::{stream::FuturesOrdered, StreamExt};
 use iox_query::exec::Executor;
-use service_common::QueryNamespaceProvider;
+use service_common::{
+    quota::{QuotaError, QuotaPermit},
+    QueryNamespaceProvider,
+};
 use snafu::Snafu;
 use std::{collections::HashMap, sync::Arc};
 use trace::span::{Span, SpanRecorder};
@@ -58,6 +62,9 @@ pub struct QuerierDatabase {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Query phase metrics.
+    query_phase_metrics: Arc<QueryPhaseMetrics>,
+
     /// Semaphore that limits the number of namespaces in used at the time by the query subsystem.
     ///
     /// This should be a 1-to-1 relation to the number of active queries.
@@ -70,11 +77,14 @@ pub struct QuerierDatabase {
 
     /// DataFusion config.
     datafusion_config: Arc<HashMap<String, String>>,
+
+    /// Per-namespace query quotas.
+    quotas: Arc<QueryQuotas>,
 }
 
 #[async_trait]
 impl QueryNamespaceProvider for QuerierDatabase {
-    type Db = QuerierNamespace;
+    type Db = FederatedNamespace;
 
     async fn db(
         &self,
@@ -82,7 +92,19 @@ impl QueryNamespaceProvider for QuerierDatabase {
         span: Option<Span>,
         include_debug_info_tables: bool,
     ) -> Option<Arc<Self::Db>> {
-        self.namespace(name, span, include_debug_info_tables).await
+        self.federated_namespace(name, &[], span, include_debug_info_tables)
+            .await
+    }
+
+    async fn federated_db(
+        &self,
+        name: &str,
+        federated: &[String],
+        span: Option<Span>,
+        include_debug_info_tables: bool,
+    ) -> Option<Arc<Self::Db>> {
+        self.federated_namespace(name, federated, span, include_debug_info_tables)
+            .await
     }
 
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
@@ -91,6 +113,10 @@ impl QueryNamespaceProvider for QuerierDatabase {
             .await
             .expect("Semaphore should not be closed by anyone")
     }
+
+    async fn acquire_quota(&self, name: &str) -> Result<QuotaPermit, QuotaError> {
+        self.quotas.acquire(name)
+    }
 }
 
 impl QuerierDatabase {
@@ -124,6 +150,7 @@ impl QuerierDatabase {
             Arc::clone(&metric_registry),
         ));
         let query_log = Arc::new(QueryLog::new(QUERY_LOG_SIZE, catalog_cache.time_provider()));
+        let query_phase_metrics = Arc::new(QueryPhaseMetrics::new(&metric_registry));
         let semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
             &metric_registry,
             &[("semaphore", "query_execution")],
@@ -133,6 +160,8 @@ impl QuerierDatabase {
 
         let prune_metrics = Arc::new(PruneMetrics::new(&metric_registry));
 
+        let quotas = Arc::new(QueryQuotas::unlimited(catalog_cache.time_provider()));
+
         Ok(Self {
             backoff_config,
             catalog_cache,
@@ -140,12 +169,27 @@ impl QuerierDatabase {
             exec,
             ingester_connection,
             query_log,
+            query_phase_metrics,
             query_execution_semaphore,
             prune_metrics,
             datafusion_config,
+            quotas,
         })
     }
 
+    /// Enforce per-namespace query quotas.
+    ///
+    /// `default` applies to all namespaces that are not listed in `overrides`. Without calling this method, queries
+    /// are not limited.
+    pub fn with_quotas(self, default: QueryQuota, overrides: HashMap<String, QueryQuota>) -> Self {
+        let quotas = Arc::new(QueryQuotas::new(
+            default,
+            overrides,
+            self.catalog_cache.time_provider(),
+        ));
+        Self { quotas, ..self }
+    }
+
     /// Get namespace if it exists.
     ///
     /// This will await the internal namespace semaphore. Existence of namespaces is checked AFTER
@@ -175,28 +219,66 @@ impl QuerierDatabase {
             exec: Arc::clone(&self.exec),
             ingester_connection: self.ingester_connection.clone(),
             query_log: Arc::clone(&self.query_log),
+            query_phase_metrics: Arc::clone(&self.query_phase_metrics),
             prune_metrics: Arc::clone(&self.prune_metrics),
             datafusion_config: Arc::clone(&self.datafusion_config),
+            max_scanned_bytes: self.quotas.quota(&name).max_scanned_bytes,
             include_debug_info_tables,
         })))
     }
 
+    /// Get namespace if it exists, with access to the tables of the `federated` namespaces.
+    ///
+    /// Federated namespaces that do not exist are ignored.
+    pub async fn federated_namespace(
+        &self,
+        name: &str,
+        federated: &[String],
+        span: Option<Span>,
+        include_debug_info_tables: bool,
+    ) -> Option<Arc<FederatedNamespace>> {
+        let span_recorder = SpanRecorder::new(span);
+
+        let primary = self
+            .namespace(
+                name,
+                span_recorder.child_span("primary namespace"),
+                include_debug_info_tables,
+            )
+            .await?;
+
+        let federated = federated
+            .iter()
+            .filter(|federated_name| federated_name.as_str() != name)
+            .map(|federated_name| {
+                self.namespace(
+                    federated_name,
+                    span_recorder.child_span("federated namespace"),
+                    include_debug_info_tables,
+                )
+            })
+            .collect::<FuturesOrdered<_>>()
+            .filter_map(futures::future::ready)
+            .collect::<Vec<_>>()
+            .await;
+
+        Some(Arc::new(FederatedNamespace::new(primary, federated)))
+    }
+
     /// Return all namespaces this querier knows about
     pub async fn namespaces(&self) -> Vec<Namespace> {
-        let catalog = &self.catalog_cache.catalog();
+        let catalog = self.catalog_cache.coalescing_catalog();
         Backoff::new(&self.backoff_config)
-            .retry_all_errors("listing namespaces", || async {
-                catalog
-                    .repositories()
-                    .await
-                    .namespaces()
-                    .list(SoftDeletedRows::ExcludeDeleted)
-                    .await
-            })
+            .retry_all_errors("listing namespaces", || catalog.namespaces())
             .await
             .expect("retry forever")
     }
 
+    /// Entries of the query log, oldest first.
+    pub fn query_log_entries(&self) -> Vec<Arc<QueryLogEntry>> {
+        self.query_log.entries().into_iter().collect()
+    }
+
     /// Return connection to ingester(s) to get and aggregate information from them
     pub fn ingester_connection(&self) -> Option<Arc<dyn IngesterConnection>> {
         self.ingester_connection.clone()
@@ -212,6 +294,8 @@ impl QuerierDatabase {
 mod tests {
     use super::*;
     use crate::create_ingester_connection_for_testing;
+    use assert_matches::assert_matches;
+    use iox_query::exec::{ExecutionContextProvider, SessionContextIOxExt};
     use iox_tests::TestCatalog;
     use tokio::runtime::Handle;
 
@@ -252,6 +336,32 @@ mod tests {
         assert!(db.namespace("ns2", None, true).await.is_none());
     }
 
+    #[tokio::test]
+    async fn test_federated_namespace() {
+        let catalog = TestCatalog::new();
+        let db = new_db(&catalog).await;
+
+        catalog.create_namespace_1hr_retention("ns1").await;
+        catalog.create_namespace_1hr_retention("ns2").await;
+
+        let ns = db
+            .federated_namespace(
+                "ns1",
+                &["ns1".to_owned(), "ns2".to_owned(), "ns3".to_owned()],
+                None,
+                true,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ns.primary().name().as_ref(), "ns1");
+        assert_eq!(ns.federated_names(), vec![Arc::from("ns2")]);
+
+        assert!(db
+            .federated_namespace("ns3", &["ns1".to_owned()], None, true)
+            .await
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_namespaces() {
         let catalog = TestCatalog::new();
@@ -267,6 +377,44 @@ mod tests {
         assert_eq!(namespaces[1].name, "ns2");
     }
 
+    #[tokio::test]
+    async fn test_quotas() {
+        let catalog = TestCatalog::new();
+        let db = new_db(&catalog).await.with_quotas(
+            QueryQuota {
+                max_concurrent_queries: Some(1),
+                ..Default::default()
+            },
+            HashMap::from([(
+                String::from("ns2"),
+                QueryQuota {
+                    max_scanned_bytes: Some(10),
+                    ..Default::default()
+                },
+            )]),
+        );
+
+        catalog.create_namespace_1hr_retention("ns1").await;
+        catalog.create_namespace_1hr_retention("ns2").await;
+
+        let permit = db.acquire_quota("ns1").await.unwrap();
+        assert_matches!(
+            db.acquire_quota("ns1").await,
+            Err(QuotaError::TooManyConcurrentQueries { limit: 1, .. })
+        );
+        let _permit2 = db.acquire_quota("ns2").await.unwrap();
+        let _permit3 = db.acquire_quota("ns2").await.unwrap();
+        drop(permit);
+        db.acquire_quota("ns1").await.unwrap();
+
+        let ns1 = db.namespace("ns1", None, true).await.unwrap();
+        let ctx = ns1.new_query_context(None);
+        assert!(ctx.inner().state().scan_budget().is_none());
+        let ns2 = db.namespace("ns2", None, true).await.unwrap();
+        let ctx = ns2.new_query_context(None);
+        assert_eq!(ctx.inner().state().scan_budget().unwrap().limit(), 10);
+    }
+
     async fn new_db(catalog: &Arc<TestCatalog>) -> QuerierDatabase {
         let catalog_cache = Arc::new(CatalogCache::new_testing(
             catalog.catalog(),
@@ -8,6 +8,7 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::Duration,
 };
 
 use async_trait::async_trait;
@@ -215,6 +216,9 @@ pub struct CircuitBreakerFlightClient {
     /// After how many consecutive errors shall we open a circuit?
     open_circuit_after_n_errors: u64,
 
+    /// Responses that take longer than this count as errors.
+    slow_response_threshold: Option<Duration>,
+
     /// Time provider.
     time_provider: Arc<dyn TimeProvider>,
 
@@ -245,6 +249,7 @@ impl CircuitBreakerFlightClient {
         Self {
             inner,
             open_circuit_after_n_errors,
+            slow_response_threshold: None,
             time_provider,
             metric_registry,
             backoff_config,
@@ -252,6 +257,18 @@ impl CircuitBreakerFlightClient {
             rng_overwrite: None,
         }
     }
+
+    /// Count responses that take longer than `threshold` as errors.
+    ///
+    /// The response is still passed to the caller, but enough consecutive slow responses open the circuit just like
+    /// failed ones do. Only the time until the ingester starts answering is measured, not the time it takes to stream
+    /// the data.
+    pub fn with_slow_response_threshold(self, threshold: Duration) -> Self {
+        Self {
+            slow_response_threshold: Some(threshold),
+            ..self
+        }
+    }
 }
 
 #[async_trait]
@@ -367,12 +384,28 @@ impl IngesterFlightClient for CircuitBreakerFlightClient {
         );
         let not_cancelled = test_signal.unwrap_or_else(|| Arc::new(AtomicBool::new(true)));
         let fut = TrackedFuture::new(fut, not_cancelled);
+        let t_start = self.time_provider.now();
         let res = fut.await;
-
-        let is_error = if let Err(e) = &res {
-            e.is_upstream_error()
-        } else {
-            false
+        let elapsed = self
+            .time_provider
+            .now()
+            .checked_duration_since(t_start)
+            .unwrap_or_default();
+
+        let is_error = match &res {
+            Err(e) => e.is_upstream_error(),
+            Ok(_) => match self.slow_response_threshold {
+                Some(threshold) if elapsed > threshold => {
+                    warn!(
+                        ingester_address = ingester_addr.as_ref(),
+                        ?elapsed,
+                        "Slow ingester response",
+                    );
+                    span_recorder.event("Slow ingester response");
+                    true
+                }
+                _ => false,
+            },
         };
 
         if is_error {
@@ -605,6 +638,64 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_cut_after_n_slow_responses() {
+        maybe_start_logging();
+
+        let TestSetup {
+            client,
+            metric_registry,
+            ..
+        } = TestSetup::from([
+            MockAction {
+                delay: Some(Duration::from_secs(2)),
+                ..Default::default()
+            },
+            MockAction {
+                delay: Some(Duration::from_secs(1)),
+                ..Default::default()
+            },
+            MockAction {
+                delay: Some(Duration::from_secs(2)),
+                ..Default::default()
+            },
+            MockAction {
+                delay: Some(Duration::from_secs(2)),
+                ..Default::default()
+            },
+        ]);
+        let client = Arc::new(
+            Arc::try_unwrap(client)
+                .unwrap()
+                .with_slow_response_threshold(Duration::from_secs(1)),
+        );
+
+        // slow responses are still returned
+        client.assert_query_ok().await;
+        // not slow, resets error counter
+        client.assert_query_ok().await;
+        client.assert_query_ok().await;
+        assert_eq!(
+            Metrics {
+                open: 0,
+                closed: 1,
+                half_open: 0
+            },
+            Metrics::from(&metric_registry),
+        );
+
+        client.assert_query_ok().await;
+        assert_eq!(
+            Metrics {
+                open: 1,
+                closed: 0,
+                half_open: 0
+            },
+            Metrics::from(&metric_registry),
+        );
+        client.assert_query_err_circuit().await;
+    }
+
     #[tokio::test]
     async fn test_ok_resets_error_counter() {
         maybe_start_logging();
@@ -1175,17 +1266,21 @@ mod tests {
     struct MockAction {
         err: Option<FlightClientError>,
         wait: Option<Arc<Barrier>>,
+        /// Advance mocked time by this duration before responding.
+        delay: Option<Duration>,
     }
 
     #[derive(Debug)]
     struct MockClient {
         actions: Mutex<Vec<MockAction>>,
+        time_provider: Option<Arc<MockProvider>>,
     }
 
     impl<const N: usize> From<[MockAction; N]> for MockClient {
         fn from(actions: [MockAction; N]) -> Self {
             Self {
                 actions: Mutex::new(actions.into()),
+                time_provider: None,
             }
         }
     }
@@ -1221,6 +1316,13 @@ mod tests {
                 barrier.wait().await;
             }
 
+            if let Some(delay) = action.delay {
+                self.time_provider
+                    .as_ref()
+                    .expect("time provider set")
+                    .inc(delay);
+            }
+
             if let Some(e) = action.err {
                 return Err(e);
             }
@@ -1256,6 +1358,7 @@ mod tests {
             table_id: TableId::new(0),
             columns: vec![],
             predicate: None,
+            announce_partitions: false,
         }
     }
 
@@ -1271,8 +1374,11 @@ mod tests {
 
     impl<const N: usize> From<[MockAction; N]> for TestSetup {
         fn from(actions: [MockAction; N]) -> Self {
-            let mock_client = MockClient::from(actions);
             let time_provider = Arc::new(MockProvider::new(Time::MIN));
+            let mock_client = MockClient {
+                time_provider: Some(Arc::clone(&time_provider)),
+                ..MockClient::from(actions)
+            };
             let metric_registry = Arc::new(Registry::new());
 
             let mut client = CircuitBreakerFlightClient::new(
@@ -1,19 +1,27 @@
 use self::{
     circuit_breaker::CircuitBreakerFlightClient,
     flight_client::{
-        Error as FlightClientError, FlightClientImpl, FlightError, IngesterFlightClient,
+        Error as FlightClientError, FlightClientImpl, FlightError, IngesterFlightClient, QueryData,
     },
     invalidate_on_error::InvalidateOnErrorFlightClient,
+    response_cache::ResponseCacheFlightClient,
+    stream::IngesterResponseStream,
     test_util::MockIngesterConnection,
 };
 use crate::cache::{namespace::CachedTable, CatalogCache};
-use arrow::{datatypes::DataType, error::ArrowError, record_batch::RecordBatch};
+use arrow::{
+    array::new_null_array,
+    datatypes::DataType,
+    error::ArrowError,
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
 use arrow_flight::decode::DecodedPayload;
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig, BackoffError};
 use client_util::connection;
 use data_types::{
-    ChunkId, ChunkOrder, NamespaceId, PartitionHashId, PartitionId, TransitionPartitionId,
+    ChunkId, ChunkOrder, NamespaceId, PartitionHashId, PartitionId, SequenceNumber,
+    TransitionPartitionId,
 };
 use datafusion::physical_plan::Statistics;
 use futures::{stream::FuturesUnordered, TryStreamExt};
@@ -23,6 +31,7 @@ use ingester_query_grpc::{
 };
 use iox_query::{
     chunk_statistics::{create_chunk_statistics, ColumnRanges},
+    exec::warnings::QueryWarnings,
     util::compute_timenanosecond_min_max,
     QueryChunk, QueryChunkData,
 };
@@ -44,6 +53,8 @@ use uuid::Uuid;
 mod circuit_breaker;
 pub(crate) mod flight_client;
 mod invalidate_on_error;
+mod response_cache;
+mod stream;
 pub(crate) mod test_util;
 
 #[derive(Debug, Snafu)]
@@ -120,6 +131,19 @@ pub enum Error {
         ingester_address: String,
     },
 
+    #[snafu(display(
+        "Got data for partition {partition_id} that was not announced, ingester: {ingester_address}"
+    ))]
+    UnannouncedPartition {
+        partition_id: PartitionId,
+        ingester_address: String,
+    },
+
+    #[snafu(display(
+        "Got partition announcement after partition data, ingester: {ingester_address}"
+    ))]
+    UnexpectedAnnouncement { ingester_address: String },
+
     #[snafu(display("Could not parse `{ingester_uuid}` as a UUID: {source}"))]
     IngesterUuid {
         ingester_uuid: String,
@@ -134,11 +158,16 @@ pub enum Error {
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
+/// Maximum number of ingester responses kept by the querier-side response cache.
+const RESPONSE_CACHE_MAX_ENTRIES: usize = 1_000;
+
 /// Create a new set of connections given ingester configurations
 pub fn create_ingester_connections(
     ingester_addresses: Vec<Arc<str>>,
     catalog_cache: Arc<CatalogCache>,
     open_circuit_after_n_errors: u64,
+    slow_response_threshold: Option<Duration>,
+    response_cache_ttl: Duration,
     trace_context_header_name: &str,
 ) -> Arc<dyn IngesterConnection> {
     // This backoff config is used to retry requests for a specific table-scoped query.
@@ -164,6 +193,8 @@ pub fn create_ingester_connections(
         retry_backoff_config,
         circuit_breaker_backoff_config,
         open_circuit_after_n_errors,
+        slow_response_threshold,
+        response_cache_ttl,
         trace_context_header_name,
     ))
 }
@@ -184,6 +215,7 @@ pub trait IngesterConnection: std::fmt::Debug + Send + Sync + 'static {
         columns: Vec<String>,
         predicate: &Predicate,
         span: Option<Span>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<IngesterPartition>>;
 
     /// Return backend as [`Any`] which can be used to downcast to a specific implementation.
@@ -329,23 +361,45 @@ pub struct IngesterConnectionImpl {
 
 impl IngesterConnectionImpl {
     /// Create a new set of connections given a list of ingester addresses.
+    ///
+    /// Circuits are opened after `open_circuit_after_n_errors` consecutive errors. If `slow_response_threshold` is
+    /// set, responses that take longer than that count as errors.
+    ///
+    /// Ingester responses are cached for `response_cache_ttl`. A zero TTL disables the cache.
     pub fn by_addrs(
         ingester_addresses: Vec<Arc<str>>,
         catalog_cache: Arc<CatalogCache>,
         backoff_config: BackoffConfig,
         circuit_breaker_backoff_config: BackoffConfig,
         open_circuit_after_n_errors: u64,
+        slow_response_threshold: Option<Duration>,
+        response_cache_ttl: Duration,
         trace_context_header_name: &str,
     ) -> Self {
         let flight_client = Arc::new(FlightClientImpl::new(trace_context_header_name));
         let flight_client = Arc::new(InvalidateOnErrorFlightClient::new(flight_client));
-        let flight_client = Arc::new(CircuitBreakerFlightClient::new(
+        let mut circuit_breaker = CircuitBreakerFlightClient::new(
             flight_client,
             catalog_cache.time_provider(),
             catalog_cache.metric_registry(),
             open_circuit_after_n_errors,
             circuit_breaker_backoff_config,
-        ));
+        );
+        if let Some(threshold) = slow_response_threshold {
+            circuit_breaker = circuit_breaker.with_slow_response_threshold(threshold);
+        }
+        let flight_client = Arc::new(circuit_breaker);
+        let flight_client: Arc<dyn IngesterFlightClient> = if response_cache_ttl.is_zero() {
+            flight_client
+        } else {
+            Arc::new(ResponseCacheFlightClient::new(
+                flight_client,
+                catalog_cache.time_provider(),
+                &catalog_cache.metric_registry(),
+                response_cache_ttl,
+                RESPONSE_CACHE_MAX_ENTRIES,
+            ))
+        };
 
         Self::by_addrs_with_flight_client(
             ingester_addresses,
@@ -388,6 +442,7 @@ struct GetPartitionForIngester<'a> {
     columns: Vec<String>,
     predicate: &'a Predicate,
     cached_table: Arc<CachedTable>,
+    query_warnings: Option<Arc<QueryWarnings>>,
 }
 
 /// Fetches the partitions for a single ingester
@@ -403,6 +458,7 @@ async fn execute(
         columns,
         predicate,
         cached_table,
+        query_warnings,
     } = request;
 
     let ingester_query_request = IngesterQueryRequest {
@@ -410,6 +466,7 @@ async fn execute(
         table_id: cached_table.id,
         columns: columns.clone(),
         predicate: Some(predicate.clone()),
+        announce_partitions: true,
     };
 
     let query_res = {
@@ -431,6 +488,11 @@ async fn execute(
                 table_id = cached_table.id.get(),
                 "Could not connect to ingester, circuit broken",
             );
+            if let Some(query_warnings) = &query_warnings {
+                query_warnings.push(format!(
+                    "ingester {ingester_address} is unavailable, result does not include its unpersisted data"
+                ));
+            }
             return Ok(vec![]);
         }
         Err(FlightClientError::Flight {
@@ -468,32 +530,122 @@ async fn execute(
             e
         })?;
 
-    // collect data from IO stream
-    // Drain data from ingester so we don't block the ingester while performing catalog IO or CPU
-    // computations
-    let mut messages = vec![];
-    while let Some(data) = perform_query
+    let first = next_message(perform_query.as_mut(), &ingester_address).await?;
+    match first {
+        Some((DecodedPayload::None, md)) if md.announcement => {
+            // Only read the announcements, the data is streamed when the chunks are executed. See
+            // `IngesterQueryRequest::announce_partitions`.
+            let mut partitions = vec![partition_from_metadata(md)?];
+            let pending = loop {
+                match next_message(perform_query.as_mut(), &ingester_address).await? {
+                    Some((DecodedPayload::None, md)) if md.announcement => {
+                        let partition = partition_from_metadata(md)?;
+                        ensure!(
+                            !partitions
+                                .iter()
+                                .any(|p| p.partition_id == partition.partition_id),
+                            DuplicatePartitionInfoSnafu {
+                                partition_id: partition.partition_id,
+                                ingester_address: ingester_address.as_ref(),
+                            },
+                        );
+                        partitions.push(partition);
+                    }
+                    other => break other,
+                }
+            };
+
+            // The per-partition schemas are only known once the data is read, so use a common
+            // schema for all partitions. Columns that a partition lacks are filled with NULLs.
+            let column_names: Vec<_> = cached_table
+                .schema
+                .iter()
+                .map(|(_t, field)| field.name().as_str())
+                .filter(|name| columns.iter().any(|col| col.as_str() == *name))
+                .collect();
+            let schema = cached_table
+                .schema
+                .select_by_names(&column_names)
+                .context(ConvertingSchemaSnafu)?;
+
+            let stream = Arc::new(IngesterResponseStream::new(
+                ingester_address,
+                perform_query,
+                pending,
+                schema.clone(),
+                partitions.iter().map(|p| p.partition_id),
+            ));
+            let mut partitions: Vec<_> = partitions
+                .into_iter()
+                .map(|p| p.with_streamed_chunk(ChunkId::new(), schema.clone(), Arc::clone(&stream)))
+                .collect();
+
+            // deterministic order
+            partitions.sort_by(|a, b| a.partition_id.cmp(&b.partition_id));
+            Ok(partitions)
+        }
+        first => {
+            // The ingester does not announce its partitions (e.g. because it runs an older
+            // version), so the partition layout is only known after reading the entire response.
+            //
+            // Drain data from ingester so we don't block the ingester while performing catalog IO
+            // or CPU computations
+            let mut messages: Vec<_> = first.into_iter().collect();
+            if !messages.is_empty() {
+                while let Some(data) =
+                    next_message(perform_query.as_mut(), &ingester_address).await?
+                {
+                    messages.push(data);
+                }
+            }
+
+            // reconstruct partitions
+            let mut decoder = IngesterStreamDecoder::new(
+                ingester_address,
+                cached_table,
+                span_recorder.child_span("IngesterStreamDecoder"),
+            );
+            for (msg, md) in messages {
+                decoder.register(msg, md)?;
+            }
+
+            decoder.finalize()
+        }
+    }
+}
+
+/// Read next message from the ingester response.
+async fn next_message(
+    query_data: &mut dyn QueryData,
+    ingester_address: &str,
+) -> Result<Option<(DecodedPayload, IngesterQueryResponseMetadata)>> {
+    query_data
         .next_message()
         .await
         .map_err(|source| FlightClientError::Flight { source })
-        .context(RemoteQuerySnafu {
-            ingester_address: ingester_address.as_ref(),
-        })?
-    {
-        messages.push(data);
-    }
-
-    // reconstruct partitions
-    let mut decoder = IngesterStreamDecoder::new(
-        ingester_address,
-        cached_table,
-        span_recorder.child_span("IngesterStreamDecoder"),
-    );
-    for (msg, md) in messages {
-        decoder.register(msg, md)?;
-    }
+        .context(RemoteQuerySnafu { ingester_address })
+}
 
-    decoder.finalize()
+/// Create an [`IngesterPartition`] w/o any chunks from the metadata that starts or announces it.
+fn partition_from_metadata(md: IngesterQueryResponseMetadata) -> Result<IngesterPartition> {
+    let partition_id = PartitionId::new(md.partition_id);
+    let partition_hash_id = md
+        .partition_hash_id
+        .map(|bytes| PartitionHashId::try_from(&bytes[..]).context(PartitionHashIdSnafu))
+        .transpose()?;
+
+    let ingester_uuid = Uuid::parse_str(&md.ingester_uuid).context(IngesterUuidSnafu {
+        ingester_uuid: md.ingester_uuid,
+    })?;
+
+    Ok(IngesterPartition::new(
+        ingester_uuid,
+        partition_id,
+        partition_hash_id,
+        md.completed_persistence_count,
+        md.max_persisted_sequence_number.map(SequenceNumber::new),
+        md.buffered_row_count,
+    ))
 }
 
 /// Helper to disassemble the data from the ingester Apache Flight arrow stream.
@@ -562,12 +714,6 @@ impl IngesterStreamDecoder {
                 self.flush_partition()?;
 
                 let partition_id = PartitionId::new(md.partition_id);
-                let partition_hash_id = md
-                    .partition_hash_id
-                    .map(|bytes| {
-                        PartitionHashId::try_from(&bytes[..]).context(PartitionHashIdSnafu)
-                    })
-                    .transpose()?;
                 ensure!(
                     !self.finished_partitions.contains_key(&partition_id),
                     DuplicatePartitionInfoSnafu {
@@ -576,18 +722,7 @@ impl IngesterStreamDecoder {
                     },
                 );
 
-                let ingester_uuid =
-                    Uuid::parse_str(&md.ingester_uuid).context(IngesterUuidSnafu {
-                        ingester_uuid: md.ingester_uuid,
-                    })?;
-
-                let partition = IngesterPartition::new(
-                    ingester_uuid,
-                    partition_id,
-                    partition_hash_id,
-                    md.completed_persistence_count,
-                );
-                self.current_partition = Some(partition);
+                self.current_partition = Some(partition_from_metadata(md)?);
             }
             DecodedPayload::Schema(schema) => {
                 self.flush_chunk()?;
@@ -663,6 +798,7 @@ impl IngesterConnection for IngesterConnectionImpl {
         columns: Vec<String>,
         predicate: &Predicate,
         span: Option<Span>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<IngesterPartition>> {
         let mut span_recorder = SpanRecorder::new(span);
 
@@ -678,6 +814,7 @@ impl IngesterConnection for IngesterConnectionImpl {
                 cached_table: Arc::clone(&cached_table),
                 columns: columns.clone(),
                 predicate,
+                query_warnings: query_warnings.clone(),
             };
 
             let backoff_config = self.backoff_config.clone();
@@ -707,8 +844,9 @@ impl IngesterConnection for IngesterConnectionImpl {
                             status.n_partitions += 1;
                             for c in p.chunks() {
                                 status.n_chunks += 1;
-                                status.n_rows += c.rows();
-                                status.memory_bytes += c.estimate_size()
+                                // streamed chunks were not read yet, their size is unknown
+                                status.n_rows += c.rows().unwrap_or_default();
+                                status.memory_bytes += c.estimate_size().unwrap_or_default();
                             }
                         }
 
@@ -780,6 +918,15 @@ pub struct IngesterPartition {
     /// The number of Parquet files this ingester UUID has persisted for this partition.
     completed_persistence_count: u64,
 
+    /// The highest sequence number this ingester UUID has persisted for this partition, if any.
+    ///
+    /// Not all ingester responses will contain this value yet.
+    max_persisted_sequence_number: Option<SequenceNumber>,
+
+    /// The number of unpersisted rows buffered by the ingester for this partition, regardless of
+    /// the projection and predicate of the query.
+    buffered_row_count: u64,
+
     chunks: Vec<IngesterChunk>,
 }
 
@@ -791,12 +938,16 @@ impl IngesterPartition {
         partition_id: PartitionId,
         partition_hash_id: Option<PartitionHashId>,
         completed_persistence_count: u64,
+        max_persisted_sequence_number: Option<SequenceNumber>,
+        buffered_row_count: u64,
     ) -> Self {
         Self {
             ingester_uuid,
             partition_id,
             partition_hash_id,
             completed_persistence_count,
+            max_persisted_sequence_number,
+            buffered_row_count,
             chunks: vec![],
         }
     }
@@ -829,7 +980,7 @@ impl IngesterPartition {
             partition_id: self.partition_id,
             transition_partition_id: self.transition_partition_id(),
             schema: expected_schema,
-            batches,
+            data: IngesterChunkData::Buffered(batches),
             stats: None,
         };
 
@@ -838,24 +989,57 @@ impl IngesterPartition {
         Ok(self)
     }
 
+    /// Add a chunk that reads its data from the given ingester response on demand.
+    ///
+    /// The size of the chunk is estimated from the [buffered row count](Self::buffered_row_count)
+    /// that the ingester announced for this partition.
+    pub(crate) fn with_streamed_chunk(
+        mut self,
+        chunk_id: ChunkId,
+        schema: Schema,
+        stream: Arc<IngesterResponseStream>,
+    ) -> Self {
+        let chunk = IngesterChunk {
+            chunk_id,
+            partition_id: self.partition_id,
+            transition_partition_id: self.transition_partition_id(),
+            schema,
+            data: IngesterChunkData::Streamed {
+                stream,
+                buffered_row_count: self.buffered_row_count,
+            },
+            stats: None,
+        };
+
+        self.chunks.push(chunk);
+
+        self
+    }
+
     pub(crate) fn set_partition_column_ranges(&mut self, partition_column_ranges: &ColumnRanges) {
         for chunk in &mut self.chunks {
-            // TODO: may want to ask the Ingester to send this value instead of computing it here.
-            let ts_min_max =
-                compute_timenanosecond_min_max(&chunk.batches).expect("Should have time range");
-
-            let row_count = chunk
-                .batches
-                .iter()
-                .map(|batch| batch.num_rows())
-                .sum::<usize>() as u64;
-            let stats = Arc::new(create_chunk_statistics(
-                row_count,
-                &chunk.schema,
-                Some(ts_min_max),
-                partition_column_ranges,
-            ));
-            chunk.stats = Some(stats);
+            let stats = match &chunk.data {
+                IngesterChunkData::Buffered(batches) => {
+                    // TODO: may want to ask the Ingester to send this value instead of computing
+                    // it here.
+                    let ts_min_max =
+                        compute_timenanosecond_min_max(batches).expect("Should have time range");
+
+                    let row_count =
+                        batches.iter().map(|batch| batch.num_rows()).sum::<usize>() as u64;
+                    create_chunk_statistics(
+                        Some(row_count),
+                        &chunk.schema,
+                        Some(ts_min_max),
+                        partition_column_ranges,
+                    )
+                }
+                // data was not read yet, only the partition-wide ranges are known
+                IngesterChunkData::Streamed { .. } => {
+                    create_chunk_statistics(None, &chunk.schema, None, partition_column_ranges)
+                }
+            };
+            chunk.stats = Some(Arc::new(stats));
         }
     }
 
@@ -875,6 +1059,14 @@ impl IngesterPartition {
         self.completed_persistence_count
     }
 
+    pub(crate) fn max_persisted_sequence_number(&self) -> Option<SequenceNumber> {
+        self.max_persisted_sequence_number
+    }
+
+    pub(crate) fn buffered_row_count(&self) -> u64 {
+        self.buffered_row_count
+    }
+
     pub(crate) fn chunks(&self) -> &[IngesterChunk] {
         &self.chunks
     }
@@ -892,7 +1084,7 @@ pub struct IngesterChunk {
     schema: Schema,
 
     /// The raw table data
-    batches: Vec<RecordBatch>,
+    data: IngesterChunkData,
 
     /// Summary Statistics
     ///
@@ -900,25 +1092,86 @@ pub struct IngesterChunk {
     stats: Option<Arc<Statistics>>,
 }
 
+/// Raw data of an [`IngesterChunk`].
+#[derive(Debug, Clone)]
+enum IngesterChunkData {
+    /// Data was fully read from the ingester response.
+    Buffered(Vec<RecordBatch>),
+
+    /// Data is read from the ingester response when the chunk is executed.
+    Streamed {
+        stream: Arc<IngesterResponseStream>,
+
+        /// The number of rows the ingester announced to buffer for the partition.
+        buffered_row_count: u64,
+    },
+}
+
+/// Assumed in-memory size of a single value of a [streamed](IngesterChunkData::Streamed) chunk.
+///
+/// This is the size of the fixed-width column types, string values are usually
+/// dictionary-encoded.
+const STREAMED_BYTES_PER_VALUE: usize = 8;
+
 impl IngesterChunk {
-    pub(crate) fn estimate_size(&self) -> usize {
-        self.batches
-            .iter()
-            .map(|batch| {
-                batch
-                    .columns()
+    /// Estimated in-memory size.
+    ///
+    /// This is unknown (`None`) for [streamed](IngesterChunkData::Streamed) chunks because their
+    /// data was not read yet.
+    pub(crate) fn estimate_size(&self) -> Option<usize> {
+        match &self.data {
+            IngesterChunkData::Buffered(batches) => Some(
+                batches
                     .iter()
-                    .map(|array| array.get_array_memory_size())
-                    .sum::<usize>()
-            })
-            .sum::<usize>()
+                    .map(|batch| {
+                        batch
+                            .columns()
+                            .iter()
+                            .map(|array| array.get_array_memory_size())
+                            .sum::<usize>()
+                    })
+                    .sum::<usize>(),
+            ),
+            IngesterChunkData::Streamed { .. } => None,
+        }
     }
 
-    pub(crate) fn rows(&self) -> usize {
-        self.batches
-            .iter()
-            .map(|batch| batch.num_rows())
-            .sum::<usize>()
+    /// Estimated in-memory size of the data that is scanned when the chunk is executed.
+    ///
+    /// For [streamed](IngesterChunkData::Streamed) chunks the data was not read yet, so this
+    /// assumes that all rows the ingester buffers for the partition are returned, with
+    /// [`STREAMED_BYTES_PER_VALUE`] per value of every column of the chunk. This is an upper bound
+    /// because it ignores the predicate of the query.
+    pub(crate) fn estimate_scan_size(&self) -> usize {
+        match &self.data {
+            IngesterChunkData::Buffered(_) => self.estimate_size().unwrap_or_default(),
+            IngesterChunkData::Streamed {
+                buffered_row_count, ..
+            } => (*buffered_row_count as usize)
+                .saturating_mul(self.schema.len())
+                .saturating_mul(STREAMED_BYTES_PER_VALUE),
+        }
+    }
+
+    /// Number of rows.
+    ///
+    /// This is unknown (`None`) for [streamed](IngesterChunkData::Streamed) chunks because their
+    /// data was not read yet.
+    pub(crate) fn rows(&self) -> Option<usize> {
+        match &self.data {
+            IngesterChunkData::Buffered(batches) => {
+                Some(batches.iter().map(|batch| batch.num_rows()).sum::<usize>())
+            }
+            IngesterChunkData::Streamed { .. } => None,
+        }
+    }
+
+    #[cfg(test)]
+    fn batches(&self) -> &[RecordBatch] {
+        match &self.data {
+            IngesterChunkData::Buffered(batches) => batches,
+            IngesterChunkData::Streamed { .. } => panic!("chunk is streamed"),
+        }
     }
 }
 
@@ -955,7 +1208,12 @@ impl QueryChunk for IngesterChunk {
     }
 
     fn data(&self) -> QueryChunkData {
-        QueryChunkData::RecordBatches(self.batches.clone())
+        match &self.data {
+            IngesterChunkData::Buffered(batches) => QueryChunkData::RecordBatches(batches.clone()),
+            IngesterChunkData::Streamed { stream, .. } => {
+                QueryChunkData::Stream(stream.partition_stream(self.partition_id))
+            }
+        }
     }
 
     fn chunk_type(&self) -> &str {
@@ -983,8 +1241,9 @@ impl QueryChunk for IngesterChunk {
 /// Namely, dictionary encoded columns (e.g. tags) are returned as `DataType::Utf8` even when they
 /// were sent as `DataType::Dictionary(Int32, Utf8)`.
 ///
-/// # Panic
-/// Panics when a column was not found in the given batch.
+/// # Missing Columns
+/// Columns that are not part of the given batch are filled with NULLs. This happens for
+/// [streamed](IngesterChunkData::Streamed) data where a single schema is used for all partitions.
 fn ensure_schema(batch: RecordBatch, expected_schema: &Schema) -> Result<RecordBatch> {
     let actual_schema = batch.schema();
     let desired_fields = expected_schema.iter().map(|(_, f)| f);
@@ -1023,24 +1282,31 @@ fn ensure_schema(batch: RecordBatch, expected_schema: &Schema) -> Result<RecordB
                         Ok(Arc::clone(col))
                     }
                 }
-                None => panic!("Column not found: {}", desired_field.name()),
+                None => Ok(new_null_array(desired_type, batch.num_rows())),
             }
         })
         .collect::<Result<Vec<_>>>()?;
 
-    RecordBatch::try_new(expected_schema.as_arrow(), new_columns).context(CreatingRecordBatchSnafu)
+    RecordBatch::try_new_with_options(
+        expected_schema.as_arrow(),
+        new_columns,
+        &RecordBatchOptions::new().with_row_count(Some(batch.num_rows())),
+    )
+    .context(CreatingRecordBatchSnafu)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{flight_client::QueryData, *};
+    use super::*;
     use arrow::{
         array::{ArrayRef, DictionaryArray, Int64Array, StringArray, TimestampNanosecondArray},
         datatypes::Int32Type,
     };
+    use arrow_util::assert_batches_eq;
     use assert_matches::assert_matches;
     use data_types::{PartitionKey, TableId};
     use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryResponseMetadata;
+    use iox_query::exec::IOxSessionContext;
     use iox_tests::TestCatalog;
     use metric::Attributes;
     use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
@@ -1099,6 +1365,40 @@ mod tests {
         assert!(partitions.is_empty());
     }
 
+    #[tokio::test]
+    async fn test_flight_circuit_broken() {
+        let mock_flight_client = Arc::new(
+            MockFlightClient::new([
+                (
+                    "addr1",
+                    Err(FlightClientError::CircuitBroken {
+                        ingester_address: String::from("addr1"),
+                    }),
+                ),
+                ("addr2", Ok(MockQueryData { results: vec![] })),
+            ])
+            .await,
+        );
+        let ingester_conn = mock_flight_client.ingester_conn().await;
+        let query_warnings = Arc::new(QueryWarnings::default());
+        let partitions = ingester_conn
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                vec![String::from("col")],
+                &Predicate::default(),
+                None,
+                Some(Arc::clone(&query_warnings)),
+            )
+            .await
+            .unwrap();
+        assert!(partitions.is_empty());
+        assert_eq!(
+            query_warnings.warnings(),
+            vec!["ingester addr1 is unavailable, result does not include its unpersisted data"],
+        );
+    }
+
     #[tokio::test]
     async fn test_flight_stream_error() {
         let mock_flight_client = Arc::new(
@@ -1168,6 +1468,9 @@ mod tests {
                             partition_hash_id: None,
                             ingester_uuid: ingester_uuid.to_string(),
                             completed_persistence_count: 5,
+                            max_persisted_sequence_number: None,
+                            buffered_row_count: 0,
+                            announcement: false,
                         },
                     ))],
                 }),
@@ -1187,6 +1490,44 @@ mod tests {
         assert_eq!(p.completed_persistence_count, 5);
     }
 
+    #[tokio::test]
+    async fn test_flight_persistence_watermark() {
+        let ingester_uuid = Uuid::new_v4();
+
+        let mock_flight_client = Arc::new(
+            MockFlightClient::new([(
+                "addr1",
+                Ok(MockQueryData {
+                    results: vec![Ok((
+                        DecodedPayload::None,
+                        IngesterQueryResponseMetadata {
+                            partition_id: 1,
+                            partition_hash_id: None,
+                            ingester_uuid: ingester_uuid.to_string(),
+                            completed_persistence_count: 5,
+                            max_persisted_sequence_number: Some(42),
+                            buffered_row_count: 13,
+                            announcement: false,
+                        },
+                    ))],
+                }),
+            )])
+            .await,
+        );
+        let ingester_conn = mock_flight_client.ingester_conn().await;
+
+        let partitions = get_partitions(&ingester_conn).await.unwrap();
+        assert_eq!(partitions.len(), 1);
+
+        let p = &partitions[0];
+        assert_eq!(p.completed_persistence_count, 5);
+        assert_eq!(
+            p.max_persisted_sequence_number,
+            Some(SequenceNumber::new(42))
+        );
+        assert_eq!(p.buffered_row_count, 13);
+    }
+
     #[tokio::test]
     async fn test_flight_err_duplicate_partition_info() {
         let ingester_uuid = Uuid::new_v4();
@@ -1337,12 +1678,12 @@ mod tests {
         );
         assert_eq!(p1.chunks.len(), 2);
         assert_eq!(p1.chunks[0].schema().as_arrow(), schema_1_1);
-        assert_eq!(p1.chunks[0].batches.len(), 2);
-        assert_eq!(p1.chunks[0].batches[0].schema(), schema_1_1);
-        assert_eq!(p1.chunks[0].batches[1].schema(), schema_1_1);
+        assert_eq!(p1.chunks[0].batches().len(), 2);
+        assert_eq!(p1.chunks[0].batches()[0].schema(), schema_1_1);
+        assert_eq!(p1.chunks[0].batches()[1].schema(), schema_1_1);
         assert_eq!(p1.chunks[1].schema().as_arrow(), schema_1_2);
-        assert_eq!(p1.chunks[1].batches.len(), 1);
-        assert_eq!(p1.chunks[1].batches[0].schema(), schema_1_2);
+        assert_eq!(p1.chunks[1].batches().len(), 1);
+        assert_eq!(p1.chunks[1].batches()[0].schema(), schema_1_2);
 
         let p2 = &partitions[1];
         assert_eq!(p2.partition_id.get(), 2);
@@ -1352,8 +1693,8 @@ mod tests {
         );
         assert_eq!(p2.chunks.len(), 1);
         assert_eq!(p2.chunks[0].schema().as_arrow(), schema_2_1);
-        assert_eq!(p2.chunks[0].batches.len(), 1);
-        assert_eq!(p2.chunks[0].batches[0].schema(), schema_2_1);
+        assert_eq!(p2.chunks[0].batches().len(), 1);
+        assert_eq!(p2.chunks[0].batches()[0].schema(), schema_2_1);
 
         let p3 = &partitions[2];
         assert_eq!(p3.partition_id.get(), 3);
@@ -1363,8 +1704,119 @@ mod tests {
         );
         assert_eq!(p3.chunks.len(), 1);
         assert_eq!(p3.chunks[0].schema().as_arrow(), schema_3_1);
-        assert_eq!(p3.chunks[0].batches.len(), 1);
-        assert_eq!(p3.chunks[0].batches[0].schema(), schema_3_1);
+        assert_eq!(p3.chunks[0].batches().len(), 1);
+        assert_eq!(p3.chunks[0].batches()[0].schema(), schema_3_1);
+    }
+
+    #[tokio::test]
+    async fn test_flight_streamed() {
+        let ingester_uuid = Uuid::new_v4();
+
+        let record_batch_1 = lp_to_record_batch("table bar=20,foo=1 1");
+        let record_batch_2 = lp_to_record_batch("table foo=2 2");
+
+        let mock_flight_client = Arc::new(
+            MockFlightClient::new([(
+                "addr1",
+                Ok(MockQueryData {
+                    results: vec![
+                        announcement(1, ingester_uuid.to_string(), 3, 10),
+                        announcement(2, ingester_uuid.to_string(), 4, 20),
+                        metadata(1, ingester_uuid.to_string(), 3),
+                        Ok((
+                            DecodedPayload::Schema(record_batch_1.schema()),
+                            IngesterQueryResponseMetadata::default(),
+                        )),
+                        Ok((
+                            DecodedPayload::RecordBatch(record_batch_1),
+                            IngesterQueryResponseMetadata::default(),
+                        )),
+                        metadata(2, ingester_uuid.to_string(), 4),
+                        Ok((
+                            DecodedPayload::Schema(record_batch_2.schema()),
+                            IngesterQueryResponseMetadata::default(),
+                        )),
+                        Ok((
+                            DecodedPayload::RecordBatch(record_batch_2),
+                            IngesterQueryResponseMetadata::default(),
+                        )),
+                    ],
+                }),
+            )])
+            .await,
+        );
+        let ingester_conn = mock_flight_client.ingester_conn().await;
+
+        let columns = vec![
+            String::from("bar"),
+            String::from("foo"),
+            String::from("time"),
+        ];
+        let mut partitions = ingester_conn
+            .partitions(
+                NamespaceId::new(1),
+                cached_table(),
+                columns,
+                &Predicate::default(),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(partitions.len(), 2);
+
+        let expected_schema = schema().select_by_names(&["bar", "foo", "time"]).unwrap();
+        for (p, (partition_id, completed_persistence_count, buffered_row_count)) in
+            partitions.iter_mut().zip([(1, 3, 10), (2, 4, 20)])
+        {
+            assert_eq!(p.partition_id.get(), partition_id);
+            assert_eq!(p.ingester_uuid, ingester_uuid);
+            assert_eq!(p.completed_persistence_count, completed_persistence_count);
+            assert_eq!(p.chunks.len(), 1);
+            assert_eq!(p.chunks[0].schema(), &expected_schema);
+            assert_eq!(p.chunks[0].rows(), None);
+            assert_eq!(p.chunks[0].estimate_size(), None);
+            // 3 columns with 8 bytes per value
+            assert_eq!(p.chunks[0].estimate_scan_size(), buffered_row_count * 3 * 8);
+
+            p.set_partition_column_ranges(&Default::default());
+            assert_eq!(p.chunks[0].stats().num_rows, None);
+            assert!(!p.chunks[0].stats().is_exact);
+        }
+
+        // read partitions in reverse order
+        let ctx = IOxSessionContext::with_testing();
+        let chunk = &partitions[1].chunks[0];
+        let batches = chunk
+            .data()
+            .read_to_batches(chunk.schema(), ctx.inner())
+            .await;
+        assert_batches_eq!(
+            [
+                "+-----+-----+--------------------------------+",
+                "| bar | foo | time                           |",
+                "+-----+-----+--------------------------------+",
+                "|     | 2.0 | 1970-01-01T00:00:00.000000002Z |",
+                "+-----+-----+--------------------------------+",
+            ],
+            &batches
+        );
+
+        let chunk = &partitions[0].chunks[0];
+        let batches = chunk
+            .data()
+            .read_to_batches(chunk.schema(), ctx.inner())
+            .await;
+        assert_batches_eq!(
+            [
+                "+------+-----+--------------------------------+",
+                "| bar  | foo | time                           |",
+                "+------+-----+--------------------------------+",
+                "| 20.0 | 1.0 | 1970-01-01T00:00:00.000000001Z |",
+                "+------+-----+--------------------------------+",
+            ],
+            &batches
+        );
     }
 
     #[tokio::test]
@@ -1387,6 +1839,7 @@ mod tests {
                 columns,
                 &Predicate::default(),
                 None,
+                None,
             )
             .await
             .unwrap_err();
@@ -1412,6 +1865,9 @@ mod tests {
                             ),
                             ingester_uuid: ingester_uuid.to_string(),
                             completed_persistence_count: 5,
+                            max_persisted_sequence_number: None,
+                            buffered_row_count: 0,
+                            announcement: false,
                         },
                     ))],
                 }),
@@ -1427,6 +1883,7 @@ mod tests {
                 columns,
                 &Predicate::default(),
                 None,
+                None,
             )
             .await
             .unwrap_err();
@@ -1468,6 +1925,7 @@ mod tests {
                 columns,
                 &Predicate::default(),
                 None,
+                None,
             )
             .await
             .unwrap();
@@ -1615,6 +2073,7 @@ mod tests {
                 columns,
                 &Predicate::default(),
                 span,
+                None,
             )
             .await
     }
@@ -1653,6 +2112,26 @@ mod tests {
                 partition_hash_id: Some(partition_hash_id(partition_id).as_bytes().to_owned()),
                 ingester_uuid: ingester_uuid.into(),
                 completed_persistence_count,
+                max_persisted_sequence_number: None,
+                buffered_row_count: 0,
+                announcement: false,
+            },
+        ))
+    }
+
+    fn announcement(
+        partition_id: i64,
+        ingester_uuid: impl Into<String>,
+        completed_persistence_count: u64,
+        buffered_row_count: u64,
+    ) -> MockFlightResult {
+        let (payload, md) = metadata(partition_id, ingester_uuid, completed_persistence_count)?;
+        Ok((
+            payload,
+            IngesterQueryResponseMetadata {
+                announcement: true,
+                buffered_row_count,
+                ..md
             },
         ))
     }
@@ -1774,11 +2253,13 @@ mod tests {
                     &PartitionKey::from("arbitrary"),
                 )),
                 0,
+                None,
+                0,
             )
             .try_add_chunk(ChunkId::new(), expected_schema.clone(), vec![case])
             .unwrap();
 
-            for batch in &ingester_partition.chunks[0].batches {
+            for batch in ingester_partition.chunks[0].batches() {
                 assert_eq!(batch.schema(), expected_schema.as_arrow());
             }
         }
@@ -1804,6 +2285,8 @@ mod tests {
                 &PartitionKey::from("arbitrary"),
             )),
             0,
+            None,
+            0,
         )
         .try_add_chunk(ChunkId::new(), expected_schema, vec![batch])
         .unwrap_err();
@@ -1852,6 +2335,7 @@ mod tests {
             column_id_map_rev: Default::default(),
             primary_key_column_ids: Default::default(),
             partition_template: Default::default(),
+            retention_period: None,
         })
     }
 }
@@ -0,0 +1,624 @@
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use arrow::{datatypes::SchemaRef, record_batch::RecordBatch};
+use arrow_flight::decode::DecodedPayload;
+use async_trait::async_trait;
+use data_types::TableId;
+use ingester_query_grpc::{influxdata::iox::ingester::v1 as proto, IngesterQueryRequest};
+use iox_time::{Time, TimeProvider};
+use metric::{Metric, Registry, U64Counter};
+use parking_lot::Mutex;
+use prost::Message;
+use trace::{ctx::SpanContext, span::SpanRecorder};
+
+use crate::ingester::flight_client::{
+    Error as FlightClientError, FlightError, IngesterFlightClient, QueryData,
+};
+
+/// Persistence watermark that an ingester advertises for a partition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Watermark {
+    ingester_uuid: String,
+    completed_persistence_count: u64,
+    max_persisted_sequence_number: Option<u64>,
+}
+
+impl From<&proto::IngesterQueryResponseMetadata> for Watermark {
+    fn from(md: &proto::IngesterQueryResponseMetadata) -> Self {
+        Self {
+            ingester_uuid: md.ingester_uuid.clone(),
+            completed_persistence_count: md.completed_persistence_count,
+            max_persisted_sequence_number: md.max_persisted_sequence_number,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    ingester_address: Arc<str>,
+    table_id: TableId,
+    /// Encoded request, incl. namespace, projection and predicate.
+    request: Vec<u8>,
+}
+
+/// Clonable version of [`DecodedPayload`].
+#[derive(Debug, Clone, PartialEq)]
+enum CachedPayload {
+    None,
+    Schema(SchemaRef),
+    RecordBatch(RecordBatch),
+}
+
+impl From<&DecodedPayload> for CachedPayload {
+    fn from(payload: &DecodedPayload) -> Self {
+        match payload {
+            DecodedPayload::None => Self::None,
+            DecodedPayload::Schema(schema) => Self::Schema(Arc::clone(schema)),
+            DecodedPayload::RecordBatch(batch) => Self::RecordBatch(batch.clone()),
+        }
+    }
+}
+
+impl From<CachedPayload> for DecodedPayload {
+    fn from(payload: CachedPayload) -> Self {
+        match payload {
+            CachedPayload::None => Self::None,
+            CachedPayload::Schema(schema) => Self::Schema(schema),
+            CachedPayload::RecordBatch(batch) => Self::RecordBatch(batch),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedResponse {
+    messages: Vec<(CachedPayload, proto::IngesterQueryResponseMetadata)>,
+
+    /// Watermarks of all partitions in this response.
+    watermarks: Vec<(i64, Watermark)>,
+
+    fetched_at: Time,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<CacheKey, Arc<CachedResponse>>,
+
+    /// Latest watermark seen for every partition.
+    ///
+    /// Key: `(ingester_address, partition_id)`
+    watermarks: HashMap<(Arc<str>, i64), Watermark>,
+}
+
+impl State {
+    /// Record watermarks of a fresh response.
+    fn observe(&mut self, ingester_address: &Arc<str>, watermarks: &[(i64, Watermark)]) {
+        for (partition_id, watermark) in watermarks {
+            self.watermarks.insert(
+                (Arc::clone(ingester_address), *partition_id),
+                watermark.clone(),
+            );
+        }
+    }
+
+    /// Checks if any partition of the given response was persisted (or the ingester restarted) since the response was
+    /// fetched.
+    fn is_outdated(&self, ingester_address: &Arc<str>, response: &CachedResponse) -> bool {
+        response.watermarks.iter().any(|(partition_id, watermark)| {
+            self.watermarks
+                .get(&(Arc::clone(ingester_address), *partition_id))
+                .map(|latest| latest != watermark)
+                .unwrap_or_default()
+        })
+    }
+}
+
+#[derive(Debug)]
+struct CacheMetrics {
+    hit: U64Counter,
+    miss: U64Counter,
+    expired: U64Counter,
+    invalidated: U64Counter,
+}
+
+impl CacheMetrics {
+    fn new(metric_registry: &Registry) -> Self {
+        let metric: Metric<U64Counter> = metric_registry.register_metric(
+            "ingester_response_cache",
+            "number of ingester queries served by the querier-side response cache",
+        );
+
+        Self {
+            hit: metric.recorder(&[("status", "hit")]),
+            miss: metric.recorder(&[("status", "miss")]),
+            expired: metric.recorder(&[("status", "expired")]),
+            invalidated: metric.recorder(&[("status", "invalidated")]),
+        }
+    }
+}
+
+/// Caches complete ingester responses for a short time.
+///
+/// Dashboards tend to re-issue the same queries every few seconds. Without this cache, every refresh pulls the same
+/// unpersisted data from the ingesters again.
+///
+/// Responses are keyed by ingester, table and the full request (namespace, projection and predicate). A cached
+/// response is dropped if:
+///
+/// - it is older than the configured TTL, which bounds how stale the returned unpersisted data can be;
+/// - any ingester response (for any query) advertised a different persistence watermark (`ingester_uuid`,
+///   `completed_persistence_count` and `max_persisted_sequence_number`) for one of its partitions, i.e. data was
+///   persisted or the ingester restarted. Serving the cached response would otherwise return buffered data that is
+///   now also part of a parquet file;
+/// - the connection to the ingester is invalidated.
+///
+/// Only successful and fully drained responses are cached.
+#[derive(Debug)]
+pub struct ResponseCacheFlightClient {
+    /// The underlying client.
+    inner: Arc<dyn IngesterFlightClient>,
+
+    time_provider: Arc<dyn TimeProvider>,
+
+    ttl: Duration,
+
+    /// Maximum number of cached responses.
+    max_entries: usize,
+
+    state: Mutex<State>,
+
+    metrics: CacheMetrics,
+}
+
+impl ResponseCacheFlightClient {
+    /// Create new cache.
+    pub fn new(
+        inner: Arc<dyn IngesterFlightClient>,
+        time_provider: Arc<dyn TimeProvider>,
+        metric_registry: &Registry,
+        ttl: Duration,
+        max_entries: usize,
+    ) -> Self {
+        Self {
+            inner,
+            time_provider,
+            ttl,
+            max_entries,
+            state: Default::default(),
+            metrics: CacheMetrics::new(metric_registry),
+        }
+    }
+
+    fn get(&self, key: &CacheKey) -> Option<Arc<CachedResponse>> {
+        let mut state = self.state.lock();
+        let response = Arc::clone(state.entries.get(key)?);
+
+        let now = self.time_provider.now();
+        let expired = now
+            .checked_duration_since(response.fetched_at)
+            .map(|age| age >= self.ttl)
+            .unwrap_or_default();
+        if expired {
+            self.metrics.expired.inc(1);
+        } else if state.is_outdated(&key.ingester_address, &response) {
+            self.metrics.invalidated.inc(1);
+        } else {
+            self.metrics.hit.inc(1);
+            return Some(response);
+        }
+
+        state.entries.remove(key);
+        None
+    }
+
+    fn insert(&self, key: CacheKey, response: CachedResponse) {
+        let mut state = self.state.lock();
+        state.observe(&key.ingester_address, &response.watermarks);
+
+        if state.entries.len() >= self.max_entries && !state.entries.contains_key(&key) {
+            // evict oldest entry
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_k, v)| v.fetched_at)
+                .map(|(k, _v)| k.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+
+        if self.max_entries > 0 {
+            state.entries.insert(key, Arc::new(response));
+        }
+    }
+}
+
+#[async_trait]
+impl IngesterFlightClient for ResponseCacheFlightClient {
+    async fn invalidate_connection(&self, ingester_address: Arc<str>) {
+        {
+            let mut state = self.state.lock();
+            state
+                .entries
+                .retain(|k, _v| k.ingester_address != ingester_address);
+            state
+                .watermarks
+                .retain(|(addr, _), _v| addr != &ingester_address);
+        }
+
+        self.inner.invalidate_connection(ingester_address).await;
+    }
+
+    async fn query(
+        &self,
+        ingester_addr: Arc<str>,
+        request: IngesterQueryRequest,
+        span_context: Option<SpanContext>,
+    ) -> Result<Box<dyn QueryData>, FlightClientError> {
+        let span = span_context.map(|s| s.child("response cache"));
+        let mut span_recorder = SpanRecorder::new(span);
+
+        // requests that cannot be encoded are passed through, the inner client deals with them
+        let key = proto::IngesterQueryRequest::try_from(request.clone())
+            .ok()
+            .map(|encoded| CacheKey {
+                ingester_address: Arc::clone(&ingester_addr),
+                table_id: request.table_id,
+                request: encoded.encode_to_vec(),
+            });
+        let Some(key) = key else {
+            return self
+                .inner
+                .query(
+                    ingester_addr,
+                    request,
+                    span_recorder.span().map(|span| span.ctx.clone()),
+                )
+                .await;
+        };
+
+        if let Some(response) = self.get(&key) {
+            span_recorder.ok("hit");
+            return Ok(Box::new(CachedQueryData::new(&response)));
+        }
+        self.metrics.miss.inc(1);
+
+        let mut query_data = self
+            .inner
+            .query(
+                ingester_addr,
+                request,
+                span_recorder.span().map(|span| span.ctx.clone()),
+            )
+            .await?;
+
+        // drain the response so it can be replayed later
+        let mut messages = vec![];
+        let mut watermarks = vec![];
+        while let Some((payload, md)) = query_data
+            .next_message()
+            .await
+            .map_err(|source| FlightClientError::Flight { source })?
+        {
+            if matches!(payload, DecodedPayload::None) && !md.announcement {
+                // start of partition data, announcements carry the same watermark
+                watermarks.push((md.partition_id, Watermark::from(&md)));
+            }
+            messages.push((CachedPayload::from(&payload), md));
+        }
+
+        let response = CachedResponse {
+            messages,
+            watermarks,
+            fetched_at: self.time_provider.now(),
+        };
+        let query_data = CachedQueryData::new(&response);
+        self.insert(key, response);
+
+        span_recorder.ok("miss");
+        Ok(Box::new(query_data))
+    }
+}
+
+/// Replays a [`CachedResponse`].
+#[derive(Debug)]
+struct CachedQueryData {
+    messages: std::vec::IntoIter<(CachedPayload, proto::IngesterQueryResponseMetadata)>,
+}
+
+impl CachedQueryData {
+    fn new(response: &CachedResponse) -> Self {
+        Self {
+            messages: response.messages.clone().into_iter(),
+        }
+    }
+}
+
+#[async_trait]
+impl QueryData for CachedQueryData {
+    async fn next_message(
+        &mut self,
+    ) -> Result<Option<(DecodedPayload, proto::IngesterQueryResponseMetadata)>, FlightError> {
+        Ok(self
+            .messages
+            .next()
+            .map(|(payload, md)| (payload.into(), md)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::Int64Array;
+    use data_types::NamespaceId;
+    use iox_time::MockProvider;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hit() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider,
+            metric_registry,
+        } = TestSetup::new();
+
+        inner.push_response(response(1, 0));
+        assert_eq!(query(&client, request(1)).await, response(1, 0));
+        assert_eq!(inner.n_queries(), 1);
+
+        time_provider.inc(Duration::from_secs(1));
+        assert_eq!(query(&client, request(1)).await, response(1, 0));
+        assert_eq!(inner.n_queries(), 1);
+
+        // different request
+        inner.push_response(response(2, 0));
+        assert_eq!(query(&client, request(2)).await, response(2, 0));
+        assert_eq!(inner.n_queries(), 2);
+
+        assert_eq!(metric_value(&metric_registry, "hit"), 1);
+        assert_eq!(metric_value(&metric_registry, "miss"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_expire() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider,
+            metric_registry,
+        } = TestSetup::new();
+
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+
+        time_provider.inc(TTL);
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+        assert_eq!(inner.n_queries(), 2);
+
+        assert_eq!(metric_value(&metric_registry, "expired"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_on_persistence() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider: _,
+            metric_registry,
+        } = TestSetup::new();
+
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+
+        // another query reports that the partition was persisted in the meantime
+        inner.push_response(response(1, 1));
+        assert_eq!(query(&client, request(2)).await, response(1, 1));
+
+        // so the first query must not be served from the cache anymore
+        inner.push_response(response(1, 1));
+        assert_eq!(query(&client, request(1)).await, response(1, 1));
+        assert_eq!(inner.n_queries(), 3);
+
+        assert_eq!(metric_value(&metric_registry, "invalidated"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_connection() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider: _,
+            metric_registry: _,
+        } = TestSetup::new();
+
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+
+        client.invalidate_connection(ingester_address()).await;
+
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+        assert_eq!(inner.n_queries(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider: _,
+            metric_registry: _,
+        } = TestSetup::new();
+
+        client
+            .query(ingester_address(), request(1), None)
+            .await
+            .unwrap_err();
+
+        inner.push_response(response(1, 0));
+        assert_eq!(query(&client, request(1)).await, response(1, 0));
+        assert_eq!(inner.n_queries(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_max_entries() {
+        let TestSetup {
+            client,
+            inner,
+            time_provider,
+            metric_registry: _,
+        } = TestSetup::new();
+
+        for i in 1..=(MAX_ENTRIES as i64 + 1) {
+            inner.push_response(response(i, 0));
+            query(&client, request(i)).await;
+            time_provider.inc(Duration::from_millis(1));
+        }
+        assert_eq!(client.state.lock().entries.len(), MAX_ENTRIES);
+
+        // oldest entry was evicted
+        inner.push_response(response(1, 0));
+        query(&client, request(1)).await;
+        assert_eq!(inner.n_queries(), MAX_ENTRIES + 2);
+    }
+
+    const TTL: Duration = Duration::from_secs(10);
+    const MAX_ENTRIES: usize = 2;
+
+    struct TestSetup {
+        client: ResponseCacheFlightClient,
+        inner: Arc<MockClient>,
+        time_provider: Arc<MockProvider>,
+        metric_registry: Arc<Registry>,
+    }
+
+    impl TestSetup {
+        fn new() -> Self {
+            let inner = Arc::new(MockClient::default());
+            let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+            let metric_registry = Arc::new(Registry::new());
+            let client = ResponseCacheFlightClient::new(
+                Arc::clone(&inner) as _,
+                Arc::clone(&time_provider) as _,
+                &metric_registry,
+                TTL,
+                MAX_ENTRIES,
+            );
+
+            Self {
+                client,
+                inner,
+                time_provider,
+                metric_registry,
+            }
+        }
+    }
+
+    type Response = Vec<(CachedPayload, proto::IngesterQueryResponseMetadata)>;
+
+    /// Mock client that returns pre-configured responses or an error if there are none left.
+    #[derive(Debug, Default)]
+    struct MockClient {
+        responses: Mutex<Vec<Response>>,
+        n_queries: Mutex<usize>,
+    }
+
+    impl MockClient {
+        fn push_response(&self, response: Response) {
+            self.responses.lock().push(response);
+        }
+
+        fn n_queries(&self) -> usize {
+            *self.n_queries.lock()
+        }
+    }
+
+    #[async_trait]
+    impl IngesterFlightClient for MockClient {
+        async fn invalidate_connection(&self, _ingester_address: Arc<str>) {}
+
+        async fn query(
+            &self,
+            ingester_addr: Arc<str>,
+            _request: IngesterQueryRequest,
+            _span_context: Option<SpanContext>,
+        ) -> Result<Box<dyn QueryData>, FlightClientError> {
+            *self.n_queries.lock() += 1;
+
+            let mut responses = self.responses.lock();
+            if responses.is_empty() {
+                return Err(FlightClientError::CircuitBroken {
+                    ingester_address: ingester_addr.to_string(),
+                });
+            }
+            let messages = responses.remove(0);
+            Ok(Box::new(CachedQueryData {
+                messages: messages.into_iter(),
+            }))
+        }
+    }
+
+    const TEST_INGESTER: &str = "http://my-ingester";
+
+    fn ingester_address() -> Arc<str> {
+        Arc::from(TEST_INGESTER)
+    }
+
+    fn request(table_id: i64) -> IngesterQueryRequest {
+        IngesterQueryRequest {
+            namespace_id: NamespaceId::new(1),
+            table_id: TableId::new(table_id),
+            columns: vec![],
+            predicate: None,
+            announce_partitions: false,
+        }
+    }
+
+    /// Response with a single partition that contains a single row.
+    fn response(partition_id: i64, completed_persistence_count: u64) -> Response {
+        let md = proto::IngesterQueryResponseMetadata {
+            partition_id,
+            partition_hash_id: None,
+            ingester_uuid: "00000000-0000-0000-0000-000000000001".to_owned(),
+            completed_persistence_count,
+            max_persisted_sequence_number: None,
+            buffered_row_count: 0,
+            announcement: false,
+        };
+        let batch = RecordBatch::try_from_iter([(
+            "x",
+            Arc::new(Int64Array::from(vec![partition_id])) as _,
+        )])
+        .unwrap();
+
+        vec![
+            (CachedPayload::None, md.clone()),
+            (CachedPayload::Schema(batch.schema()), md.clone()),
+            (CachedPayload::RecordBatch(batch), md),
+        ]
+    }
+
+    async fn query(client: &ResponseCacheFlightClient, request: IngesterQueryRequest) -> Response {
+        let mut query_data = client
+            .query(ingester_address(), request, None)
+            .await
+            .unwrap();
+
+        let mut messages = vec![];
+        while let Some((payload, md)) = query_data.next_message().await.unwrap() {
+            messages.push((CachedPayload::from(&payload), md));
+        }
+        messages
+    }
+
+    fn metric_value(metric_registry: &Registry, status: &'static str) -> u64 {
+        metric_registry
+            .get_instrument::<Metric<U64Counter>>("ingester_response_cache")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("status", status)]))
+            .unwrap()
+            .fetch()
+    }
+}
@@ -0,0 +1,433 @@
+//! Demultiplexes a streamed ingester response into per-partition record batch streams.
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+};
+
+use arrow::record_batch::RecordBatch;
+use arrow_flight::decode::DecodedPayload;
+use data_types::PartitionId;
+use datafusion::{
+    error::DataFusionError,
+    physical_plan::{stream::RecordBatchStreamAdapter, SendableRecordBatchStream},
+};
+use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryResponseMetadata;
+use schema::Schema;
+use snafu::{ensure, OptionExt, ResultExt};
+use tokio::sync::Mutex;
+
+use super::{
+    ensure_schema,
+    flight_client::{Error as FlightClientError, QueryData},
+    BatchWithoutChunkSnafu, ChunkWithoutPartitionSnafu, DuplicatePartitionInfoSnafu, Error,
+    RemoteQuerySnafu, UnannouncedPartitionSnafu, UnexpectedAnnouncementSnafu,
+};
+
+/// Response of an ingester that announced all partitions upfront.
+///
+/// The response is only read when one of the [partition streams](Self::partition_stream) is polled. Data of other
+/// partitions that is read on the way is buffered until their streams are polled.
+pub(crate) struct IngesterResponseStream {
+    ingester_address: Arc<str>,
+
+    /// Schema of all partition streams.
+    schema: Schema,
+
+    state: Mutex<State>,
+}
+
+impl IngesterResponseStream {
+    /// Create new stream.
+    ///
+    /// `pending` is the first message after the announcements that was already read from `query_data`.
+    pub(crate) fn new(
+        ingester_address: Arc<str>,
+        query_data: Box<dyn QueryData>,
+        pending: Option<(DecodedPayload, IngesterQueryResponseMetadata)>,
+        schema: Schema,
+        partitions: impl IntoIterator<Item = PartitionId>,
+    ) -> Self {
+        Self {
+            ingester_address,
+            schema,
+            state: Mutex::new(State {
+                query_data,
+                pending,
+                partitions: partitions
+                    .into_iter()
+                    .map(|partition_id| (partition_id, PartitionState::default()))
+                    .collect(),
+                current: None,
+                error: None,
+            }),
+        }
+    }
+
+    /// Stream data of the given partition.
+    ///
+    /// The data of every partition can only be consumed once.
+    ///
+    /// # Panic
+    /// Polling the stream panics if the partition was not announced.
+    pub(crate) fn partition_stream(
+        self: &Arc<Self>,
+        partition_id: PartitionId,
+    ) -> SendableRecordBatchStream {
+        let stream = futures::stream::unfold(Some(Arc::clone(self)), move |this| async move {
+            let this = this?;
+            match this.next_batch(partition_id).await {
+                Ok(Some(batch)) => Some((Ok(batch), Some(this))),
+                Ok(None) => None,
+                Err(e) => Some((Err(DataFusionError::External(Box::new(e))), None)),
+            }
+        });
+
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.as_arrow(),
+            stream,
+        ))
+    }
+
+    async fn next_batch(
+        &self,
+        partition_id: PartitionId,
+    ) -> Result<Option<RecordBatch>, Arc<Error>> {
+        let mut state = self.state.lock().await;
+
+        loop {
+            if let Some(e) = &state.error {
+                return Err(Arc::clone(e));
+            }
+
+            let partition = state
+                .partitions
+                .get_mut(&partition_id)
+                .expect("partition was announced");
+            if let Some(batch) = partition.buffer.pop_front() {
+                return Ok(Some(batch));
+            }
+            if partition.complete {
+                return Ok(None);
+            }
+
+            if let Err(e) = state.advance(&self.ingester_address, &self.schema).await {
+                state.error = Some(Arc::new(e));
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for IngesterResponseStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IngesterResponseStream")
+            .field("ingester_address", &self.ingester_address)
+            .field("schema", &self.schema)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    query_data: Box<dyn QueryData>,
+
+    /// Message that was read from [`query_data`](Self::query_data) but not processed yet.
+    pending: Option<(DecodedPayload, IngesterQueryResponseMetadata)>,
+
+    partitions: HashMap<PartitionId, PartitionState>,
+
+    /// Partition that the ingester currently sends data for.
+    current: Option<PartitionId>,
+
+    /// Sticky error, reported to all partition streams.
+    error: Option<Arc<Error>>,
+}
+
+impl State {
+    /// Read and process the next message.
+    async fn advance(&mut self, ingester_address: &str, schema: &Schema) -> Result<(), Error> {
+        let msg = match self.pending.take() {
+            Some(msg) => Some(msg),
+            None => self
+                .query_data
+                .next_message()
+                .await
+                .map_err(|source| FlightClientError::Flight { source })
+                .context(RemoteQuerySnafu { ingester_address })?,
+        };
+
+        let Some((msg, md)) = msg else {
+            // end of response
+            self.current = None;
+            for partition in self.partitions.values_mut() {
+                partition.complete = true;
+            }
+            return Ok(());
+        };
+
+        match msg {
+            DecodedPayload::None => {
+                ensure!(
+                    !md.announcement,
+                    UnexpectedAnnouncementSnafu { ingester_address }
+                );
+
+                if let Some(current) = self.current.take() {
+                    self.partitions
+                        .get_mut(&current)
+                        .expect("current partition was announced")
+                        .complete = true;
+                }
+
+                let partition_id = PartitionId::new(md.partition_id);
+                let partition =
+                    self.partitions
+                        .get_mut(&partition_id)
+                        .context(UnannouncedPartitionSnafu {
+                            partition_id,
+                            ingester_address,
+                        })?;
+                ensure!(
+                    !partition.complete,
+                    DuplicatePartitionInfoSnafu {
+                        partition_id,
+                        ingester_address,
+                    }
+                );
+                self.current = Some(partition_id);
+            }
+            DecodedPayload::Schema(_) => {
+                // The batches are converted to the common schema, see `ensure_schema`.
+                ensure!(
+                    self.current.is_some(),
+                    ChunkWithoutPartitionSnafu { ingester_address }
+                );
+            }
+            DecodedPayload::RecordBatch(batch) => {
+                let current = self
+                    .current
+                    .context(BatchWithoutChunkSnafu { ingester_address })?;
+                let batch = ensure_schema(batch, schema)?;
+                self.partitions
+                    .get_mut(&current)
+                    .expect("current partition was announced")
+                    .buffer
+                    .push_back(batch);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct PartitionState {
+    /// Batches that were read but not consumed yet.
+    buffer: VecDeque<RecordBatch>,
+
+    /// No further data follows for this partition.
+    complete: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_flight::error::FlightError;
+    use arrow_util::assert_batches_eq;
+    use assert_matches::assert_matches;
+    use async_trait::async_trait;
+    use datafusion::physical_plan::common::collect;
+    use futures::StreamExt;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use schema::{builder::SchemaBuilder, InfluxFieldType, Projection};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demux() {
+        let stream = Arc::new(IngesterResponseStream::new(
+            Arc::from("addr"),
+            Box::new(MockQueryData {
+                results: vec![
+                    header(1),
+                    schema_msg("table foo=1 1"),
+                    batch("table foo=1 1"),
+                    batch("table foo=2 2"),
+                    header(2),
+                    schema_msg("table bar=20,foo=3 3"),
+                    batch("table bar=20,foo=3 3"),
+                ],
+            }),
+            None,
+            schema(),
+            [
+                PartitionId::new(1),
+                PartitionId::new(2),
+                PartitionId::new(3),
+            ],
+        ));
+
+        // poll partitions in reverse order, so the data of the others is buffered
+        let batches_3 = collect(stream.partition_stream(PartitionId::new(3)))
+            .await
+            .unwrap();
+        assert!(batches_3.is_empty());
+
+        let batches_2 = collect(stream.partition_stream(PartitionId::new(2)))
+            .await
+            .unwrap();
+        assert_batches_eq!(
+            [
+                "+------+-----+--------------------------------+",
+                "| bar  | foo | time                           |",
+                "+------+-----+--------------------------------+",
+                "| 20.0 | 3.0 | 1970-01-01T00:00:00.000000003Z |",
+                "+------+-----+--------------------------------+",
+            ],
+            &batches_2
+        );
+
+        let batches_1 = collect(stream.partition_stream(PartitionId::new(1)))
+            .await
+            .unwrap();
+        assert_batches_eq!(
+            [
+                "+-----+-----+--------------------------------+",
+                "| bar | foo | time                           |",
+                "+-----+-----+--------------------------------+",
+                "|     | 1.0 | 1970-01-01T00:00:00.000000001Z |",
+                "|     | 2.0 | 1970-01-01T00:00:00.000000002Z |",
+                "+-----+-----+--------------------------------+",
+            ],
+            &batches_1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pending_message() {
+        let stream = Arc::new(IngesterResponseStream::new(
+            Arc::from("addr"),
+            Box::new(MockQueryData {
+                results: vec![batch("table foo=1 1")],
+            }),
+            Some(header(1).unwrap()),
+            schema(),
+            [PartitionId::new(1)],
+        ));
+
+        let batches = collect(stream.partition_stream(PartitionId::new(1)))
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unannounced_partition() {
+        let stream = Arc::new(IngesterResponseStream::new(
+            Arc::from("addr"),
+            Box::new(MockQueryData {
+                results: vec![header(2), batch("table foo=1 1")],
+            }),
+            None,
+            schema(),
+            [PartitionId::new(1)],
+        ));
+
+        let err = collect(stream.partition_stream(PartitionId::new(1)))
+            .await
+            .unwrap_err();
+        assert_matches!(err, DataFusionError::External(_));
+        assert_eq!(
+            err.to_string(),
+            "External error: Got data for partition 2 that was not announced, ingester: addr",
+        );
+
+        // error is sticky
+        let err = collect(stream.partition_stream(PartitionId::new(1)))
+            .await
+            .unwrap_err();
+        assert_matches!(err, DataFusionError::External(_));
+    }
+
+    #[tokio::test]
+    async fn test_flight_error() {
+        let stream = Arc::new(IngesterResponseStream::new(
+            Arc::from("addr"),
+            Box::new(MockQueryData {
+                results: vec![
+                    header(1),
+                    batch("table foo=1 1"),
+                    Err(FlightError::Tonic(tonic::Status::internal("cow exploded"))),
+                ],
+            }),
+            None,
+            schema(),
+            [PartitionId::new(1), PartitionId::new(2)],
+        ));
+
+        // data that was read before the error is still returned
+        let mut partition_stream = stream.partition_stream(PartitionId::new(1));
+        partition_stream.next().await.unwrap().unwrap();
+        partition_stream.next().await.unwrap().unwrap_err();
+        assert!(partition_stream.next().await.is_none());
+
+        collect(stream.partition_stream(PartitionId::new(2)))
+            .await
+            .unwrap_err();
+    }
+
+    fn schema() -> Schema {
+        SchemaBuilder::new()
+            .influx_field("bar", InfluxFieldType::Float)
+            .influx_field("foo", InfluxFieldType::Float)
+            .timestamp()
+            .build()
+            .unwrap()
+    }
+
+    fn lp_to_record_batch(lp: &str) -> RecordBatch {
+        lp_to_mutable_batch(lp).1.to_arrow(Projection::All).unwrap()
+    }
+
+    type MockFlightResult = Result<(DecodedPayload, IngesterQueryResponseMetadata), FlightError>;
+
+    fn header(partition_id: i64) -> MockFlightResult {
+        Ok((
+            DecodedPayload::None,
+            IngesterQueryResponseMetadata {
+                partition_id,
+                ..Default::default()
+            },
+        ))
+    }
+
+    fn schema_msg(lp: &str) -> MockFlightResult {
+        Ok((
+            DecodedPayload::Schema(lp_to_record_batch(lp).schema()),
+            IngesterQueryResponseMetadata::default(),
+        ))
+    }
+
+    fn batch(lp: &str) -> MockFlightResult {
+        Ok((
+            DecodedPayload::RecordBatch(lp_to_record_batch(lp)),
+            IngesterQueryResponseMetadata::default(),
+        ))
+    }
+
+    #[derive(Debug)]
+    struct MockQueryData {
+        results: Vec<MockFlightResult>,
+    }
+
+    #[async_trait]
+    impl QueryData for MockQueryData {
+        async fn next_message(
+            &mut self,
+        ) -> Result<Option<(DecodedPayload, IngesterQueryResponseMetadata)>, FlightError> {
+            if self.results.is_empty() {
+                Ok(None)
+            } else {
+                self.results.remove(0).map(Some)
+            }
+        }
+    }
+}
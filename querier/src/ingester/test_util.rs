@@ -2,6 +2,7 @@ use super::IngesterConnection;
 use crate::cache::namespace::CachedTable;
 use async_trait::async_trait;
 use data_types::NamespaceId;
+use iox_query::exec::warnings::QueryWarnings;
 use parking_lot::Mutex;
 use schema::Schema as IOxSchema;
 use std::{any::Any, collections::HashSet, sync::Arc};
@@ -35,6 +36,7 @@ impl IngesterConnection for MockIngesterConnection {
         columns: Vec<String>,
         _predicate: &predicate::Predicate,
         _span: Option<Span>,
+        _query_warnings: Option<Arc<QueryWarnings>>,
     ) -> super::Result<Vec<super::IngesterPartition>> {
         let Some(partitions) = self.next_response.lock().take() else {
             return Ok(vec![]);
@@ -62,8 +64,10 @@ impl IngesterConnection for MockIngesterConnection {
                             .filter(|(_idx, f)| cols.contains(f.name()))
                             .map(|(idx, _f)| idx)
                             .collect::<Vec<_>>();
-                        let batches: Vec<_> = ic
-                            .batches
+                        let super::IngesterChunkData::Buffered(batches) = &ic.data else {
+                            panic!("mock only supports buffered chunks");
+                        };
+                        let batches: Vec<_> = batches
                             .iter()
                             .map(|batch| batch.project(&projection).unwrap())
                             .collect();
@@ -71,7 +75,7 @@ impl IngesterConnection for MockIngesterConnection {
                         assert!(!batches.is_empty(), "Error: empty batches");
                         let schema = IOxSchema::try_from(batches[0].schema()).unwrap();
                         super::IngesterChunk {
-                            batches,
+                            data: super::IngesterChunkData::Buffered(batches),
                             schema,
                             ..ic
                         }
@@ -22,6 +22,7 @@ mod ingester;
 mod namespace;
 mod parquet;
 mod query_log;
+mod quota;
 mod server;
 mod system_tables;
 mod table;
@@ -41,5 +42,7 @@ pub use ingester::{
     },
     Error as IngesterError, IngesterConnection, IngesterConnectionImpl, IngesterPartition,
 };
-pub use namespace::QuerierNamespace;
+pub use namespace::{FederatedNamespace, QuerierNamespace};
+pub use query_log::QueryLogEntry;
+pub use quota::QueryQuota;
 pub use server::QuerierServer;
@@ -0,0 +1,233 @@
+//! Queries across multiple namespaces.
+
+use crate::namespace::QuerierNamespace;
+use async_trait::async_trait;
+use datafusion::error::DataFusionError;
+use iox_query::{
+    exec::{ExecutionContextProvider, IOxSessionContext},
+    QueryChunk, QueryCompletedToken, QueryNamespace, QueryText,
+};
+use predicate::{rpc_predicate::QueryNamespaceMeta, Predicate};
+use schema::Schema;
+use std::{collections::BTreeMap, sync::Arc};
+use trace::ctx::SpanContext;
+
+/// A [`QuerierNamespace`] that can also access the tables of other namespaces.
+///
+/// SQL addresses the tables of a federated namespace via a schema of the same name, e.g.
+/// `SELECT * FROM cpu JOIN other_ns.mem USING (time)`. The table-based [`QueryNamespace`]
+/// interface names them `<namespace>.<table>`.
+///
+/// Everything else -- query log, system tables and DataFusion config -- is taken from the primary
+/// namespace. Every table enforces its own retention period, i.e. tables of federated namespaces
+/// fall back to the retention period of their own namespace.
+#[derive(Debug)]
+pub struct FederatedNamespace {
+    /// The namespace that the query was issued against.
+    primary: Arc<QuerierNamespace>,
+
+    /// Other namespaces, keyed by name.
+    federated: BTreeMap<Arc<str>, Arc<QuerierNamespace>>,
+}
+
+impl FederatedNamespace {
+    /// Create new federation of `primary` and `federated`.
+    ///
+    /// Federated namespaces with the same name as the primary one are ignored.
+    pub fn new(
+        primary: Arc<QuerierNamespace>,
+        federated: impl IntoIterator<Item = Arc<QuerierNamespace>>,
+    ) -> Self {
+        let federated = federated
+            .into_iter()
+            .filter(|ns| ns.name != primary.name)
+            .map(|ns| (ns.name(), ns))
+            .collect();
+
+        Self { primary, federated }
+    }
+
+    /// The namespace that the query was issued against.
+    pub fn primary(&self) -> &Arc<QuerierNamespace> {
+        &self.primary
+    }
+
+    /// Names of the federated namespaces, sorted.
+    pub fn federated_names(&self) -> Vec<Arc<str>> {
+        self.federated.keys().cloned().collect()
+    }
+
+    /// Find namespace and unqualified table name for given table name.
+    fn resolve<'a>(&'a self, table_name: &'a str) -> Option<(&'a QuerierNamespace, &'a str)> {
+        if self.primary.tables.contains_key(table_name) {
+            return Some((&self.primary, table_name));
+        }
+
+        let (ns_name, table_name) = table_name.split_once('.')?;
+        let ns = self.federated.get(ns_name)?;
+        Some((ns, table_name))
+    }
+}
+
+impl QueryNamespaceMeta for FederatedNamespace {
+    fn table_names(&self) -> Vec<String> {
+        let mut names = self.primary.table_names();
+        for (ns_name, ns) in &self.federated {
+            names.extend(
+                ns.table_names()
+                    .into_iter()
+                    .map(|table_name| format!("{ns_name}.{table_name}")),
+            );
+        }
+        names.sort();
+        names
+    }
+
+    fn table_schema(&self, table_name: &str) -> Option<Schema> {
+        let (ns, table_name) = self.resolve(table_name)?;
+        ns.table_schema(table_name)
+    }
+}
+
+#[async_trait]
+impl QueryNamespace for FederatedNamespace {
+    async fn chunks(
+        &self,
+        table_name: &str,
+        predicate: &Predicate,
+        projection: Option<&Vec<usize>>,
+        ctx: IOxSessionContext,
+    ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError> {
+        match self.resolve(table_name) {
+            Some((ns, table_name)) => ns.chunks(table_name, predicate, projection, ctx).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    fn retention_time_ns(&self, table_name: &str) -> Option<i64> {
+        match self.resolve(table_name) {
+            Some((ns, table_name)) => ns.retention_time_ns(table_name),
+            None => self.primary.retention_time_ns(table_name),
+        }
+    }
+
+    fn record_query(
+        &self,
+        ctx: &IOxSessionContext,
+        query_type: &str,
+        query_text: QueryText,
+    ) -> QueryCompletedToken {
+        self.primary.record_query(ctx, query_type, query_text)
+    }
+
+    fn as_meta(&self) -> &dyn QueryNamespaceMeta {
+        self
+    }
+}
+
+impl ExecutionContextProvider for FederatedNamespace {
+    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+        let catalog = self
+            .federated
+            .values()
+            .fold(self.primary.catalog_provider(), |catalog, ns| {
+                catalog.with_federated(ns)
+            });
+
+        self.primary
+            .new_query_context_with_catalog(catalog, span_ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace::test_util::querier_namespace;
+    use arrow_util::test_util::batches_to_sorted_lines;
+    use data_types::ColumnType;
+    use iox_query::frontend::sql::SqlQueryPlanner;
+    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use iox_time::Time;
+
+    #[tokio::test]
+    async fn test_federated_query() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+
+        let ns1 = catalog.create_namespace_with_retention("ns1", None).await;
+        let table_cpu = ns1.create_table("cpu").await;
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("load", ColumnType::F64).await;
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(1))
+            .with_line_protocol("cpu,host=a load=1 11\ncpu,host=b load=2 11")
+            .with_min_time(11)
+            .with_max_time(11);
+        table_cpu
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let ns2 = catalog.create_namespace_with_retention("ns2", None).await;
+        let table_mem = ns2.create_table("mem").await;
+        table_mem.create_column("host", ColumnType::Tag).await;
+        table_mem.create_column("time", ColumnType::Time).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(2))
+            .with_line_protocol("mem,host=a perc=50 11\nmem,host=c perc=70 11")
+            .with_min_time(11)
+            .with_max_time(11);
+        table_mem
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let ns1 = Arc::new(querier_namespace(&ns1).await);
+        let ns2 = Arc::new(querier_namespace(&ns2).await);
+        let federated = FederatedNamespace::new(Arc::clone(&ns1), [Arc::clone(&ns1), ns2]);
+
+        assert_eq!(federated.federated_names(), vec![Arc::from("ns2")]);
+        assert_eq!(federated.table_names(), vec!["cpu", "ns2.mem"]);
+        assert!(federated.table_schema("cpu").is_some());
+        assert!(federated.table_schema("ns2.mem").is_some());
+        assert!(federated.table_schema("mem").is_none());
+        assert!(federated.table_schema("ns3.mem").is_none());
+
+        insta::assert_yaml_snapshot!(
+            run(
+                &federated,
+                "SELECT cpu.host, load, perc FROM cpu JOIN ns2.mem ON cpu.host = mem.host"
+            )
+            .await,
+            @r###"
+        ---
+        - +------+------+------+
+        - "| host | load | perc |"
+        - +------+------+------+
+        - "| a    | 1.0  | 50.0 |"
+        - +------+------+------+
+        "###
+        );
+
+        // the plain namespace does not know about the other namespace
+        let err = SqlQueryPlanner::default()
+            .query("SELECT * FROM ns2.mem", &ns1.new_query_context(None))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Error during planning: table 'public.ns2.mem' not found"
+        );
+    }
+
+    async fn run(namespace: &FederatedNamespace, sql: &str) -> Vec<String> {
+        let ctx = namespace.new_query_context(None);
+        let plan = SqlQueryPlanner::default().query(sql, &ctx).await.unwrap();
+        batches_to_sorted_lines(&ctx.collect(plan).await.unwrap())
+    }
+}
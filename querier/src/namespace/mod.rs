@@ -4,15 +4,18 @@ use crate::{
     cache::{namespace::CachedNamespace, CatalogCache},
     ingester::IngesterConnection,
     parquet::ChunkAdapter,
-    query_log::QueryLog,
+    query_log::{QueryLog, QueryPhaseMetrics},
     table::{PruneMetrics, QuerierTable, QuerierTableArgs},
 };
 use data_types::NamespaceId;
 use iox_query::exec::Executor;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
+mod federated;
 mod query_access;
 
+pub use federated::FederatedNamespace;
+
 #[cfg(test)]
 mod test_util;
 
@@ -25,8 +28,10 @@ pub struct QuerierNamespaceArgs {
     pub exec: Arc<Executor>,
     pub ingester_connection: Option<Arc<dyn IngesterConnection>>,
     pub query_log: Arc<QueryLog>,
+    pub query_phase_metrics: Arc<QueryPhaseMetrics>,
     pub prune_metrics: Arc<PruneMetrics>,
     pub datafusion_config: Arc<HashMap<String, String>>,
+    pub max_scanned_bytes: Option<u64>,
     pub include_debug_info_tables: bool,
 }
 
@@ -59,13 +64,19 @@ pub struct QuerierNamespace {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Query phase metrics.
+    query_phase_metrics: Arc<QueryPhaseMetrics>,
+
     /// DataFusion config.
     datafusion_config: Arc<HashMap<String, String>>,
 
+    /// Maximum number of bytes that a single query may scan.
+    max_scanned_bytes: Option<u64>,
+
     /// Include debug info tables.
     include_debug_info_tables: bool,
 
-    /// Retention period.
+    /// Retention period of the namespace, tables may override it.
     retention_period: Option<Duration>,
 }
 
@@ -79,8 +90,10 @@ impl QuerierNamespace {
             exec,
             ingester_connection,
             query_log,
+            query_phase_metrics,
             prune_metrics,
             datafusion_config,
+            max_scanned_bytes,
             include_debug_info_tables,
         } = args;
 
@@ -91,7 +104,7 @@ impl QuerierNamespace {
                 let table = Arc::new(QuerierTable::new(QuerierTableArgs {
                     namespace_id: ns.id,
                     namespace_name: Arc::clone(&name),
-                    namespace_retention_period: ns.retention_period,
+                    retention_period: cached_table.retention_period.or(ns.retention_period),
                     table_id: cached_table.id,
                     table_name: Arc::clone(table_name),
                     schema: cached_table.schema.clone(),
@@ -113,7 +126,9 @@ impl QuerierNamespace {
             exec,
             catalog_cache: Arc::clone(chunk_adapter.catalog_cache()),
             query_log,
+            query_phase_metrics,
             datafusion_config,
+            max_scanned_bytes,
             include_debug_info_tables,
             retention_period: ns.retention_period,
         }
@@ -131,6 +146,8 @@ impl QuerierNamespace {
         let time_provider = catalog_cache.time_provider();
         let chunk_adapter = Arc::new(ChunkAdapter::new(catalog_cache, metric_registry));
         let query_log = Arc::new(QueryLog::new(10, time_provider));
+        let query_phase_metrics =
+            Arc::new(QueryPhaseMetrics::new(&chunk_adapter.metric_registry()));
         let prune_metrics = Arc::new(PruneMetrics::new(&chunk_adapter.metric_registry()));
 
         Self::new(QuerierNamespaceArgs {
@@ -140,8 +157,10 @@ impl QuerierNamespace {
             exec,
             ingester_connection,
             query_log,
+            query_phase_metrics,
             prune_metrics,
             datafusion_config: Default::default(),
+            max_scanned_bytes: None,
             include_debug_info_tables: true,
         })
     }
@@ -163,6 +182,7 @@ mod tests {
     use super::*;
     use crate::namespace::test_util::querier_namespace;
     use data_types::ColumnType;
+    use iox_query::QueryNamespace;
     use iox_tests::TestCatalog;
     use schema::{
         builder::SchemaBuilder, InfluxColumnType, InfluxFieldType, Schema, TIME_COLUMN_NAME,
@@ -239,6 +259,31 @@ mod tests {
         assert_eq!(actual_schema, &expected_schema);
     }
 
+    #[tokio::test]
+    async fn test_table_retention_override() {
+        const HOUR_NS: i64 = 60 * 60 * 1_000_000_000;
+
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        ns.create_table("metrics").await;
+        let events = ns.create_table("events").await;
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .update_retention_period(events.table.id, Some(24 * HOUR_NS))
+            .await
+            .unwrap();
+
+        let qns = querier_namespace(&ns).await;
+        let now = catalog.time_provider().now().timestamp_nanos();
+        assert_eq!(qns.retention_time_ns("events"), Some(now - 24 * HOUR_NS));
+        assert_eq!(qns.retention_time_ns("metrics"), Some(now - HOUR_NS));
+        assert_eq!(qns.retention_time_ns("unknown"), Some(now - HOUR_NS));
+    }
+
     fn sorted<T>(mut v: Vec<T>) -> Vec<T>
     where
         T: Ord,
@@ -1,6 +1,7 @@
 //! This module contains implementations of [`iox_query`] interfaces for [QuerierNamespace].
 
 use crate::{
+    cache::CatalogCache,
     namespace::QuerierNamespace,
     query_log::QueryLog,
     system_tables::{SystemSchemaProvider, SYSTEM_SCHEMA},
@@ -21,7 +22,11 @@ use iox_query::{
 use observability_deps::tracing::{debug, trace};
 use predicate::{rpc_predicate::QueryNamespaceMeta, Predicate};
 use schema::Schema;
-use std::{any::Any, collections::HashMap, sync::Arc};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 use trace::ctx::SpanContext;
 
 impl QueryNamespaceMeta for QuerierNamespace {
@@ -61,6 +66,8 @@ impl QueryNamespace for QuerierNamespace {
                 predicate,
                 ctx.child_span("QuerierNamespace chunks"),
                 projection,
+                ctx.pruning_reports(),
+                ctx.query_warnings(),
             )
             .await?;
 
@@ -80,10 +87,13 @@ impl QueryNamespace for QuerierNamespace {
         Ok(chunks)
     }
 
-    fn retention_time_ns(&self) -> Option<i64> {
-        self.retention_period.map(|d| {
-            self.catalog_cache.time_provider().now().timestamp_nanos() - d.as_nanos() as i64
-        })
+    fn retention_time_ns(&self, table_name: &str) -> Option<i64> {
+        match self.tables.get(table_name) {
+            Some(table) => table.retention_time(),
+            None => self.retention_period.map(|d| {
+                self.catalog_cache.time_provider().now().timestamp_nanos() - d.as_nanos() as i64
+            }),
+        }
     }
 
     fn record_query(
@@ -96,8 +106,17 @@ impl QueryNamespace for QuerierNamespace {
         // will be set.
         let query_log = Arc::clone(&self.query_log);
         let trace_id = ctx.span().map(|s| s.ctx.trace_id);
-        let entry = query_log.push(self.id, query_type, query_text, trace_id);
-        QueryCompletedToken::new(move |success| query_log.set_completed(entry, success))
+        let issuer = ctx.issuer().cloned();
+        let entry = query_log.push(self.id, query_type, query_text, trace_id, issuer);
+        let query_phase_metrics = Arc::clone(&self.query_phase_metrics);
+        let pruning_reports = ctx.pruning_reports();
+        QueryCompletedToken::new(move |completion| {
+            query_phase_metrics.record(&completion.timings);
+            if let Some(pruning_reports) = pruning_reports {
+                entry.set_pruning_reports(pruning_reports.reports());
+            }
+            query_log.set_completed(entry, completion)
+        })
     }
 
     fn as_meta(&self) -> &dyn QueryNamespaceMeta {
@@ -115,8 +134,14 @@ pub struct QuerierCatalogProvider {
     /// Query log.
     query_log: Arc<QueryLog>,
 
+    /// Catalog cache.
+    catalog_cache: Arc<CatalogCache>,
+
     /// Include debug info tables.
     include_debug_info_tables: bool,
+
+    /// Tables of other namespaces, exposed as one schema per namespace.
+    federated: BTreeMap<Arc<str>, Arc<HashMap<Arc<str>, Arc<QuerierTable>>>>,
 }
 
 impl QuerierCatalogProvider {
@@ -125,9 +150,20 @@ impl QuerierCatalogProvider {
             namespace_id: namespace.id,
             tables: Arc::clone(&namespace.tables),
             query_log: Arc::clone(&namespace.query_log),
+            catalog_cache: Arc::clone(&namespace.catalog_cache),
             include_debug_info_tables: namespace.include_debug_info_tables,
+            federated: BTreeMap::new(),
         }
     }
+
+    /// Expose the tables of `namespace` as schema `<namespace name>`.
+    ///
+    /// Built-in schemas take precedence over federated namespaces of the same name.
+    pub(crate) fn with_federated(mut self, namespace: &QuerierNamespace) -> Self {
+        self.federated
+            .insert(Arc::clone(&namespace.name), Arc::clone(&namespace.tables));
+        self
+    }
 }
 
 impl CatalogProvider for QuerierCatalogProvider {
@@ -136,7 +172,13 @@ impl CatalogProvider for QuerierCatalogProvider {
     }
 
     fn schema_names(&self) -> Vec<String> {
-        vec![DEFAULT_SCHEMA.to_string(), SYSTEM_SCHEMA.to_string()]
+        let mut names = vec![DEFAULT_SCHEMA.to_string(), SYSTEM_SCHEMA.to_string()];
+        for name in self.federated.keys() {
+            if !names.iter().any(|n| n == name.as_ref()) {
+                names.push(name.to_string());
+            }
+        }
+        names
     }
 
     fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
@@ -147,9 +189,15 @@ impl CatalogProvider for QuerierCatalogProvider {
             SYSTEM_SCHEMA => Some(Arc::new(SystemSchemaProvider::new(
                 Arc::clone(&self.query_log),
                 self.namespace_id,
+                Arc::clone(&self.tables),
+                Arc::clone(&self.catalog_cache),
                 self.include_debug_info_tables,
             ))),
-            _ => None,
+            _ => self.federated.get(name).map(|tables| {
+                Arc::new(UserSchemaProvider {
+                    tables: Arc::clone(tables),
+                }) as _
+            }),
         }
     }
 }
@@ -168,7 +216,7 @@ impl CatalogProvider for QuerierNamespace {
     }
 }
 
-/// Provider for user-provided tables in [`DEFAULT_SCHEMA`].
+/// Provider for user-provided tables in [`DEFAULT_SCHEMA`] or in the schema of a federated namespace.
 struct UserSchemaProvider {
     /// A snapshot of all tables.
     tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
@@ -195,13 +243,19 @@ impl SchemaProvider for UserSchemaProvider {
     }
 }
 
-impl ExecutionContextProvider for QuerierNamespace {
-    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+impl QuerierNamespace {
+    /// Create query context that uses `catalog` as its default catalog.
+    pub(crate) fn new_query_context_with_catalog(
+        &self,
+        catalog: QuerierCatalogProvider,
+        span_ctx: Option<SpanContext>,
+    ) -> IOxSessionContext {
         let mut cfg = self
             .exec
             .new_execution_config(ExecutorType::Query)
-            .with_default_catalog(Arc::new(QuerierCatalogProvider::from_namespace(self)) as _)
-            .with_span_context(span_ctx);
+            .with_default_catalog(Arc::new(catalog) as _)
+            .with_span_context(span_ctx)
+            .with_max_scanned_bytes(self.max_scanned_bytes);
 
         for (k, v) in self.datafusion_config.as_ref() {
             cfg = cfg.with_config_option(k, v);
@@ -209,6 +263,17 @@ impl ExecutionContextProvider for QuerierNamespace {
 
         cfg.build()
     }
+
+    /// Catalog provider for this namespace.
+    pub(crate) fn catalog_provider(&self) -> QuerierCatalogProvider {
+        QuerierCatalogProvider::from_namespace(self)
+    }
+}
+
+impl ExecutionContextProvider for QuerierNamespace {
+    fn new_query_context(&self, span_ctx: Option<SpanContext>) -> IOxSessionContext {
+        self.new_query_context_with_catalog(self.catalog_provider(), span_ctx)
+    }
 }
 
 #[cfg(test)]
@@ -217,7 +282,7 @@ mod tests {
     use crate::namespace::test_util::{clear_parquet_cache, querier_namespace};
     use arrow::record_batch::RecordBatch;
     use arrow_util::test_util::{batches_to_sorted_lines, Normalizer};
-    use data_types::ColumnType;
+    use data_types::{ColumnMetadata, ColumnType};
     use datafusion::common::DataFusionError;
     use iox_query::frontend::sql::SqlQueryPlanner;
     use iox_tests::{TestCatalog, TestParquetFileBuilder};
@@ -575,6 +640,146 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_system_chunks_and_partitions() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        let table_mem = ns.create_table("mem").await;
+
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("load", ColumnType::F64).await;
+        table_mem.create_column("host", ColumnType::Tag).await;
+        table_mem.create_column("time", ColumnType::Time).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+
+        let partition_cpu_a = table_cpu.create_partition("a").await;
+        let partition_cpu_b = table_cpu.create_partition("b").await;
+        let partition_mem_c = table_mem.create_partition("c").await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(1))
+            .with_line_protocol("cpu,host=a load=1 11")
+            .with_min_time(11)
+            .with_max_time(11);
+        partition_cpu_a.create_parquet_file(builder).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(2))
+            .with_line_protocol("cpu,host=a load=2 22")
+            .with_min_time(22)
+            .with_max_time(22);
+        partition_cpu_a.create_parquet_file(builder).await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(3))
+            .with_line_protocol("cpu,host=b load=3 33")
+            .with_min_time(33)
+            .with_max_time(33);
+        partition_cpu_b.create_parquet_file(builder).await;
+
+        let lp = [
+            "mem,host=c perc=50 11",
+            "mem,host=c perc=51 12",
+            "mem,host=d perc=53 14",
+        ]
+        .join("\n");
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(4))
+            .with_line_protocol(&lp)
+            .with_min_time(11)
+            .with_max_time(14);
+        partition_mem_c.create_parquet_file(builder).await;
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT table_name, chunk_type, row_count, min_time, max_time FROM system.chunks",
+            ).await,
+            @r###"
+        ---
+        - +------------+------------+-----------+--------------------------------+--------------------------------+
+        - "| table_name | chunk_type | row_count | min_time                       | max_time                       |"
+        - +------------+------------+-----------+--------------------------------+--------------------------------+
+        - "| cpu        | parquet    | 1         | 1970-01-01T00:00:00.000000011Z | 1970-01-01T00:00:00.000000011Z |"
+        - "| cpu        | parquet    | 1         | 1970-01-01T00:00:00.000000022Z | 1970-01-01T00:00:00.000000022Z |"
+        - "| cpu        | parquet    | 1         | 1970-01-01T00:00:00.000000033Z | 1970-01-01T00:00:00.000000033Z |"
+        - "| mem        | parquet    | 3         | 1970-01-01T00:00:00.000000011Z | 1970-01-01T00:00:00.000000014Z |"
+        - +------------+------------+-----------+--------------------------------+--------------------------------+
+        "###
+        );
+
+        insta::assert_yaml_snapshot!(
+            format_query(
+                &querier_namespace,
+                "SELECT table_name, chunk_count, parquet_chunk_count, row_count, min_time, max_time FROM system.partitions",
+            ).await,
+            @r###"
+        ---
+        - +------------+-------------+---------------------+-----------+--------------------------------+--------------------------------+
+        - "| table_name | chunk_count | parquet_chunk_count | row_count | min_time                       | max_time                       |"
+        - +------------+-------------+---------------------+-----------+--------------------------------+--------------------------------+
+        - "| cpu        | 1           | 1                   | 1         | 1970-01-01T00:00:00.000000033Z | 1970-01-01T00:00:00.000000033Z |"
+        - "| cpu        | 2           | 2                   | 2         | 1970-01-01T00:00:00.000000011Z | 1970-01-01T00:00:00.000000022Z |"
+        - "| mem        | 1           | 1                   | 3         | 1970-01-01T00:00:00.000000011Z | 1970-01-01T00:00:00.000000014Z |"
+        - +------------+-------------+---------------------+-----------+--------------------------------+--------------------------------+
+        "###
+        );
+    }
+
+    #[tokio::test]
+    async fn test_system_columns() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        let table_mem = ns.create_table("mem").await;
+
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        let load = table_cpu.create_column("load", ColumnType::F64).await;
+        table_mem.create_column("perc", ColumnType::F64).await;
+
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .columns()
+            .update_metadata(ColumnMetadata {
+                column_id: load.column.id,
+                description: Some(String::from("CPU load")),
+                unit: Some(String::from("percent")),
+                semantic_type: Some(String::from("gauge")),
+            })
+            .await
+            .unwrap();
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        insta::assert_yaml_snapshot!(
+            format_query(&querier_namespace, "SELECT * FROM system.columns").await,
+            @r###"
+        ---
+        - +------------+-------------+-------------+-------------+---------+---------------+
+        - "| table_name | column_name | column_type | description | unit    | semantic_type |"
+        - +------------+-------------+-------------+-------------+---------+---------------+
+        - "| cpu        | host        | tag         |             |         |               |"
+        - "| cpu        | load        | f64         | CPU load    | percent | gauge         |"
+        - "| cpu        | time        | time        |             |         |               |"
+        - "| mem        | perc        | f64         |             |         |               |"
+        - +------------+-------------+-------------+-------------+---------+---------------+
+        "###
+        );
+    }
+
     async fn format_query(querier_namespace: &Arc<QuerierNamespace>, sql: &str) -> Vec<String> {
         format_query_with_span_ctx(querier_namespace, sql, None).await
     }
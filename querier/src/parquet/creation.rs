@@ -1,9 +1,11 @@
 use std::{collections::HashMap, sync::Arc};
 
 use data_types::{ChunkId, ChunkOrder, ColumnId, ParquetFile, PartitionId, TransitionPartitionId};
+use datafusion::scalar::ScalarValue;
 use futures::StreamExt;
 use hashbrown::HashSet;
 use iox_catalog::interface::Catalog;
+use iox_query::chunk_statistics::{ColumnRange, ColumnRanges};
 use parquet_file::chunk::ParquetChunk;
 use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 use schema::{sort::SortKey, Schema};
@@ -176,6 +178,12 @@ impl ChunkAdapter {
             parquet_file.file.partition_hash_id.as_ref(),
         ));
 
+        let column_ranges = file_column_ranges(
+            &parquet_file.file,
+            &cached_table,
+            &cached_partition.column_ranges,
+        );
+
         let meta = Arc::new(QuerierParquetChunkMeta {
             chunk_id,
             order,
@@ -190,12 +198,43 @@ impl ChunkAdapter {
             self.catalog_cache.parquet_store(),
         ));
 
-        QuerierParquetChunk::new(
-            parquet_chunk,
-            meta,
-            Arc::clone(&cached_partition.column_ranges),
-        )
+        QuerierParquetChunk::new(parquet_chunk, meta, column_ranges)
+    }
+}
+
+/// Narrow the partition-wide `partition_ranges` down to the tag value ranges
+/// recorded for `file` in the catalog, if any.
+///
+/// This allows the file to be pruned by predicates on tag columns that are not
+/// part of the partition key, without fetching the parquet file metadata.
+fn file_column_ranges(
+    file: &ParquetFile,
+    cached_table: &CachedTable,
+    partition_ranges: &ColumnRanges,
+) -> ColumnRanges {
+    let Some(tag_ranges) = file.tag_ranges.as_ref().filter(|r| !r.is_empty()) else {
+        return Arc::clone(partition_ranges);
+    };
+
+    let mut ranges = partition_ranges.as_ref().clone();
+    for (column_id, range) in tag_ranges.iter() {
+        // ignore columns that were removed from the table
+        let Some(column_name) = cached_table.column_id_map.get(&column_id) else {
+            continue;
+        };
+
+        // The values in the file are a subset of the values in the partition,
+        // so the file range is at least as narrow as any partition range.
+        ranges.insert(
+            Arc::clone(column_name),
+            ColumnRange {
+                min_value: Arc::new(ScalarValue::from(range.min.as_str())),
+                max_value: Arc::new(ScalarValue::from(range.max.as_str())),
+            },
+        );
     }
+
+    Arc::new(ranges)
 }
 
 /// [`ParquetFile`] with some additional fields.
@@ -229,3 +268,110 @@ impl PreparedParquetFile {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use data_types::{
+        ColumnSet, CompactionLevel, NamespaceId, ParquetFileId, TableId, TagRange, TagRanges,
+        Timestamp,
+    };
+    use schema::SchemaBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_file_column_ranges() {
+        let cached_table = CachedTable {
+            id: TableId::new(1),
+            schema: SchemaBuilder::new()
+                .tag("region")
+                .tag("host")
+                .timestamp()
+                .build()
+                .unwrap(),
+            column_id_map: HashMap::from([
+                (ColumnId::new(1), Arc::from("region")),
+                (ColumnId::new(2), Arc::from("host")),
+            ]),
+            column_id_map_rev: HashMap::from([
+                (Arc::from("region"), ColumnId::new(1)),
+                (Arc::from("host"), ColumnId::new(2)),
+            ]),
+            primary_key_column_ids: Box::new([]),
+            partition_template: Default::default(),
+            retention_period: None,
+        };
+        let partition_ranges: ColumnRanges = Arc::new(HashMap::from([(
+            Arc::from("region"),
+            ColumnRange {
+                min_value: Arc::new(ScalarValue::from("eu")),
+                max_value: Arc::new(ScalarValue::from("eu")),
+            },
+        )]));
+
+        // Files without tag ranges use the partition ranges.
+        let mut file = ParquetFile {
+            id: ParquetFileId::new(1),
+            namespace_id: NamespaceId::new(1),
+            table_id: TableId::new(1),
+            partition_id: PartitionId::new(1),
+            partition_hash_id: None,
+            object_store_id: Uuid::new_v4(),
+            min_time: Timestamp::new(1),
+            max_time: Timestamp::new(2),
+            to_delete: None,
+            file_size_bytes: 1,
+            row_count: 1,
+            compaction_level: CompactionLevel::Initial,
+            created_at: Timestamp::new(1),
+            column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+            max_l0_created_at: Timestamp::new(1),
+            tag_ranges: None,
+        };
+        let got = file_column_ranges(&file, &cached_table, &partition_ranges);
+        assert!(Arc::ptr_eq(&got, &partition_ranges));
+
+        // Tag ranges extend the partition ranges, ignoring unknown columns.
+        file.tag_ranges = Some(TagRanges::new([
+            (
+                ColumnId::new(1),
+                TagRange {
+                    min: "eu".to_string(),
+                    max: "eu".to_string(),
+                },
+            ),
+            (
+                ColumnId::new(2),
+                TagRange {
+                    min: "a".to_string(),
+                    max: "c".to_string(),
+                },
+            ),
+            (
+                ColumnId::new(42),
+                TagRange {
+                    min: "x".to_string(),
+                    max: "y".to_string(),
+                },
+            ),
+        ]));
+        let got = file_column_ranges(&file, &cached_table, &partition_ranges);
+        let expected = HashMap::from([
+            (
+                Arc::from("region"),
+                ColumnRange {
+                    min_value: Arc::new(ScalarValue::from("eu")),
+                    max_value: Arc::new(ScalarValue::from("eu")),
+                },
+            ),
+            (
+                Arc::from("host"),
+                ColumnRange {
+                    min_value: Arc::new(ScalarValue::from("a")),
+                    max_value: Arc::new(ScalarValue::from("c")),
+                },
+            ),
+        ]);
+        assert_eq!(got.as_ref(), &expected);
+    }
+}
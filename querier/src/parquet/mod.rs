@@ -73,7 +73,7 @@ impl QuerierParquetChunk {
         column_ranges: ColumnRanges,
     ) -> Self {
         let stats = Arc::new(create_chunk_statistics(
-            parquet_chunk.rows() as u64,
+            Some(parquet_chunk.rows() as u64),
             parquet_chunk.schema(),
             Some(parquet_chunk.timestamp_min_max()),
             &column_ranges,
@@ -1,9 +1,12 @@
 //! Ring buffer of queries that have been run with some brief information
 
 use data_types::NamespaceId;
-use iox_query::QueryText;
+use iox_query::{
+    pruning::PruningReport, QueryCompletion, QueryPhase, QueryPhaseTimings, QueryText,
+};
 use iox_time::{Time, TimeProvider};
-use observability_deps::tracing::warn;
+use metric::DurationHistogram;
+use observability_deps::tracing::{debug, warn};
 use parking_lot::Mutex;
 use std::{
     collections::VecDeque,
@@ -29,6 +32,9 @@ pub struct QueryLogEntry {
     /// The trace ID if any
     pub trace_id: Option<TraceId>,
 
+    /// Who issued the query, if known (e.g. the client address)
+    pub issuer: Option<Arc<str>>,
+
     /// Time at which the query was run
     pub issue_time: Time,
 
@@ -38,6 +44,12 @@ pub struct QueryLogEntry {
 
     /// If the query completed successfully
     pub success: atomic::AtomicBool,
+
+    /// Time spent in the different query phases, set on completion.
+    phase_timings: Mutex<Option<QueryPhaseTimings>>,
+
+    /// Pruning reports of the scanned tables, if requested by the query.
+    pruning_reports: Mutex<Vec<PruningReport>>,
 }
 
 impl std::fmt::Debug for QueryLogEntry {
@@ -45,9 +57,12 @@ impl std::fmt::Debug for QueryLogEntry {
         f.debug_struct("QueryLogEntry")
             .field("query_type", &self.query_type)
             .field("query_text", &self.query_text.to_string())
+            .field("issuer", &self.issuer)
             .field("issue_time", &self.issue_time)
             .field("query_completed_duration", &self.query_completed_duration)
             .field("success", &self.success)
+            .field("phase_timings", &self.phase_timings)
+            .field("pruning_reports", &self.pruning_reports)
             .finish()
     }
 }
@@ -59,6 +74,7 @@ impl QueryLogEntry {
         query_type: String,
         query_text: QueryText,
         trace_id: Option<TraceId>,
+        issuer: Option<Arc<str>>,
         issue_time: Time,
     ) -> Self {
         Self {
@@ -66,9 +82,12 @@ impl QueryLogEntry {
             query_type,
             query_text,
             trace_id,
+            issuer,
             issue_time,
             query_completed_duration: UNCOMPLETED_DURATION.into(),
             success: atomic::AtomicBool::new(false),
+            phase_timings: Mutex::new(None),
+            pruning_reports: Mutex::new(vec![]),
         }
     }
 
@@ -84,11 +103,38 @@ impl QueryLogEntry {
         }
     }
 
+    /// If this query is completed, returns the time it spent in the different phases.
+    pub fn phase_timings(&self) -> Option<QueryPhaseTimings> {
+        *self.phase_timings.lock()
+    }
+
+    /// Pruning reports of the tables that were scanned by this query.
+    ///
+    /// This is empty unless pruning reports were enabled for the query, see
+    /// [`IoxConfigExt::pruning_report`](iox_query::config::IoxConfigExt::pruning_report).
+    pub fn pruning_reports(&self) -> Vec<PruningReport> {
+        self.pruning_reports.lock().clone()
+    }
+
+    /// Set the pruning reports of this query.
+    pub fn set_pruning_reports(&self, reports: Vec<PruningReport>) {
+        *self.pruning_reports.lock() = reports;
+    }
+
     /// Returns true if `set_completed` was called with `success=true`
     pub fn success(&self) -> bool {
         self.success.load(atomic::Ordering::SeqCst)
     }
 
+    /// Human-readable status of this query: `running`, `success` or `failed`.
+    pub fn status(&self) -> &'static str {
+        match (self.query_completed_duration(), self.success()) {
+            (None, _) => "running",
+            (Some(_), true) => "success",
+            (Some(_), false) => "failed",
+        }
+    }
+
     /// Mark this entry complete as of `now`. `success` records if the
     /// entry is successful or not.
     pub fn set_completed(&self, now: Time, success: bool) {
@@ -131,12 +177,14 @@ impl QueryLog {
         query_type: impl Into<String>,
         query_text: QueryText,
         trace_id: Option<TraceId>,
+        issuer: Option<Arc<str>>,
     ) -> Arc<QueryLogEntry> {
         let entry = Arc::new(QueryLogEntry::new(
             namespace_id,
             query_type.into(),
             query_text,
             trace_id,
+            issuer,
             self.time_provider.now(),
         ));
 
@@ -161,12 +209,52 @@ impl QueryLog {
     }
 
     /// Marks the provided query entry as completed using the current time.
-    /// `success` specifies the query ran successfully
-    pub fn set_completed(&self, entry: Arc<QueryLogEntry>, success: bool) {
+    pub fn set_completed(&self, entry: Arc<QueryLogEntry>, completion: QueryCompletion) {
+        let QueryCompletion { success, timings } = completion;
+        debug!(
+            namespace_id=entry.namespace_id.get(),
+            query_type=%entry.query_type,
+            success,
+            queue_wait=?timings.queue_wait,
+            planning=?timings.planning,
+            execution=?timings.execution,
+            streaming=?timings.streaming,
+            "query completed",
+        );
+
+        *entry.phase_timings.lock() = Some(timings);
         entry.set_completed(self.time_provider.now(), success)
     }
 }
 
+/// Duration metrics for the different [phases](QueryPhase) of a query.
+#[derive(Debug)]
+pub struct QueryPhaseMetrics {
+    /// Histograms, in the order of [`QueryPhase::ALL`].
+    durations: [DurationHistogram; 4],
+}
+
+impl QueryPhaseMetrics {
+    pub fn new(metric_registry: &metric::Registry) -> Self {
+        let metric = metric_registry.register_metric::<DurationHistogram>(
+            "query_phase_duration",
+            "Time that queries spent in the different phases (queue wait, planning, execution, streaming)",
+        );
+        let durations = QueryPhase::ALL.map(|phase| metric.recorder(&[("phase", phase.name())]));
+
+        Self { durations }
+    }
+
+    /// Record timings of a completed query.
+    pub fn record(&self, timings: &QueryPhaseTimings) {
+        for (phase, histogram) in QueryPhase::ALL.iter().zip(&self.durations) {
+            if let Some(d) = timings.get(*phase) {
+                histogram.record(d);
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod test_super {
     use iox_time::MockProvider;
@@ -182,11 +270,13 @@ mod test_super {
             "sql".into(),
             Box::new("SELECT 1"),
             None,
+            None,
             time_provider.now(),
         ));
         // query has not completed
         assert_eq!(entry.query_completed_duration(), None);
         assert!(!entry.success());
+        assert_eq!(entry.status(), "running");
 
         // when the query completes at the same time it's issued
         entry.set_completed(time_provider.now(), true);
@@ -195,6 +285,7 @@ mod test_super {
             Some(Duration::from_millis(0))
         );
         assert!(entry.success());
+        assert_eq!(entry.status(), "success");
 
         // when the query completes some time in the future.
         time_provider.set(Time::from_timestamp_millis(300).unwrap());
@@ -204,5 +295,64 @@ mod test_super {
             Some(Duration::from_millis(200))
         );
         assert!(!entry.success());
+        assert_eq!(entry.status(), "failed");
+    }
+
+    #[test]
+    fn test_query_log_set_completed() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_millis(100).unwrap()));
+        let query_log = QueryLog::new(10, Arc::clone(&time_provider) as _);
+        let entry = query_log.push(NamespaceId::new(1), "sql", Box::new("SELECT 1"), None, None);
+        assert_eq!(entry.phase_timings(), None);
+
+        let timings = QueryPhaseTimings {
+            queue_wait: Some(Duration::from_millis(1)),
+            planning: Some(Duration::from_millis(2)),
+            execution: Some(Duration::from_millis(3)),
+            streaming: None,
+        };
+        time_provider.set(Time::from_timestamp_millis(110).unwrap());
+        query_log.set_completed(
+            Arc::clone(&entry),
+            QueryCompletion {
+                success: true,
+                timings,
+            },
+        );
+
+        assert!(entry.success());
+        assert_eq!(
+            entry.query_completed_duration(),
+            Some(Duration::from_millis(10))
+        );
+        assert_eq!(entry.phase_timings(), Some(timings));
+    }
+
+    #[test]
+    fn test_query_phase_metrics() {
+        let registry = metric::Registry::new();
+        let metrics = QueryPhaseMetrics::new(&registry);
+
+        metrics.record(&QueryPhaseTimings {
+            queue_wait: None,
+            planning: Some(Duration::from_millis(2)),
+            execution: Some(Duration::from_millis(3)),
+            streaming: None,
+        });
+
+        for (phase, expected) in [
+            ("queue_wait", 0),
+            ("planning", 1),
+            ("execution", 1),
+            ("streaming", 0),
+        ] {
+            let hist = registry
+                .get_instrument::<metric::Metric<DurationHistogram>>("query_phase_duration")
+                .unwrap()
+                .get_observer(&metric::Attributes::from(&[("phase", phase)]))
+                .unwrap()
+                .fetch();
+            assert_eq!(hist.sample_count(), expected, "phase: {phase}");
+        }
     }
 }
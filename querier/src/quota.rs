@@ -0,0 +1,253 @@
+//! Per-namespace query quotas.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use iox_time::{Time, TimeProvider};
+use parking_lot::Mutex;
+use service_common::quota::{QuotaError, QuotaPermit};
+
+/// Window over which [`QueryQuota::queries_per_minute`] is enforced.
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Query limits of a single namespace.
+///
+/// `None` means "unlimited".
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct QueryQuota {
+    /// Maximum number of queries that may run concurrently.
+    pub max_concurrent_queries: Option<usize>,
+
+    /// Maximum number of queries that may be started within any 60 second window.
+    pub queries_per_minute: Option<usize>,
+
+    /// Maximum number of bytes that a single query may scan.
+    ///
+    /// This is an estimate that is computed during planning, after pruning.
+    pub max_scanned_bytes: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct NamespaceState {
+    /// Number of queries that currently hold a permit.
+    running: usize,
+
+    /// Start times of the queries within the last [`RATE_WINDOW`], oldest first.
+    started: VecDeque<Time>,
+}
+
+type State = Arc<Mutex<HashMap<Arc<str>, NamespaceState>>>;
+
+/// Tracks and enforces the [quotas](QueryQuota) of all namespaces.
+#[derive(Debug)]
+pub struct QueryQuotas {
+    default: QueryQuota,
+    overrides: HashMap<String, QueryQuota>,
+    time_provider: Arc<dyn TimeProvider>,
+    state: State,
+}
+
+impl QueryQuotas {
+    /// Create new quotas.
+    ///
+    /// `default` applies to all namespaces that are not listed in `overrides`.
+    pub fn new(
+        default: QueryQuota,
+        overrides: HashMap<String, QueryQuota>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            default,
+            overrides,
+            time_provider,
+            state: Default::default(),
+        }
+    }
+
+    /// Quotas that do not limit anything.
+    pub fn unlimited(time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self::new(QueryQuota::default(), HashMap::default(), time_provider)
+    }
+
+    /// Quota of the given namespace.
+    pub fn quota(&self, namespace: &str) -> QueryQuota {
+        self.overrides
+            .get(namespace)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Count a new query against the quota of the given namespace.
+    pub fn acquire(&self, namespace: &str) -> Result<QuotaPermit, QuotaError> {
+        let quota = self.quota(namespace);
+        if quota.max_concurrent_queries.is_none() && quota.queries_per_minute.is_none() {
+            return Ok(QuotaPermit::unlimited());
+        }
+
+        let now = self.time_provider.now();
+        let mut state = self.state.lock();
+        let ns_state = state.entry(Arc::from(namespace)).or_default();
+
+        if let Some(cutoff) = now.checked_sub(RATE_WINDOW) {
+            while let Some(t) = ns_state.started.front() {
+                if *t > cutoff {
+                    break;
+                }
+                ns_state.started.pop_front();
+            }
+        }
+
+        if let Some(limit) = quota.max_concurrent_queries {
+            if ns_state.running >= limit {
+                return Err(QuotaError::TooManyConcurrentQueries {
+                    namespace: namespace.to_owned(),
+                    limit,
+                });
+            }
+        }
+        if let Some(limit) = quota.queries_per_minute {
+            if ns_state.started.len() >= limit {
+                return Err(QuotaError::TooManyQueriesPerMinute {
+                    namespace: namespace.to_owned(),
+                    limit,
+                });
+            }
+            ns_state.started.push_back(now);
+        }
+        ns_state.running += 1;
+
+        Ok(QuotaPermit::new(RunningQuery {
+            namespace: Arc::from(namespace),
+            state: Arc::clone(&self.state),
+        }))
+    }
+}
+
+/// Releases the concurrency slot of a query when dropped.
+#[derive(Debug)]
+struct RunningQuery {
+    namespace: Arc<str>,
+    state: State,
+}
+
+impl Drop for RunningQuery {
+    fn drop(&mut self) {
+        let mut state = self.state.lock();
+        if let Some(ns_state) = state.get_mut(&self.namespace) {
+            ns_state.running = ns_state.running.saturating_sub(1);
+            if ns_state.running == 0 && ns_state.started.is_empty() {
+                state.remove(&self.namespace);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    #[test]
+    fn test_unlimited() {
+        let quotas = QueryQuotas::unlimited(time_provider());
+
+        let permits = (0..100)
+            .map(|_| quotas.acquire("ns").unwrap())
+            .collect::<Vec<_>>();
+        drop(permits);
+        assert!(quotas.state.lock().is_empty());
+    }
+
+    #[test]
+    fn test_max_concurrent_queries() {
+        let quotas = QueryQuotas::new(
+            QueryQuota {
+                max_concurrent_queries: Some(2),
+                ..Default::default()
+            },
+            HashMap::default(),
+            time_provider(),
+        );
+
+        let p1 = quotas.acquire("ns").unwrap();
+        let p2 = quotas.acquire("ns").unwrap();
+        let err = quotas.acquire("ns").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "namespace 'ns' already runs the maximum of 2 concurrent queries",
+        );
+
+        // other namespaces are not affected
+        let _p_other = quotas.acquire("other").unwrap();
+
+        drop(p1);
+        let p3 = quotas.acquire("ns").unwrap();
+
+        drop(p2);
+        drop(p3);
+        assert!(!quotas.state.lock().contains_key("ns"));
+    }
+
+    #[test]
+    fn test_queries_per_minute() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let quotas = QueryQuotas::new(
+            QueryQuota {
+                queries_per_minute: Some(2),
+                ..Default::default()
+            },
+            HashMap::default(),
+            Arc::clone(&time_provider) as _,
+        );
+
+        quotas.acquire("ns").unwrap();
+        time_provider.inc(Duration::from_secs(30));
+        quotas.acquire("ns").unwrap();
+        let err = quotas.acquire("ns").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "namespace 'ns' exceeded the limit of 2 queries per minute",
+        );
+
+        // first query leaves the window
+        time_provider.inc(Duration::from_secs(30));
+        quotas.acquire("ns").unwrap();
+        quotas.acquire("ns").unwrap_err();
+    }
+
+    #[test]
+    fn test_overrides() {
+        let quotas = QueryQuotas::new(
+            QueryQuota {
+                max_concurrent_queries: Some(1),
+                ..Default::default()
+            },
+            HashMap::from([(
+                String::from("big"),
+                QueryQuota {
+                    max_concurrent_queries: None,
+                    queries_per_minute: None,
+                    max_scanned_bytes: Some(10),
+                },
+            )]),
+            time_provider(),
+        );
+
+        assert_eq!(quotas.quota("big").max_scanned_bytes, Some(10));
+        assert_eq!(quotas.quota("small").max_scanned_bytes, None);
+
+        let _p1 = quotas.acquire("small").unwrap();
+        quotas.acquire("small").unwrap_err();
+
+        let _p2 = quotas.acquire("big").unwrap();
+        let _p3 = quotas.acquire("big").unwrap();
+    }
+
+    fn time_provider() -> Arc<dyn TimeProvider> {
+        Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)))
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    ingester::IngesterChunk, parquet::QuerierParquetChunk, system_tables::AsyncIoxSystemTable,
+    table::QuerierTable,
+};
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use datafusion::{error::DataFusionError, scalar::ScalarValue};
+use futures::{stream::FuturesOrdered, TryStreamExt};
+use iox_query::QueryChunk;
+use predicate::Predicate;
+use schema::TIME_COLUMN_NAME;
+use std::{collections::HashMap, sync::Arc};
+
+/// Information about a single chunk, as seen by the querier right now.
+#[derive(Debug)]
+pub(super) struct ChunkInfo {
+    pub(super) table_name: Arc<str>,
+    pub(super) partition_id: String,
+    pub(super) chunk_id: String,
+    pub(super) chunk_type: String,
+    pub(super) chunk_order: i64,
+    pub(super) row_count: Option<i64>,
+    pub(super) size_bytes: Option<i64>,
+    pub(super) min_time: Option<i64>,
+    pub(super) max_time: Option<i64>,
+    pub(super) sort_key: Option<String>,
+}
+
+impl ChunkInfo {
+    fn new(table_name: &Arc<str>, chunk: &dyn QueryChunk) -> Self {
+        let stats = chunk.stats();
+        let time_stats = chunk
+            .schema()
+            .find_index_of(TIME_COLUMN_NAME)
+            .and_then(|idx| stats.column_statistics.as_ref()?.get(idx));
+        let time = |v: Option<&ScalarValue>| match v {
+            Some(ScalarValue::TimestampNanosecond(ts, _)) => *ts,
+            _ => None,
+        };
+
+        Self {
+            table_name: Arc::clone(table_name),
+            partition_id: chunk.transition_partition_id().to_string(),
+            chunk_id: chunk.id().get().to_string(),
+            chunk_type: chunk.chunk_type().to_owned(),
+            chunk_order: chunk.order().get(),
+            row_count: stats.num_rows.map(|n| n as i64),
+            size_bytes: chunk_size(chunk).map(|n| n as i64),
+            min_time: time(time_stats.and_then(|s| s.min_value.as_ref())),
+            max_time: time(time_stats.and_then(|s| s.max_value.as_ref())),
+            sort_key: chunk.sort_key().map(|sort_key| sort_key.to_string()),
+        }
+    }
+}
+
+/// Estimated size of the chunk in bytes.
+///
+/// For parquet files this is the file size, for ingester data the in-memory size. Unknown for
+/// streamed ingester data.
+fn chunk_size(chunk: &dyn QueryChunk) -> Option<usize> {
+    let chunk = chunk.as_any();
+    if let Some(chunk) = chunk.downcast_ref::<QuerierParquetChunk>() {
+        Some(chunk.estimate_size())
+    } else {
+        chunk
+            .downcast_ref::<IngesterChunk>()
+            .and_then(|chunk| chunk.estimate_size())
+    }
+}
+
+/// Get all chunks of all tables, incl. ingester data.
+pub(super) async fn collect_chunks(
+    tables: &HashMap<Arc<str>, Arc<QuerierTable>>,
+) -> Result<Vec<ChunkInfo>, DataFusionError> {
+    let predicate = Predicate::default();
+    let predicate = &predicate;
+
+    let chunks = tables
+        .iter()
+        .map(|(table_name, table)| async move {
+            let chunks = table
+                .chunks(predicate, None, None, None, None)
+                .await
+                .map_err(|e| DataFusionError::External(Box::new(e)))?;
+            Ok::<_, DataFusionError>(
+                chunks
+                    .iter()
+                    .map(|chunk| ChunkInfo::new(table_name, chunk.as_ref()))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<FuturesOrdered<_>>()
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+/// Implementation of system.chunks table
+#[derive(Debug)]
+pub(super) struct ChunksTable {
+    schema: SchemaRef,
+    tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+}
+
+impl ChunksTable {
+    pub(super) fn new(tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>) -> Self {
+        Self {
+            schema: chunks_schema(),
+            tables,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncIoxSystemTable for ChunksTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn snapshot(&self) -> Result<RecordBatch, DataFusionError> {
+        let chunks = collect_chunks(&self.tables).await?;
+        Ok(from_chunk_infos(self.schema(), &chunks)?)
+    }
+}
+
+fn chunks_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("partition_id", DataType::Utf8, false),
+        Field::new("chunk_id", DataType::Utf8, false),
+        Field::new("chunk_type", DataType::Utf8, false),
+        Field::new("chunk_order", DataType::Int64, false),
+        Field::new("row_count", DataType::Int64, true),
+        Field::new("size_bytes", DataType::Int64, true),
+        Field::new(
+            "min_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new(
+            "max_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new("sort_key", DataType::Utf8, true),
+    ]))
+}
+
+fn from_chunk_infos(
+    schema: SchemaRef,
+    chunks: &[ChunkInfo],
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| Some(c.table_name.as_ref()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| Some(&c.partition_id))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| Some(&c.chunk_id))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| Some(&c.chunk_type))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| Some(c.chunk_order))
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(chunks.iter().map(|c| c.row_count).collect::<Int64Array>()),
+        Arc::new(chunks.iter().map(|c| c.size_bytes).collect::<Int64Array>()),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| c.min_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| c.max_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            chunks
+                .iter()
+                .map(|c| c.sort_key.as_deref())
+                .collect::<StringArray>(),
+        ),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
@@ -0,0 +1,137 @@
+use crate::{cache::CatalogCache, system_tables::AsyncIoxSystemTable, table::QuerierTable};
+use arrow::{
+    array::{ArrayRef, StringArray},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use data_types::{ColumnMetadata, ColumnType, NamespaceId};
+use datafusion::error::DataFusionError;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// A single column and its (optional) metadata.
+#[derive(Debug)]
+struct ColumnInfo<'a> {
+    column_type: ColumnType,
+    metadata: Option<&'a ColumnMetadata>,
+}
+
+/// Implementation of system.columns table
+#[derive(Debug)]
+pub(super) struct ColumnsTable {
+    schema: SchemaRef,
+    namespace_id: NamespaceId,
+    tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+    catalog_cache: Arc<CatalogCache>,
+}
+
+impl ColumnsTable {
+    pub(super) fn new(
+        namespace_id: NamespaceId,
+        tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+        catalog_cache: Arc<CatalogCache>,
+    ) -> Self {
+        Self {
+            schema: columns_schema(),
+            namespace_id,
+            tables,
+            catalog_cache,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncIoxSystemTable for ColumnsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn snapshot(&self) -> Result<RecordBatch, DataFusionError> {
+        let catalog = self.catalog_cache.coalescing_catalog();
+        let (columns, metadata) = futures::join!(
+            catalog.columns_by_namespace(self.namespace_id),
+            catalog.column_metadata_by_namespace(self.namespace_id),
+        );
+        let columns = columns.map_err(|e| DataFusionError::External(Box::new(e)))?;
+        let metadata = metadata.map_err(|e| DataFusionError::External(Box::new(e)))?;
+
+        let metadata: HashMap<_, _> = metadata.iter().map(|m| (m.column_id, m)).collect();
+
+        // only list columns of the tables that are visible to this query
+        let table_names: HashMap<_, _> = self
+            .tables
+            .iter()
+            .map(|(name, table)| (table.id(), name.as_ref()))
+            .collect();
+
+        let mut infos: BTreeMap<(&str, &str), ColumnInfo<'_>> = BTreeMap::new();
+        for column in &columns {
+            let Some(table_name) = table_names.get(&column.table_id) else {
+                continue;
+            };
+            infos.insert(
+                (*table_name, column.name.as_str()),
+                ColumnInfo {
+                    column_type: column.column_type,
+                    metadata: metadata.get(&column.id).copied(),
+                },
+            );
+        }
+
+        Ok(from_column_infos(self.schema(), &infos)?)
+    }
+}
+
+fn columns_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("column_type", DataType::Utf8, false),
+        Field::new("description", DataType::Utf8, true),
+        Field::new("unit", DataType::Utf8, true),
+        Field::new("semantic_type", DataType::Utf8, true),
+    ]))
+}
+
+fn from_column_infos(
+    schema: SchemaRef,
+    infos: &BTreeMap<(&str, &str), ColumnInfo<'_>>,
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let metadata_field = |f: fn(&ColumnMetadata) -> Option<&str>| -> ArrayRef {
+        Arc::new(
+            infos
+                .values()
+                .map(|c| c.metadata.and_then(f))
+                .collect::<StringArray>(),
+        )
+    };
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            infos
+                .keys()
+                .map(|(table_name, _)| Some(*table_name))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            infos
+                .keys()
+                .map(|(_, column_name)| Some(*column_name))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            infos
+                .values()
+                .map(|c| Some(c.column_type.as_str()))
+                .collect::<StringArray>(),
+        ),
+        metadata_field(|m| m.description.as_deref()),
+        metadata_field(|m| m.unit.as_deref()),
+        metadata_field(|m| m.semantic_type.as_deref()),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
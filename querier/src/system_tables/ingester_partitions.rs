@@ -0,0 +1,157 @@
+use crate::{ingester::IngesterPartition, system_tables::AsyncIoxSystemTable, table::QuerierTable};
+use arrow::{
+    array::{ArrayRef, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use datafusion::error::DataFusionError;
+use futures::{stream::FuturesOrdered, TryStreamExt};
+use predicate::Predicate;
+use std::{collections::HashMap, sync::Arc};
+
+/// The persistence state of a single partition, as reported by an ingester.
+#[derive(Debug)]
+struct IngesterPartitionInfo {
+    table_name: Arc<str>,
+    partition_id: String,
+    ingester_uuid: String,
+    completed_persistence_count: u64,
+    max_persisted_sequence_number: Option<u64>,
+    buffered_row_count: u64,
+}
+
+impl IngesterPartitionInfo {
+    fn new(table_name: &Arc<str>, partition: &IngesterPartition) -> Self {
+        Self {
+            table_name: Arc::clone(table_name),
+            partition_id: partition.transition_partition_id().to_string(),
+            ingester_uuid: partition.ingester_uuid().to_string(),
+            completed_persistence_count: partition.completed_persistence_count(),
+            max_persisted_sequence_number: partition
+                .max_persisted_sequence_number()
+                .map(|v| v.get()),
+            buffered_row_count: partition.buffered_row_count(),
+        }
+    }
+}
+
+/// Implementation of system.ingester_partitions table
+#[derive(Debug)]
+pub(super) struct IngesterPartitionsTable {
+    schema: SchemaRef,
+    tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+}
+
+impl IngesterPartitionsTable {
+    pub(super) fn new(tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>) -> Self {
+        Self {
+            schema: ingester_partitions_schema(),
+            tables,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncIoxSystemTable for IngesterPartitionsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn snapshot(&self) -> Result<RecordBatch, DataFusionError> {
+        let predicate = Predicate::default();
+        let predicate = &predicate;
+
+        // Only the primary key columns are requested, as the buffered data
+        // itself is not part of this table.
+        let projection = vec![];
+        let projection = &projection;
+
+        let partitions = self
+            .tables
+            .iter()
+            .map(|(table_name, table)| async move {
+                let partitions = table
+                    .ingester_partitions(predicate, None, Some(projection), None)
+                    .await
+                    .map_err(|e| DataFusionError::External(Box::new(e)))?;
+                Ok::<_, DataFusionError>(
+                    partitions
+                        .iter()
+                        .map(|p| IngesterPartitionInfo::new(table_name, p))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<FuturesOrdered<_>>()
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        let mut partitions = partitions.into_iter().flatten().collect::<Vec<_>>();
+        partitions.sort_by(|a, b| {
+            (&a.table_name, &a.partition_id, &a.ingester_uuid).cmp(&(
+                &b.table_name,
+                &b.partition_id,
+                &b.ingester_uuid,
+            ))
+        });
+
+        Ok(from_ingester_partition_infos(self.schema(), &partitions)?)
+    }
+}
+
+fn ingester_partitions_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("partition_id", DataType::Utf8, false),
+        Field::new("ingester_uuid", DataType::Utf8, false),
+        Field::new("completed_persistence_count", DataType::UInt64, false),
+        Field::new("max_persisted_sequence_number", DataType::UInt64, true),
+        Field::new("buffered_row_count", DataType::UInt64, false),
+    ]))
+}
+
+fn from_ingester_partition_infos(
+    schema: SchemaRef,
+    partitions: &[IngesterPartitionInfo],
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| Some(p.table_name.as_ref()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| Some(p.partition_id.as_str()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| Some(p.ingester_uuid.as_str()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| Some(p.completed_persistence_count))
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| p.max_persisted_sequence_number)
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .iter()
+                .map(|p| Some(p.buffered_row_count))
+                .collect::<UInt64Array>(),
+        ),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
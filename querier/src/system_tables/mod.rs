@@ -1,4 +1,4 @@
-use crate::query_log::QueryLog;
+use crate::{cache::CatalogCache, query_log::QueryLog, table::QuerierTable};
 use arrow::{datatypes::SchemaRef, error::Result as ArrowResult, record_batch::RecordBatch};
 use async_trait::async_trait;
 use data_types::NamespaceId;
@@ -24,10 +24,18 @@ use std::{
     task::{Context, Poll},
 };
 
+mod chunks;
+mod columns;
+mod ingester_partitions;
+mod partitions;
 mod queries;
 
-pub const SYSTEM_SCHEMA: &str = "system";
+pub use datafusion_util::config::SYSTEM_SCHEMA;
 
+const CHUNKS_TABLE: &str = "chunks";
+const COLUMNS_TABLE: &str = "columns";
+const INGESTER_PARTITIONS_TABLE: &str = "ingester_partitions";
+const PARTITIONS_TABLE: &str = "partitions";
 const QUERIES_TABLE: &str = "queries";
 
 pub struct SystemSchemaProvider {
@@ -38,15 +46,44 @@ impl SystemSchemaProvider {
     pub fn new(
         query_log: Arc<QueryLog>,
         namespace_id: NamespaceId,
+        user_tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+        catalog_cache: Arc<CatalogCache>,
         include_debug_info: bool,
     ) -> Self {
         let mut tables: HashMap<&'static str, Arc<dyn TableProvider>> = HashMap::new();
 
+        // column metadata is user-provided and not debug info, so it is always available
+        let columns = Arc::new(AsyncSystemTableProvider {
+            table: Arc::new(columns::ColumnsTable::new(
+                namespace_id,
+                Arc::clone(&user_tables),
+                catalog_cache,
+            )),
+        });
+        tables.insert(COLUMNS_TABLE, columns);
+
         if include_debug_info {
             let queries = Arc::new(SystemTableProvider {
                 table: Arc::new(queries::QueriesTable::new(query_log, Some(namespace_id))),
             });
             tables.insert(QUERIES_TABLE, queries);
+
+            let chunks = Arc::new(AsyncSystemTableProvider {
+                table: Arc::new(chunks::ChunksTable::new(Arc::clone(&user_tables))),
+            });
+            tables.insert(CHUNKS_TABLE, chunks);
+
+            let ingester_partitions = Arc::new(AsyncSystemTableProvider {
+                table: Arc::new(ingester_partitions::IngesterPartitionsTable::new(
+                    Arc::clone(&user_tables),
+                )),
+            });
+            tables.insert(INGESTER_PARTITIONS_TABLE, ingester_partitions);
+
+            let partitions = Arc::new(AsyncSystemTableProvider {
+                table: Arc::new(partitions::PartitionsTable::new(user_tables)),
+            });
+            tables.insert(PARTITIONS_TABLE, partitions);
         }
 
         Self { tables }
@@ -89,6 +126,85 @@ trait IoxSystemTable: Send + Sync {
     fn scan(&self, batch_size: usize) -> ArrowResult<BatchIterator>;
 }
 
+/// A system table whose content is computed asynchronously, e.g. because it requires catalog or
+/// ingester requests.
+///
+/// The content is computed once per scan.
+#[async_trait]
+trait AsyncIoxSystemTable: Send + Sync {
+    /// Produce the schema from this system table
+    fn schema(&self) -> SchemaRef;
+
+    /// Get the current contents of the system table
+    async fn snapshot(&self) -> DataFusionResult<RecordBatch>;
+}
+
+/// Materialized contents of an `AsyncIoxSystemTable`.
+struct SnapshotTable {
+    batch: RecordBatch,
+}
+
+impl IoxSystemTable for SnapshotTable {
+    fn schema(&self) -> SchemaRef {
+        self.batch.schema()
+    }
+
+    fn scan(&self, batch_size: usize) -> ArrowResult<BatchIterator> {
+        let batch = self.batch.clone();
+        let batch_size = batch_size.max(1);
+        let mut offset = 0;
+        Ok(Box::new(std::iter::from_fn(move || {
+            if offset >= batch.num_rows() {
+                return None;
+            }
+
+            let len = batch_size.min(batch.num_rows() - offset);
+            let slice = batch.slice(offset, len);
+            offset += len;
+            Some(Ok(slice))
+        })))
+    }
+}
+
+/// Adapter that makes any `AsyncIoxSystemTable` a DataFusion `TableProvider`
+struct AsyncSystemTableProvider<T: AsyncIoxSystemTable> {
+    table: Arc<T>,
+}
+
+#[async_trait]
+impl<T> TableProvider for AsyncSystemTableProvider<T>
+where
+    T: AsyncIoxSystemTable + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.table.schema()
+    }
+
+    async fn scan(
+        &self,
+        ctx: &SessionState,
+        projection: Option<&Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> DataFusionResult<Arc<dyn ExecutionPlan>> {
+        let batch = self.table.snapshot().await?;
+
+        SystemTableProvider {
+            table: Arc::new(SnapshotTable { batch }),
+        }
+        .scan(ctx, projection, filters, limit)
+        .await
+    }
+
+    fn table_type(&self) -> TableType {
+        TableType::Base
+    }
+}
+
 /// Adapter that makes any `IoxSystemTable` a DataFusion `TableProvider`
 struct SystemTableProvider<T: IoxSystemTable> {
     table: Arc<T>,
@@ -0,0 +1,166 @@
+use crate::{
+    system_tables::{
+        chunks::{collect_chunks, ChunkInfo},
+        AsyncIoxSystemTable,
+    },
+    table::QuerierTable,
+};
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, TimestampNanosecondArray},
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    record_batch::RecordBatch,
+};
+use async_trait::async_trait;
+use datafusion::error::DataFusionError;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// Chunks of a single partition, aggregated.
+#[derive(Debug, Default)]
+struct PartitionInfo {
+    chunk_count: i64,
+    parquet_chunk_count: i64,
+    row_count: Option<i64>,
+    size_bytes: Option<i64>,
+    min_time: Option<i64>,
+    max_time: Option<i64>,
+}
+
+impl PartitionInfo {
+    fn add(&mut self, chunk: &ChunkInfo) {
+        self.chunk_count += 1;
+        if chunk.chunk_type == "parquet" {
+            self.parquet_chunk_count += 1;
+        }
+        self.row_count = combine(self.row_count, chunk.row_count, |a, b| a + b);
+        self.size_bytes = combine(self.size_bytes, chunk.size_bytes, |a, b| a + b);
+        self.min_time = combine(self.min_time, chunk.min_time, i64::min);
+        self.max_time = combine(self.max_time, chunk.max_time, i64::max);
+    }
+}
+
+/// Combine two optional values, ignoring missing ones.
+fn combine(a: Option<i64>, b: Option<i64>, f: impl FnOnce(i64, i64) -> i64) -> Option<i64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(f(a, b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Implementation of system.partitions table
+#[derive(Debug)]
+pub(super) struct PartitionsTable {
+    schema: SchemaRef,
+    tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+}
+
+impl PartitionsTable {
+    pub(super) fn new(tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>) -> Self {
+        Self {
+            schema: partitions_schema(),
+            tables,
+        }
+    }
+}
+
+#[async_trait]
+impl AsyncIoxSystemTable for PartitionsTable {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    async fn snapshot(&self) -> Result<RecordBatch, DataFusionError> {
+        let chunks = collect_chunks(&self.tables).await?;
+
+        let mut partitions: BTreeMap<(Arc<str>, &str), PartitionInfo> = BTreeMap::new();
+        for chunk in &chunks {
+            partitions
+                .entry((Arc::clone(&chunk.table_name), chunk.partition_id.as_str()))
+                .or_default()
+                .add(chunk);
+        }
+
+        Ok(from_partition_infos(self.schema(), &partitions)?)
+    }
+}
+
+fn partitions_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("partition_id", DataType::Utf8, false),
+        Field::new("chunk_count", DataType::Int64, false),
+        Field::new("parquet_chunk_count", DataType::Int64, false),
+        Field::new("row_count", DataType::Int64, true),
+        Field::new("size_bytes", DataType::Int64, true),
+        Field::new(
+            "min_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+        Field::new(
+            "max_time",
+            DataType::Timestamp(TimeUnit::Nanosecond, None),
+            true,
+        ),
+    ]))
+}
+
+fn from_partition_infos(
+    schema: SchemaRef,
+    partitions: &BTreeMap<(Arc<str>, &str), PartitionInfo>,
+) -> Result<RecordBatch, arrow::error::ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(
+            partitions
+                .keys()
+                .map(|(table_name, _)| Some(table_name.as_ref()))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            partitions
+                .keys()
+                .map(|(_, partition_id)| Some(*partition_id))
+                .collect::<StringArray>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| Some(p.chunk_count))
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| Some(p.parquet_chunk_count))
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| p.row_count)
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| p.size_bytes)
+                .collect::<Int64Array>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| p.min_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+        Arc::new(
+            partitions
+                .values()
+                .map(|p| p.max_time)
+                .collect::<TimestampNanosecondArray>(),
+        ),
+    ];
+
+    RecordBatch::try_new(schema, columns)
+}
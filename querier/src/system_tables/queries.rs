@@ -12,6 +12,7 @@ use arrow::{
     record_batch::RecordBatch,
 };
 use data_types::NamespaceId;
+use iox_query::QueryPhase;
 use observability_deps::tracing::error;
 use std::{collections::VecDeque, sync::Arc};
 
@@ -94,7 +95,16 @@ fn queries_schema(include_namespace_id: bool) -> SchemaRef {
         ),
         Field::new("success", DataType::Boolean, false),
         Field::new("trace_id", DataType::Utf8, true),
+        Field::new("status", DataType::Utf8, false),
+        Field::new("issuer", DataType::Utf8, true),
     ]);
+    for phase in QueryPhase::ALL {
+        columns.push(Field::new(
+            format!("{}_duration", phase.name()),
+            DataType::Duration(TimeUnit::Nanosecond),
+            true,
+        ));
+    }
 
     Arc::new(Schema::new(columns))
 }
@@ -174,6 +184,39 @@ fn from_query_log_entries(
             .collect::<StringArray>(),
     ));
 
+    columns.push(Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| Some(e.status()))
+            .collect::<StringArray>(),
+    ));
+
+    columns.push(Arc::new(
+        entries
+            .iter()
+            .skip(offset)
+            .take(len)
+            .map(|e| e.issuer.as_deref())
+            .collect::<StringArray>(),
+    ));
+
+    for phase in QueryPhase::ALL {
+        columns.push(Arc::new(
+            entries
+                .iter()
+                .skip(offset)
+                .take(len)
+                .map(|e| {
+                    e.phase_timings()
+                        .and_then(|timings| timings.get(phase))
+                        .map(|d| d.as_nanos() as i64)
+                })
+                .collect::<DurationNanosecondArray>(),
+        ));
+    }
+
     RecordBatch::try_new(schema, columns)
 }
 
@@ -181,6 +224,7 @@ fn from_query_log_entries(
 mod tests {
     use super::*;
     use arrow_util::assert_batches_eq;
+    use iox_query::{QueryCompletion, QueryPhaseTimings};
     use iox_time::{Time, TimeProvider};
     use trace::ctx::TraceId;
 
@@ -196,26 +240,27 @@ mod tests {
             10,
             Arc::clone(&time_provider) as Arc<dyn TimeProvider>,
         ));
-        query_log.push(id1, "sql", Box::new("select * from foo"), None);
+        query_log.push(id1, "sql", Box::new("select * from foo"), None, None);
         time_provider.inc(std::time::Duration::from_secs(24 * 60 * 60));
-        let sql2_entry = query_log.push(id1, "sql", Box::new("select * from bar"), None);
+        let sql2_entry = query_log.push(id1, "sql", Box::new("select * from bar"), None, None);
         let read_filter_entry = query_log.push(
             id2,
             "read_filter",
             Box::new("json goop"),
             Some(TraceId::new(0x45fe).unwrap()),
+            Some(Arc::from("10.0.0.1:1234")),
         );
 
         let table = QueriesTable::new(Arc::clone(&query_log), None);
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar |                    | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         |                    | false   | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
+            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id | status  | issuer        | queue_wait_duration | planning_duration | execution_duration | streaming_duration |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
+            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          | running |               |                     |                   |                    |                    |",
+            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar |                    | false   |          | running |               |                     |                   |                    |                    |",
+            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         |                    | false   | 45fe     | running | 10.0.0.1:1234 |                     |                   |                    |                    |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
         ];
 
         let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -226,17 +271,29 @@ mod tests {
         let now = Time::from_rfc3339("1996-12-20T16:40:01+00:00").unwrap();
         sql2_entry.set_completed(now, false);
 
-        // mark the read_filter query completed after 4s successfuly
-        read_filter_entry.set_completed(now, true);
+        // mark the read_filter query completed after 4s successfuly, incl. phase timings
+        time_provider.set(now);
+        query_log.set_completed(
+            read_filter_entry,
+            QueryCompletion {
+                success: true,
+                timings: QueryPhaseTimings {
+                    queue_wait: Some(std::time::Duration::from_millis(1)),
+                    planning: Some(std::time::Duration::from_secs(2)),
+                    execution: Some(std::time::Duration::from_secs(1)),
+                    streaming: None,
+                },
+            },
+        );
 
         let expected = vec![
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
-            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          |",
-            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar | 4s                 | false   |          |",
-            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         | 4s                 | true    | 45fe     |",
-            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
+            "| namespace_id | issue_time           | query_type  | query_text        | completed_duration | success | trace_id | status  | issuer        | queue_wait_duration | planning_duration | execution_duration | streaming_duration |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
+            "| 1            | 1996-12-19T16:39:57Z | sql         | select * from foo |                    | false   |          | running |               |                     |                   |                    |                    |",
+            "| 1            | 1996-12-20T16:39:57Z | sql         | select * from bar | 4s                 | false   |          | failed  |               |                     |                   |                    |                    |",
+            "| 2            | 1996-12-20T16:39:57Z | read_filter | json goop         | 4s                 | true    | 45fe     | success | 10.0.0.1:1234 | 1ms                 | 2s                | 1s                 |                    |",
+            "+--------------+----------------------+-------------+-------------------+--------------------+---------+----------+---------+---------------+---------------------+-------------------+--------------------+--------------------+",
         ];
 
         let entries = table.scan(2).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -247,12 +304,12 @@ mod tests {
         let table = QueriesTable::new(Arc::clone(&query_log), Some(id1));
 
         let expected = vec![
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| issue_time           | query_type | query_text        | completed_duration | success | trace_id |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
-            "| 1996-12-19T16:39:57Z | sql        | select * from foo |                    | false   |          |",
-            "| 1996-12-20T16:39:57Z | sql        | select * from bar | 4s                 | false   |          |",
-            "+----------------------+------------+-------------------+--------------------+---------+----------+",
+            "+----------------------+------------+-------------------+--------------------+---------+----------+---------+--------+---------------------+-------------------+--------------------+--------------------+",
+            "| issue_time           | query_type | query_text        | completed_duration | success | trace_id | status  | issuer | queue_wait_duration | planning_duration | execution_duration | streaming_duration |",
+            "+----------------------+------------+-------------------+--------------------+---------+----------+---------+--------+---------------------+-------------------+--------------------+--------------------+",
+            "| 1996-12-19T16:39:57Z | sql        | select * from foo |                    | false   |          | running |        |                     |                   |                    |                    |",
+            "| 1996-12-20T16:39:57Z | sql        | select * from bar | 4s                 | false   |          | failed  |        |                     |                   |                    |                    |",
+            "+----------------------+------------+-------------------+--------------------+---------+----------+---------+--------+---------------------+-------------------+--------------------+--------------------+",
         ];
 
         let entries = table.scan(3).unwrap().collect::<Result<Vec<_>>>().unwrap();
@@ -11,7 +11,10 @@ use crate::{
 use data_types::{ColumnId, NamespaceId, ParquetFile, PartitionId, TableId};
 use datafusion::error::DataFusionError;
 use futures::join;
-use iox_query::{provider, provider::ChunkPruner, QueryChunk};
+use iox_query::{
+    exec::warnings::QueryWarnings, provider, provider::ChunkPruner, pruning::PruningReports,
+    QueryChunk,
+};
 use observability_deps::tracing::{debug, trace};
 use predicate::Predicate;
 use schema::Schema;
@@ -54,7 +57,7 @@ impl From<Error> for DataFusionError {
 pub struct QuerierTableArgs {
     pub namespace_id: NamespaceId,
     pub namespace_name: Arc<str>,
-    pub namespace_retention_period: Option<Duration>,
+    pub retention_period: Option<Duration>,
     pub table_id: TableId,
     pub table_name: Arc<str>,
     pub schema: Schema,
@@ -72,8 +75,8 @@ pub struct QuerierTable {
     /// Namespace ID for this table.
     namespace_id: NamespaceId,
 
-    /// Namespace retenion
-    namespace_retention_period: Option<Duration>,
+    /// Retention period of this table, i.e. the table override or the namespace retention period.
+    retention_period: Option<Duration>,
 
     /// Table name.
     table_name: Arc<str>,
@@ -100,7 +103,7 @@ impl QuerierTable {
         let QuerierTableArgs {
             namespace_id,
             namespace_name,
-            namespace_retention_period,
+            retention_period,
             table_id,
             table_name,
             schema,
@@ -112,7 +115,7 @@ impl QuerierTable {
         Self {
             namespace_name,
             namespace_id,
-            namespace_retention_period,
+            retention_period,
             table_name,
             table_id,
             schema,
@@ -138,15 +141,27 @@ impl QuerierTable {
     }
 
     /// Query all chunks within this table.
+    ///
+    /// If `pruning_reports` is given, a [pruning report](iox_query::pruning::PruningReport) for this table is added
+    /// to it. Problems that make the result incomplete without failing it, e.g. unavailable ingesters, are added to
+    /// `query_warnings`.
     pub async fn chunks(
         &self,
         predicate: &Predicate,
         span: Option<Span>,
         projection: Option<&Vec<usize>>,
+        pruning_reports: Option<Arc<PruningReports>>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         let mut span_recorder = SpanRecorder::new(span);
         match self
-            .chunks_inner(predicate, &span_recorder, projection)
+            .chunks_inner(
+                predicate,
+                &span_recorder,
+                projection,
+                pruning_reports,
+                query_warnings,
+            )
             .await
         {
             Ok(chunks) => {
@@ -165,6 +180,8 @@ impl QuerierTable {
         predicate: &Predicate,
         span_recorder: &SpanRecorder,
         projection: Option<&Vec<usize>>,
+        pruning_reports: Option<Arc<PruningReports>>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<Arc<dyn QueryChunk>>> {
         debug!(
             ?predicate,
@@ -189,6 +206,7 @@ impl QuerierTable {
                         predicate,
                         span_recorder.child_span("ingester partitions"),
                         projection,
+                        query_warnings,
                     )
                     .await;
                 ingester_ready.cancel();
@@ -298,7 +316,7 @@ impl QuerierTable {
 
         let num_initial_chunks = chunks.len();
         let chunks = self
-            .chunk_pruner()
+            .chunk_pruner(pruning_reports)
             .prune_chunks(
                 self.table_name(),
                 // use up-to-date schema
@@ -370,18 +388,41 @@ impl QuerierTable {
     }
 
     /// Get a chunk pruner that can be used to prune chunks retrieved via [`chunks`](Self::chunks)
-    pub fn chunk_pruner(&self) -> Arc<dyn ChunkPruner> {
-        Arc::new(QuerierTableChunkPruner::new(Arc::clone(
-            &self.prune_metrics,
-        )))
+    ///
+    /// If `pruning_reports` is given, the pruner adds a [pruning report](iox_query::pruning::PruningReport) to it.
+    pub fn chunk_pruner(
+        &self,
+        pruning_reports: Option<Arc<PruningReports>>,
+    ) -> Arc<dyn ChunkPruner> {
+        let pruner = QuerierTableChunkPruner::new(Arc::clone(&self.prune_metrics));
+        let pruner = match pruning_reports {
+            Some(reports) => pruner.with_report(reports, self.retention_time()),
+            None => pruner,
+        };
+        Arc::new(pruner)
+    }
+
+    /// Cutoff timestamp (in nanoseconds) of the table retention period, if any.
+    ///
+    /// Data at or before this timestamp is expired.
+    pub(crate) fn retention_time(&self) -> Option<i64> {
+        self.retention_period.map(|d| {
+            self.chunk_adapter
+                .catalog_cache()
+                .time_provider()
+                .now()
+                .timestamp_nanos()
+                - d.as_nanos() as i64
+        })
     }
 
     /// Get partitions from ingesters.
-    async fn ingester_partitions(
+    pub(crate) async fn ingester_partitions(
         &self,
         predicate: &Predicate,
         span: Option<Span>,
         projection: Option<&Vec<usize>>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<IngesterPartition>> {
         let mut span_recorder = SpanRecorder::new(span);
 
@@ -392,6 +433,7 @@ impl QuerierTable {
                     predicate,
                     &span_recorder,
                     projection,
+                    query_warnings,
                 )
                 .await
             {
@@ -417,6 +459,7 @@ impl QuerierTable {
         predicate: &Predicate,
         span_recorder: &SpanRecorder,
         projection: Option<&Vec<usize>>,
+        query_warnings: Option<Arc<QueryWarnings>>,
     ) -> Result<Vec<IngesterPartition>> {
         // If the projection is provided, use it. Otherwise, use all columns of the table
         // The provided projection should include all columns needed by the query
@@ -445,6 +488,7 @@ impl QuerierTable {
                 columns,
                 predicate,
                 span_recorder.child_span("IngesterConnection partitions"),
+                query_warnings,
             )
             .await
             .context(GettingIngesterPartitionsSnafu);
@@ -1045,7 +1089,9 @@ mod tests {
                 .next_response(Ok(self.ingester_partitions.clone()));
 
             let span = Some(Span::root("root", Arc::clone(&self.traces) as _));
-            self.querier_table.chunks(pred, span, projection).await
+            self.querier_table
+                .chunks(pred, span, projection, None, None)
+                .await
         }
     }
 }
@@ -13,7 +13,10 @@ use datafusion::{
 use iox_query::{
     exec::SessionContextIOxExt,
     provider::{ChunkPruner, Error as ProviderError, ProviderBuilder},
-    pruning::{prune_chunks, NotPrunedReason, PruningObserver},
+    pruning::{
+        prune_chunks, prune_chunks_with_report, NotPrunedReason, PruningObserver, PruningOutcome,
+        PruningReports,
+    },
     QueryChunk,
 };
 use predicate::Predicate;
@@ -51,29 +54,22 @@ impl TableProvider for QuerierTable {
         // build provider out of all chunks
         // TODO: push down some predicates to catalog
 
+        let retention_time = self.retention_time();
         let mut builder =
-            ProviderBuilder::new(Arc::clone(self.table_name()), self.schema().clone());
-
-        let filters = match self.namespace_retention_period {
-            Some(d) => {
-                let ts = self
-                    .chunk_adapter
-                    .catalog_cache()
-                    .time_provider()
-                    .now()
-                    .timestamp_nanos()
-                    - d.as_nanos() as i64;
-                filters
-                    .iter()
-                    .cloned()
-                    .chain(
-                        Predicate::default()
-                            .with_retention(ts)
-                            .filter_expr()
-                            .into_iter(),
-                    )
-                    .collect::<Vec<_>>()
-            }
+            ProviderBuilder::new(Arc::clone(self.table_name()), self.schema().clone())
+                .with_retention_time(retention_time);
+
+        let filters = match retention_time {
+            Some(ts) => filters
+                .iter()
+                .cloned()
+                .chain(
+                    Predicate::default()
+                        .with_retention(ts)
+                        .filter_expr()
+                        .into_iter(),
+                )
+                .collect::<Vec<_>>(),
             None => filters.to_vec(),
         };
 
@@ -87,9 +83,20 @@ impl TableProvider for QuerierTable {
                 &pruning_predicate,
                 ctx.child_span("QuerierTable chunks"),
                 projection,
+                ctx.pruning_reports(),
+                ctx.query_warnings(),
             )
             .await?;
 
+        // reject oversized queries before any data is read
+        if let Some(scan_budget) = ctx.scan_budget() {
+            let bytes: u64 = chunks
+                .iter()
+                .map(|chunk| chunk_scan_size(chunk.as_ref()) as u64)
+                .sum();
+            scan_budget.consume(bytes)?;
+        }
+
         for chunk in chunks {
             builder = builder.add_chunk(chunk);
         }
@@ -113,24 +120,64 @@ impl TableProvider for QuerierTable {
 #[derive(Debug)]
 pub struct QuerierTableChunkPruner {
     metrics: Arc<PruneMetrics>,
+
+    /// Collects the pruning report, along with the retention cutoff that is used to attribute prune reasons.
+    report: Option<(Arc<PruningReports>, Option<i64>)>,
 }
 
 impl QuerierTableChunkPruner {
     pub fn new(metrics: Arc<PruneMetrics>) -> Self {
-        Self { metrics }
+        Self {
+            metrics,
+            report: None,
+        }
+    }
+
+    /// Record a [pruning report](iox_query::pruning::PruningReport) for every pruning operation.
+    pub fn with_report(self, reports: Arc<PruningReports>, retention_time: Option<i64>) -> Self {
+        Self {
+            report: Some((reports, retention_time)),
+            ..self
+        }
     }
 }
 
 impl ChunkPruner for QuerierTableChunkPruner {
     fn prune_chunks(
         &self,
-        _table_name: &str,
+        table_name: &str,
         table_schema: &Schema,
         chunks: Vec<Arc<dyn QueryChunk>>,
         predicate: &Predicate,
     ) -> Result<Vec<Arc<dyn QueryChunk>>, ProviderError> {
         let observer = &MetricPruningObserver::new(Arc::clone(&self.metrics));
 
+        if let Some((reports, retention_time)) = &self.report {
+            let (keeps, report) = prune_chunks_with_report(
+                table_name,
+                table_schema,
+                &chunks,
+                predicate,
+                *retention_time,
+            );
+            for (chunk, outcome) in chunks.iter().zip(&report.chunks) {
+                match outcome.outcome {
+                    PruningOutcome::NotPruned => observer.was_not_pruned(chunk.as_ref()),
+                    PruningOutcome::Pruned(_) => observer.was_pruned(chunk.as_ref()),
+                    PruningOutcome::CouldNotPrune(reason) => {
+                        observer.could_not_prune(reason, chunk.as_ref())
+                    }
+                }
+            }
+            reports.push(report);
+
+            return Ok(chunks
+                .into_iter()
+                .zip(keeps)
+                .filter_map(|(chunk, keep)| keep.then_some(chunk))
+                .collect());
+        }
+
         let chunks = match prune_chunks(table_schema, &chunks, predicate) {
             Ok(keeps) => {
                 assert_eq!(chunks.len(), keeps.len());
@@ -208,7 +255,24 @@ fn chunk_estimate_size(chunk: &dyn QueryChunk) -> usize {
     let chunk = chunk.as_any();
 
     if let Some(chunk) = chunk.downcast_ref::<IngesterChunk>() {
+        // unknown for streamed chunks
+        chunk.estimate_size().unwrap_or_default()
+    } else if let Some(chunk) = chunk.downcast_ref::<QuerierParquetChunk>() {
         chunk.estimate_size()
+    } else {
+        panic!("Unknown chunk type")
+    }
+}
+
+/// Estimated number of bytes scanned when executing the chunk.
+///
+/// Unlike [`chunk_estimate_size`], this also charges streamed ingester chunks, whose data was not
+/// read yet.
+fn chunk_scan_size(chunk: &dyn QueryChunk) -> usize {
+    let chunk = chunk.as_any();
+
+    if let Some(chunk) = chunk.downcast_ref::<IngesterChunk>() {
+        chunk.estimate_scan_size()
     } else if let Some(chunk) = chunk.downcast_ref::<QuerierParquetChunk>() {
         chunk.estimate_size()
     } else {
@@ -222,7 +286,8 @@ fn chunk_rows(chunk: &dyn QueryChunk) -> usize {
     if let Some(chunk) = chunk.downcast_ref::<QuerierParquetChunk>() {
         chunk.rows()
     } else if let Some(chunk) = chunk.downcast_ref::<IngesterChunk>() {
-        chunk.rows()
+        // unknown for streamed chunks
+        chunk.rows().unwrap_or_default()
     } else {
         panic!("Unknown chunk type");
     }
@@ -38,15 +38,14 @@ pub async fn querier_table(catalog: &Arc<TestCatalog>, table: &Arc<TestTable>) -
 
     let namespace_name = Arc::from(table.namespace.namespace.name.as_str());
 
-    let namespace_retention_period = table
-        .namespace
-        .namespace
+    let retention_period = table_info
         .retention_period_ns
+        .or(table.namespace.namespace.retention_period_ns)
         .map(|retention| Duration::from_nanos(retention as u64));
     QuerierTable::new(QuerierTableArgs {
         namespace_id: table.namespace.namespace.id,
         namespace_name,
-        namespace_retention_period,
+        retention_period,
         table_id: table.table.id,
         table_name: table.table.name.clone().into(),
         schema,
@@ -107,6 +106,8 @@ impl IngesterPartitionBuilder {
             self.partition.partition.id,
             self.partition.partition.hash_id().cloned(),
             0,
+            None,
+            0,
         )
         .try_add_chunk(
             ChunkId::new_test(self.ingester_chunk_id),
@@ -0,0 +1,347 @@
+//! Implementation of the `histogram` aggregate function.
+//!
+//! ```text
+//! histogram(value, bucket_width)
+//! ```
+//!
+//! Counts the non-null `value`s in buckets of `bucket_width`, returning
+//! a list of `{bucket, count}` structs ordered by `bucket`, the lower
+//! bound of each bucket. A value `v` falls into the bucket starting at
+//! `floor(v / bucket_width) * bucket_width`. Buckets without values are
+//! omitted, and the result is `NULL` if there are no values at all.
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow::{
+    array::{as_list_array, Array, ArrayRef, AsArray},
+    compute::cast,
+    datatypes::{DataType, Field, Fields, Float64Type, UInt64Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        Accumulator, AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature,
+        StateTypeFunction, TypeSignature, Volatility,
+    },
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the histogram aggregate function.
+pub const HISTOGRAM_UDAF_NAME: &str = "histogram";
+
+/// Types accepted for the `value` argument.
+const VALUE_TYPES: [DataType; 3] = [DataType::Int64, DataType::UInt64, DataType::Float64];
+
+/// Types accepted for the `bucket_width` argument.
+const WIDTH_TYPES: [DataType; 2] = [DataType::Int64, DataType::Float64];
+
+/// Valid signatures for the histogram aggregate function.
+static SIGNATURE: Lazy<Signature> = Lazy::new(|| {
+    Signature::one_of(
+        VALUE_TYPES
+            .iter()
+            .flat_map(|value| {
+                WIDTH_TYPES
+                    .iter()
+                    .map(|width| TypeSignature::Exact(vec![value.clone(), width.clone()]))
+            })
+            .collect(),
+        Volatility::Immutable,
+    )
+});
+
+/// Fields of the structs in the result list.
+static BUCKET_FIELDS: Lazy<Fields> = Lazy::new(|| {
+    Fields::from(vec![
+        Field::new("bucket", DataType::Float64, false),
+        Field::new("count", DataType::UInt64, false),
+    ])
+});
+
+/// The result type, a list of `{bucket, count}` structs.
+static RETURN_TYPE: Lazy<Arc<DataType>> = Lazy::new(|| {
+    Arc::new(DataType::List(Arc::new(Field::new(
+        "item",
+        DataType::Struct(BUCKET_FIELDS.clone()),
+        true,
+    ))))
+});
+
+/// The intermediate state of the aggregate: the bucket width, followed
+/// by the lower bounds and counts of the non-empty buckets.
+static STATE_TYPE: Lazy<Arc<Vec<DataType>>> = Lazy::new(|| {
+    Arc::new(vec![
+        DataType::Float64,
+        DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+        DataType::List(Arc::new(Field::new("item", DataType::UInt64, true))),
+    ])
+});
+
+/// Implementation of histogram
+pub(crate) static HISTOGRAM: Lazy<Arc<AggregateUDF>> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(return_type);
+    let accumulator: AccumulatorFactoryFunction = Arc::new(accumulator);
+    let state_type: StateTypeFunction = Arc::new(state_type);
+
+    Arc::new(AggregateUDF::new(
+        HISTOGRAM_UDAF_NAME,
+        &SIGNATURE,
+        &return_type,
+        &accumulator,
+        &state_type,
+    ))
+});
+
+fn return_type(_: &[DataType]) -> DataFusionResult<Arc<DataType>> {
+    Ok(Arc::clone(&RETURN_TYPE))
+}
+
+fn accumulator(_: &DataType) -> DataFusionResult<Box<dyn Accumulator>> {
+    Ok(Box::<HistogramAccumulator>::default())
+}
+
+fn state_type(_: &DataType) -> DataFusionResult<Arc<Vec<DataType>>> {
+    Ok(Arc::clone(&STATE_TYPE))
+}
+
+#[derive(Debug, Default)]
+struct HistogramAccumulator {
+    /// The bucket width, which is only known once the first batch of
+    /// arguments or intermediate states has been seen.
+    width: Option<f64>,
+    /// Count of values per bucket, keyed by the bucket number, which is
+    /// the lower bound of the bucket divided by the width.
+    buckets: BTreeMap<i64, u64>,
+}
+
+impl HistogramAccumulator {
+    fn set_width(&mut self, width: f64) -> DataFusionResult<()> {
+        if self.width.is_some() {
+            return Ok(());
+        }
+        if !(width.is_finite() && width > 0.0) {
+            return Err(DataFusionError::Execution(format!(
+                "{HISTOGRAM_UDAF_NAME}: bucket width must be greater than 0, got {width}"
+            )));
+        }
+        self.width = Some(width);
+        Ok(())
+    }
+}
+
+impl Accumulator for HistogramAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        if values[0].is_empty() {
+            return Ok(());
+        }
+
+        let width = cast(&values[1], &DataType::Float64)?;
+        let width = width.as_primitive::<Float64Type>();
+        if width.is_null(0) {
+            return Err(DataFusionError::Execution(format!(
+                "{HISTOGRAM_UDAF_NAME}: bucket width must not be NULL"
+            )));
+        }
+        self.set_width(width.value(0))?;
+        let width = width.value(0);
+
+        let values = cast(&values[0], &DataType::Float64)?;
+        for v in values.as_primitive::<Float64Type>().iter().flatten() {
+            // NaN has no bucket
+            if v.is_nan() {
+                continue;
+            }
+            *self.buckets.entry((v / width).floor() as i64).or_default() += 1;
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        let (Some(width), false) = (self.width, self.buckets.is_empty()) else {
+            return Ok(ScalarValue::new_list(
+                None,
+                DataType::Struct(BUCKET_FIELDS.clone()),
+            ));
+        };
+
+        let buckets = self
+            .buckets
+            .iter()
+            .map(|(bucket, count)| {
+                ScalarValue::Struct(
+                    Some(vec![
+                        ScalarValue::Float64(Some(*bucket as f64 * width)),
+                        ScalarValue::UInt64(Some(*count)),
+                    ]),
+                    BUCKET_FIELDS.clone(),
+                )
+            })
+            .collect();
+        Ok(ScalarValue::new_list(
+            Some(buckets),
+            DataType::Struct(BUCKET_FIELDS.clone()),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.buckets.len() * (std::mem::size_of::<i64>() + std::mem::size_of::<u64>())
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        let width = self.width.unwrap_or(1.0);
+        let (bounds, counts) = self
+            .buckets
+            .iter()
+            .map(|(bucket, count)| {
+                (
+                    ScalarValue::Float64(Some(*bucket as f64 * width)),
+                    ScalarValue::UInt64(Some(*count)),
+                )
+            })
+            .unzip();
+
+        Ok(vec![
+            ScalarValue::Float64(self.width),
+            ScalarValue::new_list(Some(bounds), DataType::Float64),
+            ScalarValue::new_list(Some(counts), DataType::UInt64),
+        ])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        let widths = states[0].as_primitive::<Float64Type>();
+        let bounds = as_list_array(&states[1]);
+        let counts = as_list_array(&states[2]);
+
+        for row in 0..widths.len() {
+            // states of accumulators that never saw any values carry
+            // no width and are skipped
+            if widths.is_null(row) {
+                continue;
+            }
+            self.set_width(widths.value(row))?;
+            let width = widths.value(row);
+
+            let row_bounds = bounds.value(row);
+            let row_counts = counts.value(row);
+            for (bound, count) in row_bounds
+                .as_primitive::<Float64Type>()
+                .values()
+                .iter()
+                .zip(row_counts.as_primitive::<UInt64Type>().values().iter())
+            {
+                *self
+                    .buckets
+                    .entry((bound / width).round() as i64)
+                    .or_default() += count;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{Float64Array, Int64Array},
+        record_batch::RecordBatch,
+    };
+    use datafusion::assert_batches_eq;
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    async fn run(batch: RecordBatch, sql: &str) -> DataFusionResult<Vec<RecordBatch>> {
+        let ctx = context_with_table(batch);
+        ctx.register_udaf(HISTOGRAM.as_ref().clone());
+        ctx.sql(sql).await?.collect().await
+    }
+
+    #[tokio::test]
+    async fn test_int() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(Int64Array::from(vec![
+                Some(1),
+                Some(-3),
+                None,
+                Some(12),
+                Some(9),
+                Some(31),
+            ])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let got = run(batch, "SELECT histogram(v, 10) AS h FROM t")
+            .await
+            .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+----------------------------------------------------------------------------------------------------------+",
+                "| h                                                                                                        |",
+                "+----------------------------------------------------------------------------------------------------------+",
+                "| [{bucket: -10.0, count: 1}, {bucket: 0.0, count: 2}, {bucket: 10.0, count: 1}, {bucket: 30.0, count: 1}] |",
+                "+----------------------------------------------------------------------------------------------------------+",
+            ],
+            &got
+        );
+    }
+
+    #[tokio::test]
+    async fn test_float() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(Float64Array::from(vec![0.1, 0.2, 0.5, f64::NAN, 0.75])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let got = run(batch, "SELECT histogram(v, 0.5) AS h FROM t")
+            .await
+            .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+----------------------------------------------------+",
+                "| h                                                  |",
+                "+----------------------------------------------------+",
+                "| [{bucket: 0.0, count: 2}, {bucket: 0.5, count: 2}] |",
+                "+----------------------------------------------------+",
+            ],
+            &got
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_values() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(Int64Array::from(vec![None, None])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let got = run(batch, "SELECT histogram(v, 10) AS h FROM t")
+            .await
+            .unwrap();
+
+        assert_batches_eq!(&["+---+", "| h |", "+---+", "|   |", "+---+"], &got);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_width() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(Int64Array::from(vec![1])) as ArrayRef,
+        )])
+        .unwrap();
+
+        let err = run(batch, "SELECT histogram(v, 0) AS h FROM t")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("bucket width must be greater than 0, got 0"),
+            "{err}"
+        );
+    }
+}
@@ -28,12 +28,24 @@ pub mod coalesce_struct;
 /// Grouping by structs
 pub mod group_by;
 
+/// Histogram aggregate
+mod histogram;
+
+/// Percentile aggregate
+mod percentile;
+
 /// Regular Expressions
 mod regex;
 
 /// Selector Functions
 pub mod selectors;
 
+/// String functions for tag values
+mod string;
+
+/// Totalize window function
+mod totalize;
+
 /// window_bounds expressions
 mod window;
 
@@ -42,9 +54,15 @@ pub mod gapfill;
 /// Function registry
 mod registry;
 
+pub use crate::histogram::HISTOGRAM_UDAF_NAME;
+pub use crate::percentile::PERCENTILE_UDAF_NAME;
 pub use crate::regex::clean_non_meta_escapes;
 pub use crate::regex::REGEX_MATCH_UDF_NAME;
 pub use crate::regex::REGEX_NOT_MATCH_UDF_NAME;
+pub use crate::string::REGEXP_EXTRACT_UDF_NAME;
+pub use crate::string::SPLIT_PART_UDF_NAME;
+pub use crate::string::TAG_CONCAT_UDF_NAME;
+pub use crate::totalize::TOTALIZE_UDWF_NAME;
 
 /// Return an Expr that invokes a InfluxRPC compatible regex match to
 /// determine which values satisfy the pattern. Equivalent to:
@@ -72,6 +90,29 @@ pub fn regex_not_match_expr(input: Expr, pattern: String) -> Expr {
         .call(vec![input, lit(pattern)])
 }
 
+/// Return an Expr that invokes the `percentile` aggregate to calculate the
+/// `n`th percentile of `input`. Equivalent to:
+///
+/// ```text
+/// percentile(input, n, exact)
+/// ```
+pub fn percentile_expr(input: Expr, n: Expr, exact: bool) -> Expr {
+    registry()
+        .udaf(percentile::PERCENTILE_UDAF_NAME)
+        .expect("Percentile function not registered")
+        .call(vec![input, n, lit(exact)])
+}
+
+/// Return an Expr that invokes the `percentile` aggregate to calculate the
+/// median of `input`. Equivalent to:
+///
+/// ```text
+/// percentile(input, 50, exact)
+/// ```
+pub fn median_expr(input: Expr, exact: bool) -> Expr {
+    percentile_expr(input, lit(50_i64), exact)
+}
+
 /// Create a DataFusion `Expr` that invokes `window_bounds` with the
 /// appropriate every and offset arguments at runtime
 pub fn make_window_bound_expr(
@@ -110,6 +151,27 @@ pub fn register_scalar_functions(ctx: &SessionContext) {
     }
 }
 
+/// registers aggregate functions so they can be invoked via SQL
+pub fn register_aggregate_functions(ctx: &SessionContext) {
+    let registry = registry();
+    for f in [
+        histogram::HISTOGRAM_UDAF_NAME,
+        percentile::PERCENTILE_UDAF_NAME,
+    ] {
+        let udaf = registry.udaf(f).unwrap();
+        ctx.register_udaf(udaf.as_ref().clone())
+    }
+}
+
+/// registers window functions so they can be invoked via SQL
+pub fn register_window_functions(ctx: &SessionContext) {
+    let registry = registry();
+    for f in [totalize::TOTALIZE_UDWF_NAME] {
+        let udwf = registry.udwf(f).unwrap();
+        ctx.register_udwf(udwf.as_ref().clone())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use arrow::{
@@ -0,0 +1,472 @@
+//! Implementation of the `percentile` aggregate function.
+//!
+//! ```text
+//! percentile(value, n)
+//! percentile(value, n, exact)
+//! ```
+//!
+//! Returns the `n`th percentile (`0 <= n <= 100`) of the non-null
+//! `value`s. By default the result is estimated from a [t-digest], which
+//! needs a bounded amount of memory regardless of the number of input
+//! rows. If `exact` is `true` all values are buffered and the result is
+//! the value at the nearest rank, matching the InfluxQL `PERCENTILE`
+//! function.
+//!
+//! The median is the 50th percentile, see [`median_expr`].
+//!
+//! [`median_expr`]: crate::median_expr
+//! [t-digest]: https://arxiv.org/abs/1902.04023
+use std::sync::Arc;
+
+use arrow::{
+    array::{as_boolean_array, as_list_array, Array, ArrayRef, AsArray, Float64Array},
+    compute::cast,
+    datatypes::{DataType, Field, Float64Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        Accumulator, AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction, Signature,
+        StateTypeFunction, TypeSignature, Volatility,
+    },
+    physical_expr::expressions::{ApproxPercentileCont, Column, Literal},
+    physical_plan::AggregateExpr,
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the percentile aggregate function.
+pub const PERCENTILE_UDAF_NAME: &str = "percentile";
+
+/// Types accepted for the `value` argument.
+const VALUE_TYPES: [DataType; 3] = [DataType::Int64, DataType::UInt64, DataType::Float64];
+
+/// Types accepted for the `n` argument.
+const PERCENTILE_TYPES: [DataType; 2] = [DataType::Int64, DataType::Float64];
+
+/// Valid signatures for the percentile aggregate function.
+static SIGNATURE: Lazy<Signature> = Lazy::new(|| {
+    let mut signatures = vec![];
+    for value in &VALUE_TYPES {
+        for n in &PERCENTILE_TYPES {
+            signatures.push(TypeSignature::Exact(vec![value.clone(), n.clone()]));
+            signatures.push(TypeSignature::Exact(vec![
+                value.clone(),
+                n.clone(),
+                DataType::Boolean,
+            ]));
+        }
+    }
+    Signature::one_of(signatures, Volatility::Immutable)
+});
+
+/// The intermediate state of the aggregate. The first three fields are
+/// the percentile, the exact flag and, in exact mode, the buffered
+/// values. These are followed by the state of the t-digest, which is
+/// empty in exact mode.
+static STATE_TYPE: Lazy<Arc<Vec<DataType>>> = Lazy::new(|| {
+    let mut state_type = vec![
+        DataType::Float64,
+        DataType::Boolean,
+        DataType::List(Arc::new(Field::new("item", DataType::Float64, true))),
+    ];
+    state_type.extend(
+        digest_expr(0.5)
+            .and_then(|expr| expr.state_fields())
+            .expect("valid t-digest expression")
+            .into_iter()
+            .map(|field| field.data_type().clone()),
+    );
+    Arc::new(state_type)
+});
+
+/// Offset of the t-digest state within [`STATE_TYPE`].
+const DIGEST_STATE_OFFSET: usize = 3;
+
+/// Offset of the count of values within the t-digest state.
+const DIGEST_COUNT_OFFSET: usize = 2;
+
+/// Implementation of percentile
+pub(crate) static PERCENTILE: Lazy<Arc<AggregateUDF>> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(return_type);
+    let accumulator: AccumulatorFactoryFunction = Arc::new(accumulator);
+    let state_type: StateTypeFunction = Arc::new(state_type);
+
+    Arc::new(AggregateUDF::new(
+        PERCENTILE_UDAF_NAME,
+        &SIGNATURE,
+        &return_type,
+        &accumulator,
+        &state_type,
+    ))
+});
+
+/// Percentile always returns a `Float64`, regardless of the input type.
+fn return_type(_: &[DataType]) -> DataFusionResult<Arc<DataType>> {
+    Ok(Arc::new(DataType::Float64))
+}
+
+fn accumulator(_: &DataType) -> DataFusionResult<Box<dyn Accumulator>> {
+    Ok(Box::<PercentileAccumulator>::default())
+}
+
+fn state_type(_: &DataType) -> DataFusionResult<Arc<Vec<DataType>>> {
+    Ok(Arc::clone(&STATE_TYPE))
+}
+
+/// Create a DataFusion `approx_percentile_cont` expression for the
+/// quantile `q` (`0 <= q <= 1`) of a single `Float64` column.
+fn digest_expr(q: f64) -> DataFusionResult<ApproxPercentileCont> {
+    ApproxPercentileCont::new(
+        vec![
+            Arc::new(Column::new("value", 0)),
+            Arc::new(Literal::new(ScalarValue::Float64(Some(q)))),
+        ],
+        PERCENTILE_UDAF_NAME,
+        DataType::Float64,
+    )
+}
+
+#[derive(Debug)]
+enum Mode {
+    /// Estimate the percentile using a t-digest.
+    Approximate(Box<dyn Accumulator>),
+    /// Buffer all values and pick the value at the nearest rank.
+    Exact(Vec<f64>),
+}
+
+#[derive(Debug, Default)]
+struct PercentileAccumulator {
+    /// The requested percentile, in the range `0..=100`. This, and the
+    /// mode, are only known once the first batch of arguments or
+    /// intermediate states has been seen.
+    percentile: Option<f64>,
+    mode: Option<Mode>,
+    /// The number of non-null values aggregated so far.
+    count: u64,
+}
+
+impl PercentileAccumulator {
+    fn init(&mut self, percentile: f64, exact: bool) -> DataFusionResult<()> {
+        if self.mode.is_some() {
+            return Ok(());
+        }
+        if !(0.0..=100.0).contains(&percentile) {
+            return Err(DataFusionError::Execution(format!(
+                "{PERCENTILE_UDAF_NAME}: percentile must be between 0 and 100, got {percentile}"
+            )));
+        }
+
+        self.percentile = Some(percentile);
+        self.mode = Some(if exact {
+            Mode::Exact(vec![])
+        } else {
+            Mode::Approximate(digest_expr(percentile / 100.0)?.create_accumulator()?)
+        });
+        Ok(())
+    }
+
+    fn is_exact(&self) -> Option<bool> {
+        self.mode.as_ref().map(|m| matches!(m, Mode::Exact(_)))
+    }
+}
+
+impl Accumulator for PercentileAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> DataFusionResult<()> {
+        if values[0].is_empty() {
+            return Ok(());
+        }
+
+        let percentile = match constant_arg(&values[1], "percentile")? {
+            ScalarValue::Int64(Some(v)) => v as f64,
+            ScalarValue::Float64(Some(v)) => v,
+            _ => {
+                return Err(DataFusionError::Execution(format!(
+                    "{PERCENTILE_UDAF_NAME}: percentile must not be NULL"
+                )))
+            }
+        };
+        let exact = match values.get(2) {
+            Some(exact) => matches!(
+                constant_arg(exact, "exact")?,
+                ScalarValue::Boolean(Some(true))
+            ),
+            None => false,
+        };
+        self.init(percentile, exact)?;
+
+        let values = cast(&values[0], &DataType::Float64)?;
+        let values: Float64Array = values
+            .as_primitive::<Float64Type>()
+            .iter()
+            .flatten()
+            .collect();
+        self.count += values.len() as u64;
+
+        match self.mode.as_mut() {
+            Some(Mode::Approximate(acc)) => acc.update_batch(&[Arc::new(values)]),
+            Some(Mode::Exact(data)) => {
+                data.extend(values.values().iter());
+                Ok(())
+            }
+            None => unreachable!("mode initialised above"),
+        }
+    }
+
+    fn evaluate(&self) -> DataFusionResult<ScalarValue> {
+        match (&self.mode, self.percentile) {
+            (Some(Mode::Approximate(acc)), _) if self.count > 0 => acc.evaluate(),
+            (Some(Mode::Exact(data)), Some(percentile)) => {
+                let mut data = data.clone();
+                data.sort_by(|a, b| a.total_cmp(b));
+                Ok(ScalarValue::Float64(
+                    percentile_idx(data.len(), percentile).map(|idx| data[idx]),
+                ))
+            }
+            _ => Ok(ScalarValue::Float64(None)),
+        }
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + match &self.mode {
+                Some(Mode::Approximate(acc)) => acc.size(),
+                Some(Mode::Exact(data)) => data.capacity() * std::mem::size_of::<f64>(),
+                None => 0,
+            }
+    }
+
+    fn state(&self) -> DataFusionResult<Vec<ScalarValue>> {
+        let (data, digest) = match &self.mode {
+            Some(Mode::Approximate(acc)) => (vec![], acc.state()?),
+            Some(Mode::Exact(data)) => (
+                data.iter()
+                    .map(|v| ScalarValue::Float64(Some(*v)))
+                    .collect(),
+                digest_expr(0.5)?.create_accumulator()?.state()?,
+            ),
+            None => (vec![], digest_expr(0.5)?.create_accumulator()?.state()?),
+        };
+
+        let mut state = vec![
+            ScalarValue::Float64(self.percentile),
+            ScalarValue::Boolean(self.is_exact()),
+            ScalarValue::new_list(Some(data), DataType::Float64),
+        ];
+        state.extend(digest);
+        Ok(state)
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> DataFusionResult<()> {
+        let percentiles = cast(&states[0], &DataType::Float64)?;
+        let percentiles = percentiles.as_primitive::<Float64Type>();
+        let exact = as_boolean_array(&states[1]);
+        let data = as_list_array(&states[2]);
+        let digest = &states[DIGEST_STATE_OFFSET..];
+        let counts = digest[DIGEST_COUNT_OFFSET].as_primitive::<Float64Type>();
+
+        for row in 0..percentiles.len() {
+            // states of accumulators that never saw any values carry
+            // no percentile and are skipped
+            if percentiles.is_null(row) {
+                continue;
+            }
+            self.init(
+                percentiles.value(row),
+                exact.is_valid(row) && exact.value(row),
+            )?;
+
+            match self.mode.as_mut() {
+                Some(Mode::Approximate(acc)) => {
+                    let row_state = digest
+                        .iter()
+                        .map(|array| array.slice(row, 1))
+                        .collect::<Vec<_>>();
+                    acc.merge_batch(&row_state)?;
+                    self.count += counts.value(row) as u64;
+                }
+                Some(Mode::Exact(values)) => {
+                    let row_data = data.value(row);
+                    let row_data = row_data.as_primitive::<Float64Type>();
+                    let len = values.len();
+                    values.extend(row_data.iter().flatten());
+                    self.count += (values.len() - len) as u64;
+                }
+                None => unreachable!("mode initialised above"),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the value of the argument `name`, which must be the same in
+/// every row of `array`.
+///
+/// Non-constant arguments are already rejected during planning, this
+/// guards against plans that bypassed that check.
+fn constant_arg(array: &ArrayRef, name: &str) -> DataFusionResult<ScalarValue> {
+    let value = ScalarValue::try_from_array(array, 0)?;
+    for row in 1..array.len() {
+        if ScalarValue::try_from_array(array, row)? != value {
+            return Err(DataFusionError::Execution(format!(
+                "{PERCENTILE_UDAF_NAME}: {name} must be a constant"
+            )));
+        }
+    }
+    Ok(value)
+}
+
+/// Calculate the location in an ordered list of len items where the
+/// location of the item at the given percentile would be found.
+///
+/// This uses the same algorithm as the InfluxQL `PERCENTILE` function.
+fn percentile_idx(len: usize, percentile: f64) -> Option<usize> {
+    match TryInto::<usize>::try_into(
+        (((len as f64) * percentile / 100.0 + 0.5).floor() as isize) - 1,
+    ) {
+        Ok(idx) if idx < len => Some(idx),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{Float64Array, Int64Array},
+        record_batch::RecordBatch,
+    };
+    use datafusion::prelude::col;
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    async fn run(batch: RecordBatch, sql: &str) -> DataFusionResult<Option<f64>> {
+        let ctx = context_with_table(batch);
+        ctx.register_udaf(PERCENTILE.as_ref().clone());
+
+        let batches = ctx.sql(sql).await?.collect().await?;
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].num_rows(), 1);
+
+        let array = batches[0].column(0).as_primitive::<Float64Type>();
+        Ok(array.is_valid(0).then(|| array.value(0)))
+    }
+
+    fn int_batch(values: impl IntoIterator<Item = Option<i64>>) -> RecordBatch {
+        RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(values.into_iter().collect::<Int64Array>()) as ArrayRef,
+        )])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exact() {
+        let batch = int_batch((1..=10).map(Some).chain([None]));
+
+        let got = run(batch.clone(), "SELECT percentile(v, 50, true) FROM t").await;
+        assert_eq!(got.unwrap(), Some(5.0));
+
+        let got = run(batch.clone(), "SELECT percentile(v, 90.0, true) FROM t").await;
+        assert_eq!(got.unwrap(), Some(9.0));
+
+        let got = run(batch.clone(), "SELECT percentile(v, 100, true) FROM t").await;
+        assert_eq!(got.unwrap(), Some(10.0));
+
+        // the rank of the 0th percentile is outside of the data
+        let got = run(batch, "SELECT percentile(v, 0, true) FROM t").await;
+        assert_eq!(got.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_approximate() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "v",
+            Arc::new(Float64Array::from_iter_values((1..=1000).map(|v| v as f64))) as ArrayRef,
+        )])
+        .unwrap();
+
+        let got = run(batch.clone(), "SELECT percentile(v, 50) FROM t")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((got - 500.0).abs() < 5.0, "got {got}");
+
+        let got = run(batch, "SELECT percentile(v, 99, false) FROM t")
+            .await
+            .unwrap()
+            .unwrap();
+        assert!((got - 990.0).abs() < 5.0, "got {got}");
+    }
+
+    #[tokio::test]
+    async fn test_no_values() {
+        let batch = int_batch([None, None]);
+
+        let got = run(batch.clone(), "SELECT percentile(v, 50) FROM t").await;
+        assert_eq!(got.unwrap(), None);
+
+        let got = run(batch, "SELECT percentile(v, 50, true) FROM t").await;
+        assert_eq!(got.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_percentile() {
+        let batch = int_batch([Some(1)]);
+
+        let err = run(batch, "SELECT percentile(v, 101) FROM t")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("percentile must be between 0 and 100, got 101"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_non_constant_percentile() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "v",
+                Arc::new(Int64Array::from_iter_values(1..=4)) as ArrayRef,
+            ),
+            (
+                "n",
+                Arc::new(Int64Array::from_iter_values([50, 50, 90, 90])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let err = run(batch, "SELECT percentile(v, n) FROM t")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("percentile: percentile must be a constant"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_median() {
+        let ctx = context_with_table(int_batch((1..=10).map(Some)));
+        let df = ctx
+            .table("t")
+            .await
+            .unwrap()
+            .aggregate(vec![], vec![crate::median_expr(col("v"), true)])
+            .unwrap();
+        let batches = df.collect().await.unwrap();
+        let array = batches[0].column(0).as_primitive::<Float64Type>();
+        assert_eq!(array.value(0), 5.0);
+    }
+
+    #[test]
+    fn test_percentile_idx() {
+        assert_eq!(percentile_idx(10, 50.0), Some(4));
+        assert_eq!(percentile_idx(10, 95.0), Some(9));
+        assert_eq!(percentile_idx(10, 0.0), None);
+        assert_eq!(percentile_idx(0, 50.0), None);
+    }
+}
@@ -7,7 +7,7 @@ use datafusion::{
 };
 use once_cell::sync::Lazy;
 
-use crate::{gapfill, regex, window};
+use crate::{gapfill, histogram, percentile, regex, string, totalize, window};
 
 static REGISTRY: Lazy<IOxFunctionRegistry> = Lazy::new(IOxFunctionRegistry::new);
 
@@ -29,6 +29,9 @@ impl FunctionRegistry for IOxFunctionRegistry {
             gapfill::INTERPOLATE_UDF_NAME,
             regex::REGEX_MATCH_UDF_NAME,
             regex::REGEX_NOT_MATCH_UDF_NAME,
+            string::REGEXP_EXTRACT_UDF_NAME,
+            string::SPLIT_PART_UDF_NAME,
+            string::TAG_CONCAT_UDF_NAME,
             window::WINDOW_BOUNDS_UDF_NAME,
         ]
         .into_iter()
@@ -43,6 +46,9 @@ impl FunctionRegistry for IOxFunctionRegistry {
             gapfill::INTERPOLATE_UDF_NAME => Ok(gapfill::INTERPOLATE.clone()),
             regex::REGEX_MATCH_UDF_NAME => Ok(regex::REGEX_MATCH_UDF.clone()),
             regex::REGEX_NOT_MATCH_UDF_NAME => Ok(regex::REGEX_NOT_MATCH_UDF.clone()),
+            string::REGEXP_EXTRACT_UDF_NAME => Ok(string::REGEXP_EXTRACT.clone()),
+            string::SPLIT_PART_UDF_NAME => Ok(string::SPLIT_PART.clone()),
+            string::TAG_CONCAT_UDF_NAME => Ok(string::TAG_CONCAT.clone()),
             window::WINDOW_BOUNDS_UDF_NAME => Ok(window::WINDOW_BOUNDS_UDF.clone()),
             _ => Err(DataFusionError::Plan(format!(
                 "IOx FunctionRegistry does not contain function '{name}'"
@@ -51,15 +57,22 @@ impl FunctionRegistry for IOxFunctionRegistry {
     }
 
     fn udaf(&self, name: &str) -> DataFusionResult<Arc<AggregateUDF>> {
-        Err(DataFusionError::Plan(format!(
-            "IOx FunctionRegistry does not contain user defined aggregate function '{name}'"
-        )))
+        match name {
+            histogram::HISTOGRAM_UDAF_NAME => Ok(histogram::HISTOGRAM.clone()),
+            percentile::PERCENTILE_UDAF_NAME => Ok(percentile::PERCENTILE.clone()),
+            _ => Err(DataFusionError::Plan(format!(
+                "IOx FunctionRegistry does not contain user defined aggregate function '{name}'"
+            ))),
+        }
     }
 
     fn udwf(&self, name: &str) -> DataFusionResult<Arc<WindowUDF>> {
-        Err(DataFusionError::Plan(format!(
-            "IOx FunctionRegistry does not contain user defined window function '{name}'"
-        )))
+        match name {
+            totalize::TOTALIZE_UDWF_NAME => Ok(totalize::TOTALIZE.clone()),
+            _ => Err(DataFusionError::Plan(format!(
+                "IOx FunctionRegistry does not contain user defined window function '{name}'"
+            ))),
+        }
     }
 }
 
@@ -76,6 +76,24 @@
 //! +----------------------------------------+-----------------------------------------+
 //! ```
 //!
+//! ## Additional Columns
+//!
+//! Any arguments after `time` are carried along with the selected
+//! row, and returned as additional struct fields named `other_1`,
+//! `other_2`, and so on. This provides the InfluxQL semantics of
+//! selecting tags or fields alongside a single selector, such as
+//! `SELECT last(water_level), location FROM h2o_feet`:
+//!
+//! ```sql
+//! select selector_last(water_level, time, location) from "h2o_feet";
+//!
+//! +-----------------------------------------------------------------+
+//! | selector_last(water_level,time,location)                        |
+//! +-----------------------------------------------------------------+
+//! | {value: 9.9, time: 2019-08-28T07:25:00Z, other_1: coyote_creek} |
+//! +-----------------------------------------------------------------+
+//! ```
+//!
 //! ## Supported Selectors
 //!
 //! IOx supports the following selectors:
@@ -122,12 +140,13 @@ pub fn register_selector_aggregates(ctx: &SessionContext) {
 /// Returns a DataFusion user defined aggregate function for computing
 /// the first(value, time) selector function, returning a struct:
 ///
-/// first(value, time) -> struct { value, time }
+/// first(value, time[, other, ...]) -> struct { value, time[, other_1, ...] }
 ///
 /// ```text
 /// {
 ///   value: value at the row of the minimum of the time column.
 ///   time: value of the minimum time column
+///   other_n: value of the nth other argument at the same row
 /// }
 /// ```
 ///
@@ -140,12 +159,13 @@ pub fn selector_first() -> AggregateUDF {
 /// Returns a DataFusion user defined aggregate function for computing
 /// the last(value, time) selector function, returning a struct:
 ///
-/// last(value, time) -> struct { value, time }
+/// last(value, time[, other, ...]) -> struct { value, time[, other_1, ...] }
 ///
 /// ```text
 /// {
 ///   value: value at the row of the maximum of the time column.
 ///   time: value of the maximum time column
+///   other_n: value of the nth other argument at the same row
 /// }
 /// ```
 ///
@@ -158,12 +178,13 @@ pub fn selector_last() -> AggregateUDF {
 /// Returns a DataFusion user defined aggregate function for computing
 /// the min(value, time) selector function, returning a struct:
 ///
-/// min(value, time) -> struct { value, time }
+/// min(value, time[, other, ...]) -> struct { value, time[, other_1, ...] }
 ///
 /// ```text
 /// {
 ///   value: value at the row with minimum value
 ///   time: value of time for row with minimum value
+///   other_n: value of the nth other argument at the same row
 /// }
 /// ```
 ///
@@ -176,12 +197,13 @@ pub fn selector_min() -> AggregateUDF {
 /// Returns a DataFusion user defined aggregate function for computing
 /// the max(value, time) selector function, returning a struct:
 ///
-/// max(value, time) -> struct { value, time }
+/// max(value, time[, other, ...]) -> struct { value, time[, other_1, ...] }
 ///
 /// ```text
 /// {
 ///   value: value at the row with maximum value
 ///   time: value of time for row with maximum value
+///   other_n: value of the nth other argument at the same row
 /// }
 /// ```
 ///
@@ -268,8 +290,8 @@ fn make_uda(name: &str, factory_builder: FactoryBuilder) -> AggregateUDF {
 
     // return type of the selector is based on the input arguments.
     //
-    // The inputs are (value, time) and the output is a struct with a
-    // 'value' and 'time' field of the same time.
+    // The inputs are (value, time, other...) and the output is a struct
+    // with 'value', 'time' and 'other_n' fields of the same types.
     let captured_name = name.to_string();
     let return_type_func: ReturnTypeFunction = Arc::new(move |arg_types| {
         let agg_type = AggType::try_from_arg_types(arg_types, &captured_name)?;
@@ -0,0 +1,509 @@
+//! String functions for processing tag values.
+//!
+//! Tag columns are dictionary encoded and usually contain far fewer
+//! distinct values than rows. The functions in this module transform
+//! each distinct dictionary value once and reuse the keys of the input
+//! column, rather than decoding and processing the value of every row.
+use std::{collections::HashMap, sync::Arc};
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, DictionaryArray, Int32Array, StringArray, StringDictionaryBuilder,
+    },
+    compute::cast,
+    datatypes::{DataType, Int32Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature, TypeSignature,
+        Volatility,
+    },
+    physical_expr::string_expressions,
+    physical_plan::ColumnarValue,
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::clean_non_meta_escapes;
+
+/// The name of the regexp_extract UDF given to DataFusion.
+pub const REGEXP_EXTRACT_UDF_NAME: &str = "regexp_extract";
+
+/// The name of the split_part UDF given to DataFusion.
+pub const SPLIT_PART_UDF_NAME: &str = "split_part";
+
+/// The name of the tag_concat UDF given to DataFusion.
+pub const TAG_CONCAT_UDF_NAME: &str = "tag_concat";
+
+/// The type of a dictionary-encoded tag column.
+static TAG_TYPE: Lazy<DataType> =
+    Lazy::new(|| DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)));
+
+/// `regexp_extract(value, pattern[, group])`
+///
+/// Returns the capture `group` (default `1`, `0` is the entire match) of
+/// the first match of `pattern` in `value`, or `NULL` if the pattern or
+/// group does not match. The pattern uses the same syntax as the InfluxQL
+/// regex operators.
+pub(crate) static REGEXP_EXTRACT: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let signature = Signature::one_of(
+        [DataType::Utf8, TAG_TYPE.clone()]
+            .into_iter()
+            .flat_map(|dt| {
+                [
+                    TypeSignature::Exact(vec![dt.clone(), DataType::Utf8]),
+                    TypeSignature::Exact(vec![dt, DataType::Utf8, DataType::Int64]),
+                ]
+            })
+            .collect(),
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(same_as_first_arg);
+    let fun: ScalarFunctionImplementation = Arc::new(regexp_extract);
+
+    Arc::new(ScalarUDF::new(
+        REGEXP_EXTRACT_UDF_NAME,
+        &signature,
+        &return_type,
+        &fun,
+    ))
+});
+
+/// `split_part(value, delimiter, n)`
+///
+/// Splits `value` on `delimiter` and returns the `n`th part, counting from
+/// `1`. This takes precedence over, and has the same semantics as, the
+/// DataFusion built-in function of the same name, which it calls if the
+/// delimiter or position are not constants.
+pub(crate) static SPLIT_PART: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let signature = Signature::one_of(
+        [DataType::Utf8, TAG_TYPE.clone()]
+            .into_iter()
+            .map(|dt| TypeSignature::Exact(vec![dt, DataType::Utf8, DataType::Int64]))
+            .collect(),
+        Volatility::Immutable,
+    );
+    let return_type: ReturnTypeFunction = Arc::new(same_as_first_arg);
+    let fun: ScalarFunctionImplementation = Arc::new(split_part);
+
+    Arc::new(ScalarUDF::new(
+        SPLIT_PART_UDF_NAME,
+        &signature,
+        &return_type,
+        &fun,
+    ))
+});
+
+/// `tag_concat(separator, value, ...)`
+///
+/// Concatenates the non-null `value`s, separated by `separator`, and
+/// returns a dictionary-encoded column. The result is `NULL` if all
+/// values are `NULL`. Each distinct combination of input values is
+/// concatenated only once.
+pub(crate) static TAG_CONCAT: Lazy<Arc<ScalarUDF>> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(|_| Ok(Arc::new(TAG_TYPE.clone())));
+    let fun: ScalarFunctionImplementation = Arc::new(tag_concat);
+
+    Arc::new(ScalarUDF::new(
+        TAG_CONCAT_UDF_NAME,
+        &Signature::variadic_any(Volatility::Immutable),
+        &return_type,
+        &fun,
+    ))
+});
+
+fn same_as_first_arg(args: &[DataType]) -> DataFusionResult<Arc<DataType>> {
+    Ok(Arc::new(args[0].clone()))
+}
+
+/// Apply `f` to every distinct string of `value`, which may be a
+/// dictionary-encoded or plain string column or scalar.
+fn map_strings(
+    name: &str,
+    value: &ColumnarValue,
+    mut f: impl FnMut(&str) -> Option<String>,
+) -> DataFusionResult<ColumnarValue> {
+    match value {
+        ColumnarValue::Array(array) => match array.data_type() {
+            DataType::Dictionary(key, _) if key.as_ref() == &DataType::Int32 => {
+                let dict = array.as_dictionary::<Int32Type>();
+                let values = dict
+                    .values()
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|v| v.and_then(&mut f))
+                    .collect::<StringArray>();
+                let dict =
+                    DictionaryArray::<Int32Type>::try_new(dict.keys().clone(), Arc::new(values))?;
+                Ok(ColumnarValue::Array(Arc::new(dict)))
+            }
+            DataType::Utf8 => {
+                let values = array
+                    .as_string::<i32>()
+                    .iter()
+                    .map(|v| v.and_then(&mut f))
+                    .collect::<StringArray>();
+                Ok(ColumnarValue::Array(Arc::new(values)))
+            }
+            dt => Err(DataFusionError::Internal(format!(
+                "{name} expected a string argument, got {dt}"
+            ))),
+        },
+        ColumnarValue::Scalar(ScalarValue::Utf8(v)) => Ok(ColumnarValue::Scalar(
+            ScalarValue::Utf8(v.as_deref().and_then(f)),
+        )),
+        ColumnarValue::Scalar(ScalarValue::Dictionary(key, v)) => {
+            let ColumnarValue::Scalar(v) =
+                map_strings(name, &ColumnarValue::Scalar(v.as_ref().clone()), f)?
+            else {
+                unreachable!("scalar input produces a scalar")
+            };
+            Ok(ColumnarValue::Scalar(ScalarValue::Dictionary(
+                key.clone(),
+                Box::new(v),
+            )))
+        }
+        ColumnarValue::Scalar(v) => Err(DataFusionError::Internal(format!(
+            "{name} expected a string argument, got {v:?}"
+        ))),
+    }
+}
+
+/// Return the constant string argument `arg`.
+fn string_arg<'a>(
+    name: &str,
+    arg: &'a ColumnarValue,
+) -> DataFusionResult<Option<&'a Option<String>>> {
+    match arg {
+        ColumnarValue::Array(_) => Ok(None),
+        ColumnarValue::Scalar(ScalarValue::Utf8(v)) => Ok(Some(v)),
+        ColumnarValue::Scalar(v) => Err(DataFusionError::Internal(format!(
+            "{name} expected a string argument, got {v:?}"
+        ))),
+    }
+}
+
+/// Return the constant integer argument `arg`.
+fn int_arg(name: &str, arg: &ColumnarValue) -> DataFusionResult<Option<Option<i64>>> {
+    match arg {
+        ColumnarValue::Array(_) => Ok(None),
+        ColumnarValue::Scalar(ScalarValue::Int64(v)) => Ok(Some(*v)),
+        ColumnarValue::Scalar(v) => Err(DataFusionError::Internal(format!(
+            "{name} expected an integer argument, got {v:?}"
+        ))),
+    }
+}
+
+fn regexp_extract(args: &[ColumnarValue]) -> DataFusionResult<ColumnarValue> {
+    let name = REGEXP_EXTRACT_UDF_NAME;
+
+    let pattern = string_arg(name, &args[1])?.ok_or_else(|| {
+        DataFusionError::NotImplemented(format!(
+            "{name} with non scalar patterns not yet implemented"
+        ))
+    })?;
+    let group = match args.get(2) {
+        Some(arg) => int_arg(name, arg)?.ok_or_else(|| {
+            DataFusionError::NotImplemented(format!(
+                "{name} with non scalar groups not yet implemented"
+            ))
+        })?,
+        None => Some(1),
+    };
+
+    let (Some(pattern), Some(group)) = (pattern, group) else {
+        return map_strings(name, &args[0], |_| None);
+    };
+    let group = usize::try_from(group).map_err(|_| {
+        DataFusionError::Execution(format!("{name} group must not be negative, got {group}"))
+    })?;
+    let pattern = Regex::new(&clean_non_meta_escapes(pattern))
+        .map_err(|e| DataFusionError::Execution(format!("error compiling regex pattern: {e}")))?;
+
+    map_strings(name, &args[0], |v| {
+        pattern
+            .captures(v)
+            .and_then(|c| c.get(group))
+            .map(|m| m.as_str().to_string())
+    })
+}
+
+fn split_part(args: &[ColumnarValue]) -> DataFusionResult<ColumnarValue> {
+    let name = SPLIT_PART_UDF_NAME;
+
+    let (Some(delimiter), Some(n)) = (string_arg(name, &args[1])?, int_arg(name, &args[2])?) else {
+        return split_part_fallback(args);
+    };
+    let (Some(delimiter), Some(n)) = (delimiter, n) else {
+        return map_strings(name, &args[0], |_| None);
+    };
+    if n <= 0 {
+        return Err(DataFusionError::Execution(
+            "field position must be greater than zero".to_string(),
+        ));
+    }
+
+    map_strings(name, &args[0], |v| {
+        Some(
+            v.split(delimiter.as_str())
+                .nth(n as usize - 1)
+                .unwrap_or_default()
+                .to_string(),
+        )
+    })
+}
+
+/// Evaluate `split_part` row by row with the DataFusion implementation.
+fn split_part_fallback(args: &[ColumnarValue]) -> DataFusionResult<ColumnarValue> {
+    let len = args
+        .iter()
+        .find_map(|arg| match arg {
+            ColumnarValue::Array(array) => Some(array.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1);
+    let arrays = args
+        .iter()
+        .map(|arg| {
+            let array = arg.clone().into_array(len);
+            match array.data_type() {
+                DataType::Dictionary(_, _) => cast(&array, &DataType::Utf8),
+                _ => Ok(array),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let result = string_expressions::split_part::<i32>(&arrays)?;
+    let result = match args[0].data_type() {
+        DataType::Dictionary(_, _) => cast(&result, &TAG_TYPE)?,
+        _ => result,
+    };
+    Ok(ColumnarValue::Array(result))
+}
+
+/// One argument of `tag_concat`.
+enum TagArg<'a> {
+    Scalar(Option<&'a str>),
+    Strings(&'a StringArray),
+    Dictionary(&'a Int32Array, &'a StringArray),
+}
+
+impl<'a> TagArg<'a> {
+    fn try_new(value: &'a ColumnarValue) -> DataFusionResult<Self> {
+        let name = TAG_CONCAT_UDF_NAME;
+        Ok(match value {
+            ColumnarValue::Scalar(ScalarValue::Utf8(v)) => Self::Scalar(v.as_deref()),
+            ColumnarValue::Scalar(ScalarValue::Dictionary(_, v)) => match v.as_ref() {
+                ScalarValue::Utf8(v) => Self::Scalar(v.as_deref()),
+                v => {
+                    return Err(DataFusionError::Internal(format!(
+                        "{name} expected a string argument, got {v:?}"
+                    )))
+                }
+            },
+            ColumnarValue::Array(array) => match array.data_type() {
+                DataType::Utf8 => Self::Strings(array.as_string::<i32>()),
+                DataType::Dictionary(key, _) if key.as_ref() == &DataType::Int32 => {
+                    let dict = array.as_dictionary::<Int32Type>();
+                    Self::Dictionary(dict.keys(), dict.values().as_string::<i32>())
+                }
+                dt => {
+                    return Err(DataFusionError::Internal(format!(
+                        "{name} expected a string argument, got {dt}"
+                    )))
+                }
+            },
+            ColumnarValue::Scalar(v) => {
+                return Err(DataFusionError::Internal(format!(
+                    "{name} expected a string argument, got {v:?}"
+                )))
+            }
+        })
+    }
+
+    fn value(&self, row: usize) -> Option<&'a str> {
+        match self {
+            Self::Scalar(v) => *v,
+            Self::Strings(array) => array.is_valid(row).then(|| array.value(row)),
+            Self::Dictionary(keys, values) => {
+                if keys.is_null(row) {
+                    return None;
+                }
+                let key = keys.value(row) as usize;
+                values.is_valid(key).then(|| values.value(key))
+            }
+        }
+    }
+}
+
+fn tag_concat(args: &[ColumnarValue]) -> DataFusionResult<ColumnarValue> {
+    let name = TAG_CONCAT_UDF_NAME;
+
+    if args.len() < 2 {
+        return Err(DataFusionError::Plan(format!(
+            "{name} expects a separator and at least one value"
+        )));
+    }
+    let separator = string_arg(name, &args[0])?.ok_or_else(|| {
+        DataFusionError::NotImplemented(format!(
+            "{name} with non scalar separators not yet implemented"
+        ))
+    })?;
+    let separator = separator.as_deref().unwrap_or_default();
+
+    let values = args[1..]
+        .iter()
+        .map(TagArg::try_new)
+        .collect::<DataFusionResult<Vec<_>>>()?;
+    let len = args[1..]
+        .iter()
+        .find_map(|arg| match arg {
+            ColumnarValue::Array(array) => Some(array.len()),
+            ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1);
+
+    let mut builder = StringDictionaryBuilder::<Int32Type>::new();
+    let mut cache: HashMap<Vec<Option<&str>>, Option<i32>> = HashMap::new();
+    for row in 0..len {
+        let parts = values.iter().map(|v| v.value(row)).collect::<Vec<_>>();
+        let key = match cache.get(&parts) {
+            Some(key) => *key,
+            None => {
+                let key = if parts.iter().all(Option::is_none) {
+                    None
+                } else {
+                    let concatenated = parts.iter().flatten().copied().collect::<Vec<_>>();
+                    Some(builder.append(concatenated.join(separator))?)
+                };
+                cache.insert(parts, key);
+                key
+            }
+        };
+        match key {
+            Some(key) => builder.append_keys(key),
+            None => builder.append_null(),
+        }
+    }
+
+    Ok(ColumnarValue::Array(Arc::new(builder.finish())))
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::record_batch::RecordBatch;
+    use datafusion::assert_batches_eq;
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    async fn run(sql: &str) -> DataFusionResult<Vec<RecordBatch>> {
+        let host = vec![
+            Some("web-01.us-east"),
+            Some("web-02.us-east"),
+            None,
+            Some("db-01.eu-west"),
+            Some("web-01.us-east"),
+        ];
+        let region = vec![Some("us"), Some("us"), Some("eu"), None, Some("us")];
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "host",
+                Arc::new(host.into_iter().collect::<DictionaryArray<Int32Type>>()) as ArrayRef,
+            ),
+            (
+                "region",
+                Arc::new(region.into_iter().collect::<DictionaryArray<Int32Type>>()) as ArrayRef,
+            ),
+            (
+                "delim",
+                Arc::new(StringArray::from(vec!["-", ".", "-", ".", "-"])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let ctx = context_with_table(batch);
+        ctx.register_udf(REGEXP_EXTRACT.as_ref().clone());
+        ctx.register_udf(SPLIT_PART.as_ref().clone());
+        ctx.register_udf(TAG_CONCAT.as_ref().clone());
+        ctx.sql(sql).await?.collect().await
+    }
+
+    #[tokio::test]
+    async fn test_regexp_extract() {
+        let got = run(r#"SELECT regexp_extract(host, '^([a-z]+)-([0-9]+)') AS a, regexp_extract(host, '^([a-z]+)-([0-9]+)', 2) AS b, regexp_extract(host, 'us-[a-z]+', 0) AS c, arrow_typeof(regexp_extract(host, '(.*)')) AS t FROM t"#)
+            .await
+            .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+-----+----+---------+-------------------------+",
+                "| a   | b  | c       | t                       |",
+                "+-----+----+---------+-------------------------+",
+                "| web | 01 | us-east | Dictionary(Int32, Utf8) |",
+                "| web | 02 | us-east | Dictionary(Int32, Utf8) |",
+                "|     |    |         | Dictionary(Int32, Utf8) |",
+                "| db  | 01 |         | Dictionary(Int32, Utf8) |",
+                "| web | 01 | us-east | Dictionary(Int32, Utf8) |",
+                "+-----+----+---------+-------------------------+",
+            ],
+            &got
+        );
+    }
+
+    #[tokio::test]
+    async fn test_split_part() {
+        let got = run("SELECT split_part(host, '.', 1) AS a, split_part(host, '.', 3) AS b, split_part(host, delim, 1) AS c FROM t")
+            .await
+            .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+--------+---+--------+",
+                "| a      | b | c      |",
+                "+--------+---+--------+",
+                "| web-01 |   | web    |",
+                "| web-02 |   | web-02 |",
+                "|        |   |        |",
+                "| db-01  |   | db-01  |",
+                "| web-01 |   | web    |",
+                "+--------+---+--------+",
+            ],
+            &got
+        );
+
+        let err = run("SELECT split_part(host, '.', 0) FROM t")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("field position must be greater than zero"),
+            "{err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tag_concat() {
+        let got =
+            run("SELECT tag_concat('/', region, host) AS a, tag_concat('', host, 'x') AS b FROM t")
+                .await
+                .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+-------------------+-----------------+",
+                "| a                 | b               |",
+                "+-------------------+-----------------+",
+                "| us/web-01.us-east | web-01.us-eastx |",
+                "| us/web-02.us-east | web-02.us-eastx |",
+                "| eu                | x               |",
+                "| db-01.eu-west     | db-01.eu-westx  |",
+                "| us/web-01.us-east | web-01.us-eastx |",
+                "+-------------------+-----------------+",
+            ],
+            &got
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Implementation of the `totalize` window function.
+//!
+//! ```text
+//! totalize(value[, policy]) OVER (ORDER BY time)
+//! ```
+//!
+//! Converts a monotonically increasing counter into the running total of
+//! its increases within the window partition. The first value establishes
+//! the baseline, so its total is `0`. A value lower than its predecessor is
+//! treated as a counter reset, such as a restarted process, and adds the
+//! value itself to the total rather than the negative delta.
+//!
+//! `policy` controls the result for `NULL` and `NaN` input values, which
+//! never change the total:
+//!
+//! * `'skip'` (the default) returns `NULL`.
+//! * `'carry'` returns the total so far.
+use std::sync::Arc;
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray, Float64Builder},
+    compute::cast,
+    datatypes::{DataType, Float64Type},
+};
+use datafusion::{
+    error::{DataFusionError, Result as DataFusionResult},
+    logical_expr::{
+        PartitionEvaluator, PartitionEvaluatorFactory, ReturnTypeFunction, Signature,
+        TypeSignature, Volatility, WindowUDF,
+    },
+    scalar::ScalarValue,
+};
+use once_cell::sync::Lazy;
+
+/// The name of the totalize window function.
+pub const TOTALIZE_UDWF_NAME: &str = "totalize";
+
+/// Types accepted for the `value` argument.
+const VALUE_TYPES: [DataType; 3] = [DataType::Int64, DataType::UInt64, DataType::Float64];
+
+/// Valid signatures for the totalize window function.
+static SIGNATURE: Lazy<Signature> = Lazy::new(|| {
+    Signature::one_of(
+        VALUE_TYPES
+            .iter()
+            .flat_map(|dt| {
+                [
+                    TypeSignature::Exact(vec![dt.clone()]),
+                    TypeSignature::Exact(vec![dt.clone(), DataType::Utf8]),
+                ]
+            })
+            .collect(),
+        Volatility::Immutable,
+    )
+});
+
+/// Implementation of totalize
+pub(crate) static TOTALIZE: Lazy<Arc<WindowUDF>> = Lazy::new(|| {
+    let return_type: ReturnTypeFunction = Arc::new(return_type);
+    let partition_evaluator_factory: PartitionEvaluatorFactory =
+        Arc::new(partition_evaluator_factory);
+
+    Arc::new(WindowUDF::new(
+        TOTALIZE_UDWF_NAME,
+        &SIGNATURE,
+        &return_type,
+        &partition_evaluator_factory,
+    ))
+});
+
+fn return_type(_: &[DataType]) -> DataFusionResult<Arc<DataType>> {
+    Ok(Arc::new(DataType::Float64))
+}
+
+fn partition_evaluator_factory() -> DataFusionResult<Box<dyn PartitionEvaluator>> {
+    Ok(Box::new(TotalizePartitionEvaluator {}))
+}
+
+/// The result for `NULL` and `NaN` input values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Policy {
+    /// Return `NULL`.
+    Skip,
+    /// Return the total so far.
+    Carry,
+}
+
+impl Policy {
+    fn try_from_array(array: Option<&ArrayRef>) -> DataFusionResult<Self> {
+        let Some(array) = array else {
+            return Ok(Self::Skip);
+        };
+        match ScalarValue::try_from_array(array, 0)? {
+            ScalarValue::Utf8(Some(v)) if v.eq_ignore_ascii_case("skip") => Ok(Self::Skip),
+            ScalarValue::Utf8(Some(v)) if v.eq_ignore_ascii_case("carry") => Ok(Self::Carry),
+            v => Err(DataFusionError::Execution(format!(
+                "{TOTALIZE_UDWF_NAME}: policy must be 'skip' or 'carry', got {v}"
+            ))),
+        }
+    }
+}
+
+/// PartitionEvaluator which returns the running total of the increases
+/// of a counter.
+#[derive(Debug)]
+struct TotalizePartitionEvaluator {}
+
+impl PartitionEvaluator for TotalizePartitionEvaluator {
+    fn evaluate_all(&mut self, values: &[ArrayRef], num_rows: usize) -> DataFusionResult<ArrayRef> {
+        let policy = Policy::try_from_array(values.get(1))?;
+        let array = cast(&values[0], &DataType::Float64)?;
+        let array = array.as_primitive::<Float64Type>();
+
+        let mut builder = Float64Builder::with_capacity(num_rows);
+        let mut total = 0.0;
+        let mut last: Option<f64> = None;
+        for v in array.iter() {
+            match v {
+                Some(v) if !v.is_nan() => {
+                    total += match last {
+                        Some(last) if v >= last => v - last,
+                        // counter reset
+                        Some(_) => v,
+                        None => 0.0,
+                    };
+                    last = Some(v);
+                    builder.append_value(total);
+                }
+                _ => match (policy, last) {
+                    (Policy::Carry, Some(_)) => builder.append_value(total),
+                    _ => builder.append_null(),
+                },
+            }
+        }
+        Ok(Arc::new(builder.finish()))
+    }
+
+    fn uses_window_frame(&self) -> bool {
+        false
+    }
+
+    fn include_rank(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{Float64Array, Int64Array},
+        record_batch::RecordBatch,
+    };
+    use datafusion::assert_batches_eq;
+    use datafusion_util::context_with_table;
+
+    use super::*;
+
+    async fn run(batch: RecordBatch, sql: &str) -> DataFusionResult<Vec<RecordBatch>> {
+        let ctx = context_with_table(batch);
+        ctx.register_udwf(TOTALIZE.as_ref().clone());
+        ctx.sql(sql).await?.collect().await
+    }
+
+    fn float_batch() -> RecordBatch {
+        RecordBatch::try_from_iter(vec![
+            (
+                "time",
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5, 6, 7])) as ArrayRef,
+            ),
+            (
+                "v",
+                Arc::new(Float64Array::from(vec![
+                    None,
+                    Some(10.0),
+                    Some(15.0),
+                    Some(f64::NAN),
+                    Some(4.0),
+                    None,
+                    Some(6.5),
+                ])) as ArrayRef,
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_counter_reset() {
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "time",
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4, 5])) as ArrayRef,
+            ),
+            (
+                "v",
+                Arc::new(Int64Array::from(vec![100, 120, 130, 5, 25])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let got = run(
+            batch,
+            "SELECT time, v, totalize(v) OVER (ORDER BY time) AS total FROM t",
+        )
+        .await
+        .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+------+-----+-------+",
+                "| time | v   | total |",
+                "+------+-----+-------+",
+                "| 1    | 100 | 0.0   |",
+                "| 2    | 120 | 20.0  |",
+                "| 3    | 130 | 30.0  |",
+                "| 4    | 5   | 35.0  |",
+                "| 5    | 25  | 55.0  |",
+                "+------+-----+-------+",
+            ],
+            &got
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policy() {
+        let got = run(
+            float_batch(),
+            "SELECT time, totalize(v) OVER (ORDER BY time) AS skip, totalize(v, 'carry') OVER (ORDER BY time) AS carry FROM t",
+        )
+        .await
+        .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+------+------+-------+",
+                "| time | skip | carry |",
+                "+------+------+-------+",
+                "| 1    |      |       |",
+                "| 2    | 0.0  | 0.0   |",
+                "| 3    | 5.0  | 5.0   |",
+                "| 4    |      | 5.0   |",
+                "| 5    | 9.0  | 9.0   |",
+                "| 6    |      | 9.0   |",
+                "| 7    | 11.5 | 11.5  |",
+                "+------+------+-------+",
+            ],
+            &got
+        );
+    }
+
+    #[tokio::test]
+    async fn test_invalid_policy() {
+        let err = run(
+            float_batch(),
+            "SELECT totalize(v, 'zero') OVER (ORDER BY time) FROM t",
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("policy must be 'skip' or 'carry', got zero"),
+            "{err}"
+        );
+    }
+}
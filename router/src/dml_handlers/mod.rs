@@ -53,6 +53,9 @@ pub mod nop;
 mod retention_validation;
 pub use retention_validation::*;
 
+mod rate_limiter;
+pub use rate_limiter::*;
+
 mod partitioner;
 pub use partitioner::*;
 
@@ -68,5 +71,11 @@ pub use fan_out::*;
 mod rpc_write;
 pub use rpc_write::*;
 
+mod write_mirror;
+pub use write_mirror::*;
+
+mod sharded_write;
+pub use sharded_write::*;
+
 #[cfg(test)]
 pub mod mock;
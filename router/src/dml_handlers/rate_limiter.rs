@@ -0,0 +1,480 @@
+use std::{sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use data_types::{NamespaceId, NamespaceName, NamespaceSchema};
+use hashbrown::HashMap;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use iox_time::{SystemProvider, Time, TimeProvider};
+use metric::U64Counter;
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use parking_lot::{Mutex, RwLock};
+use thiserror::Error;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+use trace::ctx::SpanContext;
+
+use super::DmlHandler;
+
+/// Errors emitted when a write exceeds the write rate limits of its
+/// namespace.
+#[derive(Debug, Error)]
+pub enum RateLimitError {
+    /// The namespace has used up its write budget.
+    #[error("namespace {namespace} exceeded its write rate limit of {limit} {unit} per second")]
+    Exceeded {
+        /// The name of the rate limited namespace.
+        namespace: String,
+        /// The exceeded limit.
+        limit: u64,
+        /// The unit of the exceeded limit ("lines" or "bytes").
+        unit: &'static str,
+        /// The time after which the namespace has write budget again.
+        retry_after: Duration,
+    },
+}
+
+impl RateLimitError {
+    /// The time after which the client should retry the write.
+    pub fn retry_after(&self) -> Duration {
+        match self {
+            Self::Exceeded { retry_after, .. } => *retry_after,
+        }
+    }
+}
+
+/// The write rate limits of a namespace, as configured in the catalog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct WriteRateLimits {
+    lines_per_second: Option<u64>,
+    bytes_per_second: Option<u64>,
+}
+
+/// A token bucket that refills at the rate of its limit per second, holding
+/// at most one second's worth of budget.
+///
+/// The budget may be overdrawn by a single write, so that writes larger than
+/// the limit are possible. Subsequent writes are rejected until the overdraft
+/// is paid back.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Time,
+}
+
+impl TokenBucket {
+    fn new(limit: u64, now: Time) -> Self {
+        Self {
+            tokens: limit as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: u64, now: Time) {
+        if let Some(elapsed) = now.checked_duration_since(self.last_refill) {
+            self.tokens = (self.tokens + elapsed.as_secs_f64() * limit as f64).min(limit as f64);
+            self.last_refill = now;
+        }
+    }
+
+    /// The time until the budget is no longer overdrawn, if it is.
+    fn overdrawn_for(&self, limit: u64) -> Option<Duration> {
+        (self.tokens < 0.0).then(|| Duration::from_secs_f64(-self.tokens / limit as f64))
+    }
+}
+
+/// The write budget of a namespace.
+#[derive(Debug, Default)]
+struct NamespaceBudget {
+    lines: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    limits: RwLock<HashMap<NamespaceId, WriteRateLimits>>,
+    budgets: Mutex<HashMap<NamespaceId, NamespaceBudget>>,
+}
+
+impl State {
+    /// Replace the limits with the limits of all namespaces in `catalog`.
+    async fn refresh(&self, catalog: &dyn Catalog) {
+        let namespaces = match catalog
+            .repositories()
+            .await
+            .namespaces()
+            .list(SoftDeletedRows::ExcludeDeleted)
+            .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "failed to refresh namespace write rate limits");
+                return;
+            }
+        };
+
+        let limits = namespaces
+            .into_iter()
+            .filter_map(|ns| {
+                // Limits are validated to be positive when set.
+                let limits = WriteRateLimits {
+                    lines_per_second: ns.max_write_lines_per_second.map(|v| v.max(1) as u64),
+                    bytes_per_second: ns.max_write_bytes_per_second.map(|v| v.max(1) as u64),
+                };
+                (limits != WriteRateLimits::default()).then_some((ns.id, limits))
+            })
+            .collect::<HashMap<_, _>>();
+
+        // Drop the budgets of namespaces that are no longer rate limited.
+        self.budgets.lock().retain(|id, _| limits.contains_key(id));
+        *self.limits.write() = limits;
+    }
+}
+
+/// A [`DmlHandler`] implementation that rejects writes exceeding the write
+/// rate limits of their namespace.
+///
+/// The lines and bytes (the size of the decoded data) per second limits are
+/// stored in the namespace record in the catalog. They are periodically
+/// reloaded, so changes take effect without restarting the router. Namespaces
+/// without limits are not rate limited.
+///
+/// Each router enforces the limits independently, so the effective limit of a
+/// namespace is the configured limit multiplied by the number of routers
+/// receiving its writes.
+#[derive(Debug)]
+pub struct RateLimiter<P = SystemProvider> {
+    state: Arc<State>,
+    time_provider: P,
+    refresh_task: JoinHandle<()>,
+
+    rejected_lines: U64Counter,
+    rejected_bytes: U64Counter,
+}
+
+impl RateLimiter {
+    /// Initialise a new [`RateLimiter`], loading the namespace write rate
+    /// limits from `catalog` and reloading them every `refresh_interval`.
+    pub async fn new(
+        catalog: Arc<dyn Catalog>,
+        refresh_interval: Duration,
+        metrics: &metric::Registry,
+    ) -> Self {
+        Self::new_with_time_provider(catalog, refresh_interval, metrics, Default::default()).await
+    }
+}
+
+impl<P> RateLimiter<P> {
+    async fn new_with_time_provider(
+        catalog: Arc<dyn Catalog>,
+        refresh_interval: Duration,
+        metrics: &metric::Registry,
+        time_provider: P,
+    ) -> Self {
+        let state = Arc::new(State::default());
+        state.refresh(&*catalog).await;
+
+        let refresh_task = tokio::spawn({
+            let state = Arc::clone(&state);
+            async move {
+                let mut ticker = tokio::time::interval(refresh_interval);
+                ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                // The first tick completes immediately, and the limits were
+                // just loaded.
+                ticker.tick().await;
+                loop {
+                    ticker.tick().await;
+                    state.refresh(&*catalog).await;
+                }
+            }
+        });
+
+        let rejected = metrics.register_metric::<U64Counter>(
+            "dml_handler_rate_limited_writes",
+            "number of writes rejected for exceeding the write rate limits of their namespace",
+        );
+
+        Self {
+            state,
+            time_provider,
+            refresh_task,
+            rejected_lines: rejected.recorder(&[("limit", "lines")]),
+            rejected_bytes: rejected.recorder(&[("limit", "bytes")]),
+        }
+    }
+}
+
+impl<P> Drop for RateLimiter<P> {
+    fn drop(&mut self) {
+        self.refresh_task.abort()
+    }
+}
+
+#[async_trait]
+impl<P> DmlHandler for RateLimiter<P>
+where
+    P: TimeProvider,
+{
+    type WriteError = RateLimitError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+
+    /// Charge the write to the budget of its namespace, rejecting it if the
+    /// budget is used up.
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: Arc<NamespaceSchema>,
+        batch: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let Some(limits) = self.state.limits.read().get(&namespace_schema.id).copied() else {
+            return Ok(batch);
+        };
+
+        let lines = batch.values().map(|b| b.rows()).sum::<usize>() as f64;
+        let bytes = batch.values().map(|b| b.size_data()).sum::<usize>() as f64;
+        let now = self.time_provider.now();
+
+        let mut budgets = self.state.budgets.lock();
+        let budget = budgets.entry(namespace_schema.id).or_default();
+
+        // Check all limits before charging any of them, so that rejected
+        // writes are not charged.
+        let checks = [
+            (
+                limits.lines_per_second,
+                &mut budget.lines,
+                lines,
+                "lines",
+                &self.rejected_lines,
+            ),
+            (
+                limits.bytes_per_second,
+                &mut budget.bytes,
+                bytes,
+                "bytes",
+                &self.rejected_bytes,
+            ),
+        ];
+        let mut charges = Vec::with_capacity(checks.len());
+        for (limit, bucket, cost, unit, rejected) in checks {
+            let Some(limit) = limit else {
+                *bucket = None;
+                continue;
+            };
+            let bucket = bucket.get_or_insert_with(|| TokenBucket::new(limit, now));
+            bucket.refill(limit, now);
+
+            if let Some(retry_after) = bucket.overdrawn_for(limit) {
+                rejected.inc(1);
+                debug!(
+                    %namespace,
+                    limit,
+                    unit,
+                    ?retry_after,
+                    "rejecting write exceeding namespace write rate limit"
+                );
+                return Err(RateLimitError::Exceeded {
+                    namespace: namespace.to_string(),
+                    limit,
+                    unit,
+                    retry_after,
+                });
+            }
+
+            charges.push((bucket, cost));
+        }
+
+        for (bucket, cost) in charges {
+            bucket.tokens -= cost;
+        }
+
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use iox_tests::TestCatalog;
+    use iox_time::MockProvider;
+    use metric::{Attributes, Metric};
+    use once_cell::sync::Lazy;
+
+    use super::*;
+
+    static NAMESPACE: Lazy<NamespaceName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    // Parse `lp` into a table-keyed MutableBatch map.
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    fn rejected(metrics: &metric::Registry, limit: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("dml_handler_rate_limited_writes")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("limit", limit)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    async fn set_limits(catalog: &TestCatalog, lines: Option<i64>, bytes: Option<i64>) {
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_write_rate_limits(NAMESPACE.as_str(), lines, bytes)
+            .await
+            .expect("failed to update namespace write rate limits");
+    }
+
+    #[tokio::test]
+    async fn test_lines_limit() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+        set_limits(&catalog, Some(2), None).await;
+
+        let metrics = metric::Registry::default();
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+        let handler = RateLimiter::new_with_time_provider(
+            catalog.catalog(),
+            Duration::from_secs(3600),
+            &metrics,
+            time.clone(),
+        )
+        .await;
+        let schema: Arc<NamespaceSchema> = namespace.schema().await.into();
+        let writes = lp_to_writes("bananas val=1i 1\nbananas val=2i 2\nbananas val=3i 3");
+
+        // A write larger than the limit is admitted with a full budget ...
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect("write should be admitted");
+
+        // ... after which the budget is overdrawn by one line, which takes
+        // half a second to pay back.
+        let err = handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect_err("write should be rate limited");
+        assert_matches!(
+            err,
+            RateLimitError::Exceeded {
+                limit: 2,
+                unit: "lines",
+                ..
+            }
+        );
+        assert_eq!(err.retry_after(), Duration::from_millis(500));
+        assert_eq!(rejected(&metrics, "lines"), 1);
+        assert_eq!(rejected(&metrics, "bytes"), 0);
+
+        // Once the budget is paid back, writes are admitted again.
+        time.inc(Duration::from_millis(500));
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes, None)
+            .await
+            .expect("write should be admitted");
+    }
+
+    #[tokio::test]
+    async fn test_bytes_limit() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+        set_limits(&catalog, Some(1_000), Some(1)).await;
+
+        let metrics = metric::Registry::default();
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+        let handler = RateLimiter::new_with_time_provider(
+            catalog.catalog(),
+            Duration::from_secs(3600),
+            &metrics,
+            time.clone(),
+        )
+        .await;
+        let schema: Arc<NamespaceSchema> = namespace.schema().await.into();
+        let writes = lp_to_writes("bananas val=1i 1");
+
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect("write should be admitted");
+        let err = handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes, None)
+            .await
+            .expect_err("write should be rate limited");
+        assert_matches!(
+            err,
+            RateLimitError::Exceeded {
+                limit: 1,
+                unit: "bytes",
+                ..
+            }
+        );
+        assert_eq!(rejected(&metrics, "bytes"), 1);
+        assert_eq!(rejected(&metrics, "lines"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_no_limits() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+
+        let metrics = metric::Registry::default();
+        let handler =
+            RateLimiter::new(catalog.catalog(), Duration::from_secs(3600), &metrics).await;
+        let schema: Arc<NamespaceSchema> = namespace.schema().await.into();
+        let writes = lp_to_writes("bananas val=1i 1\nbananas val=2i 2");
+
+        for _ in 0..10 {
+            handler
+                .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+                .await
+                .expect("write should be admitted");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refresh() {
+        let catalog = TestCatalog::new();
+        let namespace = catalog.create_namespace_1hr_retention(&NAMESPACE).await;
+
+        let metrics = metric::Registry::default();
+        let handler =
+            RateLimiter::new(catalog.catalog(), Duration::from_secs(3600), &metrics).await;
+        let schema: Arc<NamespaceSchema> = namespace.schema().await.into();
+        let writes = lp_to_writes("bananas val=1i 1\nbananas val=2i 2");
+
+        // Limits set after the initial load take effect once reloaded.
+        set_limits(&catalog, Some(1), None).await;
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect("write should be admitted");
+
+        handler.state.refresh(&*catalog.catalog()).await;
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect("write should be admitted with a full budget");
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes.clone(), None)
+            .await
+            .expect_err("write should be rate limited");
+
+        // Removing the limits removes the budget.
+        set_limits(&catalog, None, None).await;
+        handler.state.refresh(&*catalog.catalog()).await;
+        assert!(handler.state.budgets.lock().is_empty());
+        handler
+            .write(&NAMESPACE, Arc::clone(&schema), writes, None)
+            .await
+            .expect("write should be admitted");
+    }
+}
@@ -35,7 +35,8 @@ pub enum RetentionError {
 ///
 /// Each row of data being wrote is inspected, and if any "time" column
 /// timestamp lays outside of the configured namespace retention period, the
-/// entire write is rejected.
+/// entire write is rejected. A retention period set on an existing table
+/// overrides the namespace retention period for writes to that table.
 #[derive(Debug, Default)]
 pub struct RetentionValidator<P = SystemProvider> {
     time_provider: P,
@@ -66,22 +67,32 @@ where
         batch: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
-        // retention is not infinte, validate all lines of a write are within the retention period
-        if let Some(retention_period_ns) = namespace_schema.retention_period_ns {
-            let min_retention = self.time_provider.now().timestamp_nanos() - retention_period_ns;
-            // batch is a HashMap<tring, MutableBatch>
-            for (table_name, batch) in &batch {
-                if let Some(min) = batch.timestamp_summary().and_then(|v| v.stats.min) {
-                    if min < min_retention {
-                        return Err(RetentionError::OutsideRetention {
-                            table_name: table_name.clone(),
-                            min_acceptable_ts: iox_time::Time::from_timestamp_nanos(min_retention),
-                            observed_ts: iox_time::Time::from_timestamp_nanos(min),
-                        });
-                    }
+        let now = self.time_provider.now().timestamp_nanos();
+
+        // batch is a HashMap<tring, MutableBatch>
+        for (table_name, batch) in &batch {
+            // table retention, if it exists, overrides namespace retention
+            let retention_period_ns = namespace_schema
+                .tables
+                .get(table_name)
+                .and_then(|t| t.retention_period_ns)
+                .or(namespace_schema.retention_period_ns);
+
+            // retention is not infinte, validate all lines of a write are within the retention period
+            let Some(retention_period_ns) = retention_period_ns else {
+                continue;
+            };
+            let min_retention = now - retention_period_ns;
+            if let Some(min) = batch.timestamp_summary().and_then(|v| v.stats.min) {
+                if min < min_retention {
+                    return Err(RetentionError::OutsideRetention {
+                        table_name: table_name.clone(),
+                        min_acceptable_ts: iox_time::Time::from_timestamp_nanos(min_retention),
+                        observed_ts: iox_time::Time::from_timestamp_nanos(min),
+                    });
                 }
             }
-        };
+        }
 
         Ok(batch)
     }
@@ -238,6 +249,58 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_table_retention_overrides_namespace_retention() {
+        let namespace = test_setup().await;
+
+        // keep `events` for a day, `bananas` uses the 1 hour namespace retention period
+        namespace.create_table("bananas").await;
+        let events = namespace.create_table("events").await;
+        namespace
+            .catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .update_retention_period(events.table.id, Some(24 * 3_600 * 1_000_000_000))
+            .await
+            .unwrap();
+
+        let mock_now = iox_time::Time::from_rfc3339("2023-05-23T09:59:06+00:00").unwrap();
+        let handler = RetentionValidator {
+            time_provider: MockProvider::new(mock_now),
+        };
+
+        let two_hours_ago = (mock_now.timestamp_nanos() - 2 * 3_600 * 1_000_000_000).to_string();
+        let writes = lp_to_writes(&format!("events,tag1=A val=42i {two_hours_ago}"));
+        handler
+            .write(&NAMESPACE, namespace.schema().await.into(), writes, None)
+            .await
+            .expect("write within the table retention period should succeed");
+
+        let writes = lp_to_writes(&format!("bananas,tag1=A val=42i {two_hours_ago}"));
+        let result = handler
+            .write(&NAMESPACE, namespace.schema().await.into(), writes, None)
+            .await;
+        assert_matches!(result, Err(RetentionError::OutsideRetention { table_name, .. }) => {
+            assert_eq!(table_name, "bananas");
+        });
+
+        let two_days_ago = (mock_now.timestamp_nanos() - 48 * 3_600 * 1_000_000_000).to_string();
+        let writes = lp_to_writes(&format!("events,tag1=A val=42i {two_days_ago}"));
+        let result = handler
+            .write(&NAMESPACE, namespace.schema().await.into(), writes, None)
+            .await;
+        assert_matches!(result, Err(e) => {
+            assert_eq!(
+                e.to_string(),
+                "data in table events is outside of the retention period: minimum \
+                 acceptable timestamp is 2023-05-22T09:59:06+00:00, but observed \
+                 timestamp 2023-05-21T09:59:06+00:00 is older."
+            )
+        });
+    }
+
     // Parse `lp` into a table-keyed MutableBatch map.
     fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
         let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
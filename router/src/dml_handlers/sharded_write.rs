@@ -0,0 +1,230 @@
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::{NamespaceName, NamespaceSchema, TableId};
+use futures::{stream::FuturesUnordered, TryStreamExt};
+use hashbrown::HashMap;
+use mutable_batch::MutableBatch;
+use sharder::Sharder;
+use trace::ctx::SpanContext;
+
+use super::{DmlHandler, Partitioned};
+
+/// A [`ShardedWrite`] splits a partitioned write into per-shard writes
+/// according to the [`Sharder`] `S`, and executes them concurrently against
+/// the [`DmlHandler`] each shard maps to.
+///
+/// This allows the writes of specific tables to be routed to a dedicated set
+/// of ingesters, for example by using a [`TableRegexSharder`].
+///
+/// If writing to a shard produces an error the remaining in-flight writes are
+/// aborted and the error is immediately returned.
+///
+/// [`TableRegexSharder`]: sharder::TableRegexSharder
+#[derive(Debug)]
+pub struct ShardedWrite<S> {
+    sharder: S,
+}
+
+impl<S> ShardedWrite<S> {
+    /// Construct a [`ShardedWrite`] that routes the tables in each write to
+    /// the handler `sharder` maps them to.
+    pub fn new(sharder: S) -> Self {
+        Self { sharder }
+    }
+}
+
+#[async_trait]
+impl<S, H> DmlHandler for ShardedWrite<S>
+where
+    S: Sharder<MutableBatch, Item = Arc<H>>,
+    H: DmlHandler<WriteInput = Partitioned<HashMap<TableId, (String, MutableBatch)>>>,
+{
+    type WriteInput = Partitioned<HashMap<TableId, (String, MutableBatch)>>;
+    type WriteOutput = Vec<H::WriteOutput>;
+    type WriteError = H::WriteError;
+
+    /// Split `input` by shard, and write each subset of tables to the handler
+    /// of its shard, returning the output of each shard written to.
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: Arc<NamespaceSchema>,
+        input: Self::WriteInput,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let (partition_key, batches) = input.into_parts();
+
+        // Group the tables by the handler they are sharded to. There are
+        // typically only a handful of distinct shards, so a linear search is
+        // cheap.
+        let mut shards: Vec<(Arc<H>, HashMap<TableId, (String, MutableBatch)>)> = Vec::new();
+        for (table_id, (table_name, batch)) in batches {
+            let handler = self.sharder.shard(&table_name, namespace, &batch);
+            match shards.iter_mut().find(|(h, _)| Arc::ptr_eq(h, &handler)) {
+                Some((_, tables)) => {
+                    tables.insert(table_id, (table_name, batch));
+                }
+                None => {
+                    let mut tables = HashMap::with_capacity(1);
+                    tables.insert(table_id, (table_name, batch));
+                    shards.push((handler, tables));
+                }
+            }
+        }
+
+        shards
+            .into_iter()
+            .map(|(handler, tables)| {
+                let namespace_schema = Arc::clone(&namespace_schema);
+                let input = Partitioned::new(partition_key.clone(), tables);
+                let span_ctx = span_ctx.clone();
+                async move {
+                    handler
+                        .write(namespace, namespace_schema, input, span_ctx)
+                        .await
+                }
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect::<Vec<_>>()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::{NamespaceId, PartitionKey};
+
+    use super::*;
+    use crate::dml_handlers::{
+        mock::{MockDmlHandler, MockDmlHandlerCall},
+        DmlError,
+    };
+
+    type Handler = MockDmlHandler<Partitioned<HashMap<TableId, (String, MutableBatch)>>>;
+
+    /// A [`Sharder`] mapping each table to a fixed handler.
+    #[derive(Debug)]
+    struct TableSharder(HashMap<&'static str, Arc<Handler>>);
+
+    impl Sharder<MutableBatch> for TableSharder {
+        type Item = Arc<Handler>;
+
+        fn shard(
+            &self,
+            table: &str,
+            _namespace: &NamespaceName<'_>,
+            _payload: &MutableBatch,
+        ) -> Self::Item {
+            Arc::clone(self.0.get(table).expect("unexpected table"))
+        }
+    }
+
+    fn new_empty_namespace_schema() -> Arc<NamespaceSchema> {
+        Arc::new(NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: Default::default(),
+            max_columns_per_table: 500,
+            max_tables: 200,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        })
+    }
+
+    fn lp_to_writes(lp: &str) -> Partitioned<HashMap<TableId, (String, MutableBatch)>> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+
+        let writes = writes
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, data))| (TableId::new(i as _), (name, data)))
+            .collect();
+
+        Partitioned::new(PartitionKey::from("2022-01-01"), writes)
+    }
+
+    /// Return the sorted names of the tables written in `call`.
+    fn written_tables(
+        call: &MockDmlHandlerCall<Partitioned<HashMap<TableId, (String, MutableBatch)>>>,
+    ) -> Vec<String> {
+        let MockDmlHandlerCall::Write { write_input, .. } = call;
+        assert_eq!(write_input.clone().into_parts().0, "2022-01-01".into());
+        let mut tables = write_input
+            .payload()
+            .values()
+            .map(|(name, _)| name.clone())
+            .collect::<Vec<_>>();
+        tables.sort_unstable();
+        tables
+    }
+
+    #[tokio::test]
+    async fn test_write_split_by_shard() {
+        let ns = "platanos".try_into().unwrap();
+        let pinned = Arc::new(Handler::default().with_write_return([Ok(())]));
+        let other = Arc::new(Handler::default().with_write_return([Ok(())]));
+        let unused = Arc::new(Handler::default());
+
+        let handler = ShardedWrite::new(TableSharder(
+            [
+                ("cpu", Arc::clone(&pinned)),
+                ("mem", Arc::clone(&other)),
+                ("disk", Arc::clone(&other)),
+                ("net", Arc::clone(&unused)),
+            ]
+            .into_iter()
+            .collect(),
+        ));
+
+        let output = handler
+            .write(
+                &ns,
+                new_empty_namespace_schema(),
+                lp_to_writes("cpu v=1 1\nmem v=2 1\ndisk v=3 1"),
+                None,
+            )
+            .await
+            .expect("write should succeed");
+
+        // One write is made per shard.
+        assert_eq!(output.len(), 2);
+        assert_matches!(pinned.calls().as_slice(), [call] => {
+            assert_eq!(written_tables(call), ["cpu"]);
+        });
+        assert_matches!(other.calls().as_slice(), [call] => {
+            assert_eq!(written_tables(call), ["disk", "mem"]);
+        });
+        assert!(unused.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_shard_error() {
+        let ns = "platanos".try_into().unwrap();
+        let pinned = Arc::new(
+            Handler::default()
+                .with_write_return([Err(DmlError::NamespaceNotFound("nope".to_owned()))]),
+        );
+        let other = Arc::new(Handler::default().with_write_return([Ok(())]));
+
+        let handler = ShardedWrite::new(TableSharder(
+            [("cpu", Arc::clone(&pinned)), ("mem", Arc::clone(&other))]
+                .into_iter()
+                .collect(),
+        ));
+
+        let err = handler
+            .write(
+                &ns,
+                new_empty_namespace_schema(),
+                lp_to_writes("cpu v=1 1\nmem v=2 1"),
+                None,
+            )
+            .await
+            .expect_err("shard write configured to fail");
+
+        assert_matches!(err, DmlError::NamespaceNotFound(_));
+        assert_eq!(pinned.calls().len(), 1);
+    }
+}
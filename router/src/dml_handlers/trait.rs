@@ -1,5 +1,6 @@
 use super::{
-    partitioner::PartitionError, retention_validation::RetentionError, RpcWriteError, SchemaError,
+    partitioner::PartitionError, retention_validation::RetentionError, RateLimitError,
+    RpcWriteError, SchemaError,
 };
 use async_trait::async_trait;
 use data_types::{NamespaceName, NamespaceSchema};
@@ -32,6 +33,10 @@ pub enum DmlError {
     #[error(transparent)]
     Retention(#[from] RetentionError),
 
+    /// The write exceeds the write rate limits of the namespace.
+    #[error(transparent)]
+    RateLimited(#[from] RateLimitError),
+
     /// An unknown error occured while processing the DML request.
     #[error("internal dml handler error: {0}")]
     Internal(Box<dyn Error + Send + Sync>),
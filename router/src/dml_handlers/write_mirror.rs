@@ -0,0 +1,312 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use data_types::{NamespaceName, NamespaceSchema};
+use metric::U64Counter;
+use observability_deps::tracing::*;
+use trace::ctx::SpanContext;
+
+use super::{DmlError, DmlHandler};
+
+/// Defines how the outcome of a mirrored write affects the outcome of the
+/// write request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorMode {
+    /// The mirrored write is executed in the background and its outcome is
+    /// only logged and counted - the write request succeeds once the primary
+    /// handler succeeds.
+    FireAndForget,
+
+    /// The primary and mirrored write are executed concurrently, and the write
+    /// request only succeeds if both succeed.
+    Quorum,
+}
+
+/// A [`WriteMirror`] passes writes to a primary [`DmlHandler`] and a copy of
+/// each write to an optional secondary handler, such as an RPC writer for a
+/// warm-standby set of ingesters.
+///
+/// If no secondary handler is configured, writes are passed through to the
+/// primary handler unmodified.
+#[derive(Debug)]
+pub struct WriteMirror<P, S> {
+    primary: P,
+    secondary: Option<Arc<S>>,
+    mode: MirrorMode,
+
+    mirror_errors: U64Counter,
+}
+
+impl<P, S> WriteMirror<P, S> {
+    /// Construct a [`WriteMirror`] that writes to `primary`, and mirrors each
+    /// write to `secondary` (if any) according to `mode`.
+    pub fn new(
+        primary: P,
+        secondary: Option<S>,
+        mode: MirrorMode,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let mode_label = match mode {
+            MirrorMode::FireAndForget => "fire_and_forget",
+            MirrorMode::Quorum => "quorum",
+        };
+        let mirror_errors = metrics
+            .register_metric::<U64Counter>(
+                "dml_handler_mirror_write_errors",
+                "number of writes that failed to be mirrored to the secondary handler",
+            )
+            .recorder(&[("mode", mode_label)]);
+
+        Self {
+            primary,
+            secondary: secondary.map(Arc::new),
+            mode,
+            mirror_errors,
+        }
+    }
+}
+
+#[async_trait]
+impl<P, S> DmlHandler for WriteMirror<P, S>
+where
+    P: DmlHandler,
+    P::WriteInput: Clone + 'static,
+    S: DmlHandler<WriteInput = P::WriteInput> + 'static,
+{
+    type WriteInput = P::WriteInput;
+    type WriteOutput = P::WriteOutput;
+
+    // Errors from the primary and secondary handler are converted into DML
+    // errors to present a consistent error type.
+    type WriteError = DmlError;
+
+    /// Write `input` to the primary handler, mirroring it to the secondary
+    /// handler if one is configured.
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: Arc<NamespaceSchema>,
+        input: Self::WriteInput,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let secondary = match &self.secondary {
+            Some(v) => Arc::clone(v),
+            None => {
+                return self
+                    .primary
+                    .write(namespace, namespace_schema, input, span_ctx)
+                    .await
+                    .map_err(Into::into)
+            }
+        };
+
+        let mirror = {
+            let namespace = namespace.clone();
+            let namespace_schema = Arc::clone(&namespace_schema);
+            let input = input.clone();
+            let span_ctx = span_ctx.clone();
+            let mirror_errors = self.mirror_errors.clone();
+            async move {
+                let res: Result<_, DmlError> = secondary
+                    .write(&namespace, namespace_schema, input, span_ctx)
+                    .await
+                    .map_err(Into::into);
+                if let Err(e) = &res {
+                    warn!(error=%e, %namespace, "failed to mirror write to secondary handler");
+                    mirror_errors.inc(1);
+                }
+                res.map(|_| ())
+            }
+        };
+
+        match self.mode {
+            MirrorMode::FireAndForget => {
+                // The outcome of the mirrored write is recorded by the task
+                // itself.
+                tokio::spawn(mirror);
+                self.primary
+                    .write(namespace, namespace_schema, input, span_ctx)
+                    .await
+                    .map_err(Into::into)
+            }
+            MirrorMode::Quorum => {
+                let primary = self
+                    .primary
+                    .write(namespace, namespace_schema, input, span_ctx);
+                let (primary, mirror) = futures::join!(primary, mirror);
+                let output = primary.map_err(Into::<DmlError>::into)?;
+                mirror?;
+                Ok(output)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+    use data_types::NamespaceId;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+    use crate::dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall};
+
+    fn new_empty_namespace_schema() -> Arc<NamespaceSchema> {
+        Arc::new(NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: Default::default(),
+            max_columns_per_table: 500,
+            max_tables: 200,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        })
+    }
+
+    fn mirror_errors(metrics: &metric::Registry, mode: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("dml_handler_mirror_write_errors")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("mode", mode)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    fn not_found() -> DmlError {
+        DmlError::NamespaceNotFound("nope".to_owned())
+    }
+
+    #[tokio::test]
+    async fn test_no_secondary() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = metric::Registry::default();
+        let primary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+
+        let handler = WriteMirror::<_, MockDmlHandler<()>>::new(
+            Arc::clone(&primary),
+            None,
+            MirrorMode::Quorum,
+            &metrics,
+        );
+
+        handler
+            .write(&ns, new_empty_namespace_schema(), (), None)
+            .await
+            .expect("write should succeed");
+
+        assert_matches!(
+            primary.calls().as_slice(),
+            [MockDmlHandlerCall::Write { .. }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_quorum_ok() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = metric::Registry::default();
+        let primary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let secondary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+
+        let handler = WriteMirror::new(
+            Arc::clone(&primary),
+            Some(Arc::clone(&secondary)),
+            MirrorMode::Quorum,
+            &metrics,
+        );
+
+        handler
+            .write(&ns, new_empty_namespace_schema(), (), None)
+            .await
+            .expect("write should succeed");
+
+        assert_matches!(primary.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, .. }] => {
+            assert_eq!(namespace, "platanos");
+        });
+        assert_matches!(secondary.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, .. }] => {
+            assert_eq!(namespace, "platanos");
+        });
+        assert_eq!(mirror_errors(&metrics, "quorum"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_secondary_err() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = metric::Registry::default();
+        let primary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let secondary = Arc::new(MockDmlHandler::default().with_write_return([Err(not_found())]));
+
+        let handler = WriteMirror::new(
+            Arc::clone(&primary),
+            Some(Arc::clone(&secondary)),
+            MirrorMode::Quorum,
+            &metrics,
+        );
+
+        let err = handler
+            .write(&ns, new_empty_namespace_schema(), (), None)
+            .await
+            .expect_err("secondary handler configured to fail");
+
+        assert_matches!(err, DmlError::NamespaceNotFound(_));
+        assert_eq!(primary.calls().len(), 1);
+        assert_eq!(mirror_errors(&metrics, "quorum"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_primary_err() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = metric::Registry::default();
+        let primary = Arc::new(MockDmlHandler::default().with_write_return([Err(not_found())]));
+        let secondary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+
+        let handler = WriteMirror::new(
+            Arc::clone(&primary),
+            Some(Arc::clone(&secondary)),
+            MirrorMode::Quorum,
+            &metrics,
+        );
+
+        let err = handler
+            .write(&ns, new_empty_namespace_schema(), (), None)
+            .await
+            .expect_err("primary handler configured to fail");
+
+        assert_matches!(err, DmlError::NamespaceNotFound(_));
+        assert_eq!(mirror_errors(&metrics, "quorum"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_fire_and_forget_secondary_err() {
+        let ns = "platanos".try_into().unwrap();
+        let metrics = metric::Registry::default();
+        let primary = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let secondary = Arc::new(MockDmlHandler::default().with_write_return([Err(not_found())]));
+
+        let handler = WriteMirror::new(
+            Arc::clone(&primary),
+            Some(Arc::clone(&secondary)),
+            MirrorMode::FireAndForget,
+            &metrics,
+        );
+
+        // The mirrored write failing does not fail the write request.
+        handler
+            .write(&ns, new_empty_namespace_schema(), (), None)
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(primary.calls().len(), 1);
+
+        // The mirrored write is executed in the background.
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while mirror_errors(&metrics, "fire_and_forget") == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("mirrored write failure was not recorded");
+
+        assert_eq!(secondary.calls().len(), 1);
+    }
+}
@@ -11,6 +11,9 @@ pub mod metrics;
 mod read_through_cache;
 pub use read_through_cache::*;
 
+mod refresh;
+pub use refresh::*;
+
 use std::{error::Error, fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
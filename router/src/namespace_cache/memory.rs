@@ -204,6 +204,7 @@ mod tests {
         TableSchema {
             id,
             partition_template: Default::default(),
+            retention_period_ns: None,
             columns: ColumnsByName::new([]),
         }
     }
@@ -415,6 +416,7 @@ mod tests {
             TableSchema {
                 id: TableId::new(id),
                 partition_template: Default::default(),
+                retention_period_ns: None,
                 columns,
             }
         }
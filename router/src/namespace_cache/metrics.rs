@@ -156,6 +156,7 @@ mod tests {
                     TableSchema {
                         id: TableId::new(i as _),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
                         columns: ColumnsByName::new(columns),
                     },
                 )
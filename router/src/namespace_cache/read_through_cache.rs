@@ -1,11 +1,15 @@
 //! Read-through caching behaviour for a [`NamespaceCache`] implementation
 
-use std::{ops::DerefMut, sync::Arc};
+use std::{ops::DerefMut, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use data_types::{NamespaceName, NamespaceSchema};
+use hashbrown::HashMap;
 use iox_catalog::interface::{get_schema_by_name, Catalog, SoftDeletedRows};
+use iox_time::{SystemProvider, Time, TimeProvider};
 use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use service_grpc_namespace::UndeleteObserver;
 
 use super::memory::CacheMissErr;
 use super::{ChangeStats, NamespaceCache};
@@ -21,10 +25,44 @@ use super::{ChangeStats, NamespaceCache};
 /// catalog queries, and `N` [`NamespaceSchema`] instances replacing each other
 /// in the cache before converging on a single instance (last resolved wins).
 /// Subsequent queries will return the currently cached instance.
+///
+/// # Expiry
+///
+/// If configured with [`ReadThroughCache::with_ttl()`], a cached schema that
+/// was resolved from the catalog longer than the TTL ago is refreshed from the
+/// catalog on the next read, bounding the time changes made to the namespace
+/// by other nodes (such as a new retention period or table limit) take to
+/// propagate. Should the refresh fail, the stale schema continues to be
+/// served until the catalog becomes available again.
+///
+/// If configured with [`ReadThroughCache::with_negative_ttl()`], namespaces
+/// the catalog reports as not existing are remembered for the negative TTL,
+/// during which reads for them return the same error without querying the
+/// catalog. A namespace created during this window is not visible until the
+/// negative entry expires, a schema is placed in the cache for it, or the
+/// entry is removed with [`ReadThroughCache::forget_not_found()`] (such as
+/// when the namespace is undeleted).
 #[derive(Debug)]
-pub struct ReadThroughCache<T> {
+pub struct ReadThroughCache<T, P = SystemProvider> {
     inner_cache: T,
     catalog: Arc<dyn Catalog>,
+    time_provider: P,
+
+    /// The maximum age of a cached schema before it is refreshed from the
+    /// catalog, if any.
+    ttl: Option<Duration>,
+    /// The time each cached schema was last resolved from the catalog.
+    ///
+    /// Only populated when `ttl` is set.
+    resolved_at: Mutex<HashMap<NamespaceName<'static>, Time>>,
+
+    /// The duration for which a "namespace not found" catalog response is
+    /// cached, if any.
+    negative_ttl: Option<Duration>,
+    /// The time each namespace was found not to exist in the catalog.
+    ///
+    /// Only populated when `negative_ttl` is set.
+    not_found_at: Mutex<HashMap<NamespaceName<'static>, Time>>,
 }
 
 impl<T> ReadThroughCache<T> {
@@ -34,56 +72,161 @@ impl<T> ReadThroughCache<T> {
         Self {
             inner_cache,
             catalog,
+            time_provider: Default::default(),
+            ttl: None,
+            resolved_at: Default::default(),
+            negative_ttl: None,
+            not_found_at: Default::default(),
         }
     }
 }
 
+impl<T, P> ReadThroughCache<T, P> {
+    /// Refresh cached schemas from the catalog once they were resolved longer
+    /// than `ttl` ago.
+    pub fn with_ttl(self, ttl: Duration) -> Self {
+        Self {
+            ttl: Some(ttl),
+            ..self
+        }
+    }
+
+    /// Cache "namespace not found" catalog responses for `negative_ttl`.
+    pub fn with_negative_ttl(self, negative_ttl: Duration) -> Self {
+        Self {
+            negative_ttl: Some(negative_ttl),
+            ..self
+        }
+    }
+
+    /// Forget that `namespace` was found not to exist in the catalog, if
+    /// cached, so that the next read for it queries the catalog again.
+    pub fn forget_not_found(&self, namespace: &NamespaceName<'static>) {
+        if self.not_found_at.lock().remove(namespace).is_some() {
+            debug!(%namespace, "removed cached namespace not found entry");
+        }
+    }
+
+    /// Use `time_provider` to determine the age of cache entries.
+    pub fn with_time_provider<U>(self, time_provider: U) -> ReadThroughCache<T, U> {
+        ReadThroughCache {
+            inner_cache: self.inner_cache,
+            catalog: self.catalog,
+            time_provider,
+            ttl: self.ttl,
+            resolved_at: self.resolved_at,
+            negative_ttl: self.negative_ttl,
+            not_found_at: self.not_found_at,
+        }
+    }
+}
+
+/// Undeleted namespaces are resolved from the catalog on the next read, rather
+/// than reported as not found until their negative entry expires.
+impl<T, P> UndeleteObserver for ReadThroughCache<T, P>
+where
+    T: std::fmt::Debug + Send + Sync,
+    P: std::fmt::Debug + Send + Sync,
+{
+    fn namespace_undeleted(&self, namespace: &NamespaceName<'static>) {
+        self.forget_not_found(namespace);
+    }
+}
+
+/// Returns true if an entry recorded at `at` is older than `ttl` at `now`.
+fn is_expired(at: Time, ttl: Duration, now: Time) -> bool {
+    now.checked_duration_since(at)
+        .map_or(false, |age| age >= ttl)
+}
+
 #[async_trait]
-impl<T> NamespaceCache for Arc<ReadThroughCache<T>>
+impl<T, P> NamespaceCache for Arc<ReadThroughCache<T, P>>
 where
     T: NamespaceCache<ReadError = CacheMissErr>,
+    P: TimeProvider,
 {
     type ReadError = iox_catalog::interface::Error;
     /// Fetch the schema for `namespace` directly from the inner cache if
-    /// present, pullng from the catalog if not.
+    /// present and not expired, pullng from the catalog if not.
     async fn get_schema(
         &self,
         namespace: &NamespaceName<'static>,
     ) -> Result<Arc<NamespaceSchema>, Self::ReadError> {
-        match self.inner_cache.get_schema(namespace).await {
-            Ok(v) => Ok(v),
+        let now = self.time_provider.now();
+
+        if let Some(negative_ttl) = self.negative_ttl {
+            let not_found_at = self.not_found_at.lock().get(namespace).copied();
+            if not_found_at.map_or(false, |at| !is_expired(at, negative_ttl, now)) {
+                trace!(%namespace, "namespace cached as not found");
+                return Err(iox_catalog::interface::Error::NamespaceNotFoundByName {
+                    name: namespace.to_string(),
+                });
+            }
+        }
+
+        let stale = match self.inner_cache.get_schema(namespace).await {
+            Ok(v) => {
+                let expired = self.ttl.map_or(false, |ttl| {
+                    self.resolved_at
+                        .lock()
+                        .get(namespace)
+                        .map_or(false, |at| is_expired(*at, ttl, now))
+                });
+                if !expired {
+                    return Ok(v);
+                }
+                debug!(%namespace, "cached namespace schema expired");
+                Some(v)
+            }
             Err(CacheMissErr {
                 namespace: cache_ns,
             }) => {
                 // Invariant: the cache should not return misses for a different
                 // namespace name.
                 assert_eq!(cache_ns, *namespace);
-                let mut repos = self.catalog.repositories().await;
-
-                let schema = match get_schema_by_name(
-                    namespace,
-                    repos.deref_mut(),
-                    SoftDeletedRows::ExcludeDeleted,
-                )
-                .await
-                {
-                    Ok(v) => v,
-                    Err(e) => {
-                        warn!(
-                            error = %e,
-                            %namespace,
-                            "failed to retrieve namespace schema"
-                        );
-                        return Err(e);
-                    }
-                };
+                None
+            }
+        };
 
-                let (new_schema, _) = self.put_schema(namespace.clone(), schema);
+        let mut repos = self.catalog.repositories().await;
 
-                trace!(%namespace, "schema cache populated");
-                Ok(new_schema)
+        let schema = match get_schema_by_name(
+            namespace,
+            repos.deref_mut(),
+            SoftDeletedRows::ExcludeDeleted,
+        )
+        .await
+        {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(
+                    error = %e,
+                    %namespace,
+                    "failed to retrieve namespace schema"
+                );
+                match e {
+                    iox_catalog::interface::Error::NamespaceNotFoundByName { .. } => {
+                        self.record_not_found(namespace, now);
+                    }
+                    // Keep serving the stale schema until the catalog can be
+                    // reached again.
+                    _ => {
+                        if let Some(v) = stale {
+                            return Ok(v);
+                        }
+                    }
+                }
+                return Err(e);
             }
+        };
+
+        if self.ttl.is_some() {
+            self.resolved_at.lock().insert(namespace.clone(), now);
         }
+        let (new_schema, _) = self.put_schema(namespace.clone(), schema);
+
+        trace!(%namespace, "schema cache populated");
+        Ok(new_schema)
     }
 
     fn put_schema(
@@ -91,15 +234,48 @@ where
         namespace: NamespaceName<'static>,
         schema: NamespaceSchema,
     ) -> (Arc<NamespaceSchema>, ChangeStats) {
+        if self.negative_ttl.is_some() {
+            self.not_found_at.lock().remove(&namespace);
+        }
+        if self.ttl.is_some() {
+            // Schemas placed in the cache by other means (such as pre-warming)
+            // start to age when first seen. Merging in new columns must not
+            // postpone the refresh from the catalog.
+            let now = self.time_provider.now();
+            self.resolved_at
+                .lock()
+                .entry(namespace.clone())
+                .or_insert(now);
+        }
         self.inner_cache.put_schema(namespace, schema)
     }
 }
 
+impl<T, P> ReadThroughCache<T, P>
+where
+    P: TimeProvider,
+{
+    /// Remember `namespace` as not existing in the catalog at `now`, dropping
+    /// any expired negative entries.
+    ///
+    /// A no-op if negative caching is not enabled.
+    fn record_not_found(&self, namespace: &NamespaceName<'static>, now: Time) {
+        let Some(negative_ttl) = self.negative_ttl else {
+            return;
+        };
+
+        let mut not_found_at = self.not_found_at.lock();
+        not_found_at.retain(|_, at| !is_expired(*at, negative_ttl, now));
+        not_found_at.insert(namespace.clone(), now);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
     use data_types::NamespaceId;
     use iox_catalog::mem::MemCatalog;
+    use iox_time::MockProvider;
 
     use super::*;
     use crate::namespace_cache::memory::MemoryNamespaceCache;
@@ -178,4 +354,182 @@ mod tests {
             assert_eq!(*v, schema1);
         })
     }
+
+    #[tokio::test]
+    async fn test_get_expired_refreshed_from_catalog() {
+        let ns = NamespaceName::try_from("arán").expect("namespace name should be valid");
+
+        let inner = Arc::new(MemoryNamespaceCache::default());
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+
+        let cache = Arc::new(
+            ReadThroughCache::new(inner, Arc::clone(&catalog))
+                .with_ttl(Duration::from_secs(10))
+                .with_time_provider(time.clone()),
+        );
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(&ns, None, None, None)
+            .await
+            .expect("namespace create should succeed");
+
+        // Populate the cache from the catalog.
+        assert_matches!(cache.get_schema(&ns).await, Ok(v) => {
+            assert_eq!(v.max_tables, iox_catalog::DEFAULT_MAX_TABLES as usize);
+        });
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_table_limit(ns.as_str(), 42)
+            .await
+            .expect("table limit update should succeed");
+
+        // The cached schema is served until the TTL elapses.
+        time.inc(Duration::from_secs(9));
+        assert_matches!(cache.get_schema(&ns).await, Ok(v) => {
+            assert_eq!(v.max_tables, iox_catalog::DEFAULT_MAX_TABLES as usize);
+        });
+
+        time.inc(Duration::from_secs(1));
+        assert_matches!(cache.get_schema(&ns).await, Ok(v) => {
+            assert_eq!(v.max_tables, 42);
+        });
+
+        // Merging a schema into the refreshed entry does not extend its life.
+        let schema = (*cache.get_schema(&ns).await.unwrap()).clone();
+        cache.put_schema(ns.clone(), schema);
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_table_limit(ns.as_str(), 24)
+            .await
+            .expect("table limit update should succeed");
+
+        time.inc(Duration::from_secs(10));
+        assert_matches!(cache.get_schema(&ns).await, Ok(v) => {
+            assert_eq!(v.max_tables, 24);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_get_not_found_cached() {
+        let ns = NamespaceName::try_from("arán").expect("namespace name should be valid");
+
+        let inner = Arc::new(MemoryNamespaceCache::default());
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+
+        let cache = Arc::new(
+            ReadThroughCache::new(inner, Arc::clone(&catalog))
+                .with_negative_ttl(Duration::from_secs(1))
+                .with_time_provider(time.clone()),
+        );
+
+        assert_matches!(
+            cache.get_schema(&ns).await,
+            Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. })
+        );
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(&ns, None, None, None)
+            .await
+            .expect("namespace create should succeed");
+
+        // The namespace is reported as not found without consulting the
+        // catalog until the negative TTL elapses.
+        assert_matches!(
+            cache.get_schema(&ns).await,
+            Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. })
+        );
+
+        time.inc(Duration::from_secs(1));
+        assert_matches!(cache.get_schema(&ns).await, Ok(_));
+    }
+
+    #[tokio::test]
+    async fn test_put_clears_not_found() {
+        let ns = NamespaceName::try_from("arán").expect("namespace name should be valid");
+
+        let inner = Arc::new(MemoryNamespaceCache::default());
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+
+        let cache = Arc::new(
+            ReadThroughCache::new(inner, catalog)
+                .with_negative_ttl(Duration::from_secs(60))
+                .with_time_provider(time),
+        );
+
+        assert_matches!(cache.get_schema(&ns).await, Err(_));
+
+        let schema1 = NamespaceSchema {
+            id: NamespaceId::new(1),
+            tables: Default::default(),
+            max_columns_per_table: iox_catalog::DEFAULT_MAX_COLUMNS_PER_TABLE as usize,
+            max_tables: iox_catalog::DEFAULT_MAX_TABLES as usize,
+            retention_period_ns: iox_catalog::DEFAULT_RETENTION_PERIOD,
+            partition_template: Default::default(),
+        };
+        cache.put_schema(ns.clone(), schema1.clone());
+
+        assert_matches!(cache.get_schema(&ns).await, Ok(v) => {
+            assert_eq!(*v, schema1);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_forget_not_found() {
+        let ns = NamespaceName::try_from("arán").expect("namespace name should be valid");
+
+        let inner = Arc::new(MemoryNamespaceCache::default());
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let time = MockProvider::new(Time::from_timestamp_nanos(0));
+
+        let cache = Arc::new(
+            ReadThroughCache::new(inner, Arc::clone(&catalog))
+                .with_negative_ttl(Duration::from_secs(60))
+                .with_time_provider(time),
+        );
+
+        assert_matches!(
+            cache.get_schema(&ns).await,
+            Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. })
+        );
+
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(&ns, None, None, None)
+            .await
+            .expect("namespace create should succeed");
+        assert_matches!(
+            cache.get_schema(&ns).await,
+            Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. })
+        );
+
+        // Once forgotten, the namespace is resolved from the catalog well
+        // before the negative TTL elapses.
+        cache.forget_not_found(&ns);
+        assert_matches!(cache.get_schema(&ns).await, Ok(_));
+
+        // Forgetting a namespace that is not cached as not found is a no-op.
+        cache.forget_not_found(&ns);
+        assert_matches!(cache.get_schema(&ns).await, Ok(_));
+    }
 }
@@ -0,0 +1,181 @@
+//! Periodic refreshing of a [`NamespaceCache`] from the catalog.
+
+use std::{
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use data_types::NamespaceName;
+use iox_catalog::interface::{list_schemas, Catalog};
+use observability_deps::tracing::*;
+use tokio::{task::JoinHandle, time::MissedTickBehavior};
+
+use super::NamespaceCache;
+
+/// Spawn a background task that loads the schemas of all namespaces from
+/// `catalog` every `interval`, and merges them into `cache`.
+///
+/// This allows a router to converge on the tables and columns added by other
+/// routers (and on changes to the namespace limits) without waiting for a
+/// write to miss its cached schema. Namespaces not yet in `cache` are added
+/// to it.
+///
+/// The task only holds a weak reference to `cache`, and exits once the cache
+/// is dropped.
+pub fn spawn_refresh_task<T>(
+    cache: &Arc<T>,
+    catalog: Arc<dyn Catalog>,
+    interval: Duration,
+) -> JoinHandle<()>
+where
+    T: Send + Sync + 'static,
+    Arc<T>: NamespaceCache,
+{
+    let cache = Arc::downgrade(cache);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick completes immediately, and the cache is typically
+        // pre-warmed when created.
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if !refresh(&cache, &*catalog).await {
+                debug!("namespace cache dropped, stopping refresh task");
+                return;
+            }
+        }
+    })
+}
+
+/// Merge the schemas of all namespaces in `catalog` into `cache`.
+///
+/// Returns false if `cache` has been dropped.
+async fn refresh<T>(cache: &Weak<T>, catalog: &dyn Catalog) -> bool
+where
+    Arc<T>: NamespaceCache,
+{
+    if cache.strong_count() == 0 {
+        return false;
+    }
+
+    let schemas = match list_schemas(catalog).await {
+        Ok(v) => v,
+        Err(e) => {
+            warn!(error=%e, "failed to refresh namespace cache");
+            return true;
+        }
+    };
+
+    let Some(cache) = cache.upgrade() else {
+        return false;
+    };
+
+    let (mut new_tables, mut new_columns) = (0, 0);
+    for (ns, schema) in schemas {
+        let name = match NamespaceName::try_from(ns.name) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "invalid namespace name in catalog");
+                continue;
+            }
+        };
+
+        let (_, stats) = cache.put_schema(name, schema);
+        new_tables += stats.new_tables;
+        new_columns += stats.new_columns;
+    }
+
+    debug!(new_tables, new_columns, "refreshed namespace cache");
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::ColumnType;
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use test_helpers::timeout::FutureTimeout;
+
+    use super::*;
+    use crate::namespace_cache::MemoryNamespaceCache;
+
+    #[tokio::test]
+    async fn test_refresh_merges_catalog_schema() {
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        let cache = Arc::new(MemoryNamespaceCache::default());
+
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "bananas").await;
+        let table = arbitrary_table(&mut *repos, "platanos", &namespace).await;
+        repos
+            .columns()
+            .create_or_get("v", table.id, ColumnType::F64)
+            .await
+            .unwrap();
+        drop(repos);
+
+        let ns = NamespaceName::try_from("bananas").unwrap();
+        assert!(cache.get_schema(&ns).await.is_err());
+
+        assert!(refresh(&Arc::downgrade(&cache), &*catalog).await);
+
+        let schema = cache
+            .get_schema(&ns)
+            .await
+            .expect("namespace must be cached");
+        assert_eq!(schema.tables["platanos"].columns.column_count(), 1);
+
+        // Columns added by other routers are merged into the cached schema.
+        catalog
+            .repositories()
+            .await
+            .columns()
+            .create_or_get("time", table.id, ColumnType::Time)
+            .await
+            .unwrap();
+
+        assert!(refresh(&Arc::downgrade(&cache), &*catalog).await);
+
+        let schema = cache
+            .get_schema(&ns)
+            .await
+            .expect("namespace must be cached");
+        assert_eq!(schema.tables["platanos"].columns.column_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_task_exits_when_cache_dropped() {
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        let cache = Arc::new(MemoryNamespaceCache::default());
+
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "bananas").await;
+        let table = arbitrary_table(&mut *repos, "platanos", &namespace).await;
+        repos
+            .columns()
+            .create_or_get("v", table.id, ColumnType::F64)
+            .await
+            .unwrap();
+        drop(repos);
+
+        let task = spawn_refresh_task(&cache, Arc::clone(&catalog), Duration::from_millis(10));
+
+        // The namespace is eventually loaded by the background task.
+        let ns = NamespaceName::try_from("bananas").unwrap();
+        async {
+            while cache.get_schema(&ns).await.is_err() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        drop(cache);
+        task.with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("refresh task must not panic");
+    }
+}
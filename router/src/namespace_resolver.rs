@@ -11,6 +11,8 @@ use crate::namespace_cache::NamespaceCache;
 pub mod mock;
 pub(crate) mod ns_autocreation;
 pub use ns_autocreation::*;
+mod soft_delete;
+pub use soft_delete::*;
 
 /// Error states encountered during [`NamespaceSchema`] lookup.
 #[derive(Debug, Error)]
@@ -22,6 +24,10 @@ pub enum Error {
     /// An error state for errors returned by [`NamespaceAutocreation`].
     #[error(transparent)]
     Create(#[from] NamespaceCreationError),
+
+    /// The namespace exists, but has been soft-deleted.
+    #[error("namespace {0} has been deleted")]
+    SoftDeleted(String),
 }
 
 /// An abstract resolver of [`NamespaceName`] to [`NamespaceSchema`].
@@ -34,6 +40,19 @@ pub trait NamespaceResolver: std::fmt::Debug + Send + Sync {
     ) -> Result<Arc<NamespaceSchema>, Error>;
 }
 
+#[async_trait]
+impl<T> NamespaceResolver for Arc<T>
+where
+    T: NamespaceResolver,
+{
+    async fn get_namespace_schema(
+        &self,
+        namespace: &NamespaceName<'static>,
+    ) -> Result<Arc<NamespaceSchema>, Error> {
+        (**self).get_namespace_schema(namespace).await
+    }
+}
+
 /// An implementation of [`NamespaceResolver`] that resolves the [`NamespaceSchema`]
 /// for a given name through a [`NamespaceCache`].
 #[derive(Debug)]
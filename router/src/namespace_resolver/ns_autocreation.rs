@@ -248,6 +248,9 @@ mod tests {
                 retention_period_ns: TEST_RETENTION_PERIOD_NS,
                 deleted_at: None,
                 partition_template: Default::default(),
+                compaction_paused: false,
+                max_write_lines_per_second: None,
+                max_write_bytes_per_second: None,
             }
         );
     }
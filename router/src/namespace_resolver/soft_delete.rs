@@ -0,0 +1,177 @@
+//! A [`NamespaceResolver`] decorator distinguishing soft-deleted namespaces
+//! from those that never existed.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use data_types::{NamespaceName, NamespaceSchema};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::*;
+
+use super::{Error, NamespaceCreationError, NamespaceResolver};
+
+/// A [`NamespaceResolver`] decorator that converts the failure of the inner
+/// resolver to find a namespace into an [`Error::SoftDeleted`] if the
+/// namespace exists in the [`Catalog`], but is marked as soft-deleted.
+///
+/// The catalog is only queried when the inner resolver reports the namespace
+/// as not existing, leaving successful resolutions unaffected. This decorator
+/// keeps no state of its own, but when the namespace is undeleted, a negative
+/// entry cached for it by a [`ReadThroughCache`] must be removed with
+/// [`ReadThroughCache::forget_not_found()`] for writes to be accepted before
+/// the entry expires.
+///
+/// [`ReadThroughCache`]: crate::namespace_cache::ReadThroughCache
+/// [`ReadThroughCache::forget_not_found()`]: crate::namespace_cache::ReadThroughCache::forget_not_found
+#[derive(Debug)]
+pub struct SoftDeleteCheck<T> {
+    inner: T,
+    catalog: Arc<dyn Catalog>,
+}
+
+impl<T> SoftDeleteCheck<T> {
+    /// Decorate `inner`, checking `catalog` for soft-deleted namespaces when
+    /// `inner` fails to find one.
+    pub fn new(inner: T, catalog: Arc<dyn Catalog>) -> Self {
+        Self { inner, catalog }
+    }
+}
+
+#[async_trait]
+impl<T> NamespaceResolver for SoftDeleteCheck<T>
+where
+    T: NamespaceResolver,
+{
+    async fn get_namespace_schema(
+        &self,
+        namespace: &NamespaceName<'static>,
+    ) -> Result<Arc<NamespaceSchema>, Error> {
+        let err = match self.inner.get_namespace_schema(namespace).await {
+            Ok(v) => return Ok(v),
+            Err(
+                e @ (Error::Lookup(iox_catalog::interface::Error::NamespaceNotFoundByName {
+                    ..
+                })
+                | Error::Create(NamespaceCreationError::Reject(_))),
+            ) => e,
+            Err(e) => return Err(e),
+        };
+
+        match self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_name(namespace.as_str(), SoftDeletedRows::OnlyDeleted)
+            .await
+        {
+            Ok(Some(_)) => {
+                debug!(%namespace, "rejecting write to soft-deleted namespace");
+                Err(Error::SoftDeleted(namespace.to_string()))
+            }
+            Ok(None) => Err(err),
+            Err(e) => {
+                warn!(error=%e, %namespace, "failed to check for soft-deleted namespace");
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use iox_catalog::mem::MemCatalog;
+
+    use super::*;
+    use crate::{
+        namespace_cache::{MemoryNamespaceCache, ReadThroughCache},
+        namespace_resolver::{
+            MissingNamespaceAction, NamespaceAutocreation, NamespaceSchemaResolver,
+        },
+    };
+
+    fn new_resolver(
+        catalog: &Arc<dyn Catalog>,
+        action: MissingNamespaceAction,
+    ) -> impl NamespaceResolver {
+        let cache = Arc::new(ReadThroughCache::new(
+            Arc::new(MemoryNamespaceCache::default()),
+            Arc::clone(catalog),
+        ));
+        let resolver = NamespaceAutocreation::new(
+            NamespaceSchemaResolver::new(Arc::clone(&cache)),
+            cache,
+            Arc::clone(catalog),
+            action,
+        );
+        SoftDeleteCheck::new(resolver, Arc::clone(catalog))
+    }
+
+    async fn create_namespace(catalog: &Arc<dyn Catalog>, ns: &NamespaceName<'static>) {
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(ns, None, None, None)
+            .await
+            .expect("failed to setup catalog state");
+    }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let ns = NamespaceName::try_from("bananas").unwrap();
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        create_namespace(&catalog, &ns).await;
+
+        let resolver = new_resolver(&catalog, MissingNamespaceAction::Reject);
+
+        resolver
+            .get_namespace_schema(&ns)
+            .await
+            .expect("lookup should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_does_not_exist() {
+        let ns = NamespaceName::try_from("bananas").unwrap();
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+
+        let resolver = new_resolver(&catalog, MissingNamespaceAction::Reject);
+
+        let err = resolver
+            .get_namespace_schema(&ns)
+            .await
+            .expect_err("lookup should fail");
+        assert_matches!(err, Error::Create(NamespaceCreationError::Reject(_)));
+    }
+
+    #[tokio::test]
+    async fn test_soft_deleted() {
+        let ns = NamespaceName::try_from("bananas").unwrap();
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        create_namespace(&catalog, &ns).await;
+        catalog
+            .repositories()
+            .await
+            .namespaces()
+            .soft_delete(&ns)
+            .await
+            .expect("failed to setup catalog state");
+
+        for action in [
+            MissingNamespaceAction::Reject,
+            MissingNamespaceAction::AutoCreate(None),
+        ] {
+            let resolver = new_resolver(&catalog, action);
+
+            let err = resolver
+                .get_namespace_schema(&ns)
+                .await
+                .expect_err("lookup should fail");
+            assert_matches!(err, Error::SoftDeleted(name) => {
+                assert_eq!(name, "bananas");
+            });
+        }
+    }
+}
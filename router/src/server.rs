@@ -15,7 +15,7 @@ pub struct RpcWriteRouterServer<D, N> {
     trace_collector: Option<Arc<dyn TraceCollector>>,
 
     http: HttpDelegate<D, N>,
-    grpc: RpcWriteGrpcDelegate,
+    grpc: RpcWriteGrpcDelegate<D, N>,
 }
 
 impl<D, N> RpcWriteRouterServer<D, N> {
@@ -23,7 +23,7 @@ impl<D, N> RpcWriteRouterServer<D, N> {
     /// handlers.
     pub fn new(
         http: HttpDelegate<D, N>,
-        grpc: RpcWriteGrpcDelegate,
+        grpc: RpcWriteGrpcDelegate<D, N>,
         metrics: Arc<metric::Registry>,
         trace_collector: Option<Arc<dyn TraceCollector>>,
     ) -> Self {
@@ -51,7 +51,7 @@ impl<D, N> RpcWriteRouterServer<D, N> {
     }
 
     /// Get a reference to the router grpc delegate.
-    pub fn grpc(&self) -> &RpcWriteGrpcDelegate {
+    pub fn grpc(&self) -> &RpcWriteGrpcDelegate<D, N> {
         &self.grpc
     }
 }
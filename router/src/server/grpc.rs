@@ -1,33 +1,133 @@
 //! gRPC service implementations for `router`.
 
+pub mod otlp;
+pub mod write;
+
+use audit::AuditLog;
+use authz::{extract_token, Action, Authorizer, Permission, Resource};
+use data_types::NamespaceName;
 use generated_types::influxdata::iox::{
     catalog::v1::*, namespace::v1::*, object_store::v1::*, table::v1::*,
 };
 use iox_catalog::interface::Catalog;
 use object_store::DynObjectStore;
 use service_grpc_catalog::CatalogService;
-use service_grpc_namespace::NamespaceService;
+use service_grpc_namespace::{NamespaceService, UndeleteObserver};
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
 use service_grpc_table::TableService;
 use std::sync::Arc;
+use tonic::{metadata::MetadataMap, Code};
+
+use self::{
+    otlp::{OtlpMetricsService, TableNaming},
+    write::RpcWriteService,
+};
 
 /// This type manages all gRPC services exposed by a `router` using the RPC write path.
 #[derive(Debug)]
-pub struct RpcWriteGrpcDelegate {
+pub struct RpcWriteGrpcDelegate<D, N> {
     catalog: Arc<dyn Catalog>,
     object_store: Arc<DynObjectStore>,
+    dml_handler: D,
+    namespace_resolver: N,
+    otlp_table_naming: TableNaming,
+    authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
+    undelete_observer: Option<Arc<dyn UndeleteObserver>>,
 }
 
-impl RpcWriteGrpcDelegate {
-    /// Create a new gRPC handler
-    pub fn new(catalog: Arc<dyn Catalog>, object_store: Arc<DynObjectStore>) -> Self {
+impl<D, N> RpcWriteGrpcDelegate<D, N> {
+    /// Create a new gRPC handler, writing to `dml_handler` the writes to the
+    /// namespaces resolved by `namespace_resolver`.
+    pub fn new(
+        catalog: Arc<dyn Catalog>,
+        object_store: Arc<DynObjectStore>,
+        dml_handler: D,
+        namespace_resolver: N,
+    ) -> Self {
         Self {
             catalog,
             object_store,
+            dml_handler,
+            namespace_resolver,
+            otlp_table_naming: TableNaming::default(),
+            authz: None,
+            audit_log: None,
+            undelete_observer: None,
         }
     }
 
+    /// Name the tables written by the OTLP metrics service according to
+    /// `table_naming`, instead of the default [`TableNaming::PerMetric`]
+    /// without a prefix.
+    pub fn with_otlp_table_naming(self, table_naming: TableNaming) -> Self {
+        Self {
+            otlp_table_naming: table_naming,
+            ..self
+        }
+    }
+
+    /// Authorise the requests of the write services with `authz`, requiring
+    /// the token of a request to grant write access to the namespace it
+    /// writes to.
+    pub fn with_authz(self, authz: Arc<dyn Authorizer>) -> Self {
+        Self {
+            authz: Some(authz),
+            ..self
+        }
+    }
+
+    /// Record the requests of the write services in `audit_log`.
+    pub fn with_audit_log(self, audit_log: AuditLog) -> Self {
+        Self {
+            audit_log: Some(audit_log),
+            ..self
+        }
+    }
+
+    /// Notify `observer` of the namespaces undeleted through the namespace
+    /// service, such as the namespace cache, which must forget that they did
+    /// not exist.
+    pub fn with_undelete_observer(self, observer: Arc<dyn UndeleteObserver>) -> Self {
+        Self {
+            undelete_observer: Some(observer),
+            ..self
+        }
+    }
+
+    /// Acquire a [`RpcWriteService`] gRPC service implementation.
+    ///
+    /// The service shares the DML handler and namespace resolver of this
+    /// delegate, which are therefore typically wrapped in an [`Arc`].
+    pub fn write_service(&self) -> RpcWriteService<D, N>
+    where
+        D: Clone,
+        N: Clone,
+    {
+        RpcWriteService::new(self.dml_handler.clone(), self.namespace_resolver.clone())
+            .with_authz(self.authz.clone())
+            .with_audit_log(self.audit_log.clone())
+    }
+
+    /// Acquire a [`OtlpMetricsService`] gRPC service implementation.
+    ///
+    /// Like the [`RpcWriteService`], the service shares the DML handler and
+    /// namespace resolver of this delegate.
+    pub fn otlp_metrics_service(&self) -> OtlpMetricsService<D, N>
+    where
+        D: Clone,
+        N: Clone,
+    {
+        OtlpMetricsService::new(
+            self.dml_handler.clone(),
+            self.namespace_resolver.clone(),
+            self.otlp_table_naming.clone(),
+        )
+        .with_authz(self.authz.clone())
+        .with_audit_log(self.audit_log.clone())
+    }
+
     /// Acquire a [`SchemaService`] gRPC service implementation.
     ///
     /// [`SchemaService`]: generated_types::influxdata::iox::schema::v1::schema_service_server::SchemaService.
@@ -53,7 +153,11 @@ impl RpcWriteGrpcDelegate {
     ///
     /// [`NamespaceService`]: generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService.
     pub fn namespace_service(&self) -> impl namespace_service_server::NamespaceService {
-        NamespaceService::new(Arc::clone(&self.catalog))
+        let service = NamespaceService::new(Arc::clone(&self.catalog));
+        match &self.undelete_observer {
+            Some(observer) => service.with_undelete_observer(Arc::clone(observer)),
+            None => service,
+        }
     }
 
     /// Acquire a [`TableService`] gRPC service implementation.
@@ -63,3 +167,34 @@ impl RpcWriteGrpcDelegate {
         TableService::new(Arc::clone(&self.catalog))
     }
 }
+
+/// Retrieve the authorization token of a gRPC request, if any.
+fn request_token(metadata: &MetadataMap) -> Option<Vec<u8>> {
+    extract_token(metadata.get("authorization"))
+}
+
+/// Check that `token` grants write access to `namespace`.
+///
+/// Any token is accepted if no [`Authorizer`] is configured.
+async fn authorize_write(
+    authz: &Option<Arc<dyn Authorizer>>,
+    token: Option<Vec<u8>>,
+    namespace: &NamespaceName<'_>,
+) -> Result<(), authz::Error> {
+    let perms = [Permission::ResourceAction(
+        Resource::Database(namespace.to_string()),
+        Action::Write,
+    )];
+    authz.permissions(token, &perms).await?;
+    Ok(())
+}
+
+/// Map an authorization failure to the gRPC status [`Code`] returned to the
+/// end user, mirroring the querier's Flight service.
+fn authz_error_code(e: &authz::Error) -> Code {
+    match e {
+        authz::Error::Forbidden | authz::Error::InvalidToken => Code::PermissionDenied,
+        authz::Error::NoToken => Code::Unauthenticated,
+        authz::Error::Verification { .. } => Code::Internal,
+    }
+}
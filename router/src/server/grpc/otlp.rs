@@ -0,0 +1,727 @@
+//! An OpenTelemetry protocol (OTLP) metrics ingest API for the router.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use audit::{AuditLog, AuditRecord, Operation};
+use authz::Authorizer;
+use data_types::{NamespaceName, NamespaceNameError};
+use generated_types::opentelemetry::proto::{
+    collector::metrics::v1::{
+        metrics_service_server::MetricsService, ExportMetricsPartialSuccess,
+        ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+    },
+    common::v1::{any_value, KeyValue},
+    metrics::v1::{metric::Data, number_data_point, Metric, NumberDataPoint},
+};
+use hashbrown::HashMap;
+use mutable_batch::{writer::Writer, MutableBatch};
+use observability_deps::tracing::*;
+use prost::Message;
+use thiserror::Error;
+use tonic::{metadata::MetadataMap, Code, Request, Response, Status};
+use trace::ctx::SpanContext;
+
+use super::{authorize_write, authz_error_code, request_token, write::dml_error_code};
+use crate::{
+    dml_handlers::{DmlError, DmlHandler},
+    namespace_resolver::{self, NamespaceCreationError, NamespaceResolver},
+};
+
+/// The gRPC metadata key holding the name of the namespace the metrics of a
+/// request are written to.
+pub const NAMESPACE_METADATA_KEY: &str = "iox-namespace-name";
+
+/// The tag the metric name is written to when all metrics are written to a
+/// single table.
+const METRIC_NAME_TAG: &str = "metric_name";
+
+/// The column the data point timestamps are written to.
+const TIME_COLUMN: &str = "time";
+
+/// The names of the field columns data points are written to, which therefore
+/// cannot be used as attribute names.
+const FIELD_COLUMNS: &[&str] = &["value", "count", "sum", "min", "max"];
+
+/// How the metrics of an OTLP request are mapped to tables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableNaming {
+    /// Write each metric to a table named after the metric, prefixed with the
+    /// given string.
+    PerMetric {
+        /// The prefix of all table names, which may be empty.
+        prefix: String,
+    },
+    /// Write all metrics to the given table, with the metric name as the
+    /// `metric_name` tag.
+    Single(String),
+}
+
+impl Default for TableNaming {
+    fn default() -> Self {
+        Self::PerMetric {
+            prefix: String::new(),
+        }
+    }
+}
+
+impl TableNaming {
+    fn table_name(&self, metric: &str) -> String {
+        match self {
+            Self::PerMetric { prefix } => format!("{prefix}{metric}"),
+            Self::Single(table) => table.clone(),
+        }
+    }
+}
+
+/// A list of error states when handling an OTLP export request.
+#[derive(Debug, Error)]
+enum OtlpError {
+    /// The request does not specify a namespace.
+    #[error("request does not specify a namespace in the {NAMESPACE_METADATA_KEY} metadata")]
+    NoNamespace,
+
+    /// The namespace name in the request is invalid.
+    #[error(transparent)]
+    InvalidNamespace(#[from] NamespaceNameError),
+
+    /// The request is not authorised to write to the namespace.
+    #[error("authorization failed: {0}")]
+    Authz(authz::Error),
+
+    /// An error resolving the namespace of the request.
+    #[error(transparent)]
+    NamespaceResolver(#[from] namespace_resolver::Error),
+
+    /// An error returned from the [`DmlHandler`].
+    #[error("dml handler error: {0}")]
+    DmlHandler(#[from] DmlError),
+}
+
+impl From<OtlpError> for Status {
+    fn from(e: OtlpError) -> Self {
+        let code = match &e {
+            OtlpError::NoNamespace | OtlpError::InvalidNamespace(_) => Code::InvalidArgument,
+            OtlpError::Authz(e) => authz_error_code(e),
+            OtlpError::NamespaceResolver(
+                namespace_resolver::Error::Create(NamespaceCreationError::Reject(_))
+                | namespace_resolver::Error::SoftDeleted(_),
+            ) => Code::NotFound,
+            OtlpError::NamespaceResolver(_) => Code::Internal,
+            OtlpError::DmlHandler(e) => dml_error_code(e),
+        };
+
+        Self::new(code, e.to_string())
+    }
+}
+
+/// A single data point, converted into the fields of a row.
+struct Row<'a> {
+    attributes: &'a [KeyValue],
+    time_unix_nano: u64,
+    fields: Vec<(&'static str, FieldValue)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum FieldValue {
+    F64(f64),
+    U64(u64),
+}
+
+/// The result of converting the metrics of an OTLP request.
+#[derive(Debug, Default)]
+struct Converted {
+    batches: HashMap<String, MutableBatch>,
+    num_points: usize,
+    rejected_points: usize,
+    first_error: Option<String>,
+}
+
+/// Convert the metrics of `request` into a set of [`MutableBatch`] keyed by
+/// table name, according to `naming`.
+///
+/// Each data point is written as a row tagged with the attributes of its
+/// resource and its own attributes (with the latter taking precedence), and
+/// timestamped with the time of the data point:
+///
+/// * gauge and sum data points are written to the `value` float field.
+/// * histogram and summary data points are written to the `count`, `sum`, and
+///   (if present) `min` and `max` fields. Buckets and quantiles are not
+///   written.
+///
+/// Only string, boolean, integer and float attribute values are written.
+/// Data points that cannot be written (for example because an attribute uses
+/// a reserved column name) are rejected individually.
+fn convert(request: ExportMetricsServiceRequest, naming: &TableNaming) -> Converted {
+    let mut converted = Converted::default();
+
+    for resource_metrics in &request.resource_metrics {
+        let resource_attributes = resource_metrics
+            .resource
+            .as_ref()
+            .map(|r| r.attributes.as_slice())
+            .unwrap_or_default();
+
+        for metric in resource_metrics
+            .scope_metrics
+            .iter()
+            .flat_map(|s| s.metrics.iter())
+        {
+            let table = naming.table_name(&metric.name);
+            for row in rows(metric) {
+                converted.num_points += 1;
+
+                let batch = converted.batches.entry_ref(table.as_str()).or_default();
+                if let Err(e) = write_row(batch, naming, metric, resource_attributes, row) {
+                    converted.rejected_points += 1;
+                    converted
+                        .first_error
+                        .get_or_insert_with(|| format!("metric {}: {e}", metric.name));
+                }
+            }
+        }
+    }
+
+    // Tables without any rows have nothing to write.
+    converted.batches.retain(|_, batch| batch.rows() > 0);
+    converted
+}
+
+/// Return the data points of `metric` as rows, or no rows for unsupported
+/// metric types.
+fn rows(metric: &Metric) -> Vec<Row<'_>> {
+    match &metric.data {
+        Some(Data::Gauge(v)) => number_rows(&v.data_points),
+        Some(Data::Sum(v)) => number_rows(&v.data_points),
+        Some(Data::Histogram(v)) => v
+            .data_points
+            .iter()
+            .map(|p| {
+                let mut fields = vec![("count", FieldValue::U64(p.count))];
+                fields.extend(p.sum.map(|v| ("sum", FieldValue::F64(v))));
+                fields.extend(p.min.map(|v| ("min", FieldValue::F64(v))));
+                fields.extend(p.max.map(|v| ("max", FieldValue::F64(v))));
+                Row {
+                    attributes: &p.attributes,
+                    time_unix_nano: p.time_unix_nano,
+                    fields,
+                }
+            })
+            .collect(),
+        Some(Data::Summary(v)) => v
+            .data_points
+            .iter()
+            .map(|p| Row {
+                attributes: &p.attributes,
+                time_unix_nano: p.time_unix_nano,
+                fields: vec![
+                    ("count", FieldValue::U64(p.count)),
+                    ("sum", FieldValue::F64(p.sum)),
+                ],
+            })
+            .collect(),
+        None => {
+            debug!(metric=%metric.name, "skipping metric of unsupported type");
+            vec![]
+        }
+    }
+}
+
+fn number_rows(points: &[NumberDataPoint]) -> Vec<Row<'_>> {
+    points
+        .iter()
+        .filter_map(|p| {
+            let value = match p.value.as_ref()? {
+                number_data_point::Value::AsDouble(v) => *v,
+                number_data_point::Value::AsInt(v) => *v as f64,
+            };
+            Some(Row {
+                attributes: &p.attributes,
+                time_unix_nano: p.time_unix_nano,
+                fields: vec![("value", FieldValue::F64(value))],
+            })
+        })
+        .collect()
+}
+
+/// Render an attribute value as a tag value, if it has a scalar value.
+fn tag_value(kv: &KeyValue) -> Option<String> {
+    match kv.value.as_ref()?.value.as_ref()? {
+        any_value::Value::StringValue(v) => Some(v.clone()),
+        any_value::Value::BoolValue(v) => Some(v.to_string()),
+        any_value::Value::IntValue(v) => Some(v.to_string()),
+        any_value::Value::DoubleValue(v) => Some(v.to_string()),
+        any_value::Value::ArrayValue(_)
+        | any_value::Value::KvlistValue(_)
+        | any_value::Value::BytesValue(_) => None,
+    }
+}
+
+/// Append `row` to `batch`, leaving `batch` unchanged if it cannot be
+/// written.
+fn write_row(
+    batch: &mut MutableBatch,
+    naming: &TableNaming,
+    metric: &Metric,
+    resource_attributes: &[KeyValue],
+    row: Row<'_>,
+) -> Result<(), String> {
+    let time = i64::try_from(row.time_unix_nano)
+        .map_err(|_| format!("timestamp {} overflows i64", row.time_unix_nano))?;
+
+    // Data point attributes take precedence over resource attributes.
+    let mut tags = BTreeMap::new();
+    for kv in resource_attributes.iter().chain(row.attributes) {
+        if let Some(v) = tag_value(kv) {
+            tags.insert(kv.key.as_str(), v);
+        }
+    }
+    if let TableNaming::Single(_) = naming {
+        if tags.insert(METRIC_NAME_TAG, metric.name.clone()).is_some() {
+            return Err(format!("attribute {METRIC_NAME_TAG} is reserved"));
+        }
+    }
+
+    let mut writer = Writer::new(batch, 1);
+    for (key, value) in &tags {
+        if *key == TIME_COLUMN || FIELD_COLUMNS.contains(key) {
+            return Err(format!("attribute {key} is reserved"));
+        }
+        writer
+            .write_tag(key, None, std::iter::once(value.as_str()))
+            .map_err(|e| e.to_string())?;
+    }
+    for (name, value) in row.fields {
+        match value {
+            FieldValue::F64(v) => writer.write_f64(name, None, std::iter::once(v)),
+            FieldValue::U64(v) => writer.write_u64(name, None, std::iter::once(v)),
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    writer
+        .write_time(TIME_COLUMN, std::iter::once(time))
+        .map_err(|e| e.to_string())?;
+
+    writer.commit();
+    Ok(())
+}
+
+/// Read the namespace name from the [`NAMESPACE_METADATA_KEY`] of `metadata`.
+fn namespace_name(metadata: &MetadataMap) -> Result<NamespaceName<'static>, OtlpError> {
+    let name = metadata
+        .get(NAMESPACE_METADATA_KEY)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.is_empty())
+        .ok_or(OtlpError::NoNamespace)?;
+
+    Ok(NamespaceName::try_from(name.to_string())?)
+}
+
+/// A gRPC OTLP [`MetricsService`] handler.
+///
+/// This handler converts the exported metrics into tables according to its
+/// [`TableNaming`], and passes them through the same [`NamespaceResolver`] and
+/// [`DmlHandler`] stack as the HTTP write API. The namespace is read from the
+/// [`NAMESPACE_METADATA_KEY`] metadata of each request.
+///
+/// Data points that cannot be converted are rejected individually, and
+/// reported as a partial success.
+#[derive(Debug, Clone)]
+pub struct OtlpMetricsService<D, N> {
+    dml_handler: D,
+    namespace_resolver: N,
+    table_naming: TableNaming,
+    authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
+}
+
+impl<D, N> OtlpMetricsService<D, N> {
+    /// Initialise a new [`OtlpMetricsService`] resolving namespaces with
+    /// `namespace_resolver`, and writing to `dml_handler` the tables named
+    /// according to `table_naming`.
+    pub fn new(dml_handler: D, namespace_resolver: N, table_naming: TableNaming) -> Self {
+        Self {
+            dml_handler,
+            namespace_resolver,
+            table_naming,
+            authz: None,
+            audit_log: None,
+        }
+    }
+
+    /// Authorise each export request with `authz`, if any, requiring the
+    /// request token to grant write access to the namespace.
+    pub fn with_authz(self, authz: Option<Arc<dyn Authorizer>>) -> Self {
+        Self { authz, ..self }
+    }
+
+    /// Record each export request in `audit_log`, if any.
+    pub fn with_audit_log(self, audit_log: Option<AuditLog>) -> Self {
+        Self { audit_log, ..self }
+    }
+}
+
+impl<D, N> OtlpMetricsService<D, N>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>>,
+    N: NamespaceResolver,
+{
+    async fn export_request(
+        &self,
+        namespace: NamespaceName<'static>,
+        request: ExportMetricsServiceRequest,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<ExportMetricsServiceResponse, OtlpError> {
+        let Converted {
+            batches,
+            num_points,
+            rejected_points,
+            first_error,
+        } = convert(request, &self.table_naming);
+
+        let partial_success = first_error.map(|error_message| ExportMetricsPartialSuccess {
+            rejected_data_points: rejected_points as _,
+            error_message,
+        });
+
+        if batches.is_empty() {
+            debug!(%namespace, rejected_points, "nothing to write");
+            return Ok(ExportMetricsServiceResponse { partial_success });
+        }
+
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&namespace)
+            .await?;
+
+        trace!(
+            %namespace,
+            num_tables=batches.len(),
+            num_points,
+            rejected_points,
+            "routing otlp metrics",
+        );
+
+        self.dml_handler
+            .write(&namespace, namespace_schema, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        Ok(ExportMetricsServiceResponse { partial_success })
+    }
+}
+
+#[tonic::async_trait]
+impl<D, N> MetricsService for OtlpMetricsService<D, N>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>> + 'static,
+    N: NamespaceResolver + 'static,
+{
+    /// Handle an OTLP metrics export request.
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let namespace = namespace_name(request.metadata());
+        let token = request_token(request.metadata());
+
+        let record = self.audit_log.as_ref().map(|_| {
+            let record = AuditRecord::new(Operation::Write, "otlp metrics export")
+                .with_token(token.as_deref())
+                .with_bytes(request.get_ref().encoded_len() as _);
+            match &namespace {
+                Ok(namespace) => record.with_namespace(namespace.as_str()),
+                Err(_) => record,
+            }
+        });
+
+        let response = async {
+            let namespace = namespace?;
+            authorize_write(&self.authz, token, &namespace)
+                .await
+                .map_err(OtlpError::Authz)?;
+            self.export_request(namespace, request.into_inner(), span_ctx)
+                .await
+        }
+        .await;
+        if let (Some(audit_log), Some(record)) = (&self.audit_log, record) {
+            audit_log.record(record.with_result(&response));
+        }
+
+        let response = response.map_err(|e| {
+            debug!(error=%e, "otlp metrics export failed");
+            Status::from(e)
+        })?;
+
+        Ok(Response::new(response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use assert_matches::assert_matches;
+    use data_types::{NamespaceId, NamespaceSchema};
+    use generated_types::opentelemetry::proto::{
+        common::v1::AnyValue,
+        metrics::v1::{Gauge, Histogram, HistogramDataPoint, ResourceMetrics, ScopeMetrics},
+        resource::v1::Resource,
+    };
+    use mutable_batch::column::ColumnData;
+
+    use super::*;
+    use crate::{
+        dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
+        namespace_resolver::mock::MockNamespaceResolver,
+        server::http::write::single_tenant::auth::mock::{MockAuthorizer, MOCK_AUTH_VALID_TOKEN},
+    };
+
+    const NAMESPACE_NAME: &str = "bananas_test";
+
+    type Handler = Arc<MockDmlHandler<HashMap<String, MutableBatch>>>;
+
+    fn new_service(
+        dml_handler: &Handler,
+        table_naming: TableNaming,
+    ) -> OtlpMetricsService<Handler, MockNamespaceResolver> {
+        let schema = NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: Default::default(),
+            max_columns_per_table: 500,
+            max_tables: 200,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        };
+        let namespace_resolver = MockNamespaceResolver::new(
+            [(
+                NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                Arc::new(schema),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        OtlpMetricsService::new(Arc::clone(dml_handler), namespace_resolver, table_naming)
+    }
+
+    fn attribute(key: &str, value: any_value::Value) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue { value: Some(value) }),
+        }
+    }
+
+    fn string_attribute(key: &str, value: &str) -> KeyValue {
+        attribute(key, any_value::Value::StringValue(value.to_string()))
+    }
+
+    fn gauge(name: &str, points: Vec<NumberDataPoint>) -> Metric {
+        Metric {
+            name: name.to_string(),
+            data: Some(Data::Gauge(Gauge {
+                data_points: points,
+            })),
+            ..Default::default()
+        }
+    }
+
+    fn number_point(attributes: Vec<KeyValue>, time: u64, value: f64) -> NumberDataPoint {
+        NumberDataPoint {
+            attributes,
+            time_unix_nano: time,
+            value: Some(number_data_point::Value::AsDouble(value)),
+            ..Default::default()
+        }
+    }
+
+    fn new_request(metrics: Vec<Metric>) -> Request<ExportMetricsServiceRequest> {
+        let mut request = Request::new(ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![
+                        string_attribute("service.name", "platanos"),
+                        string_attribute("host", "resource"),
+                    ],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    metrics,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        });
+        request
+            .metadata_mut()
+            .insert(NAMESPACE_METADATA_KEY, NAMESPACE_NAME.parse().unwrap());
+        request
+    }
+
+    fn string_tag(batch: &MutableBatch, name: &str, row: usize) -> String {
+        assert_matches!(batch.column(name).unwrap().data(), ColumnData::Tag(keys, dictionary, _) => {
+            dictionary.lookup_id(keys[row]).unwrap().to_string()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_export_per_metric() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(
+            &dml_handler,
+            TableNaming::PerMetric {
+                prefix: "otel_".to_string(),
+            },
+        );
+
+        let response = service
+            .export(new_request(vec![
+                gauge(
+                    "cpu",
+                    vec![
+                        number_point(vec![string_attribute("host", "point")], 1, 4.2),
+                        number_point(
+                            vec![attribute("core", any_value::Value::IntValue(1))],
+                            2,
+                            2.4,
+                        ),
+                    ],
+                ),
+                Metric {
+                    name: "latency".to_string(),
+                    data: Some(Data::Histogram(Histogram {
+                        data_points: vec![HistogramDataPoint {
+                            time_unix_nano: 3,
+                            count: 2,
+                            sum: Some(1.5),
+                            ..Default::default()
+                        }],
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                },
+            ]))
+            .await
+            .expect("export should succeed")
+            .into_inner();
+        assert_eq!(response.partial_success, None);
+
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+
+            let mut tables = write_input.keys().collect::<Vec<_>>();
+            tables.sort_unstable();
+            assert_eq!(tables, ["otel_cpu", "otel_latency"]);
+
+            let cpu = &write_input["otel_cpu"];
+            assert_eq!(cpu.rows(), 2);
+            assert_eq!(
+                cpu.column_names().into_iter().collect::<Vec<_>>(),
+                ["core", "host", "service.name", "time", "value"]
+            );
+            // Data point attributes take precedence over resource attributes.
+            assert_eq!(string_tag(cpu, "host", 0), "point");
+            assert_eq!(string_tag(cpu, "host", 1), "resource");
+            assert_eq!(string_tag(cpu, "core", 1), "1");
+
+            let latency = &write_input["otel_latency"];
+            assert_eq!(
+                latency.column_names().into_iter().collect::<Vec<_>>(),
+                ["count", "host", "service.name", "sum", "time"]
+            );
+            assert_matches!(latency.column("count").unwrap().data(), ColumnData::U64(v, _) => {
+                assert_eq!(v, &[2]);
+            });
+        });
+    }
+
+    #[tokio::test]
+    async fn test_export_single_table() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, TableNaming::Single("metrics".to_string()));
+
+        service
+            .export(new_request(vec![
+                gauge("cpu", vec![number_point(vec![], 1, 4.2)]),
+                gauge("mem", vec![number_point(vec![], 1, 2.4)]),
+            ]))
+            .await
+            .expect("export should succeed");
+
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { write_input, .. }] => {
+            assert_eq!(write_input.keys().collect::<Vec<_>>(), ["metrics"]);
+            let batch = &write_input["metrics"];
+            assert_eq!(batch.rows(), 2);
+            assert_eq!(string_tag(batch, METRIC_NAME_TAG, 0), "cpu");
+            assert_eq!(string_tag(batch, METRIC_NAME_TAG, 1), "mem");
+        });
+    }
+
+    #[tokio::test]
+    async fn test_export_partial_success() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, TableNaming::default());
+
+        let response = service
+            .export(new_request(vec![gauge(
+                "cpu",
+                vec![
+                    number_point(vec![string_attribute("value", "reserved")], 1, 4.2),
+                    number_point(vec![], 2, 2.4),
+                ],
+            )]))
+            .await
+            .expect("export should succeed")
+            .into_inner();
+
+        assert_matches!(response.partial_success, Some(p) => {
+            assert_eq!(p.rejected_data_points, 1);
+            assert!(p.error_message.contains("attribute value is reserved"));
+        });
+
+        // The remaining data point is written.
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { write_input, .. }] => {
+            assert_eq!(write_input["cpu"].rows(), 1);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_export_no_namespace() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let service = new_service(&dml_handler, TableNaming::default());
+
+        let err = service
+            .export(Request::new(ExportMetricsServiceRequest::default()))
+            .await
+            .expect_err("export without namespace should fail");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_export_authz() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, TableNaming::default())
+            .with_authz(Some(Arc::new(MockAuthorizer::default())));
+
+        let request = || new_request(vec![gauge("cpu", vec![number_point(vec![], 1, 4.2)])]);
+
+        let err = service
+            .export(request())
+            .await
+            .expect_err("export without token should fail");
+        assert_eq!(err.code(), Code::Unauthenticated);
+        assert!(dml_handler.calls().is_empty());
+
+        let mut authorised = request();
+        authorised.metadata_mut().insert(
+            "authorization",
+            format!("Token {MOCK_AUTH_VALID_TOKEN}").parse().unwrap(),
+        );
+        service
+            .export(authorised)
+            .await
+            .expect("authorised export should succeed");
+        assert_eq!(dml_handler.calls().len(), 1);
+    }
+}
@@ -0,0 +1,772 @@
+//! A gRPC write API for the router, accepting the same payloads as the HTTP
+//! write API.
+
+use std::{pin::Pin, sync::Arc};
+
+use audit::{AuditLog, AuditRecord, Operation};
+use authz::Authorizer;
+use data_types::{NamespaceName, NamespaceNameError};
+use futures::{stream, Stream, StreamExt};
+use generated_types::influxdata::iox::router::v1::{
+    self as proto, write_request::Payload, write_service_server::WriteService,
+};
+use hashbrown::HashMap;
+use iox_time::{SystemProvider, TimeProvider};
+use mutable_batch::MutableBatch;
+use mutable_batch_lp::{LinesConverter, PayloadStatistics};
+use mutable_batch_pb::decode::write_table_batch;
+use observability_deps::tracing::*;
+use prost::Message;
+use thiserror::Error;
+use tonic::{Code, Request, Response, Status, Streaming};
+use trace::ctx::SpanContext;
+
+use crate::{
+    dml_handlers::{
+        client::RpcWriteClientError, DmlError, DmlHandler, PartitionError, RateLimitError,
+        RetentionError, RpcWriteError, SchemaError,
+    },
+    namespace_resolver::{self, NamespaceCreationError, NamespaceResolver},
+    server::http::{validate, write::Precision},
+};
+
+use super::{authorize_write, authz_error_code, request_token};
+
+type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send + 'static>>;
+
+/// A list of error states when handling a gRPC write request.
+///
+/// As for the ingester's write service, these errors serve as documentation of
+/// the potential error states, and are converted into [`tonic::Status`] for
+/// the handler.
+#[derive(Debug, Error)]
+enum RpcError {
+    /// The write request did not contain a write payload.
+    #[error("write request does not contain a payload")]
+    NoPayload,
+
+    /// The namespace name in the request is invalid.
+    #[error(transparent)]
+    InvalidNamespace(#[from] NamespaceNameError),
+
+    /// The request is not authorised to write to the namespace.
+    #[error("authorization failed: {0}")]
+    Authz(authz::Error),
+
+    /// A table batch in the request has no table name.
+    #[error("table batch does not specify a table name")]
+    NoTableName,
+
+    /// A table batch in the request has no table data.
+    #[error("table batch for table {0} does not contain any data")]
+    NoTableData(String),
+
+    /// The serialised table data could not be read.
+    #[error("failed to decode table data: {0}")]
+    Decode(mutable_batch_pb::decode::Error),
+
+    /// Failure to decode the provided line protocol.
+    #[error("failed to parse line protocol: {0}")]
+    ParseLineProtocol(mutable_batch_lp::Error),
+
+    /// An error resolving the namespace of the request.
+    #[error(transparent)]
+    NamespaceResolver(#[from] namespace_resolver::Error),
+
+    /// An error returned from the [`DmlHandler`].
+    #[error("dml handler error: {0}")]
+    DmlHandler(#[from] DmlError),
+}
+
+impl From<RpcError> for Status {
+    fn from(e: RpcError) -> Self {
+        let code = match &e {
+            RpcError::NoPayload
+            | RpcError::InvalidNamespace(_)
+            | RpcError::NoTableName
+            | RpcError::NoTableData(_)
+            | RpcError::Decode(_)
+            | RpcError::ParseLineProtocol(_) => Code::InvalidArgument,
+            RpcError::Authz(e) => authz_error_code(e),
+            RpcError::NamespaceResolver(
+                namespace_resolver::Error::Create(NamespaceCreationError::Reject(_))
+                | namespace_resolver::Error::SoftDeleted(_),
+            ) => Code::NotFound,
+            RpcError::NamespaceResolver(_) => Code::Internal,
+            RpcError::DmlHandler(e) => dml_error_code(e),
+        };
+
+        Self::new(code, e.to_string())
+    }
+}
+
+/// Map a [`DmlError`] to the gRPC status [`Code`] returned to the end user,
+/// mirroring the HTTP status codes of the HTTP write API.
+///
+/// All error states are enumerated, so that new error additions cause a
+/// compilation failure and must be explicitly mapped to a status code.
+pub(super) fn dml_error_code(e: &DmlError) -> Code {
+    match e {
+        DmlError::NamespaceNotFound(_) => Code::NotFound,
+        DmlError::Schema(SchemaError::ServiceLimit(_) | SchemaError::Conflict(_)) => {
+            Code::InvalidArgument
+        }
+        DmlError::Schema(SchemaError::UnexpectedCatalogError(_)) => Code::Internal,
+        DmlError::Internal(_) => Code::Internal,
+        DmlError::Partition(PartitionError::BatchWrite(_) | PartitionError::Partitioner(_)) => {
+            Code::Internal
+        }
+        DmlError::Retention(RetentionError::OutsideRetention { .. }) => Code::InvalidArgument,
+        DmlError::RateLimited(RateLimitError::Exceeded { .. }) => Code::ResourceExhausted,
+        DmlError::RpcWrite(RpcWriteError::Client(
+            RpcWriteClientError::Upstream(_)
+            | RpcWriteClientError::MisconfiguredMetadataKey(_)
+            | RpcWriteClientError::MisconfiguredMetadataValue(_),
+        )) => Code::Internal,
+        DmlError::RpcWrite(RpcWriteError::Client(RpcWriteClientError::UpstreamNotConnected(_))) => {
+            Code::Unavailable
+        }
+        DmlError::RpcWrite(RpcWriteError::Timeout(_)) => Code::DeadlineExceeded,
+        DmlError::RpcWrite(
+            RpcWriteError::NoHealthyUpstreams
+            | RpcWriteError::NotEnoughReplicas
+            | RpcWriteError::PartialWrite { .. },
+        ) => Code::Unavailable,
+    }
+}
+
+impl From<proto::Precision> for Precision {
+    fn from(v: proto::Precision) -> Self {
+        match v {
+            proto::Precision::Unspecified | proto::Precision::Nanoseconds => Self::Nanoseconds,
+            proto::Precision::Microseconds => Self::Microseconds,
+            proto::Precision::Milliseconds => Self::Milliseconds,
+            proto::Precision::Seconds => Self::Seconds,
+        }
+    }
+}
+
+/// A converted write payload.
+#[derive(Debug)]
+enum Converted {
+    /// A line protocol payload, converted by a [`LinesConverter`].
+    LineProtocol {
+        body: String,
+        timestamp_base: i64,
+        batches: HashMap<String, MutableBatch>,
+        stats: PayloadStatistics,
+    },
+    /// Pre-parsed table data.
+    TableBatches(HashMap<String, MutableBatch>),
+}
+
+/// Decode the pre-parsed table data in `batches`, merging the batches of each
+/// table.
+fn decode_table_batches(
+    batches: proto::TableBatches,
+) -> Result<HashMap<String, MutableBatch>, RpcError> {
+    let mut tables = HashMap::<String, MutableBatch>::new();
+    for proto::TableBatch { table_name, batch } in batches.table_batches {
+        if table_name.is_empty() {
+            return Err(RpcError::NoTableName);
+        }
+        let batch = batch.ok_or_else(|| RpcError::NoTableData(table_name.clone()))?;
+        write_table_batch(tables.entry(table_name).or_default(), &batch)
+            .map_err(RpcError::Decode)?;
+    }
+
+    // Tables without any rows have nothing to write.
+    tables.retain(|_, batch| batch.rows() > 0);
+    Ok(tables)
+}
+
+/// A gRPC [`WriteService`] handler.
+///
+/// This handler accepts line protocol and pre-parsed table data, and passes
+/// them through the same [`NamespaceResolver`] and [`DmlHandler`] stack as the
+/// HTTP write API. As for the HTTP write API, the lines of a line protocol
+/// payload that cannot be written to the namespace are rejected individually
+/// while the remaining lines are written, with the rejected lines reported in
+/// the response.
+#[derive(Debug, Clone)]
+pub struct RpcWriteService<D, N, T = SystemProvider> {
+    dml_handler: D,
+    namespace_resolver: N,
+    time_provider: T,
+    authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
+}
+
+impl<D, N> RpcWriteService<D, N> {
+    /// Initialise a new [`RpcWriteService`] resolving namespaces with
+    /// `namespace_resolver`, and writing to `dml_handler`.
+    pub fn new(dml_handler: D, namespace_resolver: N) -> Self {
+        Self {
+            dml_handler,
+            namespace_resolver,
+            time_provider: SystemProvider::default(),
+            authz: None,
+            audit_log: None,
+        }
+    }
+}
+
+impl<D, N, T> RpcWriteService<D, N, T> {
+    /// Authorise each write request with `authz`, if any, requiring the
+    /// request token to grant write access to the namespace.
+    pub fn with_authz(self, authz: Option<Arc<dyn Authorizer>>) -> Self {
+        Self { authz, ..self }
+    }
+
+    /// Record each write request in `audit_log`, if any.
+    pub fn with_audit_log(self, audit_log: Option<AuditLog>) -> Self {
+        Self { audit_log, ..self }
+    }
+}
+
+impl<D, N, T> RpcWriteService<D, N, T>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>>,
+    N: NamespaceResolver,
+    T: TimeProvider,
+{
+    /// Write the payload of `request`, recording it in the audit log, if
+    /// enabled.
+    async fn audited_write_request(
+        &self,
+        request: proto::WriteRequest,
+        token: Option<Vec<u8>>,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<proto::WriteResponse, RpcError> {
+        let Some(audit_log) = &self.audit_log else {
+            return self.write_request(request, token, span_ctx).await;
+        };
+
+        let record = AuditRecord::new(Operation::Write, "grpc write")
+            .with_namespace(request.namespace.clone())
+            .with_token(token.as_deref())
+            .with_bytes(request.encoded_len() as _);
+        let res = self.write_request(request, token, span_ctx).await;
+        audit_log.record(record.with_result(&res));
+        res
+    }
+
+    /// Write the payload of `request`.
+    async fn write_request(
+        &self,
+        request: proto::WriteRequest,
+        token: Option<Vec<u8>>,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<proto::WriteResponse, RpcError> {
+        let namespace = NamespaceName::try_from(request.namespace)?;
+        authorize_write(&self.authz, token, &namespace)
+            .await
+            .map_err(RpcError::Authz)?;
+        let payload = request.payload.ok_or(RpcError::NoPayload)?;
+
+        // The time, in nanoseconds since the epoch, to assign to any points
+        // that don't contain a timestamp.
+        let default_time = self.time_provider.now().timestamp_nanos();
+
+        let converted = match payload {
+            Payload::LineProtocol(lp) => {
+                let timestamp_base = Precision::from(lp.precision()).timestamp_base();
+                let mut converter = LinesConverter::new(default_time);
+                converter.set_timestamp_base(timestamp_base);
+                match converter
+                    .write_lp(&lp.body)
+                    .and_then(|_| converter.finish())
+                {
+                    Ok((batches, stats)) => Converted::LineProtocol {
+                        body: lp.body,
+                        timestamp_base,
+                        batches,
+                        stats,
+                    },
+                    Err(mutable_batch_lp::Error::EmptyPayload) => {
+                        debug!("nothing to write");
+                        return Ok(Default::default());
+                    }
+                    Err(e) => return Err(RpcError::ParseLineProtocol(e)),
+                }
+            }
+            Payload::TableBatches(batches) => {
+                let batches = decode_table_batches(batches)?;
+                if batches.is_empty() {
+                    debug!("nothing to write");
+                    return Ok(Default::default());
+                }
+                Converted::TableBatches(batches)
+            }
+        };
+
+        // Retrieve the namespace schema for this namespace.
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&namespace)
+            .await?;
+
+        // Reject the lines that cannot be written to the namespace, writing
+        // the rest.
+        let (batches, rejected) = match converted {
+            Converted::LineProtocol {
+                body,
+                timestamp_base,
+                batches,
+                stats,
+            } => {
+                let filtered = validate::reject_lines(
+                    &body,
+                    batches,
+                    stats,
+                    &namespace_schema,
+                    default_time,
+                    timestamp_base,
+                )
+                .map_err(RpcError::ParseLineProtocol)?;
+                (filtered.batches, filtered.rejected)
+            }
+            Converted::TableBatches(batches) => (batches, vec![]),
+        };
+
+        trace!(
+            %namespace,
+            num_tables=batches.len(),
+            num_rejected_lines=rejected.len(),
+            "routing grpc write",
+        );
+
+        self.dml_handler
+            .write(&namespace, namespace_schema, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        Ok(proto::WriteResponse {
+            rejected_lines: rejected
+                .into_iter()
+                .map(|v| proto::LineError {
+                    line: v.line as _,
+                    error: v.error,
+                })
+                .collect(),
+        })
+    }
+
+    /// Write each of the `requests` in order, authorised by the `token` of
+    /// the stream, returning a stream of their responses.
+    ///
+    /// The returned stream ends after the first error, without reading any
+    /// further requests.
+    fn write_requests<S>(
+        self,
+        requests: S,
+        token: Option<Vec<u8>>,
+        span_ctx: Option<SpanContext>,
+    ) -> impl Stream<Item = Result<proto::WriteResponse, Status>>
+    where
+        S: Stream<Item = Result<proto::WriteRequest, Status>> + Unpin,
+    {
+        stream::unfold(Some((self, requests)), move |state| {
+            let token = token.clone();
+            let span_ctx = span_ctx.clone();
+            async move {
+                let (this, mut requests) = state?;
+                let res = match requests.next().await? {
+                    Ok(request) => this
+                        .audited_write_request(request, token, span_ctx)
+                        .await
+                        .map_err(Status::from),
+                    Err(e) => Err(e),
+                };
+
+                let next = res.is_ok().then_some((this, requests));
+                Some((res, next))
+            }
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl<D, N, T> WriteService for RpcWriteService<D, N, T>
+where
+    D: DmlHandler<WriteInput = HashMap<String, MutableBatch>> + Clone + 'static,
+    N: NamespaceResolver + Clone + 'static,
+    T: TimeProvider + Clone,
+{
+    /// Handle a gRPC write request.
+    async fn write(
+        &self,
+        request: Request<proto::WriteRequest>,
+    ) -> Result<Response<proto::WriteResponse>, Status> {
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let token = request_token(request.metadata());
+
+        let response = self
+            .audited_write_request(request.into_inner(), token, span_ctx)
+            .await
+            .map_err(|e| {
+                debug!(error=%e, "grpc write failed");
+                Status::from(e)
+            })?;
+
+        Ok(Response::new(response))
+    }
+
+    type WriteStreamStream = TonicStream<proto::WriteResponse>;
+
+    /// Handle a stream of gRPC write requests.
+    async fn write_stream(
+        &self,
+        request: Request<Streaming<proto::WriteRequest>>,
+    ) -> Result<Response<Self::WriteStreamStream>, Status> {
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let token = request_token(request.metadata());
+
+        let responses = self
+            .clone()
+            .write_requests(request.into_inner(), token, span_ctx);
+
+        Ok(Response::new(Box::pin(responses)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use assert_matches::assert_matches;
+    use data_types::{NamespaceId, NamespaceSchema};
+    use generated_types::influxdata::pbdata::v1::{
+        column::{SemanticType, Values},
+        Column,
+    };
+    use iox_time::{MockProvider, Time};
+
+    use super::*;
+    use crate::{
+        dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
+        namespace_resolver::mock::MockNamespaceResolver,
+        server::http::write::single_tenant::auth::mock::{
+            MockAuthorizer, MOCK_AUTH_NO_PERMS_TOKEN, MOCK_AUTH_VALID_TOKEN,
+        },
+    };
+
+    const NAMESPACE_NAME: &str = "bananas_test";
+
+    type Handler = Arc<MockDmlHandler<HashMap<String, MutableBatch>>>;
+
+    fn new_service(
+        dml_handler: &Handler,
+        max_tables: usize,
+    ) -> RpcWriteService<Handler, Arc<MockNamespaceResolver>, Arc<MockProvider>> {
+        let schema = NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: BTreeMap::new(),
+            max_columns_per_table: 500,
+            max_tables,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        };
+        let namespace_resolver = MockNamespaceResolver::new(
+            [(
+                NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                Arc::new(schema),
+            )]
+            .into_iter()
+            .collect(),
+        );
+
+        RpcWriteService {
+            dml_handler: Arc::clone(dml_handler),
+            namespace_resolver: Arc::new(namespace_resolver),
+            time_provider: Arc::new(MockProvider::new(Time::from_timestamp_nanos(4242))),
+            authz: None,
+            audit_log: None,
+        }
+    }
+
+    fn lp_request(body: &str, precision: proto::Precision) -> proto::WriteRequest {
+        proto::WriteRequest {
+            namespace: NAMESPACE_NAME.to_string(),
+            payload: Some(Payload::LineProtocol(proto::LineProtocol {
+                body: body.to_string(),
+                precision: precision.into(),
+            })),
+        }
+    }
+
+    fn table_batch(table_name: &str, times: Vec<i64>) -> proto::TableBatch {
+        proto::TableBatch {
+            table_name: table_name.to_string(),
+            batch: Some(generated_types::influxdata::pbdata::v1::TableBatch {
+                table_id: 0,
+                row_count: times.len() as _,
+                columns: vec![Column {
+                    column_name: "time".to_string(),
+                    semantic_type: SemanticType::Time.into(),
+                    values: Some(Values {
+                        i64_values: times,
+                        f64_values: vec![],
+                        u64_values: vec![],
+                        string_values: vec![],
+                        bool_values: vec![],
+                        bytes_values: vec![],
+                        packed_string_values: None,
+                        interned_string_values: None,
+                    }),
+                    null_mask: vec![],
+                }],
+            }),
+        }
+    }
+
+    /// Return the batches written in the single call made to `dml_handler`.
+    fn written(dml_handler: &Handler) -> HashMap<String, MutableBatch> {
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+                assert_eq!(namespace, NAMESPACE_NAME);
+                write_input.clone()
+            }
+        )
+    }
+
+    #[tokio::test]
+    async fn test_write_line_protocol() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, 10);
+
+        let got = service
+            .write(Request::new(lp_request(
+                "platanos,tag1=A val=42i 1\nplatanos val=24i",
+                proto::Precision::Seconds,
+            )))
+            .await
+            .expect("write should succeed")
+            .into_inner();
+        assert!(got.rejected_lines.is_empty());
+
+        // The timestamp of the first line is scaled to nanoseconds, and the
+        // second line is assigned the default time.
+        let summary = written(&dml_handler)["platanos"]
+            .timestamp_summary()
+            .unwrap();
+        assert_eq!(summary.stats.min, Some(4242));
+        assert_eq!(summary.stats.max, Some(1_000_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_write_line_protocol_rejected_lines() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, 1);
+
+        let got = service
+            .write(Request::new(lp_request(
+                "platanos val=42i 1\nbananas val=4i 1\nplatanos val=24i 2",
+                proto::Precision::Unspecified,
+            )))
+            .await
+            .expect("write should succeed")
+            .into_inner();
+
+        assert_matches!(got.rejected_lines.as_slice(), [proto::LineError { line: 2, error }] => {
+            assert!(error.contains("couldn't create new table"));
+        });
+
+        let written = written(&dml_handler);
+        assert_eq!(written.len(), 1);
+        assert_eq!(written["platanos"].rows(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_write_table_batches() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service = new_service(&dml_handler, 10);
+
+        service
+            .write(Request::new(proto::WriteRequest {
+                namespace: NAMESPACE_NAME.to_string(),
+                payload: Some(Payload::TableBatches(proto::TableBatches {
+                    table_batches: vec![
+                        table_batch("platanos", vec![1, 2]),
+                        table_batch("bananas", vec![]),
+                        table_batch("platanos", vec![3]),
+                    ],
+                })),
+            }))
+            .await
+            .expect("write should succeed");
+
+        // Empty tables are not written, and batches of the same table are
+        // merged.
+        let written = written(&dml_handler);
+        assert_eq!(written.len(), 1);
+        assert_eq!(written["platanos"].rows(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_write_errors() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let service = new_service(&dml_handler, 10);
+
+        let write = |request| {
+            let service = service.clone();
+            async move {
+                service
+                    .write(Request::new(request))
+                    .await
+                    .expect_err("write should fail")
+            }
+        };
+
+        let err = write(proto::WriteRequest {
+            namespace: NAMESPACE_NAME.to_string(),
+            payload: None,
+        })
+        .await;
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let err = write(lp_request("platanos val=", proto::Precision::Unspecified)).await;
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let err = write(proto::WriteRequest {
+            namespace: NAMESPACE_NAME.to_string(),
+            payload: Some(Payload::TableBatches(proto::TableBatches {
+                table_batches: vec![table_batch("", vec![1])],
+            })),
+        })
+        .await;
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        let err = write(proto::WriteRequest {
+            namespace: "bananas".to_string(),
+            ..lp_request("platanos val=42i", proto::Precision::Unspecified)
+        })
+        .await;
+        assert_eq!(err.code(), Code::Internal);
+
+        // Nothing is written.
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_authz() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let service =
+            new_service(&dml_handler, 10).with_authz(Some(Arc::new(MockAuthorizer::default())));
+
+        let write = |token: Option<&str>| {
+            let service = service.clone();
+            let mut request = Request::new(lp_request(
+                "platanos val=42i",
+                proto::Precision::Unspecified,
+            ));
+            if let Some(token) = token {
+                request
+                    .metadata_mut()
+                    .insert("authorization", format!("Bearer {token}").parse().unwrap());
+            }
+            async move { service.write(request).await }
+        };
+
+        let err = write(None)
+            .await
+            .expect_err("write without token should fail");
+        assert_eq!(err.code(), Code::Unauthenticated);
+        let err = write(Some(MOCK_AUTH_NO_PERMS_TOKEN))
+            .await
+            .expect_err("write without permission should fail");
+        assert_eq!(err.code(), Code::PermissionDenied);
+        assert!(dml_handler.calls().is_empty());
+
+        write(Some(MOCK_AUTH_VALID_TOKEN))
+            .await
+            .expect("authorised write should succeed");
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, .. }] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_write_audit_log() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let sink = Arc::new(audit::mock::MockAuditSink::default());
+        let audit_log = AuditLog::new(
+            "router",
+            Arc::clone(&sink) as _,
+            &metric::Registry::default(),
+        );
+        let service = new_service(&dml_handler, 10)
+            .with_authz(Some(Arc::new(MockAuthorizer::default())))
+            .with_audit_log(Some(audit_log));
+
+        let write = |token: &str| {
+            let mut request = Request::new(lp_request(
+                "platanos val=42i",
+                proto::Precision::Unspecified,
+            ));
+            request
+                .metadata_mut()
+                .insert("authorization", format!("Bearer {token}").parse().unwrap());
+            request
+        };
+
+        service
+            .write(write(MOCK_AUTH_NO_PERMS_TOKEN))
+            .await
+            .expect_err("write without permission should fail");
+        service
+            .write(write(MOCK_AUTH_VALID_TOKEN))
+            .await
+            .expect("authorised write should succeed");
+
+        let records = sink.wait_for_records(2).await;
+        assert_matches!(records.as_slice(), [denied, written] => {
+            assert_eq!(denied.namespace.as_deref(), Some(NAMESPACE_NAME));
+            assert_eq!(denied.identity, Some(audit::identity(MOCK_AUTH_NO_PERMS_TOKEN.as_bytes())));
+            assert_eq!(denied.status, audit::Status::Error);
+            assert_eq!(written.operation, Operation::Write);
+            assert_eq!(written.identity, Some(audit::identity(MOCK_AUTH_VALID_TOKEN.as_bytes())));
+            assert_eq!(written.status, audit::Status::Ok);
+            assert!(written.bytes > 0);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_write_empty() {
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let service = new_service(&dml_handler, 10);
+
+        service
+            .write(Request::new(lp_request("", proto::Precision::Unspecified)))
+            .await
+            .expect("empty write should succeed");
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_requests() {
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([
+            Ok(()),
+            Err(DmlError::NamespaceNotFound(NAMESPACE_NAME.to_string())),
+            Ok(()),
+        ]));
+        let service = new_service(&dml_handler, 10);
+
+        let requests = stream::iter(
+            ["platanos val=1i", "platanos val=2i", "platanos val=3i"]
+                .into_iter()
+                .map(|lp| Ok(lp_request(lp, proto::Precision::Unspecified))),
+        );
+        let responses = service
+            .write_requests(requests, None, None)
+            .collect::<Vec<_>>()
+            .await;
+
+        // The stream ends after the first failed write.
+        assert_matches!(responses.as_slice(), [Ok(_), Err(e)] => {
+            assert_eq!(e.code(), Code::NotFound);
+        });
+        assert_eq!(dml_handler.calls().len(), 2);
+    }
+}
@@ -1,13 +1,24 @@
 //! HTTP service implementations for `router`.
 
+pub mod buckets;
+pub mod json;
+pub mod prometheus;
+pub mod validate;
 pub mod write;
 
-use std::{str::Utf8Error, time::Instant};
+use std::{
+    str::Utf8Error,
+    time::{Duration, Instant},
+};
 
+use audit::{AuditLog, AuditRecord, Operation};
 use bytes::{Bytes, BytesMut};
 use futures::StreamExt;
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, U64Counter};
 use mutable_batch::MutableBatch;
@@ -17,14 +28,17 @@ use thiserror::Error;
 use tokio::sync::{Semaphore, TryAcquireError};
 use trace::ctx::SpanContext;
 
-use self::write::{
-    multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError, WriteParams,
-    WriteRequestUnifier,
+use self::{
+    buckets::{BucketsApi, BucketsError},
+    write::{
+        multi_tenant::MultiTenantExtractError, single_tenant::SingleTenantExtractError,
+        WriteParams, WriteRequestUnifier,
+    },
 };
 use crate::{
     dml_handlers::{
-        client::RpcWriteClientError, DmlError, DmlHandler, PartitionError, RetentionError,
-        RpcWriteError, SchemaError,
+        client::RpcWriteClientError, DmlError, DmlHandler, PartitionError, RateLimitError,
+        RetentionError, RpcWriteError, SchemaError,
     },
     namespace_resolver::NamespaceResolver,
 };
@@ -72,10 +86,26 @@ pub enum Error {
     #[error("error decoding gzip stream: {0}")]
     InvalidGzip(std::io::Error),
 
+    /// Decoding a zstd-compressed stream of data failed.
+    #[error("error decoding zstd stream: {0}")]
+    InvalidZstd(std::io::Error),
+
+    /// Decoding a snappy-compressed block of data failed.
+    #[error("error decoding snappy block: {0}")]
+    InvalidSnappy(snap::Error),
+
     /// Failure to decode the provided line protocol.
     #[error("failed to parse line protocol: {0}")]
     ParseLineProtocol(mutable_batch_lp::Error),
 
+    /// Failure to decode the provided JSON write request.
+    #[error("failed to parse json write request: {0}")]
+    ParseJson(json::Error),
+
+    /// Failure to decode the provided Prometheus remote-write request.
+    #[error("failed to parse prometheus remote-write request: {0}")]
+    ParsePrometheus(prometheus::Error),
+
     /// An error returned from the [`DmlHandler`].
     #[error("dml handler error: {0}")]
     DmlHandler(#[from] DmlError),
@@ -99,6 +129,15 @@ pub enum Error {
     /// The provided authorization is not sufficient to perform the request.
     #[error("access denied")]
     Forbidden,
+
+    /// Some lines of the write could not be written to the namespace and were
+    /// rejected, while the remaining lines were written.
+    #[error(transparent)]
+    PartialWrite(validate::RejectedLines),
+
+    /// An error serving the V2 buckets API.
+    #[error(transparent)]
+    Buckets(#[from] BucketsError),
 }
 
 impl Error {
@@ -110,9 +149,13 @@ impl Error {
             Error::DeletesUnsupported => StatusCode::NOT_IMPLEMENTED,
             Error::ClientHangup(_) => StatusCode::BAD_REQUEST,
             Error::InvalidGzip(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidZstd(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidSnappy(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8ContentHeader(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8Body(_) => StatusCode::BAD_REQUEST,
             Error::ParseLineProtocol(_) => StatusCode::BAD_REQUEST,
+            Error::ParseJson(_) => StatusCode::BAD_REQUEST,
+            Error::ParsePrometheus(_) => StatusCode::BAD_REQUEST,
             Error::RequestSizeExceeded(_) => StatusCode::PAYLOAD_TOO_LARGE,
             Error::InvalidContentEncoding(_) => {
                 // https://www.rfc-editor.org/rfc/rfc7231#section-6.5.13
@@ -123,12 +166,35 @@ impl Error {
             Error::NamespaceResolver(crate::namespace_resolver::Error::Create(
                 crate::namespace_resolver::ns_autocreation::NamespaceCreationError::Reject(_),
             )) => StatusCode::BAD_REQUEST,
+            Error::NamespaceResolver(crate::namespace_resolver::Error::SoftDeleted(_)) => {
+                StatusCode::GONE
+            }
             Error::NamespaceResolver(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Error::RequestLimit => StatusCode::SERVICE_UNAVAILABLE,
             Error::Unauthenticated => StatusCode::UNAUTHORIZED,
             Error::Forbidden => StatusCode::FORBIDDEN,
             Error::SingleTenantError(e) => StatusCode::from(e),
             Error::MultiTenantError(e) => StatusCode::from(e),
+            Error::PartialWrite(_) => StatusCode::BAD_REQUEST,
+            Error::Buckets(e) => StatusCode::from(e),
+        }
+    }
+
+    /// The time after which the client should retry the request, if the
+    /// request was rejected due to rate limiting.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Error::DmlHandler(DmlError::RateLimited(e)) => Some(e.retry_after()),
+            _ => None,
+        }
+    }
+
+    /// The individual lines of the request that were rejected, if the request
+    /// was partially written.
+    pub fn line_errors(&self) -> &[validate::LineDiagnostic] {
+        match self {
+            Error::PartialWrite(e) => &e.line_errors,
+            _ => &[],
         }
     }
 }
@@ -153,6 +219,7 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             DmlError::Retention(RetentionError::OutsideRetention { .. }) => StatusCode::FORBIDDEN,
+            DmlError::RateLimited(RateLimitError::Exceeded { .. }) => StatusCode::TOO_MANY_REQUESTS,
             DmlError::RpcWrite(RpcWriteError::Client(RpcWriteClientError::Upstream(_))) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -184,11 +251,18 @@ impl From<&DmlError> for StatusCode {
 #[derive(Debug)]
 pub struct HttpDelegate<D, N, T = SystemProvider> {
     max_request_bytes: usize,
+    max_decompressed_request_bytes: usize,
     time_provider: T,
     namespace_resolver: N,
     dml_handler: D,
     write_request_mode_handler: Box<dyn WriteRequestUnifier>,
 
+    // The V2 buckets API, if enabled.
+    buckets: Option<BucketsApi>,
+
+    // The log writes are recorded in, if enabled.
+    audit_log: Option<AuditLog>,
+
     // A request limiter to restrict the number of simultaneous requests this
     // router services.
     //
@@ -211,7 +285,8 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
     /// specified `dml_handler`.
     ///
     /// HTTP request bodies are limited to `max_request_bytes` in size,
-    /// returning an error if exceeded.
+    /// returning an error if exceeded. By default, this limit also applies to
+    /// compressed request bodies once decompressed.
     pub fn new(
         max_request_bytes: usize,
         max_requests: usize,
@@ -259,10 +334,13 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
 
         Self {
             max_request_bytes,
+            max_decompressed_request_bytes: max_request_bytes,
             time_provider: SystemProvider::default(),
             namespace_resolver,
             write_request_mode_handler,
             dml_handler,
+            buckets: None,
+            audit_log: None,
             request_sem: Semaphore::new(max_requests),
             write_metric_lines,
             http_line_protocol_parse_duration,
@@ -274,6 +352,31 @@ impl<D, N> HttpDelegate<D, N, SystemProvider> {
     }
 }
 
+impl<D, N, T> HttpDelegate<D, N, T> {
+    /// Limit compressed HTTP request bodies to `max_decompressed_request_bytes`
+    /// in size once decompressed, returning an error if exceeded.
+    pub fn with_max_decompressed_request_bytes(
+        mut self,
+        max_decompressed_request_bytes: usize,
+    ) -> Self {
+        self.max_decompressed_request_bytes = max_decompressed_request_bytes;
+        self
+    }
+
+    /// Serve the V2 buckets API (listing and creating buckets) using
+    /// `buckets`, instead of responding with "not found".
+    pub fn with_buckets(mut self, buckets: BucketsApi) -> Self {
+        self.buckets = Some(buckets);
+        self
+    }
+
+    /// Record every write request in `audit_log`.
+    pub fn with_audit_log(mut self, audit_log: AuditLog) -> Self {
+        self.audit_log = Some(audit_log);
+        self
+    }
+}
+
 impl<D, N, T> HttpDelegate<D, N, T>
 where
     D: DmlHandler<WriteInput = HashMap<String, MutableBatch>, WriteOutput = ()>,
@@ -282,7 +385,33 @@ where
 {
     /// Routes `req` to the appropriate handler, if any, returning the handler
     /// response.
+    ///
+    /// Write requests are recorded in the audit log, if enabled.
     pub async fn route(&self, req: Request<Body>) -> Result<Response<Body>, Error> {
+        let (Some(audit_log), Some(summary)) = (&self.audit_log, write_summary(&req)) else {
+            return self.route_request(req, &mut None).await;
+        };
+
+        let record = AuditRecord::new(Operation::Write, summary)
+            .with_token(write_token(&req).as_deref())
+            .with_bytes(content_length(&req));
+        let mut namespace = None;
+        let res = self.route_request(req, &mut namespace).await;
+        let record = match namespace {
+            Some(namespace) => record.with_namespace(namespace),
+            None => record,
+        };
+        audit_log.record(record.with_result(&res));
+        res
+    }
+
+    /// Routes `req` to the appropriate handler, setting `namespace` to the
+    /// namespace of a write request once it is parsed.
+    async fn route_request(
+        &self,
+        req: Request<Body>,
+        namespace: &mut Option<String>,
+    ) -> Result<Response<Body>, Error> {
         // Acquire and hold a permit for the duration of this request, or return
         // a 503 if the existing requests have already exhausted the allocation.
         //
@@ -304,13 +433,36 @@ where
         match (req.method(), req.uri().path()) {
             (&Method::POST, "/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v1(&req).await?;
+                *namespace = Some(dml_info.namespace.to_string());
                 self.write_handler(req, dml_info).await
             }
             (&Method::POST, "/api/v2/write") => {
                 let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                *namespace = Some(dml_info.namespace.to_string());
                 self.write_handler(req, dml_info).await
             }
+            (&Method::POST, "/api/v2/prom/write") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                *namespace = Some(dml_info.namespace.to_string());
+                self.prometheus_write_handler(req, dml_info).await
+            }
             (&Method::POST, "/api/v2/delete") => return Err(Error::DeletesUnsupported),
+            (&Method::POST, "/api/v3/validate") => {
+                let dml_info = self.write_request_mode_handler.parse_v2(&req).await?;
+                return self.validate_handler(req, dml_info).await;
+            }
+            (&Method::GET, "/api/v2/buckets") => {
+                let buckets = self.buckets.as_ref().ok_or(Error::NoHandler)?;
+                let list = buckets.list(&req).await?;
+                return Ok(json_response(StatusCode::OK, &list));
+            }
+            (&Method::POST, "/api/v2/buckets") => {
+                let buckets = self.buckets.as_ref().ok_or(Error::NoHandler)?;
+                let token = buckets::request_token(&req);
+                let body = self.read_body(req).await?;
+                let bucket = buckets.create(token, &body).await?;
+                return Ok(json_response(StatusCode::CREATED, &bucket));
+            }
             _ => return Err(Error::NoHandler),
         }
         .map(|_summary| {
@@ -326,6 +478,12 @@ where
         req: Request<Body>,
         write_info: WriteParams,
     ) -> Result<(), Error> {
+        // Bodies declared as JSON are decoded as a JSON array of points
+        // instead of line protocol.
+        if is_json(&req) {
+            return self.json_write_handler(req, write_info).await;
+        }
+
         let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
 
         trace!(
@@ -373,6 +531,23 @@ where
             .get_namespace_schema(&write_info.namespace)
             .await?;
 
+        // Reject the lines that cannot be written to the namespace, writing the
+        // rest.
+        let validate::FilteredLines {
+            batches,
+            stats,
+            rejected,
+        } = validate::reject_lines(
+            body,
+            batches,
+            stats,
+            &namespace_schema,
+            default_time,
+            write_info.precision.timestamp_base(),
+        )
+        .map_err(Error::ParseLineProtocol)?;
+        let num_tables = batches.len();
+
         self.dml_handler
             .write(&write_info.namespace, namespace_schema, batches, span_ctx)
             .await
@@ -383,9 +558,177 @@ where
         self.write_metric_tables.inc(num_tables as _);
         self.write_metric_body_size.inc(body.len() as _);
 
+        if !rejected.is_empty() {
+            debug!(
+                num_rejected_lines=rejected.len(),
+                namespace=%write_info.namespace,
+                "rejected lines of write",
+            );
+            return Err(Error::PartialWrite(validate::RejectedLines {
+                num_lines: stats.num_lines + rejected.len(),
+                line_errors: rejected,
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// Write the points of a JSON write request to the namespace of
+    /// `write_info`.
+    ///
+    /// Each measurement is written to a table of the same name - see the
+    /// [`json`] module for the format of the request body.
+    async fn json_write_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<(), Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        trace!(
+            namespace=%write_info.namespace,
+            "processing json write request"
+        );
+
+        let body = self.read_body(req).await?;
+
+        let default_time = self.time_provider.now().timestamp_nanos();
+        let (batches, stats) =
+            json::decode(&body, default_time, write_info.precision.timestamp_base())
+                .map_err(Error::ParseJson)?;
+
+        let num_tables = batches.len();
+        debug!(
+            num_points=stats.num_points,
+            num_fields=stats.num_fields,
+            num_tables,
+            precision=?write_info.precision,
+            body_size=body.len(),
+            namespace=%write_info.namespace,
+            "routing json write",
+        );
+
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&write_info.namespace)
+            .await?;
+
+        self.dml_handler
+            .write(&write_info.namespace, namespace_schema, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        // Each point is the equivalent of a line protocol line.
+        self.write_metric_lines.inc(stats.num_points as _);
+        self.write_metric_fields.inc(stats.num_fields as _);
+        self.write_metric_tables.inc(num_tables as _);
+        self.write_metric_body_size.inc(body.len() as _);
+
+        Ok(())
+    }
+
+    /// Write the samples of a Prometheus remote-write request (a
+    /// protobuf-encoded `WriteRequest`) to the namespace of `write_info`.
+    ///
+    /// Each metric is written to a table of the same name - see
+    /// [`prometheus::decode()`] for the mapping of series to rows.
+    async fn prometheus_write_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<(), Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
+        trace!(
+            namespace=%write_info.namespace,
+            "processing prometheus remote-write request"
+        );
+
+        let body = self.read_body(req).await?;
+
+        let (batches, stats) = prometheus::decode(&body).map_err(Error::ParsePrometheus)?;
+        if batches.is_empty() {
+            debug!("nothing to write");
+            return Ok(());
+        }
+
+        let num_tables = batches.len();
+        debug!(
+            num_series=stats.num_series,
+            num_samples=stats.num_samples,
+            num_tables,
+            body_size=body.len(),
+            namespace=%write_info.namespace,
+            "routing prometheus write",
+        );
+
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&write_info.namespace)
+            .await?;
+
+        self.dml_handler
+            .write(&write_info.namespace, namespace_schema, batches, span_ctx)
+            .await
+            .map_err(Into::into)?;
+
+        // Each sample is the equivalent of a line with a single field.
+        self.write_metric_lines.inc(stats.num_samples as _);
+        self.write_metric_fields.inc(stats.num_samples as _);
+        self.write_metric_tables.inc(num_tables as _);
+        self.write_metric_body_size.inc(body.len() as _);
+
         Ok(())
     }
 
+    /// Validate the line protocol in the request body as if it were written
+    /// with `write_info`, without writing it, returning a JSON
+    /// [`ValidationReport`] describing any problems found.
+    ///
+    /// The namespace is resolved as for a write, and therefore may be created
+    /// if namespace autocreation is enabled.
+    ///
+    /// [`ValidationReport`]: validate::ValidationReport
+    async fn validate_handler(
+        &self,
+        req: Request<Body>,
+        write_info: WriteParams,
+    ) -> Result<Response<Body>, Error> {
+        trace!(
+            namespace=%write_info.namespace,
+            "processing validation request"
+        );
+
+        let body = self.read_body(req).await?;
+        let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
+
+        let namespace_schema = self
+            .namespace_resolver
+            .get_namespace_schema(&write_info.namespace)
+            .await?;
+
+        let report = validate::validate_lp(
+            body,
+            &namespace_schema,
+            self.time_provider.now().timestamp_nanos(),
+            write_info.precision.timestamp_base(),
+        );
+        debug!(
+            valid=report.valid,
+            num_lines=report.num_lines,
+            num_line_errors=report.line_errors.len(),
+            namespace=%write_info.namespace,
+            "validated write payload",
+        );
+
+        let body = serde_json::to_vec(&report).expect("validation report must serialise");
+        Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(body))
+            .unwrap())
+    }
+
     /// Parse the request's body into raw bytes, applying the configured size
     /// limits and decoding any content encoding.
     async fn read_body(&self, req: hyper::Request<Body>) -> Result<Bytes, Error> {
@@ -394,9 +737,12 @@ where
             .get(&CONTENT_ENCODING)
             .map(|v| v.to_str().map_err(Error::NonUtf8ContentHeader))
             .transpose()?;
-        let ungzip = match encoding {
-            None | Some("identity") => false,
-            Some("gzip") => true,
+        let encoding = match encoding {
+            None | Some("identity") => ContentEncoding::Identity,
+            Some("gzip") => ContentEncoding::Gzip,
+            Some("zstd") => ContentEncoding::Zstd,
+            // Used by the Prometheus remote-write protocol.
+            Some("snappy") => ContentEncoding::Snappy,
             Some(v) => return Err(Error::InvalidContentEncoding(v.to_string())),
         };
 
@@ -413,47 +759,135 @@ where
         }
         let body = body.freeze();
 
-        // If the body is not compressed, return early.
-        if !ungzip {
-            return Ok(body);
-        }
-
-        // Unzip the gzip-encoded content
-        use std::io::Read;
-        let decoder = flate2::read::GzDecoder::new(&body[..]);
-
-        // Read at most max_request_bytes bytes to prevent a decompression bomb
-        // based DoS.
-        //
-        // In order to detect if the entire stream ahs been read, or truncated,
-        // read an extra byte beyond the limit and check the resulting data
-        // length - see the max_request_size_truncation test.
-        let mut decoder = decoder.take(self.max_request_bytes as u64 + 1);
-        let mut decoded_data = Vec::new();
-        decoder
-            .read_to_end(&mut decoded_data)
-            .map_err(Error::InvalidGzip)?;
+        let max_decoded_bytes = self.max_decompressed_request_bytes;
+        let decoded_data = match encoding {
+            // If the body is not compressed, return early.
+            ContentEncoding::Identity => return Ok(body),
+            // Decode the snappy block format, checking the decoded length
+            // encoded in the block header before allocating for it.
+            ContentEncoding::Snappy => {
+                let len = snap::raw::decompress_len(&body).map_err(Error::InvalidSnappy)?;
+                if len > max_decoded_bytes {
+                    return Err(Error::RequestSizeExceeded(max_decoded_bytes));
+                }
+                return snap::raw::Decoder::new()
+                    .decompress_vec(&body)
+                    .map(Into::into)
+                    .map_err(Error::InvalidSnappy);
+            }
+            ContentEncoding::Gzip => {
+                let decoder = flate2::read::GzDecoder::new(&body[..]);
+                read_limited(decoder, max_decoded_bytes).map_err(Error::InvalidGzip)?
+            }
+            ContentEncoding::Zstd => {
+                let decoder = zstd::stream::read::Decoder::with_buffer(&body[..])
+                    .map_err(Error::InvalidZstd)?;
+                read_limited(decoder, max_decoded_bytes).map_err(Error::InvalidZstd)?
+            }
+        };
 
         // If the length is max_size+1, the body is at least max_size+1 bytes in
         // length, and possibly longer, but truncated.
-        if decoded_data.len() > self.max_request_bytes {
-            return Err(Error::RequestSizeExceeded(self.max_request_bytes));
+        if decoded_data.len() > max_decoded_bytes {
+            return Err(Error::RequestSizeExceeded(max_decoded_bytes));
         }
 
         Ok(decoded_data.into())
     }
 }
 
+/// The `Content-Encoding` of a request body.
+#[derive(Debug, Clone, Copy)]
+enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+    Snappy,
+}
+
+/// Read at most `max_bytes + 1` bytes from the (decompressing) `decoder`.
+///
+/// Reading at most `max_bytes` prevents a decompression bomb based DoS.
+///
+/// In order to detect if the entire stream has been read, or truncated, an
+/// extra byte beyond the limit is read - the caller must check the resulting
+/// data length, see the max_request_size_truncation test.
+fn read_limited(decoder: impl std::io::Read, max_bytes: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut decoder = decoder.take(max_bytes as u64 + 1);
+    let mut decoded_data = Vec::new();
+    decoder.read_to_end(&mut decoded_data)?;
+    Ok(decoded_data)
+}
+
+/// Returns true if the `Content-Type` of `req` declares a JSON body.
+/// Build a response with the JSON representation of `value` as body.
+fn json_response(status: StatusCode, value: &impl serde::Serialize) -> Response<Body> {
+    let body = serde_json::to_vec(value).expect("response must serialise");
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+/// The summary of the write request `req` recorded in the audit log, or
+/// [`None`] if `req` is not a write request.
+fn write_summary(req: &Request<Body>) -> Option<&'static str> {
+    match (req.method(), req.uri().path()) {
+        (&Method::POST, "/write") => Some("v1 write"),
+        (&Method::POST, "/api/v2/write") => Some("v2 write"),
+        (&Method::POST, "/api/v2/prom/write") => Some("prometheus remote write"),
+        _ => None,
+    }
+}
+
+/// The authorization token of the write request `req`, which V1 writes may
+/// pass in the "p" query parameter instead of the authorization header.
+fn write_token(req: &Request<Body>) -> Option<Vec<u8>> {
+    buckets::request_token(req).or_else(|| {
+        serde_urlencoded::from_str::<Vec<(String, String)>>(req.uri().query()?)
+            .ok()?
+            .into_iter()
+            .find(|(k, _)| k == "p")
+            .map(|(_, v)| v.into_bytes())
+    })
+}
+
+/// The declared size of the (possibly compressed) body of `req`.
+fn content_length(req: &Request<Body>) -> u64 {
+    req.headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse().ok())
+        .unwrap_or_default()
+}
+
+fn is_json(req: &Request<Body>) -> bool {
+    req.headers()
+        .get(&CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        // Ignore any parameters, such as the charset.
+        .and_then(|v| v.split(';').next())
+        .map(|v| v.trim().eq_ignore_ascii_case("application/json"))
+        .unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use std::{io::Write, iter, sync::Arc, time::Duration};
 
     use assert_matches::assert_matches;
     use data_types::{
-        NamespaceId, NamespaceName, NamespaceNameError, OrgBucketMappingError, TableId,
+        NamespaceId, NamespaceName, NamespaceNameError, NamespaceSchema, OrgBucketMappingError,
+        TableId,
     };
     use flate2::{write::GzEncoder, Compression};
     use hyper::header::HeaderValue;
+    use iox_catalog::{
+        interface::{Catalog, SoftDeletedRows},
+        mem::MemCatalog,
+    };
     use metric::{Attributes, Metric};
     use mutable_batch::column::ColumnData;
     use mutable_batch_lp::LineWriteError;
@@ -505,9 +939,9 @@ mod tests {
     //
     ////////////////////////////////////////////////////////////////////////////
 
-    // Generate two HTTP handler tests - one for a plain request and one with a
-    // gzip-encoded body (and appropriate header), asserting the handler return
-    // value & write op.
+    // Generate HTTP handler tests - for a plain request, and with an identity,
+    // gzip-encoded and zstd-encoded body (and appropriate header), asserting
+    // the handler return value & write op.
     macro_rules! test_http_handler {
         (
             $name:ident,
@@ -518,7 +952,7 @@ mod tests {
             want_result = $want_result:pat,                 // Expected handler return value (as pattern)
             want_dml_calls = $($want_dml_calls:tt )+        // assert_matches slice pattern for expected DML calls
         ) => {
-            // Generate the three test cases by feed the same inputs, but varying
+            // Generate the four test cases by feed the same inputs, but varying
             // the encoding.
             test_http_handler!(
                 $name,
@@ -550,6 +984,16 @@ mod tests {
                 want_result = $want_result,
                 want_dml_calls = $($want_dml_calls)+
             );
+            test_http_handler!(
+                $name,
+                encoding=zstd,
+                uri = $uri,
+                body = $body,
+                dml_write_handler = $dml_write_handler,
+                dml_delete_handler = $dml_delete_handler,
+                want_result = $want_result,
+                want_dml_calls = $($want_dml_calls)+
+            );
         };
         // Actual test body generator.
         (
@@ -630,6 +1074,10 @@ mod tests {
             e.write_all(&$body).unwrap();
             e.finish().expect("failed to compress test body")
         }};
+        (encoding=zstd, $body:ident) => {{
+            // Apply zstd compression to the body
+            zstd::stream::encode_all(&$body[..], 0).expect("failed to compress test body")
+        }};
         (encoding_header=plain, $request:ident) => {};
         (encoding_header=identity, $request:ident) => {{
             // Set the identity content encoding
@@ -643,6 +1091,12 @@ mod tests {
                 .headers_mut()
                 .insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
         }};
+        (encoding_header=zstd, $request:ident) => {{
+            // Set the zstd content encoding
+            $request
+                .headers_mut()
+                .insert(CONTENT_ENCODING, HeaderValue::from_static("zstd"));
+        }};
     }
 
     // Wrapper over test_http_handler specifically for write requests.
@@ -884,6 +1338,22 @@ mod tests {
         }
     );
 
+    test_write_handler!(
+        rate_limited,
+        query_string = "?org=bananas&bucket=test",
+        body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
+        dml_handler = [Err(DmlError::RateLimited(RateLimitError::Exceeded {
+            namespace: NAMESPACE_NAME.to_string(),
+            limit: 1,
+            unit: "lines",
+            retry_after: Duration::from_secs(2),
+        }))],
+        want_result = Err(Error::DmlHandler(DmlError::RateLimited(_))),
+        want_dml_calls = [MockDmlHandlerCall::Write { namespace, .. }] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+        }
+    );
+
     test_write_handler!(
         field_upsert_within_batch,
         query_string = "?org=bananas&bucket=test",
@@ -1276,6 +1746,444 @@ mod tests {
         );
     }
 
+    /// Assert validation requests are parsed as v2 writes, and return a report
+    /// of the payload without writing it.
+    #[tokio::test]
+    async fn test_validate() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NamespaceId::new(42));
+
+        let request_unifier = Arc::new(MockWriteRequestUnifier::default().with_ret([Ok(
+            WriteParams {
+                namespace: NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                precision: Precision::default(),
+            },
+        )]));
+
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::new(Arc::clone(&request_unifier)),
+        );
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v3/validate")
+            .method("POST")
+            .body(Body::from(
+                "platanos val=42i 123456\nplatanos val=4.2 123456",
+            ))
+            .unwrap();
+        let got = delegate
+            .route(request)
+            .await
+            .expect("validation should succeed");
+
+        assert_eq!(got.status(), StatusCode::OK);
+        assert_eq!(got.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+        let body = hyper::body::to_bytes(got.into_body()).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["num_lines"], 2);
+        assert_eq!(report["line_errors"][0]["line"], 2);
+
+        assert_matches!(
+            request_unifier.calls().as_slice(),
+            [MockUnifyingParseCall::V2]
+        );
+        // Nothing is written.
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    /// Assert the lines of a write that exceed the namespace limits are
+    /// rejected, while the remaining lines are written.
+    #[tokio::test]
+    async fn test_write_partial() {
+        let namespace = NamespaceName::new(NAMESPACE_NAME).unwrap();
+        let schema = NamespaceSchema {
+            id: NAMESPACE_ID,
+            tables: Default::default(),
+            max_columns_per_table: 500,
+            max_tables: 1,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        };
+        let mock_namespace_resolver = MockNamespaceResolver::new(
+            [(namespace.clone(), Arc::new(schema))]
+                .into_iter()
+                .collect(),
+        );
+
+        let request_unifier = Arc::new(MockWriteRequestUnifier::default().with_ret([Ok(
+            WriteParams {
+                namespace,
+                precision: Precision::default(),
+            },
+        )]));
+
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::new(Arc::clone(&request_unifier)),
+        );
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .body(Body::from(
+                "platanos val=42i 123456\nbananas val=4i 123456\nplatanos val=24i 123457",
+            ))
+            .unwrap();
+        let err = delegate
+            .route(request)
+            .await
+            .expect_err("write should be partially rejected");
+
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+        assert_matches!(err.line_errors(), [diag] => {
+            assert_eq!(diag.line, 2);
+            assert!(diag.error.contains("couldn't create new table"));
+        });
+        assert_matches!(&err, Error::PartialWrite(e) => {
+            assert_eq!(e.num_lines, 3);
+        });
+
+        // The accepted lines are written.
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { write_input, .. }] => {
+            let mut tables = write_input.keys().collect::<Vec<_>>();
+            tables.sort_unstable();
+            assert_eq!(tables, ["platanos"]);
+            assert_eq!(write_input["platanos"].rows(), 2);
+        });
+        assert_metric_hit(&metrics, "http_write_lines", Some(2));
+    }
+
+    /// Assert compressed request bodies are limited by the decompressed size
+    /// limit once decompressed, independently of the (compressed) request
+    /// size limit.
+    #[tokio::test]
+    async fn test_max_decompressed_request_bytes() {
+        // A body of 2 * MAX_BYTES that compresses to well below MAX_BYTES.
+        let lp = iter::repeat("platanos,tag1=A,tag2=B val=42i 123456\n")
+            .take(2 * MAX_BYTES / 38 + 1)
+            .collect::<String>();
+        assert!(lp.len() > 2 * MAX_BYTES);
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(lp.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+        let zstd = zstd::stream::encode_all(lp.as_bytes(), 0).unwrap();
+
+        for (encoding, body) in [("gzip", gzip), ("zstd", zstd)] {
+            assert!(body.len() < MAX_BYTES);
+
+            let request = || {
+                Request::builder()
+                    .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+                    .method("POST")
+                    .header(CONTENT_ENCODING, encoding)
+                    .body(Body::from(body.clone()))
+                    .unwrap()
+            };
+            let delegate = |max_decompressed_bytes| {
+                let mock_namespace_resolver =
+                    MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+                let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+                HttpDelegate::new(
+                    MAX_BYTES,
+                    1,
+                    mock_namespace_resolver,
+                    dml_handler,
+                    &metric::Registry::default(),
+                    Box::<MultiTenantRequestUnifier>::default(),
+                )
+                .with_max_decompressed_request_bytes(max_decompressed_bytes)
+            };
+
+            // The decompressed body exceeds the limit.
+            let err = delegate(2 * MAX_BYTES)
+                .route(request())
+                .await
+                .expect_err("decompressed body should exceed the limit");
+            assert_matches!(err, Error::RequestSizeExceeded(n) => {
+                assert_eq!(n, 2 * MAX_BYTES, "{encoding}");
+            });
+
+            // The decompressed body is within the limit.
+            let got = delegate(4 * MAX_BYTES)
+                .route(request())
+                .await
+                .expect("write should succeed");
+            assert_eq!(got.status(), StatusCode::NO_CONTENT, "{encoding}");
+        }
+    }
+
+    /// Assert snappy-compressed Prometheus remote-write requests are decoded
+    /// and written to a table per metric.
+    #[tokio::test]
+    async fn test_prometheus_write() {
+        use generated_types::prometheus::{Label, Sample, TimeSeries, WriteRequest};
+        use prost::Message;
+
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let body = WriteRequest {
+            timeseries: vec![TimeSeries {
+                labels: vec![
+                    Label {
+                        name: "__name__".to_string(),
+                        value: "up".to_string(),
+                    },
+                    Label {
+                        name: "job".to_string(),
+                        value: "bananas".to_string(),
+                    },
+                ],
+                samples: vec![
+                    Sample {
+                        value: 1.0,
+                        timestamp: 1,
+                    },
+                    Sample {
+                        value: 0.0,
+                        timestamp: 2,
+                    },
+                ],
+            }],
+        }
+        .encode_to_vec();
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&body)
+            .expect("failed to compress test body");
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/prom/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_ENCODING, "snappy")
+            .body(Body::from(body))
+            .unwrap();
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::NO_CONTENT);
+
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+            assert_eq!(write_input.keys().collect::<Vec<_>>(), ["up"]);
+            assert_eq!(write_input["up"].rows(), 2);
+        });
+        assert_metric_hit(&metrics, "http_write_lines", Some(2));
+    }
+
+    /// Assert write requests with a JSON content type are decoded as JSON
+    /// points, using the precision of the request.
+    #[tokio::test]
+    async fn test_json_write() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let body = r#"[
+            { "measurement": "cpu", "tags": { "host": "a" }, "fields": { "usage": 4.2 }, "time": 1 },
+            { "measurement": "cpu", "fields": { "cores": { "integer": 8 } }, "time": 2 }
+        ]"#;
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test&precision=s")
+            .method("POST")
+            .header(CONTENT_TYPE, "application/json; charset=utf-8")
+            .body(Body::from(body))
+            .unwrap();
+        let got = delegate.route(request).await.expect("write should succeed");
+        assert_eq!(got.status(), StatusCode::NO_CONTENT);
+
+        assert_matches!(dml_handler.calls().as_slice(), [MockDmlHandlerCall::Write { namespace, write_input, .. }] => {
+            assert_eq!(namespace, NAMESPACE_NAME);
+            assert_eq!(write_input.keys().collect::<Vec<_>>(), ["cpu"]);
+            assert_matches!(write_input["cpu"].column("time").unwrap().data(), ColumnData::I64(v, _) => {
+                assert_eq!(v, &[1_000_000_000, 2_000_000_000]);
+            });
+        });
+        assert_metric_hit(&metrics, "http_write_lines", Some(2));
+
+        // A malformed JSON body is rejected without writing.
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from("cpu usage=4.2"))
+            .unwrap();
+        let err = delegate
+            .route(request)
+            .await
+            .expect_err("invalid json should fail");
+        assert_matches!(err, Error::ParseJson(json::Error::Decode(_)));
+        assert_eq!(err.as_status_code(), StatusCode::BAD_REQUEST);
+        assert_eq!(dml_handler.calls().len(), 1);
+    }
+
+    /// Assert the V2 buckets API is only served when enabled, creating and
+    /// listing namespaces.
+    #[tokio::test]
+    async fn test_buckets() {
+        let mock_namespace_resolver = MockNamespaceResolver::default();
+        let dml_handler = Arc::new(MockDmlHandler::default());
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        );
+
+        let list_request = || {
+            Request::builder()
+                .uri("https://bananas.example/api/v2/buckets?org=bananas")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap()
+        };
+        let err = delegate
+            .route(list_request())
+            .await
+            .expect_err("buckets API is disabled");
+        assert_matches!(err, Error::NoHandler);
+
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let delegate = delegate.with_buckets(BucketsApi::new(
+            Arc::clone(&catalog),
+            buckets::BucketTenancy::MultiTenant,
+            None,
+        ));
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/buckets")
+            .method("POST")
+            .body(Body::from(r#"{"orgID": "bananas", "name": "test"}"#))
+            .unwrap();
+        let got = delegate
+            .route(request)
+            .await
+            .expect("create should succeed");
+        assert_eq!(got.status(), StatusCode::CREATED);
+        assert!(catalog
+            .repositories()
+            .await
+            .namespaces()
+            .get_by_name(NAMESPACE_NAME, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .is_some());
+
+        let got = delegate
+            .route(list_request())
+            .await
+            .expect("list should succeed");
+        assert_eq!(got.status(), StatusCode::OK);
+        assert_eq!(got.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+        let body = hyper::body::to_bytes(got.into_body()).await.unwrap();
+        let list: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(list["buckets"][0]["name"], "test");
+        assert_eq!(list["buckets"][0]["orgID"], "bananas");
+        assert_eq!(list["buckets"].as_array().unwrap().len(), 1);
+        assert!(dml_handler.calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let mock_namespace_resolver =
+            MockNamespaceResolver::default().with_mapping(NAMESPACE_NAME, NAMESPACE_ID);
+        let dml_handler = Arc::new(MockDmlHandler::default().with_write_return([Ok(())]));
+        let metrics = Arc::new(metric::Registry::default());
+        let sink = Arc::new(audit::mock::MockAuditSink::default());
+        let delegate = HttpDelegate::new(
+            MAX_BYTES,
+            1,
+            mock_namespace_resolver,
+            Arc::clone(&dml_handler),
+            &metrics,
+            Box::<MultiTenantRequestUnifier>::default(),
+        )
+        .with_audit_log(AuditLog::new("router", Arc::clone(&sink) as _, &metrics));
+
+        let body = "platanos,tag1=A,tag2=B val=42i 123456";
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write?org=bananas&bucket=test")
+            .method("POST")
+            .header(CONTENT_LENGTH, body.len())
+            .extension(authz::http::AuthorizationHeaderExtension::new(Some(
+                HeaderValue::from_static("Token GOOD"),
+            )))
+            .body(Body::from(body))
+            .unwrap();
+        delegate.route(request).await.expect("write should succeed");
+
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/write")
+            .method("POST")
+            .body(Body::from(body))
+            .unwrap();
+        delegate
+            .route(request)
+            .await
+            .expect_err("write without bucket should fail");
+
+        // Other requests are not recorded.
+        let request = Request::builder()
+            .uri("https://bananas.example/api/v2/buckets?org=bananas")
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        delegate
+            .route(request)
+            .await
+            .expect_err("buckets API is disabled");
+
+        let records = sink.wait_for_records(2).await;
+        assert_matches!(records.as_slice(), [written, failed] => {
+            assert_eq!(written.operation, Operation::Write);
+            assert_eq!(written.summary, "v2 write");
+            assert_eq!(written.namespace.as_deref(), Some(NAMESPACE_NAME));
+            assert_eq!(written.identity, Some(audit::identity(b"GOOD")));
+            assert_eq!(written.bytes, body.len() as u64);
+            assert_eq!(written.status, audit::Status::Ok);
+
+            assert_eq!(failed.namespace, None);
+            assert_eq!(failed.identity, None);
+            assert_eq!(failed.status, audit::Status::Error);
+            assert!(failed.error.is_some());
+        });
+    }
+
     // The display text of Error gets passed through `ioxd_router::IoxHttpErrorAdaptor` then
     // `ioxd_common::http::error::HttpApiError` as the JSON "message" value in error response
     // bodies. These are fixture tests to document error messages that users might see when
@@ -1367,6 +2275,11 @@ mod tests {
             "error decoding gzip stream: [io Error]",
         ),
 
+        (
+            InvalidZstd(std::io::Error::new(std::io::ErrorKind::Other, "[io Error]")),
+            "error decoding zstd stream: [io Error]",
+        ),
+
         (
             ParseLineProtocol(mutable_batch_lp::Error::LineProtocol {
                 source: influxdb_line_protocol::Error::FieldSetMissing,
@@ -1410,6 +2323,17 @@ mod tests {
             "failed to parse line protocol: timestamp overflows i64",
         ),
 
+        (
+            ParseJson(json::Error::EmptyPayload),
+            "failed to parse json write request: empty write payload",
+        ),
+
+        (
+            ParsePrometheus(prometheus::Error::NoMetricName),
+            "failed to parse prometheus remote-write request: \
+            time series has no __name__ label",
+        ),
+
         (
             DmlHandler(DmlError::NamespaceNotFound("[namespace name]".into())),
             "dml handler error: namespace [namespace name] does not exist",
@@ -1431,6 +2355,13 @@ mod tests {
             "rejecting write due to non-existing namespace: bananas",
         ),
 
+        (
+            NamespaceResolver(
+                crate::namespace_resolver::Error::SoftDeleted("bananas".to_string())
+            ),
+            "namespace bananas has been deleted",
+        ),
+
         (
             RequestLimit,
             "this service is overloaded, please try again later",
@@ -1446,6 +2377,17 @@ mod tests {
             "access denied",
         ),
 
+        (
+            PartialWrite(validate::RejectedLines {
+                num_lines: 3,
+                line_errors: vec![validate::LineDiagnostic {
+                    line: 2,
+                    error: "[line error]".to_string(),
+                }],
+            }),
+            "partial write: 1 of 3 lines rejected, first error on line 2: [line error]",
+        ),
+
         (
             DmlHandler(DmlError::Schema(SchemaError::ServiceLimit(Box::new(CachedServiceProtectionLimit::Column {
                 table_name: "bananas".to_string(),
@@ -1517,5 +2459,11 @@ mod tests {
             MultiTenantError(MultiTenantExtractError::ParseV2Request(V2WriteParseError::NoQueryParams)),
             "no org/bucket destination provided",
         ),
+
+        // A buckets API error
+        (
+            Buckets(BucketsError::AlreadyExists("bananas".to_string())),
+            "bucket with name bananas already exists",
+        ),
     }
 }
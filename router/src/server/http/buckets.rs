@@ -0,0 +1,521 @@
+//! A minimal implementation of the [V2 Buckets API], mapping buckets onto
+//! namespaces.
+//!
+//! Only listing and creating buckets is supported, which is enough for tooling
+//! that checks for or provisions a bucket before writing to it to work
+//! unmodified. Buckets map to namespaces as for the V2 write API:
+//!
+//!   * Multi-tenant: the namespace is named `{org}_{bucket}`. Org IDs and org
+//!     names are treated alike.
+//!   * Single-tenant: the namespace is named after the bucket only (the org is
+//!     discarded).
+//!
+//! If an [`Authorizer`] is configured, only buckets the request token is
+//! allowed to read the schema of are listed, and creating a bucket requires
+//! the permission to create its namespace.
+//!
+//! The ID of a bucket is the hex encoded ID of its namespace.
+//!
+//! [V2 Buckets API]:
+//!     https://docs.influxdata.com/influxdb/v2.6/api/#tag/Buckets
+
+use std::sync::Arc;
+
+use authz::{
+    extract_token, http::AuthorizationHeaderExtension, Action, Authorizer, Permission, Resource,
+};
+use data_types::{Namespace, NamespaceName, NamespaceNameError, OrgBucketMappingError};
+use hyper::{Body, Request, StatusCode};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Errors returned by the buckets API.
+#[derive(Debug, Error)]
+pub enum BucketsError {
+    /// The query string of a list request could not be decoded.
+    #[error("failed to decode query string: {0}")]
+    DecodeParams(serde_urlencoded::de::Error),
+
+    /// The body of a create request is not a valid bucket definition.
+    #[error("invalid bucket definition: {0}")]
+    DecodeBody(serde_json::Error),
+
+    /// Buckets can only be listed for a given org in multi-tenant mode.
+    #[error("missing org value")]
+    NoOrgSpecified,
+
+    /// A failure to map a org & bucket to a reasonable [`NamespaceName`].
+    #[error(transparent)]
+    InvalidOrgAndBucket(#[from] OrgBucketMappingError),
+
+    /// The bucket name is not a valid namespace name.
+    #[error(transparent)]
+    InvalidBucket(#[from] NamespaceNameError),
+
+    /// The retention period is negative or out of range.
+    #[error("invalid retention period: {0} seconds")]
+    InvalidRetention(i64),
+
+    /// A namespace with the name of the bucket already exists.
+    #[error("bucket with name {0} already exists")]
+    AlreadyExists(String),
+
+    /// The catalog request failed.
+    #[error("catalog error: {0}")]
+    Catalog(iox_catalog::interface::Error),
+
+    /// An error occurred verifying the authorization token.
+    #[error(transparent)]
+    Authorizer(authz::Error),
+}
+
+impl From<&BucketsError> for StatusCode {
+    fn from(value: &BucketsError) -> Self {
+        match value {
+            BucketsError::DecodeParams(_)
+            | BucketsError::DecodeBody(_)
+            | BucketsError::NoOrgSpecified
+            | BucketsError::InvalidOrgAndBucket(_)
+            | BucketsError::InvalidBucket(_)
+            | BucketsError::InvalidRetention(_) => Self::BAD_REQUEST,
+            // https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostBuckets
+            BucketsError::AlreadyExists(_) => Self::UNPROCESSABLE_ENTITY,
+            BucketsError::Catalog(_) => Self::INTERNAL_SERVER_ERROR,
+            BucketsError::Authorizer(e) => match e {
+                authz::Error::Forbidden => Self::FORBIDDEN,
+                authz::Error::NoToken => Self::UNAUTHORIZED,
+                _ => Self::FORBIDDEN,
+            },
+        }
+    }
+}
+
+/// How buckets map onto namespaces, which must match the
+/// [`WriteRequestUnifier`] used for writes.
+///
+/// [`WriteRequestUnifier`]: super::write::WriteRequestUnifier
+#[derive(Debug)]
+pub enum BucketTenancy {
+    /// Namespaces are named `{org}_{bucket}`, as for the
+    /// [`MultiTenantRequestUnifier`](super::write::multi_tenant::MultiTenantRequestUnifier).
+    MultiTenant,
+
+    /// Namespaces are named after the bucket, as for the
+    /// [`SingleTenantRequestUnifier`](super::write::single_tenant::SingleTenantRequestUnifier).
+    SingleTenant,
+}
+
+/// A bucket, as returned by the V2 API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bucket {
+    id: String,
+    name: String,
+    #[serde(rename = "orgID")]
+    org_id: String,
+    #[serde(rename = "type")]
+    bucket_type: &'static str,
+    retention_rules: Vec<RetentionRule>,
+}
+
+impl Bucket {
+    fn new(namespace: &Namespace, name: &str, org: &str) -> Self {
+        Self {
+            id: format!("{:016x}", namespace.id.get()),
+            name: name.to_string(),
+            org_id: org.to_string(),
+            bucket_type: "user",
+            // A retention period of 0 seconds represents infinite retention.
+            retention_rules: vec![RetentionRule {
+                rule_type: RetentionRule::expire(),
+                every_seconds: namespace.retention_period_ns.unwrap_or_default() / 1_000_000_000,
+            }],
+        }
+    }
+}
+
+/// A list of buckets, as returned by the V2 API.
+#[derive(Debug, Serialize)]
+pub struct Buckets {
+    buckets: Vec<Bucket>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RetentionRule {
+    #[serde(rename = "type", default = "RetentionRule::expire")]
+    rule_type: String,
+    every_seconds: i64,
+}
+
+impl RetentionRule {
+    fn expire() -> String {
+        "expire".to_string()
+    }
+}
+
+/// The query string of a list request.
+#[derive(Debug, Deserialize)]
+struct ListParams {
+    org: Option<String>,
+    #[serde(rename = "orgID")]
+    org_id: Option<String>,
+    name: Option<String>,
+}
+
+/// The body of a create request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostBucket {
+    name: String,
+    org: Option<String>,
+    #[serde(rename = "orgID")]
+    org_id: Option<String>,
+    #[serde(default)]
+    retention_rules: Vec<RetentionRule>,
+}
+
+/// Serves the V2 buckets API from the namespaces in the catalog.
+#[derive(Debug)]
+pub struct BucketsApi {
+    catalog: Arc<dyn Catalog>,
+    tenancy: BucketTenancy,
+    authz: Option<Arc<dyn Authorizer>>,
+}
+
+impl BucketsApi {
+    /// Initialise a new [`BucketsApi`], mapping buckets onto the namespaces in
+    /// `catalog` according to `tenancy` and authorising requests with `authz`,
+    /// if any.
+    pub fn new(
+        catalog: Arc<dyn Catalog>,
+        tenancy: BucketTenancy,
+        authz: Option<Arc<dyn Authorizer>>,
+    ) -> Self {
+        Self {
+            catalog,
+            tenancy,
+            authz,
+        }
+    }
+
+    /// List the buckets of the org given in the query string of `req`,
+    /// optionally filtered by bucket name.
+    ///
+    /// If authorization is enabled, only the buckets the request token is
+    /// allowed to read the schema of are listed.
+    pub(crate) async fn list(&self, req: &Request<Body>) -> Result<Buckets, BucketsError> {
+        let params: ListParams = serde_urlencoded::from_str(req.uri().query().unwrap_or_default())
+            .map_err(BucketsError::DecodeParams)?;
+        let org = params.org.or(params.org_id).unwrap_or_default();
+
+        let namespaces = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .list(SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(BucketsError::Catalog)?;
+
+        // The namespaces of the requested org, and the bucket names they map to.
+        let mut candidates = match &self.tenancy {
+            BucketTenancy::MultiTenant => {
+                if org.is_empty() {
+                    return Err(BucketsError::NoOrgSpecified);
+                }
+                let prefix = format!("{org}_");
+                namespaces
+                    .iter()
+                    .filter_map(|ns| Some((ns, ns.name.strip_prefix(&prefix)?)))
+                    .collect::<Vec<_>>()
+            }
+            BucketTenancy::SingleTenant => namespaces
+                .iter()
+                .map(|ns| (ns, ns.name.as_str()))
+                .collect::<Vec<_>>(),
+        };
+
+        if let Some(name) = &params.name {
+            candidates.retain(|(_, bucket)| bucket == name);
+        }
+
+        if let Some(authz) = &self.authz {
+            let perms = candidates
+                .iter()
+                .map(|(ns, _)| read_schema_permission(&ns.name))
+                .collect::<Vec<_>>();
+            let permitted = match authz.permissions(request_token(req), &perms).await {
+                Ok(permitted) => permitted,
+                // The token is valid, but not for any of the namespaces (if
+                // any).
+                Err(authz::Error::Forbidden) => vec![],
+                Err(e) => return Err(BucketsError::Authorizer(e)),
+            };
+            candidates.retain(|(ns, _)| permitted.contains(&read_schema_permission(&ns.name)));
+        }
+
+        let mut buckets = candidates
+            .into_iter()
+            .map(|(ns, name)| Bucket::new(ns, name, &org))
+            .collect::<Vec<_>>();
+        buckets.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Buckets { buckets })
+    }
+
+    /// Create the bucket defined by the JSON `body` of a request, authorised
+    /// by `token` if authorization is enabled.
+    ///
+    /// Only the first retention rule of the bucket is respected, as a
+    /// namespace has a single retention period.
+    pub(crate) async fn create(
+        &self,
+        token: Option<Vec<u8>>,
+        body: &[u8],
+    ) -> Result<Bucket, BucketsError> {
+        let PostBucket {
+            name,
+            org,
+            org_id,
+            retention_rules,
+        } = serde_json::from_slice(body).map_err(BucketsError::DecodeBody)?;
+        let org = org_id.or(org).unwrap_or_default();
+
+        let namespace = match &self.tenancy {
+            BucketTenancy::MultiTenant => NamespaceName::from_org_and_bucket(&org, &name)?,
+            BucketTenancy::SingleTenant => NamespaceName::new(name.clone())?,
+        };
+
+        if let Some(authz) = &self.authz {
+            let perms = [Permission::ResourceAction(
+                Resource::Database(namespace.to_string()),
+                Action::Create,
+            )];
+            authz
+                .permissions(token, &perms)
+                .await
+                .map_err(BucketsError::Authorizer)?;
+        }
+
+        let retention_period_ns = match retention_rules.first().map(|r| r.every_seconds) {
+            None | Some(0) => None,
+            Some(secs) if secs > 0 => Some(
+                secs.checked_mul(1_000_000_000)
+                    .ok_or(BucketsError::InvalidRetention(secs))?,
+            ),
+            Some(secs) => return Err(BucketsError::InvalidRetention(secs)),
+        };
+
+        let ns = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .create(&namespace, None, retention_period_ns, None)
+            .await
+            .map_err(|e| match e {
+                iox_catalog::interface::Error::NameExists { .. } => {
+                    BucketsError::AlreadyExists(name.clone())
+                }
+                e => BucketsError::Catalog(e),
+            })?;
+
+        Ok(Bucket::new(&ns, &name, &org))
+    }
+}
+
+/// Extract the authorization token of `req`, if any.
+pub(crate) fn request_token(req: &Request<Body>) -> Option<Vec<u8>> {
+    extract_token(
+        req.extensions()
+            .get::<AuthorizationHeaderExtension>()
+            .and_then(|v| v.as_ref()),
+    )
+}
+
+fn read_schema_permission(namespace: &str) -> Permission {
+    Permission::ResourceAction(
+        Resource::Database(namespace.to_string()),
+        Action::ReadSchema,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use hyper::header::HeaderValue;
+    use iox_catalog::mem::MemCatalog;
+
+    use super::*;
+    use crate::server::http::write::single_tenant::auth::mock::{
+        MockAuthorizer, MOCK_AUTH_NO_PERMS_TOKEN, MOCK_AUTH_VALID_TOKEN,
+    };
+
+    fn catalog() -> Arc<dyn Catalog> {
+        Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())))
+    }
+
+    fn list_request(query: &str, token: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder()
+            .uri(format!("https://bananas.example/api/v2/buckets{query}"))
+            .method("GET")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(AuthorizationHeaderExtension::new(
+                token.map(|t| HeaderValue::from_str(&format!("Token {t}")).unwrap()),
+            ));
+        request
+    }
+
+    fn names(buckets: &Buckets) -> Vec<&str> {
+        buckets.buckets.iter().map(|b| b.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_multi_tenant() {
+        let api = BucketsApi::new(catalog(), BucketTenancy::MultiTenant, None);
+
+        let bucket = api
+            .create(
+                None,
+                br#"{"orgID": "bananas", "name": "platanos", "retentionRules": [{"type": "expire", "everySeconds": 3600}]}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(bucket.name, "platanos");
+        assert_eq!(bucket.org_id, "bananas");
+        assert_eq!(bucket.retention_rules[0].every_seconds, 3600);
+
+        api.create(None, br#"{"org": "bananas", "name": "plantains"}"#)
+            .await
+            .unwrap();
+        api.create(None, br#"{"org": "apples", "name": "platanos"}"#)
+            .await
+            .unwrap();
+
+        let got = api
+            .create(None, br#"{"org": "bananas", "name": "platanos"}"#)
+            .await;
+        assert_matches!(got, Err(BucketsError::AlreadyExists(name)) => {
+            assert_eq!(name, "platanos");
+        });
+
+        let got = api.list(&list_request("?org=bananas", None)).await.unwrap();
+        assert_eq!(names(&got), ["plantains", "platanos"]);
+        let got = api
+            .list(&list_request("?orgID=bananas&name=platanos", None))
+            .await
+            .unwrap();
+        assert_eq!(names(&got), ["platanos"]);
+
+        let got = api.list(&list_request("", None)).await;
+        assert_matches!(got, Err(BucketsError::NoOrgSpecified));
+
+        let got = api.create(None, br#"{"name": "platanos"}"#).await;
+        assert_matches!(
+            got,
+            Err(BucketsError::InvalidOrgAndBucket(
+                OrgBucketMappingError::NoOrgBucketSpecified
+            ))
+        );
+
+        let got = api
+            .create(
+                None,
+                br#"{"org": "bananas", "name": "negative", "retentionRules": [{"everySeconds": -1}]}"#,
+            )
+            .await;
+        assert_matches!(got, Err(BucketsError::InvalidRetention(-1)));
+    }
+
+    #[tokio::test]
+    async fn test_single_tenant() {
+        let api = BucketsApi::new(
+            catalog(),
+            BucketTenancy::SingleTenant,
+            Some(Arc::new(MockAuthorizer::default())),
+        );
+
+        let got = api.create(None, br#"{"name": "platanos"}"#).await;
+        assert_matches!(got, Err(BucketsError::Authorizer(authz::Error::NoToken)));
+        let got = api
+            .create(
+                Some(MOCK_AUTH_NO_PERMS_TOKEN.into()),
+                br#"{"name": "platanos"}"#,
+            )
+            .await;
+        assert_matches!(got, Err(BucketsError::Authorizer(authz::Error::Forbidden)));
+
+        let bucket = api
+            .create(
+                Some(MOCK_AUTH_VALID_TOKEN.into()),
+                br#"{"org": "ignored", "name": "platanos"}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(bucket.name, "platanos");
+        assert_eq!(bucket.retention_rules[0].every_seconds, 0);
+
+        let got = api
+            .list(&list_request("", Some(MOCK_AUTH_VALID_TOKEN)))
+            .await
+            .unwrap();
+        assert_eq!(names(&got), ["platanos"]);
+
+        // Buckets the token has no permissions for are not listed.
+        let got = api
+            .list(&list_request("", Some(MOCK_AUTH_NO_PERMS_TOKEN)))
+            .await
+            .unwrap();
+        assert!(got.buckets.is_empty());
+
+        let got = api.list(&list_request("", None)).await;
+        assert_matches!(got, Err(BucketsError::Authorizer(authz::Error::NoToken)));
+    }
+
+    #[tokio::test]
+    async fn test_multi_tenant_authz() {
+        let api = BucketsApi::new(
+            catalog(),
+            BucketTenancy::MultiTenant,
+            Some(Arc::new(MockAuthorizer::default())),
+        );
+
+        let got = api
+            .create(
+                Some(MOCK_AUTH_NO_PERMS_TOKEN.into()),
+                br#"{"org": "bananas", "name": "platanos"}"#,
+            )
+            .await;
+        assert_matches!(got, Err(BucketsError::Authorizer(authz::Error::Forbidden)));
+
+        api.create(
+            Some(MOCK_AUTH_VALID_TOKEN.into()),
+            br#"{"org": "bananas", "name": "platanos"}"#,
+        )
+        .await
+        .unwrap();
+
+        let got = api
+            .list(&list_request("?org=bananas", Some(MOCK_AUTH_VALID_TOKEN)))
+            .await
+            .unwrap();
+        assert_eq!(names(&got), ["platanos"]);
+
+        let got = api
+            .list(&list_request(
+                "?org=bananas",
+                Some(MOCK_AUTH_NO_PERMS_TOKEN),
+            ))
+            .await
+            .unwrap();
+        assert!(got.buckets.is_empty());
+
+        let got = api.list(&list_request("?org=bananas", None)).await;
+        assert_matches!(got, Err(BucketsError::Authorizer(authz::Error::NoToken)));
+    }
+}
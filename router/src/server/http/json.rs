@@ -0,0 +1,341 @@
+//! Conversion of JSON write request bodies into [`MutableBatch`].
+//!
+//! A JSON write request body is an array of points, each an object of the
+//! form:
+//!
+//! ```json
+//! {
+//!     "measurement": "cpu",
+//!     "tags": { "host": "bananas" },
+//!     "fields": {
+//!         "usage": 42.5,
+//!         "cores": { "integer": 8 },
+//!         "uptime": { "unsigned": 1234 },
+//!         "healthy": true,
+//!         "state": "running"
+//!     },
+//!     "time": 1690000000
+//! }
+//! ```
+//!
+//! The `tags` and `time` keys are optional, and `fields` must contain at least
+//! one field. Field values are mapped to column types following the line
+//! protocol conventions:
+//!
+//! | JSON value                 | Column type |
+//! |----------------------------|-------------|
+//! | number                     | float       |
+//! | `{ "integer": <number> }`  | integer     |
+//! | `{ "unsigned": <number> }` | unsigned    |
+//! | string                     | string      |
+//! | boolean                    | boolean     |
+//!
+//! Timestamps are interpreted in the precision of the write request, and
+//! points without a timestamp are assigned the time the request is received.
+
+use std::{collections::BTreeMap, iter};
+
+use hashbrown::HashMap;
+use mutable_batch::{writer::Writer, MutableBatch};
+use serde::Deserialize;
+use thiserror::Error;
+
+/// The column the point timestamps are written to.
+const TIME_COLUMN: &str = "time";
+
+/// Errors converting a JSON write request body into [`MutableBatch`].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The body is not a valid JSON array of points.
+    #[error("invalid json body: {0}")]
+    Decode(serde_json::Error),
+
+    /// The body contains no points.
+    #[error("empty write payload")]
+    EmptyPayload,
+
+    /// A point cannot be written.
+    #[error("error writing point {index}: {source}")]
+    Point {
+        /// The zero-based index of the point in the request body.
+        index: usize,
+        /// The reason the point cannot be written.
+        source: PointError,
+    },
+}
+
+/// The reasons an individual point in a JSON write request cannot be written.
+#[derive(Debug, Error)]
+pub enum PointError {
+    /// The point has an empty measurement name.
+    #[error("measurement name is empty")]
+    EmptyMeasurement,
+
+    /// The point has no fields.
+    #[error("point has no fields")]
+    NoFields,
+
+    /// The point has a tag or field named after the time column.
+    #[error("column name {TIME_COLUMN} is reserved")]
+    ReservedName,
+
+    /// The point has a tag and a field with the same name.
+    #[error("{0} is specified as both a tag and a field")]
+    DuplicateName(String),
+
+    /// The timestamp of the point cannot be represented in nanoseconds.
+    #[error("timestamp {0} overflows i64")]
+    TimestampOverflow(i64),
+
+    /// The point conflicts with the types of the preceding points of the same
+    /// measurement.
+    #[error(transparent)]
+    Write(mutable_batch::writer::Error),
+}
+
+/// Statistics describing a converted JSON write request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteStats {
+    /// The number of points in the request.
+    pub num_points: usize,
+    /// The number of fields in the request.
+    pub num_fields: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Point {
+    measurement: String,
+    #[serde(default)]
+    tags: BTreeMap<String, String>,
+    fields: BTreeMap<String, FieldValue>,
+    time: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase", deny_unknown_fields)]
+enum TypedNumber {
+    Integer(i64),
+    Unsigned(u64),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum FieldValue {
+    Bool(bool),
+    Float(f64),
+    String(String),
+    Typed(TypedNumber),
+}
+
+/// Decode the JSON array of points in `body`, and convert it into a set of
+/// [`MutableBatch`] keyed by measurement name.
+///
+/// Point timestamps are multiplied by `timestamp_base` to convert them to
+/// nanoseconds, and points without a timestamp are assigned `default_time`.
+///
+/// The first point that cannot be written fails the whole request.
+pub fn decode(
+    body: &[u8],
+    default_time: i64,
+    timestamp_base: i64,
+) -> Result<(HashMap<String, MutableBatch>, WriteStats), Error> {
+    let points: Vec<Point> = serde_json::from_slice(body).map_err(Error::Decode)?;
+    if points.is_empty() {
+        return Err(Error::EmptyPayload);
+    }
+
+    let mut batches: HashMap<String, MutableBatch> = HashMap::new();
+    let mut stats = WriteStats::default();
+    for (index, point) in points.into_iter().enumerate() {
+        let time = match point.time {
+            Some(t) => t
+                .checked_mul(timestamp_base)
+                .ok_or(PointError::TimestampOverflow(t)),
+            None => Ok(default_time),
+        };
+
+        time.and_then(|time| {
+            if point.measurement.is_empty() {
+                return Err(PointError::EmptyMeasurement);
+            }
+            let batch = batches.entry_ref(point.measurement.as_str()).or_default();
+            write_point(batch, &point, time)
+        })
+        .map_err(|source| Error::Point { index, source })?;
+
+        stats.num_points += 1;
+        stats.num_fields += point.fields.len();
+    }
+
+    Ok((batches, stats))
+}
+
+/// Append `point` to `batch` with the timestamp `time`, leaving `batch`
+/// unchanged if it cannot be written.
+fn write_point(batch: &mut MutableBatch, point: &Point, time: i64) -> Result<(), PointError> {
+    if point.fields.is_empty() {
+        return Err(PointError::NoFields);
+    }
+    if point.tags.contains_key(TIME_COLUMN) || point.fields.contains_key(TIME_COLUMN) {
+        return Err(PointError::ReservedName);
+    }
+    if let Some(name) = point.tags.keys().find(|k| point.fields.contains_key(*k)) {
+        return Err(PointError::DuplicateName(name.clone()));
+    }
+
+    let mut writer = Writer::new(batch, 1);
+    for (name, value) in &point.tags {
+        writer
+            .write_tag(name, None, iter::once(value.as_str()))
+            .map_err(PointError::Write)?;
+    }
+    for (name, value) in &point.fields {
+        match value {
+            FieldValue::Bool(v) => writer.write_bool(name, None, iter::once(*v)),
+            FieldValue::Float(v) => writer.write_f64(name, None, iter::once(*v)),
+            FieldValue::String(v) => writer.write_string(name, None, iter::once(v.as_str())),
+            FieldValue::Typed(TypedNumber::Integer(v)) => {
+                writer.write_i64(name, None, iter::once(*v))
+            }
+            FieldValue::Typed(TypedNumber::Unsigned(v)) => {
+                writer.write_u64(name, None, iter::once(*v))
+            }
+        }
+        .map_err(PointError::Write)?;
+    }
+    writer
+        .write_time(TIME_COLUMN, iter::once(time))
+        .map_err(PointError::Write)?;
+
+    writer.commit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use mutable_batch::column::ColumnData;
+
+    use super::*;
+
+    const DEFAULT_TIME: i64 = 42;
+
+    #[test]
+    fn test_decode() {
+        let body = r#"[
+            {
+                "measurement": "cpu",
+                "tags": { "host": "bananas" },
+                "fields": {
+                    "usage": 42.5,
+                    "cores": { "integer": 8 },
+                    "uptime": { "unsigned": 1234 },
+                    "healthy": true,
+                    "state": "running"
+                },
+                "time": 1
+            },
+            { "measurement": "cpu", "fields": { "usage": 1 } },
+            { "measurement": "mem", "fields": { "free": 2.0 }, "time": 3 }
+        ]"#;
+
+        let (batches, stats) = decode(body.as_bytes(), DEFAULT_TIME, 1_000).unwrap();
+        assert_eq!(
+            stats,
+            WriteStats {
+                num_points: 3,
+                num_fields: 7
+            }
+        );
+
+        let mut tables = batches.keys().collect::<Vec<_>>();
+        tables.sort_unstable();
+        assert_eq!(tables, ["cpu", "mem"]);
+
+        let batch = &batches["cpu"];
+        assert_eq!(batch.rows(), 2);
+        assert_eq!(
+            batch.column_names().into_iter().collect::<Vec<_>>(),
+            ["cores", "healthy", "host", "state", "time", "uptime", "usage"]
+        );
+        assert_matches!(batch.column("usage").unwrap().data(), ColumnData::F64(v, _) => {
+            assert_eq!(v, &[42.5, 1.0]);
+        });
+        assert_matches!(batch.column("cores").unwrap().data(), ColumnData::I64(v, _) => {
+            assert_eq!(v[0], 8);
+        });
+        assert_matches!(batch.column("uptime").unwrap().data(), ColumnData::U64(v, _) => {
+            assert_eq!(v[0], 1234);
+        });
+        assert_matches!(
+            batch.column("healthy").unwrap().data(),
+            ColumnData::Bool(_, _)
+        );
+        assert_matches!(
+            batch.column("state").unwrap().data(),
+            ColumnData::String(_, _)
+        );
+        // Timestamps are scaled by the precision, or default to the current
+        // time.
+        assert_matches!(batch.column("time").unwrap().data(), ColumnData::I64(v, _) => {
+            assert_eq!(v, &[1_000, DEFAULT_TIME]);
+        });
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_matches!(decode(b"{}", DEFAULT_TIME, 1), Err(Error::Decode(_)));
+        assert_matches!(
+            decode(
+                br#"[{ "measurement": "cpu", "fields": { "v": 1 }, "bananas": 1 }]"#,
+                DEFAULT_TIME,
+                1
+            ),
+            Err(Error::Decode(_))
+        );
+        assert_matches!(decode(b"[]", DEFAULT_TIME, 1), Err(Error::EmptyPayload));
+
+        let point_err = |body: &str| match decode(body.as_bytes(), DEFAULT_TIME, 1_000) {
+            Err(Error::Point { index, source }) => (index, source),
+            v => panic!("unexpected result: {v:?}"),
+        };
+
+        assert_matches!(
+            point_err(r#"[{ "measurement": "", "fields": { "v": 1 } }]"#),
+            (0, PointError::EmptyMeasurement)
+        );
+        assert_matches!(
+            point_err(r#"[{ "measurement": "cpu", "fields": {} }]"#),
+            (0, PointError::NoFields)
+        );
+        assert_matches!(
+            point_err(
+                r#"[{ "measurement": "cpu", "tags": { "time": "a" }, "fields": { "v": 1 } }]"#
+            ),
+            (0, PointError::ReservedName)
+        );
+        assert_matches!(
+            point_err(r#"[{ "measurement": "cpu", "tags": { "v": "a" }, "fields": { "v": 1 } }]"#),
+            (0, PointError::DuplicateName(name)) => {
+                assert_eq!(name, "v");
+            }
+        );
+        assert_matches!(
+            point_err(&format!(
+                r#"[{{ "measurement": "cpu", "fields": {{ "v": 1 }}, "time": {} }}]"#,
+                i64::MAX
+            )),
+            (0, PointError::TimestampOverflow(_))
+        );
+        assert_matches!(
+            point_err(
+                r#"[
+                    { "measurement": "cpu", "fields": { "v": 1 } },
+                    { "measurement": "cpu", "fields": { "v": "a" } }
+                ]"#
+            ),
+            (1, PointError::Write(_))
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! Conversion of Prometheus remote-write requests into [`MutableBatch`].
+
+use std::iter;
+
+use generated_types::prometheus::{TimeSeries, WriteRequest};
+use hashbrown::{HashMap, HashSet};
+use mutable_batch::{writer::Writer, MutableBatch};
+use prost::Message;
+use thiserror::Error;
+
+/// The label holding the metric name of a series, which is used as the table
+/// name.
+const METRIC_NAME_LABEL: &str = "__name__";
+
+/// The field column the sample values are written to.
+const VALUE_COLUMN: &str = "value";
+
+/// The column the sample timestamps are written to.
+const TIME_COLUMN: &str = "time";
+
+/// Errors converting a Prometheus remote-write request into [`MutableBatch`].
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The request body is not a valid protobuf-encoded remote-write request.
+    #[error("invalid remote-write request: {0}")]
+    Decode(prost::DecodeError),
+
+    /// A series has no metric name label.
+    #[error("time series has no {METRIC_NAME_LABEL} label")]
+    NoMetricName,
+
+    /// A series has more than one label with the same name.
+    #[error("time series of metric {metric} has duplicate label {label}")]
+    DuplicateLabel {
+        /// The metric name of the series.
+        metric: String,
+        /// The duplicated label name.
+        label: String,
+    },
+
+    /// A series has a label named after a column the samples are written to.
+    #[error("time series of metric {metric} has reserved label {label}")]
+    ReservedLabel {
+        /// The metric name of the series.
+        metric: String,
+        /// The reserved label name.
+        label: String,
+    },
+
+    /// A sample timestamp cannot be represented in nanoseconds.
+    #[error("sample timestamp {0}ms overflows the range of nanosecond timestamps")]
+    TimestampOverflow(i64),
+
+    /// The samples of a series cannot be written to the table of the metric.
+    #[error("failed to write samples of metric {metric}: {source}")]
+    Write {
+        /// The metric name of the series.
+        metric: String,
+        /// The underlying write error.
+        source: mutable_batch::writer::Error,
+    },
+}
+
+/// Statistics describing a converted remote-write request.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct WriteStats {
+    /// The number of series with samples in the request.
+    pub num_series: usize,
+    /// The number of samples in the request.
+    pub num_samples: usize,
+}
+
+/// Decode the protobuf-encoded Prometheus remote-write request in `body`, and
+/// convert it into a set of [`MutableBatch`] keyed by table name.
+///
+/// Each metric is written to a table of the same name, with the labels of a
+/// series as tags, the sample values as the `value` float field, and the
+/// sample timestamps (in milliseconds) as the `time` column. Labels with an
+/// empty value are treated as absent, and series without samples are
+/// skipped.
+pub fn decode(body: &[u8]) -> Result<(HashMap<String, MutableBatch>, WriteStats), Error> {
+    let request = WriteRequest::decode(body).map_err(Error::Decode)?;
+
+    let mut batches: HashMap<String, MutableBatch> = HashMap::new();
+    let mut stats = WriteStats::default();
+    for series in request.timeseries {
+        if series.samples.is_empty() {
+            continue;
+        }
+
+        let metric = metric_name(&series)?.to_string();
+        let batch = batches.entry_ref(metric.as_str()).or_default();
+        write_series(batch, &metric, &series)?;
+
+        stats.num_series += 1;
+        stats.num_samples += series.samples.len();
+    }
+
+    Ok((batches, stats))
+}
+
+/// Return the value of the metric name label of `series`.
+fn metric_name(series: &TimeSeries) -> Result<&str, Error> {
+    series
+        .labels
+        .iter()
+        .find(|l| l.name == METRIC_NAME_LABEL)
+        .map(|l| l.value.as_str())
+        .filter(|v| !v.is_empty())
+        .ok_or(Error::NoMetricName)
+}
+
+/// Append the samples of `series` to `batch`.
+fn write_series(batch: &mut MutableBatch, metric: &str, series: &TimeSeries) -> Result<(), Error> {
+    let timestamps = series
+        .samples
+        .iter()
+        .map(|s| {
+            s.timestamp
+                .checked_mul(1_000_000)
+                .ok_or(Error::TimestampOverflow(s.timestamp))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let num_samples = series.samples.len();
+    let write_err = |source| Error::Write {
+        metric: metric.to_string(),
+        source,
+    };
+
+    let mut writer = Writer::new(batch, num_samples);
+
+    let mut seen = HashSet::with_capacity(series.labels.len());
+    for label in &series.labels {
+        if !seen.insert(label.name.as_str()) {
+            return Err(Error::DuplicateLabel {
+                metric: metric.to_string(),
+                label: label.name.clone(),
+            });
+        }
+        if label.name == METRIC_NAME_LABEL || label.value.is_empty() {
+            continue;
+        }
+        if label.name == VALUE_COLUMN || label.name == TIME_COLUMN {
+            return Err(Error::ReservedLabel {
+                metric: metric.to_string(),
+                label: label.name.clone(),
+            });
+        }
+
+        writer
+            .write_tag(
+                &label.name,
+                None,
+                iter::repeat(label.value.as_str()).take(num_samples),
+            )
+            .map_err(write_err)?;
+    }
+
+    writer
+        .write_f64(VALUE_COLUMN, None, series.samples.iter().map(|s| s.value))
+        .map_err(write_err)?;
+    writer
+        .write_time(TIME_COLUMN, timestamps.into_iter())
+        .map_err(write_err)?;
+
+    writer.commit();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use generated_types::prometheus::{Label, Sample};
+    use mutable_batch::column::ColumnData;
+
+    use super::*;
+
+    fn label(name: &str, value: &str) -> Label {
+        Label {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    fn series(labels: &[(&str, &str)], samples: &[(f64, i64)]) -> TimeSeries {
+        TimeSeries {
+            labels: labels.iter().map(|(n, v)| label(n, v)).collect(),
+            samples: samples
+                .iter()
+                .map(|&(value, timestamp)| Sample { value, timestamp })
+                .collect(),
+        }
+    }
+
+    fn encode(timeseries: Vec<TimeSeries>) -> Vec<u8> {
+        WriteRequest { timeseries }.encode_to_vec()
+    }
+
+    #[test]
+    fn test_decode() {
+        let body = encode(vec![
+            series(
+                &[("__name__", "http_requests_total"), ("code", "200")],
+                &[(1.0, 1), (2.0, 2)],
+            ),
+            series(
+                &[
+                    ("__name__", "http_requests_total"),
+                    ("code", "500"),
+                    ("path", "/bananas"),
+                ],
+                &[(3.0, 3)],
+            ),
+            series(&[("__name__", "up"), ("job", "")], &[(1.0, 4)]),
+            // Series without samples are skipped.
+            series(&[("__name__", "empty")], &[]),
+        ]);
+
+        let (batches, stats) = decode(&body).expect("decode should succeed");
+
+        assert_eq!(
+            stats,
+            WriteStats {
+                num_series: 3,
+                num_samples: 4
+            }
+        );
+
+        let mut tables = batches.keys().collect::<Vec<_>>();
+        tables.sort_unstable();
+        assert_eq!(tables, ["http_requests_total", "up"]);
+
+        let batch = &batches["http_requests_total"];
+        assert_eq!(batch.rows(), 3);
+        assert_eq!(
+            batch.column_names().into_iter().collect::<Vec<_>>(),
+            ["code", "path", "time", "value"]
+        );
+        assert_matches!(batch.column("value").unwrap().data(), ColumnData::F64(values, _) => {
+            assert_eq!(values, &[1.0, 2.0, 3.0]);
+        });
+        assert_matches!(batch.column("time").unwrap().data(), ColumnData::I64(values, _) => {
+            assert_eq!(values, &[1_000_000, 2_000_000, 3_000_000]);
+        });
+
+        // Empty label values are not written.
+        let batch = &batches["up"];
+        assert_eq!(
+            batch.column_names().into_iter().collect::<Vec<_>>(),
+            ["time", "value"]
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_matches!(decode(b"\xFFbananas"), Err(Error::Decode(_)));
+
+        assert_matches!(
+            decode(&encode(vec![series(&[("job", "a")], &[(1.0, 1)])])),
+            Err(Error::NoMetricName)
+        );
+
+        assert_matches!(
+            decode(&encode(vec![series(
+                &[("__name__", "up"), ("job", "a"), ("job", "b")],
+                &[(1.0, 1)]
+            )])),
+            Err(Error::DuplicateLabel { label, .. }) => {
+                assert_eq!(label, "job");
+            }
+        );
+
+        assert_matches!(
+            decode(&encode(vec![series(
+                &[("__name__", "up"), ("time", "now")],
+                &[(1.0, 1)]
+            )])),
+            Err(Error::ReservedLabel { label, .. }) => {
+                assert_eq!(label, "time");
+            }
+        );
+
+        assert_matches!(
+            decode(&encode(vec![series(
+                &[("__name__", "up")],
+                &[(1.0, i64::MAX)]
+            )])),
+            Err(Error::TimestampOverflow(_))
+        );
+    }
+}
@@ -0,0 +1,680 @@
+//! Per-line validation of line protocol write payloads.
+//!
+//! Each line of a payload is checked against the schema, retention period and
+//! service limits of the namespace it is written to. This allows individual
+//! lines of a write to be rejected while the remaining lines are written, and
+//! payloads to be validated without writing them at all.
+
+use std::{fmt::Display, iter};
+
+use data_types::{column_type_from_field, ColumnType, NamespaceSchema};
+use hashbrown::{HashMap, HashSet};
+use influxdb_line_protocol::{parse_lines, ParsedLine};
+use mutable_batch::{writer::Writer, MutableBatch};
+use mutable_batch_lp::PayloadStatistics;
+use serde::Serialize;
+
+use crate::dml_handlers::{validate_schema_limits, CachedServiceProtectionLimit};
+
+/// A problem preventing a single line of a payload from being written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LineDiagnostic {
+    /// The 1-based number of the line within the payload, not counting blank
+    /// lines and comments (as in the errors returned by the write API).
+    pub line: usize,
+    /// A description of the problem.
+    pub error: String,
+}
+
+/// The outcome of validating a line protocol payload.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    /// True if the payload would be accepted when written.
+    pub valid: bool,
+    /// The number of lines in the payload.
+    pub num_lines: usize,
+    /// The problems found with individual lines.
+    pub line_errors: Vec<LineDiagnostic>,
+}
+
+/// The lines of a write that were rejected, while the remaining lines were
+/// written.
+#[derive(Debug)]
+pub struct RejectedLines {
+    /// The number of lines in the write.
+    pub num_lines: usize,
+    /// The rejected lines (never empty).
+    pub line_errors: Vec<LineDiagnostic>,
+}
+
+impl Display for RejectedLines {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "partial write: {} of {} lines rejected",
+            self.line_errors.len(),
+            self.num_lines
+        )?;
+        if let Some(first) = self.line_errors.first() {
+            write!(f, ", first error on line {}: {}", first.line, first.error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RejectedLines {}
+
+/// Checks if individual lines can be written to a namespace, accounting for
+/// the tables and columns created by the lines accepted so far.
+#[derive(Debug)]
+struct LineValidator<'a> {
+    schema: &'a NamespaceSchema,
+    now: i64,
+    min_acceptable_ts: Option<i64>,
+
+    /// The number of tables not in `schema` created by the accepted lines.
+    new_tables: usize,
+    /// The columns not in `schema` created by the accepted lines, by table.
+    new_columns: HashMap<String, HashSet<String>>,
+}
+
+impl<'a> LineValidator<'a> {
+    fn new(schema: &'a NamespaceSchema, now: i64) -> Self {
+        Self {
+            schema,
+            now,
+            min_acceptable_ts: schema.retention_period_ns.map(|v| now - v),
+            new_tables: 0,
+            new_columns: HashMap::new(),
+        }
+    }
+
+    /// Check `line` (with its timestamp in nanoseconds) can be written,
+    /// returning the columns it creates.
+    ///
+    /// The line must be passed to [`Self::accept`] once it is written.
+    fn check<'l>(&self, line: &'l ParsedLine<'_>) -> Result<Vec<&'l str>, String> {
+        if let Some(min_acceptable_ts) = self.min_acceptable_ts {
+            let ts = line.timestamp.unwrap_or(self.now);
+            if ts < min_acceptable_ts {
+                return Err(format!(
+                    "timestamp {ts} is outside of the retention period: minimum acceptable \
+                    timestamp is {min_acceptable_ts}"
+                ));
+            }
+        }
+
+        let table_name = line.series.measurement.as_str();
+        let table = self.schema.tables.get(table_name);
+
+        // Find the columns of this line that do not exist yet, rejecting the
+        // line if it conflicts with the existing columns.
+        let tags = line
+            .series
+            .tag_set
+            .iter()
+            .flatten()
+            .map(|(k, _)| (k.as_str(), ColumnType::Tag));
+        let fields = line
+            .field_set
+            .iter()
+            .map(|(k, v)| (k.as_str(), column_type_from_field(v)));
+        let mut created = vec![];
+        for (column_name, new) in tags
+            .chain(fields)
+            .chain(iter::once(("time", ColumnType::Time)))
+        {
+            match table.and_then(|t| t.columns.get(column_name)) {
+                Some(existing) if existing.column_type != new => {
+                    return Err(format!(
+                        "schema conflict: column {column_name} in table {table_name} is type \
+                        {existing}, but the line has type {new}",
+                        existing = existing.column_type,
+                    ));
+                }
+                Some(_) => {}
+                None => created.push(column_name),
+            }
+        }
+
+        // Enforce the service limits as the schema validator does, but only
+        // rejecting the lines that would exceed them.
+        let new_columns = self.new_columns.get(table_name);
+        let is_new_table = table.is_none() && new_columns.is_none();
+        if is_new_table {
+            let merged_table_count = self.schema.tables.len() + self.new_tables + 1;
+            if merged_table_count > self.schema.max_tables {
+                return Err(CachedServiceProtectionLimit::Table {
+                    existing_table_count: self.schema.tables.len(),
+                    merged_table_count,
+                    table_count_limit: self.schema.max_tables,
+                }
+                .to_string());
+            }
+        }
+
+        created.retain(|c| !new_columns.map_or(false, |v| v.contains(*c)));
+        if !created.is_empty() {
+            let existing_column_count = table.map_or(0, |t| t.columns.column_count());
+            let merged_column_count =
+                existing_column_count + new_columns.map_or(0, |v| v.len()) + created.len();
+            if merged_column_count > self.schema.max_columns_per_table {
+                return Err(CachedServiceProtectionLimit::Column {
+                    table_name: table_name.to_string(),
+                    existing_column_count,
+                    merged_column_count,
+                    max_columns_per_table: self.schema.max_columns_per_table,
+                }
+                .to_string());
+            }
+        }
+
+        Ok(created)
+    }
+
+    /// Record the tables and `created` columns of the written `line`.
+    fn accept(&mut self, line: &ParsedLine<'_>, created: Vec<&str>) {
+        let table_name = line.series.measurement.as_str();
+        let is_new_table = !self.schema.tables.contains_key(table_name);
+        if !is_new_table && created.is_empty() {
+            return;
+        }
+
+        if is_new_table && !self.new_columns.contains_key(table_name) {
+            self.new_tables += 1;
+        }
+        self.new_columns
+            .entry_ref(table_name)
+            .or_default()
+            .extend(created.into_iter().map(ToString::to_string));
+    }
+}
+
+/// Convert the timestamp of `line` (if any) to nanoseconds.
+fn scale_timestamp(
+    line: &mut ParsedLine<'_>,
+    timestamp_base: i64,
+) -> Result<(), mutable_batch_lp::Error> {
+    if let Some(t) = line.timestamp.as_mut() {
+        *t = t
+            .checked_mul(timestamp_base)
+            .ok_or(mutable_batch_lp::Error::TimestampOverflow)?;
+    }
+    Ok(())
+}
+
+/// Return the [`MutableBatch`] for the table of `line` in `batches`.
+fn batch_for<'a>(
+    batches: &'a mut HashMap<String, MutableBatch>,
+    line: &ParsedLine<'_>,
+) -> &'a mut MutableBatch {
+    let measurement = line.series.measurement.as_str();
+    batches
+        .raw_entry_mut()
+        .from_key(measurement)
+        .or_insert_with(|| (measurement.to_string(), MutableBatch::new()))
+        .1
+}
+
+/// Validate the line protocol in `body` against `schema`, as if it were
+/// written at `now` (in nanoseconds since the epoch) with the given
+/// `timestamp_base` precision multiplier.
+///
+/// Unlike a write, validation continues past lines that cannot be parsed so
+/// that all problems are reported at once.
+pub(crate) fn validate_lp(
+    body: &str,
+    schema: &NamespaceSchema,
+    now: i64,
+    timestamp_base: i64,
+) -> ValidationReport {
+    let mut validator = LineValidator::new(schema, now);
+
+    let mut num_lines = 0;
+    let mut line_errors = vec![];
+    let mut batches = HashMap::new();
+
+    for (idx, line) in parse_lines(body).enumerate() {
+        num_lines += 1;
+        let res = line.map_err(|e| e.to_string()).and_then(|mut line| {
+            scale_timestamp(&mut line, timestamp_base).map_err(|e| e.to_string())?;
+
+            let created = validator.check(&line)?;
+
+            // Writing each line to a batch of its table surfaces the
+            // conflicts between lines of the payload.
+            let mut writer = Writer::new(batch_for(&mut batches, &line), 1);
+            mutable_batch_lp::write_line(&mut writer, &line, now).map_err(|e| e.to_string())?;
+            writer.commit();
+            validator.accept(&line, created);
+            Ok(())
+        });
+        if let Err(error) = res {
+            line_errors.push(LineDiagnostic {
+                line: idx + 1,
+                error,
+            });
+        }
+    }
+
+    ValidationReport {
+        valid: line_errors.is_empty(),
+        num_lines,
+        line_errors,
+    }
+}
+
+/// Returns true if some lines of the converted `batches` may be rejected when
+/// written to `schema` at `now`.
+///
+/// This is a cheap check over the converted batches, so that the payload only
+/// needs to be converted line by line by [`filter_lines`] if some lines
+/// actually need to be rejected.
+fn may_reject_lines(
+    batches: &HashMap<String, MutableBatch>,
+    schema: &NamespaceSchema,
+    now: i64,
+) -> bool {
+    if validate_schema_limits(batches, schema).is_err() {
+        return true;
+    }
+
+    let min_acceptable_ts = schema.retention_period_ns.map(|v| now - v);
+    batches.iter().any(|(table_name, batch)| {
+        let outside_retention = min_acceptable_ts.map_or(false, |min_acceptable_ts| {
+            batch
+                .timestamp_summary()
+                .and_then(|v| v.stats.min)
+                .map_or(false, |ts| ts < min_acceptable_ts)
+        });
+        let conflicts = schema.tables.get(table_name).map_or(false, |table| {
+            batch.columns().any(|(name, column)| {
+                table.columns.get(name).map_or(false, |existing| {
+                    existing.column_type != ColumnType::from(column.influx_type())
+                })
+            })
+        });
+        outside_retention || conflicts
+    })
+}
+
+/// The payload of a write, converted with the lines that cannot be written to
+/// the namespace rejected.
+#[derive(Debug)]
+pub(crate) struct FilteredLines {
+    /// The accepted lines, by table.
+    pub(crate) batches: HashMap<String, MutableBatch>,
+    /// Statistics of the accepted lines.
+    pub(crate) stats: PayloadStatistics,
+    /// The rejected lines.
+    pub(crate) rejected: Vec<LineDiagnostic>,
+}
+
+/// Convert the line protocol in `body` to per-table batches in the same way
+/// as a [`LinesConverter`], rejecting the lines that cannot be written to
+/// `schema` at `default_time`.
+///
+/// Lines that cannot be parsed fail the whole payload, as for a
+/// [`LinesConverter`].
+///
+/// [`LinesConverter`]: mutable_batch_lp::LinesConverter
+fn filter_lines(
+    body: &str,
+    schema: &NamespaceSchema,
+    default_time: i64,
+    timestamp_base: i64,
+) -> Result<FilteredLines, mutable_batch_lp::Error> {
+    let mut validator = LineValidator::new(schema, default_time);
+
+    let mut stats = PayloadStatistics::default();
+    let mut rejected = vec![];
+    let mut batches = HashMap::new();
+
+    for (idx, line) in parse_lines(body).enumerate() {
+        let mut line = line.map_err(|source| mutable_batch_lp::Error::LineProtocol {
+            source,
+            line: idx + 1,
+        })?;
+        scale_timestamp(&mut line, timestamp_base)?;
+
+        let created = match validator.check(&line) {
+            Ok(v) => v,
+            Err(error) => {
+                rejected.push(LineDiagnostic {
+                    line: idx + 1,
+                    error,
+                });
+                continue;
+            }
+        };
+
+        let mut writer = Writer::new(batch_for(&mut batches, &line), 1);
+        mutable_batch_lp::write_line(&mut writer, &line, default_time).map_err(|source| {
+            mutable_batch_lp::Error::Write {
+                source,
+                line: idx + 1,
+            }
+        })?;
+        writer.commit();
+        validator.accept(&line, created);
+
+        stats.num_lines += 1;
+        stats.num_fields += line.field_set.len();
+    }
+
+    Ok(FilteredLines {
+        batches,
+        stats,
+        rejected,
+    })
+}
+
+/// Reject the lines of the line protocol `body`, converted to `batches` with
+/// `stats` by a [`LinesConverter`], that cannot be written to `schema` at
+/// `default_time`.
+///
+/// If none of the lines can be written, the converted payload is returned as
+/// is, so that writing it fails with the error of the DML handlers.
+///
+/// [`LinesConverter`]: mutable_batch_lp::LinesConverter
+pub(crate) fn reject_lines(
+    body: &str,
+    batches: HashMap<String, MutableBatch>,
+    stats: PayloadStatistics,
+    schema: &NamespaceSchema,
+    default_time: i64,
+    timestamp_base: i64,
+) -> Result<FilteredLines, mutable_batch_lp::Error> {
+    let unfiltered = |batches, stats| FilteredLines {
+        batches,
+        stats,
+        rejected: vec![],
+    };
+
+    if !may_reject_lines(&batches, schema, default_time) {
+        return Ok(unfiltered(batches, stats));
+    }
+
+    let filtered = filter_lines(body, schema, default_time, timestamp_base)?;
+    if filtered.batches.is_empty() {
+        return Ok(unfiltered(batches, stats));
+    }
+    Ok(filtered)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_matches::assert_matches;
+    use data_types::{Column, ColumnId, ColumnsByName, NamespaceId, TableId, TableSchema};
+
+    use super::*;
+
+    const NOW: i64 = 1_000_000_000_000;
+
+    fn new_schema() -> NamespaceSchema {
+        let columns = [
+            ("region", ColumnType::Tag),
+            ("usage", ColumnType::F64),
+            ("time", ColumnType::Time),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (name, column_type))| Column {
+            id: ColumnId::new(i as _),
+            table_id: TableId::new(1),
+            name: name.to_string(),
+            column_type,
+        });
+
+        NamespaceSchema {
+            id: NamespaceId::new(42),
+            tables: BTreeMap::from([(
+                "cpu".to_string(),
+                TableSchema {
+                    id: TableId::new(1),
+                    partition_template: Default::default(),
+                    retention_period_ns: None,
+                    columns: ColumnsByName::new(columns),
+                },
+            )]),
+            max_columns_per_table: 5,
+            max_tables: 2,
+            retention_period_ns: None,
+            partition_template: Default::default(),
+        }
+    }
+
+    fn line_numbers(report: &ValidationReport) -> Vec<usize> {
+        report.line_errors.iter().map(|d| d.line).collect()
+    }
+
+    #[test]
+    fn test_valid() {
+        let report = validate_lp(
+            "cpu,region=eu usage=4.2 1\ncpu,region=us usage=1,idle=9i\nmem free=1i",
+            &new_schema(),
+            NOW,
+            1,
+        );
+
+        assert_eq!(
+            report,
+            ValidationReport {
+                valid: true,
+                num_lines: 3,
+                line_errors: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_empty() {
+        let report = validate_lp("", &new_schema(), NOW, 1);
+        assert!(report.valid);
+        assert_eq!(report.num_lines, 0);
+    }
+
+    #[test]
+    fn test_per_line_diagnostics() {
+        let report = validate_lp(
+            "cpu,region=eu usage=4.2\n\
+            cpu,region=eu usage=\n\
+            cpu usage=\"high\"\n\
+            cpu,usage=high idle=1i\n\
+            mem free=1i\n\
+            mem free=1.0\n\
+            disk,a=1,a=2 v=1",
+            &new_schema(),
+            NOW,
+            1,
+        );
+
+        assert!(!report.valid);
+        assert_eq!(report.num_lines, 7);
+        assert_eq!(line_numbers(&report), [2, 3, 4, 6, 7]);
+        assert!(report.line_errors[1].error.contains("schema conflict"));
+        assert!(report.line_errors[1]
+            .error
+            .contains("column usage in table cpu"));
+    }
+
+    #[test]
+    fn test_timestamp_precision() {
+        let report = validate_lp(
+            "cpu usage=1 1\ncpu usage=1 9999999999999",
+            &new_schema(),
+            NOW,
+            1_000_000_000,
+        );
+        assert_eq!(line_numbers(&report), [2]);
+        assert_eq!(report.line_errors[0].error, "timestamp overflows i64");
+    }
+
+    #[test]
+    fn test_retention() {
+        let schema = NamespaceSchema {
+            retention_period_ns: Some(1_000),
+            ..new_schema()
+        };
+
+        let report = validate_lp(
+            &format!(
+                "cpu usage=1 {}\ncpu usage=1 {}\ncpu usage=1",
+                NOW - 1_001,
+                NOW - 1_000
+            ),
+            &schema,
+            NOW,
+            1,
+        );
+        assert_eq!(line_numbers(&report), [1]);
+        assert!(report.line_errors[0]
+            .error
+            .contains("outside of the retention period"));
+    }
+
+    #[test]
+    fn test_service_limits() {
+        let report = validate_lp("mem free=1i\ndisk free=1i", &new_schema(), NOW, 1);
+        assert!(!report.valid);
+        assert_eq!(line_numbers(&report), [2]);
+        assert!(report.line_errors[0]
+            .error
+            .contains("couldn't create new table"));
+
+        // Invalid lines do not count towards the limits.
+        let report = validate_lp("mem free=\ndisk free=1i", &new_schema(), NOW, 1);
+        assert_eq!(line_numbers(&report), [1]);
+
+        // Only the line exceeding the column limit is rejected.
+        let report = validate_lp(
+            "cpu a=1\ncpu b=2\ncpu c=3\ncpu a=2,usage=1",
+            &new_schema(),
+            NOW,
+            1,
+        );
+        assert_eq!(line_numbers(&report), [3]);
+        assert!(report.line_errors[0]
+            .error
+            .contains("couldn't create columns in table `cpu`"));
+    }
+
+    #[test]
+    fn test_filter_lines() {
+        let body = "cpu,region=eu usage=4.2\n\
+            cpu usage=\"high\"\n\
+            mem free=1i\n\
+            disk free=1i\n\
+            mem free=2i,used=1i";
+        let filtered = filter_lines(body, &new_schema(), NOW, 1).unwrap();
+
+        assert_eq!(
+            filtered.rejected.iter().map(|d| d.line).collect::<Vec<_>>(),
+            [2, 4]
+        );
+        assert_eq!(filtered.stats.num_lines, 3);
+        assert_eq!(filtered.stats.num_fields, 4);
+
+        let mut tables = filtered.batches.keys().collect::<Vec<_>>();
+        tables.sort_unstable();
+        assert_eq!(tables, ["cpu", "mem"]);
+        assert_eq!(filtered.batches["cpu"].rows(), 1);
+        assert_eq!(filtered.batches["mem"].rows(), 2);
+    }
+
+    #[test]
+    fn test_filter_lines_parse_error() {
+        let err = filter_lines("cpu usage=1\ncpu usage=", &new_schema(), NOW, 1)
+            .expect_err("unparsable lines fail the payload");
+        assert_matches!(err, mutable_batch_lp::Error::LineProtocol { line: 2, .. });
+
+        let err = filter_lines("cpu,a=1,a=2 usage=1", &new_schema(), NOW, 1)
+            .expect_err("unwritable lines fail the payload");
+        assert_matches!(err, mutable_batch_lp::Error::Write { line: 1, .. });
+    }
+
+    #[test]
+    fn test_reject_lines() {
+        let reject = |lp: &str| {
+            let (batches, stats) = mutable_batch_lp::lines_to_batches_stats(lp, NOW).unwrap();
+            reject_lines(lp, batches, stats, &new_schema(), NOW, 1).unwrap()
+        };
+
+        // Payloads that can be written are not converted again.
+        let filtered = reject("cpu,region=eu usage=4.2\nmem free=1i");
+        assert!(filtered.rejected.is_empty());
+        assert_eq!(filtered.stats.num_lines, 2);
+
+        let filtered = reject("cpu usage=4.2\nmem free=1i\ndisk free=1i");
+        assert_eq!(
+            filtered.rejected.iter().map(|d| d.line).collect::<Vec<_>>(),
+            [3]
+        );
+        assert_eq!(filtered.stats.num_lines, 2);
+        assert!(!filtered.batches.contains_key("disk"));
+
+        // Payloads that cannot be written at all are returned as is.
+        let filtered = reject("cpu usage=1i");
+        assert!(filtered.rejected.is_empty());
+        assert_eq!(filtered.stats.num_lines, 1);
+        assert_eq!(filtered.batches["cpu"].rows(), 1);
+    }
+
+    #[test]
+    fn test_may_reject_lines() {
+        let schema = NamespaceSchema {
+            retention_period_ns: Some(1_000),
+            ..new_schema()
+        };
+        let may_reject = |lp: &str| {
+            let batches = mutable_batch_lp::lines_to_batches(lp, NOW).unwrap();
+            may_reject_lines(&batches, &schema, NOW)
+        };
+
+        assert!(!may_reject("cpu,region=eu usage=4.2\nmem free=1i"));
+        assert!(may_reject("cpu usage=1i"));
+        assert!(may_reject(&format!("cpu usage=1 {}", NOW - 1_001)));
+        assert!(may_reject("mem free=1i\ndisk free=1i"));
+    }
+
+    #[test]
+    fn test_rejected_lines_display() {
+        let rejected = RejectedLines {
+            num_lines: 5,
+            line_errors: vec![
+                LineDiagnostic {
+                    line: 2,
+                    error: "bananas".to_string(),
+                },
+                LineDiagnostic {
+                    line: 4,
+                    error: "platanos".to_string(),
+                },
+            ],
+        };
+
+        assert_eq!(
+            rejected.to_string(),
+            "partial write: 2 of 5 lines rejected, first error on line 2: bananas"
+        );
+    }
+
+    #[test]
+    fn test_serialise() {
+        let report = ValidationReport {
+            valid: false,
+            num_lines: 2,
+            line_errors: vec![LineDiagnostic {
+                line: 2,
+                error: "bananas".to_string(),
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_string(&report).unwrap(),
+            r#"{"valid":false,"num_lines":2,"line_errors":[{"line":2,"error":"bananas"}]}"#
+        );
+    }
+}
@@ -3,11 +3,15 @@
 //! [V2 Write API]:
 //!     https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostWrite
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
+use authz::Authorizer;
 use data_types::{NamespaceName, OrgBucketMappingError};
 use hyper::{Body, Request};
 
 use super::{
+    single_tenant::auth::authorize,
     v2::{V2WriteParseError, WriteParamsV2},
     WriteParams, WriteRequestUnifier,
 };
@@ -23,6 +27,10 @@ pub enum MultiTenantExtractError {
     /// A [`WriteParamsV2`] failed to be parsed from the HTTP request.
     #[error(transparent)]
     ParseV2Request(#[from] V2WriteParseError),
+
+    /// An error occurred verifying the authorization token.
+    #[error(transparent)]
+    Authorizer(authz::Error),
 }
 
 /// Implement a by-ref conversion to avoid "moving" the inner errors when only
@@ -38,6 +46,11 @@ impl From<&MultiTenantExtractError> for hyper::StatusCode {
             MultiTenantExtractError::ParseV2Request(
                 V2WriteParseError::NoQueryParams | V2WriteParseError::DecodeFail(_),
             ) => Self::BAD_REQUEST,
+            MultiTenantExtractError::Authorizer(e) => match e {
+                authz::Error::Forbidden => Self::FORBIDDEN,
+                authz::Error::NoToken => Self::UNAUTHORIZED,
+                _ => Self::FORBIDDEN,
+            },
         }
     }
 }
@@ -47,10 +60,24 @@ impl From<&MultiTenantExtractError> for hyper::StatusCode {
 /// This handler respects the [V2 Write API] without modification, and rejects
 /// any V1 write requests.
 ///
+/// If an [`Authorizer`] is configured, the authorization token of a request
+/// must grant write access to the namespace. Otherwise, requests are expected
+/// to be authorised before reaching the router.
+///
 /// [V2 Write API]:
 ///     https://docs.influxdata.com/influxdb/v2.6/api/#operation/PostWrite
 #[derive(Debug, Default)]
-pub struct MultiTenantRequestUnifier;
+pub struct MultiTenantRequestUnifier {
+    authz: Option<Arc<dyn Authorizer>>,
+}
+
+impl MultiTenantRequestUnifier {
+    /// Creates a new [`MultiTenantRequestUnifier`], authorising requests with
+    /// `authz`, if any.
+    pub fn new(authz: Option<Arc<dyn Authorizer>>) -> Self {
+        Self { authz }
+    }
+}
 
 #[async_trait]
 impl WriteRequestUnifier for MultiTenantRequestUnifier {
@@ -59,7 +86,13 @@ impl WriteRequestUnifier for MultiTenantRequestUnifier {
     }
 
     async fn parse_v2(&self, req: &Request<Body>) -> Result<WriteParams, Error> {
-        Ok(parse_v2(req)?)
+        let write_params = parse_v2(req)?;
+        if let Some(authz) = &self.authz {
+            authorize(authz, req, &write_params.namespace, None)
+                .await
+                .map_err(MultiTenantExtractError::Authorizer)?;
+        }
+        Ok(write_params)
     }
 }
 
@@ -78,19 +111,62 @@ fn parse_v2(req: &Request<Body>) -> Result<WriteParams, MultiTenantExtractError>
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use authz::http::AuthorizationHeaderExtension;
     use data_types::NamespaceNameError;
+    use hyper::header::HeaderValue;
 
     use super::*;
-    use crate::server::http::write::Precision;
+    use crate::server::http::write::{single_tenant::auth::mock::*, Precision};
 
     #[tokio::test]
     async fn test_parse_v1_always_errors() {
-        let unifier = MultiTenantRequestUnifier;
+        let unifier = MultiTenantRequestUnifier::default();
 
         let got = unifier.parse_v1(&Request::default()).await;
         assert_matches!(got, Err(Error::NoHandler));
     }
 
+    #[tokio::test]
+    async fn test_parse_v2_authz() {
+        let unifier = MultiTenantRequestUnifier::new(Some(Arc::new(MockAuthorizer::default())));
+
+        let request = |token: Option<&str>| {
+            Request::builder()
+                .uri("https://itsallbroken.com/ignored?org=bananas&bucket=test")
+                .method("POST")
+                .extension(AuthorizationHeaderExtension::new(token.map(|t| {
+                    HeaderValue::from_str(format!("Token {t}").as_str()).unwrap()
+                })))
+                .body(Body::from(""))
+                .unwrap()
+        };
+
+        let got = unifier.parse_v2(&request(None)).await;
+        assert_matches!(
+            got,
+            Err(Error::MultiTenantError(
+                MultiTenantExtractError::Authorizer(authz::Error::NoToken)
+            ))
+        );
+
+        let got = unifier
+            .parse_v2(&request(Some(MOCK_AUTH_NO_PERMS_TOKEN)))
+            .await;
+        assert_matches!(
+            got,
+            Err(Error::MultiTenantError(
+                MultiTenantExtractError::Authorizer(authz::Error::Forbidden)
+            ))
+        );
+
+        let got = unifier
+            .parse_v2(&request(Some(MOCK_AUTH_VALID_TOKEN)))
+            .await;
+        assert_matches!(got, Ok(WriteParams { namespace, .. }) => {
+            assert_eq!(namespace.as_str(), "bananas_test");
+        });
+    }
+
     macro_rules! test_parse_v2 {
         (
             $name:ident,
@@ -100,7 +176,7 @@ mod tests {
             paste::paste! {
                 #[tokio::test]
                 async fn [<test_parse_v2_ $name>]() {
-                    let unifier = MultiTenantRequestUnifier;
+                    let unifier = MultiTenantRequestUnifier::default();
 
                     let query = $query_string;
                     let request = Request::builder()
@@ -17,7 +17,9 @@ use router::{
         SchemaValidator,
     },
     namespace_cache::{MemoryNamespaceCache, ReadThroughCache, ShardedCache},
-    namespace_resolver::{MissingNamespaceAction, NamespaceAutocreation, NamespaceSchemaResolver},
+    namespace_resolver::{
+        MissingNamespaceAction, NamespaceAutocreation, NamespaceSchemaResolver, SoftDeleteCheck,
+    },
     server::{
         grpc::RpcWriteGrpcDelegate,
         http::{write::multi_tenant::MultiTenantRequestUnifier, HttpDelegate},
@@ -35,6 +37,7 @@ pub struct TestContextBuilder {
     namespace_autocreation: MissingNamespaceAction,
     single_tenancy: bool,
     rpc_write_error_window: Duration,
+    namespace_cache_negative_ttl: Option<Duration>,
 }
 
 impl Default for TestContextBuilder {
@@ -43,6 +46,7 @@ impl Default for TestContextBuilder {
             namespace_autocreation: MissingNamespaceAction::Reject,
             single_tenancy: false,
             rpc_write_error_window: Duration::from_secs(5),
+            namespace_cache_negative_ttl: None,
         }
     }
 }
@@ -70,6 +74,13 @@ impl TestContextBuilder {
         self
     }
 
+    /// Cache "namespace not found" lookups for `ttl` in all subsequently
+    /// built [`TestContext`]s.
+    pub fn with_namespace_cache_negative_ttl(mut self, ttl: Duration) -> Self {
+        self.namespace_cache_negative_ttl = Some(ttl);
+        self
+    }
+
     pub async fn build(self) -> TestContext {
         test_helpers::maybe_start_logging();
 
@@ -82,6 +93,7 @@ impl TestContextBuilder {
             catalog,
             metrics,
             self.rpc_write_error_window,
+            self.namespace_cache_negative_ttl,
         )
         .await
     }
@@ -91,37 +103,39 @@ impl TestContextBuilder {
 pub struct TestContext {
     client: Arc<MockWriteClient>,
     http_delegate: HttpDelegateStack,
-    grpc_delegate: RpcWriteGrpcDelegate,
+    grpc_delegate: GrpcDelegateStack,
     catalog: Arc<dyn Catalog>,
     metrics: Arc<metric::Registry>,
 
     namespace_autocreation: MissingNamespaceAction,
     single_tenancy: bool,
     rpc_write_error_window: Duration,
+    namespace_cache_negative_ttl: Option<Duration>,
 }
 
 // This mass of words is certainly a downside of chained handlers.
 //
 // Fortunately the compiler errors are very descriptive and updating this is
 // relatively easy when something changes!
-type HttpDelegateStack = HttpDelegate<
-    InstrumentationDecorator<
+type DmlHandlerStack = InstrumentationDecorator<
+    Chain<
         Chain<
             Chain<
-                Chain<
-                    RetentionValidator,
-                    SchemaValidator<
-                        Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
-                    >,
+                RetentionValidator,
+                SchemaValidator<
+                    Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
                 >,
-                Partitioner,
-            >,
-            FanOutAdaptor<
-                RpcWrite<Arc<MockWriteClient>>,
-                Vec<Partitioned<HashMap<TableId, (String, MutableBatch)>>>,
             >,
+            Partitioner,
+        >,
+        FanOutAdaptor<
+            RpcWrite<Arc<MockWriteClient>>,
+            Vec<Partitioned<HashMap<TableId, (String, MutableBatch)>>>,
         >,
     >,
+>;
+
+type NamespaceResolverStack = SoftDeleteCheck<
     NamespaceAutocreation<
         Arc<ReadThroughCache<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>>,
         NamespaceSchemaResolver<
@@ -130,6 +144,10 @@ type HttpDelegateStack = HttpDelegate<
     >,
 >;
 
+type HttpDelegateStack = HttpDelegate<Arc<DmlHandlerStack>, Arc<NamespaceResolverStack>>;
+
+type GrpcDelegateStack = RpcWriteGrpcDelegate<Arc<DmlHandlerStack>, Arc<NamespaceResolverStack>>;
+
 /// A [`router`] stack configured with the various DML handlers using mock catalog backends.
 impl TestContext {
     async fn new(
@@ -138,6 +156,7 @@ impl TestContext {
         catalog: Arc<dyn Catalog>,
         metrics: Arc<metric::Registry>,
         rpc_write_error_window: Duration,
+        namespace_cache_negative_ttl: Option<Duration>,
     ) -> Self {
         let client = Arc::new(MockWriteClient::default());
         let rpc_writer = RpcWrite::new(
@@ -147,12 +166,16 @@ impl TestContext {
             rpc_write_error_window,
         );
 
-        let ns_cache = Arc::new(ReadThroughCache::new(
+        let mut ns_cache = ReadThroughCache::new(
             Arc::new(ShardedCache::new(
                 iter::repeat_with(|| Arc::new(MemoryNamespaceCache::default())).take(10),
             )),
             Arc::clone(&catalog),
-        ));
+        );
+        if let Some(ttl) = namespace_cache_negative_ttl {
+            ns_cache = ns_cache.with_negative_ttl(ttl);
+        }
+        let ns_cache = Arc::new(ns_cache);
 
         let schema_validator =
             SchemaValidator::new(Arc::clone(&catalog), Arc::clone(&ns_cache), &metrics);
@@ -168,6 +191,10 @@ impl TestContext {
             Arc::clone(&catalog),
             namespace_autocreation,
         );
+        let namespace_resolver = Arc::new(SoftDeleteCheck::new(
+            namespace_resolver,
+            Arc::clone(&catalog),
+        ));
 
         let parallel_write = FanOutAdaptor::new(rpc_writer);
 
@@ -176,21 +203,30 @@ impl TestContext {
             .and_then(partitioner)
             .and_then(parallel_write);
 
-        let handler_stack = InstrumentationDecorator::new("request", &metrics, handler_stack);
+        let handler_stack = Arc::new(InstrumentationDecorator::new(
+            "request",
+            &metrics,
+            handler_stack,
+        ));
 
         let write_request_unifier = Box::<MultiTenantRequestUnifier>::default();
 
         let http_delegate = HttpDelegate::new(
             1024,
             100,
-            namespace_resolver,
-            handler_stack,
+            Arc::clone(&namespace_resolver),
+            Arc::clone(&handler_stack),
             &metrics,
             write_request_unifier,
         );
 
-        let grpc_delegate =
-            RpcWriteGrpcDelegate::new(Arc::clone(&catalog), Arc::new(InMemory::default()));
+        let grpc_delegate = RpcWriteGrpcDelegate::new(
+            Arc::clone(&catalog),
+            Arc::new(InMemory::default()),
+            handler_stack,
+            namespace_resolver,
+        )
+        .with_undelete_observer(Arc::clone(&ns_cache) as _);
 
         Self {
             client,
@@ -202,6 +238,7 @@ impl TestContext {
             namespace_autocreation,
             single_tenancy,
             rpc_write_error_window,
+            namespace_cache_negative_ttl,
         }
     }
 
@@ -216,6 +253,7 @@ impl TestContext {
             catalog,
             metrics,
             self.rpc_write_error_window,
+            self.namespace_cache_negative_ttl,
         )
         .await
     }
@@ -226,7 +264,7 @@ impl TestContext {
     }
 
     /// Get a reference to the test context's grpc delegate.
-    pub fn grpc_delegate(&self) -> &RpcWriteGrpcDelegate {
+    pub fn grpc_delegate(&self) -> &GrpcDelegateStack {
         &self.grpc_delegate
     }
 
@@ -275,10 +275,67 @@ async fn test_namespace_delete() {
         .expect_err("write should fail");
     assert_matches!(
         err,
-        router::server::http::Error::NamespaceResolver(router::namespace_resolver::Error::Lookup(
-            iox_catalog::interface::Error::NamespaceNotFoundByName { .. }
-        ))
+        router::server::http::Error::NamespaceResolver(
+            router::namespace_resolver::Error::SoftDeleted(ref name)
+        ) => {
+            assert_eq!(name, "bananas_test");
+        }
     );
+    assert_eq!(err.as_status_code(), StatusCode::GONE);
+}
+
+/// Ensure undeleting a namespace through the gRPC NamespaceService allows
+/// writes to it immediately, even though the router cached it as not found.
+#[tokio::test]
+async fn test_namespace_undelete() {
+    let ctx = TestContextBuilder::default()
+        .without_autocreate_namespace()
+        .with_namespace_cache_negative_ttl(Duration::from_secs(60 * 60))
+        .build()
+        .await;
+
+    // Create and delete the namespace before it is ever written to.
+    ctx.grpc_delegate()
+        .namespace_service()
+        .create_namespace(Request::new(CreateNamespaceRequest {
+            name: "bananas_test".to_string(),
+            retention_period_ns: None,
+            partition_template: None,
+            service_protection_limits: None,
+        }))
+        .await
+        .expect("failed to create namespace");
+    ctx.grpc_delegate()
+        .namespace_service()
+        .delete_namespace(Request::new(DeleteNamespaceRequest {
+            name: "bananas_test".to_string(),
+        }))
+        .await
+        .expect("must delete");
+
+    // Writes are rejected, and the router caches the namespace as not found.
+    let lp = "platanos,tag1=A,tag2=B val=42i 123456";
+    let err = ctx
+        .write_lp("bananas", "test", lp)
+        .await
+        .expect_err("write should fail");
+    assert_eq!(err.as_status_code(), StatusCode::GONE);
+
+    ctx.grpc_delegate()
+        .namespace_service()
+        .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+            name: "bananas_test".to_string(),
+        }))
+        .await
+        .expect("must undelete");
+
+    // The cached "not found" entry was dropped, so the write succeeds well
+    // before the negative TTL elapses.
+    let response = ctx
+        .write_lp("bananas", "test", lp)
+        .await
+        .expect("write failed");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
 }
 
 /// Ensure creating a namespace with a retention period of 0 maps to "infinite"
@@ -1265,3 +1322,70 @@ async fn test_namespace_without_partition_template_table_with_partition_template
         assert_eq!(partition_key, "B");
     });
 }
+
+/// Ensure writes made through the gRPC WriteService are routed to the
+/// ingesters in the same way as HTTP writes.
+#[tokio::test]
+async fn test_write_service() {
+    use generated_types::influxdata::iox::router::v1::{
+        self as proto, write_request::Payload, write_service_server::WriteService,
+    };
+
+    let ctx = TestContextBuilder::default()
+        .with_autocreate_namespace(None)
+        .build()
+        .await;
+
+    let now = SystemProvider::default()
+        .now()
+        .timestamp_nanos()
+        .to_string();
+
+    // Drive the creation of the namespace and table schema over HTTP.
+    let response = ctx
+        .write_lp("bananas", "test", format!("platanos,tag1=A val=42i {now}"))
+        .await
+        .expect("write should succeed");
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = ctx
+        .grpc_delegate()
+        .write_service()
+        .write(Request::new(proto::WriteRequest {
+            namespace: "bananas_test".to_string(),
+            payload: Some(Payload::LineProtocol(proto::LineProtocol {
+                body: format!("platanos val=4.2 {now}\nplatanos,tag1=B val=24i {now}"),
+                precision: proto::Precision::Unspecified.into(),
+            })),
+        }))
+        .await
+        .expect("write should succeed")
+        .into_inner();
+
+    // The first line conflicts with the table schema, and is rejected.
+    assert_matches!(
+        response.rejected_lines.as_slice(),
+        [proto::LineError { line: 1, error }] => {
+            assert!(error.contains("schema conflict"));
+        }
+    );
+
+    // The accepted line is written to the ingester.
+    let writes = ctx.write_calls();
+    assert_matches!(
+        writes.as_slice(),
+        [
+            _,
+            WriteRequest {
+                payload: Some(DatabaseBatch {
+                    table_batches,
+                    ..
+                }),
+            },
+        ] => {
+        let table_id = ctx.table_id("bananas_test", "platanos").await.get();
+        assert_eq!(table_batches.len(), 1);
+        assert_eq!(table_batches[0].table_id, table_id);
+        assert_eq!(table_batches[0].row_count, 1);
+    });
+}
@@ -19,12 +19,14 @@ use workspace_hack as _;
 
 mod error;
 pub mod planner;
+pub mod quota;
 pub mod test_util;
 
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use iox_query::{exec::ExecutionContextProvider, QueryNamespace};
+use quota::{QuotaError, QuotaPermit};
 use trace::span::Span;
 use tracker::InstrumentedAsyncOwnedSemaphorePermit;
 
@@ -47,8 +49,36 @@ pub trait QueryNamespaceProvider: std::fmt::Debug + Send + Sync + 'static {
         include_debug_info_tables: bool,
     ) -> Option<Arc<Self::Db>>;
 
+    /// Get namespace if it exists, with access to the tables of the `federated` namespaces.
+    ///
+    /// SQL addresses the tables of a federated namespace as `<namespace>.<table>`. Federated
+    /// namespaces that do not exist are ignored; queries that use them fail during planning.
+    ///
+    /// Providers that do not support federation ignore `federated`.
+    async fn federated_db(
+        &self,
+        name: &str,
+        federated: &[String],
+        span: Option<Span>,
+        include_debug_info_tables: bool,
+    ) -> Option<Arc<Self::Db>> {
+        let _ = federated;
+        self.db(name, span, include_debug_info_tables).await
+    }
+
     /// Acquire concurrency-limiting sempahore
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit;
+
+    /// Count a query against the quota of the namespace `name`.
+    ///
+    /// Fails immediately (i.e. without waiting) if the namespace has exhausted its quota. The query must hold on to
+    /// the returned permit until it finishes.
+    ///
+    /// Providers that do not support quotas never fail.
+    async fn acquire_quota(&self, name: &str) -> Result<QuotaPermit, QuotaError> {
+        let _ = name;
+        Ok(QuotaPermit::unlimited())
+    }
 }
 
 pub use error::datafusion_error_to_tonic_code;
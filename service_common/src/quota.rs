@@ -0,0 +1,44 @@
+//! Per-namespace query quotas.
+use std::{any::Any, fmt::Debug};
+
+use snafu::Snafu;
+
+/// Error returned by [`QueryNamespaceProvider::acquire_quota`](crate::QueryNamespaceProvider::acquire_quota).
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum QuotaError {
+    #[snafu(display(
+        "namespace '{namespace}' already runs the maximum of {limit} concurrent queries"
+    ))]
+    TooManyConcurrentQueries { namespace: String, limit: usize },
+
+    #[snafu(display("namespace '{namespace}' exceeded the limit of {limit} queries per minute"))]
+    TooManyQueriesPerMinute { namespace: String, limit: usize },
+}
+
+/// Permit that counts a query against the quota of its namespace.
+///
+/// The query is accounted for until the permit is dropped.
+pub struct QuotaPermit {
+    _guard: Option<Box<dyn Any + Send + Sync>>,
+}
+
+impl QuotaPermit {
+    /// Permit that is not tracked by any quota.
+    pub fn unlimited() -> Self {
+        Self { _guard: None }
+    }
+
+    /// Permit that releases its quota by dropping `guard`.
+    pub fn new(guard: impl Any + Send + Sync) -> Self {
+        Self {
+            _guard: Some(Box::new(guard)),
+        }
+    }
+}
+
+impl Debug for QuotaPermit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuotaPermit").finish_non_exhaustive()
+    }
+}
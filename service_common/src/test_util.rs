@@ -37,6 +37,11 @@ impl TestDatabaseStore {
         }
     }
 
+    /// Use the given executor for all databases created afterwards.
+    pub fn with_executor(self, executor: Arc<Executor>) -> Self {
+        Self { executor, ..self }
+    }
+
     pub async fn db_or_create(&self, name: &str) -> Arc<TestDatabase> {
         let mut databases = self.databases.lock();
 
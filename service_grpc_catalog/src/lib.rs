@@ -22,7 +22,7 @@ use data_types::{PartitionId, TableId, TransitionPartitionId};
 use generated_types::influxdata::iox::catalog::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::*;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use tonic::{Request, Response, Status};
 
 /// Implementation of the Catalog gRPC service
@@ -58,7 +58,7 @@ impl catalog_service_server::CatalogService for CatalogService {
                 Status::not_found(e.to_string())
             })?;
 
-        let parquet_files: Vec<_> = parquet_files.into_iter().map(to_parquet_file).collect();
+        let parquet_files: Vec<_> = parquet_files.into_iter().map(ParquetFile::from).collect();
 
         let response = GetParquetFilesByPartitionIdResponse { parquet_files };
 
@@ -79,7 +79,7 @@ impl catalog_service_server::CatalogService for CatalogService {
             .await
             .map_err(|e| Status::unknown(e.to_string()))?;
 
-        let partitions: Vec<_> = partitions.into_iter().map(to_partition).collect();
+        let partitions: Vec<_> = partitions.into_iter().map(Partition::from).collect();
 
         let response = GetPartitionsByTableIdResponse { partitions };
 
@@ -125,7 +125,7 @@ impl catalog_service_server::CatalogService for CatalogService {
                 Status::not_found(e.to_string())
             })?;
 
-        let parquet_files: Vec<_> = parquet_files.into_iter().map(to_parquet_file).collect();
+        let parquet_files: Vec<_> = parquet_files.into_iter().map(ParquetFile::from).collect();
 
         let response = GetParquetFilesByNamespaceTableResponse { parquet_files };
 
@@ -161,41 +161,81 @@ impl catalog_service_server::CatalogService for CatalogService {
                 Status::not_found(e.to_string())
             })?;
 
-        let parquet_files: Vec<_> = parquet_files.into_iter().map(to_parquet_file).collect();
+        let parquet_files: Vec<_> = parquet_files.into_iter().map(ParquetFile::from).collect();
 
         let response = GetParquetFilesByNamespaceResponse { parquet_files };
 
         Ok(Response::new(response))
     }
-}
 
-// converts the catalog ParquetFile to protobuf
-fn to_parquet_file(p: data_types::ParquetFile) -> ParquetFile {
-    ParquetFile {
-        id: p.id.get(),
-        namespace_id: p.namespace_id.get(),
-        table_id: p.table_id.get(),
-        partition_id: p.partition_id.get(),
-        object_store_id: p.object_store_id.to_string(),
-        min_time: p.min_time.get(),
-        max_time: p.max_time.get(),
-        to_delete: p.to_delete.map(|t| t.get()).unwrap_or(0),
-        file_size_bytes: p.file_size_bytes,
-        row_count: p.row_count,
-        compaction_level: p.compaction_level as i32,
-        created_at: p.created_at.get(),
-        column_set: p.column_set.iter().map(|id| id.get()).collect(),
-        max_l0_created_at: p.max_l0_created_at.get(),
-    }
-}
+    async fn get_table_export(
+        &self,
+        request: Request<GetTableExportRequest>,
+    ) -> Result<Response<GetTableExportResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let req = request.into_inner();
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&req.namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Namespace {} not found", req.namespace_name))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table_name)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("Table {} not found", req.table_name)))?;
+
+        let partitions: Vec<_> = repos
+            .partitions()
+            .list_by_table_id(table.id)
+            .await
+            .map_err(|e| Status::unknown(e.to_string()))?
+            .into_iter()
+            .filter(|p| {
+                let key = p.partition_key.inner();
+                req.partition_key_start
+                    .as_deref()
+                    .map_or(true, |start| key >= start)
+                    && req
+                        .partition_key_end
+                        .as_deref()
+                        .map_or(true, |end| key < end)
+            })
+            .collect();
+        let partition_ids: HashSet<_> = partitions.iter().map(|p| p.id).collect();
+
+        let parquet_files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error=%e,
+                    %req.namespace_name,
+                    %req.table_name,
+                    "failed to get parquet_files for table export"
+                );
+                Status::unknown(e.to_string())
+            })?
+            .into_iter()
+            .filter(|f| partition_ids.contains(&f.partition_id))
+            .map(ParquetFile::from)
+            .collect();
 
-// converts the catalog Partition to protobuf
-fn to_partition(p: data_types::Partition) -> Partition {
-    Partition {
-        id: p.id.get(),
-        key: p.partition_key.to_string(),
-        table_id: p.table_id.get(),
-        array_sort_key: p.sort_key,
+        let response = GetTableExportResponse {
+            namespace_id: namespace.id.get(),
+            table_id: table.id.get(),
+            partitions: partitions.into_iter().map(Partition::from).collect(),
+            parquet_files,
+        };
+
+        Ok(Response::new(response))
     }
 }
 
@@ -241,6 +281,7 @@ mod tests {
                 created_at: Timestamp::new(2343),
                 column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
                 max_l0_created_at: Timestamp::new(2343),
+                tag_ranges: None,
             };
             let p2params = ParquetFileParams {
                 object_store_id: Uuid::new_v4(),
@@ -262,7 +303,7 @@ mod tests {
             .await
             .expect("rpc request should succeed");
         let response = tonic_response.into_inner();
-        let expect: Vec<_> = [p1, p2].into_iter().map(to_parquet_file).collect();
+        let expect: Vec<_> = [p1, p2].into_iter().map(ParquetFile::from).collect();
         assert_eq!(expect, response.parquet_files,);
     }
 
@@ -305,8 +346,100 @@ mod tests {
         let response = tonic_response.into_inner();
         let expect: Vec<_> = [partition1, partition2]
             .into_iter()
-            .map(to_partition)
+            .map(Partition::from)
             .collect();
         assert_eq!(expect, response.partitions);
     }
+
+    #[tokio::test]
+    async fn get_table_export() {
+        // create a catalog with files in two partitions, then drop the write lock
+        let namespace_id;
+        let table_id;
+        let partition;
+        let file;
+        let catalog = {
+            let metrics = Arc::new(metric::Registry::default());
+            let catalog = Arc::new(MemCatalog::new(metrics));
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "catalog_export_test").await;
+            let table = arbitrary_table(&mut *repos, "schema_test_table", &namespace).await;
+            let mut files = vec![];
+            let mut partitions = vec![];
+            for key in ["2023-01-01", "2023-01-02"] {
+                let p = repos
+                    .partitions()
+                    .create_or_get(key.into(), table.id)
+                    .await
+                    .unwrap();
+                let params = ParquetFileParams {
+                    namespace_id: namespace.id,
+                    table_id: table.id,
+                    partition_id: p.id,
+                    partition_hash_id: p.hash_id().cloned(),
+                    object_store_id: Uuid::new_v4(),
+                    min_time: Timestamp::new(1),
+                    max_time: Timestamp::new(5),
+                    file_size_bytes: 2343,
+                    row_count: 29,
+                    compaction_level: CompactionLevel::Initial,
+                    created_at: Timestamp::new(2343),
+                    column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
+                    max_l0_created_at: Timestamp::new(2343),
+                    tag_ranges: None,
+                };
+                files.push(repos.parquet_files().create(params).await.unwrap());
+                partitions.push(p);
+            }
+            namespace_id = namespace.id;
+            table_id = table.id;
+            partition = partitions.pop().unwrap();
+            file = files.pop().unwrap();
+            Arc::clone(&catalog)
+        };
+
+        let grpc = super::CatalogService::new(catalog);
+
+        // the whole table
+        let request = GetTableExportRequest {
+            namespace_name: "catalog_export_test".to_string(),
+            table_name: "schema_test_table".to_string(),
+            partition_key_start: None,
+            partition_key_end: None,
+        };
+        let response = grpc
+            .get_table_export(Request::new(request.clone()))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        assert_eq!(response.namespace_id, namespace_id.get());
+        assert_eq!(response.table_id, table_id.get());
+        assert_eq!(response.partitions.len(), 2);
+        assert_eq!(response.parquet_files.len(), 2);
+
+        // only the second partition
+        let request = GetTableExportRequest {
+            partition_key_start: Some("2023-01-02".to_string()),
+            partition_key_end: Some("2023-01-03".to_string()),
+            ..request
+        };
+        let response = grpc
+            .get_table_export(Request::new(request.clone()))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        assert_eq!(response.partitions, vec![Partition::from(partition)]);
+        assert_eq!(response.parquet_files, vec![ParquetFile::from(file)]);
+
+        // unknown table
+        let request = GetTableExportRequest {
+            table_name: "not_a_table".to_string(),
+            ..request
+        };
+        let status = grpc
+            .get_table_export(Request::new(request))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
 }
@@ -0,0 +1,460 @@
+//! gRPC service for the compactor.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr,
+    clippy::todo,
+    clippy::dbg_macro,
+    unused_crate_dependencies
+)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use compactor_scheduler::{CompactionBacklog, ManualPartitionQueue};
+use data_types::PartitionId;
+use generated_types::influxdata::iox::compactor::v1::*;
+use iox_catalog::interface::Catalog;
+use observability_deps::tracing::*;
+use std::{collections::HashMap, sync::Arc};
+use tonic::{Request, Response, Status};
+
+/// Implementation of the compaction gRPC service
+#[derive(Debug)]
+pub struct CompactionService {
+    /// Catalog.
+    catalog: Arc<dyn Catalog>,
+
+    /// Partitions requested via [`CompactPartition`](compaction_service_server::CompactionService::compact_partition).
+    ///
+    /// `None` if the compaction scheduler does not support manual requests.
+    manual_partition_queue: Option<ManualPartitionQueue>,
+
+    /// Backlog served via [`GetBacklog`](compaction_service_server::CompactionService::get_backlog).
+    ///
+    /// `None` if the service is not backed by a running compactor.
+    backlog: Option<CompactionBacklog>,
+}
+
+impl CompactionService {
+    /// Create a new compaction service with the given catalog, feeding requested partitions into the given queue
+    /// and serving the given backlog.
+    pub fn new(
+        catalog: Arc<dyn Catalog>,
+        manual_partition_queue: Option<ManualPartitionQueue>,
+        backlog: Option<CompactionBacklog>,
+    ) -> Self {
+        Self {
+            catalog,
+            manual_partition_queue,
+            backlog,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl compaction_service_server::CompactionService for CompactionService {
+    async fn list_skipped_compactions(
+        &self,
+        _request: Request<ListSkippedCompactionsRequest>,
+    ) -> Result<Response<ListSkippedCompactionsResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let mut errors = repos
+            .partitions()
+            .list_skipped_compaction_errors()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(|e| (e.partition_id, e))
+            .collect::<HashMap<_, _>>();
+
+        let skipped_compactions = repos
+            .partitions()
+            .list_skipped_compactions()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(|sc| SkippedCompaction {
+                error: errors.remove(&sc.partition_id).map(From::from),
+                ..sc.into()
+            })
+            .collect();
+
+        Ok(Response::new(ListSkippedCompactionsResponse {
+            skipped_compactions,
+        }))
+    }
+
+    async fn delete_skipped_compactions(
+        &self,
+        request: Request<DeleteSkippedCompactionsRequest>,
+    ) -> Result<Response<DeleteSkippedCompactionsResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let error = repos
+            .partitions()
+            .delete_skipped_compaction_error(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map(From::from);
+
+        let skipped_compaction = repos
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map(|sc| SkippedCompaction { error, ..sc.into() });
+
+        Ok(Response::new(DeleteSkippedCompactionsResponse {
+            skipped_compaction,
+        }))
+    }
+
+    async fn compact_partition(
+        &self,
+        request: Request<CompactPartitionRequest>,
+    ) -> Result<Response<CompactPartitionResponse>, Status> {
+        let queue = self.manual_partition_queue.as_ref().ok_or_else(|| {
+            Status::unimplemented("compaction scheduler does not support manual compaction")
+        })?;
+        let mut repos = self.catalog.repositories().await;
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        repos
+            .partitions()
+            .get_by_id(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("partition {partition_id} not found")))?;
+
+        let queued = queue.push(partition_id);
+        info!(
+            partition_id = partition_id.get(),
+            queued, "manual compaction requested"
+        );
+
+        Ok(Response::new(CompactPartitionResponse { queued }))
+    }
+
+    async fn get_backlog(
+        &self,
+        _request: Request<GetBacklogRequest>,
+    ) -> Result<Response<GetBacklogResponse>, Status> {
+        let backlog = self
+            .backlog
+            .as_ref()
+            .ok_or_else(|| Status::unimplemented("no compactor backlog available"))?;
+        let snapshot = backlog.snapshot();
+
+        let partitions = snapshot
+            .partitions
+            .into_iter()
+            .map(|p| PartitionBacklog {
+                partition_id: p.partition_id.get(),
+                num_l0_files: p.num_l0_files as i64,
+                num_l1_files: p.num_l1_files as i64,
+                bytes: p.bytes as i64,
+                oldest_file_created_at: p.oldest_file_created_at.timestamp_nanos(),
+            })
+            .collect();
+        let jobs = snapshot
+            .jobs
+            .into_iter()
+            .map(|j| InFlightJob {
+                partition_id: j.partition_id.get(),
+                started_at: j.started_at.timestamp_nanos(),
+            })
+            .collect();
+
+        Ok(Response::new(GetBacklogResponse { partitions, jobs }))
+    }
+
+    async fn get_compaction_history(
+        &self,
+        request: Request<GetCompactionHistoryRequest>,
+    ) -> Result<Response<GetCompactionHistoryResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let history = repos
+            .partitions()
+            .list_compaction_history(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(From::from)
+            .collect();
+
+        Ok(Response::new(GetCompactionHistoryResponse { history }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_types::{CompactionHistoryParams, CompactionLevel, Timestamp};
+    use generated_types::influxdata::iox::compactor::v1::compaction_service_server::CompactionService as _;
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::{MockProvider, Time};
+
+    #[tokio::test]
+    async fn compact_partition() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let partition_id = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "ns").await;
+            let table = arbitrary_table(&mut *repos, "table", &namespace).await;
+            repos
+                .partitions()
+                .create_or_get("foo".into(), table.id)
+                .await
+                .unwrap()
+                .id
+        };
+
+        let queue = ManualPartitionQueue::default();
+        let service = CompactionService::new(Arc::clone(&catalog), Some(queue.clone()), None);
+
+        let request = || {
+            Request::new(CompactPartitionRequest {
+                partition_id: partition_id.get(),
+            })
+        };
+        assert!(
+            service
+                .compact_partition(request())
+                .await
+                .unwrap()
+                .into_inner()
+                .queued
+        );
+        assert!(
+            !service
+                .compact_partition(request())
+                .await
+                .unwrap()
+                .into_inner()
+                .queued
+        );
+        assert_eq!(queue.take(), vec![partition_id]);
+
+        let err = service
+            .compact_partition(Request::new(CompactPartitionRequest {
+                partition_id: partition_id.get() + 1,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::NotFound);
+        assert!(queue.is_empty());
+
+        let service = CompactionService::new(catalog, None, None);
+        let err = service.compact_partition(request()).await.unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+    }
+
+    #[tokio::test]
+    async fn skipped_compactions() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let partition_id = PartitionId::new(1);
+        catalog
+            .repositories()
+            .await
+            .partitions()
+            .record_skipped_compaction(partition_id, "too many files", 10, 5, 5, 100, 50)
+            .await
+            .unwrap();
+
+        let service = CompactionService::new(Arc::clone(&catalog), None, None);
+
+        let skipped = service
+            .list_skipped_compactions(Request::new(ListSkippedCompactionsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .skipped_compactions;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].partition_id, partition_id.get());
+        assert_eq!(skipped[0].reason, "too many files");
+        assert_eq!(skipped[0].error, None);
+
+        catalog
+            .repositories()
+            .await
+            .partitions()
+            .record_skipped_compaction_error(partition_id, "timeout", 10, 100, 3, "took too long")
+            .await
+            .unwrap();
+
+        let skipped = service
+            .list_skipped_compactions(Request::new(ListSkippedCompactionsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .skipped_compactions;
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(
+            skipped[0].error,
+            Some(SkippedCompactionError {
+                error_kind: String::from("timeout"),
+                num_files: 10,
+                total_bytes: 100,
+                num_failures: 3,
+                last_error: String::from("took too long"),
+            })
+        );
+
+        let deleted = service
+            .delete_skipped_compactions(Request::new(DeleteSkippedCompactionsRequest {
+                partition_id: partition_id.get(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .skipped_compaction
+            .unwrap();
+        assert_eq!(deleted, skipped[0]);
+
+        let skipped = service
+            .list_skipped_compactions(Request::new(ListSkippedCompactionsRequest {}))
+            .await
+            .unwrap()
+            .into_inner()
+            .skipped_compactions;
+        assert!(skipped.is_empty());
+        let errors = catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_skipped_compaction_errors()
+            .await
+            .unwrap();
+        assert!(errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_compaction_history() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let partition_id = PartitionId::new(1);
+        let job_id = uuid::Uuid::new_v4();
+        catalog
+            .repositories()
+            .await
+            .partitions()
+            .record_compaction_history(CompactionHistoryParams {
+                partition_id,
+                job_id,
+                num_input_files: 3,
+                input_bytes: 300,
+                num_upgraded_files: 1,
+                num_output_files: 2,
+                output_bytes: 250,
+                target_level: CompactionLevel::FileNonOverlapped,
+                duration_ms: 1_000,
+                committed_at: Timestamp::new(10),
+            })
+            .await
+            .unwrap();
+
+        let service = CompactionService::new(catalog, None, None);
+        let request = |partition_id: PartitionId| {
+            Request::new(GetCompactionHistoryRequest {
+                partition_id: partition_id.get(),
+            })
+        };
+
+        let history = service
+            .get_compaction_history(request(partition_id))
+            .await
+            .unwrap()
+            .into_inner()
+            .history;
+        assert_eq!(
+            history,
+            vec![CompactionHistory {
+                partition_id: 1,
+                job_id: job_id.to_string(),
+                num_input_files: 3,
+                input_bytes: 300,
+                num_upgraded_files: 1,
+                num_output_files: 2,
+                output_bytes: 250,
+                target_level: 1,
+                duration_ms: 1_000,
+                committed_at: 10,
+            }]
+        );
+
+        let history = service
+            .get_compaction_history(request(PartitionId::new(2)))
+            .await
+            .unwrap()
+            .into_inner()
+            .history;
+        assert!(history.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_backlog() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+
+        let service = CompactionService::new(Arc::clone(&catalog), None, None);
+        let err = service
+            .get_backlog(Request::new(GetBacklogRequest {}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Unimplemented);
+
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(100)));
+        let backlog = CompactionBacklog::new(&metrics, time_provider);
+        let partition_id = PartitionId::new(1);
+        backlog.job_started(partition_id);
+        backlog.record_files(
+            partition_id,
+            &[ParquetFileBuilder::new(1)
+                .with_compaction_level(CompactionLevel::Initial)
+                .with_file_size_bytes(10)
+                .build()],
+        );
+
+        let service = CompactionService::new(catalog, None, Some(backlog));
+        let response = service
+            .get_backlog(Request::new(GetBacklogRequest {}))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(
+            response.partitions,
+            vec![PartitionBacklog {
+                partition_id: 1,
+                num_l0_files: 1,
+                num_l1_files: 0,
+                bytes: 10,
+                oldest_file_created_at: 0,
+            }]
+        );
+        assert_eq!(
+            response.jobs,
+            vec![InFlightJob {
+                partition_id: 1,
+                started_at: 100,
+            }]
+        );
+    }
+}
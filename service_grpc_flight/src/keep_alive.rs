@@ -218,7 +218,7 @@ struct State {
 }
 
 /// Decode [`Schema`] from response data stream.
-fn decode_schema(data: &FlightData) -> Option<Schema> {
+pub(crate) fn decode_schema(data: &FlightData) -> Option<Schema> {
     let message = arrow::ipc::root_as_message(&data.data_header[..]).ok()?;
 
     if arrow::ipc::MessageHeader::Schema != message.header_type() {
@@ -230,7 +230,7 @@ fn decode_schema(data: &FlightData) -> Option<Schema> {
 /// Check that the [`Schema`] that we've [decoded](decode_schema) is sensible.
 ///
 /// Returns `true` if the [`Schema`] is OK. Will log a warning and return `false` if there is a problem.
-fn check_schema(schema: &Schema) -> bool {
+pub(crate) fn check_schema(schema: &Schema) -> bool {
     schema.fields().iter().all(|field| match field.data_type() {
         DataType::Dictionary(_, _) => {
             warn!(
@@ -246,7 +246,7 @@ fn check_schema(schema: &Schema) -> bool {
 /// Encode an empty [`RecordBatch`] as a message.
 ///
 /// This must only be sent AFTER a [`Schema`] was transmitted.
-fn build_empty_batch_msg(schema: Option<&SchemaRef>) -> Option<FlightData> {
+pub(crate) fn build_empty_batch_msg(schema: Option<&SchemaRef>) -> Option<FlightData> {
     let Some(schema) = schema else {
         warn!(
             "cannot send keep-alive because no schema was transmitted yet",
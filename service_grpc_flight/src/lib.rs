@@ -17,42 +17,61 @@
 )]
 
 use keep_alive::KeepAliveStream;
+use progress::{ProgressStream, QueryProgressTracker};
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
 mod keep_alive;
+mod progress;
 mod request;
 
 use arrow::error::ArrowError;
 use arrow_flight::{
+    decode::FlightRecordBatchStream,
     encode::FlightDataEncoderBuilder,
+    error::FlightError,
     flight_descriptor::DescriptorType,
     flight_service_server::{FlightService as Flight, FlightServiceServer as FlightServer},
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, HandshakeResponse, PutResult, SchemaResult, Ticket,
 };
+use audit::{AuditLog, AuditRecord, Operation};
 use authz::{extract_token, Authorizer};
 use data_types::NamespaceNameError;
 use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
-use flightsql::FlightSQLCommand;
-use futures::{ready, Stream, StreamExt, TryStreamExt};
+use flightsql::{FlightSQLCommand, FlightSQLPlanner};
+use futures::{ready, stream::BoxStream, Stream, StreamExt, TryStreamExt};
 use generated_types::influxdata::iox::querier::v1 as proto;
 use iox_query::{
-    exec::{ExecutionContextProvider, IOxSessionContext},
-    QueryCompletedToken, QueryNamespace,
+    exec::{
+        admission::{AdmissionPermit, QueryPriority},
+        ExecutionContextProvider, IOxSessionContext,
+    },
+    frontend::sql::referenced_schemas,
+    QueryCompletedToken, QueryNamespace, QueryPhase,
 };
 use observability_deps::tracing::{debug, info, warn};
 use prost::Message;
 use request::{IoxGetRequest, RunQuery};
-use service_common::{datafusion_error_to_tonic_code, planner::Planner, QueryNamespaceProvider};
+use service_common::{
+    datafusion_error_to_tonic_code,
+    planner::Planner,
+    quota::{QuotaError, QuotaPermit},
+    QueryNamespaceProvider,
+};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::{
     fmt::Debug,
+    net::SocketAddr,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::Poll,
     time::{Duration, Instant},
 };
+use tokio_util::sync::DropGuard;
 use tonic::{
     metadata::{AsciiMetadataValue, MetadataMap},
     Request, Response, Streaming,
@@ -73,9 +92,22 @@ const IOX_FLIGHT_SQL_DATABASE_HEADERS: [&str; 4] = [
     "iox-namespace-name", // deprecated
 ];
 
+/// Header that sets the [priority class](QueryPriority) of a `DoGet` request.
+const PRIORITY_HEADER: &str = "iox-query-priority";
+
+/// Standard user agent header, used to describe the issuer of a query.
+const USER_AGENT_HEADER: &str = "user-agent";
+
+/// Response header of a `DoGet` request that carries a warning, e.g. if the result is incomplete because an ingester
+/// was unavailable. Set once per warning.
+const QUERY_WARNING_HEADER: &str = "iox-query-warning";
+
 /// In which interval should the `DoGet` stream send empty messages as keep alive markers?
 const DO_GET_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(5);
 
+/// In which interval should the `DoExchange` stream send progress messages?
+const DO_EXCHANGE_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Snafu)]
 pub enum Error {
@@ -117,6 +149,9 @@ pub enum Error {
         source: tonic::metadata::errors::ToStrError,
     },
 
+    #[snafu(display("Invalid '{}' header in request: {}", PRIORITY_HEADER, reason))]
+    InvalidPriorityHeader { reason: String },
+
     #[snafu(display("Invalid database name: {}", source))]
     InvalidDatabaseName { source: NamespaceNameError },
 
@@ -139,6 +174,12 @@ pub enum Error {
     #[snafu(display("Invalid protobuf: {}", source))]
     Deserialization { source: prost::DecodeError },
 
+    #[snafu(display("DoPut request without flight descriptor"))]
+    MissingFlightDescriptor,
+
+    #[snafu(display("DoExchange request must start with a command flight descriptor"))]
+    MissingExchangeDescriptor,
+
     #[snafu(display("Unsupported message type: {}", description))]
     UnsupportedMessageType { description: String },
 
@@ -150,6 +191,12 @@ pub enum Error {
 
     #[snafu(display("Authz error: {}", source))]
     Authz { source: authz::Error },
+
+    #[snafu(display("Query quota exceeded: {}", source))]
+    QuotaExceeded {
+        namespace_name: String,
+        source: QuotaError,
+    },
 }
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -169,14 +216,18 @@ impl From<Error> for tonic::Status {
             | Error::Unauthenticated { .. }
             | Error::PermissionDenied { .. }
             | Error::InvalidDatabaseName { .. }
+            | Error::QuotaExceeded { .. }
             | Error::Query { .. } => info!(e=%err, %namespace, %query, msg),
             Error::Optimize { .. }
             | Error::EncodeSchema { .. }
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidPriorityHeader { .. }
             | Error::Planning { .. }
             | Error::Deserialization { .. }
+            | Error::MissingFlightDescriptor
+            | Error::MissingExchangeDescriptor
             | Error::InternalCreatingTicket { .. }
             | Error::UnsupportedMessageType { .. }
             | Error::FlightSQL { .. }
@@ -199,9 +250,12 @@ impl Error {
             Self::InvalidTicket { .. }
             | Self::InvalidHandshake { .. }
             | Self::Deserialization { .. }
+            | Self::MissingFlightDescriptor
+            | Self::MissingExchangeDescriptor
             | Self::TooManyFlightSQLDatabases { .. }
             | Self::NoFlightSQLDatabase
             | Self::InvalidDatabaseHeader { .. }
+            | Self::InvalidPriorityHeader { .. }
             | Self::InvalidDatabaseName { .. } => tonic::Code::InvalidArgument,
             Self::Planning { source, .. } | Self::Query { source, .. } => {
                 datafusion_error_to_tonic_code(&source)
@@ -209,6 +263,8 @@ impl Error {
             Self::UnsupportedMessageType { .. } => tonic::Code::Unimplemented,
             Self::FlightSQL { source } => match source {
                 flightsql::Error::InvalidHandle { .. }
+                | flightsql::Error::InvalidParams { .. }
+                | flightsql::Error::MissingParams
                 | flightsql::Error::Decode { .. }
                 | flightsql::Error::Protocol { .. }
                 | flightsql::Error::UnsupportedMessageType { .. } => tonic::Code::InvalidArgument,
@@ -226,6 +282,7 @@ impl Error {
             | Self::Authz { .. } => tonic::Code::Internal,
             Self::Unauthenticated => tonic::Code::Unauthenticated,
             Self::PermissionDenied => tonic::Code::PermissionDenied,
+            Self::QuotaExceeded { .. } => tonic::Code::ResourceExhausted,
         };
 
         tonic::Status::new(code, msg)
@@ -240,11 +297,14 @@ impl Error {
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidPriorityHeader { .. }
             | Error::InvalidDatabaseName { .. }
             | Error::Optimize { .. }
             | Error::EncodeSchema { .. }
             | Error::FlightSQL { .. }
             | Error::Deserialization { .. }
+            | Error::MissingFlightDescriptor
+            | Error::MissingExchangeDescriptor
             | Error::UnsupportedMessageType { .. }
             | Error::Unauthenticated
             | Error::PermissionDenied
@@ -252,6 +312,7 @@ impl Error {
             Error::DatabaseNotFound { namespace_name } => namespace_name,
             Error::Query { namespace_name, .. } => namespace_name,
             Error::Planning { namespace_name, .. } => namespace_name,
+            Error::QuotaExceeded { namespace_name, .. } => namespace_name,
         }
     }
 
@@ -264,16 +325,20 @@ impl Error {
             | Error::TooManyFlightSQLDatabases { .. }
             | Error::NoFlightSQLDatabase
             | Error::InvalidDatabaseHeader { .. }
+            | Error::InvalidPriorityHeader { .. }
             | Error::InvalidDatabaseName { .. }
             | Error::Optimize { .. }
             | Error::EncodeSchema { .. }
             | Error::FlightSQL { .. }
             | Error::Deserialization { .. }
+            | Error::MissingFlightDescriptor
+            | Error::MissingExchangeDescriptor
             | Error::UnsupportedMessageType { .. }
             | Error::Unauthenticated
             | Error::PermissionDenied
             | Error::Authz { .. }
-            | Error::DatabaseNotFound { .. } => "NONE",
+            | Error::DatabaseNotFound { .. }
+            | Error::QuotaExceeded { .. } => "NONE",
             Error::Query { query, .. } => query,
             Error::Planning { query, .. } => query,
         }
@@ -359,6 +424,13 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 ///     3 ┃◀ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ━ ┃
 /// ```
 ///
+/// # Native IOx API query with progress
+///
+/// Alternatively, the client can call the `DoExchange` method. The first [`FlightData`] message of the request
+/// carries the encoded `Ticket` as the `cmd` of its [`FlightDescriptor`], any further messages are ignored. The
+/// response contains the same data as the `DoGet` response, interleaved with a `QueryProgress` message every
+/// [`DO_EXCHANGE_PROGRESS_INTERVAL`] and once after the last result. See [`ProgressStream`] for the encoding.
+///
 /// # FlightSQL
 ///
 /// IOx also supports [Arrow FlightSQL]. In addition to `DoGet`,
@@ -414,7 +486,7 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 ///       ┃                                                  ┃
 /// ```
 ///
-/// ## FlightSQL Prepared Statement (no bind parameters like $1, etc)
+/// ## FlightSQL Prepared Statement
 ///
 /// To run a prepared query, via FlightSQL, the client undertakes a
 /// few more steps:
@@ -433,6 +505,21 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 ///
 /// 5. Steps 5,6,7 proceed the same as for a FlightSQL ad-hoc query
 ///
+/// ## FlightSQL Prepared Statement with bind parameters
+///
+/// Queries may contain placeholders such as `$1` or `$name`. The
+/// `ActionCreatePreparedStatementResult` then contains a
+/// `parameter_schema` with one field per placeholder. To bind values,
+/// the client calls `DoPut` with the `CommandPreparedStatementQuery` in
+/// the [`FlightDescriptor`] and a single-row record batch with one
+/// column per parameter.
+///
+/// IOx does not keep any state for prepared statements, so the values
+/// are embedded into a new handle that is sent back as
+/// `DoPutPreparedStatementResult` in the `app_metadata` of the
+/// [`PutResult`]. The client then uses this new handle for
+/// `GetFlightInfo` and `DoGet` as above.
+///
 /// ```text
 ///                                                      .───────.
 /// ╔═══════════╗                                       (         )
@@ -473,48 +560,235 @@ where
 {
     server: Arc<S>,
     authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
 }
 
 pub fn make_server<S>(
     server: Arc<S>,
     authz: Option<Arc<dyn Authorizer>>,
+    audit_log: Option<AuditLog>,
 ) -> FlightServer<impl Flight>
 where
     S: QueryNamespaceProvider,
 {
-    FlightServer::new(FlightService { server, authz })
+    FlightServer::new(FlightService {
+        server,
+        authz,
+        audit_log,
+    })
+}
+
+/// The RPC method a query was sent to, which determines how the results are streamed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseMode {
+    /// `DoGet`: the results, with empty record batches as keep-alive markers.
+    DoGet,
+    /// `DoExchange`: the results, interleaved with progress messages.
+    DoExchange,
+}
+
+impl ResponseMode {
+    fn method(&self) -> &'static str {
+        match self {
+            Self::DoGet => "DoGet",
+            Self::DoExchange => "DoExchange",
+        }
+    }
+}
+
+/// The parts of a `DoGet` or `DoExchange` request that are needed to run the query.
+#[derive(Debug)]
+struct QueryRequest {
+    span_ctx: Option<SpanContext>,
+    trace: String,
+    authz_token: Option<Vec<u8>>,
+    is_debug: bool,
+    priority: QueryPriority,
+    issuer: Option<Arc<str>>,
+}
+
+impl QueryRequest {
+    fn try_new<T>(request: &Request<T>) -> Result<Self, tonic::Status> {
+        let external_span_ctx: Option<RequestLogContext> = request.extensions().get().cloned();
+        Ok(Self {
+            span_ctx: request.extensions().get().cloned(),
+            trace: external_span_ctx.format_jaeger(),
+            authz_token: get_flight_authz(request.metadata()),
+            is_debug: has_debug_header(request.metadata()),
+            priority: get_priority_header(request.metadata())?,
+            issuer: get_issuer(request.remote_addr(), request.metadata()),
+        })
+    }
 }
 
 impl<S> FlightService<S>
 where
     S: QueryNamespaceProvider,
 {
-    /// Implementation of the `DoGet` method
-    async fn run_do_get(
+    /// Shared implementation of the `DoGet` and `DoExchange` methods, recording
+    /// the query in the audit log, if enabled.
+    ///
+    /// Only errors planning the query are recorded, not those streaming the
+    /// results.
+    async fn handle_query(
         &self,
-        span_ctx: Option<SpanContext>,
-        trace: String,
-        permit: InstrumentedAsyncOwnedSemaphorePermit,
-        query: RunQuery,
-        namespace_name: String,
-        is_debug: bool,
+        query_request: QueryRequest,
+        request: IoxGetRequest,
+        mode: ResponseMode,
     ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
+        let Some(audit_log) = &self.audit_log else {
+            return self.authorize_and_run(query_request, request, mode).await;
+        };
+
+        let query = request.query().to_string();
+        let record = AuditRecord::new(Operation::Query, format!("{}: {query}", mode.method()))
+            .with_namespace(request.database())
+            .with_token(query_request.authz_token.as_deref())
+            .with_bytes(query.len() as u64);
+        let response = self.authorize_and_run(query_request, request, mode).await;
+        audit_log.record(record.with_result(&response));
+        response
+    }
+
+    /// Authorizes and runs the query of a `DoGet` or `DoExchange` request
+    async fn authorize_and_run(
+        &self,
+        query_request: QueryRequest,
+        request: IoxGetRequest,
+        mode: ResponseMode,
+    ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
+        let QueryRequest {
+            span_ctx,
+            trace,
+            authz_token,
+            mut is_debug,
+            priority,
+            issuer,
+        } = query_request;
+
+        let namespace_name = request.database();
+        let query = request.query();
+        is_debug |= request.is_debug();
+
+        // SQL queries may read tables of other namespaces, see `QueryNamespaceProvider::federated_db`
+        let federated = match query {
+            RunQuery::Sql(sql) => referenced_schemas(sql),
+            RunQuery::InfluxQL(_) | RunQuery::FlightSQL(_) => vec![],
+        };
+
+        let perms = match query {
+            RunQuery::FlightSQL(cmd) => flightsql_permissions(namespace_name, cmd),
+            RunQuery::Sql(_) | RunQuery::InfluxQL(_) => std::iter::once(namespace_name)
+                .chain(federated.iter().map(|name| name.as_str()))
+                .map(|name| {
+                    authz::Permission::ResourceAction(
+                        authz::Resource::Database(name.to_string()),
+                        authz::Action::Read,
+                    )
+                })
+                .collect(),
+        };
+        self.authz
+            .permissions(authz_token, &perms)
+            .await
+            .map_err(Error::from)?;
+
+        // reject queries of namespaces that exhausted their quota before they take a slot of the global semaphore
+        let quota_permit = self
+            .server
+            .acquire_quota(namespace_name)
+            .await
+            .context(QuotaExceededSnafu { namespace_name })?;
+
         let db = self
             .server
-            .db(
-                &namespace_name,
+            .federated_db(
+                namespace_name,
+                &federated,
                 span_ctx.child_span("get namespace"),
                 is_debug,
             )
             .await
-            .context(DatabaseNotFoundSnafu {
-                namespace_name: &namespace_name,
-            })?;
+            .context(DatabaseNotFoundSnafu { namespace_name })?;
+
+        let ctx = db
+            .new_query_context(span_ctx.clone())
+            .with_priority(priority)
+            .with_issuer(issuer);
+
+        // Cancel all work related to this query once the request is dropped, e.g. because the client disconnected.
+        let cancel_guard = ctx.cancellation_token().clone().drop_guard();
+
+        // Admit the query once, so that all plans it executes share the same slot. Queries wait for admission before
+        // they take a slot of the global semaphore, so that queued low-priority queries cannot hold up others.
+        let queue_start = Instant::now();
+        let admission_permit = ctx.admit().await.context(QuerySnafu {
+            namespace_name,
+            query: query.to_string(),
+        })?;
+        let permit = self
+            .server
+            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+            .await;
+        let queue_wait = queue_start.elapsed();
 
-        let ctx = db.new_query_context(span_ctx);
+        // Log after we acquire the permit and are about to start execution
+        let start = Instant::now();
+        let method = mode.method();
+        info!(
+            %namespace_name,
+            %query,
+            %trace,
+            variant=query.variant(),
+            "{method} request",
+        );
+
+        let response = self
+            .run_query(
+                db,
+                ctx,
+                trace.clone(),
+                permit,
+                quota_permit,
+                admission_permit,
+                cancel_guard,
+                query.clone(),
+                namespace_name.to_string(),
+                queue_wait,
+                mode,
+            )
+            .await;
+
+        if let Err(e) = &response {
+            info!(%namespace_name, %query, %trace, %e, "Error running {method}");
+        } else {
+            let elapsed = Instant::now() - start;
+            debug!(%namespace_name, %query, %trace, ?elapsed, "Completed {method} request");
+        }
+        response
+    }
+
+    /// Plans and executes the query of a `DoGet` or `DoExchange` request
+    #[allow(clippy::too_many_arguments)]
+    async fn run_query(
+        &self,
+        db: Arc<S::Db>,
+        ctx: IOxSessionContext,
+        trace: String,
+        permit: InstrumentedAsyncOwnedSemaphorePermit,
+        quota_permit: QuotaPermit,
+        admission_permit: Option<AdmissionPermit>,
+        cancel_guard: DropGuard,
+        query: RunQuery,
+        namespace_name: String,
+        queue_wait: Duration,
+        mode: ResponseMode,
+    ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
         let (query_completed_token, physical_plan) = match &query {
             RunQuery::Sql(sql_query) => {
-                let token = db.record_query(&ctx, "sql", Box::new(sql_query.clone()));
+                let mut token = db.record_query(&ctx, "sql", Box::new(sql_query.clone()));
+                token.record_phase(QueryPhase::QueueWait, queue_wait);
+                token.enter_phase(QueryPhase::Planning);
                 let plan = Planner::new(&ctx)
                     .sql(sql_query)
                     .await
@@ -525,7 +799,9 @@ where
                 (token, plan)
             }
             RunQuery::InfluxQL(sql_query) => {
-                let token = db.record_query(&ctx, "influxql", Box::new(sql_query.clone()));
+                let mut token = db.record_query(&ctx, "influxql", Box::new(sql_query.clone()));
+                token.record_phase(QueryPhase::QueueWait, queue_wait);
+                token.enter_phase(QueryPhase::Planning);
                 let plan = Planner::new(&ctx)
                     .influxql(sql_query)
                     .await
@@ -536,7 +812,9 @@ where
                 (token, plan)
             }
             RunQuery::FlightSQL(msg) => {
-                let token = db.record_query(&ctx, "flightsql", Box::new(msg.to_string()));
+                let mut token = db.record_query(&ctx, "flightsql", Box::new(msg.to_string()));
+                token.record_phase(QueryPhase::QueueWait, queue_wait);
+                token.enter_phase(QueryPhase::Planning);
                 let plan = Planner::new(&ctx)
                     .flight_sql_do_get(&namespace_name, db, msg.clone())
                     .await
@@ -548,6 +826,12 @@ where
             }
         };
 
+        // warnings are issued during planning, so they are complete at this point
+        let warnings = ctx
+            .query_warnings()
+            .map(|warnings| warnings.warnings())
+            .unwrap_or_default();
+
         let output = GetStream::new(
             ctx,
             physical_plan,
@@ -555,19 +839,35 @@ where
             &query,
             query_completed_token,
             permit,
+            quota_permit,
+            admission_permit,
+            cancel_guard,
+            mode,
         )
         .await?;
 
         // Log any error that happens *during* execution (other error
         // handling in this file happen during planning)
+        let method = mode.method();
         let output = output.map(move |res| {
             if let Err(e) = &res {
-                info!(%namespace_name, %query, %trace, %e, "Error executing query via DoGet");
+                info!(%namespace_name, %query, %trace, %e, "Error executing query via {method}");
             }
             res
         });
 
-        Ok(Response::new(Box::pin(output) as TonicStream<FlightData>))
+        let mut response = Response::new(Box::pin(output) as TonicStream<FlightData>);
+        for warning in warnings {
+            match AsciiMetadataValue::try_from(warning.as_str()) {
+                Ok(value) => {
+                    response.metadata_mut().append(QUERY_WARNING_HEADER, value);
+                }
+                Err(e) => {
+                    warn!(%warning, %e, "Cannot send query warning as header");
+                }
+            }
+        }
+        Ok(response)
     }
 }
 
@@ -597,11 +897,7 @@ where
         &self,
         request: Request<Ticket>,
     ) -> Result<Response<Self::DoGetStream>, tonic::Status> {
-        let external_span_ctx: Option<RequestLogContext> = request.extensions().get().cloned();
-        let trace = external_span_ctx.format_jaeger();
-        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
-        let authz_token = get_flight_authz(request.metadata());
-        let mut is_debug = has_debug_header(request.metadata());
+        let query_request = QueryRequest::try_new(&request)?;
         let ticket = request.into_inner();
 
         // attempt to decode ticket
@@ -611,56 +907,8 @@ where
             info!(%e, "Error decoding Flight API ticket");
         };
 
-        let request = request?;
-        let namespace_name = request.database();
-        let query = request.query();
-        is_debug |= request.is_debug();
-
-        let perms = match query {
-            RunQuery::FlightSQL(cmd) => flightsql_permissions(namespace_name, cmd),
-            RunQuery::Sql(_) | RunQuery::InfluxQL(_) => vec![authz::Permission::ResourceAction(
-                authz::Resource::Database(namespace_name.to_string()),
-                authz::Action::Read,
-            )],
-        };
-        self.authz
-            .permissions(authz_token, &perms)
+        self.handle_query(query_request, request?, ResponseMode::DoGet)
             .await
-            .map_err(Error::from)?;
-
-        let permit = self
-            .server
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
-
-        // Log after we acquire the permit and are about to start execution
-        let start = Instant::now();
-        info!(
-            %namespace_name,
-            %query,
-            %trace,
-            variant=query.variant(),
-            "DoGet request",
-        );
-
-        let response = self
-            .run_do_get(
-                span_ctx,
-                trace.clone(),
-                permit,
-                query.clone(),
-                namespace_name.to_string(),
-                is_debug,
-            )
-            .await;
-
-        if let Err(e) = &response {
-            info!(%namespace_name, %query, %trace, %e, "Error running DoGet");
-        } else {
-            let elapsed = Instant::now() - start;
-            debug!(%namespace_name, %query, %trace, ?elapsed, "Completed DoGet request");
-        }
-        response
     }
 
     async fn handshake(
@@ -787,11 +1035,51 @@ where
 
     async fn do_put(
         &self,
-        _request: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> Result<Response<Self::DoPutStream>, tonic::Status> {
-        info!("Handling flightsql do_put body");
+        let external_span_ctx: Option<RequestLogContext> = request.extensions().get().cloned();
+        let trace = external_span_ctx.format_jaeger();
+
+        let namespace_name = get_flightsql_namespace(request.metadata())?;
+        let authz_token = get_flight_authz(request.metadata());
+        let mut stream = request.into_inner();
+
+        // the first message carries the descriptor with the FlightSQL command
+        let first = stream
+            .message()
+            .await?
+            .context(MissingFlightDescriptorSnafu)?;
+        let flight_descriptor = first
+            .flight_descriptor
+            .clone()
+            .context(MissingFlightDescriptorSnafu)?;
+        let cmd = cmd_from_descriptor(flight_descriptor)?;
+
+        info!(%namespace_name, %cmd, %trace, "DoPut request");
+
+        let perms = flightsql_permissions(&namespace_name, &cmd);
+        self.authz
+            .permissions(authz_token, &perms)
+            .await
+            .map_err(Error::from)?;
+
+        let batches: Vec<_> = FlightRecordBatchStream::new_from_flight_data(
+            futures::stream::once(async { Ok::<_, tonic::Status>(first) })
+                .chain(stream)
+                .map_err(FlightError::Tonic),
+        )
+        .try_collect()
+        .await
+        .map_err(flightsql::Error::from)
+        .context(FlightSQLSnafu)?;
+
+        let app_metadata =
+            FlightSQLPlanner::do_put(&namespace_name, cmd, batches).context(FlightSQLSnafu)?;
 
-        Err(tonic::Status::unimplemented("Not yet implemented: do_put"))
+        let result = PutResult { app_metadata };
+        let stream = futures::stream::iter([Ok(result)]);
+
+        Ok(Response::new(stream.boxed()))
     }
 
     async fn do_action(
@@ -859,11 +1147,30 @@ where
 
     async fn do_exchange(
         &self,
-        _request: Request<Streaming<FlightData>>,
+        request: Request<Streaming<FlightData>>,
     ) -> Result<Response<Self::DoExchangeStream>, tonic::Status> {
-        Err(tonic::Status::unimplemented(
-            "Not yet implemented: do_exchange",
-        ))
+        let query_request = QueryRequest::try_new(&request)?;
+        let mut stream = request.into_inner();
+
+        // the first message carries the ticket, the client does not send anything else
+        let flight_descriptor = stream
+            .message()
+            .await?
+            .and_then(|data| data.flight_descriptor)
+            .filter(|descriptor| descriptor.r#type() == DescriptorType::Cmd)
+            .context(MissingExchangeDescriptorSnafu)?;
+        let ticket = Ticket {
+            ticket: flight_descriptor.cmd,
+        };
+
+        let request = IoxGetRequest::try_decode(ticket).context(InvalidTicketSnafu);
+
+        if let Err(e) = &request {
+            info!(%e, "Error decoding Flight API ticket");
+        };
+
+        self.handle_query(query_request, request?, ResponseMode::DoExchange)
+            .await
     }
 }
 
@@ -956,36 +1263,92 @@ fn has_debug_header(metadata: &MetadataMap) -> bool {
         .unwrap_or_default()
 }
 
+/// Get query priority from the IOx priority header, defaults to [`QueryPriority::Interactive`].
+fn get_priority_header(metadata: &MetadataMap) -> Result<QueryPriority> {
+    let Some(value) = metadata.get(PRIORITY_HEADER) else {
+        return Ok(QueryPriority::default());
+    };
+    let value = value.to_str().map_err(|e| Error::InvalidPriorityHeader {
+        reason: e.to_string(),
+    })?;
+    value
+        .parse()
+        .map_err(|reason| Error::InvalidPriorityHeader { reason })
+}
+
+/// Describe who issued a request, e.g. `10.0.0.1:51234 (grpc-go/1.55.0)`.
+///
+/// This is only informational (e.g. for `system.queries`) and MUST NOT be used for authorization.
+fn get_issuer(remote_addr: Option<SocketAddr>, metadata: &MetadataMap) -> Option<Arc<str>> {
+    let user_agent = metadata
+        .get(USER_AGENT_HEADER)
+        .and_then(|v| v.to_str().ok());
+
+    match (remote_addr, user_agent) {
+        (Some(addr), Some(user_agent)) => Some(format!("{addr} ({user_agent})").into()),
+        (Some(addr), None) => Some(addr.to_string().into()),
+        (None, Some(user_agent)) => Some(user_agent.into()),
+        (None, None) => None,
+    }
+}
+
 /// Wrapper over a FlightDataEncodeStream that adds IOx specfic
 /// metadata and records completion
 struct GetStream {
-    inner: KeepAliveStream,
+    inner: BoxStream<'static, Result<FlightData, FlightError>>,
     #[allow(dead_code)]
     permit: InstrumentedAsyncOwnedSemaphorePermit,
+
+    /// Keeps the query counted against the namespace quota until the stream is dropped.
+    _quota_permit: QuotaPermit,
+
+    /// Keeps the query admitted until the stream is dropped.
+    _admission_permit: Option<AdmissionPermit>,
     query_completed_token: QueryCompletedToken,
     done: bool,
+
+    /// Set once the query produced its first record batch.
+    has_results: Arc<AtomicBool>,
+
+    /// Cancels the query execution when the stream is dropped before completion.
+    _cancel_guard: DropGuard,
 }
 
 impl GetStream {
+    #[allow(clippy::too_many_arguments)]
     async fn new(
         ctx: IOxSessionContext,
         physical_plan: Arc<dyn ExecutionPlan>,
         namespace_name: String,
         query: &RunQuery,
-        query_completed_token: QueryCompletedToken,
+        mut query_completed_token: QueryCompletedToken,
         permit: InstrumentedAsyncOwnedSemaphorePermit,
+        quota_permit: QuotaPermit,
+        admission_permit: Option<AdmissionPermit>,
+        cancel_guard: DropGuard,
+        mode: ResponseMode,
     ) -> Result<Self, tonic::Status> {
         let app_metadata = proto::AppMetadata {};
 
+        query_completed_token.enter_phase(QueryPhase::Execution);
+
         let schema = physical_plan.schema();
+        let progress = QueryProgressTracker::new(Arc::clone(&physical_plan));
+        let rows_returned = progress.rows_returned();
 
+        let has_results = Arc::new(AtomicBool::new(false));
+        let has_results_captured = Arc::clone(&has_results);
         let query_results = ctx
-            .execute_stream(Arc::clone(&physical_plan))
+            .execute_stream_cached(Arc::clone(&physical_plan))
             .await
             .context(QuerySnafu {
                 namespace_name: namespace_name.clone(),
                 query: query.to_string(),
             })?
+            .inspect_ok(move |batch| {
+                has_results_captured.store(true, Ordering::Relaxed);
+                rows_returned.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+            })
             .map_err(|e| {
                 let code = datafusion_error_to_tonic_code(&e);
                 tonic::Status::new(code, e.to_string()).into()
@@ -997,14 +1360,23 @@ impl GetStream {
             .with_metadata(app_metadata.encode_to_vec().into())
             .build(query_results);
 
-        // add keep alive
-        let inner = KeepAliveStream::new(inner, DO_GET_KEEP_ALIVE_INTERVAL);
+        // add keep alive, progress messages keep the stream alive as well
+        let inner = match mode {
+            ResponseMode::DoGet => KeepAliveStream::new(inner, DO_GET_KEEP_ALIVE_INTERVAL).boxed(),
+            ResponseMode::DoExchange => {
+                ProgressStream::new(inner, progress, DO_EXCHANGE_PROGRESS_INTERVAL).boxed()
+            }
+        };
 
         Ok(Self {
             inner,
             permit,
+            _quota_permit: quota_permit,
+            _admission_permit: admission_permit,
             query_completed_token,
             done: false,
+            has_results,
+            _cancel_guard: cancel_guard,
         })
     }
 }
@@ -1029,6 +1401,12 @@ impl Stream for GetStream {
                     self.query_completed_token.set_success();
                 }
                 Some(Ok(data)) => {
+                    if self.has_results.load(Ordering::Relaxed)
+                        && self.query_completed_token.current_phase() == Some(QueryPhase::Execution)
+                    {
+                        self.query_completed_token
+                            .enter_phase(QueryPhase::Streaming);
+                    }
                     return Poll::Ready(Some(Ok(data)));
                 }
                 Some(Err(e)) => {
@@ -1041,10 +1419,11 @@ impl Stream for GetStream {
 }
 #[cfg(test)]
 mod tests {
-    use arrow_flight::sql::ProstMessageExt;
+    use arrow_flight::{decode::FlightRecordBatchStream, sql::ProstMessageExt};
     use async_trait::async_trait;
     use authz::Permission;
     use futures::Future;
+    use iox_query::exec::{admission::AdmissionLimits, Executor};
     use metric::{Attributes, Metric, U64Gauge};
     use service_common::test_util::TestDatabaseStore;
     use tokio::pin;
@@ -1079,6 +1458,7 @@ mod tests {
         let service = FlightService {
             server: Arc::clone(&test_storage),
             authz: Option::<Arc<dyn Authorizer>>::None,
+            audit_log: None,
         };
         let ticket = Ticket {
             ticket: br#"{"namespace_name": "my_db", "sql_query": "SELECT 1;"}"#
@@ -1188,6 +1568,132 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_quota_exceeded() {
+        let test_storage = Arc::new(TestDatabaseStore::new_with_semaphore_size(2));
+        test_storage.db_or_create("my_db").await;
+
+        let service = FlightService {
+            server: Arc::new(ExhaustedQuotaStore(Arc::clone(&test_storage))),
+            authz: Option::<Arc<dyn Authorizer>>::None,
+            audit_log: None,
+        };
+        let ticket = Ticket {
+            ticket: br#"{"namespace_name": "my_db", "sql_query": "SELECT 1;"}"#
+                .to_vec()
+                .into(),
+        };
+        let status = service
+            .do_get(tonic::Request::new(ticket))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        assert_eq!(
+            status.message(),
+            "Query quota exceeded: namespace 'my_db' already runs the maximum of 1 concurrent queries",
+        );
+
+        // rejected queries do not take a slot of the global semaphore
+        assert_semaphore_metric(
+            &test_storage.metric_registry,
+            "iox_async_semaphore_permits_acquired",
+            0,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_admission_control() {
+        let executor = Executor::new_testing().with_admission_control(AdmissionLimits {
+            total: 2,
+            interactive: 1,
+            batch: 1,
+            background: 1,
+        });
+        let test_storage = Arc::new(TestDatabaseStore::new().with_executor(Arc::new(executor)));
+        test_storage.db_or_create("my_db").await;
+
+        let service = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Option::<Arc<dyn Authorizer>>::None,
+            audit_log: None,
+        };
+        let ticket = Ticket {
+            ticket: br#"{"namespace_name": "my_db", "sql_query": "SELECT 1;"}"#
+                .to_vec()
+                .into(),
+        };
+        let request = |priority: Option<&'static str>| {
+            let mut req = tonic::Request::new(ticket.clone());
+            if let Some(priority) = priority {
+                req.metadata_mut().insert(
+                    MetadataKey::from_static(PRIORITY_HEADER),
+                    MetadataValue::from_static(priority),
+                );
+            }
+            req
+        };
+
+        // the admitted query runs to completion while it holds its slot
+        let streaming_resp1 = service.do_get(request(None)).await.unwrap();
+        let batches = FlightRecordBatchStream::new_from_flight_data(
+            streaming_resp1.into_inner().map_err(FlightError::Tonic),
+        )
+        .try_collect::<Vec<_>>()
+        .await
+        .unwrap();
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 1);
+
+        // classes are limited independently
+        let streaming_resp1 = service.do_get(request(None)).await.unwrap();
+        let streaming_resp2 = service.do_get(request(Some("batch"))).await.unwrap();
+
+        // the slot is held until the response is dropped
+        let fut = service.do_get(request(Some("interactive")));
+        pin!(fut);
+        assert_fut_pending(&mut fut).await;
+
+        drop(streaming_resp1);
+        let streaming_resp3 = fut.await.unwrap();
+        drop(streaming_resp2);
+        drop(streaming_resp3);
+
+        // invalid priorities are rejected
+        let status = service.do_get(request(Some("urgent"))).await.unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    /// Wrapper around [`TestDatabaseStore`] that rejects all queries because of their quota.
+    #[derive(Debug)]
+    struct ExhaustedQuotaStore(Arc<TestDatabaseStore>);
+
+    #[async_trait]
+    impl QueryNamespaceProvider for ExhaustedQuotaStore {
+        type Db = <TestDatabaseStore as QueryNamespaceProvider>::Db;
+
+        async fn db(
+            &self,
+            name: &str,
+            span: Option<trace::span::Span>,
+            include_debug_info_tables: bool,
+        ) -> Option<Arc<Self::Db>> {
+            self.0.db(name, span, include_debug_info_tables).await
+        }
+
+        async fn acquire_semaphore(
+            &self,
+            span: Option<trace::span::Span>,
+        ) -> InstrumentedAsyncOwnedSemaphorePermit {
+            self.0.acquire_semaphore(span).await
+        }
+
+        async fn acquire_quota(&self, name: &str) -> Result<QuotaPermit, QuotaError> {
+            Err(QuotaError::TooManyConcurrentQueries {
+                namespace: name.to_owned(),
+                limit: 1,
+            })
+        }
+    }
+
     /// Assert that given future is pending.
     ///
     /// This will try to poll the future a bit to ensure that it is not stuck in tokios task preemption.
@@ -1242,6 +1748,7 @@ mod tests {
         let svc = FlightService {
             server: Arc::clone(&test_storage),
             authz: Some(Arc::new(MockAuthorizer {})),
+            audit_log: None,
         };
 
         async fn assert_code(
@@ -1342,6 +1849,65 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn do_get_federated_authz() {
+        /// Only grants read access to `bananas`.
+        #[derive(Debug)]
+        struct BananasAuthorizer;
+
+        #[async_trait]
+        impl Authorizer for BananasAuthorizer {
+            async fn permissions(
+                &self,
+                _token: Option<Vec<u8>>,
+                perms: &[Permission],
+            ) -> Result<Vec<Permission>, authz::Error> {
+                let bananas = Permission::ResourceAction(
+                    authz::Resource::Database("bananas".to_string()),
+                    authz::Action::Read,
+                );
+                if perms.iter().all(|perm| perm == &bananas) {
+                    Ok(perms.to_vec())
+                } else {
+                    Err(authz::Error::Forbidden)
+                }
+            }
+        }
+
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("bananas").await;
+
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Some(Arc::new(BananasAuthorizer)),
+            audit_log: None,
+        };
+
+        async fn code(svc: &FlightService<TestDatabaseStore>, sql: &str) -> tonic::Code {
+            let mut req = tonic::Request::new(
+                IoxGetRequest::new("bananas".to_string(), RunQuery::Sql(sql.to_string()), false)
+                    .try_encode()
+                    .unwrap(),
+            );
+            req.metadata_mut().insert(
+                MetadataKey::from_static("authorization"),
+                MetadataValue::from_static("Bearer GOOD"),
+            );
+            match svc.do_get(req).await {
+                Ok(_) => tonic::Code::Ok,
+                Err(e) => e.code(),
+            }
+        }
+
+        assert_eq!(code(&svc, "SELECT 1").await, tonic::Code::Ok);
+
+        // reading another namespace requires access to it as well
+        assert_eq!(
+            code(&svc, "SELECT * FROM apples.cpu").await,
+            tonic::Code::PermissionDenied
+        );
+    }
+
     #[tokio::test]
     async fn get_flight_info_authz() {
         let test_storage = Arc::new(TestDatabaseStore::default());
@@ -1350,6 +1916,7 @@ mod tests {
         let svc = FlightService {
             server: Arc::clone(&test_storage),
             authz: Some(Arc::new(MockAuthorizer {})),
+            audit_log: None,
         };
 
         async fn assert_code(
@@ -1386,4 +1953,92 @@ mod tests {
         assert_code(&svc, tonic::Code::PermissionDenied, request("Bearer BAD")).await;
         assert_code(&svc, tonic::Code::Internal, request("Bearer UGLY")).await;
     }
+
+    #[tokio::test]
+    async fn do_get_audit_log() {
+        let test_storage = Arc::new(TestDatabaseStore::default());
+        test_storage.db_or_create("bananas").await;
+
+        let metrics = metric::Registry::default();
+        let sink = Arc::new(audit::mock::MockAuditSink::default());
+        let svc = FlightService {
+            server: Arc::clone(&test_storage),
+            authz: Some(Arc::new(MockAuthorizer {})),
+            audit_log: Some(AuditLog::new("querier", Arc::clone(&sink) as _, &metrics)),
+        };
+
+        fn request(authorization: &'static str) -> tonic::Request<arrow_flight::Ticket> {
+            let mut req = tonic::Request::new(
+                IoxGetRequest::new(
+                    "bananas".to_string(),
+                    RunQuery::Sql("SELECT 1".to_string()),
+                    false,
+                )
+                .try_encode()
+                .unwrap(),
+            );
+            req.metadata_mut().insert(
+                MetadataKey::from_static("authorization"),
+                MetadataValue::from_static(authorization),
+            );
+            req
+        }
+
+        svc.do_get(request("Bearer GOOD")).await.unwrap();
+        svc.do_get(request("Bearer BAD")).await.unwrap_err();
+
+        let records = sink.wait_for_records(2).await;
+        let [ok, denied] = records.as_slice() else {
+            panic!("unexpected records: {records:?}");
+        };
+        assert_eq!(ok.operation, Operation::Query);
+        assert_eq!(ok.namespace.as_deref(), Some("bananas"));
+        assert_eq!(ok.identity, Some(audit::identity(b"GOOD")));
+        assert_eq!(ok.summary, "DoGet: SELECT 1");
+        assert_eq!(ok.bytes, 8);
+        assert_eq!(ok.status, audit::Status::Ok);
+
+        assert_eq!(denied.identity, Some(audit::identity(b"BAD")));
+        assert_eq!(denied.status, audit::Status::Error);
+    }
+
+    #[test]
+    fn test_priority_header() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(
+            get_priority_header(&metadata).unwrap(),
+            QueryPriority::Interactive
+        );
+
+        metadata.insert(PRIORITY_HEADER, MetadataValue::from_static("batch"));
+        assert_eq!(
+            get_priority_header(&metadata).unwrap(),
+            QueryPriority::Batch
+        );
+
+        metadata.insert(PRIORITY_HEADER, MetadataValue::from_static("urgent"));
+        let status = tonic::Status::from(get_priority_header(&metadata).unwrap_err());
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_issuer() {
+        let addr: SocketAddr = "10.0.0.1:1234".parse().unwrap();
+        let mut metadata = MetadataMap::new();
+        assert_eq!(get_issuer(None, &metadata), None);
+        assert_eq!(
+            get_issuer(Some(addr), &metadata).as_deref(),
+            Some("10.0.0.1:1234")
+        );
+
+        metadata.insert(
+            USER_AGENT_HEADER,
+            MetadataValue::from_static("grafana/10.0"),
+        );
+        assert_eq!(get_issuer(None, &metadata).as_deref(), Some("grafana/10.0"));
+        assert_eq!(
+            get_issuer(Some(addr), &metadata).as_deref(),
+            Some("10.0.0.1:1234 (grafana/10.0)")
+        );
+    }
 }
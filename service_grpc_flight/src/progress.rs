@@ -0,0 +1,266 @@
+//! Progress reporting for queries sent via `DoExchange`.
+//!
+//! Long-running queries may not produce any results for a long time. Clients want to render how far the query got
+//! and -- as explained in [`keep_alive`](crate::keep_alive) -- intermediaries may kill streams without any activity.
+//! So for `DoExchange` requests we regularly send a [`QueryProgress`] message, even when results are flowing.
+//!
+//! The progress is sent as the `app_metadata` of an empty [`RecordBatch`](arrow::record_batch::RecordBatch), for the
+//! same reason that keep-alive messages are empty record batches: clients that don't know about the progress messages
+//! will just decode them as empty batches.
+//!
+//! The progress is derived from the metrics of the data sources (= leaf nodes) of the physical plan, which are updated
+//! while the query executes.
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use arrow::datatypes::SchemaRef;
+use arrow_flight::{error::FlightError, FlightData};
+use datafusion::physical_plan::{metrics::MetricValue, ExecutionPlan};
+use futures::{stream::BoxStream, Stream, StreamExt};
+use generated_types::influxdata::iox::querier::v1::QueryProgress;
+use observability_deps::tracing::debug;
+use prost::Message;
+use tokio::time::{Interval, MissedTickBehavior};
+
+use crate::keep_alive::{build_empty_batch_msg, check_schema, decode_schema};
+
+/// Tracks the progress of a query while it executes.
+#[derive(Debug)]
+pub struct QueryProgressTracker {
+    plan: Arc<dyn ExecutionPlan>,
+    rows_returned: Arc<AtomicU64>,
+    start: Instant,
+}
+
+impl QueryProgressTracker {
+    /// Track the progress of the execution of `plan`, which starts now.
+    pub fn new(plan: Arc<dyn ExecutionPlan>) -> Self {
+        Self {
+            plan,
+            rows_returned: Default::default(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Counter of the result rows sent to the client, to be incremented by the caller.
+    pub fn rows_returned(&self) -> Arc<AtomicU64> {
+        Arc::clone(&self.rows_returned)
+    }
+
+    /// Current progress of the query.
+    pub fn progress(&self, done: bool) -> QueryProgress {
+        let mut progress = QueryProgress {
+            rows_returned: self.rows_returned.load(Ordering::Relaxed),
+            elapsed_ns: self.start.elapsed().as_nanos() as u64,
+            done,
+            ..Default::default()
+        };
+        add_scan_progress(self.plan.as_ref(), &mut progress);
+        progress
+    }
+}
+
+/// Add the progress of the data sources in `plan` to `progress`.
+fn add_scan_progress(plan: &dyn ExecutionPlan, progress: &mut QueryProgress) {
+    let children = plan.children();
+    if children.is_empty() {
+        progress.partitions_total += plan.output_partitioning().partition_count() as u64;
+
+        if let Some(metrics) = plan.metrics() {
+            progress.rows_scanned += metrics.output_rows().unwrap_or_default() as u64;
+            // the end timestamp of a partition is set once it is exhausted
+            progress.partitions_completed += metrics
+                .iter()
+                .filter(|metric| {
+                    matches!(metric.value(), MetricValue::EndTimestamp(ts) if ts.value().is_some())
+                })
+                .count() as u64;
+        }
+    }
+
+    for child in children {
+        add_scan_progress(child.as_ref(), progress);
+    }
+}
+
+/// Interleaves the underlying response stream with regular [`QueryProgress`] messages.
+///
+/// Contrary to [`KeepAliveStream`](crate::keep_alive::KeepAliveStream), the progress is sent in fixed intervals, not
+/// only when the underlying stream is idle. A final progress message with [`QueryProgress::done`] set is sent after
+/// the underlying stream ended successfully.
+pub struct ProgressStream {
+    inner: BoxStream<'static, Result<FlightData, FlightError>>,
+}
+
+impl ProgressStream {
+    /// Create new progress wrapper from the underlying stream, the tracker of the query and the given interval.
+    pub fn new<S>(s: S, tracker: QueryProgressTracker, interval: Duration) -> Self
+    where
+        S: Stream<Item = Result<FlightData, FlightError>> + Send + 'static,
+    {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // the first tick completes immediately, but there is no progress to report yet
+        ticker.reset();
+        let state = State {
+            inner: Some(s.boxed()),
+            schema: None,
+            tracker,
+            ticker,
+        };
+
+        let inner = futures::stream::unfold(state, |mut state| async move {
+            loop {
+                let Some(inner) = state.inner.as_mut() else {
+                    return None;
+                };
+
+                tokio::select! {
+                    _ = state.ticker.tick() => {
+                        let Some(data) = state.progress_msg(false) else {
+                            continue;
+                        };
+                        debug!("stream progress");
+                        return Some((Ok(data), state));
+                    }
+                    res = inner.next() => {
+                        match res {
+                            Some(Ok(data)) => {
+                                // peek at content to detect schema transmission
+                                if let Some(schema) = decode_schema(&data) {
+                                    if check_schema(&schema) {
+                                        state.schema = Some(Arc::new(schema));
+                                    }
+                                }
+                                return Some((Ok(data), state));
+                            }
+                            Some(Err(e)) => {
+                                state.inner = None;
+                                return Some((Err(e), state));
+                            }
+                            None => {
+                                state.inner = None;
+                                let data = state.progress_msg(true)?;
+                                return Some((Ok(data), state));
+                            }
+                        }
+                    }
+                }
+            }
+        })
+        .boxed();
+
+        Self { inner }
+    }
+}
+
+impl Stream for ProgressStream {
+    type Item = Result<FlightData, FlightError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+/// Inner state of [`ProgressStream`]
+struct State {
+    /// The underlying stream, `None` once it ended.
+    inner: Option<BoxStream<'static, Result<FlightData, FlightError>>>,
+
+    /// A [`Schema`](arrow::datatypes::Schema) that was already received from the stream.
+    ///
+    /// Progress messages are empty record batches, which can only be sent AFTER the schema.
+    schema: Option<SchemaRef>,
+
+    /// Progress of the query.
+    tracker: QueryProgressTracker,
+
+    /// Progress ticker.
+    ticker: Interval,
+}
+
+impl State {
+    /// Encode the current progress as a message, if the schema was already sent.
+    fn progress_msg(&self, done: bool) -> Option<FlightData> {
+        let mut data = build_empty_batch_msg(self.schema.as_ref())?;
+        data.app_metadata = self.tracker.progress(done).encode_to_vec().into();
+        Some(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+        record_batch::RecordBatch,
+    };
+    use arrow_flight::{decode::FlightDataDecoder, encode::FlightDataEncoderBuilder};
+    use datafusion::{physical_plan::memory::MemoryExec, prelude::SessionContext};
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::keep_alive::test_util::make_stream_slow;
+
+    #[tokio::test]
+    async fn test_progress() {
+        let schema = Arc::new(Schema::new(vec![Field::new("f", DataType::Int64, false)]));
+        let batch = |values: Vec<i64>| {
+            RecordBatch::try_new(
+                Arc::clone(&schema),
+                vec![Arc::new(Int64Array::from(values))],
+            )
+            .unwrap()
+        };
+        let plan: Arc<dyn ExecutionPlan> = Arc::new(
+            MemoryExec::try_new(
+                &[vec![batch(vec![1, 2, 3])], vec![batch(vec![4, 5])]],
+                Arc::clone(&schema),
+                None,
+            )
+            .unwrap(),
+        );
+
+        let tracker = QueryProgressTracker::new(Arc::clone(&plan));
+        let progress = tracker.progress(false);
+        assert_eq!(progress.partitions_total, 2);
+        assert_eq!(progress.rows_returned, 0);
+        assert!(!progress.done);
+
+        let rows_returned = tracker.rows_returned();
+        let results =
+            datafusion::physical_plan::execute_stream(plan, SessionContext::new().task_ctx())
+                .unwrap()
+                .inspect_ok(move |batch| {
+                    rows_returned.fetch_add(batch.num_rows() as u64, Ordering::Relaxed);
+                })
+                .map_err(|e| FlightError::ExternalError(Box::new(e)));
+        let results = make_stream_slow(results, Duration::from_millis(300));
+        let s = FlightDataEncoderBuilder::new()
+            .with_schema(Arc::clone(&schema))
+            .build(results);
+        let s = ProgressStream::new(s, tracker, Duration::from_millis(100));
+
+        let decoded: Vec<_> = FlightDataDecoder::new(s).try_collect().await.unwrap();
+        let progress: Vec<_> = decoded
+            .iter()
+            .filter(|data| !data.inner.app_metadata.is_empty())
+            .map(|data| QueryProgress::decode(&data.inner.app_metadata[..]).unwrap())
+            .collect();
+
+        // progress was reported while the results were slowly streamed
+        assert!(progress.len() > 1, "{progress:?}");
+        let (last, others) = progress.split_last().unwrap();
+        assert!(others.iter().all(|p| !p.done));
+        assert!(last.done);
+        assert_eq!(last.rows_returned, 5);
+        assert_eq!(last.partitions_total, 2);
+    }
+}
@@ -4,9 +4,12 @@ use std::{
 };
 
 use futures::{ready, Stream, StreamExt};
-use iox_query::QueryCompletedToken;
+use iox_query::{QueryCompletedToken, QueryPhase};
 
 /// Wraps an inner query stream, calling the `QueryCompletedToken::set_success` on success
+///
+/// The token is in the [execution](QueryPhase::Execution) phase until the first result is produced and in the
+/// [streaming](QueryPhase::Streaming) phase afterwards.
 #[derive(Debug)]
 pub struct QueryCompletedTokenStream<S, T, E>
 where
@@ -21,7 +24,8 @@ impl<S, T, E> QueryCompletedTokenStream<S, T, E>
 where
     S: Stream<Item = Result<T, E>> + Unpin + Send,
 {
-    pub fn new(inner: S, token: QueryCompletedToken) -> Self {
+    pub fn new(inner: S, mut token: QueryCompletedToken) -> Self {
+        token.enter_phase(QueryPhase::Execution);
         Self {
             inner,
             token,
@@ -46,7 +50,12 @@ where
                 }
                 Poll::Ready(None)
             }
-            Some(Ok(x)) => Poll::Ready(Some(Ok(x))),
+            Some(Ok(x)) => {
+                if this.token.current_phase() == Some(QueryPhase::Execution) {
+                    this.token.enter_phase(QueryPhase::Streaming);
+                }
+                Poll::Ready(Some(Ok(x)))
+            }
             Some(Err(e)) => {
                 this.found_err = true;
                 Poll::Ready(Some(Err(e)))
@@ -97,11 +106,31 @@ mod tests {
         assert_eq!(*res.lock(), Some(false));
     }
 
+    #[tokio::test]
+    async fn test_phases() {
+        let token = Arc::new(Mutex::new(None));
+        let token_captured = Arc::clone(&token);
+        let qct = QueryCompletedToken::new(move |completion| {
+            *token_captured.lock() = Some(completion);
+        });
+
+        let stream =
+            QueryCompletedTokenStream::new(futures::stream::iter([Ok::<_, ()>(()), Ok(())]), qct);
+        stream.collect::<Vec<_>>().await;
+
+        let completion = token.lock().unwrap();
+        assert!(completion.success);
+        assert!(completion.timings.queue_wait.is_none());
+        assert!(completion.timings.planning.is_none());
+        assert!(completion.timings.execution.is_some());
+        assert!(completion.timings.streaming.is_some());
+    }
+
     fn token() -> (Arc<Mutex<Option<bool>>>, QueryCompletedToken) {
         let token = Arc::new(Mutex::new(None));
         let token_captured = Arc::clone(&token);
-        let qct = QueryCompletedToken::new(move |success| {
-            *token_captured.lock() = Some(success);
+        let qct = QueryCompletedToken::new(move |completion| {
+            *token_captured.lock() = Some(completion.success);
         });
         (token, qct)
     }
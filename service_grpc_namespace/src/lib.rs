@@ -29,16 +29,38 @@ use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, info, warn};
 use tonic::{Request, Response, Status};
 
+/// Notified of the namespaces undeleted through a [`NamespaceService`], so
+/// that state derived from a namespace not existing (such as cached "not
+/// found" lookups) can be invalidated.
+pub trait UndeleteObserver: std::fmt::Debug + Send + Sync {
+    /// Called after `namespace` was undeleted in the catalog.
+    fn namespace_undeleted(&self, namespace: &NamespaceName<'static>);
+}
+
 /// Implementation of the gRPC namespace service
 #[derive(Debug)]
 pub struct NamespaceService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// Notified of undeleted namespaces, if any.
+    undelete_observer: Option<Arc<dyn UndeleteObserver>>,
 }
 
 impl NamespaceService {
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            undelete_observer: None,
+        }
+    }
+
+    /// Notify `observer` of every namespace undeleted through this service.
+    pub fn with_undelete_observer(self, observer: Arc<dyn UndeleteObserver>) -> Self {
+        Self {
+            undelete_observer: Some(observer),
+            ..self
+        }
     }
 }
 
@@ -139,6 +161,42 @@ impl namespace_service_server::NamespaceService for NamespaceService {
         Ok(Response::new(Default::default()))
     }
 
+    async fn undelete_namespace(
+        &self,
+        request: Request<UndeleteNamespaceRequest>,
+    ) -> Result<Response<UndeleteNamespaceResponse>, Status> {
+        let namespace_name = request.into_inner().name;
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .undelete(&namespace_name)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %namespace_name, "failed to undelete namespace");
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            namespace_name,
+            namespace_id = %namespace.id,
+            "undeleted namespace"
+        );
+
+        if let Some(observer) = &self.undelete_observer {
+            match NamespaceName::try_from(namespace.name.clone()) {
+                Ok(name) => observer.namespace_undeleted(&name),
+                Err(e) => warn!(error=%e, namespace_name, "invalid undeleted namespace name"),
+            }
+        }
+
+        Ok(Response::new(UndeleteNamespaceResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
+
     async fn update_namespace_retention(
         &self,
         request: Request<UpdateNamespaceRetentionRequest>,
@@ -256,6 +314,238 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             },
         ))
     }
+
+    async fn update_namespace_compaction(
+        &self,
+        request: Request<UpdateNamespaceCompactionRequest>,
+    ) -> Result<Response<UpdateNamespaceCompactionResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceCompactionRequest {
+            name: namespace_name,
+            compaction_paused,
+        } = request.into_inner();
+
+        debug!(
+            %namespace_name,
+            compaction_paused,
+            "updating namespace compaction",
+        );
+
+        let namespace = repos
+            .namespaces()
+            .update_compaction_paused(&namespace_name, compaction_paused)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    compaction_paused,
+                    "failed to update compaction of namespace",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            compaction_paused = namespace.compaction_paused,
+            "updated namespace compaction",
+        );
+
+        Ok(Response::new(UpdateNamespaceCompactionResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
+
+    async fn update_namespace_write_rate_limit(
+        &self,
+        request: Request<UpdateNamespaceWriteRateLimitRequest>,
+    ) -> Result<Response<UpdateNamespaceWriteRateLimitResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceWriteRateLimitRequest {
+            name: namespace_name,
+            max_write_lines_per_second,
+            max_write_bytes_per_second,
+        } = request.into_inner();
+
+        debug!(
+            %namespace_name,
+            ?max_write_lines_per_second,
+            ?max_write_bytes_per_second,
+            "updating namespace write rate limits",
+        );
+
+        if max_write_lines_per_second.map_or(false, |v| v <= 0) {
+            return Err(Status::invalid_argument(
+                "write lines per second limit for namespace must be greater than 0",
+            ));
+        }
+        if max_write_bytes_per_second.map_or(false, |v| v <= 0) {
+            return Err(Status::invalid_argument(
+                "write bytes per second limit for namespace must be greater than 0",
+            ));
+        }
+
+        let namespace = repos
+            .namespaces()
+            .update_write_rate_limits(
+                &namespace_name,
+                max_write_lines_per_second,
+                max_write_bytes_per_second,
+            )
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    ?max_write_lines_per_second,
+                    ?max_write_bytes_per_second,
+                    "failed to update write rate limits of namespace",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            max_write_lines_per_second = ?namespace.max_write_lines_per_second,
+            max_write_bytes_per_second = ?namespace.max_write_bytes_per_second,
+            "updated namespace write rate limits",
+        );
+
+        Ok(Response::new(UpdateNamespaceWriteRateLimitResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
+
+    async fn update_namespace(
+        &self,
+        request: Request<UpdateNamespaceRequest>,
+    ) -> Result<Response<UpdateNamespaceResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateNamespaceRequest {
+            name: namespace_name,
+            retention_period_ns,
+            max_tables,
+            max_columns_per_table,
+            compaction_paused,
+        } = request.into_inner();
+
+        debug!(
+            %namespace_name,
+            ?retention_period_ns,
+            ?max_tables,
+            ?max_columns_per_table,
+            ?compaction_paused,
+            "updating namespace",
+        );
+
+        // Validate the whole request before changing anything, the updates
+        // below are not applied atomically.
+        let retention_period_ns = retention_period_ns
+            .map(|v| map_retention_period(Some(v)))
+            .transpose()?;
+        if max_tables.map_or(false, |n| n <= 0) {
+            return Err(Status::invalid_argument(
+                "max table limit for namespace must be greater than 0",
+            ));
+        }
+        if max_columns_per_table.map_or(false, |n| n <= 0) {
+            return Err(Status::invalid_argument(
+                "max columns per table limit for namespace must be greater than 0",
+            ));
+        }
+
+        let mut namespace = None;
+        if let Some(retention_period_ns) = retention_period_ns {
+            namespace = Some(
+                repos
+                    .namespaces()
+                    .update_retention_period(&namespace_name, retention_period_ns)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            ?retention_period_ns,
+                            "failed to update namespace retention",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })?,
+            );
+        }
+        if let Some(n) = max_tables {
+            namespace = Some(
+                repos
+                    .namespaces()
+                    .update_table_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            table_limit = %n,
+                            "failed to update table limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })?,
+            );
+        }
+        if let Some(n) = max_columns_per_table {
+            namespace = Some(
+                repos
+                    .namespaces()
+                    .update_column_limit(&namespace_name, n)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            per_table_column_limit = %n,
+                            "failed to update per table column limit for namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })?,
+            );
+        }
+        if let Some(compaction_paused) = compaction_paused {
+            namespace = Some(
+                repos
+                    .namespaces()
+                    .update_compaction_paused(&namespace_name, compaction_paused)
+                    .await
+                    .map_err(|e| {
+                        warn!(
+                            error = %e,
+                            %namespace_name,
+                            compaction_paused,
+                            "failed to update compaction of namespace",
+                        );
+                        status_from_catalog_namespace_error(e)
+                    })?,
+            );
+        }
+
+        let namespace =
+            namespace.ok_or_else(|| Status::invalid_argument("no namespace settings to update"))?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            retention_period_ns = ?namespace.retention_period_ns,
+            max_tables = %namespace.max_tables,
+            max_columns_per_table = %namespace.max_columns_per_table,
+            compaction_paused = namespace.compaction_paused,
+            "updated namespace",
+        );
+
+        Ok(Response::new(UpdateNamespaceResponse {
+            namespace: Some(namespace_to_proto(namespace)),
+        }))
+    }
 }
 
 fn namespace_to_proto(namespace: CatalogNamespace) -> Namespace {
@@ -265,6 +555,9 @@ fn namespace_to_proto(namespace: CatalogNamespace) -> Namespace {
         retention_period_ns: namespace.retention_period_ns,
         max_tables: namespace.max_tables,
         max_columns_per_table: namespace.max_columns_per_table,
+        compaction_paused: namespace.compaction_paused,
+        max_write_lines_per_second: namespace.max_write_lines_per_second,
+        max_write_bytes_per_second: namespace.max_write_bytes_per_second,
     }
 }
 
@@ -276,6 +569,9 @@ fn namespace_to_create_response_proto(namespace: CatalogNamespace) -> CreateName
             retention_period_ns: namespace.retention_period_ns,
             max_tables: namespace.max_tables,
             max_columns_per_table: namespace.max_columns_per_table,
+            compaction_paused: namespace.compaction_paused,
+            max_write_lines_per_second: namespace.max_write_lines_per_second,
+            max_write_bytes_per_second: namespace.max_write_bytes_per_second,
         }),
     }
 }
@@ -480,6 +776,150 @@ mod tests {
         assert_eq!(updated_ns.max_tables, want_max_tables);
         assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
 
+        // Pause compaction
+        assert!(!created_ns.compaction_paused);
+        let updated_ns = handler
+            .update_namespace_compaction(Request::new(UpdateNamespaceCompactionRequest {
+                name: NS_NAME.to_string(),
+                compaction_paused: true,
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.name, created_ns.name);
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert!(updated_ns.compaction_paused);
+
+        // Pausing compaction of an unknown namespace fails
+        let status = handler
+            .update_namespace_compaction(Request::new(UpdateNamespaceCompactionRequest {
+                name: "does_not_exist".to_string(),
+                compaction_paused: true,
+            }))
+            .await
+            .expect_err("updating unknown namespace should fail");
+        assert_eq!(status.code(), Code::NotFound);
+
+        // Set write rate limits
+        assert_eq!(created_ns.max_write_lines_per_second, None);
+        assert_eq!(created_ns.max_write_bytes_per_second, None);
+        let updated_ns = handler
+            .update_namespace_write_rate_limit(Request::new(UpdateNamespaceWriteRateLimitRequest {
+                name: NS_NAME.to_string(),
+                max_write_lines_per_second: Some(1_000),
+                max_write_bytes_per_second: None,
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.max_write_lines_per_second, Some(1_000));
+        assert_eq!(updated_ns.max_write_bytes_per_second, None);
+
+        // Non-positive write rate limits are rejected
+        let status = handler
+            .update_namespace_write_rate_limit(Request::new(UpdateNamespaceWriteRateLimitRequest {
+                name: NS_NAME.to_string(),
+                max_write_lines_per_second: None,
+                max_write_bytes_per_second: Some(0),
+            }))
+            .await
+            .expect_err("zero write rate limit should be rejected");
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        // Update several settings at once, leaving the others unchanged
+        let updated_ns = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: Some(RETENTION),
+                max_tables: Some(want_max_tables + 1),
+                max_columns_per_table: None,
+                compaction_paused: Some(false),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.name, created_ns.name);
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.retention_period_ns, Some(RETENTION));
+        assert_eq!(updated_ns.max_tables, want_max_tables + 1);
+        assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
+        assert!(!updated_ns.compaction_paused);
+        assert_eq!(updated_ns.max_write_lines_per_second, Some(1_000));
+
+        // A zero retention period means infinite retention
+        let updated_ns = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: Some(0),
+                ..Default::default()
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.retention_period_ns, None);
+        assert_eq!(updated_ns.max_tables, want_max_tables + 1);
+
+        // Invalid values are rejected without applying any of the changes
+        let status = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: Some(RETENTION),
+                max_tables: None,
+                max_columns_per_table: Some(0),
+                compaction_paused: None,
+            }))
+            .await
+            .expect_err("zero column limit should be rejected");
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let status = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: Some(-1),
+                ..Default::default()
+            }))
+            .await
+            .expect_err("negative retention should be rejected");
+        assert_eq!(status.code(), Code::InvalidArgument);
+        {
+            let current = handler
+                .get_namespaces(Request::new(Default::default()))
+                .await
+                .expect("must return namespaces")
+                .into_inner()
+                .namespaces;
+            assert_eq!(current, vec![updated_ns]);
+        }
+
+        // An update without any settings is rejected
+        let status = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                ..Default::default()
+            }))
+            .await
+            .expect_err("empty update should be rejected");
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        // Updating an unknown namespace fails
+        let status = handler
+            .update_namespace(Request::new(UpdateNamespaceRequest {
+                name: "does_not_exist".to_string(),
+                max_tables: Some(1),
+                ..Default::default()
+            }))
+            .await
+            .expect_err("updating unknown namespace should fail");
+        assert_eq!(status.code(), Code::NotFound);
+
         // Deleting the namespace should cause it to disappear
         handler
             .delete_namespace(Request::new(DeleteNamespaceRequest {
@@ -498,6 +938,88 @@ mod tests {
                 .namespaces;
             assert_matches!(current.as_slice(), []);
         }
+
+        // Undeleting the namespace restores it
+        let undeleted_ns = handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must undelete")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(undeleted_ns.name, NS_NAME);
+        {
+            let current = handler
+                .get_namespaces(Request::new(Default::default()))
+                .await
+                .expect("must return namespaces")
+                .into_inner()
+                .namespaces;
+            assert_eq!(current, vec![undeleted_ns]);
+        }
+
+        // Undeleting a namespace that is not soft-deleted fails
+        let status = handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect_err("undeleting an active namespace should fail");
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[derive(Debug, Default)]
+    struct MockUndeleteObserver {
+        undeleted: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl UndeleteObserver for MockUndeleteObserver {
+        fn namespace_undeleted(&self, namespace: &NamespaceName<'static>) {
+            self.undeleted.lock().unwrap().push(namespace.to_string());
+        }
+    }
+
+    #[tokio::test]
+    async fn undelete_notifies_observer() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let observer = Arc::new(MockUndeleteObserver::default());
+        let handler = NamespaceService::new(Arc::clone(&catalog))
+            .with_undelete_observer(Arc::clone(&observer) as _);
+
+        handler
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                ..Default::default()
+            }))
+            .await
+            .expect("must create");
+        handler
+            .delete_namespace(Request::new(DeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must delete");
+        assert!(observer.undeleted.lock().unwrap().is_empty());
+
+        handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect("must undelete");
+        assert_eq!(*observer.undeleted.lock().unwrap(), [NS_NAME]);
+
+        // A failed undelete is not observed.
+        handler
+            .undelete_namespace(Request::new(UndeleteNamespaceRequest {
+                name: NS_NAME.to_string(),
+            }))
+            .await
+            .expect_err("undeleting an active namespace should fail");
+        assert_eq!(*observer.undeleted.lock().unwrap(), [NS_NAME]);
     }
 
     #[tokio::test]
@@ -139,6 +139,7 @@ mod tests {
                 created_at: Timestamp::new(2343),
                 column_set: ColumnSet::new([ColumnId::new(1), ColumnId::new(2)]),
                 max_l0_created_at: Timestamp::new(2343),
+                tag_ranges: None,
             };
 
             p1 = repos.parquet_files().create(p1params).await.unwrap();
@@ -22,10 +22,11 @@
 // Workaround for "unused crate" lint false positives.
 use workspace_hack as _;
 
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use data_types::{
-    partition_template::TablePartitionTemplateOverride, NamespaceName, Table as CatalogTable,
+    partition_template::TablePartitionTemplateOverride, ColumnMetadata as CatalogColumnMetadata,
+    NamespaceName, Table as CatalogTable,
 };
 use generated_types::influxdata::iox::table::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
@@ -112,26 +113,350 @@ impl table_service_server::TableService for TableService {
 
         Ok(Response::new(table_to_create_response_proto(table)))
     }
+
+    // list the tables of a namespace
+    async fn get_tables(
+        &self,
+        request: Request<GetTablesRequest>,
+    ) -> Result<Response<GetTablesResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let GetTablesRequest { namespace } = request.into_inner();
+
+        debug!(%namespace, "Listing tables");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Could not find a namespace with name {namespace}"))
+            })?;
+
+        let mut tables = repos
+            .tables()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, namespace_id=%namespace.id, "failed to list tables");
+                Status::internal(e.to_string())
+            })?;
+        tables.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(Response::new(GetTablesResponse {
+            tables: tables.into_iter().map(table_to_proto).collect(),
+        }))
+    }
+
+    // delete a table that has not been written to
+    async fn delete_table(
+        &self,
+        request: Request<DeleteTableRequest>,
+    ) -> Result<Response<DeleteTableResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let DeleteTableRequest { namespace, name } = request.into_inner();
+
+        debug!(%name, %namespace, "Deleting table");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Could not find a namespace with name {namespace}"))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &name)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {name} in namespace {}",
+                    namespace.name
+                ))
+            })?;
+
+        let table = repos.tables().delete(table.id).await.map_err(|e| {
+            warn!(error=%e, %name, "failed to delete table");
+            match e {
+                iox_catalog::interface::Error::TableNotEmpty { .. } => {
+                    Status::failed_precondition(format!(
+                        "The table `{name}` in the namespace `{}` has been written to \
+                            and cannot be deleted",
+                        namespace.name
+                    ))
+                }
+                iox_catalog::interface::Error::TableNotFound { .. } => {
+                    Status::not_found(e.to_string())
+                }
+                other => Status::internal(other.to_string()),
+            }
+        })?;
+
+        info!(%name, table_id = %table.id, "deleted table");
+
+        Ok(Response::new(DeleteTableResponse {
+            table: Some(table_to_proto(table)),
+        }))
+    }
+
+    // set or remove the retention period override of a table
+    async fn update_table_retention(
+        &self,
+        request: Request<UpdateTableRetentionRequest>,
+    ) -> Result<Response<UpdateTableRetentionResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateTableRetentionRequest {
+            namespace,
+            name,
+            retention_period_ns,
+        } = request.into_inner();
+
+        let retention_period_ns = map_retention_period(retention_period_ns)?;
+
+        debug!(%name, %namespace, ?retention_period_ns, "Updating table retention");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Could not find a namespace with name {namespace}"))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &name)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {name} in namespace {}",
+                    namespace.name
+                ))
+            })?;
+
+        let table = repos
+            .tables()
+            .update_retention_period(table.id, retention_period_ns)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %name, "failed to update table retention");
+                Status::internal(e.to_string())
+            })?;
+
+        info!(
+            %name,
+            table_id = %table.id,
+            retention_period_ns,
+            "updated table retention"
+        );
+
+        Ok(Response::new(UpdateTableRetentionResponse {
+            table: Some(table_to_proto(table)),
+        }))
+    }
+
+    // set the metadata of a column
+    async fn update_column_metadata(
+        &self,
+        request: Request<UpdateColumnMetadataRequest>,
+    ) -> Result<Response<UpdateColumnMetadataResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateColumnMetadataRequest {
+            namespace,
+            table,
+            column,
+            description,
+            unit,
+            semantic_type,
+        } = request.into_inner();
+
+        debug!(%namespace, %table, %column, "Updating column metadata");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Could not find a namespace with name {namespace}"))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &table)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {table} in namespace {}",
+                    namespace.name
+                ))
+            })?;
+
+        let catalog_column = repos
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .find(|c| c.name == column)
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a column with name {column} in table {}",
+                    table.name
+                ))
+            })?;
+
+        let metadata = repos
+            .columns()
+            .update_metadata(CatalogColumnMetadata {
+                column_id: catalog_column.id,
+                description,
+                unit,
+                semantic_type,
+            })
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %column, "failed to update column metadata");
+                Status::internal(e.to_string())
+            })?;
+
+        info!(
+            table = %table.name,
+            %column,
+            column_id = %catalog_column.id,
+            "updated column metadata"
+        );
+
+        Ok(Response::new(UpdateColumnMetadataResponse {
+            metadata: Some(column_metadata_to_proto(
+                table.name,
+                catalog_column.name,
+                metadata,
+            )),
+        }))
+    }
+
+    // get the metadata of the columns of a namespace
+    async fn get_column_metadata(
+        &self,
+        request: Request<GetColumnMetadataRequest>,
+    ) -> Result<Response<GetColumnMetadataResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let GetColumnMetadataRequest { namespace, table } = request.into_inner();
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!("Could not find a namespace with name {namespace}"))
+            })?;
+
+        let tables: HashMap<_, _> = repos
+            .tables()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .filter(|t| table.as_ref().map_or(true, |name| &t.name == name))
+            .map(|t| (t.id, t.name))
+            .collect();
+
+        let columns: HashMap<_, _> = repos
+            .columns()
+            .list_by_namespace_id(namespace.id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(|c| (c.id, c))
+            .collect();
+
+        let mut metadata = repos
+            .columns()
+            .list_metadata_by_namespace_id(namespace.id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .filter_map(|m| {
+                let column = columns.get(&m.column_id)?;
+                let table = tables.get(&column.table_id)?;
+                Some(column_metadata_to_proto(
+                    table.clone(),
+                    column.name.clone(),
+                    m,
+                ))
+            })
+            .collect::<Vec<_>>();
+        metadata.sort_by(|a, b| (&a.table, &a.column).cmp(&(&b.table, &b.column)));
+
+        Ok(Response::new(GetColumnMetadataResponse { metadata }))
+    }
 }
 
 fn table_to_create_response_proto(table: CatalogTable) -> CreateTableResponse {
     CreateTableResponse {
-        table: Some(Table {
-            id: table.id.get(),
-            name: table.name.clone(),
-            namespace_id: table.namespace_id.get(),
-        }),
+        table: Some(table_to_proto(table)),
+    }
+}
+
+fn table_to_proto(table: CatalogTable) -> Table {
+    Table {
+        id: table.id.get(),
+        name: table.name,
+        namespace_id: table.namespace_id.get(),
+        retention_period_ns: table.retention_period_ns,
+    }
+}
+
+fn column_metadata_to_proto(
+    table: String,
+    column: String,
+    metadata: CatalogColumnMetadata,
+) -> ColumnMetadata {
+    ColumnMetadata {
+        table,
+        column,
+        description: metadata.description,
+        unit: metadata.unit,
+        semantic_type: metadata.semantic_type,
+    }
+}
+
+/// Map the retention period override of a request, rejecting values that are not positive.
+fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
+    match v {
+        Some(v @ 1..) => Ok(Some(v)),
+        Some(_) => Err(Status::invalid_argument(
+            "retention period override must be positive",
+        )),
+        None => Ok(None),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use data_types::{partition_template::NamespacePartitionTemplateOverride, TableId};
+    use data_types::{partition_template::NamespacePartitionTemplateOverride, ColumnType, TableId};
     use generated_types::influxdata::iox::{
         partition_template::v1::{template_part, PartitionTemplate, TemplatePart},
         table::v1::table_service_server::TableService as _,
     };
-    use iox_catalog::{mem::MemCatalog, test_helpers::arbitrary_namespace};
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
     use tonic::Code;
 
     use super::*;
@@ -174,6 +499,92 @@ mod tests {
         assert!(table_columns.is_empty());
     }
 
+    #[tokio::test]
+    async fn list_and_delete_tables() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let (namespace, varietals, regions) = {
+            let mut repos = catalog.repositories().await;
+            let namespace = arbitrary_namespace(&mut *repos, "grapes").await;
+            let varietals = arbitrary_table(&mut *repos, "varietals", &namespace).await;
+            let regions = arbitrary_table(&mut *repos, "regions", &namespace).await;
+            // `regions` has been written to
+            repos
+                .partitions()
+                .create_or_get("bananas".into(), regions.id)
+                .await
+                .unwrap();
+            (namespace, varietals, regions)
+        };
+
+        let tables = handler
+            .get_tables(Request::new(GetTablesRequest {
+                namespace: namespace.name.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .tables;
+        assert_eq!(
+            tables,
+            vec![
+                table_to_proto(regions.clone()),
+                table_to_proto(varietals.clone())
+            ]
+        );
+
+        let deleted = handler
+            .delete_table(Request::new(DeleteTableRequest {
+                namespace: namespace.name.clone(),
+                name: varietals.name.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(deleted, table_to_proto(varietals.clone()));
+
+        // a table with partitions is kept
+        let error = handler
+            .delete_table(Request::new(DeleteTableRequest {
+                namespace: namespace.name.clone(),
+                name: regions.name.clone(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::FailedPrecondition);
+
+        let error = handler
+            .delete_table(Request::new(DeleteTableRequest {
+                namespace: namespace.name.clone(),
+                name: varietals.name.clone(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        let tables = handler
+            .get_tables(Request::new(GetTablesRequest {
+                namespace: namespace.name.clone(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .tables;
+        assert_eq!(tables, vec![table_to_proto(regions)]);
+
+        let error = handler
+            .get_tables(Request::new(GetTablesRequest {
+                namespace: "does_not_exist".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+    }
+
     #[tokio::test]
     async fn creating_same_table_twice_fails() {
         let catalog: Arc<dyn Catalog> =
@@ -397,4 +808,168 @@ mod tests {
         let all_tables = catalog.repositories().await.tables().list().await.unwrap();
         assert!(all_tables.is_empty());
     }
+
+    #[tokio::test]
+    async fn update_table_retention() {
+        const RETENTION: i64 = 7 * 24 * 60 * 60 * 1_000_000_000;
+
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let table =
+            arbitrary_table(&mut *catalog.repositories().await, "metrics", &namespace).await;
+
+        let request = |retention_period_ns| UpdateTableRetentionRequest {
+            namespace: namespace.name.clone(),
+            name: table.name.clone(),
+            retention_period_ns,
+        };
+
+        let updated = handler
+            .update_table_retention(Request::new(request(Some(RETENTION))))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.id, table.id.get());
+        assert_eq!(updated.retention_period_ns, Some(RETENTION));
+        let got = catalog
+            .repositories()
+            .await
+            .tables()
+            .get_by_id(table.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.retention_period_ns, Some(RETENTION));
+
+        for invalid in [0, -1] {
+            let error = handler
+                .update_table_retention(Request::new(request(Some(invalid))))
+                .await
+                .unwrap_err();
+            assert_eq!(error.code(), Code::InvalidArgument);
+        }
+
+        // removing the override
+        let updated = handler
+            .update_table_retention(Request::new(request(None)))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.retention_period_ns, None);
+
+        let error = handler
+            .update_table_retention(Request::new(UpdateTableRetentionRequest {
+                namespace: namespace.name.clone(),
+                name: "does_not_exist".into(),
+                retention_period_ns: Some(RETENTION),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        assert_eq!(
+            error.message(),
+            "Could not find a table with name does_not_exist in namespace grapes"
+        );
+    }
+
+    #[tokio::test]
+    async fn update_and_get_column_metadata() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let cpu = arbitrary_table(&mut *catalog.repositories().await, "cpu", &namespace).await;
+        let mem = arbitrary_table(&mut *catalog.repositories().await, "mem", &namespace).await;
+        for (table, column) in [(&cpu, "load"), (&cpu, "host"), (&mem, "used")] {
+            catalog
+                .repositories()
+                .await
+                .columns()
+                .create_or_get(column, table.id, ColumnType::F64)
+                .await
+                .unwrap();
+        }
+
+        let request = |table: &str, column: &str, unit: &str| UpdateColumnMetadataRequest {
+            namespace: namespace.name.clone(),
+            table: table.into(),
+            column: column.into(),
+            description: Some(format!("{table} {column}")),
+            unit: Some(unit.into()),
+            semantic_type: None,
+        };
+
+        let updated = handler
+            .update_column_metadata(Request::new(request("cpu", "load", "percent")))
+            .await
+            .unwrap()
+            .into_inner()
+            .metadata
+            .unwrap();
+        assert_eq!(
+            updated,
+            ColumnMetadata {
+                table: "cpu".into(),
+                column: "load".into(),
+                description: Some("cpu load".into()),
+                unit: Some("percent".into()),
+                semantic_type: None,
+            }
+        );
+        handler
+            .update_column_metadata(Request::new(request("mem", "used", "bytes")))
+            .await
+            .unwrap();
+
+        let all = handler
+            .get_column_metadata(Request::new(GetColumnMetadataRequest {
+                namespace: namespace.name.clone(),
+                table: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .metadata;
+        let units = all
+            .iter()
+            .map(|m| (m.table.as_str(), m.column.as_str(), m.unit.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            units,
+            [
+                ("cpu", "load", Some("percent")),
+                ("mem", "used", Some("bytes"))
+            ]
+        );
+
+        let only_mem = handler
+            .get_column_metadata(Request::new(GetColumnMetadataRequest {
+                namespace: namespace.name.clone(),
+                table: Some("mem".into()),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .metadata;
+        assert_eq!(only_mem.len(), 1);
+        assert_eq!(only_mem[0].column, "used");
+
+        let error = handler
+            .update_column_metadata(Request::new(request("cpu", "does_not_exist", "bytes")))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+        assert_eq!(
+            error.message(),
+            "Could not find a column with name does_not_exist in table cpu"
+        );
+    }
 }
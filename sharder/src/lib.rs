@@ -35,5 +35,8 @@ pub use round_robin::*;
 mod jumphash;
 pub use jumphash::*;
 
+mod table_regex;
+pub use table_regex::*;
+
 #[allow(missing_docs)]
 pub mod mock;
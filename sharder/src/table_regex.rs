@@ -0,0 +1,151 @@
+use std::{fmt::Debug, sync::Arc};
+
+use data_types::NamespaceName;
+use mutable_batch::MutableBatch;
+use regex::Regex;
+
+use super::{JumpHash, Sharder};
+
+/// A [`TableRegexSharder`] maps tables whose name matches a configured regex
+/// to a dedicated set of shards, and all other tables to the shards of the
+/// `fallback` sharder.
+///
+/// This allows pinning high-volume tables to dedicated shards, while the rest
+/// of the tables are sharded as usual.
+///
+/// The rules are evaluated in order, and the first rule matching the table
+/// name is used. Within the shard set of a rule, tables are consistently
+/// mapped to a shard using a [`JumpHash`].
+#[derive(Debug)]
+pub struct TableRegexSharder<T, S> {
+    rules: Vec<(Regex, JumpHash<T>)>,
+    fallback: S,
+}
+
+impl<T, S> TableRegexSharder<T, S> {
+    /// Initialise a [`TableRegexSharder`] that maps tables matching the regex
+    /// of a rule to the shards of that rule, and all other tables to
+    /// `fallback`.
+    ///
+    /// # Panics
+    ///
+    /// This constructor panics if the shard set of any rule is empty.
+    pub fn new<I>(rules: impl IntoIterator<Item = (Regex, I)>, fallback: S) -> Self
+    where
+        I: IntoIterator<Item = T>,
+    {
+        Self {
+            rules: rules
+                .into_iter()
+                .map(|(regex, shards)| (regex, JumpHash::new(shards)))
+                .collect(),
+            fallback,
+        }
+    }
+
+    /// Return the shard set of the first rule matching `table`, if any.
+    fn rule_for(&self, table: &str) -> Option<&JumpHash<T>> {
+        self.rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(table))
+            .map(|(_, shards)| shards)
+    }
+}
+
+impl<T, S> Sharder<()> for TableRegexSharder<Arc<T>, S>
+where
+    T: Debug + Send + Sync,
+    S: Sharder<(), Item = Arc<T>>,
+{
+    type Item = Arc<T>;
+
+    fn shard(&self, table: &str, namespace: &NamespaceName<'_>, payload: &()) -> Self::Item {
+        match self.rule_for(table) {
+            Some(shards) => Arc::clone(shards.shard_for_query(table, namespace.as_ref())),
+            None => self.fallback.shard(table, namespace, payload),
+        }
+    }
+}
+
+/// A [`TableRegexSharder`] mapping a [`MutableBatch`] reference according to
+/// the table it is destined for.
+impl<T, S> Sharder<MutableBatch> for TableRegexSharder<Arc<T>, S>
+where
+    T: Debug + Send + Sync,
+    S: Sharder<(), Item = Arc<T>>,
+{
+    type Item = Arc<T>;
+
+    fn shard(
+        &self,
+        table: &str,
+        namespace: &NamespaceName<'_>,
+        _payload: &MutableBatch,
+    ) -> Self::Item {
+        // The payload is not used to derive the shard destination.
+        Self::shard(self, table, namespace, &())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let pinned = (0..3).map(Arc::new).collect::<Vec<_>>();
+        let other = Arc::new(42);
+        let sharder = TableRegexSharder::new(
+            [
+                (Regex::new("^cpu$").unwrap(), vec![Arc::clone(&pinned[0])]),
+                (
+                    Regex::new("^(cpu|mem).*").unwrap(),
+                    vec![Arc::clone(&pinned[1]), Arc::clone(&pinned[2])],
+                ),
+            ],
+            JumpHash::new([Arc::clone(&other)]),
+        );
+        let namespace = NamespaceName::try_from("namespace").unwrap();
+
+        // The first matching rule is used.
+        assert_eq!(sharder.shard("cpu", &namespace, &()), pinned[0]);
+
+        // Tables matching the second rule are spread over its shard set.
+        for table in ["cpu2", "mem", "memory"] {
+            let got = sharder.shard(table, &namespace, &());
+            assert!(got == pinned[1] || got == pinned[2], "{table} -> {got}");
+
+            // ... consistently.
+            assert_eq!(sharder.shard(table, &namespace, &()), got);
+        }
+
+        // Other tables are mapped by the fallback sharder.
+        assert_eq!(sharder.shard("disk", &namespace, &()), other);
+        assert_eq!(
+            sharder.shard("disk", &namespace, &MutableBatch::default()),
+            other
+        );
+    }
+
+    #[test]
+    fn test_no_rules() {
+        let fallback = JumpHash::new((0..10).map(Arc::new));
+        let namespace = NamespaceName::try_from("namespace").unwrap();
+        let want = fallback.shard("table", &namespace, &());
+
+        let sharder = TableRegexSharder::new(
+            std::iter::empty::<(Regex, Vec<Arc<i32>>)>(),
+            JumpHash::new((0..10).map(Arc::new)),
+        );
+        assert_eq!(sharder.shard("table", &namespace, &()), want);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty shard set given to sharder")]
+    fn test_empty_rule() {
+        TableRegexSharder::new(
+            [(Regex::new("cpu").unwrap(), Vec::<Arc<i32>>::new())],
+            JumpHash::new([Arc::new(1)]),
+        );
+    }
+}
@@ -44,6 +44,20 @@ pub async fn write_to_router(
         .expect("http error sending write")
 }
 
+/// Runs the InfluxQL query via the v1 query_base/query endpoint (typically on the querier)
+pub async fn query_influxql_via_http(
+    query: impl AsRef<str>,
+    database: impl AsRef<str>,
+    query_base: impl AsRef<str>,
+) -> reqwest::Response {
+    reqwest::Client::new()
+        .get(format!("{}/query", query_base.as_ref()))
+        .query(&[("db", database.as_ref()), ("q", query.as_ref())])
+        .send()
+        .await
+        .expect("http error sending query")
+}
+
 /// Writes the line protocol to the WriteService endpoint (typically on the ingester)
 pub async fn write_to_ingester(
     line_protocol: impl Into<String>,
@@ -317,6 +317,11 @@ impl TestConfig {
         self.with_env("INFLUXDB_IOX_EXEC_MEM_POOL_BYTES", bytes.to_string())
     }
 
+    /// Configure the size of the query result cache of the querier.
+    pub fn with_querier_result_cache_bytes(self, bytes: usize) -> Self {
+        self.with_env("INFLUXDB_IOX_QUERY_RESULT_CACHE_BYTES", bytes.to_string())
+    }
+
     /// Configure sharding splits for the compactor.
     pub fn with_compactor_shards(self, n_shards: usize, shard_id: usize) -> Self {
         self.with_env("INFLUXDB_IOX_COMPACTION_SHARD_COUNT", n_shards.to_string())
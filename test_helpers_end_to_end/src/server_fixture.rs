@@ -103,6 +103,13 @@ impl ServerFixture {
         self.server.addrs().router_http_api().client_base()
     }
 
+    /// Return the http base URL for the querier HTTP API
+    ///
+    /// Every server binds its HTTP API to the address allocated for the router HTTP API.
+    pub fn querier_http_base(&self) -> Arc<str> {
+        self.server.addrs().router_http_api().client_base()
+    }
+
     /// Return the http base URL for the router gRPC API
     pub fn router_grpc_base(&self) -> Arc<str> {
         self.server.addrs().router_grpc_api().client_base()
@@ -118,6 +125,19 @@ impl ServerFixture {
         self.server.addrs().querier_grpc_api().client_base()
     }
 
+    /// Fetch the metrics of this server in the Prometheus text format.
+    pub async fn metrics(&self) -> String {
+        let url = format!("{}/metrics", self.querier_http_base());
+        reqwest::Client::new()
+            .get(&url)
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap()
+    }
+
     /// Return log path for server process.
     pub async fn log_path(&self) -> Box<Path> {
         self.server.server_process.lock().await.log_path.clone()
@@ -1,4 +1,4 @@
-use crate::{FileTypeIdentifier, SegmentEntry, SegmentIdBytes, SequencedWalOp};
+use crate::{FileTypeIdentifier, SegmentCompression, SegmentEntry, SegmentIdBytes, SequencedWalOp};
 use byteorder::{BigEndian, ReadBytesExt};
 use crc32fast::Hasher;
 use generated_types::influxdata::iox::wal::v1::WalOpBatch as ProtoWalOpBatch;
@@ -12,7 +12,10 @@ use std::{
 };
 
 #[derive(Debug)]
-pub struct ClosedSegmentFileReader<R>(R);
+pub struct ClosedSegmentFileReader<R> {
+    f: R,
+    compression: SegmentCompression,
+}
 
 impl ClosedSegmentFileReader<BufReader<File>> {
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
@@ -28,12 +31,21 @@ where
     R: Read,
 {
     pub fn new(f: R) -> Self {
-        Self(f)
+        Self {
+            f,
+            compression: SegmentCompression::default(),
+        }
+    }
+
+    /// Decode the entries of the segment as compressed with `compression`,
+    /// as recorded in the segment header.
+    pub fn set_compression(&mut self, compression: SegmentCompression) {
+        self.compression = compression;
     }
 
     fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
         let mut data = [0u8; N];
-        self.0
+        self.f
             .read_exact(&mut data)
             .context(UnableToReadArraySnafu { length: N })?;
         Ok(data)
@@ -44,27 +56,36 @@ where
     }
 
     fn one_entry(&mut self) -> Result<Option<SegmentEntry>> {
-        let expected_checksum = match self.0.read_u32::<BigEndian>() {
+        let expected_checksum = match self.f.read_u32::<BigEndian>() {
             Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
             other => other.context(UnableToReadChecksumSnafu)?,
         };
 
         let expected_len = self
-            .0
+            .f
             .read_u32::<BigEndian>()
             .context(UnableToReadLengthSnafu)?
             .into();
 
-        let compressed_read = self.0.by_ref().take(expected_len);
-        let hashing_read = CrcReader::new(compressed_read);
-        let mut decompressing_read = FrameDecoder::new(hashing_read);
+        let compressed_read = self.f.by_ref().take(expected_len);
+        let mut hashing_read = CrcReader::new(compressed_read);
 
         let mut data = Vec::with_capacity(100);
-        decompressing_read
-            .read_to_end(&mut data)
-            .context(UnableToReadDataSnafu)?;
+        match self.compression {
+            SegmentCompression::Snappy => {
+                FrameDecoder::new(&mut hashing_read)
+                    .read_to_end(&mut data)
+                    .context(UnableToReadDataSnafu)?;
+            }
+            SegmentCompression::Zstd => {
+                zstd::stream::read::Decoder::new(&mut hashing_read)
+                    .context(UnableToReadDataSnafu)?
+                    .read_to_end(&mut data)
+                    .context(UnableToReadDataSnafu)?;
+            }
+        }
 
-        let (actual_compressed_len, actual_checksum) = decompressing_read.get_mut().checksum();
+        let (actual_compressed_len, actual_checksum) = hashing_read.checksum();
 
         ensure!(
             expected_len == actual_compressed_len,
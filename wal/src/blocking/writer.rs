@@ -1,4 +1,4 @@
-use crate::{ClosedSegment, SegmentId, WriteSummary, FILE_TYPE_IDENTIFIER};
+use crate::{ClosedSegment, SegmentCompression, SegmentId, WriteSummary};
 use byteorder::{BigEndian, WriteBytesExt};
 use crc32fast::Hasher;
 use snafu::prelude::*;
@@ -24,12 +24,19 @@ use std::{
 /// amount of memory overhead for the lifetime of the writer.
 const SOFT_MAX_BUFFER_LEN: usize = 1024 * 128; // 128kiB
 
+/// The zstd compression level used for [`SegmentCompression::Zstd`] segments.
+///
+/// Low levels compress considerably better than snappy while remaining fast
+/// enough for the latency-sensitive write path.
+const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
 /// Struct for writing data to a segment file in a wal
 #[derive(Debug)]
 pub struct OpenSegmentFileWriter {
     id: SegmentId,
     path: PathBuf,
     f: File,
+    compression: SegmentCompression,
     bytes_written: usize,
 
     buffer: Vec<u8>,
@@ -39,6 +46,7 @@ impl OpenSegmentFileWriter {
     pub fn new_in_directory(
         dir: impl Into<PathBuf>,
         next_id_source: Arc<AtomicU64>,
+        compression: SegmentCompression,
     ) -> Result<Self> {
         let id = SegmentId::new(next_id_source.fetch_add(1, Ordering::Relaxed));
         let path = crate::build_segment_path(dir, id);
@@ -49,9 +57,9 @@ impl OpenSegmentFileWriter {
             .open(&path)
             .context(SegmentCreateSnafu)?;
 
-        f.write_all(FILE_TYPE_IDENTIFIER)
-            .context(SegmentWriteFileTypeSnafu)?;
-        let file_type_bytes_written = FILE_TYPE_IDENTIFIER.len();
+        let file_type = compression.file_type_identifier();
+        f.write_all(file_type).context(SegmentWriteFileTypeSnafu)?;
+        let file_type_bytes_written = file_type.len();
 
         let id_bytes = id.as_bytes();
         f.write_all(&id_bytes).context(SegmentWriteIdSnafu)?;
@@ -65,6 +73,7 @@ impl OpenSegmentFileWriter {
             id,
             path,
             f,
+            compression,
             bytes_written,
             buffer: Vec::with_capacity(8 * 1204), // 8kiB initial size
         })
@@ -96,12 +105,26 @@ impl OpenSegmentFileWriter {
 
         // Compress the payload into the reused buffer, recording the crc hash
         // as it is wrote.
-        let mut encoder = snap::write::FrameEncoder::new(HasherWrapper::new(&mut self.buffer));
-        encoder.write_all(data).context(UnableToCompressDataSnafu)?;
-        let (checksum, buf) = encoder
-            .into_inner()
-            .expect("cannot fail to flush to a Vec")
-            .finalize();
+        let hasher = HasherWrapper::new(&mut self.buffer);
+        let (checksum, buf) = match self.compression {
+            SegmentCompression::Snappy => {
+                let mut encoder = snap::write::FrameEncoder::new(hasher);
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                encoder
+                    .into_inner()
+                    .expect("cannot fail to flush to a Vec")
+                    .finalize()
+            }
+            SegmentCompression::Zstd => {
+                let mut encoder = zstd::stream::write::Encoder::new(hasher, ZSTD_COMPRESSION_LEVEL)
+                    .context(UnableToCompressDataSnafu)?;
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                encoder
+                    .finish()
+                    .context(UnableToCompressDataSnafu)?
+                    .finalize()
+            }
+        };
 
         // Adjust the compressed length to take into account the u64 padding
         // above.
@@ -197,12 +197,55 @@ pub(crate) fn build_segment_path(dir: impl Into<PathBuf>, id: SegmentId) -> Path
 }
 
 /// The first bytes written into a segment file to identify it and its version.
-// TODO: What's the expected way of upgrading -- what happens when we need version 31?
+///
+/// The version determines how the entries of the segment are encoded, allowing
+/// segments of different versions to be read back transparently - see
+/// [`SegmentCompression`].
 type FileTypeIdentifier = [u8; 8];
 const FILE_TYPE_IDENTIFIER: &FileTypeIdentifier = b"INFLUXV3";
+/// The identifier of segment files with zstd-compressed entries.
+const ZSTD_FILE_TYPE_IDENTIFIER: &FileTypeIdentifier = b"INFLUXZ3";
 /// File extension for segment files.
 const SEGMENT_FILE_EXTENSION: &str = "dat";
 
+/// The compression applied to the entries of the segment files written by a
+/// [`Wal`].
+///
+/// The compression of each segment is recorded in its header, so segments
+/// written with any compression can be read regardless of the compression
+/// the [`Wal`] is configured to write new segments with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentCompression {
+    /// Compress entries with the snappy frame format.
+    ///
+    /// This is fast, but has a lower compression ratio than zstd.
+    #[default]
+    Snappy,
+
+    /// Compress entries with zstd, reducing the disk usage and write volume of
+    /// the WAL at the cost of additional CPU time.
+    Zstd,
+}
+
+impl SegmentCompression {
+    /// The header identifier of segment files with entries compressed with
+    /// this compression.
+    fn file_type_identifier(&self) -> &'static FileTypeIdentifier {
+        match self {
+            Self::Snappy => FILE_TYPE_IDENTIFIER,
+            Self::Zstd => ZSTD_FILE_TYPE_IDENTIFIER,
+        }
+    }
+
+    /// The compression of segment files with the header identifier `id`, if
+    /// it identifies a segment file.
+    fn from_file_type_identifier(id: &FileTypeIdentifier) -> Option<Self> {
+        [Self::Snappy, Self::Zstd]
+            .into_iter()
+            .find(|c| c.file_type_identifier() == id)
+    }
+}
+
 /// The main type representing one WAL for one ingester instance.
 ///
 /// # Constraints
@@ -217,6 +260,7 @@ pub struct Wal {
     root: PathBuf,
     segments: Arc<Mutex<Segments>>,
     next_id_source: Arc<AtomicU64>,
+    compression: SegmentCompression,
     buffer: Mutex<WalBuffer>,
 
     /// The handle to the [`Wal::flush_buffer_background_task()`] task.
@@ -234,8 +278,21 @@ impl Wal {
     /// Similarly, editing or deleting files within a `Wal`'s root directory via some other
     /// mechanism is not supported.
     pub async fn new(root: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        Self::new_with_compression(root, SegmentCompression::default()).await
+    }
+
+    /// Creates a `Wal` instance that manages files in the specified root
+    /// directory, compressing the entries of new segment files with
+    /// `compression`.
+    ///
+    /// Existing segment files are read using the compression recorded in their
+    /// headers. The same constraints as [`Wal::new()`] apply.
+    pub async fn new_with_compression(
+        root: impl Into<PathBuf>,
+        compression: SegmentCompression,
+    ) -> Result<Arc<Self>> {
         let root = root.into();
-        info!(wal_dir=?root, "Initalizing Write Ahead Log (WAL)");
+        info!(wal_dir=?root, ?compression, "Initalizing Write Ahead Log (WAL)");
         tokio::fs::create_dir_all(&root)
             .await
             .context(UnableToCreateWalDirSnafu { path: &root })?;
@@ -289,9 +346,12 @@ impl Wal {
             .map(|id| id.get() + 1)
             .unwrap_or(0);
         let next_id_source = Arc::new(AtomicU64::new(next_id));
-        let open_segment =
-            OpenSegmentFileWriter::new_in_directory(&root, Arc::clone(&next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let open_segment = OpenSegmentFileWriter::new_in_directory(
+            &root,
+            Arc::clone(&next_id_source),
+            compression,
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let buffer = WalBuffer::new(None);
 
@@ -303,6 +363,7 @@ impl Wal {
                 open_segment_ids: SequenceNumberSet::default(),
             })),
             next_id_source,
+            compression,
             buffer: Mutex::new(buffer),
             flusher_task: Default::default(),
         };
@@ -342,9 +403,12 @@ impl Wal {
     /// closed segment details, including the [`SequenceNumberSet`] containing
     /// the sequence numbers of the writes within the closed segment.
     pub fn rotate(&self) -> Result<(ClosedSegment, SequenceNumberSet)> {
-        let new_open_segment =
-            OpenSegmentFileWriter::new_in_directory(&self.root, Arc::clone(&self.next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let new_open_segment = OpenSegmentFileWriter::new_in_directory(
+            &self.root,
+            Arc::clone(&self.next_id_source),
+            self.compression,
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let mut segments = self.segments.lock();
 
@@ -437,6 +501,7 @@ impl std::fmt::Debug for Wal {
         f.debug_struct("Wal")
             .field("root", &self.root)
             .field("next_id_source", &self.next_id_source)
+            .field("compression", &self.compression)
             .finish()
     }
 }
@@ -573,10 +638,9 @@ impl ClosedSegmentFileReader {
 
         let (file_type, id) = file.read_header().context(UnableToReadFileHeaderSnafu)?;
 
-        ensure!(
-            &file_type == FILE_TYPE_IDENTIFIER,
-            SegmentFileIdentifierMismatchSnafu,
-        );
+        let compression = SegmentCompression::from_file_type_identifier(&file_type)
+            .context(SegmentFileIdentifierMismatchSnafu)?;
+        file.set_compression(compression);
 
         let id = SegmentId::from_bytes(id);
 
@@ -767,6 +831,50 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn read_segments_of_mixed_compression() {
+        let dir = test_helpers::tmp_dir().unwrap();
+
+        let op = |seq, lp| SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), seq)].into_iter().collect(),
+            op: WalOp::Write(test_data(lp)),
+        };
+        let op1 = op(0, "m1,t=foo v=1i 1");
+        let op2 = op(1, "m1,t=foo v=2i 2");
+
+        // Write a zstd-compressed segment.
+        let zstd_segment = {
+            let wal = Wal::new_with_compression(dir.path(), SegmentCompression::Zstd)
+                .await
+                .unwrap();
+            wal.write_op(op1.clone()).changed().await.unwrap();
+            let (closed, _) = wal.rotate().unwrap();
+            closed
+        };
+
+        // Re-open the WAL with snappy compression and write another segment.
+        let wal = Wal::new(dir.path()).await.unwrap();
+        wal.write_op(op2.clone()).changed().await.unwrap();
+        let (snappy_segment, _) = wal.rotate().unwrap();
+
+        // Both segments are read back using the compression recorded in their
+        // headers.
+        for (segment, file_type, want) in [
+            (zstd_segment, ZSTD_FILE_TYPE_IDENTIFIER, op1),
+            (snappy_segment, FILE_TYPE_IDENTIFIER, op2),
+        ] {
+            let data = std::fs::read(build_segment_path(dir.path(), segment.id())).unwrap();
+            assert_eq!(&data[..file_type.len()], file_type);
+
+            let ops: Vec<SequencedWalOp> = wal
+                .reader_for_segment(segment.id())
+                .expect("should be able to open reader for closed WAL segment")
+                .flat_map(|batch| batch.expect("failed to read WAL op batch"))
+                .collect();
+            assert_eq!(ops, [want]);
+        }
+    }
+
     // open wal with files that aren't segments (should log and skip)
 
     // read segment works even if last entry is truncated